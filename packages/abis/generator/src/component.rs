@@ -0,0 +1,90 @@
+//! Finding the Stylus components and reading their interfaces.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::{ensure, eyre, WrapErr};
+
+/// A component with a Stylus contract, e.g. `packages/components/erc1155-stylus`.
+pub struct Component {
+    /// Directory name, like `erc1155-stylus`
+    pub name: String,
+    /// Version of the contract crate
+    pub version: String,
+    /// The contract crate, like `contract/erc1155`
+    pub contract: PathBuf,
+}
+
+/// Every component under `dir` that has a contract crate, by name.
+pub fn all(dir: &Path) -> eyre::Result<Vec<Component>> {
+    let mut components = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("can't read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(contract) = contract_crate(&path)? else { continue };
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let version = crate_version(&contract).wrap_err_with(|| format!("no version for {name}"))?;
+        components.push(Component { name, version, contract });
+    }
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(components)
+}
+
+impl Component {
+    /// The contract's Solidity interface: the complete one it commits if it generates one, like
+    /// `IERC1155Token.sol`, or else the output of the SDK's `export-abi`, which has no events.
+    pub fn interface(&self) -> eyre::Result<String> {
+        if let Some(path) = self.committed_interface()? {
+            return Ok(fs::read_to_string(path)?);
+        }
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--features", "export-abi"])
+            // Let the contract's rust-toolchain.toml pick the toolchain rather than ours
+            .env_remove("RUSTUP_TOOLCHAIN")
+            .current_dir(&self.contract)
+            .output()
+            .wrap_err("failed to run cargo")?;
+        ensure!(
+            output.status.success(),
+            "export-abi failed for {}:\n{}",
+            self.name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn committed_interface(&self) -> eyre::Result<Option<PathBuf>> {
+        for entry in fs::read_dir(&self.contract)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "sol") {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The one crate in `component/contract`, if there is one.
+fn contract_crate(component: &Path) -> eyre::Result<Option<PathBuf>> {
+    let Ok(entries) = fs::read_dir(component.join("contract")) else { return Ok(None) };
+    let mut crates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.join("Cargo.toml").exists() {
+            crates.push(path);
+        }
+    }
+    ensure!(crates.len() <= 1, "{} has more than one contract crate", component.display());
+    Ok(crates.pop())
+}
+
+fn crate_version(contract: &Path) -> eyre::Result<String> {
+    let manifest: toml::Table = fs::read_to_string(contract.join("Cargo.toml"))?.parse()?;
+    manifest["package"]
+        .get("version")
+        .and_then(|version| version.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| eyre!("Cargo.toml has no version"))
+}
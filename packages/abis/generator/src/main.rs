@@ -0,0 +1,81 @@
+//! Generates the ABIs of Cradle's Stylus components for frontends.
+//!
+//! For every component in `packages/components` with a contract crate, reads the contract's
+//! Solidity interface and writes its ABI to `packages/abis`, as JSON in `abis/<component>.json`
+//! and as a TypeScript module for viem and wagmi in `src/<component>.ts`. With `--check`
+//! nothing is written, and the run fails if the committed files are out of date.
+
+mod component;
+mod render;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use eyre::{bail, WrapErr};
+
+#[derive(Parser)]
+struct Args {
+    /// Fail if the committed ABIs differ from the contracts instead of writing them
+    #[arg(long)]
+    check: bool,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let out = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").canonicalize()?;
+    let components = component::all(&out.join("../components"))?;
+
+    let mut files = BTreeMap::new();
+    for component in &components {
+        println!("{} {}", component.name, component.version);
+        let abi = render::parse(&component.interface()?).wrap_err_with(|| format!("in {}", component.name))?;
+        files.insert(out.join("abis").join(format!("{}.json", component.name)), render::json(component, &abi)?);
+        files.insert(out.join("src").join(format!("{}.ts", component.name)), render::typescript(component, &abi)?);
+    }
+    files.insert(out.join("src/index.ts"), render::index(&components));
+
+    let stale = stale_files(&out, &files)?;
+    if args.check {
+        let outdated: Vec<_> =
+            files.iter().filter(|(path, contents)| fs::read_to_string(path).ok().as_ref() != Some(contents)).collect();
+        for (path, _) in &outdated {
+            println!("out of date: {}", path.display());
+        }
+        for path in &stale {
+            println!("no longer generated: {}", path.display());
+        }
+        if !outdated.is_empty() || !stale.is_empty() {
+            bail!("the ABIs in packages/abis are out of date, regenerate them with `cargo run` in packages/abis/generator");
+        }
+        return Ok(());
+    }
+
+    for (path, contents) in &files {
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, contents)?;
+    }
+    for path in &stale {
+        fs::remove_file(path)?;
+    }
+    println!("Wrote {} components", components.len());
+    Ok(())
+}
+
+/// Generated files of components that no longer exist.
+fn stale_files(out: &Path, files: &BTreeMap<PathBuf, String>) -> eyre::Result<Vec<PathBuf>> {
+    let mut stale = Vec::new();
+    for (dir, extension) in [("abis", "json"), ("src", "ts")] {
+        let Ok(entries) = fs::read_dir(out.join(dir)) else { continue };
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == extension) && !files.contains_key(&path) {
+                stale.push(path);
+            }
+        }
+    }
+    Ok(stale)
+}
@@ -0,0 +1,127 @@
+//! Turning a Solidity interface into ABI JSON and TypeScript.
+
+use alloy_json_abi::{Error, Event, Function, JsonAbi};
+use eyre::{eyre, WrapErr};
+use serde::Serialize;
+
+use crate::component::Component;
+
+/// `abis/<component>.json`.
+#[derive(Serialize)]
+struct Artifact<'a> {
+    component: &'a str,
+    version: &'a str,
+    abi: &'a JsonAbi,
+}
+
+/// Parses the declarations of `interface`, one per line as the SDK and the generated interfaces
+/// write them. Declarations repeated by several of the SDK's interfaces are kept once.
+pub fn parse(interface: &str) -> eyre::Result<JsonAbi> {
+    let mut abi = JsonAbi::new();
+    let mut seen = Vec::new();
+    for line in interface.lines().map(str::trim) {
+        let declaration = line.trim_end_matches(';');
+        if seen.contains(&declaration) {
+            continue;
+        }
+        let parsed = if declaration.starts_with("function ") {
+            Function::parse(&normalize(declaration)).map(|function| {
+                abi.functions.entry(function.name.clone()).or_default().push(function);
+            })
+        } else if declaration.starts_with("event ") {
+            Event::parse(declaration).map(|event| abi.events.entry(event.name.clone()).or_default().push(event))
+        } else if declaration.starts_with("error ") {
+            Error::parse(declaration).map(|error| abi.errors.entry(error.name.clone()).or_default().push(error))
+        } else {
+            continue;
+        };
+        parsed.map_err(|e| eyre!("{e}")).wrap_err_with(|| format!("can't parse `{declaration}`"))?;
+        seen.push(declaration);
+    }
+    Ok(abi)
+}
+
+/// Rewrites what the SDK exports for tuples into something the ABI parser reads: it drops the
+/// data locations, which it also writes inside tuples, and adds the parentheses it leaves out
+/// around a returned array of tuples, as in `returns (uint8,address)[] memory`.
+fn normalize(function: &str) -> String {
+    let mut normalized = function.to_string();
+    for location in [" memory", " calldata"] {
+        while let Some(start) = find_word(&normalized, location) {
+            normalized.replace_range(start..start + location.len(), "");
+        }
+    }
+    if let Some(start) = normalized.find(" returns (") {
+        let returns = &normalized[start + " returns ".len()..];
+        if closing_paren(returns) != Some(returns.len() - 1) {
+            normalized = format!("{} returns ({returns})", &normalized[..start]);
+        }
+    }
+    normalized
+}
+
+/// The first `word` in `text` that isn't followed by more of an identifier.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    text.match_indices(word)
+        .map(|(start, _)| start)
+        .find(|&start| !text[start + word.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+/// Index of the `)` closing the `(` that `text` starts with.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn json(component: &Component, abi: &JsonAbi) -> eyre::Result<String> {
+    let artifact = Artifact { component: &component.name, version: &component.version, abi };
+    Ok(serde_json::to_string_pretty(&artifact)? + "\n")
+}
+
+/// A module exporting the ABI `as const`, so viem and wagmi infer the argument and return types.
+pub fn typescript(component: &Component, abi: &JsonAbi) -> eyre::Result<String> {
+    let name = camel_case(&component.name);
+    Ok(format!(
+        "// Generated by packages/abis/generator from {} {}, do not edit.\n\n\
+         export const {name}Version = '{}';\n\n\
+         export const {name}Abi = {} as const;\n",
+        component.name,
+        component.version,
+        component.version,
+        serde_json::to_string_pretty(abi)?
+    ))
+}
+
+pub fn index(components: &[Component]) -> String {
+    let mut index = String::from("// Generated by packages/abis/generator, do not edit.\n\n");
+    for component in components {
+        index.push_str(&format!("export * from './{}';\n", component.name));
+    }
+    index
+}
+
+/// `erc1155-stylus` becomes `erc1155Stylus`.
+fn camel_case(name: &str) -> String {
+    let mut words = name.split('-');
+    let mut camel = words.next().unwrap_or_default().to_string();
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
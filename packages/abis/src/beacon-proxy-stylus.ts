@@ -0,0 +1,22 @@
+// Generated by packages/abis/generator from beacon-proxy-stylus 0.1.0, do not edit.
+
+export const beaconProxyStylusVersion = '0.1.0';
+
+export const beaconProxyStylusAbi = [
+  {
+    "type": "function",
+    "name": "initializeProxy",
+    "inputs": [
+      {
+        "name": "beacon",
+        "type": "address"
+      },
+      {
+        "name": "data",
+        "type": "bytes"
+      }
+    ],
+    "outputs": [],
+    "stateMutability": "payable"
+  }
+] as const;
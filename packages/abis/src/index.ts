@@ -0,0 +1,45 @@
+// Generated by packages/abis/generator, do not edit.
+
+export * from './arbitrum-messenger-stylus';
+export * from './attestation-stylus';
+export * from './auction-stylus';
+export * from './beacon-proxy-stylus';
+export * from './bonding-curve-stylus';
+export * from './bridge-adapter-stylus';
+export * from './cdp-stylus';
+export * from './clone-factory-stylus';
+export * from './crowdsale-stylus';
+export * from './disperse-stylus';
+export * from './dividends-stylus';
+export * from './erc1155-stylus';
+export * from './erc1155-wrapper-stylus';
+export * from './erc20-stylus';
+export * from './erc3525-stylus';
+export * from './erc721-converter-stylus';
+export * from './erc721-stylus';
+export * from './faucet-stylus';
+export * from './forwarder-stylus';
+export * from './fractional-vault-stylus';
+export * from './gas-station-stylus';
+export * from './governor-stylus';
+export * from './lending-stylus';
+export * from './locker-stylus';
+export * from './loyalty-stylus';
+export * from './marketplace-stylus';
+export * from './name-registry-stylus';
+export * from './oracle-stylus';
+export * from './otc-escrow-stylus';
+export * from './pause-registry-stylus';
+export * from './paymaster-stylus';
+export * from './permit2-stylus';
+export * from './prediction-market-stylus';
+export * from './raffle-stylus';
+export * from './randomness-stylus';
+export * from './rfq-stylus';
+export * from './smart-account-stylus';
+export * from './staking-rewards-stylus';
+export * from './streams-stylus';
+export * from './subscription-stylus';
+export * from './ticketing-stylus';
+export * from './treasury-stylus';
+export * from './upgradeable-beacon-stylus';
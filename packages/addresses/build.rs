@@ -0,0 +1,34 @@
+use std::{env, fs, path::PathBuf};
+
+#[path = "src/generate.rs"]
+mod generate;
+
+/// Directory of the manifests, relative to this crate, unless `CRADLE_DEPLOYMENTS` names another.
+const DEPLOYMENTS: &str = "../deploy/deployments";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CRADLE_DEPLOYMENTS");
+    let dir = match env::var_os("CRADLE_DEPLOYMENTS") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(DEPLOYMENTS),
+    };
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    // Only the manifests themselves, not the build metadata in the per-network directories
+    let mut manifests = Vec::new();
+    if dir.is_dir() {
+        for entry in fs::read_dir(&dir).expect("failed to list the deployments manifests") {
+            let path = entry.expect("failed to list the deployments manifests").path();
+            if path.is_file() && path.extension().is_some_and(|extension| extension == "json") {
+                println!("cargo:rerun-if-changed={}", path.display());
+                let json = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+                manifests.push((path.display().to_string(), json));
+            }
+        }
+    }
+    manifests.sort();
+
+    let networks = generate::render(&manifests).unwrap_or_else(|e| panic!("invalid deployments manifest {e}"));
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out.join("networks.rs"), networks).expect("failed to write networks.rs");
+}
@@ -0,0 +1,179 @@
+//! Renders the deployments manifests of `cradle-deploy` as Rust, for `build.rs`.
+//!
+//! The build script and the tests share this module, so it only depends on `std` and
+//! `serde_json`.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use serde_json::Value;
+
+/// What the address book keeps of `deployments/<network>.json`.
+struct Manifest {
+    network: String,
+    chain_id: u64,
+    deployments: Vec<Deployment>,
+}
+
+struct Deployment {
+    name: String,
+    component: String,
+    version: String,
+    features: Vec<String>,
+    address: String,
+    /// Role and account of each grant the setup calls left in place
+    roles: Vec<(String, String)>,
+}
+
+/// The declarations of every network in `manifests`, each the file name and contents of a
+/// manifest: a struct per network with a field per deployment, its constant, and `NETWORKS`.
+pub fn render(manifests: &[(String, String)]) -> Result<String, String> {
+    let mut networks = BTreeMap::new();
+    for (file, json) in manifests {
+        let manifest = parse(json).map_err(|e| format!("{file}: {e}"))?;
+        let name = manifest.network.clone();
+        if networks.insert(name.clone(), manifest).is_some() {
+            return Err(format!("{file}: another manifest is for {name} too"));
+        }
+    }
+
+    let mut out = String::new();
+    let mut all = Vec::new();
+    for manifest in networks.values() {
+        let ty = pascal_case(&manifest.network)?;
+        let constant = snake_case(&manifest.network)?.to_uppercase();
+        let mut fields = Vec::new();
+        for deployment in &manifest.deployments {
+            let field = snake_case(&deployment.name)?;
+            if fields.contains(&field) {
+                return Err(format!("{}: two deployments are named {field}", manifest.network));
+            }
+            fields.push(field);
+        }
+
+        writeln!(out, "/// Deployments on `{}`.", manifest.network).unwrap();
+        writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq)]").unwrap();
+        writeln!(out, "pub struct {ty} {{").unwrap();
+        for (deployment, field) in manifest.deployments.iter().zip(&fields) {
+            writeln!(out, "    /// `{}`, {} {}", deployment.name, deployment.component, deployment.version).unwrap();
+            writeln!(out, "    pub {field}: Deployment,").unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        let list: Vec<_> = fields.iter().map(|field| format!("{constant}.{field}")).collect();
+        writeln!(out, "impl {ty} {{").unwrap();
+        writeln!(out, "    pub const NAME: &'static str = {:?};", manifest.network).unwrap();
+        writeln!(out, "    pub const CHAIN_ID: u64 = {};", manifest.chain_id).unwrap();
+        writeln!(out, "    pub const NETWORK: Network = Network {{").unwrap();
+        writeln!(out, "        name: Self::NAME,\n        chain_id: Self::CHAIN_ID,").unwrap();
+        writeln!(out, "        deployments: &[{}],\n    }};", list.join(", ")).unwrap();
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "pub const {constant}: {ty} = {ty} {{").unwrap();
+        for (deployment, field) in manifest.deployments.iter().zip(&fields) {
+            let features: Vec<_> = deployment.features.iter().map(|feature| format!("{feature:?}")).collect();
+            let member = |(role, account): &(String, String)| {
+                format!("RoleMember {{ role: b256!({role:?}), account: address!({account:?}) }}")
+            };
+            let roles: Vec<_> = deployment.roles.iter().map(member).collect();
+            writeln!(out, "    {field}: Deployment {{").unwrap();
+            writeln!(out, "        name: {:?},", deployment.name).unwrap();
+            writeln!(out, "        component: {:?},", deployment.component).unwrap();
+            writeln!(out, "        version: {:?},", deployment.version).unwrap();
+            writeln!(out, "        features: &[{}],", features.join(", ")).unwrap();
+            writeln!(out, "        address: address!({:?}),", deployment.address).unwrap();
+            writeln!(out, "        roles: &[{}],", roles.join(", ")).unwrap();
+            writeln!(out, "    }},").unwrap();
+        }
+        writeln!(out, "}};\n").unwrap();
+        all.push(format!("{ty}::NETWORK"));
+    }
+    writeln!(out, "/// Every network with a manifest, by name.").unwrap();
+    writeln!(out, "pub const NETWORKS: &[Network] = &[{}];", all.join(", ")).unwrap();
+    Ok(out)
+}
+
+fn parse(json: &str) -> Result<Manifest, String> {
+    let manifest: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let network = string(&manifest, "network")?;
+    let chain_id = manifest.get("chainId").and_then(Value::as_u64).ok_or("no chainId")?;
+    let mut deployments = Vec::new();
+    for (name, deployment) in manifest.get("deployments").and_then(Value::as_object).ok_or("no deployments")? {
+        let context = |e: String| format!("{name}: {e}");
+        let features = match deployment.get("features") {
+            Some(features) => features
+                .as_array()
+                .and_then(|features| features.iter().map(|f| f.as_str().map(String::from)).collect())
+                .ok_or_else(|| context("features aren't strings".into()))?,
+            None => Vec::new(),
+        };
+        let calls = deployment.get("calls").and_then(Value::as_array).ok_or_else(|| context("no calls".into()))?;
+        deployments.push(Deployment {
+            name: name.clone(),
+            component: string(deployment, "component").map_err(context)?,
+            version: string(deployment, "version").map_err(context)?,
+            features,
+            address: hex(&string(deployment, "address").map_err(context)?, 20).map_err(context)?,
+            roles: roles(calls).map_err(context)?,
+        });
+    }
+    Ok(Manifest { network, chain_id, deployments })
+}
+
+/// The grants of `grantRole` calls that no later `revokeRole` or `renounceRole` undid, in the
+/// order they were granted. Roles granted by `initialize` aren't in the calls.
+fn roles(calls: &[Value]) -> Result<Vec<(String, String)>, String> {
+    let mut roles = Vec::new();
+    for call in calls {
+        let function = string(call, "function")?;
+        let name = function.split('(').next().unwrap_or_default();
+        if !matches!(name, "grantRole" | "revokeRole" | "renounceRole") {
+            continue;
+        }
+        let args = call.get("args").and_then(Value::as_array).ok_or_else(|| format!("`{function}` has no args"))?;
+        let [role, account] = args.as_slice() else {
+            return Err(format!("`{function}` doesn't take a role and an account"));
+        };
+        let role = hex(role.as_str().unwrap_or_default(), 32)?;
+        let account = hex(account.as_str().unwrap_or_default(), 20)?;
+        let grant = (role, account);
+        let granted = roles.contains(&grant);
+        if name == "grantRole" && !granted {
+            roles.push(grant);
+        } else if name != "grantRole" {
+            roles.retain(|existing| *existing != grant);
+        }
+    }
+    Ok(roles)
+}
+
+fn string(value: &Value, key: &str) -> Result<String, String> {
+    value.get(key).and_then(Value::as_str).map(String::from).ok_or_else(|| format!("no {key}"))
+}
+
+/// `value`, lowercased and without its `0x`, if it's `bytes` bytes of hex.
+fn hex(value: &str, bytes: usize) -> Result<String, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.len() != bytes * 2 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("{value:?} isn't {bytes} bytes of hex"));
+    }
+    Ok(digits.to_lowercase())
+}
+
+/// The words of `name`, split at anything but letters and digits.
+fn words(name: &str) -> Result<Vec<String>, String> {
+    let words: Vec<_> = name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    match words.first() {
+        Some(first) if first.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+            Ok(words.into_iter().map(str::to_lowercase).collect())
+        }
+        _ => Err(format!("{name:?} doesn't start with a letter, so it can't name a Rust item")),
+    }
+}
+
+fn pascal_case(name: &str) -> Result<String, String> {
+    Ok(words(name)?.iter().map(|word| word[..1].to_uppercase() + &word[1..]).collect())
+}
+
+fn snake_case(name: &str) -> Result<String, String> {
+    Ok(words(name)?.join("_"))
+}
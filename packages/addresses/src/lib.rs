@@ -0,0 +1,115 @@
+//! Addresses of Cradle's deployed components, by network.
+//!
+//! `build.rs` generates the address book from the manifests `cradle-deploy` writes to
+//! `packages/deploy/deployments`, or to the directory `CRADLE_DEPLOYMENTS` names, so services
+//! resolve contracts from the same record the deployments were made from instead of hardcoding
+//! them. Each manifest becomes a struct with a field per deployment, named after the network and
+//! the deployments' names in it, and a constant of it:
+//!
+//! ```ignore
+//! use cradle_addresses::{ArbitrumSepolia, ARBITRUM_SEPOLIA};
+//!
+//! let items = ARBITRUM_SEPOLIA.game_items.address;
+//! assert_eq!(ArbitrumSepolia::CHAIN_ID, 421614);
+//! ```
+//!
+//! Code that runs against whichever network its node is on looks the network up by chain ID
+//! instead, and the deployment by name:
+//!
+//! ```ignore
+//! let network = cradle_addresses::Network::by_chain_id(chain_id).ok_or("no deployments on this chain")?;
+//! let items = network.address("game-items");
+//! ```
+//!
+//! Deployments list the roles their setup calls granted with `grantRole` and didn't revoke. Roles
+//! `initialize` grants, such as the admin role of the initial owner, aren't in the manifests.
+
+use alloy_primitives::{Address, B256};
+
+#[cfg(test)]
+mod generate;
+#[cfg(test)]
+mod tests;
+
+/// The deployments of one manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Network {
+    /// Name of the manifest, like `arbitrum-sepolia`
+    pub name: &'static str,
+    pub chain_id: u64,
+    /// In order of name
+    pub deployments: &'static [Deployment],
+}
+
+/// A component deployed by `cradle-deploy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    /// Key in the manifest, the component unless the config named it
+    pub name: &'static str,
+    /// Directory in `packages/components`, like `erc1155-stylus`
+    pub component: &'static str,
+    /// Version of the contract crate
+    pub version: &'static str,
+    /// Cargo features it was built with
+    pub features: &'static [&'static str],
+    pub address: Address,
+    /// Roles its setup calls granted, in the order they were granted
+    pub roles: &'static [RoleMember],
+}
+
+/// An account granted a role.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoleMember {
+    pub role: B256,
+    pub account: Address,
+}
+
+impl Network {
+    /// The network on chain `chain_id`. Manifests of the same chain, such as a local node under
+    /// two names, resolve to the first by name.
+    pub fn by_chain_id(chain_id: u64) -> Option<&'static Network> {
+        NETWORKS.iter().find(|network| network.chain_id == chain_id)
+    }
+
+    pub fn by_name(name: &str) -> Option<&'static Network> {
+        NETWORKS.iter().find(|network| network.name == name)
+    }
+
+    pub fn deployment(&self, name: &str) -> Option<&'static Deployment> {
+        self.deployments.iter().find(|deployment| deployment.name == name)
+    }
+
+    /// Address of the deployment named `name`.
+    pub fn address(&self, name: &str) -> Option<Address> {
+        self.deployment(name).map(|deployment| deployment.address)
+    }
+
+    /// Deployments of `component`, in order of name.
+    pub fn deployments_of<'a>(&self, component: &'a str) -> impl Iterator<Item = &'static Deployment> + 'a {
+        self.deployments.iter().filter(move |deployment| deployment.component == component)
+    }
+}
+
+impl Deployment {
+    /// Accounts the setup calls granted `role` to.
+    pub fn members(&self, role: B256) -> impl Iterator<Item = Address> + 'static {
+        self.roles.iter().filter(move |member| member.role == role).map(|member| member.account)
+    }
+
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.roles.contains(&RoleMember { role, account })
+    }
+}
+
+// The networks, generated by `build.rs`. Without manifests there are none, and nothing uses the
+// imports
+#[allow(unused_imports)]
+mod networks {
+    use alloy_primitives::{address, b256};
+
+    use super::{Deployment, Network, RoleMember};
+
+    include!(concat!(env!("OUT_DIR"), "/networks.rs"));
+}
+
+pub use networks::*;
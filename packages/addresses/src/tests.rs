@@ -0,0 +1,118 @@
+use alloy_primitives::{address, b256, Address, B256};
+
+use crate::{generate::render, Deployment, Network, RoleMember, NETWORKS};
+
+const BRIDGE_ROLE: B256 = b256!("52ba824bfabc2bcfcdf7f0edbb486ebb05e1836c90e78047efeb949990f72e5f");
+const DEPLOYER: Address = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+const MARKETPLACE: Address = address!("00000000000000000000000000000000000b3c4e");
+
+/// A manifest as `cradle-deploy` writes it, with `calls` after `initialize`.
+fn manifest(network: &str, address: &str, calls: &str) -> (String, String) {
+    let json = format!(
+        r#"{{
+  "network": "{network}",
+  "chainId": 421614,
+  "deployments": {{
+    "game-items": {{
+      "component": "erc1155-stylus",
+      "version": "0.1.0",
+      "features": ["royalties"],
+      "address": "{address}",
+      "deployer": "0x3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e",
+      "programVersion": 2,
+      "sourceHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+      "calls": [
+        {{
+          "function": "initialize(string,address)",
+          "args": ["ipfs://x/", "0x3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e"],
+          "transaction": "0x0000000000000000000000000000000000000000000000000000000000000002"
+        }}{calls}
+      ],
+      "deployedAt": 1700000000
+    }}
+  }}
+}}"#
+    );
+    (format!("deployments/{network}.json"), json)
+}
+
+/// A `function(BRIDGE_ROLE, account)` call, to append to a manifest's calls.
+fn grant(function: &str, account: Address) -> String {
+    let transaction = B256::with_last_byte(3);
+    format!(
+        r#", {{
+          "function": "{function}(bytes32,address)",
+          "args": ["{BRIDGE_ROLE}", "{account}"],
+          "transaction": "{transaction}"
+        }}"#
+    )
+}
+
+#[test]
+fn manifests_render_as_a_struct_per_network() {
+    let calls = [grant("grantRole", DEPLOYER), grant("grantRole", MARKETPLACE), grant("revokeRole", DEPLOYER)];
+    let address = "0xA6E41FFD769491A42A6E5CE453259B93983A22EF";
+    let rendered = render(&[manifest("arbitrum-sepolia", address, &calls.concat())]).unwrap();
+    assert!(rendered.contains("pub struct ArbitrumSepolia {"));
+    assert!(rendered.contains("    pub game_items: Deployment,"));
+    assert!(rendered.contains("    pub const CHAIN_ID: u64 = 421614;"));
+    assert!(rendered.contains("pub const ARBITRUM_SEPOLIA: ArbitrumSepolia = ArbitrumSepolia {"));
+    assert!(rendered.contains(r#"        address: address!("a6e41ffd769491a42a6e5ce453259b93983a22ef"),"#));
+    assert!(rendered.contains(r#"        features: &["royalties"],"#));
+    // Only the grant that wasn't revoked is left
+    let (role, account) = (hex(BRIDGE_ROLE), hex(MARKETPLACE));
+    let member = format!(r#"RoleMember {{ role: b256!("{role}"), account: address!("{account}") }}"#);
+    assert!(rendered.contains(&format!("        roles: &[{member}],")));
+    assert!(rendered.contains("pub const NETWORKS: &[Network] = &[ArbitrumSepolia::NETWORK];"));
+}
+
+#[test]
+fn invalid_manifests_fail_the_build() {
+    let error = render(&[manifest("arbitrum-sepolia", "0x1234", "")]).unwrap_err();
+    assert!(error.contains("game-items") && error.contains("isn't 20 bytes of hex"), "{error}");
+    let twice = manifest("local", "0xa6e41ffd769491a42a6e5ce453259b93983a22ef", "");
+    let error = render(&[twice.clone(), (String::from("deployments/copy.json"), twice.1)]).unwrap_err();
+    assert!(error.contains("another manifest is for local"), "{error}");
+    let error = render(&[manifest("1-devnet", "0xa6e41ffd769491a42a6e5ce453259b93983a22ef", "")]).unwrap_err();
+    assert!(error.contains("doesn't start with a letter"), "{error}");
+    let empty = render(&[]).unwrap();
+    assert_eq!(empty, "/// Every network with a manifest, by name.\npub const NETWORKS: &[Network] = &[];\n");
+}
+
+#[test]
+fn deployments_resolve_by_name_component_and_role() {
+    const ITEMS: Deployment = Deployment {
+        name: "game-items",
+        component: "erc1155-stylus",
+        version: "0.1.0",
+        features: &[],
+        address: address!("a6e41ffd769491a42a6e5ce453259b93983a22ef"),
+        roles: &[RoleMember { role: BRIDGE_ROLE, account: MARKETPLACE }],
+    };
+    const GOLD: Deployment = Deployment { name: "gold", component: "erc20-stylus", roles: &[], ..ITEMS };
+    const NETWORK: Network = Network { name: "local", chain_id: 412346, deployments: &[ITEMS, GOLD] };
+
+    assert_eq!(NETWORK.address("game-items"), Some(ITEMS.address));
+    assert_eq!(NETWORK.deployment("gold"), Some(&GOLD));
+    assert_eq!(NETWORK.address("missing"), None);
+    assert_eq!(NETWORK.deployments_of("erc20-stylus").collect::<Vec<_>>(), vec![&GOLD]);
+    assert_eq!(ITEMS.members(BRIDGE_ROLE).collect::<Vec<_>>(), vec![MARKETPLACE]);
+    assert!(ITEMS.has_role(BRIDGE_ROLE, MARKETPLACE) && !ITEMS.has_role(BRIDGE_ROLE, DEPLOYER));
+    assert_eq!(GOLD.members(BRIDGE_ROLE).count(), 0);
+}
+
+#[test]
+fn committed_manifests_resolve_by_chain_id_and_name() {
+    for network in NETWORKS {
+        assert_eq!(Network::by_name(network.name), Some(network));
+        assert_eq!(Network::by_chain_id(network.chain_id).map(|found| found.chain_id), Some(network.chain_id));
+        for deployment in network.deployments {
+            assert_eq!(network.address(deployment.name), Some(deployment.address));
+        }
+    }
+    assert_eq!(Network::by_name("missing"), None);
+}
+
+fn hex(value: impl AsRef<[u8]>) -> String {
+    alloy_primitives::hex::encode(value)
+}
@@ -0,0 +1,137 @@
+//! Deploying the contracts under test and measuring the gas of their transactions.
+
+use std::{path::Path, process::Command};
+
+use alloy_network::{AnyNetwork, EthereumWallet, Network, ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{Address, Bytes, U64};
+use alloy_provider::{DynProvider, Provider, ProviderBuilder};
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{SolCall, SolValue};
+use eyre::{ensure, eyre, WrapErr};
+
+use crate::scenarios::IStylusErc1155;
+
+const BASE_URI: &str = "ipfs://cradle/";
+
+/// Requests on `AnyNetwork` keep Arbitrum's extra receipt fields, such as `gasUsedForL1`.
+type TransactionRequest = <AnyNetwork as Network>::TransactionRequest;
+
+/// Gas of one transaction. Arbitrum receipts include the L1 data fee in `gasUsed`, which
+/// depends on calldata size and L1 prices rather than on the contract.
+#[derive(Clone, Copy, Debug)]
+pub struct Gas {
+    pub total: u64,
+    pub l1: u64,
+}
+
+impl Gas {
+    /// Gas spent executing on L2.
+    pub fn l2(&self) -> u64 {
+        self.total - self.l1
+    }
+}
+
+/// A dev node and the funded account that sends every transaction.
+pub struct Chain {
+    provider: DynProvider<AnyNetwork>,
+    rpc_url: String,
+    private_key: String,
+    pub owner: Address,
+}
+
+impl Chain {
+    pub async fn connect(rpc_url: &str, private_key: &str) -> eyre::Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse().wrap_err("invalid private key")?;
+        let owner = signer.address();
+        let provider = ProviderBuilder::<_, _, AnyNetwork>::default()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(rpc_url.parse().wrap_err("invalid RPC URL")?)
+            .erased();
+        provider.get_chain_id().await.wrap_err_with(|| format!("no node at {rpc_url}"))?;
+        Ok(Self { provider, rpc_url: rpc_url.into(), private_key: private_key.into(), owner })
+    }
+
+    /// Deploys and initializes the Stylus contract in `dir` with `cargo stylus deploy`.
+    pub async fn deploy_stylus(&self, dir: &Path, features: Option<&str>) -> eyre::Result<Address> {
+        let mut command = Command::new("cargo");
+        command.args([
+            "stylus",
+            "deploy",
+            "--no-verify",
+            "--endpoint",
+            &self.rpc_url,
+            "--private-key",
+            &self.private_key,
+        ]);
+        if let Some(features) = features {
+            command.args(["--features", features]);
+        }
+        let output =
+            command.current_dir(dir).output().wrap_err("failed to run cargo stylus, is cargo-stylus installed?")?;
+        let log = strip_ansi(&format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+        ensure!(output.status.success(), "cargo stylus deploy failed:\n{log}");
+
+        let address = log
+            .lines()
+            .find(|line| line.contains("deployed code at address"))
+            .and_then(|line| line.split_whitespace().find_map(|word| word.parse::<Address>().ok()))
+            .ok_or_else(|| eyre!("no contract address in cargo stylus output:\n{log}"))?;
+        let initialize = IStylusErc1155::initializeCall { base_uri: BASE_URI.into(), owner: self.owner };
+        self.send(address, initialize.abi_encode().into()).await?;
+        Ok(address)
+    }
+
+    /// Deploys the Foundry artifact of `contract` from `dir`, building it first if needed.
+    pub async fn deploy_solidity(&self, dir: &Path, contract: &str) -> eyre::Result<Address> {
+        let artifact = dir.join("out").join(format!("{contract}.sol")).join(format!("{contract}.json"));
+        if !artifact.exists() {
+            let status = Command::new("forge")
+                .arg("build")
+                .current_dir(dir)
+                .status()
+                .wrap_err("failed to run forge, is Foundry installed?")?;
+            ensure!(status.success(), "forge build failed");
+        }
+        let artifact: serde_json::Value = serde_json::from_slice(&std::fs::read(&artifact)?)?;
+        let bytecode = artifact["bytecode"]["object"]
+            .as_str()
+            .ok_or_else(|| eyre!("no bytecode in the {contract} artifact"))?
+            .parse::<Bytes>()?;
+
+        let mut code = bytecode.to_vec();
+        code.extend((BASE_URI.to_string(), self.owner).abi_encode_params());
+        let tx = TransactionRequest::default().with_deploy_code(code);
+        let receipt = self.provider.send_transaction(tx).await?.get_receipt().await?;
+        ensure!(receipt.status(), "deploying {contract} reverted");
+        receipt.contract_address().ok_or_else(|| eyre!("no contract address in the {contract} deployment receipt"))
+    }
+
+    /// Sends `input` to `to` and returns the gas it used.
+    pub async fn send(&self, to: Address, input: Bytes) -> eyre::Result<Gas> {
+        let tx = TransactionRequest::default().with_to(to).with_input(input);
+        let receipt = self.provider.send_transaction(tx).await?.get_receipt().await?;
+        ensure!(receipt.status(), "transaction {} reverted", receipt.transaction_hash());
+
+        let l1 = receipt.other.get_deserialized::<U64>("gasUsedForL1").transpose()?.unwrap_or_default();
+        Ok(Gas { total: receipt.gas_used(), l1: l1.to() })
+    }
+}
+
+/// Removes the terminal colour codes cargo-stylus wraps its output in.
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
@@ -0,0 +1,97 @@
+//! Gas benchmarks for the erc1155-stylus component against OpenZeppelin's Solidity ERC-1155.
+//!
+//! Deploys both contracts to a dev node, sends each the same mints, transfers, batches and
+//! burns, and writes the L2 gas of every transaction to a JSON report. With `--baseline`, the
+//! run fails when the Stylus contract got more expensive than in an earlier report.
+
+mod chain;
+mod report;
+mod scenarios;
+
+use std::path::PathBuf;
+
+use alloy_primitives::address;
+use clap::Parser;
+use eyre::{bail, ensure};
+
+use chain::Chain;
+use report::{Measurement, Report};
+
+/// The prefunded account of `nitro-devnode`.
+const DEV_NODE_KEY: &str = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9acb659";
+
+#[derive(Parser)]
+struct Args {
+    #[arg(long, env = "RPC_URL", default_value = "http://localhost:8547")]
+    rpc_url: String,
+    #[arg(long, env = "PRIVATE_KEY", default_value = DEV_NODE_KEY, hide_default_value = true)]
+    private_key: String,
+    /// Comma-separated features to build the Stylus contract with, e.g. `packed-balances`
+    #[arg(long)]
+    features: Option<String>,
+    /// Where to write the report
+    #[arg(long, default_value = "reports/erc1155.json")]
+    out: PathBuf,
+    /// Earlier report to compare the Stylus gas against
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Allowed increase over the baseline, in percent
+    #[arg(long, default_value_t = 1.0)]
+    tolerance: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let features: Vec<String> = args.features.iter().flat_map(|list| list.split(',')).map(str::to_string).collect();
+
+    let chain = Chain::connect(&args.rpc_url, &args.private_key).await?;
+    let stylus = chain
+        .deploy_stylus(&root.join("../components/erc1155-stylus/contract/erc1155"), args.features.as_deref())
+        .await?;
+    let solidity = chain.deploy_solidity(&root.join("solidity"), "OzErc1155").await?;
+    println!("erc1155-stylus at {stylus}, OzErc1155 at {solidity}\n");
+
+    let recipient = address!("00000000000000000000000000000000000b3c4e");
+    let mut results = Vec::new();
+    println!("{:<28} {:>12} {:>12} {:>8}", "scenario", "stylus", "solidity", "ratio");
+    for scenario in scenarios::all(chain.owner, recipient) {
+        let measurement = Measurement {
+            stylus: chain.send(stylus, scenario.stylus).await?.l2(),
+            solidity: chain.send(solidity, scenario.solidity).await?.l2(),
+            scenario: scenario.name,
+        };
+        println!(
+            "{:<28} {:>12} {:>12} {:>8.2}",
+            measurement.scenario,
+            measurement.stylus,
+            measurement.solidity,
+            measurement.ratio()
+        );
+        results.push(measurement);
+    }
+
+    let report = Report { component: "erc1155-stylus".into(), features, results };
+    report.save(&args.out)?;
+    println!("\nWrote {}", args.out.display());
+
+    if let Some(path) = &args.baseline {
+        let baseline = Report::load(path)?;
+        ensure!(
+            baseline.features == report.features,
+            "{} was measured with features {:?}, not {:?}",
+            path.display(),
+            baseline.features,
+            report.features
+        );
+        let regressions = report.regressions(&baseline, args.tolerance);
+        for regression in &regressions {
+            println!("{} regressed: {} -> {} gas", regression.scenario, regression.baseline, regression.current);
+        }
+        if !regressions.is_empty() {
+            bail!("{} scenarios use over {}% more gas than {}", regressions.len(), args.tolerance, path.display());
+        }
+    }
+    Ok(())
+}
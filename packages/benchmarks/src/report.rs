@@ -0,0 +1,66 @@
+//! The JSON report and comparisons against a baseline.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// L2 execution gas of every scenario on both contracts.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    pub component: String,
+    /// Features the Stylus contract was built with
+    pub features: Vec<String>,
+    pub results: Vec<Measurement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Measurement {
+    pub scenario: String,
+    pub stylus: u64,
+    pub solidity: u64,
+}
+
+impl Measurement {
+    /// Stylus gas as a fraction of Solidity gas; below 1 means Stylus is cheaper.
+    pub fn ratio(&self) -> f64 {
+        self.stylus as f64 / self.solidity as f64
+    }
+}
+
+/// A scenario the Stylus contract got more expensive on.
+pub struct Regression<'a> {
+    pub scenario: &'a str,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+impl Report {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Scenarios whose Stylus gas exceeds `baseline`'s by more than `tolerance` percent.
+    /// Scenarios missing from the baseline are skipped.
+    pub fn regressions<'a>(&'a self, baseline: &Report, tolerance: f64) -> Vec<Regression<'a>> {
+        self.results
+            .iter()
+            .filter_map(|current| {
+                let previous = baseline.results.iter().find(|previous| previous.scenario == current.scenario)?;
+                let limit = previous.stylus as f64 * (1.0 + tolerance / 100.0);
+                (current.stylus as f64 > limit).then_some(Regression {
+                    scenario: &current.scenario,
+                    baseline: previous.stylus,
+                    current: current.stylus,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,126 @@
+//! The transactions each contract is measured on.
+//!
+//! Scenarios run in order against one deployment, so later ones build on the balances of
+//! earlier ones: `transfer` moves tokens that `mint` created.
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall};
+
+sol! {
+    /// erc1155-stylus.
+    interface IStylusErc1155 {
+        function initialize(string base_uri, address owner) external;
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+        function mintBatch(address to, uint256[] ids, uint256[] amounts, bytes data) external;
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+        function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values, bytes data) external;
+        function burn(uint256 id, uint256 amount) external;
+        function burnBatch(uint256[] ids, uint256[] amounts) external;
+    }
+
+    /// `solidity/src/OzErc1155.sol`.
+    interface IOzErc1155 {
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+        function mintBatch(address to, uint256[] ids, uint256[] amounts, bytes data) external;
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+        function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values, bytes data) external;
+        function burn(address account, uint256 id, uint256 value) external;
+        function burnBatch(address account, uint256[] ids, uint256[] values) external;
+    }
+}
+
+/// Batch sizes measured for batch mints, transfers and burns.
+pub const BATCH_SIZES: [usize; 3] = [10, 50, 200];
+
+/// One operation, encoded for each contract.
+pub struct Scenario {
+    pub name: String,
+    pub stylus: Bytes,
+    pub solidity: Bytes,
+}
+
+impl Scenario {
+    fn new(name: impl Into<String>, stylus: impl SolCall, solidity: impl SolCall) -> Self {
+        Self { name: name.into(), stylus: stylus.abi_encode().into(), solidity: solidity.abi_encode().into() }
+    }
+}
+
+/// Every scenario, in the order they must run. `owner` holds the tokens and `recipient` is
+/// an account that holds none yet.
+pub fn all(owner: Address, recipient: Address) -> Vec<Scenario> {
+    let id = U256::from(1);
+    let amount = U256::from(1_000);
+    let value = U256::from(10);
+
+    let mut scenarios = vec![
+        Scenario::new(
+            "mint",
+            IStylusErc1155::mintCall { to: owner, id, amount, data: Bytes::new() },
+            IOzErc1155::mintCall { to: owner, id, amount, data: Bytes::new() },
+        ),
+        Scenario::new(
+            "mint_existing_holder",
+            IStylusErc1155::mintCall { to: owner, id, amount, data: Bytes::new() },
+            IOzErc1155::mintCall { to: owner, id, amount, data: Bytes::new() },
+        ),
+        Scenario::new(
+            "transfer_new_recipient",
+            IStylusErc1155::safeTransferFromCall { from: owner, to: recipient, id, value, data: Bytes::new() },
+            IOzErc1155::safeTransferFromCall { from: owner, to: recipient, id, value, data: Bytes::new() },
+        ),
+        Scenario::new(
+            "transfer_existing_recipient",
+            IStylusErc1155::safeTransferFromCall { from: owner, to: recipient, id, value, data: Bytes::new() },
+            IOzErc1155::safeTransferFromCall { from: owner, to: recipient, id, value, data: Bytes::new() },
+        ),
+        Scenario::new(
+            "burn",
+            IStylusErc1155::burnCall { id, amount: value },
+            IOzErc1155::burnCall { account: owner, id, value },
+        ),
+    ];
+
+    for size in BATCH_SIZES {
+        // Consecutive IDs, so `packed-balances` builds share slots the way it's meant to be used
+        let first = 1_000 * size as u64;
+        let ids: Vec<U256> = (first..first + size as u64).map(U256::from).collect();
+        let amounts = vec![amount; size];
+        let values = vec![value; size];
+
+        scenarios.extend([
+            Scenario::new(
+                format!("mint_batch_{size}"),
+                IStylusErc1155::mintBatchCall {
+                    to: owner,
+                    ids: ids.clone(),
+                    amounts: amounts.clone(),
+                    data: Bytes::new(),
+                },
+                IOzErc1155::mintBatchCall { to: owner, ids: ids.clone(), amounts, data: Bytes::new() },
+            ),
+            Scenario::new(
+                format!("transfer_batch_{size}"),
+                IStylusErc1155::safeBatchTransferFromCall {
+                    from: owner,
+                    to: recipient,
+                    ids: ids.clone(),
+                    values: values.clone(),
+                    data: Bytes::new(),
+                },
+                IOzErc1155::safeBatchTransferFromCall {
+                    from: owner,
+                    to: recipient,
+                    ids: ids.clone(),
+                    values: values.clone(),
+                    data: Bytes::new(),
+                },
+            ),
+            Scenario::new(
+                format!("burn_batch_{size}"),
+                IStylusErc1155::burnBatchCall { ids: ids.clone(), amounts: values.clone() },
+                IOzErc1155::burnBatchCall { account: owner, ids, values },
+            ),
+        ]);
+    }
+    scenarios
+}
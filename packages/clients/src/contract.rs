@@ -0,0 +1,115 @@
+//! Calling a deployed component and decoding its events.
+
+use core::marker::PhantomData;
+
+use alloy_network::{Ethereum, TransactionBuilder};
+use alloy_primitives::{Address, U256};
+use alloy_provider::{PendingTransactionBuilder, Provider};
+use alloy_rpc_types_eth::{Log, TransactionReceipt, TransactionRequest};
+use alloy_sol_types::{SolCall, SolEventInterface, SolInterface};
+use cradle_addresses::Network;
+
+use crate::Error;
+
+/// The types `sol!` generates for one component's interface.
+pub trait Interface {
+    /// Directory of the component in `packages/components`
+    const COMPONENT: &'static str;
+    /// Every error the contract reverts with
+    type Errors: SolInterface;
+    /// Every event the contract emits
+    type Events: SolEventInterface;
+}
+
+/// A deployed component, reached through `provider`.
+#[derive(Clone, Debug)]
+pub struct Contract<P, I> {
+    address: Address,
+    provider: P,
+    interface: PhantomData<I>,
+}
+
+impl<P: Provider, I: Interface> Contract<P, I> {
+    pub fn new(address: Address, provider: P) -> Self {
+        Self { address, provider, interface: PhantomData }
+    }
+
+    /// The deployment named `name` in `network`'s manifest, if it's a deployment of this
+    /// component, e.g. from `cradle_addresses::Network::by_chain_id`.
+    pub fn deployed(network: &Network, name: &str, provider: P) -> Option<Self> {
+        let deployment = network.deployment(name).filter(|deployment| deployment.component == I::COMPONENT)?;
+        Some(Self::new(deployment.address, provider))
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Prepares `call`, to simulate with [`CallBuilder::call`] or send with [`CallBuilder::send`].
+    pub fn call<C: SolCall>(&self, call: C) -> CallBuilder<'_, P, I, C> {
+        CallBuilder { contract: self, call, request: TransactionRequest::default() }
+    }
+
+    /// This contract's events in `receipt`.
+    pub fn events(&self, receipt: &TransactionReceipt) -> Vec<I::Events> {
+        self.decode_logs(receipt.inner.logs())
+    }
+
+    /// This contract's events among `logs`, e.g. from `eth_getLogs`. Logs of other contracts, and
+    /// ones that aren't in the interface, are skipped.
+    pub fn decode_logs<'a>(&self, logs: impl IntoIterator<Item = &'a Log>) -> Vec<I::Events> {
+        logs.into_iter()
+            .filter(|log| log.inner.address == self.address)
+            .filter_map(|log| I::Events::decode_raw_log(log.inner.data.topics(), &log.inner.data.data, true).ok())
+            .collect()
+    }
+}
+
+/// One call to a [`Contract`], with the transaction fields to send it with.
+#[must_use]
+pub struct CallBuilder<'a, P, I, C> {
+    contract: &'a Contract<P, I>,
+    call: C,
+    request: TransactionRequest,
+}
+
+impl<P: Provider, I: Interface, C: SolCall> CallBuilder<'_, P, I, C> {
+    pub fn from(mut self, from: Address) -> Self {
+        self.request.set_from(from);
+        self
+    }
+
+    pub fn value(mut self, value: U256) -> Self {
+        self.request.set_value(value);
+        self
+    }
+
+    pub fn gas(mut self, gas: u64) -> Self {
+        self.request.set_gas_limit(gas);
+        self
+    }
+
+    /// Simulates the call with `eth_call` and decodes what it returns.
+    pub async fn call(&self) -> Result<C::Return, Error<I::Errors>> {
+        let output = self.contract.provider.call(&self.request()).await.map_err(Error::from_transport)?;
+        C::abi_decode_returns(&output, true).map_err(Error::Decode)
+    }
+
+    pub async fn estimate_gas(&self) -> Result<u64, Error<I::Errors>> {
+        self.contract.provider.estimate_gas(&self.request()).await.map_err(Error::from_transport)
+    }
+
+    /// Sends the call as a transaction. Providers with a gas filler estimate gas first, so a call
+    /// that would revert fails here with [`Error::Revert`] and isn't sent.
+    pub async fn send(&self) -> Result<PendingTransactionBuilder<Ethereum>, Error<I::Errors>> {
+        self.contract.provider.send_transaction(self.request()).await.map_err(Error::from_transport)
+    }
+
+    fn request(&self) -> TransactionRequest {
+        self.request.clone().with_to(self.contract.address).with_input(self.call.abi_encode())
+    }
+}
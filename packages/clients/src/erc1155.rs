@@ -0,0 +1,58 @@
+//! Client for the erc1155-stylus component.
+
+use alloy_sol_types::sol;
+
+use crate::{revert::display_errors, Contract, Interface};
+
+sol!(
+    #[sol(all_derives)]
+    "../components/erc1155-stylus/contract/erc1155/IERC1155Token.sol"
+);
+
+pub use IERC1155Token::{IERC1155TokenCalls, IERC1155TokenErrors, IERC1155TokenEvents};
+
+display_errors!(IERC1155TokenErrors {
+    AlreadyInitialized,
+    UnauthorizedInitializer,
+    GuardianExpired,
+    ERC1155InvalidApprovalExpiry,
+    ERC1155IdRetired,
+    ERC1155BatchTooLong,
+    ERC1155ReceiverHookFailed,
+    ERC1155MetadataIsFrozen,
+    ERC1155SupplyIsFrozen,
+    ERC1155InvalidSeries,
+    ERC1155TooManySeries,
+    ERC1155OrderAlreadyFulfilled,
+    ERC1155InsufficientBalance,
+    ERC1155InvalidSender,
+    ERC1155InvalidReceiver,
+    ERC1155InvalidApprover,
+    ERC1155InvalidOperator,
+    ERC1155InvalidArrayLength,
+    ERC1155MissingApprovalForAll,
+    ERC1155BalanceOverflow,
+    ERC1155InsufficientSupply,
+    ERC1155ZeroAmount,
+    ERC1155DuplicateId,
+    OwnableUnauthorizedAccount,
+    OwnableInvalidOwner,
+    EnforcedPause,
+    ExpectedPause,
+    AccessControlUnauthorizedAccount,
+    AccessControlBadConfirmation,
+    AccessControlMemberOutOfBounds,
+});
+
+/// The calls, events and errors of erc1155-stylus.
+#[derive(Clone, Copy, Debug)]
+pub struct Erc1155Interface;
+
+impl Interface for Erc1155Interface {
+    const COMPONENT: &'static str = "erc1155-stylus";
+    type Errors = IERC1155TokenErrors;
+    type Events = IERC1155TokenEvents;
+}
+
+/// A deployed erc1155-stylus token.
+pub type Erc1155<P> = Contract<P, Erc1155Interface>;
@@ -0,0 +1,62 @@
+//! Client for the erc20-stylus component.
+
+use alloy_sol_types::sol;
+
+use crate::{revert::display_errors, Contract, Interface};
+
+sol!(
+    #[sol(all_derives)]
+    "../components/erc20-stylus/contract/erc20/IERC20Token.sol"
+);
+
+pub use IERC20Token::{IERC20TokenCalls, IERC20TokenErrors, IERC20TokenEvents};
+
+display_errors!(IERC20TokenErrors {
+    AlreadyInitialized,
+    InsufficientBalance,
+    InsufficientAllowance,
+    AuthorizationNotYetValid,
+    AuthorizationExpired,
+    AuthorizationAlreadyUsed,
+    InvalidAuthorizationSignature,
+    InvalidAuthorizationCaller,
+    ArbitrumGatewayNotSet,
+    NotExpectedCall,
+    UnauthorizedGateway,
+    IncorrectRegistrationValue,
+    GatewayCallFailed,
+    NotMinter,
+    MinterAllowanceExceeded,
+    ERC20RecoveryFailed,
+    InvalidBurnRate,
+    BuybackNotConfigured,
+    InvalidBuybackPair,
+    BuybackCallFailed,
+    BuybackBelowMinimum,
+    LaunchGuardIsRenounced,
+    TradingNotEnabled,
+    MaxBuyExceeded,
+    MaxWalletExceeded,
+    TradeCooldown,
+    PrecompileCallFailed,
+    DividendsDisabled,
+    InvalidDividendFee,
+    OwnableUnauthorizedAccount,
+    OwnableInvalidOwner,
+    AccessControlUnauthorizedAccount,
+    AccessControlBadConfirmation,
+    AccessControlMemberOutOfBounds,
+});
+
+/// The calls, events and errors of erc20-stylus.
+#[derive(Clone, Copy, Debug)]
+pub struct Erc20Interface;
+
+impl Interface for Erc20Interface {
+    const COMPONENT: &'static str = "erc20-stylus";
+    type Errors = IERC20TokenErrors;
+    type Events = IERC20TokenEvents;
+}
+
+/// A deployed erc20-stylus token.
+pub type Erc20<P> = Contract<P, Erc20Interface>;
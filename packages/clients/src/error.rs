@@ -0,0 +1,74 @@
+//! Failed calls, with reverts decoded into the component's errors.
+
+use core::fmt;
+
+use alloy_json_rpc::ErrorPayload;
+use alloy_primitives::Bytes;
+use alloy_sol_types::SolInterface;
+use alloy_transport::TransportError;
+
+use crate::Revert;
+
+/// Why a call failed. `E` is the `Errors` enum of the component's interface, such as
+/// [`IERC1155TokenErrors`](crate::erc1155::IERC1155TokenErrors).
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The contract reverted with one of its errors
+    Revert(E),
+    /// The contract reverted with data that isn't one of its errors, e.g. empty data from a method
+    /// it doesn't have
+    UnknownRevert(Bytes),
+    /// The node couldn't be reached or rejected the request
+    Transport(TransportError),
+    /// The contract returned data that doesn't match the interface
+    Decode(alloy_sol_types::Error),
+}
+
+impl<E: SolInterface> Error<E> {
+    /// Decodes the revert data of a failed `eth_call` or `eth_estimateGas`.
+    pub fn from_transport(error: TransportError) -> Self {
+        match error.as_error_resp().and_then(ErrorPayload::as_revert_data) {
+            Some(data) => match E::abi_decode(&data, true) {
+                Ok(revert) => Self::Revert(revert),
+                Err(_) => Self::UnknownRevert(data),
+            },
+            None => Self::Transport(error),
+        }
+    }
+}
+
+impl<E> Error<E> {
+    /// The contract's error, if it reverted with one.
+    pub fn as_revert(&self) -> Option<&E> {
+        match self {
+            Self::Revert(revert) => Some(revert),
+            _ => None,
+        }
+    }
+}
+
+/// Reverts show the error and its arguments. Data that isn't one of the component's errors is
+/// shown decoded if it's another component's, or Solidity's `Error(string)` or `Panic(uint256)`.
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Revert(revert) => write!(f, "reverted with {revert}"),
+            Self::UnknownRevert(data) => match Revert::decode(data) {
+                Some(revert) => write!(f, "reverted with {revert}"),
+                None => write!(f, "reverted with unknown data {data}"),
+            },
+            Self::Transport(error) => write!(f, "{error}"),
+            Self::Decode(error) => write!(f, "unexpected return data: {error}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(error) => Some(error),
+            Self::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
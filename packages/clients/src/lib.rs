@@ -0,0 +1,49 @@
+//! Typed alloy clients for Cradle's Stylus components.
+//!
+//! The bindings are generated by `sol!` from the Solidity interface each component generates
+//! next to its contract, so they have the contract's methods, events and errors without any
+//! hand-written ABI. Each component is a [`Contract`] with its own [`Interface`]:
+//!
+//! ```no_run
+//! use alloy_primitives::{address, Bytes, U256};
+//! use alloy_provider::ProviderBuilder;
+//! use cradle_clients::erc1155::{Erc1155, IERC1155Token, IERC1155TokenErrors};
+//! use cradle_clients::Error;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = ProviderBuilder::new().on_http("http://localhost:8547".parse()?);
+//! let token = Erc1155::new(address!("a6e41ffd769491a42a6e5ce453259b93983a22ef"), provider);
+//! let holder = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+//! let to = address!("00000000000000000000000000000000000b3c4e");
+//!
+//! let balance = token.call(IERC1155Token::balanceOfCall { account: holder, id: U256::from(1) }).call().await?._0;
+//!
+//! let transfer = IERC1155Token::safeTransferFromCall { from: holder, to, id: U256::from(1), value: balance, data: Bytes::new() };
+//! match token.call(transfer).from(holder).send().await {
+//!     Ok(pending) => {
+//!         let receipt = pending.get_receipt().await?;
+//!         for event in token.events(&receipt) {
+//!             println!("{event:?}");
+//!         }
+//!     }
+//!     Err(Error::Revert(IERC1155TokenErrors::EnforcedPause(_))) => println!("the token is paused"),
+//!     Err(error) => return Err(error.into()),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod contract;
+mod error;
+mod revert;
+
+pub mod erc1155;
+pub mod erc20;
+pub mod smart_account;
+
+pub use contract::{CallBuilder, Contract, Interface};
+pub use error::Error;
+pub use revert::Revert;
+
+#[cfg(test)]
+mod tests;
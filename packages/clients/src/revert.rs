@@ -0,0 +1,74 @@
+//! Revert data decoded without knowing which component reverted, and readable errors.
+
+use core::fmt;
+
+use alloy_primitives::U256;
+use alloy_sol_types::{Panic, Revert as RevertReason, SolError, SolInterface};
+
+use crate::{erc1155::IERC1155TokenErrors, erc20::IERC20TokenErrors, smart_account::ISmartAccountErrors};
+
+/// Implements `Display` for a component's `Errors` enum, as the error's name and arguments:
+/// `ERC1155InsufficientBalance { sender: 0x…, balance: 1, needed: 2, id: 3 }`. Every error must
+/// be listed, so one added to the interface fails to compile until it's listed here too.
+macro_rules! display_errors {
+    ($errors:ident { $($error:ident),* $(,)? }) => {
+        impl core::fmt::Display for $errors {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    $(Self::$error(error) => write!(f, "{error:?}"),)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use display_errors;
+
+/// Revert data from any Cradle component, or Solidity's own reverts, which a component can pass
+/// on from a contract it calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Revert {
+    Erc1155(IERC1155TokenErrors),
+    Erc20(IERC20TokenErrors),
+    SmartAccount(ISmartAccountErrors),
+    /// `Error(string)`, from `revert("...")` or a failed `require`
+    Reason(String),
+    /// `Panic(uint256)`, from a failed `assert`, an overflow or the like
+    Panic(U256),
+}
+
+impl Revert {
+    /// Decodes `data` as the first of the components' errors, then Solidity's, that it's valid
+    /// for. Errors that several components share, like `OwnableUnauthorizedAccount`, decode as
+    /// erc1155-stylus's.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if let Ok(error) = IERC1155TokenErrors::abi_decode(data, true) {
+            return Some(Self::Erc1155(error));
+        }
+        if let Ok(error) = IERC20TokenErrors::abi_decode(data, true) {
+            return Some(Self::Erc20(error));
+        }
+        if let Ok(error) = ISmartAccountErrors::abi_decode(data, true) {
+            return Some(Self::SmartAccount(error));
+        }
+        if let Ok(revert) = RevertReason::abi_decode(data, true) {
+            return Some(Self::Reason(revert.reason));
+        }
+        Panic::abi_decode(data, true).ok().map(|panic| Self::Panic(panic.code))
+    }
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Erc1155(error) => write!(f, "{error}"),
+            Self::Erc20(error) => write!(f, "{error}"),
+            Self::SmartAccount(error) => write!(f, "{error}"),
+            Self::Reason(reason) => write!(f, "Error({reason:?})"),
+            Self::Panic(code) => match (Panic { code: *code }).kind() {
+                Some(kind) => write!(f, "Panic({code:#x}: {})", kind.as_str()),
+                None => write!(f, "Panic({code:#x})"),
+            },
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Client for the smart-account-stylus component, and batches of calls for it to make.
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall};
+
+use crate::{revert::display_errors, Contract, Interface};
+
+sol!(
+    #[sol(all_derives)]
+    "../components/smart-account-stylus/contract/smart-account/ISmartAccount.sol"
+);
+
+pub use ISmartAccount::{ISmartAccountCalls, ISmartAccountErrors, ISmartAccountEvents};
+
+display_errors!(ISmartAccountErrors {
+    AlreadyInitialized,
+    Unauthorized,
+    LengthMismatch,
+    InvalidOwner,
+    EntryPointCallFailed,
+});
+
+/// The calls, events and errors of smart-account-stylus.
+#[derive(Clone, Copy, Debug)]
+pub struct SmartAccountInterface;
+
+impl Interface for SmartAccountInterface {
+    const COMPONENT: &'static str = "smart-account-stylus";
+    type Errors = ISmartAccountErrors;
+    type Events = ISmartAccountEvents;
+}
+
+/// A deployed smart-account-stylus account, or an EOA delegated to one with EIP-7702.
+pub type SmartAccount<P> = Contract<P, SmartAccountInterface>;
+
+/// One call in a [`Batch`], with the fields of an EIP-5792 `wallet_sendCalls` call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Call {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+/// Calls for an account to make in order, all reverting if one fails, like an approval, a mint
+/// and a listing in one user operation.
+///
+/// [`Batch::execute_batch`] encodes them for the account's `executeBatch`, as a user operation's
+/// `callData` or, for an EOA delegated to the account with EIP-7702, a transaction the EOA sends
+/// to itself. [`Batch::calls`] are the same calls for wallets that take `wallet_sendCalls`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Batch {
+    calls: Vec<Call>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `call` to the contract at `to`.
+    pub fn call<C: SolCall>(self, to: Address, call: C) -> Self {
+        self.call_with_value(to, U256::ZERO, call)
+    }
+
+    /// Adds `call` to the contract at `to`, sending `value` wei with it.
+    pub fn call_with_value<C: SolCall>(self, to: Address, value: U256, call: C) -> Self {
+        self.raw_call(Call { to, value, data: call.abi_encode().into() })
+    }
+
+    /// Adds a call already encoded, or a plain ETH transfer with empty `data`.
+    pub fn raw_call(mut self, call: Call) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// The account's `executeBatch` making every call, with `value` left empty when none of them
+    /// sends ETH.
+    pub fn execute_batch(&self) -> ISmartAccount::executeBatchCall {
+        let sends_value = self.calls.iter().any(|call| !call.value.is_zero());
+        ISmartAccount::executeBatchCall {
+            dest: self.calls.iter().map(|call| call.to).collect(),
+            value: if sends_value { self.calls.iter().map(|call| call.value).collect() } else { Vec::new() },
+            func: self.calls.iter().map(|call| call.data.clone()).collect(),
+        }
+    }
+}
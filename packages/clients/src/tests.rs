@@ -0,0 +1,177 @@
+use alloy_json_rpc::{ErrorPayload, RpcError};
+use alloy_primitives::{address, hex, Address, Bytes, LogData, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types_eth::Log;
+use alloy_sol_types::{Panic, Revert as RevertReason, SolCall, SolError, SolEvent};
+use alloy_transport::TransportError;
+
+use cradle_addresses::{Deployment, Network};
+
+use crate::{
+    erc1155::{Erc1155, IERC1155Token, IERC1155TokenErrors, IERC1155TokenEvents},
+    erc20::{Erc20, IERC20Token, IERC20TokenErrors},
+    smart_account::{Batch, Call, ISmartAccount},
+    Error, Revert,
+};
+
+const TOKEN: Address = address!("a6e41ffd769491a42a6e5ce453259b93983a22ef");
+const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+
+/// What a node answers `eth_call` with when the contract reverts with `data`.
+fn reverted(data: &[u8]) -> TransportError {
+    let payload = format!(r#"{{"code":3,"message":"execution reverted","data":"0x{}"}}"#, hex::encode(data));
+    RpcError::ErrorResp(serde_json::from_str::<ErrorPayload>(&payload).unwrap())
+}
+
+fn token() -> Erc1155<impl Provider> {
+    Erc1155::new(TOKEN, ProviderBuilder::new().on_http("http://localhost:8547".parse().unwrap()))
+}
+
+fn log(address: Address, data: LogData) -> Log {
+    Log { inner: alloy_primitives::Log { address, data }, ..Default::default() }
+}
+
+#[test]
+fn reverts_decode_into_the_component_errors() {
+    let revert = IERC1155Token::ERC1155InsufficientBalance {
+        sender: ALICE,
+        balance: U256::from(1),
+        needed: U256::from(2),
+        id: U256::from(3),
+    };
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&revert.abi_encode()));
+    assert_eq!(error.as_revert(), Some(&IERC1155TokenErrors::ERC1155InsufficientBalance(revert)));
+
+    let revert =
+        IERC20Token::InsufficientAllowance { owner: ALICE, spender: TOKEN, have: U256::ZERO, want: U256::from(1) };
+    let error = Error::<IERC20TokenErrors>::from_transport(reverted(&revert.abi_encode()));
+    assert_eq!(error.as_revert(), Some(&IERC20TokenErrors::InsufficientAllowance(revert)));
+}
+
+#[test]
+fn unknown_reverts_keep_their_data() {
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&[0xde, 0xad]));
+    assert!(matches!(error, Error::UnknownRevert(data) if data == Bytes::from_static(&[0xde, 0xad])));
+
+    // An ERC-20 error isn't one of the ERC-1155 token's
+    let revert = IERC20Token::InsufficientBalance { from: ALICE, have: U256::ZERO, want: U256::from(1) };
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&revert.abi_encode()));
+    assert!(matches!(error, Error::UnknownRevert(_)));
+}
+
+#[test]
+fn reverts_display_the_error_and_its_arguments() {
+    let revert = IERC1155Token::ERC1155InsufficientBalance {
+        sender: ALICE,
+        balance: U256::from(1),
+        needed: U256::from(2),
+        id: U256::from(3),
+    };
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&revert.abi_encode()));
+    assert_eq!(
+        error.to_string(),
+        "reverted with ERC1155InsufficientBalance { sender: 0x00000000000000000000000000000000000a11ce, balance: 1, \
+         needed: 2, id: 3 }"
+    );
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&IERC1155Token::EnforcedPause {}.abi_encode()));
+    assert_eq!(error.to_string(), "reverted with EnforcedPause");
+
+    // Another component's error, passed on by the one called, still reads as that error
+    let revert = IERC20Token::InsufficientBalance { from: ALICE, have: U256::ZERO, want: U256::from(1) };
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&revert.abi_encode()));
+    assert!(error.to_string().starts_with("reverted with InsufficientBalance { from: "));
+    let error = Error::<IERC1155TokenErrors>::from_transport(reverted(&[0xde, 0xad]));
+    assert_eq!(error.to_string(), "reverted with unknown data 0xdead");
+}
+
+#[test]
+fn revert_data_decodes_without_knowing_the_component() {
+    let revert = IERC20Token::NotMinter { caller: ALICE };
+    assert_eq!(Revert::decode(&revert.abi_encode()), Some(Revert::Erc20(IERC20TokenErrors::NotMinter(revert))));
+    let revert = IERC1155Token::ERC1155IdRetired { id: U256::from(7) };
+    assert_eq!(
+        Revert::decode(&revert.abi_encode()),
+        Some(Revert::Erc1155(IERC1155TokenErrors::ERC1155IdRetired(revert)))
+    );
+
+    let reason = RevertReason { reason: "not enough".into() }.abi_encode();
+    assert_eq!(Revert::decode(&reason).unwrap().to_string(), r#"Error("not enough")"#);
+    let panic = Panic { code: U256::from(0x11) }.abi_encode();
+    assert_eq!(Revert::decode(&panic), Some(Revert::Panic(U256::from(0x11))));
+    assert_eq!(Revert::decode(&panic).unwrap().to_string(), "Panic(0x11: arithmetic underflow or overflow)");
+    assert_eq!(Revert::decode(&[]), None);
+}
+
+#[test]
+fn other_rpc_errors_are_transport_errors() {
+    let payload = serde_json::from_str::<ErrorPayload>(r#"{"code":-32000,"message":"nonce too low"}"#).unwrap();
+    let error = Error::<IERC1155TokenErrors>::from_transport(RpcError::ErrorResp(payload));
+    assert!(matches!(error, Error::Transport(_)));
+}
+
+#[test]
+fn decodes_the_contract_events_only() {
+    let transfer = IERC1155Token::TransferSingle {
+        operator: ALICE,
+        from: Address::ZERO,
+        to: ALICE,
+        id: U256::from(1),
+        value: U256::from(100),
+    };
+    let approval = IERC1155Token::ApprovalForAll { account: ALICE, operator: TOKEN, approved: true };
+    let logs = [
+        log(TOKEN, transfer.encode_log_data()),
+        log(ALICE, transfer.encode_log_data()),
+        log(TOKEN, approval.encode_log_data()),
+        log(TOKEN, LogData::new_unchecked(vec![], Bytes::new())),
+    ];
+
+    assert_eq!(
+        token().decode_logs(&logs),
+        [IERC1155TokenEvents::TransferSingle(transfer), IERC1155TokenEvents::ApprovalForAll(approval)]
+    );
+}
+
+#[test]
+fn deployments_resolve_to_clients_of_their_component() {
+    const ITEMS: Deployment = Deployment {
+        name: "game-items",
+        component: "erc1155-stylus",
+        version: "0.1.0",
+        features: &[],
+        address: TOKEN,
+        roles: &[],
+    };
+    const NETWORK: Network = Network { name: "local", chain_id: 412346, deployments: &[ITEMS] };
+    let provider = || ProviderBuilder::new().on_http("http://localhost:8547".parse().unwrap());
+
+    let token = Erc1155::deployed(&NETWORK, "game-items", provider()).unwrap();
+    assert_eq!(token.address(), TOKEN);
+    assert!(Erc20::deployed(&NETWORK, "game-items", provider()).is_none());
+    assert!(Erc1155::deployed(&NETWORK, "gold", provider()).is_none());
+}
+
+#[test]
+fn batches_encode_as_execute_batch() {
+    let approve = IERC20Token::approveCall { spender: TOKEN, value: U256::from(100) };
+    let mint = IERC1155Token::mintCall { to: ALICE, id: U256::from(1), amount: U256::from(2), _data: Bytes::new() };
+    let batch = Batch::new().call(ALICE, approve.clone()).call(TOKEN, mint.clone());
+    assert_eq!(
+        batch.calls(),
+        [
+            Call { to: ALICE, value: U256::ZERO, data: approve.abi_encode().into() },
+            Call { to: TOKEN, value: U256::ZERO, data: mint.abi_encode().into() },
+        ]
+    );
+
+    // No call sends ETH, so `value` is left empty
+    let call = batch.execute_batch();
+    assert_eq!(call.dest, [ALICE, TOKEN]);
+    assert!(call.value.is_empty());
+    assert_eq!(call.func, [Bytes::from(approve.abi_encode()), mint.abi_encode().into()]);
+    let decoded = ISmartAccount::executeBatchCall::abi_decode(&call.abi_encode(), true).unwrap();
+    assert_eq!(decoded.func, call.func);
+
+    let call = batch.raw_call(Call { to: ALICE, value: U256::from(5), data: Bytes::new() }).execute_batch();
+    assert_eq!(call.value, [U256::ZERO, U256::ZERO, U256::from(5)]);
+}
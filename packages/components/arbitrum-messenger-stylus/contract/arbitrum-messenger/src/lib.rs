@@ -0,0 +1,305 @@
+//! Arbitrum L1 and L2 messaging helper
+//!
+//! Pairs with one contract on L1, the counterpart, and handles both directions
+//! of traffic with it:
+//!
+//! - L2 to L1: authorized senders, such as other Cradle components, send
+//!   messages to the counterpart through the ArbSys precompile. They can be
+//!   executed on L1 through the Outbox once the challenge period has passed.
+//! - L1 to L2: the counterpart creates retryable tickets that call this
+//!   contract. Those calls arrive from the counterpart's aliased address, which
+//!   is what `finalize_deposit` checks.
+//!
+//! `finalize_deposit` mints on the linked token when the counterpart is an L1
+//! escrow that locked the original items. Each deposit ID is only accepted
+//! once, so a retryable redeemed twice can't mint twice.
+//!
+//! The helper must be allowed to mint: for the ERC-1155 component that means
+//! owning it, for the ERC-20 component `mintTo` is open.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::{aliases::U160, Address, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Offset added to an L1 contract's address when it calls L2, `0x1111000000000000000000000000000000001111`.
+const L1_TO_L2_ALIAS_OFFSET: U160 = U160::from_limbs([0x0000_0000_0000_1111, 0x0000_0000_0000_0000, 0x1111_0000]);
+/// `token_kind` of an ERC-20 minted with `mintTo`.
+const TOKEN_KIND_ERC20: u8 = 0;
+/// `token_kind` of an ERC-1155 minted with `mint`.
+const TOKEN_KIND_ERC1155: u8 = 1;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct ArbitrumMessenger {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Configures the counterpart and senders
+        address owner;
+        /// Contract on L1 this helper exchanges messages with
+        address l1_counterpart;
+        /// Contracts allowed to send messages to the counterpart
+        mapping(address => bool) senders;
+        /// Token minted by `finalize_deposit`
+        address token;
+        /// Whether `token` is an ERC-20 (0) or an ERC-1155 (1)
+        uint8 token_kind;
+        /// Deposit IDs already minted
+        mapping(uint256 => bool) processed_deposits;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event MessageSentToL1(address indexed sender, uint256 indexed message_id, bytes data);
+    event DepositFinalized(uint256 indexed deposit_id, address indexed to, uint256 token_id, uint256 amount);
+    event SenderUpdated(address indexed sender, bool allowed);
+    event L1CounterpartUpdated(address indexed l1_counterpart);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Token kind is neither ERC-20 nor ERC-1155
+    error InvalidTokenKind(uint8 token_kind);
+    // Deposit ID was already minted
+    error DepositAlreadyProcessed(uint256 deposit_id);
+    // A call to ArbSys failed
+    error ArbSysCallFailed();
+    // Minting on the token failed
+    error MintFailed(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum MessengerError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidTokenKind(InvalidTokenKind),
+    DepositAlreadyProcessed(DepositAlreadyProcessed),
+    ArbSysCallFailed(ArbSysCallFailed),
+    MintFailed(MintFailed),
+}
+
+// External interfaces
+sol! {
+    interface ICradleToken {
+        function mintTo(address to, uint256 value) external;
+    }
+
+    interface ICradle1155 {
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+    }
+}
+
+/// Context of the mints on the linked token. Made through the host, unlike `sol_interface!` calls,
+/// they build with the SDK's `reentrant` feature and are mocked under test.
+struct MintCall;
+
+impl calls::CallContext for MintCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale if the token calls back into the helper, as an ERC-1155 mint's receiver hook may
+unsafe impl calls::MutatingCallContext for MintCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+/// Address an L1 contract appears as when it calls L2.
+pub fn apply_l1_to_l2_alias(l1_address: Address) -> Address {
+    Address::from(U160::from_be_bytes(l1_address.0 .0).wrapping_add(L1_TO_L2_ALIAS_OFFSET))
+}
+
+/// L1 address behind an aliased L2 caller.
+pub fn undo_l1_to_l2_alias(l2_address: Address) -> Address {
+    Address::from(U160::from_be_bytes(l2_address.0 .0).wrapping_sub(L1_TO_L2_ALIAS_OFFSET))
+}
+
+// Helpers used by the public methods below.
+impl ArbitrumMessenger {
+    fn only_owner(&self) -> Result<(), MessengerError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(MessengerError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Fails unless the caller is the aliased L1 counterpart, i.e. a retryable ticket it created.
+    fn only_l1_counterpart(&self) -> Result<(), MessengerError> {
+        let caller = self.vm().msg_sender();
+        if caller != apply_l1_to_l2_alias(self.l1_counterpart.get()) {
+            return Err(MessengerError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl ArbitrumMessenger {
+    /// Sets the owner, the L1 counterpart and the token minted for deposits. Can only be called once.
+    /// `token_kind` is 0 for an ERC-20 and 1 for an ERC-1155.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        l1_counterpart: Address,
+        token: Address,
+        token_kind: u8,
+    ) -> Result<(), MessengerError> {
+        if self.initialized.get() {
+            return Err(MessengerError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if token_kind != TOKEN_KIND_ERC20 && token_kind != TOKEN_KIND_ERC1155 {
+            return Err(MessengerError::InvalidTokenKind(InvalidTokenKind { token_kind }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.l1_counterpart.set(l1_counterpart);
+        self.token.set(token);
+        self.token_kind.set(alloy_primitives::Uint::<8, 1>::from(token_kind));
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), L1CounterpartUpdated { l1_counterpart });
+        Ok(())
+    }
+
+    /// Sends `data` to the L1 counterpart (authorized senders and owner only). Returns the ArbSys
+    /// message ID. The counterpart should check that the Outbox's `l2ToL1Sender` is this contract.
+    pub fn send_to_l1(&mut self, data: Bytes) -> Result<U256, MessengerError> {
+        let sender = self.vm().msg_sender();
+        if !self.senders.get(sender) && sender != self.owner.get() {
+            return Err(MessengerError::Unauthorized(Unauthorized { caller: sender }));
+        }
+        let destination = self.l1_counterpart.get();
+        let message_id = cradle_arb::send_tx_to_l1(self.vm(), destination, &data, U256::ZERO)
+            .map_err(|_| MessengerError::ArbSysCallFailed(ArbSysCallFailed {}))?;
+
+        log(self.vm(), MessageSentToL1 {
+            sender,
+            message_id,
+            data: data.0.into(),
+        });
+        Ok(message_id)
+    }
+
+    /// Mints `amount` to `to` for a deposit locked on L1 (L1 counterpart's retryables only).
+    /// `token_id` is ignored for ERC-20s. Each `deposit_id` is only minted once.
+    pub fn finalize_deposit(
+        &mut self,
+        deposit_id: U256,
+        to: Address,
+        token_id: U256,
+        amount: U256,
+    ) -> Result<(), MessengerError> {
+        self.only_l1_counterpart()?;
+        if self.processed_deposits.get(deposit_id) {
+            return Err(MessengerError::DepositAlreadyProcessed(DepositAlreadyProcessed { deposit_id }));
+        }
+        self.processed_deposits.insert(deposit_id, true);
+
+        let token = self.token.get();
+        let calldata = if self.token_kind.get().to::<u8>() == TOKEN_KIND_ERC1155 {
+            ICradle1155::mintCall { to, id: token_id, amount, data: Default::default() }.abi_encode()
+        } else {
+            ICradleToken::mintToCall { to, value: amount }.abi_encode()
+        };
+        if self.vm().call(&MintCall, token, &calldata).is_err() {
+            return Err(MessengerError::MintFailed(MintFailed { token }));
+        }
+
+        log(self.vm(), DepositFinalized {
+            deposit_id,
+            to,
+            token_id,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Allows or disallows a contract to send messages to the counterpart (owner only).
+    pub fn set_sender(&mut self, sender: Address, allowed: bool) -> Result<(), MessengerError> {
+        self.only_owner()?;
+        self.senders.insert(sender, allowed);
+
+        log(self.vm(), SenderUpdated { sender, allowed });
+        Ok(())
+    }
+
+    /// Replaces the L1 counterpart (owner only).
+    pub fn set_l1_counterpart(&mut self, l1_counterpart: Address) -> Result<(), MessengerError> {
+        self.only_owner()?;
+        self.l1_counterpart.set(l1_counterpart);
+
+        log(self.vm(), L1CounterpartUpdated { l1_counterpart });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), MessengerError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Address an L1 contract appears as when it calls L2.
+    pub fn l1_to_l2_alias(&self, l1_address: Address) -> Address {
+        apply_l1_to_l2_alias(l1_address)
+    }
+
+    /// L1 address behind an aliased L2 caller.
+    pub fn l2_to_l1_alias(&self, l2_address: Address) -> Address {
+        undo_l1_to_l2_alias(l2_address)
+    }
+
+    /// Whether `caller` is the aliased L1 counterpart.
+    pub fn is_l1_counterpart(&self, caller: Address) -> bool {
+        caller == apply_l1_to_l2_alias(self.l1_counterpart.get())
+    }
+
+    /// Whether a deposit ID has been minted.
+    pub fn is_deposit_processed(&self, deposit_id: U256) -> bool {
+        self.processed_deposits.get(deposit_id)
+    }
+
+    /// Whether `sender` may send messages to the counterpart.
+    pub fn is_sender(&self, sender: Address) -> bool {
+        self.senders.get(sender)
+    }
+
+    /// Returns `(l1_counterpart, token, token_kind)`.
+    pub fn config(&self) -> (Address, Address, u8) {
+        (
+            self.l1_counterpart.get(),
+            self.token.get(),
+            self.token_kind.get().to::<u8>(),
+        )
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
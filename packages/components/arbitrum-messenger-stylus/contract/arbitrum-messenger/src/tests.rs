@@ -0,0 +1,143 @@
+use super::*;
+use alloc::vec::Vec;
+use alloy_primitives::address;
+use alloy_sol_types::{SolError, SolValue};
+use cradle_arb::{ArbSys, ARB_SYS};
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const COMPONENT: Address = address!("000000000000000000000000000000000000c0de");
+const ESCROW: Address = address!("000000000000000000000000000000000000e5c0");
+const TOKEN: Address = address!("0000000000000000000000000000000000001155");
+
+/// A helper paired with `ESCROW` on L1 that mints `TOKEN` of `token_kind`.
+fn setup(token_kind: u8) -> (TestVM, ArbitrumMessenger) {
+    let (vm, mut contract) = deploy::<ArbitrumMessenger>(OWNER);
+    ok(contract.initialize(OWNER, ESCROW, TOKEN, token_kind));
+    (vm, contract)
+}
+
+/// Mocks the token answering `answer` to the mint `finalize_deposit` makes for `amount` of
+/// `token_id` to `ALICE`.
+fn mock_mint(vm: &TestVM, token_kind: u8, token_id: u64, amount: u64, answer: Result<Vec<u8>, Vec<u8>>) {
+    let calldata = if token_kind == TOKEN_KIND_ERC1155 {
+        ICradle1155::mintCall { to: ALICE, id: n(token_id), amount: n(amount), data: Default::default() }.abi_encode()
+    } else {
+        ICradleToken::mintToCall { to: ALICE, value: n(amount) }.abi_encode()
+    };
+    vm.mock_call(TOKEN, calldata, answer);
+}
+
+#[test]
+fn initialize_runs_once_with_a_known_token_kind() {
+    let (vm, mut contract) = deploy::<ArbitrumMessenger>(OWNER);
+    let error = InvalidTokenKind { token_kind: 2 }.abi_encode();
+    assert_eq!(reverted(contract.initialize(OWNER, ESCROW, TOKEN, 2)), error);
+    ok(contract.initialize(OWNER, ESCROW, TOKEN, TOKEN_KIND_ERC1155));
+    assert_eq!((contract.owner(), contract.config()), (OWNER, (ESCROW, TOKEN, TOKEN_KIND_ERC1155)));
+    let event = emitted::<L1CounterpartUpdated>(&vm).pop().expect("counterpart logged");
+    assert_eq!(event.l1_counterpart, ESCROW);
+
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.initialize(ALICE, ALICE, ALICE, 0)), AlreadyInitialized {}.abi_encode());
+}
+
+#[test]
+fn l1_addresses_are_aliased_by_the_fixed_offset() {
+    let (_vm, contract) = setup(TOKEN_KIND_ERC20);
+    let offset = address!("1111000000000000000000000000000000001111");
+    assert_eq!(contract.l1_to_l2_alias(Address::ZERO), offset);
+    assert_eq!(contract.l1_to_l2_alias(ESCROW), address!("111100000000000000000000000000000000f6d1"));
+    // The offset wraps around the top of the address space
+    let high = address!("ffffffffffffffffffffffffffffffffffffffff");
+    assert_eq!(contract.l1_to_l2_alias(high), address!("1111000000000000000000000000000000001110"));
+    for l1_address in [Address::ZERO, ESCROW, high] {
+        assert_eq!(contract.l2_to_l1_alias(contract.l1_to_l2_alias(l1_address)), l1_address);
+    }
+    assert!(contract.is_l1_counterpart(apply_l1_to_l2_alias(ESCROW)));
+    assert!(!contract.is_l1_counterpart(ESCROW));
+}
+
+#[test]
+fn senders_and_the_owner_message_the_counterpart() {
+    let (vm, mut contract) = setup(TOKEN_KIND_ERC20);
+    let data = Bytes::from(vec![0xab, 0xcd]);
+    let call = ArbSys::sendTxToL1Call { destination: ESCROW, data: data.0.clone().into() };
+    vm.mock_call(ARB_SYS, call.abi_encode(), Ok(n(7).abi_encode()));
+
+    vm.set_sender(COMPONENT);
+    assert_eq!(reverted(contract.send_to_l1(data.clone())), Unauthorized { caller: COMPONENT }.abi_encode());
+    vm.set_sender(OWNER);
+    ok(contract.set_sender(COMPONENT, true));
+    assert!(contract.is_sender(COMPONENT));
+    let event = emitted::<SenderUpdated>(&vm).pop().expect("sender logged");
+    assert_eq!((event.sender, event.allowed), (COMPONENT, true));
+
+    assert_eq!(ok(contract.send_to_l1(data.clone())), n(7));
+    vm.set_sender(COMPONENT);
+    assert_eq!(ok(contract.send_to_l1(data.clone())), n(7));
+    let event = emitted::<MessageSentToL1>(&vm).pop().expect("message logged");
+    assert_eq!((event.sender, event.message_id, event.data.to_vec()), (COMPONENT, n(7), data.0.clone()));
+
+    // Off Arbitrum, ArbSys answers nothing
+    vm.mock_call(ARB_SYS, call.abi_encode(), Ok(Vec::new()));
+    assert_eq!(reverted(contract.send_to_l1(data)), ArbSysCallFailed {}.abi_encode());
+}
+
+#[test]
+fn only_the_counterparts_retryables_finalize_deposits() {
+    let (vm, mut contract) = setup(TOKEN_KIND_ERC20);
+    for caller in [ESCROW, OWNER] {
+        vm.set_sender(caller);
+        assert_eq!(
+            reverted(contract.finalize_deposit(n(1), ALICE, U256::ZERO, n(50))),
+            Unauthorized { caller }.abi_encode()
+        );
+    }
+    assert!(!contract.is_deposit_processed(n(1)));
+
+    vm.set_sender(apply_l1_to_l2_alias(ESCROW));
+    ok(contract.finalize_deposit(n(1), ALICE, U256::ZERO, n(50)));
+    assert!(contract.is_deposit_processed(n(1)));
+    let event = emitted::<DepositFinalized>(&vm).pop().expect("deposit logged");
+    assert_eq!((event.deposit_id, event.to, event.token_id, event.amount), (n(1), ALICE, U256::ZERO, n(50)));
+    let error = DepositAlreadyProcessed { deposit_id: n(1) }.abi_encode();
+    assert_eq!(reverted(contract.finalize_deposit(n(1), ALICE, U256::ZERO, n(50))), error);
+}
+
+#[test]
+fn deposits_mint_on_the_linked_token() {
+    for token_kind in [TOKEN_KIND_ERC20, TOKEN_KIND_ERC1155] {
+        let (vm, mut contract) = setup(token_kind);
+        vm.set_sender(apply_l1_to_l2_alias(ESCROW));
+
+        // The token only fails the mint the deposit asks for, so failing shows it was asked
+        mock_mint(&vm, token_kind, 3, 50, Err(Vec::new()));
+        let error = MintFailed { token: TOKEN }.abi_encode();
+        assert_eq!(reverted(contract.finalize_deposit(n(1), ALICE, n(3), n(50))), error);
+        mock_mint(&vm, token_kind, 3, 50, Ok(Vec::new()));
+        ok(contract.finalize_deposit(n(2), ALICE, n(3), n(50)));
+        assert!(contract.is_deposit_processed(n(2)));
+    }
+}
+
+#[test]
+fn only_the_owner_configures_the_helper() {
+    let (vm, mut contract) = setup(TOKEN_KIND_ERC20);
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_sender(ALICE, true)), error);
+    assert_eq!(reverted(contract.set_l1_counterpart(ALICE)), error);
+    assert_eq!(reverted(contract.transfer_ownership(ALICE)), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.set_l1_counterpart(ALICE));
+    assert!(contract.is_l1_counterpart(apply_l1_to_l2_alias(ALICE)));
+    assert!(!contract.is_l1_counterpart(apply_l1_to_l2_alias(ESCROW)));
+    ok(contract.transfer_ownership(ALICE));
+    let event = emitted::<OwnershipTransferred>(&vm).pop().expect("ownership logged");
+    assert_eq!((event.previous_owner, event.new_owner), (OWNER, ALICE));
+    assert_eq!(reverted(contract.set_sender(OWNER, true)), Unauthorized { caller: OWNER }.abi_encode());
+}
@@ -0,0 +1,322 @@
+//! EAS-style attestation registry
+//!
+//! Anyone can register a schema describing the data of an attestation, and
+//! anyone can attest to a recipient under a schema. Consumers decide which
+//! attesters they trust: an allowlist or KYC gate checks
+//! `has_valid_attestation(schema, user, trusted_attester)`.
+//!
+//! A schema may name a resolver contract that is asked to approve every
+//! attestation and revocation made under it, e.g. to restrict who may attest.
+//! Attestations can expire, and revocable ones can be revoked by their
+//! attester if their schema allows revocation.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::string::String;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+sol_storage! {
+    #[entrypoint]
+    pub struct AttestationRegistry {
+        /// Registered schemas by UID
+        mapping(bytes32 => Schema) schemas;
+        /// Attestations by UID
+        mapping(bytes32 => Attestation) attestations;
+        /// Latest attestation per schema, recipient and attester
+        mapping(bytes32 => mapping(address => mapping(address => bytes32))) latest;
+        /// Attestations made so far, mixed into UIDs
+        uint256 total_attestations;
+    }
+
+    pub struct Schema {
+        bool registered;
+        /// Field definition, e.g. "bool kycPassed,uint8 tier"
+        string definition;
+        /// Contract approving attestations and revocations; zero for none
+        address resolver;
+        /// Whether attestations under the schema can be revoked
+        bool revocable;
+    }
+
+    pub struct Attestation {
+        bytes32 schema;
+        address recipient;
+        address attester;
+        uint256 time;
+        /// Zero if it never expires
+        uint256 expiration_time;
+        /// Zero if not revoked
+        uint256 revocation_time;
+        bool revocable;
+        bytes data;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event SchemaRegistered(bytes32 indexed uid, address indexed registerer, address resolver, bool revocable);
+    event Attested(address indexed recipient, address indexed attester, bytes32 uid, bytes32 indexed schema);
+    event Revoked(address indexed recipient, address indexed attester, bytes32 uid, bytes32 indexed schema);
+
+    // Schema with the same definition, resolver and revocability exists
+    error SchemaAlreadyExists(bytes32 uid);
+    // Schema isn't registered
+    error UnknownSchema(bytes32 uid);
+    // Attestation doesn't exist
+    error UnknownAttestation(bytes32 uid);
+    // Expiration time is in the past
+    error InvalidExpirationTime(uint256 expiration_time);
+    // Revocable attestation under a schema that doesn't allow revocation, or revoking an irrevocable one
+    error Irrevocable(bytes32 uid);
+    // Attestation was already revoked
+    error AlreadyRevoked(bytes32 uid);
+    // Caller isn't the attester
+    error Unauthorized(address caller);
+    // Schema resolver rejected the attestation or revocation
+    error ResolverRejected(address resolver);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum AttestationError {
+    SchemaAlreadyExists(SchemaAlreadyExists),
+    UnknownSchema(UnknownSchema),
+    UnknownAttestation(UnknownAttestation),
+    InvalidExpirationTime(InvalidExpirationTime),
+    Irrevocable(Irrevocable),
+    AlreadyRevoked(AlreadyRevoked),
+    Unauthorized(Unauthorized),
+    ResolverRejected(ResolverRejected),
+}
+
+// External interfaces
+sol! {
+    interface ISchemaResolver {
+        function onAttest(bytes32 uid, address attester, address recipient, bytes data) external returns (bool);
+        function onRevoke(bytes32 uid, address attester, address recipient) external returns (bool);
+    }
+}
+
+/// Context of the calls to schema resolvers. Made through the host, unlike `sol_interface!` calls,
+/// they build with the SDK's `reentrant` feature and are mocked under test.
+struct ResolverCall;
+
+impl calls::CallContext for ResolverCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale if a resolver calls back into the registry
+unsafe impl calls::MutatingCallContext for ResolverCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl AttestationRegistry {
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn is_valid(&self, uid: B256) -> bool {
+        let attestation = self.attestations.getter(uid);
+        let expiration_time = attestation.expiration_time.get();
+        !attestation.attester.get().is_zero()
+            && attestation.revocation_time.get().is_zero()
+            && (expiration_time.is_zero() || expiration_time > self.now())
+    }
+
+    /// Whether `resolver` answers `true` to `calldata`. Reverts and other answers reject.
+    fn resolver_approves(&mut self, resolver: Address, calldata: &[u8]) -> bool {
+        self.vm()
+            .call(&ResolverCall, resolver, calldata)
+            .is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+    }
+}
+
+#[public]
+impl AttestationRegistry {
+    /// Registers a schema. `resolver` may be zero. Returns the schema UID,
+    /// `keccak256(abi.encode(definition, resolver, revocable))`.
+    pub fn register_schema(
+        &mut self,
+        definition: String,
+        resolver: Address,
+        revocable: bool,
+    ) -> Result<B256, AttestationError> {
+        let uid = self
+            .vm()
+            .native_keccak256(&(definition.clone(), resolver, revocable).abi_encode_params());
+        if self.schemas.getter(uid).registered.get() {
+            return Err(AttestationError::SchemaAlreadyExists(SchemaAlreadyExists { uid }));
+        }
+        let mut schema = self.schemas.setter(uid);
+        schema.registered.set(true);
+        schema.definition.set_str(definition);
+        schema.resolver.set(resolver);
+        schema.revocable.set(revocable);
+
+        log(self.vm(), SchemaRegistered {
+            uid,
+            registerer: self.vm().msg_sender(),
+            resolver,
+            revocable,
+        });
+        Ok(uid)
+    }
+
+    /// Attests to `recipient` under `schema` with the caller as attester. `expiration_time` is zero
+    /// for an attestation that never expires. Returns the attestation UID.
+    pub fn attest(
+        &mut self,
+        schema: B256,
+        recipient: Address,
+        data: Bytes,
+        expiration_time: U256,
+        revocable: bool,
+    ) -> Result<B256, AttestationError> {
+        let schema_record = self.schemas.getter(schema);
+        if !schema_record.registered.get() {
+            return Err(AttestationError::UnknownSchema(UnknownSchema { uid: schema }));
+        }
+        let resolver = schema_record.resolver.get();
+        let schema_revocable = schema_record.revocable.get();
+        let now = self.now();
+        if !expiration_time.is_zero() && expiration_time <= now {
+            return Err(AttestationError::InvalidExpirationTime(InvalidExpirationTime { expiration_time }));
+        }
+        let attester = self.vm().msg_sender();
+        let nonce = self.total_attestations.get();
+        let data_bytes = alloy_primitives::Bytes::from(data.0.clone());
+        let encoded = (schema, recipient, attester, now, expiration_time, revocable, data_bytes, nonce).abi_encode_params();
+        let uid = self.vm().native_keccak256(&encoded);
+        if revocable && !schema_revocable {
+            return Err(AttestationError::Irrevocable(Irrevocable { uid }));
+        }
+        self.total_attestations.set(nonce + U256::from(1));
+
+        let mut attestation = self.attestations.setter(uid);
+        attestation.schema.set(schema);
+        attestation.recipient.set(recipient);
+        attestation.attester.set(attester);
+        attestation.time.set(now);
+        attestation.expiration_time.set(expiration_time);
+        attestation.revocable.set(revocable);
+        attestation.data.set_bytes(&data);
+        self.latest.setter(schema).setter(recipient).insert(attester, uid);
+
+        if !resolver.is_zero() {
+            let call = ISchemaResolver::onAttestCall { uid, attester, recipient, data: data.0.into() };
+            if !self.resolver_approves(resolver, &call.abi_encode()) {
+                return Err(AttestationError::ResolverRejected(ResolverRejected { resolver }));
+            }
+        }
+
+        log(self.vm(), Attested {
+            recipient,
+            attester,
+            uid,
+            schema,
+        });
+        Ok(uid)
+    }
+
+    /// Revokes an attestation made by the caller.
+    pub fn revoke(&mut self, uid: B256) -> Result<(), AttestationError> {
+        let attestation = self.attestations.getter(uid);
+        let attester = attestation.attester.get();
+        if attester.is_zero() {
+            return Err(AttestationError::UnknownAttestation(UnknownAttestation { uid }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != attester {
+            return Err(AttestationError::Unauthorized(Unauthorized { caller }));
+        }
+        if !attestation.revocable.get() {
+            return Err(AttestationError::Irrevocable(Irrevocable { uid }));
+        }
+        if !attestation.revocation_time.get().is_zero() {
+            return Err(AttestationError::AlreadyRevoked(AlreadyRevoked { uid }));
+        }
+        let schema = attestation.schema.get();
+        let recipient = attestation.recipient.get();
+        let now = self.now();
+        self.attestations.setter(uid).revocation_time.set(now);
+
+        let resolver = self.schemas.getter(schema).resolver.get();
+        if !resolver.is_zero() {
+            let call = ISchemaResolver::onRevokeCall { uid, attester, recipient };
+            if !self.resolver_approves(resolver, &call.abi_encode()) {
+                return Err(AttestationError::ResolverRejected(ResolverRejected { resolver }));
+            }
+        }
+
+        log(self.vm(), Revoked {
+            recipient,
+            attester,
+            uid,
+            schema,
+        });
+        Ok(())
+    }
+
+    /// Returns `(registered, definition, resolver, revocable)` for a schema.
+    pub fn get_schema(&self, uid: B256) -> (bool, String, Address, bool) {
+        let schema = self.schemas.getter(uid);
+        (
+            schema.registered.get(),
+            schema.definition.get_string(),
+            schema.resolver.get(),
+            schema.revocable.get(),
+        )
+    }
+
+    /// Returns `(schema, recipient, attester, time, expiration_time, revocation_time, revocable, data)`.
+    #[allow(clippy::type_complexity)]
+    pub fn get_attestation(&self, uid: B256) -> (B256, Address, Address, U256, U256, U256, bool, Bytes) {
+        let attestation = self.attestations.getter(uid);
+        (
+            attestation.schema.get(),
+            attestation.recipient.get(),
+            attestation.attester.get(),
+            attestation.time.get(),
+            attestation.expiration_time.get(),
+            attestation.revocation_time.get(),
+            attestation.revocable.get(),
+            attestation.data.get_bytes().into(),
+        )
+    }
+
+    /// Whether an attestation exists, isn't revoked and hasn't expired.
+    pub fn is_attestation_valid(&self, uid: B256) -> bool {
+        self.is_valid(uid)
+    }
+
+    /// UID of the latest attestation `attester` made to `recipient` under `schema`; zero if none.
+    pub fn latest_attestation(&self, schema: B256, recipient: Address, attester: Address) -> B256 {
+        self.latest.getter(schema).getter(recipient).get(attester)
+    }
+
+    /// Whether the latest attestation `attester` made to `recipient` under `schema` is valid.
+    pub fn has_valid_attestation(&self, schema: B256, recipient: Address, attester: Address) -> bool {
+        let uid = self.latest.getter(schema).getter(recipient).get(attester);
+        self.is_valid(uid)
+    }
+
+    /// Number of attestations made.
+    pub fn total_attestations(&self) -> U256 {
+        self.total_attestations.get()
+    }
+}
@@ -0,0 +1,165 @@
+use super::*;
+use alloc::vec::Vec;
+use alloy_primitives::address;
+use alloy_sol_types::SolError;
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const ISSUER: Address = address!("000000000000000000000000000000000000155e");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const RESOLVER: Address = address!("0000000000000000000000000000000000005e50");
+const KYC: &str = "bool kycPassed,uint8 tier";
+
+/// A registry at timestamp 1000 with a revocable KYC schema, called by `ISSUER`.
+fn setup() -> (TestVM, AttestationRegistry, B256) {
+    let (vm, mut contract) = deploy::<AttestationRegistry>(ISSUER);
+    vm.set_block_timestamp(1_000);
+    let schema = ok(contract.register_schema(KYC.into(), Address::ZERO, true));
+    (vm, contract, schema)
+}
+
+fn kyc_data() -> Bytes {
+    (true, n(2)).abi_encode().into()
+}
+
+#[test]
+fn schemas_are_registered_once_under_their_uid() {
+    let (vm, mut contract, schema) = setup();
+    let uid = vm.native_keccak256(&(String::from(KYC), Address::ZERO, true).abi_encode_params());
+    assert_eq!(schema, uid);
+    assert_eq!(contract.get_schema(schema), (true, KYC.into(), Address::ZERO, true));
+    let event = emitted::<SchemaRegistered>(&vm).pop().expect("registration logged");
+    assert_eq!((event.uid, event.registerer, event.resolver, event.revocable), (schema, ISSUER, Address::ZERO, true));
+
+    vm.set_sender(ALICE);
+    let error = SchemaAlreadyExists { uid: schema }.abi_encode();
+    assert_eq!(reverted(contract.register_schema(KYC.into(), Address::ZERO, true)), error);
+    // The same definition with another revocability is another schema
+    let irrevocable = ok(contract.register_schema(KYC.into(), Address::ZERO, false));
+    assert_ne!(irrevocable, schema);
+    assert_eq!(contract.get_schema(B256::ZERO), (false, String::new(), Address::ZERO, false));
+}
+
+#[test]
+fn attestations_are_recorded_as_the_latest_from_their_attester() {
+    let (vm, mut contract, schema) = setup();
+    let uid = ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true));
+    assert_eq!(
+        contract.get_attestation(uid),
+        (schema, ALICE, ISSUER, n(1_000), U256::ZERO, U256::ZERO, true, kyc_data())
+    );
+    assert!(contract.is_attestation_valid(uid));
+    assert!(contract.has_valid_attestation(schema, ALICE, ISSUER));
+    assert!(!contract.has_valid_attestation(schema, ALICE, BOB));
+    assert_eq!(contract.total_attestations(), n(1));
+    let event = emitted::<Attested>(&vm).pop().expect("attestation logged");
+    assert_eq!((event.recipient, event.attester, event.uid, event.schema), (ALICE, ISSUER, uid, schema));
+
+    // The same attestation again gets a new UID and replaces the latest
+    let again = ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true));
+    assert_ne!(again, uid);
+    assert_eq!(contract.latest_attestation(schema, ALICE, ISSUER), again);
+    assert!(contract.is_attestation_valid(uid));
+    assert_eq!(contract.total_attestations(), n(2));
+}
+
+#[test]
+fn attestations_must_match_a_registered_schema() {
+    let (_vm, mut contract, schema) = setup();
+    let unknown = B256::repeat_byte(1);
+    let error = UnknownSchema { uid: unknown }.abi_encode();
+    assert_eq!(reverted(contract.attest(unknown, ALICE, kyc_data(), U256::ZERO, false)), error);
+
+    let irrevocable = ok(contract.register_schema(KYC.into(), Address::ZERO, false));
+    let error = reverted(contract.attest(irrevocable, ALICE, kyc_data(), U256::ZERO, true));
+    assert_eq!(error[..4], Irrevocable::SELECTOR);
+    ok(contract.attest(irrevocable, ALICE, kyc_data(), U256::ZERO, false));
+    ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, false));
+    assert_eq!(contract.total_attestations(), n(2));
+}
+
+#[test]
+fn attestations_expire_at_their_expiration_time() {
+    let (vm, mut contract, schema) = setup();
+    for past in [999, 1_000] {
+        let error = InvalidExpirationTime { expiration_time: n(past) }.abi_encode();
+        assert_eq!(reverted(contract.attest(schema, ALICE, kyc_data(), n(past), true)), error);
+    }
+    let uid = ok(contract.attest(schema, ALICE, kyc_data(), n(2_000), true));
+
+    vm.set_block_timestamp(1_999);
+    assert!(contract.has_valid_attestation(schema, ALICE, ISSUER));
+    vm.set_block_timestamp(2_000);
+    assert!(!contract.is_attestation_valid(uid));
+    assert!(!contract.has_valid_attestation(schema, ALICE, ISSUER));
+}
+
+#[test]
+fn attesters_revoke_their_revocable_attestations() {
+    let (vm, mut contract, schema) = setup();
+    let uid = ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true));
+    let irrevocable = ok(contract.attest(schema, BOB, kyc_data(), U256::ZERO, false));
+
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.revoke(uid)), Unauthorized { caller: ALICE }.abi_encode());
+    vm.set_sender(ISSUER);
+    assert_eq!(reverted(contract.revoke(irrevocable)), Irrevocable { uid: irrevocable }.abi_encode());
+    let unknown = B256::repeat_byte(1);
+    assert_eq!(reverted(contract.revoke(unknown)), UnknownAttestation { uid: unknown }.abi_encode());
+    assert!(contract.is_attestation_valid(uid));
+
+    vm.set_block_timestamp(1_500);
+    ok(contract.revoke(uid));
+    assert_eq!(contract.get_attestation(uid).5, n(1_500));
+    assert!(!contract.has_valid_attestation(schema, ALICE, ISSUER));
+    let event = emitted::<Revoked>(&vm).pop().expect("revocation logged");
+    assert_eq!((event.recipient, event.attester, event.uid, event.schema), (ALICE, ISSUER, uid, schema));
+    assert_eq!(reverted(contract.revoke(uid)), AlreadyRevoked { uid }.abi_encode());
+}
+
+/// Mocks `RESOLVER` answering `answer` when asked about the next attestation `ISSUER` makes to
+/// `ALICE` under `schema` at timestamp 1000, and returns that attestation's UID.
+fn mock_on_attest(vm: &TestVM, contract: &AttestationRegistry, schema: B256, answer: Result<Vec<u8>, Vec<u8>>) -> B256 {
+    let data = alloy_primitives::Bytes::from(kyc_data().0);
+    let nonce = contract.total_attestations();
+    let preimage = (schema, ALICE, ISSUER, n(1_000), U256::ZERO, true, data.clone(), nonce);
+    let uid = vm.native_keccak256(&preimage.abi_encode_params());
+    let call = ISchemaResolver::onAttestCall { uid, attester: ISSUER, recipient: ALICE, data };
+    vm.mock_call(RESOLVER, call.abi_encode(), answer);
+    uid
+}
+
+#[test]
+fn resolvers_approve_attestations() {
+    let (vm, mut contract, _) = setup();
+    let schema = ok(contract.register_schema(KYC.into(), RESOLVER, true));
+    let error = ResolverRejected { resolver: RESOLVER }.abi_encode();
+
+    // A resolver that answers nothing or false, or reverts, rejects
+    for answer in [Ok(Vec::new()), Ok(false.abi_encode()), Err(Vec::new())] {
+        mock_on_attest(&vm, &contract, schema, answer);
+        assert_eq!(reverted(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true)), error);
+    }
+    let uid = mock_on_attest(&vm, &contract, schema, Ok(true.abi_encode()));
+    assert_eq!(ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true)), uid);
+    assert!(contract.is_attestation_valid(uid));
+}
+
+#[test]
+fn resolvers_approve_revocations() {
+    let (vm, mut contract, _) = setup();
+    let schema = ok(contract.register_schema(KYC.into(), RESOLVER, true));
+    let approved = mock_on_attest(&vm, &contract, schema, Ok(true.abi_encode()));
+    ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true));
+    let rejected = mock_on_attest(&vm, &contract, schema, Ok(true.abi_encode()));
+    ok(contract.attest(schema, ALICE, kyc_data(), U256::ZERO, true));
+
+    for (uid, answer) in [(approved, true), (rejected, false)] {
+        let call = ISchemaResolver::onRevokeCall { uid, attester: ISSUER, recipient: ALICE };
+        vm.mock_call(RESOLVER, call.abi_encode(), Ok(answer.abi_encode()));
+    }
+    ok(contract.revoke(approved));
+    assert!(!contract.is_attestation_valid(approved));
+    assert_eq!(reverted(contract.revoke(rejected)), ResolverRejected { resolver: RESOLVER }.abi_encode());
+}
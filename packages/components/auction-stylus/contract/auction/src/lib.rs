@@ -0,0 +1,690 @@
+//! English auction for ERC-1155 and ERC-721 lots
+//!
+//! A seller escrows a lot in the contract and opens an ascending-price auction
+//! paid in ETH or an ERC-20. Each new bid must clear the reserve price and beat
+//! the previous bid by the configured increment; the outbid bidder is credited
+//! and withdraws their funds later (pull pattern), so a reverting bidder can't
+//! block the auction.
+//!
+//! Bids placed within the extension window of the end push the end back, which
+//! stops last-second sniping. Once the auction ends anyone can settle it: the lot
+//! goes to the winner and the proceeds are split between the protocol fee, the
+//! ERC-2981 royalty and the seller, all credited as withdrawable balances.
+//!
+//! Durations and end times are counted by the clock chosen at initialization, in
+//! seconds of `block.timestamp`, L2 blocks or L1 blocks.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_arb::{
+    clock::{Clock, InvalidClockMode},
+    PrecompileCallFailed,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Upper bound for the protocol fee, 10%.
+const MAX_PROTOCOL_FEE_BPS: u64 = 1_000;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+const IERC1155_INTERFACE_ID: u32 = 0xd9b67a26;
+const IERC721_INTERFACE_ID: u32 = 0x80ac58cd;
+const IERC2981_INTERFACE_ID: u32 = 0x2a55205a;
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC721Received`.
+const ERC721_RECEIVED: u32 = 0x150b7a02;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Auction {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Can update fees and auction parameters
+        address owner;
+        /// Receives the protocol fee of every settled auction
+        address fee_recipient;
+        /// Protocol fee in basis points
+        uint256 protocol_fee_bps;
+        /// Minimum raise over the current highest bid, in basis points
+        uint256 min_bid_increment_bps;
+        /// Bids in the last `extension_window` of an auction push the end to `now + extension_window`
+        uint256 extension_window;
+        /// Id assigned to the next auction
+        uint256 next_auction_id;
+        /// Auction id to auction
+        mapping(uint256 => Lot) auctions;
+        /// Payment token to account to withdrawable amount
+        mapping(address => mapping(address => uint256)) pending_returns;
+        /// What durations and end times are counted in, set by `initialize`
+        Clock clock;
+    }
+
+    pub struct Lot {
+        address seller;
+        address token_contract;
+        uint256 token_id;
+        uint256 amount;
+        bool is_erc1155;
+        /// ERC-20 used for bids, or the zero address for ETH
+        address payment_token;
+        uint256 reserve_price;
+        uint256 end_time;
+        address highest_bidder;
+        uint256 highest_bid;
+        /// Set once the lot has been handed out, by settlement or cancellation
+        bool closed;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event AuctionCreated(uint256 indexed auction_id, address indexed seller, address indexed token_contract, uint256 token_id, uint256 amount, address payment_token, uint256 reserve_price, uint256 end_time);
+    event BidPlaced(uint256 indexed auction_id, address indexed bidder, uint256 amount);
+    event AuctionExtended(uint256 indexed auction_id, uint256 end_time);
+    event AuctionSettled(uint256 indexed auction_id, address indexed winner, uint256 amount, uint256 protocol_fee, address royalty_receiver, uint256 royalty_amount);
+    event AuctionCancelled(uint256 indexed auction_id);
+    event Withdrawal(address indexed account, address indexed payment_token, uint256 amount);
+    event ParametersUpdated(address fee_recipient, uint256 fee_bps, uint256 min_bid_increment_bps, uint256 extension_window);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Protocol fee is above the allowed maximum, or nonzero without a recipient
+    error InvalidFee(uint256 fee_bps);
+    // Amount, duration or token standard is not valid for a lot
+    error InvalidLot(address token_contract, uint256 token_id, uint256 amount);
+    // Auction is closed, has ended, or was never created
+    error AuctionNotActive(uint256 auction_id);
+    // Auction can't be settled before its end time
+    error AuctionNotEnded(uint256 auction_id, uint256 end_time);
+    // Bid is below the reserve price or the minimum increment
+    error BidTooLow(uint256 auction_id, uint256 minimum, uint256 bid);
+    // Attached ETH does not match the bid
+    error IncorrectPayment(uint256 expected, uint256 received);
+    // Auction already has bids and can no longer be cancelled
+    error HasBids(uint256 auction_id);
+    // Moving tokens or funds failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum AuctionError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidFee(InvalidFee),
+    InvalidLot(InvalidLot),
+    AuctionNotActive(AuctionNotActive),
+    AuctionNotEnded(AuctionNotEnded),
+    BidTooLow(BidTooLow),
+    IncorrectPayment(IncorrectPayment),
+    HasBids(HasBids),
+    TransferFailed(TransferFailed),
+    InvalidClockMode(InvalidClockMode),
+    PrecompileCallFailed(PrecompileCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC165 {
+        function supportsInterface(bytes4 interface_id) external view returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    interface IERC721 {
+        function safeTransferFrom(address from, address to, uint256 token_id) external;
+    }
+
+    interface IERC2981 {
+        function royaltyInfo(uint256 token_id, uint256 sale_price) external view returns (address, uint256);
+    }
+
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Context of the calls to lot contracts and payment tokens, with the ETH they send. Made through
+/// the host, unlike `sol_interface!` calls, they build with the SDK's `reentrant` feature and are
+/// mocked under test.
+struct AuctionCall(U256);
+
+impl calls::CallContext for AuctionCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a lot contract calls back into the auction's receiver hooks
+unsafe impl calls::MutatingCallContext for AuctionCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl Auction {
+    fn only_owner(&self) -> Result<(), AuctionError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(AuctionError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> Result<U256, AuctionError> {
+        self.clock.now().map_err(AuctionError::PrecompileCallFailed)
+    }
+
+    fn supports(&self, token_contract: Address, interface_id: u32) -> bool {
+        let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+        self.vm()
+            .static_call(&self, token_contract, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC165::supportsInterfaceCall::abi_decode_returns(&output, true).ok())
+            .is_some_and(|supported| supported._0)
+    }
+
+    /// Calls `target` with `calldata`, attaching `value` wei.
+    fn call(&mut self, target: Address, value: U256, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&AuctionCall(value), target, calldata).map_err(Vec::from)
+    }
+
+    /// Calls an ERC-20 transfer method, which must return `true`.
+    fn call_token(&mut self, token: Address, calldata: &[u8]) -> bool {
+        self.call(token, U256::ZERO, calldata)
+            .is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+    }
+
+    /// Moves the lot of an auction from `from` to `to`.
+    fn transfer_lot(&mut self, auction_id: U256, from: Address, to: Address) -> Result<(), AuctionError> {
+        let lot = self.auctions.get(auction_id);
+        let token_contract = lot.token_contract.get();
+        let token_id = lot.token_id.get();
+        let amount = lot.amount.get();
+        let calldata = if lot.is_erc1155.get() {
+            IERC1155::safeTransferFromCall { from, to, id: token_id, value: amount, data: Default::default() }
+                .abi_encode()
+        } else {
+            IERC721::safeTransferFromCall { from, to, token_id }.abi_encode()
+        };
+        if self.call(token_contract, U256::ZERO, &calldata).is_err() {
+            return Err(AuctionError::TransferFailed(TransferFailed {
+                token: token_contract,
+                to,
+                amount,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Credits `amount` of `payment_token` to `account`.
+    fn credit(&mut self, payment_token: Address, account: Address, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let mut pending = self.pending_returns.setter(payment_token);
+        let mut balance = pending.setter(account);
+        let new_balance = balance.get() + amount;
+        balance.set(new_balance);
+    }
+
+    /// Returns the ERC-2981 royalty for a sale, capped at `max_amount`.
+    fn royalty_for(
+        &self,
+        token_contract: Address,
+        token_id: U256,
+        sale_price: U256,
+        max_amount: U256,
+    ) -> (Address, U256) {
+        if !self.supports(token_contract, IERC2981_INTERFACE_ID) {
+            return (Address::ZERO, U256::ZERO);
+        }
+        let call = IERC2981::royaltyInfoCall { token_id, sale_price };
+        let royalty = self
+            .vm()
+            .static_call(&self, token_contract, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC2981::royaltyInfoCall::abi_decode_returns(&output, true).ok());
+        match royalty {
+            Some(royalty) if !royalty._0.is_zero() => (royalty._0, royalty._1.min(max_amount)),
+            _ => (Address::ZERO, U256::ZERO),
+        }
+    }
+
+    /// Fails if the fee is above the maximum, or nonzero with no one to receive it, which would
+    /// credit the fee to the zero address where no one can withdraw it.
+    fn check_protocol_fee(fee_recipient: Address, fee_bps: U256) -> Result<(), AuctionError> {
+        if fee_bps > U256::from(MAX_PROTOCOL_FEE_BPS)
+            || (fee_recipient.is_zero() && !fee_bps.is_zero())
+        {
+            return Err(AuctionError::InvalidFee(InvalidFee { fee_bps }));
+        }
+        Ok(())
+    }
+
+    fn write_parameters(
+        &mut self,
+        fee_recipient: Address,
+        fee_bps: U256,
+        min_bid_increment_bps: U256,
+        extension_window: U256,
+    ) -> Result<(), AuctionError> {
+        Self::check_protocol_fee(fee_recipient, fee_bps)?;
+        self.fee_recipient.set(fee_recipient);
+        self.protocol_fee_bps.set(fee_bps);
+        self.min_bid_increment_bps.set(min_bid_increment_bps);
+        self.extension_window.set(extension_window);
+
+        log(self.vm(), ParametersUpdated {
+            fee_recipient,
+            fee_bps,
+            min_bid_increment_bps,
+            extension_window,
+        });
+        Ok(())
+    }
+
+    /// Only accepts tokens pulled in by this contract itself, so stray transfers revert.
+    fn escrow_receipt(&self, operator: Address, selector: u32) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes(selector.to_be_bytes())
+        } else {
+            FixedBytes([0; 4])
+        }
+    }
+}
+
+#[public]
+impl Auction {
+    /// Sets the owner, the protocol fee, the bidding parameters and the clock. Can only be called
+    /// once. `clock_mode` is 0 to count time in seconds, 1 in L2 blocks and 2 in L1 blocks.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        fee_recipient: Address,
+        fee_bps: U256,
+        min_bid_increment_bps: U256,
+        extension_window: U256,
+        clock_mode: u8,
+    ) -> Result<(), AuctionError> {
+        if self.initialized.get() {
+            return Err(AuctionError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        Self::check_protocol_fee(fee_recipient, fee_bps)?;
+        self.clock.init(clock_mode).map_err(AuctionError::InvalidClockMode)?;
+        self.initialized.set(true);
+        self.owner.set(owner);
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        self.write_parameters(fee_recipient, fee_bps, min_bid_increment_bps, extension_window)
+    }
+
+    /// Escrows the lot and opens an auction running for `duration`, in the clock's unit.
+    /// Pass the zero address as `payment_token` to accept ETH bids.
+    pub fn create_auction(
+        &mut self,
+        token_contract: Address,
+        token_id: U256,
+        amount: U256,
+        payment_token: Address,
+        reserve_price: U256,
+        duration: U256,
+    ) -> Result<U256, AuctionError> {
+        let invalid_lot = AuctionError::InvalidLot(InvalidLot {
+            token_contract,
+            token_id,
+            amount,
+        });
+        let is_erc1155 = if self.supports(token_contract, IERC1155_INTERFACE_ID) {
+            true
+        } else if self.supports(token_contract, IERC721_INTERFACE_ID) {
+            false
+        } else {
+            return Err(invalid_lot);
+        };
+        if amount.is_zero() || duration.is_zero() || (!is_erc1155 && amount != U256::from(1)) {
+            return Err(invalid_lot);
+        }
+
+        let seller = self.vm().msg_sender();
+        let end_time = self.now()? + duration;
+        let auction_id = self.next_auction_id.get();
+        self.next_auction_id.set(auction_id + U256::from(1));
+
+        let mut lot = self.auctions.setter(auction_id);
+        lot.seller.set(seller);
+        lot.token_contract.set(token_contract);
+        lot.token_id.set(token_id);
+        lot.amount.set(amount);
+        lot.is_erc1155.set(is_erc1155);
+        lot.payment_token.set(payment_token);
+        lot.reserve_price.set(reserve_price);
+        lot.end_time.set(end_time);
+
+        let this = self.vm().contract_address();
+        self.transfer_lot(auction_id, seller, this)?;
+
+        log(self.vm(), AuctionCreated {
+            auction_id,
+            seller,
+            token_contract,
+            token_id,
+            amount,
+            payment_token,
+            reserve_price,
+            end_time,
+        });
+        Ok(auction_id)
+    }
+
+    /// Places a bid of `amount`. ETH auctions require `amount` as `msg.value`;
+    /// ERC-20 auctions pull `amount` from the bidder.
+    #[payable]
+    pub fn bid(&mut self, auction_id: U256, amount: U256) -> Result<(), AuctionError> {
+        let bidder = self.vm().msg_sender();
+        let now = self.now()?;
+        let lot = self.auctions.get(auction_id);
+        let payment_token = lot.payment_token.get();
+        let end_time = lot.end_time.get();
+        let previous_bidder = lot.highest_bidder.get();
+        let previous_bid = lot.highest_bid.get();
+
+        if lot.seller.get().is_zero() || lot.closed.get() || now >= end_time {
+            return Err(AuctionError::AuctionNotActive(AuctionNotActive { auction_id }));
+        }
+
+        let minimum = if previous_bidder.is_zero() {
+            lot.reserve_price.get().max(U256::from(1))
+        } else {
+            let increment = previous_bid * self.min_bid_increment_bps.get() / U256::from(BPS_DENOMINATOR);
+            previous_bid + increment.max(U256::from(1))
+        };
+        if amount < minimum {
+            return Err(AuctionError::BidTooLow(BidTooLow {
+                auction_id,
+                minimum,
+                bid: amount,
+            }));
+        }
+
+        let expected_value = if payment_token.is_zero() { amount } else { U256::ZERO };
+        let received = self.vm().msg_value();
+        if received != expected_value {
+            return Err(AuctionError::IncorrectPayment(IncorrectPayment {
+                expected: expected_value,
+                received,
+            }));
+        }
+
+        // The outbid bidder withdraws their funds themselves
+        self.credit(payment_token, previous_bidder, previous_bid);
+
+        let mut lot = self.auctions.setter(auction_id);
+        lot.highest_bidder.set(bidder);
+        lot.highest_bid.set(amount);
+
+        let window = self.extension_window.get();
+        let extended = end_time - now < window;
+        if extended {
+            self.auctions.setter(auction_id).end_time.set(now + window);
+        }
+
+        if !payment_token.is_zero() {
+            let this = self.vm().contract_address();
+            let pull = IERC20::transferFromCall { from: bidder, to: this, value: amount };
+            if !self.call_token(payment_token, &pull.abi_encode()) {
+                return Err(AuctionError::TransferFailed(TransferFailed {
+                    token: payment_token,
+                    to: this,
+                    amount,
+                }));
+            }
+        }
+
+        log(self.vm(), BidPlaced {
+            auction_id,
+            bidder,
+            amount,
+        });
+        if extended {
+            log(self.vm(), AuctionExtended {
+                auction_id,
+                end_time: now + window,
+            });
+        }
+        Ok(())
+    }
+
+    /// Settles an ended auction. Callable by anyone.
+    /// The lot goes to the highest bidder, or back to the seller if there were no bids.
+    pub fn settle(&mut self, auction_id: U256) -> Result<(), AuctionError> {
+        let now = self.now()?;
+        let lot = self.auctions.get(auction_id);
+        let seller = lot.seller.get();
+        let token_contract = lot.token_contract.get();
+        let token_id = lot.token_id.get();
+        let payment_token = lot.payment_token.get();
+        let end_time = lot.end_time.get();
+        let winner = lot.highest_bidder.get();
+        let winning_bid = lot.highest_bid.get();
+
+        if seller.is_zero() || lot.closed.get() {
+            return Err(AuctionError::AuctionNotActive(AuctionNotActive { auction_id }));
+        }
+        if now < end_time {
+            return Err(AuctionError::AuctionNotEnded(AuctionNotEnded { auction_id, end_time }));
+        }
+        self.auctions.setter(auction_id).closed.set(true);
+
+        let this = self.vm().contract_address();
+        if winner.is_zero() {
+            self.transfer_lot(auction_id, this, seller)?;
+            log(self.vm(), AuctionCancelled { auction_id });
+            return Ok(());
+        }
+
+        let protocol_fee = winning_bid * self.protocol_fee_bps.get() / U256::from(BPS_DENOMINATOR);
+        let (royalty_receiver, royalty_amount) =
+            self.royalty_for(token_contract, token_id, winning_bid, winning_bid - protocol_fee);
+        let fee_recipient = self.fee_recipient.get();
+        self.credit(payment_token, fee_recipient, protocol_fee);
+        self.credit(payment_token, royalty_receiver, royalty_amount);
+        self.credit(payment_token, seller, winning_bid - protocol_fee - royalty_amount);
+
+        self.transfer_lot(auction_id, this, winner)?;
+
+        log(self.vm(), AuctionSettled {
+            auction_id,
+            winner,
+            amount: winning_bid,
+            protocol_fee,
+            royalty_receiver,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    /// Cancels an auction that has no bids yet and returns the lot to the seller.
+    /// Callable by the seller or the owner.
+    pub fn cancel(&mut self, auction_id: U256) -> Result<(), AuctionError> {
+        let caller = self.vm().msg_sender();
+        let lot = self.auctions.get(auction_id);
+        let seller = lot.seller.get();
+        if seller.is_zero() || lot.closed.get() {
+            return Err(AuctionError::AuctionNotActive(AuctionNotActive { auction_id }));
+        }
+        if caller != seller && caller != self.owner.get() {
+            return Err(AuctionError::Unauthorized(Unauthorized { caller }));
+        }
+        if !lot.highest_bidder.get().is_zero() {
+            return Err(AuctionError::HasBids(HasBids { auction_id }));
+        }
+        self.auctions.setter(auction_id).closed.set(true);
+
+        let this = self.vm().contract_address();
+        self.transfer_lot(auction_id, this, seller)?;
+
+        log(self.vm(), AuctionCancelled { auction_id });
+        Ok(())
+    }
+
+    /// Withdraws the caller's refunds and proceeds in `payment_token` (zero address for ETH).
+    pub fn withdraw(&mut self, payment_token: Address) -> Result<(), AuctionError> {
+        let account = self.vm().msg_sender();
+        let amount = self.pending_returns.getter(payment_token).get(account);
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.pending_returns.setter(payment_token).insert(account, U256::ZERO);
+
+        let sent = if payment_token.is_zero() {
+            self.call(account, amount, &[]).is_ok()
+        } else {
+            self.call_token(payment_token, &IERC20::transferCall { to: account, value: amount }.abi_encode())
+        };
+        if !sent {
+            return Err(AuctionError::TransferFailed(TransferFailed {
+                token: payment_token,
+                to: account,
+                amount,
+            }));
+        }
+
+        log(self.vm(), Withdrawal {
+            account,
+            payment_token,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Updates the protocol fee and bidding parameters (owner only).
+    pub fn set_parameters(
+        &mut self,
+        fee_recipient: Address,
+        fee_bps: U256,
+        min_bid_increment_bps: U256,
+        extension_window: U256,
+    ) -> Result<(), AuctionError> {
+        self.only_owner()?;
+        self.write_parameters(fee_recipient, fee_bps, min_bid_increment_bps, extension_window)
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), AuctionError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Accepts ERC-1155 lots escrowed by `create_auction`.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC1155_RECEIVED)
+    }
+
+    /// Accepts ERC-721 lots escrowed by `create_auction`.
+    #[selector(name = "onERC721Received")]
+    pub fn on_erc721_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _token_id: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC721_RECEIVED)
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Returns `(seller, token_contract, token_id, amount, payment_token, reserve_price, end_time, highest_bidder, highest_bid, closed)`.
+    #[allow(clippy::type_complexity)]
+    pub fn auction(
+        &self,
+        auction_id: U256,
+    ) -> (Address, Address, U256, U256, Address, U256, U256, Address, U256, bool) {
+        let lot = self.auctions.get(auction_id);
+        (
+            lot.seller.get(),
+            lot.token_contract.get(),
+            lot.token_id.get(),
+            lot.amount.get(),
+            lot.payment_token.get(),
+            lot.reserve_price.get(),
+            lot.end_time.get(),
+            lot.highest_bidder.get(),
+            lot.highest_bid.get(),
+            lot.closed.get(),
+        )
+    }
+
+    /// Amount of `payment_token` that `account` can withdraw.
+    pub fn pending_returns(&self, payment_token: Address, account: Address) -> U256 {
+        self.pending_returns.getter(payment_token).get(account)
+    }
+
+    /// Returns `(fee_recipient, fee_bps, min_bid_increment_bps, extension_window)`.
+    pub fn parameters(&self) -> (Address, U256, U256, U256) {
+        (
+            self.fee_recipient.get(),
+            self.protocol_fee_bps.get(),
+            self.min_bid_increment_bps.get(),
+            self.extension_window.get(),
+        )
+    }
+
+    /// Number of auctions created so far.
+    pub fn total_auctions(&self) -> U256 {
+        self.next_auction_id.get()
+    }
+
+    /// What durations and end times are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1
+    /// blocks.
+    pub fn clock_mode(&self) -> u8 {
+        self.clock.mode()
+    }
+
+    /// Current time on the contract's clock, which end times are compared against.
+    pub fn clock(&self) -> Result<U256, AuctionError> {
+        self.now()
+    }
+}
+
@@ -0,0 +1,233 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const TREASURY: Address = address!("000000000000000000000000000000000000fee5");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const SELLER: Address = address!("0000000000000000000000000000000000005e11");
+const ARTIST: Address = address!("00000000000000000000000000000000000a2715");
+const NFT: Address = address!("0000000000000000000000000000000000000721");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+
+/// Mocks whether `NFT` reports supporting `interface_id`.
+fn mock_support(vm: &TestVM, interface_id: u32, supported: bool) {
+    let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+    vm.mock_static_call(NFT, call.abi_encode(), Ok(supported.abi_encode()));
+}
+
+/// An auction with a 2.5% fee, 5% increments and a 100 second extension window, and `SELLER`'s
+/// ERC-721 token 7 up for 1,000 seconds from 10,000 with a reserve of 1,000, paid in
+/// `payment_token`.
+fn setup(payment_token: Address) -> (TestVM, Auction, U256) {
+    let (vm, mut contract) = deploy::<Auction>(OWNER);
+    vm.set_block_timestamp(10_000);
+    ok(contract.initialize(OWNER, TREASURY, n(250), n(500), n(100), 0));
+
+    mock_support(&vm, IERC721_INTERFACE_ID, true);
+    vm.set_sender(SELLER);
+    let auction_id = ok(contract.create_auction(NFT, n(7), n(1), payment_token, n(1_000), n(1_000)));
+    (vm, contract, auction_id)
+}
+
+/// Places an ETH bid of `amount` as `bidder`.
+fn bid(vm: &TestVM, contract: &mut Auction, bidder: Address, amount: u64) -> Result<(), AuctionError> {
+    vm.set_sender(bidder);
+    vm.set_value(n(amount));
+    let result = contract.bid(U256::ZERO, n(amount));
+    vm.set_value(U256::ZERO);
+    result
+}
+
+#[test]
+fn protocol_fees_need_a_recipient() {
+    let (vm, mut contract) = deploy::<Auction>(OWNER);
+    let result = contract.initialize(OWNER, Address::ZERO, U256::from(250), U256::from(500), U256::from(600), 0);
+    assert!(matches!(result, Err(AuctionError::InvalidFee(e)) if e.fee_bps == U256::from(250)));
+    let result = contract.initialize(OWNER, TREASURY, U256::from(1_001), U256::from(500), U256::from(600), 0);
+    assert!(matches!(result, Err(AuctionError::InvalidFee(_))));
+
+    // No fee needs no one to receive it
+    ok(contract.initialize(OWNER, Address::ZERO, U256::ZERO, U256::from(500), U256::from(600), 0));
+    assert_eq!(contract.parameters(), (Address::ZERO, U256::ZERO, U256::from(500), U256::from(600)));
+    let result = contract.set_parameters(Address::ZERO, U256::from(100), U256::from(500), U256::from(600));
+    assert!(matches!(result, Err(AuctionError::InvalidFee(_))));
+    ok(contract.set_parameters(TREASURY, U256::from(100), U256::from(500), U256::from(600)));
+    assert_eq!(contract.parameters(), (TREASURY, U256::from(100), U256::from(500), U256::from(600)));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_parameters(ALICE, U256::from(100), U256::from(500), U256::from(600));
+    assert!(matches!(result, Err(AuctionError::Unauthorized(e)) if e.caller == ALICE));
+}
+
+#[test]
+fn lots_must_be_tokens_the_auction_can_escrow() {
+    let (vm, mut contract, _) = setup(Address::ZERO);
+    let auction = contract.auction(U256::ZERO);
+    assert_eq!((auction.0, auction.1, auction.2, auction.6), (SELLER, NFT, n(7), n(11_000)));
+
+    // An ERC-721 lot is a single token
+    let result = contract.create_auction(NFT, n(8), n(2), Address::ZERO, n(1_000), n(1_000));
+    assert!(matches!(result, Err(AuctionError::InvalidLot(e)) if e.amount == n(2)));
+    mock_support(&vm, IERC721_INTERFACE_ID, false);
+    let result = contract.create_auction(NFT, n(8), n(1), Address::ZERO, n(1_000), n(1_000));
+    assert!(matches!(result, Err(AuctionError::InvalidLot(_))));
+    assert_eq!(contract.total_auctions(), n(1));
+}
+
+#[test]
+fn bids_must_clear_the_reserve_and_the_increment() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    let result = bid(&vm, &mut contract, ALICE, 999);
+    assert!(matches!(result, Err(AuctionError::BidTooLow(e)) if e.minimum == n(1_000)));
+    ok(bid(&vm, &mut contract, ALICE, 1_000));
+
+    // 5% over 1,000
+    let result = bid(&vm, &mut contract, BOB, 1_049);
+    assert!(matches!(result, Err(AuctionError::BidTooLow(e)) if e.minimum == n(1_050)));
+    vm.set_value(n(1_000));
+    let result = contract.bid(auction_id, n(1_050));
+    assert!(matches!(result, Err(AuctionError::IncorrectPayment(e)) if e.expected == n(1_050)));
+    ok(bid(&vm, &mut contract, BOB, 1_050));
+
+    // The outbid bidder is credited, not paid
+    assert_eq!(contract.pending_returns(Address::ZERO, ALICE), n(1_000));
+    let auction = contract.auction(auction_id);
+    assert_eq!((auction.7, auction.8), (BOB, n(1_050)));
+    vm.set_sender(SELLER);
+    let result = contract.cancel(auction_id);
+    assert!(matches!(result, Err(AuctionError::HasBids(_))));
+}
+
+#[test]
+fn late_bids_push_the_end_back() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    vm.set_block_timestamp(10_950);
+    ok(bid(&vm, &mut contract, ALICE, 1_000));
+    assert_eq!(contract.auction(auction_id).6, n(11_050));
+    let event = emitted::<AuctionExtended>(&vm).pop().expect("extension logged");
+    assert_eq!(event.end_time, n(11_050));
+
+    vm.set_block_timestamp(11_050);
+    let result = bid(&vm, &mut contract, BOB, 2_000);
+    assert!(matches!(result, Err(AuctionError::AuctionNotActive(_))));
+}
+
+#[test]
+fn settling_splits_the_winning_bid_between_fee_royalty_and_seller() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    ok(bid(&vm, &mut contract, ALICE, 10_000));
+    let result = contract.settle(auction_id);
+    assert!(matches!(result, Err(AuctionError::AuctionNotEnded(e)) if e.end_time == n(11_000)));
+
+    // The token asks for a 10% royalty
+    mock_support(&vm, IERC2981_INTERFACE_ID, true);
+    let royalty = IERC2981::royaltyInfoCall { token_id: n(7), sale_price: n(10_000) };
+    vm.mock_static_call(NFT, royalty.abi_encode(), Ok((ARTIST, n(1_000)).abi_encode()));
+    vm.set_block_timestamp(11_000);
+    vm.set_sender(BOB);
+    ok(contract.settle(auction_id));
+    assert_eq!(contract.pending_returns(Address::ZERO, TREASURY), n(250));
+    assert_eq!(contract.pending_returns(Address::ZERO, ARTIST), n(1_000));
+    assert_eq!(contract.pending_returns(Address::ZERO, SELLER), n(8_750));
+    assert!(contract.auction(auction_id).9);
+    let event = emitted::<AuctionSettled>(&vm).pop().expect("settlement logged");
+    assert_eq!((event.winner, event.protocol_fee, event.royalty_amount), (ALICE, n(250), n(1_000)));
+
+    let result = contract.settle(auction_id);
+    assert!(matches!(result, Err(AuctionError::AuctionNotActive(_))));
+}
+
+#[test]
+fn royalties_are_capped_at_what_the_fee_leaves() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    ok(bid(&vm, &mut contract, ALICE, 10_000));
+    mock_support(&vm, IERC2981_INTERFACE_ID, true);
+    let royalty = IERC2981::royaltyInfoCall { token_id: n(7), sale_price: n(10_000) };
+    vm.mock_static_call(NFT, royalty.abi_encode(), Ok((ARTIST, n(20_000)).abi_encode()));
+    vm.set_block_timestamp(11_000);
+    ok(contract.settle(auction_id));
+    assert_eq!(contract.pending_returns(Address::ZERO, ARTIST), n(9_750));
+    assert_eq!(contract.pending_returns(Address::ZERO, SELLER), U256::ZERO);
+}
+
+#[test]
+fn unsold_lots_go_back_to_the_seller() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    vm.set_block_timestamp(11_000);
+    ok(contract.settle(auction_id));
+    assert!(contract.auction(auction_id).9);
+    assert_eq!(emitted::<AuctionCancelled>(&vm).len(), 1);
+    assert_eq!(contract.pending_returns(Address::ZERO, SELLER), U256::ZERO);
+}
+
+#[test]
+fn only_the_seller_or_owner_cancels() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    vm.set_sender(ALICE);
+    let result = contract.cancel(auction_id);
+    assert!(matches!(result, Err(AuctionError::Unauthorized(e)) if e.caller == ALICE));
+
+    // A lot the token contract won't move back can't be cancelled
+    let back = IERC721::safeTransferFromCall { from: vm.contract_address(), to: SELLER, token_id: n(7) };
+    vm.mock_call(NFT, back.abi_encode(), Err(Vec::new()));
+    vm.set_sender(OWNER);
+    let result = contract.cancel(auction_id);
+    assert!(matches!(result, Err(AuctionError::TransferFailed(e)) if e.to == SELLER));
+}
+
+#[test]
+fn withdrawals_pay_out_credited_balances() {
+    let (vm, mut contract, auction_id) = setup(Address::ZERO);
+    ok(bid(&vm, &mut contract, ALICE, 1_000));
+    ok(bid(&vm, &mut contract, BOB, 2_000));
+
+    vm.set_sender(ALICE);
+    ok(contract.withdraw(Address::ZERO));
+    assert_eq!(contract.pending_returns(Address::ZERO, ALICE), U256::ZERO);
+    let event = emitted::<Withdrawal>(&vm).pop().expect("withdrawal logged");
+    assert_eq!((event.account, event.amount), (ALICE, n(1_000)));
+    // Nothing left is a no-op
+    ok(contract.withdraw(Address::ZERO));
+    assert_eq!(emitted::<Withdrawal>(&vm).len(), 1);
+
+    vm.set_block_timestamp(11_000);
+    ok(contract.settle(auction_id));
+    vm.mock_call(SELLER, Vec::new(), Err(Vec::new()));
+    vm.set_sender(SELLER);
+    let result = contract.withdraw(Address::ZERO);
+    assert!(matches!(result, Err(AuctionError::TransferFailed(e)) if e.to == SELLER && e.amount == n(1_950)));
+}
+
+#[test]
+fn token_bids_are_pulled_from_the_bidder() {
+    let (vm, mut contract, auction_id) = setup(USDC);
+    vm.set_sender(ALICE);
+    let pull = IERC20::transferFromCall { from: ALICE, to: vm.contract_address(), value: n(1_000) };
+    vm.mock_call(USDC, pull.abi_encode(), Ok(true.abi_encode()));
+    ok(contract.bid(auction_id, n(1_000)));
+    // ETH can't be attached to a token bid
+    vm.set_sender(BOB);
+    vm.set_value(n(2_000));
+    let result = contract.bid(auction_id, n(2_000));
+    assert!(matches!(result, Err(AuctionError::IncorrectPayment(e)) if e.expected.is_zero()));
+    vm.set_value(U256::ZERO);
+
+    vm.set_block_timestamp(11_000);
+    ok(contract.settle(auction_id));
+    let payout = IERC20::transferCall { to: SELLER, value: n(975) };
+    vm.mock_call(USDC, payout.abi_encode(), Ok(true.abi_encode()));
+    vm.set_sender(SELLER);
+    ok(contract.withdraw(USDC));
+    assert_eq!(contract.pending_returns(USDC, SELLER), U256::ZERO);
+}
+
+#[test]
+fn token_bids_fail_unless_the_tokens_move() {
+    let (vm, mut contract, auction_id) = setup(USDC);
+    vm.set_sender(ALICE);
+    let result = contract.bid(auction_id, n(1_000));
+    assert!(matches!(result, Err(AuctionError::TransferFailed(e)) if e.token == USDC && e.amount == n(1_000)));
+}
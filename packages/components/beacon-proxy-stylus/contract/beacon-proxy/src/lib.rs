@@ -0,0 +1,143 @@
+//! Beacon proxy
+//!
+//! Forwards every call to the implementation currently returned by an
+//! upgradeable beacon, running it against the proxy's own storage. Upgrading the
+//! beacon upgrades every proxy that points at it.
+//!
+//! The beacon address lives in the EIP-1967 beacon slot rather than in regular
+//! Stylus storage, so it can't collide with the implementation's fields. The
+//! proxy's only method of its own is `initialize_proxy`, which sets the beacon
+//! and runs the implementation's initializer in the same call. Call it in the
+//! deployment transaction, for example through the clone factory, so nobody
+//! else can initialize the proxy first.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{abi::Bytes, prelude::*, ArbResult};
+
+/// EIP-1967 beacon slot, `keccak256("eip1967.proxy.beacon") - 1`.
+const BEACON_SLOT: U256 = U256::from_be_bytes([
+    0xa3, 0xf0, 0xad, 0x74, 0xe5, 0x42, 0x3a, 0xeb, 0xfd, 0x80, 0xd3, 0xef, 0x43, 0x46, 0x57, 0x83, 0x35, 0xa9, 0xa7,
+    0x2a, 0xea, 0xee, 0x59, 0xff, 0x6c, 0xb3, 0x58, 0x2b, 0x35, 0x13, 0x3d, 0x50,
+]);
+
+#[storage]
+#[entrypoint]
+pub struct BeaconProxy {}
+
+// Declare events and Solidity error types
+sol! {
+    event BeaconUpgraded(address indexed beacon);
+
+    // Proxy already has a beacon
+    error AlreadyInitialized();
+    // Beacon has no code or returned an implementation without code
+    error InvalidBeacon(address beacon);
+    // Proxy was called before `initialize_proxy`
+    error NotInitialized();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum BeaconProxyError {
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidBeacon(InvalidBeacon),
+    NotInitialized(NotInitialized),
+}
+
+// External interfaces
+sol! {
+    interface IBeacon {
+        function implementation() external view returns (address);
+    }
+}
+
+/// Context of the delegate calls to the implementation. Made through the host, unlike
+/// `stylus_sdk::call::delegate_call`, they aren't deprecated and are mocked under test.
+struct DelegateCall;
+
+impl calls::CallContext for DelegateCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale after the implementation writes the proxy's storage
+unsafe impl calls::MutatingCallContext for DelegateCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl BeaconProxy {
+    fn beacon(&self) -> Address {
+        Address::from_word(self.vm().storage_load_bytes32(BEACON_SLOT))
+    }
+
+    fn implementation(&self, beacon: Address) -> Result<Address, BeaconProxyError> {
+        let implementation = self
+            .vm()
+            .static_call(&self, beacon, &IBeacon::implementationCall {}.abi_encode())
+            .ok()
+            .and_then(|output| IBeacon::implementationCall::abi_decode_returns(&output, true).ok())
+            .ok_or(BeaconProxyError::InvalidBeacon(InvalidBeacon { beacon }))?
+            ._0;
+        if self.vm().code_size(implementation) == 0 {
+            return Err(BeaconProxyError::InvalidBeacon(InvalidBeacon { beacon }));
+        }
+        Ok(implementation)
+    }
+
+    /// Delegates `calldata` to `implementation`, returning its output or revert data.
+    fn delegate(&mut self, implementation: Address, calldata: &[u8]) -> ArbResult {
+        unsafe { self.vm().delegate_call(&DelegateCall, implementation, calldata) }.map_err(Vec::<u8>::from)
+    }
+}
+
+#[public]
+impl BeaconProxy {
+    /// Points the proxy at `beacon` and delegates `data` to the beacon's implementation,
+    /// usually an encoded `initialize` call. Pass empty data to skip the call. Can only be called once.
+    #[payable]
+    pub fn initialize_proxy(&mut self, beacon: Address, data: Bytes) -> Result<(), Vec<u8>> {
+        if !self.beacon().is_zero() {
+            return Err(BeaconProxyError::AlreadyInitialized(AlreadyInitialized {}).into());
+        }
+        if self.vm().code_size(beacon) == 0 {
+            return Err(BeaconProxyError::InvalidBeacon(InvalidBeacon { beacon }).into());
+        }
+        let implementation = self.implementation(beacon)?;
+        unsafe { self.vm().storage_cache_bytes32(BEACON_SLOT, B256::left_padding_from(beacon.as_slice())) };
+        self.vm().flush_cache(false);
+
+        log(self.vm(), BeaconUpgraded { beacon });
+        if !data.is_empty() {
+            self.delegate(implementation, &data)?;
+        }
+        Ok(())
+    }
+
+    /// Forwards every other call to the beacon's current implementation.
+    #[fallback]
+    #[payable]
+    fn fallback(&mut self, calldata: &[u8]) -> ArbResult {
+        let beacon = self.beacon();
+        if beacon.is_zero() {
+            return Err(BeaconProxyError::NotInitialized(NotInitialized {}).into());
+        }
+        let implementation = self.implementation(beacon)?;
+        self.delegate(implementation, calldata)
+    }
+}
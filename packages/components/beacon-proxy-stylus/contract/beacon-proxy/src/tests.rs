@@ -0,0 +1,75 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::{SolEvent, SolValue};
+use stylus_sdk::testing::TestVM;
+
+const BEACON: Address = address!("000000000000000000000000000000000000bea0");
+const V1: Address = address!("0000000000000000000000000000000000000001");
+const V2: Address = address!("0000000000000000000000000000000000000002");
+
+/// An encoded call to `initialize()`.
+const INITIALIZE: [u8; 4] = [0x81, 0x29, 0xfc, 0x1c];
+/// An encoded call to `totalSupply()`.
+const TOTAL_SUPPLY: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+
+/// Revert data of `error`.
+fn revert(error: BeaconProxyError) -> Vec<u8> {
+    error.into()
+}
+
+/// Makes `BEACON` return `implementation`.
+fn point_beacon(vm: &TestVM, implementation: Address) {
+    let call = IBeacon::implementationCall {};
+    vm.mock_static_call(BEACON, call.abi_encode(), Ok(implementation.abi_encode()));
+}
+
+/// A beacon returning `V1`, with both implementations deployed.
+fn setup() -> (TestVM, BeaconProxy) {
+    let vm = TestVM::new();
+    for account in [BEACON, V1, V2] {
+        vm.set_code(account, vec![0xfe]);
+    }
+    point_beacon(&vm, V1);
+    let contract = BeaconProxy::from(&vm);
+    (vm, contract)
+}
+
+#[test]
+fn calls_follow_the_beacon_to_its_implementation() {
+    let (vm, mut contract) = setup();
+    vm.mock_delegate_call(V1, INITIALIZE.to_vec(), Ok(Vec::new()));
+    contract.initialize_proxy(BEACON, INITIALIZE.to_vec().into()).unwrap();
+    assert_eq!(contract.beacon(), BEACON);
+    let (topics, data) = &vm.get_emitted_logs()[0];
+    assert_eq!(BeaconUpgraded::decode_raw_log(topics, data, true).unwrap().beacon, BEACON);
+
+    vm.mock_delegate_call(V1, TOTAL_SUPPLY.to_vec(), Ok(U256::from(100).abi_encode()));
+    assert_eq!(contract.fallback(&TOTAL_SUPPLY), Ok(U256::from(100).abi_encode()));
+
+    // Upgrading the beacon moves the proxy along, and reverts pass through unchanged
+    point_beacon(&vm, V2);
+    vm.mock_delegate_call(V2, TOTAL_SUPPLY.to_vec(), Err(b"paused".to_vec()));
+    assert_eq!(contract.fallback(&TOTAL_SUPPLY), Err(b"paused".to_vec()));
+    let result = contract.initialize_proxy(BEACON, Vec::new().into());
+    assert_eq!(result, Err(revert(BeaconProxyError::AlreadyInitialized(AlreadyInitialized {}))));
+}
+
+#[test]
+fn proxies_need_a_beacon_with_a_deployed_implementation() {
+    let (vm, mut contract) = setup();
+    let result = contract.fallback(&TOTAL_SUPPLY);
+    assert_eq!(result, Err(revert(BeaconProxyError::NotInitialized(NotInitialized {}))));
+    let result = contract.initialize_proxy(V2, Vec::new().into());
+    assert_eq!(result, Err(revert(BeaconProxyError::InvalidBeacon(InvalidBeacon { beacon: V2 }))));
+
+    point_beacon(&vm, Address::ZERO);
+    let result = contract.initialize_proxy(BEACON, Vec::new().into());
+    assert_eq!(result, Err(revert(BeaconProxyError::InvalidBeacon(InvalidBeacon { beacon: BEACON }))));
+    assert!(contract.beacon().is_zero());
+
+    // A failing initializer fails the whole call
+    point_beacon(&vm, V1);
+    vm.mock_delegate_call(V1, INITIALIZE.to_vec(), Err(b"bad config".to_vec()));
+    let result = contract.initialize_proxy(BEACON, INITIALIZE.to_vec().into());
+    assert_eq!(result, Err(b"bad config".to_vec()));
+}
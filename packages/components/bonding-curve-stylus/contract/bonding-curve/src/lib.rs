@@ -0,0 +1,460 @@
+//! Bonding curve sale for a Cradle ERC-20
+//!
+//! Buyers pay ETH into the curve's reserve and the curve mints them tokens;
+//! sellers hand tokens back, which are burned against a payout from the reserve.
+//! The spot price follows the configured curve as a function of the tokens sold
+//! through it:
+//!
+//! - linear: `price(s) = base_price + slope * s`
+//! - exponential: `price(s) = base_price * e^(growth_rate * s)`
+//!
+//! Costs are the exact integral of the price between the old and new supply, so
+//! buying and selling the same amount back to back is neutral.
+//!
+//! Once the reserve reaches the graduation threshold, trading stops and the
+//! reserve is sent to the graduation hook together with a final mint of tokens,
+//! which is where an AMM pool gets seeded.
+//!
+//! The token must let the curve call `mintTo` and `burn`, like the Cradle ERC-20 component does.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale used for token amounts, the slope and the growth rate.
+const WAD: u128 = 1_000_000_000_000_000_000;
+/// e, scaled by `WAD`.
+const E_WAD: u128 = 2_718_281_828_459_045_235;
+/// Largest whole exponent accepted by the exponential curve; e^40 ~ 2.35e17.
+const MAX_EXPONENT: u64 = 40;
+
+/// `curve_type` of a linear curve.
+const CURVE_LINEAR: u8 = 0;
+/// `curve_type` of an exponential curve.
+const CURVE_EXPONENTIAL: u8 = 1;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct BondingCurve {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Can configure graduation
+        address owner;
+        /// ERC-20 minted and burned by the curve
+        address token;
+        /// `CURVE_LINEAR` or `CURVE_EXPONENTIAL`
+        uint8 curve_type;
+        /// Price of the first token, in wei per whole token
+        uint256 base_price;
+        /// Linear: price increase per whole token sold, in wei. Exponential: growth rate per token, WAD-scaled
+        uint256 curve_parameter;
+        /// Tokens sold through the curve and not sold back
+        uint256 curve_supply;
+        /// ETH backing `curve_supply`
+        uint256 reserve;
+        /// Reserve at which the curve graduates; zero disables graduation
+        uint256 graduation_reserve;
+        /// Tokens minted to the hook on graduation
+        uint256 graduation_token_amount;
+        /// Receives the reserve and the graduation tokens
+        address graduation_hook;
+        /// Trading is closed once graduated
+        bool graduated;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Bought(address indexed buyer, uint256 amount, uint256 cost, uint256 new_supply);
+    event Sold(address indexed seller, uint256 amount, uint256 payout, uint256 new_supply);
+    event Graduated(address indexed hook, uint256 reserve, uint256 token_amount);
+    event GraduationConfigured(address hook, uint256 graduation_reserve, uint256 token_amount);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Curve type is unknown
+    error InvalidCurve(uint8 curve_type);
+    // Amount is zero or exceeds the tokens sold through the curve
+    error InvalidAmount(uint256 amount);
+    // Cost or payout moved past the caller's slippage bound
+    error SlippageExceeded(uint256 quoted, uint256 limit);
+    // Attached ETH does not cover the cost
+    error InsufficientPayment(uint256 cost, uint256 received);
+    // Curve math overflowed; the supply is beyond what the curve supports
+    error CurveOverflow();
+    // Curve has graduated and no longer trades
+    error AlreadyGraduated();
+    // A token call, ETH transfer or hook call failed
+    error ExternalCallFailed(address target);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum BondingCurveError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidCurve(InvalidCurve),
+    InvalidAmount(InvalidAmount),
+    SlippageExceeded(SlippageExceeded),
+    InsufficientPayment(InsufficientPayment),
+    CurveOverflow(CurveOverflow),
+    AlreadyGraduated(AlreadyGraduated),
+    ExternalCallFailed(ExternalCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface ICradleToken {
+        function mintTo(address to, uint256 value) external;
+        function burn(uint256 value) external;
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+sol! {
+    /// Called with the reserve as `msg.value` when the curve graduates.
+    interface IGraduationHook {
+        function onGraduation(address token, uint256 token_amount) external payable;
+    }
+}
+
+/// Computes `e^x` for a WAD-scaled `x`, or `None` past `MAX_EXPONENT`.
+fn exp_wad(x: U256) -> Option<U256> {
+    let wad = U256::from(WAD);
+    let whole = x / wad;
+    if whole > U256::from(MAX_EXPONENT) {
+        return None;
+    }
+
+    // e^frac through its Taylor series; frac < 1 so it converges quickly
+    let frac = x % wad;
+    let mut term = wad;
+    let mut result = wad;
+    for i in 1..=30u64 {
+        term = term * frac / wad / U256::from(i);
+        if term.is_zero() {
+            break;
+        }
+        result += term;
+    }
+
+    // e^whole by squaring
+    let mut base = U256::from(E_WAD);
+    let mut n = whole.to::<u64>();
+    while n > 0 {
+        if n & 1 == 1 {
+            result = result * base / wad;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = base * base / wad;
+        }
+    }
+    Some(result)
+}
+
+impl BondingCurve {
+    fn only_owner(&self) -> Result<(), BondingCurveError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(BondingCurveError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Integral of the price between `from` and `to` supply, in wei.
+    fn area(&self, from: U256, to: U256) -> Result<U256, BondingCurveError> {
+        let wad = U256::from(WAD);
+        let base_price = self.base_price.get();
+        let parameter = self.curve_parameter.get();
+        let overflow = || BondingCurveError::CurveOverflow(CurveOverflow {});
+
+        if self.curve_type.get().to::<u8>() == CURVE_LINEAR {
+            // base * n + slope * (to^2 - from^2) / 2, all divided by WAD twice for the token scale
+            let amount = to - from;
+            let squares = to
+                .checked_mul(to)
+                .and_then(|t| from.checked_mul(from).map(|f| t - f))
+                .ok_or_else(overflow)?;
+            let linear = base_price.checked_mul(amount).ok_or_else(overflow)? / wad;
+            let quadratic = parameter.checked_mul(squares / wad).ok_or_else(overflow)? / (U256::from(2) * wad);
+            return Ok(linear + quadratic);
+        }
+
+        // base / k * (e^(k * to) - e^(k * from))
+        if parameter.is_zero() {
+            return Ok(base_price.checked_mul(to - from).ok_or_else(overflow)? / wad);
+        }
+        let exponent_to = parameter.checked_mul(to).ok_or_else(overflow)? / wad;
+        let exponent_from = parameter.checked_mul(from).ok_or_else(overflow)? / wad;
+        let growth = exp_wad(exponent_to).ok_or_else(overflow)? - exp_wad(exponent_from).ok_or_else(overflow)?;
+        Ok(base_price.checked_mul(growth).ok_or_else(overflow)? / parameter)
+    }
+
+    /// Calls the token, failing with `ExternalCallFailed` if it reverts.
+    fn call_token(&mut self, calldata: Vec<u8>) -> Result<Vec<u8>, BondingCurveError> {
+        let token = self.token.get();
+        self.vm()
+            .call(&calls::context::Call::new(), token, &calldata)
+            .map_err(|_| BondingCurveError::ExternalCallFailed(ExternalCallFailed { target: token }))
+    }
+
+    fn graduate(&mut self) -> Result<(), BondingCurveError> {
+        let hook = self.graduation_hook.get();
+        let reserve = self.reserve.get();
+        let token_amount = self.graduation_token_amount.get();
+        let token = self.token.get();
+        self.graduated.set(true);
+        self.reserve.set(U256::ZERO);
+
+        if !token_amount.is_zero() {
+            self.call_token(ICradleToken::mintToCall { to: hook, value: token_amount }.abi_encode())?;
+        }
+        let calldata = IGraduationHook::onGraduationCall { token, token_amount }.abi_encode();
+        let context = calls::context::Call::new().value(reserve);
+        self.vm()
+            .call(&context, hook, &calldata)
+            .map_err(|_| BondingCurveError::ExternalCallFailed(ExternalCallFailed { target: hook }))?;
+
+        log(self.vm(), Graduated {
+            hook,
+            reserve,
+            token_amount,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl BondingCurve {
+    /// Configures the token and the curve. Can only be called once.
+    /// `curve_parameter` is the slope for linear curves and the WAD-scaled growth rate for exponential ones.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        token: Address,
+        curve_type: u8,
+        base_price: U256,
+        curve_parameter: U256,
+    ) -> Result<(), BondingCurveError> {
+        if self.initialized.get() {
+            return Err(BondingCurveError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if curve_type != CURVE_LINEAR && curve_type != CURVE_EXPONENTIAL {
+            return Err(BondingCurveError::InvalidCurve(InvalidCurve { curve_type }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.token.set(token);
+        self.curve_type.set(alloy_primitives::Uint::<8, 1>::from(curve_type));
+        self.base_price.set(base_price);
+        self.curve_parameter.set(curve_parameter);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Buys exactly `amount` tokens, paying at most `max_cost`. Excess ETH is refunded.
+    #[payable]
+    pub fn buy(&mut self, amount: U256, max_cost: U256) -> Result<U256, BondingCurveError> {
+        if self.graduated.get() {
+            return Err(BondingCurveError::AlreadyGraduated(AlreadyGraduated {}));
+        }
+        if amount.is_zero() {
+            return Err(BondingCurveError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let supply = self.curve_supply.get();
+        let cost = self.area(supply, supply + amount)?;
+        if cost > max_cost {
+            return Err(BondingCurveError::SlippageExceeded(SlippageExceeded {
+                quoted: cost,
+                limit: max_cost,
+            }));
+        }
+        let received = self.vm().msg_value();
+        if received < cost {
+            return Err(BondingCurveError::InsufficientPayment(InsufficientPayment { cost, received }));
+        }
+
+        let buyer = self.vm().msg_sender();
+        let new_supply = supply + amount;
+        self.curve_supply.set(new_supply);
+        self.reserve.set(self.reserve.get() + cost);
+
+        self.call_token(ICradleToken::mintToCall { to: buyer, value: amount }.abi_encode())?;
+        if received > cost {
+            self.vm()
+                .transfer_eth(buyer, received - cost)
+                .map_err(|_| BondingCurveError::ExternalCallFailed(ExternalCallFailed { target: buyer }))?;
+        }
+
+        log(self.vm(), Bought {
+            buyer,
+            amount,
+            cost,
+            new_supply,
+        });
+
+        let threshold = self.graduation_reserve.get();
+        if !threshold.is_zero() && self.reserve.get() >= threshold {
+            self.graduate()?;
+        }
+        Ok(cost)
+    }
+
+    /// Sells `amount` tokens back to the curve for at least `min_payout`.
+    /// Requires an allowance for the curve, which burns the tokens.
+    pub fn sell(&mut self, amount: U256, min_payout: U256) -> Result<U256, BondingCurveError> {
+        if self.graduated.get() {
+            return Err(BondingCurveError::AlreadyGraduated(AlreadyGraduated {}));
+        }
+        let supply = self.curve_supply.get();
+        if amount.is_zero() || amount > supply {
+            return Err(BondingCurveError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let payout = self.area(supply - amount, supply)?.min(self.reserve.get());
+        if payout < min_payout {
+            return Err(BondingCurveError::SlippageExceeded(SlippageExceeded {
+                quoted: payout,
+                limit: min_payout,
+            }));
+        }
+
+        let seller = self.vm().msg_sender();
+        let new_supply = supply - amount;
+        self.curve_supply.set(new_supply);
+        self.reserve.set(self.reserve.get() - payout);
+
+        let token = self.token.get();
+        let this = self.vm().contract_address();
+        let call_failed = || BondingCurveError::ExternalCallFailed(ExternalCallFailed { target: token });
+        let call = ICradleToken::transferFromCall { from: seller, to: this, value: amount };
+        let pulled = self.call_token(call.abi_encode())?;
+        if !bool::abi_decode(&pulled, true).unwrap_or(false) {
+            return Err(call_failed());
+        }
+        self.call_token(ICradleToken::burnCall { value: amount }.abi_encode())?;
+        self.vm()
+            .transfer_eth(seller, payout)
+            .map_err(|_| BondingCurveError::ExternalCallFailed(ExternalCallFailed { target: seller }))?;
+
+        log(self.vm(), Sold {
+            seller,
+            amount,
+            payout,
+            new_supply,
+        });
+        Ok(payout)
+    }
+
+    /// Configures graduation (owner only). A zero `graduation_reserve` disables it.
+    pub fn configure_graduation(
+        &mut self,
+        hook: Address,
+        graduation_reserve: U256,
+        token_amount: U256,
+    ) -> Result<(), BondingCurveError> {
+        self.only_owner()?;
+        if self.graduated.get() {
+            return Err(BondingCurveError::AlreadyGraduated(AlreadyGraduated {}));
+        }
+        self.graduation_hook.set(hook);
+        self.graduation_reserve.set(graduation_reserve);
+        self.graduation_token_amount.set(token_amount);
+
+        log(self.vm(), GraduationConfigured {
+            hook,
+            graduation_reserve,
+            token_amount,
+        });
+        Ok(())
+    }
+
+    /// Transfers ownership of the curve (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), BondingCurveError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Cost in wei of buying `amount` tokens now.
+    pub fn quote_buy(&self, amount: U256) -> Result<U256, BondingCurveError> {
+        let supply = self.curve_supply.get();
+        self.area(supply, supply + amount)
+    }
+
+    /// Payout in wei for selling `amount` tokens now.
+    pub fn quote_sell(&self, amount: U256) -> Result<U256, BondingCurveError> {
+        let supply = self.curve_supply.get();
+        if amount > supply {
+            return Err(BondingCurveError::InvalidAmount(InvalidAmount { amount }));
+        }
+        Ok(self.area(supply - amount, supply)?.min(self.reserve.get()))
+    }
+
+    /// Spot price of the next token, in wei per whole token.
+    pub fn spot_price(&self) -> Result<U256, BondingCurveError> {
+        let wad = U256::from(WAD);
+        let supply = self.curve_supply.get();
+        let base_price = self.base_price.get();
+        let parameter = self.curve_parameter.get();
+        let overflow = || BondingCurveError::CurveOverflow(CurveOverflow {});
+        if self.curve_type.get().to::<u8>() == CURVE_LINEAR {
+            return Ok(base_price + parameter.checked_mul(supply).ok_or_else(overflow)? / wad);
+        }
+        let exponent = parameter.checked_mul(supply).ok_or_else(overflow)? / wad;
+        let growth = exp_wad(exponent).ok_or_else(overflow)?;
+        Ok(base_price.checked_mul(growth).ok_or_else(overflow)? / wad)
+    }
+
+    /// Returns `(curve_supply, reserve, graduated)`.
+    pub fn state(&self) -> (U256, U256, bool) {
+        (self.curve_supply.get(), self.reserve.get(), self.graduated.get())
+    }
+
+    /// Returns `(token, curve_type, base_price, curve_parameter)`.
+    pub fn curve(&self) -> (Address, u8, U256, U256) {
+        (
+            self.token.get(),
+            self.curve_type.get().to::<u8>(),
+            self.base_price.get(),
+            self.curve_parameter.get(),
+        )
+    }
+
+    /// Returns `(hook, graduation_reserve, token_amount)`.
+    pub fn graduation(&self) -> (Address, U256, U256) {
+        (
+            self.graduation_hook.get(),
+            self.graduation_reserve.get(),
+            self.graduation_token_amount.get(),
+        )
+    }
+
+    /// Current owner of the curve.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
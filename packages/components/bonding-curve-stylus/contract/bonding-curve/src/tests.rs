@@ -0,0 +1,120 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const TOKEN: Address = address!("00000000000000000000000000000000000020c0");
+const HOOK: Address = address!("000000000000000000000000000000000000900c");
+
+/// `value` whole tokens.
+fn tokens(value: u64) -> U256 {
+    U256::from(WAD) * U256::from(value)
+}
+
+/// A curve of `curve_type` selling `TOKEN` from 1,000 wei per token.
+fn setup(curve_type: u8, curve_parameter: U256) -> (TestVM, BondingCurve) {
+    let (vm, mut contract) = deploy::<BondingCurve>(OWNER);
+    ok(contract.initialize(OWNER, TOKEN, curve_type, n(1_000), curve_parameter));
+    (vm, contract)
+}
+
+/// Buys `amount` whole tokens as `buyer`, attaching `value` wei.
+fn buy(
+    vm: &TestVM,
+    contract: &mut BondingCurve,
+    buyer: Address,
+    amount: u64,
+    value: u64,
+) -> Result<U256, BondingCurveError> {
+    vm.set_sender(buyer);
+    vm.set_value(n(value));
+    vm.set_balance(vm.contract_address(), vm.balance(vm.contract_address()) + n(value));
+    contract.buy(tokens(amount), n(value))
+}
+
+#[test]
+fn buying_and_selling_back_is_neutral() {
+    // 1,000 wei plus 100 per token sold: the first 10 cost 10,000 + 100 * 10^2 / 2
+    let (vm, mut contract) = setup(CURVE_LINEAR, n(100));
+    assert_eq!(ok(contract.quote_buy(tokens(10))), n(15_000));
+    assert_eq!(ok(buy(&vm, &mut contract, ALICE, 10, 20_000)), n(15_000));
+    assert_eq!(contract.state(), (tokens(10), n(15_000), false));
+    assert_eq!(ok(contract.spot_price()), n(2_000));
+    // The excess is refunded
+    assert_eq!(vm.balance(ALICE), n(5_000));
+    let bought = &emitted::<Bought>(&vm)[0];
+    assert_eq!((bought.buyer, bought.amount, bought.cost), (ALICE, tokens(10), n(15_000)));
+
+    let call = ICradleToken::transferFromCall { from: ALICE, to: vm.contract_address(), value: tokens(10) };
+    vm.mock_call(TOKEN, call.abi_encode(), Ok(true.abi_encode()));
+    vm.set_value(U256::ZERO);
+    assert_eq!(ok(contract.sell(tokens(10), n(15_000))), n(15_000));
+    assert_eq!(contract.state(), (U256::ZERO, U256::ZERO, false));
+    assert_eq!((vm.balance(ALICE), vm.balance(vm.contract_address())), (n(20_000), U256::ZERO));
+}
+
+#[test]
+fn exponential_curves_price_the_integral() {
+    // 1,000 * (e - 1) for the first token at a growth rate of 1 per token
+    let (_vm, contract) = setup(CURVE_EXPONENTIAL, tokens(1));
+    assert_eq!(ok(contract.quote_buy(tokens(1))), n(1_718));
+    assert!(matches!(contract.quote_buy(tokens(41)), Err(BondingCurveError::CurveOverflow(_))));
+}
+
+#[test]
+fn trades_outside_their_bounds_revert() {
+    let (vm, mut contract) = setup(CURVE_LINEAR, n(100));
+    vm.set_sender(ALICE);
+    vm.set_value(n(20_000));
+    let result = contract.buy(tokens(10), n(14_999));
+    assert!(matches!(
+        result,
+        Err(BondingCurveError::SlippageExceeded(e)) if e.quoted == n(15_000) && e.limit == n(14_999)
+    ));
+    vm.set_value(n(14_999));
+    let result = contract.buy(tokens(10), n(15_000));
+    assert!(matches!(result, Err(BondingCurveError::InsufficientPayment(e)) if e.received == n(14_999)));
+
+    ok(buy(&vm, &mut contract, ALICE, 10, 15_000));
+    vm.set_value(U256::ZERO);
+    let result = contract.sell(tokens(11), U256::ZERO);
+    assert!(matches!(result, Err(BondingCurveError::InvalidAmount(e)) if e.amount == tokens(11)));
+    let result = contract.sell(tokens(10), n(15_001));
+    assert!(matches!(result, Err(BondingCurveError::SlippageExceeded(e)) if e.quoted == n(15_000)));
+
+    // A token that doesn't hand the tokens over fails the sale
+    let result = contract.sell(tokens(10), U256::ZERO);
+    assert!(matches!(result, Err(BondingCurveError::ExternalCallFailed(e)) if e.target == TOKEN));
+}
+
+#[test]
+fn reaching_the_threshold_graduates_to_the_hook() {
+    let (vm, mut contract) = setup(CURVE_LINEAR, n(100));
+    vm.set_sender(ALICE);
+    let result = contract.configure_graduation(HOOK, n(15_000), tokens(5));
+    assert!(matches!(result, Err(BondingCurveError::Unauthorized(e)) if e.caller == ALICE));
+    vm.set_sender(OWNER);
+    ok(contract.configure_graduation(HOOK, n(15_000), tokens(5)));
+
+    let graduation = IGraduationHook::onGraduationCall { token: TOKEN, token_amount: tokens(5) };
+    vm.mock_call(HOOK, graduation.abi_encode(), Ok(Vec::new()));
+    ok(buy(&vm, &mut contract, ALICE, 10, 15_000));
+    assert_eq!(contract.state(), (tokens(10), U256::ZERO, true));
+    let graduated = &emitted::<Graduated>(&vm)[0];
+    assert_eq!((graduated.hook, graduated.reserve, graduated.token_amount), (HOOK, n(15_000), tokens(5)));
+
+    let result = buy(&vm, &mut contract, BOB, 1, 5_000);
+    assert!(matches!(result, Err(BondingCurveError::AlreadyGraduated(_))));
+}
+
+#[test]
+fn a_failed_mint_fails_the_purchase() {
+    let (vm, mut contract) = setup(CURVE_LINEAR, n(100));
+    let mint = ICradleToken::mintToCall { to: ALICE, value: tokens(10) };
+    vm.mock_call(TOKEN, mint.abi_encode(), Err(b"not a minter".to_vec()));
+    let result = buy(&vm, &mut contract, ALICE, 10, 15_000);
+    assert!(matches!(result, Err(BondingCurveError::ExternalCallFailed(e)) if e.target == TOKEN));
+}
@@ -0,0 +1,523 @@
+//! Cross-chain bridge adapter for Cradle ERC-1155 collections
+//!
+//! One adapter is deployed per chain a collection exists on, and each adapter
+//! is paired with a peer adapter on the other chain:
+//!
+//! - On the collection's home chain the adapter runs in lock mode. Bridging
+//!   out locks the caller's tokens in the adapter, and tokens bridged back are
+//!   released from it.
+//! - On every other chain the adapter runs in mint mode and owns its copy of
+//!   the collection. Bridging out burns the caller's tokens, and tokens bridged
+//!   in are minted.
+//!
+//! Messages travel through a configurable messenger. With the Arbitrum
+//! messenger, messages to the parent chain go through ArbSys and messages from
+//! it arrive as retryable tickets from the peer's aliased address. With an
+//! endpoint messenger, a generic messaging endpoint delivers payloads to
+//! `receive_message` and tags them with their source chain and sender.
+//!
+//! Every transfer has a unique ID that is only accepted once, and the owner
+//! sets how much of each token ID may move in each direction per window.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::{aliases::U160, Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Offset added to a parent chain contract's address when it calls Arbitrum.
+const L1_TO_L2_ALIAS_OFFSET: U160 = U160::from_limbs([0x1111, 0, 0x1111_0000]);
+/// `mode` of an adapter that locks and releases the original tokens.
+const MODE_LOCK: u8 = 0;
+/// `mode` of an adapter that mints and burns a copy of the collection.
+const MODE_MINT: u8 = 1;
+/// `messenger_kind` using ArbSys and retryable tickets.
+const MESSENGER_ARBITRUM: u8 = 0;
+/// `messenger_kind` using a generic messaging endpoint.
+const MESSENGER_ENDPOINT: u8 = 1;
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct BridgeAdapter {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Configures the peer and rate limits
+        address owner;
+        /// ERC-1155 collection being bridged
+        address token;
+        /// Lock (0) or mint (1)
+        uint8 mode;
+        /// Arbitrum (0) or endpoint (1)
+        uint8 messenger_kind;
+        /// Messaging endpoint; unused with the Arbitrum messenger
+        address endpoint;
+        /// Adapter on the other chain
+        address peer;
+        /// Chain ID of the peer
+        uint256 peer_chain_id;
+        /// Transfers sent so far, mixed into transfer IDs
+        uint256 nonce;
+        /// Transfer IDs already received
+        mapping(bytes32 => bool) processed;
+        /// Length of a rate limit window in seconds
+        uint256 window_length;
+        /// Rate limit per token ID
+        mapping(uint256 => RateLimit) rate_limits;
+    }
+
+    pub struct RateLimit {
+        /// Amount that may move in each direction per window; zero disables bridging the ID
+        uint256 limit;
+        uint256 window_start;
+        /// Amount sent in the current window
+        uint256 outbound;
+        /// Amount received in the current window
+        uint256 inbound;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event TransferSent(bytes32 indexed transfer_id, address indexed from, address to, uint256 indexed id, uint256 amount);
+    event TransferReceived(bytes32 indexed transfer_id, address indexed to, uint256 indexed id, uint256 amount);
+    event PeerUpdated(address indexed peer, uint256 peer_chain_id);
+    event EndpointUpdated(address indexed endpoint);
+    event RateLimitUpdated(uint256 indexed id, uint256 limit);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Mode or messenger kind isn't supported
+    error InvalidConfig();
+    // Function doesn't belong to the configured messenger
+    error WrongMessenger();
+    // Amount is zero
+    error InvalidAmount();
+    // Recipient is the zero address
+    error InvalidRecipient();
+    // Amount exceeds what the ID may still move in this window
+    error RateLimitExceeded(uint256 id, uint256 available);
+    // Transfer ID was already received
+    error AlreadyProcessed(bytes32 transfer_id);
+    // Endpoint payload couldn't be decoded
+    error InvalidMessage();
+    // ETH was sent with the Arbitrum messenger
+    error UnexpectedValue();
+    // Locking, releasing, minting or burning on the collection failed
+    error TokenCallFailed(address token);
+    // Sending the message failed
+    error MessengerCallFailed();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum BridgeError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidConfig(InvalidConfig),
+    WrongMessenger(WrongMessenger),
+    InvalidAmount(InvalidAmount),
+    InvalidRecipient(InvalidRecipient),
+    RateLimitExceeded(RateLimitExceeded),
+    AlreadyProcessed(AlreadyProcessed),
+    InvalidMessage(InvalidMessage),
+    UnexpectedValue(UnexpectedValue),
+    TokenCallFailed(TokenCallFailed),
+    MessengerCallFailed(MessengerCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface ICradle1155 {
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+        function burnFrom(address from, uint256 id, uint256 amount) external;
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    /// Call made on the peer when a transfer is sent through ArbSys.
+    interface IBridgeAdapter {
+        function finalizeTransfer(bytes32 transfer_id, address to, uint256 id, uint256 amount) external;
+    }
+
+    /// Endpoint method that takes the messaging fee, called with the value sent to `bridge`.
+    interface IMessageEndpoint {
+        function sendMessage(uint256 dst_chain_id, address receiver, bytes payload) external payable returns (bytes32);
+    }
+}
+
+/// Context of the calls that lock, release, mint and burn on the collection. Made through the host,
+/// unlike `sol_interface!` calls, they build with the SDK's `reentrant` feature and are mocked under
+/// test.
+struct TokenCall;
+
+impl calls::CallContext for TokenCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale if the collection calls back into the adapter, as `onERC1155Received` is when locking
+unsafe impl calls::MutatingCallContext for TokenCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl BridgeAdapter {
+    fn only_owner(&self) -> Result<(), BridgeError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(BridgeError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn messenger_kind(&self) -> u8 {
+        self.messenger_kind.get().to::<u8>()
+    }
+
+    /// Records `amount` of `id` moving in one direction, starting a new window if the last one ended.
+    fn consume_rate_limit(&mut self, id: U256, amount: U256, outbound: bool) -> Result<(), BridgeError> {
+        let now = self.now();
+        let window_length = self.window_length.get();
+        let mut rate_limit = self.rate_limits.setter(id);
+        if now >= rate_limit.window_start.get() + window_length {
+            rate_limit.window_start.set(now);
+            rate_limit.outbound.set(U256::ZERO);
+            rate_limit.inbound.set(U256::ZERO);
+        }
+        let limit = rate_limit.limit.get();
+        let used = if outbound {
+            rate_limit.outbound.get()
+        } else {
+            rate_limit.inbound.get()
+        };
+        if used + amount > limit {
+            return Err(BridgeError::RateLimitExceeded(RateLimitExceeded {
+                id,
+                available: limit.saturating_sub(used),
+            }));
+        }
+        if outbound {
+            rate_limit.outbound.set(used + amount);
+        } else {
+            rate_limit.inbound.set(used + amount);
+        }
+        Ok(())
+    }
+
+    /// Sends a transfer to the peer through the configured messenger.
+    fn send_message(&mut self, transfer_id: B256, to: Address, id: U256, amount: U256) -> Result<(), BridgeError> {
+        let peer = self.peer.get();
+        if self.messenger_kind() == MESSENGER_ARBITRUM {
+            if !self.vm().msg_value().is_zero() {
+                return Err(BridgeError::UnexpectedValue(UnexpectedValue {}));
+            }
+            let data = IBridgeAdapter::finalizeTransferCall {
+                transfer_id,
+                to,
+                id,
+                amount,
+            }
+            .abi_encode();
+            cradle_arb::send_tx_to_l1(self.vm(), peer, &data, U256::ZERO)
+                .map(|_| ())
+                .map_err(|_| BridgeError::MessengerCallFailed(MessengerCallFailed {}))
+        } else {
+            let payload = (transfer_id, to, id, amount).abi_encode_params();
+            let calldata = IMessageEndpoint::sendMessageCall {
+                dst_chain_id: self.peer_chain_id.get(),
+                receiver: peer,
+                payload: payload.into(),
+            }
+            .abi_encode();
+            let context = calls::context::Call::new().value(self.vm().msg_value());
+            self.vm()
+                .call(&context, self.endpoint.get(), &calldata)
+                .map(|_| ())
+                .map_err(|_| BridgeError::MessengerCallFailed(MessengerCallFailed {}))
+        }
+    }
+
+    /// Releases or mints a transfer received from the peer.
+    fn complete_transfer(&mut self, transfer_id: B256, to: Address, id: U256, amount: U256) -> Result<(), BridgeError> {
+        if self.processed.get(transfer_id) {
+            return Err(BridgeError::AlreadyProcessed(AlreadyProcessed { transfer_id }));
+        }
+        if to.is_zero() {
+            return Err(BridgeError::InvalidRecipient(InvalidRecipient {}));
+        }
+        self.processed.insert(transfer_id, true);
+        self.consume_rate_limit(id, amount, false)?;
+
+        let token = self.token.get();
+        let calldata = if self.mode.get().to::<u8>() == MODE_MINT {
+            ICradle1155::mintCall { to, id, amount, data: Default::default() }.abi_encode()
+        } else {
+            let from = self.vm().contract_address();
+            ICradle1155::safeTransferFromCall {
+                from,
+                to,
+                id,
+                value: amount,
+                data: Default::default(),
+            }
+            .abi_encode()
+        };
+        if self.vm().call(&TokenCall, token, &calldata).is_err() {
+            return Err(BridgeError::TokenCallFailed(TokenCallFailed { token }));
+        }
+
+        log(self.vm(), TransferReceived {
+            transfer_id,
+            to,
+            id,
+            amount,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl BridgeAdapter {
+    /// Sets up the adapter. Can only be called once. `mode` is 0 to lock and 1 to mint, and
+    /// `messenger_kind` is 0 for Arbitrum and 1 for an endpoint. Every ID starts with a zero rate
+    /// limit, so nothing can be bridged until the owner sets limits.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        token: Address,
+        mode: u8,
+        messenger_kind: u8,
+        endpoint: Address,
+        peer: Address,
+        peer_chain_id: U256,
+        window_length: U256,
+    ) -> Result<(), BridgeError> {
+        if self.initialized.get() {
+            return Err(BridgeError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if (mode != MODE_LOCK && mode != MODE_MINT)
+            || (messenger_kind != MESSENGER_ARBITRUM && messenger_kind != MESSENGER_ENDPOINT)
+            || window_length.is_zero()
+        {
+            return Err(BridgeError::InvalidConfig(InvalidConfig {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.token.set(token);
+        self.mode.set(alloy_primitives::Uint::<8, 1>::from(mode));
+        self.messenger_kind.set(alloy_primitives::Uint::<8, 1>::from(messenger_kind));
+        self.endpoint.set(endpoint);
+        self.peer.set(peer);
+        self.peer_chain_id.set(peer_chain_id);
+        self.window_length.set(window_length);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), PeerUpdated { peer, peer_chain_id });
+        Ok(())
+    }
+
+    /// Sends `amount` of `id` to `to` on the peer's chain. The caller must have approved the adapter
+    /// with `setApprovalForAll`. With an endpoint messenger, send the messaging fee as ETH. Returns
+    /// the transfer ID.
+    #[payable]
+    pub fn bridge(&mut self, to: Address, id: U256, amount: U256) -> Result<B256, BridgeError> {
+        if amount.is_zero() {
+            return Err(BridgeError::InvalidAmount(InvalidAmount {}));
+        }
+        if to.is_zero() {
+            return Err(BridgeError::InvalidRecipient(InvalidRecipient {}));
+        }
+        self.consume_rate_limit(id, amount, true)?;
+
+        let from = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        let token = self.token.get();
+        let calldata = if self.mode.get().to::<u8>() == MODE_MINT {
+            ICradle1155::burnFromCall { from, id, amount }.abi_encode()
+        } else {
+            ICradle1155::safeTransferFromCall {
+                from,
+                to: this,
+                id,
+                value: amount,
+                data: Default::default(),
+            }
+            .abi_encode()
+        };
+        if self.vm().call(&TokenCall, token, &calldata).is_err() {
+            return Err(BridgeError::TokenCallFailed(TokenCallFailed { token }));
+        }
+
+        let nonce = self.nonce.get();
+        self.nonce.set(nonce + U256::from(1));
+        let transfer_id = self
+            .vm()
+            .native_keccak256(&(U256::from(self.vm().chain_id()), this, nonce).abi_encode());
+        self.send_message(transfer_id, to, id, amount)?;
+
+        log(self.vm(), TransferSent {
+            transfer_id,
+            from,
+            to,
+            id,
+            amount,
+        });
+        Ok(transfer_id)
+    }
+
+    /// Completes a transfer sent by the peer through a retryable ticket (Arbitrum messenger only).
+    pub fn finalize_transfer(&mut self, transfer_id: B256, to: Address, id: U256, amount: U256) -> Result<(), BridgeError> {
+        if self.messenger_kind() != MESSENGER_ARBITRUM {
+            return Err(BridgeError::WrongMessenger(WrongMessenger {}));
+        }
+        let caller = self.vm().msg_sender();
+        let aliased_peer = Address::from(U160::from_be_bytes(self.peer.get().0 .0).wrapping_add(L1_TO_L2_ALIAS_OFFSET));
+        if caller != aliased_peer {
+            return Err(BridgeError::Unauthorized(Unauthorized { caller }));
+        }
+        self.complete_transfer(transfer_id, to, id, amount)
+    }
+
+    /// Completes a transfer delivered by the endpoint (endpoint messenger only). `payload` is
+    /// `abi.encode(transferId, to, id, amount)`.
+    pub fn receive_message(&mut self, src_chain_id: U256, sender: Address, payload: Bytes) -> Result<(), BridgeError> {
+        if self.messenger_kind() != MESSENGER_ENDPOINT {
+            return Err(BridgeError::WrongMessenger(WrongMessenger {}));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != self.endpoint.get() || src_chain_id != self.peer_chain_id.get() || sender != self.peer.get() {
+            return Err(BridgeError::Unauthorized(Unauthorized { caller }));
+        }
+        let (transfer_id, to, id, amount) = <(B256, Address, U256, U256)>::abi_decode_params(&payload, true)
+            .map_err(|_| BridgeError::InvalidMessage(InvalidMessage {}))?;
+        self.complete_transfer(transfer_id, to, id, amount)
+    }
+
+    /// Sets how much of `id` may move in each direction per window (owner only). Zero stops the ID
+    /// from being bridged.
+    pub fn set_rate_limit(&mut self, id: U256, limit: U256) -> Result<(), BridgeError> {
+        self.only_owner()?;
+        self.rate_limits.setter(id).limit.set(limit);
+
+        log(self.vm(), RateLimitUpdated { id, limit });
+        Ok(())
+    }
+
+    /// Replaces the peer adapter (owner only).
+    pub fn set_peer(&mut self, peer: Address, peer_chain_id: U256) -> Result<(), BridgeError> {
+        self.only_owner()?;
+        self.peer.set(peer);
+        self.peer_chain_id.set(peer_chain_id);
+
+        log(self.vm(), PeerUpdated { peer, peer_chain_id });
+        Ok(())
+    }
+
+    /// Replaces the messaging endpoint (owner only).
+    pub fn set_endpoint(&mut self, endpoint: Address) -> Result<(), BridgeError> {
+        self.only_owner()?;
+        self.endpoint.set(endpoint);
+
+        log(self.vm(), EndpointUpdated { endpoint });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), BridgeError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Accepts tokens the adapter locks itself and rejects any sent to it directly.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes::from(ERC1155_RECEIVED.to_be_bytes())
+        } else {
+            FixedBytes::ZERO
+        }
+    }
+
+    /// Returns `(limit, available_outbound, available_inbound)` for a token ID in the current window.
+    pub fn rate_limit(&self, id: U256) -> (U256, U256, U256) {
+        let rate_limit = self.rate_limits.getter(id);
+        let limit = rate_limit.limit.get();
+        if self.now() >= rate_limit.window_start.get() + self.window_length.get() {
+            return (limit, limit, limit);
+        }
+        (
+            limit,
+            limit.saturating_sub(rate_limit.outbound.get()),
+            limit.saturating_sub(rate_limit.inbound.get()),
+        )
+    }
+
+    /// Whether a transfer from the peer has been received.
+    pub fn is_processed(&self, transfer_id: B256) -> bool {
+        self.processed.get(transfer_id)
+    }
+
+    /// Returns `(token, mode, messenger_kind, endpoint, peer, peer_chain_id)`.
+    pub fn config(&self) -> (Address, u8, u8, Address, Address, U256) {
+        (
+            self.token.get(),
+            self.mode.get().to::<u8>(),
+            self.messenger_kind(),
+            self.endpoint.get(),
+            self.peer.get(),
+            self.peer_chain_id.get(),
+        )
+    }
+
+    /// Length of a rate limit window in seconds.
+    pub fn window_length(&self) -> U256 {
+        self.window_length.get()
+    }
+
+    /// Number of transfers sent.
+    pub fn nonce(&self) -> U256 {
+        self.nonce.get()
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
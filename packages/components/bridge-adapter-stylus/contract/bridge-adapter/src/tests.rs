@@ -0,0 +1,258 @@
+use super::*;
+use alloc::vec::Vec;
+use alloy_primitives::address;
+use alloy_sol_types::SolError;
+use cradle_arb::{ArbSys, ARB_SYS};
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const TOKEN: Address = address!("0000000000000000000000000000000000001155");
+const ENDPOINT: Address = address!("00000000000000000000000000000000000e4d00");
+const PEER: Address = address!("0000000000000000000000000000000000009ee5");
+const PEER_CHAIN_ID: u64 = 1;
+const WINDOW: u64 = 3_600;
+const ID: u64 = 7;
+
+/// An adapter at timestamp 1000 bridging `TOKEN` in `mode` over `messenger_kind`, letting 100 of
+/// `ID` move each way per window.
+fn setup(mode: u8, messenger_kind: u8) -> (TestVM, BridgeAdapter) {
+    let (vm, mut contract) = deploy::<BridgeAdapter>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, TOKEN, mode, messenger_kind, ENDPOINT, PEER, n(PEER_CHAIN_ID), n(WINDOW)));
+    ok(contract.set_rate_limit(n(ID), n(100)));
+    (vm, contract)
+}
+
+/// The ID of the transfer the adapter sends with `nonce`.
+fn transfer_id(vm: &TestVM, nonce: u64) -> B256 {
+    vm.native_keccak256(&(n(vm.chain_id()), vm.contract_address(), n(nonce)).abi_encode())
+}
+
+/// Mocks the collection answering `answer` to the call `bridge` makes to take `amount` of `ID`
+/// from `ALICE`.
+fn mock_take(vm: &TestVM, mode: u8, amount: u64, answer: Result<Vec<u8>, Vec<u8>>) {
+    let calldata = if mode == MODE_MINT {
+        ICradle1155::burnFromCall { from: ALICE, id: n(ID), amount: n(amount) }.abi_encode()
+    } else {
+        let to = vm.contract_address();
+        ICradle1155::safeTransferFromCall { from: ALICE, to, id: n(ID), value: n(amount), data: Default::default() }
+            .abi_encode()
+    };
+    vm.mock_call(TOKEN, calldata, answer);
+}
+
+/// Mocks the collection answering `answer` to the call a received transfer makes to give `amount`
+/// of `ID` to `BOB`.
+fn mock_give(vm: &TestVM, mode: u8, amount: u64, answer: Result<Vec<u8>, Vec<u8>>) {
+    let calldata = if mode == MODE_MINT {
+        ICradle1155::mintCall { to: BOB, id: n(ID), amount: n(amount), data: Default::default() }.abi_encode()
+    } else {
+        let from = vm.contract_address();
+        ICradle1155::safeTransferFromCall { from, to: BOB, id: n(ID), value: n(amount), data: Default::default() }
+            .abi_encode()
+    };
+    vm.mock_call(TOKEN, calldata, answer);
+}
+
+/// Mocks ArbSys answering `answer` to the message `bridge` sends the peer for `amount` of `ID`.
+fn mock_arb_sys(vm: &TestVM, nonce: u64, amount: u64, answer: Vec<u8>) {
+    let data = IBridgeAdapter::finalizeTransferCall {
+        transfer_id: transfer_id(vm, nonce),
+        to: BOB,
+        id: n(ID),
+        amount: n(amount),
+    }
+    .abi_encode();
+    let call = ArbSys::sendTxToL1Call { destination: PEER, data: data.into() };
+    vm.mock_call(ARB_SYS, call.abi_encode(), Ok(answer));
+}
+
+fn payload(transfer_id: B256, to: Address, amount: u64) -> Bytes {
+    (transfer_id, to, n(ID), n(amount)).abi_encode_params().into()
+}
+
+#[test]
+fn initialize_runs_once_with_a_known_config() {
+    let (vm, mut contract) = deploy::<BridgeAdapter>(OWNER);
+    let peer_chain_id = n(PEER_CHAIN_ID);
+    for (mode, messenger_kind, window) in [(2, 0, WINDOW), (0, 2, WINDOW), (0, 0, 0)] {
+        let result = contract.initialize(OWNER, TOKEN, mode, messenger_kind, ENDPOINT, PEER, peer_chain_id, n(window));
+        assert_eq!(reverted(result), InvalidConfig {}.abi_encode());
+    }
+    ok(contract.initialize(OWNER, TOKEN, MODE_MINT, MESSENGER_ENDPOINT, ENDPOINT, PEER, peer_chain_id, n(WINDOW)));
+    assert_eq!(contract.config(), (TOKEN, MODE_MINT, MESSENGER_ENDPOINT, ENDPOINT, PEER, peer_chain_id));
+    assert_eq!((contract.owner(), contract.window_length()), (OWNER, n(WINDOW)));
+    let event = emitted::<PeerUpdated>(&vm).pop().expect("peer logged");
+    assert_eq!((event.peer, event.peer_chain_id), (PEER, peer_chain_id));
+    // Nothing moves until the owner sets limits
+    assert_eq!(contract.rate_limit(n(ID)), (U256::ZERO, U256::ZERO, U256::ZERO));
+
+    vm.set_sender(ALICE);
+    let result = contract.initialize(ALICE, ALICE, MODE_LOCK, MESSENGER_ARBITRUM, ALICE, ALICE, U256::ZERO, n(1));
+    assert_eq!(reverted(result), AlreadyInitialized {}.abi_encode());
+}
+
+#[test]
+fn bridging_takes_the_tokens_and_messages_the_peer_through_arb_sys() {
+    for mode in [MODE_LOCK, MODE_MINT] {
+        let (vm, mut contract) = setup(mode, MESSENGER_ARBITRUM);
+        vm.set_sender(ALICE);
+        mock_arb_sys(&vm, 0, 40, n(1).abi_encode());
+
+        // The collection only fails the call the transfer asks for, so failing shows it was asked
+        mock_take(&vm, mode, 40, Err(Vec::new()));
+        assert_eq!(reverted(contract.bridge(BOB, n(ID), n(40))), TokenCallFailed { token: TOKEN }.abi_encode());
+        mock_take(&vm, mode, 40, Ok(Vec::new()));
+        assert_eq!(ok(contract.bridge(BOB, n(ID), n(40))), transfer_id(&vm, 0));
+        assert_eq!(contract.nonce(), n(1));
+        let event = emitted::<TransferSent>(&vm).pop().expect("transfer logged");
+        assert_eq!((event.transfer_id, event.from, event.to), (transfer_id(&vm, 0), ALICE, BOB));
+        assert_eq!((event.id, event.amount), (n(ID), n(40)));
+
+        // Off Arbitrum, ArbSys answers nothing
+        mock_take(&vm, mode, 10, Ok(Vec::new()));
+        mock_arb_sys(&vm, 1, 10, Vec::new());
+        assert_eq!(reverted(contract.bridge(BOB, n(ID), n(10))), MessengerCallFailed {}.abi_encode());
+    }
+}
+
+#[test]
+fn bridging_checks_the_transfer_and_the_fee() {
+    let (vm, mut contract) = setup(MODE_LOCK, MESSENGER_ARBITRUM);
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.bridge(BOB, n(ID), U256::ZERO)), InvalidAmount {}.abi_encode());
+    assert_eq!(reverted(contract.bridge(Address::ZERO, n(ID), n(1))), InvalidRecipient {}.abi_encode());
+    vm.set_value(n(1));
+    assert_eq!(reverted(contract.bridge(BOB, n(ID), n(1))), UnexpectedValue {}.abi_encode());
+
+    // With an endpoint, the fee goes with the message
+    let (vm, mut contract) = setup(MODE_LOCK, MESSENGER_ENDPOINT);
+    vm.set_sender(ALICE);
+    vm.set_value(n(1));
+    let send = |nonce| IMessageEndpoint::sendMessageCall {
+        dst_chain_id: n(PEER_CHAIN_ID),
+        receiver: PEER,
+        payload: payload(transfer_id(&vm, nonce), BOB, 40).0.into(),
+    };
+    vm.mock_call(ENDPOINT, send(0).abi_encode(), Ok(B256::ZERO.abi_encode()));
+    assert_eq!(ok(contract.bridge(BOB, n(ID), n(40))), transfer_id(&vm, 0));
+    vm.mock_call(ENDPOINT, send(1).abi_encode(), Err(Vec::new()));
+    assert_eq!(reverted(contract.bridge(BOB, n(ID), n(40))), MessengerCallFailed {}.abi_encode());
+}
+
+#[test]
+fn each_id_moves_up_to_its_limit_each_way_per_window() {
+    let (vm, mut contract) = setup(MODE_LOCK, MESSENGER_ENDPOINT);
+    vm.set_sender(ALICE);
+    ok(contract.bridge(BOB, n(ID), n(60)));
+    let error = RateLimitExceeded { id: n(ID), available: n(40) }.abi_encode();
+    assert_eq!(reverted(contract.bridge(BOB, n(ID), n(41))), error);
+    ok(contract.bridge(BOB, n(ID), n(40)));
+    let error = RateLimitExceeded { id: n(ID + 1), available: U256::ZERO }.abi_encode();
+    assert_eq!(reverted(contract.bridge(BOB, n(ID + 1), n(1))), error);
+
+    // Inbound transfers count separately
+    vm.set_sender(ENDPOINT);
+    ok(contract.receive_message(n(PEER_CHAIN_ID), PEER, payload(B256::repeat_byte(1), BOB, 30)));
+    assert_eq!(contract.rate_limit(n(ID)), (n(100), U256::ZERO, n(70)));
+
+    vm.set_block_timestamp(1_000 + WINDOW);
+    assert_eq!(contract.rate_limit(n(ID)), (n(100), n(100), n(100)));
+    vm.set_sender(ALICE);
+    ok(contract.bridge(BOB, n(ID), n(100)));
+}
+
+#[test]
+fn only_the_aliased_peer_finalizes_transfers_once() {
+    for mode in [MODE_LOCK, MODE_MINT] {
+        let (vm, mut contract) = setup(mode, MESSENGER_ARBITRUM);
+        let transfer = B256::repeat_byte(1);
+        for caller in [PEER, ALICE] {
+            vm.set_sender(caller);
+            let error = Unauthorized { caller }.abi_encode();
+            assert_eq!(reverted(contract.finalize_transfer(transfer, BOB, n(ID), n(30))), error);
+        }
+
+        let aliased_peer = Address::from(U160::from_be_bytes(PEER.0 .0) + L1_TO_L2_ALIAS_OFFSET);
+        vm.set_sender(aliased_peer);
+        let error = InvalidRecipient {}.abi_encode();
+        assert_eq!(reverted(contract.finalize_transfer(B256::repeat_byte(2), Address::ZERO, n(ID), n(30))), error);
+        mock_give(&vm, mode, 30, Err(Vec::new()));
+        let error = TokenCallFailed { token: TOKEN }.abi_encode();
+        assert_eq!(reverted(contract.finalize_transfer(B256::repeat_byte(3), BOB, n(ID), n(30))), error);
+
+        mock_give(&vm, mode, 30, Ok(Vec::new()));
+        ok(contract.finalize_transfer(transfer, BOB, n(ID), n(30)));
+        assert!(contract.is_processed(transfer));
+        let event = emitted::<TransferReceived>(&vm).pop().expect("transfer logged");
+        assert_eq!((event.transfer_id, event.to, event.id, event.amount), (transfer, BOB, n(ID), n(30)));
+        let error = AlreadyProcessed { transfer_id: transfer }.abi_encode();
+        assert_eq!(reverted(contract.finalize_transfer(transfer, BOB, n(ID), n(30))), error);
+
+        let error = WrongMessenger {}.abi_encode();
+        assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID), PEER, payload(transfer, BOB, 30))), error);
+    }
+}
+
+#[test]
+fn only_the_endpoint_delivers_the_peers_messages() {
+    let (vm, mut contract) = setup(MODE_MINT, MESSENGER_ENDPOINT);
+    let transfer = B256::repeat_byte(1);
+    let message = payload(transfer, BOB, 30);
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID), PEER, message.clone())), error);
+    vm.set_sender(ENDPOINT);
+    let error = Unauthorized { caller: ENDPOINT }.abi_encode();
+    assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID + 1), PEER, message.clone())), error);
+    assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID), ALICE, message.clone())), error);
+    let error = InvalidMessage {}.abi_encode();
+    assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID), PEER, Bytes::from(vec![1, 2, 3]))), error);
+
+    mock_give(&vm, MODE_MINT, 30, Ok(Vec::new()));
+    ok(contract.receive_message(n(PEER_CHAIN_ID), PEER, message.clone()));
+    assert!(contract.is_processed(transfer));
+    let error = AlreadyProcessed { transfer_id: transfer }.abi_encode();
+    assert_eq!(reverted(contract.receive_message(n(PEER_CHAIN_ID), PEER, message)), error);
+    assert_eq!(reverted(contract.finalize_transfer(transfer, BOB, n(ID), n(30))), WrongMessenger {}.abi_encode());
+}
+
+#[test]
+fn only_tokens_the_adapter_locks_are_accepted() {
+    let (vm, mut contract) = setup(MODE_LOCK, MESSENGER_ARBITRUM);
+    let this = vm.contract_address();
+    let accepted = contract.on_erc1155_received(this, ALICE, n(ID), n(1), Bytes::from(Vec::new()));
+    assert_eq!(accepted, FixedBytes::from(ERC1155_RECEIVED.to_be_bytes()));
+    let rejected = contract.on_erc1155_received(ALICE, ALICE, n(ID), n(1), Bytes::from(Vec::new()));
+    assert_eq!(rejected, FixedBytes::ZERO);
+}
+
+#[test]
+fn only_the_owner_configures_the_adapter() {
+    let (vm, mut contract) = setup(MODE_LOCK, MESSENGER_ENDPOINT);
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_rate_limit(n(ID), n(1))), error);
+    assert_eq!(reverted(contract.set_peer(ALICE, n(2))), error);
+    assert_eq!(reverted(contract.set_endpoint(ALICE)), error);
+    assert_eq!(reverted(contract.transfer_ownership(ALICE)), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.set_rate_limit(n(ID), n(5)));
+    assert_eq!(contract.rate_limit(n(ID)).0, n(5));
+    let event = emitted::<RateLimitUpdated>(&vm).pop().expect("rate limit logged");
+    assert_eq!((event.id, event.limit), (n(ID), n(5)));
+    ok(contract.set_peer(BOB, n(2)));
+    let event = emitted::<PeerUpdated>(&vm).pop().expect("peer logged");
+    assert_eq!((event.peer, event.peer_chain_id), (BOB, n(2)));
+    ok(contract.set_endpoint(BOB));
+    assert_eq!(emitted::<EndpointUpdated>(&vm).pop().expect("endpoint logged").endpoint, BOB);
+    assert_eq!(contract.config(), (TOKEN, MODE_LOCK, MESSENGER_ENDPOINT, BOB, BOB, n(2)));
+    ok(contract.transfer_ownership(ALICE));
+    let event = emitted::<OwnershipTransferred>(&vm).pop().expect("ownership logged");
+    assert_eq!((event.previous_owner, event.new_owner), (OWNER, ALICE));
+    assert_eq!(reverted(contract.set_endpoint(OWNER)), Unauthorized { caller: OWNER }.abi_encode());
+}
@@ -0,0 +1,538 @@
+//! Collateralized stablecoin vaults
+//!
+//! Every account has one vault. It deposits an ERC-20 collateral and mints a
+//! stable asset, a Cradle ERC-20, against it up to the minimum collateral
+//! ratio. The collateral is priced in units of the stable asset by a feed of
+//! the Cradle price oracle, and is assumed to have 18 decimals.
+//!
+//! Debt grows with a per-second stability fee through a global rate index, so
+//! every vault pays the same rate without being touched. The fees can be
+//! minted to a recipient of the owner's choice, which keeps the stable supply
+//! equal to the total debt.
+//!
+//! Vaults below the minimum ratio can be liquidated: the liquidator repays debt
+//! and receives collateral worth the repayment plus a penalty.
+//!
+//! The stable asset mints and burns through ERC-7802, so this contract needs
+//! the token's bridge role.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale of prices, the rate index and the stability fee.
+const WAD: u128 = 1_000_000_000_000_000_000;
+/// Basis point denominator.
+const BPS: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Cdp {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the risk parameters and collects fees
+        address owner;
+        /// ERC-20 deposited as collateral
+        address collateral_token;
+        /// Cradle ERC-20 minted against the collateral
+        address stable_token;
+        /// Cradle price oracle
+        address oracle;
+        /// Oracle feed pricing the collateral in stable units
+        address feed;
+        /// Collateral value to debt ratio vaults must keep, in basis points
+        uint256 min_ratio_bps;
+        /// Extra collateral paid to liquidators, in basis points
+        uint256 liquidation_penalty_bps;
+        /// Stability fee per second, WAD-scaled
+        uint256 fee_per_second;
+        /// Highest total debt across all vaults
+        uint256 debt_ceiling;
+        /// Cumulative stability fee factor, WAD-scaled
+        uint256 rate_index;
+        /// Time at which `rate_index` was last updated
+        uint256 last_accrual;
+        /// Sum of all vault debts divided by the rate index
+        uint256 total_scaled_debt;
+        /// Stable tokens minted by this contract and not yet burned
+        uint256 total_issued;
+        /// Vaults by owner
+        mapping(address => Vault) vaults;
+    }
+
+    pub struct Vault {
+        uint256 collateral;
+        /// Debt divided by the rate index
+        uint256 scaled_debt;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event CollateralDeposited(address indexed vault, uint256 amount);
+    event CollateralWithdrawn(address indexed vault, uint256 amount);
+    event Minted(address indexed vault, address indexed to, uint256 amount);
+    event Repaid(address indexed vault, address indexed from, uint256 amount);
+    event Liquidated(address indexed vault, address indexed liquidator, uint256 repaid, uint256 seized);
+    event FeesCollected(address indexed to, uint256 amount);
+    event ParametersUpdated(uint256 min_ratio_bps, uint256 liquidation_penalty_bps, uint256 fee_per_second, uint256 debt_ceiling);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Amount is zero
+    error InvalidAmount(uint256 amount);
+    // Risk parameters are inconsistent
+    error InvalidParameters(uint256 min_ratio_bps, uint256 liquidation_penalty_bps);
+    // Vault doesn't have that much collateral
+    error InsufficientCollateral(uint256 have, uint256 want);
+    // Action would leave the vault below the minimum collateral ratio
+    error Undercollateralized(address vault, uint256 debt, uint256 max_debt);
+    // Minting would take the total debt above the ceiling
+    error DebtCeilingExceeded(uint256 total_debt, uint256 debt_ceiling);
+    // Vault is above the minimum ratio and can't be liquidated
+    error VaultHealthy(address vault);
+    // The oracle didn't return a price
+    error OracleCallFailed(address feed);
+    // An ERC-20 transfer, mint or burn failed
+    error ExternalCallFailed(address target);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum CdpError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidAmount(InvalidAmount),
+    InvalidParameters(InvalidParameters),
+    InsufficientCollateral(InsufficientCollateral),
+    Undercollateralized(Undercollateralized),
+    DebtCeilingExceeded(DebtCeilingExceeded),
+    VaultHealthy(VaultHealthy),
+    OracleCallFailed(OracleCallFailed),
+    ExternalCallFailed(ExternalCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface ICradleToken {
+        function crosschainMint(address to, uint256 amount) external;
+        function crosschainBurn(address from, uint256 amount) external;
+    }
+
+    interface ICradleOracle {
+        function latestPrice(address feed) external view returns (uint256, uint256);
+    }
+}
+
+// Helpers used by the public methods below.
+impl Cdp {
+    fn only_owner(&self) -> Result<(), CdpError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(CdpError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Rate index as of the current time.
+    fn current_index(&self) -> U256 {
+        let index = self.rate_index.get();
+        let elapsed = self.now() - self.last_accrual.get();
+        index + index * self.fee_per_second.get() * elapsed / U256::from(WAD)
+    }
+
+    /// Brings the rate index up to the current time.
+    fn accrue(&mut self) {
+        let index = self.current_index();
+        self.rate_index.set(index);
+        self.last_accrual.set(self.now());
+    }
+
+    /// Converts scaled debt to stable units, rounding up.
+    fn debt_at(scaled: U256, index: U256) -> U256 {
+        (scaled * index).div_ceil(U256::from(WAD))
+    }
+
+    /// Collateral price in stable units, WAD-scaled, from the oracle.
+    fn price(&self) -> Result<U256, CdpError> {
+        let feed = self.feed.get();
+        let calldata = ICradleOracle::latestPriceCall { feed }.abi_encode();
+        self.vm()
+            .static_call(&self, self.oracle.get(), &calldata)
+            .ok()
+            .and_then(|output| ICradleOracle::latestPriceCall::abi_decode_returns(&output, true).ok())
+            .map(|latest| latest._0)
+            .ok_or(CdpError::OracleCallFailed(OracleCallFailed { feed }))
+    }
+
+    /// Most debt `collateral` can back at `price`.
+    fn max_debt(&self, collateral: U256, price: U256) -> U256 {
+        collateral * price / U256::from(WAD) * U256::from(BPS) / self.min_ratio_bps.get()
+    }
+
+    /// Fails if the vault's debt is above what its collateral can back.
+    fn check_ratio(&self, vault: Address) -> Result<(), CdpError> {
+        let position = self.vaults.getter(vault);
+        let debt = Self::debt_at(position.scaled_debt.get(), self.rate_index.get());
+        if debt.is_zero() {
+            return Ok(());
+        }
+        let max_debt = self.max_debt(position.collateral.get(), self.price()?);
+        if debt > max_debt {
+            return Err(CdpError::Undercollateralized(Undercollateralized { vault, debt, max_debt }));
+        }
+        Ok(())
+    }
+
+    /// Reduces a vault's debt by up to `amount`, returning the amount actually repaid.
+    fn reduce_debt(&mut self, vault: Address, amount: U256) -> U256 {
+        let index = self.rate_index.get();
+        let scaled = self.vaults.getter(vault).scaled_debt.get();
+        let debt = Self::debt_at(scaled, index);
+        let (repaid, scaled_repaid) = if amount >= debt {
+            (debt, scaled)
+        } else {
+            (amount, amount * U256::from(WAD) / index)
+        };
+        self.vaults.setter(vault).scaled_debt.set(scaled - scaled_repaid);
+        self.total_scaled_debt.set(self.total_scaled_debt.get().saturating_sub(scaled_repaid));
+        self.total_issued.set(self.total_issued.get().saturating_sub(repaid));
+        repaid
+    }
+
+    fn call_token(&mut self, token: Address, calldata: &[u8]) -> Result<Vec<u8>, CdpError> {
+        self.vm()
+            .call(&calls::context::Call::new(), token, calldata)
+            .map_err(|_| CdpError::ExternalCallFailed(ExternalCallFailed { target: token }))
+    }
+
+    /// Calls the collateral token, which must return `true`.
+    fn call_collateral(&mut self, calldata: &[u8]) -> Result<(), CdpError> {
+        let token = self.collateral_token.get();
+        match self.call_token(token, calldata) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(CdpError::ExternalCallFailed(ExternalCallFailed { target: token })),
+        }
+    }
+
+    fn burn_stable(&mut self, from: Address, amount: U256) -> Result<(), CdpError> {
+        let token = self.stable_token.get();
+        self.call_token(token, &ICradleToken::crosschainBurnCall { from, amount }.abi_encode())?;
+        Ok(())
+    }
+
+    fn mint_stable(&mut self, to: Address, amount: U256) -> Result<(), CdpError> {
+        let token = self.stable_token.get();
+        self.call_token(token, &ICradleToken::crosschainMintCall { to, amount }.abi_encode())?;
+        self.total_issued.set(self.total_issued.get() + amount);
+        Ok(())
+    }
+
+    fn send_collateral(&mut self, to: Address, amount: U256) -> Result<(), CdpError> {
+        self.call_collateral(&IERC20::transferCall { to, value: amount }.abi_encode())
+    }
+
+    fn write_parameters(
+        &mut self,
+        min_ratio_bps: U256,
+        liquidation_penalty_bps: U256,
+        fee_per_second: U256,
+        debt_ceiling: U256,
+    ) -> Result<(), CdpError> {
+        // Liquidations must leave something for the vault, so the penalty has to fit in the buffer
+        if min_ratio_bps <= U256::from(BPS) || U256::from(BPS) + liquidation_penalty_bps > min_ratio_bps {
+            return Err(CdpError::InvalidParameters(InvalidParameters {
+                min_ratio_bps,
+                liquidation_penalty_bps,
+            }));
+        }
+        self.min_ratio_bps.set(min_ratio_bps);
+        self.liquidation_penalty_bps.set(liquidation_penalty_bps);
+        self.fee_per_second.set(fee_per_second);
+        self.debt_ceiling.set(debt_ceiling);
+
+        log(self.vm(), ParametersUpdated {
+            min_ratio_bps,
+            liquidation_penalty_bps,
+            fee_per_second,
+            debt_ceiling,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl Cdp {
+    /// Sets the tokens, the price feed and the risk parameters. Can only be called once.
+    /// The contract must hold the stable token's bridge role to mint and burn it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        collateral_token: Address,
+        stable_token: Address,
+        oracle: Address,
+        feed: Address,
+        min_ratio_bps: U256,
+        liquidation_penalty_bps: U256,
+        fee_per_second: U256,
+        debt_ceiling: U256,
+    ) -> Result<(), CdpError> {
+        if self.initialized.get() {
+            return Err(CdpError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.collateral_token.set(collateral_token);
+        self.stable_token.set(stable_token);
+        self.oracle.set(oracle);
+        self.feed.set(feed);
+        self.rate_index.set(U256::from(WAD));
+        self.last_accrual.set(self.now());
+        self.write_parameters(min_ratio_bps, liquidation_penalty_bps, fee_per_second, debt_ceiling)?;
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Deposits `amount` collateral from the caller into their vault. The collateral must be approved.
+    pub fn deposit(&mut self, amount: U256) -> Result<(), CdpError> {
+        if amount.is_zero() {
+            return Err(CdpError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let vault = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        self.call_collateral(&IERC20::transferFromCall { from: vault, to: this, value: amount }.abi_encode())?;
+        let collateral = self.vaults.getter(vault).collateral.get();
+        self.vaults.setter(vault).collateral.set(collateral + amount);
+
+        log(self.vm(), CollateralDeposited { vault, amount });
+        Ok(())
+    }
+
+    /// Withdraws `amount` collateral from the caller's vault, as long as it stays above the minimum ratio.
+    pub fn withdraw(&mut self, amount: U256) -> Result<(), CdpError> {
+        if amount.is_zero() {
+            return Err(CdpError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let vault = self.vm().msg_sender();
+        let collateral = self.vaults.getter(vault).collateral.get();
+        if amount > collateral {
+            return Err(CdpError::InsufficientCollateral(InsufficientCollateral {
+                have: collateral,
+                want: amount,
+            }));
+        }
+        self.vaults.setter(vault).collateral.set(collateral - amount);
+        self.check_ratio(vault)?;
+        self.send_collateral(vault, amount)?;
+
+        log(self.vm(), CollateralWithdrawn { vault, amount });
+        Ok(())
+    }
+
+    /// Mints `amount` of the stable token to `to` against the caller's vault.
+    pub fn mint(&mut self, to: Address, amount: U256) -> Result<(), CdpError> {
+        if amount.is_zero() {
+            return Err(CdpError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let index = self.rate_index.get();
+        let total_debt = Self::debt_at(self.total_scaled_debt.get(), index) + amount;
+        let debt_ceiling = self.debt_ceiling.get();
+        if total_debt > debt_ceiling {
+            return Err(CdpError::DebtCeilingExceeded(DebtCeilingExceeded {
+                total_debt,
+                debt_ceiling,
+            }));
+        }
+
+        let vault = self.vm().msg_sender();
+        // Round the new debt up so the vault never owes less than it minted
+        let scaled = (amount * U256::from(WAD)).div_ceil(index);
+        let scaled_debt = self.vaults.getter(vault).scaled_debt.get();
+        self.vaults.setter(vault).scaled_debt.set(scaled_debt + scaled);
+        self.total_scaled_debt.set(self.total_scaled_debt.get() + scaled);
+        self.check_ratio(vault)?;
+        self.mint_stable(to, amount)?;
+
+        log(self.vm(), Minted { vault, to, amount });
+        Ok(())
+    }
+
+    /// Burns up to `amount` of the caller's stable tokens to repay a vault's debt. Anyone may repay
+    /// any vault. Returns the amount repaid.
+    pub fn repay(&mut self, vault: Address, amount: U256) -> Result<U256, CdpError> {
+        if amount.is_zero() {
+            return Err(CdpError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let repaid = self.reduce_debt(vault, amount);
+        let from = self.vm().msg_sender();
+        self.burn_stable(from, repaid)?;
+
+        log(self.vm(), Repaid {
+            vault,
+            from,
+            amount: repaid,
+        });
+        Ok(repaid)
+    }
+
+    /// Burns up to `amount` of the caller's stable tokens to repay an undercollateralized vault and
+    /// seizes collateral worth the repayment plus the liquidation penalty. Returns the collateral seized.
+    pub fn liquidate(&mut self, vault: Address, amount: U256) -> Result<U256, CdpError> {
+        if amount.is_zero() {
+            return Err(CdpError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let position = self.vaults.getter(vault);
+        let debt = Self::debt_at(position.scaled_debt.get(), self.rate_index.get());
+        let collateral = position.collateral.get();
+        let price = self.price()?;
+        if debt.is_zero() || debt <= self.max_debt(collateral, price) {
+            return Err(CdpError::VaultHealthy(VaultHealthy { vault }));
+        }
+
+        let repaid = self.reduce_debt(vault, amount);
+        let penalty = U256::from(BPS) + self.liquidation_penalty_bps.get();
+        let seized = (repaid * U256::from(WAD) / price * penalty / U256::from(BPS)).min(collateral);
+        self.vaults.setter(vault).collateral.set(collateral - seized);
+
+        let liquidator = self.vm().msg_sender();
+        self.burn_stable(liquidator, repaid)?;
+        self.send_collateral(liquidator, seized)?;
+
+        log(self.vm(), Liquidated {
+            vault,
+            liquidator,
+            repaid,
+            seized,
+        });
+        Ok(seized)
+    }
+
+    /// Mints the stability fees accrued so far to `to` (owner only). Returns the amount minted.
+    pub fn collect_fees(&mut self, to: Address) -> Result<U256, CdpError> {
+        self.only_owner()?;
+        self.accrue();
+        let total_debt = Self::debt_at(self.total_scaled_debt.get(), self.rate_index.get());
+        let amount = total_debt.saturating_sub(self.total_issued.get());
+        if !amount.is_zero() {
+            self.mint_stable(to, amount)?;
+        }
+
+        log(self.vm(), FeesCollected { to, amount });
+        Ok(amount)
+    }
+
+    /// Updates the risk parameters and stability fee (owner only). Accrues fees at the old rate first.
+    pub fn set_parameters(
+        &mut self,
+        min_ratio_bps: U256,
+        liquidation_penalty_bps: U256,
+        fee_per_second: U256,
+        debt_ceiling: U256,
+    ) -> Result<(), CdpError> {
+        self.only_owner()?;
+        self.accrue();
+        self.write_parameters(min_ratio_bps, liquidation_penalty_bps, fee_per_second, debt_ceiling)
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), CdpError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(collateral, debt)` of a vault, with fees accrued to the current time.
+    pub fn vault(&self, vault: Address) -> (U256, U256) {
+        let position = self.vaults.getter(vault);
+        (
+            position.collateral.get(),
+            Self::debt_at(position.scaled_debt.get(), self.current_index()),
+        )
+    }
+
+    /// Collateral value over debt in basis points; liquidatable below the minimum ratio.
+    pub fn collateral_ratio_bps(&self, vault: Address) -> Result<U256, CdpError> {
+        let position = self.vaults.getter(vault);
+        let debt = Self::debt_at(position.scaled_debt.get(), self.current_index());
+        if debt.is_zero() {
+            return Ok(U256::MAX);
+        }
+        let value = position.collateral.get() * self.price()? / U256::from(WAD);
+        Ok(value * U256::from(BPS) / debt)
+    }
+
+    /// Collateral price in stable units, WAD-scaled.
+    pub fn collateral_price(&self) -> Result<U256, CdpError> {
+        self.price()
+    }
+
+    /// Returns `(total_debt, total_issued)`; the difference is the fees not yet collected.
+    pub fn totals(&self) -> (U256, U256) {
+        (
+            Self::debt_at(self.total_scaled_debt.get(), self.current_index()),
+            self.total_issued.get(),
+        )
+    }
+
+    /// Returns `(min_ratio_bps, liquidation_penalty_bps, fee_per_second, debt_ceiling)`.
+    pub fn parameters(&self) -> (U256, U256, U256, U256) {
+        (
+            self.min_ratio_bps.get(),
+            self.liquidation_penalty_bps.get(),
+            self.fee_per_second.get(),
+            self.debt_ceiling.get(),
+        )
+    }
+
+    /// Returns `(collateral_token, stable_token, oracle, feed)`.
+    pub fn config(&self) -> (Address, Address, Address, Address) {
+        (
+            self.collateral_token.get(),
+            self.stable_token.get(),
+            self.oracle.get(),
+            self.feed.get(),
+        )
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
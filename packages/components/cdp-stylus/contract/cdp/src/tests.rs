@@ -0,0 +1,136 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const WETH: Address = address!("000000000000000000000000000000000000e770");
+const STABLE: Address = address!("0000000000000000000000000000000000005dc0");
+const ORACLE: Address = address!("000000000000000000000000000000000000047a");
+const FEED: Address = address!("000000000000000000000000000000000000fee0");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+
+/// `tenths` / 10 with 18 decimals.
+fn wad(tenths: u128) -> U256 {
+    U256::from(tenths * WAD / 10)
+}
+
+/// Makes the oracle price the collateral at `price` stable units.
+fn mock_price(vm: &TestVM, price: U256) {
+    let call = ICradleOracle::latestPriceCall { feed: FEED };
+    vm.mock_static_call(ORACLE, call.abi_encode(), Ok((price, n(1_000)).abi_encode_params()));
+}
+
+/// Lets `call` to the collateral token succeed.
+fn expect_collateral<C: SolCall>(vm: &TestVM, call: C) {
+    vm.mock_call(WETH, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets `call` to the stable token succeed.
+fn expect_stable<C: SolCall>(vm: &TestVM, call: C) {
+    vm.mock_call(STABLE, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// Vaults at time 1,000 that keep a 150% ratio, with a 10% liquidation penalty, `fee_per_second`
+/// and a ceiling of 1,500 debt. Collateral is worth 2 stable units, and Alice deposited 1,000 of it
+/// and minted 1,000 stable units.
+fn setup(fee_per_second: U256) -> (TestVM, Cdp) {
+    let (vm, mut contract) = deploy::<Cdp>(OWNER);
+    vm.set_block_timestamp(1_000);
+    mock_price(&vm, wad(20));
+    ok(contract.initialize(OWNER, WETH, STABLE, ORACLE, FEED, n(15_000), n(1_000), fee_per_second, n(1_500)));
+
+    vm.set_sender(ALICE);
+    expect_collateral(&vm, IERC20::transferFromCall { from: ALICE, to: vm.contract_address(), value: n(1_000) });
+    ok(contract.deposit(n(1_000)));
+    expect_stable(&vm, ICradleToken::crosschainMintCall { to: ALICE, amount: n(1_000) });
+    ok(contract.mint(ALICE, n(1_000)));
+    (vm, contract)
+}
+
+#[test]
+fn vaults_mint_against_collateral_and_repay_to_withdraw_it() {
+    let (vm, mut contract) = setup(U256::ZERO);
+    assert_eq!(contract.vault(ALICE), (n(1_000), n(1_000)));
+    assert_eq!(ok(contract.collateral_ratio_bps(ALICE)), n(20_000));
+    assert_eq!(emitted::<Minted>(&vm)[0].amount, n(1_000));
+
+    expect_collateral(&vm, IERC20::transferCall { to: ALICE, value: n(200) });
+    ok(contract.withdraw(n(200)));
+    assert_eq!(emitted::<CollateralWithdrawn>(&vm)[0].amount, n(200));
+
+    // Anyone can repay a vault, and repayments stop at its debt
+    vm.set_sender(BOB);
+    expect_stable(&vm, ICradleToken::crosschainBurnCall { from: BOB, amount: n(400) });
+    assert_eq!(ok(contract.repay(ALICE, n(400))), n(400));
+    expect_stable(&vm, ICradleToken::crosschainBurnCall { from: BOB, amount: n(600) });
+    assert_eq!(ok(contract.repay(ALICE, n(1_000))), n(600));
+    assert_eq!(contract.vault(ALICE), (n(800), U256::ZERO));
+    assert_eq!(contract.totals(), (U256::ZERO, U256::ZERO));
+
+    vm.set_sender(ALICE);
+    expect_collateral(&vm, IERC20::transferCall { to: ALICE, value: n(800) });
+    ok(contract.withdraw(n(800)));
+    assert_eq!(contract.vault(ALICE), (U256::ZERO, U256::ZERO));
+}
+
+#[test]
+fn stability_fees_grow_the_debt_and_are_minted_to_the_owner() {
+    // 0.1% per second
+    let (vm, mut contract) = setup(U256::from(WAD / 1_000));
+    vm.set_block_timestamp(1_100);
+    assert_eq!(contract.vault(ALICE).1, n(1_100));
+    assert_eq!(contract.totals(), (n(1_100), n(1_000)));
+
+    let result = contract.collect_fees(ALICE);
+    assert!(matches!(result, Err(CdpError::Unauthorized(e)) if e.caller == ALICE));
+    vm.set_sender(OWNER);
+    expect_stable(&vm, ICradleToken::crosschainMintCall { to: OWNER, amount: n(100) });
+    assert_eq!(ok(contract.collect_fees(OWNER)), n(100));
+    assert_eq!(contract.totals(), (n(1_100), n(1_100)));
+    assert_eq!(emitted::<FeesCollected>(&vm)[0].amount, n(100));
+}
+
+#[test]
+fn undercollateralized_vaults_are_liquidated_with_a_penalty() {
+    let (vm, mut contract) = setup(U256::ZERO);
+    vm.set_sender(BOB);
+    let result = contract.liquidate(ALICE, n(500));
+    assert!(matches!(result, Err(CdpError::VaultHealthy(e)) if e.vault == ALICE));
+
+    // At 1.2 the collateral backs at most 800 debt
+    mock_price(&vm, wad(12));
+    expect_stable(&vm, ICradleToken::crosschainBurnCall { from: BOB, amount: n(500) });
+    expect_collateral(&vm, IERC20::transferCall { to: BOB, value: n(457) });
+    assert_eq!(ok(contract.liquidate(ALICE, n(500))), n(457));
+    assert_eq!(contract.vault(ALICE), (n(543), n(500)));
+    let liquidated = &emitted::<Liquidated>(&vm)[0];
+    assert_eq!((liquidated.liquidator, liquidated.repaid, liquidated.seized), (BOB, n(500), n(457)));
+}
+
+#[test]
+fn unbacked_mints_and_failed_token_calls_revert() {
+    let (vm, mut contract) = setup(U256::ZERO);
+    let result = contract.mint(ALICE, n(501));
+    assert!(matches!(result, Err(CdpError::DebtCeilingExceeded(e)) if e.total_debt == n(1_501)));
+    let result = contract.withdraw(n(1_001));
+    assert!(matches!(result, Err(CdpError::InsufficientCollateral(e)) if e.have == n(1_000)));
+
+    // Collateral that doesn't move and stable tokens that don't mint fail the call
+    vm.set_sender(BOB);
+    let result = contract.deposit(n(1_000));
+    assert!(matches!(result, Err(CdpError::ExternalCallFailed(e)) if e.target == WETH));
+    expect_collateral(&vm, IERC20::transferFromCall { from: BOB, to: vm.contract_address(), value: n(1_000) });
+    ok(contract.deposit(n(1_000)));
+    let mint = ICradleToken::crosschainMintCall { to: BOB, amount: n(100) };
+    vm.mock_call(STABLE, mint.abi_encode(), Err(b"missing bridge role".to_vec()));
+    let result = contract.mint(BOB, n(100));
+    assert!(matches!(result, Err(CdpError::ExternalCallFailed(e)) if e.target == STABLE));
+
+    // Without a price nothing that needs the ratio goes through
+    vm.mock_static_call(ORACLE, ICradleOracle::latestPriceCall { feed: FEED }.abi_encode(), Err(Vec::new()));
+    vm.set_sender(ALICE);
+    let result = contract.withdraw(n(100));
+    assert!(matches!(result, Err(CdpError::OracleCallFailed(e)) if e.feed == FEED));
+}
@@ -0,0 +1,323 @@
+//! EIP-1167 minimal proxy clone factory
+//!
+//! Deploys 45-byte clones that delegate every call to a registered
+//! implementation, so a per-creator ERC-1155 costs a fraction of a full
+//! deployment. The clone's `initialize` call is made in the same transaction as
+//! the deployment, leaving no window in which someone else can initialize it.
+//!
+//! Deterministic clones use CREATE2 with the creator's address mixed into the
+//! salt, so an address can be predicted off-chain with
+//! `predict_deterministic_address` but only its creator can deploy to it.
+//! `deploy_deterministic` also mixes in the initialization calldata, so the
+//! address `predict_address` returns stands for one configuration of the clone,
+//! and retrying a deployment that already went through returns the clone
+//! instead of failing.
+//!
+//! Every clone is recorded with its implementation, creator and deployment time.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// EIP-1167 creation code before the implementation address.
+const CLONE_PREFIX: [u8; 20] = [
+    0x3d, 0x60, 0x2d, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x81, 0xf3, 0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d,
+    0x73,
+];
+/// EIP-1167 creation code after the implementation address.
+const CLONE_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+sol_storage! {
+    #[entrypoint]
+    pub struct CloneFactory {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Registers implementations
+        address owner;
+        /// Implementations that may be cloned
+        mapping(address => bool) implementations;
+        /// Every clone deployed, in order
+        address[] deployments;
+        /// Deployment record per clone
+        mapping(address => Deployment) clones;
+        /// Clones deployed per creator
+        mapping(address => address[]) creator_deployments;
+    }
+
+    pub struct Deployment {
+        address implementation;
+        address creator;
+        uint256 deployed_at;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event ImplementationUpdated(address indexed implementation, bool allowed);
+    event CloneDeployed(address indexed clone, address indexed implementation, address indexed creator, bytes32 salt);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Implementation is not registered, or has no code when registering it
+    error InvalidImplementation(address implementation);
+    // CREATE or CREATE2 failed, e.g. because the salt was already used
+    error DeploymentFailed(address implementation, bytes32 salt);
+    // The clone's initialization call reverted
+    error InitializationFailed(address clone);
+    // Sending back the ETH of a deployment that was already made failed
+    error RefundFailed(address creator, uint256 value);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum CloneFactoryError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidImplementation(InvalidImplementation),
+    DeploymentFailed(DeploymentFailed),
+    InitializationFailed(InitializationFailed),
+    RefundFailed(RefundFailed),
+}
+
+// Helpers used by the public methods below.
+impl CloneFactory {
+    fn only_owner(&self) -> Result<(), CloneFactoryError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(CloneFactoryError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// EIP-1167 creation code for a clone of `implementation`.
+    fn creation_code(implementation: Address) -> Vec<u8> {
+        let mut code = Vec::with_capacity(CLONE_PREFIX.len() + 20 + CLONE_SUFFIX.len());
+        code.extend_from_slice(&CLONE_PREFIX);
+        code.extend_from_slice(implementation.as_slice());
+        code.extend_from_slice(&CLONE_SUFFIX);
+        code
+    }
+
+    /// CREATE2 salt for a creator's salt, so creators can't take each other's addresses.
+    fn creator_salt(creator: Address, salt: B256) -> B256 {
+        let mut preimage = [0u8; 52];
+        preimage[..20].copy_from_slice(creator.as_slice());
+        preimage[20..].copy_from_slice(salt.as_slice());
+        keccak256(preimage)
+    }
+
+    /// CREATE2 salt of `deploy_deterministic`, binding the clone's address to its
+    /// initialization calldata as well as the creator.
+    fn init_salt(creator: Address, salt: B256, init_data: &[u8]) -> B256 {
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(Self::creator_salt(creator, salt).as_slice());
+        preimage[32..].copy_from_slice(keccak256(init_data).as_slice());
+        keccak256(preimage)
+    }
+
+    /// Address a clone of `implementation` deployed with `create2_salt` gets.
+    fn create2_address(&self, implementation: Address, create2_salt: B256) -> Address {
+        self.vm().contract_address().create2(create2_salt, keccak256(Self::creation_code(implementation)))
+    }
+
+    /// Deploys a clone, records it and forwards the call value with `init_data`.
+    /// `salt` is the creator's salt, and `create2_salt` the one deployed with.
+    fn deploy_clone(
+        &mut self,
+        implementation: Address,
+        salt: Option<B256>,
+        create2_salt: Option<B256>,
+        init_data: Bytes,
+    ) -> Result<Address, CloneFactoryError> {
+        if !self.implementations.get(implementation) {
+            return Err(CloneFactoryError::InvalidImplementation(InvalidImplementation { implementation }));
+        }
+        let creator = self.vm().msg_sender();
+        let code = Self::creation_code(implementation);
+        // The creation code only returns the clone's runtime code; it makes no calls
+        let clone = unsafe { self.vm().deploy(&code, U256::ZERO, create2_salt) }.map_err(|_| {
+            CloneFactoryError::DeploymentFailed(DeploymentFailed {
+                implementation,
+                salt: salt.unwrap_or_default(),
+            })
+        })?;
+
+        let deployed_at = U256::from(self.vm().block_timestamp());
+        let mut record = self.clones.setter(clone);
+        record.implementation.set(implementation);
+        record.creator.set(creator);
+        record.deployed_at.set(deployed_at);
+        self.deployments.push(clone);
+        self.creator_deployments.setter(creator).push(clone);
+
+        let value = self.vm().msg_value();
+        if !init_data.is_empty() || !value.is_zero() {
+            let context = calls::context::Call::new().value(value);
+            self.vm()
+                .call(&context, clone, &init_data)
+                .map_err(|_| CloneFactoryError::InitializationFailed(InitializationFailed { clone }))?;
+        }
+
+        log(self.vm(), CloneDeployed {
+            clone,
+            implementation,
+            creator,
+            salt: salt.unwrap_or_default(),
+        });
+        Ok(clone)
+    }
+}
+
+#[public]
+impl CloneFactory {
+    /// Sets the owner. Can only be called once.
+    pub fn initialize(&mut self, owner: Address) -> Result<(), CloneFactoryError> {
+        if self.initialized.get() {
+            return Err(CloneFactoryError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Allows or disallows cloning an implementation (owner only).
+    pub fn set_implementation(&mut self, implementation: Address, allowed: bool) -> Result<(), CloneFactoryError> {
+        self.only_owner()?;
+        if allowed && self.vm().code_size(implementation) == 0 {
+            return Err(CloneFactoryError::InvalidImplementation(InvalidImplementation { implementation }));
+        }
+        self.implementations.insert(implementation, allowed);
+
+        log(self.vm(), ImplementationUpdated {
+            implementation,
+            allowed,
+        });
+        Ok(())
+    }
+
+    /// Deploys a clone of `implementation` and calls it with `init_data`, forwarding any ETH sent.
+    /// `init_data` is the full calldata, usually an encoded `initialize` call. Returns the clone's address.
+    #[payable]
+    pub fn clone(&mut self, implementation: Address, init_data: Bytes) -> Result<Address, CloneFactoryError> {
+        self.deploy_clone(implementation, None, None, init_data)
+    }
+
+    /// Like `clone`, but deploys with CREATE2 to the address returned by
+    /// `predict_deterministic_address` for the caller and `salt`.
+    #[payable]
+    pub fn clone_deterministic(
+        &mut self,
+        implementation: Address,
+        salt: B256,
+        init_data: Bytes,
+    ) -> Result<Address, CloneFactoryError> {
+        let creator = self.vm().msg_sender();
+        self.deploy_clone(implementation, Some(salt), Some(Self::creator_salt(creator, salt)), init_data)
+    }
+
+    /// Like `clone_deterministic`, but deploys to the address returned by
+    /// `predict_address` for the caller, `salt` and `init_data`. If the clone is
+    /// already there, returns it without calling it and sends back any ETH sent,
+    /// so a retried deployment succeeds.
+    #[payable]
+    pub fn deploy_deterministic(
+        &mut self,
+        implementation: Address,
+        salt: B256,
+        init_data: Bytes,
+    ) -> Result<Address, CloneFactoryError> {
+        let creator = self.vm().msg_sender();
+        let create2_salt = Self::init_salt(creator, salt, &init_data);
+        let clone = self.create2_address(implementation, create2_salt);
+        if self.clones.getter(clone).implementation.get() == implementation {
+            let value = self.vm().msg_value();
+            if !value.is_zero() {
+                self.vm()
+                    .transfer_eth(creator, value)
+                    .map_err(|_| CloneFactoryError::RefundFailed(RefundFailed { creator, value }))?;
+            }
+            return Ok(clone);
+        }
+        self.deploy_clone(implementation, Some(salt), Some(create2_salt), init_data)
+    }
+
+    /// Address `clone_deterministic` deploys to for `creator` and `salt`.
+    pub fn predict_deterministic_address(&self, implementation: Address, creator: Address, salt: B256) -> Address {
+        self.create2_address(implementation, Self::creator_salt(creator, salt))
+    }
+
+    /// Address `deploy_deterministic` deploys to for `creator`, `salt` and `init_data`.
+    pub fn predict_address(&self, implementation: Address, creator: Address, salt: B256, init_data: Bytes) -> Address {
+        self.create2_address(implementation, Self::init_salt(creator, salt, &init_data))
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), CloneFactoryError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Whether `implementation` may be cloned.
+    pub fn is_implementation(&self, implementation: Address) -> bool {
+        self.implementations.get(implementation)
+    }
+
+    /// Whether `account` was deployed by this factory.
+    pub fn is_clone(&self, account: Address) -> bool {
+        !self.clones.getter(account).implementation.get().is_zero()
+    }
+
+    /// Returns `(implementation, creator, deployed_at)` for a clone.
+    pub fn deployment(&self, clone: Address) -> (Address, Address, U256) {
+        let record = self.clones.getter(clone);
+        (record.implementation.get(), record.creator.get(), record.deployed_at.get())
+    }
+
+    /// Clone at `index` in deployment order; the zero address if out of range.
+    pub fn deployment_at(&self, index: U256) -> Address {
+        self.deployments.get(index).unwrap_or_default()
+    }
+
+    /// Clones deployed by `creator`.
+    pub fn deployments_of(&self, creator: Address) -> Vec<Address> {
+        let deployed = self.creator_deployments.getter(creator);
+        (0..deployed.len()).filter_map(|i| deployed.get(i)).collect()
+    }
+
+    /// Number of clones deployed.
+    pub fn total_deployments(&self) -> U256 {
+        U256::from(self.deployments.len())
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+use alloy_primitives::{address, b256};
+use cradle_test_utils::{deploy, emitted, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const IMPLEMENTATION: Address = address!("0000000000000000000000000000000000001155");
+const CLONE: Address = address!("000000000000000000000000000000000000c10e");
+const SALT: B256 = b256!("0000000000000000000000000000000000000000000000000000000000000007");
+
+/// The calldata clones are initialized with.
+fn init_data() -> Bytes {
+    vec![0x81, 0x29, 0xfc, 0x1c].into()
+}
+
+/// A factory at time 1,000 that may clone `IMPLEMENTATION`, called by Alice.
+fn setup() -> (TestVM, CloneFactory) {
+    let (vm, mut contract) = deploy::<CloneFactory>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER));
+    vm.set_code(IMPLEMENTATION, vec![0xfe]);
+    ok(contract.set_implementation(IMPLEMENTATION, true));
+    vm.set_sender(ALICE);
+    (vm, contract)
+}
+
+#[test]
+fn clones_are_deployed_initialized_and_recorded() {
+    let (vm, mut contract) = setup();
+    let code = CloneFactory::creation_code(IMPLEMENTATION);
+    assert_eq!(code.len(), 55);
+    vm.mock_deploy(code, None, Ok(CLONE));
+    vm.mock_call(CLONE, init_data().to_vec(), Ok(Vec::new()));
+    assert_eq!(ok(contract.clone(IMPLEMENTATION, init_data())), CLONE);
+
+    assert!(contract.is_clone(CLONE));
+    assert_eq!(contract.deployment(CLONE), (IMPLEMENTATION, ALICE, U256::from(1_000)));
+    assert_eq!((contract.deployment_at(U256::ZERO), contract.deployments_of(ALICE)), (CLONE, vec![CLONE]));
+    let deployed = &emitted::<CloneDeployed>(&vm)[0];
+    assert_eq!((deployed.clone, deployed.creator, deployed.salt), (CLONE, ALICE, B256::ZERO));
+
+    // Deterministic clones land where the creator's prediction says
+    let predicted = contract.predict_deterministic_address(IMPLEMENTATION, ALICE, SALT);
+    assert_ne!(predicted, contract.predict_deterministic_address(IMPLEMENTATION, BOB, SALT));
+    let create2_salt = CloneFactory::creator_salt(ALICE, SALT);
+    vm.mock_deploy(CloneFactory::creation_code(IMPLEMENTATION), Some(create2_salt), Ok(predicted));
+    assert_eq!(ok(contract.clone_deterministic(IMPLEMENTATION, SALT, Vec::new().into())), predicted);
+    assert_eq!(contract.total_deployments(), U256::from(2));
+}
+
+#[test]
+fn retried_deployments_return_the_clone_and_the_eth() {
+    let (vm, mut contract) = setup();
+    let predicted = contract.predict_address(IMPLEMENTATION, ALICE, SALT, init_data());
+    let create2_salt = CloneFactory::init_salt(ALICE, SALT, &init_data());
+    vm.mock_deploy(CloneFactory::creation_code(IMPLEMENTATION), Some(create2_salt), Ok(predicted));
+    assert_eq!(ok(contract.deploy_deterministic(IMPLEMENTATION, SALT, init_data())), predicted);
+
+    vm.set_value(U256::from(500));
+    vm.set_balance(vm.contract_address(), U256::from(500));
+    assert_eq!(ok(contract.deploy_deterministic(IMPLEMENTATION, SALT, init_data())), predicted);
+    assert_eq!(vm.balance(ALICE), U256::from(500));
+    assert_eq!((contract.total_deployments(), emitted::<CloneDeployed>(&vm).len()), (U256::from(1), 1));
+
+    let result = contract.deploy_deterministic(IMPLEMENTATION, SALT, init_data());
+    assert!(matches!(result, Err(CloneFactoryError::RefundFailed(e)) if e.creator == ALICE));
+}
+
+#[test]
+fn only_registered_implementations_are_cloned() {
+    let (vm, mut contract) = setup();
+    let result = contract.set_implementation(CLONE, true);
+    assert!(matches!(result, Err(CloneFactoryError::Unauthorized(e)) if e.caller == ALICE));
+    assert!(matches!(contract.initialize(ALICE), Err(CloneFactoryError::AlreadyInitialized(_))));
+    vm.set_sender(OWNER);
+    let result = contract.set_implementation(CLONE, true);
+    assert!(matches!(result, Err(CloneFactoryError::InvalidImplementation(e)) if e.implementation == CLONE));
+
+    ok(contract.set_implementation(IMPLEMENTATION, false));
+    let result = contract.clone(IMPLEMENTATION, init_data());
+    assert!(matches!(result, Err(CloneFactoryError::InvalidImplementation(_))));
+    assert!(!contract.is_implementation(IMPLEMENTATION));
+}
+
+#[test]
+fn failed_deployments_and_initializations_revert() {
+    let (vm, mut contract) = setup();
+    let create2_salt = CloneFactory::creator_salt(ALICE, SALT);
+    vm.mock_deploy(CloneFactory::creation_code(IMPLEMENTATION), Some(create2_salt), Err(Vec::new()));
+    let result = contract.clone_deterministic(IMPLEMENTATION, SALT, init_data());
+    assert!(matches!(result, Err(CloneFactoryError::DeploymentFailed(e)) if e.salt == SALT));
+
+    vm.mock_deploy(CloneFactory::creation_code(IMPLEMENTATION), None, Ok(CLONE));
+    vm.mock_call(CLONE, init_data().to_vec(), Err(b"already initialized".to_vec()));
+    let result = contract.clone(IMPLEMENTATION, init_data());
+    assert!(matches!(result, Err(CloneFactoryError::InitializationFailed(e)) if e.clone == CLONE));
+}
@@ -0,0 +1,548 @@
+//! Crowdsale for launching a Cradle ERC-20
+//!
+//! The owner deposits the tokens for sale, configures caps and a schedule, and
+//! contributors send ETH while the sale is open. The sale opens with a whitelist
+//! phase, where only whitelisted accounts may contribute, followed by a public
+//! phase until the end time.
+//!
+//! Two allocation modes are supported:
+//!
+//! - rate: each wei buys a fixed amount of tokens, and contributions stop at the hard cap
+//! - overflow: a fixed amount of tokens is split pro rata between contributors; if
+//!   more than the hard cap is raised, each contributor gets the unused share of
+//!   their ETH back on claim
+//!
+//! Anyone can finalize the sale after the end time (or once the hard cap is hit in
+//! rate mode). If the soft cap was reached, the proceeds go to the beneficiary and
+//! contributors claim their tokens; otherwise contributors take a full refund and
+//! the tokens go back to the owner.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale of the rate.
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// `allocation_mode` of a fixed-rate sale.
+const MODE_RATE: u8 = 0;
+/// `allocation_mode` of an overflow sale.
+const MODE_OVERFLOW: u8 = 1;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Crowdsale {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Configures the sale and the whitelist
+        address owner;
+        /// Token being sold, deposited by the owner
+        address token;
+        /// Receives the proceeds of a successful sale
+        address beneficiary;
+        /// `MODE_RATE` or `MODE_OVERFLOW`
+        uint8 allocation_mode;
+        /// Rate mode: tokens per wei, WAD-scaled. Overflow mode: total tokens for sale
+        uint256 allocation;
+        /// Minimum raise for the sale to succeed
+        uint256 soft_cap;
+        /// Rate mode: maximum raise. Overflow mode: ETH kept from the raise
+        uint256 hard_cap;
+        /// Minimum total contribution per account
+        uint256 min_contribution;
+        /// Maximum total contribution per account; zero means no limit
+        uint256 max_contribution;
+        /// Whitelist phase start
+        uint256 start_time;
+        /// Public phase start
+        uint256 public_start_time;
+        /// Sale end
+        uint256 end_time;
+        /// Accounts allowed to contribute during the whitelist phase
+        mapping(address => bool) whitelist;
+        /// ETH contributed per account
+        mapping(address => uint256) contributions;
+        /// Whether an account has claimed its tokens or refund
+        mapping(address => bool) settled;
+        /// Total ETH contributed
+        uint256 total_raised;
+        /// Set by `finalize`
+        bool finalized;
+        /// Whether the soft cap was reached at finalization
+        bool successful;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Contributed(address indexed contributor, uint256 amount, uint256 total_raised);
+    event Finalized(bool successful, uint256 total_raised, uint256 proceeds);
+    event Claimed(address indexed contributor, uint256 tokens, uint256 refund);
+    event Refunded(address indexed contributor, uint256 amount);
+    event WhitelistUpdated(address indexed account, bool whitelisted);
+    event SaleConfigured(uint256 soft_cap, uint256 hard_cap, uint256 start_time, uint256 end_time);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Allocation mode is unknown or its parameter is zero
+    error InvalidAllocation(uint8 mode, uint256 allocation);
+    // Caps or contribution limits are inconsistent
+    error InvalidCaps(uint256 soft_cap, uint256 hard_cap);
+    // Schedule is not ordered or starts in the past
+    error InvalidSchedule(uint256 start_time, uint256 public_start_time, uint256 end_time);
+    // Sale has already started and can't be reconfigured
+    error SaleStarted();
+    // Sale is not open for contributions
+    error SaleNotOpen();
+    // Caller is not whitelisted during the whitelist phase
+    error NotWhitelisted(address account);
+    // Contribution is outside the per-account limits
+    error ContributionOutOfRange(uint256 contribution, uint256 min, uint256 max);
+    // Contribution would exceed the hard cap
+    error HardCapExceeded(uint256 total_raised, uint256 hard_cap);
+    // Sale holds fewer tokens than it would owe
+    error InsufficientTokens(uint256 balance, uint256 required);
+    // Sale can't be finalized yet
+    error SaleNotEnded();
+    // Sale has already been finalized
+    error AlreadyFinalized();
+    // Sale has not been finalized
+    error NotFinalized();
+    // Claim or refund is not available in the sale's outcome
+    error WrongOutcome(bool successful);
+    // Nothing to claim or refund for the account
+    error NothingToSettle(address account);
+    // An ERC-20 or ETH transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum CrowdsaleError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidAllocation(InvalidAllocation),
+    InvalidCaps(InvalidCaps),
+    InvalidSchedule(InvalidSchedule),
+    SaleStarted(SaleStarted),
+    SaleNotOpen(SaleNotOpen),
+    NotWhitelisted(NotWhitelisted),
+    ContributionOutOfRange(ContributionOutOfRange),
+    HardCapExceeded(HardCapExceeded),
+    InsufficientTokens(InsufficientTokens),
+    SaleNotEnded(SaleNotEnded),
+    AlreadyFinalized(AlreadyFinalized),
+    NotFinalized(NotFinalized),
+    WrongOutcome(WrongOutcome),
+    NothingToSettle(NothingToSettle),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+// Helpers used by the public methods below.
+impl Crowdsale {
+    fn only_owner(&self) -> Result<(), CrowdsaleError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(CrowdsaleError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Configuration may only change before the sale opens.
+    fn only_before_start(&self) -> Result<(), CrowdsaleError> {
+        let start_time = self.start_time.get();
+        if !start_time.is_zero() && self.now() >= start_time {
+            return Err(CrowdsaleError::SaleStarted(SaleStarted {}));
+        }
+        Ok(())
+    }
+
+    fn is_overflow(&self) -> bool {
+        self.allocation_mode.get().to::<u8>() == MODE_OVERFLOW
+    }
+
+    /// Tokens owed to contributors if the sale succeeds with `raised`.
+    fn tokens_owed(&self, raised: U256) -> U256 {
+        if self.is_overflow() {
+            self.allocation.get()
+        } else {
+            raised * self.allocation.get() / U256::from(WAD)
+        }
+    }
+
+    /// ETH kept as proceeds out of `raised`.
+    fn proceeds_for(&self, raised: U256) -> U256 {
+        if self.is_overflow() {
+            raised.min(self.hard_cap.get())
+        } else {
+            raised
+        }
+    }
+
+    /// Tokens and ETH refund owed to `account` after a successful sale.
+    fn allocation_for(&self, account: Address) -> (U256, U256) {
+        let contribution = self.contributions.get(account);
+        let raised = self.total_raised.get();
+        if contribution.is_zero() {
+            return (U256::ZERO, U256::ZERO);
+        }
+        if !self.is_overflow() {
+            return (contribution * self.allocation.get() / U256::from(WAD), U256::ZERO);
+        }
+        let tokens = self.allocation.get() * contribution / raised;
+        let accepted = contribution * self.proceeds_for(raised) / raised;
+        (tokens, contribution - accepted)
+    }
+
+    fn token_balance(&self) -> Result<U256, CrowdsaleError> {
+        let token = self.token.get();
+        let this = self.vm().contract_address();
+        let call = IERC20::balanceOfCall { account: this };
+        self.vm()
+            .static_call(&self, token, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC20::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map(|balance| balance._0)
+            .ok_or(CrowdsaleError::TransferFailed(TransferFailed {
+                token,
+                to: this,
+                amount: U256::ZERO,
+            }))
+    }
+
+    fn transfer_tokens(&mut self, to: Address, amount: U256) -> Result<(), CrowdsaleError> {
+        let token = self.token.get();
+        let call = IERC20::transferCall { to, value: amount };
+        match self.vm().call(&calls::context::Call::new(), token, &call.abi_encode()) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(CrowdsaleError::TransferFailed(TransferFailed { token, to, amount })),
+        }
+    }
+
+    fn send_eth(&mut self, to: Address, amount: U256) -> Result<(), CrowdsaleError> {
+        self.vm()
+            .transfer_eth(to, amount)
+            .map_err(|_| CrowdsaleError::TransferFailed(TransferFailed {
+                token: Address::ZERO,
+                to,
+                amount,
+            }))
+    }
+}
+
+#[public]
+impl Crowdsale {
+    /// Sets the owner, the token, the beneficiary and the allocation mode. Can only be called once.
+    /// `allocation` is the WAD-scaled tokens per wei in rate mode and the total tokens for sale in overflow mode.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        token: Address,
+        beneficiary: Address,
+        allocation_mode: u8,
+        allocation: U256,
+    ) -> Result<(), CrowdsaleError> {
+        if self.initialized.get() {
+            return Err(CrowdsaleError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if (allocation_mode != MODE_RATE && allocation_mode != MODE_OVERFLOW) || allocation.is_zero() {
+            return Err(CrowdsaleError::InvalidAllocation(InvalidAllocation {
+                mode: allocation_mode,
+                allocation,
+            }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.token.set(token);
+        self.beneficiary.set(beneficiary);
+        self.allocation_mode.set(alloy_primitives::Uint::<8, 1>::from(allocation_mode));
+        self.allocation.set(allocation);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Sets the caps, contribution limits and schedule (owner only, before the sale opens).
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure_sale(
+        &mut self,
+        soft_cap: U256,
+        hard_cap: U256,
+        min_contribution: U256,
+        max_contribution: U256,
+        start_time: U256,
+        public_start_time: U256,
+        end_time: U256,
+    ) -> Result<(), CrowdsaleError> {
+        self.only_owner()?;
+        self.only_before_start()?;
+        if hard_cap.is_zero()
+            || soft_cap > hard_cap
+            || (!max_contribution.is_zero() && min_contribution > max_contribution)
+        {
+            return Err(CrowdsaleError::InvalidCaps(InvalidCaps { soft_cap, hard_cap }));
+        }
+        if start_time < self.now() || public_start_time < start_time || end_time <= public_start_time {
+            return Err(CrowdsaleError::InvalidSchedule(InvalidSchedule {
+                start_time,
+                public_start_time,
+                end_time,
+            }));
+        }
+        self.soft_cap.set(soft_cap);
+        self.hard_cap.set(hard_cap);
+        self.min_contribution.set(min_contribution);
+        self.max_contribution.set(max_contribution);
+        self.start_time.set(start_time);
+        self.public_start_time.set(public_start_time);
+        self.end_time.set(end_time);
+
+        log(self.vm(), SaleConfigured {
+            soft_cap,
+            hard_cap,
+            start_time,
+            end_time,
+        });
+        Ok(())
+    }
+
+    /// Adds or removes accounts from the whitelist (owner only).
+    pub fn set_whitelist(&mut self, accounts: Vec<Address>, whitelisted: bool) -> Result<(), CrowdsaleError> {
+        self.only_owner()?;
+        for account in accounts {
+            self.whitelist.insert(account, whitelisted);
+            log(self.vm(), WhitelistUpdated { account, whitelisted });
+        }
+        Ok(())
+    }
+
+    /// Contributes the attached ETH.
+    #[payable]
+    pub fn contribute(&mut self) -> Result<(), CrowdsaleError> {
+        let now = self.now();
+        let start_time = self.start_time.get();
+        if self.finalized.get() || start_time.is_zero() || now < start_time || now >= self.end_time.get() {
+            return Err(CrowdsaleError::SaleNotOpen(SaleNotOpen {}));
+        }
+        let contributor = self.vm().msg_sender();
+        if now < self.public_start_time.get() && !self.whitelist.get(contributor) {
+            return Err(CrowdsaleError::NotWhitelisted(NotWhitelisted { account: contributor }));
+        }
+
+        let amount = self.vm().msg_value();
+        let contribution = self.contributions.get(contributor) + amount;
+        let min = self.min_contribution.get();
+        let max = self.max_contribution.get();
+        if amount.is_zero() || contribution < min || (!max.is_zero() && contribution > max) {
+            return Err(CrowdsaleError::ContributionOutOfRange(ContributionOutOfRange {
+                contribution,
+                min,
+                max,
+            }));
+        }
+        let total_raised = self.total_raised.get() + amount;
+        let hard_cap = self.hard_cap.get();
+        if !self.is_overflow() && total_raised > hard_cap {
+            return Err(CrowdsaleError::HardCapExceeded(HardCapExceeded { total_raised, hard_cap }));
+        }
+        let required = self.tokens_owed(total_raised);
+        let balance = self.token_balance()?;
+        if balance < required {
+            return Err(CrowdsaleError::InsufficientTokens(InsufficientTokens { balance, required }));
+        }
+
+        self.contributions.insert(contributor, contribution);
+        self.total_raised.set(total_raised);
+
+        log(self.vm(), Contributed {
+            contributor,
+            amount,
+            total_raised,
+        });
+        Ok(())
+    }
+
+    /// Closes the sale. Callable by anyone after the end time, or once a rate-mode sale hits its hard cap.
+    /// On success the proceeds go to the beneficiary; unsold tokens, or all of them on failure, go to the owner.
+    pub fn finalize(&mut self) -> Result<(), CrowdsaleError> {
+        if self.finalized.get() {
+            return Err(CrowdsaleError::AlreadyFinalized(AlreadyFinalized {}));
+        }
+        let total_raised = self.total_raised.get();
+        let end_time = self.end_time.get();
+        let cap_reached = !self.is_overflow() && total_raised >= self.hard_cap.get();
+        if end_time.is_zero() || (self.now() < end_time && !cap_reached) {
+            return Err(CrowdsaleError::SaleNotEnded(SaleNotEnded {}));
+        }
+
+        let successful = total_raised >= self.soft_cap.get() && !total_raised.is_zero();
+        self.finalized.set(true);
+        self.successful.set(successful);
+
+        let (proceeds, owed) = if successful {
+            (self.proceeds_for(total_raised), self.tokens_owed(total_raised))
+        } else {
+            (U256::ZERO, U256::ZERO)
+        };
+        let unsold = self.token_balance()?.saturating_sub(owed);
+        if !unsold.is_zero() {
+            let owner = self.owner.get();
+            self.transfer_tokens(owner, unsold)?;
+        }
+        if !proceeds.is_zero() {
+            let beneficiary = self.beneficiary.get();
+            self.send_eth(beneficiary, proceeds)?;
+        }
+
+        log(self.vm(), Finalized {
+            successful,
+            total_raised,
+            proceeds,
+        });
+        Ok(())
+    }
+
+    /// Claims the caller's tokens, and any overflow refund, after a successful sale.
+    pub fn claim(&mut self) -> Result<(), CrowdsaleError> {
+        if !self.finalized.get() {
+            return Err(CrowdsaleError::NotFinalized(NotFinalized {}));
+        }
+        if !self.successful.get() {
+            return Err(CrowdsaleError::WrongOutcome(WrongOutcome { successful: false }));
+        }
+        let contributor = self.vm().msg_sender();
+        let (tokens, refund) = self.allocation_for(contributor);
+        if self.settled.get(contributor) || (tokens.is_zero() && refund.is_zero()) {
+            return Err(CrowdsaleError::NothingToSettle(NothingToSettle { account: contributor }));
+        }
+        self.settled.insert(contributor, true);
+
+        if !tokens.is_zero() {
+            self.transfer_tokens(contributor, tokens)?;
+        }
+        if !refund.is_zero() {
+            self.send_eth(contributor, refund)?;
+        }
+
+        log(self.vm(), Claimed {
+            contributor,
+            tokens,
+            refund,
+        });
+        Ok(())
+    }
+
+    /// Refunds the caller's contribution after a sale that missed its soft cap.
+    pub fn refund(&mut self) -> Result<(), CrowdsaleError> {
+        if !self.finalized.get() {
+            return Err(CrowdsaleError::NotFinalized(NotFinalized {}));
+        }
+        if self.successful.get() {
+            return Err(CrowdsaleError::WrongOutcome(WrongOutcome { successful: true }));
+        }
+        let contributor = self.vm().msg_sender();
+        let amount = self.contributions.get(contributor);
+        if self.settled.get(contributor) || amount.is_zero() {
+            return Err(CrowdsaleError::NothingToSettle(NothingToSettle { account: contributor }));
+        }
+        self.settled.insert(contributor, true);
+        self.send_eth(contributor, amount)?;
+
+        log(self.vm(), Refunded { contributor, amount });
+        Ok(())
+    }
+
+    /// Transfers ownership of the sale (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), CrowdsaleError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// ETH contributed by `account`.
+    pub fn contribution_of(&self, account: Address) -> U256 {
+        self.contributions.get(account)
+    }
+
+    /// Returns `(tokens, refund)` that `account` can claim if the sale succeeds with the current raise.
+    pub fn claimable(&self, account: Address) -> (U256, U256) {
+        if self.settled.get(account) {
+            return (U256::ZERO, U256::ZERO);
+        }
+        self.allocation_for(account)
+    }
+
+    /// Whether `account` may contribute during the whitelist phase.
+    pub fn is_whitelisted(&self, account: Address) -> bool {
+        self.whitelist.get(account)
+    }
+
+    /// Returns `(total_raised, finalized, successful)`.
+    pub fn sale_state(&self) -> (U256, bool, bool) {
+        (self.total_raised.get(), self.finalized.get(), self.successful.get())
+    }
+
+    /// Returns `(token, beneficiary, allocation_mode, allocation)`.
+    pub fn allocation(&self) -> (Address, Address, u8, U256) {
+        (
+            self.token.get(),
+            self.beneficiary.get(),
+            self.allocation_mode.get().to::<u8>(),
+            self.allocation.get(),
+        )
+    }
+
+    /// Returns `(soft_cap, hard_cap, min_contribution, max_contribution)`.
+    pub fn caps(&self) -> (U256, U256, U256, U256) {
+        (
+            self.soft_cap.get(),
+            self.hard_cap.get(),
+            self.min_contribution.get(),
+            self.max_contribution.get(),
+        )
+    }
+
+    /// Returns `(start_time, public_start_time, end_time)`.
+    pub fn schedule(&self) -> (U256, U256, U256) {
+        (self.start_time.get(), self.public_start_time.get(), self.end_time.get())
+    }
+
+    /// Current owner of the sale.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,161 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const BENEFICIARY: Address = address!("000000000000000000000000000000000000be9e");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const TOKEN: Address = address!("00000000000000000000000000000000000020c0");
+
+/// Mocks the sale's balance of `TOKEN`.
+fn mock_tokens(vm: &TestVM, balance: u64) {
+    let call = IERC20::balanceOfCall { account: vm.contract_address() };
+    vm.mock_static_call(TOKEN, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// Lets the sale send exactly `amount` tokens to `to`.
+fn expect_tokens(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC20::transferCall { to, value: n(amount) };
+    vm.mock_call(TOKEN, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// A sale in `mode` at time 1,000 with a soft cap of 1,000 wei, whitelisted from 2,000, public
+/// from 3,000 and ending at 4,000.
+fn setup(mode: u8, allocation: U256, hard_cap: u64, max_contribution: u64) -> (TestVM, Crowdsale) {
+    let (vm, mut contract) = deploy::<Crowdsale>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, TOKEN, BENEFICIARY, mode, allocation));
+    let (start, public_start, end) = (n(2_000), n(3_000), n(4_000));
+    ok(contract.configure_sale(n(1_000), n(hard_cap), n(100), n(max_contribution), start, public_start, end));
+    (vm, contract)
+}
+
+/// Contributes `amount` wei as `contributor`, crediting the sale with it.
+fn contribute(vm: &TestVM, contract: &mut Crowdsale, contributor: Address, amount: u64) -> Result<(), CrowdsaleError> {
+    vm.set_sender(contributor);
+    vm.set_value(n(amount));
+    let result = contract.contribute();
+    if result.is_ok() {
+        vm.set_balance(vm.contract_address(), vm.balance(vm.contract_address()) + n(amount));
+    }
+    result
+}
+
+#[test]
+fn rate_sales_pay_the_beneficiary_and_return_unsold_tokens() {
+    // Two tokens per wei, capped at 5,000 wei
+    let (vm, mut contract) = setup(MODE_RATE, U256::from(2 * WAD), 5_000, 3_000);
+    mock_tokens(&vm, 10_000);
+    ok(contract.set_whitelist(vec![ALICE], true));
+    let result = contribute(&vm, &mut contract, ALICE, 2_000);
+    assert!(matches!(result, Err(CrowdsaleError::SaleNotOpen(_))));
+
+    // Only the whitelist may contribute before the public phase
+    vm.set_block_timestamp(2_000);
+    ok(contribute(&vm, &mut contract, ALICE, 2_000));
+    let result = contribute(&vm, &mut contract, BOB, 1_000);
+    assert!(matches!(result, Err(CrowdsaleError::NotWhitelisted(e)) if e.account == BOB));
+    vm.set_block_timestamp(3_000);
+    ok(contribute(&vm, &mut contract, BOB, 1_000));
+    assert_eq!(contract.sale_state(), (n(3_000), false, false));
+    assert_eq!(contract.claimable(ALICE), (n(4_000), U256::ZERO));
+
+    // 6,000 tokens are owed, the other 4,000 go back to the owner
+    vm.set_block_timestamp(4_000);
+    expect_tokens(&vm, OWNER, 4_000);
+    ok(contract.finalize());
+    assert_eq!(contract.sale_state(), (n(3_000), true, true));
+    assert_eq!(vm.balance(BENEFICIARY), n(3_000));
+    let finalized = &emitted::<Finalized>(&vm)[0];
+    assert_eq!((finalized.successful, finalized.proceeds), (true, n(3_000)));
+
+    expect_tokens(&vm, ALICE, 4_000);
+    vm.set_sender(ALICE);
+    ok(contract.claim());
+    assert!(matches!(contract.claim(), Err(CrowdsaleError::NothingToSettle(e)) if e.account == ALICE));
+    assert!(matches!(contract.refund(), Err(CrowdsaleError::WrongOutcome(e)) if e.successful));
+
+    // A token that doesn't return `true` fails the claim
+    vm.set_sender(BOB);
+    let result = contract.claim();
+    assert!(matches!(
+        result,
+        Err(CrowdsaleError::TransferFailed(e)) if e.token == TOKEN && e.to == BOB && e.amount == n(2_000)
+    ));
+}
+
+#[test]
+fn overflow_sales_refund_what_the_cap_doesnt_keep() {
+    // 1,000 tokens for 2,000 wei, oversubscribed twice over
+    let (vm, mut contract) = setup(MODE_OVERFLOW, n(1_000), 2_000, 0);
+    mock_tokens(&vm, 1_000);
+    vm.set_block_timestamp(3_000);
+    ok(contribute(&vm, &mut contract, ALICE, 3_000));
+    ok(contribute(&vm, &mut contract, BOB, 1_000));
+
+    vm.set_block_timestamp(4_000);
+    ok(contract.finalize());
+    assert_eq!(vm.balance(BENEFICIARY), n(2_000));
+
+    expect_tokens(&vm, ALICE, 750);
+    vm.set_sender(ALICE);
+    ok(contract.claim());
+    expect_tokens(&vm, BOB, 250);
+    vm.set_sender(BOB);
+    ok(contract.claim());
+    assert_eq!((vm.balance(ALICE), vm.balance(BOB)), (n(1_500), n(500)));
+    let claimed = &emitted::<Claimed>(&vm)[1];
+    assert_eq!((claimed.contributor, claimed.tokens, claimed.refund), (BOB, n(250), n(500)));
+    assert_eq!(vm.balance(vm.contract_address()), U256::ZERO);
+}
+
+#[test]
+fn contributions_outside_the_limits_revert() {
+    let (vm, mut contract) = setup(MODE_RATE, U256::from(2 * WAD), 5_000, 3_000);
+    mock_tokens(&vm, 8_000);
+    vm.set_block_timestamp(3_000);
+    let result = contribute(&vm, &mut contract, ALICE, 99);
+    assert!(matches!(result, Err(CrowdsaleError::ContributionOutOfRange(e)) if e.contribution == n(99)));
+    let result = contribute(&vm, &mut contract, ALICE, 3_001);
+    assert!(matches!(result, Err(CrowdsaleError::ContributionOutOfRange(e)) if e.max == n(3_000)));
+    ok(contribute(&vm, &mut contract, ALICE, 3_000));
+
+    // The sale holds tokens for 4,000 wei, and stops at 5,000
+    let result = contribute(&vm, &mut contract, BOB, 1_001);
+    assert!(matches!(
+        result,
+        Err(CrowdsaleError::InsufficientTokens(e)) if e.balance == n(8_000) && e.required == n(8_002)
+    ));
+    mock_tokens(&vm, 20_000);
+    ok(contribute(&vm, &mut contract, BOB, 2_000));
+    let result = contribute(&vm, &mut contract, OWNER, 100);
+    assert!(matches!(result, Err(CrowdsaleError::HardCapExceeded(e)) if e.total_raised == n(5_100)));
+    assert_eq!(contract.sale_state(), (n(5_000), false, false));
+}
+
+#[test]
+fn missing_the_soft_cap_refunds_everyone() {
+    let (vm, mut contract) = setup(MODE_RATE, U256::from(2 * WAD), 5_000, 3_000);
+    mock_tokens(&vm, 10_000);
+    vm.set_block_timestamp(3_000);
+    ok(contribute(&vm, &mut contract, ALICE, 600));
+    assert!(matches!(contract.finalize(), Err(CrowdsaleError::SaleNotEnded(_))));
+    assert!(matches!(contract.refund(), Err(CrowdsaleError::NotFinalized(_))));
+
+    // Every token goes back to the owner, and nothing to the beneficiary
+    vm.set_block_timestamp(4_000);
+    expect_tokens(&vm, OWNER, 10_000);
+    ok(contract.finalize());
+    assert_eq!(contract.sale_state(), (n(600), true, false));
+    assert_eq!(vm.balance(BENEFICIARY), U256::ZERO);
+    assert!(matches!(contract.finalize(), Err(CrowdsaleError::AlreadyFinalized(_))));
+
+    assert!(matches!(contract.claim(), Err(CrowdsaleError::WrongOutcome(e)) if !e.successful));
+    ok(contract.refund());
+    assert_eq!(vm.balance(ALICE), n(600));
+    assert!(matches!(contract.refund(), Err(CrowdsaleError::NothingToSettle(_))));
+    vm.set_sender(BOB);
+    assert!(matches!(contract.refund(), Err(CrowdsaleError::NothingToSettle(e)) if e.account == BOB));
+}
@@ -0,0 +1,169 @@
+//! Batch sender
+//!
+//! Sends ETH, an ERC-20 or an ERC-1155 token ID from the caller to many
+//! recipients in one transaction, for community payouts and airdrops.
+//!
+//! Each payment is packed into a single 32-byte word instead of an address and
+//! an amount in separate arrays, which halves the calldata:
+//!
+//! `recipient (20 bytes) | amount (12 bytes)`
+//!
+//! Amounts are therefore limited to 96 bits, about 79 billion tokens with 18
+//! decimals. Use `pack` to build the words.
+//!
+//! The contract holds nothing between calls. Tokens move straight from the
+//! caller to the recipients, and any ETH not paid out is returned.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Bits of a packed word holding the amount.
+const AMOUNT_BITS: usize = 96;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Disperse {}
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Dispersed(address indexed sender, address indexed token, uint256 id, uint256 recipients, uint256 total);
+
+    // ETH sent is less than the payments add up to
+    error InsufficientValue(uint256 total, uint256 msg_value);
+    // Amount doesn't fit in 96 bits
+    error AmountTooLarge(uint256 amount);
+    // Sending to a recipient failed
+    error TransferFailed(address recipient);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum DisperseError {
+    InsufficientValue(InsufficientValue),
+    AmountTooLarge(AmountTooLarge),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+/// Splits a packed word into its recipient and amount.
+pub fn unpack(payment: B256) -> (Address, U256) {
+    let recipient = Address::from_slice(&payment[..20]);
+    let amount = U256::from_be_slice(&payment[20..]);
+    (recipient, amount)
+}
+
+#[public]
+impl Disperse {
+    /// Sends ETH to each packed payment. The ETH sent must cover the total; the rest is returned.
+    #[payable]
+    pub fn disperse_ether(&mut self, payments: Vec<B256>) -> Result<(), DisperseError> {
+        let sender = self.vm().msg_sender();
+        let msg_value = self.vm().msg_value();
+        let total = payments.iter().map(|&payment| unpack(payment).1).fold(U256::ZERO, |sum, amount| sum + amount);
+        if total > msg_value {
+            return Err(DisperseError::InsufficientValue(InsufficientValue { total, msg_value }));
+        }
+
+        for payment in &payments {
+            let (recipient, amount) = unpack(*payment);
+            self.vm()
+                .transfer_eth(recipient, amount)
+                .map_err(|_| DisperseError::TransferFailed(TransferFailed { recipient }))?;
+        }
+        if msg_value > total {
+            self.vm()
+                .transfer_eth(sender, msg_value - total)
+                .map_err(|_| DisperseError::TransferFailed(TransferFailed { recipient: sender }))?;
+        }
+
+        log(self.vm(), Dispersed {
+            sender,
+            token: Address::ZERO,
+            id: U256::ZERO,
+            recipients: U256::from(payments.len()),
+            total,
+        });
+        Ok(())
+    }
+
+    /// Sends an ERC-20 from the caller to each packed payment. The caller must approve the total.
+    pub fn disperse_token(&mut self, token: Address, payments: Vec<B256>) -> Result<(), DisperseError> {
+        let sender = self.vm().msg_sender();
+        let mut total = U256::ZERO;
+        for payment in &payments {
+            let (recipient, amount) = unpack(*payment);
+            let calldata = IERC20::transferFromCall { from: sender, to: recipient, value: amount }.abi_encode();
+            match self.vm().call(&calls::context::Call::new(), token, &calldata) {
+                Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => {}
+                _ => return Err(DisperseError::TransferFailed(TransferFailed { recipient })),
+            }
+            total += amount;
+        }
+
+        log(self.vm(), Dispersed {
+            sender,
+            token,
+            id: U256::ZERO,
+            recipients: U256::from(payments.len()),
+            total,
+        });
+        Ok(())
+    }
+
+    /// Sends one ERC-1155 token ID from the caller to each packed payment. The caller must approve
+    /// this contract with `setApprovalForAll`.
+    pub fn disperse_erc1155(&mut self, token: Address, id: U256, payments: Vec<B256>) -> Result<(), DisperseError> {
+        let sender = self.vm().msg_sender();
+        let mut total = U256::ZERO;
+        for payment in &payments {
+            let (recipient, amount) = unpack(*payment);
+            let data = Vec::new().into();
+            let calldata = IERC1155::safeTransferFromCall { from: sender, to: recipient, id, value: amount, data };
+            self.vm()
+                .call(&calls::context::Call::new(), token, &calldata.abi_encode())
+                .map_err(|_| DisperseError::TransferFailed(TransferFailed { recipient }))?;
+            total += amount;
+        }
+
+        log(self.vm(), Dispersed {
+            sender,
+            token,
+            id,
+            recipients: U256::from(payments.len()),
+            total,
+        });
+        Ok(())
+    }
+
+    /// Packs a recipient and an amount into a payment word. Fails if the amount doesn't fit in 96 bits.
+    pub fn pack(&self, recipient: Address, amount: U256) -> Result<B256, DisperseError> {
+        if amount.bit_len() > AMOUNT_BITS {
+            return Err(DisperseError::AmountTooLarge(AmountTooLarge { amount }));
+        }
+        let mut payment = B256::from(amount);
+        payment[..20].copy_from_slice(recipient.as_slice());
+        Ok(payment)
+    }
+}
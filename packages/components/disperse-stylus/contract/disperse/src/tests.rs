@@ -0,0 +1,91 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const CAROL: Address = address!("000000000000000000000000000000000000ca01");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const BADGES: Address = address!("0000000000000000000000000000000000001155");
+
+/// Payments of 300 to Bob and 200 to Carol.
+fn payments(contract: &Disperse) -> Vec<B256> {
+    vec![ok(contract.pack(BOB, n(300))), ok(contract.pack(CAROL, n(200)))]
+}
+
+/// Lets `amount` USDC move from Alice to `to`.
+fn expect_usdc(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from: ALICE, to, value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets `amount` badges of ID 3 move from Alice to `to`.
+fn expect_badges(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC1155::safeTransferFromCall { from: ALICE, to, id: n(3), value: n(amount), data: Vec::new().into() };
+    vm.mock_call(BADGES, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// A disperser called by Alice.
+fn setup() -> (TestVM, Disperse) {
+    let (vm, contract) = deploy::<Disperse>(ALICE);
+    (vm, contract)
+}
+
+#[test]
+fn ether_is_paid_out_and_the_rest_returned() {
+    let (vm, mut contract) = setup();
+    vm.set_value(n(600));
+    vm.set_balance(vm.contract_address(), n(600));
+    ok(contract.disperse_ether(payments(&contract)));
+    assert_eq!((vm.balance(BOB), vm.balance(CAROL), vm.balance(ALICE)), (n(300), n(200), n(100)));
+    let dispersed = &emitted::<Dispersed>(&vm)[0];
+    assert_eq!((dispersed.token, dispersed.recipients, dispersed.total), (Address::ZERO, n(2), n(500)));
+
+    vm.set_value(n(499));
+    let result = contract.disperse_ether(payments(&contract));
+    assert!(matches!(result, Err(DisperseError::InsufficientValue(e)) if e.total == n(500)));
+}
+
+#[test]
+fn tokens_move_from_the_sender_to_each_recipient() {
+    let (vm, mut contract) = setup();
+    expect_usdc(&vm, BOB, 300);
+    expect_usdc(&vm, CAROL, 200);
+    ok(contract.disperse_token(USDC, payments(&contract)));
+    expect_badges(&vm, BOB, 300);
+    expect_badges(&vm, CAROL, 200);
+    ok(contract.disperse_erc1155(BADGES, n(3), payments(&contract)));
+
+    let dispersed = emitted::<Dispersed>(&vm);
+    assert_eq!((dispersed[0].token, dispersed[0].total), (USDC, n(500)));
+    assert_eq!((dispersed[1].token, dispersed[1].id, dispersed[1].total), (BADGES, n(3), n(500)));
+}
+
+#[test]
+fn payments_pack_into_one_word() {
+    let (_, contract) = setup();
+    let max = U256::MAX >> (256 - AMOUNT_BITS);
+    assert_eq!(unpack(ok(contract.pack(BOB, max))), (BOB, max));
+    let result = contract.pack(BOB, max + n(1));
+    assert!(matches!(result, Err(DisperseError::AmountTooLarge(e)) if e.amount == max + n(1)));
+}
+
+#[test]
+fn failed_payments_revert_with_their_recipient() {
+    let (vm, mut contract) = setup();
+    expect_usdc(&vm, BOB, 300);
+    let result = contract.disperse_token(USDC, payments(&contract));
+    assert!(matches!(result, Err(DisperseError::TransferFailed(e)) if e.recipient == CAROL));
+
+    let call =
+        IERC1155::safeTransferFromCall { from: ALICE, to: BOB, id: n(3), value: n(300), data: Vec::new().into() };
+    vm.mock_call(BADGES, call.abi_encode(), Err(b"not approved".to_vec()));
+    let result = contract.disperse_erc1155(BADGES, n(3), payments(&contract));
+    assert!(matches!(result, Err(DisperseError::TransferFailed(e)) if e.recipient == BOB));
+
+    // ETH the disperser doesn't hold can't be sent
+    vm.set_value(n(500));
+    let result = contract.disperse_ether(payments(&contract));
+    assert!(matches!(result, Err(DisperseError::TransferFailed(e)) if e.recipient == BOB));
+}
@@ -0,0 +1,279 @@
+//! Dividend distributor for ERC-1155 holders
+//!
+//! The owner deposits ETH or an ERC-20 against a token ID of an ERC-1155 contract, such as a
+//! revenue-sharing membership pass, and the holders of that ID claim it pro-rata to their
+//! balances. Deposits are split through a cumulative `dividends_per_share` value per ID and
+//! asset, so a deposit costs the same however many holders there are and holders claim whenever
+//! they like, alongside other deposits.
+//!
+//! The ERC-1155 contract doesn't report transfers to the distributor, so it keeps a snapshot of
+//! each holder's balance, their shares, which `checkpoint` refreshes from the token. A deposit is
+//! split over the shares at the time of the deposit: a holder who buys in afterwards doesn't earn
+//! it, and one who sells afterwards keeps it. Anyone can checkpoint any holder, so whoever funds
+//! the distributor should checkpoint the holders that moved since the last deposit first, or the
+//! sellers' stale shares keep diluting the buyers'.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale of `dividends_per_share`.
+const PRECISION: u128 = 1_000_000_000_000_000_000;
+/// Most assets deposited against one token ID, which bounds the cost of settling an account.
+pub const MAX_ASSETS: usize = 16;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Dividends {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Deposits dividends
+        #[borrow]
+        Ownable ownable;
+        /// ERC-1155 contract whose holders earn the dividends
+        address token;
+
+        /// Shares of each account in each token ID, its balance when last checkpointed
+        mapping(uint256 => mapping(address => uint256)) shares;
+        /// Sum of the shares in each token ID
+        mapping(uint256 => uint256) total_shares;
+        /// Assets deposited against each token ID, the zero address for ETH
+        mapping(uint256 => address[]) assets;
+        /// Dividends deposited per share by token ID and asset, scaled by `PRECISION`
+        mapping(uint256 => mapping(address => uint256)) dividends_per_share;
+        /// `dividends_per_share` when each account was last settled, by token ID, asset and account
+        mapping(uint256 => mapping(address => mapping(address => uint256))) paid_per_share;
+        /// Dividends settled but not yet claimed, by token ID, asset and account
+        mapping(uint256 => mapping(address => mapping(address => uint256))) owed;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event SharesUpdated(address indexed account, uint256 indexed id, uint256 shares);
+    event DividendDeposited(uint256 indexed id, address indexed asset, uint256 amount, uint256 totalShares);
+    event DividendClaimed(address indexed account, uint256 indexed id, address indexed asset, uint256 amount);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Depositing or claiming nothing
+    error ZeroAmount();
+    // Nobody holds checkpointed shares of the ID to split a deposit over
+    error NoShares(uint256 id);
+    // The ID already has `MAX_ASSETS` assets
+    error TooManyAssets(uint256 id);
+    // Attached ETH does not match the deposit
+    error IncorrectPayment(uint256 expected, uint256 received);
+    // Reading a balance from the ERC-1155 contract failed
+    error BalanceQueryFailed(address token, address account, uint256 id);
+    // Moving a deposit in or a claim out failed
+    error TransferFailed(address asset, address to, uint256 amount);
+
+    function balanceOf(address account, uint256 id) returns (uint256);
+    function transfer(address to, uint256 value) returns (bool);
+    function transferFrom(address from, address to, uint256 value) returns (bool);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum DividendsError {
+    AlreadyInitialized(AlreadyInitialized),
+    ZeroAmount(ZeroAmount),
+    NoShares(NoShares),
+    TooManyAssets(TooManyAssets),
+    IncorrectPayment(IncorrectPayment),
+    BalanceQueryFailed(BalanceQueryFailed),
+    TransferFailed(TransferFailed),
+    OwnableUnauthorizedAccount(OwnableUnauthorizedAccount),
+    OwnableInvalidOwner(OwnableInvalidOwner),
+}
+
+impl From<OwnableError> for DividendsError {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => DividendsError::OwnableUnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => DividendsError::OwnableInvalidOwner(e),
+        }
+    }
+}
+
+#[public]
+#[inherit(Ownable)]
+impl Dividends {
+    /// Sets the owner and the ERC-1155 contract. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, token: Address) -> Result<(), DividendsError> {
+        if self.initialized.get() {
+            return Err(DividendsError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.ownable.initialize(owner)?;
+        self.initialized.set(true);
+        self.token.set(token);
+        Ok(())
+    }
+
+    /// Splits `amount` of `asset` over the current shares of `id` (owner only). Pass the zero
+    /// address for ETH and attach `amount`; ERC-20 deposits are pulled from the caller and need
+    /// an allowance.
+    #[payable]
+    pub fn deposit(&mut self, id: U256, asset: Address, amount: U256) -> Result<(), DividendsError> {
+        self.ownable.require_owner()?;
+        if amount.is_zero() {
+            return Err(DividendsError::ZeroAmount(ZeroAmount {}));
+        }
+        let expected = if asset.is_zero() { amount } else { U256::ZERO };
+        let received = self.vm().msg_value();
+        if received != expected {
+            return Err(DividendsError::IncorrectPayment(IncorrectPayment { expected, received }));
+        }
+        let total_shares = self.total_shares.get(id);
+        if total_shares.is_zero() {
+            return Err(DividendsError::NoShares(NoShares { id }));
+        }
+        self.add_asset(id, asset)?;
+
+        let dividends_per_share = self.dividends_per_share.getter(id).get(asset);
+        let per_share = amount * U256::from(PRECISION) / total_shares;
+        self.dividends_per_share.setter(id).insert(asset, dividends_per_share + per_share);
+
+        if !asset.is_zero() {
+            let from = self.vm().msg_sender();
+            let to = self.vm().contract_address();
+            if !self.call_token(asset, &transferFromCall { from, to, value: amount }.abi_encode()) {
+                return Err(DividendsError::TransferFailed(TransferFailed { asset, to, amount }));
+            }
+        }
+
+        log(self.vm(), DividendDeposited { id, asset, amount, totalShares: total_shares });
+        Ok(())
+    }
+
+    /// Sets `account`'s shares of `id` to its current balance, after settling what its old
+    /// shares earned. Anyone can checkpoint any account.
+    pub fn checkpoint(&mut self, account: Address, id: U256) -> Result<U256, DividendsError> {
+        let token = self.token.get();
+        let query = balanceOfCall { account, id };
+        let failed = || DividendsError::BalanceQueryFailed(BalanceQueryFailed { token, account, id });
+        let result =
+            self.vm().static_call(&calls::context::Call::new(), token, &query.abi_encode()).map_err(|_| failed())?;
+        let balance = U256::abi_decode(&result, true).map_err(|_| failed())?;
+
+        self.settle(account, id);
+        let shares = self.shares.getter(id).get(account);
+        if shares != balance {
+            let total_shares = self.total_shares.get(id);
+            self.total_shares.insert(id, total_shares - shares + balance);
+            self.shares.setter(id).insert(account, balance);
+            log(self.vm(), SharesUpdated { account, id, shares: balance });
+        }
+        Ok(balance)
+    }
+
+    /// Pays the caller its dividends of `asset` for holding `id`, returning the amount.
+    pub fn claim(&mut self, id: U256, asset: Address) -> Result<U256, DividendsError> {
+        let account = self.vm().msg_sender();
+        self.settle(account, id);
+        let amount = self.owed.getter(id).getter(asset).get(account);
+        if amount.is_zero() {
+            return Err(DividendsError::ZeroAmount(ZeroAmount {}));
+        }
+        self.owed.setter(id).setter(asset).insert(account, U256::ZERO);
+
+        let paid = if asset.is_zero() {
+            self.vm().transfer_eth(account, amount).is_ok()
+        } else {
+            self.call_token(asset, &transferCall { to: account, value: amount }.abi_encode())
+        };
+        if !paid {
+            return Err(DividendsError::TransferFailed(TransferFailed { asset, to: account, amount }));
+        }
+
+        log(self.vm(), DividendClaimed { account, id, asset, amount });
+        Ok(amount)
+    }
+
+    /// Dividends of `asset` `account` can claim for holding `id`.
+    pub fn claimable(&self, account: Address, id: U256, asset: Address) -> U256 {
+        self.owed.getter(id).getter(asset).get(account) + self.unsettled(account, id, asset)
+    }
+
+    /// `account`'s checkpointed shares of `id`.
+    pub fn shares_of(&self, account: Address, id: U256) -> U256 {
+        self.shares.getter(id).get(account)
+    }
+
+    /// Sum of the checkpointed shares of `id`.
+    pub fn total_shares(&self, id: U256) -> U256 {
+        self.total_shares.get(id)
+    }
+
+    /// Assets deposited against `id`, the zero address for ETH.
+    pub fn assets_of(&self, id: U256) -> Vec<Address> {
+        let assets = self.assets.getter(id);
+        (0..assets.len()).filter_map(|i| assets.get(i)).collect()
+    }
+
+    /// Dividends of `asset` deposited per share of `id`, scaled by 1e18.
+    pub fn dividends_per_share(&self, id: U256, asset: Address) -> U256 {
+        self.dividends_per_share.getter(id).get(asset)
+    }
+
+    /// ERC-1155 contract whose holders earn the dividends.
+    pub fn token(&self) -> Address {
+        self.token.get()
+    }
+}
+
+// Helpers used by the public methods above.
+impl Dividends {
+    /// Dividends of `asset` deposited against `id` since `account` was last settled.
+    fn unsettled(&self, account: Address, id: U256, asset: Address) -> U256 {
+        let paid = self.paid_per_share.getter(id).getter(asset).get(account);
+        let accrued = self.dividends_per_share.getter(id).get(asset) - paid;
+        self.shares.getter(id).get(account) * accrued / U256::from(PRECISION)
+    }
+
+    /// Moves what `account`'s shares of `id` earned since it was last settled into `owed`, for
+    /// every asset, before its shares change or it claims.
+    fn settle(&mut self, account: Address, id: U256) {
+        for asset in self.assets_of(id) {
+            let earned = self.unsettled(account, id, asset);
+            let dividends_per_share = self.dividends_per_share.getter(id).get(asset);
+            self.paid_per_share.setter(id).setter(asset).insert(account, dividends_per_share);
+            if !earned.is_zero() {
+                let owed = self.owed.getter(id).getter(asset).get(account);
+                self.owed.setter(id).setter(asset).insert(account, owed + earned);
+            }
+        }
+    }
+
+    /// Records `asset` as deposited against `id` the first time it is.
+    fn add_asset(&mut self, id: U256, asset: Address) -> Result<(), DividendsError> {
+        let assets = self.assets_of(id);
+        if assets.contains(&asset) {
+            return Ok(());
+        }
+        if assets.len() == MAX_ASSETS {
+            return Err(DividendsError::TooManyAssets(TooManyAssets { id }));
+        }
+        self.assets.setter(id).push(asset);
+        Ok(())
+    }
+
+    /// Calls an ERC-20, requiring it to return `true`.
+    fn call_token(&mut self, asset: Address, calldata: &[u8]) -> bool {
+        self.vm()
+            .call(&calls::context::Call::new(), asset, calldata)
+            .is_ok_and(|result| bool::abi_decode(&result, true).unwrap_or(false))
+    }
+}
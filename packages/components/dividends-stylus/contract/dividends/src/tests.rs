@@ -0,0 +1,118 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const PASS: Address = address!("0000000000000000000000000000000000001155");
+const USDC: Address = address!("0000000000000000000000000000000000000020");
+const ID: U256 = U256::from_limbs([7, 0, 0, 0]);
+
+/// Makes `PASS` report `balance` of `ID` for `account`, and checkpoints it.
+fn hold(vm: &TestVM, contract: &mut Dividends, account: Address, balance: u64) {
+    let query = balanceOfCall { account, id: ID };
+    vm.mock_static_call(PASS, query.abi_encode(), Ok(n(balance).abi_encode()));
+    assert_eq!(ok(contract.checkpoint(account, ID)), n(balance));
+}
+
+/// Deposits `amount` of `USDC` against `ID` as `OWNER`.
+fn deposit(vm: &TestVM, contract: &mut Dividends, amount: u64) {
+    vm.set_sender(OWNER);
+    let pull = transferFromCall { from: OWNER, to: vm.contract_address(), value: n(amount) };
+    vm.mock_call(USDC, pull.abi_encode(), Ok(true.abi_encode()));
+    ok(contract.deposit(ID, USDC, n(amount)));
+}
+
+/// A distributor for `PASS` initialized by `OWNER`.
+fn setup() -> (TestVM, Dividends) {
+    let (vm, mut contract) = deploy::<Dividends>(OWNER);
+    ok(contract.initialize(OWNER, PASS));
+    (vm, contract)
+}
+
+#[test]
+fn deposits_split_pro_rata_over_the_shares() {
+    let (vm, mut contract) = setup();
+    hold(&vm, &mut contract, ALICE, 30);
+    hold(&vm, &mut contract, BOB, 10);
+    assert_eq!(contract.total_shares(ID), n(40));
+
+    deposit(&vm, &mut contract, 400);
+    assert_eq!(contract.assets_of(ID), vec![USDC]);
+    assert_eq!(contract.claimable(ALICE, ID, USDC), n(300));
+    assert_eq!(contract.claimable(BOB, ID, USDC), n(100));
+    let deposited = emitted::<DividendDeposited>(&vm);
+    assert_eq!((deposited[0].amount, deposited[0].totalShares), (n(400), n(40)));
+
+    vm.set_sender(ALICE);
+    vm.mock_call(USDC, transferCall { to: ALICE, value: n(300) }.abi_encode(), Ok(true.abi_encode()));
+    assert_eq!(ok(contract.claim(ID, USDC)), n(300));
+    assert_eq!(contract.claimable(ALICE, ID, USDC), U256::ZERO);
+    assert!(matches!(contract.claim(ID, USDC), Err(DividendsError::ZeroAmount(_))));
+    assert_eq!(emitted::<DividendClaimed>(&vm)[0].amount, n(300));
+}
+
+#[test]
+fn shares_only_earn_deposits_made_while_they_are_held() {
+    let (vm, mut contract) = setup();
+    hold(&vm, &mut contract, ALICE, 10);
+    deposit(&vm, &mut contract, 100);
+
+    // Bob buys in after the first deposit and Alice sells out after the second
+    hold(&vm, &mut contract, BOB, 10);
+    assert_eq!(contract.claimable(BOB, ID, USDC), U256::ZERO);
+    deposit(&vm, &mut contract, 100);
+    hold(&vm, &mut contract, ALICE, 0);
+    deposit(&vm, &mut contract, 100);
+
+    assert_eq!(contract.claimable(ALICE, ID, USDC), n(150));
+    assert_eq!(contract.claimable(BOB, ID, USDC), n(150));
+    assert_eq!((contract.shares_of(ALICE, ID), contract.total_shares(ID)), (U256::ZERO, n(10)));
+    assert_eq!(emitted::<SharesUpdated>(&vm).len(), 3);
+}
+
+#[test]
+fn deposits_need_the_owner_shares_and_the_right_payment() {
+    let (vm, mut contract) = setup();
+    let result = contract.deposit(ID, USDC, n(100));
+    assert!(matches!(result, Err(DividendsError::NoShares(e)) if e.id == ID));
+    hold(&vm, &mut contract, ALICE, 10);
+
+    vm.set_sender(ALICE);
+    let result = contract.deposit(ID, USDC, n(100));
+    assert!(matches!(result, Err(DividendsError::OwnableUnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    vm.set_value(n(4));
+    let result = contract.deposit(ID, Address::ZERO, n(5));
+    assert!(matches!(result, Err(DividendsError::IncorrectPayment(e)) if e.expected == n(5) && e.received == n(4)));
+    vm.set_value(n(5));
+    ok(contract.deposit(ID, Address::ZERO, n(5)));
+    assert_eq!(contract.claimable(ALICE, ID, Address::ZERO), n(5));
+
+    let result = contract.deposit(ID, USDC, n(100));
+    assert!(matches!(result, Err(DividendsError::IncorrectPayment(e)) if e.expected.is_zero()));
+    vm.set_value(U256::ZERO);
+    let pull = transferFromCall { from: OWNER, to: vm.contract_address(), value: n(100) };
+    vm.mock_call(USDC, pull.abi_encode(), Ok(false.abi_encode()));
+    let result = contract.deposit(ID, USDC, n(100));
+    assert!(matches!(result, Err(DividendsError::TransferFailed(e)) if e.asset == USDC));
+}
+
+#[test]
+fn each_id_holds_a_bounded_number_of_assets() {
+    let (vm, mut contract) = setup();
+    hold(&vm, &mut contract, ALICE, 1);
+    vm.set_sender(OWNER);
+    for i in 1..=MAX_ASSETS as u64 {
+        let asset = Address::from_word(n(i).into());
+        let pull = transferFromCall { from: OWNER, to: vm.contract_address(), value: n(1) };
+        vm.mock_call(asset, pull.abi_encode(), Ok(true.abi_encode()));
+        ok(contract.deposit(ID, asset, n(1)));
+    }
+    assert_eq!(contract.assets_of(ID).len(), MAX_ASSETS);
+    let result = contract.deposit(ID, USDC, n(1));
+    assert!(matches!(result, Err(DividendsError::TooManyAssets(e)) if e.id == ID));
+}
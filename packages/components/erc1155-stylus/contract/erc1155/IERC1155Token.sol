@@ -0,0 +1,127 @@
+/**
+ * Generated from the erc1155-stylus contract, do not edit. To regenerate, run in contract/erc1155:
+ *   cargo run --features export-abi -- interface > IERC1155Token.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface IERC1155Token {
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event ApprovalForAllExpiry(address indexed account, address indexed operator, uint256 expires);
+    event CrosschainMint(address indexed to, uint256 id, uint256 amount, address indexed sender);
+    event CrosschainBurn(address indexed from, uint256 id, uint256 amount, address indexed sender);
+    event GuardianExpirySet(uint256 expiry);
+    event BatchLogChunkSet(uint256 entries);
+    event PauseRegistrySet(address registry);
+    event ExistenceChanged(uint256 indexed id, bool exists);
+    event RetireBurnedOutSet(bool retire);
+    event MaxBatchLengthSet(uint256 entries);
+    event ReceiverHookCalled(address indexed receiver, bytes returnData, uint256 gasUsed);
+    event MetadataFrozen(string uri);
+    event SupplyFrozen(uint256 indexed id, uint256 totalSupply);
+    event URI(string value, uint256 indexed id);
+    event SeriesUriSet(uint256 indexed startId, uint256 indexed endId, string base);
+    event MintedWithReceipt(bytes32 indexed orderId, address indexed to, uint256 id, uint256 amount, address indexed processor);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event Paused(address account);
+    event Unpaused(address account);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole);
+
+    error AlreadyInitialized();
+    error UnauthorizedInitializer(address caller, address deployer);
+    error GuardianExpired(uint256 expiry);
+    error ERC1155InvalidApprovalExpiry(uint256 expires);
+    error ERC1155IdRetired(uint256 id);
+    error ERC1155BatchTooLong(uint256 length, uint256 maxLength);
+    error ERC1155ReceiverHookFailed(address receiver, bytes returnData, uint256 gasUsed);
+    error ERC1155MetadataIsFrozen();
+    error ERC1155SupplyIsFrozen(uint256 id);
+    error ERC1155InvalidSeries(uint256 startId, uint256 endId);
+    error ERC1155TooManySeries(uint256 maxSeries);
+    error ERC1155OrderAlreadyFulfilled(bytes32 orderId);
+    error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 id);
+    error ERC1155InvalidSender(address sender);
+    error ERC1155InvalidReceiver(address receiver);
+    error ERC1155InvalidApprover(address approver);
+    error ERC1155InvalidOperator(address operator);
+    error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
+    error ERC1155MissingApprovalForAll(address operator, address owner);
+    error ERC1155BalanceOverflow(uint256 id, uint256 balance);
+    error ERC1155InsufficientSupply(uint256 id, uint256 supply, uint256 needed);
+    error ERC1155ZeroAmount(uint256 id);
+    error ERC1155DuplicateId(uint256 id);
+    error OwnableUnauthorizedAccount(address account);
+    error OwnableInvalidOwner(address owner);
+    error EnforcedPause();
+    error ExpectedPause();
+    error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+    error AccessControlBadConfirmation();
+    error AccessControlMemberOutOfBounds(bytes32 role, uint256 index);
+
+    function owner() external view returns (address);
+    function transferOwnership(address new_owner) external;
+    function renounceOwnership() external;
+    function paused() external view returns (bool);
+    function hasRole(bytes32 role, address account) external view returns (bool);
+    function getRoleAdmin(bytes32 role) external view returns (bytes32);
+    function grantRole(bytes32 role, address account) external;
+    function revokeRole(bytes32 role, address account) external;
+    function renounceRole(bytes32 role, address caller_confirmation) external;
+    function getRoleMember(bytes32 role, uint256 index) external view returns (address);
+    function getRoleMemberCount(bytes32 role) external view returns (uint256);
+    function DEFAULT_ADMIN_ROLE() external view returns (bytes32);
+    function initialize(string calldata base_uri, address owner) external;
+    function uri(uint256 id) external view returns (string memory);
+    function setUri(string calldata new_uri) external;
+    function setTokenUris(uint256[] memory ids, string[] memory uris) external;
+    function setSeriesUri(uint256 start_id, uint256 end_id, string calldata base) external;
+    function metadataFrozen() external view returns (bool);
+    function freezeMetadata() external;
+    function pause() external;
+    function unpause() external;
+    function totalSupply(uint256 id) external view returns (uint256);
+    function exists(uint256 id) external view returns (bool);
+    function everExisted(uint256 id) external view returns (bool);
+    function retiresBurnedOut() external view returns (bool);
+    function setRetireBurnedOut(bool retire) external;
+    function supplyFrozen(uint256 id) external view returns (bool);
+    function freezeSupply(uint256 id) external;
+    function mint(address to, uint256 id, uint256 amount, bytes calldata _data) external;
+    function mintBatch(address to, uint256[] memory ids, uint256[] memory amounts, bytes calldata _data) external;
+    function burn(uint256 id, uint256 amount) external;
+    function burnFrom(address from, uint256 id, uint256 amount) external;
+    function burnBatch(uint256[] memory ids, uint256[] memory amounts) external;
+    function crosschainMint(address to, uint256 id, uint256 amount) external;
+    function crosschainBurn(address from, uint256 id, uint256 amount) external;
+    function mintWithReceipt(address to, uint256 id, uint256 amount, bytes32 order_id) external;
+    function orderFulfilled(bytes32 order_id) external view returns (bool);
+    function BRIDGE_ROLE() external view returns (bytes32);
+    function GUARDIAN_ROLE() external view returns (bytes32);
+    function PAYMENT_PROCESSOR_ROLE() external view returns (bytes32);
+    function guardianExpiry() external view returns (uint256);
+    function setGuardianExpiry(uint256 expiry) external;
+    function batchLogChunk() external view returns (uint256);
+    function setBatchLogChunk(uint256 entries) external;
+    function maxBatchLength() external view returns (uint256);
+    function setMaxBatchLength(uint256 entries) external;
+    function pauseRegistry() external view returns (address);
+    function setPauseRegistry(address registry) external;
+    function buildMetadata() external view returns (bytes32, string memory);
+    function balanceOf(address account, uint256 id) external view returns (uint256);
+    function balanceOfBatch(address[] memory accounts, uint256[] memory ids) external view returns (uint256[] memory);
+    function setApprovalForAll(address operator, bool approved) external;
+    function setApprovalForAllWithExpiry(address operator, bool approved, uint256 expires) external;
+    function approvalExpiry(address account, address operator) external view returns (uint256);
+    function isApprovedForAll(address account, address operator) external view returns (bool);
+    function canTransfer(address from, address to, uint256 id, uint256 amount) external view returns (bool, bytes4);
+    function supportsInterface(bytes4 interface_id) external view returns (bool);
+    function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes calldata data) external;
+    function safeTransferFromWithReturn(address from, address to, uint256 id, uint256 value, bytes calldata data) external returns (bytes memory);
+    function safeBatchTransferFrom(address from, address to, uint256[] memory ids, uint256[] memory values, bytes calldata data) external;
+    function safeBatchTransferFromWithReturn(address from, address to, uint256[] memory ids, uint256[] memory values, bytes calldata data) external returns (bytes memory);
+}
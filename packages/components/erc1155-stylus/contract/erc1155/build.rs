@@ -0,0 +1,44 @@
+use std::{env, fs, path::Path};
+
+fn main() {
+    cradle_build_info::emit().expect("failed to hash the contract sources");
+    emit_deployer();
+    check_testnet_profile();
+}
+
+/// Writes `DEPLOYER`, the account `CRADLE_DEPLOYER` names, which alone may call `initialize`.
+fn emit_deployer() {
+    println!("cargo:rerun-if-env-changed=CRADLE_DEPLOYER");
+    let deployer = match env::var("CRADLE_DEPLOYER") {
+        Ok(deployer) if !deployer.is_empty() => {
+            let hex = deployer.strip_prefix("0x").unwrap_or(&deployer);
+            assert!(
+                hex.len() == 40 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()),
+                "CRADLE_DEPLOYER must be an address, not {deployer:?}"
+            );
+            let bytes: Vec<String> = (0..40).step_by(2).map(|at| format!("0x{}", &hex[at..at + 2])).collect();
+            format!("Some(Address::new([{}]))", bytes.join(", "))
+        }
+        _ => "None".to_string(),
+    };
+    let code = format!("const DEPLOYER: Option<Address> = {deployer};\n");
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(Path::new(&out).join("deployer.rs"), code).expect("failed to write deployer.rs");
+}
+
+/// Fails a build with the `testnet` feature in the `release` profile, the one `cargo stylus`
+/// deploys, so its faucet and short admin delays can't reach a mainnet deployment by accident.
+fn check_testnet_profile() {
+    if env::var_os("CARGO_FEATURE_TESTNET").is_none() {
+        return;
+    }
+    // `PROFILE` is `release` for profiles that inherit it too, so take the name from the output
+    // directory, `target/[<triple>/]<profile>/build/<package>-<hash>/out`
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let profile = Path::new(&out).ancestors().nth(3).and_then(Path::file_name).and_then(|name| name.to_str());
+    assert!(
+        profile != Some("release"),
+        "the `testnet` feature can't be built with the release profile, build testnet deployments with \
+         `--profile testnet`"
+    );
+}
@@ -0,0 +1,58 @@
+#!/bin/bash
+# Checks the extension features with none of them, each alone, every pair and all of them
+# together, and tests each extension alone, the pairs with tests of their own and all of them
+# together. Extensions only interact in pairs, through one's hooks or setters running in the
+# other, so the pairs cover the combinations without walking every subset. Exits non-zero on the
+# first failure, so CI can run it as is.
+#
+#   ./feature-matrix.sh           # the full matrix
+#   ./feature-matrix.sh --quick   # without the pairs
+
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+EXTENSIONS=(royalties enumerable supply-cap soulbound meta-tx paid-mint id-ranges split-ids rate-limits admin-delay tiers validity packs market composable attested-mint checkpoints votes approval-index transfer-hooks modules holder-snapshots testnet transfer-security reveal open-editions)
+# Pairs with tests that only build with both, see `src/tests.rs`
+TESTED_PAIRS=(market,transfer-security testnet,admin-delay)
+QUICK=false
+if [[ "${1:-}" == "--quick" ]]; then
+  QUICK=true
+fi
+
+run() {
+  echo "==> $*"
+  "$@"
+}
+
+check() {
+  local list=$1
+  run cargo clippy --all-targets --features "$list" -- -D warnings
+  run cargo check --features "export-abi${list:+,$list}"
+}
+
+all=$(IFS=,; echo "${EXTENSIONS[*]}")
+
+# No extensions, each alone and all of them, with and without export-abi
+check ""
+for features in "${EXTENSIONS[@]}" "$all"; do
+  check "$features"
+done
+
+# Every pair, n(n-1)/2 runs
+if [[ "$QUICK" == false ]]; then
+  for ((i = 0; i < ${#EXTENSIONS[@]}; i++)); do
+    for ((j = i + 1; j < ${#EXTENSIONS[@]}; j++)); do
+      check "${EXTENSIONS[$i]},${EXTENSIONS[$j]}"
+    done
+  done
+fi
+
+# Each extension alone, the tested pairs and all of them, including the interface they export
+run cargo test
+for features in "${EXTENSIONS[@]}" "${TESTED_PAIRS[@]}" "$all"; do
+  run cargo test --features "$features"
+  run cargo run --quiet --features "export-abi,$features" -- interface > /dev/null
+done
+
+echo "All feature combinations pass"
@@ -0,0 +1,326 @@
+//! The MUST and SHOULD rules of EIP-1155, one test each and named after the rule, so that a change
+//! breaking conformance fails under the rule it breaks.
+//!
+//! On chain a call that fails reverts all its writes, which the test VM doesn't, so batch
+//! atomicity is checked as the call failing without logging anything.
+
+use super::*;
+use crate::tests::{id, setup, setup_with_balances, ALICE, BOB, OWNER, PARTNER};
+use alloc::{vec, vec::Vec};
+use cradle_test_utils::{emitted, ok};
+use erc1155::{ApprovalForAll, TransferBatch, TransferSingle};
+use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+fn answer<C: SolCall>() -> Vec<u8> {
+    C::SELECTOR.abi_encode()
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_must_revert_for_the_zero_address() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, Address::ZERO, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+    let result = contract.safe_batch_transfer_from(ALICE, Address::ZERO, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_must_revert_when_the_balance_is_lower_than_the_value() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(101), vec![].into());
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(_))));
+    // Any entry of a batch, here the second
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(1), id(51)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_must_revert_unless_the_caller_is_the_holder_or_an_approved_operator() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(BOB);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(_))));
+
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, false));
+    vm.set_sender(BOB);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_must_log_transfer_single_with_the_caller_as_operator() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(ALICE, PARTNER, id(1), id(30), vec![].into()));
+
+    let log = emitted::<TransferSingle>(&vm).pop().unwrap();
+    assert_eq!((log.operator, log.from, log.to, log.id, log.value), (BOB, ALICE, PARTNER, id(1), id(30)));
+    assert_eq!((contract.balance_of(ALICE, id(1)), contract.balance_of(PARTNER, id(1))), (id(70), id(30)));
+}
+
+// Builds with `reject-zero-amounts` deliberately break this rule
+#[cfg(not(feature = "reject-zero-amounts"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn zero_value_transfers_must_be_treated_as_normal_transfers() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(BOB);
+    // Even of an ID the sender holds none of
+    ok(contract.safe_transfer_from(BOB, ALICE, id(1), U256::ZERO, vec![].into()));
+    let log = emitted::<TransferSingle>(&vm).pop().unwrap();
+    assert_eq!((log.from, log.to, log.value), (BOB, ALICE, U256::ZERO));
+
+    ok(contract.safe_batch_transfer_from(BOB, ALICE, vec![id(1), id(9)], vec![U256::ZERO; 2], vec![].into()));
+    let log = emitted::<TransferBatch>(&vm).pop().unwrap();
+    assert_eq!((log.ids, log.values), (vec![id(1), id(9)], vec![U256::ZERO; 2]));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfers_must_revert_when_ids_and_values_differ_in_length() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidArrayLength(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfers_must_be_atomic() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let logged = vm.get_emitted_logs().len();
+    // The first entry can move, the second can't
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(2), id(1)], vec![id(1), id(101)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(_))));
+    assert_eq!(vm.get_emitted_logs().len(), logged);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_balance_changes_and_logs_must_follow_the_order_submitted() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.set_batch_log_chunk(id(1)));
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(2), id(1)], vec![id(5), id(6)], vec![].into()));
+
+    let logs = emitted::<TransferBatch>(&vm);
+    let moved: Vec<_> = logs[logs.len() - 2..].iter().map(|log| (log.ids.clone(), log.values.clone())).collect();
+    assert_eq!(moved, vec![(vec![id(2)], vec![id(5)]), (vec![id(1)], vec![id(6)])]);
+    assert_eq!((contract.balance_of(BOB, id(2)), contract.balance_of(BOB, id(1))), (id(5), id(6)));
+}
+
+#[test]
+fn mints_must_log_the_zero_address_as_from() {
+    let (vm, mut contract) = setup();
+    ok(contract.mint(ALICE, id(1), id(3), vec![].into()));
+    let log = emitted::<TransferSingle>(&vm).pop().unwrap();
+    assert_eq!((log.operator, log.from, log.to), (OWNER, Address::ZERO, ALICE));
+
+    ok(contract.mint_batch(ALICE, vec![id(1), id(2)], vec![id(1), id(2)], vec![].into()));
+    let log = emitted::<TransferBatch>(&vm).pop().unwrap();
+    assert_eq!((log.from, log.to, log.ids), (Address::ZERO, ALICE, vec![id(1), id(2)]));
+}
+
+#[test]
+fn burns_must_log_the_zero_address_as_to() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(1), id(3)));
+    let log = emitted::<TransferSingle>(&vm).pop().unwrap();
+    assert_eq!((log.operator, log.from, log.to, log.value), (ALICE, ALICE, Address::ZERO, id(3)));
+
+    ok(contract.burn_batch(vec![id(1), id(2)], vec![id(1), id(2)]));
+    let log = emitted::<TransferBatch>(&vm).pop().unwrap();
+    assert_eq!((log.from, log.to, log.values), (ALICE, Address::ZERO, vec![id(1), id(2)]));
+}
+
+#[test]
+fn approvals_must_log_approval_for_all() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    ok(contract.set_approval_for_all(BOB, false));
+    let logs = emitted::<ApprovalForAll>(&vm);
+    let approvals: Vec<_> = logs.iter().map(|log| (log.account, log.operator, log.approved)).collect();
+    assert_eq!(approvals, vec![(ALICE, BOB, true), (ALICE, BOB, false)]);
+    assert!(!contract.is_approved_for_all(ALICE, BOB));
+}
+
+#[test]
+fn balance_of_batch_must_return_balances_in_the_order_asked() {
+    let (_vm, contract) = setup_with_balances();
+    let balances = ok(contract.balance_of_batch(vec![ALICE, BOB, ALICE], vec![id(2), id(1), id(1)]));
+    assert_eq!(balances, vec![id(50), U256::ZERO, id(100)]);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn receiver_hooks_must_be_called_on_contracts_with_the_data_unaltered() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_code(PARTNER, vec![0]);
+    vm.set_sender(ALICE);
+    // The mocks only answer for exactly this calldata
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: ALICE,
+        from: ALICE,
+        id: id(1),
+        value: id(2),
+        data: vec![1, 2].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155ReceivedCall>()));
+    ok(contract.safe_transfer_from(ALICE, PARTNER, id(1), id(2), vec![1, 2].into()));
+
+    // Batches call the batch hook
+    let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+        operator: ALICE,
+        from: ALICE,
+        ids: vec![id(1), id(2)],
+        values: vec![id(1), id(1)],
+        data: vec![3].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155BatchReceivedCall>()));
+    let ids = vec![id(1), id(2)];
+    ok(contract.safe_batch_transfer_from(ALICE, PARTNER, ids, vec![id(1), id(1)], vec![3].into()));
+    assert_eq!((contract.balance_of(PARTNER, id(1)), contract.balance_of(PARTNER, id(2))), (id(3), id(1)));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_must_revert_when_the_receiver_hook_rejects_or_reverts() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_code(PARTNER, vec![0]);
+    vm.set_sender(ALICE);
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: ALICE,
+        from: ALICE,
+        id: id(1),
+        value: id(1),
+        data: vec![].into(),
+    };
+    // Another selector, even the batch hook's, is a rejection
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155BatchReceivedCall>()));
+    let result = contract.safe_transfer_from(ALICE, PARTNER, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(_))));
+
+    vm.mock_call(PARTNER, hook.abi_encode(), Err(b"no".to_vec()));
+    let result = contract.safe_transfer_from(ALICE, PARTNER, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(e)) if e.returnData.as_ref() == b"no"));
+}
+
+#[test]
+fn mints_must_call_the_receiver_hook_with_the_zero_address_as_from() {
+    let (vm, mut contract) = setup();
+    vm.set_code(PARTNER, vec![0]);
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        id: id(1),
+        value: id(2),
+        data: vec![1].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155ReceivedCall>()));
+    ok(contract.mint(PARTNER, id(1), id(2), vec![1].into()));
+
+    let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        ids: vec![id(1), id(2)],
+        values: vec![id(1), id(3)],
+        data: vec![2].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155BatchReceivedCall>()));
+    ok(contract.mint_batch(PARTNER, vec![id(1), id(2)], vec![id(1), id(3)], vec![2].into()));
+
+    // Mints without a `data` parameter pass an empty one
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, OWNER).map_err(My1155Error::from));
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        id: id(1),
+        value: id(4),
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155ReceivedCall>()));
+    ok(contract.crosschain_mint(PARTNER, id(1), id(4)));
+    assert_eq!((contract.balance_of(PARTNER, id(1)), contract.balance_of(PARTNER, id(2))), (id(7), id(3)));
+}
+
+#[test]
+fn mints_must_revert_when_the_receiver_hook_answers_another_selector() {
+    let (vm, mut contract) = setup();
+    vm.set_code(PARTNER, vec![0]);
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        id: id(1),
+        value: id(2),
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155BatchReceivedCall>()));
+    let result = contract.mint(PARTNER, id(1), id(2), vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(e)) if e.receiver == PARTNER));
+
+    let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        ids: vec![id(1)],
+        values: vec![id(2)],
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer::<IERC1155Receiver::onERC1155ReceivedCall>()));
+    let result = contract.mint_batch(PARTNER, vec![id(1)], vec![id(2)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(e)) if e.receiver == PARTNER));
+}
+
+#[test]
+fn mints_must_revert_when_the_receiver_hook_reverts() {
+    let (vm, mut contract) = setup();
+    vm.set_code(PARTNER, vec![0]);
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        id: id(1),
+        value: id(2),
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Err(b"no".to_vec()));
+    let result = contract.mint(PARTNER, id(1), id(2), vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(e)) if e.returnData.as_ref() == b"no"));
+
+    let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+        operator: OWNER,
+        from: Address::ZERO,
+        ids: vec![id(1)],
+        values: vec![id(2)],
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, hook.abi_encode(), Err(b"no".to_vec()));
+    let result = contract.mint_batch(PARTNER, vec![id(1)], vec![id(2)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::ReceiverHookFailed(e)) if e.returnData.as_ref() == b"no"));
+}
+
+#[test]
+fn erc165_must_report_erc1155_and_itself_but_not_0xffffffff() {
+    let (_vm, contract) = setup();
+    let supports = |id: u32| contract.supports_interface(FixedBytes(id.to_be_bytes()));
+    assert!(supports(0x01ffc9a7) && supports(0xd9b67a26));
+    assert!(!supports(0xffffffff));
+    // ERC1155Metadata_URI, which `uri` implements, SHOULD be reported
+    assert!(supports(0x0e89341c));
+}
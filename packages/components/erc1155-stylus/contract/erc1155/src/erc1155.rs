@@ -1,15 +1,26 @@
 use alloc::vec::Vec;
 use stylus_sdk::{
     alloy_primitives::{Address, U256},
-    alloy_sol_types::{sol, SolError},
-    evm, msg,
+    alloy_sol_types::sol,
     prelude::*,
 };
 
+use crate::profiling;
+
+/// IDs whose balances share one storage slot with `packed-balances`.
+#[cfg(feature = "packed-balances")]
+const IDS_PER_SLOT: u64 = 4;
+/// Bits of a slot holding one ID's balance with `packed-balances`.
+#[cfg(feature = "packed-balances")]
+const BALANCE_BITS: usize = 64;
+
 sol_storage! {
     pub struct Erc1155 {
+        /// Balances by ID and account. With `packed-balances` the outer key is `id / 4`, and each
+        /// slot holds the balances of four consecutive IDs in 64-bit lanes, lowest ID first.
         mapping(uint256 => mapping(address => uint256)) balances;
         mapping(address => mapping(address => bool)) operator_approvals;
+        mapping(uint256 => uint256) total_supply;
     }
 }
 
@@ -19,38 +30,37 @@ sol! {
     event ApprovalForAll(address indexed account, address indexed operator, bool approved);
 
     error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 id);
+    error ERC1155InvalidSender(address sender);
     error ERC1155InvalidReceiver(address receiver);
     error ERC1155InvalidApprover(address approver);
     error ERC1155InvalidOperator(address operator);
     error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
     error ERC1155MissingApprovalForAll(address operator, address owner);
+    error ERC1155BalanceOverflow(uint256 id, uint256 balance);
+    error ERC1155InsufficientSupply(uint256 id, uint256 supply, uint256 needed);
+    error ERC1155ZeroAmount(uint256 id);
+    error ERC1155DuplicateId(uint256 id);
 }
 
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
 pub enum Erc1155Error {
     InsufficientBalance(ERC1155InsufficientBalance),
+    InvalidSender(ERC1155InvalidSender),
     InvalidReceiver(ERC1155InvalidReceiver),
     InvalidApprover(ERC1155InvalidApprover),
     InvalidOperator(ERC1155InvalidOperator),
     InvalidArrayLength(ERC1155InvalidArrayLength),
     MissingApprovalForAll(ERC1155MissingApprovalForAll),
-}
-
-impl From<Erc1155Error> for Vec<u8> {
-    fn from(error: Erc1155Error) -> Self {
-        match error {
-            Erc1155Error::InsufficientBalance(e) => e.abi_encode(),
-            Erc1155Error::InvalidReceiver(e) => e.abi_encode(),
-            Erc1155Error::InvalidApprover(e) => e.abi_encode(),
-            Erc1155Error::InvalidOperator(e) => e.abi_encode(),
-            Erc1155Error::InvalidArrayLength(e) => e.abi_encode(),
-            Erc1155Error::MissingApprovalForAll(e) => e.abi_encode(),
-        }
-    }
+    BalanceOverflow(ERC1155BalanceOverflow),
+    InsufficientSupply(ERC1155InsufficientSupply),
+    ZeroAmount(ERC1155ZeroAmount),
+    DuplicateId(ERC1155DuplicateId),
 }
 
 impl Erc1155 {
     pub fn balance_of(&self, account: Address, id: U256) -> U256 {
-        self.balances.get(id).get(account)
+        self.read_balance(id, account)
     }
 
     pub fn balance_of_batch(&self, accounts: Vec<Address>, ids: Vec<U256>) -> Result<Vec<U256>, Erc1155Error> {
@@ -63,8 +73,25 @@ impl Erc1155 {
         Ok(accounts.iter().zip(ids.iter()).map(|(acc, id)| self.balance_of(*acc, *id)).collect())
     }
 
+    pub fn total_supply(&self, id: U256) -> U256 {
+        self.total_supply.get(id)
+    }
+
+    pub fn exists(&self, id: U256) -> bool {
+        !self.total_supply(id).is_zero()
+    }
+
     pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Erc1155Error> {
-        let owner = msg::sender();
+        self._set_approval_for_all(self.vm().msg_sender(), operator, approved)
+    }
+
+    /// Approves `operator` for `owner`'s tokens, such as with the owner's signature rather than their call.
+    pub fn _set_approval_for_all(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc1155Error> {
         if owner == operator {
             return Err(Erc1155Error::InvalidOperator(ERC1155InvalidOperator { operator }));
         }
@@ -72,7 +99,7 @@ impl Erc1155 {
         let mut owner_approvals = self.operator_approvals.setter(owner);
         owner_approvals.insert(operator, approved);
 
-        evm::log(ApprovalForAll {
+        log(self.vm(), ApprovalForAll {
             account: owner,
             operator,
             approved,
@@ -91,9 +118,9 @@ impl Erc1155 {
         to: Address,
         id: U256,
         value: U256,
-        _data: Vec<u8>,
+        _data: &[u8],
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
+        let operator = self.vm().msg_sender();
         if from != operator && !self.is_approved_for_all(from, operator) {
             return Err(Erc1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll {
                 operator,
@@ -114,11 +141,12 @@ impl Erc1155 {
         &mut self,
         from: Address,
         to: Address,
-        ids: Vec<U256>,
-        values: Vec<U256>,
-        _data: Vec<u8>,
+        ids: &[U256],
+        values: &[U256],
+        _data: &[u8],
+        log_chunk: usize,
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
+        let operator = self.vm().msg_sender();
         if from != operator && !self.is_approved_for_all(from, operator) {
             return Err(Erc1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll {
                 operator,
@@ -137,7 +165,7 @@ impl Erc1155 {
             }));
         }
 
-        self._update_batch(from, to, ids, values)?;
+        self._update_batch(from, to, ids, values, log_chunk)?;
 
         Ok(())
     }
@@ -149,83 +177,251 @@ impl Erc1155 {
         id: U256,
         value: U256,
     ) -> Result<(), Erc1155Error> {
-        if !from.is_zero() {
-            let mut balance_map = self.balances.setter(id);
-            let mut from_balance_setter = balance_map.setter(from);
-            let from_balance = from_balance_setter.get();
-            if from_balance < value {
-                return Err(Erc1155Error::InsufficientBalance(ERC1155InsufficientBalance {
-                    sender: from,
-                    balance: from_balance,
-                    needed: value,
-                    id,
-                }));
-            }
-            from_balance_setter.set(from_balance - value);
-        }
-
-        if !to.is_zero() {
-            let mut balance_map = self.balances.setter(id);
-            let mut to_balance_setter = balance_map.setter(to);
-            let to_balance = to_balance_setter.get();
-            to_balance_setter.set(to_balance + value);
-        }
+        let probe = profiling::start(self.vm());
+        Self::check_amount(id, value)?;
+        self.move_balance(id, from, to, value)?;
+        self._update_supply(from, to, id, value)?;
 
-        evm::log(TransferSingle {
-            operator: msg::sender(),
+        log(self.vm(), TransferSingle {
+            operator: self.vm().msg_sender(),
             from,
             to,
             id,
             value,
         });
 
+        profiling::finish(self.vm(), profiling::UPDATE_SINGLE, probe);
         Ok(())
     }
 
+    /// Moves a batch and logs it with a `TransferBatch` per `log_chunk` entries, in order, or one
+    /// for the whole batch if `log_chunk` is zero.
     pub fn _update_batch(
         &mut self,
         from: Address,
         to: Address,
-        ids: Vec<U256>,
-        values: Vec<U256>,
+        ids: &[U256],
+        values: &[U256],
+        log_chunk: usize,
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
-        for i in 0..ids.len() {
-            let id = ids[i];
-            let value = values[i];
-
-            if !from.is_zero() {
-                let mut balance_map = self.balances.setter(id);
-                let mut from_balance_setter = balance_map.setter(from);
-                let from_balance = from_balance_setter.get();
-                if from_balance < value {
-                    return Err(Erc1155Error::InsufficientBalance(ERC1155InsufficientBalance {
-                        sender: from,
-                        balance: from_balance,
-                        needed: value,
-                        id,
-                    }));
-                }
-                from_balance_setter.set(from_balance - value);
+        let probe = profiling::start(self.vm());
+        let operator = self.vm().msg_sender();
+        // Entries repeating an ID are merged, so each balance is read and written once and is
+        // checked against the entries' total. A transfer to self moves nothing, so like separate
+        // transfers it only needs the largest entry covered.
+        let mut moves: Vec<(U256, U256)> = Vec::with_capacity(ids.len());
+        for (&id, &value) in ids.iter().zip(values.iter()) {
+            Self::check_amount(id, value)?;
+            let merged = moves.iter_mut().find(|(moved_id, _)| *moved_id == id);
+            #[cfg(feature = "reject-duplicate-ids")]
+            if merged.is_some() {
+                return Err(Erc1155Error::DuplicateId(ERC1155DuplicateId { id }));
+            }
+            match merged {
+                Some((_, total)) if from == to => *total = (*total).max(value),
+                Some((_, total)) => match total.checked_add(value) {
+                    Some(sum) => *total = sum,
+                    // Entries no balance could cover, which a wrapped total would let through
+                    None if from.is_zero() => {
+                        return Err(Erc1155Error::BalanceOverflow(ERC1155BalanceOverflow { id, balance: *total }));
+                    }
+                    None => return Err(Self::insufficient_balance(from, self.read_balance(id, from), U256::MAX, id)),
+                },
+                None => moves.push((id, value)),
             }
+        }
+        for (id, value) in moves {
+            self.move_balance(id, from, to, value)?;
+            self._update_supply(from, to, id, value)?;
+        }
 
-            if !to.is_zero() {
-                let mut balance_map = self.balances.setter(id);
-                let mut to_balance_setter = balance_map.setter(to);
-                let to_balance = to_balance_setter.get();
-                to_balance_setter.set(to_balance + value);
+        if log_chunk == 0 || ids.len() <= log_chunk {
+            log(self.vm(), TransferBatch {
+                operator,
+                from,
+                to,
+                ids: ids.to_vec(),
+                values: values.to_vec(),
+            });
+        } else {
+            for (ids, values) in ids.chunks(log_chunk).zip(values.chunks(log_chunk)) {
+                log(self.vm(), TransferBatch {
+                    operator,
+                    from,
+                    to,
+                    ids: ids.to_vec(),
+                    values: values.to_vec(),
+                });
             }
         }
 
-        evm::log(TransferBatch {
-            operator,
-            from,
-            to,
-            ids,
-            values,
-        });
+        profiling::finish(self.vm(), profiling::UPDATE_BATCH, probe);
+        Ok(())
+    }
+
+    pub fn _mint(&mut self, to: Address, id: U256, value: U256) -> Result<(), Erc1155Error> {
+        if to.is_zero() {
+            return Err(Erc1155Error::InvalidReceiver(ERC1155InvalidReceiver { receiver: Address::ZERO }));
+        }
+        self._update_single(Address::ZERO, to, id, value)
+    }
 
+    pub fn _mint_batch(
+        &mut self,
+        to: Address,
+        ids: &[U256],
+        values: &[U256],
+        log_chunk: usize,
+    ) -> Result<(), Erc1155Error> {
+        if to.is_zero() {
+            return Err(Erc1155Error::InvalidReceiver(ERC1155InvalidReceiver { receiver: Address::ZERO }));
+        }
+        if ids.len() != values.len() {
+            return Err(Erc1155Error::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(values.len()),
+            }));
+        }
+        self._update_batch(Address::ZERO, to, ids, values, log_chunk)
+    }
+
+    pub fn _burn(&mut self, from: Address, id: U256, value: U256) -> Result<(), Erc1155Error> {
+        if from.is_zero() {
+            return Err(Erc1155Error::InvalidSender(ERC1155InvalidSender { sender: Address::ZERO }));
+        }
+        self._update_single(from, Address::ZERO, id, value)
+    }
+
+    pub fn _burn_batch(
+        &mut self,
+        from: Address,
+        ids: &[U256],
+        values: &[U256],
+        log_chunk: usize,
+    ) -> Result<(), Erc1155Error> {
+        if from.is_zero() {
+            return Err(Erc1155Error::InvalidSender(ERC1155InvalidSender { sender: Address::ZERO }));
+        }
+        if ids.len() != values.len() {
+            return Err(Erc1155Error::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(values.len()),
+            }));
+        }
+        self._update_batch(from, Address::ZERO, ids, values, log_chunk)
+    }
+
+    /// Moves `value` of `id` from `from` to `to`; a zero `from` mints and a zero `to` burns.
+    #[cfg(not(feature = "packed-balances"))]
+    fn move_balance(&mut self, id: U256, from: Address, to: Address, value: U256) -> Result<(), Erc1155Error> {
+        let mut holders = self.balances.setter(id);
+        if !from.is_zero() {
+            let from_balance = holders.get(from);
+            if from_balance < value {
+                return Err(Self::insufficient_balance(from, from_balance, value, id));
+            }
+            holders.insert(from, from_balance - value);
+        }
+        if !to.is_zero() {
+            let to_balance = holders.get(to);
+            holders.insert(to, Self::credit(id, to_balance, value)?);
+        }
+        Ok(())
+    }
+
+    /// Moves `value` of `id` from `from` to `to`; a zero `from` mints and a zero `to` burns.
+    #[cfg(feature = "packed-balances")]
+    fn move_balance(&mut self, id: U256, from: Address, to: Address, value: U256) -> Result<(), Erc1155Error> {
+        if !from.is_zero() {
+            let from_balance = self.read_balance(id, from);
+            if from_balance < value {
+                return Err(Self::insufficient_balance(from, from_balance, value, id));
+            }
+            self.write_balance(id, from, from_balance - value)?;
+        }
+        if !to.is_zero() {
+            let to_balance = self.read_balance(id, to);
+            self.write_balance(id, to, Self::credit(id, to_balance, value)?)?;
+        }
+        Ok(())
+    }
+
+    /// Fails on a zero `value`, which `reject-zero-amounts` builds refuse to move or log.
+    #[cfg(feature = "reject-zero-amounts")]
+    pub fn check_amount(id: U256, value: U256) -> Result<(), Erc1155Error> {
+        if value.is_zero() {
+            return Err(Erc1155Error::ZeroAmount(ERC1155ZeroAmount { id }));
+        }
         Ok(())
     }
 
+    #[cfg(not(feature = "reject-zero-amounts"))]
+    #[inline(always)]
+    pub fn check_amount(_id: U256, _value: U256) -> Result<(), Erc1155Error> {
+        Ok(())
+    }
+
+    /// `balance` plus `value`, failing rather than wrapping past `2^256 - 1`.
+    fn credit(id: U256, balance: U256, value: U256) -> Result<U256, Erc1155Error> {
+        balance.checked_add(value).ok_or(Erc1155Error::BalanceOverflow(ERC1155BalanceOverflow { id, balance }))
+    }
+
+    fn insufficient_balance(sender: Address, balance: U256, needed: U256, id: U256) -> Erc1155Error {
+        Erc1155Error::InsufficientBalance(ERC1155InsufficientBalance {
+            sender,
+            balance,
+            needed,
+            id,
+        })
+    }
+
+    #[cfg(not(feature = "packed-balances"))]
+    fn read_balance(&self, id: U256, account: Address) -> U256 {
+        self.balances.get(id).get(account)
+    }
+
+    /// Reads `id`'s 64-bit lane of the slot shared with its neighbouring IDs.
+    #[cfg(feature = "packed-balances")]
+    fn read_balance(&self, id: U256, account: Address) -> U256 {
+        let (slot, shift) = Self::packed_position(id);
+        (self.balances.get(slot).get(account) >> shift) & U256::from(u64::MAX)
+    }
+
+    /// Writes `id`'s 64-bit lane, leaving the other IDs in the slot untouched.
+    #[cfg(feature = "packed-balances")]
+    fn write_balance(&mut self, id: U256, account: Address, balance: U256) -> Result<(), Erc1155Error> {
+        if balance > U256::from(u64::MAX) {
+            return Err(Erc1155Error::BalanceOverflow(ERC1155BalanceOverflow { id, balance }));
+        }
+        let (slot, shift) = Self::packed_position(id);
+        let mut slots = self.balances.setter(slot);
+        let mut packed = slots.setter(account);
+        let cleared = packed.get() & !(U256::from(u64::MAX) << shift);
+        packed.set(cleared | (balance << shift));
+        Ok(())
+    }
+
+    /// Outer mapping key and bit offset of `id`'s balance lane.
+    #[cfg(feature = "packed-balances")]
+    fn packed_position(id: U256) -> (U256, usize) {
+        let lane = (id % U256::from(IDS_PER_SLOT)).to::<usize>();
+        (id / U256::from(IDS_PER_SLOT), lane * BALANCE_BITS)
+    }
+
+    /// Keeps `total_supply` in step with mints (`from` zero) and burns (`to` zero). A mint past
+    /// `2^256 - 1` fails, and so would a burn of more than the supply, which the balances rule out.
+    fn _update_supply(&mut self, from: Address, to: Address, id: U256, value: U256) -> Result<(), Erc1155Error> {
+        if from.is_zero() {
+            let supply = self.total_supply.get(id);
+            self.total_supply.insert(id, Self::credit(id, supply, value)?);
+        }
+        if to.is_zero() {
+            let supply = self.total_supply.get(id);
+            let remaining = supply.checked_sub(value).ok_or(Erc1155Error::InsufficientSupply(
+                ERC1155InsufficientSupply { id, supply, needed: value },
+            ))?;
+            self.total_supply.insert(id, remaining);
+        }
+        Ok(())
+    }
 }
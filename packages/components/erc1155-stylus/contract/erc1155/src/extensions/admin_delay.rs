@@ -0,0 +1,359 @@
+//! A delay on sensitive owner actions, with the `admin-delay` feature.
+//!
+//! Once the owner sets an admin delay, it can no longer change the URIs, transfer ownership,
+//! unpause, change royalties, attach transfer hooks, route selectors to modules, change the
+//! transfer security level or its allowlist, or change the delay itself directly. It schedules
+//! the call instead with `scheduleAdminAction`, and anyone can execute it with
+//! `executeAdminAction` once the delay has passed, so holders see a malicious change coming and
+//! have time to leave. The owner can cancel an action until it's executed. A
+//! zero delay, the default, lets the owner act directly.
+//!
+//! Actions are the calldata of the owner method they call, and a salt to schedule the same call
+//! twice. A new owner inherits the actions its predecessor scheduled and should cancel those it
+//! doesn't want. Pausing is never delayed, so the owner and guardians can still stop the token.
+//! Built with `testnet`, actions wait at most five minutes whatever the delay.
+
+#[cfg(feature = "admin-delay")]
+use alloc::{string::String, vec::Vec};
+
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "admin-delay")]
+use cradle_ownable::{OwnableError, OwnableInvalidOwner};
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{B256, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(all(feature = "admin-delay", feature = "royalties"))]
+use stylus_sdk::alloy_primitives::aliases::U96;
+#[cfg(feature = "admin-delay")]
+use stylus_sdk::{
+    alloy_primitives::{keccak256, Address, FixedBytes},
+    alloy_sol_types::SolCall,
+};
+
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155AdminDelay`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "adminDelay"(),
+    "setAdminDelay"(U256),
+    "scheduleAdminAction"(Bytes, B256),
+    "executeAdminAction"(Bytes, B256),
+    "cancelAdminAction"(B256),
+    "adminActionReadyAt"(B256),
+);
+
+sol_storage! {
+    pub struct AdminDelay {
+        /// Seconds between scheduling an owner action and executing it, zero to act directly
+        uint256 delay;
+        /// Time each scheduled action can be executed from, zero for actions that aren't scheduled
+        mapping(bytes32 => uint256) ready_at;
+    }
+}
+
+sol! {
+    interface IERC1155AdminDelay {
+        function adminDelay() external view returns (uint256);
+        function setAdminDelay(uint256 delay) external;
+        function scheduleAdminAction(bytes data, bytes32 salt) external returns (bytes32 id);
+        function executeAdminAction(bytes data, bytes32 salt) external;
+        function cancelAdminAction(bytes32 id) external;
+        function adminActionReadyAt(bytes32 id) external view returns (uint256);
+    }
+
+    // The token's own owner methods that wait for the delay, besides `setAdminDelay`, the
+    // royalty setters of `IERC2981`, `setTransferHook` of `IERC1155TransferHooks`, `setModule`
+    // of `IERC1155Modules` and the setters of `IERC1155TransferSecurity`
+    function setUri(string newUri) external;
+    function setTokenUris(uint256[] ids, string[] uris) external;
+    function setSeriesUri(uint256 startId, uint256 endId, string base) external;
+    function transferOwnership(address newOwner) external;
+    function unpause() external;
+
+    event AdminDelaySet(uint256 delay);
+    event AdminActionScheduled(bytes32 indexed id, bytes data, bytes32 salt, uint256 readyAt);
+    event AdminActionExecuted(bytes32 indexed id);
+    event AdminActionCancelled(bytes32 indexed id);
+
+    // The owner must schedule the method with `selector` rather than call it
+    error ERC1155AdminDelayRequired(bytes4 selector);
+    // The data isn't a call to a method that waits for the delay
+    error ERC1155InvalidAdminAction(bytes4 selector);
+    error ERC1155AdminActionAlreadyScheduled(bytes32 id);
+    error ERC1155UnknownAdminAction(bytes32 id);
+    error ERC1155AdminActionNotReady(bytes32 id, uint256 readyAt);
+}
+
+#[cfg(not(feature = "admin-delay"))]
+disabled!(IAdminDelay);
+
+/// The methods of [`IERC1155AdminDelay`], and `transferOwnership`, which takes precedence over
+/// the one `My1155` inherits from `Ownable`.
+#[cfg(feature = "admin-delay")]
+pub trait IAdminDelay {
+    fn admin_delay(&self) -> U256;
+    fn set_admin_delay(&mut self, delay: U256) -> Result<(), My1155Error>;
+    fn schedule_admin_action(&mut self, data: Bytes, salt: B256) -> Result<B256, My1155Error>;
+    fn execute_admin_action(&mut self, data: Bytes, salt: B256) -> Result<(), My1155Error>;
+    fn cancel_admin_action(&mut self, id: B256) -> Result<(), My1155Error>;
+    fn admin_action_ready_at(&self, id: B256) -> U256;
+    fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "admin-delay")]
+#[public]
+impl IAdminDelay for My1155 {
+    /// Seconds between scheduling an owner action and executing it, zero if the owner acts
+    /// directly.
+    fn admin_delay(&self) -> U256 {
+        self.admin_delay.delay.get()
+    }
+
+    /// Sets the admin delay (owner only). Once it isn't zero, changing it is itself delayed.
+    fn set_admin_delay(&mut self, delay: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC1155AdminDelay::setAdminDelayCall::SELECTOR)?;
+        self.take_admin_action(AdminAction::SetAdminDelay(delay))
+    }
+
+    /// Schedules the owner call `data`, executable by anyone once the delay has passed (owner
+    /// only). Returns the action's ID.
+    fn schedule_admin_action(&mut self, data: Bytes, salt: B256) -> Result<B256, My1155Error> {
+        self.only_owner()?;
+        AdminAction::decode(&data)?;
+        let id = admin_action_id(&data, salt);
+        if !self.admin_delay.ready_at.get(id).is_zero() {
+            return Err(My1155Error::AdminActionAlreadyScheduled(ERC1155AdminActionAlreadyScheduled { id }));
+        }
+        let now = U256::from(self.vm().block_timestamp());
+        // A ready time of zero means the action isn't scheduled, so it's at least one
+        let ready_at = now.saturating_add(self.scheduled_admin_delay()).max(U256::from(1));
+        self.admin_delay.ready_at.insert(id, ready_at);
+        log(self.vm(), AdminActionScheduled {
+            id,
+            data: data.0.into(),
+            salt,
+            readyAt: ready_at,
+        });
+        Ok(id)
+    }
+
+    /// Executes the scheduled owner call `data` once its delay has passed. Anyone can call it.
+    fn execute_admin_action(&mut self, data: Bytes, salt: B256) -> Result<(), My1155Error> {
+        let id = admin_action_id(&data, salt);
+        let ready_at = self.admin_delay.ready_at.get(id);
+        if ready_at.is_zero() {
+            return Err(My1155Error::UnknownAdminAction(ERC1155UnknownAdminAction { id }));
+        }
+        if U256::from(self.vm().block_timestamp()) < ready_at {
+            return Err(My1155Error::AdminActionNotReady(ERC1155AdminActionNotReady { id, readyAt: ready_at }));
+        }
+        self.admin_delay.ready_at.delete(id);
+        self.take_admin_action(AdminAction::decode(&data)?)?;
+        log(self.vm(), AdminActionExecuted { id });
+        Ok(())
+    }
+
+    /// Drops a scheduled action before it's executed (owner only).
+    fn cancel_admin_action(&mut self, id: B256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if self.admin_delay.ready_at.get(id).is_zero() {
+            return Err(My1155Error::UnknownAdminAction(ERC1155UnknownAdminAction { id }));
+        }
+        self.admin_delay.ready_at.delete(id);
+        log(self.vm(), AdminActionCancelled { id });
+        Ok(())
+    }
+
+    /// Time the action `id` can be executed from, or zero if it isn't scheduled.
+    fn admin_action_ready_at(&self, id: B256) -> U256 {
+        self.admin_delay.ready_at.get(id)
+    }
+
+    /// Hands ownership to `new_owner` (owner only, and delayed like the other owner actions).
+    fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(transferOwnershipCall::SELECTOR)?;
+        self.take_admin_action(AdminAction::TransferOwnership(new_owner))
+    }
+}
+
+/// ID of the action calling `data` with `salt`, `keccak256(data ++ salt)`.
+#[cfg(feature = "admin-delay")]
+pub fn admin_action_id(data: &[u8], salt: B256) -> B256 {
+    keccak256([data, salt.as_slice()].concat())
+}
+
+/// An owner call that waits for the delay, decoded from its calldata.
+#[cfg(feature = "admin-delay")]
+enum AdminAction {
+    SetUri(String),
+    SetTokenUris(Vec<U256>, Vec<String>),
+    SetSeriesUri(U256, U256, String),
+    TransferOwnership(Address),
+    Unpause,
+    SetAdminDelay(U256),
+    #[cfg(feature = "royalties")]
+    SetDefaultRoyalty(Address, U96),
+    #[cfg(feature = "royalties")]
+    DeleteDefaultRoyalty,
+    #[cfg(feature = "royalties")]
+    SetTokenRoyalty(U256, Address, U96),
+    #[cfg(feature = "royalties")]
+    ResetTokenRoyalty(U256),
+    #[cfg(feature = "transfer-hooks")]
+    SetTransferHook(Address, u64, bool),
+    #[cfg(feature = "modules")]
+    SetModule(Vec<FixedBytes<4>>, Address),
+    #[cfg(feature = "transfer-security")]
+    SetTransferSecurityLevel(u8),
+    #[cfg(feature = "transfer-security")]
+    SetAllowedOperator(Address, bool),
+}
+
+#[cfg(feature = "admin-delay")]
+impl AdminAction {
+    fn decode(data: &[u8]) -> Result<Self, My1155Error> {
+        #[cfg(feature = "royalties")]
+        use super::royalties::IERC2981::*;
+        #[cfg(feature = "transfer-hooks")]
+        use super::transfer_hooks::IERC1155TransferHooks::*;
+        #[cfg(feature = "modules")]
+        use super::modules::IERC1155Modules::*;
+        #[cfg(feature = "transfer-security")]
+        use super::transfer_security::IERC1155TransferSecurity::*;
+
+        let selector: [u8; 4] = data.get(..4).and_then(|s| s.try_into().ok()).unwrap_or_default();
+        let invalid = || My1155Error::InvalidAdminAction(ERC1155InvalidAdminAction { selector: FixedBytes(selector) });
+        let action = match selector {
+            setUriCall::SELECTOR => Self::SetUri(setUriCall::abi_decode(data, true).map_err(|_| invalid())?.newUri),
+            setTokenUrisCall::SELECTOR => {
+                let call = setTokenUrisCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetTokenUris(call.ids, call.uris)
+            }
+            setSeriesUriCall::SELECTOR => {
+                let call = setSeriesUriCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetSeriesUri(call.startId, call.endId, call.base)
+            }
+            transferOwnershipCall::SELECTOR => {
+                Self::TransferOwnership(transferOwnershipCall::abi_decode(data, true).map_err(|_| invalid())?.newOwner)
+            }
+            unpauseCall::SELECTOR if data.len() == 4 => Self::Unpause,
+            IERC1155AdminDelay::setAdminDelayCall::SELECTOR => Self::SetAdminDelay(
+                IERC1155AdminDelay::setAdminDelayCall::abi_decode(data, true).map_err(|_| invalid())?.delay,
+            ),
+            #[cfg(feature = "royalties")]
+            setDefaultRoyaltyCall::SELECTOR => {
+                let call = setDefaultRoyaltyCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetDefaultRoyalty(call.receiver, call.feeNumerator)
+            }
+            #[cfg(feature = "royalties")]
+            deleteDefaultRoyaltyCall::SELECTOR if data.len() == 4 => Self::DeleteDefaultRoyalty,
+            #[cfg(feature = "royalties")]
+            setTokenRoyaltyCall::SELECTOR => {
+                let call = setTokenRoyaltyCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetTokenRoyalty(call.tokenId, call.receiver, call.feeNumerator)
+            }
+            #[cfg(feature = "royalties")]
+            resetTokenRoyaltyCall::SELECTOR => {
+                Self::ResetTokenRoyalty(resetTokenRoyaltyCall::abi_decode(data, true).map_err(|_| invalid())?.tokenId)
+            }
+            #[cfg(feature = "transfer-hooks")]
+            setTransferHookCall::SELECTOR => {
+                let call = setTransferHookCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetTransferHook(call.hook, call.gasLimit, call.failClosed)
+            }
+            #[cfg(feature = "modules")]
+            setModuleCall::SELECTOR => {
+                let call = setModuleCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetModule(call.selectors, call.module)
+            }
+            #[cfg(feature = "transfer-security")]
+            setTransferSecurityLevelCall::SELECTOR => Self::SetTransferSecurityLevel(
+                setTransferSecurityLevelCall::abi_decode(data, true).map_err(|_| invalid())?.level,
+            ),
+            #[cfg(feature = "transfer-security")]
+            setAllowedOperatorCall::SELECTOR => {
+                let call = setAllowedOperatorCall::abi_decode(data, true).map_err(|_| invalid())?;
+                Self::SetAllowedOperator(call.operator, call.allowed)
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(action)
+    }
+}
+
+impl My1155 {
+    /// Fails if the owner must schedule the method with `selector` rather than call it.
+    #[cfg(feature = "admin-delay")]
+    pub(crate) fn check_admin_delay(&self, selector: [u8; 4]) -> Result<(), My1155Error> {
+        if self.admin_delay.delay.get().is_zero() {
+            return Ok(());
+        }
+        Err(My1155Error::AdminDelayRequired(ERC1155AdminDelayRequired { selector: FixedBytes(selector) }))
+    }
+
+    #[cfg(not(feature = "admin-delay"))]
+    #[inline(always)]
+    pub(crate) fn check_admin_delay(&self, _selector: [u8; 4]) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    /// Seconds an action scheduled now waits, the admin delay or at most
+    /// `MAX_TESTNET_ADMIN_DELAY` on a testnet build.
+    #[cfg(feature = "admin-delay")]
+    fn scheduled_admin_delay(&self) -> U256 {
+        let delay = self.admin_delay.delay.get();
+        match cfg!(feature = "testnet") {
+            true => delay.min(U256::from(super::testnet::MAX_TESTNET_ADMIN_DELAY)),
+            false => delay,
+        }
+    }
+
+    /// Takes an owner action, once the owner or the delay allowed it.
+    #[cfg(feature = "admin-delay")]
+    fn take_admin_action(&mut self, action: AdminAction) -> Result<(), My1155Error> {
+        match action {
+            AdminAction::SetUri(new_uri) => self._set_uri(new_uri)?,
+            AdminAction::SetTokenUris(ids, uris) => self._set_token_uris(ids, uris)?,
+            AdminAction::SetSeriesUri(start_id, end_id, base) => self._set_series_uri(start_id, end_id, base)?,
+            AdminAction::TransferOwnership(new_owner) => {
+                if new_owner.is_zero() {
+                    return Err(OwnableError::InvalidOwner(OwnableInvalidOwner { owner: new_owner }).into());
+                }
+                self.ownable._transfer_ownership(new_owner);
+            }
+            AdminAction::Unpause => self.pausable._unpause()?,
+            AdminAction::SetAdminDelay(delay) => {
+                self.admin_delay.delay.set(delay);
+                log(self.vm(), AdminDelaySet { delay });
+            }
+            #[cfg(feature = "royalties")]
+            AdminAction::SetDefaultRoyalty(receiver, fee_numerator) => {
+                self._set_default_royalty(receiver, fee_numerator)?
+            }
+            #[cfg(feature = "royalties")]
+            AdminAction::DeleteDefaultRoyalty => self._delete_default_royalty(),
+            #[cfg(feature = "royalties")]
+            AdminAction::SetTokenRoyalty(token_id, receiver, fee_numerator) => {
+                self._set_token_royalty(token_id, receiver, fee_numerator)?
+            }
+            #[cfg(feature = "royalties")]
+            AdminAction::ResetTokenRoyalty(token_id) => self._reset_token_royalty(token_id),
+            #[cfg(feature = "transfer-hooks")]
+            AdminAction::SetTransferHook(hook, gas_limit, fail_closed) => {
+                self._set_transfer_hook(hook, gas_limit, fail_closed)?
+            }
+            #[cfg(feature = "modules")]
+            AdminAction::SetModule(selectors, module) => self._set_module(selectors, module)?,
+            #[cfg(feature = "transfer-security")]
+            AdminAction::SetTransferSecurityLevel(level) => self._set_transfer_security_level(level)?,
+            #[cfg(feature = "transfer-security")]
+            AdminAction::SetAllowedOperator(operator, allowed) => self._set_allowed_operator(operator, allowed),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Operators each account has approved, with the `approval-index` feature.
+//!
+//! Keeps the list of operators each account has approved for all of its tokens, so a wallet can
+//! show them with `operatorsOf` without indexing `ApprovalForAll` events, and a holder whose
+//! marketplace was exploited can drop every approval at once with `revokeAllApprovals`. Each
+//! approval and revocation costs an extra few storage writes.
+
+#[cfg(feature = "approval-index")]
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{alloy_primitives::Address, alloy_sol_types::sol, prelude::*};
+#[cfg(feature = "approval-index")]
+use stylus_sdk::alloy_primitives::U256;
+
+use crate::My1155;
+#[cfg(feature = "approval-index")]
+use crate::My1155Error;
+
+/// [`IERC1155ApprovalIndex`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!("operatorsOf"(Address), "revokeAllApprovals"());
+
+sol_storage! {
+    pub struct ApprovalIndex {
+        /// Operators each account has approved
+        mapping(address => OperatorSet) operators;
+    }
+
+    pub struct OperatorSet {
+        address[] operators;
+        /// One more than the position of each operator in `operators`, zero for those not in it
+        mapping(address => uint256) positions;
+    }
+}
+
+sol! {
+    interface IERC1155ApprovalIndex {
+        function operatorsOf(address account) external view returns (address[] memory);
+        function revokeAllApprovals() external returns (uint256 revoked);
+    }
+}
+
+#[cfg(not(feature = "approval-index"))]
+disabled!(IApprovalIndex);
+
+/// The methods of [`IERC1155ApprovalIndex`].
+#[cfg(feature = "approval-index")]
+pub trait IApprovalIndex {
+    fn operators_of(&self, account: Address) -> Vec<Address>;
+    fn revoke_all_approvals(&mut self) -> Result<U256, My1155Error>;
+}
+
+#[cfg(feature = "approval-index")]
+#[public]
+impl IApprovalIndex for My1155 {
+    /// Operators approved for all of `account`'s tokens, in no particular order. Approvals that
+    /// have lapsed aren't listed.
+    fn operators_of(&self, account: Address) -> Vec<Address> {
+        let mut operators = self.indexed_operators(account);
+        operators.retain(|&operator| !self.approval_expired(account, operator));
+        operators
+    }
+
+    /// Revokes every operator the caller has approved, lapsed approvals included, logging an
+    /// `ApprovalForAll` for each. Returns how many were revoked.
+    fn revoke_all_approvals(&mut self) -> Result<U256, My1155Error> {
+        let account = self.vm().msg_sender();
+        let operators = self.indexed_operators(account);
+        for &operator in &operators {
+            self._set_approval_for_all(account, operator, false, U256::ZERO)?;
+        }
+        Ok(U256::from(operators.len()))
+    }
+}
+
+impl My1155 {
+    /// Adds `operator` to `owner`'s list if `approved` and removes it otherwise.
+    #[cfg(feature = "approval-index")]
+    pub(crate) fn index_approval(&mut self, owner: Address, operator: Address, approved: bool) {
+        self.approval_index.operators.setter(owner).update(operator, approved);
+    }
+
+    #[cfg(not(feature = "approval-index"))]
+    #[inline(always)]
+    pub(crate) fn index_approval(&mut self, _owner: Address, _operator: Address, _approved: bool) {}
+
+    /// Every operator in `account`'s list.
+    #[cfg(feature = "approval-index")]
+    fn indexed_operators(&self, account: Address) -> Vec<Address> {
+        let set = self.approval_index.operators.get(account);
+        (0..set.operators.len()).filter_map(|index| set.operators.get(index)).collect()
+    }
+}
+
+#[cfg(feature = "approval-index")]
+impl OperatorSet {
+    /// Adds `operator` if `present` and removes it otherwise, moving the last operator into its
+    /// place.
+    fn update(&mut self, operator: Address, present: bool) {
+        let position = self.positions.get(operator);
+        match (position.is_zero(), present) {
+            (true, true) => {
+                self.operators.push(operator);
+                self.positions.insert(operator, U256::from(self.operators.len()));
+            }
+            (false, false) => {
+                let last = self.operators.pop().unwrap_or_default();
+                if last != operator {
+                    if let Some(mut moved) = self.operators.setter(position - U256::from(1)) {
+                        moved.set(last);
+                    }
+                    self.positions.insert(last, position);
+                }
+                self.positions.delete(operator);
+            }
+            _ => {}
+        }
+    }
+}
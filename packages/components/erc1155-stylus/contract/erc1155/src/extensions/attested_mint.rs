@@ -0,0 +1,138 @@
+//! Allowlist phases of paid mints backed by attestations, with the `attested-mint` feature.
+//!
+//! Instead of a Merkle root of allowed accounts, the owner gives an ID on sale through `paid-mint`
+//! a gate: an `attestation-stylus` registry, a schema, such as passed KYC or completed a quest,
+//! and the attester it trusts. Until the gate's phase ends, `mintPaid` of the ID only mints to
+//! accounts holding a valid attestation of the schema from that attester, checked on-chain at
+//! mint time, so revoked and expired attestations stop counting at once. After the phase, the
+//! mint is public. The feature enables `paid-mint`.
+
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "attested-mint")]
+use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "paid-mint")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155AttestedMint`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setMintGate"(U256, Address, B256, Address, U256),
+    "mintGate"(U256),
+    "canMint"(Address, U256),
+);
+
+sol_storage! {
+    pub struct AttestedMint {
+        /// Gate on each ID's paid mints
+        mapping(uint256 => MintGate) gates;
+    }
+
+    pub struct MintGate {
+        /// `attestation-stylus` registry checked, zero for IDs without a gate
+        address registry;
+        bytes32 schema;
+        address attester;
+        /// Time the allowlist phase ends and the mint turns public, zero for a phase without end
+        uint256 until;
+    }
+}
+
+sol! {
+    interface IERC1155AttestedMint {
+        function setMintGate(uint256 id, address registry, bytes32 schema, address attester, uint256 until) external;
+        function mintGate(uint256 id) external view returns (address registry, bytes32 schema, address attester, uint256 until);
+        function canMint(address account, uint256 id) external view returns (bool);
+    }
+
+    interface IAttestationRegistry {
+        function hasValidAttestation(bytes32 schema, address recipient, address attester) external view returns (bool);
+    }
+
+    event MintGateSet(uint256 indexed id, address registry, bytes32 schema, address attester, uint256 until);
+
+    error ERC1155MissingAttestation(address account, uint256 id, bytes32 schema);
+}
+
+#[cfg(not(feature = "attested-mint"))]
+disabled!(IAttestedMint);
+
+/// The methods of [`IERC1155AttestedMint`].
+#[cfg(feature = "attested-mint")]
+pub trait IAttestedMint {
+    fn set_mint_gate(
+        &mut self,
+        id: U256,
+        registry: Address,
+        schema: B256,
+        attester: Address,
+        until: U256,
+    ) -> Result<(), My1155Error>;
+    fn mint_gate(&self, id: U256) -> (Address, B256, Address, U256);
+    fn can_mint(&self, account: Address, id: U256) -> bool;
+}
+
+#[cfg(feature = "attested-mint")]
+#[public]
+impl IAttestedMint for My1155 {
+    /// Gates `id`'s paid mints on `attester`'s attestations of `schema` in `registry` until
+    /// `until`, zero for no end, or removes the gate with a zero `registry` (owner only).
+    fn set_mint_gate(
+        &mut self,
+        id: U256,
+        registry: Address,
+        schema: B256,
+        attester: Address,
+        until: U256,
+    ) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let mut gate = self.attested_mint.gates.setter(id);
+        gate.registry.set(registry);
+        gate.schema.set(schema);
+        gate.attester.set(attester);
+        gate.until.set(until);
+        log(self.vm(), MintGateSet { id, registry, schema, attester, until });
+        Ok(())
+    }
+
+    /// The registry, schema, attester and phase end of `id`'s gate.
+    fn mint_gate(&self, id: U256) -> (Address, B256, Address, U256) {
+        let gate = self.attested_mint.gates.getter(id);
+        (gate.registry.get(), gate.schema.get(), gate.attester.get(), gate.until.get())
+    }
+
+    /// Whether `id`'s gate lets `account` receive its paid mints now.
+    fn can_mint(&self, account: Address, id: U256) -> bool {
+        let (registry, schema, attester, until) = self.mint_gate(id);
+        if registry.is_zero() || (!until.is_zero() && U256::from(self.vm().block_timestamp()) >= until) {
+            return true;
+        }
+        let query = IAttestationRegistry::hasValidAttestationCall { schema, recipient: account, attester };
+        self.vm()
+            .static_call(&calls::context::Call::new(), registry, &query.abi_encode())
+            .is_ok_and(|result| bool::abi_decode(&result, true).unwrap_or(false))
+    }
+}
+
+#[cfg(feature = "paid-mint")]
+impl My1155 {
+    /// Fails for paid mints of `id` to an account its gate doesn't admit yet.
+    #[cfg(feature = "attested-mint")]
+    pub(crate) fn check_mint_gate(&self, to: Address, id: U256) -> Result<(), My1155Error> {
+        if !self.can_mint(to, id) {
+            let schema = self.attested_mint.gates.getter(id).schema.get();
+            return Err(My1155Error::MissingAttestation(ERC1155MissingAttestation { account: to, id, schema }));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "attested-mint"))]
+    #[inline(always)]
+    pub(crate) fn check_mint_gate(&self, _to: Address, _id: U256) -> Result<(), My1155Error> {
+        Ok(())
+    }
+}
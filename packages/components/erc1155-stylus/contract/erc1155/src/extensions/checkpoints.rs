@@ -0,0 +1,195 @@
+//! Balance history for voting strategies, with the `checkpoints` feature.
+//!
+//! Every balance change records the new balance of each account involved, and mints and burns the
+//! new total supply, as a checkpoint of the block it happened in. `getPastBalance` and
+//! `getPastTotalSupply` then read an ID's holdings as they stood at the end of an earlier block,
+//! so a Snapshot strategy or a governor can count votes at a proposal's snapshot on-chain, and
+//! tokens moved or minted after it count for nothing. Lookups are a binary search of the history.
+//!
+//! Blocks are those of `block.number`, which on Arbitrum follows the L1 block number, and `clock`
+//! and `CLOCK_MODE` report them as ERC-6372 describes.
+
+#[cfg(feature = "checkpoints")]
+use alloc::string::String;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(feature = "checkpoints")]
+use stylus_sdk::{alloy_primitives::U64, storage::StorageVec};
+
+use crate::My1155;
+#[cfg(feature = "checkpoints")]
+use crate::My1155Error;
+
+/// [`IERC1155Checkpoints`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "getPastBalance"(Address, U256, U256),
+    "getPastTotalSupply"(U256, U256),
+    "numCheckpoints"(Address, U256),
+    "checkpointAt"(Address, U256, U256),
+    "clock"(),
+    "CLOCK_MODE"(),
+);
+
+/// ERC-6372 description of the clock.
+#[cfg(feature = "checkpoints")]
+pub const CLOCK_MODE: &str = "mode=blocknumber&from=default";
+
+sol_storage! {
+    pub struct Checkpoints {
+        /// History of each account's balance of each ID, oldest first
+        mapping(address => mapping(uint256 => Checkpoint[])) balances;
+        /// History of each ID's total supply, oldest first
+        mapping(uint256 => Checkpoint[]) supplies;
+    }
+
+    pub struct Checkpoint {
+        /// Block the value was set in, and holds from the end of
+        uint64 block;
+        uint256 value;
+    }
+}
+
+sol! {
+    interface IERC1155Checkpoints {
+        function getPastBalance(address account, uint256 id, uint256 blockNumber) external view returns (uint256);
+        function getPastTotalSupply(uint256 id, uint256 blockNumber) external view returns (uint256);
+        function numCheckpoints(address account, uint256 id) external view returns (uint256);
+        function checkpointAt(address account, uint256 id, uint256 index) external view returns (uint64 blockNumber, uint256 balance);
+        function clock() external view returns (uint64);
+        function CLOCK_MODE() external view returns (string memory);
+    }
+
+    error ERC1155FutureLookup(uint256 blockNumber, uint256 currentBlock);
+}
+
+#[cfg(not(feature = "checkpoints"))]
+disabled!(ICheckpoints);
+
+/// The methods of [`IERC1155Checkpoints`].
+#[cfg(feature = "checkpoints")]
+pub trait ICheckpoints {
+    fn get_past_balance(&self, account: Address, id: U256, block_number: U256) -> Result<U256, My1155Error>;
+    fn get_past_total_supply(&self, id: U256, block_number: U256) -> Result<U256, My1155Error>;
+    fn num_checkpoints(&self, account: Address, id: U256) -> U256;
+    fn checkpoint_at(&self, account: Address, id: U256, index: U256) -> (u64, U256);
+    fn clock(&self) -> u64;
+    fn clock_mode(&self) -> String;
+}
+
+#[cfg(feature = "checkpoints")]
+#[public]
+impl ICheckpoints for My1155 {
+    /// `account`'s balance of `id` at the end of block `block_number`, which must have passed.
+    fn get_past_balance(&self, account: Address, id: U256, block_number: U256) -> Result<U256, My1155Error> {
+        let block = self.past_block(block_number)?;
+        Ok(value_at(&self.checkpoints.balances.get(account).get(id), block))
+    }
+
+    /// `id`'s total supply at the end of block `block_number`, which must have passed.
+    fn get_past_total_supply(&self, id: U256, block_number: U256) -> Result<U256, My1155Error> {
+        let block = self.past_block(block_number)?;
+        Ok(value_at(&self.checkpoints.supplies.get(id), block))
+    }
+
+    /// Number of checkpoints of `account`'s balance of `id`.
+    fn num_checkpoints(&self, account: Address, id: U256) -> U256 {
+        U256::from(self.checkpoints.balances.get(account).get(id).len())
+    }
+
+    /// Block and balance of `account`'s checkpoint of `id` at `index`, zeros past the last one.
+    fn checkpoint_at(&self, account: Address, id: U256, index: U256) -> (u64, U256) {
+        let balances = self.checkpoints.balances.get(account);
+        let history = balances.get(id);
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| history.get(index))
+            .map_or((0, U256::ZERO), |checkpoint| (checkpoint.block.get().to::<u64>(), checkpoint.value.get()))
+    }
+
+    /// Current block, ERC-6372's clock.
+    fn clock(&self) -> u64 {
+        self.vm().block_number()
+    }
+
+    #[selector(name = "CLOCK_MODE")]
+    fn clock_mode(&self) -> String {
+        CLOCK_MODE.into()
+    }
+}
+
+impl My1155 {
+    /// Records the balances of `from` and `to`, and the supply on mints and burns, after balances
+    /// of `ids` moved, where a zero `from` mints and a zero `to` burns.
+    #[cfg(feature = "checkpoints")]
+    pub(crate) fn record_checkpoints(&mut self, from: Address, to: Address, ids: &[U256]) {
+        let block = self.vm().block_number();
+        for &id in ids {
+            if from.is_zero() || to.is_zero() {
+                let supply = self.erc1155.total_supply(id);
+                push(&mut self.checkpoints.supplies.setter(id), block, supply);
+            }
+            for account in [from, to] {
+                if !account.is_zero() {
+                    let balance = self.erc1155.balance_of(account, id);
+                    push(&mut self.checkpoints.balances.setter(account).setter(id), block, balance);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "checkpoints"))]
+    #[inline(always)]
+    pub(crate) fn record_checkpoints(&mut self, _from: Address, _to: Address, _ids: &[U256]) {}
+
+    /// `block_number` as a block whose history is final, failing for the current block or later.
+    #[cfg(feature = "checkpoints")]
+    pub(crate) fn past_block(&self, block_number: U256) -> Result<u64, My1155Error> {
+        let current = self.vm().block_number();
+        match u64::try_from(block_number) {
+            Ok(block) if block < current => Ok(block),
+            _ => Err(My1155Error::FutureLookup(ERC1155FutureLookup {
+                blockNumber: block_number,
+                currentBlock: U256::from(current),
+            })),
+        }
+    }
+}
+
+/// Appends `value` as of `block` to `history`, or replaces the last checkpoint if it's of the same
+/// block. Values that didn't change add nothing.
+#[cfg(feature = "checkpoints")]
+pub(crate) fn push(history: &mut StorageVec<Checkpoint>, block: u64, value: U256) {
+    if let Some(last) = history.len().checked_sub(1) {
+        let mut checkpoint = history.setter(last).expect("the last checkpoint exists");
+        if checkpoint.value.get() == value {
+            return;
+        }
+        if checkpoint.block.get().to::<u64>() == block {
+            checkpoint.value.set(value);
+            return;
+        }
+    }
+    let mut checkpoint = history.grow();
+    checkpoint.block.set(U64::from(block));
+    checkpoint.value.set(value);
+}
+
+/// The value of the last checkpoint in `history` from `block` or earlier, zero if there's none.
+#[cfg(feature = "checkpoints")]
+pub(crate) fn value_at(history: &StorageVec<Checkpoint>, block: u64) -> U256 {
+    let (mut low, mut high) = (0, history.len());
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let checkpoint = history.get(middle).expect("checkpoints below the length exist");
+        if checkpoint.block.get().to::<u64>() > block {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    high.checked_sub(1).and_then(|index| history.get(index)).map_or(U256::ZERO, |checkpoint| checkpoint.value.get())
+}
@@ -0,0 +1,211 @@
+//! Tokens that hold other tokens, ERC-998 style, with the `composable` feature.
+//!
+//! A parent is an ERC-721 token, or an ID of this contract with a supply of one, such as a
+//! `split-ids` item. Its owner attaches balances of this contract's IDs to it, a game
+//! character's equipped items, and they move into the contract's escrow under the parent. The
+//! children belong to the parent rather than to an account: whoever owns the parent can detach
+//! them, so transferring the parent transfers the whole bundle with it. A parent can be attached
+//! to another parent, and its own children then follow the outer one.
+
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "composable")]
+use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "composable")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155Composable`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "attachChild"(Address, U256, U256, U256),
+    "detachChild"(Address, U256, U256, U256),
+    "childBalanceOf"(Address, U256, U256),
+    "childIdsOf"(Address, U256),
+    "ownsParent"(Address, Address, U256),
+);
+/// Most different IDs attached to one parent.
+pub const MAX_CHILD_IDS: usize = 16;
+
+sol_storage! {
+    pub struct Composable {
+        /// Balances held for each parent, by parent contract, parent ID and child ID
+        mapping(address => mapping(uint256 => mapping(uint256 => uint256))) child_balances;
+        /// IDs with a balance held for each parent, by parent contract and parent ID
+        mapping(address => mapping(uint256 => uint256[])) child_ids;
+    }
+}
+
+sol! {
+    interface IERC1155Composable {
+        function attachChild(address parentContract, uint256 parentId, uint256 id, uint256 amount) external;
+        function detachChild(address parentContract, uint256 parentId, uint256 id, uint256 amount) external;
+        function childBalanceOf(address parentContract, uint256 parentId, uint256 id) external view returns (uint256);
+        function childIdsOf(address parentContract, uint256 parentId) external view returns (uint256[] memory);
+        function ownsParent(address account, address parentContract, uint256 parentId) external view returns (bool);
+    }
+
+    interface IERC721 {
+        function ownerOf(uint256 tokenId) external view returns (address);
+    }
+
+    event ChildAttached(
+        address indexed parentContract, uint256 indexed parentId, uint256 indexed id, uint256 amount, address from
+    );
+    event ChildDetached(
+        address indexed parentContract, uint256 indexed parentId, uint256 indexed id, uint256 amount, address to
+    );
+
+    error ERC1155NotParentOwner(address parentContract, uint256 parentId, address account);
+    error ERC1155InvalidChild(address parentContract, uint256 parentId, uint256 id);
+    error ERC1155InsufficientChildBalance(uint256 parentId, uint256 id, uint256 balance, uint256 needed);
+    error ERC1155TooManyChildIds(address parentContract, uint256 parentId);
+}
+
+#[cfg(not(feature = "composable"))]
+disabled!(IComposable);
+
+/// The methods of [`IERC1155Composable`].
+#[cfg(feature = "composable")]
+pub trait IComposable {
+    fn attach_child(&mut self, parent_contract: Address, parent_id: U256, id: U256, amount: U256)
+        -> Result<(), My1155Error>;
+    fn detach_child(&mut self, parent_contract: Address, parent_id: U256, id: U256, amount: U256)
+        -> Result<(), My1155Error>;
+    fn child_balance_of(&self, parent_contract: Address, parent_id: U256, id: U256) -> U256;
+    fn child_ids_of(&self, parent_contract: Address, parent_id: U256) -> Vec<U256>;
+    fn owns_parent(&self, account: Address, parent_contract: Address, parent_id: U256) -> bool;
+}
+
+#[cfg(feature = "composable")]
+#[public]
+impl IComposable for My1155 {
+    /// Moves `amount` of the caller's `id` into escrow under a parent the caller owns. A parent
+    /// can't hold itself.
+    fn attach_child(
+        &mut self,
+        parent_contract: Address,
+        parent_id: U256,
+        id: U256,
+        amount: U256,
+    ) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.check_transferable()?;
+        let (from, escrow) = (self.vm().msg_sender(), self.vm().contract_address());
+        self.require_parent_owner(from, parent_contract, parent_id)?;
+        if parent_contract == escrow && parent_id == id {
+            return Err(My1155Error::InvalidChild(ERC1155InvalidChild {
+                parentContract: parent_contract,
+                parentId: parent_id,
+                id,
+            }));
+        }
+        self.erc1155._update_single(from, escrow, id, amount)?;
+        self.after_update(from, escrow, &[id])?;
+
+        let balance = self.child_balance_of(parent_contract, parent_id, id);
+        if balance.is_zero() && !amount.is_zero() {
+            let mut ids = self.composable.child_ids.setter(parent_contract);
+            let mut ids = ids.setter(parent_id);
+            if ids.len() == MAX_CHILD_IDS {
+                return Err(My1155Error::TooManyChildIds(ERC1155TooManyChildIds {
+                    parentContract: parent_contract,
+                    parentId: parent_id,
+                }));
+            }
+            ids.push(id);
+        }
+        let mut balances = self.composable.child_balances.setter(parent_contract);
+        balances.setter(parent_id).insert(id, balance + amount);
+        log(self.vm(), ChildAttached { parentContract: parent_contract, parentId: parent_id, id, amount, from });
+        Ok(())
+    }
+
+    /// Moves `amount` of a parent's `id` out of escrow to the caller, who must own the parent.
+    fn detach_child(
+        &mut self,
+        parent_contract: Address,
+        parent_id: U256,
+        id: U256,
+        amount: U256,
+    ) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let (to, escrow) = (self.vm().msg_sender(), self.vm().contract_address());
+        self.require_parent_owner(to, parent_contract, parent_id)?;
+        let balance = self.child_balance_of(parent_contract, parent_id, id);
+        if balance < amount {
+            return Err(My1155Error::InsufficientChildBalance(ERC1155InsufficientChildBalance {
+                parentId: parent_id,
+                id,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        let mut balances = self.composable.child_balances.setter(parent_contract);
+        balances.setter(parent_id).insert(id, balance - amount);
+        if balance == amount && !amount.is_zero() {
+            let mut ids = self.composable.child_ids.setter(parent_contract);
+            let mut ids = ids.setter(parent_id);
+            let last = ids.len() - 1;
+            let index = (0..ids.len()).find(|&i| ids.get(i) == Some(id)).expect("held IDs are listed");
+            let moved = ids.get(last).expect("the list isn't empty");
+            ids.setter(index).expect("index is in bounds").set(moved);
+            ids.pop();
+        }
+        self.erc1155._update_single(escrow, to, id, amount)?;
+        self.after_update(escrow, to, &[id])?;
+        log(self.vm(), ChildDetached { parentContract: parent_contract, parentId: parent_id, id, amount, to });
+        Ok(())
+    }
+
+    /// Balance of `id` held for a parent.
+    fn child_balance_of(&self, parent_contract: Address, parent_id: U256, id: U256) -> U256 {
+        self.composable.child_balances.getter(parent_contract).getter(parent_id).get(id)
+    }
+
+    /// IDs with a balance held for a parent, in no particular order.
+    fn child_ids_of(&self, parent_contract: Address, parent_id: U256) -> Vec<U256> {
+        let ids = self.composable.child_ids.getter(parent_contract);
+        let ids = ids.getter(parent_id);
+        (0..ids.len()).filter_map(|i| ids.get(i)).collect()
+    }
+
+    /// Whether `account` owns a parent: the ERC-721's `ownerOf`, or the whole supply of one of
+    /// this contract's IDs.
+    fn owns_parent(&self, account: Address, parent_contract: Address, parent_id: U256) -> bool {
+        if parent_contract == self.vm().contract_address() {
+            return self.erc1155.total_supply(parent_id) == U256::from(1)
+                && self.erc1155.balance_of(account, parent_id) == U256::from(1);
+        }
+        let query = IERC721::ownerOfCall { tokenId: parent_id }.abi_encode();
+        self.vm()
+            .static_call(&calls::context::Call::new(), parent_contract, &query)
+            .ok()
+            .and_then(|result| Address::abi_decode(&result, true).ok())
+            .is_some_and(|owner| !account.is_zero() && owner == account)
+    }
+}
+
+#[cfg(feature = "composable")]
+impl My1155 {
+    fn require_parent_owner(
+        &self,
+        account: Address,
+        parent_contract: Address,
+        parent_id: U256,
+    ) -> Result<(), My1155Error> {
+        if !self.owns_parent(account, parent_contract, parent_id) {
+            return Err(My1155Error::NotParentOwner(ERC1155NotParentOwner {
+                parentContract: parent_contract,
+                parentId: parent_id,
+                account,
+            }));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! Enumeration of token IDs, with the `enumerable` feature.
+//!
+//! Keeps the IDs with a nonzero total supply, and for each account the IDs it has a balance of,
+//! so frontends can list a collection and a holder's tokens without indexing events. Each mint,
+//! burn and transfer that takes a balance or a supply to or from zero costs an extra few storage
+//! writes.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::My1155;
+#[cfg(feature = "enumerable")]
+use crate::My1155Error;
+
+/// [`IERC1155Enumerable`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "tokenCount"(),
+    "tokenByIndex"(U256),
+    "tokenCountOf"(Address),
+    "tokenOfOwnerByIndex"(Address, U256),
+);
+
+sol_storage! {
+    pub struct Enumerable {
+        /// IDs with a nonzero total supply
+        IdSet ids;
+        /// IDs each account has a nonzero balance of
+        mapping(address => IdSet) owned_ids;
+    }
+
+    pub struct IdSet {
+        uint256[] ids;
+        /// One more than the position of each ID in `ids`, zero for IDs that aren't in it
+        mapping(uint256 => uint256) positions;
+    }
+}
+
+sol! {
+    interface IERC1155Enumerable {
+        function tokenCount() external view returns (uint256);
+        function tokenByIndex(uint256 index) external view returns (uint256);
+        function tokenCountOf(address owner) external view returns (uint256);
+        function tokenOfOwnerByIndex(address owner, uint256 index) external view returns (uint256);
+    }
+
+    error ERC1155OutOfBoundsIndex(address owner, uint256 index);
+}
+
+#[cfg(not(feature = "enumerable"))]
+disabled!(IEnumerable);
+
+/// The methods of [`IERC1155Enumerable`].
+#[cfg(feature = "enumerable")]
+pub trait IEnumerable {
+    fn token_count(&self) -> U256;
+    fn token_by_index(&self, index: U256) -> Result<U256, My1155Error>;
+    fn token_count_of(&self, owner: Address) -> U256;
+    fn token_of_owner_by_index(&self, owner: Address, index: U256) -> Result<U256, My1155Error>;
+}
+
+#[cfg(feature = "enumerable")]
+#[public]
+impl IEnumerable for My1155 {
+    /// Number of IDs with a nonzero total supply.
+    fn token_count(&self) -> U256 {
+        U256::from(self.enumerable.ids.ids.len())
+    }
+
+    /// The ID at `index` of those with a nonzero total supply, in no particular order.
+    fn token_by_index(&self, index: U256) -> Result<U256, My1155Error> {
+        self.enumerable.ids.at(index).ok_or_else(|| out_of_bounds(Address::ZERO, index))
+    }
+
+    /// Number of IDs `owner` has a nonzero balance of.
+    fn token_count_of(&self, owner: Address) -> U256 {
+        U256::from(self.enumerable.owned_ids.get(owner).ids.len())
+    }
+
+    /// The ID at `index` of those `owner` has a nonzero balance of, in no particular order.
+    fn token_of_owner_by_index(&self, owner: Address, index: U256) -> Result<U256, My1155Error> {
+        self.enumerable.owned_ids.get(owner).at(index).ok_or_else(|| out_of_bounds(owner, index))
+    }
+}
+
+#[cfg(feature = "enumerable")]
+fn out_of_bounds(owner: Address, index: U256) -> My1155Error {
+    My1155Error::OutOfBoundsIndex(ERC1155OutOfBoundsIndex { owner, index })
+}
+
+impl My1155 {
+    /// Updates the lists after balances of `ids` moved from `from` to `to`, where a zero `from`
+    /// mints and a zero `to` burns.
+    #[cfg(feature = "enumerable")]
+    pub(crate) fn track_ids(&mut self, from: Address, to: Address, ids: &[U256]) {
+        for &id in ids {
+            if from.is_zero() || to.is_zero() {
+                let in_circulation = self.erc1155.exists(id);
+                self.enumerable.ids.update(id, in_circulation);
+            }
+            for account in [from, to] {
+                if !account.is_zero() {
+                    let held = !self.erc1155.balance_of(account, id).is_zero();
+                    self.enumerable.owned_ids.setter(account).update(id, held);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "enumerable"))]
+    #[inline(always)]
+    pub(crate) fn track_ids(&mut self, _from: Address, _to: Address, _ids: &[U256]) {}
+}
+
+#[cfg(feature = "enumerable")]
+impl IdSet {
+    fn at(&self, index: U256) -> Option<U256> {
+        self.ids.get(index)
+    }
+
+    /// Adds `id` if `present` and removes it otherwise, moving the last ID into its place.
+    fn update(&mut self, id: U256, present: bool) {
+        let position = self.positions.get(id);
+        match (position.is_zero(), present) {
+            (true, true) => {
+                self.ids.push(id);
+                self.positions.insert(id, U256::from(self.ids.len()));
+            }
+            (false, false) => {
+                let last = self.ids.pop().unwrap_or_default();
+                if last != id {
+                    if let Some(mut moved) = self.ids.setter(position - U256::from(1)) {
+                        moved.set(last);
+                    }
+                    self.positions.insert(last, position);
+                }
+                self.positions.delete(id);
+            }
+            _ => {}
+        }
+    }
+}
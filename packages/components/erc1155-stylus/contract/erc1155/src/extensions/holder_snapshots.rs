@@ -0,0 +1,252 @@
+//! Frozen holder lists for airdrops and payouts, with the `holder-snapshots` feature.
+//!
+//! Keeps the accounts with a nonzero balance of each ID, and `snapshotHolders` copies an ID's
+//! holders and their balances into a snapshot as they stand at that point of the block, sorted by
+//! address. A raffle drawing among holders or a distributor paying them pro rata reads the
+//! snapshot page by page with `holdersAt`, or an account's share with `balanceAtSnapshot`, so
+//! tokens moved after the announcement change nothing. Each mint, burn and transfer that takes a
+//! balance to or from zero costs an extra few storage writes, and a snapshot two storage writes
+//! per holder, which bounds the holders one snapshot can take by the block's gas limit to a few
+//! hundred.
+
+#[cfg(feature = "holder-snapshots")]
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(feature = "holder-snapshots")]
+use stylus_sdk::alloy_primitives::U64;
+
+use crate::My1155;
+#[cfg(feature = "holder-snapshots")]
+use crate::My1155Error;
+
+/// [`IERC1155HolderSnapshots`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "snapshotHolders"(U256),
+    "snapshot"(U256),
+    "holdersAt"(U256, U256, U256),
+    "balanceAtSnapshot"(U256, Address),
+    "snapshotCount"(),
+    "holderCount"(U256),
+);
+
+sol_storage! {
+    pub struct HolderSnapshots {
+        /// Accounts with a nonzero balance of each ID
+        mapping(uint256 => HolderSet) holders;
+        /// Snapshots taken, the ID of the next one
+        uint256 snapshot_count;
+        mapping(uint256 => Snapshot) snapshots;
+    }
+
+    pub struct HolderSet {
+        address[] holders;
+        /// One more than the position of each holder in `holders`, zero for those not in it
+        mapping(address => uint256) positions;
+    }
+
+    pub struct Snapshot {
+        uint256 id;
+        /// Block the snapshot was taken in
+        uint64 block;
+        uint256 total_supply;
+        /// The ID's holders then, sorted by address
+        Holding[] holdings;
+    }
+
+    pub struct Holding {
+        address holder;
+        uint256 balance;
+    }
+}
+
+sol! {
+    interface IERC1155HolderSnapshots {
+        function snapshotHolders(uint256 id) external returns (uint256 snapshotId);
+        function snapshot(uint256 snapshotId) external view returns (uint256 id, uint64 blockNumber, uint256 holderCount, uint256 totalSupply);
+        function holdersAt(uint256 snapshotId, uint256 start, uint256 count) external view returns (address[] memory holders, uint256[] memory balances);
+        function balanceAtSnapshot(uint256 snapshotId, address account) external view returns (uint256);
+        function snapshotCount() external view returns (uint256);
+        function holderCount(uint256 id) external view returns (uint256);
+    }
+
+    event HoldersSnapshotted(uint256 indexed snapshotId, uint256 indexed id, uint64 blockNumber, uint256 holderCount);
+
+    error ERC1155UnknownSnapshot(uint256 snapshotId);
+}
+
+#[cfg(not(feature = "holder-snapshots"))]
+disabled!(IHolderSnapshots);
+
+/// The methods of [`IERC1155HolderSnapshots`].
+#[cfg(feature = "holder-snapshots")]
+pub trait IHolderSnapshots {
+    fn snapshot_holders(&mut self, id: U256) -> Result<U256, My1155Error>;
+    fn snapshot(&self, snapshot_id: U256) -> Result<(U256, u64, U256, U256), My1155Error>;
+    fn holders_at(
+        &self,
+        snapshot_id: U256,
+        start: U256,
+        count: U256,
+    ) -> Result<(Vec<Address>, Vec<U256>), My1155Error>;
+    fn balance_at_snapshot(&self, snapshot_id: U256, account: Address) -> Result<U256, My1155Error>;
+    fn snapshot_count(&self) -> U256;
+    fn holder_count(&self, id: U256) -> U256;
+}
+
+#[cfg(feature = "holder-snapshots")]
+#[public]
+impl IHolderSnapshots for My1155 {
+    /// Freezes the holders of `id` and their balances as they stand now, returning the snapshot
+    /// ID (owner only).
+    fn snapshot_holders(&mut self, id: U256) -> Result<U256, My1155Error> {
+        self.only_owner()?;
+        let mut holdings: Vec<(Address, U256)> = {
+            let set = self.holder_snapshots.holders.get(id);
+            (0..set.holders.len())
+                .filter_map(|index| set.holders.get(index))
+                .map(|holder| (holder, self.erc1155.balance_of(holder, id)))
+                .collect()
+        };
+        holdings.sort_unstable_by_key(|&(holder, _)| holder);
+
+        let snapshot_id = self.holder_snapshots.snapshot_count.get();
+        self.holder_snapshots.snapshot_count.set(snapshot_id + U256::from(1));
+        let block = self.vm().block_number();
+        let total_supply = self.erc1155.total_supply(id);
+        let mut snapshot = self.holder_snapshots.snapshots.setter(snapshot_id);
+        snapshot.id.set(id);
+        snapshot.block.set(U64::from(block));
+        snapshot.total_supply.set(total_supply);
+        for &(holder, balance) in &holdings {
+            let mut holding = snapshot.holdings.grow();
+            holding.holder.set(holder);
+            holding.balance.set(balance);
+        }
+
+        log(self.vm(), HoldersSnapshotted {
+            snapshotId: snapshot_id,
+            id,
+            blockNumber: block,
+            holderCount: U256::from(holdings.len()),
+        });
+        Ok(snapshot_id)
+    }
+
+    /// The ID a snapshot is of, the block it was taken in, how many holders it lists and the ID's
+    /// total supply then.
+    fn snapshot(&self, snapshot_id: U256) -> Result<(U256, u64, U256, U256), My1155Error> {
+        self.check_snapshot(snapshot_id)?;
+        let snapshot = self.holder_snapshots.snapshots.get(snapshot_id);
+        Ok((
+            snapshot.id.get(),
+            snapshot.block.get().to::<u64>(),
+            U256::from(snapshot.holdings.len()),
+            snapshot.total_supply.get(),
+        ))
+    }
+
+    /// Up to `count` of a snapshot's holders from position `start`, by address, with their
+    /// balances. Pages past the end are short or empty.
+    fn holders_at(
+        &self,
+        snapshot_id: U256,
+        start: U256,
+        count: U256,
+    ) -> Result<(Vec<Address>, Vec<U256>), My1155Error> {
+        self.check_snapshot(snapshot_id)?;
+        let snapshot = self.holder_snapshots.snapshots.get(snapshot_id);
+        let holdings = &snapshot.holdings;
+        let start = usize::try_from(start).unwrap_or(usize::MAX).min(holdings.len());
+        let end = start.saturating_add(usize::try_from(count).unwrap_or(usize::MAX)).min(holdings.len());
+        Ok((start..end)
+            .filter_map(|index| holdings.get(index))
+            .map(|holding| (holding.holder.get(), holding.balance.get()))
+            .unzip())
+    }
+
+    /// `account`'s balance in a snapshot, zero if it held none of the ID then.
+    fn balance_at_snapshot(&self, snapshot_id: U256, account: Address) -> Result<U256, My1155Error> {
+        self.check_snapshot(snapshot_id)?;
+        let snapshot = self.holder_snapshots.snapshots.get(snapshot_id);
+        let holdings = &snapshot.holdings;
+        let (mut low, mut high) = (0, holdings.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let holding = holdings.get(middle).expect("holdings below the length exist");
+            match holding.holder.get().cmp(&account) {
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+                core::cmp::Ordering::Equal => return Ok(holding.balance.get()),
+            }
+        }
+        Ok(U256::ZERO)
+    }
+
+    /// Number of snapshots taken, of every ID.
+    fn snapshot_count(&self) -> U256 {
+        self.holder_snapshots.snapshot_count.get()
+    }
+
+    /// Number of accounts with a nonzero balance of `id` now, which a snapshot of it would list.
+    fn holder_count(&self, id: U256) -> U256 {
+        U256::from(self.holder_snapshots.holders.get(id).holders.len())
+    }
+}
+
+impl My1155 {
+    /// Updates the holders of `ids` after their balances moved from `from` to `to`, where a zero
+    /// `from` mints and a zero `to` burns.
+    #[cfg(feature = "holder-snapshots")]
+    pub(crate) fn track_holders(&mut self, from: Address, to: Address, ids: &[U256]) {
+        for &id in ids {
+            for account in [from, to] {
+                if !account.is_zero() {
+                    let held = !self.erc1155.balance_of(account, id).is_zero();
+                    self.holder_snapshots.holders.setter(id).update(account, held);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "holder-snapshots"))]
+    #[inline(always)]
+    pub(crate) fn track_holders(&mut self, _from: Address, _to: Address, _ids: &[U256]) {}
+
+    #[cfg(feature = "holder-snapshots")]
+    fn check_snapshot(&self, snapshot_id: U256) -> Result<(), My1155Error> {
+        if snapshot_id >= self.holder_snapshots.snapshot_count.get() {
+            return Err(My1155Error::UnknownSnapshot(ERC1155UnknownSnapshot { snapshotId: snapshot_id }));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "holder-snapshots")]
+impl HolderSet {
+    /// Adds `holder` if `present` and removes it otherwise, moving the last holder into its place.
+    fn update(&mut self, holder: Address, present: bool) {
+        let position = self.positions.get(holder);
+        match (position.is_zero(), present) {
+            (true, true) => {
+                self.holders.push(holder);
+                self.positions.insert(holder, U256::from(self.holders.len()));
+            }
+            (false, false) => {
+                let last = self.holders.pop().unwrap_or_default();
+                if last != holder {
+                    if let Some(mut moved) = self.holders.setter(position - U256::from(1)) {
+                        moved.set(last);
+                    }
+                    self.positions.insert(last, position);
+                }
+                self.positions.delete(holder);
+            }
+            _ => {}
+        }
+    }
+}
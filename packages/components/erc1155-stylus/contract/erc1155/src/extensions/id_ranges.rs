@@ -0,0 +1,146 @@
+//! Contiguous ID ranges reserved for creators, with the `id-ranges` feature.
+//!
+//! The owner reserves the next `count` IDs for a creator with `reserveIdRange`, which makes a
+//! series: ranges are numbered from 1 and follow each other from ID 1 up, so a collection can
+//! hold several collections without tracking their IDs off-chain. The creator of a series mints
+//! its IDs in order with `mintNew`, and `seriesOf` finds the series of an ID. Reservations only
+//! bind `mintNew`: the owner's `mint` can still mint any ID.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "id-ranges")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155IdRanges`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "reserveIdRange"(Address, U256),
+    "mintNew"(Address, U256, U256),
+    "seriesOf"(U256),
+    "series"(U256),
+    "seriesCount"(),
+);
+
+sol_storage! {
+    pub struct IdRanges {
+        /// Number of series reserved so far
+        uint256 count;
+        /// Creator of each series
+        mapping(uint256 => address) creator;
+        /// First ID of each series
+        mapping(uint256 => uint256) start;
+        /// Last ID of each series
+        mapping(uint256 => uint256) end;
+        /// Number of IDs of each series `mintNew` has minted
+        mapping(uint256 => uint256) minted;
+    }
+}
+
+sol! {
+    interface IERC1155IdRanges {
+        function reserveIdRange(address creator, uint256 count) external returns (uint256 start, uint256 end);
+        function mintNew(address to, uint256 series, uint256 amount) external returns (uint256 id);
+        function seriesOf(uint256 id) external view returns (uint256);
+        function series(uint256 series) external view returns (address creator, uint256 start, uint256 end, uint256 minted);
+        function seriesCount() external view returns (uint256);
+    }
+
+    event IdRangeReserved(uint256 indexed series, address indexed creator, uint256 start, uint256 end);
+
+    error ERC1155InvalidIdRange(address creator, uint256 count);
+    error ERC1155UnauthorizedSeriesMinter(uint256 series, address account);
+    error ERC1155SeriesExhausted(uint256 series);
+}
+
+#[cfg(not(feature = "id-ranges"))]
+disabled!(IIdRanges);
+
+/// The methods of [`IERC1155IdRanges`].
+#[cfg(feature = "id-ranges")]
+pub trait IIdRanges {
+    fn reserve_id_range(&mut self, creator: Address, count: U256) -> Result<(U256, U256), My1155Error>;
+    fn mint_new(&mut self, to: Address, series: U256, amount: U256) -> Result<U256, My1155Error>;
+    fn series_of(&self, id: U256) -> U256;
+    fn series(&self, series: U256) -> (Address, U256, U256, U256);
+    fn series_count(&self) -> U256;
+}
+
+#[cfg(feature = "id-ranges")]
+#[public]
+impl IIdRanges for My1155 {
+    /// Reserves the next `count` IDs for `creator` as a new series (owner only), and returns its
+    /// first and last ID.
+    fn reserve_id_range(&mut self, creator: Address, count: U256) -> Result<(U256, U256), My1155Error> {
+        self.only_owner()?;
+        let invalid = || My1155Error::InvalidIdRange(ERC1155InvalidIdRange { creator, count });
+        if creator.is_zero() || count.is_zero() {
+            return Err(invalid());
+        }
+        let series = self.id_ranges.count.get() + U256::from(1);
+        let start = self.id_ranges.end.get(series - U256::from(1)) + U256::from(1);
+        let end = start.checked_add(count - U256::from(1)).ok_or_else(invalid)?;
+
+        self.id_ranges.count.set(series);
+        self.id_ranges.creator.insert(series, creator);
+        self.id_ranges.start.insert(series, start);
+        self.id_ranges.end.insert(series, end);
+        log(self.vm(), IdRangeReserved { series, creator, start, end });
+        Ok((start, end))
+    }
+
+    /// Mints `amount` of the next ID of `series` to `to`, and returns the ID (creator of the
+    /// series only).
+    fn mint_new(&mut self, to: Address, series: U256, amount: U256) -> Result<U256, My1155Error> {
+        self.require_not_paused()?;
+        let account = self.vm().msg_sender();
+        if series.is_zero() || account != self.id_ranges.creator.get(series) {
+            return Err(My1155Error::UnauthorizedSeriesMinter(ERC1155UnauthorizedSeriesMinter { series, account }));
+        }
+        let minted = self.id_ranges.minted.get(series);
+        let id = self.id_ranges.start.get(series) + minted;
+        if id > self.id_ranges.end.get(series) {
+            return Err(My1155Error::SeriesExhausted(ERC1155SeriesExhausted { series }));
+        }
+        self.id_ranges.minted.insert(series, minted + U256::from(1));
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+        self.call_mint_hook(to, id, amount, Vec::new())?;
+        Ok(id)
+    }
+
+    /// Series `id` belongs to, or zero if it's outside every reserved range.
+    fn series_of(&self, id: U256) -> U256 {
+        // Ranges follow each other, so the series is the last one starting at or before `id`
+        let (mut low, mut high) = (U256::ZERO, self.id_ranges.count.get());
+        while low < high {
+            let middle = (low + high + U256::from(1)) >> 1;
+            if self.id_ranges.start.get(middle) <= id {
+                low = middle;
+            } else {
+                high = middle - U256::from(1);
+            }
+        }
+        if low.is_zero() || id > self.id_ranges.end.get(low) {
+            return U256::ZERO;
+        }
+        low
+    }
+
+    /// Creator, first ID, last ID and number of IDs minted of `series`.
+    fn series(&self, series: U256) -> (Address, U256, U256, U256) {
+        let ranges = &self.id_ranges;
+        (ranges.creator.get(series), ranges.start.get(series), ranges.end.get(series), ranges.minted.get(series))
+    }
+
+    /// Number of series reserved, which are numbered from 1.
+    fn series_count(&self) -> U256 {
+        self.id_ranges.count.get()
+    }
+}
@@ -0,0 +1,224 @@
+//! Peer-to-peer listings for ERC-20 prices, with the `market` feature.
+//!
+//! A holder lists an amount of an ID at a price per item in an ERC-20 token, and the items move
+//! into the contract's escrow until they're bought or the listing is cancelled, so a listing can
+//! always be filled. Buyers take any part of a listing and pay with `transferFrom`, after
+//! approving the price token to this contract. The owner can switch on a fee, in basis points of
+//! each sale, paid to a recipient of its choice; it's off until set. This is the simplest trading
+//! built into the token, for games that don't need offers, auctions or royalties from the
+//! `marketplace-stylus` component.
+//!
+//! Since the market pays no royalties, listings and sales are held to `transfer-security` as if
+//! the seller moved the items and the buyer then took them from the seller: at the allowlist levels
+//! only allowlisted buyers can buy, so a collection enforcing royalties can't be traded around them
+//! here. Cancelling returns the items whatever the level.
+
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "market")]
+use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "market")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155Market`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "list"(U256, U256, Address, U256),
+    "buy"(U256, U256),
+    "cancel"(U256),
+    "listing"(U256),
+    "setMarketFee"(Address, U256),
+    "marketFee"(),
+);
+/// Highest market fee, 10%.
+pub const MAX_MARKET_FEE_BPS: u64 = 1_000;
+#[cfg(feature = "market")]
+const BPS: u64 = 10_000;
+
+sol_storage! {
+    pub struct Market {
+        /// Receives the market fee
+        address fee_recipient;
+        /// Fee on each sale in basis points, zero while the fee is off
+        uint256 fee_bps;
+        /// Listings created, the ID of the next one
+        uint256 listing_count;
+        mapping(uint256 => Listing) listings;
+    }
+
+    pub struct Listing {
+        address seller;
+        uint256 id;
+        /// Items left in escrow, zero once bought out or cancelled
+        uint256 amount;
+        address price_token;
+        /// Price of each item in `price_token`
+        uint256 price;
+    }
+}
+
+sol! {
+    interface IERC1155Market {
+        function list(uint256 id, uint256 amount, address priceToken, uint256 price) external returns (uint256 listingId);
+        function buy(uint256 listingId, uint256 amount) external;
+        function cancel(uint256 listingId) external;
+        function listing(uint256 listingId) external view returns (address seller, uint256 id, uint256 amount, address priceToken, uint256 price);
+        function setMarketFee(address recipient, uint256 feeBps) external;
+        function marketFee() external view returns (address recipient, uint256 feeBps);
+    }
+
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    event Listed(
+        uint256 indexed listingId, address indexed seller, uint256 indexed id, uint256 amount, address priceToken, uint256 price
+    );
+    event ListingBought(uint256 indexed listingId, address indexed buyer, uint256 amount, uint256 paid, uint256 fee);
+    event ListingCancelled(uint256 indexed listingId);
+    event MarketFeeSet(address recipient, uint256 feeBps);
+
+    error ERC1155InvalidListing(uint256 amount, address priceToken);
+    error ERC1155InsufficientListing(uint256 listingId, uint256 available, uint256 requested);
+    error ERC1155NotSeller(uint256 listingId, address caller);
+    error ERC1155InvalidMarketFee(address recipient, uint256 feeBps);
+    error ERC1155PaymentFailed(address token, address to, uint256 amount);
+}
+
+#[cfg(not(feature = "market"))]
+disabled!(IMarket);
+
+/// The methods of [`IERC1155Market`].
+#[cfg(feature = "market")]
+pub trait IMarket {
+    fn list(&mut self, id: U256, amount: U256, price_token: Address, price: U256) -> Result<U256, My1155Error>;
+    fn buy(&mut self, listing_id: U256, amount: U256) -> Result<(), My1155Error>;
+    fn cancel(&mut self, listing_id: U256) -> Result<(), My1155Error>;
+    fn listing(&self, listing_id: U256) -> (Address, U256, U256, Address, U256);
+    fn set_market_fee(&mut self, recipient: Address, fee_bps: U256) -> Result<(), My1155Error>;
+    fn market_fee(&self) -> (Address, U256);
+}
+
+#[cfg(feature = "market")]
+#[public]
+impl IMarket for My1155 {
+    /// Escrows `amount` of the caller's `id` and lists them at `price` each in `price_token`,
+    /// returning the listing ID.
+    fn list(&mut self, id: U256, amount: U256, price_token: Address, price: U256) -> Result<U256, My1155Error> {
+        self.require_not_paused()?;
+        self.check_transferable()?;
+        // Sales of the whole listing must leave room to work out the fee
+        let total = price.checked_mul(amount).and_then(|total| total.checked_mul(U256::from(BPS)));
+        if amount.is_zero() || price_token.is_zero() || total.is_none() {
+            return Err(My1155Error::InvalidListing(ERC1155InvalidListing { amount, priceToken: price_token }));
+        }
+        let (seller, escrow) = (self.vm().msg_sender(), self.vm().contract_address());
+        self.check_transfer_security(seller, escrow)?;
+        self.erc1155._update_single(seller, escrow, id, amount)?;
+        self.after_update(seller, escrow, &[id])?;
+
+        let listing_id = self.market.listing_count.get();
+        self.market.listing_count.set(listing_id + U256::from(1));
+        let mut listing = self.market.listings.setter(listing_id);
+        listing.seller.set(seller);
+        listing.id.set(id);
+        listing.amount.set(amount);
+        listing.price_token.set(price_token);
+        listing.price.set(price);
+        log(self.vm(), Listed { listingId: listing_id, seller, id, amount, priceToken: price_token, price });
+        Ok(listing_id)
+    }
+
+    /// Buys `amount` of a listing's items, paying the seller and the market fee from the caller's
+    /// `price_token` balance. The sale counts against `rate-limits` as a transfer from the seller,
+    /// and `transfer-security` must let the caller transfer the seller's items.
+    fn buy(&mut self, listing_id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let listing = self.market.listings.getter(listing_id);
+        let (seller, id, available) = (listing.seller.get(), listing.id.get(), listing.amount.get());
+        let (price_token, price) = (listing.price_token.get(), listing.price.get());
+        if amount.is_zero() || amount > available {
+            return Err(My1155Error::InsufficientListing(ERC1155InsufficientListing {
+                listingId: listing_id,
+                available,
+                requested: amount,
+            }));
+        }
+        let (buyer, escrow) = (self.vm().msg_sender(), self.vm().contract_address());
+        self.check_transfer_security(seller, buyer)?;
+        self.check_rate_limits(seller, buyer, &[id], &[amount])?;
+        self.call_transfer_hooks(seller, buyer, &[id], &[amount])?;
+        self.market.listings.setter(listing_id).amount.set(available - amount);
+        self.erc1155._update_single(escrow, buyer, id, amount)?;
+        self.after_update(escrow, buyer, &[id])?;
+
+        let paid = price * amount;
+        let fee = paid * self.market.fee_bps.get() / U256::from(BPS);
+        self.pay_price(price_token, buyer, self.market.fee_recipient.get(), fee)?;
+        self.pay_price(price_token, buyer, seller, paid - fee)?;
+        log(self.vm(), ListingBought { listingId: listing_id, buyer, amount, paid, fee });
+        Ok(())
+    }
+
+    /// Returns a listing's remaining items to its seller (seller only), even while paused.
+    fn cancel(&mut self, listing_id: U256) -> Result<(), My1155Error> {
+        let listing = self.market.listings.getter(listing_id);
+        let (seller, id, amount) = (listing.seller.get(), listing.id.get(), listing.amount.get());
+        let caller = self.vm().msg_sender();
+        if caller != seller || amount.is_zero() {
+            return Err(My1155Error::NotSeller(ERC1155NotSeller { listingId: listing_id, caller }));
+        }
+        self.market.listings.setter(listing_id).amount.set(U256::ZERO);
+        let escrow = self.vm().contract_address();
+        self.erc1155._update_single(escrow, seller, id, amount)?;
+        self.after_update(escrow, seller, &[id])?;
+        log(self.vm(), ListingCancelled { listingId: listing_id });
+        Ok(())
+    }
+
+    /// A listing's seller, ID, items left, price token and price per item.
+    fn listing(&self, listing_id: U256) -> (Address, U256, U256, Address, U256) {
+        let listing = self.market.listings.getter(listing_id);
+        (listing.seller.get(), listing.id.get(), listing.amount.get(), listing.price_token.get(), listing.price.get())
+    }
+
+    /// Sets the fee on each sale and its recipient (owner only). A zero fee switches it off.
+    fn set_market_fee(&mut self, recipient: Address, fee_bps: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if fee_bps > U256::from(MAX_MARKET_FEE_BPS) || (recipient.is_zero() && !fee_bps.is_zero()) {
+            return Err(My1155Error::InvalidMarketFee(ERC1155InvalidMarketFee { recipient, feeBps: fee_bps }));
+        }
+        self.market.fee_recipient.set(recipient);
+        self.market.fee_bps.set(fee_bps);
+        log(self.vm(), MarketFeeSet { recipient, feeBps: fee_bps });
+        Ok(())
+    }
+
+    /// The market fee's recipient and basis points.
+    fn market_fee(&self) -> (Address, U256) {
+        (self.market.fee_recipient.get(), self.market.fee_bps.get())
+    }
+}
+
+#[cfg(feature = "market")]
+impl My1155 {
+    /// Moves `amount` of `token` from `from` to `to`, requiring the token to return `true`.
+    fn pay_price(&mut self, token: Address, from: Address, to: Address, amount: U256) -> Result<(), My1155Error> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let calldata = IERC20::transferFromCall { from, to, value: amount }.abi_encode();
+        let paid = self
+            .vm()
+            .call(&calls::context::Call::new(), token, &calldata)
+            .is_ok_and(|result| bool::abi_decode(&result, true).unwrap_or(false));
+        if !paid {
+            return Err(My1155Error::PaymentFailed(ERC1155PaymentFailed { token, to, amount }));
+        }
+        Ok(())
+    }
+}
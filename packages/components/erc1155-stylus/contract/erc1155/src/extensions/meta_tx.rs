@@ -0,0 +1,129 @@
+//! Signed approvals, with the `meta-tx` feature.
+//!
+//! A holder signs an EIP-712 `Permit` off-chain and anyone, such as a relayer or the operator
+//! itself, submits it with `permit`, so the holder approves an operator without sending a
+//! transaction or holding ETH for gas. The operator, a marketplace say, then moves the tokens.
+//!
+//! The SDK rejects calldata with trailing bytes, so the token can't read an ERC-2771 sender that
+//! a forwarder appends, and `forwarder-stylus` can't relay calls to it. Signed approvals give
+//! holders the gasless flow that matters for a token without it.
+//!
+//! A holder revokes permits they've signed but nobody has submitted with `invalidateNoncesUpTo`,
+//! which moves their nonce past them.
+
+use cradle_crypto::Nonces;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(feature = "meta-tx")]
+use stylus_sdk::alloy_primitives::{b256, B256};
+
+#[cfg(feature = "meta-tx")]
+use crate::{My1155, My1155Error};
+
+/// Name in the EIP-712 domain.
+pub const DOMAIN_NAME: &str = "Cradle ERC1155";
+/// Version in the EIP-712 domain.
+pub const DOMAIN_VERSION: &str = "1";
+/// `keccak256("Permit(address owner,address operator,bool approved,uint256 nonce,uint256 deadline)")`
+#[cfg(feature = "meta-tx")]
+pub const PERMIT_TYPEHASH: B256 = b256!("7bf72c3e57bc00556754c7454ead7d72d1c0e263909e7c643fbc0d1e0f3c3e74");
+
+/// [`IERC1155Permit`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "permit"(Address, Address, bool, U256, Bytes),
+    "nonces"(Address),
+    "DOMAIN_SEPARATOR"(),
+);
+
+sol_storage! {
+    pub struct MetaTx {
+        /// Next nonce each holder must sign over. Permits don't use its unordered nonces.
+        Nonces nonces;
+    }
+}
+
+sol! {
+    interface IERC1155Permit {
+        function permit(address owner, address operator, bool approved, uint256 deadline, bytes signature) external;
+        function nonces(address owner) external view returns (uint256);
+        function DOMAIN_SEPARATOR() external view returns (bytes32);
+    }
+
+    // Revokes permits, outside `IERC1155Permit` so its interface ID stays that of the standard methods
+    function invalidateNoncesUpTo(uint256 newNonce) external;
+
+    error ERC1155ExpiredSignature(uint256 deadline);
+    error ERC1155InvalidSignature(address owner);
+}
+
+#[cfg(not(feature = "meta-tx"))]
+disabled!(IMetaTx);
+
+/// The methods of [`IERC1155Permit`].
+#[cfg(feature = "meta-tx")]
+pub trait IMetaTx {
+    fn permit(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+        deadline: U256,
+        signature: Bytes,
+    ) -> Result<(), My1155Error>;
+    fn nonces(&self, owner: Address) -> U256;
+    fn domain_separator(&self) -> B256;
+    fn invalidate_nonces_up_to(&mut self, new_nonce: U256) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "meta-tx")]
+#[public]
+impl IMetaTx for My1155 {
+    /// Sets `operator`'s approval for all of `owner`'s tokens, as `owner` signed. Contract
+    /// accounts sign through ERC-1271.
+    fn permit(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+        deadline: U256,
+        signature: Bytes,
+    ) -> Result<(), My1155Error> {
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(My1155Error::ExpiredSignature(ERC1155ExpiredSignature { deadline }));
+        }
+        let nonce = self.meta_tx.nonces.use_nonce(owner);
+
+        let struct_hash = cradle_crypto::hash_struct(
+            self.vm(),
+            PERMIT_TYPEHASH,
+            (owner, operator, approved, nonce, deadline),
+        );
+        let digest = cradle_crypto::hash_typed_data(self.vm(), self.domain_separator(), struct_hash);
+        if !cradle_crypto::is_valid_signature_now(self.vm(), owner, digest, &signature) {
+            return Err(My1155Error::InvalidSignature(ERC1155InvalidSignature { owner }));
+        }
+        self._set_approval_for_all(owner, operator, approved, U256::ZERO)
+    }
+
+    /// Next nonce `owner` must sign a permit over.
+    fn nonces(&self, owner: Address) -> U256 {
+        self.meta_tx.nonces.nonces(owner)
+    }
+
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    fn domain_separator(&self) -> B256 {
+        cradle_crypto::domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Moves the caller's nonce forward to `new_nonce`, revoking the permits they signed over
+    /// earlier nonces.
+    fn invalidate_nonces_up_to(&mut self, new_nonce: U256) -> Result<(), My1155Error> {
+        let account = self.vm().msg_sender();
+        Ok(self.meta_tx.nonces.invalidate_up_to(account, new_nonce)?)
+    }
+}
@@ -0,0 +1,161 @@
+//! Optional extensions of the token, each behind a Cargo feature of the same name.
+//!
+//! - `royalties`: ERC-2981 royalty info, per token or by default
+//! - `enumerable`: lists of the IDs in circulation and of the IDs each account holds
+//! - `supply-cap`: a maximum supply per ID, which the owner can only lower
+//! - `soulbound`: tokens can be minted and burned but not transferred, reported through ERC-5633
+//! - `meta-tx`: EIP-712 signed approvals, so holders can approve an operator without paying gas
+//! - `paid-mint`: public mints for ETH, with revenue per ID and proceeds withdrawn by the owner or
+//!   each ID's creator, and optional refund windows with the payments held in escrow
+//! - `id-ranges`: contiguous ID ranges reserved for creators, who mint the IDs of their series in
+//!   order
+//! - `split-ids`: the EIP-1155 split ID convention, with non-fungible items minted within a type
+//! - `rate-limits`: caps on the volume minted and transferred of an ID per block and per hour, in
+//!   total and by each account
+//! - `admin-delay`: a delay on sensitive owner actions, which are scheduled and executed by anyone
+//!   once it has passed
+//! - `tiers`: membership tiers by the combined balance of chosen IDs, for other contracts to gate
+//!   features on
+//! - `validity`: a period each ID's balances count in, with expired balances burnable by anyone
+//!   for a bounty
+//! - `packs`: packs burned for weighted random drops, with commit-reveal randomness
+//! - `market`: escrowed listings of items for ERC-20 prices, with an optional fee on each sale
+//! - `composable`: balances attached to a parent token, ERC-998 style, which move with the parent
+//! - `attested-mint`: allowlist phases of `paid-mint` IDs open to accounts with an attestation of
+//!   a schema, checked against an `attestation-stylus` registry
+//! - `checkpoints`: the history of each account's balance of each ID, and of each ID's supply, for
+//!   voting strategies to read past holdings by block
+//! - `votes`: voting power holders delegate per ID without moving their tokens, with tallies
+//!   checkpointed by block
+//! - `approval-index`: the operators each account has approved, listed and revoked all at once
+//! - `transfer-hooks`: external contracts the owner attaches, called before transfers and mints
+//!   with a gas limit and a fail-open or fail-closed policy each
+//! - `modules`: selectors the owner routes to module contracts, other builds of the token that the
+//!   token delegates them to, so extensions can outgrow one contract's size limit
+//! - `holder-snapshots`: the holders of each ID, frozen with their balances by the owner for
+//!   airdrops, raffles and payouts
+//! - `testnet`: a public minting faucet, short admin delays and balance logs, for testnet builds
+//!   only
+//! - `transfer-security`: ERC-721C style levels restricting transfers to allowlisted operators or
+//!   away from contracts, so sales go through marketplaces that pay royalties
+//! - `reveal`: a provenance hash committed to before a drop mints and an offset shuffling its
+//!   metadata once revealed, for fair drops
+//! - `open-editions`: IDs minted without a limit for a set window, their supply capped at what was
+//!   minted once it closes
+//!
+//! Enabled extensions compose into the one `My1155` entrypoint, and disabled ones add no code.
+//! Each declares a Rust interface that `My1155` implements, which `#[implements]` routes to, and
+//! an empty interface of the same name when its feature is off, so the list never changes. Their
+//! storage is always part of `My1155`, after the token's own, so enabling an extension keeps the
+//! layout of existing fields and an upgraded deployment can turn it on.
+
+/// Declares `$interface` with no methods and routes nothing to it, for an extension whose
+/// feature is off.
+#[allow(unused_macros)]
+macro_rules! disabled {
+    ($interface:ident) => {
+        pub trait $interface {}
+
+        #[stylus_sdk::prelude::public]
+        impl $interface for crate::My1155 {}
+    };
+}
+
+use cradle_introspection::Interface;
+
+pub mod admin_delay;
+pub mod approval_index;
+pub mod attested_mint;
+pub mod checkpoints;
+pub mod composable;
+pub mod enumerable;
+pub mod holder_snapshots;
+pub mod id_ranges;
+pub mod market;
+pub mod meta_tx;
+pub mod modules;
+pub mod open_editions;
+pub mod packs;
+pub mod paid_mint;
+pub mod rate_limits;
+pub mod reveal;
+pub mod royalties;
+pub mod soulbound;
+pub mod split_ids;
+pub mod supply_cap;
+pub mod testnet;
+pub mod tiers;
+pub mod transfer_hooks;
+pub mod transfer_security;
+pub mod validity;
+pub mod votes;
+
+/// Extensions by feature name, and whether this build includes them.
+pub const EXTENSIONS: [(&str, bool); 26] = [
+    ("royalties", cfg!(feature = "royalties")),
+    ("enumerable", cfg!(feature = "enumerable")),
+    ("supply-cap", cfg!(feature = "supply-cap")),
+    ("soulbound", cfg!(feature = "soulbound")),
+    ("meta-tx", cfg!(feature = "meta-tx")),
+    ("paid-mint", cfg!(feature = "paid-mint")),
+    ("id-ranges", cfg!(feature = "id-ranges")),
+    ("split-ids", cfg!(feature = "split-ids")),
+    ("rate-limits", cfg!(feature = "rate-limits")),
+    ("admin-delay", cfg!(feature = "admin-delay")),
+    ("tiers", cfg!(feature = "tiers")),
+    ("validity", cfg!(feature = "validity")),
+    ("packs", cfg!(feature = "packs")),
+    ("market", cfg!(feature = "market")),
+    ("composable", cfg!(feature = "composable")),
+    ("attested-mint", cfg!(feature = "attested-mint")),
+    ("checkpoints", cfg!(feature = "checkpoints")),
+    ("votes", cfg!(feature = "votes")),
+    ("approval-index", cfg!(feature = "approval-index")),
+    ("transfer-hooks", cfg!(feature = "transfer-hooks")),
+    ("modules", cfg!(feature = "modules")),
+    ("holder-snapshots", cfg!(feature = "holder-snapshots")),
+    ("testnet", cfg!(feature = "testnet")),
+    ("transfer-security", cfg!(feature = "transfer-security")),
+    ("reveal", cfg!(feature = "reveal")),
+    ("open-editions", cfg!(feature = "open-editions")),
+];
+
+/// Names of the extensions this build includes.
+pub fn enabled() -> impl Iterator<Item = &'static str> {
+    EXTENSIONS.into_iter().filter(|(_, enabled)| *enabled).map(|(name, _)| name)
+}
+
+/// The interfaces of the extensions, in the order of [`EXTENSIONS`].
+const INTERFACES: [Interface; 26] = [
+    royalties::INTERFACE,
+    enumerable::INTERFACE,
+    supply_cap::INTERFACE,
+    soulbound::INTERFACE,
+    meta_tx::INTERFACE,
+    paid_mint::INTERFACE,
+    id_ranges::INTERFACE,
+    split_ids::INTERFACE,
+    rate_limits::INTERFACE,
+    admin_delay::INTERFACE,
+    tiers::INTERFACE,
+    validity::INTERFACE,
+    packs::INTERFACE,
+    market::INTERFACE,
+    composable::INTERFACE,
+    attested_mint::INTERFACE,
+    checkpoints::INTERFACE,
+    votes::INTERFACE,
+    approval_index::INTERFACE,
+    transfer_hooks::INTERFACE,
+    modules::INTERFACE,
+    holder_snapshots::INTERFACE,
+    testnet::INTERFACE,
+    transfer_security::INTERFACE,
+    reveal::INTERFACE,
+    open_editions::INTERFACE,
+];
+
+/// The ERC-165 interfaces of the extensions this build includes.
+pub fn interfaces() -> impl Iterator<Item = Interface> {
+    INTERFACES.into_iter().zip(EXTENSIONS).filter(|(_, (_, enabled))| *enabled).map(|(interface, _)| interface)
+}
@@ -0,0 +1,142 @@
+//! Selectors routed to separate module contracts, with the `modules` feature.
+//!
+//! As extensions accumulate, a build with all of them outgrows the 24 KiB ArbOS allows a
+//! contract. Under `modules` the token can leave extensions out and still serve them: the owner
+//! routes their selectors to a module, another deployment of this contract built with those
+//! extensions, and the token delegates each call to a routed selector to it. The module's code
+//! runs against the token's storage, with the caller and value of the original call, and since
+//! every build lays out the storage of all the extensions whether or not it enables them, the
+//! module reads and writes the same fields the token would.
+//!
+//! Only selectors the token doesn't implement itself reach the router, so a module can add methods
+//! but never replace one. A module can change any of the token's storage, so only the owner routes
+//! selectors, behind `admin-delay` if it's enabled, and a module must be a build of this contract
+//! or follow its layout. That includes building it with the same `packed-balances` setting as the
+//! token, since packed and unpacked balances live in different slots and a mismatched module would
+//! read and write the wrong ones. `supportsInterface` only reports the extensions of the token's
+//! own build.
+
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, FixedBytes},
+    alloy_sol_types::sol,
+    prelude::*,
+    ArbResult,
+};
+#[cfg(feature = "modules")]
+use stylus_sdk::alloy_sol_types::SolCall;
+
+use crate::My1155;
+#[cfg(feature = "modules")]
+use crate::My1155Error;
+
+/// [`IERC1155Modules`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setModule"(Vec<FixedBytes<4>>, Address),
+    "moduleOf"(FixedBytes<4>),
+    "routedSelectors"(),
+);
+
+sol_storage! {
+    pub struct Modules {
+        /// Module each routed selector is delegated to
+        mapping(bytes4 => address) routes;
+        /// Routed selectors, in the order they were first routed
+        bytes4[] selectors;
+    }
+}
+
+sol! {
+    interface IERC1155Modules {
+        function setModule(bytes4[] selectors, address module) external;
+        function moduleOf(bytes4 selector) external view returns (address);
+        function routedSelectors() external view returns (bytes4[] memory);
+    }
+
+    event ModuleSet(bytes4 indexed selector, address module);
+
+    // The module is a contract without code
+    error ERC1155InvalidModule(address module);
+    // No module is routed the selector, which the token doesn't implement either
+    error ERC1155UnknownSelector(bytes4 selector);
+}
+
+#[cfg(not(feature = "modules"))]
+disabled!(IModules);
+
+/// The methods of [`IERC1155Modules`].
+#[cfg(feature = "modules")]
+pub trait IModules {
+    fn set_module(&mut self, selectors: Vec<FixedBytes<4>>, module: Address) -> Result<(), My1155Error>;
+    fn module_of(&self, selector: FixedBytes<4>) -> Address;
+    fn routed_selectors(&self) -> Vec<FixedBytes<4>>;
+}
+
+#[cfg(feature = "modules")]
+#[public]
+impl IModules for My1155 {
+    /// Routes each of `selectors` to `module`, or stops routing them if `module` is zero (owner
+    /// only).
+    fn set_module(&mut self, selectors: Vec<FixedBytes<4>>, module: Address) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC1155Modules::setModuleCall::SELECTOR)?;
+        self._set_module(selectors, module)
+    }
+
+    /// Module `selector` is delegated to, zero if it isn't routed.
+    fn module_of(&self, selector: FixedBytes<4>) -> Address {
+        self.modules.routes.get(selector)
+    }
+
+    /// Routed selectors, in the order they were first routed.
+    fn routed_selectors(&self) -> Vec<FixedBytes<4>> {
+        let selectors = &self.modules.selectors;
+        (0..selectors.len()).filter_map(|index| selectors.get(index)).collect()
+    }
+}
+
+impl My1155 {
+    /// `setModule` without its checks, which `admin-delay` also calls once the action is due.
+    #[cfg(feature = "modules")]
+    pub(crate) fn _set_module(&mut self, selectors: Vec<FixedBytes<4>>, module: Address) -> Result<(), My1155Error> {
+        if !module.is_zero() && self.vm().code_size(module) == 0 {
+            return Err(My1155Error::InvalidModule(ERC1155InvalidModule { module }));
+        }
+        for selector in selectors {
+            let routed = !self.modules.routes.get(selector).is_zero();
+            if module.is_zero() && routed {
+                let list = &mut self.modules.selectors;
+                let index = (0..list.len()).find(|&index| list.get(index) == Some(selector)).unwrap_or_default();
+                let last = list.pop().unwrap_or_default();
+                if let Some(mut moved) = list.setter(index) {
+                    moved.set(last);
+                }
+            } else if !module.is_zero() && !routed {
+                self.modules.selectors.push(selector);
+            }
+            self.modules.routes.insert(selector, module);
+            log(self.vm(), ModuleSet { selector, module });
+        }
+        Ok(())
+    }
+
+    /// Delegates `calldata` to the module its selector is routed to, returning the module's output
+    /// or revert data.
+    #[cfg(feature = "modules")]
+    pub(crate) fn route_to_module(&mut self, calldata: &[u8]) -> ArbResult {
+        let selector = FixedBytes(calldata.get(..4).and_then(|s| s.try_into().ok()).unwrap_or_default());
+        let module = self.modules.routes.get(selector);
+        if module.is_zero() {
+            return Err(My1155Error::UnknownSelector(ERC1155UnknownSelector { selector }).into());
+        }
+        unsafe { self.vm().delegate_call(&calls::context::Call::new(), module, calldata) }.map_err(Vec::<u8>::from)
+    }
+
+    /// Reverts with no data, as the token does for selectors it doesn't know without a fallback.
+    #[cfg(not(feature = "modules"))]
+    #[inline(always)]
+    pub(crate) fn route_to_module(&mut self, _calldata: &[u8]) -> ArbResult {
+        Err(Vec::new())
+    }
+}
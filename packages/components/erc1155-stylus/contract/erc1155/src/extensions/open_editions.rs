@@ -0,0 +1,168 @@
+//! Open editions, IDs minted without a limit for a set window, with the `open-editions` feature.
+//!
+//! The owner opens an edition of an ID from `start` to `end`. Mints of the ID, through every
+//! minting path, only go through inside the window, with no cap but those of `supply-cap` and
+//! `rate-limits`, and the edition's size is whatever was minted by the time it closes. After
+//! `end` the ID can never be minted again. Anyone can then call `finalizeOpenEdition`, which logs
+//! the size and sets it as the ID's `maxSupply`, so the edition reads like any capped ID. A window
+//! can be moved until it starts. Tokens minted before the edition opened count toward its size.
+//! The feature enables `supply-cap`.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155OpenEditions`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setOpenEdition"(U256, U256, U256),
+    "openEdition"(U256),
+    "finalizeOpenEdition"(U256),
+);
+
+sol_storage! {
+    pub struct OpenEditions {
+        mapping(uint256 => OpenEdition) editions;
+    }
+
+    pub struct OpenEdition {
+        /// Time mints open
+        uint256 start;
+        /// Time mints close for good, zero for IDs that aren't open editions
+        uint256 end;
+        /// Tokens minted so far, the edition's size once it closes
+        uint256 minted;
+        /// The supply after the last mint or burn, to count what the next one minted
+        uint256 last_supply;
+        bool finalized;
+    }
+}
+
+sol! {
+    interface IERC1155OpenEditions {
+        function setOpenEdition(uint256 id, uint256 start, uint256 end) external;
+        function openEdition(uint256 id) external view returns (uint256 start, uint256 end, uint256 minted, bool finalized);
+        function finalizeOpenEdition(uint256 id) external;
+    }
+
+    event OpenEditionSet(uint256 indexed id, uint256 start, uint256 end);
+    event OpenEditionFinalized(uint256 indexed id, uint256 size);
+
+    // The window ends before it starts or has passed, or the edition already started
+    error ERC1155InvalidOpenEdition(uint256 id, uint256 start, uint256 end);
+    // The ID is an open edition and the time is outside its window
+    error ERC1155OpenEditionNotOpen(uint256 id, uint256 start, uint256 end);
+    // The edition can't be finalized before it closes
+    error ERC1155OpenEditionStillOpen(uint256 id, uint256 end);
+    // The ID isn't an open edition waiting to be finalized
+    error ERC1155NotOpenEdition(uint256 id);
+}
+
+#[cfg(not(feature = "open-editions"))]
+disabled!(IOpenEditions);
+
+/// The methods of [`IERC1155OpenEditions`].
+#[cfg(feature = "open-editions")]
+pub trait IOpenEditions {
+    fn set_open_edition(&mut self, id: U256, start: U256, end: U256) -> Result<(), My1155Error>;
+    fn open_edition(&self, id: U256) -> (U256, U256, U256, bool);
+    fn finalize_open_edition(&mut self, id: U256) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "open-editions")]
+#[public]
+impl IOpenEditions for My1155 {
+    /// Opens an edition of `id` from `start` to `end`, or moves its window until it starts (owner
+    /// only).
+    fn set_open_edition(&mut self, id: U256, start: U256, end: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let now = U256::from(self.vm().block_timestamp());
+        let edition = self.open_editions.editions.get(id);
+        let started = !edition.end.get().is_zero() && edition.start.get() <= now;
+        if start >= end || end <= now || started {
+            return Err(My1155Error::InvalidOpenEdition(ERC1155InvalidOpenEdition { id, start, end }));
+        }
+        let supply = self.erc1155.total_supply(id);
+        let mut edition = self.open_editions.editions.setter(id);
+        edition.start.set(start);
+        edition.end.set(end);
+        edition.minted.set(supply);
+        edition.last_supply.set(supply);
+        log(self.vm(), OpenEditionSet { id, start, end });
+        Ok(())
+    }
+
+    /// The window of `id`'s edition, the tokens minted in it so far and whether it was finalized,
+    /// all zero for IDs that aren't open editions.
+    fn open_edition(&self, id: U256) -> (U256, U256, U256, bool) {
+        let edition = self.open_editions.editions.get(id);
+        (edition.start.get(), edition.end.get(), edition.minted.get(), edition.finalized.get())
+    }
+
+    /// Sets the size of `id`'s closed edition as its `maxSupply` and logs it, once (anyone).
+    fn finalize_open_edition(&mut self, id: U256) -> Result<(), My1155Error> {
+        let edition = self.open_editions.editions.get(id);
+        let (end, size) = (edition.end.get(), edition.minted.get());
+        if end.is_zero() || edition.finalized.get() {
+            return Err(My1155Error::NotOpenEdition(ERC1155NotOpenEdition { id }));
+        }
+        if U256::from(self.vm().block_timestamp()) < end {
+            return Err(My1155Error::OpenEditionStillOpen(ERC1155OpenEditionStillOpen { id, end }));
+        }
+        self.open_editions.editions.setter(id).finalized.set(true);
+        // A zero cap means no cap, and closed editions can't be minted anyway
+        if !size.is_zero() {
+            self.supply_cap.max_supply.insert(id, size);
+        }
+        log(self.vm(), OpenEditionFinalized { id, size });
+        Ok(())
+    }
+}
+
+impl My1155 {
+    /// Fails if `id` is an open edition outside its window.
+    #[cfg(feature = "open-editions")]
+    pub(crate) fn check_open_edition(&self, id: U256) -> Result<(), My1155Error> {
+        let edition = self.open_editions.editions.get(id);
+        let (start, end) = (edition.start.get(), edition.end.get());
+        let now = U256::from(self.vm().block_timestamp());
+        if !end.is_zero() && (now < start || now >= end) {
+            return Err(My1155Error::OpenEditionNotOpen(ERC1155OpenEditionNotOpen { id, start, end }));
+        }
+        Ok(())
+    }
+
+    /// Counts what mints (`from` zero) of open editions among `ids` minted, after
+    /// [`My1155::check_mintable`] held them to their windows, and follows burns (`to` zero) of them.
+    #[cfg(feature = "open-editions")]
+    pub(crate) fn track_open_editions(&mut self, from: Address, to: Address, ids: &[U256]) {
+        if !from.is_zero() && !to.is_zero() {
+            return;
+        }
+        for &id in ids {
+            if self.open_editions.editions.get(id).end.get().is_zero() {
+                continue;
+            }
+            let supply = self.erc1155.total_supply(id);
+            let mut edition = self.open_editions.editions.setter(id);
+            let minted = supply.saturating_sub(edition.last_supply.get());
+            let total = edition.minted.get().saturating_add(minted);
+            edition.minted.set(total);
+            edition.last_supply.set(supply);
+        }
+    }
+
+    #[cfg(not(feature = "open-editions"))]
+    #[inline(always)]
+    pub(crate) fn check_open_edition(&self, _id: U256) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    #[cfg(not(feature = "open-editions"))]
+    #[inline(always)]
+    pub(crate) fn track_open_editions(&mut self, _from: Address, _to: Address, _ids: &[U256]) {}
+}
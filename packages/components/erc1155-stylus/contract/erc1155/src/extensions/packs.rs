@@ -0,0 +1,310 @@
+//! Packs opened for random drops, with the `packs` feature.
+//!
+//! The owner gives a pack ID a drop table: the IDs a pack can drop, none of them packs, a weight
+//! for each and the drops in each pack. Opening packs burns them and requests randomness from a
+//! `randomness-stylus` deployment against the opener's commitment, `keccak256(secret)`. Once the
+//! opener reveals the secret there, the randomness comes back through `onRandomnessFulfilled`, or
+//! `settleOpening` if that callback failed, and the drops are minted to the opener.
+//!
+//! Drop `i` of an opening is the ID whose cumulative weight range, in table order, holds
+//! `uint256(keccak256(randomness ‖ i)) % totalWeight`. `PackOpened` logs the randomness and every
+//! drop, so anyone can recompute them from the table. A pack's table can't change while openings of
+//! it are waiting for randomness, and an opening whose secret is never revealed drops nothing,
+//! since withholding the secret is the one choice the opener has left.
+
+#[cfg(feature = "packs")]
+use alloc::vec;
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "packs")]
+use stylus_sdk::{alloy_sol_types::SolCall, storage::Erase};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "packs")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155Packs`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setDropTable"(U256, Vec<U256>, Vec<U256>, U256),
+    "dropTable"(U256),
+    "setPackRandomness"(Address),
+    "packRandomness"(),
+    "openPack"(U256, U256, B256),
+    "onRandomnessFulfilled"(U256, B256),
+    "settleOpening"(U256),
+    "opening"(U256),
+);
+/// Most IDs in a drop table.
+pub const MAX_DROP_IDS: usize = 32;
+/// Most drops in one pack.
+pub const MAX_DROPS_PER_PACK: u64 = 10;
+/// Most packs opened at once, which with [`MAX_DROPS_PER_PACK`] bounds the cost of settling.
+pub const MAX_PACKS_PER_OPENING: u64 = 10;
+
+sol_storage! {
+    pub struct Packs {
+        /// `randomness-stylus` deployment packs are opened through
+        address randomness;
+        /// IDs each pack can drop, by pack ID
+        mapping(uint256 => uint256[]) drop_ids;
+        /// Weight of each of the pack's `drop_ids`, by pack ID
+        mapping(uint256 => uint256[]) drop_weights;
+        /// Drops in each pack, by pack ID; zero for IDs that aren't packs
+        mapping(uint256 => uint256) drops_per_pack;
+        /// Openings waiting for randomness, by pack ID
+        mapping(uint256 => uint256) pending;
+        /// Openings by randomness request ID
+        mapping(uint256 => Opening) openings;
+    }
+
+    pub struct Opening {
+        /// Receives the drops; zero if there's no opening with the request ID
+        address opener;
+        uint256 pack_id;
+        uint256 amount;
+        /// Set once the drops are minted
+        bool settled;
+    }
+}
+
+sol! {
+    interface IERC1155Packs {
+        function setDropTable(uint256 packId, uint256[] memory ids, uint256[] memory weights, uint256 dropsPerPack) external;
+        function dropTable(uint256 packId) external view returns (uint256[] memory ids, uint256[] memory weights, uint256 dropsPerPack);
+        function setPackRandomness(address randomness) external;
+        function packRandomness() external view returns (address);
+        function openPack(uint256 packId, uint256 amount, bytes32 commitment) external returns (uint256 requestId);
+        function onRandomnessFulfilled(uint256 requestId, bytes32 randomness) external;
+        function settleOpening(uint256 requestId) external;
+        function opening(uint256 requestId) external view returns (address opener, uint256 packId, uint256 amount, bool settled);
+    }
+
+    interface IRandomness {
+        function request(bytes32 commitment, bool mix_blockhash) external returns (uint256);
+        function randomness(uint256 request_id) external view returns (bool, bytes32);
+    }
+
+    event DropTableSet(uint256 indexed packId, uint256[] ids, uint256[] weights, uint256 dropsPerPack);
+    event PackRandomnessSet(address randomness);
+    event PackOpenRequested(uint256 indexed requestId, address indexed opener, uint256 indexed packId, uint256 amount);
+    event PackOpened(
+        uint256 indexed requestId, address indexed opener, uint256 indexed packId, bytes32 randomness, uint256[] drops
+    );
+
+    error ERC1155InvalidDropTable(uint256 packId);
+    error ERC1155OpeningsPending(uint256 packId);
+    error ERC1155NotAPack(uint256 id);
+    error ERC1155InvalidPackAmount(uint256 amount);
+    error ERC1155UnknownOpening(uint256 requestId);
+    error ERC1155RandomnessPending(uint256 requestId);
+    error ERC1155RandomnessCallFailed(address randomness);
+    error ERC1155UnauthorizedRandomness(address caller);
+}
+
+#[cfg(not(feature = "packs"))]
+disabled!(IPacks);
+
+/// The methods of [`IERC1155Packs`].
+#[cfg(feature = "packs")]
+pub trait IPacks {
+    fn set_drop_table(
+        &mut self,
+        pack_id: U256,
+        ids: Vec<U256>,
+        weights: Vec<U256>,
+        drops_per_pack: U256,
+    ) -> Result<(), My1155Error>;
+    fn drop_table(&self, pack_id: U256) -> (Vec<U256>, Vec<U256>, U256);
+    fn set_pack_randomness(&mut self, randomness: Address) -> Result<(), My1155Error>;
+    fn pack_randomness(&self) -> Address;
+    fn open_pack(&mut self, pack_id: U256, amount: U256, commitment: B256) -> Result<U256, My1155Error>;
+    fn on_randomness_fulfilled(&mut self, request_id: U256, randomness: B256) -> Result<(), My1155Error>;
+    fn settle_opening(&mut self, request_id: U256) -> Result<(), My1155Error>;
+    fn opening(&self, request_id: U256) -> (Address, U256, U256, bool);
+}
+
+#[cfg(feature = "packs")]
+#[public]
+impl IPacks for My1155 {
+    /// Replaces `pack_id`'s drop table (owner only). Empty `ids` and zero drops make it an
+    /// ordinary ID again.
+    fn set_drop_table(
+        &mut self,
+        pack_id: U256,
+        ids: Vec<U256>,
+        weights: Vec<U256>,
+        drops_per_pack: U256,
+    ) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if !self.packs.pending.get(pack_id).is_zero() {
+            return Err(My1155Error::OpeningsPending(ERC1155OpeningsPending { packId: pack_id }));
+        }
+        let invalid = ids.len() != weights.len()
+            || ids.len() > MAX_DROP_IDS
+            || ids.iter().any(|&id| id == pack_id || !self.packs.drops_per_pack.get(id).is_zero())
+            || weights.contains(&U256::ZERO)
+            || ids.is_empty() != drops_per_pack.is_zero()
+            || drops_per_pack > U256::from(MAX_DROPS_PER_PACK);
+        if invalid {
+            return Err(My1155Error::InvalidDropTable(ERC1155InvalidDropTable { packId: pack_id }));
+        }
+
+        let mut stored_ids = self.packs.drop_ids.setter(pack_id);
+        stored_ids.erase();
+        for &id in &ids {
+            stored_ids.push(id);
+        }
+        let mut stored_weights = self.packs.drop_weights.setter(pack_id);
+        stored_weights.erase();
+        for &weight in &weights {
+            stored_weights.push(weight);
+        }
+        self.packs.drops_per_pack.insert(pack_id, drops_per_pack);
+        log(self.vm(), DropTableSet { packId: pack_id, ids, weights, dropsPerPack: drops_per_pack });
+        Ok(())
+    }
+
+    /// IDs `pack_id` can drop, their weights and the drops in each pack.
+    fn drop_table(&self, pack_id: U256) -> (Vec<U256>, Vec<U256>, U256) {
+        let ids = self.packs.drop_ids.getter(pack_id);
+        let weights = self.packs.drop_weights.getter(pack_id);
+        (
+            (0..ids.len()).filter_map(|i| ids.get(i)).collect(),
+            (0..weights.len()).filter_map(|i| weights.get(i)).collect(),
+            self.packs.drops_per_pack.get(pack_id),
+        )
+    }
+
+    /// Sets the `randomness-stylus` deployment packs are opened through (owner only). Openings
+    /// already waiting can still be settled from the previous one with `settleOpening`.
+    fn set_pack_randomness(&mut self, randomness: Address) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.packs.randomness.set(randomness);
+        log(self.vm(), PackRandomnessSet { randomness });
+        Ok(())
+    }
+
+    /// `randomness-stylus` deployment packs are opened through.
+    fn pack_randomness(&self) -> Address {
+        self.packs.randomness.get()
+    }
+
+    /// Burns `amount` of the caller's `pack_id` packs and requests the randomness of their drops
+    /// against `commitment = keccak256(secret)`, returning the request ID. Reveal the secret with
+    /// `fulfill` on the randomness contract to receive the drops.
+    fn open_pack(&mut self, pack_id: U256, amount: U256, commitment: B256) -> Result<U256, My1155Error> {
+        self.require_not_paused()?;
+        if self.packs.drops_per_pack.get(pack_id).is_zero() {
+            return Err(My1155Error::NotAPack(ERC1155NotAPack { id: pack_id }));
+        }
+        if amount.is_zero() || amount > U256::from(MAX_PACKS_PER_OPENING) {
+            return Err(My1155Error::InvalidPackAmount(ERC1155InvalidPackAmount { amount }));
+        }
+        let opener = self.vm().msg_sender();
+        self.erc1155._burn(opener, pack_id, amount)?;
+        self.after_update(opener, Address::ZERO, &[pack_id])?;
+
+        let randomness = self.packs.randomness.get();
+        let request = IRandomness::requestCall { commitment, mix_blockhash: true };
+        let request_id = self
+            .vm()
+            .call(&calls::context::Call::new(), randomness, &request.abi_encode())
+            .ok()
+            .and_then(|result| IRandomness::requestCall::abi_decode_returns(&result, true).ok())
+            .ok_or(My1155Error::RandomnessCallFailed(ERC1155RandomnessCallFailed { randomness }))?
+            ._0;
+
+        let mut opening = self.packs.openings.setter(request_id);
+        opening.opener.set(opener);
+        opening.pack_id.set(pack_id);
+        opening.amount.set(amount);
+        let pending = self.packs.pending.get(pack_id);
+        self.packs.pending.insert(pack_id, pending + U256::from(1));
+        log(self.vm(), PackOpenRequested { requestId: request_id, opener, packId: pack_id, amount });
+        Ok(request_id)
+    }
+
+    /// Mints an opening's drops with its randomness. Only the randomness contract calls this.
+    fn on_randomness_fulfilled(&mut self, request_id: U256, randomness: B256) -> Result<(), My1155Error> {
+        let caller = self.vm().msg_sender();
+        if caller != self.packs.randomness.get() {
+            return Err(My1155Error::UnauthorizedRandomness(ERC1155UnauthorizedRandomness { caller }));
+        }
+        self.settle(request_id, randomness)
+    }
+
+    /// Mints an opening's drops with randomness read from the randomness contract, for openings
+    /// whose callback failed. Callable by anyone.
+    fn settle_opening(&mut self, request_id: U256) -> Result<(), My1155Error> {
+        let randomness = self.packs.randomness.get();
+        let query = IRandomness::randomnessCall { request_id };
+        let result = self
+            .vm()
+            .static_call(&calls::context::Call::new(), randomness, &query.abi_encode())
+            .ok()
+            .and_then(|result| IRandomness::randomnessCall::abi_decode_returns(&result, true).ok())
+            .ok_or(My1155Error::RandomnessCallFailed(ERC1155RandomnessCallFailed { randomness }))?;
+        if !result._0 {
+            return Err(My1155Error::RandomnessPending(ERC1155RandomnessPending { requestId: request_id }));
+        }
+        self.settle(request_id, result._1)
+    }
+
+    /// An opening's opener, pack ID, packs opened and whether its drops were minted.
+    fn opening(&self, request_id: U256) -> (Address, U256, U256, bool) {
+        let opening = self.packs.openings.getter(request_id);
+        (opening.opener.get(), opening.pack_id.get(), opening.amount.get(), opening.settled.get())
+    }
+}
+
+#[cfg(feature = "packs")]
+impl My1155 {
+    /// Draws an opening's drops from its pack's table and mints them to the opener.
+    fn settle(&mut self, request_id: U256, randomness: B256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let opening = self.packs.openings.getter(request_id);
+        let (opener, pack_id, amount) = (opening.opener.get(), opening.pack_id.get(), opening.amount.get());
+        if opener.is_zero() || opening.settled.get() {
+            return Err(My1155Error::UnknownOpening(ERC1155UnknownOpening { requestId: request_id }));
+        }
+
+        let (ids, weights, drops_per_pack) = self.drop_table(pack_id);
+        let total_weight = weights.iter().fold(U256::ZERO, |total, &weight| total + weight);
+        let mut amounts = vec![U256::ZERO; ids.len()];
+        let mut drops = Vec::new();
+        for i in 0..(amount * drops_per_pack).to::<u64>() {
+            let mut seed = [0; 64];
+            seed[..32].copy_from_slice(randomness.as_slice());
+            seed[32..].copy_from_slice(&U256::from(i).to_be_bytes::<32>());
+            let mut roll = U256::from_be_bytes(self.vm().native_keccak256(&seed).0) % total_weight;
+            let index = weights
+                .iter()
+                .position(|&weight| {
+                    let hit = roll < weight;
+                    roll = roll.saturating_sub(weight);
+                    hit
+                })
+                .expect("the roll is below the total weight");
+            amounts[index] += U256::from(1);
+            drops.push(ids[index]);
+        }
+        let (ids, amounts): (Vec<U256>, Vec<U256>) =
+            ids.into_iter().zip(amounts).filter(|(_, amount)| !amount.is_zero()).unzip();
+
+        self.packs.openings.setter(request_id).settled.set(true);
+        let pending = self.packs.pending.get(pack_id);
+        self.packs.pending.insert(pack_id, pending - U256::from(1));
+        self.check_mintable(&ids)?;
+        self.check_rate_limits(Address::ZERO, opener, &ids, &amounts)?;
+        self.call_transfer_hooks(Address::ZERO, opener, &ids, &amounts)?;
+        let log_chunk = self.log_chunk();
+        self.erc1155._mint_batch(opener, &ids, &amounts, log_chunk)?;
+        self.after_update(Address::ZERO, opener, &ids)?;
+        log(self.vm(), PackOpened { requestId: request_id, opener, packId: pack_id, randomness, drops });
+        self.call_batch_mint_hook(opener, ids, amounts, Vec::new())
+    }
+}
@@ -0,0 +1,353 @@
+//! Public mints for ETH, with the `paid-mint` feature.
+//!
+//! The owner puts an ID on sale at a price per token, and anyone can then mint it by paying
+//! exactly the price of the amount. An ID can be delegated to a creator, who is credited with
+//! its proceeds; proceeds of the other IDs are credited to the owner at the time of the mint.
+//! Nothing is sent during a mint: each account withdraws what it's owed with
+//! `withdrawProceeds`, even while the token is paused, so a payee that can't receive ETH only
+//! blocks itself. `revenueOf` reports everything an ID has raised, withdrawn or not.
+//!
+//! The owner can give an ID a refund window, so buyers of an unproven drop can change their mind.
+//! Each paid mint of it is then a purchase escrowed until the window has passed since the mint:
+//! until then the recipient can burn tokens of the purchase with `refundMint`, which credits the
+//! buyer with what they paid for them, and afterwards anyone can release what's left to the payee
+//! with `releaseEscrow`. Refunds go through while the token is paused, like withdrawals, so
+//! pausing can't run out a buyer's window. A window only applies to purchases made after it's set.
+
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(feature = "paid-mint")]
+use stylus_sdk::alloy_primitives::FixedBytes;
+
+#[cfg(feature = "paid-mint")]
+use crate::{My1155, My1155Error, ERC1155IdRetired, ID_BURNED_OUT};
+
+/// [`IERC1155PaidMint`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setMintPrice"(U256, U256, Address),
+    "mintPrice"(U256),
+    "mintPaid"(Address, U256, U256),
+    "quotePublicMint"(U256, U256),
+    "revenueOf"(U256),
+    "proceedsOf"(Address),
+    "withdrawProceeds"(),
+    "setRefundWindow"(U256, U256),
+    "refundWindow"(U256),
+    "purchase"(U256),
+    "refundMint"(U256, U256),
+    "releaseEscrow"(Vec<U256>),
+);
+
+sol_storage! {
+    pub struct PaidMint {
+        /// Price per token of each ID, zero for IDs that aren't on sale
+        mapping(uint256 => uint256) price;
+        /// Creator each ID's proceeds are credited to, zero for the owner
+        mapping(uint256 => address) creator;
+        /// ETH each ID has raised
+        mapping(uint256 => uint256) revenue;
+        /// ETH each account can withdraw
+        mapping(address => uint256) proceeds;
+    }
+
+    pub struct MintRefunds {
+        /// Seconds after a paid mint of each ID that it can be refunded, zero for IDs without refunds
+        mapping(uint256 => uint256) window;
+        uint256 purchase_count;
+        mapping(uint256 => Purchase) purchases;
+    }
+
+    pub struct Purchase {
+        address buyer;
+        /// Recipient of the tokens, who can refund them
+        address holder;
+        /// Account credited with the escrow once released
+        address payee;
+        uint256 id;
+        /// Price paid per token
+        uint256 price;
+        /// Tokens neither refunded nor released
+        uint256 escrowed;
+        uint256 refundable_until;
+    }
+}
+
+sol! {
+    interface IERC1155PaidMint {
+        function setMintPrice(uint256 id, uint256 price, address creator) external;
+        function mintPrice(uint256 id) external view returns (uint256 price, address creator);
+        function mintPaid(address to, uint256 id, uint256 amount) external payable;
+        function quotePublicMint(uint256 id, uint256 amount) external view returns (uint256 cost, bytes4 reason);
+        function revenueOf(uint256 id) external view returns (uint256);
+        function proceedsOf(address account) external view returns (uint256);
+        function withdrawProceeds() external;
+        function setRefundWindow(uint256 id, uint256 window) external;
+        function refundWindow(uint256 id) external view returns (uint256);
+        function purchase(uint256 purchaseId) external view returns (address buyer, address holder, address payee, uint256 id, uint256 price, uint256 escrowed, uint256 refundableUntil);
+        function refundMint(uint256 purchaseId, uint256 amount) external;
+        function releaseEscrow(uint256[] purchaseIds) external;
+    }
+
+    event MintPriceSet(uint256 indexed id, uint256 price, address indexed creator);
+    event MintPurchased(address indexed buyer, address indexed to, uint256 indexed id, uint256 amount, uint256 paid);
+    event ProceedsWithdrawn(address indexed account, uint256 amount);
+    event RefundWindowSet(uint256 indexed id, uint256 window);
+    event MintEscrowed(uint256 indexed purchaseId, address indexed buyer, uint256 indexed id, uint256 amount, uint256 paid, uint256 refundableUntil);
+    event MintRefunded(uint256 indexed purchaseId, address indexed buyer, uint256 amount, uint256 refund);
+    event EscrowReleased(uint256 indexed purchaseId, address indexed payee, uint256 amount);
+
+    error ERC1155MintNotForSale(uint256 id);
+    error ERC1155IncorrectPayment(uint256 id, uint256 expected, uint256 paid);
+    error ERC1155ProceedsTransferFailed(address account, uint256 amount);
+    // Only the purchase's recipient can refund it, within its window, and no more than is escrowed
+    error ERC1155InvalidRefund(uint256 purchaseId, address account, uint256 amount);
+    // The purchase can still be refunded
+    error ERC1155RefundWindowOpen(uint256 purchaseId, uint256 refundableUntil);
+}
+
+#[cfg(not(feature = "paid-mint"))]
+disabled!(IPaidMint);
+
+/// The methods of [`IERC1155PaidMint`].
+#[cfg(feature = "paid-mint")]
+pub trait IPaidMint {
+    fn set_mint_price(&mut self, id: U256, price: U256, creator: Address) -> Result<(), My1155Error>;
+    fn mint_price(&self, id: U256) -> (U256, Address);
+    fn mint_paid(&mut self, to: Address, id: U256, amount: U256) -> Result<(), My1155Error>;
+    fn quote_public_mint(&self, id: U256, amount: U256) -> (U256, FixedBytes<4>);
+    fn revenue_of(&self, id: U256) -> U256;
+    fn proceeds_of(&self, account: Address) -> U256;
+    fn withdraw_proceeds(&mut self) -> Result<(), My1155Error>;
+    fn set_refund_window(&mut self, id: U256, window: U256) -> Result<(), My1155Error>;
+    fn refund_window(&self, id: U256) -> U256;
+    fn purchase(&self, purchase_id: U256) -> (Address, Address, Address, U256, U256, U256, U256);
+    fn refund_mint(&mut self, purchase_id: U256, amount: U256) -> Result<(), My1155Error>;
+    fn release_escrow(&mut self, purchase_ids: Vec<U256>) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "paid-mint")]
+#[public]
+impl IPaidMint for My1155 {
+    /// Puts `id` on sale at `price` per token, or takes it off sale with a zero price (owner
+    /// only). A non-zero `creator` is credited with the proceeds instead of the owner.
+    fn set_mint_price(&mut self, id: U256, price: U256, creator: Address) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.paid_mint.price.insert(id, price);
+        self.paid_mint.creator.insert(id, creator);
+        log(self.vm(), MintPriceSet { id, price, creator });
+        Ok(())
+    }
+
+    /// Price per token of `id`, zero if it isn't on sale, and the creator of its proceeds.
+    fn mint_price(&self, id: U256) -> (U256, Address) {
+        (self.paid_mint.price.get(id), self.paid_mint.creator.get(id))
+    }
+
+    /// Mints `amount` of `id` to `to` for exactly its price, if `attested-mint` admits `to`.
+    #[payable]
+    fn mint_paid(&mut self, to: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let price = self.paid_mint.price.get(id);
+        if price.is_zero() {
+            return Err(My1155Error::MintNotForSale(ERC1155MintNotForSale { id }));
+        }
+        let paid = self.vm().msg_value();
+        let expected = price.checked_mul(amount).unwrap_or(U256::MAX);
+        if paid != expected {
+            return Err(My1155Error::IncorrectPayment(ERC1155IncorrectPayment { id, expected, paid }));
+        }
+        self.check_mint_gate(to, id)?;
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+
+        let revenue = self.paid_mint.revenue.get(id);
+        self.paid_mint.revenue.insert(id, revenue + paid);
+        let creator = self.paid_mint.creator.get(id);
+        let payee = if creator.is_zero() { self.ownable.owner() } else { creator };
+        let buyer = self.vm().msg_sender();
+        let window = self.mint_refunds.window.get(id);
+        if window.is_zero() {
+            self.credit_proceeds(payee, paid);
+        } else {
+            self.escrow_purchase(to, payee, id, price, amount, window);
+        }
+
+        log(self.vm(), MintPurchased { buyer, to, id, amount, paid });
+        self.call_mint_hook(to, id, amount, Vec::new())
+    }
+
+    /// ETH `mintPaid` of `amount` of `id` costs, and the selector of the error it would revert with
+    /// now, zero when it wouldn't. The attestation gate and each account's rate limits depend on
+    /// the recipient and aren't checked: `canMint` reports the gate.
+    fn quote_public_mint(&self, id: U256, amount: U256) -> (U256, FixedBytes<4>) {
+        let cost = self.paid_mint.price.get(id).checked_mul(amount).unwrap_or(U256::MAX);
+        (cost, Self::error_selector(self.check_public_mint(id, amount)))
+    }
+
+    /// ETH minters have paid for `id`.
+    fn revenue_of(&self, id: U256) -> U256 {
+        self.paid_mint.revenue.get(id)
+    }
+
+    /// ETH `account` can withdraw.
+    fn proceeds_of(&self, account: Address) -> U256 {
+        self.paid_mint.proceeds.get(account)
+    }
+
+    /// Sends the caller the proceeds they're owed.
+    fn withdraw_proceeds(&mut self) -> Result<(), My1155Error> {
+        let account = self.vm().msg_sender();
+        let amount = self.paid_mint.proceeds.get(account);
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.paid_mint.proceeds.insert(account, U256::ZERO);
+        self.vm()
+            .transfer_eth(account, amount)
+            .map_err(|_| My1155Error::ProceedsTransferFailed(ERC1155ProceedsTransferFailed { account, amount }))?;
+        log(self.vm(), ProceedsWithdrawn { account, amount });
+        Ok(())
+    }
+
+    /// Lets paid mints of `id` from now on be refunded for `window` seconds, or stops new ones
+    /// being refundable with a zero window (owner only).
+    fn set_refund_window(&mut self, id: U256, window: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.mint_refunds.window.insert(id, window);
+        log(self.vm(), RefundWindowSet { id, window });
+        Ok(())
+    }
+
+    /// Seconds a paid mint of `id` can be refunded for, zero if it can't.
+    fn refund_window(&self, id: U256) -> U256 {
+        self.mint_refunds.window.get(id)
+    }
+
+    /// The buyer, recipient and payee of an escrowed purchase, its ID and price per token, the
+    /// tokens still escrowed and when its window ends, all zero for unknown purchases.
+    fn purchase(&self, purchase_id: U256) -> (Address, Address, Address, U256, U256, U256, U256) {
+        let purchase = self.mint_refunds.purchases.get(purchase_id);
+        (
+            purchase.buyer.get(),
+            purchase.holder.get(),
+            purchase.payee.get(),
+            purchase.id.get(),
+            purchase.price.get(),
+            purchase.escrowed.get(),
+            purchase.refundable_until.get(),
+        )
+    }
+
+    /// Burns `amount` of the caller's tokens of a purchase within its window, crediting its buyer
+    /// with what they paid for them (the purchase's recipient only).
+    fn refund_mint(&mut self, purchase_id: U256, amount: U256) -> Result<(), My1155Error> {
+        let account = self.vm().msg_sender();
+        let purchase = self.mint_refunds.purchases.get(purchase_id);
+        let (id, price, escrowed) = (purchase.id.get(), purchase.price.get(), purchase.escrowed.get());
+        let buyer = purchase.buyer.get();
+        let open = U256::from(self.vm().block_timestamp()) < purchase.refundable_until.get();
+        if account != purchase.holder.get() || !open || amount.is_zero() || amount > escrowed {
+            return Err(My1155Error::InvalidRefund(ERC1155InvalidRefund { purchaseId: purchase_id, account, amount }));
+        }
+        self.mint_refunds.purchases.setter(purchase_id).escrowed.set(escrowed - amount);
+        self.erc1155._burn(account, id, amount)?;
+        self.after_update(account, Address::ZERO, &[id])?;
+
+        let refund = price * amount;
+        let revenue = self.paid_mint.revenue.get(id);
+        self.paid_mint.revenue.insert(id, revenue - refund);
+        self.credit_proceeds(buyer, refund);
+        log(self.vm(), MintRefunded { purchaseId: purchase_id, buyer, amount, refund });
+        Ok(())
+    }
+
+    /// Credits the payees of purchases whose windows have passed with what's left in escrow
+    /// (anyone). Purchases already released are skipped.
+    fn release_escrow(&mut self, purchase_ids: Vec<U256>) -> Result<(), My1155Error> {
+        self.check_batch_length(purchase_ids.len())?;
+        let now = U256::from(self.vm().block_timestamp());
+        for purchase_id in purchase_ids {
+            let purchase = self.mint_refunds.purchases.get(purchase_id);
+            let (payee, escrowed) = (purchase.payee.get(), purchase.escrowed.get());
+            let refundable_until = purchase.refundable_until.get();
+            if now < refundable_until {
+                return Err(My1155Error::RefundWindowOpen(ERC1155RefundWindowOpen {
+                    purchaseId: purchase_id,
+                    refundableUntil: refundable_until,
+                }));
+            }
+            if escrowed.is_zero() {
+                continue;
+            }
+            let amount = escrowed * purchase.price.get();
+            self.mint_refunds.purchases.setter(purchase_id).escrowed.set(U256::ZERO);
+            self.credit_proceeds(payee, amount);
+            log(self.vm(), EscrowReleased { purchaseId: purchase_id, payee, amount });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "paid-mint")]
+impl My1155 {
+    /// Adds `amount` to what `account` can withdraw.
+    fn credit_proceeds(&mut self, account: Address, amount: U256) {
+        let proceeds = self.paid_mint.proceeds.get(account);
+        self.paid_mint.proceeds.insert(account, proceeds + amount);
+    }
+
+    /// Records the caller's paid mint of a refundable ID, escrowing its payment until `window` has
+    /// passed.
+    fn escrow_purchase(&mut self, holder: Address, payee: Address, id: U256, price: U256, amount: U256, window: U256) {
+        let buyer = self.vm().msg_sender();
+        let purchase_id = self.mint_refunds.purchase_count.get();
+        self.mint_refunds.purchase_count.set(purchase_id + U256::from(1));
+        let refundable_until = U256::from(self.vm().block_timestamp()).saturating_add(window);
+        let mut purchase = self.mint_refunds.purchases.setter(purchase_id);
+        purchase.buyer.set(buyer);
+        purchase.holder.set(holder);
+        purchase.payee.set(payee);
+        purchase.id.set(id);
+        purchase.price.set(price);
+        purchase.escrowed.set(amount);
+        purchase.refundable_until.set(refundable_until);
+        log(self.vm(), MintEscrowed {
+            purchaseId: purchase_id,
+            buyer,
+            id,
+            amount,
+            paid: price * amount,
+            refundableUntil: refundable_until,
+        });
+    }
+
+    /// Fails as a paid mint of `amount` of `id` would, for the checks that don't depend on the
+    /// recipient, without minting anything.
+    fn check_public_mint(&self, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let price = self.paid_mint.price.get(id);
+        if price.is_zero() {
+            return Err(My1155Error::MintNotForSale(ERC1155MintNotForSale { id }));
+        }
+        if price.checked_mul(amount).is_none() {
+            let expected = U256::MAX;
+            return Err(My1155Error::IncorrectPayment(ERC1155IncorrectPayment { id, expected, paid: U256::ZERO }));
+        }
+        let supply = self.erc1155.total_supply(id).saturating_add(amount);
+        self.check_supply_within_cap(id, supply)?;
+        self.check_item_supply(id, supply)?;
+        let burned_out = self.id_states.get(id).to::<u8>() == ID_BURNED_OUT;
+        if burned_out && !amount.is_zero() && self.retire_burned_out.get() {
+            return Err(My1155Error::IdRetired(ERC1155IdRetired { id }));
+        }
+        self.check_mintable(&[id])
+    }
+}
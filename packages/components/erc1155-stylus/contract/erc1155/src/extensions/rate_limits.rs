@@ -0,0 +1,297 @@
+//! Volume caps per block and per hour, with the `rate-limits` feature.
+//!
+//! The owner caps how much of an ID can be minted and transferred in a block and in an hour, in
+//! total and by each account, so a compromised minter or holder key can only move so much before
+//! someone notices. Mints count against the recipient and transfers against the sender, and burns
+//! aren't limited. Hours are fixed windows of the block timestamp, not rolling ones.
+//!
+//! Calls that would take a window past its cap revert with `ERC1155RateLimitExceeded`. A reverted
+//! call can't leave an event behind, so the call that fills a window to its cap emits
+//! `RateLimitReached` instead: the breaker has tripped, and the window refuses more volume until
+//! it passes.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "rate-limits")]
+use crate::profiling;
+use crate::{My1155, My1155Error};
+
+/// Length of the hourly window, in seconds.
+pub const HOUR: u64 = 3600;
+
+/// A volume with the account it's counted for (zero for the total), whether it's hourly and its
+/// cap.
+#[cfg(feature = "rate-limits")]
+type Cap = (Address, bool, U256, U256);
+
+/// [`IERC1155RateLimits`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setRateLimit"(U256, U256, U256, U256, U256),
+    "rateLimit"(U256),
+    "volume"(U256),
+    "accountVolume"(U256, Address),
+);
+
+sol_storage! {
+    pub struct RateLimits {
+        /// Caps of each ID, zero for windows without one
+        mapping(uint256 => RateLimit) limits;
+        /// Volume of each ID in its current windows
+        mapping(uint256 => Volume) volume;
+        /// Volume of each ID by each account in its current windows
+        mapping(uint256 => mapping(address => Volume)) account_volume;
+    }
+
+    pub struct RateLimit {
+        uint256 per_block;
+        uint256 per_hour;
+        uint256 account_per_block;
+        uint256 account_per_hour;
+    }
+
+    pub struct Volume {
+        /// Block `block_volume` was counted in
+        uint256 block;
+        uint256 block_volume;
+        /// Hour since the epoch `hour_volume` was counted in
+        uint256 hour;
+        uint256 hour_volume;
+    }
+}
+
+sol! {
+    interface IERC1155RateLimits {
+        function setRateLimit(uint256 id, uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour) external;
+        function rateLimit(uint256 id) external view returns (uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour);
+        function volume(uint256 id) external view returns (uint256 blockVolume, uint256 hourVolume);
+        function accountVolume(uint256 id, address account) external view returns (uint256 blockVolume, uint256 hourVolume);
+    }
+
+    event RateLimitSet(uint256 indexed id, uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour);
+    // `account` is zero for the caps on the total volume of `id`
+    event RateLimitReached(uint256 indexed id, address indexed account, bool hourly, uint256 limit);
+
+    error ERC1155RateLimitExceeded(uint256 id, address account, uint256 volume, uint256 limit);
+}
+
+#[cfg(not(feature = "rate-limits"))]
+disabled!(IRateLimits);
+
+/// The methods of [`IERC1155RateLimits`].
+#[cfg(feature = "rate-limits")]
+pub trait IRateLimits {
+    fn set_rate_limit(
+        &mut self,
+        id: U256,
+        per_block: U256,
+        per_hour: U256,
+        account_per_block: U256,
+        account_per_hour: U256,
+    ) -> Result<(), My1155Error>;
+    fn rate_limit(&self, id: U256) -> (U256, U256, U256, U256);
+    fn volume(&self, id: U256) -> (U256, U256);
+    fn account_volume(&self, id: U256, account: Address) -> (U256, U256);
+}
+
+#[cfg(feature = "rate-limits")]
+#[public]
+impl IRateLimits for My1155 {
+    /// Caps the volume of `id` per block and per hour, in total and by each account (owner only).
+    /// Zero removes a cap.
+    fn set_rate_limit(
+        &mut self,
+        id: U256,
+        per_block: U256,
+        per_hour: U256,
+        account_per_block: U256,
+        account_per_hour: U256,
+    ) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let mut limit = self.rate_limits.limits.setter(id);
+        limit.per_block.set(per_block);
+        limit.per_hour.set(per_hour);
+        limit.account_per_block.set(account_per_block);
+        limit.account_per_hour.set(account_per_hour);
+        log(self.vm(), RateLimitSet {
+            id,
+            perBlock: per_block,
+            perHour: per_hour,
+            accountPerBlock: account_per_block,
+            accountPerHour: account_per_hour,
+        });
+        Ok(())
+    }
+
+    /// Caps of `id` per block and per hour, in total and by each account, zero where it has none.
+    fn rate_limit(&self, id: U256) -> (U256, U256, U256, U256) {
+        let limit = self.rate_limits.limits.get(id);
+        (limit.per_block.get(), limit.per_hour.get(), limit.account_per_block.get(), limit.account_per_hour.get())
+    }
+
+    /// Volume of `id` in the current block and hour.
+    fn volume(&self, id: U256) -> (U256, U256) {
+        let (block, hour) = self.windows();
+        self.rate_limits.volume.get(id).current(block, hour)
+    }
+
+    /// Volume of `id` by `account` in the current block and hour.
+    fn account_volume(&self, id: U256, account: Address) -> (U256, U256) {
+        let (block, hour) = self.windows();
+        self.rate_limits.account_volume.get(id).get(account).current(block, hour)
+    }
+}
+
+#[cfg(feature = "rate-limits")]
+impl Volume {
+    /// Volumes of the given block and hour.
+    fn current(&self, block: U256, hour: U256) -> (U256, U256) {
+        let block_volume = if self.block.get() == block { self.block_volume.get() } else { U256::ZERO };
+        let hour_volume = if self.hour.get() == hour { self.hour_volume.get() } else { U256::ZERO };
+        (block_volume, hour_volume)
+    }
+
+    /// Volumes of the given block and hour once `amount` more is counted.
+    fn with(&self, block: U256, hour: U256, amount: U256) -> (U256, U256) {
+        let (block_volume, hour_volume) = self.current(block, hour);
+        (block_volume.saturating_add(amount), hour_volume.saturating_add(amount))
+    }
+
+    /// Stores the volumes of the given block and hour, replacing those of windows that passed.
+    fn set(&mut self, block: U256, hour: U256, (block_volume, hour_volume): (U256, U256)) {
+        self.block.set(block);
+        self.block_volume.set(block_volume);
+        self.hour.set(hour);
+        self.hour_volume.set(hour_volume);
+    }
+}
+
+impl My1155 {
+    /// Counts `amounts` of `ids` moving from `from` to `to` against their caps, and fails if one
+    /// is exceeded. Burns, when `to` is zero, aren't counted.
+    #[cfg(feature = "rate-limits")]
+    pub(crate) fn check_rate_limits(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: &[U256],
+        amounts: &[U256],
+    ) -> Result<(), My1155Error> {
+        if to.is_zero() {
+            return Ok(());
+        }
+        let probe = profiling::start(self.vm());
+        let account = if from.is_zero() { to } else { from };
+        let (block, hour) = self.windows();
+        for (&id, &amount) in ids.iter().zip(amounts) {
+            let Some(caps) = self.counted_caps(account, id, amount, block, hour)? else {
+                continue;
+            };
+            let (per_block, per_hour, account_per_block, account_per_hour) = self.rate_limit(id);
+            if !per_block.is_zero() || !per_hour.is_zero() {
+                self.rate_limits.volume.setter(id).set(block, hour, (caps[0].2, caps[1].2));
+            }
+            if !account_per_block.is_zero() || !account_per_hour.is_zero() {
+                self.rate_limits.account_volume.setter(id).setter(account).set(block, hour, (caps[2].2, caps[3].2));
+            }
+            // The breaker trips when a window fills up, since later calls revert
+            for (account, hourly, volume, limit) in caps {
+                if !limit.is_zero() && volume == limit {
+                    log(self.vm(), RateLimitReached { id, account, hourly, limit });
+                }
+            }
+        }
+        profiling::finish(self.vm(), profiling::RATE_LIMITS, probe);
+        Ok(())
+    }
+
+    /// Fails if moving `amount` of `id` from `from` to `to` now would exceed one of its caps,
+    /// without counting it.
+    #[cfg(feature = "rate-limits")]
+    pub(crate) fn preview_rate_limits(
+        &self,
+        from: Address,
+        to: Address,
+        id: U256,
+        amount: U256,
+    ) -> Result<(), My1155Error> {
+        if to.is_zero() {
+            return Ok(());
+        }
+        let account = if from.is_zero() { to } else { from };
+        let (block, hour) = self.windows();
+        self.counted_caps(account, id, amount, block, hour).map(|_| ())
+    }
+
+    /// The volumes of `id` in total and by `account` once `amount` more is counted, or `None` with
+    /// nothing to count. Fails if a volume would be past its cap.
+    #[cfg(feature = "rate-limits")]
+    fn counted_caps(
+        &self,
+        account: Address,
+        id: U256,
+        amount: U256,
+        block: U256,
+        hour: U256,
+    ) -> Result<Option<[Cap; 4]>, My1155Error> {
+        let (per_block, per_hour, account_per_block, account_per_hour) = self.rate_limit(id);
+        let limits_total = !per_block.is_zero() || !per_hour.is_zero();
+        let limits_account = !account_per_block.is_zero() || !account_per_hour.is_zero();
+        if amount.is_zero() || !(limits_total || limits_account) {
+            return Ok(None);
+        }
+        let (mut total, mut own) = ((U256::ZERO, U256::ZERO), (U256::ZERO, U256::ZERO));
+        if limits_total {
+            total = self.rate_limits.volume.get(id).with(block, hour, amount);
+        }
+        if limits_account {
+            own = self.rate_limits.account_volume.get(id).get(account).with(block, hour, amount);
+        }
+        let caps = [
+            (Address::ZERO, false, total.0, per_block),
+            (Address::ZERO, true, total.1, per_hour),
+            (account, false, own.0, account_per_block),
+            (account, true, own.1, account_per_hour),
+        ];
+        if let Some(&(account, _, volume, limit)) =
+            caps.iter().find(|(_, _, volume, limit)| !limit.is_zero() && volume > limit)
+        {
+            return Err(My1155Error::RateLimitExceeded(ERC1155RateLimitExceeded { id, account, volume, limit }));
+        }
+        Ok(Some(caps))
+    }
+
+    #[cfg(not(feature = "rate-limits"))]
+    #[inline(always)]
+    pub(crate) fn preview_rate_limits(
+        &self,
+        _from: Address,
+        _to: Address,
+        _id: U256,
+        _amount: U256,
+    ) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    #[cfg(not(feature = "rate-limits"))]
+    #[inline(always)]
+    pub(crate) fn check_rate_limits(
+        &mut self,
+        _from: Address,
+        _to: Address,
+        _ids: &[U256],
+        _amounts: &[U256],
+    ) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    /// The current block, and the current hour since the epoch.
+    #[cfg(feature = "rate-limits")]
+    fn windows(&self) -> (U256, U256) {
+        (U256::from(self.vm().block_number()), U256::from(self.vm().block_timestamp() / HOUR))
+    }
+}
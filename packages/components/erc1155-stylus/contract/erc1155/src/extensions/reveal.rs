@@ -0,0 +1,188 @@
+//! Fair drops, with metadata committed to before the mint and shuffled by an offset fixed after
+//! it, with the `reveal` feature.
+//!
+//! The owner publishes the drop's metadata files, numbered `0` to `size - 1`, and commits to them
+//! with `setProvenanceHash` before minting, typically the keccak256 of their hashes in order. From
+//! then until `reveal`, `uri` returns the base URI as is, for every ID, so the owner points it at
+//! one placeholder. Once the drop has minted out, the owner sets `setRevealOffset`, ideally from
+//! randomness no one had at commit time, points `setUri` at the metadata folder and calls
+//! `reveal`, after which ID `id` gets file `(id + offset) % size`. Anyone can then check the files
+//! against the hash, and since the offset came after both the commit and the mint, that the owner
+//! couldn't steer rare items to accounts of their choosing.
+//!
+//! The hash is set once and the reveal can't be undone. Both change what `uri` returns, so neither
+//! goes through once the metadata is frozen.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{B256, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::My1155;
+#[cfg(feature = "reveal")]
+use crate::{My1155Error, ERC1155MetadataIsFrozen};
+
+/// [`IERC1155Reveal`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "provenanceHash"(),
+    "setProvenanceHash"(B256, U256),
+    "revealOffset"(),
+    "setRevealOffset"(U256),
+    "revealed"(),
+    "reveal"(),
+);
+
+sol_storage! {
+    pub struct Reveal {
+        /// The commitment to the drop's metadata, zero until set
+        bytes32 provenance_hash;
+        /// How many metadata files the drop has
+        uint256 size;
+        /// How far IDs are shifted through the files once revealed
+        uint256 offset;
+        bool revealed;
+    }
+}
+
+sol! {
+    interface IERC1155Reveal {
+        function provenanceHash() external view returns (bytes32 hash, uint256 size);
+        function setProvenanceHash(bytes32 hash, uint256 size) external;
+        function revealOffset() external view returns (uint256);
+        function setRevealOffset(uint256 offset) external;
+        function revealed() external view returns (bool);
+        function reveal() external;
+    }
+
+    event ProvenanceHashSet(bytes32 hash, uint256 size);
+    event RevealOffsetSet(uint256 offset);
+    event Revealed(uint256 offset);
+
+    // The provenance hash was already set
+    error ERC1155ProvenanceAlreadySet(bytes32 hash);
+    // No provenance hash was set yet
+    error ERC1155ProvenanceNotSet();
+    // The hash or size is zero
+    error ERC1155InvalidProvenance(bytes32 hash, uint256 size);
+    // The offset isn't below the drop's size
+    error ERC1155InvalidRevealOffset(uint256 offset, uint256 size);
+    // The drop was already revealed
+    error ERC1155AlreadyRevealed();
+}
+
+#[cfg(not(feature = "reveal"))]
+disabled!(IReveal);
+
+/// The methods of [`IERC1155Reveal`].
+#[cfg(feature = "reveal")]
+pub trait IReveal {
+    fn provenance_hash(&self) -> (B256, U256);
+    fn set_provenance_hash(&mut self, hash: B256, size: U256) -> Result<(), My1155Error>;
+    fn reveal_offset(&self) -> U256;
+    fn set_reveal_offset(&mut self, offset: U256) -> Result<(), My1155Error>;
+    fn revealed(&self) -> bool;
+    fn reveal(&mut self) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "reveal")]
+#[public]
+impl IReveal for My1155 {
+    /// The commitment to the drop's metadata and how many files it covers, zero until set.
+    fn provenance_hash(&self) -> (B256, U256) {
+        (self.reveal.provenance_hash.get(), self.reveal.size.get())
+    }
+
+    /// Commits to the drop's `size` metadata files, once, before minting (owner only). `uri`
+    /// returns the bare base URI from now on until `reveal`.
+    fn set_provenance_hash(&mut self, hash: B256, size: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_metadata_unfrozen()?;
+        let current = self.reveal.provenance_hash.get();
+        if !current.is_zero() {
+            return Err(My1155Error::ProvenanceAlreadySet(ERC1155ProvenanceAlreadySet { hash: current }));
+        }
+        if hash.is_zero() || size.is_zero() {
+            return Err(My1155Error::InvalidProvenance(ERC1155InvalidProvenance { hash, size }));
+        }
+        self.reveal.provenance_hash.set(hash);
+        self.reveal.size.set(size);
+        log(self.vm(), ProvenanceHashSet { hash, size });
+        Ok(())
+    }
+
+    /// How far IDs are shifted through the metadata files once revealed.
+    fn reveal_offset(&self) -> U256 {
+        self.reveal.offset.get()
+    }
+
+    /// Sets how far IDs are shifted through the metadata files, which can change until `reveal`
+    /// (owner only).
+    fn set_reveal_offset(&mut self, offset: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let size = self.check_unrevealed()?;
+        if offset >= size {
+            return Err(My1155Error::InvalidRevealOffset(ERC1155InvalidRevealOffset { offset, size }));
+        }
+        self.reveal.offset.set(offset);
+        log(self.vm(), RevealOffsetSet { offset });
+        Ok(())
+    }
+
+    /// Whether `uri` returns each ID's own metadata file.
+    fn revealed(&self) -> bool {
+        self.reveal.revealed.get()
+    }
+
+    /// Switches `uri` to each ID's shifted metadata file, for good (owner only).
+    fn reveal(&mut self) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_metadata_unfrozen()?;
+        self.check_unrevealed()?;
+        self.reveal.revealed.set(true);
+        log(self.vm(), Revealed { offset: self.reveal.offset.get() });
+        Ok(())
+    }
+}
+
+impl My1155 {
+    /// The number `uri` appends to the base URI for `id`: `id` itself without a drop, none while a
+    /// drop is unrevealed, and its shifted metadata file once revealed.
+    #[cfg(feature = "reveal")]
+    pub(crate) fn metadata_index(&self, id: U256) -> Option<U256> {
+        if self.reveal.provenance_hash.get().is_zero() {
+            return Some(id);
+        }
+        if !self.reveal.revealed.get() {
+            return None;
+        }
+        Some(id.add_mod(self.reveal.offset.get(), self.reveal.size.get()))
+    }
+
+    #[cfg(not(feature = "reveal"))]
+    #[inline(always)]
+    pub(crate) fn metadata_index(&self, id: U256) -> Option<U256> {
+        Some(id)
+    }
+
+    /// Fails unless a provenance hash is set and the drop is unrevealed, returning its size.
+    #[cfg(feature = "reveal")]
+    fn check_unrevealed(&self) -> Result<U256, My1155Error> {
+        if self.reveal.provenance_hash.get().is_zero() {
+            return Err(My1155Error::ProvenanceNotSet(ERC1155ProvenanceNotSet {}));
+        }
+        if self.reveal.revealed.get() {
+            return Err(My1155Error::AlreadyRevealed(ERC1155AlreadyRevealed {}));
+        }
+        Ok(self.reveal.size.get())
+    }
+
+    #[cfg(feature = "reveal")]
+    fn check_metadata_unfrozen(&self) -> Result<(), My1155Error> {
+        if self.metadata_frozen() {
+            return Err(My1155Error::MetadataIsFrozen(ERC1155MetadataIsFrozen {}));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! ERC-2981 royalties, with the `royalties` feature.
+//!
+//! Marketplaces ask `royaltyInfo` what to pay, and to whom, for a sale of a token. Tokens with a
+//! royalty of their own use it, and the others the default royalty, if one is set. Fees are in
+//! basis points of the sale price.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{alloy_primitives::U256, alloy_sol_types::sol, prelude::*};
+#[cfg(feature = "royalties")]
+use stylus_sdk::{
+    alloy_primitives::{aliases::U96, Address},
+    alloy_sol_types::SolCall,
+    storage::Erase,
+};
+
+#[cfg(feature = "royalties")]
+use crate::{My1155, My1155Error};
+
+/// Denominator of royalty fees, which are in basis points.
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
+/// ERC-2981, which is only `royaltyInfo`.
+pub const INTERFACE: Interface = interface!("royaltyInfo"(U256, U256));
+
+sol_storage! {
+    pub struct Royalties {
+        RoyaltyInfo default_royalty;
+        /// Royalties of tokens that don't use the default
+        mapping(uint256 => RoyaltyInfo) token_royalties;
+    }
+
+    pub struct RoyaltyInfo {
+        address receiver;
+        uint96 fee;
+    }
+}
+
+sol! {
+    interface IERC2981 {
+        function royaltyInfo(uint256 tokenId, uint256 salePrice) external view returns (address receiver, uint256 royaltyAmount);
+        function setDefaultRoyalty(address receiver, uint96 feeNumerator) external;
+        function deleteDefaultRoyalty() external;
+        function setTokenRoyalty(uint256 tokenId, address receiver, uint96 feeNumerator) external;
+        function resetTokenRoyalty(uint256 tokenId) external;
+    }
+
+    error ERC2981InvalidDefaultRoyalty(uint256 numerator, uint256 denominator);
+    error ERC2981InvalidDefaultRoyaltyReceiver(address receiver);
+    error ERC2981InvalidTokenRoyalty(uint256 tokenId, uint256 numerator, uint256 denominator);
+    error ERC2981InvalidTokenRoyaltyReceiver(uint256 tokenId, address receiver);
+}
+
+#[cfg(not(feature = "royalties"))]
+disabled!(IRoyalties);
+
+/// The methods of [`IERC2981`].
+#[cfg(feature = "royalties")]
+pub trait IRoyalties {
+    fn royalty_info(&self, token_id: U256, sale_price: U256) -> (Address, U256);
+    fn set_default_royalty(&mut self, receiver: Address, fee_numerator: U96) -> Result<(), My1155Error>;
+    fn delete_default_royalty(&mut self) -> Result<(), My1155Error>;
+    fn set_token_royalty(&mut self, token_id: U256, receiver: Address, fee_numerator: U96) -> Result<(), My1155Error>;
+    fn reset_token_royalty(&mut self, token_id: U256) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "royalties")]
+#[public]
+impl IRoyalties for My1155 {
+    /// The receiver and amount of the royalty on a sale of `token_id` for `sale_price`.
+    fn royalty_info(&self, token_id: U256, sale_price: U256) -> (Address, U256) {
+        let token = self.royalties.token_royalties.get(token_id);
+        let royalty = if token.receiver.get().is_zero() { &self.royalties.default_royalty } else { &*token };
+        let fee = U256::from(royalty.fee.get());
+        // Split the price so a large one can't overflow
+        let denominator = U256::from(FEE_DENOMINATOR);
+        let amount = sale_price / denominator * fee + sale_price % denominator * fee / denominator;
+        (royalty.receiver.get(), amount)
+    }
+
+    /// Sets the royalty of tokens without their own (owner only).
+    fn set_default_royalty(&mut self, receiver: Address, fee_numerator: U96) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC2981::setDefaultRoyaltyCall::SELECTOR)?;
+        self._set_default_royalty(receiver, fee_numerator)
+    }
+
+    /// Removes the default royalty, so only tokens with their own pay one (owner only).
+    fn delete_default_royalty(&mut self) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC2981::deleteDefaultRoyaltyCall::SELECTOR)?;
+        self._delete_default_royalty();
+        Ok(())
+    }
+
+    /// Sets the royalty of `token_id`, instead of the default (owner only).
+    fn set_token_royalty(&mut self, token_id: U256, receiver: Address, fee_numerator: U96) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC2981::setTokenRoyaltyCall::SELECTOR)?;
+        self._set_token_royalty(token_id, receiver, fee_numerator)
+    }
+
+    /// Makes `token_id` use the default royalty again (owner only).
+    fn reset_token_royalty(&mut self, token_id: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC2981::resetTokenRoyaltyCall::SELECTOR)?;
+        self._reset_token_royalty(token_id);
+        Ok(())
+    }
+}
+
+// The owner methods without their checks, which `admin-delay` also calls once an action is due
+#[cfg(feature = "royalties")]
+impl My1155 {
+    pub(crate) fn _set_default_royalty(&mut self, receiver: Address, fee_numerator: U96) -> Result<(), My1155Error> {
+        if fee_numerator > U96::from(FEE_DENOMINATOR) {
+            return Err(My1155Error::InvalidDefaultRoyalty(ERC2981InvalidDefaultRoyalty {
+                numerator: U256::from(fee_numerator),
+                denominator: U256::from(FEE_DENOMINATOR),
+            }));
+        }
+        if receiver.is_zero() {
+            return Err(My1155Error::InvalidDefaultRoyaltyReceiver(ERC2981InvalidDefaultRoyaltyReceiver { receiver }));
+        }
+        self.royalties.default_royalty.receiver.set(receiver);
+        self.royalties.default_royalty.fee.set(fee_numerator);
+        Ok(())
+    }
+
+    pub(crate) fn _delete_default_royalty(&mut self) {
+        self.royalties.default_royalty.receiver.erase();
+        self.royalties.default_royalty.fee.erase();
+    }
+
+    pub(crate) fn _set_token_royalty(
+        &mut self,
+        token_id: U256,
+        receiver: Address,
+        fee_numerator: U96,
+    ) -> Result<(), My1155Error> {
+        if fee_numerator > U96::from(FEE_DENOMINATOR) {
+            return Err(My1155Error::InvalidTokenRoyalty(ERC2981InvalidTokenRoyalty {
+                tokenId: token_id,
+                numerator: U256::from(fee_numerator),
+                denominator: U256::from(FEE_DENOMINATOR),
+            }));
+        }
+        if receiver.is_zero() {
+            return Err(My1155Error::InvalidTokenRoyaltyReceiver(ERC2981InvalidTokenRoyaltyReceiver {
+                tokenId: token_id,
+                receiver,
+            }));
+        }
+        let mut royalty = self.royalties.token_royalties.setter(token_id);
+        royalty.receiver.set(receiver);
+        royalty.fee.set(fee_numerator);
+        Ok(())
+    }
+
+    pub(crate) fn _reset_token_royalty(&mut self, token_id: U256) {
+        let mut royalty = self.royalties.token_royalties.setter(token_id);
+        royalty.receiver.erase();
+        royalty.fee.erase();
+    }
+}
@@ -0,0 +1,55 @@
+//! Non-transferable tokens, with the `soulbound` feature.
+//!
+//! Tokens can still be minted, burned and bridged, but `safeTransferFrom` and
+//! `safeBatchTransferFrom` revert, for credentials and memberships that must stay with the
+//! account they were issued to. Every ID is soulbound, as ERC-5633's `isSoulbound` reports.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{alloy_primitives::U256, alloy_sol_types::sol};
+#[cfg(feature = "soulbound")]
+use stylus_sdk::prelude::*;
+
+use crate::{My1155, My1155Error};
+
+/// ERC-5633, `isSoulbound`.
+pub const INTERFACE: Interface = interface!("isSoulbound"(U256));
+
+sol! {
+    interface IERC5633 {
+        function isSoulbound(uint256 id) external view returns (bool);
+    }
+
+    error ERC1155NonTransferable();
+}
+
+#[cfg(not(feature = "soulbound"))]
+disabled!(ISoulbound);
+
+/// The methods of [`IERC5633`].
+#[cfg(feature = "soulbound")]
+pub trait ISoulbound {
+    fn is_soulbound(&self, id: U256) -> bool;
+}
+
+#[cfg(feature = "soulbound")]
+#[public]
+impl ISoulbound for My1155 {
+    /// Whether `id` can't be transferred, which holds for every ID.
+    fn is_soulbound(&self, _id: U256) -> bool {
+        true
+    }
+}
+
+impl My1155 {
+    /// Fails for transfers between accounts.
+    #[cfg(feature = "soulbound")]
+    pub(crate) fn check_transferable(&self) -> Result<(), My1155Error> {
+        Err(My1155Error::NonTransferable(ERC1155NonTransferable {}))
+    }
+
+    #[cfg(not(feature = "soulbound"))]
+    #[inline(always)]
+    pub(crate) fn check_transferable(&self) -> Result<(), My1155Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Split IDs for non-fungible items within a type, with the `split-ids` feature.
+//!
+//! Follows the split ID convention of EIP-1155: the top bit of an ID marks a non-fungible type,
+//! the upper 128 bits are the base type and the lower 128 bits the index of an item within it.
+//! IDs without the top bit are fungible. The functions below encode and decode IDs in any build,
+//! and the extension exports them as views, mints the next items of a type with
+//! `mintNonFungible`, and rejects mints that would give a non-fungible item a supply above one.
+
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::{My1155, My1155Error};
+
+/// The bit marking non-fungible types.
+pub const TYPE_NF_BIT: U256 = U256::from_limbs([0, 0, 0, 1 << 63]);
+/// The base type bits of an ID.
+pub const TYPE_MASK: U256 = U256::from_limbs([0, 0, u64::MAX, u64::MAX]);
+/// The index bits of an ID.
+pub const NF_INDEX_MASK: U256 = U256::from_limbs([u64::MAX, u64::MAX, 0, 0]);
+
+/// Whether `id` is in a non-fungible type, as a base type or as an item.
+pub fn is_non_fungible(id: U256) -> bool {
+    id & TYPE_NF_BIT == TYPE_NF_BIT
+}
+
+pub fn is_fungible(id: U256) -> bool {
+    !is_non_fungible(id)
+}
+
+/// Whether `id` is an item of a non-fungible type, rather than the type itself.
+pub fn is_non_fungible_item(id: U256) -> bool {
+    is_non_fungible(id) && !(id & NF_INDEX_MASK).is_zero()
+}
+
+/// The base type of `id`, `id` itself for fungible IDs without index bits.
+pub fn base_type(id: U256) -> U256 {
+    id & TYPE_MASK
+}
+
+/// The index of `id` within its type.
+pub fn non_fungible_index(id: U256) -> U256 {
+    id & NF_INDEX_MASK
+}
+
+/// The ID of item `index` of `base_type`.
+pub fn non_fungible_id(base_type: U256, index: U256) -> U256 {
+    (base_type & TYPE_MASK) | (index & NF_INDEX_MASK)
+}
+
+/// [`IERC1155SplitIds`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "isFungible"(U256),
+    "isNonFungible"(U256),
+    "baseType"(U256),
+    "nonFungibleIndex"(U256),
+    "nonFungibleCount"(U256),
+    "mintNonFungible"(Address, U256, U256),
+);
+
+sol_storage! {
+    pub struct SplitIds {
+        /// Number of items `mintNonFungible` has minted of each base type
+        mapping(uint256 => uint256) minted;
+    }
+}
+
+sol! {
+    interface IERC1155SplitIds {
+        function isFungible(uint256 id) external view returns (bool);
+        function isNonFungible(uint256 id) external view returns (bool);
+        function baseType(uint256 id) external view returns (uint256);
+        function nonFungibleIndex(uint256 id) external view returns (uint256);
+        function nonFungibleCount(uint256 baseType) external view returns (uint256);
+        function mintNonFungible(address to, uint256 baseType, uint256 count) external returns (uint256[] ids);
+    }
+
+    error ERC1155InvalidBaseType(uint256 baseType);
+    error ERC1155NonFungibleSupply(uint256 id, uint256 supply);
+}
+
+#[cfg(not(feature = "split-ids"))]
+disabled!(ISplitIds);
+
+/// The methods of [`IERC1155SplitIds`].
+#[cfg(feature = "split-ids")]
+pub trait ISplitIds {
+    fn is_fungible(&self, id: U256) -> bool;
+    fn is_non_fungible(&self, id: U256) -> bool;
+    fn base_type(&self, id: U256) -> U256;
+    fn non_fungible_index(&self, id: U256) -> U256;
+    fn non_fungible_count(&self, base_type: U256) -> U256;
+    fn mint_non_fungible(&mut self, to: Address, base_type: U256, count: U256) -> Result<Vec<U256>, My1155Error>;
+}
+
+#[cfg(feature = "split-ids")]
+#[public]
+impl ISplitIds for My1155 {
+    fn is_fungible(&self, id: U256) -> bool {
+        is_fungible(id)
+    }
+
+    fn is_non_fungible(&self, id: U256) -> bool {
+        is_non_fungible(id)
+    }
+
+    fn base_type(&self, id: U256) -> U256 {
+        base_type(id)
+    }
+
+    fn non_fungible_index(&self, id: U256) -> U256 {
+        non_fungible_index(id)
+    }
+
+    /// Number of items of `base_type` minted with `mintNonFungible`.
+    fn non_fungible_count(&self, base_type: U256) -> U256 {
+        self.split_ids.minted.get(base_type)
+    }
+
+    /// Mints one each of the next `count` items of the non-fungible `base_type` to `to`, indexed
+    /// from 1, and returns their IDs (owner only).
+    fn mint_non_fungible(&mut self, to: Address, base_type: U256, count: U256) -> Result<Vec<U256>, My1155Error> {
+        self.require_not_paused()?;
+        self.only_owner()?;
+        self.check_batch_length(usize::try_from(count).unwrap_or(usize::MAX))?;
+        let mut index = self.split_ids.minted.get(base_type);
+        let last = index.checked_add(count).filter(|last| *last <= NF_INDEX_MASK);
+        let last = match last {
+            Some(last) if is_non_fungible(base_type) && !is_non_fungible_item(base_type) => last,
+            _ => return Err(My1155Error::InvalidBaseType(ERC1155InvalidBaseType { baseType: base_type })),
+        };
+        self.split_ids.minted.insert(base_type, last);
+
+        let mut ids = Vec::new();
+        while index < last {
+            index += U256::from(1);
+            ids.push(base_type | index);
+        }
+        let amounts: Vec<U256> = ids.iter().map(|_| U256::from(1)).collect();
+        self.check_mintable(&ids)?;
+        self.check_rate_limits(Address::ZERO, to, &ids, &amounts)?;
+        self.call_transfer_hooks(Address::ZERO, to, &ids, &amounts)?;
+        let log_chunk = self.log_chunk();
+        self.erc1155._mint_batch(to, &ids, &amounts, log_chunk)?;
+        self.after_update(Address::ZERO, to, &ids)?;
+        self.call_batch_mint_hook(to, ids.clone(), amounts, Vec::new())?;
+        Ok(ids)
+    }
+}
+
+impl My1155 {
+    /// Fails if minting `ids`, when `from` is zero, gave a non-fungible item a supply above one.
+    #[cfg(feature = "split-ids")]
+    pub(crate) fn check_non_fungible_supply(&self, from: Address, ids: &[U256]) -> Result<(), My1155Error> {
+        if !from.is_zero() {
+            return Ok(());
+        }
+        for &id in ids {
+            self.check_item_supply(id, self.erc1155.total_supply(id))?;
+        }
+        Ok(())
+    }
+
+    /// Fails if a `supply` of `id` would be above one for a non-fungible item.
+    #[cfg(feature = "split-ids")]
+    pub(crate) fn check_item_supply(&self, id: U256, supply: U256) -> Result<(), My1155Error> {
+        if is_non_fungible_item(id) && supply > U256::from(1) {
+            return Err(My1155Error::NonFungibleSupply(ERC1155NonFungibleSupply { id, supply }));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "split-ids"))]
+    #[inline(always)]
+    pub(crate) fn check_non_fungible_supply(&self, _from: Address, _ids: &[U256]) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    #[cfg(all(feature = "paid-mint", not(feature = "split-ids")))]
+    #[inline(always)]
+    pub(crate) fn check_item_supply(&self, _id: U256, _supply: U256) -> Result<(), My1155Error> {
+        Ok(())
+    }
+}
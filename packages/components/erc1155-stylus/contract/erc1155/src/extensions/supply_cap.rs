@@ -0,0 +1,105 @@
+//! Maximum supplies per ID, with the `supply-cap` feature.
+//!
+//! The owner caps the supply of an ID, and mints that would take it past the cap revert. IDs
+//! without a cap can be minted freely. A cap can be lowered down to the current supply but never
+//! raised or removed, so holders can rely on it.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155SupplyCap`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!("maxSupply"(U256), "setMaxSupply"(U256, U256));
+
+sol_storage! {
+    pub struct SupplyCap {
+        /// Maximum supply of each ID, zero for IDs without one
+        mapping(uint256 => uint256) max_supply;
+    }
+}
+
+sol! {
+    interface IERC1155SupplyCap {
+        function maxSupply(uint256 id) external view returns (uint256);
+        function setMaxSupply(uint256 id, uint256 maxSupply) external;
+    }
+
+    event MaxSupplySet(uint256 indexed id, uint256 maxSupply);
+
+    error ERC1155ExceededMaxSupply(uint256 id, uint256 supply, uint256 maxSupply);
+    error ERC1155InvalidMaxSupply(uint256 id, uint256 maxSupply);
+}
+
+#[cfg(not(feature = "supply-cap"))]
+disabled!(ISupplyCap);
+
+/// The methods of [`IERC1155SupplyCap`].
+#[cfg(feature = "supply-cap")]
+pub trait ISupplyCap {
+    fn max_supply(&self, id: U256) -> U256;
+    fn set_max_supply(&mut self, id: U256, max_supply: U256) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "supply-cap")]
+#[public]
+impl ISupplyCap for My1155 {
+    /// Maximum supply of `id`, or zero if it has none.
+    fn max_supply(&self, id: U256) -> U256 {
+        self.supply_cap.max_supply.get(id)
+    }
+
+    /// Caps the supply of `id`, or lowers its cap (owner only). The cap can't be below the
+    /// current supply.
+    fn set_max_supply(&mut self, id: U256, max_supply: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let current = self.supply_cap.max_supply.get(id);
+        let raised = !current.is_zero() && max_supply > current;
+        if max_supply.is_zero() || raised || max_supply < self.erc1155.total_supply(id) {
+            return Err(My1155Error::InvalidMaxSupply(ERC1155InvalidMaxSupply { id, maxSupply: max_supply }));
+        }
+        self.supply_cap.max_supply.insert(id, max_supply);
+        log(self.vm(), MaxSupplySet { id, maxSupply: max_supply });
+        Ok(())
+    }
+}
+
+impl My1155 {
+    /// Fails if minting `ids`, when `from` is zero, took one past its cap.
+    #[cfg(feature = "supply-cap")]
+    pub(crate) fn check_max_supply(&self, from: Address, ids: &[U256]) -> Result<(), My1155Error> {
+        if !from.is_zero() {
+            return Ok(());
+        }
+        for &id in ids {
+            self.check_supply_within_cap(id, self.erc1155.total_supply(id))?;
+        }
+        Ok(())
+    }
+
+    /// Fails if a `supply` of `id` would be past its cap.
+    #[cfg(feature = "supply-cap")]
+    pub(crate) fn check_supply_within_cap(&self, id: U256, supply: U256) -> Result<(), My1155Error> {
+        let max_supply = self.supply_cap.max_supply.get(id);
+        if !max_supply.is_zero() && supply > max_supply {
+            return Err(My1155Error::ExceededMaxSupply(ERC1155ExceededMaxSupply { id, supply, maxSupply: max_supply }));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "supply-cap"))]
+    #[inline(always)]
+    pub(crate) fn check_max_supply(&self, _from: Address, _ids: &[U256]) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    #[cfg(all(feature = "paid-mint", not(feature = "supply-cap")))]
+    #[inline(always)]
+    pub(crate) fn check_supply_within_cap(&self, _id: U256, _supply: U256) -> Result<(), My1155Error> {
+        Ok(())
+    }
+}
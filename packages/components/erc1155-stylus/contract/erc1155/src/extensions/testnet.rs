@@ -0,0 +1,128 @@
+//! Conveniences for testnet deployments, with the `testnet` feature.
+//!
+//! `faucetMint` lets anyone mint any ID to any account, up to the faucet limit per call, so
+//! testers and integrators get tokens without asking the owner. The limit is zero, which closes
+//! the faucet, until the owner sets one. Admin actions wait at most
+//! [`MAX_TESTNET_ADMIN_DELAY`] whatever the admin delay, so a delayed change can be tried out in
+//! minutes, and every mint, burn and transfer logs `TestnetBalances` with the balances it left.
+//!
+//! None of this is safe on mainnet, so `build.rs` refuses to build the feature with the `release`
+//! profile, the one `cargo stylus check` and `cargo stylus deploy` build with. Testnet builds use
+//! the `testnet` profile, which is `release` under another name.
+
+#[cfg(feature = "testnet")]
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use crate::My1155;
+#[cfg(feature = "testnet")]
+use crate::My1155Error;
+
+/// [`IERC1155Testnet`], for `supportsInterface`.
+pub const INTERFACE: Interface =
+    interface!("faucetLimit"(), "setFaucetLimit"(U256), "faucetMint"(Address, U256, U256));
+
+/// Most seconds an admin action waits on a testnet build, five minutes.
+pub const MAX_TESTNET_ADMIN_DELAY: u64 = 300;
+
+sol_storage! {
+    pub struct Testnet {
+        /// Most one faucet mint can take, zero while the faucet is closed
+        uint256 faucet_limit;
+    }
+}
+
+sol! {
+    interface IERC1155Testnet {
+        function faucetLimit() external view returns (uint256);
+        function setFaucetLimit(uint256 limit) external;
+        function faucetMint(address to, uint256 id, uint256 amount) external;
+    }
+
+    event FaucetLimitSet(uint256 limit);
+    event FaucetMinted(address indexed caller, address indexed to, uint256 id, uint256 amount);
+    event TestnetBalances(address indexed from, address indexed to, uint256[] ids, uint256[] fromBalances, uint256[] toBalances);
+
+    // A faucet mint asked for more than the faucet limit, which is zero while the faucet is closed
+    error ERC1155FaucetLimitExceeded(uint256 amount, uint256 limit);
+}
+
+#[cfg(not(feature = "testnet"))]
+disabled!(ITestnet);
+
+/// The methods of [`IERC1155Testnet`].
+#[cfg(feature = "testnet")]
+pub trait ITestnet {
+    fn faucet_limit(&self) -> U256;
+    fn set_faucet_limit(&mut self, limit: U256) -> Result<(), My1155Error>;
+    fn faucet_mint(&mut self, to: Address, id: U256, amount: U256) -> Result<(), My1155Error>;
+}
+
+#[cfg(feature = "testnet")]
+#[public]
+impl ITestnet for My1155 {
+    /// Most one faucet mint can take, zero while the faucet is closed.
+    fn faucet_limit(&self) -> U256 {
+        self.testnet.faucet_limit.get()
+    }
+
+    /// Opens the faucet with `limit` per mint, or closes it with zero (owner only).
+    fn set_faucet_limit(&mut self, limit: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.testnet.faucet_limit.set(limit);
+        log(self.vm(), FaucetLimitSet { limit });
+        Ok(())
+    }
+
+    /// Mints `amount` of `id` to `to`, up to the faucet limit. Anyone can call it, as often as
+    /// the rate limits allow.
+    fn faucet_mint(&mut self, to: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let limit = self.testnet.faucet_limit.get();
+        if amount > limit {
+            return Err(My1155Error::FaucetLimitExceeded(ERC1155FaucetLimitExceeded { amount, limit }));
+        }
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+
+        log(self.vm(), FaucetMinted {
+            caller: self.vm().msg_sender(),
+            to,
+            id,
+            amount,
+        });
+        self.call_mint_hook(to, id, amount, Vec::new())
+    }
+}
+
+impl My1155 {
+    /// Logs the balances of `ids` that `from` and `to` have after a move between them, zero for
+    /// the zero address.
+    #[cfg(feature = "testnet")]
+    pub(crate) fn log_testnet_balances(&self, from: Address, to: Address, ids: &[U256]) {
+        let balances = |account: Address| -> Vec<U256> {
+            ids.iter()
+                .map(|&id| if account.is_zero() { U256::ZERO } else { self.erc1155.balance_of(account, id) })
+                .collect()
+        };
+        log(self.vm(), TestnetBalances {
+            from,
+            to,
+            ids: ids.to_vec(),
+            fromBalances: balances(from),
+            toBalances: balances(to),
+        });
+    }
+
+    #[cfg(not(feature = "testnet"))]
+    #[inline(always)]
+    pub(crate) fn log_testnet_balances(&self, _from: Address, _to: Address, _ids: &[U256]) {}
+}
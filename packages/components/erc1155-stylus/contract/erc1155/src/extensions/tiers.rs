@@ -0,0 +1,142 @@
+//! Membership tiers by holdings, with the `tiers` feature.
+//!
+//! The owner picks the IDs that count towards membership, such as the passes of one program, and
+//! the combined balance of them each tier needs. An account is in the highest tier its combined
+//! balance reaches, or tier 0 if it reaches none, and `hasAccess` lets other contracts gate a
+//! feature on a tier without reading the balances themselves. Tiers are numbered from 1 and their
+//! thresholds strictly increase, so a higher tier always includes the lower ones.
+
+#[cfg(feature = "tiers")]
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+#[cfg(feature = "tiers")]
+use stylus_sdk::storage::Erase;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "tiers")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155Tiers`], for `supportsInterface`. `setTiers` is left out so contracts that only
+/// read tiers can check for them.
+pub const INTERFACE: Interface = interface!(
+    "tierIds"(),
+    "tierThresholds"(),
+    "tierBalanceOf"(Address),
+    "tierOf"(Address),
+    "hasAccess"(Address, U256)
+);
+/// Most IDs that count towards the tiers, which bounds the cost of `tierOf`.
+pub const MAX_TIER_IDS: usize = 8;
+/// Most tiers.
+pub const MAX_TIERS: usize = 16;
+
+sol_storage! {
+    pub struct Tiers {
+        /// IDs whose balances count towards the tiers
+        uint256[] ids;
+        /// Combined balance each tier needs, tier 1 first
+        uint256[] thresholds;
+    }
+}
+
+sol! {
+    interface IERC1155Tiers {
+        function tierIds() external view returns (uint256[] memory);
+        function tierThresholds() external view returns (uint256[] memory);
+        function setTiers(uint256[] memory ids, uint256[] memory thresholds) external;
+        function tierBalanceOf(address account) external view returns (uint256);
+        function tierOf(address account) external view returns (uint256);
+        function hasAccess(address account, uint256 tier) external view returns (bool);
+    }
+
+    event TiersSet(uint256[] ids, uint256[] thresholds);
+
+    // No IDs for nonempty tiers, more than `MAX_TIER_IDS` or an ID twice
+    error ERC1155InvalidTierIds(uint256[] ids);
+    // A tier's threshold is zero or not above the previous one, or there are more than `MAX_TIERS`
+    error ERC1155InvalidTierThreshold(uint256 tier, uint256 threshold);
+}
+
+#[cfg(not(feature = "tiers"))]
+disabled!(ITiers);
+
+/// The methods of [`IERC1155Tiers`].
+#[cfg(feature = "tiers")]
+pub trait ITiers {
+    fn tier_ids(&self) -> Vec<U256>;
+    fn tier_thresholds(&self) -> Vec<U256>;
+    fn set_tiers(&mut self, ids: Vec<U256>, thresholds: Vec<U256>) -> Result<(), My1155Error>;
+    fn tier_balance_of(&self, account: Address) -> U256;
+    fn tier_of(&self, account: Address) -> U256;
+    fn has_access(&self, account: Address, tier: U256) -> bool;
+}
+
+#[cfg(feature = "tiers")]
+#[public]
+impl ITiers for My1155 {
+    /// IDs whose balances count towards the tiers.
+    fn tier_ids(&self) -> Vec<U256> {
+        let ids = &self.tiers.ids;
+        (0..ids.len()).filter_map(|i| ids.get(i)).collect()
+    }
+
+    /// Combined balance each tier needs, tier 1 first.
+    fn tier_thresholds(&self) -> Vec<U256> {
+        let thresholds = &self.tiers.thresholds;
+        (0..thresholds.len()).filter_map(|i| thresholds.get(i)).collect()
+    }
+
+    /// Replaces the counted IDs and the tiers' thresholds (owner only). Empty thresholds remove
+    /// the tiers, putting every account in tier 0.
+    fn set_tiers(&mut self, ids: Vec<U256>, thresholds: Vec<U256>) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let duplicate = ids.iter().enumerate().any(|(i, id)| ids[..i].contains(id));
+        if (ids.is_empty() && !thresholds.is_empty()) || ids.len() > MAX_TIER_IDS || duplicate {
+            return Err(My1155Error::InvalidTierIds(ERC1155InvalidTierIds { ids }));
+        }
+        let mut previous = U256::ZERO;
+        for (i, &threshold) in thresholds.iter().enumerate() {
+            if threshold <= previous || i == MAX_TIERS {
+                let tier = U256::from(i + 1);
+                return Err(My1155Error::InvalidTierThreshold(ERC1155InvalidTierThreshold { tier, threshold }));
+            }
+            previous = threshold;
+        }
+
+        self.tiers.ids.erase();
+        for &id in &ids {
+            self.tiers.ids.push(id);
+        }
+        self.tiers.thresholds.erase();
+        for &threshold in &thresholds {
+            self.tiers.thresholds.push(threshold);
+        }
+        log(self.vm(), TiersSet { ids, thresholds });
+        Ok(())
+    }
+
+    /// `account`'s combined balance of the counted IDs.
+    fn tier_balance_of(&self, account: Address) -> U256 {
+        let mut balance = U256::ZERO;
+        for id in self.tier_ids() {
+            balance = balance.saturating_add(self.erc1155.balance_of(account, id));
+        }
+        balance
+    }
+
+    /// Highest tier `account` reaches, or 0 if it reaches none.
+    fn tier_of(&self, account: Address) -> U256 {
+        let balance = self.tier_balance_of(account);
+        let reached = self.tier_thresholds().into_iter().take_while(|&threshold| balance >= threshold).count();
+        U256::from(reached)
+    }
+
+    /// Whether `account` is in `tier` or a higher one. Every account has access to tier 0.
+    fn has_access(&self, account: Address, tier: U256) -> bool {
+        self.tier_of(account) >= tier
+    }
+}
@@ -0,0 +1,215 @@
+//! External contracts consulted before tokens move, with the `transfer-hooks` feature.
+//!
+//! The owner attaches up to [`MAX_TRANSFER_HOOKS`] contracts, such as a compliance check, a fee
+//! module or a game's rules, and the token calls each with `beforeTransfer` before every transfer
+//! and mint, in the order they were attached, so behaviour can be added after deployment without
+//! an upgrade. A `market` sale counts as a transfer from the seller to the buyer, and burns don't
+//! call the hooks, so no hook can stop holders from burning their tokens.
+//!
+//! Each hook runs with the gas limit the owner gave it and must answer with `beforeTransfer`'s
+//! selector. A fail-closed hook that reverts, runs out of gas or answers anything else fails the
+//! call with `ERC1155TransferHookRejected`, while a fail-open one is skipped and logged with
+//! `TransferHookFailed`. A caller can't skip a fail-open hook by starving it: a hook that failed
+//! with too little gas left to have had its whole limit fails the call whatever its policy. The
+//! token isn't reentrant, so hooks can't call back into it.
+
+#[cfg(feature = "transfer-hooks")]
+use alloc::vec::Vec;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+#[cfg(feature = "transfer-hooks")]
+use stylus_sdk::{
+    alloy_primitives::{FixedBytes, U64},
+    alloy_sol_types::{SolCall, SolValue},
+};
+
+#[cfg(feature = "transfer-hooks")]
+use crate::profiling;
+use crate::{My1155, My1155Error};
+
+/// Most hooks the owner can attach.
+pub const MAX_TRANSFER_HOOKS: usize = 8;
+
+/// [`IERC1155TransferHooks`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setTransferHook"(Address, u64, bool),
+    "transferHooks"(),
+    "transferHookConfig"(Address),
+);
+
+sol_storage! {
+    pub struct TransferHooks {
+        /// Hooks in the order they're called
+        address[] hooks;
+        /// Configuration of each hook, zeros for contracts that aren't attached
+        mapping(address => TransferHook) configs;
+    }
+
+    pub struct TransferHook {
+        /// Gas the hook runs with
+        uint64 gas_limit;
+        /// Whether the hook failing fails the call, rather than being skipped
+        bool fail_closed;
+    }
+}
+
+sol! {
+    interface IERC1155TransferHooks {
+        function setTransferHook(address hook, uint64 gasLimit, bool failClosed) external;
+        function transferHooks() external view returns (address[] memory);
+        function transferHookConfig(address hook) external view returns (uint64 gasLimit, bool failClosed);
+    }
+
+    // Called before `values` of `ids` move from `from` to `to`, where a zero `from` mints
+    interface ITransferHook {
+        function beforeTransfer(address operator, address from, address to, uint256[] ids, uint256[] values) external returns (bytes4);
+    }
+
+    event TransferHookSet(address indexed hook, uint64 gasLimit, bool failClosed);
+    event TransferHookFailed(address indexed hook, bytes returnData);
+
+    // A fail-closed hook reverted or didn't return its selector, or a hook had too little gas
+    error ERC1155TransferHookRejected(address hook, bytes returnData);
+    // The hook is the zero address or has no code
+    error ERC1155InvalidTransferHook(address hook);
+    // The owner has attached as many hooks as it can
+    error ERC1155TooManyTransferHooks(uint256 max);
+}
+
+#[cfg(not(feature = "transfer-hooks"))]
+disabled!(ITransferHooks);
+
+/// The methods of [`IERC1155TransferHooks`].
+#[cfg(feature = "transfer-hooks")]
+pub trait ITransferHooks {
+    fn set_transfer_hook(&mut self, hook: Address, gas_limit: u64, fail_closed: bool) -> Result<(), My1155Error>;
+    fn transfer_hooks(&self) -> Vec<Address>;
+    fn transfer_hook_config(&self, hook: Address) -> (u64, bool);
+}
+
+#[cfg(feature = "transfer-hooks")]
+#[public]
+impl ITransferHooks for My1155 {
+    /// Attaches `hook`, or changes its gas limit and policy if it's attached (owner only). A zero
+    /// gas limit detaches it.
+    fn set_transfer_hook(&mut self, hook: Address, gas_limit: u64, fail_closed: bool) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC1155TransferHooks::setTransferHookCall::SELECTOR)?;
+        self._set_transfer_hook(hook, gas_limit, fail_closed)
+    }
+
+    /// Attached hooks, in the order they're called.
+    fn transfer_hooks(&self) -> Vec<Address> {
+        let hooks = &self.transfer_hooks.hooks;
+        (0..hooks.len()).filter_map(|index| hooks.get(index)).collect()
+    }
+
+    /// Gas limit and policy of `hook`, zeros if it isn't attached.
+    fn transfer_hook_config(&self, hook: Address) -> (u64, bool) {
+        let config = self.transfer_hooks.configs.get(hook);
+        (config.gas_limit.get().to::<u64>(), config.fail_closed.get())
+    }
+}
+
+impl My1155 {
+    /// `setTransferHook` without its checks, which `admin-delay` also calls once the action is due.
+    #[cfg(feature = "transfer-hooks")]
+    pub(crate) fn _set_transfer_hook(
+        &mut self,
+        hook: Address,
+        gas_limit: u64,
+        fail_closed: bool,
+    ) -> Result<(), My1155Error> {
+        let attached = self.transfer_hook_config(hook).0 != 0;
+        let fail_closed = fail_closed && gas_limit != 0;
+        if gas_limit == 0 && attached {
+            let hooks = &mut self.transfer_hooks.hooks;
+            let index = (0..hooks.len()).find(|&index| hooks.get(index) == Some(hook)).unwrap_or_default();
+            let last = hooks.pop().unwrap_or_default();
+            if let Some(mut moved) = hooks.setter(index) {
+                moved.set(last);
+            }
+        } else if gas_limit != 0 && !attached {
+            if hook.is_zero() || self.vm().code_size(hook) == 0 {
+                return Err(My1155Error::InvalidTransferHook(ERC1155InvalidTransferHook { hook }));
+            }
+            if self.transfer_hooks.hooks.len() >= MAX_TRANSFER_HOOKS {
+                let max = U256::from(MAX_TRANSFER_HOOKS);
+                return Err(My1155Error::TooManyTransferHooks(ERC1155TooManyTransferHooks { max }));
+            }
+            self.transfer_hooks.hooks.push(hook);
+        }
+        let mut config = self.transfer_hooks.configs.setter(hook);
+        config.gas_limit.set(U64::from(gas_limit));
+        config.fail_closed.set(fail_closed);
+        log(self.vm(), TransferHookSet { hook, gasLimit: gas_limit, failClosed: fail_closed });
+        Ok(())
+    }
+
+    /// Calls each hook before `values` of `ids` move from `from` to `to`, where a zero `from`
+    /// mints.
+    #[cfg(feature = "transfer-hooks")]
+    pub(crate) fn call_transfer_hooks(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: &[U256],
+        values: &[U256],
+    ) -> Result<(), My1155Error> {
+        let count = self.transfer_hooks.hooks.len();
+        if count == 0 {
+            return Ok(());
+        }
+        let probe = profiling::start(self.vm());
+        let calldata = ITransferHook::beforeTransferCall {
+            operator: self.vm().msg_sender(),
+            from,
+            to,
+            ids: ids.to_vec(),
+            values: values.to_vec(),
+        }
+        .abi_encode();
+        for index in 0..count {
+            let hook = self.transfer_hooks.hooks.get(index).unwrap_or_default();
+            let (gas_limit, fail_closed) = self.transfer_hook_config(hook);
+            let context = calls::context::Call::new().gas(gas_limit);
+            let result = self.vm().call(&context, hook, &calldata);
+            let selector = ITransferHook::beforeTransferCall::SELECTOR;
+            let accepted = result
+                .as_ref()
+                .is_ok_and(|output| FixedBytes::<4>::abi_decode(output, true).is_ok_and(|answer| answer == selector));
+            if accepted {
+                continue;
+            }
+            let return_data = result.unwrap_or_else(Vec::<u8>::from);
+            // The call keeps back 1/64 of the gas left, so with less than this left the hook may
+            // have had less than its limit
+            let starved = self.vm().evm_gas_left() < gas_limit / 63;
+            if fail_closed || starved {
+                return Err(My1155Error::TransferHookRejected(ERC1155TransferHookRejected {
+                    hook,
+                    returnData: return_data.into(),
+                }));
+            }
+            log(self.vm(), TransferHookFailed { hook, returnData: return_data.into() });
+        }
+        profiling::finish(self.vm(), profiling::TRANSFER_HOOKS, probe);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "transfer-hooks"))]
+    #[inline(always)]
+    pub(crate) fn call_transfer_hooks(
+        &mut self,
+        _from: Address,
+        _to: Address,
+        _ids: &[U256],
+        _values: &[U256],
+    ) -> Result<(), My1155Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//! Transfer restrictions that keep sales on royalty-paying marketplaces, ERC-721C style, with the
+//! `transfer-security` feature.
+//!
+//! The owner picks a security level, and every transfer is checked against it before anything
+//! moves:
+//!
+//! - [`LEVEL_OPEN`], the default: no restrictions
+//! - [`LEVEL_NO_CONTRACTS`]: contracts can't call transfers unless they're allowlisted, whether
+//!   they move their own tokens or act as an operator, so marketplace contracts that skip
+//!   royalties can't settle sales
+//! - [`LEVEL_ALLOWLIST`]: operators moving someone else's tokens must be allowlisted, while
+//!   holders still transfer their own, over the counter
+//! - [`LEVEL_ALLOWLIST_NO_OTC`]: every transfer goes through an allowlisted operator, holders'
+//!   own transfers included
+//!
+//! The owner allowlists the operators it trusts to pay royalties with `setAllowedOperator`.
+//! Marketplaces check a sale with `isTransferAllowed` before listing it. Mints and burns are never
+//! restricted, and neither is the token's own `composable` escrow, which isn't an operator. Sales
+//! on the token's own `market`, which pays no royalties, are checked as the buyer taking the
+//! seller's items. A contract calling from its constructor has no code yet, and an EOA delegated
+//! with EIP-7702 has some, so `LEVEL_NO_CONTRACTS` is a deterrent rather than a guarantee.
+//!
+//! Both setters wait for `admin-delay`, since a stricter level or a removed operator can stop
+//! holders from selling.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{alloy_primitives::Address, alloy_sol_types::sol, prelude::*};
+#[cfg(feature = "transfer-security")]
+use stylus_sdk::{alloy_primitives::U8, alloy_sol_types::SolCall};
+
+use crate::{My1155, My1155Error};
+
+/// No restrictions.
+pub const LEVEL_OPEN: u8 = 0;
+/// Contracts must be allowlisted to call transfers.
+pub const LEVEL_NO_CONTRACTS: u8 = 1;
+/// Operators must be allowlisted, holders transfer their own tokens.
+pub const LEVEL_ALLOWLIST: u8 = 2;
+/// Every transfer goes through an allowlisted operator.
+pub const LEVEL_ALLOWLIST_NO_OTC: u8 = 3;
+
+/// [`IERC1155TransferSecurity`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "transferSecurityLevel"(),
+    "setTransferSecurityLevel"(u8),
+    "isAllowedOperator"(Address),
+    "setAllowedOperator"(Address, bool),
+    "isTransferAllowed"(Address, Address, Address),
+);
+
+sol_storage! {
+    pub struct TransferSecurity {
+        /// One of the `LEVEL_*` constants
+        uint8 level;
+        /// Operators the owner trusts to pay royalties
+        mapping(address => bool) allowed_operators;
+    }
+}
+
+sol! {
+    interface IERC1155TransferSecurity {
+        function transferSecurityLevel() external view returns (uint8);
+        function setTransferSecurityLevel(uint8 level) external;
+        function isAllowedOperator(address operator) external view returns (bool);
+        function setAllowedOperator(address operator, bool allowed) external;
+        function isTransferAllowed(address caller, address from, address to) external view returns (bool);
+    }
+
+    event TransferSecurityLevelSet(uint8 level);
+    event AllowedOperatorSet(address indexed operator, bool allowed);
+
+    // The security level doesn't let `caller` transfer `from`'s tokens
+    error ERC1155TransferRestricted(address caller, address from, uint8 level);
+    // The level isn't one of the `LEVEL_*` constants
+    error ERC1155InvalidTransferSecurityLevel(uint8 level);
+}
+
+#[cfg(not(feature = "transfer-security"))]
+disabled!(ITransferSecurity);
+
+/// The methods of [`IERC1155TransferSecurity`].
+#[cfg(feature = "transfer-security")]
+pub trait ITransferSecurity {
+    fn transfer_security_level(&self) -> u8;
+    fn set_transfer_security_level(&mut self, level: u8) -> Result<(), My1155Error>;
+    fn is_allowed_operator(&self, operator: Address) -> bool;
+    fn set_allowed_operator(&mut self, operator: Address, allowed: bool) -> Result<(), My1155Error>;
+    fn is_transfer_allowed(&self, caller: Address, from: Address, to: Address) -> bool;
+}
+
+#[cfg(feature = "transfer-security")]
+#[public]
+impl ITransferSecurity for My1155 {
+    /// The security level transfers are checked against, one of the `LEVEL_*` constants.
+    fn transfer_security_level(&self) -> u8 {
+        self.transfer_security.level.get().to::<u8>()
+    }
+
+    /// Sets the security level transfers are checked against (owner only).
+    fn set_transfer_security_level(&mut self, level: u8) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC1155TransferSecurity::setTransferSecurityLevelCall::SELECTOR)?;
+        self._set_transfer_security_level(level)
+    }
+
+    /// Whether the owner trusts `operator` to pay royalties.
+    fn is_allowed_operator(&self, operator: Address) -> bool {
+        self.transfer_security.allowed_operators.get(operator)
+    }
+
+    /// Adds `operator` to the allowlist, or removes it (owner only).
+    fn set_allowed_operator(&mut self, operator: Address, allowed: bool) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(IERC1155TransferSecurity::setAllowedOperatorCall::SELECTOR)?;
+        self._set_allowed_operator(operator, allowed);
+        Ok(())
+    }
+
+    /// Whether the security level lets `caller` transfer `from`'s tokens to `to`, for
+    /// marketplaces to check a sale before listing it. Doesn't check balances or approvals.
+    fn is_transfer_allowed(&self, caller: Address, from: Address, to: Address) -> bool {
+        self.transfer_allowed(caller, from, to)
+    }
+}
+
+impl My1155 {
+    /// Fails if the security level doesn't let the caller transfer `from`'s tokens to `to`.
+    #[cfg(feature = "transfer-security")]
+    pub(crate) fn check_transfer_security(&self, from: Address, to: Address) -> Result<(), My1155Error> {
+        let caller = self.vm().msg_sender();
+        if self.transfer_allowed(caller, from, to) {
+            return Ok(());
+        }
+        let level = self.transfer_security_level();
+        Err(My1155Error::TransferRestricted(ERC1155TransferRestricted { caller, from, level }))
+    }
+
+    #[cfg(not(feature = "transfer-security"))]
+    #[inline(always)]
+    pub(crate) fn check_transfer_security(&self, _from: Address, _to: Address) -> Result<(), My1155Error> {
+        Ok(())
+    }
+
+    #[cfg(feature = "transfer-security")]
+    fn transfer_allowed(&self, caller: Address, from: Address, to: Address) -> bool {
+        if from.is_zero() || to.is_zero() || self.is_allowed_operator(caller) {
+            return true;
+        }
+        match self.transfer_security_level() {
+            LEVEL_NO_CONTRACTS => self.vm().code_size(caller) == 0,
+            LEVEL_ALLOWLIST => caller == from,
+            LEVEL_ALLOWLIST_NO_OTC => false,
+            _ => true,
+        }
+    }
+
+    /// `setTransferSecurityLevel` without its checks, which `admin-delay` also calls once the
+    /// action is due.
+    #[cfg(feature = "transfer-security")]
+    pub(crate) fn _set_transfer_security_level(&mut self, level: u8) -> Result<(), My1155Error> {
+        if level > LEVEL_ALLOWLIST_NO_OTC {
+            return Err(My1155Error::InvalidTransferSecurityLevel(ERC1155InvalidTransferSecurityLevel { level }));
+        }
+        self.transfer_security.level.set(U8::from(level));
+        log(self.vm(), TransferSecurityLevelSet { level });
+        Ok(())
+    }
+
+    /// `setAllowedOperator` without its checks, which `admin-delay` also calls once the action is
+    /// due.
+    #[cfg(feature = "transfer-security")]
+    pub(crate) fn _set_allowed_operator(&mut self, operator: Address, allowed: bool) {
+        self.transfer_security.allowed_operators.insert(operator, allowed);
+        log(self.vm(), AllowedOperatorSet { operator, allowed });
+    }
+}
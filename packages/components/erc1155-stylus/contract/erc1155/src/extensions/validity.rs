@@ -0,0 +1,182 @@
+//! Validity periods per ID, with the `validity` feature.
+//!
+//! The owner gives an ID, such as an event ticket or a seasonal pass, a time its balances start
+//! counting and a time they stop. The token's own balances never change with the time:
+//! `validBalanceOf` reports a balance outside the period as zero, for contracts and frontends to
+//! check instead of `balanceOf`. Once an ID has expired, anyone can burn an account's balance of
+//! it with `burnExpired`, and is paid the ID's bounty, if the owner funded one, for cleaning up.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+#[cfg(feature = "validity")]
+use crate::{My1155, My1155Error};
+
+/// [`IERC1155Validity`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "setValidity"(U256, U256, U256),
+    "validity"(U256),
+    "isValid"(U256),
+    "validBalanceOf"(Address, U256),
+    "setExpiryBounty"(U256, U256),
+    "withdrawExpiryBounty"(U256),
+    "expiryBounty"(U256),
+    "burnExpired"(Address, U256),
+);
+
+sol_storage! {
+    pub struct Validity {
+        /// Time each ID's balances start counting, zero for IDs valid from the start
+        mapping(uint256 => uint256) start;
+        /// Time each ID's balances stop counting, zero for IDs that don't expire
+        mapping(uint256 => uint256) end;
+        /// ETH paid for each burn of an expired balance, by ID
+        mapping(uint256 => uint256) bounty;
+        /// ETH left to pay the bounties of each ID
+        mapping(uint256 => uint256) bounty_funds;
+    }
+}
+
+sol! {
+    interface IERC1155Validity {
+        function setValidity(uint256 id, uint256 start, uint256 end) external;
+        function validity(uint256 id) external view returns (uint256 start, uint256 end);
+        function isValid(uint256 id) external view returns (bool);
+        function validBalanceOf(address account, uint256 id) external view returns (uint256);
+        function setExpiryBounty(uint256 id, uint256 bounty) external payable;
+        function withdrawExpiryBounty(uint256 id) external;
+        function expiryBounty(uint256 id) external view returns (uint256 bounty, uint256 funds);
+        function burnExpired(address account, uint256 id) external returns (uint256 amount);
+    }
+
+    event ValiditySet(uint256 indexed id, uint256 start, uint256 end);
+    event ExpiryBountySet(uint256 indexed id, uint256 bounty, uint256 funds);
+    event ExpiredBurned(
+        address indexed account, uint256 indexed id, uint256 amount, address indexed burner, uint256 bounty
+    );
+
+    error ERC1155InvalidValidity(uint256 id, uint256 start, uint256 end);
+    error ERC1155NotExpired(uint256 id, uint256 end);
+    error ERC1155BountyTransferFailed(address account, uint256 amount);
+}
+
+#[cfg(not(feature = "validity"))]
+disabled!(IValidity);
+
+/// The methods of [`IERC1155Validity`].
+#[cfg(feature = "validity")]
+pub trait IValidity {
+    fn set_validity(&mut self, id: U256, start: U256, end: U256) -> Result<(), My1155Error>;
+    fn validity(&self, id: U256) -> (U256, U256);
+    fn is_valid(&self, id: U256) -> bool;
+    fn valid_balance_of(&self, account: Address, id: U256) -> U256;
+    fn set_expiry_bounty(&mut self, id: U256, bounty: U256) -> Result<(), My1155Error>;
+    fn withdraw_expiry_bounty(&mut self, id: U256) -> Result<(), My1155Error>;
+    fn expiry_bounty(&self, id: U256) -> (U256, U256);
+    fn burn_expired(&mut self, account: Address, id: U256) -> Result<U256, My1155Error>;
+}
+
+#[cfg(feature = "validity")]
+#[public]
+impl IValidity for My1155 {
+    /// Sets the period `id`'s balances count in (owner only). A zero `start` or `end` leaves
+    /// that side open; otherwise `start` must be before `end`.
+    fn set_validity(&mut self, id: U256, start: U256, end: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if !end.is_zero() && start >= end {
+            return Err(My1155Error::InvalidValidity(ERC1155InvalidValidity { id, start, end }));
+        }
+        self.validity.start.insert(id, start);
+        self.validity.end.insert(id, end);
+        log(self.vm(), ValiditySet { id, start, end });
+        Ok(())
+    }
+
+    /// Time `id`'s balances start and stop counting, zero for an open side.
+    fn validity(&self, id: U256) -> (U256, U256) {
+        (self.validity.start.get(id), self.validity.end.get(id))
+    }
+
+    /// Whether `id`'s balances count now.
+    fn is_valid(&self, id: U256) -> bool {
+        let now = U256::from(self.vm().block_timestamp());
+        let (start, end) = self.validity(id);
+        now >= start && (end.is_zero() || now < end)
+    }
+
+    /// `account`'s balance of `id`, or zero outside the ID's validity period.
+    fn valid_balance_of(&self, account: Address, id: U256) -> U256 {
+        if !self.is_valid(id) {
+            return U256::ZERO;
+        }
+        self.erc1155.balance_of(account, id)
+    }
+
+    /// Sets the ETH paid for each burn of an expired balance of `id` (owner only). ETH sent
+    /// with the call is added to the ID's bounty funds.
+    #[payable]
+    fn set_expiry_bounty(&mut self, id: U256, bounty: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let funds = self.validity.bounty_funds.get(id) + self.vm().msg_value();
+        self.validity.bounty.insert(id, bounty);
+        self.validity.bounty_funds.insert(id, funds);
+        log(self.vm(), ExpiryBountySet { id, bounty, funds });
+        Ok(())
+    }
+
+    /// Sends the owner what's left of `id`'s bounty funds (owner only).
+    fn withdraw_expiry_bounty(&mut self, id: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        let owner = self.vm().msg_sender();
+        let amount = self.validity.bounty_funds.get(id);
+        self.validity.bounty_funds.insert(id, U256::ZERO);
+        self.pay_bounty(owner, amount)?;
+        log(self.vm(), ExpiryBountySet { id, bounty: self.validity.bounty.get(id), funds: U256::ZERO });
+        Ok(())
+    }
+
+    /// ETH paid for each burn of an expired balance of `id`, and the ETH left to pay it.
+    fn expiry_bounty(&self, id: U256) -> (U256, U256) {
+        (self.validity.bounty.get(id), self.validity.bounty_funds.get(id))
+    }
+
+    /// Burns `account`'s whole balance of `id` once the ID has expired, paying the caller the
+    /// ID's bounty, or what's left of its funds, if the balance wasn't zero.
+    fn burn_expired(&mut self, account: Address, id: U256) -> Result<U256, My1155Error> {
+        self.require_not_paused()?;
+        let end = self.validity.end.get(id);
+        if end.is_zero() || U256::from(self.vm().block_timestamp()) < end {
+            return Err(My1155Error::NotExpired(ERC1155NotExpired { id, end }));
+        }
+        let amount = self.erc1155.balance_of(account, id);
+        if amount.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        self.erc1155._burn(account, id, amount)?;
+        self.after_update(account, Address::ZERO, &[id])?;
+
+        let funds = self.validity.bounty_funds.get(id);
+        let bounty = self.validity.bounty.get(id).min(funds);
+        self.validity.bounty_funds.insert(id, funds - bounty);
+        let burner = self.vm().msg_sender();
+        self.pay_bounty(burner, bounty)?;
+        log(self.vm(), ExpiredBurned { account, id, amount, burner, bounty });
+        Ok(amount)
+    }
+}
+
+#[cfg(feature = "validity")]
+impl My1155 {
+    fn pay_bounty(&mut self, account: Address, amount: U256) -> Result<(), My1155Error> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.vm()
+            .transfer_eth(account, amount)
+            .map_err(|_| My1155Error::BountyTransferFailed(ERC1155BountyTransferFailed { account, amount }))
+    }
+}
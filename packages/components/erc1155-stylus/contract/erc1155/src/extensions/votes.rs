@@ -0,0 +1,141 @@
+//! Voting power delegated per ID, with the `votes` feature.
+//!
+//! A holder delegates the votes of their balance of an ID with `delegate`, to another account or
+//! to themselves, without moving the tokens, so a membership pass can stay in a passive holder's
+//! wallet while a delegate votes with it. Balances only count once delegated, and each ID has its
+//! own delegates and tallies. Tallies are checkpointed by block like the balances of `checkpoints`,
+//! which the feature enables, so a governor reads them with `getPastVotes` at a proposal's
+//! snapshot and `getPastTotalSupply` for its quorum.
+
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+};
+
+use super::checkpoints::Checkpoint;
+#[cfg(feature = "votes")]
+use super::checkpoints::{push, value_at};
+use crate::My1155;
+#[cfg(feature = "votes")]
+use crate::My1155Error;
+
+/// [`IERC1155Votes`], for `supportsInterface`.
+pub const INTERFACE: Interface = interface!(
+    "delegate"(U256, Address),
+    "delegates"(Address, U256),
+    "getVotes"(Address, U256),
+    "getPastVotes"(Address, U256, U256),
+);
+
+sol_storage! {
+    pub struct Votes {
+        /// Delegate of each account's votes by ID, zero for votes nobody casts
+        mapping(address => mapping(uint256 => address)) delegates;
+        /// Balance each account's delegate last counted for it, by ID
+        mapping(address => mapping(uint256 => uint256)) delegated;
+        /// History of each delegate's votes by ID, oldest first
+        mapping(address => mapping(uint256 => Checkpoint[])) tallies;
+    }
+}
+
+sol! {
+    interface IERC1155Votes {
+        function delegate(uint256 id, address delegatee) external;
+        function delegates(address account, uint256 id) external view returns (address);
+        function getVotes(address account, uint256 id) external view returns (uint256);
+        function getPastVotes(address account, uint256 id, uint256 blockNumber) external view returns (uint256);
+    }
+
+    event DelegateChanged(address indexed delegator, uint256 indexed id, address fromDelegate, address toDelegate);
+    event DelegateVotesChanged(address indexed delegate, uint256 indexed id, uint256 previousVotes, uint256 newVotes);
+}
+
+#[cfg(not(feature = "votes"))]
+disabled!(IVotes);
+
+/// The methods of [`IERC1155Votes`].
+#[cfg(feature = "votes")]
+pub trait IVotes {
+    fn delegate(&mut self, id: U256, delegatee: Address);
+    fn delegates(&self, account: Address, id: U256) -> Address;
+    fn get_votes(&self, account: Address, id: U256) -> U256;
+    fn get_past_votes(&self, account: Address, id: U256, block_number: U256) -> Result<U256, My1155Error>;
+}
+
+#[cfg(feature = "votes")]
+#[public]
+impl IVotes for My1155 {
+    /// Moves the votes of the caller's balance of `id` to `delegatee`, or withdraws them with a
+    /// zero `delegatee`. Later balance changes move the votes along.
+    fn delegate(&mut self, id: U256, delegatee: Address) {
+        let delegator = self.vm().msg_sender();
+        let previous = self.delegates(delegator, id);
+        self.votes.delegates.setter(delegator).insert(id, delegatee);
+        log(self.vm(), DelegateChanged { delegator, id, fromDelegate: previous, toDelegate: delegatee });
+
+        let counted = self.votes.delegated.get(delegator).get(id);
+        let balance = if delegatee.is_zero() { U256::ZERO } else { self.erc1155.balance_of(delegator, id) };
+        self.votes.delegated.setter(delegator).insert(id, balance);
+        self.move_votes(previous, id, counted, U256::ZERO);
+        self.move_votes(delegatee, id, U256::ZERO, balance);
+    }
+
+    /// Account casting `account`'s votes of `id`, zero if nobody does.
+    fn delegates(&self, account: Address, id: U256) -> Address {
+        self.votes.delegates.get(account).get(id)
+    }
+
+    /// Votes of `id` delegated to `account`.
+    fn get_votes(&self, account: Address, id: U256) -> U256 {
+        let tallies = self.votes.tallies.get(account);
+        let history = tallies.get(id);
+        let last = history.len().checked_sub(1).and_then(|last| history.get(last));
+        last.map_or(U256::ZERO, |checkpoint| checkpoint.value.get())
+    }
+
+    /// Votes of `id` delegated to `account` at the end of block `block_number`, which must have
+    /// passed.
+    fn get_past_votes(&self, account: Address, id: U256, block_number: U256) -> Result<U256, My1155Error> {
+        let block = self.past_block(block_number)?;
+        Ok(value_at(&self.votes.tallies.get(account).get(id), block))
+    }
+}
+
+impl My1155 {
+    /// Moves votes with the balances of `from` and `to` after balances of `ids` moved, where a
+    /// zero `from` mints and a zero `to` burns.
+    #[cfg(feature = "votes")]
+    pub(crate) fn track_votes(&mut self, from: Address, to: Address, ids: &[U256]) {
+        for &id in ids {
+            for account in [from, to] {
+                let delegatee = self.delegates(account, id);
+                if account.is_zero() || delegatee.is_zero() {
+                    continue;
+                }
+                let counted = self.votes.delegated.get(account).get(id);
+                let balance = self.erc1155.balance_of(account, id);
+                self.votes.delegated.setter(account).insert(id, balance);
+                self.move_votes(delegatee, id, counted, balance);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "votes"))]
+    #[inline(always)]
+    pub(crate) fn track_votes(&mut self, _from: Address, _to: Address, _ids: &[U256]) {}
+
+    /// Replaces `removed` of `delegatee`'s votes of `id` with `added`.
+    #[cfg(feature = "votes")]
+    fn move_votes(&mut self, delegatee: Address, id: U256, removed: U256, added: U256) {
+        if delegatee.is_zero() || removed == added {
+            return;
+        }
+        let previous = self.get_votes(delegatee, id);
+        let votes = previous - removed + added;
+        let block = self.vm().block_number();
+        push(&mut self.votes.tallies.setter(delegatee).setter(id), block, votes);
+        log(self.vm(), DelegateVotesChanged { delegate: delegatee, id, previousVotes: previous, newVotes: votes });
+    }
+}
@@ -0,0 +1,1071 @@
+//! A complete Solidity interface for contracts that call the token cross-VM.
+//!
+//! The SDK's `export-abi` output spreads the methods over one interface per inherited module,
+//! leaves out events and drops the names of error parameters. [`solidity_interface`] flattens
+//! the methods into a single `IERC1155Token` and adds the events and errors with their parameter
+//! names. Every event and error declaration is checked against the Rust type it describes, and
+//! the errors against those the SDK exports, so the interface can't drift from the contract.
+//!
+//! The SDK doesn't export the methods of the enabled [extensions](crate::extensions), which are
+//! declared here too and checked against the selectors of their Solidity interfaces.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use alloy_json_abi::{Error, Event, Function};
+use cradle_access_control::{
+    AccessControlBadConfirmation, AccessControlMemberOutOfBounds, AccessControlUnauthorizedAccount, RoleAdminChanged,
+    RoleGranted, RoleRevoked,
+};
+use cradle_ownable::{OwnableInvalidOwner, OwnableUnauthorizedAccount, OwnershipTransferred};
+use cradle_pausable::{EnforcedPause, ExpectedPause, Paused, Unpaused};
+use stylus_sdk::{
+    abi::GenerateAbi,
+    alloy_sol_types::{SolCall, SolError, SolEvent, TopicList},
+};
+
+use crate::{
+    erc1155::{
+        ApprovalForAll, ERC1155BalanceOverflow, ERC1155DuplicateId, ERC1155InsufficientBalance,
+        ERC1155InsufficientSupply, ERC1155InvalidApprover, ERC1155InvalidArrayLength, ERC1155InvalidOperator,
+        ERC1155InvalidReceiver, ERC1155InvalidSender, ERC1155MissingApprovalForAll, ERC1155ZeroAmount, TransferBatch,
+        TransferSingle,
+    },
+    extensions, AlreadyInitialized, ApprovalForAllExpiry, BatchLogChunkSet, CrosschainBurn, CrosschainMint,
+    ERC1155BatchTooLong, ERC1155IdRetired, ERC1155InvalidApprovalExpiry, ERC1155InvalidSeries, ERC1155MetadataIsFrozen,
+    ERC1155OrderAlreadyFulfilled, ERC1155ReceiverHookFailed, ERC1155SupplyIsFrozen, ERC1155TooManySeries,
+    ExistenceChanged, GuardianExpired, GuardianExpirySet, MaxBatchLengthSet, MetadataFrozen, MintedWithReceipt, My1155,
+    PauseRegistrySet, ReceiverHookCalled, RetireBurnedOutSet, SeriesUriSet, SupplyFrozen, UnauthorizedInitializer, URI,
+};
+
+const HEADER: &str = "\
+/**
+ * Generated from the erc1155-stylus contract, do not edit. To regenerate, run in contract/erc1155:
+ *   cargo run --features export-abi -- interface > IERC1155Token.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface IERC1155Token {
+";
+
+/// `HEADER`, with the extensions of this build in the command that regenerates it.
+fn header() -> String {
+    let features: Vec<_> = core::iter::once("export-abi").chain(extensions::enabled()).collect();
+    HEADER.replace("--features export-abi", &format!("--features {}", features.join(",")))
+}
+
+/// Renders `IERC1155Token.sol`.
+///
+/// # Panics
+///
+/// If a declaration below doesn't match its Rust type, or the contract can revert with an
+/// error that has no declaration here.
+pub fn solidity_interface() -> String {
+    let exported = format!("{}", Exported);
+    let mut functions = declarations(&exported, "function ");
+    functions.extend(extension_functions());
+
+    let errors = errors();
+    let exported_errors = declarations(&exported, "error ");
+    for line in &exported_errors {
+        let selector = parse_error(line).selector();
+        assert!(
+            errors.iter().any(|declaration| parse_error(declaration).selector() == selector),
+            "`{line}` is exported but isn't declared in the interface"
+        );
+    }
+    for declaration in &errors {
+        let selector = parse_error(declaration).selector();
+        assert!(
+            exported_errors.iter().any(|line| parse_error(line).selector() == selector),
+            "`{declaration}` is declared but the contract doesn't export it"
+        );
+    }
+
+    let mut out = header();
+    for (i, group) in [events(), errors, functions].iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for declaration in group {
+            out.push_str(&format!("    {declaration};\n"));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn events() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut declarations = alloc::vec![
+        event::<TransferSingle>(
+            "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+        ),
+        event::<TransferBatch>(
+            "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+        ),
+        event::<ApprovalForAll>("event ApprovalForAll(address indexed account, address indexed operator, bool approved)"),
+        event::<ApprovalForAllExpiry>(
+            "event ApprovalForAllExpiry(address indexed account, address indexed operator, uint256 expires)",
+        ),
+        event::<CrosschainMint>(
+            "event CrosschainMint(address indexed to, uint256 id, uint256 amount, address indexed sender)",
+        ),
+        event::<CrosschainBurn>(
+            "event CrosschainBurn(address indexed from, uint256 id, uint256 amount, address indexed sender)",
+        ),
+        event::<GuardianExpirySet>("event GuardianExpirySet(uint256 expiry)"),
+        event::<BatchLogChunkSet>("event BatchLogChunkSet(uint256 entries)"),
+        event::<PauseRegistrySet>("event PauseRegistrySet(address registry)"),
+        event::<ExistenceChanged>("event ExistenceChanged(uint256 indexed id, bool exists)"),
+        event::<RetireBurnedOutSet>("event RetireBurnedOutSet(bool retire)"),
+        event::<MaxBatchLengthSet>("event MaxBatchLengthSet(uint256 entries)"),
+        event::<ReceiverHookCalled>("event ReceiverHookCalled(address indexed receiver, bytes returnData, uint256 gasUsed)"),
+        event::<MetadataFrozen>("event MetadataFrozen(string uri)"),
+        event::<SupplyFrozen>("event SupplyFrozen(uint256 indexed id, uint256 totalSupply)"),
+        event::<URI>("event URI(string value, uint256 indexed id)"),
+        event::<SeriesUriSet>("event SeriesUriSet(uint256 indexed startId, uint256 indexed endId, string base)"),
+        event::<MintedWithReceipt>(
+            "event MintedWithReceipt(bytes32 indexed orderId, address indexed to, uint256 id, uint256 amount, address indexed processor)",
+        ),
+        event::<OwnershipTransferred>(
+            "event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)",
+        ),
+        event::<Paused>("event Paused(address account)"),
+        event::<Unpaused>("event Unpaused(address account)"),
+        event::<RoleGranted>("event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender)"),
+        event::<RoleRevoked>("event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender)"),
+        event::<RoleAdminChanged>(
+            "event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole)",
+        ),
+    ];
+    #[cfg(feature = "supply-cap")]
+    declarations.push(event::<extensions::supply_cap::MaxSupplySet>(
+        "event MaxSupplySet(uint256 indexed id, uint256 maxSupply)",
+    ));
+    #[cfg(feature = "meta-tx")]
+    declarations.push(event::<cradle_crypto::NoncesInvalidated>(
+        "event NoncesInvalidated(address indexed account, uint256 newNonce)",
+    ));
+    #[cfg(feature = "paid-mint")]
+    {
+        use extensions::paid_mint::*;
+        declarations.extend([
+            event::<MintPriceSet>("event MintPriceSet(uint256 indexed id, uint256 price, address indexed creator)"),
+            event::<MintPurchased>(
+                "event MintPurchased(address indexed buyer, address indexed to, uint256 indexed id, uint256 amount, uint256 paid)",
+            ),
+            event::<ProceedsWithdrawn>("event ProceedsWithdrawn(address indexed account, uint256 amount)"),
+            event::<RefundWindowSet>("event RefundWindowSet(uint256 indexed id, uint256 window)"),
+            event::<MintEscrowed>(
+                "event MintEscrowed(uint256 indexed purchaseId, address indexed buyer, uint256 indexed id, uint256 amount, uint256 paid, uint256 refundableUntil)",
+            ),
+            event::<MintRefunded>(
+                "event MintRefunded(uint256 indexed purchaseId, address indexed buyer, uint256 amount, uint256 refund)",
+            ),
+            event::<EscrowReleased>(
+                "event EscrowReleased(uint256 indexed purchaseId, address indexed payee, uint256 amount)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "id-ranges")]
+    declarations.push(event::<extensions::id_ranges::IdRangeReserved>(
+        "event IdRangeReserved(uint256 indexed series, address indexed creator, uint256 start, uint256 end)",
+    ));
+    #[cfg(feature = "rate-limits")]
+    {
+        use extensions::rate_limits::*;
+        declarations.extend([
+            event::<RateLimitSet>(
+                "event RateLimitSet(uint256 indexed id, uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour)",
+            ),
+            event::<RateLimitReached>(
+                "event RateLimitReached(uint256 indexed id, address indexed account, bool hourly, uint256 limit)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "admin-delay")]
+    {
+        use extensions::admin_delay::*;
+        declarations.extend([
+            event::<AdminDelaySet>("event AdminDelaySet(uint256 delay)"),
+            event::<AdminActionScheduled>(
+                "event AdminActionScheduled(bytes32 indexed id, bytes data, bytes32 salt, uint256 readyAt)",
+            ),
+            event::<AdminActionExecuted>("event AdminActionExecuted(bytes32 indexed id)"),
+            event::<AdminActionCancelled>("event AdminActionCancelled(bytes32 indexed id)"),
+        ]);
+    }
+    #[cfg(feature = "tiers")]
+    declarations.push(event::<extensions::tiers::TiersSet>("event TiersSet(uint256[] ids, uint256[] thresholds)"));
+    #[cfg(feature = "validity")]
+    {
+        use extensions::validity::*;
+        declarations.extend([
+            event::<ValiditySet>("event ValiditySet(uint256 indexed id, uint256 start, uint256 end)"),
+            event::<ExpiryBountySet>("event ExpiryBountySet(uint256 indexed id, uint256 bounty, uint256 funds)"),
+            event::<ExpiredBurned>(
+                "event ExpiredBurned(address indexed account, uint256 indexed id, uint256 amount, address indexed burner, uint256 bounty)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "packs")]
+    {
+        use extensions::packs::*;
+        declarations.extend([
+            event::<DropTableSet>(
+                "event DropTableSet(uint256 indexed packId, uint256[] ids, uint256[] weights, uint256 dropsPerPack)",
+            ),
+            event::<PackRandomnessSet>("event PackRandomnessSet(address randomness)"),
+            event::<PackOpenRequested>(
+                "event PackOpenRequested(uint256 indexed requestId, address indexed opener, uint256 indexed packId, uint256 amount)",
+            ),
+            event::<PackOpened>(
+                "event PackOpened(uint256 indexed requestId, address indexed opener, uint256 indexed packId, bytes32 randomness, uint256[] drops)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "market")]
+    {
+        use extensions::market::*;
+        declarations.extend([
+            event::<Listed>(
+                "event Listed(uint256 indexed listingId, address indexed seller, uint256 indexed id, uint256 amount, address priceToken, uint256 price)",
+            ),
+            event::<ListingBought>(
+                "event ListingBought(uint256 indexed listingId, address indexed buyer, uint256 amount, uint256 paid, uint256 fee)",
+            ),
+            event::<ListingCancelled>("event ListingCancelled(uint256 indexed listingId)"),
+            event::<MarketFeeSet>("event MarketFeeSet(address recipient, uint256 feeBps)"),
+        ]);
+    }
+    #[cfg(feature = "composable")]
+    {
+        use extensions::composable::*;
+        declarations.extend([
+            event::<ChildAttached>(
+                "event ChildAttached(address indexed parentContract, uint256 indexed parentId, uint256 indexed id, uint256 amount, address from)",
+            ),
+            event::<ChildDetached>(
+                "event ChildDetached(address indexed parentContract, uint256 indexed parentId, uint256 indexed id, uint256 amount, address to)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "attested-mint")]
+    declarations.push(event::<extensions::attested_mint::MintGateSet>(
+        "event MintGateSet(uint256 indexed id, address registry, bytes32 schema, address attester, uint256 until)",
+    ));
+    #[cfg(feature = "votes")]
+    {
+        use extensions::votes::{DelegateChanged, DelegateVotesChanged};
+        declarations.extend([
+            event::<DelegateChanged>(
+                "event DelegateChanged(address indexed delegator, uint256 indexed id, address fromDelegate, address toDelegate)",
+            ),
+            event::<DelegateVotesChanged>(
+                "event DelegateVotesChanged(address indexed delegate, uint256 indexed id, uint256 previousVotes, uint256 newVotes)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "transfer-hooks")]
+    {
+        use extensions::transfer_hooks::{TransferHookFailed, TransferHookSet};
+        declarations.extend([
+            event::<TransferHookSet>("event TransferHookSet(address indexed hook, uint64 gasLimit, bool failClosed)"),
+            event::<TransferHookFailed>("event TransferHookFailed(address indexed hook, bytes returnData)"),
+        ]);
+    }
+    #[cfg(feature = "modules")]
+    declarations.push(event::<extensions::modules::ModuleSet>(
+        "event ModuleSet(bytes4 indexed selector, address module)",
+    ));
+    #[cfg(feature = "holder-snapshots")]
+    declarations.push(event::<extensions::holder_snapshots::HoldersSnapshotted>(
+        "event HoldersSnapshotted(uint256 indexed snapshotId, uint256 indexed id, uint64 blockNumber, uint256 holderCount)",
+    ));
+    #[cfg(feature = "testnet")]
+    {
+        use extensions::testnet::{FaucetLimitSet, FaucetMinted, TestnetBalances};
+        declarations.extend([
+            event::<FaucetLimitSet>("event FaucetLimitSet(uint256 limit)"),
+            event::<FaucetMinted>(
+                "event FaucetMinted(address indexed caller, address indexed to, uint256 id, uint256 amount)",
+            ),
+            event::<TestnetBalances>(
+                "event TestnetBalances(address indexed from, address indexed to, uint256[] ids, uint256[] fromBalances, uint256[] toBalances)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "transfer-security")]
+    {
+        use extensions::transfer_security::{AllowedOperatorSet, TransferSecurityLevelSet};
+        declarations.extend([
+            event::<TransferSecurityLevelSet>("event TransferSecurityLevelSet(uint8 level)"),
+            event::<AllowedOperatorSet>("event AllowedOperatorSet(address indexed operator, bool allowed)"),
+        ]);
+    }
+    #[cfg(feature = "reveal")]
+    {
+        use extensions::reveal::{ProvenanceHashSet, RevealOffsetSet, Revealed};
+        declarations.extend([
+            event::<ProvenanceHashSet>("event ProvenanceHashSet(bytes32 hash, uint256 size)"),
+            event::<RevealOffsetSet>("event RevealOffsetSet(uint256 offset)"),
+            event::<Revealed>("event Revealed(uint256 offset)"),
+        ]);
+    }
+    #[cfg(feature = "open-editions")]
+    {
+        use extensions::open_editions::{OpenEditionFinalized, OpenEditionSet};
+        declarations.extend([
+            event::<OpenEditionSet>("event OpenEditionSet(uint256 indexed id, uint256 start, uint256 end)"),
+            event::<OpenEditionFinalized>("event OpenEditionFinalized(uint256 indexed id, uint256 size)"),
+        ]);
+    }
+    #[cfg(feature = "profiling")]
+    declarations.push(event::<crate::profiling::GasProfiled>(
+        "event GasProfiled(bytes32 indexed section, uint64 inkStart, uint64 inkEnd, uint64 gasStart, uint64 gasEnd)",
+    ));
+    declarations
+}
+
+fn errors() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut declarations = alloc::vec![
+        error::<AlreadyInitialized>("error AlreadyInitialized()"),
+        error::<UnauthorizedInitializer>("error UnauthorizedInitializer(address caller, address deployer)"),
+        error::<GuardianExpired>("error GuardianExpired(uint256 expiry)"),
+        error::<ERC1155InvalidApprovalExpiry>("error ERC1155InvalidApprovalExpiry(uint256 expires)"),
+        error::<ERC1155IdRetired>("error ERC1155IdRetired(uint256 id)"),
+        error::<ERC1155BatchTooLong>("error ERC1155BatchTooLong(uint256 length, uint256 maxLength)"),
+        error::<ERC1155ReceiverHookFailed>(
+            "error ERC1155ReceiverHookFailed(address receiver, bytes returnData, uint256 gasUsed)",
+        ),
+        error::<ERC1155MetadataIsFrozen>("error ERC1155MetadataIsFrozen()"),
+        error::<ERC1155SupplyIsFrozen>("error ERC1155SupplyIsFrozen(uint256 id)"),
+        error::<ERC1155InvalidSeries>("error ERC1155InvalidSeries(uint256 startId, uint256 endId)"),
+        error::<ERC1155TooManySeries>("error ERC1155TooManySeries(uint256 maxSeries)"),
+        error::<ERC1155OrderAlreadyFulfilled>("error ERC1155OrderAlreadyFulfilled(bytes32 orderId)"),
+        error::<ERC1155InsufficientBalance>(
+            "error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 id)",
+        ),
+        error::<ERC1155InvalidSender>("error ERC1155InvalidSender(address sender)"),
+        error::<ERC1155InvalidReceiver>("error ERC1155InvalidReceiver(address receiver)"),
+        error::<ERC1155InvalidApprover>("error ERC1155InvalidApprover(address approver)"),
+        error::<ERC1155InvalidOperator>("error ERC1155InvalidOperator(address operator)"),
+        error::<ERC1155InvalidArrayLength>("error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength)"),
+        error::<ERC1155MissingApprovalForAll>("error ERC1155MissingApprovalForAll(address operator, address owner)"),
+        error::<ERC1155BalanceOverflow>("error ERC1155BalanceOverflow(uint256 id, uint256 balance)"),
+        error::<ERC1155InsufficientSupply>("error ERC1155InsufficientSupply(uint256 id, uint256 supply, uint256 needed)"),
+        error::<ERC1155ZeroAmount>("error ERC1155ZeroAmount(uint256 id)"),
+        error::<ERC1155DuplicateId>("error ERC1155DuplicateId(uint256 id)"),
+        error::<OwnableUnauthorizedAccount>("error OwnableUnauthorizedAccount(address account)"),
+        error::<OwnableInvalidOwner>("error OwnableInvalidOwner(address owner)"),
+        error::<EnforcedPause>("error EnforcedPause()"),
+        error::<ExpectedPause>("error ExpectedPause()"),
+        error::<AccessControlUnauthorizedAccount>(
+            "error AccessControlUnauthorizedAccount(address account, bytes32 neededRole)",
+        ),
+        error::<AccessControlBadConfirmation>("error AccessControlBadConfirmation()"),
+        error::<AccessControlMemberOutOfBounds>("error AccessControlMemberOutOfBounds(bytes32 role, uint256 index)"),
+    ];
+    #[cfg(feature = "royalties")]
+    {
+        use extensions::royalties::*;
+        declarations.extend([
+            error::<ERC2981InvalidDefaultRoyalty>(
+                "error ERC2981InvalidDefaultRoyalty(uint256 numerator, uint256 denominator)",
+            ),
+            error::<ERC2981InvalidDefaultRoyaltyReceiver>("error ERC2981InvalidDefaultRoyaltyReceiver(address receiver)"),
+            error::<ERC2981InvalidTokenRoyalty>(
+                "error ERC2981InvalidTokenRoyalty(uint256 tokenId, uint256 numerator, uint256 denominator)",
+            ),
+            error::<ERC2981InvalidTokenRoyaltyReceiver>(
+                "error ERC2981InvalidTokenRoyaltyReceiver(uint256 tokenId, address receiver)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "enumerable")]
+    declarations.push(error::<extensions::enumerable::ERC1155OutOfBoundsIndex>(
+        "error ERC1155OutOfBoundsIndex(address owner, uint256 index)",
+    ));
+    #[cfg(feature = "supply-cap")]
+    declarations.extend([
+        error::<extensions::supply_cap::ERC1155ExceededMaxSupply>(
+            "error ERC1155ExceededMaxSupply(uint256 id, uint256 supply, uint256 maxSupply)",
+        ),
+        error::<extensions::supply_cap::ERC1155InvalidMaxSupply>(
+            "error ERC1155InvalidMaxSupply(uint256 id, uint256 maxSupply)",
+        ),
+    ]);
+    #[cfg(feature = "soulbound")]
+    declarations.push(error::<extensions::soulbound::ERC1155NonTransferable>("error ERC1155NonTransferable()"));
+    #[cfg(feature = "meta-tx")]
+    declarations.extend([
+        error::<extensions::meta_tx::ERC1155ExpiredSignature>("error ERC1155ExpiredSignature(uint256 deadline)"),
+        error::<extensions::meta_tx::ERC1155InvalidSignature>("error ERC1155InvalidSignature(address owner)"),
+        error::<cradle_crypto::InvalidNonceInvalidation>(
+            "error InvalidNonceInvalidation(address account, uint256 currentNonce, uint256 newNonce)",
+        ),
+    ]);
+    #[cfg(feature = "paid-mint")]
+    declarations.extend([
+        error::<extensions::paid_mint::ERC1155MintNotForSale>("error ERC1155MintNotForSale(uint256 id)"),
+        error::<extensions::paid_mint::ERC1155IncorrectPayment>(
+            "error ERC1155IncorrectPayment(uint256 id, uint256 expected, uint256 paid)",
+        ),
+        error::<extensions::paid_mint::ERC1155ProceedsTransferFailed>(
+            "error ERC1155ProceedsTransferFailed(address account, uint256 amount)",
+        ),
+        error::<extensions::paid_mint::ERC1155InvalidRefund>(
+            "error ERC1155InvalidRefund(uint256 purchaseId, address account, uint256 amount)",
+        ),
+        error::<extensions::paid_mint::ERC1155RefundWindowOpen>(
+            "error ERC1155RefundWindowOpen(uint256 purchaseId, uint256 refundableUntil)",
+        ),
+    ]);
+    #[cfg(feature = "id-ranges")]
+    declarations.extend([
+        error::<extensions::id_ranges::ERC1155InvalidIdRange>("error ERC1155InvalidIdRange(address creator, uint256 count)"),
+        error::<extensions::id_ranges::ERC1155UnauthorizedSeriesMinter>(
+            "error ERC1155UnauthorizedSeriesMinter(uint256 series, address account)",
+        ),
+        error::<extensions::id_ranges::ERC1155SeriesExhausted>("error ERC1155SeriesExhausted(uint256 series)"),
+    ]);
+    #[cfg(feature = "split-ids")]
+    declarations.extend([
+        error::<extensions::split_ids::ERC1155InvalidBaseType>("error ERC1155InvalidBaseType(uint256 baseType)"),
+        error::<extensions::split_ids::ERC1155NonFungibleSupply>(
+            "error ERC1155NonFungibleSupply(uint256 id, uint256 supply)",
+        ),
+    ]);
+    #[cfg(feature = "rate-limits")]
+    declarations.push(error::<extensions::rate_limits::ERC1155RateLimitExceeded>(
+        "error ERC1155RateLimitExceeded(uint256 id, address account, uint256 volume, uint256 limit)",
+    ));
+    #[cfg(feature = "admin-delay")]
+    declarations.extend([
+        error::<extensions::admin_delay::ERC1155AdminDelayRequired>("error ERC1155AdminDelayRequired(bytes4 selector)"),
+        error::<extensions::admin_delay::ERC1155InvalidAdminAction>("error ERC1155InvalidAdminAction(bytes4 selector)"),
+        error::<extensions::admin_delay::ERC1155AdminActionAlreadyScheduled>(
+            "error ERC1155AdminActionAlreadyScheduled(bytes32 id)",
+        ),
+        error::<extensions::admin_delay::ERC1155UnknownAdminAction>("error ERC1155UnknownAdminAction(bytes32 id)"),
+        error::<extensions::admin_delay::ERC1155AdminActionNotReady>(
+            "error ERC1155AdminActionNotReady(bytes32 id, uint256 readyAt)",
+        ),
+    ]);
+    #[cfg(feature = "tiers")]
+    declarations.extend([
+        error::<extensions::tiers::ERC1155InvalidTierIds>("error ERC1155InvalidTierIds(uint256[] ids)"),
+        error::<extensions::tiers::ERC1155InvalidTierThreshold>(
+            "error ERC1155InvalidTierThreshold(uint256 tier, uint256 threshold)",
+        ),
+    ]);
+    #[cfg(feature = "validity")]
+    declarations.extend([
+        error::<extensions::validity::ERC1155InvalidValidity>(
+            "error ERC1155InvalidValidity(uint256 id, uint256 start, uint256 end)",
+        ),
+        error::<extensions::validity::ERC1155NotExpired>("error ERC1155NotExpired(uint256 id, uint256 end)"),
+        error::<extensions::validity::ERC1155BountyTransferFailed>(
+            "error ERC1155BountyTransferFailed(address account, uint256 amount)",
+        ),
+    ]);
+    #[cfg(feature = "packs")]
+    {
+        use extensions::packs::*;
+        declarations.extend([
+            error::<ERC1155InvalidDropTable>("error ERC1155InvalidDropTable(uint256 packId)"),
+            error::<ERC1155OpeningsPending>("error ERC1155OpeningsPending(uint256 packId)"),
+            error::<ERC1155NotAPack>("error ERC1155NotAPack(uint256 id)"),
+            error::<ERC1155InvalidPackAmount>("error ERC1155InvalidPackAmount(uint256 amount)"),
+            error::<ERC1155UnknownOpening>("error ERC1155UnknownOpening(uint256 requestId)"),
+            error::<ERC1155RandomnessPending>("error ERC1155RandomnessPending(uint256 requestId)"),
+            error::<ERC1155RandomnessCallFailed>("error ERC1155RandomnessCallFailed(address randomness)"),
+            error::<ERC1155UnauthorizedRandomness>("error ERC1155UnauthorizedRandomness(address caller)"),
+        ]);
+    }
+    #[cfg(feature = "market")]
+    {
+        use extensions::market::*;
+        declarations.extend([
+            error::<ERC1155InvalidListing>("error ERC1155InvalidListing(uint256 amount, address priceToken)"),
+            error::<ERC1155InsufficientListing>(
+                "error ERC1155InsufficientListing(uint256 listingId, uint256 available, uint256 requested)",
+            ),
+            error::<ERC1155NotSeller>("error ERC1155NotSeller(uint256 listingId, address caller)"),
+            error::<ERC1155InvalidMarketFee>("error ERC1155InvalidMarketFee(address recipient, uint256 feeBps)"),
+            error::<ERC1155PaymentFailed>("error ERC1155PaymentFailed(address token, address to, uint256 amount)"),
+        ]);
+    }
+    #[cfg(feature = "composable")]
+    {
+        use extensions::composable::*;
+        declarations.extend([
+            error::<ERC1155NotParentOwner>(
+                "error ERC1155NotParentOwner(address parentContract, uint256 parentId, address account)",
+            ),
+            error::<ERC1155InvalidChild>(
+                "error ERC1155InvalidChild(address parentContract, uint256 parentId, uint256 id)",
+            ),
+            error::<ERC1155InsufficientChildBalance>(
+                "error ERC1155InsufficientChildBalance(uint256 parentId, uint256 id, uint256 balance, uint256 needed)",
+            ),
+            error::<ERC1155TooManyChildIds>("error ERC1155TooManyChildIds(address parentContract, uint256 parentId)"),
+        ]);
+    }
+    #[cfg(feature = "attested-mint")]
+    declarations.push(error::<extensions::attested_mint::ERC1155MissingAttestation>(
+        "error ERC1155MissingAttestation(address account, uint256 id, bytes32 schema)",
+    ));
+    #[cfg(feature = "checkpoints")]
+    declarations.push(error::<extensions::checkpoints::ERC1155FutureLookup>(
+        "error ERC1155FutureLookup(uint256 blockNumber, uint256 currentBlock)",
+    ));
+    #[cfg(feature = "transfer-hooks")]
+    {
+        use extensions::transfer_hooks::*;
+        declarations.extend([
+            error::<ERC1155TransferHookRejected>("error ERC1155TransferHookRejected(address hook, bytes returnData)"),
+            error::<ERC1155InvalidTransferHook>("error ERC1155InvalidTransferHook(address hook)"),
+            error::<ERC1155TooManyTransferHooks>("error ERC1155TooManyTransferHooks(uint256 max)"),
+        ]);
+    }
+    #[cfg(feature = "modules")]
+    declarations.extend([
+        error::<extensions::modules::ERC1155InvalidModule>("error ERC1155InvalidModule(address module)"),
+        error::<extensions::modules::ERC1155UnknownSelector>("error ERC1155UnknownSelector(bytes4 selector)"),
+    ]);
+    #[cfg(feature = "holder-snapshots")]
+    declarations.push(error::<extensions::holder_snapshots::ERC1155UnknownSnapshot>(
+        "error ERC1155UnknownSnapshot(uint256 snapshotId)",
+    ));
+    #[cfg(feature = "testnet")]
+    declarations.push(error::<extensions::testnet::ERC1155FaucetLimitExceeded>(
+        "error ERC1155FaucetLimitExceeded(uint256 amount, uint256 limit)",
+    ));
+    #[cfg(feature = "transfer-security")]
+    {
+        use extensions::transfer_security::*;
+        declarations.extend([
+            error::<ERC1155TransferRestricted>(
+                "error ERC1155TransferRestricted(address caller, address from, uint8 level)",
+            ),
+            error::<ERC1155InvalidTransferSecurityLevel>("error ERC1155InvalidTransferSecurityLevel(uint8 level)"),
+        ]);
+    }
+    #[cfg(feature = "reveal")]
+    {
+        use extensions::reveal::*;
+        declarations.extend([
+            error::<ERC1155ProvenanceAlreadySet>("error ERC1155ProvenanceAlreadySet(bytes32 hash)"),
+            error::<ERC1155ProvenanceNotSet>("error ERC1155ProvenanceNotSet()"),
+            error::<ERC1155InvalidProvenance>("error ERC1155InvalidProvenance(bytes32 hash, uint256 size)"),
+            error::<ERC1155InvalidRevealOffset>("error ERC1155InvalidRevealOffset(uint256 offset, uint256 size)"),
+            error::<ERC1155AlreadyRevealed>("error ERC1155AlreadyRevealed()"),
+        ]);
+    }
+    #[cfg(feature = "open-editions")]
+    {
+        use extensions::open_editions::*;
+        declarations.extend([
+            error::<ERC1155InvalidOpenEdition>(
+                "error ERC1155InvalidOpenEdition(uint256 id, uint256 start, uint256 end)",
+            ),
+            error::<ERC1155OpenEditionNotOpen>(
+                "error ERC1155OpenEditionNotOpen(uint256 id, uint256 start, uint256 end)",
+            ),
+            error::<ERC1155OpenEditionStillOpen>("error ERC1155OpenEditionStillOpen(uint256 id, uint256 end)"),
+            error::<ERC1155NotOpenEdition>("error ERC1155NotOpenEdition(uint256 id)"),
+        ]);
+    }
+    declarations
+}
+
+/// The methods of the enabled extensions, which the SDK's output leaves out.
+fn extension_functions() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut declarations = Vec::new();
+    #[cfg(feature = "royalties")]
+    {
+        use extensions::royalties::IERC2981::*;
+        declarations.extend([
+            function::<royaltyInfoCall>(
+                "function royaltyInfo(uint256 tokenId, uint256 salePrice) external view returns (address receiver, uint256 royaltyAmount)",
+            ),
+            function::<setDefaultRoyaltyCall>("function setDefaultRoyalty(address receiver, uint96 feeNumerator) external"),
+            function::<deleteDefaultRoyaltyCall>("function deleteDefaultRoyalty() external"),
+            function::<setTokenRoyaltyCall>(
+                "function setTokenRoyalty(uint256 tokenId, address receiver, uint96 feeNumerator) external",
+            ),
+            function::<resetTokenRoyaltyCall>("function resetTokenRoyalty(uint256 tokenId) external"),
+        ]);
+    }
+    #[cfg(feature = "enumerable")]
+    {
+        use extensions::enumerable::IERC1155Enumerable::*;
+        declarations.extend([
+            function::<tokenCountCall>("function tokenCount() external view returns (uint256)"),
+            function::<tokenByIndexCall>("function tokenByIndex(uint256 index) external view returns (uint256)"),
+            function::<tokenCountOfCall>("function tokenCountOf(address owner) external view returns (uint256)"),
+            function::<tokenOfOwnerByIndexCall>(
+                "function tokenOfOwnerByIndex(address owner, uint256 index) external view returns (uint256)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "supply-cap")]
+    {
+        use extensions::supply_cap::IERC1155SupplyCap::*;
+        declarations.extend([
+            function::<maxSupplyCall>("function maxSupply(uint256 id) external view returns (uint256)"),
+            function::<setMaxSupplyCall>("function setMaxSupply(uint256 id, uint256 maxSupply) external"),
+        ]);
+    }
+    #[cfg(feature = "soulbound")]
+    declarations.push(function::<extensions::soulbound::IERC5633::isSoulboundCall>(
+        "function isSoulbound(uint256 id) external view returns (bool)",
+    ));
+    #[cfg(feature = "meta-tx")]
+    {
+        use extensions::meta_tx::IERC1155Permit::*;
+        declarations.extend([
+            function::<permitCall>(
+                "function permit(address owner, address operator, bool approved, uint256 deadline, bytes calldata signature) external",
+            ),
+            function::<noncesCall>("function nonces(address owner) external view returns (uint256)"),
+            function::<DOMAIN_SEPARATORCall>("function DOMAIN_SEPARATOR() external view returns (bytes32)"),
+            function::<extensions::meta_tx::invalidateNoncesUpToCall>(
+                "function invalidateNoncesUpTo(uint256 newNonce) external",
+            ),
+        ]);
+    }
+    #[cfg(feature = "paid-mint")]
+    {
+        use extensions::paid_mint::IERC1155PaidMint::*;
+        declarations.extend([
+            function::<setMintPriceCall>("function setMintPrice(uint256 id, uint256 price, address creator) external"),
+            function::<mintPriceCall>(
+                "function mintPrice(uint256 id) external view returns (uint256 price, address creator)",
+            ),
+            function::<mintPaidCall>("function mintPaid(address to, uint256 id, uint256 amount) external payable"),
+            function::<quotePublicMintCall>(
+                "function quotePublicMint(uint256 id, uint256 amount) external view returns (uint256 cost, bytes4 reason)",
+            ),
+            function::<revenueOfCall>("function revenueOf(uint256 id) external view returns (uint256)"),
+            function::<proceedsOfCall>("function proceedsOf(address account) external view returns (uint256)"),
+            function::<withdrawProceedsCall>("function withdrawProceeds() external"),
+            function::<setRefundWindowCall>("function setRefundWindow(uint256 id, uint256 window) external"),
+            function::<refundWindowCall>("function refundWindow(uint256 id) external view returns (uint256)"),
+            function::<purchaseCall>(
+                "function purchase(uint256 purchaseId) external view returns (address buyer, address holder, address payee, uint256 id, uint256 price, uint256 escrowed, uint256 refundableUntil)",
+            ),
+            function::<refundMintCall>("function refundMint(uint256 purchaseId, uint256 amount) external"),
+            function::<releaseEscrowCall>("function releaseEscrow(uint256[] purchaseIds) external"),
+        ]);
+    }
+    #[cfg(feature = "id-ranges")]
+    {
+        use extensions::id_ranges::IERC1155IdRanges::*;
+        declarations.extend([
+            function::<reserveIdRangeCall>(
+                "function reserveIdRange(address creator, uint256 count) external returns (uint256 start, uint256 end)",
+            ),
+            function::<mintNewCall>(
+                "function mintNew(address to, uint256 series, uint256 amount) external returns (uint256 id)",
+            ),
+            function::<seriesOfCall>("function seriesOf(uint256 id) external view returns (uint256)"),
+            function::<seriesCall>(
+                "function series(uint256 series) external view returns (address creator, uint256 start, uint256 end, uint256 minted)",
+            ),
+            function::<seriesCountCall>("function seriesCount() external view returns (uint256)"),
+        ]);
+    }
+    #[cfg(feature = "split-ids")]
+    {
+        use extensions::split_ids::IERC1155SplitIds::*;
+        declarations.extend([
+            function::<isFungibleCall>("function isFungible(uint256 id) external view returns (bool)"),
+            function::<isNonFungibleCall>("function isNonFungible(uint256 id) external view returns (bool)"),
+            function::<baseTypeCall>("function baseType(uint256 id) external view returns (uint256)"),
+            function::<nonFungibleIndexCall>("function nonFungibleIndex(uint256 id) external view returns (uint256)"),
+            function::<nonFungibleCountCall>(
+                "function nonFungibleCount(uint256 baseType) external view returns (uint256)",
+            ),
+            function::<mintNonFungibleCall>(
+                "function mintNonFungible(address to, uint256 baseType, uint256 count) external returns (uint256[] ids)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "rate-limits")]
+    {
+        use extensions::rate_limits::IERC1155RateLimits::*;
+        declarations.extend([
+            function::<setRateLimitCall>(
+                "function setRateLimit(uint256 id, uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour) external",
+            ),
+            function::<rateLimitCall>(
+                "function rateLimit(uint256 id) external view returns (uint256 perBlock, uint256 perHour, uint256 accountPerBlock, uint256 accountPerHour)",
+            ),
+            function::<volumeCall>(
+                "function volume(uint256 id) external view returns (uint256 blockVolume, uint256 hourVolume)",
+            ),
+            function::<accountVolumeCall>(
+                "function accountVolume(uint256 id, address account) external view returns (uint256 blockVolume, uint256 hourVolume)",
+            ),
+        ]);
+    }
+    // `transferOwnership`, which the extension replaces, is already among the exported methods
+    #[cfg(feature = "admin-delay")]
+    {
+        use extensions::admin_delay::IERC1155AdminDelay::*;
+        declarations.extend([
+            function::<adminDelayCall>("function adminDelay() external view returns (uint256)"),
+            function::<setAdminDelayCall>("function setAdminDelay(uint256 delay) external"),
+            function::<scheduleAdminActionCall>(
+                "function scheduleAdminAction(bytes calldata data, bytes32 salt) external returns (bytes32 id)",
+            ),
+            function::<executeAdminActionCall>("function executeAdminAction(bytes calldata data, bytes32 salt) external"),
+            function::<cancelAdminActionCall>("function cancelAdminAction(bytes32 id) external"),
+            function::<adminActionReadyAtCall>("function adminActionReadyAt(bytes32 id) external view returns (uint256)"),
+        ]);
+    }
+    #[cfg(feature = "tiers")]
+    {
+        use extensions::tiers::IERC1155Tiers::*;
+        declarations.extend([
+            function::<tierIdsCall>("function tierIds() external view returns (uint256[] memory)"),
+            function::<tierThresholdsCall>("function tierThresholds() external view returns (uint256[] memory)"),
+            function::<setTiersCall>("function setTiers(uint256[] memory ids, uint256[] memory thresholds) external"),
+            function::<tierBalanceOfCall>("function tierBalanceOf(address account) external view returns (uint256)"),
+            function::<tierOfCall>("function tierOf(address account) external view returns (uint256)"),
+            function::<hasAccessCall>("function hasAccess(address account, uint256 tier) external view returns (bool)"),
+        ]);
+    }
+    #[cfg(feature = "validity")]
+    {
+        use extensions::validity::IERC1155Validity::*;
+        declarations.extend([
+            function::<setValidityCall>("function setValidity(uint256 id, uint256 start, uint256 end) external"),
+            function::<validityCall>(
+                "function validity(uint256 id) external view returns (uint256 start, uint256 end)",
+            ),
+            function::<isValidCall>("function isValid(uint256 id) external view returns (bool)"),
+            function::<validBalanceOfCall>(
+                "function validBalanceOf(address account, uint256 id) external view returns (uint256)",
+            ),
+            function::<setExpiryBountyCall>("function setExpiryBounty(uint256 id, uint256 bounty) external payable"),
+            function::<withdrawExpiryBountyCall>("function withdrawExpiryBounty(uint256 id) external"),
+            function::<expiryBountyCall>(
+                "function expiryBounty(uint256 id) external view returns (uint256 bounty, uint256 funds)",
+            ),
+            function::<burnExpiredCall>(
+                "function burnExpired(address account, uint256 id) external returns (uint256 amount)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "packs")]
+    {
+        use extensions::packs::IERC1155Packs::*;
+        declarations.extend([
+            function::<setDropTableCall>(
+                "function setDropTable(uint256 packId, uint256[] memory ids, uint256[] memory weights, uint256 dropsPerPack) external",
+            ),
+            function::<dropTableCall>(
+                "function dropTable(uint256 packId) external view returns (uint256[] memory ids, uint256[] memory weights, uint256 dropsPerPack)",
+            ),
+            function::<setPackRandomnessCall>("function setPackRandomness(address randomness) external"),
+            function::<packRandomnessCall>("function packRandomness() external view returns (address)"),
+            function::<openPackCall>(
+                "function openPack(uint256 packId, uint256 amount, bytes32 commitment) external returns (uint256 requestId)",
+            ),
+            function::<onRandomnessFulfilledCall>(
+                "function onRandomnessFulfilled(uint256 requestId, bytes32 randomness) external",
+            ),
+            function::<settleOpeningCall>("function settleOpening(uint256 requestId) external"),
+            function::<openingCall>(
+                "function opening(uint256 requestId) external view returns (address opener, uint256 packId, uint256 amount, bool settled)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "market")]
+    {
+        use extensions::market::IERC1155Market::*;
+        declarations.extend([
+            function::<listCall>(
+                "function list(uint256 id, uint256 amount, address priceToken, uint256 price) external returns (uint256 listingId)",
+            ),
+            function::<buyCall>("function buy(uint256 listingId, uint256 amount) external"),
+            function::<cancelCall>("function cancel(uint256 listingId) external"),
+            function::<listingCall>(
+                "function listing(uint256 listingId) external view returns (address seller, uint256 id, uint256 amount, address priceToken, uint256 price)",
+            ),
+            function::<setMarketFeeCall>("function setMarketFee(address recipient, uint256 feeBps) external"),
+            function::<marketFeeCall>("function marketFee() external view returns (address recipient, uint256 feeBps)"),
+        ]);
+    }
+    #[cfg(feature = "composable")]
+    {
+        use extensions::composable::IERC1155Composable::*;
+        declarations.extend([
+            function::<attachChildCall>(
+                "function attachChild(address parentContract, uint256 parentId, uint256 id, uint256 amount) external",
+            ),
+            function::<detachChildCall>(
+                "function detachChild(address parentContract, uint256 parentId, uint256 id, uint256 amount) external",
+            ),
+            function::<childBalanceOfCall>(
+                "function childBalanceOf(address parentContract, uint256 parentId, uint256 id) external view returns (uint256)",
+            ),
+            function::<childIdsOfCall>(
+                "function childIdsOf(address parentContract, uint256 parentId) external view returns (uint256[] memory)",
+            ),
+            function::<ownsParentCall>(
+                "function ownsParent(address account, address parentContract, uint256 parentId) external view returns (bool)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "attested-mint")]
+    {
+        use extensions::attested_mint::IERC1155AttestedMint::*;
+        declarations.extend([
+            function::<setMintGateCall>(
+                "function setMintGate(uint256 id, address registry, bytes32 schema, address attester, uint256 until) external",
+            ),
+            function::<mintGateCall>(
+                "function mintGate(uint256 id) external view returns (address registry, bytes32 schema, address attester, uint256 until)",
+            ),
+            function::<canMintCall>("function canMint(address account, uint256 id) external view returns (bool)"),
+        ]);
+    }
+    #[cfg(feature = "checkpoints")]
+    {
+        use extensions::checkpoints::IERC1155Checkpoints::*;
+        declarations.extend([
+            function::<getPastBalanceCall>(
+                "function getPastBalance(address account, uint256 id, uint256 blockNumber) external view returns (uint256)",
+            ),
+            function::<getPastTotalSupplyCall>(
+                "function getPastTotalSupply(uint256 id, uint256 blockNumber) external view returns (uint256)",
+            ),
+            function::<numCheckpointsCall>(
+                "function numCheckpoints(address account, uint256 id) external view returns (uint256)",
+            ),
+            function::<checkpointAtCall>(
+                "function checkpointAt(address account, uint256 id, uint256 index) external view returns (uint64 blockNumber, uint256 balance)",
+            ),
+            function::<clockCall>("function clock() external view returns (uint64)"),
+            function::<CLOCK_MODECall>("function CLOCK_MODE() external view returns (string memory)"),
+        ]);
+    }
+    #[cfg(feature = "votes")]
+    {
+        use extensions::votes::IERC1155Votes::*;
+        declarations.extend([
+            function::<delegateCall>("function delegate(uint256 id, address delegatee) external"),
+            function::<delegatesCall>(
+                "function delegates(address account, uint256 id) external view returns (address)",
+            ),
+            function::<getVotesCall>("function getVotes(address account, uint256 id) external view returns (uint256)"),
+            function::<getPastVotesCall>(
+                "function getPastVotes(address account, uint256 id, uint256 blockNumber) external view returns (uint256)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "approval-index")]
+    {
+        use extensions::approval_index::IERC1155ApprovalIndex::*;
+        declarations.extend([
+            function::<operatorsOfCall>(
+                "function operatorsOf(address account) external view returns (address[] memory)",
+            ),
+            function::<revokeAllApprovalsCall>("function revokeAllApprovals() external returns (uint256 revoked)"),
+        ]);
+    }
+    #[cfg(feature = "transfer-hooks")]
+    {
+        use extensions::transfer_hooks::IERC1155TransferHooks::*;
+        declarations.extend([
+            function::<setTransferHookCall>(
+                "function setTransferHook(address hook, uint64 gasLimit, bool failClosed) external",
+            ),
+            function::<transferHooksCall>("function transferHooks() external view returns (address[] memory)"),
+            function::<transferHookConfigCall>(
+                "function transferHookConfig(address hook) external view returns (uint64 gasLimit, bool failClosed)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "modules")]
+    {
+        use extensions::modules::IERC1155Modules::*;
+        declarations.extend([
+            function::<setModuleCall>("function setModule(bytes4[] selectors, address module) external"),
+            function::<moduleOfCall>("function moduleOf(bytes4 selector) external view returns (address)"),
+            function::<routedSelectorsCall>("function routedSelectors() external view returns (bytes4[] memory)"),
+        ]);
+    }
+    #[cfg(feature = "holder-snapshots")]
+    {
+        use extensions::holder_snapshots::IERC1155HolderSnapshots::*;
+        declarations.extend([
+            function::<snapshotHoldersCall>("function snapshotHolders(uint256 id) external returns (uint256 snapshotId)"),
+            function::<snapshotCall>(
+                "function snapshot(uint256 snapshotId) external view returns (uint256 id, uint64 blockNumber, uint256 holderCount, uint256 totalSupply)",
+            ),
+            function::<holdersAtCall>(
+                "function holdersAt(uint256 snapshotId, uint256 start, uint256 count) external view returns (address[] memory holders, uint256[] memory balances)",
+            ),
+            function::<balanceAtSnapshotCall>(
+                "function balanceAtSnapshot(uint256 snapshotId, address account) external view returns (uint256)",
+            ),
+            function::<snapshotCountCall>("function snapshotCount() external view returns (uint256)"),
+            function::<holderCountCall>("function holderCount(uint256 id) external view returns (uint256)"),
+        ]);
+    }
+    #[cfg(feature = "testnet")]
+    {
+        use extensions::testnet::IERC1155Testnet::*;
+        declarations.extend([
+            function::<faucetLimitCall>("function faucetLimit() external view returns (uint256)"),
+            function::<setFaucetLimitCall>("function setFaucetLimit(uint256 limit) external"),
+            function::<faucetMintCall>("function faucetMint(address to, uint256 id, uint256 amount) external"),
+        ]);
+    }
+    #[cfg(feature = "transfer-security")]
+    {
+        use extensions::transfer_security::IERC1155TransferSecurity::*;
+        declarations.extend([
+            function::<transferSecurityLevelCall>("function transferSecurityLevel() external view returns (uint8)"),
+            function::<setTransferSecurityLevelCall>("function setTransferSecurityLevel(uint8 level) external"),
+            function::<isAllowedOperatorCall>(
+                "function isAllowedOperator(address operator) external view returns (bool)",
+            ),
+            function::<setAllowedOperatorCall>("function setAllowedOperator(address operator, bool allowed) external"),
+            function::<isTransferAllowedCall>(
+                "function isTransferAllowed(address caller, address from, address to) external view returns (bool)",
+            ),
+        ]);
+    }
+    #[cfg(feature = "reveal")]
+    {
+        use extensions::reveal::IERC1155Reveal::*;
+        declarations.extend([
+            function::<provenanceHashCall>(
+                "function provenanceHash() external view returns (bytes32 hash, uint256 size)",
+            ),
+            function::<setProvenanceHashCall>("function setProvenanceHash(bytes32 hash, uint256 size) external"),
+            function::<revealOffsetCall>("function revealOffset() external view returns (uint256)"),
+            function::<setRevealOffsetCall>("function setRevealOffset(uint256 offset) external"),
+            function::<revealedCall>("function revealed() external view returns (bool)"),
+            function::<revealCall>("function reveal() external"),
+        ]);
+    }
+    #[cfg(feature = "open-editions")]
+    {
+        use extensions::open_editions::IERC1155OpenEditions::*;
+        declarations.extend([
+            function::<setOpenEditionCall>("function setOpenEdition(uint256 id, uint256 start, uint256 end) external"),
+            function::<openEditionCall>(
+                "function openEdition(uint256 id) external view returns (uint256 start, uint256 end, uint256 minted, bool finalized)",
+            ),
+            function::<finalizeOpenEditionCall>("function finalizeOpenEdition(uint256 id) external"),
+        ]);
+    }
+    declarations
+}
+
+/// Checks that `declaration` has the selector of `C`.
+#[allow(dead_code)]
+fn function<C: SolCall>(declaration: &str) -> String {
+    let function = Function::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"));
+    assert_eq!(function.selector(), C::SELECTOR, "`{declaration}` doesn't match `{}`", C::SIGNATURE);
+    declaration.into()
+}
+
+/// Checks that `declaration` has the signature and indexed parameters of `E`.
+fn event<E: SolEvent>(declaration: &str) -> String {
+    let event = Event::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"));
+    assert_eq!(event.selector(), E::SIGNATURE_HASH, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    let topics = 1 + event.inputs.iter().filter(|input| input.indexed).count();
+    assert_eq!(
+        topics,
+        <E::TopicList as TopicList>::COUNT,
+        "`{declaration}` indexes different parameters than `{}`",
+        E::SIGNATURE
+    );
+    declaration.into()
+}
+
+/// Checks that `declaration` has the signature of `E`.
+fn error<E: SolError>(declaration: &str) -> String {
+    assert_eq!(parse_error(declaration).selector(), E::SELECTOR, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    declaration.into()
+}
+
+fn parse_error(declaration: &str) -> Error {
+    Error::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"))
+}
+
+/// The declarations starting with `keyword` in the SDK's output, without the `;` and in order
+/// of first appearance.
+fn declarations(exported: &str, keyword: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in exported.lines().map(str::trim).filter(|line| line.starts_with(keyword)) {
+        let declaration = line.trim_end_matches(';');
+        if !found.iter().any(|seen| seen == declaration) {
+            found.push(declaration.into());
+        }
+    }
+    found
+}
+
+/// The interfaces the SDK exports for [`My1155`] and the modules it inherits.
+struct Exported;
+
+impl fmt::Display for Exported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <My1155 as GenerateAbi>::fmt_abi(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{declarations, solidity_interface, Exported};
+
+    #[test]
+    fn committed_interface_is_up_to_date() {
+        // The committed file is the interface of the default build
+        if crate::extensions::enabled().next().is_some() {
+            return;
+        }
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/IERC1155Token.sol");
+        let committed = std::fs::read_to_string(path).unwrap_or_default();
+        let generated = solidity_interface();
+        if committed != generated {
+            let (committed, generated): (Vec<_>, Vec<_>) = (committed.lines().collect(), generated.lines().collect());
+            let line = (0..committed.len().max(generated.len()))
+                .find(|&i| committed.get(i) != generated.get(i))
+                .unwrap_or(committed.len());
+            let (old, new) = (committed.get(line).unwrap_or(&""), generated.get(line).unwrap_or(&""));
+            let line = line + 1;
+            panic!(
+                "IERC1155Token.sol is out of date at line {line}:\n- {old}\n+ {new}\n\
+                 Regenerate it with `cargo run --features export-abi -- interface > IERC1155Token.sol`"
+            );
+        }
+    }
+
+    #[test]
+    fn exported_functions_are_camel_case() {
+        // A method without `#[selector]` whose name is snake_case in Rust but not camelCase, like
+        // `set_uri2`, is exported under a name a Solidity caller wouldn't guess
+        for declaration in declarations(&format!("{}", Exported), "function ") {
+            let name = declaration["function ".len()..].split('(').next().unwrap();
+            let constant = name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            assert!(constant || !name.contains('_'), "`{name}` isn't camelCase");
+        }
+    }
+}
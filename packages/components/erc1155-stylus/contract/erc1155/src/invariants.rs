@@ -0,0 +1,323 @@
+//! Property tests for the token internals. Random sequences of mints, burns, transfers and
+//! approvals run against `Erc1155` and a plain model of the standard, and the two must agree.
+
+use crate::erc1155::{Erc1155, Erc1155Error};
+use alloc::{vec, vec::Vec};
+use proptest::prelude::*;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    testing::TestVM,
+};
+
+const ACCOUNTS: usize = 4;
+/// IDs 0, 1 and 3 share a slot with `packed-balances`, and 4 starts the next one.
+const IDS: [u64; 4] = [0, 1, 3, 4];
+
+fn account(index: usize) -> Address {
+    Address::with_last_byte(index as u8 + 1)
+}
+
+fn token_id(index: usize) -> U256 {
+    U256::from(IDS[index])
+}
+
+/// `(id index, value)` pairs of a batch.
+type Entries = Vec<(usize, u64)>;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint { to: usize, id: usize, value: u64 },
+    MintBatch { to: usize, entries: Entries },
+    Burn { from: usize, id: usize, value: u64 },
+    BurnBatch { from: usize, entries: Entries },
+    Transfer { operator: usize, from: usize, to: usize, id: usize, value: u64 },
+    TransferBatch { operator: usize, from: usize, to: usize, entries: Entries },
+    Approve { owner: usize, operator: usize, approved: bool },
+}
+
+impl Op {
+    /// Whether the op may change `account`'s balance of `id`.
+    fn touches(&self, account: usize, id: usize) -> bool {
+        let named = |entries: &Entries| entries.iter().any(|&(entry, _)| entry == id);
+        match self {
+            Op::Mint { to, id: op_id, .. } => account == *to && id == *op_id,
+            Op::MintBatch { to, entries } => account == *to && named(entries),
+            Op::Burn { from, id: op_id, .. } => account == *from && id == *op_id,
+            Op::BurnBatch { from, entries } => account == *from && named(entries),
+            Op::Transfer { from, to, id: op_id, .. } => (account == *from || account == *to) && id == *op_id,
+            Op::TransferBatch { from, to, entries, .. } => (account == *from || account == *to) && named(entries),
+            Op::Approve { .. } => false,
+        }
+    }
+
+    /// Whether the build's validation options accept the op's amounts and IDs.
+    fn valid(&self) -> bool {
+        let entries = match self {
+            Op::Mint { id, value, .. } | Op::Burn { id, value, .. } | Op::Transfer { id, value, .. } => {
+                vec![(*id, *value)]
+            }
+            Op::MintBatch { entries, .. } | Op::BurnBatch { entries, .. } | Op::TransferBatch { entries, .. } => {
+                entries.clone()
+            }
+            Op::Approve { .. } => vec![],
+        };
+        let zero = entries.iter().any(|&(_, value)| value == 0);
+        let repeated = entries.iter().enumerate().any(|(i, (id, _))| entries[..i].iter().any(|(other, _)| other == id));
+        let rejected_zero = cfg!(feature = "reject-zero-amounts") && zero;
+        let rejected_repeat = cfg!(feature = "reject-duplicate-ids") && repeated;
+        !rejected_zero && !rejected_repeat
+    }
+}
+
+fn entries(len: core::ops::Range<usize>) -> impl Strategy<Value = Entries> {
+    prop::collection::vec((0..IDS.len(), 0..1_000u64), len)
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let account = || 0..ACCOUNTS;
+    let id = || 0..IDS.len();
+    let value = || 0..1_000u64;
+    prop_oneof![
+        3 => (account(), id(), value()).prop_map(|(to, id, value)| Op::Mint { to, id, value }),
+        2 => (account(), entries(0..5)).prop_map(|(to, entries)| Op::MintBatch { to, entries }),
+        2 => (account(), id(), value()).prop_map(|(from, id, value)| Op::Burn { from, id, value }),
+        1 => (account(), entries(0..5)).prop_map(|(from, entries)| Op::BurnBatch { from, entries }),
+        3 => (account(), account(), account(), id(), value())
+            .prop_map(|(operator, from, to, id, value)| Op::Transfer { operator, from, to, id, value }),
+        2 => (account(), account(), account(), entries(0..5))
+            .prop_map(|(operator, from, to, entries)| Op::TransferBatch { operator, from, to, entries }),
+        1 => (account(), account(), any::<bool>())
+            .prop_map(|(owner, operator, approved)| Op::Approve { owner, operator, approved }),
+    ]
+}
+
+/// What the standard says the state should be.
+#[derive(Clone, Default)]
+struct Model {
+    balances: [[u64; ACCOUNTS]; IDS.len()],
+    approvals: [[bool; ACCOUNTS]; ACCOUNTS],
+}
+
+impl Model {
+    /// Applies `op` and returns whether it succeeds. Failed ops change nothing.
+    fn apply(&mut self, op: &Op) -> bool {
+        if !op.valid() {
+            return false;
+        }
+        let mut next = self.clone();
+        let ok = match op {
+            Op::Mint { to, id, value } => next.move_balance(None, Some(*to), *id, *value),
+            Op::MintBatch { to, entries } => {
+                entries.iter().all(|&(id, value)| next.move_balance(None, Some(*to), id, value))
+            }
+            Op::Burn { from, id, value } => next.move_balance(Some(*from), None, *id, *value),
+            Op::BurnBatch { from, entries } => {
+                entries.iter().all(|&(id, value)| next.move_balance(Some(*from), None, id, value))
+            }
+            Op::Transfer { operator, from, to, id, value } => {
+                next.may_operate(*operator, *from) && next.move_balance(Some(*from), Some(*to), *id, *value)
+            }
+            Op::TransferBatch { operator, from, to, entries } => {
+                next.may_operate(*operator, *from)
+                    && entries.iter().all(|&(id, value)| next.move_balance(Some(*from), Some(*to), id, value))
+            }
+            Op::Approve { owner, operator, approved } => {
+                next.approvals[*owner][*operator] = *approved;
+                owner != operator
+            }
+        };
+        if ok {
+            *self = next;
+        }
+        ok
+    }
+
+    fn may_operate(&self, operator: usize, from: usize) -> bool {
+        operator == from || self.approvals[from][operator]
+    }
+
+    fn move_balance(&mut self, from: Option<usize>, to: Option<usize>, id: usize, value: u64) -> bool {
+        if let Some(from) = from {
+            if self.balances[id][from] < value {
+                return false;
+            }
+            self.balances[id][from] -= value;
+        }
+        if let Some(to) = to {
+            self.balances[id][to] += value;
+        }
+        true
+    }
+
+    fn supply(&self, id: usize) -> u64 {
+        self.balances[id].iter().sum()
+    }
+}
+
+fn split(entries: &Entries) -> (Vec<U256>, Vec<U256>) {
+    entries.iter().map(|&(id, value)| (token_id(id), U256::from(value))).unzip()
+}
+
+/// Runs `op` as its caller and returns whether it succeeds. A failing op has its storage
+/// writes undone, as the VM would when the transaction reverts.
+fn execute(vm: &TestVM, token: &mut Erc1155, op: &Op) -> bool {
+    let storage = vm.snapshot().storage;
+    let result: Result<(), Erc1155Error> = match op.clone() {
+        Op::Mint { to, id, value } => token._mint(account(to), token_id(id), U256::from(value)),
+        Op::MintBatch { to, entries } => {
+            let (ids, values) = split(&entries);
+            token._mint_batch(account(to), &ids, &values, 0)
+        }
+        Op::Burn { from, id, value } => token._burn(account(from), token_id(id), U256::from(value)),
+        Op::BurnBatch { from, entries } => {
+            let (ids, values) = split(&entries);
+            token._burn_batch(account(from), &ids, &values, 0)
+        }
+        Op::Transfer { operator, from, to, id, value } => {
+            vm.set_sender(account(operator));
+            token.safe_transfer_from(account(from), account(to), token_id(id), U256::from(value), &[])
+        }
+        Op::TransferBatch { operator, from, to, entries } => {
+            vm.set_sender(account(operator));
+            let (ids, values) = split(&entries);
+            token.safe_batch_transfer_from(account(from), account(to), &ids, &values, &[], 0)
+        }
+        Op::Approve { owner, operator, approved } => {
+            vm.set_sender(account(owner));
+            token.set_approval_for_all(account(operator), approved)
+        }
+    };
+    if result.is_err() {
+        vm.clear_storage();
+        for (key, value) in storage {
+            vm.set_storage(key, value);
+        }
+    }
+    result.is_ok()
+}
+
+fn balances(token: &Erc1155) -> [[U256; ACCOUNTS]; IDS.len()] {
+    core::array::from_fn(|id| core::array::from_fn(|holder| token.balance_of(account(holder), token_id(id))))
+}
+
+fn supplies(token: &Erc1155) -> [U256; IDS.len()] {
+    core::array::from_fn(|id| token.total_supply(token_id(id)))
+}
+
+proptest! {
+    #[test]
+    fn token_matches_the_model(ops in prop::collection::vec(op(), 1..40)) {
+        let vm = TestVM::new();
+        let mut token = Erc1155::from(&vm);
+        let mut model = Model::default();
+
+        for op in &ops {
+            prop_assert_eq!(execute(&vm, &mut token, op), model.apply(op), "{:?}", op);
+            for id in 0..IDS.len() {
+                for holder in 0..ACCOUNTS {
+                    prop_assert_eq!(token.balance_of(account(holder), token_id(id)), U256::from(model.balances[id][holder]));
+                }
+                prop_assert_eq!(token.total_supply(token_id(id)), U256::from(model.supply(id)));
+                prop_assert_eq!(token.exists(token_id(id)), model.supply(id) > 0);
+            }
+            for owner in 0..ACCOUNTS {
+                for operator in 0..ACCOUNTS {
+                    prop_assert_eq!(token.is_approved_for_all(account(owner), account(operator)), model.approvals[owner][operator]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn balances_sum_to_total_supply(ops in prop::collection::vec(op(), 1..40)) {
+        let vm = TestVM::new();
+        let mut token = Erc1155::from(&vm);
+
+        for op in &ops {
+            execute(&vm, &mut token, op);
+            for (id, holders) in balances(&token).iter().enumerate() {
+                let sum = holders.iter().fold(U256::ZERO, |sum, balance| sum + balance);
+                prop_assert_eq!(sum, token.total_supply(token_id(id)), "{:?}", op);
+            }
+        }
+    }
+
+    #[test]
+    fn ops_leave_other_balances_alone(setup in prop::collection::vec(op(), 0..20), op in op()) {
+        let vm = TestVM::new();
+        let mut token = Erc1155::from(&vm);
+        for op in &setup {
+            execute(&vm, &mut token, op);
+        }
+
+        let before = balances(&token);
+        execute(&vm, &mut token, &op);
+        let after = balances(&token);
+        for id in 0..IDS.len() {
+            for holder in 0..ACCOUNTS {
+                if !op.touches(holder, id) {
+                    prop_assert_eq!(before[id][holder], after[id][holder], "account {} id {}", holder, IDS[id]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn batches_equal_their_single_ops(
+        setup in prop::collection::vec(op(), 0..20),
+        operator in 0..ACCOUNTS,
+        from in 0..ACCOUNTS,
+        to in 0..ACCOUNTS,
+        // An empty transfer batch still checks approval, which no single op would
+        entries in entries(1..5),
+        kind in 0..3u8,
+    ) {
+        // Singles can't repeat an ID, so a build rejecting repeats only compares batches without any
+        let mut entries = entries;
+        if cfg!(feature = "reject-duplicate-ids") {
+            let mut seen = Vec::new();
+            entries.retain(|&(id, _)| {
+                let first = !seen.contains(&id);
+                seen.push(id);
+                first
+            });
+        }
+        let (batch, singles): (Op, Vec<Op>) = match kind {
+            0 => (
+                Op::MintBatch { to, entries: entries.clone() },
+                entries.iter().map(|&(id, value)| Op::Mint { to, id, value }).collect(),
+            ),
+            1 => (
+                Op::BurnBatch { from, entries: entries.clone() },
+                entries.iter().map(|&(id, value)| Op::Burn { from, id, value }).collect(),
+            ),
+            _ => (
+                Op::TransferBatch { operator, from, to, entries: entries.clone() },
+                entries.iter().map(|&(id, value)| Op::Transfer { operator, from, to, id, value }).collect(),
+            ),
+        };
+
+        let batch_vm = TestVM::new();
+        let mut batched = Erc1155::from(&batch_vm);
+        let singles_vm = TestVM::new();
+        let mut single = Erc1155::from(&singles_vm);
+        for op in &setup {
+            execute(&batch_vm, &mut batched, op);
+            execute(&singles_vm, &mut single, op);
+        }
+
+        // The singles succeed or fail together, like one transaction
+        let storage = singles_vm.snapshot().storage;
+        let singles_ok = singles.iter().all(|op| execute(&singles_vm, &mut single, op));
+        if !singles_ok {
+            singles_vm.clear_storage();
+            for (key, value) in storage {
+                singles_vm.set_storage(key, value);
+            }
+        }
+
+        prop_assert_eq!(execute(&batch_vm, &mut batched, &batch), singles_ok);
+        prop_assert_eq!(balances(&batched), balances(&single));
+        prop_assert_eq!(supplies(&batched), supplies(&single));
+    }
+}
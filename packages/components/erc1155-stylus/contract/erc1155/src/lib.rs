@@ -1,57 +1,1700 @@
 // SPDX-License-Identifier: MIT
 #![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+// `ERC1155InsufficientChildBalance` has four words, and errors leave the contract ABI-encoded
+#![cfg_attr(feature = "composable", allow(clippy::result_large_err))]
 extern crate alloc;
 
+#[cfg(test)]
+mod compliance;
 pub mod erc1155;
+pub mod extensions;
+#[cfg(feature = "export-abi")]
+pub mod interface;
+#[cfg(test)]
+mod invariants;
+pub mod profiling;
+#[cfg(test)]
+mod tests;
 
-use alloc::vec::Vec;
-use erc1155::Erc1155;
-use stylus_sdk::{alloy_primitives::{Address, U256}, prelude::*};
+use alloc::{string::String, vec::Vec};
+use cradle_access_control::{
+    only_role, AccessControl, AccessControlBadConfirmation, AccessControlError, AccessControlMemberOutOfBounds,
+    AccessControlUnauthorizedAccount, DEFAULT_ADMIN_ROLE, IACCESS_CONTROL, IACCESS_CONTROL_ENUMERABLE,
+};
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount, IERC173, IERC5313};
+use cradle_pausable::{EnforcedPause, ExpectedPause, Pausable, PausableError};
+use erc1155::{
+    Erc1155, Erc1155Error, ERC1155BalanceOverflow, ERC1155DuplicateId, ERC1155InsufficientBalance,
+    ERC1155InsufficientSupply, ERC1155InvalidApprover, ERC1155InvalidArrayLength, ERC1155InvalidOperator, ERC1155InvalidReceiver,
+    ERC1155InvalidSender, ERC1155MissingApprovalForAll, ERC1155ZeroAmount,
+};
+#[cfg(feature = "admin-delay")]
+use extensions::admin_delay::{
+    ERC1155AdminActionAlreadyScheduled, ERC1155AdminActionNotReady, ERC1155AdminDelayRequired,
+    ERC1155InvalidAdminAction, ERC1155UnknownAdminAction,
+};
+#[cfg(feature = "attested-mint")]
+use extensions::attested_mint::ERC1155MissingAttestation;
+#[cfg(feature = "checkpoints")]
+use extensions::checkpoints::ERC1155FutureLookup;
+#[cfg(feature = "composable")]
+use extensions::composable::{
+    ERC1155InsufficientChildBalance, ERC1155InvalidChild, ERC1155NotParentOwner, ERC1155TooManyChildIds,
+};
+#[cfg(feature = "enumerable")]
+use extensions::enumerable::ERC1155OutOfBoundsIndex;
+#[cfg(feature = "meta-tx")]
+use cradle_crypto::InvalidNonceInvalidation;
+#[cfg(feature = "holder-snapshots")]
+use extensions::holder_snapshots::ERC1155UnknownSnapshot;
+#[cfg(feature = "id-ranges")]
+use extensions::id_ranges::{ERC1155InvalidIdRange, ERC1155SeriesExhausted, ERC1155UnauthorizedSeriesMinter};
+#[cfg(feature = "market")]
+use extensions::market::{
+    ERC1155InsufficientListing, ERC1155InvalidListing, ERC1155InvalidMarketFee, ERC1155NotSeller, ERC1155PaymentFailed,
+};
+#[cfg(feature = "meta-tx")]
+use extensions::meta_tx::{ERC1155ExpiredSignature, ERC1155InvalidSignature};
+#[cfg(feature = "modules")]
+use extensions::modules::{ERC1155InvalidModule, ERC1155UnknownSelector};
+#[cfg(feature = "packs")]
+use extensions::packs::{
+    ERC1155InvalidDropTable, ERC1155InvalidPackAmount, ERC1155NotAPack, ERC1155OpeningsPending,
+    ERC1155RandomnessCallFailed, ERC1155RandomnessPending, ERC1155UnauthorizedRandomness, ERC1155UnknownOpening,
+};
+#[cfg(feature = "paid-mint")]
+use extensions::paid_mint::{
+    ERC1155IncorrectPayment, ERC1155InvalidRefund, ERC1155MintNotForSale, ERC1155ProceedsTransferFailed,
+    ERC1155RefundWindowOpen,
+};
+#[cfg(feature = "rate-limits")]
+use extensions::rate_limits::ERC1155RateLimitExceeded;
+#[cfg(feature = "royalties")]
+use extensions::royalties::{
+    ERC2981InvalidDefaultRoyalty, ERC2981InvalidDefaultRoyaltyReceiver, ERC2981InvalidTokenRoyalty,
+    ERC2981InvalidTokenRoyaltyReceiver,
+};
+#[cfg(feature = "soulbound")]
+use extensions::soulbound::ERC1155NonTransferable;
+#[cfg(feature = "open-editions")]
+use extensions::open_editions::{
+    ERC1155InvalidOpenEdition, ERC1155NotOpenEdition, ERC1155OpenEditionNotOpen, ERC1155OpenEditionStillOpen,
+};
+#[cfg(feature = "reveal")]
+use extensions::reveal::{
+    ERC1155AlreadyRevealed, ERC1155InvalidProvenance, ERC1155InvalidRevealOffset, ERC1155ProvenanceAlreadySet,
+    ERC1155ProvenanceNotSet,
+};
+#[cfg(feature = "split-ids")]
+use extensions::split_ids::{ERC1155InvalidBaseType, ERC1155NonFungibleSupply};
+#[cfg(feature = "supply-cap")]
+use extensions::supply_cap::{ERC1155ExceededMaxSupply, ERC1155InvalidMaxSupply};
+#[cfg(feature = "testnet")]
+use extensions::testnet::ERC1155FaucetLimitExceeded;
+#[cfg(feature = "tiers")]
+use extensions::tiers::{ERC1155InvalidTierIds, ERC1155InvalidTierThreshold};
+#[cfg(feature = "transfer-hooks")]
+use extensions::transfer_hooks::{ERC1155InvalidTransferHook, ERC1155TooManyTransferHooks, ERC1155TransferHookRejected};
+#[cfg(feature = "transfer-security")]
+use extensions::transfer_security::{ERC1155InvalidTransferSecurityLevel, ERC1155TransferRestricted};
+#[cfg(feature = "validity")]
+use extensions::validity::{ERC1155BountyTransferFailed, ERC1155InvalidValidity, ERC1155NotExpired};
+use extensions::{
+    admin_delay::{AdminDelay, IAdminDelay},
+    approval_index::{ApprovalIndex, IApprovalIndex},
+    attested_mint::{AttestedMint, IAttestedMint},
+    checkpoints::{Checkpoints, ICheckpoints},
+    composable::{Composable, IComposable},
+    enumerable::{Enumerable, IEnumerable},
+    holder_snapshots::{HolderSnapshots, IHolderSnapshots},
+    id_ranges::{IIdRanges, IdRanges},
+    market::{IMarket, Market},
+    meta_tx::{IMetaTx, MetaTx},
+    modules::{IModules, Modules},
+    open_editions::{IOpenEditions, OpenEditions},
+    packs::{IPacks, Packs},
+    paid_mint::{IPaidMint, MintRefunds, PaidMint},
+    rate_limits::{IRateLimits, RateLimits},
+    reveal::{IReveal, Reveal},
+    royalties::{IRoyalties, Royalties},
+    soulbound::ISoulbound,
+    split_ids::{ISplitIds, SplitIds},
+    supply_cap::{ISupplyCap, SupplyCap},
+    testnet::{ITestnet, Testnet},
+    tiers::{ITiers, Tiers},
+    transfer_hooks::{ITransferHooks, TransferHooks},
+    transfer_security::{ITransferSecurity, TransferSecurity},
+    validity::{IValidity, Validity},
+    votes::{IVotes, Votes},
+};
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{b256, Address, FixedBytes, B256, U256, U8},
+    alloy_sol_types::{sol, SolCall, SolError, SolValue},
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageString, StorageU256, StorageU8, StorageVec},
+    ArbResult,
+};
+
+/// Role allowed to call `crosschain_mint` and `crosschain_burn`, `keccak256("BRIDGE_ROLE")`.
+const BRIDGE_ROLE: B256 = b256!("52ba824bfabc2bcfcdf7f0edbb486ebb05e1836c90e78047efeb949990f72e5f");
+/// Role allowed to `pause` until the guardian expiry, `keccak256("GUARDIAN_ROLE")`.
+const GUARDIAN_ROLE: B256 = b256!("55435dd261a4b9b3364963f7738a7a662ad9c84396d64be3365284bb7f0a5041");
+/// Role allowed to call `mint_with_receipt`, `keccak256("PAYMENT_PROCESSOR_ROLE")`.
+const PAYMENT_PROCESSOR_ROLE: B256 = b256!("d7d8b7014b7ed36eb085c9e3e427b642d74cab75ecefda8a757042e63ec59919");
+
+/// `id_states` of an ID never minted.
+const ID_UNMINTED: u8 = 0;
+/// `id_states` of an ID with supply.
+const ID_LIVE: u8 = 1;
+/// `id_states` of an ID that had supply and was burned back to zero.
+const ID_BURNED_OUT: u8 = 2;
+
+/// Most series `setSeriesUri` keeps, so `uri` scans a bounded list.
+pub const MAX_SERIES_URIS: u64 = 256;
+
+/// ERC-1155, `0xd9b67a26`.
+pub const IERC1155: Interface = interface!(
+    "balanceOf"(Address, U256),
+    "balanceOfBatch"(Vec<Address>, Vec<U256>),
+    "setApprovalForAll"(Address, bool),
+    "isApprovedForAll"(Address, Address),
+    "safeTransferFrom"(Address, Address, U256, U256, Bytes),
+    "safeBatchTransferFrom"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+/// ERC-1155's metadata extension, `uri`.
+pub const IERC1155_METADATA_URI: Interface = interface!("uri"(U256));
+
+/// Interfaces the token supports whatever its features, besides ERC-165.
+const INTERFACES: [Interface; 6] =
+    [IERC1155, IERC1155_METADATA_URI, IERC173, IERC5313, IACCESS_CONTROL, IACCESS_CONTROL_ENUMERABLE];
+
+/// Whether an extension needs the IDs a batch transfer moved after the batch, which are otherwise
+/// left out of its bookkeeping. Batch mints and burns always pass them on, for `exists` and
+/// `everExisted`.
+const TRACKS_IDS: bool = cfg!(any(
+    feature = "enumerable",
+    feature = "supply-cap",
+    feature = "split-ids",
+    feature = "checkpoints",
+    feature = "votes",
+    feature = "holder-snapshots",
+    feature = "testnet"
+));
+
+// `SOURCE_HASH`, the hash of the sources this contract was built from, written by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+// `DEPLOYER`, the only account that may call `initialize` if `CRADLE_DEPLOYER` was set at build
+// time, so no one can initialize a fresh deployment with themselves as owner before its deployer
+include!(concat!(env!("OUT_DIR"), "/deployer.rs"));
+
+sol! {
+    event CrosschainMint(address indexed to, uint256 id, uint256 amount, address indexed sender);
+    event CrosschainBurn(address indexed from, uint256 id, uint256 amount, address indexed sender);
+    event GuardianExpirySet(uint256 expiry);
+    event ApprovalForAllExpiry(address indexed account, address indexed operator, uint256 expires);
+    event BatchLogChunkSet(uint256 entries);
+    event PauseRegistrySet(address registry);
+    event ExistenceChanged(uint256 indexed id, bool exists);
+    event RetireBurnedOutSet(bool retire);
+    event MaxBatchLengthSet(uint256 entries);
+    event ReceiverHookCalled(address indexed receiver, bytes returnData, uint256 gasUsed);
+    event MetadataFrozen(string uri);
+    event SupplyFrozen(uint256 indexed id, uint256 totalSupply);
+    event URI(string value, uint256 indexed id);
+    event SeriesUriSet(uint256 indexed startId, uint256 indexed endId, string base);
+    event MintedWithReceipt(bytes32 indexed orderId, address indexed to, uint256 id, uint256 amount, address indexed processor);
+
+    error AlreadyInitialized();
+    // The contract was built for `deployer` to initialize
+    error UnauthorizedInitializer(address caller, address deployer);
+    // Guardians could only pause until `expiry`
+    error GuardianExpired(uint256 expiry);
+    // An approval can't expire before the current block
+    error ERC1155InvalidApprovalExpiry(uint256 expires);
+    // The ID's supply was burned to zero and burned-out IDs are retired
+    error ERC1155IdRetired(uint256 id);
+    // A batch lists more entries than the owner allows
+    error ERC1155BatchTooLong(uint256 length, uint256 maxLength);
+    // The receiver's hook reverted or didn't return its selector
+    error ERC1155ReceiverHookFailed(address receiver, bytes returnData, uint256 gasUsed);
+    // The owner froze the metadata for good
+    error ERC1155MetadataIsFrozen();
+    // The owner froze the ID's supply for good
+    error ERC1155SupplyIsFrozen(uint256 id);
+    // A series can't end before it starts
+    error ERC1155InvalidSeries(uint256 startId, uint256 endId);
+    // `MAX_SERIES_URIS` series were already set
+    error ERC1155TooManySeries(uint256 maxSeries);
+    // A payment processor already minted for the order
+    error ERC1155OrderAlreadyFulfilled(bytes32 orderId);
+
+    interface IERC1155Receiver {
+        function onERC1155Received(address operator, address from, uint256 id, uint256 value, bytes data) external returns (bytes4);
+        function onERC1155BatchReceived(address operator, address from, uint256[] ids, uint256[] values, bytes data) external returns (bytes4);
+    }
+}
+
+/// Represents the ways methods may fail, including those of the token and the shared modules.
+pub enum My1155Error {
+    AlreadyInitialized(AlreadyInitialized),
+    UnauthorizedInitializer(UnauthorizedInitializer),
+    GuardianExpired(GuardianExpired),
+    InvalidApprovalExpiry(ERC1155InvalidApprovalExpiry),
+    IdRetired(ERC1155IdRetired),
+    BatchTooLong(ERC1155BatchTooLong),
+    ReceiverHookFailed(ERC1155ReceiverHookFailed),
+    MetadataIsFrozen(ERC1155MetadataIsFrozen),
+    SupplyIsFrozen(ERC1155SupplyIsFrozen),
+    InvalidSeries(ERC1155InvalidSeries),
+    TooManySeries(ERC1155TooManySeries),
+    OrderAlreadyFulfilled(ERC1155OrderAlreadyFulfilled),
+    InsufficientBalance(ERC1155InsufficientBalance),
+    InvalidSender(ERC1155InvalidSender),
+    InvalidReceiver(ERC1155InvalidReceiver),
+    InvalidApprover(ERC1155InvalidApprover),
+    InvalidOperator(ERC1155InvalidOperator),
+    InvalidArrayLength(ERC1155InvalidArrayLength),
+    MissingApprovalForAll(ERC1155MissingApprovalForAll),
+    BalanceOverflow(ERC1155BalanceOverflow),
+    InsufficientSupply(ERC1155InsufficientSupply),
+    ZeroAmount(ERC1155ZeroAmount),
+    DuplicateId(ERC1155DuplicateId),
+    UnauthorizedAccount(OwnableUnauthorizedAccount),
+    InvalidOwner(OwnableInvalidOwner),
+    EnforcedPause(EnforcedPause),
+    ExpectedPause(ExpectedPause),
+    MissingRole(AccessControlUnauthorizedAccount),
+    BadConfirmation(AccessControlBadConfirmation),
+    MemberOutOfBounds(AccessControlMemberOutOfBounds),
+    #[cfg(feature = "royalties")]
+    InvalidDefaultRoyalty(ERC2981InvalidDefaultRoyalty),
+    #[cfg(feature = "royalties")]
+    InvalidDefaultRoyaltyReceiver(ERC2981InvalidDefaultRoyaltyReceiver),
+    #[cfg(feature = "royalties")]
+    InvalidTokenRoyalty(ERC2981InvalidTokenRoyalty),
+    #[cfg(feature = "royalties")]
+    InvalidTokenRoyaltyReceiver(ERC2981InvalidTokenRoyaltyReceiver),
+    #[cfg(feature = "enumerable")]
+    OutOfBoundsIndex(ERC1155OutOfBoundsIndex),
+    #[cfg(feature = "supply-cap")]
+    ExceededMaxSupply(ERC1155ExceededMaxSupply),
+    #[cfg(feature = "supply-cap")]
+    InvalidMaxSupply(ERC1155InvalidMaxSupply),
+    #[cfg(feature = "soulbound")]
+    NonTransferable(ERC1155NonTransferable),
+    #[cfg(feature = "meta-tx")]
+    ExpiredSignature(ERC1155ExpiredSignature),
+    #[cfg(feature = "meta-tx")]
+    InvalidSignature(ERC1155InvalidSignature),
+    #[cfg(feature = "meta-tx")]
+    InvalidNonceInvalidation(InvalidNonceInvalidation),
+    #[cfg(feature = "paid-mint")]
+    MintNotForSale(ERC1155MintNotForSale),
+    #[cfg(feature = "paid-mint")]
+    IncorrectPayment(ERC1155IncorrectPayment),
+    #[cfg(feature = "paid-mint")]
+    ProceedsTransferFailed(ERC1155ProceedsTransferFailed),
+    #[cfg(feature = "paid-mint")]
+    InvalidRefund(ERC1155InvalidRefund),
+    #[cfg(feature = "paid-mint")]
+    RefundWindowOpen(ERC1155RefundWindowOpen),
+    #[cfg(feature = "id-ranges")]
+    InvalidIdRange(ERC1155InvalidIdRange),
+    #[cfg(feature = "id-ranges")]
+    UnauthorizedSeriesMinter(ERC1155UnauthorizedSeriesMinter),
+    #[cfg(feature = "id-ranges")]
+    SeriesExhausted(ERC1155SeriesExhausted),
+    #[cfg(feature = "split-ids")]
+    InvalidBaseType(ERC1155InvalidBaseType),
+    #[cfg(feature = "split-ids")]
+    NonFungibleSupply(ERC1155NonFungibleSupply),
+    #[cfg(feature = "rate-limits")]
+    RateLimitExceeded(ERC1155RateLimitExceeded),
+    #[cfg(feature = "admin-delay")]
+    AdminDelayRequired(ERC1155AdminDelayRequired),
+    #[cfg(feature = "admin-delay")]
+    InvalidAdminAction(ERC1155InvalidAdminAction),
+    #[cfg(feature = "admin-delay")]
+    AdminActionAlreadyScheduled(ERC1155AdminActionAlreadyScheduled),
+    #[cfg(feature = "admin-delay")]
+    UnknownAdminAction(ERC1155UnknownAdminAction),
+    #[cfg(feature = "admin-delay")]
+    AdminActionNotReady(ERC1155AdminActionNotReady),
+    #[cfg(feature = "tiers")]
+    InvalidTierIds(ERC1155InvalidTierIds),
+    #[cfg(feature = "tiers")]
+    InvalidTierThreshold(ERC1155InvalidTierThreshold),
+    #[cfg(feature = "validity")]
+    InvalidValidity(ERC1155InvalidValidity),
+    #[cfg(feature = "validity")]
+    NotExpired(ERC1155NotExpired),
+    #[cfg(feature = "validity")]
+    BountyTransferFailed(ERC1155BountyTransferFailed),
+    #[cfg(feature = "packs")]
+    InvalidDropTable(ERC1155InvalidDropTable),
+    #[cfg(feature = "packs")]
+    OpeningsPending(ERC1155OpeningsPending),
+    #[cfg(feature = "packs")]
+    NotAPack(ERC1155NotAPack),
+    #[cfg(feature = "packs")]
+    InvalidPackAmount(ERC1155InvalidPackAmount),
+    #[cfg(feature = "packs")]
+    UnknownOpening(ERC1155UnknownOpening),
+    #[cfg(feature = "packs")]
+    RandomnessPending(ERC1155RandomnessPending),
+    #[cfg(feature = "packs")]
+    RandomnessCallFailed(ERC1155RandomnessCallFailed),
+    #[cfg(feature = "packs")]
+    UnauthorizedRandomness(ERC1155UnauthorizedRandomness),
+    #[cfg(feature = "market")]
+    InvalidListing(ERC1155InvalidListing),
+    #[cfg(feature = "market")]
+    InsufficientListing(ERC1155InsufficientListing),
+    #[cfg(feature = "market")]
+    NotSeller(ERC1155NotSeller),
+    #[cfg(feature = "market")]
+    InvalidMarketFee(ERC1155InvalidMarketFee),
+    #[cfg(feature = "market")]
+    PaymentFailed(ERC1155PaymentFailed),
+    #[cfg(feature = "composable")]
+    NotParentOwner(ERC1155NotParentOwner),
+    #[cfg(feature = "composable")]
+    InvalidChild(ERC1155InvalidChild),
+    #[cfg(feature = "composable")]
+    InsufficientChildBalance(ERC1155InsufficientChildBalance),
+    #[cfg(feature = "composable")]
+    TooManyChildIds(ERC1155TooManyChildIds),
+    #[cfg(feature = "attested-mint")]
+    MissingAttestation(ERC1155MissingAttestation),
+    #[cfg(feature = "checkpoints")]
+    FutureLookup(ERC1155FutureLookup),
+    #[cfg(feature = "transfer-hooks")]
+    TransferHookRejected(ERC1155TransferHookRejected),
+    #[cfg(feature = "transfer-hooks")]
+    InvalidTransferHook(ERC1155InvalidTransferHook),
+    #[cfg(feature = "transfer-hooks")]
+    TooManyTransferHooks(ERC1155TooManyTransferHooks),
+    #[cfg(feature = "modules")]
+    InvalidModule(ERC1155InvalidModule),
+    #[cfg(feature = "modules")]
+    UnknownSelector(ERC1155UnknownSelector),
+    #[cfg(feature = "holder-snapshots")]
+    UnknownSnapshot(ERC1155UnknownSnapshot),
+    #[cfg(feature = "testnet")]
+    FaucetLimitExceeded(ERC1155FaucetLimitExceeded),
+    #[cfg(feature = "transfer-security")]
+    TransferRestricted(ERC1155TransferRestricted),
+    #[cfg(feature = "transfer-security")]
+    InvalidTransferSecurityLevel(ERC1155InvalidTransferSecurityLevel),
+    #[cfg(feature = "reveal")]
+    ProvenanceAlreadySet(ERC1155ProvenanceAlreadySet),
+    #[cfg(feature = "reveal")]
+    ProvenanceNotSet(ERC1155ProvenanceNotSet),
+    #[cfg(feature = "reveal")]
+    InvalidProvenance(ERC1155InvalidProvenance),
+    #[cfg(feature = "reveal")]
+    InvalidRevealOffset(ERC1155InvalidRevealOffset),
+    #[cfg(feature = "reveal")]
+    AlreadyRevealed(ERC1155AlreadyRevealed),
+    #[cfg(feature = "open-editions")]
+    InvalidOpenEdition(ERC1155InvalidOpenEdition),
+    #[cfg(feature = "open-editions")]
+    OpenEditionNotOpen(ERC1155OpenEditionNotOpen),
+    #[cfg(feature = "open-editions")]
+    OpenEditionStillOpen(ERC1155OpenEditionStillOpen),
+    #[cfg(feature = "open-editions")]
+    NotOpenEdition(ERC1155NotOpenEdition),
+}
+
+impl From<My1155Error> for Vec<u8> {
+    fn from(error: My1155Error) -> Self {
+        match error {
+            My1155Error::AlreadyInitialized(e) => e.abi_encode(),
+            My1155Error::UnauthorizedInitializer(e) => e.abi_encode(),
+            My1155Error::GuardianExpired(e) => e.abi_encode(),
+            My1155Error::InvalidApprovalExpiry(e) => e.abi_encode(),
+            My1155Error::IdRetired(e) => e.abi_encode(),
+            My1155Error::BatchTooLong(e) => e.abi_encode(),
+            My1155Error::ReceiverHookFailed(e) => e.abi_encode(),
+            My1155Error::MetadataIsFrozen(e) => e.abi_encode(),
+            My1155Error::SupplyIsFrozen(e) => e.abi_encode(),
+            My1155Error::InvalidSeries(e) => e.abi_encode(),
+            My1155Error::TooManySeries(e) => e.abi_encode(),
+            My1155Error::OrderAlreadyFulfilled(e) => e.abi_encode(),
+            My1155Error::InsufficientBalance(e) => e.abi_encode(),
+            My1155Error::InvalidSender(e) => e.abi_encode(),
+            My1155Error::InvalidReceiver(e) => e.abi_encode(),
+            My1155Error::InvalidApprover(e) => e.abi_encode(),
+            My1155Error::InvalidOperator(e) => e.abi_encode(),
+            My1155Error::InvalidArrayLength(e) => e.abi_encode(),
+            My1155Error::MissingApprovalForAll(e) => e.abi_encode(),
+            My1155Error::BalanceOverflow(e) => e.abi_encode(),
+            My1155Error::InsufficientSupply(e) => e.abi_encode(),
+            My1155Error::ZeroAmount(e) => e.abi_encode(),
+            My1155Error::DuplicateId(e) => e.abi_encode(),
+            My1155Error::UnauthorizedAccount(e) => e.abi_encode(),
+            My1155Error::InvalidOwner(e) => e.abi_encode(),
+            My1155Error::EnforcedPause(e) => e.abi_encode(),
+            My1155Error::ExpectedPause(e) => e.abi_encode(),
+            My1155Error::MissingRole(e) => e.abi_encode(),
+            My1155Error::BadConfirmation(e) => e.abi_encode(),
+            My1155Error::MemberOutOfBounds(e) => e.abi_encode(),
+            #[cfg(feature = "royalties")]
+            My1155Error::InvalidDefaultRoyalty(e) => e.abi_encode(),
+            #[cfg(feature = "royalties")]
+            My1155Error::InvalidDefaultRoyaltyReceiver(e) => e.abi_encode(),
+            #[cfg(feature = "royalties")]
+            My1155Error::InvalidTokenRoyalty(e) => e.abi_encode(),
+            #[cfg(feature = "royalties")]
+            My1155Error::InvalidTokenRoyaltyReceiver(e) => e.abi_encode(),
+            #[cfg(feature = "enumerable")]
+            My1155Error::OutOfBoundsIndex(e) => e.abi_encode(),
+            #[cfg(feature = "supply-cap")]
+            My1155Error::ExceededMaxSupply(e) => e.abi_encode(),
+            #[cfg(feature = "supply-cap")]
+            My1155Error::InvalidMaxSupply(e) => e.abi_encode(),
+            #[cfg(feature = "soulbound")]
+            My1155Error::NonTransferable(e) => e.abi_encode(),
+            #[cfg(feature = "meta-tx")]
+            My1155Error::ExpiredSignature(e) => e.abi_encode(),
+            #[cfg(feature = "meta-tx")]
+            My1155Error::InvalidSignature(e) => e.abi_encode(),
+            #[cfg(feature = "meta-tx")]
+            My1155Error::InvalidNonceInvalidation(e) => e.abi_encode(),
+            #[cfg(feature = "paid-mint")]
+            My1155Error::MintNotForSale(e) => e.abi_encode(),
+            #[cfg(feature = "paid-mint")]
+            My1155Error::IncorrectPayment(e) => e.abi_encode(),
+            #[cfg(feature = "paid-mint")]
+            My1155Error::ProceedsTransferFailed(e) => e.abi_encode(),
+            #[cfg(feature = "paid-mint")]
+            My1155Error::InvalidRefund(e) => e.abi_encode(),
+            #[cfg(feature = "paid-mint")]
+            My1155Error::RefundWindowOpen(e) => e.abi_encode(),
+            #[cfg(feature = "id-ranges")]
+            My1155Error::InvalidIdRange(e) => e.abi_encode(),
+            #[cfg(feature = "id-ranges")]
+            My1155Error::UnauthorizedSeriesMinter(e) => e.abi_encode(),
+            #[cfg(feature = "id-ranges")]
+            My1155Error::SeriesExhausted(e) => e.abi_encode(),
+            #[cfg(feature = "split-ids")]
+            My1155Error::InvalidBaseType(e) => e.abi_encode(),
+            #[cfg(feature = "split-ids")]
+            My1155Error::NonFungibleSupply(e) => e.abi_encode(),
+            #[cfg(feature = "rate-limits")]
+            My1155Error::RateLimitExceeded(e) => e.abi_encode(),
+            #[cfg(feature = "admin-delay")]
+            My1155Error::AdminDelayRequired(e) => e.abi_encode(),
+            #[cfg(feature = "admin-delay")]
+            My1155Error::InvalidAdminAction(e) => e.abi_encode(),
+            #[cfg(feature = "admin-delay")]
+            My1155Error::AdminActionAlreadyScheduled(e) => e.abi_encode(),
+            #[cfg(feature = "admin-delay")]
+            My1155Error::UnknownAdminAction(e) => e.abi_encode(),
+            #[cfg(feature = "admin-delay")]
+            My1155Error::AdminActionNotReady(e) => e.abi_encode(),
+            #[cfg(feature = "tiers")]
+            My1155Error::InvalidTierIds(e) => e.abi_encode(),
+            #[cfg(feature = "tiers")]
+            My1155Error::InvalidTierThreshold(e) => e.abi_encode(),
+            #[cfg(feature = "validity")]
+            My1155Error::InvalidValidity(e) => e.abi_encode(),
+            #[cfg(feature = "validity")]
+            My1155Error::NotExpired(e) => e.abi_encode(),
+            #[cfg(feature = "validity")]
+            My1155Error::BountyTransferFailed(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::InvalidDropTable(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::OpeningsPending(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::NotAPack(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::InvalidPackAmount(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::UnknownOpening(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::RandomnessPending(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::RandomnessCallFailed(e) => e.abi_encode(),
+            #[cfg(feature = "packs")]
+            My1155Error::UnauthorizedRandomness(e) => e.abi_encode(),
+            #[cfg(feature = "market")]
+            My1155Error::InvalidListing(e) => e.abi_encode(),
+            #[cfg(feature = "market")]
+            My1155Error::InsufficientListing(e) => e.abi_encode(),
+            #[cfg(feature = "market")]
+            My1155Error::NotSeller(e) => e.abi_encode(),
+            #[cfg(feature = "market")]
+            My1155Error::InvalidMarketFee(e) => e.abi_encode(),
+            #[cfg(feature = "market")]
+            My1155Error::PaymentFailed(e) => e.abi_encode(),
+            #[cfg(feature = "composable")]
+            My1155Error::NotParentOwner(e) => e.abi_encode(),
+            #[cfg(feature = "composable")]
+            My1155Error::InvalidChild(e) => e.abi_encode(),
+            #[cfg(feature = "composable")]
+            My1155Error::InsufficientChildBalance(e) => e.abi_encode(),
+            #[cfg(feature = "composable")]
+            My1155Error::TooManyChildIds(e) => e.abi_encode(),
+            #[cfg(feature = "attested-mint")]
+            My1155Error::MissingAttestation(e) => e.abi_encode(),
+            #[cfg(feature = "checkpoints")]
+            My1155Error::FutureLookup(e) => e.abi_encode(),
+            #[cfg(feature = "transfer-hooks")]
+            My1155Error::TransferHookRejected(e) => e.abi_encode(),
+            #[cfg(feature = "transfer-hooks")]
+            My1155Error::InvalidTransferHook(e) => e.abi_encode(),
+            #[cfg(feature = "transfer-hooks")]
+            My1155Error::TooManyTransferHooks(e) => e.abi_encode(),
+            #[cfg(feature = "modules")]
+            My1155Error::InvalidModule(e) => e.abi_encode(),
+            #[cfg(feature = "modules")]
+            My1155Error::UnknownSelector(e) => e.abi_encode(),
+            #[cfg(feature = "holder-snapshots")]
+            My1155Error::UnknownSnapshot(e) => e.abi_encode(),
+            #[cfg(feature = "testnet")]
+            My1155Error::FaucetLimitExceeded(e) => e.abi_encode(),
+            #[cfg(feature = "transfer-security")]
+            My1155Error::TransferRestricted(e) => e.abi_encode(),
+            #[cfg(feature = "transfer-security")]
+            My1155Error::InvalidTransferSecurityLevel(e) => e.abi_encode(),
+            #[cfg(feature = "reveal")]
+            My1155Error::ProvenanceAlreadySet(e) => e.abi_encode(),
+            #[cfg(feature = "reveal")]
+            My1155Error::ProvenanceNotSet(e) => e.abi_encode(),
+            #[cfg(feature = "reveal")]
+            My1155Error::InvalidProvenance(e) => e.abi_encode(),
+            #[cfg(feature = "reveal")]
+            My1155Error::InvalidRevealOffset(e) => e.abi_encode(),
+            #[cfg(feature = "reveal")]
+            My1155Error::AlreadyRevealed(e) => e.abi_encode(),
+            #[cfg(feature = "open-editions")]
+            My1155Error::InvalidOpenEdition(e) => e.abi_encode(),
+            #[cfg(feature = "open-editions")]
+            My1155Error::OpenEditionNotOpen(e) => e.abi_encode(),
+            #[cfg(feature = "open-editions")]
+            My1155Error::OpenEditionStillOpen(e) => e.abi_encode(),
+            #[cfg(feature = "open-editions")]
+            My1155Error::NotOpenEdition(e) => e.abi_encode(),
+        }
+    }
+}
+
+// `#[derive(SolidityError)]` would also list the Ownable and AccessControl errors, which `IMy1155`
+// already inherits from `IOwnable` and `IAccessControl`, and Solidity rejects the redeclaration.
+#[cfg(feature = "export-abi")]
+impl stylus_sdk::abi::export::internal::InnerTypes for My1155Error {
+    fn inner_types() -> Vec<stylus_sdk::abi::export::internal::InnerType> {
+        use alloc::format;
+        use core::any::TypeId;
+        use stylus_sdk::abi::export::internal::InnerType;
+
+        fn inner_type<E: SolError + 'static>() -> InnerType {
+            InnerType {
+                name: format!("error {};", E::SIGNATURE.replace(',', ", ")),
+                id: TypeId::of::<E>(),
+            }
+        }
+
+        #[allow(unused_mut)]
+        let mut types = alloc::vec![
+            inner_type::<AlreadyInitialized>(),
+            inner_type::<UnauthorizedInitializer>(),
+            inner_type::<GuardianExpired>(),
+            inner_type::<ERC1155InvalidApprovalExpiry>(),
+            inner_type::<ERC1155IdRetired>(),
+            inner_type::<ERC1155BatchTooLong>(),
+            inner_type::<ERC1155ReceiverHookFailed>(),
+            inner_type::<ERC1155MetadataIsFrozen>(),
+            inner_type::<ERC1155SupplyIsFrozen>(),
+            inner_type::<ERC1155InvalidSeries>(),
+            inner_type::<ERC1155TooManySeries>(),
+            inner_type::<ERC1155OrderAlreadyFulfilled>(),
+            inner_type::<ERC1155InsufficientBalance>(),
+            inner_type::<ERC1155InvalidSender>(),
+            inner_type::<ERC1155InvalidReceiver>(),
+            inner_type::<ERC1155InvalidApprover>(),
+            inner_type::<ERC1155InvalidOperator>(),
+            inner_type::<ERC1155InvalidArrayLength>(),
+            inner_type::<ERC1155MissingApprovalForAll>(),
+            inner_type::<ERC1155BalanceOverflow>(),
+            inner_type::<ERC1155InsufficientSupply>(),
+            inner_type::<ERC1155ZeroAmount>(),
+            inner_type::<ERC1155DuplicateId>(),
+            inner_type::<EnforcedPause>(),
+            inner_type::<ExpectedPause>(),
+        ];
+        #[cfg(feature = "royalties")]
+        types.extend([
+            inner_type::<ERC2981InvalidDefaultRoyalty>(),
+            inner_type::<ERC2981InvalidDefaultRoyaltyReceiver>(),
+            inner_type::<ERC2981InvalidTokenRoyalty>(),
+            inner_type::<ERC2981InvalidTokenRoyaltyReceiver>(),
+        ]);
+        #[cfg(feature = "enumerable")]
+        types.push(inner_type::<ERC1155OutOfBoundsIndex>());
+        #[cfg(feature = "supply-cap")]
+        types.extend([inner_type::<ERC1155ExceededMaxSupply>(), inner_type::<ERC1155InvalidMaxSupply>()]);
+        #[cfg(feature = "soulbound")]
+        types.push(inner_type::<ERC1155NonTransferable>());
+        #[cfg(feature = "meta-tx")]
+        types.extend([
+            inner_type::<ERC1155ExpiredSignature>(),
+            inner_type::<ERC1155InvalidSignature>(),
+            inner_type::<InvalidNonceInvalidation>(),
+        ]);
+        #[cfg(feature = "paid-mint")]
+        types.extend([
+            inner_type::<ERC1155MintNotForSale>(),
+            inner_type::<ERC1155IncorrectPayment>(),
+            inner_type::<ERC1155ProceedsTransferFailed>(),
+            inner_type::<ERC1155InvalidRefund>(),
+            inner_type::<ERC1155RefundWindowOpen>(),
+        ]);
+        #[cfg(feature = "id-ranges")]
+        types.extend([
+            inner_type::<ERC1155InvalidIdRange>(),
+            inner_type::<ERC1155UnauthorizedSeriesMinter>(),
+            inner_type::<ERC1155SeriesExhausted>(),
+        ]);
+        #[cfg(feature = "split-ids")]
+        types.extend([inner_type::<ERC1155InvalidBaseType>(), inner_type::<ERC1155NonFungibleSupply>()]);
+        #[cfg(feature = "rate-limits")]
+        types.push(inner_type::<ERC1155RateLimitExceeded>());
+        #[cfg(feature = "admin-delay")]
+        types.extend([
+            inner_type::<ERC1155AdminDelayRequired>(),
+            inner_type::<ERC1155InvalidAdminAction>(),
+            inner_type::<ERC1155AdminActionAlreadyScheduled>(),
+            inner_type::<ERC1155UnknownAdminAction>(),
+            inner_type::<ERC1155AdminActionNotReady>(),
+        ]);
+        #[cfg(feature = "tiers")]
+        types.extend([inner_type::<ERC1155InvalidTierIds>(), inner_type::<ERC1155InvalidTierThreshold>()]);
+        #[cfg(feature = "validity")]
+        types.extend([
+            inner_type::<ERC1155InvalidValidity>(),
+            inner_type::<ERC1155NotExpired>(),
+            inner_type::<ERC1155BountyTransferFailed>(),
+        ]);
+        #[cfg(feature = "packs")]
+        types.extend([
+            inner_type::<ERC1155InvalidDropTable>(),
+            inner_type::<ERC1155OpeningsPending>(),
+            inner_type::<ERC1155NotAPack>(),
+            inner_type::<ERC1155InvalidPackAmount>(),
+            inner_type::<ERC1155UnknownOpening>(),
+            inner_type::<ERC1155RandomnessPending>(),
+            inner_type::<ERC1155RandomnessCallFailed>(),
+            inner_type::<ERC1155UnauthorizedRandomness>(),
+        ]);
+        #[cfg(feature = "market")]
+        types.extend([
+            inner_type::<ERC1155InvalidListing>(),
+            inner_type::<ERC1155InsufficientListing>(),
+            inner_type::<ERC1155NotSeller>(),
+            inner_type::<ERC1155InvalidMarketFee>(),
+            inner_type::<ERC1155PaymentFailed>(),
+        ]);
+        #[cfg(feature = "composable")]
+        types.extend([
+            inner_type::<ERC1155NotParentOwner>(),
+            inner_type::<ERC1155InvalidChild>(),
+            inner_type::<ERC1155InsufficientChildBalance>(),
+            inner_type::<ERC1155TooManyChildIds>(),
+        ]);
+        #[cfg(feature = "attested-mint")]
+        types.push(inner_type::<ERC1155MissingAttestation>());
+        #[cfg(feature = "checkpoints")]
+        types.push(inner_type::<ERC1155FutureLookup>());
+        #[cfg(feature = "transfer-hooks")]
+        types.extend([
+            inner_type::<ERC1155TransferHookRejected>(),
+            inner_type::<ERC1155InvalidTransferHook>(),
+            inner_type::<ERC1155TooManyTransferHooks>(),
+        ]);
+        #[cfg(feature = "modules")]
+        types.extend([inner_type::<ERC1155InvalidModule>(), inner_type::<ERC1155UnknownSelector>()]);
+        #[cfg(feature = "holder-snapshots")]
+        types.push(inner_type::<ERC1155UnknownSnapshot>());
+        #[cfg(feature = "testnet")]
+        types.push(inner_type::<ERC1155FaucetLimitExceeded>());
+        #[cfg(feature = "transfer-security")]
+        types.extend([
+            inner_type::<ERC1155TransferRestricted>(),
+            inner_type::<ERC1155InvalidTransferSecurityLevel>(),
+        ]);
+        #[cfg(feature = "reveal")]
+        types.extend([
+            inner_type::<ERC1155ProvenanceAlreadySet>(),
+            inner_type::<ERC1155ProvenanceNotSet>(),
+            inner_type::<ERC1155InvalidProvenance>(),
+            inner_type::<ERC1155InvalidRevealOffset>(),
+            inner_type::<ERC1155AlreadyRevealed>(),
+        ]);
+        #[cfg(feature = "open-editions")]
+        types.extend([
+            inner_type::<ERC1155InvalidOpenEdition>(),
+            inner_type::<ERC1155OpenEditionNotOpen>(),
+            inner_type::<ERC1155OpenEditionStillOpen>(),
+            inner_type::<ERC1155NotOpenEdition>(),
+        ]);
+        types
+    }
+}
+
+impl From<Erc1155Error> for My1155Error {
+    fn from(error: Erc1155Error) -> Self {
+        match error {
+            Erc1155Error::InsufficientBalance(e) => My1155Error::InsufficientBalance(e),
+            Erc1155Error::InvalidSender(e) => My1155Error::InvalidSender(e),
+            Erc1155Error::InvalidReceiver(e) => My1155Error::InvalidReceiver(e),
+            Erc1155Error::InvalidApprover(e) => My1155Error::InvalidApprover(e),
+            Erc1155Error::InvalidOperator(e) => My1155Error::InvalidOperator(e),
+            Erc1155Error::InvalidArrayLength(e) => My1155Error::InvalidArrayLength(e),
+            Erc1155Error::MissingApprovalForAll(e) => My1155Error::MissingApprovalForAll(e),
+            Erc1155Error::BalanceOverflow(e) => My1155Error::BalanceOverflow(e),
+            Erc1155Error::InsufficientSupply(e) => My1155Error::InsufficientSupply(e),
+            Erc1155Error::ZeroAmount(e) => My1155Error::ZeroAmount(e),
+            Erc1155Error::DuplicateId(e) => My1155Error::DuplicateId(e),
+        }
+    }
+}
+
+#[cfg(feature = "meta-tx")]
+impl From<InvalidNonceInvalidation> for My1155Error {
+    fn from(error: InvalidNonceInvalidation) -> Self {
+        My1155Error::InvalidNonceInvalidation(error)
+    }
+}
+
+impl From<OwnableError> for My1155Error {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => My1155Error::UnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => My1155Error::InvalidOwner(e),
+        }
+    }
+}
+
+impl From<PausableError> for My1155Error {
+    fn from(error: PausableError) -> Self {
+        match error {
+            PausableError::EnforcedPause(e) => My1155Error::EnforcedPause(e),
+            PausableError::ExpectedPause(e) => My1155Error::ExpectedPause(e),
+        }
+    }
+}
+
+impl From<AccessControlError> for My1155Error {
+    fn from(error: AccessControlError) -> Self {
+        match error {
+            AccessControlError::UnauthorizedAccount(e) => My1155Error::MissingRole(e),
+            AccessControlError::BadConfirmation(e) => My1155Error::BadConfirmation(e),
+            AccessControlError::MemberOutOfBounds(e) => My1155Error::MemberOutOfBounds(e),
+        }
+    }
+}
+
+sol_storage! {
+    /// A base URI for the IDs from `start_id` to `end_id`, set with `setSeriesUri`
+    pub struct SeriesUri {
+        uint256 start_id;
+        uint256 end_id;
+        string base;
+    }
+}
 
 #[entrypoint]
 #[storage]
 pub struct My1155 {
     erc1155: Erc1155,
+    initialized: StorageBool,
+    #[borrow]
+    ownable: Ownable,
+    base_uri: StorageString,
+    #[borrow]
+    access_control: AccessControl,
+    #[borrow]
+    pausable: Pausable,
+    // Storage of the extensions, present whether or not they're enabled so the layout doesn't
+    // depend on the features
+    #[cfg_attr(not(feature = "royalties"), allow(dead_code))]
+    royalties: Royalties,
+    #[cfg_attr(not(feature = "enumerable"), allow(dead_code))]
+    enumerable: Enumerable,
+    #[cfg_attr(not(feature = "supply-cap"), allow(dead_code))]
+    supply_cap: SupplyCap,
+    #[cfg_attr(not(feature = "meta-tx"), allow(dead_code))]
+    meta_tx: MetaTx,
+    #[cfg_attr(not(feature = "paid-mint"), allow(dead_code))]
+    paid_mint: PaidMint,
+    #[cfg_attr(not(feature = "id-ranges"), allow(dead_code))]
+    id_ranges: IdRanges,
+    #[cfg_attr(not(feature = "split-ids"), allow(dead_code))]
+    split_ids: SplitIds,
+    /// Time after which guardians can no longer pause
+    guardian_expiry: StorageU256,
+    #[cfg_attr(not(feature = "rate-limits"), allow(dead_code))]
+    rate_limits: RateLimits,
+    #[cfg_attr(not(feature = "admin-delay"), allow(dead_code))]
+    admin_delay: AdminDelay,
+    /// Time each operator approval lapses by account and operator, zero for approvals that don't
+    approval_expiries: StorageMap<Address, StorageMap<Address, StorageU256>>,
+    #[cfg_attr(not(feature = "tiers"), allow(dead_code))]
+    tiers: Tiers,
+    #[cfg_attr(not(feature = "validity"), allow(dead_code))]
+    validity: Validity,
+    #[cfg_attr(not(feature = "packs"), allow(dead_code))]
+    packs: Packs,
+    #[cfg_attr(not(feature = "market"), allow(dead_code))]
+    market: Market,
+    #[cfg_attr(not(feature = "composable"), allow(dead_code))]
+    composable: Composable,
+    #[cfg_attr(not(feature = "attested-mint"), allow(dead_code))]
+    attested_mint: AttestedMint,
+    #[cfg_attr(not(feature = "checkpoints"), allow(dead_code))]
+    checkpoints: Checkpoints,
+    #[cfg_attr(not(feature = "votes"), allow(dead_code))]
+    votes: Votes,
+    /// Most entries a `TransferBatch` log lists, zero for batches logged whole
+    batch_log_chunk: StorageU256,
+    /// `pause-registry-stylus` deployment that can pause the token too, zero for none
+    pause_registry: StorageAddress,
+    /// `ID_*` state of each ID, following it from live to burned out and back
+    id_states: StorageMap<U256, StorageU8>,
+    /// Whether IDs burned out can't be minted again
+    retire_burned_out: StorageBool,
+    /// Most entries a batch may list, zero for no limit
+    max_batch_length: StorageU256,
+    #[cfg_attr(not(feature = "approval-index"), allow(dead_code))]
+    approval_index: ApprovalIndex,
+    #[cfg_attr(not(feature = "transfer-hooks"), allow(dead_code))]
+    transfer_hooks: TransferHooks,
+    #[cfg_attr(not(feature = "modules"), allow(dead_code))]
+    modules: Modules,
+    #[cfg_attr(not(feature = "holder-snapshots"), allow(dead_code))]
+    holder_snapshots: HolderSnapshots,
+    #[cfg_attr(not(feature = "testnet"), allow(dead_code))]
+    testnet: Testnet,
+    #[cfg_attr(not(feature = "transfer-security"), allow(dead_code))]
+    transfer_security: TransferSecurity,
+    /// Whether the URI can never change again
+    metadata_frozen: StorageBool,
+    /// IDs that can never be minted again
+    frozen_supplies: StorageMap<U256, StorageBool>,
+    #[cfg_attr(not(feature = "reveal"), allow(dead_code))]
+    reveal: Reveal,
+    /// URIs set for single IDs, which take precedence over every other
+    token_uris: StorageMap<U256, StorageString>,
+    /// Base URIs set for ranges of IDs, the latest first to match
+    series_uris: StorageVec<SeriesUri>,
+    #[cfg_attr(not(feature = "open-editions"), allow(dead_code))]
+    open_editions: OpenEditions,
+    /// Orders payment processors minted for
+    fulfilled_orders: StorageMap<B256, StorageBool>,
+    #[cfg_attr(not(feature = "paid-mint"), allow(dead_code))]
+    mint_refunds: MintRefunds,
+}
+
+/// Fails unless `caller` is `deployer`, when the contract was built with one.
+fn check_initializer(deployer: Option<Address>, caller: Address) -> Result<(), My1155Error> {
+    match deployer {
+        Some(deployer) if caller != deployer => {
+            Err(My1155Error::UnauthorizedInitializer(UnauthorizedInitializer { caller, deployer }))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl My1155 {
+    fn only_owner(&self) -> Result<(), My1155Error> {
+        self.ownable.require_owner().map_err(|e| e.into())
+    }
+
+    /// Fails while the token is paused, by its own `pause` or by its pause registry.
+    pub(crate) fn require_not_paused(&self) -> Result<(), My1155Error> {
+        self.pausable.require_not_paused_with(self.pause_registry.get()).map_err(|e| e.into())
+    }
+
+    fn only_holder_or_operator(&self, from: Address) -> Result<(), My1155Error> {
+        let operator = self.vm().msg_sender();
+        if from != operator && !self.is_approved_for_all(from, operator) {
+            return Err(My1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll { operator, owner: from }));
+        }
+        Ok(())
+    }
+
+    /// Fails if the caller moves `from`'s tokens with an approval that has lapsed, which the token
+    /// itself still counts.
+    fn check_approval_expiry(&self, from: Address) -> Result<(), My1155Error> {
+        let operator = self.vm().msg_sender();
+        if from != operator && self.approval_expired(from, operator) {
+            return Err(My1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll { operator, owner: from }));
+        }
+        Ok(())
+    }
+
+    fn approval_expired(&self, account: Address, operator: Address) -> bool {
+        let expires = self.approval_expiries.get(account).get(operator);
+        !expires.is_zero() && U256::from(self.vm().block_timestamp()) >= expires
+    }
+
+    /// Sets `operator`'s approval for `owner`'s tokens, lapsing at `expires` unless it's zero.
+    fn _set_approval_for_all(
+        &mut self,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+        expires: U256,
+    ) -> Result<(), My1155Error> {
+        self.erc1155._set_approval_for_all(owner, operator, approved)?;
+        self.approval_expiries.setter(owner).insert(operator, if approved { expires } else { U256::ZERO });
+        self.index_approval(owner, operator, approved);
+        Ok(())
+    }
+
+    /// Runs the extensions' hooks after balances of `ids` moved from `from` to `to`.
+    fn after_update(&mut self, from: Address, to: Address, ids: &[U256]) -> Result<(), My1155Error> {
+        let probe = profiling::start(self.vm());
+        self.track_open_editions(from, to, ids);
+        self.check_max_supply(from, ids)?;
+        self.check_non_fungible_supply(from, ids)?;
+        self.track_ids(from, to, ids);
+        self.track_holders(from, to, ids);
+        self.record_checkpoints(from, to, ids);
+        self.track_votes(from, to, ids);
+        self.track_existence(from, to, ids)?;
+        self.log_testnet_balances(from, to, ids);
+        profiling::finish(self.vm(), profiling::AFTER_UPDATE, probe);
+        Ok(())
+    }
+
+    /// Fails if any of `ids` has a frozen supply or is an open edition outside its window, which
+    /// every mint checks before minting.
+    pub(crate) fn check_mintable(&self, ids: &[U256]) -> Result<(), My1155Error> {
+        if let Some(&id) = ids.iter().find(|&&id| self.frozen_supplies.get(id)) {
+            return Err(My1155Error::SupplyIsFrozen(ERC1155SupplyIsFrozen { id }));
+        }
+        ids.iter().try_for_each(|&id| self.check_open_edition(id))
+    }
+
+    /// Moves each of `ids` between live and burned out after mints (`from` zero) and burns (`to`
+    /// zero), logging `ExistenceChanged` whenever `exists` flips. Fails when a retired ID is minted.
+    fn track_existence(&mut self, from: Address, to: Address, ids: &[U256]) -> Result<(), My1155Error> {
+        if !from.is_zero() && !to.is_zero() {
+            return Ok(());
+        }
+        for &id in ids {
+            let state = self.id_states.get(id).to::<u8>();
+            let exists = self.erc1155.exists(id);
+            if exists == (state == ID_LIVE) {
+                continue;
+            }
+            if exists && state == ID_BURNED_OUT && self.retire_burned_out.get() {
+                return Err(My1155Error::IdRetired(ERC1155IdRetired { id }));
+            }
+            self.id_states.insert(id, U8::from(if exists { ID_LIVE } else { ID_BURNED_OUT }));
+            log(self.vm(), ExistenceChanged { id, exists });
+        }
+        Ok(())
+    }
+
+    /// Fails as a transfer of `amount` of `id` from `from` to `to` by the caller would, without
+    /// moving or counting anything.
+    fn check_transfer(&self, from: Address, to: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.check_transferable()?;
+        self.check_transfer_security(from, to)?;
+        self.only_holder_or_operator(from)?;
+        if to.is_zero() {
+            return Err(My1155Error::InvalidReceiver(ERC1155InvalidReceiver { receiver: Address::ZERO }));
+        }
+        Erc1155::check_amount(id, amount)?;
+        let balance = self.erc1155.balance_of(from, id);
+        if balance < amount {
+            return Err(My1155Error::InsufficientBalance(ERC1155InsufficientBalance {
+                sender: from,
+                balance,
+                needed: amount,
+                id,
+            }));
+        }
+        self.preview_rate_limits(from, to, id, amount)
+    }
+
+    /// Fails if a batch of `length` entries is longer than the owner allows.
+    pub(crate) fn check_batch_length(&self, length: usize) -> Result<(), My1155Error> {
+        let max = self.max_batch_length.get();
+        if !max.is_zero() && U256::from(length) > max {
+            return Err(My1155Error::BatchTooLong(ERC1155BatchTooLong {
+                length: U256::from(length),
+                maxLength: max,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Calls `to`'s receiver hook with `calldata` if it's a contract, and returns what the hook
+    /// returned. Fails with the return data and the gas the hook used unless it returns `selector`.
+    fn call_receiver_hook(&mut self, to: Address, calldata: &[u8], selector: [u8; 4]) -> Result<Bytes, My1155Error> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(Bytes::from(Vec::new()));
+        }
+        let gas_start = self.vm().evm_gas_left();
+        let result = self.vm().call(&calls::context::Call::new(), to, calldata);
+        let gas_used = U256::from(gas_start.saturating_sub(self.vm().evm_gas_left()));
+        let accepted = result
+            .as_ref()
+            .is_ok_and(|output| FixedBytes::<4>::abi_decode(output, true).is_ok_and(|answer| answer == selector));
+        let return_data = result.unwrap_or_else(Vec::<u8>::from);
+        if !accepted {
+            return Err(My1155Error::ReceiverHookFailed(ERC1155ReceiverHookFailed {
+                receiver: to,
+                returnData: return_data.into(),
+                gasUsed: gas_used,
+            }));
+        }
+        log(self.vm(), ReceiverHookCalled { receiver: to, returnData: return_data.clone().into(), gasUsed: gas_used });
+        Ok(return_data.into())
+    }
+
+    /// Calls `onERC1155Received` on `to` for a mint of `amount` of `id`, with the zero address as
+    /// `from`. Mints call it last, once their own effects are done.
+    pub(crate) fn call_mint_hook(
+        &mut self,
+        to: Address,
+        id: U256,
+        amount: U256,
+        data: Vec<u8>,
+    ) -> Result<(), My1155Error> {
+        let hook = IERC1155Receiver::onERC1155ReceivedCall {
+            operator: self.vm().msg_sender(),
+            from: Address::ZERO,
+            id,
+            value: amount,
+            data: data.into(),
+        };
+        self.call_receiver_hook(to, &hook.abi_encode(), IERC1155Receiver::onERC1155ReceivedCall::SELECTOR).map(drop)
+    }
+
+    /// `call_mint_hook` for a batch mint, calling `onERC1155BatchReceived`.
+    pub(crate) fn call_batch_mint_hook(
+        &mut self,
+        to: Address,
+        ids: Vec<U256>,
+        amounts: Vec<U256>,
+        data: Vec<u8>,
+    ) -> Result<(), My1155Error> {
+        let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+            operator: self.vm().msg_sender(),
+            from: Address::ZERO,
+            ids,
+            values: amounts,
+            data: data.into(),
+        };
+        self.call_receiver_hook(to, &hook.abi_encode(), IERC1155Receiver::onERC1155BatchReceivedCall::SELECTOR)
+            .map(drop)
+    }
+
+    /// The selector of the error `result` failed with, zero if it succeeded.
+    pub(crate) fn error_selector(result: Result<(), My1155Error>) -> FixedBytes<4> {
+        result.err().map_or(FixedBytes::ZERO, |error| FixedBytes::from_slice(&Vec::<u8>::from(error)[..4]))
+    }
+
+    /// Entries per `TransferBatch` log of the batches moved, zero for one log per batch.
+    fn log_chunk(&self) -> usize {
+        usize::try_from(self.batch_log_chunk.get()).unwrap_or(usize::MAX)
+    }
+
+    /// `setUri` without its checks, which `admin-delay` also calls once the action is due.
+    fn _set_uri(&mut self, new_uri: String) -> Result<(), My1155Error> {
+        if self.metadata_frozen.get() {
+            return Err(My1155Error::MetadataIsFrozen(ERC1155MetadataIsFrozen {}));
+        }
+        self.base_uri.set_str(new_uri);
+        Ok(())
+    }
+
+    /// `setTokenUris` without its checks, which `admin-delay` also calls once the action is due.
+    fn _set_token_uris(&mut self, ids: Vec<U256>, uris: Vec<String>) -> Result<(), My1155Error> {
+        if self.metadata_frozen.get() {
+            return Err(My1155Error::MetadataIsFrozen(ERC1155MetadataIsFrozen {}));
+        }
+        if ids.len() != uris.len() {
+            return Err(My1155Error::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(uris.len()),
+            }));
+        }
+        self.check_batch_length(ids.len())?;
+        for (id, uri) in ids.into_iter().zip(uris) {
+            self.token_uris.setter(id).set_str(uri);
+            log(self.vm(), URI { value: self.uri(id), id });
+        }
+        Ok(())
+    }
+
+    /// `setSeriesUri` without its checks, which `admin-delay` also calls once the action is due.
+    fn _set_series_uri(&mut self, start_id: U256, end_id: U256, base: String) -> Result<(), My1155Error> {
+        if self.metadata_frozen.get() {
+            return Err(My1155Error::MetadataIsFrozen(ERC1155MetadataIsFrozen {}));
+        }
+        if start_id > end_id {
+            return Err(My1155Error::InvalidSeries(ERC1155InvalidSeries { startId: start_id, endId: end_id }));
+        }
+        if base.is_empty() {
+            // Clearing a series empties it in place, so `uri` skips it for whatever is under it
+            for index in 0..self.series_uris.len() {
+                let mut series = self.series_uris.setter(index).expect("index is in bounds");
+                if series.start_id.get() == start_id && series.end_id.get() == end_id {
+                    series.base.set_str("");
+                }
+            }
+        } else {
+            if self.series_uris.len() as u64 >= MAX_SERIES_URIS {
+                let max_series = U256::from(MAX_SERIES_URIS);
+                return Err(My1155Error::TooManySeries(ERC1155TooManySeries { maxSeries: max_series }));
+            }
+            let mut series = self.series_uris.grow();
+            series.start_id.set(start_id);
+            series.end_id.set(end_id);
+            series.base.set_str(&base);
+        }
+        log(self.vm(), SeriesUriSet { startId: start_id, endId: end_id, base });
+        Ok(())
+    }
+
+    /// The base URI of the latest series covering `id` with a non-empty one, if any does.
+    fn series_base_uri(&self, id: U256) -> Option<String> {
+        (0..self.series_uris.len())
+            .rev()
+            .filter_map(|index| self.series_uris.get(index))
+            .filter(|series| series.start_id.get() <= id && id <= series.end_id.get())
+            .map(|series| series.base.get_string())
+            .find(|base| !base.is_empty())
+    }
 }
 
 #[public]
+#[inherit(Ownable, Pausable, AccessControl)]
+#[implements(
+    IRoyalties,
+    IEnumerable,
+    ISupplyCap,
+    ISoulbound,
+    IMetaTx,
+    IPaidMint,
+    IIdRanges,
+    ISplitIds,
+    IRateLimits,
+    IAdminDelay,
+    ITiers,
+    IValidity,
+    IPacks,
+    IMarket,
+    IComposable,
+    IAttestedMint,
+    ICheckpoints,
+    IVotes,
+    IApprovalIndex,
+    ITransferHooks,
+    IModules,
+    IHolderSnapshots,
+    ITestnet,
+    ITransferSecurity,
+    IReveal,
+    IOpenEditions
+)]
 impl My1155 {
+    pub fn initialize(&mut self, base_uri: String, owner: Address) -> Result<(), My1155Error> {
+        if self.initialized.get() {
+            return Err(My1155Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        check_initializer(DEPLOYER, self.vm().msg_sender())?;
+        self.ownable.initialize(owner)?;
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, owner);
+        self.initialized.set(true);
+        self.base_uri.set_str(base_uri);
+        Ok(())
+    }
+
+    /// The URI set for `id` with `setTokenUris`, or else the base URI of the latest series
+    /// covering it followed by `<id>.json`, or else the token's base URI followed by the same.
+    pub fn uri(&self, id: U256) -> String {
+        let token_uri = self.token_uris.getter(id).get_string();
+        if !token_uri.is_empty() {
+            return token_uri;
+        }
+        if let Some(mut uri) = self.series_base_uri(id) {
+            uri.push_str(&id.to_string());
+            uri.push_str(".json");
+            return uri;
+        }
+        let mut uri = self.base_uri.get_string();
+        let Some(index) = self.metadata_index(id) else {
+            return uri;
+        };
+        uri.push_str(&index.to_string());
+        uri.push_str(".json");
+        uri
+    }
+
+    pub fn set_uri(&mut self, new_uri: String) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(extensions::admin_delay::setUriCall::SELECTOR)?;
+        self._set_uri(new_uri)
+    }
+
+    /// Sets the URI of each of `ids` to the one at the same index of `uris`, an empty one reverting
+    /// the ID to its series or the base URI (owner only). Logs `URI` for each.
+    pub fn set_token_uris(&mut self, ids: Vec<U256>, uris: Vec<String>) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(extensions::admin_delay::setTokenUrisCall::SELECTOR)?;
+        self._set_token_uris(ids, uris)
+    }
+
+    /// Sets the base URI of the IDs from `start_id` to `end_id` inclusive, which `uri` follows with
+    /// `<id>.json` (owner only). Later series take precedence where they overlap earlier ones. An
+    /// empty `base` clears the series set for exactly this range, handing their IDs back to the
+    /// series under them or the token's base URI. At most `MAX_SERIES_URIS` series can be set,
+    /// cleared ones included.
+    pub fn set_series_uri(&mut self, start_id: U256, end_id: U256, base: String) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(extensions::admin_delay::setSeriesUriCall::SELECTOR)?;
+        self._set_series_uri(start_id, end_id, base)
+    }
+
+    /// Whether the URI can never change again.
+    pub fn metadata_frozen(&self) -> bool {
+        self.metadata_frozen.get()
+    }
+
+    /// Fixes the URIs for good, so `setUri`, `setTokenUris` and `setSeriesUri` revert from now on
+    /// (owner only). Can't be undone.
+    pub fn freeze_metadata(&mut self) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if self.metadata_frozen.get() {
+            return Err(My1155Error::MetadataIsFrozen(ERC1155MetadataIsFrozen {}));
+        }
+        self.metadata_frozen.set(true);
+        log(self.vm(), MetadataFrozen { uri: self.base_uri.get_string() });
+        Ok(())
+    }
+
+    /// Stops transfers, mints and burns (owner, or guardians until the guardian expiry).
+    pub fn pause(&mut self) -> Result<(), My1155Error> {
+        let account = self.vm().msg_sender();
+        if self.access_control.has_role(GUARDIAN_ROLE, account) && account != self.ownable.owner() {
+            let expiry = self.guardian_expiry.get();
+            if U256::from(self.vm().block_timestamp()) >= expiry {
+                return Err(My1155Error::GuardianExpired(GuardianExpired { expiry }));
+            }
+        } else {
+            self.only_owner()?;
+        }
+        Ok(self.pausable._pause()?)
+    }
+
+    /// Resumes transfers, mints and burns (owner only).
+    pub fn unpause(&mut self) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.check_admin_delay(extensions::admin_delay::unpauseCall::SELECTOR)?;
+        Ok(self.pausable._unpause()?)
+    }
+
+    pub fn total_supply(&self, id: U256) -> U256 {
+        self.erc1155.total_supply(id)
+    }
+
+    /// Whether `id` has supply now. Burning the whole supply clears it.
+    pub fn exists(&self, id: U256) -> bool {
+        self.erc1155.exists(id)
+    }
+
+    /// Whether `id` was ever minted, including IDs burned out since.
+    pub fn ever_existed(&self, id: U256) -> bool {
+        self.id_states.get(id).to::<u8>() != ID_UNMINTED || self.exists(id)
+    }
+
+    /// Whether IDs burned out can't be minted again, false until the owner retires them.
+    pub fn retires_burned_out(&self) -> bool {
+        self.retire_burned_out.get()
+    }
+
+    /// Stops IDs whose supply is burned to zero from being minted again, or lets them be re-created
+    /// with false (owner only).
+    pub fn set_retire_burned_out(&mut self, retire: bool) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.retire_burned_out.set(retire);
+        log(self.vm(), RetireBurnedOutSet { retire });
+        Ok(())
+    }
+
+    /// Whether `id` can never be minted again.
+    pub fn supply_frozen(&self, id: U256) -> bool {
+        self.frozen_supplies.get(id)
+    }
+
+    /// Fixes `id`'s supply for good, so every mint of it reverts from now on, bridge mints
+    /// included (owner only). Holders can still burn theirs. Can't be undone.
+    pub fn freeze_supply(&mut self, id: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        if self.frozen_supplies.get(id) {
+            return Err(My1155Error::SupplyIsFrozen(ERC1155SupplyIsFrozen { id }));
+        }
+        self.frozen_supplies.insert(id, true);
+        log(self.vm(), SupplyFrozen { id, totalSupply: self.erc1155.total_supply(id) });
+        Ok(())
+    }
+
+    pub fn mint(&mut self, to: Address, id: U256, amount: U256, data: Bytes) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.only_owner()?;
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+        self.call_mint_hook(to, id, amount, data.0)
+    }
+
+    pub fn mint_batch(
+        &mut self,
+        to: Address,
+        ids: Vec<U256>,
+        amounts: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.only_owner()?;
+        self.check_batch_length(ids.len().max(amounts.len()))?;
+        self.check_mintable(&ids)?;
+        self.check_rate_limits(Address::ZERO, to, &ids, &amounts)?;
+        self.call_transfer_hooks(Address::ZERO, to, &ids, &amounts)?;
+        let log_chunk = self.log_chunk();
+        self.erc1155._mint_batch(to, &ids, &amounts, log_chunk)?;
+        self.after_update(Address::ZERO, to, &ids)?;
+        self.call_batch_mint_hook(to, ids, amounts, data.0)
+    }
+
+    pub fn burn(&mut self, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        let from = self.vm().msg_sender();
+        self.erc1155._burn(from, id, amount)?;
+        self.after_update(from, Address::ZERO, &[id])
+    }
+
+    pub fn burn_from(&mut self, from: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.only_holder_or_operator(from)?;
+        self.erc1155._burn(from, id, amount)?;
+        self.after_update(from, Address::ZERO, &[id])
+    }
+
+    pub fn burn_batch(&mut self, ids: Vec<U256>, amounts: Vec<U256>) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        self.check_batch_length(ids.len().max(amounts.len()))?;
+        let from = self.vm().msg_sender();
+        let log_chunk = self.log_chunk();
+        self.erc1155._burn_batch(from, &ids, &amounts, log_chunk)?;
+        self.after_update(from, Address::ZERO, &ids)
+    }
+
+    /// Mints tokens bridged in from another chain (bridge role only).
+    pub fn crosschain_mint(&mut self, to: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        only_role!(self.access_control, BRIDGE_ROLE);
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+
+        log(self.vm(), CrosschainMint {
+            to,
+            id,
+            amount,
+            sender: self.vm().msg_sender(),
+        });
+        self.call_mint_hook(to, id, amount, Vec::new())
+    }
+
+    /// Burns tokens bridged out to another chain (bridge role only). No approval is needed.
+    pub fn crosschain_burn(&mut self, from: Address, id: U256, amount: U256) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        only_role!(self.access_control, BRIDGE_ROLE);
+        self.erc1155._burn(from, id, amount)?;
+        self.after_update(from, Address::ZERO, &[id])?;
+
+        log(self.vm(), CrosschainBurn {
+            from,
+            id,
+            amount,
+            sender: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+
+    /// Mints tokens bought off-chain in order `order_id`, such as by card, once per order (payment
+    /// processor role only). Logs `MintedWithReceipt` for the processor to reconcile its orders.
+    pub fn mint_with_receipt(
+        &mut self,
+        to: Address,
+        id: U256,
+        amount: U256,
+        order_id: B256,
+    ) -> Result<(), My1155Error> {
+        self.require_not_paused()?;
+        only_role!(self.access_control, PAYMENT_PROCESSOR_ROLE);
+        if self.fulfilled_orders.get(order_id) {
+            return Err(My1155Error::OrderAlreadyFulfilled(ERC1155OrderAlreadyFulfilled { orderId: order_id }));
+        }
+        self.fulfilled_orders.insert(order_id, true);
+        self.check_mintable(&[id])?;
+        self.check_rate_limits(Address::ZERO, to, &[id], &[amount])?;
+        self.call_transfer_hooks(Address::ZERO, to, &[id], &[amount])?;
+        self.erc1155._mint(to, id, amount)?;
+        self.after_update(Address::ZERO, to, &[id])?;
+
+        log(self.vm(), MintedWithReceipt {
+            orderId: order_id,
+            to,
+            id,
+            amount,
+            processor: self.vm().msg_sender(),
+        });
+        self.call_mint_hook(to, id, amount, Vec::new())
+    }
+
+    /// Whether a payment processor minted for order `order_id`.
+    pub fn order_fulfilled(&self, order_id: B256) -> bool {
+        self.fulfilled_orders.get(order_id)
+    }
+
+    #[selector(name = "BRIDGE_ROLE")]
+    pub fn bridge_role(&self) -> B256 {
+        BRIDGE_ROLE
+    }
+
+    #[selector(name = "GUARDIAN_ROLE")]
+    pub fn guardian_role(&self) -> B256 {
+        GUARDIAN_ROLE
+    }
+
+    #[selector(name = "PAYMENT_PROCESSOR_ROLE")]
+    pub fn payment_processor_role(&self) -> B256 {
+        PAYMENT_PROCESSOR_ROLE
+    }
+
+    /// Time after which guardians can no longer pause, zero until the owner sets one.
+    pub fn guardian_expiry(&self) -> U256 {
+        self.guardian_expiry.get()
+    }
+
+    /// Lets guardians pause until `expiry`, or ends their power with a past time (owner only).
+    pub fn set_guardian_expiry(&mut self, expiry: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.guardian_expiry.set(expiry);
+        log(self.vm(), GuardianExpirySet { expiry });
+        Ok(())
+    }
+
+    /// Most entries a `TransferBatch` log lists, zero until the owner sets a limit.
+    pub fn batch_log_chunk(&self) -> U256 {
+        self.batch_log_chunk.get()
+    }
+
+    /// Splits the logs of batches over `entries` into `TransferBatch` logs of at most `entries`
+    /// each, in order, or logs batches whole again with zero (owner only).
+    pub fn set_batch_log_chunk(&mut self, entries: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.batch_log_chunk.set(entries);
+        log(self.vm(), BatchLogChunkSet { entries });
+        Ok(())
+    }
+
+    /// Most entries a batch may list, zero until the owner sets a limit.
+    pub fn max_batch_length(&self) -> U256 {
+        self.max_batch_length.get()
+    }
+
+    /// Rejects batches listing more than `entries` IDs, amounts or accounts with
+    /// `ERC1155BatchTooLong`, or lifts the limit with zero (owner only).
+    pub fn set_max_batch_length(&mut self, entries: U256) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.max_batch_length.set(entries);
+        log(self.vm(), MaxBatchLengthSet { entries });
+        Ok(())
+    }
+
+    /// `pause-registry-stylus` deployment the token consults, zero until the owner sets one.
+    pub fn pause_registry(&self) -> Address {
+        self.pause_registry.get()
+    }
+
+    /// Lets `registry`'s deployment-wide switch pause the token as `pause` does, or stops with a
+    /// zero address (owner only).
+    pub fn set_pause_registry(&mut self, registry: Address) -> Result<(), My1155Error> {
+        self.only_owner()?;
+        self.pause_registry.set(registry);
+        log(self.vm(), PauseRegistrySet { registry });
+        Ok(())
+    }
+
+    /// Hash of the sources the contract was built from and its crate version, see `cradle-build-info`.
+    pub fn build_metadata(&self) -> (B256, String) {
+        (B256::new(SOURCE_HASH), env!("CARGO_PKG_VERSION").into())
+    }
+
     pub fn balance_of(&self, account: Address, id: U256) -> U256 {
         self.erc1155.balance_of(account, id)
     }
 
-    pub fn balance_of_batch(&self, accounts: Vec<Address>, ids: Vec<U256>) -> Result<Vec<U256>, Vec<u8>> {
+    pub fn balance_of_batch(&self, accounts: Vec<Address>, ids: Vec<U256>) -> Result<Vec<U256>, My1155Error> {
+        self.check_batch_length(accounts.len().max(ids.len()))?;
         self.erc1155.balance_of_batch(accounts, ids).map_err(|e| e.into())
     }
 
-    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Vec<u8>> {
-        self.erc1155.set_approval_for_all(operator, approved).map_err(|e| e.into())
+    /// Approves or revokes `operator` for all of the caller's tokens, with no expiry.
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), My1155Error> {
+        self._set_approval_for_all(self.vm().msg_sender(), operator, approved, U256::ZERO)
     }
 
+    /// Approves `operator` for all of the caller's tokens until `expires`, after which it's no
+    /// longer approved. Revoking ignores `expires`.
+    pub fn set_approval_for_all_with_expiry(
+        &mut self,
+        operator: Address,
+        approved: bool,
+        expires: U256,
+    ) -> Result<(), My1155Error> {
+        if approved && expires <= U256::from(self.vm().block_timestamp()) {
+            return Err(My1155Error::InvalidApprovalExpiry(ERC1155InvalidApprovalExpiry { expires }));
+        }
+        let account = self.vm().msg_sender();
+        self._set_approval_for_all(account, operator, approved, expires)?;
+        let expires = if approved { expires } else { U256::ZERO };
+        log(self.vm(), ApprovalForAllExpiry { account, operator, expires });
+        Ok(())
+    }
+
+    /// Time `operator`'s approval for `account` lapses, or zero if it doesn't.
+    pub fn approval_expiry(&self, account: Address, operator: Address) -> U256 {
+        self.approval_expiries.get(account).get(operator)
+    }
+
+    /// Whether `operator` can move all of `account`'s tokens, false once its approval has lapsed.
     pub fn is_approved_for_all(&self, account: Address, operator: Address) -> bool {
-        self.erc1155.is_approved_for_all(account, operator)
+        self.erc1155.is_approved_for_all(account, operator) && !self.approval_expired(account, operator)
+    }
+
+    /// Whether the caller could transfer `amount` of `id` from `from` to `to` now, and if not the
+    /// selector of the error `safeTransferFrom` would revert with, zero when it wouldn't.
+    pub fn can_transfer(&self, from: Address, to: Address, id: U256, amount: U256) -> (bool, FixedBytes<4>) {
+        let reason = Self::error_selector(self.check_transfer(from, to, id, amount));
+        (reason.is_zero(), reason)
+    }
+
+    /// Whether the token supports a given standard, including those of the enabled extensions.
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, INTERFACES.into_iter().chain(extensions::interfaces()))
     }
 
+    /// Moves `value` of `id` from `from` to `to`, then calls `onERC1155Received` with `data` if `to`
+    /// is a contract. A hook that reverts or doesn't return its selector fails the transfer with
+    /// `ERC1155ReceiverHookFailed`.
     pub fn safe_transfer_from(
         &mut self,
         from: Address,
         to: Address,
         id: U256,
         value: U256,
-        data: Vec<u8>,
-    ) -> Result<(), Vec<u8>> {
-        self.erc1155.safe_transfer_from(from, to, id, value, data).map_err(|e| e.into())
+        data: Bytes,
+    ) -> Result<(), My1155Error> {
+        self.safe_transfer_from_with_return(from, to, id, value, data).map(drop)
     }
 
+    /// `safeTransferFrom`, returning what the receiver hook returned, empty if `to` isn't a
+    /// contract. An accepted hook's answer and the gas it used are logged with `ReceiverHookCalled`.
+    pub fn safe_transfer_from_with_return(
+        &mut self,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<Bytes, My1155Error> {
+        self.require_not_paused()?;
+        self.check_transferable()?;
+        self.check_transfer_security(from, to)?;
+        self.check_approval_expiry(from)?;
+        self.check_rate_limits(from, to, &[id], &[value])?;
+        self.call_transfer_hooks(from, to, &[id], &[value])?;
+        self.erc1155.safe_transfer_from(from, to, id, value, &data)?;
+        self.after_update(from, to, &[id])?;
+        let hook = IERC1155Receiver::onERC1155ReceivedCall {
+            operator: self.vm().msg_sender(),
+            from,
+            id,
+            value,
+            data: data.0.into(),
+        };
+        self.call_receiver_hook(to, &hook.abi_encode(), IERC1155Receiver::onERC1155ReceivedCall::SELECTOR)
+    }
+
+    /// `safeTransferFrom` for several ids at once, calling `onERC1155BatchReceived` instead.
     pub fn safe_batch_transfer_from(
         &mut self,
         from: Address,
         to: Address,
         ids: Vec<U256>,
         values: Vec<U256>,
-        data: Vec<u8>,
-    ) -> Result<(), Vec<u8>> {
-        self.erc1155.safe_batch_transfer_from(from, to, ids, values, data).map_err(|e| e.into())
+        data: Bytes,
+    ) -> Result<(), My1155Error> {
+        self.safe_batch_transfer_from_with_return(from, to, ids, values, data).map(drop)
+    }
+
+    /// `safeBatchTransferFrom`, returning what the receiver hook returned as
+    /// `safeTransferFromWithReturn` does.
+    pub fn safe_batch_transfer_from_with_return(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<Bytes, My1155Error> {
+        self.require_not_paused()?;
+        self.check_transferable()?;
+        self.check_transfer_security(from, to)?;
+        self.check_approval_expiry(from)?;
+        self.check_batch_length(ids.len().max(values.len()))?;
+        self.check_rate_limits(from, to, &ids, &values)?;
+        self.call_transfer_hooks(from, to, &ids, &values)?;
+        let log_chunk = self.log_chunk();
+        self.erc1155.safe_batch_transfer_from(from, to, &ids, &values, &data, log_chunk)?;
+        self.after_update(from, to, if TRACKS_IDS { &ids } else { &[] })?;
+        let hook = IERC1155Receiver::onERC1155BatchReceivedCall {
+            operator: self.vm().msg_sender(),
+            from,
+            ids,
+            values,
+            data: data.0.into(),
+        };
+        self.call_receiver_hook(to, &hook.abi_encode(), IERC1155Receiver::onERC1155BatchReceivedCall::SELECTOR)
+    }
+
+    /// Delegates selectors the token doesn't implement to the modules `modules` routes them to,
+    /// and reverts without it.
+    #[fallback]
+    #[payable]
+    fn fallback(&mut self, calldata: &[u8]) -> ArbResult {
+        self.route_to_module(calldata)
     }
 }
 
@@ -1,5 +1,15 @@
-#![cfg_attr(not(feature = "export-abi"), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
 
+#[cfg(not(any(test, feature = "export-abi")))]
+#[no_mangle]
+pub extern "C" fn main() {}
+
+#[cfg(feature = "export-abi")]
 fn main() {
-    // Empty main for binary compilation
+    // `interface` prints IERC1155Token.sol, anything else goes to the SDK's ABI export
+    if std::env::args().nth(1).as_deref() == Some("interface") {
+        print!("{}", erc1155_stylus::interface::solidity_interface());
+    } else {
+        erc1155_stylus::print_from_args();
+    }
 }
@@ -0,0 +1,72 @@
+//! Gas and ink readings around the token's hot paths, with the `profiling` feature.
+//!
+//! A profiled section reads the ink and gas left as it starts and again as it ends, and logs both
+//! readings in one `GasProfiled` event, so the receipt of a mainnet transaction shows what each
+//! part of a mint, burn or transfer cost without a tracing node. Its `section` topic is the
+//! section's name in ASCII, right padded, for filtering one section's logs. A section that fails
+//! logs nothing, since its logs revert with it, and neither does one that returns early with
+//! nothing to do, like the rate limits of a burn.
+//!
+//! Readings include reading the meters, and sections nest: `after_update` covers the extensions'
+//! bookkeeping after `update_single` or `update_batch` moved the balances, and a transfer's
+//! `rate_limits` and `transfer_hooks` run before both. Without the feature a [`Probe`] holds
+//! nothing and the calls compile away.
+
+use stylus_sdk::{alloy_sol_types::sol, stylus_core::Host};
+#[cfg(feature = "profiling")]
+use stylus_sdk::{alloy_primitives::B256, prelude::*};
+
+sol! {
+    event GasProfiled(bytes32 indexed section, uint64 inkStart, uint64 inkEnd, uint64 gasStart, uint64 gasEnd);
+}
+
+/// The balances, supply and `TransferSingle` log of one ID.
+pub(crate) const UPDATE_SINGLE: &str = "update_single";
+/// The balances, supplies and `TransferBatch` logs of a batch.
+pub(crate) const UPDATE_BATCH: &str = "update_batch";
+/// The extensions' hooks after balances moved.
+pub(crate) const AFTER_UPDATE: &str = "after_update";
+/// `rate-limits` counting a move against its caps.
+#[cfg(feature = "rate-limits")]
+pub(crate) const RATE_LIMITS: &str = "rate_limits";
+/// `transfer-hooks` calling each hook before a move.
+#[cfg(feature = "transfer-hooks")]
+pub(crate) const TRANSFER_HOOKS: &str = "transfer_hooks";
+
+/// The ink and gas left when a section started.
+#[derive(Clone, Copy)]
+pub(crate) struct Probe {
+    #[cfg(feature = "profiling")]
+    ink: u64,
+    #[cfg(feature = "profiling")]
+    gas: u64,
+}
+
+/// Starts a section.
+#[cfg(feature = "profiling")]
+pub(crate) fn start(vm: &dyn Host) -> Probe {
+    Probe { ink: vm.evm_ink_left(), gas: vm.evm_gas_left() }
+}
+
+#[cfg(not(feature = "profiling"))]
+#[inline(always)]
+pub(crate) fn start(_vm: &dyn Host) -> Probe {
+    Probe {}
+}
+
+/// Ends the section `probe` started, logging `GasProfiled` for `section`.
+#[cfg(feature = "profiling")]
+pub(crate) fn finish(vm: &dyn Host, section: &str, probe: Probe) {
+    let (ink_end, gas_end) = (vm.evm_ink_left(), vm.evm_gas_left());
+    log(vm, GasProfiled {
+        section: B256::right_padding_from(section.as_bytes()),
+        inkStart: probe.ink,
+        inkEnd: ink_end,
+        gasStart: probe.gas,
+        gasEnd: gas_end,
+    });
+}
+
+#[cfg(not(feature = "profiling"))]
+#[inline(always)]
+pub(crate) fn finish(_vm: &dyn Host, _section: &str, _probe: Probe) {}
@@ -0,0 +1,2572 @@
+use super::*;
+use alloc::{vec, vec::Vec};
+use cradle_test_utils::{deploy, emitted, ok};
+use erc1155::{ApprovalForAll, TransferBatch, TransferSingle};
+use stylus_sdk::{
+    alloy_primitives::{address, hex, keccak256},
+    testing::TestVM,
+};
+
+pub(crate) const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+pub(crate) const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+pub(crate) const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const BRIDGE: Address = address!("00000000000000000000000000000000b41d6e00");
+const BASE_URI: &str = "ipfs://cradle/";
+
+pub(crate) fn id(n: u64) -> U256 {
+    U256::from(n)
+}
+
+/// A contract initialized by `OWNER`, with the VM to drive it.
+pub(crate) fn setup() -> (TestVM, My1155) {
+    let (vm, mut contract) = deploy::<My1155>(OWNER);
+    ok(contract.initialize(BASE_URI.into(), OWNER));
+    (vm, contract)
+}
+
+/// `setup`, plus 100 of token 1 and 50 of token 2 minted to `ALICE`.
+pub(crate) fn setup_with_balances() -> (TestVM, My1155) {
+    let (vm, mut contract) = setup();
+    ok(contract.mint_batch(ALICE, vec![id(1), id(2)], vec![id(100), id(50)], vec![].into()));
+    (vm, contract)
+}
+
+#[test]
+fn initialize_sets_owner_admin_and_uri() {
+    let (_vm, contract) = setup();
+    assert_eq!(contract.ownable.owner(), OWNER);
+    assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, OWNER));
+    assert_eq!(contract.uri(id(7)), "ipfs://cradle/7.json");
+    assert!(!contract.pausable.paused());
+}
+
+#[test]
+fn initialize_runs_once() {
+    let (_vm, mut contract) = setup();
+    let result = contract.initialize(BASE_URI.into(), ALICE);
+    assert!(matches!(result, Err(My1155Error::AlreadyInitialized(_))));
+    assert_eq!(contract.ownable.owner(), OWNER);
+}
+
+#[test]
+fn initialize_is_deployer_only_when_built_with_one() {
+    assert!(check_initializer(None, ALICE).is_ok());
+    assert!(check_initializer(Some(OWNER), OWNER).is_ok());
+    let result = check_initializer(Some(OWNER), ALICE);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedInitializer(e)) if e.caller == ALICE && e.deployer == OWNER));
+}
+
+#[test]
+fn initialize_rejects_zero_owner() {
+    let vm = TestVM::new();
+    let mut contract = My1155::from(&vm);
+    let result = contract.initialize(BASE_URI.into(), Address::ZERO);
+    assert!(matches!(result, Err(My1155Error::InvalidOwner(e)) if e.owner.is_zero()));
+}
+
+#[test]
+fn set_uri_is_owner_only() {
+    let (vm, mut contract) = setup();
+    ok(contract.set_uri("https://cradle.build/".into()));
+    assert_eq!(contract.uri(id(1)), "https://cradle.build/1.json");
+
+    vm.set_sender(ALICE);
+    let result = contract.set_uri("https://evil.example/".into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(e)) if e.account == ALICE));
+    assert_eq!(contract.uri(id(1)), "https://cradle.build/1.json");
+}
+
+#[test]
+fn mint_credits_balance_and_supply() {
+    let (vm, mut contract) = setup();
+    assert!(!contract.exists(id(1)));
+
+    ok(contract.mint(ALICE, id(1), id(100), vec![].into()));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+    assert!(contract.exists(id(1)));
+
+    let events = emitted::<TransferSingle>(&vm);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operator, OWNER);
+    assert_eq!(events[0].from, Address::ZERO);
+    assert_eq!(events[0].to, ALICE);
+    assert_eq!(events[0].value, id(100));
+}
+
+#[test]
+fn mint_is_owner_only() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.mint(ALICE, id(1), id(100), vec![].into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(e)) if e.account == ALICE));
+    let result = contract.mint_batch(ALICE, vec![id(1)], vec![id(100)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    assert_eq!(contract.total_supply(id(1)), U256::ZERO);
+}
+
+#[test]
+fn mint_rejects_zero_receiver() {
+    let (_vm, mut contract) = setup();
+    let result = contract.mint(Address::ZERO, id(1), id(100), vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(e)) if e.receiver.is_zero()));
+    let result = contract.mint_batch(Address::ZERO, vec![id(1)], vec![id(100)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+}
+
+#[test]
+fn mint_batch_credits_every_id() {
+    let (vm, contract) = setup_with_balances();
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+    assert_eq!(contract.balance_of(ALICE, id(2)), id(50));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+    assert_eq!(contract.total_supply(id(2)), id(50));
+
+    let events = emitted::<TransferBatch>(&vm);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].ids, vec![id(1), id(2)]);
+    assert_eq!(events[0].values, vec![id(100), id(50)]);
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+fn mint_batch_merges_repeated_ids() {
+    let (_vm, mut contract) = setup();
+    ok(contract.mint_batch(ALICE, vec![id(3), id(4), id(3)], vec![id(1), id(2), id(5)], vec![].into()));
+    assert_eq!(contract.balance_of(ALICE, id(3)), id(6));
+    assert_eq!(contract.balance_of(ALICE, id(4)), id(2));
+    assert_eq!(contract.total_supply(id(3)), id(6));
+}
+
+#[test]
+fn mint_batch_rejects_mismatched_lengths() {
+    let (_vm, mut contract) = setup();
+    let result = contract.mint_batch(ALICE, vec![id(1), id(2)], vec![id(100)], vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::InvalidArrayLength(e)) if e.idsLength == id(2) && e.valuesLength == id(1)
+    ));
+}
+
+#[test]
+fn balance_of_batch_pairs_accounts_with_ids() {
+    let (_vm, mut contract) = setup_with_balances();
+    ok(contract.mint(BOB, id(2), id(7), vec![].into()));
+    let balances = ok(contract.balance_of_batch(vec![ALICE, BOB, BOB], vec![id(1), id(2), id(1)]));
+    assert_eq!(balances, vec![id(100), id(7), U256::ZERO]);
+
+    let result = contract.balance_of_batch(vec![ALICE], vec![id(1), id(2)]);
+    assert!(matches!(result, Err(My1155Error::InvalidArrayLength(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn holder_transfers_without_changing_supply() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(40), vec![].into()));
+
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(60));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(40));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+
+    let event = emitted::<TransferSingle>(&vm).pop().expect("transfer logged");
+    assert_eq!((event.operator, event.from, event.to), (ALICE, ALICE, BOB));
+    assert_eq!((event.id, event.value), (id(1), id(40)));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_to_self_keeps_balance() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, ALICE, id(1), id(40), vec![].into()));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_rejects_insufficient_balance() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(101), vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::InsufficientBalance(e))
+            if e.sender == ALICE && e.balance == id(100) && e.needed == id(101) && e.id == id(1)
+    ));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+    assert_eq!(contract.balance_of(BOB, id(1)), U256::ZERO);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_rejects_zero_receiver() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, Address::ZERO, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+    let result = contract.safe_batch_transfer_from(ALICE, Address::ZERO, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn can_transfer_reports_why_a_transfer_would_fail() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    assert_eq!(contract.can_transfer(ALICE, BOB, id(1), id(100)), (true, FixedBytes::ZERO));
+    let reason = |contract: &My1155, from, to, amount| contract.can_transfer(from, to, id(1), amount).1;
+    assert_eq!(reason(&contract, ALICE, BOB, id(101)), FixedBytes(ERC1155InsufficientBalance::SELECTOR));
+    assert_eq!(reason(&contract, ALICE, Address::ZERO, id(1)), FixedBytes(ERC1155InvalidReceiver::SELECTOR));
+
+    vm.set_sender(BOB);
+    assert_eq!(reason(&contract, ALICE, BOB, id(1)), FixedBytes(ERC1155MissingApprovalForAll::SELECTOR));
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+    assert_eq!(reason(&contract, ALICE, BOB, id(1)), FixedBytes(EnforcedPause::SELECTOR));
+    // Nothing moved
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+pub(crate) const PARTNER: Address = address!("00000000000000000000000000000000000c0de5");
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfers_with_return_surface_the_receiver_hook() {
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_code(PARTNER, vec![0]);
+    vm.set_sender(ALICE);
+    let hook = IERC1155Receiver::onERC1155ReceivedCall {
+        operator: ALICE,
+        from: ALICE,
+        id: id(1),
+        value: id(5),
+        data: vec![7].into(),
+    };
+    let answer = IERC1155Receiver::onERC1155ReceivedCall::SELECTOR.abi_encode();
+    vm.mock_call(PARTNER, hook.abi_encode(), Ok(answer.clone()));
+    let returned = ok(contract.safe_transfer_from_with_return(ALICE, PARTNER, id(1), id(5), vec![7].into()));
+    assert_eq!((returned.0, contract.balance_of(PARTNER, id(1))), (answer.clone(), id(5)));
+    let called = &emitted::<ReceiverHookCalled>(&vm)[0];
+    assert_eq!((called.receiver, called.returnData.to_vec()), (PARTNER, answer));
+
+    // The partner's revert comes back in the error
+    let batch = IERC1155Receiver::onERC1155BatchReceivedCall {
+        operator: ALICE,
+        from: ALICE,
+        ids: vec![id(2)],
+        values: vec![id(1)],
+        data: vec![].into(),
+    };
+    vm.mock_call(PARTNER, batch.abi_encode(), Err(b"paused".to_vec()));
+    let result = contract.safe_batch_transfer_from_with_return(ALICE, PARTNER, vec![id(2)], vec![id(1)], vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::ReceiverHookFailed(e)) if e.receiver == PARTNER && e.returnData.as_ref() == b"paused"
+    ));
+
+    // Accounts without code have no hook to call
+    assert!(ok(contract.safe_transfer_from_with_return(ALICE, BOB, id(1), id(1), vec![].into())).0.is_empty());
+    assert_eq!(emitted::<ReceiverHookCalled>(&vm).len(), 1);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_by_stranger_needs_approval() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(BOB);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::MissingApprovalForAll(e)) if e.operator == BOB && e.owner == ALICE
+    ));
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(_))));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn approval_for_all_lets_operator_transfer() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    assert!(contract.is_approved_for_all(ALICE, BOB));
+    assert!(!contract.is_approved_for_all(BOB, ALICE));
+
+    let event = emitted::<ApprovalForAll>(&vm).pop().expect("approval logged");
+    assert_eq!((event.account, event.operator, event.approved), (ALICE, BOB, true));
+
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(30), vec![].into()));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(30));
+
+    let event = emitted::<TransferSingle>(&vm).pop().expect("transfer logged");
+    assert_eq!((event.operator, event.from), (BOB, ALICE));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn revoked_operator_cannot_transfer() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    ok(contract.set_approval_for_all(BOB, false));
+    assert!(!contract.is_approved_for_all(ALICE, BOB));
+
+    vm.set_sender(BOB);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(_))));
+}
+
+#[test]
+fn approving_self_is_rejected() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.set_approval_for_all(ALICE, true);
+    assert!(matches!(result, Err(My1155Error::InvalidOperator(e)) if e.operator == ALICE));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfer_moves_every_id() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(10), id(50)], vec![].into()));
+
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(90));
+    assert_eq!(contract.balance_of(ALICE, id(2)), U256::ZERO);
+    assert_eq!(contract.balance_of(BOB, id(1)), id(10));
+    assert_eq!(contract.balance_of(BOB, id(2)), id(50));
+    assert_eq!(contract.total_supply(id(2)), id(50));
+
+    let event = emitted::<TransferBatch>(&vm).pop().expect("transfer logged");
+    assert_eq!((event.operator, event.from, event.to), (ALICE, ALICE, BOB));
+    assert_eq!(event.values, vec![id(10), id(50)]);
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfer_merges_repeated_ids_but_logs_them_as_sent() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let (ids, values) = (vec![id(1), id(2), id(1)], vec![id(10), id(5), id(30)]);
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, ids.clone(), values.clone(), vec![].into()));
+
+    assert_eq!((contract.balance_of(ALICE, id(1)), contract.balance_of(BOB, id(1))), (id(60), id(40)));
+    assert_eq!((contract.balance_of(ALICE, id(2)), contract.balance_of(BOB, id(2))), (id(45), id(5)));
+    let event = emitted::<TransferBatch>(&vm).pop().expect("transfer logged");
+    assert_eq!((event.ids, event.values), (ids, values));
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn large_batches_are_logged_in_chunks() {
+    let (vm, mut contract) = setup();
+    let ids: Vec<U256> = (1..=5).map(id).collect();
+    ok(contract.set_batch_log_chunk(id(2)));
+    assert_eq!(contract.batch_log_chunk(), id(2));
+    ok(contract.mint_batch(ALICE, ids.clone(), vec![id(3); 5], vec![].into()));
+
+    // The chunks list the batch's entries in order, repeated IDs included
+    let chunks = emitted::<TransferBatch>(&vm);
+    assert_eq!(chunks.iter().map(|e| e.ids.len()).collect::<Vec<_>>(), vec![2, 2, 1]);
+    assert_eq!(chunks.iter().flat_map(|e| e.ids.clone()).collect::<Vec<_>>(), ids);
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(1), id(2)], vec![id(1); 3], vec![].into()));
+    let chunks = emitted::<TransferBatch>(&vm);
+    assert_eq!((chunks[3].ids.clone(), chunks[4].ids.clone()), (vec![id(1), id(1)], vec![id(2)]));
+    assert_eq!((chunks[3].from, chunks[4].to), (ALICE, BOB));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(2));
+
+    // Batches within the size are logged whole, as are all batches again with zero
+    ok(contract.burn_batch(vec![id(3), id(4)], vec![id(1), id(1)]));
+    assert_eq!(emitted::<TransferBatch>(&vm).len(), 6);
+    let result = contract.set_batch_log_chunk(U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    vm.set_sender(OWNER);
+    ok(contract.set_batch_log_chunk(U256::ZERO));
+    ok(contract.mint_batch(ALICE, ids, vec![id(1); 5], vec![].into()));
+    assert_eq!(emitted::<TransferBatch>(&vm).last().map(|e| e.ids.len()), Some(5));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batches_over_the_limit_revert_up_front() {
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.balance_of_batch(vec![ALICE; 3], vec![id(1); 3]);
+    assert!(result.is_ok());
+    ok(contract.set_max_batch_length(id(2)));
+    assert_eq!(contract.max_batch_length(), id(2));
+    let too_long = |e: &ERC1155BatchTooLong| e.length == id(3) && e.maxLength == id(2);
+
+    let result = contract.balance_of_batch(vec![ALICE; 3], vec![id(1); 3]);
+    assert!(matches!(result, Err(My1155Error::BatchTooLong(e)) if too_long(&e)));
+    let result = contract.mint_batch(ALICE, vec![id(1); 3], vec![id(1); 3], vec![].into());
+    assert!(matches!(result, Err(My1155Error::BatchTooLong(e)) if too_long(&e)));
+    vm.set_sender(ALICE);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1); 3], vec![id(1); 3], vec![].into());
+    assert!(matches!(result, Err(My1155Error::BatchTooLong(e)) if too_long(&e)));
+    // The longer of the two lists counts
+    let result = contract.burn_batch(vec![id(1)], vec![id(1); 3]);
+    assert!(matches!(result, Err(My1155Error::BatchTooLong(e)) if too_long(&e)));
+    ok(contract.burn_batch(vec![id(1), id(2)], vec![id(1), id(1)]));
+
+    let result = contract.set_max_batch_length(U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    vm.set_sender(OWNER);
+    ok(contract.set_max_batch_length(U256::ZERO));
+    ok(contract.mint_batch(ALICE, vec![id(1), id(2), id(3)], vec![id(1); 3], vec![].into()));
+    assert_eq!(emitted::<MaxBatchLengthSet>(&vm).len(), 2);
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfer_checks_merged_amounts() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(2), id(2)], vec![id(30), id(30)], vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::InsufficientBalance(e)) if e.balance == id(50) && e.needed == id(60)
+    ));
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfer_to_self_checks_each_entry() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(ALICE, ALICE, vec![id(2), id(2)], vec![id(30), id(30)], vec![].into()));
+    assert_eq!(contract.balance_of(ALICE, id(2)), id(50));
+
+    let result =
+        contract.safe_batch_transfer_from(ALICE, ALICE, vec![id(2), id(2)], vec![id(30), id(51)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(e)) if e.needed == id(51)));
+}
+
+#[cfg(not(feature = "reject-duplicate-ids"))]
+#[test]
+fn batch_totals_past_the_largest_amount_are_rejected() {
+    let (vm, mut contract) = setup_with_balances();
+    // A wrapped total of the repeated entries would be zero
+    let result = contract.mint_batch(ALICE, vec![id(1), id(1)], vec![U256::MAX, id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::BalanceOverflow(e)) if e.id == id(1) && e.balance == U256::MAX));
+
+    vm.set_sender(ALICE);
+    let result = contract.burn_batch(vec![id(1), id(1)], vec![U256::MAX, id(1)]);
+    assert!(matches!(
+        result,
+        Err(My1155Error::InsufficientBalance(e)) if e.balance == id(100) && e.needed == U256::MAX
+    ));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+#[cfg(not(feature = "packed-balances"))]
+#[test]
+fn mints_past_the_largest_balance_or_supply_are_rejected() {
+    let (vm, mut contract) = setup();
+    ok(contract.mint(ALICE, id(7), U256::MAX, vec![].into()));
+    let result = contract.mint(ALICE, id(7), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::BalanceOverflow(e)) if e.id == id(7) && e.balance == U256::MAX));
+    let result = contract.mint_batch(ALICE, vec![id(7)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::BalanceOverflow(e)) if e.id == id(7) && e.balance == U256::MAX));
+    assert_eq!(contract.balance_of(ALICE, id(7)), U256::MAX);
+
+    // Another holder's balance has room, the supply doesn't
+    let result = contract.mint(BOB, id(7), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::BalanceOverflow(e)) if e.id == id(7) && e.balance == U256::MAX));
+    assert_eq!(contract.total_supply(id(7)), U256::MAX);
+    assert_eq!(emitted::<TransferSingle>(&vm).len(), 1);
+}
+
+#[cfg(feature = "reject-duplicate-ids")]
+#[test]
+fn batches_repeating_an_id_are_rejected() {
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.mint_batch(ALICE, vec![id(3), id(4), id(3)], vec![id(1); 3], vec![].into());
+    assert!(matches!(result, Err(My1155Error::DuplicateId(e)) if e.id == id(3)));
+
+    vm.set_sender(ALICE);
+    let result = contract.burn_batch(vec![id(1), id(1)], vec![id(1), id(1)]);
+    assert!(matches!(result, Err(My1155Error::DuplicateId(e)) if e.id == id(1)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+}
+
+#[cfg(feature = "reject-zero-amounts")]
+#[test]
+fn zero_amounts_are_rejected() {
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.mint(ALICE, id(3), U256::ZERO, vec![].into());
+    assert!(matches!(result, Err(My1155Error::ZeroAmount(e)) if e.id == id(3)));
+    let result = contract.mint_batch(ALICE, vec![id(3), id(4)], vec![id(1), U256::ZERO], vec![].into());
+    assert!(matches!(result, Err(My1155Error::ZeroAmount(e)) if e.id == id(4)));
+
+    vm.set_sender(ALICE);
+    let result = contract.burn(id(1), U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::ZeroAmount(e)) if e.id == id(1)));
+    assert_eq!(contract.balance_of(ALICE, id(3)), U256::ZERO);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfer_rejects_mismatched_lengths() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1), id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::InvalidArrayLength(_))));
+}
+
+#[test]
+fn burn_reduces_balance_and_supply() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(1), id(30)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(70));
+    assert_eq!(contract.total_supply(id(1)), id(70));
+
+    let event = emitted::<TransferSingle>(&vm).pop().expect("burn logged");
+    assert_eq!((event.from, event.to), (ALICE, Address::ZERO));
+
+    ok(contract.burn(id(1), id(70)));
+    assert!(!contract.exists(id(1)));
+}
+
+#[test]
+fn burned_out_ids_stop_existing_and_can_be_retired() {
+    let (vm, mut contract) = setup();
+    assert!(!contract.ever_existed(id(1)));
+    ok(contract.mint_batch(ALICE, vec![id(1), id(2)], vec![id(5), id(5)], vec![].into()));
+    vm.set_sender(ALICE);
+    ok(contract.burn_batch(vec![id(1)], vec![id(5)]));
+    assert_eq!((contract.exists(id(1)), contract.ever_existed(id(1))), (false, true));
+    let changes: Vec<_> = emitted::<ExistenceChanged>(&vm).iter().map(|e| (e.id, e.exists)).collect();
+    assert_eq!(changes, vec![(id(1), true), (id(2), true), (id(1), false)]);
+    let result = contract.set_retire_burned_out(true);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+
+    // Burned-out IDs are minted again until the owner retires them
+    vm.set_sender(OWNER);
+    ok(contract.mint(OWNER, id(1), id(1), vec![].into()));
+    assert!(contract.exists(id(1)));
+    ok(contract.burn(id(1), id(1)));
+    ok(contract.set_retire_burned_out(true));
+    assert!(contract.retires_burned_out());
+    let result = contract.mint(ALICE, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::IdRetired(e)) if e.id == id(1)));
+    ok(contract.mint(ALICE, id(2), id(1), vec![].into()));
+    ok(contract.mint(ALICE, id(3), id(1), vec![].into()));
+    assert_eq!(emitted::<ExistenceChanged>(&vm).len(), 6);
+}
+
+#[test]
+fn token_and_series_uris_override_the_base_uri() {
+    let (vm, mut contract) = setup();
+    let result = contract.set_token_uris(vec![id(1), id(2)], vec!["ar://one".into()]);
+    assert!(matches!(result, Err(My1155Error::InvalidArrayLength(_))));
+    let result = contract.set_series_uri(id(20), id(10), "ar://series/".into());
+    assert!(matches!(result, Err(My1155Error::InvalidSeries(e)) if e.startId == id(20) && e.endId == id(10)));
+    vm.set_sender(ALICE);
+    let result = contract.set_series_uri(id(10), id(20), "ar://series/".into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+
+    // Later series win where they overlap, and token URIs win over both
+    vm.set_sender(OWNER);
+    ok(contract.set_series_uri(id(10), id(20), "ar://series/".into()));
+    ok(contract.set_series_uri(id(15), id(30), "ar://later/".into()));
+    ok(contract.set_token_uris(vec![id(12), id(40)], vec!["ar://twelve".into(), "ar://forty".into()]));
+    assert_eq!(contract.uri(id(9)), "ipfs://cradle/9.json");
+    assert_eq!(contract.uri(id(10)), "ar://series/10.json");
+    assert_eq!(contract.uri(id(12)), "ar://twelve");
+    assert_eq!(contract.uri(id(15)), "ar://later/15.json");
+    assert_eq!(contract.uri(id(40)), "ar://forty");
+    let event = emitted::<SeriesUriSet>(&vm).pop().expect("series logged");
+    assert_eq!((event.startId, event.endId, event.base.as_str()), (id(15), id(30), "ar://later/"));
+
+    // Empty URIs hand IDs back to their series or the base URI
+    ok(contract.set_token_uris(vec![id(12)], vec![String::new()]));
+    ok(contract.set_series_uri(id(15), id(30), String::new()));
+    assert_eq!(contract.uri(id(12)), "ar://series/12.json");
+    assert_eq!(contract.uri(id(15)), "ar://series/15.json");
+    assert_eq!(contract.uri(id(25)), "ipfs://cradle/25.json");
+    let logged: Vec<_> = emitted::<URI>(&vm).into_iter().map(|e| (e.id, e.value)).collect();
+    let expected = [(12, "ar://twelve"), (40, "ar://forty"), (12, "ar://series/12.json")];
+    assert_eq!(logged, expected.map(|(n, uri)| (id(n), String::from(uri))));
+
+    // Cleared series still count toward the limit
+    for start in 2..MAX_SERIES_URIS {
+        ok(contract.set_series_uri(id(start), id(start), "ar://more/".into()));
+    }
+    let result = contract.set_series_uri(id(1), id(2), "ar://series/".into());
+    assert!(matches!(result, Err(My1155Error::TooManySeries(e)) if e.maxSeries == id(MAX_SERIES_URIS)));
+
+    ok(contract.freeze_metadata());
+    let result = contract.set_token_uris(vec![id(1)], vec!["ar://one".into()]);
+    assert!(matches!(result, Err(My1155Error::MetadataIsFrozen(_))));
+    let result = contract.set_series_uri(id(1), id(2), "ar://series/".into());
+    assert!(matches!(result, Err(My1155Error::MetadataIsFrozen(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn frozen_metadata_and_supplies_stay_frozen() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.freeze_metadata(), Err(My1155Error::UnauthorizedAccount(_))));
+    assert!(matches!(contract.freeze_supply(id(1)), Err(My1155Error::UnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.set_uri("ar://cradle/{id}.json".into()));
+    ok(contract.freeze_metadata());
+    assert!(contract.metadata_frozen());
+    assert_eq!(emitted::<MetadataFrozen>(&vm).pop().expect("freeze logged").uri, "ar://cradle/{id}.json");
+    let result = contract.set_uri(BASE_URI.into());
+    assert!(matches!(result, Err(My1155Error::MetadataIsFrozen(_))));
+    assert!(matches!(contract.freeze_metadata(), Err(My1155Error::MetadataIsFrozen(_))));
+
+    ok(contract.freeze_supply(id(1)));
+    assert!(contract.supply_frozen(id(1)) && !contract.supply_frozen(id(2)));
+    let event = emitted::<SupplyFrozen>(&vm).pop().expect("freeze logged");
+    assert_eq!((event.id, event.totalSupply), (id(1), id(100)));
+    assert!(matches!(contract.freeze_supply(id(1)), Err(My1155Error::SupplyIsFrozen(e)) if e.id == id(1)));
+    let result = contract.mint(BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::SupplyIsFrozen(e)) if e.id == id(1)));
+    let result = contract.mint_batch(BOB, vec![id(2), id(1)], vec![id(1), id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::SupplyIsFrozen(e)) if e.id == id(1)));
+    ok(contract.mint(BOB, id(2), id(1), vec![].into()));
+
+    // Holders still burn and transfer a frozen ID
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(1), id(10)));
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(10), vec![].into()));
+    assert_eq!(contract.total_supply(id(1)), id(90));
+}
+
+#[test]
+fn burn_rejects_insufficient_balance() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(BOB);
+    let result = contract.burn(id(1), id(1));
+    assert!(matches!(
+        result,
+        Err(My1155Error::InsufficientBalance(e)) if e.sender == BOB && e.balance.is_zero()
+    ));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+}
+
+#[test]
+fn burn_from_needs_holder_or_operator() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(BOB);
+    let result = contract.burn_from(ALICE, id(1), id(10));
+    assert!(matches!(
+        result,
+        Err(My1155Error::MissingApprovalForAll(e)) if e.operator == BOB && e.owner == ALICE
+    ));
+
+    vm.set_sender(ALICE);
+    ok(contract.burn_from(ALICE, id(1), id(10)));
+    ok(contract.set_approval_for_all(BOB, true));
+
+    vm.set_sender(BOB);
+    ok(contract.burn_from(ALICE, id(1), id(10)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(80));
+    assert_eq!(contract.total_supply(id(1)), id(80));
+}
+
+#[test]
+fn burn_batch_reduces_every_id() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.burn_batch(vec![id(1), id(2)], vec![id(100), id(20)]));
+    assert_eq!(contract.balance_of(ALICE, id(1)), U256::ZERO);
+    assert_eq!(contract.balance_of(ALICE, id(2)), id(30));
+    assert!(!contract.exists(id(1)));
+    assert_eq!(contract.total_supply(id(2)), id(30));
+
+    let result = contract.burn_batch(vec![id(2)], vec![]);
+    assert!(matches!(result, Err(My1155Error::InvalidArrayLength(_))));
+    let result = contract.burn_batch(vec![id(2)], vec![id(31)]);
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn supply_tracks_mints_transfers_and_burns() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.mint(BOB, id(1), id(25), vec![].into()));
+    assert_eq!(contract.total_supply(id(1)), id(125));
+
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(25), vec![].into()));
+    assert_eq!(contract.total_supply(id(1)), id(125));
+
+    vm.set_sender(BOB);
+    ok(contract.burn(id(1), id(50)));
+    assert_eq!(contract.total_supply(id(1)), id(75));
+    assert_eq!(contract.balance_of(ALICE, id(1)) + contract.balance_of(BOB, id(1)), id(75));
+    assert_eq!(contract.total_supply(id(2)), id(50));
+}
+
+#[test]
+fn pause_is_owner_only() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.pause(), Err(My1155Error::UnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+    assert!(contract.pausable.paused());
+
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.unpause(), Err(My1155Error::UnauthorizedAccount(_))));
+    assert!(contract.pausable.paused());
+}
+
+#[test]
+fn guardians_pause_until_their_expiry() {
+    let (vm, mut contract) = setup();
+    assert_eq!(contract.guardian_role(), keccak256("GUARDIAN_ROLE"));
+    ok(contract.access_control.grant_role(GUARDIAN_ROLE, ALICE).map_err(My1155Error::from));
+    vm.set_block_timestamp(1_000);
+
+    // No expiry is set yet
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.pause(), Err(My1155Error::GuardianExpired(e)) if e.expiry.is_zero()));
+
+    vm.set_sender(OWNER);
+    ok(contract.set_guardian_expiry(id(2_000)));
+    assert_eq!(contract.guardian_expiry(), id(2_000));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.set_guardian_expiry(id(3_000)), Err(My1155Error::UnauthorizedAccount(_))));
+    ok(contract.pause());
+    assert!(matches!(contract.unpause(), Err(My1155Error::UnauthorizedAccount(_))));
+    assert!(matches!(contract.mint(ALICE, id(1), id(1), vec![].into()), Err(My1155Error::EnforcedPause(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.unpause());
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.pause(), Err(My1155Error::GuardianExpired(e)) if e.expiry == id(2_000)));
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+}
+
+#[test]
+fn pause_and_unpause_need_the_opposite_state() {
+    let (_vm, mut contract) = setup();
+    assert!(matches!(contract.unpause(), Err(My1155Error::ExpectedPause(_))));
+    ok(contract.pause());
+    assert!(matches!(contract.pause(), Err(My1155Error::EnforcedPause(_))));
+    ok(contract.unpause());
+    assert!(!contract.pausable.paused());
+}
+
+const REGISTRY: Address = address!("0000000000000000000000000000000000009a05");
+
+#[test]
+fn the_pause_registry_pauses_the_token_too() {
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.set_pause_registry(REGISTRY);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    vm.set_sender(OWNER);
+    ok(contract.set_pause_registry(REGISTRY));
+    assert_eq!(contract.pause_registry(), REGISTRY);
+
+    let query = cradle_pausable::IPauseRegistry::isPausedCall { target: vm.contract_address() }.abi_encode();
+    vm.mock_static_call(REGISTRY, query.clone(), Ok(true.abi_encode()));
+    assert!(matches!(contract.mint(ALICE, id(1), id(1), vec![].into()), Err(My1155Error::EnforcedPause(_))));
+    assert!(!contract.pausable.paused());
+    vm.mock_static_call(REGISTRY, query.clone(), Ok(false.abi_encode()));
+    ok(contract.mint(ALICE, id(1), id(1), vec![].into()));
+
+    // A registry that doesn't answer pauses the token, and a zero one stops the checks
+    vm.mock_static_call(REGISTRY, query, Err(Vec::new()));
+    assert!(matches!(contract.burn(id(1), id(1)), Err(My1155Error::EnforcedPause(_))));
+    ok(contract.set_pause_registry(Address::ZERO));
+    ok(contract.mint(ALICE, id(1), id(1), vec![].into()));
+    assert_eq!(emitted::<PauseRegistrySet>(&vm).len(), 2);
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn pause_stops_mints_transfers_and_burns() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+    ok(contract.pause());
+
+    assert!(matches!(contract.mint(ALICE, id(1), id(1), vec![].into()), Err(My1155Error::EnforcedPause(_))));
+    let result = contract.mint_batch(ALICE, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+    assert!(matches!(contract.burn(id(1), id(1)), Err(My1155Error::EnforcedPause(_))));
+    assert!(matches!(contract.burn_from(ALICE, id(1), id(1)), Err(My1155Error::EnforcedPause(_))));
+    let result = contract.burn_batch(vec![id(1)], vec![id(1)]);
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+
+    vm.set_sender(BRIDGE);
+    let result = contract.crosschain_mint(ALICE, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+    let result = contract.crosschain_burn(ALICE, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::EnforcedPause(_))));
+
+    // Approvals and reads aren't paused
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+
+    vm.set_sender(OWNER);
+    ok(contract.unpause());
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(1));
+}
+
+#[test]
+fn ownership_transfer_moves_owner_rights() {
+    let (vm, mut contract) = setup();
+    ok(contract.ownable.transfer_ownership(ALICE).map_err(My1155Error::from));
+    assert_eq!(contract.ownable.owner(), ALICE);
+
+    let result = contract.mint(BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(e)) if e.account == OWNER));
+
+    vm.set_sender(ALICE);
+    ok(contract.mint(BOB, id(1), id(1), vec![].into()));
+    ok(contract.pause());
+}
+
+#[test]
+fn ownership_transfer_is_owner_only_and_rejects_zero() {
+    let (vm, mut contract) = setup();
+    let result = contract.ownable.transfer_ownership(Address::ZERO).map_err(My1155Error::from);
+    assert!(matches!(result, Err(My1155Error::InvalidOwner(_))));
+
+    vm.set_sender(ALICE);
+    let result = contract.ownable.transfer_ownership(ALICE).map_err(My1155Error::from);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(e)) if e.account == ALICE));
+    assert_eq!(contract.ownable.owner(), OWNER);
+}
+
+#[test]
+fn renouncing_ownership_leaves_no_owner() {
+    let (_vm, mut contract) = setup();
+    ok(contract.ownable.renounce_ownership().map_err(My1155Error::from));
+    assert!(contract.ownable.owner().is_zero());
+
+    let result = contract.mint(ALICE, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    assert!(matches!(contract.set_uri("x".into()), Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[test]
+fn bridge_role_constant_matches_its_name() {
+    let (_vm, contract) = setup();
+    assert_eq!(contract.bridge_role(), keccak256("BRIDGE_ROLE"));
+}
+
+#[test]
+fn crosschain_calls_need_the_bridge_role() {
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.crosschain_mint(ALICE, id(1), id(1));
+    assert!(matches!(
+        result,
+        Err(My1155Error::MissingRole(e)) if e.account == OWNER && e.neededRole == BRIDGE_ROLE
+    ));
+
+    vm.set_sender(BRIDGE);
+    assert!(matches!(contract.crosschain_burn(ALICE, id(1), id(1)), Err(My1155Error::MissingRole(_))));
+    assert_eq!(contract.total_supply(id(1)), id(100));
+}
+
+#[test]
+fn bridge_mints_and_burns_without_approval() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+
+    vm.set_sender(BRIDGE);
+    ok(contract.crosschain_mint(BOB, id(1), id(20)));
+    ok(contract.crosschain_burn(ALICE, id(1), id(30)));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(20));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(70));
+    assert_eq!(contract.total_supply(id(1)), id(90));
+
+    let minted = emitted::<CrosschainMint>(&vm).pop().expect("mint logged");
+    assert_eq!((minted.to, minted.id, minted.amount, minted.sender), (BOB, id(1), id(20), BRIDGE));
+    let burned = emitted::<CrosschainBurn>(&vm).pop().expect("burn logged");
+    assert_eq!((burned.from, burned.amount), (ALICE, id(30)));
+
+    let result = contract.crosschain_burn(BOB, id(1), id(21));
+    assert!(matches!(result, Err(My1155Error::InsufficientBalance(_))));
+    let result = contract.crosschain_mint(Address::ZERO, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::InvalidReceiver(_))));
+    let result = contract.crosschain_burn(Address::ZERO, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::InvalidSender(e)) if e.sender.is_zero()));
+}
+
+#[test]
+fn payment_processors_mint_each_order_once() {
+    let (vm, mut contract) = setup();
+    let order = keccak256("order-1042");
+    assert_eq!(contract.payment_processor_role(), keccak256("PAYMENT_PROCESSOR_ROLE"));
+    let result = contract.mint_with_receipt(ALICE, id(1), id(2), order);
+    assert!(matches!(
+        result,
+        Err(My1155Error::MissingRole(e)) if e.account == OWNER && e.neededRole == PAYMENT_PROCESSOR_ROLE
+    ));
+
+    ok(contract.access_control.grant_role(PAYMENT_PROCESSOR_ROLE, PARTNER).map_err(My1155Error::from));
+    vm.set_sender(PARTNER);
+    ok(contract.mint_with_receipt(ALICE, id(1), id(2), order));
+    assert!(contract.order_fulfilled(order) && !contract.order_fulfilled(keccak256("order-1043")));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(2));
+    let receipt = emitted::<MintedWithReceipt>(&vm).pop().expect("receipt logged");
+    assert_eq!((receipt.orderId, receipt.to, receipt.amount, receipt.processor), (order, ALICE, id(2), PARTNER));
+
+    let result = contract.mint_with_receipt(BOB, id(1), id(2), order);
+    assert!(matches!(result, Err(My1155Error::OrderAlreadyFulfilled(e)) if e.orderId == order));
+    assert_eq!(contract.balance_of(BOB, id(1)), U256::ZERO);
+}
+
+#[test]
+fn revoked_bridge_loses_access() {
+    let (vm, mut contract) = setup();
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+    ok(contract.access_control.revoke_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+
+    vm.set_sender(BRIDGE);
+    assert!(matches!(contract.crosschain_mint(ALICE, id(1), id(1)), Err(My1155Error::MissingRole(_))));
+}
+
+#[test]
+fn only_the_admin_grants_roles() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.access_control.grant_role(BRIDGE_ROLE, ALICE).map_err(My1155Error::from);
+    assert!(matches!(
+        result,
+        Err(My1155Error::MissingRole(e)) if e.account == ALICE && e.neededRole == DEFAULT_ADMIN_ROLE
+    ));
+    assert!(!contract.access_control.has_role(BRIDGE_ROLE, ALICE));
+}
+
+#[test]
+fn renouncing_a_role_needs_confirmation() {
+    let (vm, mut contract) = setup();
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+
+    vm.set_sender(BRIDGE);
+    let result = contract.access_control.renounce_role(BRIDGE_ROLE, ALICE).map_err(My1155Error::from);
+    assert!(matches!(result, Err(My1155Error::BadConfirmation(_))));
+    assert!(contract.access_control.has_role(BRIDGE_ROLE, BRIDGE));
+
+    ok(contract.access_control.renounce_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+    assert!(!contract.access_control.has_role(BRIDGE_ROLE, BRIDGE));
+}
+
+#[test]
+fn role_members_are_enumerable() {
+    let (_vm, mut contract) = setup();
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, ALICE).map_err(My1155Error::from));
+    assert_eq!(contract.access_control.get_role_member_count(BRIDGE_ROLE), U256::from(2));
+
+    ok(contract.access_control.revoke_role(BRIDGE_ROLE, BRIDGE).map_err(My1155Error::from));
+    assert_eq!(contract.access_control.get_role_member_count(BRIDGE_ROLE), U256::from(1));
+    assert_eq!(ok(contract.access_control.get_role_member(BRIDGE_ROLE, U256::ZERO).map_err(My1155Error::from)), ALICE);
+
+    let result = contract.access_control.get_role_member(BRIDGE_ROLE, U256::from(1)).map_err(My1155Error::from);
+    assert!(matches!(result, Err(My1155Error::MemberOutOfBounds(e)) if e.index == U256::from(1)));
+}
+
+#[test]
+fn errors_encode_as_solidity_custom_errors() {
+    let error = My1155Error::InsufficientBalance(ERC1155InsufficientBalance {
+        sender: ALICE,
+        balance: id(1),
+        needed: id(2),
+        id: id(3),
+    });
+    let encoded = Vec::<u8>::from(error);
+    assert_eq!(encoded[..4], keccak256("ERC1155InsufficientBalance(address,uint256,uint256,uint256)")[..4]);
+    assert_eq!(encoded.len(), 4 + 4 * 32);
+
+    let encoded = Vec::<u8>::from(My1155Error::EnforcedPause(EnforcedPause {}));
+    assert_eq!(encoded, keccak256("EnforcedPause()")[..4]);
+}
+
+#[test]
+fn build_metadata_matches_the_sources() {
+    let (_vm, contract) = setup();
+    let (hash, version) = contract.build_metadata();
+    let sources = cradle_build_info::source_hash(std::path::Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+    assert_eq!(hash, B256::new(sources));
+    assert_eq!(version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn supports_the_standards_it_implements() {
+    let (_vm, contract) = setup();
+    let supports = |id: u32| contract.supports_interface(FixedBytes(id.to_be_bytes()));
+    // ERC-165, ERC-1155, its metadata URI, ERC-173, ERC-5313, IAccessControl and
+    // IAccessControlEnumerable
+    for id in [0x01ffc9a7, 0xd9b67a26, 0x0e89341c, 0x7f5828d0, 0x8da5cb5b, 0x7965db0b, 0x5a05180f] {
+        assert!(supports(id), "{id:#010x}");
+    }
+    assert!(!supports(0xffffffff));
+    // ERC-2981 and ERC-5633, with their extensions
+    assert_eq!(supports(0x2a55205a), cfg!(feature = "royalties"));
+    assert_eq!(supports(0x911ec470), cfg!(feature = "soulbound"));
+    let others = [
+        extensions::enumerable::INTERFACE,
+        extensions::supply_cap::INTERFACE,
+        extensions::meta_tx::INTERFACE,
+        extensions::paid_mint::INTERFACE,
+        extensions::id_ranges::INTERFACE,
+        extensions::split_ids::INTERFACE,
+        extensions::rate_limits::INTERFACE,
+        extensions::admin_delay::INTERFACE,
+        extensions::tiers::INTERFACE,
+        extensions::validity::INTERFACE,
+        extensions::packs::INTERFACE,
+        extensions::market::INTERFACE,
+        extensions::composable::INTERFACE,
+        extensions::attested_mint::INTERFACE,
+        extensions::checkpoints::INTERFACE,
+        extensions::votes::INTERFACE,
+        extensions::approval_index::INTERFACE,
+        extensions::transfer_hooks::INTERFACE,
+        extensions::modules::INTERFACE,
+        extensions::holder_snapshots::INTERFACE,
+        extensions::testnet::INTERFACE,
+        extensions::transfer_security::INTERFACE,
+        extensions::reveal::INTERFACE,
+        extensions::open_editions::INTERFACE,
+    ];
+    for interface in others {
+        assert_eq!(supports(interface.id()), extensions::interfaces().any(|enabled| enabled.id() == interface.id()));
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "export-abi", ignore = "export-abi builds lack the hostios routing needs")]
+fn supported_interfaces_are_routed() {
+    use stylus_sdk::abi::Router;
+
+    let (_vm, mut contract) = setup();
+    assert!(<My1155 as Router<My1155>>::route(&mut contract, u32::MAX, &[]).is_none());
+    for interface in INTERFACES.into_iter().chain(extensions::interfaces()) {
+        for &selector in interface.selectors() {
+            // Empty calldata doesn't decode, but a selector the router doesn't know gives `None`
+            let routed = <My1155 as Router<My1155>>::route(&mut contract, u32::from_be_bytes(selector), &[]);
+            assert!(routed.is_some(), "0x{} of {:#010x} isn't exported", hex::encode(selector), interface.id());
+        }
+    }
+}
+
+/// Functions of the OpenZeppelin contracts the token stands in for, as their Solidity ABIs
+/// declare them: IERC1155 and its metadata URI, ERC1155Supply's per-token supply, the `mint` and
+/// `pause` of ERC1155PresetMinterPauser, Ownable, Pausable and AccessControlEnumerable.
+const OPENZEPPELIN_FUNCTIONS: &[&str] = &[
+    "balanceOf(address,uint256)",
+    "balanceOfBatch(address[],uint256[])",
+    "setApprovalForAll(address,bool)",
+    "isApprovedForAll(address,address)",
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+    "uri(uint256)",
+    "supportsInterface(bytes4)",
+    "totalSupply(uint256)",
+    "exists(uint256)",
+    "mint(address,uint256,uint256,bytes)",
+    "mintBatch(address,uint256[],uint256[],bytes)",
+    "pause()",
+    "unpause()",
+    "paused()",
+    "owner()",
+    "transferOwnership(address)",
+    "renounceOwnership()",
+    "DEFAULT_ADMIN_ROLE()",
+    "hasRole(bytes32,address)",
+    "getRoleAdmin(bytes32)",
+    "grantRole(bytes32,address)",
+    "revokeRole(bytes32,address)",
+    "renounceRole(bytes32,address)",
+    "getRoleMember(bytes32,uint256)",
+    "getRoleMemberCount(bytes32)",
+    #[cfg(feature = "royalties")]
+    "royaltyInfo(uint256,uint256)",
+];
+
+#[test]
+#[cfg_attr(feature = "export-abi", ignore = "export-abi builds lack the hostios routing needs")]
+fn exports_openzeppelin_selectors() {
+    use stylus_sdk::abi::Router;
+
+    let (_vm, mut contract) = setup();
+    for signature in OPENZEPPELIN_FUNCTIONS {
+        let selector = u32::from_be_bytes(keccak256(signature)[..4].try_into().unwrap());
+        let routed = <My1155 as Router<My1155>>::route(&mut contract, selector, &[]);
+        assert!(
+            routed.is_some(),
+            "`{signature}` ({selector:#010x}) isn't exported, so Solidity callers can't reach it"
+        );
+    }
+}
+
+#[cfg(feature = "packed-balances")]
+#[test]
+fn packed_lanes_are_independent() {
+    let (vm, mut contract) = setup();
+    ok(contract.mint_batch(ALICE, vec![id(4), id(5), id(6), id(7)], vec![id(1), id(2), id(3), id(4)], vec![].into()));
+    ok(contract.mint(ALICE, id(5), U256::from(u64::MAX - 2), vec![].into()));
+
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(6), id(3)));
+    let balances = ok(contract.balance_of_batch(vec![ALICE; 4], vec![id(4), id(5), id(6), id(7)]));
+    assert_eq!(balances, vec![id(1), U256::from(u64::MAX), U256::ZERO, id(4)]);
+}
+
+#[cfg(feature = "packed-balances")]
+#[test]
+fn packed_balances_overflow_past_64_bits() {
+    let (_vm, mut contract) = setup();
+    ok(contract.mint(ALICE, id(1), U256::from(u64::MAX), vec![].into()));
+    let result = contract.mint(ALICE, id(1), id(1), vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::BalanceOverflow(e)) if e.id == id(1) && e.balance == U256::from(u64::MAX) + id(1)
+    ));
+    assert_eq!(contract.balance_of(ALICE, id(1)), U256::from(u64::MAX));
+}
+
+#[cfg(feature = "royalties")]
+#[test]
+fn royalties_fall_back_to_the_default() {
+    use extensions::royalties::IRoyalties;
+    use stylus_sdk::alloy_primitives::aliases::U96;
+
+    let (_vm, mut contract) = setup();
+    assert_eq!(contract.royalty_info(id(1), id(10_000)), (Address::ZERO, U256::ZERO));
+
+    ok(contract.set_default_royalty(BOB, U96::from(250)));
+    ok(contract.set_token_royalty(id(2), ALICE, U96::from(1_000)));
+    assert_eq!(contract.royalty_info(id(1), id(10_000)), (BOB, id(250)));
+    assert_eq!(contract.royalty_info(id(2), id(10_000)), (ALICE, id(1_000)));
+    // Exactly `U256::MAX * 250 / 10_000`, without overflowing
+    assert_eq!(contract.royalty_info(id(1), U256::MAX).1, U256::MAX / id(10_000) * id(250) + id(248));
+
+    ok(contract.reset_token_royalty(id(2)));
+    assert_eq!(contract.royalty_info(id(2), id(10_000)), (BOB, id(250)));
+    ok(contract.delete_default_royalty());
+    assert_eq!(contract.royalty_info(id(2), id(10_000)), (Address::ZERO, U256::ZERO));
+}
+
+#[cfg(feature = "royalties")]
+#[test]
+fn royalties_are_owner_only_and_at_most_the_price() {
+    use extensions::royalties::IRoyalties;
+    use stylus_sdk::alloy_primitives::aliases::U96;
+
+    let (vm, mut contract) = setup();
+    let result = contract.set_default_royalty(BOB, U96::from(10_001));
+    assert!(matches!(result, Err(My1155Error::InvalidDefaultRoyalty(e)) if e.numerator == id(10_001)));
+    let result = contract.set_token_royalty(id(1), Address::ZERO, U96::from(1));
+    assert!(matches!(result, Err(My1155Error::InvalidTokenRoyaltyReceiver(e)) if e.tokenId == id(1)));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_default_royalty(ALICE, U96::from(1));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "royalties")]
+#[test]
+fn the_router_reaches_extension_methods() {
+    use extensions::royalties::{IRoyalties, IERC2981};
+    use stylus_sdk::{abi::Router, alloy_sol_types::SolCall};
+
+    let (_vm, mut contract) = setup();
+    let call = IERC2981::royaltyInfoCall { tokenId: id(1), salePrice: id(100) };
+    let output = <My1155 as Router<My1155, dyn IRoyalties>>::route(
+        &mut contract,
+        u32::from_be_bytes(IERC2981::royaltyInfoCall::SELECTOR),
+        &call.abi_encode()[4..],
+    );
+    let output = output.expect("royaltyInfo is routed").expect("royaltyInfo succeeds");
+    let decoded = IERC2981::royaltyInfoCall::abi_decode_returns(&output, true).unwrap();
+    assert_eq!((decoded.receiver, decoded.royaltyAmount), (Address::ZERO, U256::ZERO));
+}
+
+#[cfg(feature = "enumerable")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn enumerable_lists_ids_in_circulation_and_held() {
+    use extensions::enumerable::IEnumerable;
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.mint(BOB, id(3), id(1), vec![].into()));
+    assert_eq!(contract.token_count(), id(3));
+    assert_eq!(contract.token_count_of(ALICE), id(2));
+    assert_eq!(ok(contract.token_of_owner_by_index(BOB, U256::ZERO)), id(3));
+
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(100), vec![].into()));
+    assert_eq!(contract.token_count_of(ALICE), id(1));
+    assert_eq!(ok(contract.token_of_owner_by_index(ALICE, U256::ZERO)), id(2));
+    assert_eq!(contract.token_count_of(BOB), id(2));
+
+    ok(contract.burn_batch(vec![id(2)], vec![id(50)]));
+    assert_eq!(contract.token_count(), id(2));
+    assert_eq!(contract.token_count_of(ALICE), U256::ZERO);
+    let ids = [ok(contract.token_by_index(U256::ZERO)), ok(contract.token_by_index(id(1)))];
+    assert!(ids.contains(&id(1)) && ids.contains(&id(3)));
+
+    let result = contract.token_of_owner_by_index(ALICE, U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::OutOfBoundsIndex(e)) if e.owner == ALICE && e.index.is_zero()));
+}
+
+#[cfg(feature = "supply-cap")]
+#[test]
+fn supply_cap_stops_mints_past_it() {
+    use extensions::supply_cap::{ISupplyCap, MaxSupplySet};
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.set_max_supply(id(1), id(150)));
+    assert_eq!(contract.max_supply(id(1)), id(150));
+    assert_eq!(emitted::<MaxSupplySet>(&vm).len(), 1);
+
+    ok(contract.mint(ALICE, id(1), id(50), vec![].into()));
+    let result = contract.mint_batch(ALICE, vec![id(2), id(1)], vec![id(1), id(1)], vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::ExceededMaxSupply(e)) if e.id == id(1) && e.supply == id(151) && e.maxSupply == id(150)
+    ));
+    // Uncapped IDs mint freely
+    ok(contract.mint(ALICE, id(2), id(1_000), vec![].into()));
+}
+
+#[cfg(feature = "supply-cap")]
+#[test]
+fn supply_cap_can_only_be_lowered_to_the_supply() {
+    use extensions::supply_cap::ISupplyCap;
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.set_max_supply(id(1), id(200)));
+    for max_supply in [U256::ZERO, id(99), id(201)] {
+        let result = contract.set_max_supply(id(1), max_supply);
+        assert!(matches!(result, Err(My1155Error::InvalidMaxSupply(e)) if e.maxSupply == max_supply));
+    }
+    ok(contract.set_max_supply(id(1), id(100)));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_max_supply(id(2), id(50));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "soulbound")]
+#[test]
+fn soulbound_tokens_mint_and_burn_but_dont_transfer() {
+    use extensions::soulbound::ISoulbound;
+
+    let (vm, mut contract) = setup_with_balances();
+    assert!(contract.is_soulbound(id(1)));
+
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::NonTransferable(_))));
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::NonTransferable(_))));
+    ok(contract.burn(id(1), id(100)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), U256::ZERO);
+}
+
+#[cfg(feature = "meta-tx")]
+#[test]
+fn permit_approves_for_the_signer() {
+    use extensions::meta_tx::{IMetaTx, PERMIT_TYPEHASH};
+
+    assert_eq!(
+        PERMIT_TYPEHASH,
+        keccak256("Permit(address owner,address operator,bool approved,uint256 nonce,uint256 deadline)")
+    );
+
+    let (vm, mut contract) = setup_with_balances();
+    let deadline = U256::MAX;
+    let struct_hash = cradle_crypto::hash_struct(&vm, PERMIT_TYPEHASH, (ALICE, BOB, true, U256::ZERO, deadline));
+    let digest = cradle_crypto::hash_typed_data(&vm, contract.domain_separator(), struct_hash);
+    let mut signature = vec![0x11; 64];
+    signature.push(27);
+    let mut ecrecover_input = digest.to_vec();
+    ecrecover_input.extend([0; 31]);
+    ecrecover_input.extend([27]);
+    ecrecover_input.extend(&signature[..64]);
+    vm.mock_static_call(cradle_crypto::ECRECOVER, ecrecover_input, Ok(ALICE.into_word().to_vec()));
+
+    // Anyone can submit the signature
+    vm.set_sender(BOB);
+    ok(contract.permit(ALICE, BOB, true, deadline, signature.clone().into()));
+    assert!(contract.is_approved_for_all(ALICE, BOB));
+    assert_eq!(contract.nonces(ALICE), id(1));
+
+    // The nonce moved on, so the signature can't be replayed
+    let result = contract.permit(ALICE, BOB, true, deadline, signature.into());
+    assert!(matches!(result, Err(My1155Error::InvalidSignature(e)) if e.owner == ALICE));
+}
+
+#[cfg(feature = "meta-tx")]
+#[test]
+fn holders_revoke_unsubmitted_permits() {
+    use extensions::meta_tx::IMetaTx;
+
+    let (vm, mut contract) = setup();
+    let struct_hash = cradle_crypto::hash_struct(
+        &vm,
+        extensions::meta_tx::PERMIT_TYPEHASH,
+        (ALICE, BOB, true, U256::ZERO, U256::MAX),
+    );
+    let digest = cradle_crypto::hash_typed_data(&vm, contract.domain_separator(), struct_hash);
+    let mut signature = vec![0x11; 64];
+    signature.push(27);
+    let mut ecrecover_input = digest.to_vec();
+    ecrecover_input.extend([0; 31]);
+    ecrecover_input.extend([27]);
+    ecrecover_input.extend(&signature[..64]);
+    vm.mock_static_call(cradle_crypto::ECRECOVER, ecrecover_input, Ok(ALICE.into_word().to_vec()));
+
+    vm.set_sender(ALICE);
+    ok(contract.invalidate_nonces_up_to(id(3)));
+    assert_eq!(contract.nonces(ALICE), id(3));
+    let result = contract.invalidate_nonces_up_to(id(3));
+    assert!(matches!(result, Err(My1155Error::InvalidNonceInvalidation(e)) if e.currentNonce == id(3)));
+
+    // The permit was signed over nonce 0, which is now behind
+    let result = contract.permit(ALICE, BOB, true, U256::MAX, signature.into());
+    assert!(matches!(result, Err(My1155Error::InvalidSignature(_))));
+    assert!(!contract.is_approved_for_all(ALICE, BOB));
+}
+
+#[cfg(feature = "meta-tx")]
+#[test]
+fn permit_rejects_expired_and_foreign_signatures() {
+    use extensions::meta_tx::IMetaTx;
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    let result = contract.permit(ALICE, BOB, true, id(999), vec![0; 65].into());
+    assert!(matches!(result, Err(My1155Error::ExpiredSignature(e)) if e.deadline == id(999)));
+
+    let result = contract.permit(ALICE, BOB, true, id(1_000), vec![0; 65].into());
+    assert!(matches!(result, Err(My1155Error::InvalidSignature(_))));
+    assert!(!contract.is_approved_for_all(ALICE, BOB));
+}
+
+#[cfg(feature = "paid-mint")]
+#[test]
+fn paid_mints_credit_the_owner_or_the_creator() {
+    use extensions::paid_mint::{IPaidMint, MintPurchased};
+
+    let (vm, mut contract) = setup();
+    ok(contract.set_mint_price(id(1), id(10), Address::ZERO));
+    ok(contract.set_mint_price(id(2), id(25), BOB));
+    assert_eq!(contract.mint_price(id(2)), (id(25), BOB));
+
+    vm.set_sender(ALICE);
+    vm.set_value(id(30));
+    ok(contract.mint_paid(ALICE, id(1), id(3)));
+    vm.set_value(id(50));
+    ok(contract.mint_paid(BOB, id(2), id(2)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(3));
+    assert_eq!(contract.balance_of(BOB, id(2)), id(2));
+    assert_eq!(emitted::<MintPurchased>(&vm).len(), 2);
+
+    assert_eq!((contract.revenue_of(id(1)), contract.revenue_of(id(2))), (id(30), id(50)));
+    assert_eq!((contract.proceeds_of(OWNER), contract.proceeds_of(BOB)), (id(30), id(50)));
+}
+
+#[cfg(feature = "paid-mint")]
+#[test]
+fn paid_mints_take_the_exact_price_of_ids_on_sale() {
+    use extensions::paid_mint::IPaidMint;
+
+    let (vm, mut contract) = setup();
+    let result = contract.mint_paid(ALICE, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::MintNotForSale(e)) if e.id == id(1)));
+
+    ok(contract.set_mint_price(id(1), id(10), Address::ZERO));
+    for paid in [id(19), id(21)] {
+        vm.set_value(paid);
+        let result = contract.mint_paid(ALICE, id(1), id(2));
+        assert!(matches!(result, Err(My1155Error::IncorrectPayment(e)) if e.expected == id(20) && e.paid == paid));
+    }
+    vm.set_value(id(20));
+    ok(contract.mint_paid(ALICE, id(1), id(2)));
+
+    ok(contract.set_mint_price(id(1), U256::ZERO, Address::ZERO));
+    assert!(matches!(contract.mint_paid(ALICE, id(1), id(2)), Err(My1155Error::MintNotForSale(_))));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.set_mint_price(id(1), id(1), ALICE), Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "paid-mint")]
+#[test]
+fn quotes_price_public_mints_and_say_why_they_would_fail() {
+    use extensions::paid_mint::{ERC1155MintNotForSale, IPaidMint};
+
+    let (_vm, mut contract) = setup();
+    let not_for_sale = FixedBytes(ERC1155MintNotForSale::SELECTOR);
+    assert_eq!(contract.quote_public_mint(id(1), id(2)), (U256::ZERO, not_for_sale));
+    ok(contract.set_mint_price(id(1), id(10), Address::ZERO));
+    assert_eq!(contract.quote_public_mint(id(1), id(2)), (id(20), FixedBytes::ZERO));
+
+    ok(contract.set_retire_burned_out(true));
+    ok(contract.mint(OWNER, id(1), id(1), vec![].into()));
+    ok(contract.burn(id(1), id(1)));
+    assert_eq!(contract.quote_public_mint(id(1), id(2)), (id(20), FixedBytes(ERC1155IdRetired::SELECTOR)));
+}
+
+#[cfg(feature = "paid-mint")]
+#[test]
+fn payees_withdraw_their_proceeds() {
+    use extensions::paid_mint::{IPaidMint, ProceedsWithdrawn};
+
+    let (vm, mut contract) = setup();
+    ok(contract.set_mint_price(id(1), id(10), BOB));
+    vm.set_value(id(40));
+    ok(contract.mint_paid(ALICE, id(1), id(4)));
+    vm.set_balance(vm.contract_address(), id(40));
+
+    // Withdrawing works while paused, and does nothing for accounts owed nothing
+    ok(contract.pause());
+    ok(contract.withdraw_proceeds());
+    assert!(emitted::<ProceedsWithdrawn>(&vm).is_empty());
+
+    vm.set_sender(BOB);
+    ok(contract.withdraw_proceeds());
+    assert_eq!(vm.balance(BOB), id(40));
+    assert_eq!(contract.proceeds_of(BOB), U256::ZERO);
+    assert_eq!(contract.revenue_of(id(1)), id(40));
+    let withdrawn = emitted::<ProceedsWithdrawn>(&vm);
+    assert_eq!((withdrawn[0].account, withdrawn[0].amount), (BOB, id(40)));
+}
+
+#[cfg(feature = "paid-mint")]
+#[test]
+fn refundable_mints_escrow_payments_until_their_window_passes() {
+    use extensions::paid_mint::{EscrowReleased, IPaidMint, MintEscrowed, MintRefunded};
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    ok(contract.set_mint_price(id(1), id(10), BOB));
+    ok(contract.set_refund_window(id(1), id(100)));
+    vm.set_sender(ALICE);
+    vm.set_value(id(40));
+    ok(contract.mint_paid(ALICE, id(1), id(4)));
+    assert_eq!(contract.purchase(U256::ZERO), (ALICE, ALICE, BOB, id(1), id(10), id(4), id(1_100)));
+    assert_eq!(emitted::<MintEscrowed>(&vm)[0].refundableUntil, id(1_100));
+    assert_eq!(contract.proceeds_of(BOB), U256::ZERO);
+
+    // Refunds work while paused, only for the recipient and never for more than is escrowed
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+    vm.set_sender(BOB);
+    assert!(matches!(contract.refund_mint(U256::ZERO, id(1)), Err(My1155Error::InvalidRefund(_))));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.refund_mint(U256::ZERO, id(5)), Err(My1155Error::InvalidRefund(_))));
+    ok(contract.refund_mint(U256::ZERO, id(3)));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(1));
+    assert_eq!((contract.proceeds_of(ALICE), contract.revenue_of(id(1))), (id(30), id(10)));
+    assert_eq!(emitted::<MintRefunded>(&vm)[0].refund, id(30));
+
+    let result = contract.release_escrow(vec![U256::ZERO]);
+    assert!(matches!(result, Err(My1155Error::RefundWindowOpen(e)) if e.refundableUntil == id(1_100)));
+    vm.set_block_timestamp(1_100);
+    assert!(matches!(contract.refund_mint(U256::ZERO, id(1)), Err(My1155Error::InvalidRefund(_))));
+    ok(contract.release_escrow(vec![U256::ZERO]));
+    ok(contract.release_escrow(vec![U256::ZERO]));
+    assert_eq!(contract.proceeds_of(BOB), id(10));
+    assert_eq!(emitted::<EscrowReleased>(&vm).len(), 1);
+}
+
+#[cfg(feature = "id-ranges")]
+#[test]
+fn id_ranges_follow_each_other_and_map_back_to_their_series() {
+    use extensions::id_ranges::{IIdRanges, IdRangeReserved};
+
+    let (vm, mut contract) = setup();
+    assert_eq!(ok(contract.reserve_id_range(ALICE, id(10))), (id(1), id(10)));
+    assert_eq!(ok(contract.reserve_id_range(BOB, id(1))), (id(11), id(11)));
+    assert_eq!(ok(contract.reserve_id_range(ALICE, id(5))), (id(12), id(16)));
+    assert_eq!(contract.series_count(), id(3));
+    assert_eq!(contract.series(id(3)), (ALICE, id(12), id(16), U256::ZERO));
+    assert_eq!(emitted::<IdRangeReserved>(&vm).len(), 3);
+
+    for (token, series) in [(0, 0), (1, 1), (10, 1), (11, 2), (12, 3), (16, 3), (17, 0)] {
+        assert_eq!(contract.series_of(id(token)), id(series), "ID {token}");
+    }
+
+    for (creator, count) in [(Address::ZERO, id(1)), (ALICE, U256::ZERO), (ALICE, U256::MAX)] {
+        let result = contract.reserve_id_range(creator, count);
+        assert!(matches!(result, Err(My1155Error::InvalidIdRange(e)) if e.creator == creator && e.count == count));
+    }
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.reserve_id_range(ALICE, id(1)), Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "id-ranges")]
+#[test]
+fn creators_mint_the_ids_of_their_series_in_order() {
+    use extensions::id_ranges::IIdRanges;
+
+    let (vm, mut contract) = setup();
+    ok(contract.reserve_id_range(ALICE, id(2)));
+    ok(contract.reserve_id_range(BOB, id(3)));
+
+    let result = contract.mint_new(ALICE, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedSeriesMinter(e)) if e.series == id(1) && e.account == OWNER));
+
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.mint_new(BOB, id(1), id(7))), id(1));
+    assert_eq!(ok(contract.mint_new(ALICE, id(1), id(1))), id(2));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(7));
+    let result = contract.mint_new(ALICE, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::SeriesExhausted(e)) if e.series == id(1)));
+    assert!(matches!(contract.mint_new(ALICE, id(2), id(1)), Err(My1155Error::UnauthorizedSeriesMinter(_))));
+
+    vm.set_sender(BOB);
+    assert_eq!(ok(contract.mint_new(BOB, id(2), id(1))), id(3));
+    assert_eq!(contract.series(id(2)).3, id(1));
+}
+
+#[test]
+fn split_ids_encode_and_decode() {
+    use extensions::split_ids::*;
+
+    let base = TYPE_NF_BIT | (id(7) << 128);
+    let item = non_fungible_id(base, id(42));
+    assert_eq!((base_type(item), non_fungible_index(item)), (base, id(42)));
+    assert!(is_non_fungible(base) && !is_non_fungible_item(base));
+    assert!(is_non_fungible_item(item) && !is_fungible(item));
+    assert!(is_fungible(id(7) << 128) && !is_non_fungible_item(id(7) << 128));
+    assert_eq!(TYPE_MASK | NF_INDEX_MASK, U256::MAX);
+}
+
+#[cfg(feature = "split-ids")]
+#[test]
+fn non_fungible_items_mint_in_order_with_a_supply_of_one() {
+    use extensions::split_ids::{non_fungible_id, ISplitIds, TYPE_NF_BIT};
+
+    let (vm, mut contract) = setup();
+    let base = TYPE_NF_BIT | (id(1) << 128);
+    let ids = ok(contract.mint_non_fungible(ALICE, base, id(2)));
+    assert_eq!(ids, [non_fungible_id(base, id(1)), non_fungible_id(base, id(2))]);
+    assert_eq!(ok(contract.mint_non_fungible(BOB, base, id(1))), [non_fungible_id(base, id(3))]);
+    assert_eq!(contract.non_fungible_count(base), id(3));
+    assert_eq!(contract.balance_of(BOB, ids[0] + id(2)), id(1));
+    assert!(contract.is_non_fungible(ids[0]) && contract.base_type(ids[1]) == base);
+
+    // Fungible IDs and items aren't types items can be minted in
+    for invalid in [id(1) << 128, ids[0]] {
+        let result = contract.mint_non_fungible(ALICE, invalid, id(1));
+        assert!(matches!(result, Err(My1155Error::InvalidBaseType(e)) if e.baseType == invalid));
+    }
+
+    // Plain mints can't give an item a second copy
+    let result = contract.mint(BOB, ids[0], id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::NonFungibleSupply(e)) if e.id == ids[0] && e.supply == id(2)));
+    let fresh = non_fungible_id(base, id(9));
+    ok(contract.mint_batch(BOB, vec![fresh, id(5)], vec![id(1), id(1_000)], vec![].into()));
+
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.mint_non_fungible(ALICE, base, id(1)), Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "rate-limits")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn rate_limits_cap_volume_per_block_and_hour() {
+    use extensions::rate_limits::{IRateLimits, RateLimitReached, HOUR};
+
+    let (vm, mut contract) = setup();
+    vm.set_block_number(10);
+    vm.set_block_timestamp(HOUR * 100);
+    ok(contract.set_rate_limit(id(1), id(100), id(150), U256::ZERO, id(60)));
+    assert_eq!(contract.rate_limit(id(1)), (id(100), id(150), U256::ZERO, id(60)));
+
+    // Mints count against the recipient's hourly cap
+    ok(contract.mint(ALICE, id(1), id(60), vec![].into()));
+    let reached = emitted::<RateLimitReached>(&vm);
+    assert_eq!((reached.len(), reached[0].account, reached[0].hourly), (1, ALICE, true));
+    let result = contract.mint(ALICE, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::RateLimitExceeded(e)) if e.account == ALICE && e.limit == id(60)));
+    ok(contract.mint(BOB, id(1), id(40), vec![].into()));
+    assert_eq!(contract.volume(id(1)), (id(100), id(100)));
+    let result = contract.mint(BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::RateLimitExceeded(e)) if e.account.is_zero() && e.volume == id(101)));
+
+    // A new block resets the block cap but not the hourly one, and transfers count against the sender
+    vm.set_block_number(11);
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(BOB, ALICE, id(1), id(20), vec![].into()));
+    assert_eq!(contract.account_volume(id(1), BOB), (id(20), id(60)));
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::RateLimitExceeded(_))));
+
+    // Burns aren't limited, and the next hour starts afresh
+    ok(contract.burn(id(1), id(80)));
+    vm.set_block_timestamp(HOUR * 101);
+    assert_eq!(contract.volume(id(1)), (id(20), U256::ZERO));
+    vm.set_sender(OWNER);
+    ok(contract.mint(ALICE, id(1), id(60), vec![].into()));
+}
+
+#[cfg(feature = "admin-delay")]
+#[test]
+fn admin_actions_wait_for_the_delay() {
+    use extensions::admin_delay::{admin_action_id, setUriCall, transferOwnershipCall, unpauseCall, IAdminDelay};
+    use stylus_sdk::alloy_sol_types::SolCall;
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    // Without a delay the owner acts directly, including to set one
+    ok(contract.set_admin_delay(id(100)));
+    assert_eq!(contract.admin_delay(), id(100));
+    let result = contract.set_uri("https://cradle.build/".into());
+    assert!(matches!(result, Err(My1155Error::AdminDelayRequired(e)) if e.selector == setUriCall::SELECTOR));
+    assert!(matches!(contract.transfer_ownership(ALICE), Err(My1155Error::AdminDelayRequired(_))));
+    assert!(matches!(contract.set_admin_delay(U256::ZERO), Err(My1155Error::AdminDelayRequired(_))));
+
+    let data = setUriCall { newUri: "https://cradle.build/".into() }.abi_encode();
+    let action = ok(contract.schedule_admin_action(data.clone().into(), B256::ZERO));
+    assert_eq!(action, admin_action_id(&data, B256::ZERO));
+    assert_eq!(contract.admin_action_ready_at(action), id(1_100));
+    let result = contract.schedule_admin_action(data.clone().into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::AdminActionAlreadyScheduled(_))));
+
+    // Anyone executes it once the delay has passed, and only once
+    vm.set_sender(ALICE);
+    let result = contract.execute_admin_action(data.clone().into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::AdminActionNotReady(e)) if e.readyAt == id(1_100)));
+    vm.set_block_timestamp(1_100);
+    ok(contract.execute_admin_action(data.clone().into(), B256::ZERO));
+    assert_eq!(contract.uri(id(7)), "https://cradle.build/7.json");
+    let result = contract.execute_admin_action(data.into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnknownAdminAction(_))));
+
+    // Pausing isn't delayed, and the owner cancels actions it changes its mind about
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+    let unpause = unpauseCall {}.abi_encode();
+    let action = ok(contract.schedule_admin_action(unpause.clone().into(), B256::ZERO));
+    ok(contract.cancel_admin_action(action));
+    vm.set_block_timestamp(1_200);
+    let result = contract.execute_admin_action(unpause.into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnknownAdminAction(_))));
+    assert!(contract.pausable.paused());
+
+    // Only the owner schedules, and only the actions that wait for the delay
+    let pause = stylus_sdk::function_selector!("pause").to_vec();
+    let result = contract.schedule_admin_action(pause.into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::InvalidAdminAction(_))));
+    vm.set_sender(ALICE);
+    let transfer = transferOwnershipCall { newOwner: ALICE }.abi_encode();
+    let result = contract.schedule_admin_action(transfer.into(), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "tiers")]
+#[test]
+fn tiers_follow_the_combined_balance_of_their_ids() {
+    use extensions::tiers::{ITiers, TiersSet};
+
+    let (vm, mut contract) = setup_with_balances();
+    assert_eq!(contract.tier_of(ALICE), U256::ZERO);
+    ok(contract.set_tiers(vec![id(1), id(2)], vec![id(50), id(150), id(500)]));
+    assert_eq!(emitted::<TiersSet>(&vm)[0].thresholds.len(), 3);
+    assert_eq!(contract.tier_balance_of(ALICE), id(150));
+    assert_eq!(contract.tier_of(ALICE), id(2));
+    assert!(contract.has_access(ALICE, id(2)) && !contract.has_access(ALICE, id(3)));
+    assert!(contract.has_access(BOB, U256::ZERO) && !contract.has_access(BOB, id(1)));
+    ok(contract.mint(BOB, id(2), id(60), vec![].into()));
+    assert_eq!(contract.tier_of(BOB), id(1));
+
+    let result = contract.set_tiers(vec![id(1), id(1)], vec![id(50)]);
+    assert!(matches!(result, Err(My1155Error::InvalidTierIds(_))));
+    let result = contract.set_tiers(vec![id(1)], vec![id(50), id(50)]);
+    assert!(matches!(result, Err(My1155Error::InvalidTierThreshold(e)) if e.tier == id(2)));
+    // Removing the tiers puts everyone in tier 0
+    ok(contract.set_tiers(vec![], vec![]));
+    assert_eq!((contract.tier_ids(), contract.tier_of(ALICE)), (vec![], U256::ZERO));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_tiers(vec![id(1)], vec![id(1)]);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "validity")]
+#[test]
+fn balances_only_count_within_their_validity() {
+    use extensions::validity::{IValidity, ValiditySet};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_000);
+    for (start, end) in [(id(2_000), id(2_000)), (id(3_000), id(2_000))] {
+        let result = contract.set_validity(id(1), start, end);
+        assert!(matches!(result, Err(My1155Error::InvalidValidity(e)) if e.start == start && e.end == end));
+    }
+    ok(contract.set_validity(id(1), id(2_000), id(3_000)));
+    assert_eq!(contract.validity(id(1)), (id(2_000), id(3_000)));
+    assert_eq!(emitted::<ValiditySet>(&vm).len(), 1);
+
+    assert_eq!(contract.valid_balance_of(ALICE, id(1)), U256::ZERO);
+    vm.set_block_timestamp(2_000);
+    assert!(contract.is_valid(id(1)));
+    assert_eq!(contract.valid_balance_of(ALICE, id(1)), id(100));
+    vm.set_block_timestamp(3_000);
+    assert_eq!(contract.valid_balance_of(ALICE, id(1)), U256::ZERO);
+    // The token's balances and IDs without a period are unaffected
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(100));
+    assert_eq!(contract.valid_balance_of(ALICE, id(2)), id(50));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_validity(id(2), U256::ZERO, id(5_000));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "validity")]
+#[test]
+fn anyone_burns_expired_balances_for_the_bounty() {
+    use extensions::validity::{ExpiredBurned, IValidity};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_000);
+    ok(contract.set_validity(id(1), U256::ZERO, id(2_000)));
+    vm.set_value(id(15));
+    ok(contract.set_expiry_bounty(id(1), id(10)));
+    vm.set_value(U256::ZERO);
+    vm.set_balance(vm.contract_address(), id(15));
+    assert_eq!(contract.expiry_bounty(id(1)), (id(10), id(15)));
+
+    vm.set_sender(BOB);
+    let result = contract.burn_expired(ALICE, id(1));
+    assert!(matches!(result, Err(My1155Error::NotExpired(e)) if e.end == id(2_000)));
+    assert!(matches!(contract.burn_expired(ALICE, id(2)), Err(My1155Error::NotExpired(_))));
+    vm.set_block_timestamp(2_000);
+    assert_eq!(ok(contract.burn_expired(ALICE, id(1))), id(100));
+    assert_eq!((contract.balance_of(ALICE, id(1)), contract.total_supply(id(1))), (U256::ZERO, U256::ZERO));
+    let event = emitted::<ExpiredBurned>(&vm).pop().expect("burn logged");
+    assert_eq!((event.account, event.amount, event.burner, event.bounty), (ALICE, id(100), BOB, id(10)));
+
+    // An empty balance pays nothing, and the last bounty is what's left of the funds
+    assert_eq!(ok(contract.burn_expired(ALICE, id(1))), U256::ZERO);
+    vm.set_sender(OWNER);
+    ok(contract.mint(BOB, id(1), id(1), vec![].into()));
+    vm.set_sender(BOB);
+    ok(contract.burn_expired(BOB, id(1)));
+    assert_eq!(emitted::<ExpiredBurned>(&vm).pop().map(|e| e.bounty), Some(id(5)));
+    assert_eq!(contract.expiry_bounty(id(1)), (id(10), U256::ZERO));
+}
+
+#[cfg(feature = "packs")]
+const RANDOMNESS: Address = address!("000000000000000000000000000000000000da1e");
+
+/// `setup_with_balances`, with token 1 a pack of three drops, weighted 3:1 between tokens 10 and
+/// 11, and `ALICE` opening two of them as request 7.
+#[cfg(feature = "packs")]
+fn setup_with_opening() -> (TestVM, My1155) {
+    use extensions::packs::{IPacks, IRandomness};
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.set_drop_table(id(1), vec![id(10), id(11)], vec![id(3), id(1)], id(3)));
+    ok(contract.set_pack_randomness(RANDOMNESS));
+    let request = IRandomness::requestCall { commitment: B256::repeat_byte(1), mix_blockhash: true };
+    vm.mock_call(RANDOMNESS, request.abi_encode(), Ok(id(7).abi_encode()));
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.open_pack(id(1), id(2), B256::repeat_byte(1))), id(7));
+    (vm, contract)
+}
+
+#[cfg(feature = "packs")]
+#[test]
+fn packs_drop_their_table_once_randomness_arrives() {
+    use extensions::packs::{IPacks, PackOpenRequested, PackOpened};
+
+    let (vm, mut contract) = setup_with_opening();
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(98));
+    assert_eq!(contract.opening(id(7)), (ALICE, id(1), id(2), false));
+    assert_eq!(emitted::<PackOpenRequested>(&vm)[0].amount, id(2));
+
+    vm.set_sender(OWNER);
+    let result = contract.set_drop_table(id(1), vec![], vec![], U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::OpeningsPending(e)) if e.packId == id(1)));
+    vm.set_sender(BOB);
+    let result = contract.on_randomness_fulfilled(id(7), B256::repeat_byte(2));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedRandomness(e)) if e.caller == BOB));
+
+    vm.set_sender(RANDOMNESS);
+    ok(contract.on_randomness_fulfilled(id(7), B256::repeat_byte(2)));
+    let event = emitted::<PackOpened>(&vm).pop().expect("opening logged");
+    assert_eq!((event.opener, event.randomness, event.drops.len()), (ALICE, B256::repeat_byte(2), 6));
+    for drop in [id(10), id(11)] {
+        let dropped = event.drops.iter().filter(|&&d| d == drop).count() as u64;
+        assert_eq!(contract.balance_of(ALICE, drop), id(dropped));
+    }
+    assert!(contract.opening(id(7)).3);
+    let result = contract.on_randomness_fulfilled(id(7), B256::repeat_byte(2));
+    assert!(matches!(result, Err(My1155Error::UnknownOpening(_))));
+
+    // With nothing pending, the owner can retire the pack
+    vm.set_sender(OWNER);
+    ok(contract.set_drop_table(id(1), vec![], vec![], U256::ZERO));
+    vm.set_sender(ALICE);
+    let result = contract.open_pack(id(1), id(1), B256::ZERO);
+    assert!(matches!(result, Err(My1155Error::NotAPack(e)) if e.id == id(1)));
+}
+
+#[cfg(feature = "packs")]
+#[test]
+fn openings_settle_from_revealed_randomness() {
+    use extensions::packs::{IPacks, IRandomness};
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let (vm, mut contract) = setup_with_opening();
+    for amount in [U256::ZERO, id(11)] {
+        let result = contract.open_pack(id(1), amount, B256::ZERO);
+        assert!(matches!(result, Err(My1155Error::InvalidPackAmount(e)) if e.amount == amount));
+    }
+    vm.set_sender(OWNER);
+    for (ids, weights) in [(vec![id(10)], vec![]), (vec![id(1)], vec![id(1)]), (vec![id(10)], vec![U256::ZERO])] {
+        let result = contract.set_drop_table(id(3), ids, weights, id(1));
+        assert!(matches!(result, Err(My1155Error::InvalidDropTable(e)) if e.packId == id(3)));
+    }
+
+    // The callback failed, so anyone settles the opening once the secret is revealed
+    let query = IRandomness::randomnessCall { request_id: id(7) }.abi_encode();
+    vm.mock_static_call(RANDOMNESS, query.clone(), Ok((false, B256::ZERO).abi_encode()));
+    vm.set_sender(BOB);
+    let result = contract.settle_opening(id(7));
+    assert!(matches!(result, Err(My1155Error::RandomnessPending(e)) if e.requestId == id(7)));
+    vm.mock_static_call(RANDOMNESS, query, Ok((true, B256::repeat_byte(3)).abi_encode()));
+    ok(contract.settle_opening(id(7)));
+    assert_eq!(contract.balance_of(ALICE, id(10)) + contract.balance_of(ALICE, id(11)), id(6));
+    assert!(matches!(contract.settle_opening(id(7)), Err(My1155Error::UnknownOpening(_))));
+}
+
+#[cfg(feature = "market")]
+const GOLD: Address = address!("000000000000000000000000000000000000601d");
+
+/// Lets `GOLD` move `amount` from `from` to `to`.
+#[cfg(feature = "market")]
+fn expect_payment(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    use extensions::market::IERC20;
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let transfer = IERC20::transferFromCall { from, to, value: id(amount) };
+    vm.mock_call(GOLD, transfer.abi_encode(), Ok(true.abi_encode()));
+}
+
+#[cfg(feature = "market")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn listed_items_sell_from_escrow_with_the_fee() {
+    use extensions::market::{IMarket, ListingBought};
+
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.set_market_fee(Address::ZERO, id(250));
+    assert!(matches!(result, Err(My1155Error::InvalidMarketFee(e)) if e.feeBps == id(250)));
+    assert!(matches!(contract.set_market_fee(OWNER, id(1_001)), Err(My1155Error::InvalidMarketFee(_))));
+    ok(contract.set_market_fee(OWNER, id(250)));
+
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.list(id(1), id(10), Address::ZERO, id(40)), Err(My1155Error::InvalidListing(_))));
+    assert!(matches!(contract.list(id(1), id(10), GOLD, U256::MAX), Err(My1155Error::InvalidListing(_))));
+    let listing = ok(contract.list(id(1), id(10), GOLD, id(40)));
+    let escrow = vm.contract_address();
+    assert_eq!((contract.balance_of(ALICE, id(1)), contract.balance_of(escrow, id(1))), (id(90), id(10)));
+    assert_eq!(contract.listing(listing), (ALICE, id(1), id(10), GOLD, id(40)));
+
+    vm.set_sender(BOB);
+    let result = contract.buy(listing, id(11));
+    assert!(matches!(result, Err(My1155Error::InsufficientListing(e)) if e.available == id(10)));
+    expect_payment(&vm, BOB, OWNER, 3);
+    expect_payment(&vm, BOB, ALICE, 117);
+    ok(contract.buy(listing, id(3)));
+    assert_eq!((contract.balance_of(BOB, id(1)), contract.balance_of(escrow, id(1))), (id(3), id(7)));
+    let event = emitted::<ListingBought>(&vm).pop().expect("sale logged");
+    assert_eq!((event.buyer, event.paid, event.fee), (BOB, id(120), id(3)));
+
+    // A token that doesn't pay fails the sale
+    let result = contract.buy(listing, id(1));
+    assert!(matches!(result, Err(My1155Error::PaymentFailed(e)) if e.token == GOLD));
+}
+
+#[cfg(feature = "market")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn sellers_cancel_listings_for_their_items_back() {
+    use extensions::market::{IMarket, ListingCancelled};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let listing = ok(contract.list(id(2), id(20), GOLD, id(5)));
+    // With the fee off, the seller gets the whole price
+    vm.set_sender(BOB);
+    expect_payment(&vm, BOB, ALICE, 25);
+    ok(contract.buy(listing, id(5)));
+    let result = contract.cancel(listing);
+    assert!(matches!(result, Err(My1155Error::NotSeller(e)) if e.caller == BOB));
+
+    vm.set_sender(OWNER);
+    ok(contract.pause());
+    vm.set_sender(ALICE);
+    ok(contract.cancel(listing));
+    assert_eq!(contract.balance_of(ALICE, id(2)), id(45));
+    assert_eq!(emitted::<ListingCancelled>(&vm).len(), 1);
+    assert!(matches!(contract.cancel(listing), Err(My1155Error::NotSeller(_))));
+    assert!(matches!(contract.list(id(2), id(1), GOLD, id(5)), Err(My1155Error::EnforcedPause(_))));
+}
+
+#[cfg(all(feature = "market", feature = "transfer-security"))]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn market_sales_are_held_to_the_transfer_security_level() {
+    use extensions::{market::IMarket, transfer_security::*};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let listing = ok(contract.list(id(1), id(10), GOLD, id(4)));
+
+    // At the allowlist levels a buyer isn't an operator the owner trusts to pay royalties
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_security_level(LEVEL_ALLOWLIST));
+    vm.set_sender(BOB);
+    let result = contract.buy(listing, id(1));
+    assert!(matches!(result, Err(My1155Error::TransferRestricted(e)) if e.caller == BOB && e.from == ALICE));
+    vm.set_sender(OWNER);
+    ok(contract.set_allowed_operator(BOB, true));
+    vm.set_sender(BOB);
+    expect_payment(&vm, BOB, ALICE, 4);
+    ok(contract.buy(listing, id(1)));
+
+    // Listing counts as the seller's own transfer, which level 3 doesn't allow either
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_security_level(LEVEL_ALLOWLIST_NO_OTC));
+    vm.set_sender(ALICE);
+    let result = contract.list(id(1), id(1), GOLD, id(4));
+    assert!(matches!(result, Err(My1155Error::TransferRestricted(e)) if e.caller == ALICE));
+    ok(contract.cancel(listing));
+    assert_eq!(contract.balance_of(ALICE, id(1)), id(99));
+}
+
+#[cfg(feature = "composable")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn children_follow_their_parent_id() {
+    use extensions::composable::{ChildAttached, IComposable};
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.mint(ALICE, id(7), id(1), vec![].into()));
+    let this = vm.contract_address();
+
+    vm.set_sender(ALICE);
+    ok(contract.attach_child(this, id(7), id(1), id(10)));
+    ok(contract.attach_child(this, id(7), id(2), id(5)));
+    assert_eq!(contract.child_ids_of(this, id(7)), vec![id(1), id(2)]);
+    assert_eq!((contract.child_balance_of(this, id(7), id(1)), contract.balance_of(this, id(1))), (id(10), id(10)));
+    assert_eq!(emitted::<ChildAttached>(&vm)[0].from, ALICE);
+    let result = contract.attach_child(this, id(7), id(7), id(1));
+    assert!(matches!(result, Err(My1155Error::InvalidChild(e)) if e.id == id(7)));
+    // An ID with a larger supply has no single owner to be a parent
+    let result = contract.attach_child(this, id(1), id(2), id(1));
+    assert!(matches!(result, Err(My1155Error::NotParentOwner(e)) if e.account == ALICE));
+
+    // Moving the parent moves the bundle
+    ok(contract.safe_transfer_from(ALICE, BOB, id(7), id(1), vec![].into()));
+    let result = contract.detach_child(this, id(7), id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::NotParentOwner(_))));
+    vm.set_sender(BOB);
+    let result = contract.detach_child(this, id(7), id(1), id(11));
+    assert!(matches!(result, Err(My1155Error::InsufficientChildBalance(e)) if e.balance == id(10)));
+    ok(contract.detach_child(this, id(7), id(1), id(10)));
+    assert_eq!((contract.balance_of(BOB, id(1)), contract.balance_of(this, id(1))), (id(10), U256::ZERO));
+    assert_eq!(contract.child_ids_of(this, id(7)), vec![id(2)]);
+}
+
+#[cfg(feature = "composable")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn erc721_owners_hold_the_children_of_their_tokens() {
+    use extensions::composable::{IComposable, IERC721};
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    const HEROES: Address = address!("0000000000000000000000000000000000000721");
+    let (vm, mut contract) = setup_with_balances();
+    let owner_of = IERC721::ownerOfCall { tokenId: id(3) }.abi_encode();
+    vm.mock_static_call(HEROES, owner_of.clone(), Ok(ALICE.abi_encode()));
+    vm.set_sender(ALICE);
+    assert!(contract.owns_parent(ALICE, HEROES, id(3)));
+    ok(contract.attach_child(HEROES, id(3), id(2), id(20)));
+    assert_eq!(contract.balance_of(ALICE, id(2)), id(30));
+
+    vm.mock_static_call(HEROES, owner_of, Ok(BOB.abi_encode()));
+    assert!(matches!(contract.detach_child(HEROES, id(3), id(2), id(1)), Err(My1155Error::NotParentOwner(_))));
+    vm.set_sender(BOB);
+    let result = contract.attach_child(HEROES, id(4), id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::NotParentOwner(e)) if e.parentId == id(4)));
+    ok(contract.detach_child(HEROES, id(3), id(2), id(20)));
+    assert_eq!(contract.balance_of(BOB, id(2)), id(20));
+    assert!(contract.child_ids_of(HEROES, id(3)).is_empty());
+}
+
+#[cfg(feature = "attested-mint")]
+#[test]
+fn attested_accounts_mint_during_the_allowlist_phase() {
+    use extensions::attested_mint::{IAttestationRegistry, IAttestedMint, MintGateSet};
+    use extensions::paid_mint::IPaidMint;
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    const REGISTRY: Address = address!("00000000000000000000000000000000000a77e5");
+    const ATTESTER: Address = address!("000000000000000000000000000000000000c1a0");
+    let kyc = B256::repeat_byte(0x0c);
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    ok(contract.set_mint_price(id(1), id(10), Address::ZERO));
+    ok(contract.set_mint_gate(id(1), REGISTRY, kyc, ATTESTER, id(2_000)));
+    assert_eq!(contract.mint_gate(id(1)), (REGISTRY, kyc, ATTESTER, id(2_000)));
+    assert_eq!(emitted::<MintGateSet>(&vm)[0].schema, kyc);
+
+    for (account, attested) in [(ALICE, true), (BOB, false)] {
+        let query =
+            IAttestationRegistry::hasValidAttestationCall { schema: kyc, recipient: account, attester: ATTESTER };
+        vm.mock_static_call(REGISTRY, query.abi_encode(), Ok(attested.abi_encode()));
+    }
+    vm.set_sender(ALICE);
+    vm.set_value(id(10));
+    ok(contract.mint_paid(ALICE, id(1), id(1)));
+    // The attestation binds the recipient, whoever pays
+    let result = contract.mint_paid(BOB, id(1), id(1));
+    assert!(matches!(result, Err(My1155Error::MissingAttestation(e)) if e.account == BOB && e.schema == kyc));
+    assert!(contract.can_mint(ALICE, id(1)) && !contract.can_mint(BOB, id(1)));
+
+    // Once the phase ends, the mint is public
+    vm.set_block_timestamp(2_000);
+    ok(contract.mint_paid(BOB, id(1), id(1)));
+    assert_eq!((contract.balance_of(ALICE, id(1)), contract.balance_of(BOB, id(1))), (id(1), id(1)));
+    let result = contract.set_mint_gate(id(1), Address::ZERO, B256::ZERO, Address::ZERO, U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+}
+
+#[cfg(feature = "checkpoints")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn past_balances_are_read_by_block() {
+    use extensions::checkpoints::{ICheckpoints, CLOCK_MODE};
+
+    let (vm, mut contract) = setup();
+    vm.set_block_number(10);
+    ok(contract.mint(ALICE, id(1), id(100), vec![].into()));
+    // Later changes in the same block replace the checkpoint
+    ok(contract.mint(ALICE, id(1), id(20), vec![].into()));
+    vm.set_block_number(12);
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(30), vec![].into()));
+    vm.set_block_number(15);
+    vm.set_sender(BOB);
+    ok(contract.burn(id(1), id(10)));
+
+    assert_eq!(contract.num_checkpoints(ALICE, id(1)), id(2));
+    assert_eq!(contract.checkpoint_at(ALICE, id(1), id(0)), (10, id(120)));
+    let history = [(9, 0, 0, 0), (10, 120, 0, 120), (11, 120, 0, 120), (12, 90, 30, 120), (14, 90, 30, 120)];
+    for (block, alice, bob, supply) in history {
+        assert_eq!(ok(contract.get_past_balance(ALICE, id(1), id(block))), id(alice));
+        assert_eq!(ok(contract.get_past_balance(BOB, id(1), id(block))), id(bob));
+        assert_eq!(ok(contract.get_past_total_supply(id(1), id(block))), id(supply));
+    }
+
+    // The current block can still change
+    let result = contract.get_past_balance(BOB, id(1), id(15));
+    assert!(matches!(result, Err(My1155Error::FutureLookup(e)) if e.currentBlock == id(15)));
+    vm.set_block_number(16);
+    assert_eq!(ok(contract.get_past_balance(BOB, id(1), id(15))), id(20));
+    assert_eq!(ok(contract.get_past_total_supply(id(1), id(15))), id(110));
+    assert_eq!((contract.clock(), contract.clock_mode()), (16, CLOCK_MODE.into()));
+}
+
+#[cfg(feature = "votes")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn delegated_votes_follow_balances_per_id() {
+    use extensions::votes::{DelegateVotesChanged, IVotes};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_number(10);
+    assert_eq!(contract.get_votes(ALICE, id(1)), U256::ZERO);
+    // Holders delegate each ID on its own, and balances count once delegated
+    vm.set_sender(ALICE);
+    contract.delegate(id(1), BOB);
+    assert_eq!(contract.delegates(ALICE, id(1)), BOB);
+    assert_eq!((contract.get_votes(BOB, id(1)), contract.get_votes(BOB, id(2))), (id(100), U256::ZERO));
+    contract.delegate(id(2), ALICE);
+
+    vm.set_block_number(12);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(40), vec![].into()));
+    assert_eq!(contract.get_votes(BOB, id(1)), id(60));
+    vm.set_sender(BOB);
+    contract.delegate(id(1), BOB);
+    assert_eq!(contract.get_votes(BOB, id(1)), id(100));
+    ok(contract.burn(id(1), id(10)));
+    assert_eq!(contract.get_votes(BOB, id(1)), id(90));
+    assert_eq!(emitted::<DelegateVotesChanged>(&vm).last().map(|e| e.newVotes), Some(id(90)));
+
+    vm.set_sender(ALICE);
+    contract.delegate(id(1), Address::ZERO);
+    assert_eq!(contract.get_votes(BOB, id(1)), id(30));
+    vm.set_block_number(13);
+    assert_eq!(ok(contract.get_past_votes(BOB, id(1), id(11))), id(100));
+    assert_eq!(ok(contract.get_past_votes(BOB, id(1), id(12))), id(30));
+    assert_eq!(ok(contract.get_past_votes(ALICE, id(2), id(12))), id(50));
+    let result = contract.get_past_votes(BOB, id(1), id(13));
+    assert!(matches!(result, Err(My1155Error::FutureLookup(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn approvals_lapse_at_their_expiry() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    let result = contract.set_approval_for_all_with_expiry(BOB, true, id(1_000));
+    assert!(matches!(result, Err(My1155Error::InvalidApprovalExpiry(e)) if e.expires == id(1_000)));
+    ok(contract.set_approval_for_all_with_expiry(BOB, true, id(2_000)));
+    assert_eq!(contract.approval_expiry(ALICE, BOB), id(2_000));
+    let event = emitted::<ApprovalForAllExpiry>(&vm).pop().expect("expiry logged");
+    assert_eq!((event.account, event.operator, event.expires), (ALICE, BOB, id(2_000)));
+
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(10), vec![].into()));
+    vm.set_block_timestamp(2_000);
+    assert!(!contract.is_approved_for_all(ALICE, BOB));
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(10), vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(e)) if e.operator == BOB));
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::MissingApprovalForAll(_))));
+    assert!(matches!(contract.burn_from(ALICE, id(1), id(1)), Err(My1155Error::MissingApprovalForAll(_))));
+
+    // A plain approval doesn't expire
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    assert_eq!(contract.approval_expiry(ALICE, BOB), U256::ZERO);
+    assert!(contract.is_approved_for_all(ALICE, BOB));
+}
+
+#[cfg(feature = "approval-index")]
+#[test]
+fn holders_list_and_revoke_all_their_operators() {
+    use extensions::approval_index::IApprovalIndex;
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    ok(contract.set_approval_for_all(OWNER, true));
+    ok(contract.set_approval_for_all_with_expiry(BRIDGE, true, id(1_060)));
+    assert_eq!(contract.operators_of(ALICE), vec![BOB, OWNER, BRIDGE]);
+    ok(contract.set_approval_for_all(BOB, false));
+    assert_eq!(contract.operators_of(ALICE), vec![BRIDGE, OWNER]);
+    vm.set_block_timestamp(1_060);
+    assert_eq!(contract.operators_of(ALICE), vec![OWNER]);
+
+    // Lapsed approvals are revoked too
+    assert_eq!(ok(contract.revoke_all_approvals()), id(2));
+    assert!(contract.operators_of(ALICE).is_empty());
+    assert!(!contract.is_approved_for_all(ALICE, OWNER));
+    assert_eq!(contract.approval_expiry(ALICE, BRIDGE), U256::ZERO);
+    assert_eq!(emitted::<ApprovalForAll>(&vm).iter().filter(|e| !e.approved).count(), 3);
+    assert_eq!(ok(contract.revoke_all_approvals()), U256::ZERO);
+}
+
+#[cfg(feature = "transfer-hooks")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_hooks_can_block_or_just_observe_transfers() {
+    use extensions::transfer_hooks::{ITransferHook, ITransferHooks, TransferHookFailed, MAX_TRANSFER_HOOKS};
+    use stylus_sdk::alloy_sol_types::{SolCall, SolValue};
+
+    let (vm, mut contract) = setup_with_balances();
+    let result = contract.set_transfer_hook(PARTNER, 50_000, true);
+    assert!(matches!(result, Err(My1155Error::InvalidTransferHook(e)) if e.hook == PARTNER));
+    vm.set_code(PARTNER, vec![0]);
+    vm.set_code(BRIDGE, vec![0]);
+    ok(contract.set_transfer_hook(PARTNER, 50_000, true));
+    ok(contract.set_transfer_hook(BRIDGE, 30_000, false));
+    assert_eq!(contract.transfer_hooks(), vec![PARTNER, BRIDGE]);
+    assert_eq!(contract.transfer_hook_config(BRIDGE), (30_000, false));
+
+    let call = |from, to, value| {
+        ITransferHook::beforeTransferCall { operator: ALICE, from, to, ids: vec![id(1)], values: vec![value] }
+            .abi_encode()
+    };
+    let answer = ITransferHook::beforeTransferCall::SELECTOR.abi_encode();
+    vm.set_sender(ALICE);
+    vm.mock_call(PARTNER, call(ALICE, BOB, id(5)), Ok(answer.clone()));
+    vm.mock_call(BRIDGE, call(ALICE, BOB, id(5)), Err(b"fee module down".to_vec()));
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(5), vec![].into()));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(5));
+    let failed = &emitted::<TransferHookFailed>(&vm)[0];
+    assert_eq!((failed.hook, failed.returnData.to_vec()), (BRIDGE, b"fee module down".to_vec()));
+
+    // The fail-closed hook blocks what it rejects
+    vm.mock_call(PARTNER, call(ALICE, OWNER, id(1)), Err(b"sanctioned".to_vec()));
+    let result = contract.safe_transfer_from(ALICE, OWNER, id(1), id(1), vec![].into());
+    assert!(matches!(
+        result,
+        Err(My1155Error::TransferHookRejected(e)) if e.hook == PARTNER && e.returnData.as_ref() == b"sanctioned"
+    ));
+    assert_eq!(contract.balance_of(OWNER, id(1)), U256::ZERO);
+
+    // Burns don't call them, and a zero gas limit detaches a hook
+    ok(contract.burn(id(1), id(1)));
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_hook(PARTNER, 0, true));
+    assert_eq!(contract.transfer_hooks(), vec![BRIDGE]);
+    assert_eq!(contract.transfer_hook_config(PARTNER), (0, false));
+    for index in 0..MAX_TRANSFER_HOOKS as u64 {
+        let hook = Address::with_last_byte(index as u8 + 1);
+        vm.set_code(hook, vec![0]);
+        let result = contract.set_transfer_hook(hook, 10_000, false);
+        assert_eq!(result.is_ok(), index + 1 < MAX_TRANSFER_HOOKS as u64);
+    }
+}
+
+#[cfg(feature = "modules")]
+#[test]
+fn modules_serve_the_selectors_routed_to_them() {
+    use extensions::modules::{IModules, ModuleSet};
+
+    let (vm, mut contract) = setup();
+    let stake = FixedBytes(stylus_sdk::function_selector!("stake", U256, U256));
+    let craft = FixedBytes(stylus_sdk::function_selector!("craft", Vec<U256>));
+    let call = [stake.as_slice(), &(id(1), id(5)).abi_encode()].concat();
+    let unknown = ERC1155UnknownSelector { selector: stake }.abi_encode();
+    assert_eq!(contract.fallback(&call), Err(unknown.clone()));
+
+    let result = contract.set_module(vec![stake, craft], PARTNER);
+    assert!(matches!(result, Err(My1155Error::InvalidModule(e)) if e.module == PARTNER));
+    vm.set_code(PARTNER, vec![0]);
+    ok(contract.set_module(vec![stake, craft], PARTNER));
+    assert_eq!(contract.routed_selectors(), vec![stake, craft]);
+    assert_eq!(contract.module_of(craft), PARTNER);
+    assert_eq!(emitted::<ModuleSet>(&vm).len(), 2);
+
+    // Routed calls run the module's code, and its output or revert data is the token's
+    vm.mock_delegate_call(PARTNER, call.clone(), Ok(true.abi_encode()));
+    assert_eq!(contract.fallback(&call), Ok(true.abi_encode()));
+
+    // Only the owner routes selectors, and a zero module stops routing them
+    vm.set_sender(ALICE);
+    let result = contract.set_module(vec![stake], Address::ZERO);
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+    vm.set_sender(OWNER);
+    ok(contract.set_module(vec![stake], Address::ZERO));
+    assert_eq!(contract.routed_selectors(), vec![craft]);
+    assert_eq!(contract.module_of(stake), Address::ZERO);
+    assert_eq!(contract.fallback(&call), Err(unknown));
+}
+
+#[cfg(feature = "holder-snapshots")]
+#[test]
+fn snapshots_freeze_holders_and_their_balances() {
+    use extensions::holder_snapshots::{HoldersSnapshotted, IHolderSnapshots};
+
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.mint(PARTNER, id(1), id(5), vec![].into()));
+    ok(contract.mint(BOB, id(1), id(20), vec![].into()));
+    vm.set_sender(PARTNER);
+    ok(contract.burn(id(1), id(5)));
+    assert_eq!(contract.holder_count(id(1)), id(2));
+    assert!(matches!(contract.snapshot_holders(id(1)), Err(My1155Error::UnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    vm.set_block_number(7);
+    assert_eq!(ok(contract.snapshot_holders(id(1))), U256::ZERO);
+    let event = emitted::<HoldersSnapshotted>(&vm).pop().expect("snapshot logged");
+    assert_eq!((event.snapshotId, event.id, event.blockNumber, event.holderCount), (U256::ZERO, id(1), 7, id(2)));
+
+    // Moves after the snapshot leave it as it was
+    ok(contract.mint(PARTNER, id(1), id(9), vec![].into()));
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(1), id(100)));
+    assert_eq!(contract.holder_count(id(1)), id(2));
+    assert_eq!(ok(contract.snapshot(U256::ZERO)), (id(1), 7, id(2), id(120)));
+    // Sorted by address
+    assert_eq!(ok(contract.holders_at(U256::ZERO, U256::ZERO, id(10))), (vec![BOB, ALICE], vec![id(20), id(100)]));
+    assert_eq!(ok(contract.holders_at(U256::ZERO, id(1), id(1))), (vec![ALICE], vec![id(100)]));
+    assert_eq!(ok(contract.holders_at(U256::ZERO, id(2), U256::MAX)), (vec![], vec![]));
+    assert_eq!(ok(contract.balance_at_snapshot(U256::ZERO, ALICE)), id(100));
+    assert_eq!(ok(contract.balance_at_snapshot(U256::ZERO, PARTNER)), U256::ZERO);
+
+    assert_eq!(contract.snapshot_count(), id(1));
+    let result = contract.balance_at_snapshot(id(1), ALICE);
+    assert!(matches!(result, Err(My1155Error::UnknownSnapshot(e)) if e.snapshotId == id(1)));
+}
+
+#[cfg(feature = "holder-snapshots")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn batch_transfers_move_holders_in_and_out_of_snapshots() {
+    use extensions::holder_snapshots::IHolderSnapshots;
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(100), id(10)], vec![].into()));
+    // ALICE sent all of ID 1 and keeps some of ID 2, BOB now holds both
+    assert_eq!((contract.holder_count(id(1)), contract.holder_count(id(2))), (id(1), id(2)));
+
+    vm.set_sender(OWNER);
+    let snapshot_id = ok(contract.snapshot_holders(id(1)));
+    assert_eq!(ok(contract.holders_at(snapshot_id, U256::ZERO, id(10))), (vec![BOB], vec![id(100)]));
+    assert_eq!(ok(contract.balance_at_snapshot(snapshot_id, ALICE)), U256::ZERO);
+}
+
+#[cfg(feature = "testnet")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn testnet_faucet_mints_to_anyone_up_to_its_limit() {
+    use extensions::testnet::{FaucetMinted, ITestnet, TestnetBalances};
+
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    // Closed until the owner sets a limit
+    let result = contract.faucet_mint(BOB, id(3), id(1));
+    assert!(matches!(result, Err(My1155Error::FaucetLimitExceeded(e)) if e.limit.is_zero()));
+    assert!(matches!(contract.set_faucet_limit(id(10)), Err(My1155Error::UnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.set_faucet_limit(id(10)));
+    assert_eq!(contract.faucet_limit(), id(10));
+    vm.set_sender(ALICE);
+    ok(contract.faucet_mint(BOB, id(3), id(10)));
+    assert_eq!(contract.balance_of(BOB, id(3)), id(10));
+    let event = emitted::<FaucetMinted>(&vm).pop().expect("faucet mint logged");
+    assert_eq!((event.caller, event.to, event.id, event.amount), (ALICE, BOB, id(3), id(10)));
+    let result = contract.faucet_mint(BOB, id(3), id(11));
+    assert!(matches!(result, Err(My1155Error::FaucetLimitExceeded(e)) if e.amount == id(11) && e.limit == id(10)));
+
+    // Every move logs the balances it left, batches included
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(30), id(5)], vec![].into()));
+    let event = emitted::<TestnetBalances>(&vm).pop().expect("balances logged");
+    assert_eq!((event.from, event.to, event.ids), (ALICE, BOB, vec![id(1), id(2)]));
+    assert_eq!((event.fromBalances, event.toBalances), (vec![id(70), id(45)], vec![id(30), id(5)]));
+}
+
+#[cfg(all(feature = "testnet", feature = "admin-delay"))]
+#[test]
+fn testnet_admin_actions_wait_five_minutes_at_most() {
+    use extensions::admin_delay::{setUriCall, IAdminDelay};
+    use stylus_sdk::alloy_sol_types::SolCall;
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(1_000);
+    ok(contract.set_admin_delay(id(86_400)));
+    assert_eq!(contract.admin_delay(), id(86_400));
+    let data = setUriCall { newUri: "https://cradle.build/".into() }.abi_encode();
+    let action = ok(contract.schedule_admin_action(data.clone().into(), B256::ZERO));
+    assert_eq!(contract.admin_action_ready_at(action), id(1_300));
+    vm.set_block_timestamp(1_300);
+    ok(contract.execute_admin_action(data.into(), B256::ZERO));
+    assert_eq!(contract.uri(id(7)), "https://cradle.build/7.json");
+}
+
+#[cfg(feature = "profiling")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn profiling_logs_the_meters_around_each_section() {
+    use profiling::{GasProfiled, AFTER_UPDATE, UPDATE_BATCH, UPDATE_SINGLE};
+
+    let sections = |vm: &TestVM| -> Vec<B256> {
+        emitted::<GasProfiled>(vm)
+            .into_iter()
+            .map(|event| {
+                assert!(event.inkStart >= event.inkEnd && event.gasStart >= event.gasEnd);
+                event.section
+            })
+            .collect()
+    };
+    let section = |name: &str| B256::right_padding_from(name.as_bytes());
+
+    // A single transfer moves the balance, then the extensions' hooks run
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(30), vec![].into()));
+    let logged = sections(&vm);
+    assert_eq!(logged[logged.len() - 2..], [section(UPDATE_SINGLE), section(AFTER_UPDATE)]);
+
+    // And so does a batch, through its own section
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    let minted = sections(&vm).len();
+    ok(contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1), id(2)], vec![id(30), id(5)], vec![].into()));
+    let logged = sections(&vm)[minted..].to_vec();
+    assert_eq!(logged.iter().filter(|&&logged| logged == section(UPDATE_BATCH)).count(), 1);
+    assert_eq!(logged[logged.len() - 2..], [section(UPDATE_BATCH), section(AFTER_UPDATE)]);
+}
+
+#[cfg(feature = "transfer-security")]
+#[test]
+#[cfg_attr(feature = "soulbound", ignore = "soulbound tokens don't transfer")]
+fn transfer_security_keeps_transfers_to_allowlisted_operators() {
+    use extensions::transfer_security::*;
+    use stylus_sdk::alloy_sol_types::SolError;
+
+    let (vm, mut contract) = setup_with_balances();
+    assert_eq!(contract.transfer_security_level(), LEVEL_OPEN);
+    let result = contract.set_transfer_security_level(4);
+    assert!(matches!(result, Err(My1155Error::InvalidTransferSecurityLevel(e)) if e.level == 4));
+    vm.set_sender(ALICE);
+    ok(contract.set_approval_for_all(BOB, true));
+    ok(contract.set_approval_for_all(PARTNER, true));
+
+    // Contracts must be allowlisted, EOAs move tokens as before
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_security_level(LEVEL_NO_CONTRACTS));
+    vm.set_code(PARTNER, vec![0]);
+    vm.set_sender(PARTNER);
+    let result = contract.safe_transfer_from(ALICE, PARTNER, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::TransferRestricted(e)) if e.caller == PARTNER && e.level == 1));
+    let reason = contract.can_transfer(ALICE, PARTNER, id(1), id(1)).1;
+    assert_eq!(reason, FixedBytes(ERC1155TransferRestricted::SELECTOR));
+    vm.set_sender(BOB);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+
+    // Only allowlisted operators, though holders still move their own tokens
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_security_level(LEVEL_ALLOWLIST));
+    ok(contract.set_allowed_operator(PARTNER, true));
+    assert!(contract.is_allowed_operator(PARTNER));
+    assert!(!contract.is_transfer_allowed(BOB, ALICE, BOB) && contract.is_transfer_allowed(ALICE, ALICE, BOB));
+    vm.set_sender(BOB);
+    let result = contract.safe_batch_transfer_from(ALICE, BOB, vec![id(1)], vec![id(1)], vec![].into());
+    assert!(matches!(result, Err(My1155Error::TransferRestricted(e)) if e.caller == BOB && e.from == ALICE));
+    vm.set_sender(PARTNER);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+    vm.set_sender(ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+
+    // Without over-the-counter transfers, holders go through an allowlisted operator too
+    vm.set_sender(OWNER);
+    ok(contract.set_transfer_security_level(LEVEL_ALLOWLIST_NO_OTC));
+    vm.set_sender(ALICE);
+    let result = contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::TransferRestricted(e)) if e.caller == ALICE && e.level == 3));
+    vm.set_sender(PARTNER);
+    ok(contract.safe_transfer_from(ALICE, BOB, id(1), id(1), vec![].into()));
+    assert_eq!(contract.balance_of(BOB, id(1)), id(4));
+
+    // Mints and burns are never restricted
+    vm.set_sender(OWNER);
+    ok(contract.mint(BOB, id(3), id(2), vec![].into()));
+    vm.set_sender(BOB);
+    ok(contract.burn(id(3), id(2)));
+    let event = emitted::<AllowedOperatorSet>(&vm).pop().expect("allowlist change logged");
+    assert_eq!((event.operator, event.allowed), (PARTNER, true));
+}
+
+#[cfg(feature = "reveal")]
+#[test]
+fn reveal_shifts_ids_through_the_committed_metadata() {
+    use extensions::reveal::*;
+
+    let (vm, mut contract) = setup();
+    let hash = keccak256(b"drop metadata");
+    let result = contract.set_reveal_offset(id(1));
+    assert!(matches!(result, Err(My1155Error::ProvenanceNotSet(_))));
+    assert!(matches!(contract.reveal(), Err(My1155Error::ProvenanceNotSet(_))));
+    let result = contract.set_provenance_hash(hash, U256::ZERO);
+    assert!(matches!(result, Err(My1155Error::InvalidProvenance(_))));
+    vm.set_sender(ALICE);
+    let result = contract.set_provenance_hash(hash, id(10));
+    assert!(matches!(result, Err(My1155Error::UnauthorizedAccount(_))));
+
+    // Committed, every ID gets the placeholder at the base URI
+    vm.set_sender(OWNER);
+    ok(contract.set_uri("ipfs://cradle/hidden.json".into()));
+    ok(contract.set_provenance_hash(hash, id(10)));
+    assert_eq!(contract.provenance_hash(), (hash, id(10)));
+    let event = emitted::<ProvenanceHashSet>(&vm).pop().expect("commitment logged");
+    assert_eq!((event.hash, event.size), (hash, id(10)));
+    let result = contract.set_provenance_hash(keccak256(b"other metadata"), id(10));
+    assert!(matches!(result, Err(My1155Error::ProvenanceAlreadySet(e)) if e.hash == hash));
+    ok(contract.mint_batch(ALICE, vec![id(1), id(9)], vec![id(1), id(1)], vec![].into()));
+    assert_eq!(contract.uri(id(1)), "ipfs://cradle/hidden.json");
+    assert_eq!(contract.uri(id(9)), "ipfs://cradle/hidden.json");
+
+    // Revealed, IDs shift through the files and wrap around the drop
+    let result = contract.set_reveal_offset(id(10));
+    assert!(matches!(result, Err(My1155Error::InvalidRevealOffset(e)) if e.offset == id(10) && e.size == id(10)));
+    ok(contract.set_reveal_offset(id(3)));
+    ok(contract.set_uri(BASE_URI.into()));
+    ok(contract.reveal());
+    assert!(contract.revealed());
+    assert_eq!(emitted::<Revealed>(&vm).pop().expect("reveal logged").offset, id(3));
+    assert_eq!(contract.uri(id(1)), "ipfs://cradle/4.json");
+    assert_eq!(contract.uri(id(9)), "ipfs://cradle/2.json");
+    assert!(matches!(contract.reveal(), Err(My1155Error::AlreadyRevealed(_))));
+    assert!(matches!(contract.set_reveal_offset(id(4)), Err(My1155Error::AlreadyRevealed(_))));
+}
+
+#[cfg(feature = "open-editions")]
+#[test]
+fn open_editions_mint_freely_until_they_close() {
+    use extensions::{open_editions::*, supply_cap::ISupplyCap};
+
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(500);
+    let result = contract.set_open_edition(id(3), id(2_000), id(1_000));
+    assert!(matches!(result, Err(My1155Error::InvalidOpenEdition(_))));
+    ok(contract.set_open_edition(id(3), id(1_000), id(2_000)));
+    let result = contract.mint(ALICE, id(3), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::OpenEditionNotOpen(e)) if e.id == id(3) && e.start == id(1_000)));
+
+    // Open, mints count toward the edition and burns don't take from it
+    vm.set_block_timestamp(1_500);
+    ok(contract.mint(ALICE, id(3), id(5), vec![].into()));
+    ok(contract.mint_batch(BOB, vec![id(1), id(3)], vec![id(1), id(2)], vec![].into()));
+    vm.set_sender(ALICE);
+    ok(contract.burn(id(3), id(1)));
+    assert_eq!(contract.open_edition(id(3)), (id(1_000), id(2_000), id(7), false));
+    let result = contract.finalize_open_edition(id(3));
+    assert!(matches!(result, Err(My1155Error::OpenEditionStillOpen(e)) if e.end == id(2_000)));
+    vm.set_sender(OWNER);
+    let result = contract.set_open_edition(id(3), id(1_500), id(3_000));
+    assert!(matches!(result, Err(My1155Error::InvalidOpenEdition(_))));
+
+    // Closed, the ID can't be minted again and anyone finalizes its size
+    vm.set_block_timestamp(2_000);
+    let result = contract.mint(ALICE, id(3), id(1), vec![].into());
+    assert!(matches!(result, Err(My1155Error::OpenEditionNotOpen(e)) if e.end == id(2_000)));
+    ok(contract.mint(ALICE, id(1), id(1), vec![].into()));
+    vm.set_sender(BOB);
+    ok(contract.finalize_open_edition(id(3)));
+    assert_eq!(contract.max_supply(id(3)), id(7));
+    let event = emitted::<OpenEditionFinalized>(&vm).pop().expect("finalization logged");
+    assert_eq!((event.id, event.size), (id(3), id(7)));
+    assert!(matches!(contract.finalize_open_edition(id(3)), Err(My1155Error::NotOpenEdition(_))));
+    assert!(matches!(contract.finalize_open_edition(id(1)), Err(My1155Error::NotOpenEdition(_))));
+}
@@ -0,0 +1,350 @@
+//! Differential test against OpenZeppelin's ERC-1155. Random sequences of mints, burns, transfers,
+//! approvals and pauses run against this token and the benchmarks' `OzErc1155`, deployed side by
+//! side, and after each operation both must have succeeded or reverted with the same error, and
+//! hold the same balances, supplies, existence, approvals and pause.
+
+use std::cell::Cell;
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall, SolError};
+use cradle_pausable::EnforcedPause;
+use erc1155_stylus::erc1155::{ERC1155InsufficientBalance, ERC1155InvalidOperator};
+use eyre::{ensure, eyre};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseError, TestRunner},
+};
+
+use crate::{
+    harness::{Account, Deployment, Outcome},
+    IMy1155::{
+        balanceOfBatchCall, existsCall, isApprovedForAllCall, mintBatchCall, mintCall, pauseCall, pausedCall,
+        safeBatchTransferFromCall, safeTransferFromCall, setApprovalForAllCall, totalSupplyCall, unpauseCall,
+    },
+};
+
+sol! {
+    /// `ERC1155Burnable`'s burns, which take the holder where the Stylus token's `burn` doesn't.
+    interface IOzErc1155 {
+        function burn(address account, uint256 id, uint256 value) external;
+        function burnBatch(address account, uint256[] ids, uint256[] values) external;
+    }
+}
+
+/// The owner and three other accounts send the operations.
+const CALLERS: usize = 4;
+/// The callers and the zero address, last.
+const ACCOUNTS: usize = CALLERS + 1;
+const IDS: usize = 3;
+const CASES: u32 = 16;
+const MAX_OPS: usize = 24;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint { caller: usize, to: usize, id: usize, value: u64 },
+    MintBatch { caller: usize, to: usize, ids: Vec<usize>, values: Vec<u64> },
+    Burn { caller: usize, from: usize, id: usize, value: u64 },
+    BurnBatch { caller: usize, ids: Vec<usize>, values: Vec<u64> },
+    Transfer { caller: usize, from: usize, to: usize, id: usize, value: u64 },
+    TransferBatch { caller: usize, from: usize, to: usize, ids: Vec<usize>, values: Vec<u64> },
+    Approve { caller: usize, operator: usize, approved: bool },
+    Pause { caller: usize },
+    Unpause { caller: usize },
+}
+
+impl Op {
+    fn caller(&self) -> usize {
+        match *self {
+            Op::Mint { caller, .. }
+            | Op::MintBatch { caller, .. }
+            | Op::Burn { caller, .. }
+            | Op::BurnBatch { caller, .. }
+            | Op::Transfer { caller, .. }
+            | Op::TransferBatch { caller, .. }
+            | Op::Approve { caller, .. }
+            | Op::Pause { caller }
+            | Op::Unpause { caller } => caller,
+        }
+    }
+
+    /// Whether the op mints, burns or transfers, which pausing stops.
+    fn moves_tokens(&self) -> bool {
+        !matches!(self, Op::Approve { .. } | Op::Pause { .. } | Op::Unpause { .. })
+    }
+
+    /// Whether the op is a batch naming an ID more than once.
+    fn repeats_an_id(&self) -> bool {
+        match self {
+            Op::MintBatch { ids, .. } | Op::BurnBatch { ids, .. } | Op::TransferBatch { ids, .. } => {
+                ids.iter().enumerate().any(|(at, id)| ids[..at].contains(id))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// IDs and values for a batch, of the same length four times in five.
+fn batch() -> impl Strategy<Value = (Vec<usize>, Vec<u64>)> {
+    prop_oneof![
+        4 => prop::collection::vec((0..IDS, 0..100u64), 0..4).prop_map(|entries| entries.into_iter().unzip()),
+        1 => (prop::collection::vec(0..IDS, 0..4), prop::collection::vec(0..100u64, 0..4)),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // Mostly the owner, so that most mints and pauses get past `onlyOwner`
+    let owner_mostly = || prop_oneof![3 => Just(0), 1 => 1..CALLERS];
+    let caller = || 0..CALLERS;
+    let account = || 0..ACCOUNTS;
+    let id = || 0..IDS;
+    let value = || 0..100u64;
+    prop_oneof![
+        3 => (owner_mostly(), account(), id(), value())
+            .prop_map(|(caller, to, id, value)| Op::Mint { caller, to, id, value }),
+        2 => (owner_mostly(), account(), batch())
+            .prop_map(|(caller, to, (ids, values))| Op::MintBatch { caller, to, ids, values }),
+        2 => (caller(), account(), id(), value())
+            .prop_map(|(caller, from, id, value)| Op::Burn { caller, from, id, value }),
+        1 => (caller(), batch()).prop_map(|(caller, (ids, values))| Op::BurnBatch { caller, ids, values }),
+        3 => (caller(), account(), account(), id(), value())
+            .prop_map(|(caller, from, to, id, value)| Op::Transfer { caller, from, to, id, value }),
+        2 => (caller(), account(), account(), batch())
+            .prop_map(|(caller, from, to, (ids, values))| Op::TransferBatch { caller, from, to, ids, values }),
+        2 => (caller(), account(), any::<bool>())
+            .prop_map(|(caller, operator, approved)| Op::Approve { caller, operator, approved }),
+        1 => owner_mostly().prop_map(|caller| Op::Pause { caller }),
+        1 => owner_mostly().prop_map(|caller| Op::Unpause { caller }),
+    ]
+}
+
+/// Everything the two tokens must agree on, for the accounts and IDs of a case.
+#[derive(Debug, PartialEq)]
+struct State {
+    balances: Vec<U256>,
+    supplies: Vec<U256>,
+    exists: Vec<bool>,
+    /// Between distinct callers only: approving yourself or the zero address is where the two
+    /// tokens deliberately differ.
+    approvals: Vec<bool>,
+    paused: bool,
+}
+
+/// Both tokens, deployed once and shared by every case.
+struct Tokens {
+    token: Deployment,
+    reference: Address,
+}
+
+/// Fresh accounts and IDs for one sequence, so that what earlier ones left doesn't carry over.
+struct Case<'a> {
+    tokens: &'a Tokens,
+    accounts: Vec<Account>,
+    ids: [U256; IDS],
+}
+
+impl<'a> Case<'a> {
+    /// Funds the new callers and unpauses the tokens if a failed case left them paused.
+    async fn new(tokens: &'a Tokens, first_id: u64) -> eyre::Result<Self> {
+        let token = &tokens.token;
+        if token.read(pausedCall {}).await?._0 {
+            token.send(&token.owner, unpauseCall {}).await?;
+        }
+        if token.read_at(tokens.reference, pausedCall {}).await?._0 {
+            ensure!(token.owner.try_send(tokens.reference, unpauseCall {}).await?.is_ok(), "can't unpause OzErc1155");
+        }
+
+        let mut accounts = Vec::with_capacity(CALLERS - 1);
+        for _ in 1..CALLERS {
+            accounts.push(token.account().await?);
+        }
+        let ids = std::array::from_fn(|index| U256::from(first_id + index as u64));
+        Ok(Self { tokens, accounts, ids })
+    }
+
+    fn caller(&self, index: usize) -> &Account {
+        match index {
+            0 => &self.tokens.token.owner,
+            _ => &self.accounts[index - 1],
+        }
+    }
+
+    fn address(&self, index: usize) -> Address {
+        if index == CALLERS {
+            Address::ZERO
+        } else {
+            self.caller(index).address
+        }
+    }
+
+    fn batch(&self, ids: &[usize], values: &[u64]) -> (Vec<U256>, Vec<U256>) {
+        (ids.iter().map(|&id| self.ids[id]).collect(), values.iter().map(|&value| U256::from(value)).collect())
+    }
+
+    /// Sends `op` to the Stylus token and then the reference, and returns what each did.
+    async fn execute(&self, op: &Op) -> eyre::Result<(Outcome, Outcome)> {
+        let (token, reference) = (self.tokens.token.address, self.tokens.reference);
+        let caller = self.caller(op.caller());
+        match op.clone() {
+            Op::Mint { to, id, value, .. } => {
+                let (to, id, amount) = (self.address(to), self.ids[id], U256::from(value));
+                self.both(caller, mintCall { to, id, amount, data: Bytes::new() }).await
+            }
+            Op::MintBatch { to, ids, values, .. } => {
+                let (ids, amounts) = self.batch(&ids, &values);
+                self.both(caller, mintBatchCall { to: self.address(to), ids, amounts, data: Bytes::new() }).await
+            }
+            Op::Burn { caller: index, from, id, value } => {
+                let (id, value) = (self.ids[id], U256::from(value));
+                // The Stylus token splits OpenZeppelin's `burn` into burning your own and `burnFrom`
+                let stylus = if from == index {
+                    caller.try_send(token, crate::IMy1155::burnCall { id, amount: value }).await?
+                } else {
+                    let burn = crate::IMy1155::burnFromCall { from: self.address(from), id, amount: value };
+                    caller.try_send(token, burn).await?
+                };
+                let burn = IOzErc1155::burnCall { account: self.address(from), id, value };
+                Ok((stylus, caller.try_send(reference, burn).await?))
+            }
+            Op::BurnBatch { ids, values, .. } => {
+                let (ids, values) = self.batch(&ids, &values);
+                let burn = crate::IMy1155::burnBatchCall { ids: ids.clone(), amounts: values.clone() };
+                let stylus = caller.try_send(token, burn).await?;
+                let burn = IOzErc1155::burnBatchCall { account: caller.address, ids, values };
+                Ok((stylus, caller.try_send(reference, burn).await?))
+            }
+            Op::Transfer { from, to, id, value, .. } => {
+                let call = safeTransferFromCall {
+                    from: self.address(from),
+                    to: self.address(to),
+                    id: self.ids[id],
+                    value: U256::from(value),
+                    data: Bytes::new(),
+                };
+                self.both(caller, call).await
+            }
+            Op::TransferBatch { from, to, ids, values, .. } => {
+                let (ids, values) = self.batch(&ids, &values);
+                let call = safeBatchTransferFromCall {
+                    from: self.address(from),
+                    to: self.address(to),
+                    ids,
+                    values,
+                    data: Bytes::new(),
+                };
+                self.both(caller, call).await
+            }
+            Op::Approve { operator, approved, .. } => {
+                self.both(caller, setApprovalForAllCall { operator: self.address(operator), approved }).await
+            }
+            Op::Pause { .. } => self.both(caller, pauseCall {}).await,
+            Op::Unpause { .. } => self.both(caller, unpauseCall {}).await,
+        }
+    }
+
+    /// Sends the same call to both tokens.
+    async fn both<C: SolCall + Clone>(&self, caller: &Account, call: C) -> eyre::Result<(Outcome, Outcome)> {
+        let stylus = caller.try_send(self.tokens.token.address, call.clone()).await?;
+        Ok((stylus, caller.try_send(self.tokens.reference, call).await?))
+    }
+
+    async fn state(&self, contract: Address) -> eyre::Result<State> {
+        let token = &self.tokens.token;
+        let (accounts, ids): (Vec<Address>, Vec<U256>) = (0..ACCOUNTS)
+            .flat_map(|account| self.ids.iter().map(move |&id| (self.address(account), id)))
+            .unzip();
+        let balances = token.read_at(contract, balanceOfBatchCall { accounts, ids }).await?._0;
+
+        let (mut supplies, mut exists) = (Vec::with_capacity(IDS), Vec::with_capacity(IDS));
+        for &id in &self.ids {
+            supplies.push(token.read_at(contract, totalSupplyCall { id }).await?._0);
+            exists.push(token.read_at(contract, existsCall { id }).await?._0);
+        }
+        let mut approvals = Vec::with_capacity(CALLERS * (CALLERS - 1));
+        for owner in 0..CALLERS {
+            for operator in (0..CALLERS).filter(|&operator| operator != owner) {
+                let call = isApprovedForAllCall { account: self.address(owner), operator: self.address(operator) };
+                approvals.push(token.read_at(contract, call).await?._0);
+            }
+        }
+        let paused = token.read_at(contract, pausedCall {}).await?._0;
+        Ok(State { balances, supplies, exists, approvals, paused })
+    }
+}
+
+/// Checks that the two tokens did the same with `op`, sent by `caller` while `paused` or not,
+/// apart from where the Stylus token deliberately departs from OpenZeppelin's, as the README lists.
+fn check_outcomes(
+    op: &Op,
+    caller: Address,
+    paused: bool,
+    stylus: &Outcome,
+    reference: &Outcome,
+) -> Result<(), TestCaseError> {
+    let operator = match op {
+        Op::Approve { operator, .. } => Some(*operator),
+        _ => None,
+    };
+    if operator == Some(op.caller()) {
+        // Approving yourself is rejected here and allowed by OpenZeppelin
+        prop_assert_eq!(stylus, &Err(ERC1155InvalidOperator { operator: caller }.abi_encode().into()));
+        prop_assert!(reference.is_ok(), "OzErc1155 rejected approving yourself: {:?}", reference);
+    } else if operator == Some(CALLERS) {
+        // And approving the zero address the other way around
+        prop_assert!(stylus.is_ok(), "approving the zero address reverted: {:?}", stylus);
+        let zero = ERC1155InvalidOperator { operator: Address::ZERO };
+        prop_assert_eq!(reference, &Err(zero.abi_encode().into()));
+    } else if paused && op.moves_tokens() {
+        // The pause is checked before anything else, where OpenZeppelin checks it last
+        prop_assert_eq!(stylus, &Err(EnforcedPause {}.abi_encode().into()), "{:?}", op);
+        prop_assert!(reference.is_err(), "OzErc1155 ran {:?} while paused", op);
+    } else {
+        match (stylus, reference) {
+            // Batches repeating an ID are checked against each ID's total, OpenZeppelin's entry by
+            // entry, so the two fail alike but report different amounts
+            (Err(revert), Err(expected))
+                if op.repeats_an_id() && revert.starts_with(&ERC1155InsufficientBalance::SELECTOR) =>
+            {
+                prop_assert_eq!(revert.get(..4), expected.get(..4), "{:?}", op);
+            }
+            _ => {
+                prop_assert_eq!(stylus, reference, "{:?}", op);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run(tokens: &Tokens, first_id: u64, ops: &[Op]) -> Result<(), TestCaseError> {
+    let fail = |error: eyre::Report| TestCaseError::fail(format!("{error:#}"));
+    let case = Case::new(tokens, first_id).await.map_err(fail)?;
+    let mut paused = false;
+    for op in ops {
+        let (stylus, reference) = case.execute(op).await.map_err(fail)?;
+        check_outcomes(op, case.address(op.caller()), paused, &stylus, &reference)?;
+
+        let state = case.state(tokens.token.address).await.map_err(fail)?;
+        prop_assert_eq!(&state, &case.state(tokens.reference).await.map_err(fail)?, "after {:?}", op);
+        paused = state.paused;
+    }
+    Ok(())
+}
+
+#[test]
+fn matches_openzeppelin() -> eyre::Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let tokens = runtime.block_on(async {
+        let token = Deployment::new().await?;
+        let reference = token.reference().await?;
+        eyre::Ok(Tokens { token, reference })
+    })?;
+
+    // Each case, shrinking included, gets IDs no earlier case touched
+    let next_id = Cell::new(1);
+    let mut runner = TestRunner::new(Config { cases: CASES, max_shrink_iters: 64, ..Config::default() });
+    runner
+        .run(&prop::collection::vec(op(), 1..MAX_OPS), |ops| {
+            let first_id = next_id.replace(next_id.get() + IDS as u64);
+            runtime.block_on(run(&tokens, first_id, &ops))
+        })
+        .map_err(|error| eyre!("{error}"))
+}
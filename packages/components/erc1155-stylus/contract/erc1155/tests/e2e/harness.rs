@@ -0,0 +1,197 @@
+//! Deploys the contract to a local Nitro dev node and sends it transactions over JSON-RPC.
+
+use std::{path::Path, process::Command};
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{
+    network::{EthereumWallet, ReceiptResponse, TransactionBuilder},
+    DynProvider, Provider, ProviderBuilder,
+};
+use alloy_rpc_types_eth::TransactionRequest;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{SolCall, SolValue};
+use eyre::{bail, ensure, eyre, WrapErr};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::IMy1155;
+
+/// RPC endpoint of `nitro-devnode`.
+const DEFAULT_RPC_URL: &str = "http://localhost:8547";
+/// The dev node's prefunded account.
+const DEFAULT_PRIVATE_KEY: &str = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9acb659";
+const BASE_URI: &str = "ipfs://cradle/";
+
+/// What a transaction did: succeeded, or reverted with this data.
+pub type Outcome = Result<(), Bytes>;
+
+/// Every test sends from the prefunded account, so they take turns to keep its nonce in order.
+static NODE: Mutex<()> = Mutex::const_new(());
+
+/// A freshly deployed and initialized contract, owned by the dev node's prefunded account.
+pub struct Deployment {
+    pub address: Address,
+    pub owner: Account,
+    rpc_url: String,
+    private_key: String,
+    _turn: MutexGuard<'static, ()>,
+}
+
+impl Deployment {
+    /// Deploys with `cargo stylus deploy`, reading `RPC_URL` and `PRIVATE_KEY` from the
+    /// environment and falling back to the dev node defaults.
+    pub async fn new() -> eyre::Result<Self> {
+        let turn = NODE.lock().await;
+        dotenv::dotenv().ok();
+        let rpc_url = std::env::var("RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.into());
+        let private_key = std::env::var("PRIVATE_KEY").unwrap_or_else(|_| DEFAULT_PRIVATE_KEY.into());
+
+        let owner = Account::new(&rpc_url, private_key.parse().wrap_err("invalid PRIVATE_KEY")?)?;
+        owner.provider.get_chain_id().await.wrap_err_with(|| format!("no node at {rpc_url}"))?;
+
+        let address = deploy(Path::new(env!("CARGO_MANIFEST_DIR")), &rpc_url, &private_key)?;
+        owner.send(address, IMy1155::initializeCall { base_uri: BASE_URI.into(), owner: owner.address }).await?;
+
+        Ok(Self { address, owner, rpc_url, private_key, _turn: turn })
+    }
+
+    /// Deploys one of the contracts in `packages/mocks`, like `erc1155-receiver`, and returns its
+    /// address.
+    pub fn mock(&self, name: &str) -> eyre::Result<Address> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../../mocks").join(name);
+        deploy(&dir, &self.rpc_url, &self.private_key)
+    }
+
+    /// Deploys `OzErc1155` from the benchmarks, OpenZeppelin's token with the same extensions,
+    /// owned by the same account, and returns its address. It's built with `forge build` if it
+    /// hasn't been, after which the benchmarks' OpenZeppelin sources must be installed.
+    pub async fn reference(&self) -> eyre::Result<Address> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../../benchmarks/solidity");
+        let artifact = dir.join("out/OzErc1155.sol/OzErc1155.json");
+        if !artifact.exists() {
+            let status = Command::new("forge")
+                .arg("build")
+                .current_dir(&dir)
+                .status()
+                .wrap_err("failed to run forge, is Foundry installed?")?;
+            ensure!(status.success(), "forge build failed");
+        }
+        let artifact: serde_json::Value = serde_json::from_slice(&std::fs::read(&artifact)?)?;
+        let bytecode = artifact["bytecode"]["object"]
+            .as_str()
+            .ok_or_else(|| eyre!("no bytecode in the OzErc1155 artifact"))?
+            .parse::<Bytes>()?;
+
+        let mut code = bytecode.to_vec();
+        code.extend((BASE_URI.to_string(), self.owner.address).abi_encode_params());
+        let receipt =
+            self.owner.provider.send_transaction(TransactionRequest::default().with_deploy_code(code)).await?;
+        let receipt = receipt.get_receipt().await?;
+        ensure!(receipt.status(), "deploying OzErc1155 reverted");
+        receipt.contract_address().ok_or_else(|| eyre!("no contract address in the OzErc1155 deployment receipt"))
+    }
+
+    /// A new account funded with 0.1 ETH for gas.
+    pub async fn account(&self) -> eyre::Result<Account> {
+        let account = Account::new(&self.rpc_url, PrivateKeySigner::random())?;
+        let funding =
+            TransactionRequest::default().with_to(account.address).with_value(U256::from(100_000_000_000_000_000u128));
+        let receipt = self.owner.provider.send_transaction(funding).await?.get_receipt().await?;
+        ensure!(receipt.status(), "funding {} failed", account.address);
+        Ok(account)
+    }
+
+    pub async fn send<C: SolCall>(&self, from: &Account, call: C) -> eyre::Result<()> {
+        from.send(self.address, call).await
+    }
+
+    /// Sends `call` expecting it to revert, and returns the revert data.
+    pub async fn revert<C: SolCall>(&self, from: &Account, call: C) -> eyre::Result<Bytes> {
+        let tx = TransactionRequest::default().with_to(self.address).with_input(call.abi_encode());
+        match from.provider.estimate_gas(&tx).await {
+            Ok(_) => bail!("{} didn't revert", C::SIGNATURE),
+            Err(error) => error
+                .as_error_resp()
+                .and_then(|payload| payload.as_revert_data())
+                .ok_or_else(|| eyre!("{} failed without revert data: {error}", C::SIGNATURE)),
+        }
+    }
+
+    pub async fn read<C: SolCall>(&self, call: C) -> eyre::Result<C::Return> {
+        self.read_at(self.address, call).await
+    }
+
+    /// Reads `call` from another contract, like the one `reference` deployed.
+    pub async fn read_at<C: SolCall>(&self, contract: Address, call: C) -> eyre::Result<C::Return> {
+        let tx = TransactionRequest::default().with_to(contract).with_input(call.abi_encode());
+        let output = self.owner.provider.call(&tx).await?;
+        Ok(C::abi_decode_returns(&output, true)?)
+    }
+}
+
+/// An externally owned account with a provider that signs its transactions.
+pub struct Account {
+    pub address: Address,
+    provider: DynProvider,
+}
+
+impl Account {
+    fn new(rpc_url: &str, signer: PrivateKeySigner) -> eyre::Result<Self> {
+        let address = signer.address();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(rpc_url.parse().wrap_err("invalid RPC_URL")?)
+            .erased();
+        Ok(Self { address, provider })
+    }
+
+    async fn send<C: SolCall>(&self, to: Address, call: C) -> eyre::Result<()> {
+        let tx = TransactionRequest::default().with_to(to).with_input(call.abi_encode());
+        let receipt = self.provider.send_transaction(tx).await?.get_receipt().await?;
+        ensure!(receipt.status(), "{} reverted in {}", C::SIGNATURE, receipt.transaction_hash());
+        Ok(())
+    }
+
+    /// Sends `call` to `to` unless it would revert, and returns whether it did. A transaction
+    /// that fails without revert data is an error.
+    pub async fn try_send<C: SolCall>(&self, to: Address, call: C) -> eyre::Result<Outcome> {
+        let tx = TransactionRequest::default().with_to(to).with_input(call.abi_encode());
+        if let Err(error) = self.provider.estimate_gas(&tx).await {
+            let revert = error.as_error_resp().and_then(|payload| payload.as_revert_data());
+            return revert.map(Err).ok_or_else(|| eyre!("{} failed without revert data: {error}", C::SIGNATURE));
+        }
+        let receipt = self.provider.send_transaction(tx).await?.get_receipt().await?;
+        ensure!(receipt.status(), "{} reverted in {} after estimating", C::SIGNATURE, receipt.transaction_hash());
+        Ok(Ok(()))
+    }
+}
+
+/// Runs `cargo stylus deploy` from the crate in `dir` and returns the deployed address.
+fn deploy(dir: &Path, rpc_url: &str, private_key: &str) -> eyre::Result<Address> {
+    let output = Command::new("cargo")
+        .args(["stylus", "deploy", "--no-verify", "--endpoint", rpc_url, "--private-key", private_key])
+        .current_dir(dir)
+        .output()
+        .wrap_err("failed to run cargo stylus, is cargo-stylus installed?")?;
+    let log =
+        strip_ansi(&format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)));
+    ensure!(output.status.success(), "cargo stylus deploy failed:\n{log}");
+
+    log.lines()
+        .find(|line| line.contains("deployed code at address"))
+        .and_then(|line| line.split_whitespace().find_map(|word| word.parse::<Address>().ok()))
+        .ok_or_else(|| eyre!("no contract address in cargo stylus output:\n{log}"))
+}
+
+/// Removes the terminal colour codes cargo-stylus wraps its output in.
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
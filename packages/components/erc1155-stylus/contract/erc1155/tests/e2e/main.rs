@@ -0,0 +1,167 @@
+//! End-to-end tests against a local Nitro dev node, run with `cargo test --features e2e`.
+//!
+//! Each test deploys its own copy of the contract with cargo-stylus, so the unit tests'
+//! TestVM is bypassed entirely and calls go through the real Stylus VM.
+
+mod differential;
+mod harness;
+
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::{sol, SolError};
+use cradle_pausable::EnforcedPause;
+use erc1155_stylus::erc1155::{ERC1155InsufficientBalance, ERC1155MissingApprovalForAll};
+use harness::Deployment;
+
+sol! {
+    interface IMy1155 {
+        function initialize(string base_uri, address owner) external;
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+        function mintBatch(address to, uint256[] ids, uint256[] amounts, bytes data) external;
+        function burn(uint256 id, uint256 amount) external;
+        function burnFrom(address from, uint256 id, uint256 amount) external;
+        function burnBatch(uint256[] ids, uint256[] amounts) external;
+        function pause() external;
+        function unpause() external;
+        function setApprovalForAll(address operator, bool approved) external;
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+        function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values, bytes data) external;
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function balanceOfBatch(address[] accounts, uint256[] ids) external view returns (uint256[]);
+        function isApprovedForAll(address account, address operator) external view returns (bool);
+        function totalSupply(uint256 id) external view returns (uint256);
+        function exists(uint256 id) external view returns (bool);
+        function paused() external view returns (bool);
+        function uri(uint256 id) external view returns (string);
+    }
+}
+
+use IMy1155::*;
+
+fn id(n: u64) -> U256 {
+    U256::from(n)
+}
+
+#[tokio::test]
+async fn mint_transfer_and_burn() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let alice = token.account().await?;
+    let bob = token.account().await?;
+
+    token.send(&token.owner, mintCall { to: alice.address, id: id(1), amount: id(100), data: Bytes::new() }).await?;
+    let transfer =
+        safeTransferFromCall { from: alice.address, to: bob.address, id: id(1), value: id(40), data: Bytes::new() };
+    token.send(&alice, transfer).await?;
+    token.send(&alice, burnCall { id: id(1), amount: id(10) }).await?;
+
+    assert_eq!(token.read(balanceOfCall { account: alice.address, id: id(1) }).await?._0, id(50));
+    assert_eq!(token.read(balanceOfCall { account: bob.address, id: id(1) }).await?._0, id(40));
+    assert_eq!(token.read(totalSupplyCall { id: id(1) }).await?._0, id(90));
+    assert_eq!(token.read(uriCall { id: id(1) }).await?._0, "ipfs://cradle/1.json");
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_mint_transfer_and_burn() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let alice = token.account().await?;
+    let bob = token.account().await?;
+
+    let mint = mintBatchCall {
+        to: alice.address,
+        ids: vec![id(1), id(2), id(3)],
+        amounts: vec![id(10), id(20), id(30)],
+        data: Bytes::new(),
+    };
+    token.send(&token.owner, mint).await?;
+    let transfer = safeBatchTransferFromCall {
+        from: alice.address,
+        to: bob.address,
+        ids: vec![id(1), id(3)],
+        values: vec![id(10), id(5)],
+        data: Bytes::new(),
+    };
+    token.send(&alice, transfer).await?;
+    token.send(&bob, burnBatchCall { ids: vec![id(3)], amounts: vec![id(5)] }).await?;
+
+    let balances = token
+        .read(balanceOfBatchCall {
+            accounts: vec![alice.address, alice.address, alice.address, bob.address, bob.address],
+            ids: vec![id(1), id(2), id(3), id(1), id(3)],
+        })
+        .await?
+        ._0;
+    assert_eq!(balances, vec![U256::ZERO, id(20), id(25), id(10), U256::ZERO]);
+    assert_eq!(token.read(totalSupplyCall { id: id(3) }).await?._0, id(25));
+    Ok(())
+}
+
+#[tokio::test]
+async fn mints_to_a_contract_recipient() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let receiver = token.mock("erc1155-receiver")?;
+
+    token.send(&token.owner, mintCall { to: receiver, id: id(1), amount: id(5), data: Bytes::new() }).await?;
+    assert_eq!(token.read(balanceOfCall { account: receiver, id: id(1) }).await?._0, id(5));
+    Ok(())
+}
+
+#[tokio::test]
+async fn operator_burns_after_approval() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let alice = token.account().await?;
+    let bob = token.account().await?;
+    token.send(&token.owner, mintCall { to: alice.address, id: id(1), amount: id(100), data: Bytes::new() }).await?;
+
+    let burn = burnFromCall { from: alice.address, id: id(1), amount: id(30) };
+    let revert = token.revert(&bob, burn.clone()).await?;
+    let expected = ERC1155MissingApprovalForAll { operator: bob.address, owner: alice.address };
+    assert_eq!(revert, expected.abi_encode());
+
+    token.send(&alice, setApprovalForAllCall { operator: bob.address, approved: true }).await?;
+    token.send(&bob, burn).await?;
+    assert_eq!(token.read(balanceOfCall { account: alice.address, id: id(1) }).await?._0, id(70));
+    Ok(())
+}
+
+#[tokio::test]
+async fn overdrawn_transfer_reverts_with_custom_error() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let alice = token.account().await?;
+    token.send(&token.owner, mintCall { to: alice.address, id: id(7), amount: id(5), data: Bytes::new() }).await?;
+
+    let transfer = safeTransferFromCall {
+        from: alice.address,
+        to: token.owner.address,
+        id: id(7),
+        value: id(6),
+        data: Bytes::new(),
+    };
+    let revert = token.revert(&alice, transfer).await?;
+    let expected = ERC1155InsufficientBalance { sender: alice.address, balance: id(5), needed: id(6), id: id(7) };
+    assert_eq!(revert, expected.abi_encode());
+    Ok(())
+}
+
+#[tokio::test]
+async fn pause_stops_transfers_until_unpaused() -> eyre::Result<()> {
+    let token = Deployment::new().await?;
+    let alice = token.account().await?;
+    token.send(&token.owner, mintCall { to: alice.address, id: id(1), amount: id(10), data: Bytes::new() }).await?;
+    token.send(&token.owner, pauseCall {}).await?;
+
+    let transfer = safeTransferFromCall {
+        from: alice.address,
+        to: token.owner.address,
+        id: id(1),
+        value: id(1),
+        data: Bytes::new(),
+    };
+    assert_eq!(token.revert(&alice, transfer.clone()).await?, EnforcedPause {}.abi_encode());
+    let burn = burnCall { id: id(1), amount: id(1) };
+    assert_eq!(token.revert(&alice, burn).await?, EnforcedPause {}.abi_encode());
+
+    token.send(&token.owner, unpauseCall {}).await?;
+    token.send(&alice, transfer).await?;
+    assert_eq!(token.read(balanceOfCall { account: alice.address, id: id(1) }).await?._0, id(9));
+    Ok(())
+}
@@ -64,8 +64,8 @@ const ERC1155_ABI = [
   "function balanceOfBatch(address[] memory accounts, uint256[] memory ids) external view returns (uint256[] memory)",
   "function setApprovalForAll(address operator, bool approved) external",
   "function isApprovedForAll(address account, address operator) external view returns (bool)",
-  "function safeTransferFrom(address from, address to, uint256 id, uint256 value, uint8[] memory data) external",
-  "function safeBatchTransferFrom(address from, address to, uint256[] memory ids, uint256[] memory values, uint8[] memory data) external",
+  "function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes calldata data) external",
+  "function safeBatchTransferFrom(address from, address to, uint256[] memory ids, uint256[] memory values, bytes calldata data) external",
 ];
 
 // Network-specific default contract addresses (only for networks where contracts are deployed)
@@ -417,7 +417,7 @@ export function ERC1155InteractionPanel({
       const contract = await getWriteContract();
       if (!contract || !transferFrom || !transferTo || !transferTokenId || !transferAmount) return;
       handleTransaction(
-        () => contract.safeTransferFrom(transferFrom, transferTo, transferTokenId, transferAmount, []),
+        () => contract.safeTransferFrom(transferFrom, transferTo, transferTokenId, transferAmount, '0x'),
         `Transferred ${transferAmount} of ID #${transferTokenId}!`
       );
     } catch (error: any) {
@@ -435,7 +435,7 @@ export function ERC1155InteractionPanel({
       const ids = batchTokenIds.split(',').map(s => s.trim());
       const amounts = batchAmounts.split(',').map(s => s.trim());
       handleTransaction(
-        () => contract.safeBatchTransferFrom(batchTransferFrom, batchTransferTo, ids, amounts, []),
+        () => contract.safeBatchTransferFrom(batchTransferFrom, batchTransferTo, ids, amounts, '0x'),
         `Batch transfer completed!`
       );
     } catch (error: any) {
@@ -186,8 +186,7 @@ export function useERC1155Interactions(options: UseERC1155InteractionsOptions):
   const safeTransferFrom = useCallback(async (
     from: Address, to: Address, id: bigint, amount: bigint
   ): Promise<Hash> => {
-    // Convert empty data to uint8[] (empty array)
-    const data: readonly number[] = [];
+    const data = '0x';
     const hash = await executeTransaction('safeTransferFrom', [from, to, id, amount, data]);
     return hash;
   }, [executeTransaction]);
@@ -196,8 +195,7 @@ export function useERC1155Interactions(options: UseERC1155InteractionsOptions):
   const safeBatchTransferFrom = useCallback(async (
     from: Address, to: Address, ids: bigint[], amounts: bigint[]
   ): Promise<Hash> => {
-    // Convert empty data to uint8[] (empty array)
-    const data: readonly number[] = [];
+    const data = '0x';
     const hash = await executeTransaction('safeBatchTransferFrom', [from, to, ids, amounts, data]);
     return hash;
   }, [executeTransaction]);
@@ -19,7 +19,7 @@ export async function getContractInfo(
 
   const [owner, paused] = await Promise.all([
     contract.owner(),
-    contract.isPaused(),
+    contract.paused(),
   ]);
 
   // Get base URI by querying for token ID 0
@@ -0,0 +1,327 @@
+//! Wrapper of third-party ERC-1155 tokens into a Cradle ERC-1155
+//!
+//! Escrows tokens of collections the owner allows and mints the same amount of a mirrored ID in
+//! an `erc1155-stylus` deployment, where the wrapper holds the bridge role. The mirrored ID of
+//! token `id` of `collection` is `uint256(keccak256(abi.encode(collection, id)))`, so each
+//! collection keeps its own IDs and the mirrored tokens pick up the Cradle token's royalties,
+//! locking and staking without the collection migrating. Unwrapping burns mirrored tokens and
+//! releases the originals.
+//!
+//! Holders wrap with `wrap` after approving the wrapper on the collection, or by transferring
+//! tokens to the wrapper with `safeTransferFrom`, in which case the mirrored tokens go to the
+//! sender, or to the address the transfer's data encodes.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount, IERC173, IERC5313};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC1155BatchReceived`.
+const ERC1155_BATCH_RECEIVED: u32 = 0xbc197c81;
+
+/// ERC-1155's receiver hooks, for `supportsInterface`.
+pub const IERC1155_RECEIVER: Interface = interface!(
+    "onERC1155Received"(Address, Address, U256, U256, Bytes),
+    "onERC1155BatchReceived"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc1155Wrapper {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Allows collections
+        #[borrow]
+        Ownable ownable;
+        /// `erc1155-stylus` deployment minting the mirrored IDs
+        address token;
+        /// Collections that can be wrapped
+        mapping(address => bool) collections;
+        /// Collection and ID each mirrored ID wraps, zero for IDs never wrapped
+        mapping(uint256 => Origin) origins;
+        /// Amount of each collection's IDs in escrow
+        mapping(address => mapping(uint256 => uint256)) escrowed;
+    }
+
+    pub struct Origin {
+        address collection;
+        uint256 id;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    interface ICradle1155 {
+        function crosschainMint(address to, uint256 id, uint256 amount) external;
+        function crosschainBurn(address from, uint256 id, uint256 amount) external;
+    }
+
+    event CollectionSet(address indexed collection, bool allowed);
+    event Wrapped(address indexed collection, uint256 indexed id, address indexed to, uint256 wrappedId, uint256 amount);
+    event Unwrapped(address indexed collection, uint256 indexed id, address indexed to, uint256 wrappedId, uint256 amount);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // The owner hasn't allowed the collection
+    error CollectionNotAllowed(address collection);
+    // The ID wasn't minted by wrapping
+    error UnknownWrappedId(uint256 wrappedId);
+    // Amount is zero
+    error ZeroAmount();
+    // Less of the original token is in escrow than the amount unwrapped
+    error InsufficientEscrow(uint256 wrappedId, uint256 escrowed, uint256 amount);
+    // A call to the collection or the token reverted, with its revert data
+    error WrapperCallFailed(address target, bytes returnData);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum WrapperError {
+    AlreadyInitialized(AlreadyInitialized),
+    CollectionNotAllowed(CollectionNotAllowed),
+    UnknownWrappedId(UnknownWrappedId),
+    ZeroAmount(ZeroAmount),
+    InsufficientEscrow(InsufficientEscrow),
+    CallFailed(WrapperCallFailed),
+    OwnableUnauthorizedAccount(OwnableUnauthorizedAccount),
+    OwnableInvalidOwner(OwnableInvalidOwner),
+}
+
+impl From<OwnableError> for WrapperError {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => WrapperError::OwnableUnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => WrapperError::OwnableInvalidOwner(e),
+        }
+    }
+}
+
+/// The mirrored ID of token `id` of `collection`.
+pub fn wrapped_id_of(collection: Address, id: U256) -> U256 {
+    U256::from_be_bytes(keccak256((collection, id).abi_encode()).0)
+}
+
+/// Context of the calls to collections and the Cradle token, which builds with the SDK's
+/// `reentrant` feature, unlike `Call::new`.
+struct WrapperCall;
+
+impl calls::CallContext for WrapperCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a collection's transfer calls back into the wrapper's receiver hooks
+unsafe impl calls::MutatingCallContext for WrapperCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl Erc1155Wrapper {
+    /// Calls `target` with `calldata`, failing with its revert data if it reverts.
+    fn call(&mut self, target: Address, calldata: Vec<u8>) -> Result<(), WrapperError> {
+        self.vm()
+            .call(&WrapperCall, target, &calldata)
+            .map(|_| ())
+            .map_err(|error| {
+                WrapperError::CallFailed(WrapperCallFailed { target, returnData: Vec::<u8>::from(error).into() })
+            })
+    }
+
+    fn require_collection(&self, collection: Address) -> Result<(), WrapperError> {
+        if !self.collections.get(collection) {
+            return Err(WrapperError::CollectionNotAllowed(CollectionNotAllowed { collection }));
+        }
+        Ok(())
+    }
+
+    /// Records `amount` of `collection`'s `id` as escrowed and mints its mirror to `to`.
+    fn mint_wrapped(&mut self, collection: Address, id: U256, amount: U256, to: Address) -> Result<(), WrapperError> {
+        if amount.is_zero() {
+            return Err(WrapperError::ZeroAmount(ZeroAmount {}));
+        }
+        let wrapped_id = wrapped_id_of(collection, id);
+        let mut origin = self.origins.setter(wrapped_id);
+        origin.collection.set(collection);
+        origin.id.set(id);
+        let escrowed = self.escrowed.get(collection).get(id);
+        self.escrowed.setter(collection).insert(id, escrowed + amount);
+
+        let mint = ICradle1155::crosschainMintCall { to, id: wrapped_id, amount };
+        self.call(self.token.get(), mint.abi_encode())?;
+        log(self.vm(), Wrapped { collection, id, to, wrappedId: wrapped_id, amount });
+        Ok(())
+    }
+
+    /// Mints the mirrors of tokens `operator` sent to the wrapper outside `wrap`, to `from` or the
+    /// address `data` encodes. Tokens `wrap` pulls are minted by `wrap` itself.
+    fn receive(
+        &mut self,
+        operator: Address,
+        from: Address,
+        ids: &[U256],
+        values: &[U256],
+        data: &[u8],
+    ) -> Result<(), WrapperError> {
+        if operator == self.vm().contract_address() {
+            return Ok(());
+        }
+        let collection = self.vm().msg_sender();
+        self.require_collection(collection)?;
+        let to = Address::abi_decode(data, true).unwrap_or(from);
+        for (&id, &value) in ids.iter().zip(values) {
+            self.mint_wrapped(collection, id, value, to)?;
+        }
+        Ok(())
+    }
+}
+
+#[public]
+#[inherit(Ownable)]
+impl Erc1155Wrapper {
+    /// Sets the owner and the `erc1155-stylus` deployment minting the mirrored IDs, which must
+    /// grant the wrapper its bridge role. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, token: Address) -> Result<(), WrapperError> {
+        if self.initialized.get() {
+            return Err(WrapperError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.ownable.initialize(owner)?;
+        self.token.set(token);
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// Allows `collection` to be wrapped, or stops new wraps of it (owner only). Tokens already
+    /// wrapped can always be unwrapped.
+    pub fn set_collection(&mut self, collection: Address, allowed: bool) -> Result<(), WrapperError> {
+        self.ownable.require_owner()?;
+        if allowed && collection == self.token.get() {
+            return Err(WrapperError::CollectionNotAllowed(CollectionNotAllowed { collection }));
+        }
+        self.collections.insert(collection, allowed);
+        log(self.vm(), CollectionSet { collection, allowed });
+        Ok(())
+    }
+
+    /// Escrows `amount` of the caller's `id` of `collection` and mints as much of its mirrored ID
+    /// to `to`. Requires `setApprovalForAll(wrapper, true)` on the collection.
+    pub fn wrap(&mut self, collection: Address, id: U256, amount: U256, to: Address) -> Result<U256, WrapperError> {
+        self.require_collection(collection)?;
+        let this = self.vm().contract_address();
+        let pull = IERC1155::safeTransferFromCall {
+            from: self.vm().msg_sender(),
+            to: this,
+            id,
+            value: amount,
+            data: Vec::new().into(),
+        };
+        self.call(collection, pull.abi_encode())?;
+        self.mint_wrapped(collection, id, amount, to)?;
+        Ok(wrapped_id_of(collection, id))
+    }
+
+    /// Burns `amount` of the caller's `wrapped_id` and releases as much of the original token to
+    /// `to`.
+    pub fn unwrap(&mut self, wrapped_id: U256, amount: U256, to: Address) -> Result<(), WrapperError> {
+        let (collection, id) = self.origin_of(wrapped_id);
+        if collection.is_zero() {
+            return Err(WrapperError::UnknownWrappedId(UnknownWrappedId { wrappedId: wrapped_id }));
+        }
+        if amount.is_zero() {
+            return Err(WrapperError::ZeroAmount(ZeroAmount {}));
+        }
+        let escrowed = self.escrowed.get(collection).get(id);
+        if amount > escrowed {
+            let error = InsufficientEscrow { wrappedId: wrapped_id, escrowed, amount };
+            return Err(WrapperError::InsufficientEscrow(error));
+        }
+        let burn = ICradle1155::crosschainBurnCall { from: self.vm().msg_sender(), id: wrapped_id, amount };
+        self.call(self.token.get(), burn.abi_encode())?;
+        self.escrowed.setter(collection).insert(id, escrowed - amount);
+
+        let this = self.vm().contract_address();
+        let release = IERC1155::safeTransferFromCall { from: this, to, id, value: amount, data: Vec::new().into() };
+        self.call(collection, release.abi_encode())?;
+        log(self.vm(), Unwrapped { collection, id, to, wrappedId: wrapped_id, amount });
+        Ok(())
+    }
+
+    /// Mirrored ID of token `id` of `collection`, whether or not it was wrapped yet.
+    pub fn wrapped_id(&self, collection: Address, id: U256) -> U256 {
+        wrapped_id_of(collection, id)
+    }
+
+    /// Collection and ID `wrapped_id` mirrors, zeros for IDs never wrapped.
+    pub fn origin_of(&self, wrapped_id: U256) -> (Address, U256) {
+        let origin = self.origins.get(wrapped_id);
+        (origin.collection.get(), origin.id.get())
+    }
+
+    /// Amount of `collection`'s `id` in escrow, the supply of its mirrored ID unless the token's
+    /// owner mints the ID too.
+    pub fn escrowed(&self, collection: Address, id: U256) -> U256 {
+        self.escrowed.get(collection).get(id)
+    }
+
+    /// Whether `collection` can be wrapped.
+    pub fn is_collection_allowed(&self, collection: Address) -> bool {
+        self.collections.get(collection)
+    }
+
+    /// `erc1155-stylus` deployment minting the mirrored IDs.
+    pub fn token(&self) -> Address {
+        self.token.get()
+    }
+
+    /// Wraps tokens sent to the wrapper by an allowed collection and accepts those `wrap` pulls.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, WrapperError> {
+        self.receive(operator, from, &[id], &[value], &data)?;
+        Ok(FixedBytes(ERC1155_RECEIVED.to_be_bytes()))
+    }
+
+    /// `onERC1155Received`, for batch transfers.
+    #[selector(name = "onERC1155BatchReceived")]
+    pub fn on_erc1155_batch_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, WrapperError> {
+        self.receive(operator, from, &ids, &values, &data)?;
+        Ok(FixedBytes(ERC1155_BATCH_RECEIVED.to_be_bytes()))
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC1155_RECEIVER, IERC173, IERC5313])
+    }
+}
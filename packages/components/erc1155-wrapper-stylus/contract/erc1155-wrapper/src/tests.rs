@@ -0,0 +1,119 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const TOKEN: Address = address!("0000000000000000000000000000000000007043");
+const COLLECTION: Address = address!("00000000000000000000000000000000000c011e");
+
+/// A wrapper minting into `TOKEN` with `COLLECTION` allowed.
+fn setup() -> (TestVM, Erc1155Wrapper) {
+    let (vm, mut contract) = deploy::<Erc1155Wrapper>(OWNER);
+    ok(contract.initialize(OWNER, TOKEN));
+    ok(contract.set_collection(COLLECTION, true));
+    (vm, contract)
+}
+
+/// Mocks `TOKEN` minting or burning `amount` of `collection`'s `id` mirror for `account`.
+fn mock_token(vm: &TestVM, mint: bool, account: Address, id: U256, amount: U256) {
+    let id = wrapped_id_of(COLLECTION, id);
+    let calldata = if mint {
+        ICradle1155::crosschainMintCall { to: account, id, amount }.abi_encode()
+    } else {
+        ICradle1155::crosschainBurnCall { from: account, id, amount }.abi_encode()
+    };
+    vm.mock_call(TOKEN, calldata, Ok(Vec::new()));
+}
+
+fn mock_transfer(vm: &TestVM, from: Address, to: Address, id: U256, value: U256) {
+    let transfer = IERC1155::safeTransferFromCall { from, to, id, value, data: Vec::new().into() };
+    vm.mock_call(COLLECTION, transfer.abi_encode(), Ok(Vec::new()));
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IERC1155_RECEIVER.id(), 0x4e2312e0);
+    assert_eq!(ERC1155_RECEIVED ^ ERC1155_BATCH_RECEIVED, IERC1155_RECEIVER.id());
+    let expected = keccak256([&[0u8; 12][..], COLLECTION.as_slice(), &n(7).to_be_bytes::<32>()[..]].concat());
+    assert_eq!(wrapped_id_of(COLLECTION, n(7)), U256::from_be_bytes(expected.0));
+}
+
+#[test]
+fn wrapping_escrows_the_original_and_unwrapping_releases_it() {
+    let (vm, mut contract) = setup();
+    let this = vm.contract_address();
+    let wrapped = wrapped_id_of(COLLECTION, n(7));
+    vm.set_sender(ALICE);
+    mock_transfer(&vm, ALICE, this, n(7), n(5));
+    mock_token(&vm, true, BOB, n(7), n(5));
+    assert_eq!(ok(contract.wrap(COLLECTION, n(7), n(5), BOB)), wrapped);
+    assert_eq!((contract.origin_of(wrapped), contract.escrowed(COLLECTION, n(7))), ((COLLECTION, n(7)), n(5)));
+    let event = &emitted::<Wrapped>(&vm)[0];
+    assert_eq!((event.collection, event.id, event.to, event.wrappedId), (COLLECTION, n(7), BOB, wrapped));
+
+    vm.set_sender(BOB);
+    let result = contract.unwrap(wrapped, n(6), BOB);
+    assert!(matches!(result, Err(WrapperError::InsufficientEscrow(e)) if e.escrowed == n(5)));
+    mock_token(&vm, false, BOB, n(7), n(2));
+    mock_transfer(&vm, this, ALICE, n(7), n(2));
+    ok(contract.unwrap(wrapped, n(2), ALICE));
+    assert_eq!(contract.escrowed(COLLECTION, n(7)), n(3));
+    assert_eq!(emitted::<Unwrapped>(&vm)[0].amount, n(2));
+
+    let result = contract.unwrap(n(7), n(1), BOB);
+    assert!(matches!(result, Err(WrapperError::UnknownWrappedId(e)) if e.wrappedId == n(7)));
+}
+
+#[test]
+fn tokens_sent_to_the_wrapper_are_wrapped_for_the_sender() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(COLLECTION);
+    mock_token(&vm, true, ALICE, n(1), n(2));
+    mock_token(&vm, true, ALICE, n(2), n(3));
+    let (ids, values) = (vec![n(1), n(2)], vec![n(2), n(3)]);
+    let answer = ok(contract.on_erc1155_batch_received(ALICE, ALICE, ids, values, Vec::new().into()));
+    assert_eq!(answer, FixedBytes(ERC1155_BATCH_RECEIVED.to_be_bytes()));
+    assert_eq!(contract.escrowed(COLLECTION, n(2)), n(3));
+
+    // Data encoding an address sends the mirrored tokens there
+    mock_token(&vm, true, BOB, n(1), n(1));
+    ok(contract.on_erc1155_received(ALICE, ALICE, n(1), n(1), BOB.abi_encode().into()));
+    assert_eq!(emitted::<Wrapped>(&vm)[2].to, BOB);
+
+    // `wrap` mints for the tokens it pulls itself
+    let this = vm.contract_address();
+    ok(contract.on_erc1155_received(this, ALICE, n(1), n(9), Vec::new().into()));
+    assert_eq!(contract.escrowed(COLLECTION, n(1)), n(3));
+
+    vm.set_sender(BOB);
+    let result = contract.on_erc1155_received(BOB, BOB, n(1), n(1), Vec::new().into());
+    assert!(matches!(result, Err(WrapperError::CollectionNotAllowed(e)) if e.collection == BOB));
+}
+
+#[test]
+fn failed_calls_surface_the_revert_data() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(OWNER);
+    let result = contract.set_collection(TOKEN, true);
+    assert!(matches!(result, Err(WrapperError::CollectionNotAllowed(_))));
+
+    vm.set_sender(ALICE);
+    let pull = IERC1155::safeTransferFromCall {
+        from: ALICE,
+        to: vm.contract_address(),
+        id: n(7),
+        value: n(5),
+        data: Vec::new().into(),
+    };
+    vm.mock_call(COLLECTION, pull.abi_encode(), Err(b"not approved".to_vec()));
+    let result = contract.wrap(COLLECTION, n(7), n(5), ALICE);
+    assert!(matches!(
+        result,
+        Err(WrapperError::CallFailed(e)) if e.target == COLLECTION && e.returnData.as_ref() == b"not approved"
+    ));
+    let result = contract.wrap(ALICE, n(7), n(5), ALICE);
+    assert!(matches!(result, Err(WrapperError::CollectionNotAllowed(_))));
+}
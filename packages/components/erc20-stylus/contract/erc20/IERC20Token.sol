@@ -0,0 +1,144 @@
+/**
+ * Generated from the erc20-stylus contract, do not edit. To regenerate, run in contract/erc20:
+ *   cargo run --features export-abi -- interface > IERC20Token.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface IERC20Token {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event CrosschainMint(address indexed to, uint256 amount, address indexed sender);
+    event CrosschainBurn(address indexed from, uint256 amount, address indexed sender);
+    event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce);
+    event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce);
+    event ArbitrumGatewaySet(address gateway, address router, address counterpart);
+    event TokenRegisteredOnL2(address indexed l2Token, address indexed gateway);
+    event MinterConfigured(address indexed minter, uint256 allowance);
+    event MinterRemoved(address indexed minter);
+    event ERC20Recovered(address indexed token, address indexed to, uint256 amount);
+    event AutoBurnSet(uint256 burnBps);
+    event AutoBurned(address indexed from, address indexed to, uint256 amount);
+    event BuybackConfigured(address treasury, address pair, address weth);
+    event BuybackAndBurn(uint256 spent, uint256 burned);
+    event LaunchGuardArmed(uint256 maxBuy, uint256 maxWallet, uint64 cooldownBlocks);
+    event TradingEnabled();
+    event LaunchPairSet(address indexed pair, bool isPair);
+    event LaunchExemptionSet(address indexed account, bool exempt);
+    event LaunchGuardRenounced();
+    event DividendFeeSet(uint256 feeBps);
+    event DividendsDistributed(address indexed from, address indexed to, uint256 amount);
+    event DividendWithdrawn(address indexed account, uint256 amount);
+    event DividendExclusionSet(address indexed account, bool excluded);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole);
+
+    error AlreadyInitialized();
+    error InsufficientBalance(address from, uint256 have, uint256 want);
+    error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want);
+    error AuthorizationNotYetValid(uint256 validAfter);
+    error AuthorizationExpired(uint256 validBefore);
+    error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce);
+    error InvalidAuthorizationSignature(address authorizer);
+    error InvalidAuthorizationCaller(address caller, address payee);
+    error ArbitrumGatewayNotSet();
+    error NotExpectedCall();
+    error UnauthorizedGateway(address caller);
+    error IncorrectRegistrationValue(uint256 expected, uint256 received);
+    error GatewayCallFailed(address target);
+    error NotMinter(address caller);
+    error MinterAllowanceExceeded(address minter, uint256 allowance, uint256 needed);
+    error ERC20RecoveryFailed(address token);
+    error InvalidBurnRate(uint256 burnBps);
+    error BuybackNotConfigured();
+    error InvalidBuybackPair(address pair);
+    error BuybackCallFailed(address target);
+    error BuybackBelowMinimum(uint256 minimum, uint256 received);
+    error LaunchGuardIsRenounced();
+    error TradingNotEnabled();
+    error MaxBuyExceeded(uint256 maxBuy, uint256 amount);
+    error MaxWalletExceeded(address account, uint256 maxWallet, uint256 balance);
+    error TradeCooldown(address account, uint64 availableAt);
+    error PrecompileCallFailed(address precompile);
+    error DividendsDisabled();
+    error InvalidDividendFee(uint256 feeBps);
+    error OwnableUnauthorizedAccount(address account);
+    error OwnableInvalidOwner(address owner);
+    error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+    error AccessControlBadConfirmation();
+    error AccessControlMemberOutOfBounds(bytes32 role, uint256 index);
+
+    function name() external pure returns (string memory);
+    function symbol() external pure returns (string memory);
+    function decimals() external pure returns (uint8);
+    function totalSupply() external view returns (uint256);
+    function balanceOf(address owner) external view returns (uint256);
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+    function approve(address spender, uint256 value) external returns (bool);
+    function allowance(address owner, address spender) external view returns (uint256);
+    function increaseAllowance(address spender, uint256 added_value) external returns (bool);
+    function decreaseAllowance(address spender, uint256 subtracted_value) external returns (bool);
+    function owner() external view returns (address);
+    function transferOwnership(address new_owner) external;
+    function renounceOwnership() external;
+    function hasRole(bytes32 role, address account) external view returns (bool);
+    function getRoleAdmin(bytes32 role) external view returns (bytes32);
+    function grantRole(bytes32 role, address account) external;
+    function revokeRole(bytes32 role, address account) external;
+    function renounceRole(bytes32 role, address caller_confirmation) external;
+    function getRoleMember(bytes32 role, uint256 index) external view returns (address);
+    function getRoleMemberCount(bytes32 role) external view returns (uint256);
+    function DEFAULT_ADMIN_ROLE() external view returns (bytes32);
+    function initialize(address admin) external;
+    function mint(uint256 value) external;
+    function mintTo(address to, uint256 value) external;
+    function burn(uint256 value) external;
+    function crosschainMint(address to, uint256 amount) external;
+    function crosschainBurn(address from, uint256 amount) external;
+    function configureMinter(address minter, uint256 allowance) external;
+    function removeMinter(address minter) external;
+    function isMinter(address account) external view returns (bool);
+    function minterAllowance(address minter) external view returns (uint256);
+    function mintWithinAllowance(address to, uint256 amount) external;
+    function setArbitrumGateway(address gateway, address router, address counterpart) external;
+    function arbitrumGateway() external view returns (address, address, address);
+    function isArbitrumEnabled() external view returns (uint8);
+    function registerTokenOnL2(address l2_custom_token, uint256 max_submission_cost_for_custom_gateway, uint256 max_submission_cost_for_router, uint256 max_gas_for_custom_gateway, uint256 max_gas_for_router, uint256 gas_price_bid, uint256 value_for_gateway, uint256 value_for_router, address credit_back_address) external payable;
+    function bridgeMint(address account, uint256 amount) external;
+    function bridgeBurn(address account, uint256 amount) external;
+    function l1Address() external view returns (address);
+    function recoverErc20(address token, address to, uint256 amount) external;
+    function setAutoBurn(uint256 burn_bps) external;
+    function autoBurnBps() external view returns (uint256);
+    function configureBuyback(address treasury, address pair, address weth) external;
+    function buybackConfig() external view returns (address, address, address);
+    function buybackAndBurn(uint256 amount, uint256 min_burned) external returns (uint256);
+    function armLaunchGuard(uint256 max_buy, uint256 max_wallet, uint64 cooldown_blocks) external;
+    function enableTrading() external;
+    function setLaunchPair(address pair, bool is_pair) external;
+    function setLaunchExempt(address account, bool exempt) external;
+    function renounceLaunchGuard() external;
+    function launchGuard() external view returns (bool, bool, bool, uint256, uint256, uint64);
+    function launchStatus(address account) external view returns (bool, bool);
+    function setDividendFee(uint256 fee_bps) external;
+    function dividendFeeBps() external view returns (uint256);
+    function setDividendExcluded(address account, bool excluded) external;
+    function isDividendExcluded(address account) external view returns (bool);
+    function withdrawDividend() external returns (uint256);
+    function withdrawableDividendOf(address account) external view returns (uint256);
+    function withdrawnDividendOf(address account) external view returns (uint256);
+    function dividendTotals() external view returns (uint256, uint256);
+    function transferWithAuthorization(address from, address to, uint256 value, uint256 valid_after, uint256 valid_before, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external;
+    function receiveWithAuthorization(address from, address to, uint256 value, uint256 valid_after, uint256 valid_before, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external;
+    function cancelAuthorization(address authorizer, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external;
+    function authorizationState(address authorizer, bytes32 nonce) external view returns (bool);
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+    function BRIDGE_ROLE() external view returns (bytes32);
+    function MASTER_MINTER_ROLE() external view returns (bytes32);
+    function buildMetadata() external view returns (bytes32, string memory);
+    function supportsInterface(bytes4 interface_id) external view returns (bool);
+}
@@ -0,0 +1,3 @@
+fn main() {
+    cradle_build_info::emit().expect("failed to hash the contract sources");
+}
@@ -0,0 +1,123 @@
+//! EIP-3009 transfers with authorization
+//!
+//! A holder signs an EIP-712 `TransferWithAuthorization` or `ReceiveWithAuthorization` off-chain
+//! and a relayer, such as a payment processor, submits it, so the funds move in one transaction
+//! the holder doesn't pay gas for. Authorizations carry a random 32-byte nonce rather than a
+//! sequential one, so several can be outstanding and submitted in any order, and a holder revokes
+//! one that hasn't been submitted with `cancelAuthorization`.
+//!
+//! `receiveWithAuthorization` can only be submitted by the payee, so a contract that pulls a
+//! payment can't have the authorization front-run into a plain transfer it doesn't notice.
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::{erc20::Erc20Params, SuperPositionToken, SuperPositionTokenParams};
+
+/// Version in the EIP-712 domain, whose name is the token's.
+pub const DOMAIN_VERSION: &str = "1";
+/// `keccak256("TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)")`
+pub const TRANSFER_WITH_AUTHORIZATION_TYPEHASH: B256 =
+    b256!("7c7c6cdb67a18743f49ec6fa9b35f50d52ed05cbed4cc592e13b44501c1a2267");
+/// `keccak256("ReceiveWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)")`
+pub const RECEIVE_WITH_AUTHORIZATION_TYPEHASH: B256 =
+    b256!("d099cc98ef71107a616c4f0f941f04c322d8e254fe26b3c6668db87aae413de8");
+/// `keccak256("CancelAuthorization(address authorizer,bytes32 nonce)")`
+pub const CANCEL_AUTHORIZATION_TYPEHASH: B256 =
+    b256!("158b0a9edf7a828aad02f63cd515c68ef2f50ba807396f6d12842833a1597429");
+
+// EIP-3009 events and errors
+sol! {
+    event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce);
+    event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce);
+
+    // The authorization can't be used before `validAfter`
+    error AuthorizationNotYetValid(uint256 validAfter);
+    // The authorization could only be used before `validBefore`
+    error AuthorizationExpired(uint256 validBefore);
+    // The nonce was already used or cancelled
+    error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce);
+    error InvalidAuthorizationSignature(address authorizer);
+    // Only the payee can submit a `ReceiveWithAuthorization`
+    error InvalidAuthorizationCaller(address caller, address payee);
+}
+
+/// The fields an authorization to move tokens is signed over, besides its type.
+pub struct Authorization {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub valid_after: U256,
+    pub valid_before: U256,
+    pub nonce: B256,
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// EIP-712 domain separator the authorizations are signed under.
+    pub(crate) fn authorization_domain_separator(&self) -> B256 {
+        cradle_crypto::domain_separator(self.vm(), SuperPositionTokenParams::NAME, DOMAIN_VERSION)
+    }
+
+    /// Checks that `authorization` of type `typehash` is valid now and signed by its sender, and
+    /// consumes its nonce.
+    pub(crate) fn use_authorization(
+        &mut self,
+        typehash: B256,
+        authorization: &Authorization,
+        signature: (u8, B256, B256),
+    ) -> Result<(), Vec<u8>> {
+        let now = U256::from(self.vm().block_timestamp());
+        if now <= authorization.valid_after {
+            return Err(AuthorizationNotYetValid { validAfter: authorization.valid_after }.abi_encode());
+        }
+        if now >= authorization.valid_before {
+            return Err(AuthorizationExpired { validBefore: authorization.valid_before }.abi_encode());
+        }
+        let struct_hash = cradle_crypto::hash_struct(
+            self.vm(),
+            typehash,
+            (
+                authorization.from,
+                authorization.to,
+                authorization.value,
+                authorization.valid_after,
+                authorization.valid_before,
+                authorization.nonce,
+            ),
+        );
+        self.check_authorization_signature(authorization.from, struct_hash, signature)?;
+        self.consume_authorization_nonce(authorization.from, authorization.nonce)?;
+
+        log(self.vm(), AuthorizationUsed { authorizer: authorization.from, nonce: authorization.nonce });
+        Ok(())
+    }
+
+    /// Fails unless `authorizer` signed the message with `struct_hash`. Contract accounts sign
+    /// through ERC-1271.
+    pub(crate) fn check_authorization_signature(
+        &self,
+        authorizer: Address,
+        struct_hash: B256,
+        (v, r, s): (u8, B256, B256),
+    ) -> Result<(), Vec<u8>> {
+        let digest = cradle_crypto::hash_typed_data(self.vm(), self.authorization_domain_separator(), struct_hash);
+        let mut signature = Vec::with_capacity(65);
+        signature.extend_from_slice(r.as_slice());
+        signature.extend_from_slice(s.as_slice());
+        signature.push(v);
+        if !cradle_crypto::is_valid_signature_now(self.vm(), authorizer, digest, &signature) {
+            return Err(InvalidAuthorizationSignature { authorizer }.abi_encode());
+        }
+        Ok(())
+    }
+
+    /// Marks `authorizer`'s `nonce` used, failing if it already was.
+    pub(crate) fn consume_authorization_nonce(&mut self, authorizer: Address, nonce: B256) -> Result<(), Vec<u8>> {
+        self.authorizations
+            .use_unordered_nonce(authorizer, U256::from_be_bytes(nonce.0))
+            .map_err(|_| AuthorizationAlreadyUsed { authorizer, nonce }.abi_encode())
+    }
+}
@@ -0,0 +1,167 @@
+//! Deflationary burns on transfers and buybacks
+//!
+//! With an auto-burn rate set, every transfer between holders burns that share of the value from
+//! what the recipient gets, so the sender pays the full value and the recipient receives the rest.
+//! AMM routers need their fee-on-transfer variants to swap the token while the rate is nonzero.
+//!
+//! `buybackAndBurn` spends the treasury's WETH on the token through a Uniswap V2 style pair and
+//! burns what it buys. The treasury approves the token contract for WETH, and the contract moves it
+//! straight into the pair, swaps at the pair's reserves less its 0.3% fee and burns the tokens it
+//! received, failing if that's less than the owner's minimum. The pair pays the swap out to the
+//! token contract, which the launch guard always exempts, so buybacks work while the guard is armed.
+//! `AutoBurned` and `BuybackAndBurn` separate the two kinds of burn for supply analytics, on top of
+//! their `Transfer`s to zero.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::{ExternalCall, SuperPositionToken};
+
+/// Denominator of the auto-burn rate, which is in basis points.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Highest auto-burn rate, 10% of each transfer.
+pub const MAX_BURN_BPS: u64 = 1_000;
+
+sol_storage! {
+    pub struct Deflation {
+        /// Share of each transfer burned, in basis points
+        uint256 burn_bps;
+        /// Account the buyback's WETH is pulled from
+        address treasury;
+        /// Uniswap V2 style pair of the token and WETH
+        address pair;
+        address weth;
+    }
+}
+
+sol! {
+    interface IBuybackWeth {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IBuybackPair {
+        function token0() external view returns (address);
+        function token1() external view returns (address);
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast);
+        function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes calldata data) external;
+    }
+}
+
+// Deflation events and errors
+sol! {
+    event AutoBurnSet(uint256 burnBps);
+    event AutoBurned(address indexed from, address indexed to, uint256 amount);
+    event BuybackConfigured(address treasury, address pair, address weth);
+    event BuybackAndBurn(uint256 spent, uint256 burned);
+
+    // The auto-burn rate is above `MAX_BURN_BPS`
+    error InvalidBurnRate(uint256 burnBps);
+    // The treasury, pair and WETH haven't been set
+    error BuybackNotConfigured();
+    // The pair doesn't trade the token against WETH
+    error InvalidBuybackPair(address pair);
+    error BuybackCallFailed(address target);
+    // The buyback bought fewer tokens than the owner's minimum
+    error BuybackBelowMinimum(uint256 minimum, uint256 received);
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Burns the auto-burn share of `value` that `to` just received from `from`.
+    pub(crate) fn auto_burn(&mut self, from: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        let amount = value * self.deflation.burn_bps.get() / U256::from(BPS_DENOMINATOR);
+        if amount.is_zero() || to.is_zero() {
+            return Ok(());
+        }
+        self.erc20.burn(to, amount)?;
+        self.track_dividends(to, Address::ZERO, amount);
+
+        log(self.vm(), AutoBurned { from, to, amount });
+        Ok(())
+    }
+
+    /// Sets the auto-burn rate.
+    pub(crate) fn set_burn_rate(&mut self, burn_bps: U256) -> Result<(), Vec<u8>> {
+        if burn_bps > U256::from(MAX_BURN_BPS) {
+            return Err(InvalidBurnRate { burnBps: burn_bps }.abi_encode());
+        }
+        self.deflation.burn_bps.set(burn_bps);
+
+        log(self.vm(), AutoBurnSet { burnBps: burn_bps });
+        Ok(())
+    }
+
+    /// Sets where buybacks pull WETH from and swap it, checking that `pair` trades the token
+    /// against `weth`.
+    pub(crate) fn set_buyback(&mut self, treasury: Address, pair: Address, weth: Address) -> Result<(), Vec<u8>> {
+        let token0 = self.pair_call(pair, IBuybackPair::token0Call {})?._0;
+        let token1 = self.pair_call(pair, IBuybackPair::token1Call {})?._0;
+        let contract = self.vm().contract_address();
+        if (token0, token1) != (contract, weth) && (token0, token1) != (weth, contract) {
+            return Err(InvalidBuybackPair { pair }.abi_encode());
+        }
+        self.deflation.treasury.set(treasury);
+        self.deflation.pair.set(pair);
+        self.deflation.weth.set(weth);
+
+        log(self.vm(), BuybackConfigured { treasury, pair, weth });
+        Ok(())
+    }
+
+    /// Swaps `amount` of the treasury's WETH for the token and burns what the contract received,
+    /// returning it.
+    pub(crate) fn buyback(&mut self, amount: U256, min_burned: U256) -> Result<U256, Vec<u8>> {
+        let deflation = &self.deflation;
+        let (treasury, pair, weth) = (deflation.treasury.get(), deflation.pair.get(), deflation.weth.get());
+        if pair.is_zero() {
+            return Err(BuybackNotConfigured {}.abi_encode());
+        }
+        let contract = self.vm().contract_address();
+        let token_is_token0 = self.pair_call(pair, IBuybackPair::token0Call {})?._0 == contract;
+        let reserves = self.pair_call(pair, IBuybackPair::getReservesCall {})?;
+        let (reserve0, reserve1) = (U256::from(reserves.reserve0), U256::from(reserves.reserve1));
+        let (reserve_in, reserve_out) = if token_is_token0 { (reserve1, reserve0) } else { (reserve0, reserve1) };
+        let amount_in = amount * U256::from(997);
+        let amount_out = amount_in * reserve_out / (reserve_in * U256::from(1000) + amount_in);
+
+        let pulled = IBuybackWeth::transferFromCall { from: treasury, to: pair, value: amount };
+        let output = self.mutating_call(weth, &pulled.abi_encode())?;
+        if !IBuybackWeth::transferFromCall::abi_decode_returns(&output, true).is_ok_and(|pulled| pulled._0) {
+            return Err(BuybackCallFailed { target: weth }.abi_encode());
+        }
+        let (amount0_out, amount1_out) =
+            if token_is_token0 { (amount_out, U256::ZERO) } else { (U256::ZERO, amount_out) };
+        let before = self.erc20.balance_of(contract);
+        let swap = IBuybackPair::swapCall {
+            amount0Out: amount0_out,
+            amount1Out: amount1_out,
+            to: contract,
+            data: Default::default(),
+        };
+        self.mutating_call(pair, &swap.abi_encode())?;
+        let received = self.erc20.balance_of(contract).saturating_sub(before);
+        if received < min_burned {
+            return Err(BuybackBelowMinimum { minimum: min_burned, received }.abi_encode());
+        }
+        self.erc20.burn(contract, received)?;
+        self.track_dividends(contract, Address::ZERO, received);
+
+        log(self.vm(), BuybackAndBurn { spent: amount, burned: received });
+        Ok(received)
+    }
+
+    /// Calls a view of `pair` and decodes what it returns.
+    fn pair_call<C: SolCall>(&self, pair: Address, call: C) -> Result<C::Return, Vec<u8>> {
+        self.vm()
+            .static_call(&self, pair, &call.abi_encode())
+            .ok()
+            .and_then(|output| C::abi_decode_returns(&output, true).ok())
+            .ok_or_else(|| BuybackCallFailed { target: pair }.abi_encode())
+    }
+
+    fn mutating_call(&mut self, target: Address, data: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&ExternalCall, target, data).map_err(|_| BuybackCallFailed { target }.abi_encode())
+    }
+}
@@ -0,0 +1,184 @@
+//! Reflection of a share of each transfer to holders, with the `dividends` feature
+//!
+//! With a dividend fee set, every transfer between holders moves that share of the value from what
+//! the recipient gets into the contract, where it's owed to every holder in proportion to their
+//! balance. Rather than touching each holder, the contract keeps a running total of dividends per
+//! token, magnified by 2^128 so small fees on a large supply don't round away, and a correction per
+//! holder that cancels what the total earned before the holder's balance changed. A holder's
+//! dividends are then `(perShare * balance + correction) / 2^128`, less what they've withdrawn,
+//! and `withdrawDividend` pays them out of the contract, without a fee.
+//!
+//! The owner excludes pairs, bridges and the like, whose balances neither earn dividends nor
+//! dilute everyone else's; what an account earned before it was excluded stays withdrawable. The
+//! contract's own balance never earns any. Built without the feature, nothing is reflected and the
+//! methods that change state revert with `DividendsDisabled`.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::SuperPositionToken;
+
+/// Whether the contract was built with the `dividends` feature.
+pub const ENABLED: bool = cfg!(feature = "dividends");
+/// Highest dividend fee, 10% of each transfer, in basis points.
+pub const MAX_DIVIDEND_FEE_BPS: u64 = 1_000;
+/// What dividends per token are multiplied by, 2^128.
+const MAGNITUDE: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+sol_storage! {
+    pub struct Dividends {
+        /// Share of each transfer reflected to holders, in basis points
+        uint256 fee_bps;
+        /// Dividends per token ever distributed, times `MAGNITUDE`
+        uint256 magnified_per_share;
+        /// Sum of the balances of excluded accounts
+        uint256 excluded_supply;
+        /// Dividends the contract holds that weren't withdrawn yet
+        uint256 pool;
+        uint256 total_distributed;
+        /// Per holder, what `magnified_per_share` owes for balances they didn't hold, in two's
+        /// complement
+        mapping(address => uint256) corrections;
+        mapping(address => uint256) withdrawn;
+        mapping(address => bool) excluded;
+    }
+}
+
+// Dividend events and errors
+sol! {
+    event DividendFeeSet(uint256 feeBps);
+    event DividendsDistributed(address indexed from, address indexed to, uint256 amount);
+    event DividendWithdrawn(address indexed account, uint256 amount);
+    event DividendExclusionSet(address indexed account, bool excluded);
+
+    // The token was built without the `dividends` feature
+    error DividendsDisabled();
+    // The dividend fee is above `MAX_DIVIDEND_FEE_BPS`
+    error InvalidDividendFee(uint256 feeBps);
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Updates the corrections of `from` and `to` after `value` moved between them, where a zero
+    /// `from` mints and a zero `to` burns.
+    pub(crate) fn track_dividends(&mut self, from: Address, to: Address, value: U256) {
+        if !ENABLED {
+            return;
+        }
+        let contract = self.vm().contract_address();
+        let magnified = self.dividends.magnified_per_share.get().wrapping_mul(value);
+        for (account, gained) in [(from, false), (to, true)] {
+            if account.is_zero() || account == contract {
+                continue;
+            }
+            if self.dividends.excluded.get(account) {
+                let excluded = self.dividends.excluded_supply.get();
+                self.dividends.excluded_supply.set(if gained { excluded + value } else { excluded - value });
+                continue;
+            }
+            let mut correction = self.dividends.corrections.setter(account);
+            let old = correction.get();
+            correction.set(if gained { old.wrapping_sub(magnified) } else { old.wrapping_add(magnified) });
+        }
+    }
+
+    /// Moves the dividend share of `value` that `to` just received from `from` into the contract
+    /// and distributes it to every holder.
+    pub(crate) fn reflect(&mut self, from: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        let contract = self.vm().contract_address();
+        let fee = value * self.dividends.fee_bps.get() / U256::from(crate::deflation::BPS_DENOMINATOR);
+        if !ENABLED || fee.is_zero() || to.is_zero() || to == contract {
+            return Ok(());
+        }
+        let mut eligible =
+            self.erc20.total_supply() - self.dividends.excluded_supply.get() - self.erc20.balance_of(contract);
+        if !self.dividends.excluded.get(to) {
+            eligible -= fee;
+        }
+        if eligible.is_zero() {
+            return Ok(());
+        }
+        self.erc20._transfer(to, contract, fee)?;
+        self.track_dividends(to, contract, fee);
+        let per_share = self.dividends.magnified_per_share.get();
+        self.dividends.magnified_per_share.set(per_share + fee * MAGNITUDE / eligible);
+        self.dividends.pool.set(self.dividends.pool.get() + fee);
+        self.dividends.total_distributed.set(self.dividends.total_distributed.get() + fee);
+
+        log(self.vm(), DividendsDistributed { from, to, amount: fee });
+        Ok(())
+    }
+
+    /// Fails unless the contract was built with the `dividends` feature.
+    pub(crate) fn require_dividends(&self) -> Result<(), Vec<u8>> {
+        if !ENABLED {
+            return Err(DividendsDisabled {}.abi_encode());
+        }
+        Ok(())
+    }
+
+    /// Sets the dividend fee.
+    pub(crate) fn set_dividend_fee_bps(&mut self, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.require_dividends()?;
+        if fee_bps > U256::from(MAX_DIVIDEND_FEE_BPS) {
+            return Err(InvalidDividendFee { feeBps: fee_bps }.abi_encode());
+        }
+        self.dividends.fee_bps.set(fee_bps);
+
+        log(self.vm(), DividendFeeSet { feeBps: fee_bps });
+        Ok(())
+    }
+
+    /// Excludes `account` from dividends or includes it again, settling what it earned so far.
+    pub(crate) fn set_dividend_exclusion(&mut self, account: Address, excluded: bool) -> Result<(), Vec<u8>> {
+        self.require_dividends()?;
+        if self.dividends.excluded.get(account) != excluded {
+            let balance = self.erc20.balance_of(account);
+            if excluded {
+                self.track_dividends(account, Address::ZERO, balance);
+                self.dividends.excluded.insert(account, true);
+                self.dividends.excluded_supply.set(self.dividends.excluded_supply.get() + balance);
+            } else {
+                self.dividends.excluded.insert(account, false);
+                self.dividends.excluded_supply.set(self.dividends.excluded_supply.get() - balance);
+                self.track_dividends(Address::ZERO, account, balance);
+            }
+        }
+
+        log(self.vm(), DividendExclusionSet { account, excluded });
+        Ok(())
+    }
+
+    /// Pays `account` the dividends it can withdraw and returns them.
+    pub(crate) fn pay_dividend(&mut self, account: Address) -> Result<U256, Vec<u8>> {
+        self.require_dividends()?;
+        let amount = self.withdrawable_dividend(account);
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        let withdrawn = self.dividends.withdrawn.get(account);
+        self.dividends.withdrawn.insert(account, withdrawn + amount);
+        self.dividends.pool.set(self.dividends.pool.get() - amount);
+        let contract = self.vm().contract_address();
+        self.erc20._transfer(contract, account, amount)?;
+        self.track_dividends(contract, account, amount);
+
+        log(self.vm(), DividendWithdrawn { account, amount });
+        Ok(amount)
+    }
+
+    /// Dividends `account` earned and hasn't withdrawn.
+    pub(crate) fn withdrawable_dividend(&self, account: Address) -> U256 {
+        let counted = !self.dividends.excluded.get(account) && account != self.vm().contract_address();
+        let balance = if counted { self.erc20.balance_of(account) } else { U256::ZERO };
+        let magnified = self
+            .dividends
+            .magnified_per_share
+            .get()
+            .wrapping_mul(balance)
+            .wrapping_add(self.dividends.corrections.get(account));
+        (magnified / MAGNITUDE).saturating_sub(self.dividends.withdrawn.get(account))
+    }
+}
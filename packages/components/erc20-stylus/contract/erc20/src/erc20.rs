@@ -13,11 +13,7 @@ use alloc::string::String;
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::sol;
 use core::marker::PhantomData;
-use stylus_sdk::{
-    evm,
-    msg,
-    prelude::*,
-};
+use stylus_sdk::prelude::*;
 
 pub trait Erc20Params {
     /// Immutable token name
@@ -90,7 +86,7 @@ impl<T: Erc20Params> Erc20<T> {
         to_balance.set(new_to_balance);
 
         // Emitting the transfer event
-        evm::log(Transfer { from, to, value });
+        log(self.vm(), Transfer { from, to, value });
         Ok(())
     }
 
@@ -105,7 +101,7 @@ impl<T: Erc20Params> Erc20<T> {
         self.total_supply.set(self.total_supply.get() + value);
 
         // Emitting the transfer event
-        evm::log(Transfer {
+        log(self.vm(), Transfer {
             from: Address::ZERO,
             to: address,
             value,
@@ -132,7 +128,7 @@ impl<T: Erc20Params> Erc20<T> {
         self.total_supply.set(self.total_supply.get() - value);
 
         // Emitting the transfer event
-        evm::log(Transfer {
+        log(self.vm(), Transfer {
             from: address,
             to: Address::ZERO,
             value,
@@ -173,12 +169,13 @@ impl<T: Erc20Params> Erc20<T> {
 
     /// Transfers `value` tokens from msg::sender() to `to`
     pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Erc20Error> {
-        self._transfer(msg::sender(), to, value)?;
+        self._transfer(self.vm().msg_sender(), to, value)?;
         Ok(true)
     }
 
     /// Transfers `value` tokens from `from` to `to`
-    /// (msg::sender() must be able to spend at least `value` tokens from `from`)
+    /// (msg::sender() must be able to spend at least `value` tokens from `from`, and an allowance of
+    /// `U256::MAX` is infinite, so it's never decreased)
     pub fn transfer_from(
         &mut self,
         from: Address,
@@ -186,20 +183,23 @@ impl<T: Erc20Params> Erc20<T> {
         value: U256,
     ) -> Result<bool, Erc20Error> {
         // Check msg::sender() allowance
+        let spender = self.vm().msg_sender();
         let mut sender_allowances = self.allowances.setter(from);
-        let mut allowance = sender_allowances.setter(msg::sender());
+        let mut allowance = sender_allowances.setter(spender);
         let old_allowance = allowance.get();
         if old_allowance < value {
             return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
                 owner: from,
-                spender: msg::sender(),
+                spender,
                 have: old_allowance,
                 want: value,
             }));
         }
 
-        // Decreases allowance
-        allowance.set(old_allowance - value);
+        // Decreases allowance, unless it's infinite
+        if old_allowance != U256::MAX {
+            allowance.set(old_allowance - value);
+        }
 
         // Calls the internal transfer function
         self._transfer(from, to, value)?;
@@ -209,9 +209,9 @@ impl<T: Erc20Params> Erc20<T> {
 
     /// Approves the spenditure of `value` tokens of msg::sender() to `spender`
     pub fn approve(&mut self, spender: Address, value: U256) -> bool {
-        self.allowances.setter(msg::sender()).insert(spender, value);
-        evm::log(Approval {
-            owner: msg::sender(),
+        self.allowances.setter(self.vm().msg_sender()).insert(spender, value);
+        log(self.vm(), Approval {
+            owner: self.vm().msg_sender(),
             spender,
             value,
         });
@@ -222,4 +222,32 @@ impl<T: Erc20Params> Erc20<T> {
     pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
         self.allowances.getter(owner).get(spender)
     }
+
+    /// Raises the allowance of `spender` on msg::sender()'s tokens by `added_value`, saturating at
+    /// an infinite allowance
+    pub fn increase_allowance(&mut self, spender: Address, added_value: U256) -> bool {
+        let owner = self.vm().msg_sender();
+        let value = self.allowance(owner, spender).saturating_add(added_value);
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval { owner, spender, value });
+        true
+    }
+
+    /// Lowers the allowance of `spender` on msg::sender()'s tokens by `subtracted_value`
+    pub fn decrease_allowance(&mut self, spender: Address, subtracted_value: U256) -> Result<bool, Erc20Error> {
+        let owner = self.vm().msg_sender();
+        let old_allowance = self.allowance(owner, spender);
+        if old_allowance < subtracted_value {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                owner,
+                spender,
+                have: old_allowance,
+                want: subtracted_value,
+            }));
+        }
+        let value = old_allowance - subtracted_value;
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval { owner, spender, value });
+        Ok(true)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+//! Arbitrum custom gateway registration
+//!
+//! The Arbitrum token bridge moves a token through its generic gateway as an unofficial wrapped
+//! copy unless the token registers with the custom gateway and names its own counterpart on the
+//! child chain. On the parent chain, the owner sets the `L1CustomGateway` and `L1GatewayRouter`
+//! and calls `registerTokenOnL2`, which registers this token with both through retryable
+//! tickets. The gateway checks `isArbitrumEnabled` during the call, which only answers `0xb1`
+//! while a registration is in progress, so nobody else can register the token.
+//!
+//! On the child chain, the same contract is the counterpart: the owner sets the `L2CustomGateway`
+//! and the parent chain token, and the gateway mints deposits with `bridgeMint` and burns
+//! withdrawals with `bridgeBurn`.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::SuperPositionToken;
+
+/// What `isArbitrumEnabled` answers during a registration.
+pub const ARBITRUM_ENABLED: u8 = 0xb1;
+
+sol_storage! {
+    pub struct ArbitrumGateway {
+        /// Set only while `registerTokenOnL2` runs
+        bool registering;
+        /// `L1CustomGateway` on the parent chain, `L2CustomGateway` on the child chain
+        address gateway;
+        /// `L1GatewayRouter` on the parent chain, zero on the child chain
+        address router;
+        /// The token on the other chain
+        address counterpart;
+    }
+}
+
+sol! {
+    interface IL1CustomGateway {
+        function registerTokenToL2(address l2_address, uint256 max_gas, uint256 gas_price_bid, uint256 max_submission_cost, address credit_back_address) external payable returns (uint256);
+    }
+
+    interface IL1GatewayRouter {
+        function setGateway(address gateway, uint256 max_gas, uint256 gas_price_bid, uint256 max_submission_cost, address credit_back_address) external payable returns (uint256);
+    }
+}
+
+// Arbitrum gateway events and errors
+sol! {
+    event ArbitrumGatewaySet(address gateway, address router, address counterpart);
+    event TokenRegisteredOnL2(address indexed l2Token, address indexed gateway);
+
+    // The gateway and router haven't been set
+    error ArbitrumGatewayNotSet();
+    // `isArbitrumEnabled` was called outside a registration
+    error NotExpectedCall();
+    // Only the gateway mints and burns for the bridge
+    error UnauthorizedGateway(address caller);
+    // The value sent isn't what the registration pays the gateway and router
+    error IncorrectRegistrationValue(uint256 expected, uint256 received);
+    error GatewayCallFailed(address target);
+}
+
+/// Context of the registration calls, paying the gateway or the router `value` for its retryable
+/// ticket. Made through the host rather than with the deprecated `Call::new_in`, they're mocked
+/// under test.
+struct RegistrationCall {
+    value: U256,
+}
+
+impl calls::CallContext for RegistrationCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so the gateway's
+// `isArbitrumEnabled` check sees `registering` set
+unsafe impl calls::MutatingCallContext for RegistrationCall {
+    fn value(&self) -> U256 {
+        self.value
+    }
+}
+
+/// The retryable ticket parameters of a registration, for the gateway and for the router.
+pub struct Registration {
+    pub max_submission_cost_for_custom_gateway: U256,
+    pub max_submission_cost_for_router: U256,
+    pub max_gas_for_custom_gateway: U256,
+    pub max_gas_for_router: U256,
+    pub gas_price_bid: U256,
+    pub value_for_gateway: U256,
+    pub value_for_router: U256,
+    pub credit_back_address: Address,
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Registers the token and `l2_token` with the custom gateway and points the router at
+    /// the gateway, paying each its retryable tickets.
+    pub(crate) fn register_with_gateway(
+        &mut self,
+        l2_token: Address,
+        registration: &Registration,
+    ) -> Result<(), Vec<u8>> {
+        let (gateway, router) = (self.arbitrum_gateway.gateway.get(), self.arbitrum_gateway.router.get());
+        if gateway.is_zero() || router.is_zero() {
+            return Err(ArbitrumGatewayNotSet {}.abi_encode());
+        }
+        let expected = registration.value_for_gateway + registration.value_for_router;
+        let received = self.vm().msg_value();
+        if received != expected {
+            return Err(IncorrectRegistrationValue { expected, received }.abi_encode());
+        }
+
+        self.arbitrum_gateway.registering.set(true);
+        let register = IL1CustomGateway::registerTokenToL2Call {
+            l2_address: l2_token,
+            max_gas: registration.max_gas_for_custom_gateway,
+            gas_price_bid: registration.gas_price_bid,
+            max_submission_cost: registration.max_submission_cost_for_custom_gateway,
+            credit_back_address: registration.credit_back_address,
+        };
+        let context = RegistrationCall { value: registration.value_for_gateway };
+        self.vm()
+            .call(&context, gateway, &register.abi_encode())
+            .map_err(|_| GatewayCallFailed { target: gateway }.abi_encode())?;
+        let set_gateway = IL1GatewayRouter::setGatewayCall {
+            gateway,
+            max_gas: registration.max_gas_for_router,
+            gas_price_bid: registration.gas_price_bid,
+            max_submission_cost: registration.max_submission_cost_for_router,
+            credit_back_address: registration.credit_back_address,
+        };
+        let context = RegistrationCall { value: registration.value_for_router };
+        self.vm()
+            .call(&context, router, &set_gateway.abi_encode())
+            .map_err(|_| GatewayCallFailed { target: router }.abi_encode())?;
+        self.arbitrum_gateway.registering.set(false);
+        self.arbitrum_gateway.counterpart.set(l2_token);
+
+        log(self.vm(), TokenRegisteredOnL2 { l2Token: l2_token, gateway });
+        Ok(())
+    }
+
+    /// Fails unless the caller is the gateway.
+    pub(crate) fn only_gateway(&self) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller.is_zero() || caller != self.arbitrum_gateway.gateway.get() {
+            return Err(UnauthorizedGateway { caller }.abi_encode());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+//! A complete Solidity interface for contracts that call the token cross-VM.
+//!
+//! The SDK's `export-abi` output spreads the methods over one interface per inherited module,
+//! leaves out events and drops the names of error parameters. [`solidity_interface`] flattens
+//! the methods into a single `IERC20Token` and adds the events and errors with their parameter
+//! names. Every event and error declaration is checked against the Rust type it describes, and
+//! every error the SDK exports must be declared, so the interface can't drift from the contract.
+//! Errors returned as raw `Vec<u8>`, like [`AlreadyInitialized`], aren't exported by the SDK and
+//! are declared here by hand.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use alloy_json_abi::{Error, Event};
+use cradle_access_control::{
+    AccessControlBadConfirmation, AccessControlMemberOutOfBounds, AccessControlUnauthorizedAccount, RoleAdminChanged,
+    RoleGranted, RoleRevoked,
+};
+use cradle_arb::PrecompileCallFailed;
+use cradle_ownable::{OwnableInvalidOwner, OwnableUnauthorizedAccount, OwnershipTransferred};
+use stylus_sdk::{
+    abi::GenerateAbi,
+    alloy_sol_types::{SolError, SolEvent, TopicList},
+};
+
+use crate::{
+    authorization::{
+        AuthorizationAlreadyUsed, AuthorizationCanceled, AuthorizationExpired, AuthorizationNotYetValid,
+        AuthorizationUsed, InvalidAuthorizationCaller, InvalidAuthorizationSignature,
+    },
+    deflation::{
+        AutoBurnSet, AutoBurned, BuybackAndBurn, BuybackBelowMinimum, BuybackCallFailed, BuybackConfigured,
+        BuybackNotConfigured, InvalidBurnRate, InvalidBuybackPair,
+    },
+    dividends::{
+        DividendExclusionSet, DividendFeeSet, DividendWithdrawn, DividendsDisabled, DividendsDistributed,
+        InvalidDividendFee,
+    },
+    erc20::{Approval, InsufficientAllowance, InsufficientBalance, Transfer},
+    gateway::{
+        ArbitrumGatewayNotSet, ArbitrumGatewaySet, GatewayCallFailed, IncorrectRegistrationValue, NotExpectedCall,
+        TokenRegisteredOnL2, UnauthorizedGateway,
+    },
+    launch_guard::{
+        LaunchExemptionSet, LaunchGuardArmed, LaunchGuardIsRenounced, LaunchGuardRenounced, LaunchPairSet,
+        MaxBuyExceeded, MaxWalletExceeded, TradeCooldown, TradingEnabled, TradingNotEnabled,
+    },
+    minters::{MinterAllowanceExceeded, MinterConfigured, MinterRemoved, NotMinter},
+    recovery::{ERC20Recovered, ERC20RecoveryFailed},
+    AlreadyInitialized, CrosschainBurn, CrosschainMint, SuperPositionToken,
+};
+
+const HEADER: &str = "\
+/**
+ * Generated from the erc20-stylus contract, do not edit. To regenerate, run in contract/erc20:
+ *   cargo run --features export-abi -- interface > IERC20Token.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface IERC20Token {
+";
+
+/// Renders `IERC20Token.sol`.
+///
+/// # Panics
+///
+/// If a declaration below doesn't match its Rust type, or the SDK exports an error that has no
+/// declaration here.
+pub fn solidity_interface() -> String {
+    let exported = format!("{}", Exported);
+    let functions = declarations(&exported, "function ");
+
+    let errors = errors();
+    let exported_errors = declarations(&exported, "error ");
+    for line in &exported_errors {
+        let selector = parse_error(line).selector();
+        assert!(
+            errors.iter().any(|declaration| parse_error(declaration).selector() == selector),
+            "`{line}` is exported but isn't declared in the interface"
+        );
+    }
+    let mut out = String::from(HEADER);
+    for (i, group) in [events(), errors, functions].iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for declaration in group {
+            out.push_str(&format!("    {declaration};\n"));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn events() -> Vec<String> {
+    [
+        event::<Transfer>("event Transfer(address indexed from, address indexed to, uint256 value)"),
+        event::<Approval>("event Approval(address indexed owner, address indexed spender, uint256 value)"),
+        event::<CrosschainMint>("event CrosschainMint(address indexed to, uint256 amount, address indexed sender)"),
+        event::<CrosschainBurn>("event CrosschainBurn(address indexed from, uint256 amount, address indexed sender)"),
+        event::<AuthorizationUsed>("event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce)"),
+        event::<AuthorizationCanceled>("event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce)"),
+        event::<ArbitrumGatewaySet>("event ArbitrumGatewaySet(address gateway, address router, address counterpart)"),
+        event::<TokenRegisteredOnL2>("event TokenRegisteredOnL2(address indexed l2Token, address indexed gateway)"),
+        event::<MinterConfigured>("event MinterConfigured(address indexed minter, uint256 allowance)"),
+        event::<MinterRemoved>("event MinterRemoved(address indexed minter)"),
+        event::<ERC20Recovered>("event ERC20Recovered(address indexed token, address indexed to, uint256 amount)"),
+        event::<AutoBurnSet>("event AutoBurnSet(uint256 burnBps)"),
+        event::<AutoBurned>("event AutoBurned(address indexed from, address indexed to, uint256 amount)"),
+        event::<BuybackConfigured>("event BuybackConfigured(address treasury, address pair, address weth)"),
+        event::<BuybackAndBurn>("event BuybackAndBurn(uint256 spent, uint256 burned)"),
+        event::<LaunchGuardArmed>("event LaunchGuardArmed(uint256 maxBuy, uint256 maxWallet, uint64 cooldownBlocks)"),
+        event::<TradingEnabled>("event TradingEnabled()"),
+        event::<LaunchPairSet>("event LaunchPairSet(address indexed pair, bool isPair)"),
+        event::<LaunchExemptionSet>("event LaunchExemptionSet(address indexed account, bool exempt)"),
+        event::<LaunchGuardRenounced>("event LaunchGuardRenounced()"),
+        event::<DividendFeeSet>("event DividendFeeSet(uint256 feeBps)"),
+        event::<DividendsDistributed>(
+            "event DividendsDistributed(address indexed from, address indexed to, uint256 amount)",
+        ),
+        event::<DividendWithdrawn>("event DividendWithdrawn(address indexed account, uint256 amount)"),
+        event::<DividendExclusionSet>("event DividendExclusionSet(address indexed account, bool excluded)"),
+        event::<OwnershipTransferred>(
+            "event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)",
+        ),
+        event::<RoleGranted>("event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender)"),
+        event::<RoleRevoked>("event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender)"),
+        event::<RoleAdminChanged>(
+            "event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole)",
+        ),
+    ]
+    .into()
+}
+
+fn errors() -> Vec<String> {
+    [
+        error::<AlreadyInitialized>("error AlreadyInitialized()"),
+        error::<InsufficientBalance>("error InsufficientBalance(address from, uint256 have, uint256 want)"),
+        error::<InsufficientAllowance>(
+            "error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want)",
+        ),
+        error::<AuthorizationNotYetValid>("error AuthorizationNotYetValid(uint256 validAfter)"),
+        error::<AuthorizationExpired>("error AuthorizationExpired(uint256 validBefore)"),
+        error::<AuthorizationAlreadyUsed>("error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce)"),
+        error::<InvalidAuthorizationSignature>("error InvalidAuthorizationSignature(address authorizer)"),
+        error::<InvalidAuthorizationCaller>("error InvalidAuthorizationCaller(address caller, address payee)"),
+        error::<ArbitrumGatewayNotSet>("error ArbitrumGatewayNotSet()"),
+        error::<NotExpectedCall>("error NotExpectedCall()"),
+        error::<UnauthorizedGateway>("error UnauthorizedGateway(address caller)"),
+        error::<IncorrectRegistrationValue>("error IncorrectRegistrationValue(uint256 expected, uint256 received)"),
+        error::<GatewayCallFailed>("error GatewayCallFailed(address target)"),
+        error::<NotMinter>("error NotMinter(address caller)"),
+        error::<MinterAllowanceExceeded>(
+            "error MinterAllowanceExceeded(address minter, uint256 allowance, uint256 needed)",
+        ),
+        error::<ERC20RecoveryFailed>("error ERC20RecoveryFailed(address token)"),
+        error::<InvalidBurnRate>("error InvalidBurnRate(uint256 burnBps)"),
+        error::<BuybackNotConfigured>("error BuybackNotConfigured()"),
+        error::<InvalidBuybackPair>("error InvalidBuybackPair(address pair)"),
+        error::<BuybackCallFailed>("error BuybackCallFailed(address target)"),
+        error::<BuybackBelowMinimum>("error BuybackBelowMinimum(uint256 minimum, uint256 received)"),
+        error::<LaunchGuardIsRenounced>("error LaunchGuardIsRenounced()"),
+        error::<TradingNotEnabled>("error TradingNotEnabled()"),
+        error::<MaxBuyExceeded>("error MaxBuyExceeded(uint256 maxBuy, uint256 amount)"),
+        error::<MaxWalletExceeded>("error MaxWalletExceeded(address account, uint256 maxWallet, uint256 balance)"),
+        error::<TradeCooldown>("error TradeCooldown(address account, uint64 availableAt)"),
+        error::<PrecompileCallFailed>("error PrecompileCallFailed(address precompile)"),
+        error::<DividendsDisabled>("error DividendsDisabled()"),
+        error::<InvalidDividendFee>("error InvalidDividendFee(uint256 feeBps)"),
+        error::<OwnableUnauthorizedAccount>("error OwnableUnauthorizedAccount(address account)"),
+        error::<OwnableInvalidOwner>("error OwnableInvalidOwner(address owner)"),
+        error::<AccessControlUnauthorizedAccount>(
+            "error AccessControlUnauthorizedAccount(address account, bytes32 neededRole)",
+        ),
+        error::<AccessControlBadConfirmation>("error AccessControlBadConfirmation()"),
+        error::<AccessControlMemberOutOfBounds>("error AccessControlMemberOutOfBounds(bytes32 role, uint256 index)"),
+    ]
+    .into()
+}
+
+/// Checks that `declaration` has the signature and indexed parameters of `E`.
+fn event<E: SolEvent>(declaration: &str) -> String {
+    let event = Event::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"));
+    assert_eq!(event.selector(), E::SIGNATURE_HASH, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    let topics = 1 + event.inputs.iter().filter(|input| input.indexed).count();
+    assert_eq!(
+        topics,
+        <E::TopicList as TopicList>::COUNT,
+        "`{declaration}` indexes different parameters than `{}`",
+        E::SIGNATURE
+    );
+    declaration.into()
+}
+
+/// Checks that `declaration` has the signature of `E`.
+fn error<E: SolError>(declaration: &str) -> String {
+    assert_eq!(parse_error(declaration).selector(), E::SELECTOR, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    declaration.into()
+}
+
+fn parse_error(declaration: &str) -> Error {
+    Error::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"))
+}
+
+/// The declarations starting with `keyword` in the SDK's output, without the `;` and in order
+/// of first appearance.
+fn declarations(exported: &str, keyword: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in exported.lines().map(str::trim).filter(|line| line.starts_with(keyword)) {
+        let declaration = line.trim_end_matches(';');
+        if !found.iter().any(|seen| seen == declaration) {
+            found.push(declaration.into());
+        }
+    }
+    found
+}
+
+/// The interfaces the SDK exports for [`SuperPositionToken`] and the modules it inherits.
+struct Exported;
+
+impl fmt::Display for Exported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <SuperPositionToken as GenerateAbi>::fmt_abi(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use alloy_json_abi::Function;
+
+    use super::{declarations, solidity_interface, Exported};
+
+    /// Functions of the OpenZeppelin contracts the token stands in for, as their Solidity ABIs
+    /// declare them: IERC20 and its metadata, ERC20Burnable's `burn`, IERC7802, ERC-165, Ownable
+    /// and AccessControlEnumerable.
+    const OPENZEPPELIN_FUNCTIONS: &[&str] = &[
+        "name()",
+        "symbol()",
+        "decimals()",
+        "totalSupply()",
+        "balanceOf(address)",
+        "transfer(address,uint256)",
+        "transferFrom(address,address,uint256)",
+        "approve(address,uint256)",
+        "allowance(address,address)",
+        "burn(uint256)",
+        "crosschainMint(address,uint256)",
+        "crosschainBurn(address,uint256)",
+        "supportsInterface(bytes4)",
+        "owner()",
+        "transferOwnership(address)",
+        "renounceOwnership()",
+        "DEFAULT_ADMIN_ROLE()",
+        "hasRole(bytes32,address)",
+        "getRoleAdmin(bytes32)",
+        "grantRole(bytes32,address)",
+        "revokeRole(bytes32,address)",
+        "renounceRole(bytes32,address)",
+        "getRoleMember(bytes32,uint256)",
+        "getRoleMemberCount(bytes32)",
+    ];
+
+    #[test]
+    fn committed_interface_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/IERC20Token.sol");
+        let committed = std::fs::read_to_string(path).unwrap_or_default();
+        let generated = solidity_interface();
+        if committed != generated {
+            let (committed, generated): (Vec<_>, Vec<_>) = (committed.lines().collect(), generated.lines().collect());
+            let line = (0..committed.len().max(generated.len()))
+                .find(|&i| committed.get(i) != generated.get(i))
+                .unwrap_or(committed.len());
+            let (old, new) = (committed.get(line).unwrap_or(&""), generated.get(line).unwrap_or(&""));
+            let line = line + 1;
+            panic!(
+                "IERC20Token.sol is out of date at line {line}:\n- {old}\n+ {new}\n\
+                 Regenerate it with `cargo run --features export-abi -- interface > IERC20Token.sol`"
+            );
+        }
+    }
+
+    #[test]
+    fn exports_openzeppelin_selectors() {
+        // The unit tests can't link against the hostios the token still calls directly, so this
+        // checks the SDK's exported ABI rather than the router
+        let exported: Vec<_> = declarations(&format!("{}", Exported), "function ")
+            .iter()
+            .map(|declaration| Function::parse(declaration).unwrap().selector())
+            .collect();
+        for signature in OPENZEPPELIN_FUNCTIONS {
+            let selector = Function::parse(signature).unwrap().selector();
+            assert!(
+                exported.contains(&selector),
+                "`{signature}` ({selector}) isn't exported, so Solidity callers can't reach it"
+            );
+        }
+    }
+
+    #[test]
+    fn exported_functions_are_camel_case() {
+        for declaration in declarations(&format!("{}", Exported), "function ") {
+            let name = declaration["function ".len()..].split('(').next().unwrap();
+            let constant = name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            assert!(constant || !name.contains('_'), "`{name}` isn't camelCase");
+        }
+    }
+}
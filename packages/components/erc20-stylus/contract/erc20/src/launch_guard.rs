@@ -0,0 +1,132 @@
+//! Launch protections against sniping bots
+//!
+//! Bots watching for a new pair buy the moment liquidity lands, often in the same block, and sell
+//! into the first real buyers. Once the owner arms the guard, only the owner, the token contract
+//! and exempt accounts can move tokens until `enableTrading`, so liquidity can be added first.
+//! After that, a buy from a registered pair can't exceed the max buy, no account but a pair can
+//! hold more than the max wallet, and an account that bought or sold through a pair has to wait
+//! the cooldown, in L2 blocks, before trading through one again. A zero limit doesn't apply.
+//!
+//! The owner can loosen or tighten the limits during the launch, and `renounceLaunchGuard` turns
+//! every check off for good, so holders can see the limits will never come back.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256, U64};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::SuperPositionToken;
+
+sol_storage! {
+    pub struct LaunchGuard {
+        /// Whether the checks apply
+        bool armed;
+        bool trading_enabled;
+        /// Set for good by `renounceLaunchGuard`
+        bool renounced;
+        /// Most one buy from a pair can take, zero for no limit
+        uint256 max_buy;
+        /// Most an account besides a pair can hold, zero for no limit
+        uint256 max_wallet;
+        /// L2 blocks an account waits between trades through a pair, zero for none
+        uint64 cooldown_blocks;
+        /// AMM pairs, which buys come from and sells go to
+        mapping(address => bool) pairs;
+        /// Accounts no check applies to
+        mapping(address => bool) exempt;
+        /// L2 block of each account's last trade through a pair
+        mapping(address => uint64) last_trade_block;
+    }
+}
+
+// Launch guard events and errors
+sol! {
+    event LaunchGuardArmed(uint256 maxBuy, uint256 maxWallet, uint64 cooldownBlocks);
+    event TradingEnabled();
+    event LaunchPairSet(address indexed pair, bool isPair);
+    event LaunchExemptionSet(address indexed account, bool exempt);
+    event LaunchGuardRenounced();
+
+    // The launch guard was renounced, so it can't be changed
+    error LaunchGuardIsRenounced();
+    // Only the owner and exempt accounts move tokens before trading is enabled
+    error TradingNotEnabled();
+    error MaxBuyExceeded(uint256 maxBuy, uint256 amount);
+    error MaxWalletExceeded(address account, uint256 maxWallet, uint256 balance);
+    // `account` traded through a pair less than the cooldown ago
+    error TradeCooldown(address account, uint64 availableAt);
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Checks a transfer of `value` from `from` to `to` against the launch guard, before the
+    /// balances move.
+    pub(crate) fn check_launch(&mut self, from: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        let guard = &self.launch_guard;
+        if !guard.armed.get() || self.launch_exempt(from) || self.launch_exempt(to) {
+            return Ok(());
+        }
+        if !guard.trading_enabled.get() {
+            return Err(TradingNotEnabled {}.abi_encode());
+        }
+
+        let (buying, selling) = (guard.pairs.get(from), guard.pairs.get(to));
+        let max_buy = guard.max_buy.get();
+        if buying && !max_buy.is_zero() && value > max_buy {
+            return Err(MaxBuyExceeded { maxBuy: max_buy, amount: value }.abi_encode());
+        }
+        let max_wallet = guard.max_wallet.get();
+        let balance = self.erc20.balance_of(to) + value;
+        if !selling && !max_wallet.is_zero() && balance > max_wallet {
+            return Err(MaxWalletExceeded { account: to, maxWallet: max_wallet, balance }.abi_encode());
+        }
+
+        let cooldown = guard.cooldown_blocks.get().to::<u64>();
+        if cooldown == 0 || buying == selling {
+            return Ok(());
+        }
+        let trader = if buying { to } else { from };
+        let block = cradle_arb::arb_block_number(self.vm()).map_err(|e| e.abi_encode())?;
+        let last = self.launch_guard.last_trade_block.get(trader).to::<u64>();
+        if last != 0 && block < last + cooldown {
+            return Err(TradeCooldown { account: trader, availableAt: last + cooldown }.abi_encode());
+        }
+        self.launch_guard.last_trade_block.insert(trader, U64::from(block));
+        Ok(())
+    }
+
+    /// The owner, the token contract and accounts the owner exempted skip the checks.
+    fn launch_exempt(&self, account: Address) -> bool {
+        account == self.ownable.owner()
+            || account == self.vm().contract_address()
+            || self.launch_guard.exempt.get(account)
+    }
+
+    /// Fails once the launch guard is renounced.
+    pub(crate) fn require_launch_guard(&self) -> Result<(), Vec<u8>> {
+        if self.launch_guard.renounced.get() {
+            return Err(LaunchGuardIsRenounced {}.abi_encode());
+        }
+        Ok(())
+    }
+
+    /// Arms the launch guard with the given limits, keeping trading as it was.
+    pub(crate) fn set_launch_limits(&mut self, max_buy: U256, max_wallet: U256, cooldown_blocks: u64) {
+        let guard = &mut self.launch_guard;
+        guard.armed.set(true);
+        guard.max_buy.set(max_buy);
+        guard.max_wallet.set(max_wallet);
+        guard.cooldown_blocks.set(U64::from(cooldown_blocks));
+
+        log(self.vm(), LaunchGuardArmed { maxBuy: max_buy, maxWallet: max_wallet, cooldownBlocks: cooldown_blocks });
+    }
+
+    /// Turns the launch guard off for good.
+    pub(crate) fn disarm_launch_guard(&mut self) {
+        self.launch_guard.renounced.set(true);
+        self.launch_guard.armed.set(false);
+        self.launch_guard.trading_enabled.set(true);
+
+        log(self.vm(), LaunchGuardRenounced {});
+    }
+}
@@ -3,14 +3,39 @@
 extern crate alloc;
 
 // Modules and imports
+mod authorization;
+mod deflation;
+mod dividends;
 mod erc20;
+mod gateway;
+mod launch_guard;
+mod minters;
+mod recovery;
+#[cfg(feature = "export-abi")]
+pub mod interface;
+#[cfg(test)]
+mod tests;
 
-use alloy_primitives::{Address, U256};
-use stylus_sdk::{
-    msg,
-    prelude::*
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{b256, Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+use crate::authorization::{
+    Authorization, AuthorizationCanceled, InvalidAuthorizationCaller, CANCEL_AUTHORIZATION_TYPEHASH,
+    RECEIVE_WITH_AUTHORIZATION_TYPEHASH, TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
 };
-use crate::erc20::{Erc20, Erc20Params, Erc20Error};
+use crate::deflation::Deflation;
+use crate::dividends::Dividends;
+use crate::erc20::{Erc20, Erc20Params};
+use crate::gateway::{ArbitrumGateway, ArbitrumGatewaySet, NotExpectedCall, Registration, ARBITRUM_ENABLED};
+use crate::launch_guard::{LaunchExemptionSet, LaunchGuard, LaunchPairSet, TradingEnabled};
+use crate::minters::{MinterConfigured, MinterRemoved, Minters};
+use cradle_access_control::{
+    only_role, AccessControl, DEFAULT_ADMIN_ROLE, IACCESS_CONTROL, IACCESS_CONTROL_ENUMERABLE,
+};
+use cradle_crypto::Nonces;
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, IERC173, IERC5313};
 
 /// Immutable definitions
 struct SuperPositionTokenParams;
@@ -20,6 +45,36 @@ impl Erc20Params for SuperPositionTokenParams {
     const DECIMALS: u8 = 18;
 }
 
+/// Role allowed to call `crosschain_mint` and `crosschain_burn`, `keccak256("BRIDGE_ROLE")`
+const BRIDGE_ROLE: B256 = b256!("52ba824bfabc2bcfcdf7f0edbb486ebb05e1836c90e78047efeb949990f72e5f");
+/// Role allowed to configure minters and their allowances, `keccak256("MASTER_MINTER_ROLE")`
+const MASTER_MINTER_ROLE: B256 = b256!("4d722a319ebda03eb1179096607e87f360b089b3919a0d764a65ea950521a6c9");
+/// ERC-7802, crosschain mints and burns
+const IERC7802: Interface = interface!("crosschainMint"(Address, U256), "crosschainBurn"(Address, U256));
+/// Every standard besides ERC-165 the token reports supporting
+const INTERFACES: [Interface; 5] = [IERC7802, IERC173, IERC5313, IACCESS_CONTROL, IACCESS_CONTROL_ENUMERABLE];
+
+/// Context of the calls the token makes to WETH, the buyback pair and the tokens it recovers. Made
+/// through the host, unlike the deprecated `call` taking `Call::new_in(self)`, they're mocked under test.
+pub(crate) struct ExternalCall;
+
+impl calls::CallContext for ExternalCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale if the callee calls back into the token
+unsafe impl calls::MutatingCallContext for ExternalCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// `SOURCE_HASH`, the hash of the sources this contract was built from, written by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+
 // Define the entrypoint as a Solidity storage object. The sol_storage! macro
 // will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
 // storage slots and types.
@@ -29,27 +84,503 @@ sol_storage! {
         // Allows erc20 to access SuperPositionToken's storage and make calls
         #[borrow]
         Erc20<SuperPositionTokenParams> erc20;
+        // Set once by `initialize`
+        bool initialized;
+        // Owner, for administration that doesn't need a role
+        #[borrow]
+        Ownable ownable;
+        // Roles, their admins and members
+        #[borrow]
+        AccessControl access_control;
+        // Random nonces of EIP-3009 authorizations that were used or cancelled
+        Nonces authorizations;
+        // Arbitrum custom gateway the token is bridged through, and its counterpart
+        ArbitrumGateway arbitrum_gateway;
+        // Minters the master minter configured and their allowances
+        Minters minters;
+        // Auto-burn rate and where buybacks swap
+        Deflation deflation;
+        // Limits on trading during the launch
+        LaunchGuard launch_guard;
+        // Dividends reflected to holders, with the `dividends` feature
+        Dividends dividends;
     }
 }
 
+// Crosschain (ERC-7802) events and errors
+sol! {
+    event CrosschainMint(address indexed to, uint256 amount, address indexed sender);
+    event CrosschainBurn(address indexed from, uint256 amount, address indexed sender);
+
+    error AlreadyInitialized();
+}
+
 #[public]
-#[inherit(Erc20<SuperPositionTokenParams>)]
+#[inherit(Erc20<SuperPositionTokenParams>, Ownable, AccessControl)]
 impl SuperPositionToken {
-    /// Mints tokens
-    pub fn mint(&mut self, value: U256) -> Result<(), Erc20Error> {
-        self.erc20.mint(msg::sender(), value)?;
+    /// Makes `admin` the owner and gives it the admin role, which grants the bridge role. Can only be called once
+    pub fn initialize(&mut self, admin: Address) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(AlreadyInitialized {}.abi_encode());
+        }
+        self.ownable.initialize(admin)?;
+        self.initialized.set(true);
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, admin);
+        Ok(())
+    }
+
+    /// Transfers `value` tokens to `to`, burning the auto-burn share of it
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Vec<u8>> {
+        let from = self.vm().msg_sender();
+        self.check_launch(from, to, value)?;
+        self.erc20._transfer(from, to, value)?;
+        self.track_dividends(from, to, value);
+        self.auto_burn(from, to, value)?;
+        self.reflect(from, to, value)?;
+        Ok(true)
+    }
+
+    /// Transfers `value` tokens from `from` to `to` within the caller's allowance, burning the
+    /// auto-burn share of it
+    pub fn transfer_from(&mut self, from: Address, to: Address, value: U256) -> Result<bool, Vec<u8>> {
+        self.check_launch(from, to, value)?;
+        self.erc20.transfer_from(from, to, value)?;
+        self.track_dividends(from, to, value);
+        self.auto_burn(from, to, value)?;
+        self.reflect(from, to, value)?;
+        Ok(true)
+    }
+
+    /// Mints tokens (owner, or minters within their allowance)
+    pub fn mint(&mut self, value: U256) -> Result<(), Vec<u8>> {
+        let sender = self.vm().msg_sender();
+        self.authorize_mint(sender, value)?;
+        self.erc20.mint(sender, value)?;
+        self.track_dividends(Address::ZERO, sender, value);
         Ok(())
     }
 
-    /// Mints tokens to another address
-    pub fn mint_to(&mut self, to: Address, value: U256) -> Result<(), Erc20Error> {
+    /// Mints tokens to another address (owner, or minters within their allowance)
+    pub fn mint_to(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.authorize_mint(self.vm().msg_sender(), value)?;
         self.erc20.mint(to, value)?;
+        self.track_dividends(Address::ZERO, to, value);
         Ok(())
     }
 
     /// Burns tokens
-    pub fn burn(&mut self, value: U256) -> Result<(), Erc20Error> {
-        self.erc20.burn(msg::sender(), value)?;
+    pub fn burn(&mut self, value: U256) -> Result<(), Vec<u8>> {
+        let sender = self.vm().msg_sender();
+        self.erc20.burn(sender, value)?;
+        self.track_dividends(sender, Address::ZERO, value);
+        Ok(())
+    }
+
+    /// Mints tokens bridged in from another chain (bridge role only)
+    pub fn crosschain_mint(&mut self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        only_role!(self.access_control, BRIDGE_ROLE);
+        self.erc20.mint(to, amount)?;
+        self.track_dividends(Address::ZERO, to, amount);
+
+        log(self.vm(), CrosschainMint {
+            to,
+            amount,
+            sender: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+
+    /// Burns tokens bridged out to another chain (bridge role only). No allowance is needed
+    pub fn crosschain_burn(&mut self, from: Address, amount: U256) -> Result<(), Vec<u8>> {
+        only_role!(self.access_control, BRIDGE_ROLE);
+        self.erc20.burn(from, amount)?;
+        self.track_dividends(from, Address::ZERO, amount);
+
+        log(self.vm(), CrosschainBurn {
+            from,
+            amount,
+            sender: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+
+    /// Makes `minter` a minter that can mint up to `allowance` more tokens, replacing its current allowance
+    /// (master minter role only)
+    pub fn configure_minter(&mut self, minter: Address, allowance: U256) -> Result<(), Vec<u8>> {
+        only_role!(self.access_control, MASTER_MINTER_ROLE);
+        self.minters.minters.insert(minter, true);
+        self.minters.allowances.insert(minter, allowance);
+
+        log(self.vm(), MinterConfigured { minter, allowance });
+        Ok(())
+    }
+
+    /// Stops `minter` minting and clears its allowance (master minter role only)
+    pub fn remove_minter(&mut self, minter: Address) -> Result<(), Vec<u8>> {
+        only_role!(self.access_control, MASTER_MINTER_ROLE);
+        self.minters.minters.insert(minter, false);
+        self.minters.allowances.insert(minter, U256::ZERO);
+
+        log(self.vm(), MinterRemoved { minter });
+        Ok(())
+    }
+
+    /// Whether `account` is a configured minter
+    pub fn is_minter(&self, account: Address) -> bool {
+        self.minters.minters.get(account)
+    }
+
+    /// What `minter` can still mint
+    pub fn minter_allowance(&self, minter: Address) -> U256 {
+        self.minters.allowances.get(minter)
+    }
+
+    /// Mints `amount` to `to`, drawing it from the caller's minter allowance (minters only)
+    pub fn mint_within_allowance(&mut self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.use_minter_allowance(self.vm().msg_sender(), amount)?;
+        self.erc20.mint(to, amount)?;
+        self.track_dividends(Address::ZERO, to, amount);
+        Ok(())
+    }
+
+    /// Sets the Arbitrum custom gateway, the gateway router and the token on the other chain (owner only).
+    /// On the child chain the router is zero
+    pub fn set_arbitrum_gateway(
+        &mut self,
+        gateway: Address,
+        router: Address,
+        counterpart: Address,
+    ) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.arbitrum_gateway.gateway.set(gateway);
+        self.arbitrum_gateway.router.set(router);
+        self.arbitrum_gateway.counterpart.set(counterpart);
+
+        log(self.vm(), ArbitrumGatewaySet { gateway, router, counterpart });
+        Ok(())
+    }
+
+    /// The Arbitrum custom gateway, the gateway router and the token on the other chain
+    pub fn arbitrum_gateway(&self) -> (Address, Address, Address) {
+        let gateway = &self.arbitrum_gateway;
+        (gateway.gateway.get(), gateway.router.get(), gateway.counterpart.get())
+    }
+
+    /// Answers the custom gateway's check that the token is registering itself, so only the token can
+    pub fn is_arbitrum_enabled(&self) -> Result<u8, Vec<u8>> {
+        if !self.arbitrum_gateway.registering.get() {
+            return Err(NotExpectedCall {}.abi_encode());
+        }
+        Ok(ARBITRUM_ENABLED)
+    }
+
+    /// Registers `l2_custom_token` as this token's counterpart with the custom gateway and the router,
+    /// paying them `value_for_gateway` and `value_for_router` from the value sent (owner only)
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_token_on_l2(
+        &mut self,
+        l2_custom_token: Address,
+        max_submission_cost_for_custom_gateway: U256,
+        max_submission_cost_for_router: U256,
+        max_gas_for_custom_gateway: U256,
+        max_gas_for_router: U256,
+        gas_price_bid: U256,
+        value_for_gateway: U256,
+        value_for_router: U256,
+        credit_back_address: Address,
+    ) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        let registration = Registration {
+            max_submission_cost_for_custom_gateway,
+            max_submission_cost_for_router,
+            max_gas_for_custom_gateway,
+            max_gas_for_router,
+            gas_price_bid,
+            value_for_gateway,
+            value_for_router,
+            credit_back_address,
+        };
+        self.register_with_gateway(l2_custom_token, &registration)
+    }
+
+    /// Mints tokens deposited from the parent chain (child chain gateway only)
+    pub fn bridge_mint(&mut self, account: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.only_gateway()?;
+        self.erc20.mint(account, amount)?;
+        self.track_dividends(Address::ZERO, account, amount);
+        Ok(())
+    }
+
+    /// Burns tokens withdrawn to the parent chain (child chain gateway only). No allowance is needed
+    pub fn bridge_burn(&mut self, account: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.only_gateway()?;
+        self.erc20.burn(account, amount)?;
+        self.track_dividends(account, Address::ZERO, amount);
+        Ok(())
+    }
+
+    /// The parent chain token the child chain gateway bridges this token to, `l1Address` in Arbitrum's
+    /// `IArbToken`
+    #[selector(name = "l1Address")]
+    pub fn l1_address(&self) -> Address {
+        self.arbitrum_gateway.counterpart.get()
+    }
+
+    /// Moves `amount` of `token` sent to the contract, this token included, to `to` (owner only)
+    pub fn recover_erc20(&mut self, token: Address, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.recover(token, to, amount)
+    }
+
+    /// Burns `burn_bps` basis points of every transfer from what the recipient gets, at most 10%
+    /// (owner only)
+    pub fn set_auto_burn(&mut self, burn_bps: U256) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.set_burn_rate(burn_bps)
+    }
+
+    /// Basis points of every transfer that are burned
+    pub fn auto_burn_bps(&self) -> U256 {
+        self.deflation.burn_bps.get()
+    }
+
+    /// Sets the treasury buybacks pull WETH from and the token's WETH pair they swap it in
+    /// (owner only). The treasury approves the token for WETH
+    pub fn configure_buyback(&mut self, treasury: Address, pair: Address, weth: Address) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.set_buyback(treasury, pair, weth)
+    }
+
+    /// The buyback treasury, pair and WETH
+    pub fn buyback_config(&self) -> (Address, Address, Address) {
+        let deflation = &self.deflation;
+        (deflation.treasury.get(), deflation.pair.get(), deflation.weth.get())
+    }
+
+    /// Swaps `amount` of the treasury's WETH for tokens in the pair and burns them, failing if that's
+    /// fewer than `min_burned`, and returns how many were burned (owner only)
+    pub fn buyback_and_burn(&mut self, amount: U256, min_burned: U256) -> Result<U256, Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.buyback(amount, min_burned)
+    }
+
+    /// Arms the launch guard, which stops transfers besides the owner's and exempt accounts' until
+    /// `enable_trading`, then limits buys from pairs to `max_buy`, holdings to `max_wallet` and
+    /// trades through pairs to one per `cooldown_blocks` L2 blocks. Zero turns a limit off (owner only)
+    pub fn arm_launch_guard(
+        &mut self,
+        max_buy: U256,
+        max_wallet: U256,
+        cooldown_blocks: u64,
+    ) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.require_launch_guard()?;
+        self.set_launch_limits(max_buy, max_wallet, cooldown_blocks);
+        Ok(())
+    }
+
+    /// Lets everyone trade within the launch guard's limits (owner only)
+    pub fn enable_trading(&mut self) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.require_launch_guard()?;
+        self.launch_guard.trading_enabled.set(true);
+
+        log(self.vm(), TradingEnabled {});
+        Ok(())
+    }
+
+    /// Registers or unregisters an AMM pair, which buys come from and sells go to (owner only)
+    pub fn set_launch_pair(&mut self, pair: Address, is_pair: bool) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.require_launch_guard()?;
+        self.launch_guard.pairs.insert(pair, is_pair);
+
+        log(self.vm(), LaunchPairSet { pair, isPair: is_pair });
+        Ok(())
+    }
+
+    /// Exempts `account` from the launch guard, or stops exempting it (owner only)
+    pub fn set_launch_exempt(&mut self, account: Address, exempt: bool) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.require_launch_guard()?;
+        self.launch_guard.exempt.insert(account, exempt);
+
+        log(self.vm(), LaunchExemptionSet { account, exempt });
+        Ok(())
+    }
+
+    /// Turns the launch guard off for good, with trading enabled (owner only)
+    pub fn renounce_launch_guard(&mut self) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.require_launch_guard()?;
+        self.disarm_launch_guard();
+        Ok(())
+    }
+
+    /// Whether the launch guard is armed, trading is enabled and the guard was renounced, then the
+    /// max buy, the max wallet and the cooldown
+    pub fn launch_guard(&self) -> (bool, bool, bool, U256, U256, u64) {
+        let guard = &self.launch_guard;
+        (
+            guard.armed.get(),
+            guard.trading_enabled.get(),
+            guard.renounced.get(),
+            guard.max_buy.get(),
+            guard.max_wallet.get(),
+            guard.cooldown_blocks.get().to(),
+        )
+    }
+
+    /// Whether `account` is a registered pair and whether it's exempt from the launch guard
+    pub fn launch_status(&self, account: Address) -> (bool, bool) {
+        (self.launch_guard.pairs.get(account), self.launch_guard.exempt.get(account))
+    }
+
+    /// Reflects `fee_bps` basis points of every transfer to holders, at most 10% (owner only, with
+    /// the `dividends` feature)
+    pub fn set_dividend_fee(&mut self, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.set_dividend_fee_bps(fee_bps)
+    }
+
+    /// Basis points of every transfer reflected to holders
+    pub fn dividend_fee_bps(&self) -> U256 {
+        self.dividends.fee_bps.get()
+    }
+
+    /// Stops `account`'s balance earning dividends, or lets it earn them again (owner only, with the
+    /// `dividends` feature)
+    pub fn set_dividend_excluded(&mut self, account: Address, excluded: bool) -> Result<(), Vec<u8>> {
+        self.ownable.require_owner()?;
+        self.set_dividend_exclusion(account, excluded)
+    }
+
+    /// Whether `account`'s balance doesn't earn dividends
+    pub fn is_dividend_excluded(&self, account: Address) -> bool {
+        self.dividends.excluded.get(account)
+    }
+
+    /// Pays the caller the dividends they earned and returns them (with the `dividends` feature)
+    pub fn withdraw_dividend(&mut self) -> Result<U256, Vec<u8>> {
+        self.pay_dividend(self.vm().msg_sender())
+    }
+
+    /// Dividends `account` can withdraw
+    pub fn withdrawable_dividend_of(&self, account: Address) -> U256 {
+        self.withdrawable_dividend(account)
+    }
+
+    /// Dividends `account` withdrew so far
+    pub fn withdrawn_dividend_of(&self, account: Address) -> U256 {
+        self.dividends.withdrawn.get(account)
+    }
+
+    /// Dividends ever distributed, and those the contract still holds for holders
+    pub fn dividend_totals(&self) -> (U256, U256) {
+        (self.dividends.total_distributed.get(), self.dividends.pool.get())
+    }
+
+    /// Moves `value` from `from` to `to` as `from` authorized in an EIP-3009 signature, for a relayer
+    /// to submit
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_authorization(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Vec<u8>> {
+        let authorization = Authorization { from, to, value, valid_after, valid_before, nonce };
+        self.use_authorization(TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization, (v, r, s))?;
+        self.check_launch(from, to, value)?;
+        self.erc20._transfer(from, to, value)?;
+        self.track_dividends(from, to, value);
+        self.auto_burn(from, to, value)?;
+        self.reflect(from, to, value)?;
+        Ok(())
+    }
+
+    /// Like `transfer_with_authorization`, but can only be submitted by the payee `to`
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_with_authorization(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller != to {
+            return Err(InvalidAuthorizationCaller { caller, payee: to }.abi_encode());
+        }
+        let authorization = Authorization { from, to, value, valid_after, valid_before, nonce };
+        self.use_authorization(RECEIVE_WITH_AUTHORIZATION_TYPEHASH, &authorization, (v, r, s))?;
+        self.check_launch(from, to, value)?;
+        self.erc20._transfer(from, to, value)?;
+        self.track_dividends(from, to, value);
+        self.auto_burn(from, to, value)?;
+        self.reflect(from, to, value)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Revokes an authorization `authorizer` signed but nobody submitted yet, by using its nonce
+    pub fn cancel_authorization(
+        &mut self,
+        authorizer: Address,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Vec<u8>> {
+        let struct_hash = cradle_crypto::hash_struct(self.vm(), CANCEL_AUTHORIZATION_TYPEHASH, (authorizer, nonce));
+        self.check_authorization_signature(authorizer, struct_hash, (v, r, s))?;
+        self.consume_authorization_nonce(authorizer, nonce)?;
+
+        log(self.vm(), AuthorizationCanceled { authorizer, nonce });
+        Ok(())
+    }
+
+    /// Whether `authorizer`'s authorization with `nonce` was used or cancelled
+    pub fn authorization_state(&self, authorizer: Address, nonce: B256) -> bool {
+        self.authorizations.is_unordered_nonce_used(authorizer, U256::from_be_bytes(nonce.0))
+    }
+
+    /// EIP-712 domain separator EIP-3009 authorizations are signed under
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        self.authorization_domain_separator()
+    }
+
+    /// Role allowed to mint and burn for bridges
+    #[selector(name = "BRIDGE_ROLE")]
+    pub fn bridge_role(&self) -> B256 {
+        BRIDGE_ROLE
+    }
+
+    /// Role allowed to configure minters
+    #[selector(name = "MASTER_MINTER_ROLE")]
+    pub fn master_minter_role(&self) -> B256 {
+        MASTER_MINTER_ROLE
+    }
+
+    /// Hash of the sources the contract was built from and its crate version, see `cradle-build-info`
+    pub fn build_metadata(&self) -> (B256, String) {
+        (B256::new(SOURCE_HASH), env!("CARGO_PKG_VERSION").into())
+    }
+
+    /// Whether the token supports a given standard
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, INTERFACES)
+    }
+}
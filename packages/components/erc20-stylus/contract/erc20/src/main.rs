@@ -6,5 +6,10 @@ pub extern "C" fn main() {}
 
 #[cfg(feature = "export-abi")]
 fn main() {
-    stylus_hello_world::print_from_args();
+    // `interface` prints IERC20Token.sol, anything else goes to the SDK's ABI export
+    if std::env::args().nth(1).as_deref() == Some("interface") {
+        print!("{}", stylus_hello_world::interface::solidity_interface());
+    } else {
+        stylus_hello_world::print_from_args();
+    }
 }
@@ -0,0 +1,57 @@
+//! Minters with bounded allowances, like USDC's `FiatToken`
+//!
+//! Instead of every bridge and treasury sharing one key that mints without limit, a master minter
+//! configures each minter with an allowance. `mintWithinAllowance`, `mint` and `mintTo` draw it
+//! down, so a minter that is compromised or misbehaves can mint no more than it was given, and the
+//! master minter tops it up or removes the minter as needed. Configuring a minter again replaces
+//! its allowance rather than adding to it. Only the owner mints without an allowance.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+
+use crate::SuperPositionToken;
+
+sol_storage! {
+    pub struct Minters {
+        /// Accounts the master minter configured
+        mapping(address => bool) minters;
+        /// What each minter can still mint
+        mapping(address => uint256) allowances;
+    }
+}
+
+// Minter events and errors
+sol! {
+    event MinterConfigured(address indexed minter, uint256 allowance);
+    event MinterRemoved(address indexed minter);
+
+    // The caller isn't a configured minter
+    error NotMinter(address caller);
+    error MinterAllowanceExceeded(address minter, uint256 allowance, uint256 needed);
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Lets `caller` mint `amount`: freely as the owner, otherwise drawing it from its allowance.
+    pub(crate) fn authorize_mint(&mut self, caller: Address, amount: U256) -> Result<(), Vec<u8>> {
+        if caller == self.ownable.owner() {
+            return Ok(());
+        }
+        self.use_minter_allowance(caller, amount)
+    }
+
+    /// Draws `amount` from `minter`'s allowance.
+    pub(crate) fn use_minter_allowance(&mut self, minter: Address, amount: U256) -> Result<(), Vec<u8>> {
+        if !self.minters.minters.get(minter) {
+            return Err(NotMinter { caller: minter }.abi_encode());
+        }
+        let allowance = self.minters.allowances.get(minter);
+        if allowance < amount {
+            return Err(MinterAllowanceExceeded { minter, allowance, needed: amount }.abi_encode());
+        }
+        self.minters.allowances.insert(minter, allowance - amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+//! Recovery of tokens sent to the token contract
+//!
+//! Wallets let users paste the token's own address as the recipient, and tokens sent there, or any
+//! other ERC-20 sent to it, are otherwise stuck for good. The owner moves them out with
+//! `recoverErc20`. Besides dividends that weren't withdrawn yet, the contract doesn't hold its own
+//! token for anyone, since the gateway and bridges mint and burn rather than escrow here, so the
+//! rest of its balance of itself is recoverable.
+//! Other tokens are moved with their own `transfer`, accepting those that return nothing, like
+//! USDT, as long as there's a contract at the token's address.
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolError, SolValue};
+use stylus_sdk::prelude::*;
+
+use crate::{erc20::InsufficientBalance, ExternalCall, SuperPositionToken};
+
+sol! {
+    interface IERC20Transfer {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+// Recovery events and errors
+sol! {
+    event ERC20Recovered(address indexed token, address indexed to, uint256 amount);
+
+    // The token's `transfer` reverted or returned false, or the token has no code
+    error ERC20RecoveryFailed(address token);
+}
+
+// These methods aren't exposed to other contracts
+impl SuperPositionToken {
+    /// Moves `amount` of `token` the contract holds to `to`.
+    pub(crate) fn recover(&mut self, token: Address, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        let contract = self.vm().contract_address();
+        if token == contract {
+            let have = self.erc20.balance_of(contract).saturating_sub(self.dividends.pool.get());
+            if amount > have {
+                return Err(InsufficientBalance { from: contract, have, want: amount }.abi_encode());
+            }
+            self.erc20._transfer(contract, to, amount)?;
+            self.track_dividends(contract, to, amount);
+        } else {
+            let data = IERC20Transfer::transferCall { to, value: amount }.abi_encode();
+            let transferred = self.vm().call(&ExternalCall, token, &data).is_ok_and(|output| match output.is_empty() {
+                // Nothing at an address without code also returns nothing
+                true => self.vm().code_size(token) > 0,
+                false => bool::abi_decode(&output, true).unwrap_or(false),
+            });
+            if !transferred {
+                return Err(ERC20RecoveryFailed { token }.abi_encode());
+            }
+        }
+
+        log(self.vm(), ERC20Recovered { token, to, amount });
+        Ok(())
+    }
+}
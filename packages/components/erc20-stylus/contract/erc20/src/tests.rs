@@ -0,0 +1,793 @@
+use super::*;
+use crate::authorization::{
+    AuthorizationAlreadyUsed, AuthorizationExpired, AuthorizationNotYetValid, AuthorizationUsed,
+    InvalidAuthorizationSignature,
+};
+use crate::deflation::{
+    AutoBurned, BuybackAndBurn, BuybackBelowMinimum, BuybackCallFailed, BuybackConfigured, BuybackNotConfigured,
+    IBuybackPair, IBuybackWeth, InvalidBurnRate, InvalidBuybackPair, MAX_BURN_BPS,
+};
+use crate::erc20::{Approval, InsufficientAllowance, InsufficientBalance, Transfer};
+use crate::gateway::{
+    ArbitrumGatewayNotSet, GatewayCallFailed, IL1CustomGateway, IL1GatewayRouter, IncorrectRegistrationValue,
+    TokenRegisteredOnL2, UnauthorizedGateway,
+};
+use crate::launch_guard::{
+    LaunchGuardIsRenounced, MaxBuyExceeded, MaxWalletExceeded, TradeCooldown, TradingNotEnabled,
+};
+use crate::minters::{MinterAllowanceExceeded, NotMinter};
+use crate::recovery::{ERC20Recovered, ERC20RecoveryFailed, IERC20Transfer};
+use alloc::vec::Vec;
+use cradle_access_control::AccessControlUnauthorizedAccount;
+use cradle_arb::{ArbSys, ARB_SYS};
+use cradle_ownable::OwnableUnauthorizedAccount;
+use cradle_test_utils::{deploy, emitted, ok, reverted, sign};
+use stylus_sdk::{
+    alloy_primitives::address,
+    alloy_sol_types::{SolCall, SolValue},
+    testing::TestVM,
+};
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const MINTER: Address = address!("000000000000000000000000000000000000a1a7");
+const TREASURY: Address = address!("0000000000000000000000000000000000007e55");
+const PAIR: Address = address!("000000000000000000000000000000000000fa12");
+const WETH: Address = address!("000000000000000000000000000000000000e7e4");
+const BRIDGE: Address = address!("000000000000000000000000000000000000b71d");
+const GATEWAY: Address = address!("0000000000000000000000000000000000006a7e");
+const ROUTER: Address = address!("000000000000000000000000000000000000a07e");
+const L1_TOKEN: Address = address!("00000000000000000000000000000000000011a1");
+const L2_TOKEN: Address = address!("00000000000000000000000000000000000012a2");
+
+fn tokens(n: u64) -> U256 {
+    U256::from(n)
+}
+
+/// A token initialized by `OWNER`, with the VM to drive it.
+fn setup() -> (TestVM, SuperPositionToken) {
+    let (vm, mut contract) = deploy::<SuperPositionToken>(OWNER);
+    ok(contract.initialize(OWNER));
+    (vm, contract)
+}
+
+/// `setup`, plus 1000 tokens minted to `ALICE` and 500 to `BOB`.
+fn setup_with_balances() -> (TestVM, SuperPositionToken) {
+    let (vm, mut contract) = setup();
+    ok(contract.mint_to(ALICE, tokens(1_000)));
+    ok(contract.mint_to(BOB, tokens(500)));
+    (vm, contract)
+}
+
+#[test]
+fn owner_mints_without_an_allowance() {
+    let (vm, mut contract) = setup();
+    ok(contract.mint(tokens(10)));
+    ok(contract.mint_to(ALICE, tokens(20)));
+    assert_eq!(contract.erc20.balance_of(OWNER), tokens(10));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(20));
+    assert_eq!(contract.erc20.total_supply(), tokens(30));
+    let event = emitted::<Transfer>(&vm).pop().expect("mint logged");
+    assert_eq!((event.from, event.to, event.value), (Address::ZERO, ALICE, tokens(20)));
+}
+
+#[test]
+fn unauthorized_mints_revert() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.mint(tokens(1))), NotMinter { caller: ALICE }.abi_encode());
+    assert_eq!(reverted(contract.mint_to(ALICE, tokens(1))), NotMinter { caller: ALICE }.abi_encode());
+    assert_eq!(contract.erc20.balance_of(ALICE), U256::ZERO);
+    assert_eq!(contract.erc20.total_supply(), U256::ZERO);
+}
+
+#[test]
+fn minters_mint_within_their_allowance() {
+    let (vm, mut contract) = setup();
+    ok(contract.access_control.grant_role(MASTER_MINTER_ROLE, OWNER));
+    ok(contract.configure_minter(MINTER, tokens(100)));
+
+    vm.set_sender(MINTER);
+    ok(contract.mint(tokens(30)));
+    ok(contract.mint_to(ALICE, tokens(50)));
+    ok(contract.mint_within_allowance(BOB, tokens(20)));
+    assert_eq!(contract.minter_allowance(MINTER), U256::ZERO);
+    let error = MinterAllowanceExceeded { minter: MINTER, allowance: U256::ZERO, needed: tokens(1) };
+    assert_eq!(reverted(contract.mint_to(ALICE, tokens(1))), error.abi_encode());
+    assert_eq!(contract.erc20.total_supply(), tokens(100));
+
+    // A removed minter can't mint even with allowance left
+    vm.set_sender(OWNER);
+    ok(contract.configure_minter(MINTER, tokens(100)));
+    ok(contract.remove_minter(MINTER));
+    vm.set_sender(MINTER);
+    assert_eq!(reverted(contract.mint(tokens(1))), NotMinter { caller: MINTER }.abi_encode());
+}
+
+#[test]
+fn holders_burn_within_their_balance() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    ok(contract.burn(tokens(400)));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(600));
+    assert_eq!(contract.erc20.total_supply(), tokens(1_100));
+
+    let error = InsufficientBalance { from: ALICE, have: tokens(600), want: tokens(601) };
+    assert_eq!(reverted(contract.burn(tokens(601))), error.abi_encode());
+}
+
+#[test]
+fn holders_raise_and_lower_allowances_without_underflowing() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    assert!(contract.erc20.increase_allowance(BOB, tokens(100)));
+    assert!(contract.erc20.increase_allowance(BOB, tokens(50)));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), tokens(150));
+    assert!(ok(contract.erc20.decrease_allowance(BOB, tokens(30))));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), tokens(120));
+    let event = emitted::<Approval>(&vm).pop().expect("approval logged");
+    assert_eq!((event.owner, event.spender, event.value), (ALICE, BOB, tokens(120)));
+
+    let error = InsufficientAllowance { owner: ALICE, spender: BOB, have: tokens(120), want: tokens(121) };
+    assert_eq!(reverted(contract.erc20.decrease_allowance(BOB, tokens(121))), error.abi_encode());
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), tokens(120));
+    ok(contract.erc20.decrease_allowance(BOB, tokens(120)));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), U256::ZERO);
+
+    // Raising an allowance saturates at an infinite one
+    assert!(contract.erc20.increase_allowance(BOB, U256::MAX));
+    assert!(contract.erc20.increase_allowance(BOB, tokens(1)));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), U256::MAX);
+}
+
+#[test]
+fn transfers_from_spend_finite_allowances_only() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_sender(ALICE);
+    contract.erc20.approve(BOB, tokens(100));
+    vm.set_sender(BOB);
+    ok(contract.transfer_from(ALICE, BOB, tokens(60)));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), tokens(40));
+    let error = InsufficientAllowance { owner: ALICE, spender: BOB, have: tokens(40), want: tokens(41) };
+    assert_eq!(reverted(contract.transfer_from(ALICE, BOB, tokens(41))), error.abi_encode());
+
+    vm.set_sender(ALICE);
+    contract.erc20.approve(BOB, U256::MAX);
+    vm.set_sender(BOB);
+    ok(contract.transfer_from(ALICE, BOB, tokens(60)));
+    assert_eq!(contract.erc20.allowance(ALICE, BOB), U256::MAX);
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(620));
+}
+
+#[test]
+fn only_bridges_mint_and_burn_crosschain() {
+    let (vm, mut contract) = setup_with_balances();
+    let error = AccessControlUnauthorizedAccount { account: OWNER, neededRole: BRIDGE_ROLE }.abi_encode();
+    assert_eq!(reverted(contract.crosschain_mint(ALICE, tokens(1))), error);
+    assert_eq!(reverted(contract.crosschain_burn(ALICE, tokens(1))), error);
+    ok(contract.access_control.grant_role(BRIDGE_ROLE, BRIDGE));
+
+    vm.set_sender(BRIDGE);
+    ok(contract.crosschain_mint(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+    let event = emitted::<CrosschainMint>(&vm).pop().expect("mint logged");
+    assert_eq!((event.to, event.amount, event.sender), (BOB, tokens(100), BRIDGE));
+
+    // Bridges burn without an allowance
+    ok(contract.crosschain_burn(ALICE, tokens(400)));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(600));
+    assert_eq!(contract.erc20.total_supply(), tokens(1_200));
+    let event = emitted::<CrosschainBurn>(&vm).pop().expect("burn logged");
+    assert_eq!((event.from, event.amount, event.sender), (ALICE, tokens(400), BRIDGE));
+    let error = InsufficientBalance { from: ALICE, have: tokens(600), want: tokens(601) };
+    assert_eq!(reverted(contract.crosschain_burn(ALICE, tokens(601))), error.abi_encode());
+}
+
+/// Registration parameters paying the gateway `value_for_gateway` and the router `value_for_router`.
+fn registration(value_for_gateway: u64, value_for_router: u64) -> Registration {
+    Registration {
+        max_submission_cost_for_custom_gateway: tokens(1),
+        max_submission_cost_for_router: tokens(2),
+        max_gas_for_custom_gateway: tokens(3),
+        max_gas_for_router: tokens(4),
+        gas_price_bid: tokens(5),
+        value_for_gateway: tokens(value_for_gateway),
+        value_for_router: tokens(value_for_router),
+        credit_back_address: OWNER,
+    }
+}
+
+fn register_on_l2(contract: &mut SuperPositionToken, registration: &Registration) -> Result<(), Vec<u8>> {
+    contract.register_token_on_l2(
+        L2_TOKEN,
+        registration.max_submission_cost_for_custom_gateway,
+        registration.max_submission_cost_for_router,
+        registration.max_gas_for_custom_gateway,
+        registration.max_gas_for_router,
+        registration.gas_price_bid,
+        registration.value_for_gateway,
+        registration.value_for_router,
+        registration.credit_back_address,
+    )
+}
+
+/// Mocks `GATEWAY` and `ROUTER` answering `gateway_answer` and `router_answer` to the calls
+/// registering `L2_TOKEN` with `registration` makes.
+fn mock_registration(
+    vm: &TestVM,
+    registration: &Registration,
+    gateway_answer: Result<Vec<u8>, Vec<u8>>,
+    router_answer: Result<Vec<u8>, Vec<u8>>,
+) {
+    let register = IL1CustomGateway::registerTokenToL2Call {
+        l2_address: L2_TOKEN,
+        max_gas: registration.max_gas_for_custom_gateway,
+        gas_price_bid: registration.gas_price_bid,
+        max_submission_cost: registration.max_submission_cost_for_custom_gateway,
+        credit_back_address: registration.credit_back_address,
+    };
+    vm.mock_call(GATEWAY, register.abi_encode(), gateway_answer);
+    let set_gateway = IL1GatewayRouter::setGatewayCall {
+        gateway: GATEWAY,
+        max_gas: registration.max_gas_for_router,
+        gas_price_bid: registration.gas_price_bid,
+        max_submission_cost: registration.max_submission_cost_for_router,
+        credit_back_address: registration.credit_back_address,
+    };
+    vm.mock_call(ROUTER, set_gateway.abi_encode(), router_answer);
+}
+
+#[test]
+fn the_owner_registers_the_token_with_the_custom_gateway_and_router() {
+    let (vm, mut contract) = setup();
+    let registration = registration(3, 4);
+    vm.set_value(tokens(7));
+    assert_eq!(reverted(register_on_l2(&mut contract, &registration)), ArbitrumGatewayNotSet {}.abi_encode());
+    vm.set_sender(ALICE);
+    let error = OwnableUnauthorizedAccount { account: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_arbitrum_gateway(GATEWAY, ROUTER, Address::ZERO)), error);
+    assert_eq!(reverted(register_on_l2(&mut contract, &registration)), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.set_arbitrum_gateway(GATEWAY, ROUTER, Address::ZERO));
+    assert_eq!(contract.arbitrum_gateway(), (GATEWAY, ROUTER, Address::ZERO));
+    let event = emitted::<ArbitrumGatewaySet>(&vm).pop().expect("gateway logged");
+    assert_eq!((event.gateway, event.router, event.counterpart), (GATEWAY, ROUTER, Address::ZERO));
+    vm.set_value(tokens(6));
+    let error = IncorrectRegistrationValue { expected: tokens(7), received: tokens(6) }.abi_encode();
+    assert_eq!(reverted(register_on_l2(&mut contract, &registration)), error);
+
+    // The gateway and router only fail the calls the registration asks for, so failing shows it asked
+    vm.set_value(tokens(7));
+    mock_registration(&vm, &registration, Err(Vec::new()), Ok(Vec::new()));
+    let error = GatewayCallFailed { target: GATEWAY }.abi_encode();
+    assert_eq!(reverted(register_on_l2(&mut contract, &registration)), error);
+    mock_registration(&vm, &registration, Ok(Vec::new()), Err(Vec::new()));
+    let error = GatewayCallFailed { target: ROUTER }.abi_encode();
+    assert_eq!(reverted(register_on_l2(&mut contract, &registration)), error);
+
+    mock_registration(&vm, &registration, Ok(Vec::new()), Ok(Vec::new()));
+    ok(register_on_l2(&mut contract, &registration));
+    assert_eq!(contract.l1_address(), L2_TOKEN);
+    let event = emitted::<TokenRegisteredOnL2>(&vm).pop().expect("registration logged");
+    assert_eq!((event.l2Token, event.gateway), (L2_TOKEN, GATEWAY));
+    // Outside a registration, the token doesn't vouch for whoever asks the gateway
+    assert_eq!(reverted(contract.is_arbitrum_enabled()), NotExpectedCall {}.abi_encode());
+}
+
+#[test]
+fn only_the_child_chain_gateway_mints_and_burns_for_the_bridge() {
+    let (vm, mut contract) = setup_with_balances();
+    // Without a gateway, not even calls from the zero address get through
+    for caller in [Address::ZERO, GATEWAY] {
+        vm.set_sender(caller);
+        assert_eq!(reverted(contract.bridge_mint(ALICE, tokens(1))), UnauthorizedGateway { caller }.abi_encode());
+    }
+    vm.set_sender(OWNER);
+    ok(contract.set_arbitrum_gateway(GATEWAY, Address::ZERO, L1_TOKEN));
+    assert_eq!(contract.l1_address(), L1_TOKEN);
+    vm.set_sender(ALICE);
+    let error = UnauthorizedGateway { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.bridge_mint(ALICE, tokens(1))), error);
+    assert_eq!(reverted(contract.bridge_burn(ALICE, tokens(1))), error);
+
+    vm.set_sender(GATEWAY);
+    ok(contract.bridge_mint(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+    // Withdrawals burn without an allowance
+    ok(contract.bridge_burn(ALICE, tokens(400)));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(600));
+    assert_eq!(contract.erc20.total_supply(), tokens(1_200));
+    let event = emitted::<Transfer>(&vm).pop().expect("burn logged");
+    assert_eq!((event.from, event.to, event.value), (ALICE, Address::ZERO, tokens(400)));
+    let error = InsufficientBalance { from: ALICE, have: tokens(600), want: tokens(601) };
+    assert_eq!(reverted(contract.bridge_burn(ALICE, tokens(601))), error.abi_encode());
+}
+
+#[test]
+fn the_owner_recovers_tokens_sent_to_the_token_itself() {
+    let (vm, mut contract) = setup_with_balances();
+    let this = vm.contract_address();
+    vm.set_sender(ALICE);
+    ok(contract.transfer(this, tokens(100)));
+    let error = OwnableUnauthorizedAccount { account: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.recover_erc20(this, ALICE, tokens(100))), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.recover_erc20(this, ALICE, tokens(60)));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(960));
+    assert_eq!(contract.erc20.balance_of(this), tokens(40));
+    let event = emitted::<ERC20Recovered>(&vm).pop().expect("recovery logged");
+    assert_eq!((event.token, event.to, event.amount), (this, ALICE, tokens(60)));
+    let error = InsufficientBalance { from: this, have: tokens(40), want: tokens(41) };
+    assert_eq!(reverted(contract.recover_erc20(this, ALICE, tokens(41))), error.abi_encode());
+}
+
+#[test]
+fn other_tokens_are_recovered_with_their_transfer() {
+    let (vm, mut contract) = setup();
+    let transfer = IERC20Transfer::transferCall { to: ALICE, value: tokens(50) }.abi_encode();
+    let failed = ERC20RecoveryFailed { token: WETH }.abi_encode();
+    // Nothing at an address without code answers nothing too
+    assert_eq!(reverted(contract.recover_erc20(WETH, ALICE, tokens(50))), failed);
+    for answer in [Ok(false.abi_encode()), Err(Vec::new())] {
+        vm.mock_call(WETH, transfer.clone(), answer);
+        assert_eq!(reverted(contract.recover_erc20(WETH, ALICE, tokens(50))), failed);
+    }
+
+    vm.mock_call(WETH, transfer.clone(), Ok(true.abi_encode()));
+    ok(contract.recover_erc20(WETH, ALICE, tokens(50)));
+    let event = emitted::<ERC20Recovered>(&vm).pop().expect("recovery logged");
+    assert_eq!((event.token, event.to, event.amount), (WETH, ALICE, tokens(50)));
+    // Like USDT, a token with code may answer nothing
+    vm.set_code(WETH, vec![0xfe]);
+    vm.mock_call(WETH, transfer, Ok(Vec::new()));
+    ok(contract.recover_erc20(WETH, ALICE, tokens(50)));
+}
+
+#[test]
+fn transfers_burn_the_auto_burn_share_of_what_the_recipient_gets() {
+    let (vm, mut contract) = setup_with_balances();
+    let too_high = U256::from(MAX_BURN_BPS + 1);
+    assert_eq!(reverted(contract.set_auto_burn(too_high)), InvalidBurnRate { burnBps: too_high }.abi_encode());
+    ok(contract.set_auto_burn(U256::from(500)));
+    assert_eq!(contract.auto_burn_bps(), U256::from(500));
+
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(900));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(595));
+    assert_eq!(contract.erc20.total_supply(), tokens(1_495));
+    let event = emitted::<AutoBurned>(&vm).pop().expect("burn logged");
+    assert_eq!((event.from, event.to, event.amount), (ALICE, BOB, tokens(5)));
+
+    // 5% of 19 rounds down to nothing
+    ok(contract.transfer(BOB, tokens(19)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(614));
+    assert_eq!(emitted::<AutoBurned>(&vm).len(), 1);
+}
+
+/// Mocks `PAIR` as a pair of `token0` and `token1`.
+fn mock_pair(vm: &TestVM, token0: Address, token1: Address) {
+    vm.mock_static_call(PAIR, IBuybackPair::token0Call {}.abi_encode(), Ok(token0.abi_encode()));
+    vm.mock_static_call(PAIR, IBuybackPair::token1Call {}.abi_encode(), Ok(token1.abi_encode()));
+}
+
+#[test]
+fn buybacks_swap_through_the_token_weth_pair() {
+    let (vm, mut contract) = setup();
+    let error = BuybackNotConfigured {}.abi_encode();
+    assert_eq!(reverted(contract.buyback_and_burn(tokens(1), U256::ZERO)), error);
+
+    mock_pair(&vm, WETH, BOB);
+    let error = InvalidBuybackPair { pair: PAIR }.abi_encode();
+    assert_eq!(reverted(contract.configure_buyback(TREASURY, PAIR, WETH)), error);
+    assert_eq!(contract.buyback_config(), (Address::ZERO, Address::ZERO, Address::ZERO));
+
+    // Either order of the pair's tokens does
+    mock_pair(&vm, WETH, vm.contract_address());
+    ok(contract.configure_buyback(TREASURY, PAIR, WETH));
+    assert_eq!(contract.buyback_config(), (TREASURY, PAIR, WETH));
+    let event = emitted::<BuybackConfigured>(&vm).pop().expect("configuration logged");
+    assert_eq!((event.treasury, event.pair, event.weth), (TREASURY, PAIR, WETH));
+}
+
+#[test]
+fn buybacks_swap_at_the_pairs_reserves_and_burn_what_arrives() {
+    let (vm, mut contract) = setup();
+    let token = vm.contract_address();
+    mock_pair(&vm, token, WETH);
+    ok(contract.configure_buyback(TREASURY, PAIR, WETH));
+    let reserves = (tokens(10_000), tokens(1_000), U256::ZERO).abi_encode();
+    vm.mock_static_call(PAIR, IBuybackPair::getReservesCall {}.abi_encode(), Ok(reserves));
+
+    // WETH that doesn't move fails the buyback
+    let error = BuybackCallFailed { target: WETH }.abi_encode();
+    assert_eq!(reverted(contract.buyback_and_burn(tokens(100), U256::ZERO)), error);
+    let pulled = IBuybackWeth::transferFromCall { from: TREASURY, to: PAIR, value: tokens(100) };
+    vm.mock_call(WETH, pulled.abi_encode(), Ok(true.abi_encode()));
+
+    // 100 WETH less the 0.3% fee buys 99.7 * 10000 / 1099.7 tokens, rounded down. The pair only
+    // fails that exact swap, so failing the buyback shows it was asked for it
+    let swap =
+        IBuybackPair::swapCall { amount0Out: tokens(906), amount1Out: U256::ZERO, to: token, data: Default::default() };
+    vm.mock_call(PAIR, swap.abi_encode(), Err(Vec::new()));
+    let error = BuybackCallFailed { target: PAIR }.abi_encode();
+    assert_eq!(reverted(contract.buyback_and_burn(tokens(100), U256::ZERO)), error);
+
+    // The mocked pair doesn't send any tokens, so there's nothing to burn
+    vm.mock_call(PAIR, swap.abi_encode(), Ok(Vec::new()));
+    let error = BuybackBelowMinimum { minimum: tokens(1), received: U256::ZERO }.abi_encode();
+    assert_eq!(reverted(contract.buyback_and_burn(tokens(100), tokens(1))), error);
+    assert_eq!(ok(contract.buyback_and_burn(tokens(100), U256::ZERO)), U256::ZERO);
+    let event = emitted::<BuybackAndBurn>(&vm).pop().expect("buyback logged");
+    assert_eq!((event.spent, event.burned), (tokens(100), U256::ZERO));
+}
+
+/// `setup`, with the launch guard armed and trading enabled, `PAIR` registered as a pair and
+/// holding 1000 tokens.
+fn setup_launch(max_buy: U256, max_wallet: U256, cooldown_blocks: u64) -> (TestVM, SuperPositionToken) {
+    let (vm, mut contract) = setup();
+    ok(contract.mint_to(PAIR, tokens(1_000)));
+    ok(contract.arm_launch_guard(max_buy, max_wallet, cooldown_blocks));
+    ok(contract.set_launch_pair(PAIR, true));
+    ok(contract.enable_trading());
+    (vm, contract)
+}
+
+fn set_l2_block(vm: &TestVM, block: u64) {
+    vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(U256::from(block).abi_encode()));
+}
+
+#[test]
+fn armed_guard_holds_transfers_until_trading_is_enabled() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.arm_launch_guard(U256::ZERO, U256::ZERO, 0));
+    ok(contract.set_launch_exempt(BOB, true));
+
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.transfer(MINTER, tokens(10))), TradingNotEnabled {}.abi_encode());
+    // Transfers from or to the owner and exempt accounts go through
+    ok(contract.transfer(BOB, tokens(10)));
+    ok(contract.transfer(OWNER, tokens(10)));
+    vm.set_sender(OWNER);
+    ok(contract.transfer(ALICE, tokens(10)));
+
+    ok(contract.enable_trading());
+    vm.set_sender(ALICE);
+    ok(contract.transfer(MINTER, tokens(10)));
+    assert_eq!(contract.erc20.balance_of(MINTER), tokens(10));
+}
+
+#[test]
+fn guard_lets_pairs_pay_buybacks_out_to_the_token() {
+    let (vm, mut contract) = setup();
+    ok(contract.mint_to(PAIR, tokens(1_000)));
+    ok(contract.arm_launch_guard(tokens(100), tokens(100), 1));
+    ok(contract.set_launch_pair(PAIR, true));
+
+    // Before trading and past the max buy and wallet, as a buyback's swap would pay out
+    let token = vm.contract_address();
+    vm.set_sender(PAIR);
+    ok(contract.transfer(token, tokens(500)));
+    assert_eq!(contract.erc20.balance_of(token), tokens(500));
+    assert_eq!(reverted(contract.transfer(ALICE, tokens(1))), TradingNotEnabled {}.abi_encode());
+}
+
+#[test]
+fn guard_caps_buys_from_pairs_and_wallets() {
+    let (vm, mut contract) = setup_launch(tokens(100), tokens(700), 0);
+    ok(contract.mint_to(ALICE, tokens(650)));
+
+    vm.set_sender(PAIR);
+    let error = MaxBuyExceeded { maxBuy: tokens(100), amount: tokens(101) }.abi_encode();
+    assert_eq!(reverted(contract.transfer(BOB, tokens(101))), error);
+    let error = MaxWalletExceeded { account: ALICE, maxWallet: tokens(700), balance: tokens(710) }.abi_encode();
+    assert_eq!(reverted(contract.transfer(ALICE, tokens(60))), error);
+    ok(contract.transfer(ALICE, tokens(50)));
+
+    // Sells aren't capped, and the pair holds more than the max wallet
+    vm.set_sender(ALICE);
+    ok(contract.transfer(PAIR, tokens(700)));
+    assert_eq!(contract.erc20.balance_of(PAIR), tokens(1_650));
+
+    // Zero limits don't apply
+    vm.set_sender(OWNER);
+    ok(contract.arm_launch_guard(U256::ZERO, U256::ZERO, 0));
+    vm.set_sender(PAIR);
+    ok(contract.transfer(BOB, tokens(1_000)));
+}
+
+#[test]
+fn traders_wait_the_cooldown_between_trades_through_a_pair() {
+    let (vm, mut contract) = setup_launch(U256::ZERO, U256::ZERO, 10);
+    set_l2_block(&vm, 100);
+    vm.set_sender(PAIR);
+    ok(contract.transfer(ALICE, tokens(10)));
+
+    set_l2_block(&vm, 109);
+    vm.set_sender(ALICE);
+    let error = TradeCooldown { account: ALICE, availableAt: 110 }.abi_encode();
+    assert_eq!(reverted(contract.transfer(PAIR, tokens(5))), error);
+    // Transfers between wallets aren't trades, and other traders have their own cooldown
+    ok(contract.transfer(BOB, tokens(5)));
+    vm.set_sender(PAIR);
+    ok(contract.transfer(BOB, tokens(5)));
+
+    set_l2_block(&vm, 110);
+    vm.set_sender(ALICE);
+    ok(contract.transfer(PAIR, tokens(5)));
+    assert_eq!(contract.erc20.balance_of(ALICE), U256::ZERO);
+}
+
+#[test]
+fn renouncing_the_guard_turns_it_off_for_good() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.arm_launch_guard(tokens(1), tokens(1), 10));
+    ok(contract.renounce_launch_guard());
+    assert_eq!(contract.launch_guard(), (false, true, true, tokens(1), tokens(1), 10));
+    let error = LaunchGuardIsRenounced {}.abi_encode();
+    assert_eq!(reverted(contract.arm_launch_guard(U256::ZERO, U256::ZERO, 0)), error);
+    assert_eq!(reverted(contract.enable_trading()), error);
+
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+}
+
+/// `ALICE`'s authorization to pay `BOB` 100 tokens, valid strictly between timestamps 1000 and 2000.
+fn payment(nonce: u8) -> Authorization {
+    Authorization {
+        from: ALICE,
+        to: BOB,
+        value: tokens(100),
+        valid_after: tokens(1_000),
+        valid_before: tokens(2_000),
+        nonce: B256::repeat_byte(nonce),
+    }
+}
+
+/// `signer`'s `(v, r, s)` over `authorization` of type `typehash`.
+fn sign_authorization(
+    vm: &TestVM,
+    contract: &SuperPositionToken,
+    signer: Address,
+    typehash: B256,
+    authorization: &Authorization,
+) -> (u8, B256, B256) {
+    let Authorization { from, to, value, valid_after, valid_before, nonce } = *authorization;
+    let struct_hash = cradle_crypto::hash_struct(vm, typehash, (from, to, value, valid_after, valid_before, nonce));
+    let digest = cradle_crypto::hash_typed_data(vm, contract.domain_separator(), struct_hash);
+    split(sign(vm, signer, digest))
+}
+
+/// `(v, r, s)` of a 65-byte signature.
+fn split(signature: Vec<u8>) -> (u8, B256, B256) {
+    (signature[64], B256::from_slice(&signature[..32]), B256::from_slice(&signature[32..64]))
+}
+
+fn transfer_with(
+    contract: &mut SuperPositionToken,
+    authorization: &Authorization,
+    (v, r, s): (u8, B256, B256),
+) -> Result<(), Vec<u8>> {
+    let Authorization { from, to, value, valid_after, valid_before, nonce } = *authorization;
+    contract.transfer_with_authorization(from, to, value, valid_after, valid_before, nonce, v, r, s)
+}
+
+fn receive_with(
+    contract: &mut SuperPositionToken,
+    authorization: &Authorization,
+    (v, r, s): (u8, B256, B256),
+) -> Result<(), Vec<u8>> {
+    let Authorization { from, to, value, valid_after, valid_before, nonce } = *authorization;
+    contract.receive_with_authorization(from, to, value, valid_after, valid_before, nonce, v, r, s)
+}
+
+#[test]
+fn relayers_submit_authorized_transfers_once() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_500);
+    let authorization = payment(1);
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+    assert!(!contract.authorization_state(ALICE, authorization.nonce));
+
+    vm.set_sender(MINTER);
+    ok(transfer_with(&mut contract, &authorization, signature));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(900));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+    assert!(contract.authorization_state(ALICE, authorization.nonce));
+    let event = emitted::<AuthorizationUsed>(&vm).pop().expect("use logged");
+    assert_eq!((event.authorizer, event.nonce), (ALICE, authorization.nonce));
+
+    let error = AuthorizationAlreadyUsed { authorizer: ALICE, nonce: authorization.nonce }.abi_encode();
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+
+    // Nonces are random rather than sequential, so another is still good
+    let authorization = payment(0);
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+    ok(transfer_with(&mut contract, &authorization, signature));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(700));
+}
+
+#[test]
+fn authorizations_must_be_signed_by_the_payer_over_what_is_submitted() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_500);
+    let error = InvalidAuthorizationSignature { authorizer: ALICE }.abi_encode();
+    let authorization = payment(1);
+    let signature = sign_authorization(&vm, &contract, BOB, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+
+    // ALICE's signature doesn't cover a larger value, or the same fields as a receive
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+    let inflated = Authorization { value: tokens(101), ..payment(1) };
+    assert_eq!(reverted(transfer_with(&mut contract, &inflated, signature)), error);
+    vm.set_sender(BOB);
+    assert_eq!(reverted(receive_with(&mut contract, &authorization, signature)), error);
+    assert!(!contract.authorization_state(ALICE, authorization.nonce));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(500));
+}
+
+#[test]
+fn authorizations_are_valid_strictly_between_their_bounds() {
+    let (vm, mut contract) = setup_with_balances();
+    let authorization = payment(1);
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+
+    vm.set_block_timestamp(1_000);
+    let error = AuthorizationNotYetValid { validAfter: tokens(1_000) }.abi_encode();
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+    vm.set_block_timestamp(2_000);
+    let error = AuthorizationExpired { validBefore: tokens(2_000) }.abi_encode();
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+    assert!(!contract.authorization_state(ALICE, authorization.nonce));
+
+    vm.set_block_timestamp(1_999);
+    ok(transfer_with(&mut contract, &authorization, signature));
+    let authorization = payment(2);
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+    vm.set_block_timestamp(1_001);
+    ok(transfer_with(&mut contract, &authorization, signature));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(700));
+}
+
+#[test]
+fn cancelled_authorizations_cant_be_used() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_500);
+    let authorization = payment(1);
+    let signature = sign_authorization(&vm, &contract, ALICE, TRANSFER_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+
+    // Only a cancellation ALICE signed cancels
+    let nonce = authorization.nonce;
+    let struct_hash = cradle_crypto::hash_struct(&vm, CANCEL_AUTHORIZATION_TYPEHASH, (ALICE, nonce));
+    let digest = cradle_crypto::hash_typed_data(&vm, contract.domain_separator(), struct_hash);
+    let (v, r, s) = split(sign(&vm, BOB, digest));
+    let error = InvalidAuthorizationSignature { authorizer: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.cancel_authorization(ALICE, nonce, v, r, s)), error);
+    assert!(!contract.authorization_state(ALICE, nonce));
+
+    let (v, r, s) = split(sign(&vm, ALICE, digest));
+    ok(contract.cancel_authorization(ALICE, nonce, v, r, s));
+    assert!(contract.authorization_state(ALICE, nonce));
+    let event = emitted::<AuthorizationCanceled>(&vm).pop().expect("cancellation logged");
+    assert_eq!((event.authorizer, event.nonce), (ALICE, nonce));
+
+    let error = AuthorizationAlreadyUsed { authorizer: ALICE, nonce }.abi_encode();
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+    assert_eq!(reverted(contract.cancel_authorization(ALICE, nonce, v, r, s)), error);
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(500));
+}
+
+#[test]
+fn only_the_payee_submits_receive_authorizations() {
+    let (vm, mut contract) = setup_with_balances();
+    vm.set_block_timestamp(1_500);
+    let authorization = payment(1);
+    let signature = sign_authorization(&vm, &contract, ALICE, RECEIVE_WITH_AUTHORIZATION_TYPEHASH, &authorization);
+
+    vm.set_sender(MINTER);
+    let error = InvalidAuthorizationCaller { caller: MINTER, payee: BOB }.abi_encode();
+    assert_eq!(reverted(receive_with(&mut contract, &authorization, signature)), error);
+    // Nor can it be front-run into a plain transfer
+    let error = InvalidAuthorizationSignature { authorizer: ALICE }.abi_encode();
+    assert_eq!(reverted(transfer_with(&mut contract, &authorization, signature)), error);
+    assert!(!contract.authorization_state(ALICE, authorization.nonce));
+
+    vm.set_sender(BOB);
+    ok(receive_with(&mut contract, &authorization, signature));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+    assert!(contract.authorization_state(ALICE, authorization.nonce));
+}
+
+#[cfg(feature = "dividends")]
+#[test]
+fn transfers_reflect_the_fee_to_holders_who_withdraw_it() {
+    use dividends::{DividendWithdrawn, DividendsDistributed, InvalidDividendFee, MAX_DIVIDEND_FEE_BPS};
+
+    let (vm, mut contract) = setup_with_balances();
+    let too_high = U256::from(MAX_DIVIDEND_FEE_BPS + 1);
+    assert_eq!(reverted(contract.set_dividend_fee(too_high)), InvalidDividendFee { feeBps: too_high }.abi_encode());
+    ok(contract.set_dividend_fee(U256::from(1_000)));
+
+    // 10% of what BOB gets goes to the holders of the other 1490
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(590));
+    assert_eq!(contract.erc20.balance_of(vm.contract_address()), tokens(10));
+    let event = emitted::<DividendsDistributed>(&vm).pop().expect("distribution logged");
+    assert_eq!((event.from, event.to, event.amount), (ALICE, BOB, tokens(10)));
+    assert_eq!(contract.dividend_totals(), (tokens(10), tokens(10)));
+
+    // 900 * 10 / 1490 and 590 * 10 / 1490 round down, leaving the dust in the pool
+    assert_eq!(contract.withdrawable_dividend_of(ALICE), tokens(6));
+    assert_eq!(contract.withdrawable_dividend_of(BOB), tokens(3));
+    assert_eq!(ok(contract.withdraw_dividend()), tokens(6));
+    assert_eq!(contract.erc20.balance_of(ALICE), tokens(906));
+    let event = emitted::<DividendWithdrawn>(&vm).pop().expect("withdrawal logged");
+    assert_eq!((event.account, event.amount), (ALICE, tokens(6)));
+    assert_eq!(contract.withdrawn_dividend_of(ALICE), tokens(6));
+    assert_eq!(contract.withdrawable_dividend_of(ALICE), U256::ZERO);
+    assert_eq!(ok(contract.withdraw_dividend()), U256::ZERO);
+    vm.set_sender(BOB);
+    assert_eq!(ok(contract.withdraw_dividend()), tokens(3));
+    assert_eq!(contract.dividend_totals(), (tokens(10), tokens(1)));
+}
+
+#[cfg(feature = "dividends")]
+#[test]
+fn excluded_accounts_neither_earn_nor_dilute_dividends() {
+    let (vm, mut contract) = setup_with_balances();
+    ok(contract.set_dividend_fee(U256::from(1_000)));
+    ok(contract.set_dividend_excluded(BOB, true));
+    assert!(contract.is_dividend_excluded(BOB));
+
+    // ALICE holds everything that earns, so she earns the fee, less what the per-token total
+    // rounded away
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    assert_eq!(contract.withdrawable_dividend_of(ALICE), tokens(9));
+    assert_eq!(contract.withdrawable_dividend_of(BOB), U256::ZERO);
+
+    // Included again, BOB earns from then on
+    vm.set_sender(OWNER);
+    ok(contract.set_dividend_excluded(BOB, false));
+    vm.set_sender(ALICE);
+    ok(contract.transfer(OWNER, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(OWNER), tokens(90));
+    assert!(contract.withdrawable_dividend_of(BOB) > U256::ZERO);
+}
+
+#[cfg(feature = "dividends")]
+#[test]
+fn dividends_held_for_holders_arent_recoverable() {
+    let (vm, mut contract) = setup_with_balances();
+    let this = vm.contract_address();
+    ok(contract.set_dividend_fee(U256::from(1_000)));
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    ok(contract.transfer(this, tokens(5)));
+    assert_eq!(contract.erc20.balance_of(this), tokens(15));
+
+    vm.set_sender(OWNER);
+    let error = InsufficientBalance { from: this, have: tokens(5), want: tokens(6) };
+    assert_eq!(reverted(contract.recover_erc20(this, OWNER, tokens(6))), error.abi_encode());
+    ok(contract.recover_erc20(this, OWNER, tokens(5)));
+}
+
+#[cfg(not(feature = "dividends"))]
+#[test]
+fn dividends_are_off_without_the_feature() {
+    use dividends::DividendsDisabled;
+
+    let (vm, mut contract) = setup_with_balances();
+    assert_eq!(reverted(contract.set_dividend_fee(U256::from(100))), DividendsDisabled {}.abi_encode());
+    assert_eq!(reverted(contract.withdraw_dividend()), DividendsDisabled {}.abi_encode());
+    vm.set_sender(ALICE);
+    ok(contract.transfer(BOB, tokens(100)));
+    assert_eq!(contract.erc20.balance_of(BOB), tokens(600));
+    assert_eq!(contract.dividend_totals(), (U256::ZERO, U256::ZERO));
+}
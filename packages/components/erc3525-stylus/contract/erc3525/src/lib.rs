@@ -0,0 +1,596 @@
+//! ERC-3525 semi-fungible token
+//!
+//! Every token is an ERC-721 NFT with a slot and a value. Tokens of the same slot are
+//! interchangeable by value, like the bonds of one issue or the vouchers of one denomination:
+//! value moves between two tokens of a slot, or to a new token of the slot minted for the
+//! recipient, and the tokens themselves transfer as NFTs.
+//!
+//! The owner mints tokens into slots and adds value to them. Holders burn their tokens or part
+//! of their value. A token's value can be spent by its holder, the holder's ERC-721 operators,
+//! the token's approved address, or up to an allowance set with the value `approve`; moving the
+//! token clears its approvals and allowances. Value sent to a token held by a contract calls
+//! `onERC3525Received` if the contract reports `IERC3525Receiver` through ERC-165.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{aliases::U8, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount, IERC173, IERC5313};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// ERC-721, `0x80ac58cd`.
+pub const IERC721: Interface = interface!(
+    "balanceOf"(Address),
+    "ownerOf"(U256),
+    "safeTransferFrom"(Address, Address, U256, Bytes),
+    "safeTransferFrom"(Address, Address, U256),
+    "transferFrom"(Address, Address, U256),
+    "approve"(Address, U256),
+    "setApprovalForAll"(Address, bool),
+    "getApproved"(U256),
+    "isApprovedForAll"(Address, Address),
+);
+
+/// ERC-721 metadata, `0x5b5e139f`.
+pub const IERC721_METADATA: Interface = interface!("name"(), "symbol"(), "tokenURI"(U256));
+
+/// ERC-3525, `0xd5358140`.
+pub const IERC3525: Interface = interface!(
+    "valueDecimals"(),
+    "balanceOf"(U256),
+    "slotOf"(U256),
+    "approve"(U256, Address, U256),
+    "allowance"(U256, Address),
+    "transferFrom"(U256, U256, U256),
+    "transferFrom"(U256, Address, U256),
+);
+
+/// ERC-3525 metadata, `0xe1600902`.
+pub const IERC3525_METADATA: Interface = interface!("contractURI"(), "slotURI"(U256));
+
+/// ERC-3525 receiver, `0x009ce20b`.
+pub const IERC3525_RECEIVER: Interface = interface!("onERC3525Received"(Address, U256, U256, U256, Bytes));
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc3525 {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Mints tokens and sets the metadata URI
+        #[borrow]
+        Ownable ownable;
+
+        string name;
+        string symbol;
+        /// Decimals of token values
+        uint8 value_decimals;
+        /// Prefix of the token, slot and contract metadata URIs
+        string base_uri;
+
+        /// Last token ID minted; IDs start at 1
+        uint256 last_token_id;
+        /// Tokens in circulation
+        uint256 total_supply;
+        /// Holder of each token, zero for nonexistent tokens
+        mapping(uint256 => address) owners;
+        /// Number of tokens each account holds
+        mapping(address => uint256) balances;
+        /// Slot of each token
+        mapping(uint256 => uint256) slots;
+        /// Value of each token
+        mapping(uint256 => uint256) values;
+
+        /// ERC-721 approved address of each token
+        mapping(uint256 => address) token_approvals;
+        /// ERC-721 operators of each holder
+        mapping(address => mapping(address => bool)) operator_approvals;
+        /// Value allowances by token, then by the token's transfer count, then by operator
+        mapping(uint256 => mapping(uint256 => mapping(address => uint256))) allowances;
+        /// Times each token has moved, which clears its allowances
+        mapping(uint256 => uint256) transfers;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    event TransferValue(uint256 indexed fromTokenId, uint256 indexed toTokenId, uint256 value);
+    event ApprovalValue(uint256 indexed tokenId, address indexed operator, uint256 value);
+    event SlotChanged(uint256 indexed tokenId, uint256 indexed oldSlot, uint256 indexed newSlot);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Account is the zero address
+    error ERC721InvalidOwner(address owner);
+    // Token doesn't exist
+    error ERC721NonexistentToken(uint256 tokenId);
+    // Token isn't held by `sender`
+    error ERC721IncorrectOwner(address sender, uint256 tokenId, address owner);
+    // Recipient is the zero address, or a contract that refused the token
+    error ERC721InvalidReceiver(address receiver);
+    // Caller may not move or approve the token
+    error ERC721InsufficientApproval(address operator, uint256 tokenId);
+    // Operator is the zero address
+    error ERC721InvalidOperator(address operator);
+    // Value to spend is over the caller's allowance
+    error ERC3525InsufficientAllowance(uint256 tokenId, address operator, uint256 allowance, uint256 needed);
+    // Value to move or burn is over the token's value
+    error ERC3525InsufficientBalance(uint256 tokenId, uint256 balance, uint256 needed);
+    // Value can only move between tokens of the same slot
+    error ERC3525SlotMismatch(uint256 fromTokenId, uint256 toTokenId);
+    // Value can't move from a token to itself
+    error ERC3525InvalidTransfer(uint256 tokenId);
+    // Holder of the receiving token refused the value
+    error ERC3525InvalidReceiver(uint256 tokenId);
+
+    function onERC721Received(address operator, address from, uint256 tokenId, bytes data) returns (bytes4);
+    function onERC3525Received(address operator, uint256 fromTokenId, uint256 toTokenId, uint256 value, bytes data) returns (bytes4);
+    function supportsInterface(bytes4 interfaceId) returns (bool);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum Erc3525Error {
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidOwner(ERC721InvalidOwner),
+    NonexistentToken(ERC721NonexistentToken),
+    IncorrectOwner(ERC721IncorrectOwner),
+    InvalidReceiver(ERC721InvalidReceiver),
+    InsufficientApproval(ERC721InsufficientApproval),
+    InvalidOperator(ERC721InvalidOperator),
+    InsufficientAllowance(ERC3525InsufficientAllowance),
+    InsufficientBalance(ERC3525InsufficientBalance),
+    SlotMismatch(ERC3525SlotMismatch),
+    InvalidTransfer(ERC3525InvalidTransfer),
+    InvalidValueReceiver(ERC3525InvalidReceiver),
+    OwnableUnauthorizedAccount(OwnableUnauthorizedAccount),
+    OwnableInvalidOwner(OwnableInvalidOwner),
+}
+
+impl From<OwnableError> for Erc3525Error {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => Erc3525Error::OwnableUnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => Erc3525Error::OwnableInvalidOwner(e),
+        }
+    }
+}
+
+#[public]
+#[inherit(Ownable)]
+impl Erc3525 {
+    /// Sets the owner, the metadata and the decimals of values. Can only be called once.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        name: String,
+        symbol: String,
+        value_decimals: u8,
+        base_uri: String,
+    ) -> Result<(), Erc3525Error> {
+        if self.initialized.get() {
+            return Err(Erc3525Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.ownable.initialize(owner)?;
+        self.initialized.set(true);
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.value_decimals.set(U8::from(value_decimals));
+        self.base_uri.set_str(base_uri);
+        Ok(())
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    pub fn value_decimals(&self) -> u8 {
+        self.value_decimals.get().to()
+    }
+
+    /// `<base URI><token ID>.json`.
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, Erc3525Error> {
+        self.owner_of(token_id)?;
+        Ok(self.uri(&token_id.to_string()))
+    }
+
+    /// `<base URI>slot/<slot>.json`.
+    #[selector(name = "slotURI")]
+    pub fn slot_uri(&self, slot: U256) -> String {
+        self.uri(&alloc::format!("slot/{slot}"))
+    }
+
+    /// `<base URI>contract.json`.
+    #[selector(name = "contractURI")]
+    pub fn contract_uri(&self) -> String {
+        self.uri("contract")
+    }
+
+    /// Changes the prefix of the metadata URIs (owner only).
+    pub fn set_base_uri(&mut self, base_uri: String) -> Result<(), Erc3525Error> {
+        self.ownable.require_owner()?;
+        self.base_uri.set_str(base_uri);
+        Ok(())
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    /// Number of tokens `owner` holds.
+    #[selector(name = "balanceOf")]
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc3525Error> {
+        if owner.is_zero() {
+            return Err(Erc3525Error::InvalidOwner(ERC721InvalidOwner { owner }));
+        }
+        Ok(self.balances.get(owner))
+    }
+
+    /// Value of `token_id`.
+    #[selector(name = "balanceOf")]
+    pub fn balance_of_token(&self, token_id: U256) -> Result<U256, Erc3525Error> {
+        self.owner_of(token_id)?;
+        Ok(self.values.get(token_id))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc3525Error> {
+        let owner = self.owners.get(token_id);
+        if owner.is_zero() {
+            return Err(Erc3525Error::NonexistentToken(ERC721NonexistentToken { tokenId: token_id }));
+        }
+        Ok(owner)
+    }
+
+    pub fn slot_of(&self, token_id: U256) -> Result<U256, Erc3525Error> {
+        self.owner_of(token_id)?;
+        Ok(self.slots.get(token_id))
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc3525Error> {
+        self.owner_of(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(owner).get(operator)
+    }
+
+    /// Value of `token_id` that `operator` may spend, besides any ERC-721 approval.
+    pub fn allowance(&self, token_id: U256, operator: Address) -> Result<U256, Erc3525Error> {
+        self.owner_of(token_id)?;
+        let transfers = self.transfers.get(token_id);
+        Ok(self.allowances.getter(token_id).getter(transfers).get(operator))
+    }
+
+    /// Approves `approved` to move `token_id` and spend all its value.
+    #[selector(name = "approve")]
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Erc3525Error> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if sender != owner && !self.is_approved_for_all(owner, sender) {
+            return Err(Erc3525Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        self.token_approvals.insert(token_id, approved);
+        log(self.vm(), Approval { owner, approved, tokenId: token_id });
+        Ok(())
+    }
+
+    /// Allows `operator` to spend `value` of `token_id`. The holder, its operators and the
+    /// token's approved address can set allowances.
+    #[selector(name = "approve")]
+    pub fn approve_value(&mut self, token_id: U256, operator: Address, value: U256) -> Result<(), Erc3525Error> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if !self.is_authorized(owner, sender, token_id) {
+            return Err(Erc3525Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        if operator.is_zero() || operator == owner {
+            return Err(Erc3525Error::InvalidOperator(ERC721InvalidOperator { operator }));
+        }
+        let transfers = self.transfers.get(token_id);
+        self.allowances.setter(token_id).setter(transfers).insert(operator, value);
+        log(self.vm(), ApprovalValue { tokenId: token_id, operator, value });
+        Ok(())
+    }
+
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Erc3525Error> {
+        if operator.is_zero() {
+            return Err(Erc3525Error::InvalidOperator(ERC721InvalidOperator { operator }));
+        }
+        let owner = self.vm().msg_sender();
+        self.operator_approvals.setter(owner).insert(operator, approved);
+        log(self.vm(), ApprovalForAll { owner, operator, approved });
+        Ok(())
+    }
+
+    /// Moves `token_id` and its value from `from` to `to`.
+    #[selector(name = "transferFrom")]
+    pub fn transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Erc3525Error> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if !self.is_authorized(owner, sender, token_id) {
+            return Err(Erc3525Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        if owner != from {
+            return Err(Erc3525Error::IncorrectOwner(ERC721IncorrectOwner { sender: from, tokenId: token_id, owner }));
+        }
+        if to.is_zero() {
+            return Err(Erc3525Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+        self.update(to, token_id);
+        Ok(())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Erc3525Error> {
+        self.safe_transfer_from_with_data(from, to, token_id, Vec::new().into())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc3525Error> {
+        self.transfer_from(from, to, token_id)?;
+        self.check_received(from, to, token_id, data)
+    }
+
+    /// Moves `value` from `from_token_id` to `to_token_id`, which must be of the same slot.
+    #[selector(name = "transferFrom")]
+    pub fn transfer_value(&mut self, from_token_id: U256, to_token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        self.spend_allowance(from_token_id, value)?;
+        self.owner_of(to_token_id)?;
+        if from_token_id == to_token_id {
+            return Err(Erc3525Error::InvalidTransfer(ERC3525InvalidTransfer { tokenId: from_token_id }));
+        }
+        if self.slots.get(from_token_id) != self.slots.get(to_token_id) {
+            return Err(Erc3525Error::SlotMismatch(ERC3525SlotMismatch {
+                fromTokenId: from_token_id,
+                toTokenId: to_token_id,
+            }));
+        }
+        self.move_value(from_token_id, to_token_id, value)?;
+        self.check_value_received(from_token_id, to_token_id, value)
+    }
+
+    /// Moves `value` from `from_token_id` to a new token of its slot minted to `to`, and
+    /// returns the new token's ID.
+    #[selector(name = "transferFrom")]
+    pub fn transfer_value_to_address(
+        &mut self,
+        from_token_id: U256,
+        to: Address,
+        value: U256,
+    ) -> Result<U256, Erc3525Error> {
+        self.spend_allowance(from_token_id, value)?;
+        if to.is_zero() {
+            return Err(Erc3525Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+        let to_token_id = self.mint_token(to, self.slots.get(from_token_id));
+        self.move_value(from_token_id, to_token_id, value)?;
+        self.check_value_received(from_token_id, to_token_id, value)?;
+        Ok(to_token_id)
+    }
+
+    /// Mints a token of `slot` with `value` to `to`, and returns its ID (owner only).
+    pub fn mint(&mut self, to: Address, slot: U256, value: U256) -> Result<U256, Erc3525Error> {
+        self.ownable.require_owner()?;
+        if to.is_zero() {
+            return Err(Erc3525Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+        let token_id = self.mint_token(to, slot);
+        self.mint_value(token_id, value)?;
+        Ok(token_id)
+    }
+
+    /// Adds `value` to `token_id` (owner only).
+    pub fn mint_value(&mut self, token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        self.ownable.require_owner()?;
+        self.owner_of(token_id)?;
+        self.values.insert(token_id, self.values.get(token_id) + value);
+        log(self.vm(), TransferValue { fromTokenId: U256::ZERO, toTokenId: token_id, value });
+        Ok(())
+    }
+
+    /// Burns `token_id` with its remaining value. The holder, its operators and the token's
+    /// approved address can burn.
+    pub fn burn(&mut self, token_id: U256) -> Result<(), Erc3525Error> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if !self.is_authorized(owner, sender, token_id) {
+            return Err(Erc3525Error::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        let value = self.values.get(token_id);
+        let slot = self.slots.get(token_id);
+        self.values.delete(token_id);
+        self.slots.delete(token_id);
+        log(self.vm(), TransferValue { fromTokenId: token_id, toTokenId: U256::ZERO, value });
+        log(self.vm(), SlotChanged { tokenId: token_id, oldSlot: slot, newSlot: U256::ZERO });
+        self.update(Address::ZERO, token_id);
+        self.total_supply.set(self.total_supply.get() - U256::from(1));
+        Ok(())
+    }
+
+    /// Burns `value` of `token_id`, spending the caller's allowance.
+    pub fn burn_value(&mut self, token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        self.spend_allowance(token_id, value)?;
+        self.take_value(token_id, value)?;
+        log(self.vm(), TransferValue { fromTokenId: token_id, toTokenId: U256::ZERO, value });
+        Ok(())
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(
+            interface_id,
+            [IERC721, IERC721_METADATA, IERC3525, IERC3525_METADATA, IERC173, IERC5313],
+        )
+    }
+}
+
+// Helpers used by the public methods above.
+impl Erc3525 {
+    fn uri(&self, path: &str) -> String {
+        let mut uri = self.base_uri.get_string();
+        uri.push_str(path);
+        uri.push_str(".json");
+        uri
+    }
+
+    /// Whether `sender` may move `token_id` of `owner` and spend all its value.
+    fn is_authorized(&self, owner: Address, sender: Address, token_id: U256) -> bool {
+        sender == owner || self.is_approved_for_all(owner, sender) || self.token_approvals.get(token_id) == sender
+    }
+
+    /// Fails unless the caller may spend `value` of `token_id`, lowering an allowance it spends.
+    fn spend_allowance(&mut self, token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        let owner = self.owner_of(token_id)?;
+        let operator = self.vm().msg_sender();
+        if self.is_authorized(owner, operator, token_id) {
+            return Ok(());
+        }
+        let transfers = self.transfers.get(token_id);
+        let allowance = self.allowances.getter(token_id).getter(transfers).get(operator);
+        if allowance < value {
+            return Err(Erc3525Error::InsufficientAllowance(ERC3525InsufficientAllowance {
+                tokenId: token_id,
+                operator,
+                allowance,
+                needed: value,
+            }));
+        }
+        if allowance != U256::MAX {
+            self.allowances.setter(token_id).setter(transfers).insert(operator, allowance - value);
+        }
+        Ok(())
+    }
+
+    fn take_value(&mut self, token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        let balance = self.values.get(token_id);
+        if balance < value {
+            return Err(Erc3525Error::InsufficientBalance(ERC3525InsufficientBalance {
+                tokenId: token_id,
+                balance,
+                needed: value,
+            }));
+        }
+        self.values.insert(token_id, balance - value);
+        Ok(())
+    }
+
+    fn move_value(&mut self, from_token_id: U256, to_token_id: U256, value: U256) -> Result<(), Erc3525Error> {
+        self.take_value(from_token_id, value)?;
+        self.values.insert(to_token_id, self.values.get(to_token_id) + value);
+        log(self.vm(), TransferValue { fromTokenId: from_token_id, toTokenId: to_token_id, value });
+        Ok(())
+    }
+
+    /// Mints the next token ID of `slot` to `to`, without value.
+    fn mint_token(&mut self, to: Address, slot: U256) -> U256 {
+        let token_id = self.last_token_id.get() + U256::from(1);
+        self.last_token_id.set(token_id);
+        self.total_supply.set(self.total_supply.get() + U256::from(1));
+        self.update(to, token_id);
+        self.slots.insert(token_id, slot);
+        log(self.vm(), SlotChanged { tokenId: token_id, oldSlot: U256::ZERO, newSlot: slot });
+        token_id
+    }
+
+    /// Moves `token_id` to `to`, burning it for the zero address, and clears its approvals.
+    fn update(&mut self, to: Address, token_id: U256) {
+        let from = self.owners.get(token_id);
+        if !from.is_zero() {
+            let balance = self.balances.get(from);
+            self.balances.insert(from, balance - U256::from(1));
+            self.token_approvals.delete(token_id);
+            self.transfers.insert(token_id, self.transfers.get(token_id) + U256::from(1));
+        }
+        if !to.is_zero() {
+            let balance = self.balances.get(to);
+            self.balances.insert(to, balance + U256::from(1));
+        }
+        self.owners.insert(token_id, to);
+        log(self.vm(), Transfer { from, to, tokenId: token_id });
+    }
+
+    /// Calls `onERC721Received` if `to` is a contract, requiring it to return the selector.
+    fn check_received(&mut self, from: Address, to: Address, token_id: U256, data: Bytes) -> Result<(), Erc3525Error> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+        let operator = self.vm().msg_sender();
+        let call = onERC721ReceivedCall { operator, from, tokenId: token_id, data: data.0.into() };
+        let rejected = || Erc3525Error::InvalidReceiver(ERC721InvalidReceiver { receiver: to });
+        let result = self.vm().call(&calls::context::Call::new(), to, &call.abi_encode()).map_err(|_| rejected())?;
+        match FixedBytes::<4>::abi_decode(&result, true) {
+            Ok(selector) if selector == onERC721ReceivedCall::SELECTOR => Ok(()),
+            _ => Err(rejected()),
+        }
+    }
+
+    /// Calls `onERC3525Received` if the holder of `to_token_id` is a contract that reports
+    /// `IERC3525Receiver`, requiring it to return the selector.
+    fn check_value_received(
+        &mut self,
+        from_token_id: U256,
+        to_token_id: U256,
+        value: U256,
+    ) -> Result<(), Erc3525Error> {
+        let to = self.owners.get(to_token_id);
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+        let query = supportsInterfaceCall { interfaceId: IERC3525_RECEIVER.id().to_be_bytes().into() };
+        let supported = self
+            .vm()
+            .static_call(&calls::context::Call::new(), to, &query.abi_encode())
+            .is_ok_and(|result| bool::abi_decode(&result, true).unwrap_or(false));
+        if !supported {
+            return Ok(());
+        }
+        let operator = self.vm().msg_sender();
+        let call = onERC3525ReceivedCall {
+            operator,
+            fromTokenId: from_token_id,
+            toTokenId: to_token_id,
+            value,
+            data: Vec::new().into(),
+        };
+        let rejected = || Erc3525Error::InvalidValueReceiver(ERC3525InvalidReceiver { tokenId: to_token_id });
+        let result = self.vm().call(&calls::context::Call::new(), to, &call.abi_encode()).map_err(|_| rejected())?;
+        match FixedBytes::<4>::abi_decode(&result, true) {
+            Ok(selector) if selector == onERC3525ReceivedCall::SELECTOR => Ok(()),
+            _ => Err(rejected()),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const BOND: U256 = U256::from_limbs([7, 0, 0, 0]);
+
+/// A contract initialized by `OWNER`, with a token of 100 in slot `BOND` minted to `ALICE`.
+fn setup() -> (TestVM, Erc3525, U256) {
+    let (vm, mut contract) = deploy::<Erc3525>(OWNER);
+    ok(contract.initialize(OWNER, "Bonds".into(), "BOND".into(), 6, "ipfs://bonds/".into()));
+    let token_id = ok(contract.mint(ALICE, BOND, n(100)));
+    (vm, contract, token_id)
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IERC721.id(), 0x80ac58cd);
+    assert_eq!(IERC721_METADATA.id(), 0x5b5e139f);
+    assert_eq!(IERC3525.id(), 0xd5358140);
+    assert_eq!(IERC3525_METADATA.id(), 0xe1600902);
+    assert_eq!(IERC3525_RECEIVER.id(), 0x009ce20b);
+    assert_eq!(onERC3525ReceivedCall::SELECTOR, IERC3525_RECEIVER.id().to_be_bytes());
+}
+
+#[test]
+fn mints_tokens_with_slots_and_values() {
+    let (vm, mut contract, token_id) = setup();
+    assert_eq!(token_id, n(1));
+    assert_eq!(ok(contract.owner_of(token_id)), ALICE);
+    assert_eq!((ok(contract.slot_of(token_id)), ok(contract.balance_of_token(token_id))), (BOND, n(100)));
+    assert_eq!(ok(contract.balance_of(ALICE)), n(1));
+    assert_eq!(ok(contract.token_uri(token_id)), "ipfs://bonds/1.json");
+    assert_eq!(contract.slot_uri(BOND), "ipfs://bonds/slot/7.json");
+    assert_eq!(emitted::<SlotChanged>(&vm).len(), 1);
+
+    ok(contract.mint_value(token_id, n(50)));
+    assert_eq!(ok(contract.balance_of_token(token_id)), n(150));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.mint(ALICE, BOND, n(1)), Err(Erc3525Error::OwnableUnauthorizedAccount(_))));
+}
+
+#[test]
+fn value_moves_between_tokens_of_a_slot() {
+    let (vm, mut contract, from) = setup();
+    let to = ok(contract.mint(BOB, BOND, n(0)));
+    let other = ok(contract.mint(BOB, n(8), n(0)));
+
+    vm.set_sender(ALICE);
+    ok(contract.transfer_value(from, to, n(30)));
+    assert_eq!((ok(contract.balance_of_token(from)), ok(contract.balance_of_token(to))), (n(70), n(30)));
+    let result = contract.transfer_value(from, other, n(1));
+    assert!(matches!(result, Err(Erc3525Error::SlotMismatch(_))));
+    let result = contract.transfer_value(from, to, n(71));
+    assert!(matches!(result, Err(Erc3525Error::InsufficientBalance(e)) if e.balance == n(70)));
+
+    let new = ok(contract.transfer_value_to_address(from, BOB, n(20)));
+    assert_eq!(ok(contract.owner_of(new)), BOB);
+    assert_eq!((ok(contract.slot_of(new)), ok(contract.balance_of_token(new))), (BOND, n(20)));
+    assert_eq!(ok(contract.balance_of(BOB)), n(3));
+}
+
+#[test]
+fn allowances_limit_operators_and_clear_on_transfer() {
+    let (vm, mut contract, from) = setup();
+    vm.set_sender(ALICE);
+    ok(contract.approve_value(from, BOB, n(40)));
+    assert_eq!(ok(contract.allowance(from, BOB)), n(40));
+
+    vm.set_sender(BOB);
+    let to = ok(contract.transfer_value_to_address(from, BOB, n(25)));
+    assert_eq!(ok(contract.allowance(from, BOB)), n(15));
+    let result = contract.transfer_value(from, to, n(16));
+    assert!(matches!(result, Err(Erc3525Error::InsufficientAllowance(e)) if e.allowance == n(15)));
+    let result = contract.transfer_from(ALICE, BOB, from);
+    assert!(matches!(result, Err(Erc3525Error::InsufficientApproval(_))));
+
+    // Moving the token clears its allowances
+    vm.set_sender(ALICE);
+    ok(contract.transfer_from(ALICE, OWNER, from));
+    assert_eq!(ok(contract.allowance(from, BOB)), U256::ZERO);
+    vm.set_sender(OWNER);
+    ok(contract.transfer_from(OWNER, ALICE, from));
+    assert_eq!(ok(contract.allowance(from, BOB)), U256::ZERO);
+}
+
+#[test]
+fn holders_burn_tokens_and_value() {
+    let (vm, mut contract, token_id) = setup();
+    vm.set_sender(ALICE);
+    ok(contract.burn_value(token_id, n(60)));
+    assert_eq!(ok(contract.balance_of_token(token_id)), n(40));
+
+    vm.set_sender(BOB);
+    assert!(matches!(contract.burn(token_id), Err(Erc3525Error::InsufficientApproval(_))));
+    vm.set_sender(ALICE);
+    ok(contract.burn(token_id));
+    assert!(matches!(contract.owner_of(token_id), Err(Erc3525Error::NonexistentToken(_))));
+    assert_eq!((contract.total_supply(), ok(contract.balance_of(ALICE))), (U256::ZERO, U256::ZERO));
+}
+
+#[test]
+fn contract_holders_are_asked_about_value_they_receive() {
+    let (vm, mut contract, from) = setup();
+    let receiver = address!("00000000000000000000000000000000000c0de5");
+    vm.set_code(receiver, vec![0]);
+    let to = ok(contract.mint(receiver, BOND, n(0)));
+
+    let query = supportsInterfaceCall { interfaceId: IERC3525_RECEIVER.id().to_be_bytes().into() };
+    vm.mock_static_call(receiver, query.abi_encode(), Ok(true.abi_encode()));
+    let hook =
+        onERC3525ReceivedCall { operator: ALICE, fromTokenId: from, toTokenId: to, value: n(5), data: vec![].into() };
+    vm.mock_call(receiver, hook.abi_encode(), Ok(onERC3525ReceivedCall::SELECTOR.abi_encode()));
+
+    vm.set_sender(ALICE);
+    ok(contract.transfer_value(from, to, n(5)));
+    let hook = onERC3525ReceivedCall { value: n(6), ..hook };
+    vm.mock_call(receiver, hook.abi_encode(), Ok(FixedBytes::<4>::ZERO.abi_encode()));
+    let result = contract.transfer_value(from, to, n(6));
+    assert!(matches!(result, Err(Erc3525Error::InvalidValueReceiver(e)) if e.tokenId == to));
+}
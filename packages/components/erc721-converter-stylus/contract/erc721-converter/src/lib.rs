@@ -0,0 +1,307 @@
+//! Converter of ERC-721 tokens into single-supply ERC-1155 IDs
+//!
+//! Locks ERC-721 tokens and mints each as an ID of this contract's own ERC-1155 with a supply of
+//! one, so a collection can be listed on marketplaces and in wallets that only take ERC-1155s.
+//! Token `tokenId` of `collection` becomes ID `uint256(keccak256(abi.encode(collection,
+//! tokenId)))`, and `uri` answers with the locked token's `tokenURI`, so the metadata stays the
+//! collection's. Burning the ID with `unconvert` releases the ERC-721, moving the token back.
+//!
+//! Any collection can be converted: each ID is bound to its collection, so one collection can't
+//! mint the IDs of another. Holders convert with `convert` after approving the converter on the
+//! collection, or by sending the token with `safeTransferFrom`.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{keccak256, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Selector for `onERC721Received`.
+const ERC721_RECEIVED: u32 = 0x150b7a02;
+
+/// ERC-1155, `0xd9b67a26`.
+pub const IERC1155: Interface = interface!(
+    "balanceOf"(Address, U256),
+    "balanceOfBatch"(Vec<Address>, Vec<U256>),
+    "setApprovalForAll"(Address, bool),
+    "isApprovedForAll"(Address, Address),
+    "safeTransferFrom"(Address, Address, U256, U256, Bytes),
+    "safeBatchTransferFrom"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+/// ERC-1155's metadata extension, `uri`.
+pub const IERC1155_METADATA_URI: Interface = interface!("uri"(U256));
+
+/// ERC-721's receiver hook, for `supportsInterface`.
+pub const IERC721_RECEIVER: Interface = interface!("onERC721Received"(Address, Address, U256, Bytes));
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc721Converter {
+        /// Holder of each converted ID, zero for IDs not converted
+        mapping(uint256 => address) holders;
+        mapping(address => mapping(address => bool)) operator_approvals;
+        /// Collection and token each ID converts, zero for IDs never converted
+        mapping(uint256 => Origin) origins;
+    }
+
+    pub struct Origin {
+        address collection;
+        uint256 token_id;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event Converted(address indexed collection, uint256 indexed tokenId, address indexed to, uint256 id);
+    event Unconverted(address indexed collection, uint256 indexed tokenId, address indexed to, uint256 id);
+
+    error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 id);
+    error ERC1155InvalidReceiver(address receiver);
+    error ERC1155InvalidOperator(address operator);
+    error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
+    error ERC1155MissingApprovalForAll(address operator, address owner);
+    // The token was already converted and its ID is held
+    error AlreadyConverted(uint256 id);
+    // A call to the collection reverted, with its revert data
+    error ConverterCallFailed(address collection, bytes returnData);
+}
+
+// External interfaces
+sol! {
+    interface IERC721 {
+        function transferFrom(address from, address to, uint256 tokenId) external;
+        function safeTransferFrom(address from, address to, uint256 tokenId) external;
+        function tokenURI(uint256 tokenId) external view returns (string memory);
+    }
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum ConverterError {
+    InsufficientBalance(ERC1155InsufficientBalance),
+    InvalidReceiver(ERC1155InvalidReceiver),
+    InvalidOperator(ERC1155InvalidOperator),
+    InvalidArrayLength(ERC1155InvalidArrayLength),
+    MissingApprovalForAll(ERC1155MissingApprovalForAll),
+    AlreadyConverted(AlreadyConverted),
+    CallFailed(ConverterCallFailed),
+}
+
+/// ID of token `token_id` of `collection`.
+pub fn converted_id_of(collection: Address, token_id: U256) -> U256 {
+    U256::from_be_bytes(keccak256((collection, token_id).abi_encode()).0)
+}
+
+// Helpers used by the public methods below.
+impl Erc721Converter {
+    /// Calls `collection` with `calldata`, failing with its revert data if it reverts.
+    fn call(&mut self, collection: Address, calldata: Vec<u8>) -> Result<(), ConverterError> {
+        self.vm()
+            .call(&calls::context::Call::new(), collection, &calldata)
+            .map(|_| ())
+            .map_err(|error| {
+                let return_data = Vec::<u8>::from(error).into();
+                ConverterError::CallFailed(ConverterCallFailed { collection, returnData: return_data })
+            })
+    }
+
+    /// Mints the ID of `collection`'s `token_id`, which the converter now holds, to `to`.
+    fn mint(&mut self, collection: Address, token_id: U256, to: Address) -> Result<U256, ConverterError> {
+        if to.is_zero() {
+            return Err(ConverterError::InvalidReceiver(ERC1155InvalidReceiver { receiver: to }));
+        }
+        let id = converted_id_of(collection, token_id);
+        if !self.holders.get(id).is_zero() {
+            return Err(ConverterError::AlreadyConverted(AlreadyConverted { id }));
+        }
+        self.holders.insert(id, to);
+        let mut origin = self.origins.setter(id);
+        origin.collection.set(collection);
+        origin.token_id.set(token_id);
+        let operator = self.vm().msg_sender();
+        log(self.vm(), TransferSingle { operator, from: Address::ZERO, to, id, value: U256::from(1) });
+        log(self.vm(), Converted { collection, tokenId: token_id, to, id });
+        Ok(id)
+    }
+
+    fn require_approved(&self, from: Address) -> Result<(), ConverterError> {
+        let operator = self.vm().msg_sender();
+        if from != operator && !self.is_approved_for_all(from, operator) {
+            return Err(ConverterError::MissingApprovalForAll(ERC1155MissingApprovalForAll { operator, owner: from }));
+        }
+        Ok(())
+    }
+
+    /// Moves `value` of `id`, zero or its whole supply of one, from `from` to `to`.
+    fn move_token(&mut self, from: Address, to: Address, id: U256, value: U256) -> Result<(), ConverterError> {
+        if to.is_zero() {
+            return Err(ConverterError::InvalidReceiver(ERC1155InvalidReceiver { receiver: to }));
+        }
+        let balance = self.balance_of(from, id);
+        if value > balance {
+            let error = ERC1155InsufficientBalance { sender: from, balance, needed: value, id };
+            return Err(ConverterError::InsufficientBalance(error));
+        }
+        if !value.is_zero() {
+            self.holders.insert(id, to);
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl Erc721Converter {
+    /// Locks the caller's `token_id` of `collection` and mints its ID to `to`, returning the ID.
+    /// Requires the converter's approval on the collection.
+    pub fn convert(&mut self, collection: Address, token_id: U256, to: Address) -> Result<U256, ConverterError> {
+        let this = self.vm().contract_address();
+        let pull = IERC721::transferFromCall { from: self.vm().msg_sender(), to: this, tokenId: token_id };
+        self.call(collection, pull.abi_encode())?;
+        self.mint(collection, token_id, to)
+    }
+
+    /// Burns the caller's `id` and releases the ERC-721 it converts to `to`.
+    pub fn unconvert(&mut self, id: U256, to: Address) -> Result<(), ConverterError> {
+        let holder = self.vm().msg_sender();
+        let (collection, token_id) = self.origin_of(id);
+        if self.balance_of(holder, id).is_zero() {
+            let error = ERC1155InsufficientBalance { sender: holder, balance: U256::ZERO, needed: U256::from(1), id };
+            return Err(ConverterError::InsufficientBalance(error));
+        }
+        self.holders.delete(id);
+        log(self.vm(), TransferSingle { operator: holder, from: holder, to: Address::ZERO, id, value: U256::from(1) });
+
+        let this = self.vm().contract_address();
+        let release = IERC721::safeTransferFromCall { from: this, to, tokenId: token_id };
+        self.call(collection, release.abi_encode())?;
+        log(self.vm(), Unconverted { collection, tokenId: token_id, to, id });
+        Ok(())
+    }
+
+    /// ID of token `token_id` of `collection`, whether or not it was converted yet.
+    pub fn converted_id(&self, collection: Address, token_id: U256) -> U256 {
+        converted_id_of(collection, token_id)
+    }
+
+    /// Collection and token `id` converts, zeros for IDs never converted.
+    pub fn origin_of(&self, id: U256) -> (Address, U256) {
+        let origin = self.origins.get(id);
+        (origin.collection.get(), origin.token_id.get())
+    }
+
+    /// Holder of `id`, zero while its token isn't converted.
+    pub fn holder_of(&self, id: U256) -> Address {
+        self.holders.get(id)
+    }
+
+    /// The locked token's `tokenURI`, empty for IDs never converted or collections without
+    /// metadata.
+    pub fn uri(&self, id: U256) -> String {
+        let (collection, token_id) = self.origin_of(id);
+        if collection.is_zero() {
+            return String::new();
+        }
+        let query = IERC721::tokenURICall { tokenId: token_id };
+        self.vm()
+            .static_call(&calls::context::Call::new(), collection, &query.abi_encode())
+            .ok()
+            .and_then(|result| String::abi_decode(&result, true).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn balance_of(&self, account: Address, id: U256) -> U256 {
+        U256::from(!account.is_zero() && self.holders.get(id) == account)
+    }
+
+    pub fn balance_of_batch(&self, accounts: Vec<Address>, ids: Vec<U256>) -> Result<Vec<U256>, ConverterError> {
+        if accounts.len() != ids.len() {
+            return Err(ConverterError::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(accounts.len()),
+            }));
+        }
+        Ok(accounts.iter().zip(ids.iter()).map(|(&account, &id)| self.balance_of(account, id)).collect())
+    }
+
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), ConverterError> {
+        if operator.is_zero() {
+            return Err(ConverterError::InvalidOperator(ERC1155InvalidOperator { operator }));
+        }
+        let account = self.vm().msg_sender();
+        self.operator_approvals.setter(account).insert(operator, approved);
+        log(self.vm(), ApprovalForAll { account, operator, approved });
+        Ok(())
+    }
+
+    pub fn is_approved_for_all(&self, account: Address, operator: Address) -> bool {
+        self.operator_approvals.get(account).get(operator)
+    }
+
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+        _data: Bytes,
+    ) -> Result<(), ConverterError> {
+        self.require_approved(from)?;
+        self.move_token(from, to, id, value)?;
+        log(self.vm(), TransferSingle { operator: self.vm().msg_sender(), from, to, id, value });
+        Ok(())
+    }
+
+    pub fn safe_batch_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        _data: Bytes,
+    ) -> Result<(), ConverterError> {
+        self.require_approved(from)?;
+        if ids.len() != values.len() {
+            return Err(ConverterError::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(values.len()),
+            }));
+        }
+        for (&id, &value) in ids.iter().zip(values.iter()) {
+            self.move_token(from, to, id, value)?;
+        }
+        log(self.vm(), TransferBatch { operator: self.vm().msg_sender(), from, to, ids, values });
+        Ok(())
+    }
+
+    /// Converts tokens of any collection sent to the converter, minting their IDs to `from` or to
+    /// the address `data` encodes.
+    #[selector(name = "onERC721Received")]
+    pub fn on_erc721_received(
+        &mut self,
+        _operator: Address,
+        from: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, ConverterError> {
+        let to = Address::abi_decode(&data, true).unwrap_or(from);
+        self.mint(self.vm().msg_sender(), token_id, to)?;
+        Ok(FixedBytes(ERC721_RECEIVED.to_be_bytes()))
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC1155, IERC1155_METADATA_URI, IERC721_RECEIVER])
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const COLLECTION: Address = address!("00000000000000000000000000000000000c011e");
+
+/// A converter holding `COLLECTION`'s token 7, converted for `ALICE`.
+fn setup() -> (TestVM, Erc721Converter, U256) {
+    let (vm, mut contract) = deploy::<Erc721Converter>(ALICE);
+    let pull = IERC721::transferFromCall { from: ALICE, to: vm.contract_address(), tokenId: n(7) };
+    vm.mock_call(COLLECTION, pull.abi_encode(), Ok(Vec::new()));
+    let id = ok(contract.convert(COLLECTION, n(7), ALICE));
+    (vm, contract, id)
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IERC1155.id(), 0xd9b67a26);
+    assert_eq!(IERC1155_METADATA_URI.id(), 0x0e89341c);
+    assert_eq!(IERC721_RECEIVER.id(), ERC721_RECEIVED);
+    let expected = keccak256([&[0u8; 12][..], COLLECTION.as_slice(), &n(7).to_be_bytes::<32>()[..]].concat());
+    assert_eq!(converted_id_of(COLLECTION, n(7)), U256::from_be_bytes(expected.0));
+}
+
+#[test]
+fn converting_locks_the_token_and_unconverting_releases_it() {
+    let (vm, mut contract, id) = setup();
+    assert_eq!(id, converted_id_of(COLLECTION, n(7)));
+    assert_eq!((contract.balance_of(ALICE, id), contract.holder_of(id)), (n(1), ALICE));
+    assert_eq!(contract.origin_of(id), (COLLECTION, n(7)));
+    let minted = &emitted::<TransferSingle>(&vm)[0];
+    assert_eq!((minted.from, minted.to, minted.id, minted.value), (Address::ZERO, ALICE, id, n(1)));
+    assert_eq!(emitted::<Converted>(&vm)[0].tokenId, n(7));
+
+    // The metadata is the collection's
+    let query = IERC721::tokenURICall { tokenId: n(7) };
+    vm.mock_static_call(COLLECTION, query.abi_encode(), Ok(String::from("ipfs://seven").abi_encode()));
+    assert_eq!(contract.uri(id), "ipfs://seven");
+    assert_eq!(contract.uri(n(7)), "");
+
+    vm.set_sender(BOB);
+    let result = contract.unconvert(id, BOB);
+    assert!(matches!(result, Err(ConverterError::InsufficientBalance(e)) if e.sender == BOB));
+    vm.set_sender(ALICE);
+    let release = IERC721::safeTransferFromCall { from: vm.contract_address(), to: BOB, tokenId: n(7) };
+    vm.mock_call(COLLECTION, release.abi_encode(), Ok(Vec::new()));
+    ok(contract.unconvert(id, BOB));
+    assert_eq!((contract.balance_of(ALICE, id), contract.holder_of(id)), (U256::ZERO, Address::ZERO));
+    assert_eq!(emitted::<Unconverted>(&vm)[0].to, BOB);
+}
+
+#[test]
+fn tokens_sent_to_the_converter_are_converted_for_the_sender() {
+    let (vm, mut contract, id) = setup();
+    vm.set_sender(COLLECTION);
+    let answer = ok(contract.on_erc721_received(ALICE, ALICE, n(8), Vec::new().into()));
+    assert_eq!(answer, FixedBytes(ERC721_RECEIVED.to_be_bytes()));
+    assert_eq!(contract.holder_of(converted_id_of(COLLECTION, n(8))), ALICE);
+
+    // Data encoding an address sends the ID there
+    ok(contract.on_erc721_received(ALICE, ALICE, n(9), BOB.abi_encode().into()));
+    assert_eq!(contract.holder_of(converted_id_of(COLLECTION, n(9))), BOB);
+
+    let result = contract.on_erc721_received(ALICE, ALICE, n(7), Vec::new().into());
+    assert!(matches!(result, Err(ConverterError::AlreadyConverted(e)) if e.id == id));
+
+    // Another contract only mints IDs of its own tokens
+    vm.set_sender(BOB);
+    ok(contract.on_erc721_received(BOB, BOB, n(7), Vec::new().into()));
+    assert_eq!(contract.holder_of(converted_id_of(BOB, n(7))), BOB);
+    assert_eq!(contract.holder_of(id), ALICE);
+}
+
+#[test]
+fn converted_ids_transfer_as_erc1155_tokens() {
+    let (vm, mut contract, id) = setup();
+    let result = contract.safe_transfer_from(ALICE, BOB, id, n(2), Vec::new().into());
+    assert!(matches!(result, Err(ConverterError::InsufficientBalance(e)) if e.balance == n(1)));
+    ok(contract.safe_transfer_from(ALICE, BOB, id, U256::ZERO, Vec::new().into()));
+    assert_eq!(contract.holder_of(id), ALICE);
+    ok(contract.safe_transfer_from(ALICE, BOB, id, n(1), Vec::new().into()));
+    assert_eq!(ok(contract.balance_of_batch(vec![ALICE, BOB], vec![id, id])), vec![U256::ZERO, n(1)]);
+
+    let result = contract.safe_batch_transfer_from(BOB, ALICE, vec![id], vec![n(1)], Vec::new().into());
+    assert!(matches!(result, Err(ConverterError::MissingApprovalForAll(e)) if e.owner == BOB));
+    vm.set_sender(BOB);
+    ok(contract.set_approval_for_all(ALICE, true));
+    vm.set_sender(ALICE);
+    ok(contract.safe_batch_transfer_from(BOB, ALICE, vec![id], vec![n(1)], Vec::new().into()));
+    assert_eq!(contract.holder_of(id), ALICE);
+    assert_eq!(emitted::<TransferBatch>(&vm)[0].ids, vec![id]);
+}
@@ -18,6 +18,7 @@ use stylus_sdk::{
     alloy_primitives::{Address, FixedBytes, U256}
 };
 use alloy_sol_types::sol;
+use cradle_introspection::{interface, Interface};
 
 pub trait Erc721Params {
     /// Immutable NFT name.
@@ -27,6 +28,19 @@ pub trait Erc721Params {
     const SYMBOL: &'static str;
 }
 
+/// ERC-721 as [`Erc721`] exports it. ERC721Metadata isn't supported, as there's no `tokenURI`.
+pub const IERC721: Interface = interface!(
+    "balanceOf"(Address),
+    "ownerOf"(U256),
+    "safeTransferFrom"(Address, Address, U256, Bytes),
+    "safeTransferFrom"(Address, Address, U256),
+    "transferFrom"(Address, Address, U256),
+    "approve"(Address, U256),
+    "setApprovalForAll"(Address, bool),
+    "getApproved"(U256),
+    "isApprovedForAll"(Address, Address),
+);
+
 sol_storage! {
     /// Erc721 implements all ERC-721 methods
     pub struct Erc721<T: Erc721Params> {
@@ -369,20 +383,6 @@ impl<T: Erc721Params> Erc721<T> {
 
     /// Whether the NFT supports a given standard.
     pub fn supports_interface(interface: FixedBytes<4>) -> Result<bool, Erc721Error> {
-        let interface_slice_array: [u8; 4] = interface.as_slice().try_into().unwrap();
-
-        if u32::from_be_bytes(interface_slice_array) == 0xffffffff {
-            // special cased in the ERC165 standard
-            return Ok(false);
-        }
-
-        const IERC165: u32 = 0x01ffc9a7;
-        const IERC721: u32 = 0x80ac58cd;
-        const IERC721_METADATA: u32 = 0x5b5e139f;
-
-        Ok(matches!(
-            u32::from_be_bytes(interface_slice_array),
-            IERC165 | IERC721 | IERC721_METADATA
-        ))
+        Ok(cradle_introspection::supports_interface(interface, [IERC721]))
     }
 }
\ No newline at end of file
@@ -0,0 +1,395 @@
+//! Rate-limited testnet faucet
+//!
+//! Hands out a fixed drip of each configured asset, ERC-20s and ERC-1155 token
+//! IDs such as the Cradle token components, to an address at most once per
+//! cooldown. Anyone can request a drip for any address, so a backend can pay
+//! the gas for users who have none yet.
+//!
+//! If a signer is set, every drip also needs an EIP-712 signature from it over
+//! the recipient, the recipient's nonce and a deadline. A captcha service holds
+//! the signer key and only signs for requests that passed the captcha. The
+//! signer can also be a contract, such as a multisig, whose ERC-1271
+//! `isValidSignature` is asked instead of `ecrecover`.
+//!
+//! Anyone can refill the faucet; only the owner can withdraw from it.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_crypto::{
+    domain_separator, eip712_domain, hash_struct, hash_typed_data, is_valid_signature_now, recover, Eip712Domain, Nonces,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain.
+const DOMAIN_NAME: &str = "Cradle Faucet";
+/// Version in the EIP-712 domain.
+const DOMAIN_VERSION: &str = "1";
+/// `keccak256("Drip(address recipient,uint256 nonce,uint256 deadline)")`
+const DRIP_TYPEHASH: B256 = b256!("fc6d5f502bd12a11414699b90e194af37e0dbf1129002d58689a486f28cc557e");
+/// Asset kind of an ERC-20; its token ID is ignored.
+const KIND_ERC20: u8 = 0;
+/// Asset kind of an ERC-1155 token ID.
+const KIND_ERC1155: u8 = 1;
+/// Most assets the faucet can drip.
+const MAX_ASSETS: usize = 16;
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Faucet {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Configures the drips and withdraws
+        address owner;
+        /// Seconds an address must wait between drips
+        uint256 cooldown;
+        /// Signs drip requests that passed the captcha; zero disables the check
+        address signer;
+        /// Assets handed out with every drip
+        DripAsset[] assets;
+        /// Time of each address's last drip
+        mapping(address => uint256) last_drip;
+        /// Next nonce a signature for each recipient must be made over
+        #[borrow]
+        Nonces nonces;
+    }
+
+    pub struct DripAsset {
+        uint8 kind;
+        address token;
+        uint256 id;
+        /// Amount per drip; zero disables the asset
+        uint256 amount;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Dripped(address indexed recipient, address indexed caller);
+    event DripUpdated(uint8 kind, address indexed token, uint256 id, uint256 amount);
+    event Refilled(address indexed from, address indexed token, uint256 id, uint256 amount);
+    event Withdrawn(address indexed to, address indexed token, uint256 id, uint256 amount);
+    event CooldownUpdated(uint256 cooldown);
+    event SignerUpdated(address indexed signer);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Recipient must wait until the cooldown is over
+    error CooldownActive(address recipient, uint256 next_drip_time);
+    // Signature has expired
+    error ExpiredSignature(uint256 deadline);
+    // Signature is malformed or wasn't made by the signer
+    error InvalidSigner(address signer, address expected);
+    // Asset kind is neither ERC-20 nor ERC-1155, or too many assets are configured
+    error InvalidAsset(uint8 kind, address token);
+    // A token transfer failed
+    error TransferFailed(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum FaucetError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    CooldownActive(CooldownActive),
+    ExpiredSignature(ExpiredSignature),
+    InvalidSigner(InvalidSigner),
+    InvalidAsset(InvalidAsset),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+// Helpers used by the public methods below.
+impl Faucet {
+    fn only_owner(&self) -> Result<(), FaucetError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(FaucetError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// EIP-712 digest of a drip request for `recipient`.
+    fn digest(&self, recipient: Address, nonce: U256, deadline: U256) -> B256 {
+        let struct_hash = hash_struct(self.vm(), DRIP_TYPEHASH, (recipient, nonce, deadline));
+        hash_typed_data(self.vm(), domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION), struct_hash)
+    }
+
+    /// Whether a transfer call to `token` went through; ERC-20s must also return `true`.
+    fn call_token(&mut self, kind: u8, token: Address, calldata: &[u8]) -> bool {
+        match self.vm().call(&calls::context::Call::new(), token, calldata) {
+            Ok(output) => kind == KIND_ERC1155 || bool::abi_decode(&output, true).unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// Sends `amount` of an ERC-20 or ERC-1155 token ID held by the faucet to `to`.
+    fn send(&mut self, kind: u8, token: Address, id: U256, to: Address, amount: U256) -> Result<(), FaucetError> {
+        let calldata = if kind == KIND_ERC1155 {
+            let from = self.vm().contract_address();
+            IERC1155::safeTransferFromCall { from, to, id, value: amount, data: Vec::new().into() }.abi_encode()
+        } else {
+            IERC20::transferCall { to, value: amount }.abi_encode()
+        };
+        if !self.call_token(kind, token, &calldata) {
+            return Err(FaucetError::TransferFailed(TransferFailed { token }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+#[inherit(Nonces)]
+impl Faucet {
+    /// Sets the owner, the cooldown and the captcha signer (zero for none). Can only be called once.
+    pub fn initialize(&mut self, owner: Address, cooldown: U256, signer: Address) -> Result<(), FaucetError> {
+        if self.initialized.get() {
+            return Err(FaucetError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.cooldown.set(cooldown);
+        self.signer.set(signer);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), CooldownUpdated { cooldown });
+        log(self.vm(), SignerUpdated { signer });
+        Ok(())
+    }
+
+    /// Sends every configured drip to `recipient`. Anyone can call it. `deadline` and `signature`
+    /// are only checked if a signer is set, and can be left empty otherwise.
+    pub fn drip(&mut self, recipient: Address, deadline: U256, signature: Bytes) -> Result<(), FaucetError> {
+        let now = self.now();
+        let last_drip = self.last_drip.get(recipient);
+        if !last_drip.is_zero() && now < last_drip + self.cooldown.get() {
+            return Err(FaucetError::CooldownActive(CooldownActive {
+                recipient,
+                next_drip_time: last_drip + self.cooldown.get(),
+            }));
+        }
+        let expected = self.signer.get();
+        if !expected.is_zero() {
+            if now > deadline {
+                return Err(FaucetError::ExpiredSignature(ExpiredSignature { deadline }));
+            }
+            let nonce = self.nonces.use_nonce(recipient);
+            let digest = self.digest(recipient, nonce, deadline);
+            if !is_valid_signature_now(self.vm(), expected, digest, &signature) {
+                let signer = recover(self.vm(), digest, &signature);
+                return Err(FaucetError::InvalidSigner(InvalidSigner { signer, expected }));
+            }
+        }
+        self.last_drip.insert(recipient, now);
+
+        for (kind, token, id, amount) in self.drips() {
+            if !amount.is_zero() {
+                self.send(kind, token, id, recipient, amount)?;
+            }
+        }
+
+        log(self.vm(), Dripped {
+            recipient,
+            caller: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+
+    /// Sets the amount of an asset handed out per drip, adding the asset if it is new (owner only).
+    /// `kind` is 0 for an ERC-20 and 1 for an ERC-1155. A zero amount disables the asset.
+    pub fn set_drip(&mut self, kind: u8, token: Address, id: U256, amount: U256) -> Result<(), FaucetError> {
+        self.only_owner()?;
+        if kind != KIND_ERC20 && kind != KIND_ERC1155 {
+            return Err(FaucetError::InvalidAsset(InvalidAsset { kind, token }));
+        }
+        let existing = (0..self.assets.len()).find(|&i| {
+            self.assets.get(i).is_some_and(|asset| {
+                asset.kind.get().to::<u8>() == kind && asset.token.get() == token && asset.id.get() == id
+            })
+        });
+        match existing.and_then(|i| self.assets.setter(i)) {
+            Some(mut asset) => asset.amount.set(amount),
+            None => {
+                if self.assets.len() >= MAX_ASSETS {
+                    return Err(FaucetError::InvalidAsset(InvalidAsset { kind, token }));
+                }
+                let mut asset = self.assets.grow();
+                asset.kind.set(alloy_primitives::Uint::<8, 1>::from(kind));
+                asset.token.set(token);
+                asset.id.set(id);
+                asset.amount.set(amount);
+            }
+        }
+
+        log(self.vm(), DripUpdated {
+            kind,
+            token,
+            id,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Deposits `amount` of an asset from the caller into the faucet. Anyone can refill it. The
+    /// faucet must be approved on the token first.
+    pub fn refill(&mut self, kind: u8, token: Address, id: U256, amount: U256) -> Result<(), FaucetError> {
+        let from = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        let calldata = match kind {
+            KIND_ERC20 => IERC20::transferFromCall { from, to: this, value: amount }.abi_encode(),
+            KIND_ERC1155 => {
+                let data = Vec::new().into();
+                IERC1155::safeTransferFromCall { from, to: this, id, value: amount, data }.abi_encode()
+            }
+            _ => return Err(FaucetError::InvalidAsset(InvalidAsset { kind, token })),
+        };
+        if !self.call_token(kind, token, &calldata) {
+            return Err(FaucetError::TransferFailed(TransferFailed { token }));
+        }
+
+        log(self.vm(), Refilled {
+            from,
+            token,
+            id,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Withdraws `amount` of an asset held by the faucet to `to` (owner only).
+    pub fn withdraw(&mut self, kind: u8, token: Address, id: U256, amount: U256, to: Address) -> Result<(), FaucetError> {
+        self.only_owner()?;
+        if kind != KIND_ERC20 && kind != KIND_ERC1155 {
+            return Err(FaucetError::InvalidAsset(InvalidAsset { kind, token }));
+        }
+        self.send(kind, token, id, to, amount)?;
+
+        log(self.vm(), Withdrawn {
+            to,
+            token,
+            id,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Changes the cooldown between drips (owner only).
+    pub fn set_cooldown(&mut self, cooldown: U256) -> Result<(), FaucetError> {
+        self.only_owner()?;
+        self.cooldown.set(cooldown);
+
+        log(self.vm(), CooldownUpdated { cooldown });
+        Ok(())
+    }
+
+    /// Changes the captcha signer; zero disables the signature check (owner only).
+    pub fn set_signer(&mut self, signer: Address) -> Result<(), FaucetError> {
+        self.only_owner()?;
+        self.signer.set(signer);
+
+        log(self.vm(), SignerUpdated { signer });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), FaucetError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(kind, token, id, amount)` of every configured asset.
+    pub fn drips(&self) -> Vec<(u8, Address, U256, U256)> {
+        (0..self.assets.len())
+            .filter_map(|i| self.assets.get(i))
+            .map(|asset| {
+                (
+                    asset.kind.get().to::<u8>(),
+                    asset.token.get(),
+                    asset.id.get(),
+                    asset.amount.get(),
+                )
+            })
+            .collect()
+    }
+
+    /// Earliest time `recipient` can receive its next drip.
+    pub fn next_drip_time(&self, recipient: Address) -> U256 {
+        let last_drip = self.last_drip.get(recipient);
+        if last_drip.is_zero() {
+            return U256::ZERO;
+        }
+        last_drip + self.cooldown.get()
+    }
+
+    /// Returns `(cooldown, signer)`.
+    pub fn config(&self) -> (U256, Address) {
+        (self.cooldown.get(), self.signer.get())
+    }
+
+    /// EIP-712 domain separator for this faucet and chain.
+    pub fn domain_separator(&self) -> B256 {
+        domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Returns `(name, version, chain_id, verifying_contract)` of the EIP-712 domain.
+    pub fn eip712_domain(&self) -> Eip712Domain {
+        eip712_domain(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Accepts ERC-1155 refills.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes::from(ERC1155_RECEIVED.to_be_bytes())
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok, sign};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const CAPTCHA: Address = address!("000000000000000000000000000000000000c4a7");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BACKEND: Address = address!("000000000000000000000000000000000000bac0");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const BADGES: Address = address!("0000000000000000000000000000000000001155");
+
+/// Lets the faucet send `amount` USDC to `to`.
+fn expect_usdc(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC20::transferCall { to, value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets the faucet send a badge of ID 3 to `to`.
+fn expect_badge(vm: &TestVM, to: Address) {
+    let call = IERC1155::safeTransferFromCall {
+        from: vm.contract_address(),
+        to,
+        id: n(3),
+        value: n(1),
+        data: Vec::new().into(),
+    };
+    vm.mock_call(BADGES, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// A faucet at time 1,000 dripping 100 USDC and a badge of ID 3 once a day, with `signer` as the
+/// captcha signer, called by the backend.
+fn setup(signer: Address) -> (TestVM, Faucet) {
+    let (vm, mut contract) = deploy::<Faucet>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, n(86_400), signer));
+    ok(contract.set_drip(KIND_ERC20, USDC, U256::ZERO, n(100)));
+    ok(contract.set_drip(KIND_ERC1155, BADGES, n(3), n(1)));
+    vm.set_sender(BACKEND);
+    (vm, contract)
+}
+
+#[test]
+fn drips_send_every_asset_once_per_cooldown() {
+    let (vm, mut contract) = setup(Address::ZERO);
+    expect_usdc(&vm, ALICE, 100);
+    expect_badge(&vm, ALICE);
+    ok(contract.drip(ALICE, U256::ZERO, Vec::new().into()));
+    assert_eq!(contract.next_drip_time(ALICE), n(87_400));
+    let dripped = &emitted::<Dripped>(&vm)[0];
+    assert_eq!((dripped.recipient, dripped.caller), (ALICE, BACKEND));
+
+    vm.set_block_timestamp(87_399);
+    let result = contract.drip(ALICE, U256::ZERO, Vec::new().into());
+    assert!(matches!(result, Err(FaucetError::CooldownActive(e)) if e.next_drip_time == n(87_400)));
+
+    // Disabled assets are skipped
+    vm.set_block_timestamp(87_400);
+    vm.set_sender(OWNER);
+    ok(contract.set_drip(KIND_ERC20, USDC, U256::ZERO, U256::ZERO));
+    assert_eq!(contract.drips().len(), 2);
+    ok(contract.drip(ALICE, U256::ZERO, Vec::new().into()));
+}
+
+#[test]
+fn captcha_signatures_are_checked_and_used_once() {
+    let (vm, mut contract) = setup(CAPTCHA);
+    let signature = Bytes::from(sign(&vm, CAPTCHA, contract.digest(ALICE, U256::ZERO, n(2_000))));
+    expect_usdc(&vm, ALICE, 100);
+    expect_badge(&vm, ALICE);
+    ok(contract.drip(ALICE, n(2_000), signature.clone()));
+    assert_eq!(contract.nonces.nonces(ALICE), n(1));
+
+    // The signature was over the used nonce
+    vm.set_block_timestamp(87_400);
+    let result = contract.drip(ALICE, n(2_000), signature.clone());
+    assert!(matches!(result, Err(FaucetError::ExpiredSignature(e)) if e.deadline == n(2_000)));
+    let result = contract.drip(ALICE, n(90_000), signature);
+    assert!(matches!(result, Err(FaucetError::InvalidSigner(e)) if e.signer.is_zero() && e.expected == CAPTCHA));
+    // Without the rollback of a real revert, the failed drip above used nonce 1
+    let forged = Bytes::from(sign(&vm, BACKEND, contract.digest(ALICE, n(2), n(90_000))));
+    let result = contract.drip(ALICE, n(90_000), forged);
+    assert!(matches!(result, Err(FaucetError::InvalidSigner(e)) if e.signer == BACKEND));
+}
+
+#[test]
+fn anyone_refills_and_only_the_owner_withdraws() {
+    let (vm, mut contract) = setup(Address::ZERO);
+    let this = vm.contract_address();
+    let refill = IERC20::transferFromCall { from: BACKEND, to: this, value: n(5_000) };
+    vm.mock_call(USDC, refill.abi_encode(), Ok(true.abi_encode()));
+    ok(contract.refill(KIND_ERC20, USDC, U256::ZERO, n(5_000)));
+    assert_eq!(emitted::<Refilled>(&vm)[0].amount, n(5_000));
+    let result = contract.refill(2, USDC, U256::ZERO, n(1));
+    assert!(matches!(result, Err(FaucetError::InvalidAsset(e)) if e.kind == 2));
+
+    let result = contract.withdraw(KIND_ERC20, USDC, U256::ZERO, n(5_000), BACKEND);
+    assert!(matches!(result, Err(FaucetError::Unauthorized(e)) if e.caller == BACKEND));
+    vm.set_sender(OWNER);
+    expect_usdc(&vm, OWNER, 5_000);
+    ok(contract.withdraw(KIND_ERC20, USDC, U256::ZERO, n(5_000), OWNER));
+    assert_eq!(emitted::<Withdrawn>(&vm)[0].to, OWNER);
+}
+
+#[test]
+fn drips_and_refills_that_dont_transfer_revert() {
+    let (vm, mut contract) = setup(Address::ZERO);
+    let refill = IERC1155::safeTransferFromCall {
+        from: BACKEND,
+        to: vm.contract_address(),
+        id: n(3),
+        value: n(10),
+        data: Vec::new().into(),
+    };
+    vm.mock_call(BADGES, refill.abi_encode(), Err(b"not approved".to_vec()));
+    let result = contract.refill(KIND_ERC1155, BADGES, n(3), n(10));
+    assert!(matches!(result, Err(FaucetError::TransferFailed(e)) if e.token == BADGES));
+
+    // An empty faucet fails the drip
+    let result = contract.drip(ALICE, U256::ZERO, Vec::new().into());
+    assert!(matches!(result, Err(FaucetError::TransferFailed(e)) if e.token == USDC));
+}
@@ -0,0 +1,252 @@
+//! ERC-2771 trusted forwarder
+//!
+//! Relays calls signed off-chain, so users can act on Cradle contracts without
+//! holding ETH for gas. A relayer submits the user's request together with
+//! their EIP-712 signature; the forwarder checks it and calls the target with
+//! the signer's address appended to the calldata, where ERC-2771 targets read it
+//! as the sender.
+//!
+//! A request is only executed if:
+//!
+//! - it was signed by `from` over the forwarder's current nonce for `from`
+//! - its deadline hasn't passed
+//! - the target returns true from `isTrustedForwarder` for this forwarder
+//! - the relayer sends exactly the requested `value`
+//!
+//! `from` can be a smart account or multisig: requests from an address with code
+//! are checked with its ERC-1271 `isValidSignature` instead of `ecrecover`.
+//!
+//! The target gets at most the requested gas. If the relayer supplied too little
+//! gas for the call to receive it, the whole execution reverts instead of
+//! consuming the user's nonce on a call that was starved of gas.
+//!
+//! Relayers can also charge a request to the signer's gas credits at a `gas-station-stylus`
+//! that approves the forwarder as a spender. The most the request could cost at the
+//! transaction's gas price is reserved before the call, and the gas actually used, plus a fixed
+//! overhead, is spent after it for the relayer to claim.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use cradle_crypto::{
+    domain_separator, eip712_domain, hash_struct, hash_typed_data, is_valid_signature_now, recover, Eip712Domain, Nonces,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain.
+const DOMAIN_NAME: &str = "Cradle Forwarder";
+/// Version in the EIP-712 domain.
+const DOMAIN_VERSION: &str = "1";
+/// `keccak256("ForwardRequest(address from,address to,uint256 value,uint256 gas,uint256 nonce,uint256 deadline,bytes data)")`
+const FORWARD_REQUEST_TYPEHASH: B256 = b256!("ca55ce0307ac53917d02c1387bc157c21729fef42093fa6ec5e3cb506dd1fa82");
+
+/// Gas charged to credits on top of the call, for checking the request and settling the credits.
+const GAS_CREDITS_OVERHEAD: u64 = 60_000;
+
+/// `(from, to, value, gas, deadline, data)`. The nonce isn't part of the request;
+/// it is read from the forwarder when the request is verified.
+pub type ForwardRequest = (Address, Address, U256, U256, U256, Bytes);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Forwarder {
+        /// Next nonce each signer must sign over
+        #[borrow]
+        Nonces nonces;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event ExecutedForwardRequest(address indexed signer, uint256 nonce);
+    event GasCreditsCharged(address indexed signer, address indexed relayer, uint256 charged);
+
+    // Deadline has passed
+    error ExpiredRequest(uint256 deadline);
+    // Signature is malformed or wasn't made by `from` over the current nonce
+    error InvalidSigner(address signer, address from);
+    // Target doesn't trust this forwarder
+    error UntrustfulTarget(address target, address forwarder);
+    // ETH sent doesn't match the request's value
+    error MismatchedValue(uint256 requested_value, uint256 msg_value);
+    // Relayer didn't supply enough gas for the target to receive the requested gas
+    error InsufficientGas(uint256 requested_gas);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum ForwarderError {
+    ExpiredRequest(ExpiredRequest),
+    InvalidSigner(InvalidSigner),
+    UntrustfulTarget(UntrustfulTarget),
+    MismatchedValue(MismatchedValue),
+    InsufficientGas(InsufficientGas),
+}
+
+// External interfaces
+sol! {
+    interface IERC2771Target {
+        function isTrustedForwarder(address forwarder) external view returns (bool);
+    }
+
+    interface IGasStation {
+        function reserve(address account, uint256 amount) external;
+        function settle(address account, uint256 reserved, uint256 actual_cost, address beneficiary) external returns (uint256);
+    }
+}
+
+// Helpers used by the public methods below.
+impl Forwarder {
+    /// EIP-712 digest of `request` signed over `nonce`.
+    fn digest(&self, request: &ForwardRequest, nonce: U256) -> B256 {
+        let (from, to, value, gas, deadline, data) = request;
+        let struct_hash = hash_struct(
+            self.vm(),
+            FORWARD_REQUEST_TYPEHASH,
+            (*from, *to, *value, *gas, nonce, *deadline, self.vm().native_keccak256(data)),
+        );
+        hash_typed_data(self.vm(), domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION), struct_hash)
+    }
+
+    fn is_trusted_by_target(&self, target: Address) -> bool {
+        let call = IERC2771Target::isTrustedForwarderCall { forwarder: self.vm().contract_address() };
+        self.vm()
+            .static_call(&self, target, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC2771Target::isTrustedForwarderCall::abi_decode_returns(&output, true).ok())
+            .is_some_and(|trusted| trusted._0)
+    }
+
+    /// Calls `gas_station` with `calldata`, reverting with its error if it fails.
+    fn call_gas_station(&mut self, gas_station: Address, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&calls::context::Call::new(), gas_station, calldata).map_err(Vec::<u8>::from)
+    }
+
+    /// Checks everything but the call value; returns the `from` nonce the request was signed over.
+    fn validate(&self, request: &ForwardRequest, signature: &[u8]) -> Result<U256, ForwarderError> {
+        let (from, to, _, _, deadline, _) = request;
+        if U256::from(self.vm().block_timestamp()) > *deadline {
+            return Err(ForwarderError::ExpiredRequest(ExpiredRequest { deadline: *deadline }));
+        }
+        if !self.is_trusted_by_target(*to) {
+            return Err(ForwarderError::UntrustfulTarget(UntrustfulTarget {
+                target: *to,
+                forwarder: self.vm().contract_address(),
+            }));
+        }
+        let nonce = self.nonces.nonces(*from);
+        let digest = self.digest(request, nonce);
+        if !is_valid_signature_now(self.vm(), *from, digest, signature) {
+            let signer = recover(self.vm(), digest, signature);
+            return Err(ForwarderError::InvalidSigner(InvalidSigner { signer, from: *from }));
+        }
+        Ok(nonce)
+    }
+
+    /// Calls the target of a validated request, appending the signer, and consumes its nonce.
+    fn forward(&mut self, request: ForwardRequest, nonce: U256) -> Result<(), Vec<u8>> {
+        let (from, to, value, gas, _, data) = request;
+        self.nonces.use_nonce(from);
+
+        let mut calldata = Vec::with_capacity(data.len() + 20);
+        calldata.extend_from_slice(&data);
+        calldata.extend_from_slice(from.as_slice());
+        let gas_limit = gas.saturating_to::<u64>();
+        let context = calls::context::Call::new().gas(gas_limit).value(value);
+        let result = self.vm().call(&context, to, &calldata);
+
+        // The call keeps back 1/64 of the remaining gas. If less than that is left,
+        // the target was given less than `gas` and the request must not count as executed.
+        if self.vm().evm_gas_left() < gas_limit / 63 {
+            return Err(ForwarderError::InsufficientGas(InsufficientGas { requested_gas: gas }).into());
+        }
+        result.map_err(Vec::<u8>::from)?;
+
+        log(self.vm(), ExecutedForwardRequest { signer: from, nonce });
+        Ok(())
+    }
+
+    fn require_value(&self, request: &ForwardRequest) -> Result<(), ForwarderError> {
+        let msg_value = self.vm().msg_value();
+        if msg_value != request.2 {
+            return Err(ForwarderError::MismatchedValue(MismatchedValue {
+                requested_value: request.2,
+                msg_value,
+            }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+#[inherit(Nonces)]
+impl Forwarder {
+    /// Whether `request` would currently be executed with `signature`, apart from the call value.
+    pub fn verify(&self, request: ForwardRequest, signature: Bytes) -> bool {
+        self.validate(&request, &signature).is_ok()
+    }
+
+    /// Executes a signed request, calling the target with the signer appended to the calldata.
+    /// The ETH sent must equal the request's value. Reverts with the target's error if the call fails.
+    #[payable]
+    pub fn execute(&mut self, request: ForwardRequest, signature: Bytes) -> Result<(), Vec<u8>> {
+        self.require_value(&request)?;
+        let nonce = self.validate(&request, &signature)?;
+        self.forward(request, nonce)
+    }
+
+    /// Executes a signed request like `execute`, charging its gas to the signer's credits at
+    /// `gas_station` for the caller to claim. Reserves `(gas + overhead) * gas price` of credits
+    /// before the call and releases what the call didn't use. Returns the credits charged.
+    #[payable]
+    pub fn execute_with_gas_credits(
+        &mut self,
+        request: ForwardRequest,
+        signature: Bytes,
+        gas_station: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let gas_start = self.vm().evm_gas_left();
+        self.require_value(&request)?;
+        let nonce = self.validate(&request, &signature)?;
+        let from = request.0;
+        let gas_price = self.vm().tx_gas_price();
+        let max_cost = request.3.saturating_add(U256::from(GAS_CREDITS_OVERHEAD)).saturating_mul(gas_price);
+        let reserve = IGasStation::reserveCall { account: from, amount: max_cost };
+        self.call_gas_station(gas_station, &reserve.abi_encode())?;
+
+        self.forward(request, nonce)?;
+
+        let gas_used = gas_start.saturating_sub(self.vm().evm_gas_left()) + GAS_CREDITS_OVERHEAD;
+        let relayer = self.vm().msg_sender();
+        let settle = IGasStation::settleCall {
+            account: from,
+            reserved: max_cost,
+            actual_cost: U256::from(gas_used) * gas_price,
+            beneficiary: relayer,
+        };
+        let output = self.call_gas_station(gas_station, &settle.abi_encode())?;
+        let charged = IGasStation::settleCall::abi_decode_returns(&output, true).map_err(|_| Vec::new())?._0;
+
+        log(self.vm(), GasCreditsCharged { signer: from, relayer, charged });
+        Ok(charged)
+    }
+
+    /// EIP-712 domain separator for this forwarder and chain.
+    pub fn domain_separator(&self) -> B256 {
+        domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Returns `(name, version, chain_id, verifying_contract)` of the EIP-712 domain.
+    pub fn eip712_domain(&self) -> Eip712Domain {
+        eip712_domain(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+}
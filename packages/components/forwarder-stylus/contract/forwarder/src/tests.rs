@@ -0,0 +1,127 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::SolValue;
+use cradle_test_utils::{deploy, emitted, n, sign};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const WALLET: Address = address!("0000000000000000000000000000000000005afe");
+const RELAYER: Address = address!("000000000000000000000000000000000000e1a7");
+const TARGET: Address = address!("000000000000000000000000000000000000da99");
+const STATION: Address = address!("0000000000000000000000000000000000006a50");
+
+/// An encoded call to `mint()`.
+const MINT: [u8; 4] = [0x12, 0x49, 0xc5, 0x8b];
+
+/// Revert data of `error`.
+fn revert(error: ForwarderError) -> Vec<u8> {
+    error.into()
+}
+
+/// A request from `from` to mint at `TARGET` with `value` wei and 100,000 gas, valid until 2,000.
+fn request(from: Address, value: u64) -> ForwardRequest {
+    (from, TARGET, n(value), n(100_000), n(2_000), MINT.to_vec().into())
+}
+
+/// Lets `TARGET`'s mint succeed when forwarded from `from`.
+fn expect_mint(vm: &TestVM, from: Address) {
+    let calldata = [MINT.as_slice(), from.as_slice()].concat();
+    vm.mock_call(TARGET, calldata, Ok(Vec::new()));
+}
+
+/// A forwarder at time 1,000 that `TARGET` trusts, called by the relayer.
+fn setup() -> (TestVM, Forwarder) {
+    let (vm, contract) = deploy::<Forwarder>(RELAYER);
+    vm.set_block_timestamp(1_000);
+    let call = IERC2771Target::isTrustedForwarderCall { forwarder: vm.contract_address() };
+    vm.mock_static_call(TARGET, call.abi_encode(), Ok(true.abi_encode()));
+    (vm, contract)
+}
+
+#[test]
+fn signed_requests_run_once_as_their_signer() {
+    let (vm, mut contract) = setup();
+    let signature = Bytes::from(sign(&vm, ALICE, contract.digest(&request(ALICE, 5), U256::ZERO)));
+    assert!(contract.verify(request(ALICE, 5), signature.clone()));
+
+    expect_mint(&vm, ALICE);
+    vm.set_value(n(5));
+    contract.execute(request(ALICE, 5), signature.clone()).unwrap();
+    assert_eq!(contract.nonces.nonces(ALICE), n(1));
+    let executed = &emitted::<ExecutedForwardRequest>(&vm)[0];
+    assert_eq!((executed.signer, executed.nonce), (ALICE, U256::ZERO));
+
+    // The signature was over the used nonce
+    assert!(!contract.verify(request(ALICE, 5), signature.clone()));
+    let result = contract.execute(request(ALICE, 5), signature);
+    let invalid = InvalidSigner { signer: Address::ZERO, from: ALICE };
+    assert_eq!(result, Err(revert(ForwarderError::InvalidSigner(invalid))));
+}
+
+#[test]
+fn smart_accounts_sign_through_erc1271() {
+    let (vm, mut contract) = setup();
+    vm.set_code(WALLET, vec![0xfe]);
+    let digest = contract.digest(&request(WALLET, 0), U256::ZERO);
+    let signature = Bytes::from(vec![0x5a; 65]);
+    let mut calldata = cradle_crypto::ERC1271_MAGIC_VALUE.to_vec();
+    calldata.extend((digest, signature.clone()).abi_encode_params());
+    let magic = cradle_crypto::ERC1271_MAGIC_VALUE;
+    vm.mock_static_call(WALLET, calldata, Ok(magic.abi_encode()));
+
+    expect_mint(&vm, WALLET);
+    contract.execute(request(WALLET, 0), signature).unwrap();
+    assert_eq!(contract.nonces.nonces(WALLET), n(1));
+}
+
+#[test]
+fn gas_credits_pay_the_relayer_for_the_call() {
+    let (vm, mut contract) = setup();
+    let signature = Bytes::from(sign(&vm, ALICE, contract.digest(&request(ALICE, 0), U256::ZERO)));
+
+    // Up to 100,000 + 60,000 gas at TestVM's price of 1 is reserved, and only the overhead is used
+    let reserve = IGasStation::reserveCall { account: ALICE, amount: n(160_000) };
+    vm.mock_call(STATION, reserve.abi_encode(), Ok(Vec::new()));
+    let settle =
+        IGasStation::settleCall { account: ALICE, reserved: n(160_000), actual_cost: n(60_000), beneficiary: RELAYER };
+    vm.mock_call(STATION, settle.abi_encode(), Ok(n(60_000).abi_encode()));
+    expect_mint(&vm, ALICE);
+    assert_eq!(contract.execute_with_gas_credits(request(ALICE, 0), signature, STATION), Ok(n(60_000)));
+    let charged = &emitted::<GasCreditsCharged>(&vm)[0];
+    assert_eq!((charged.signer, charged.relayer, charged.charged), (ALICE, RELAYER, n(60_000)));
+}
+
+#[test]
+fn requests_that_cant_run_as_signed_revert() {
+    let (vm, mut contract) = setup();
+    let signature = Bytes::from(sign(&vm, ALICE, contract.digest(&request(ALICE, 5), U256::ZERO)));
+    let result = contract.execute(request(ALICE, 5), signature.clone());
+    let mismatched = MismatchedValue { requested_value: n(5), msg_value: U256::ZERO };
+    assert_eq!(result, Err(revert(ForwarderError::MismatchedValue(mismatched))));
+
+    vm.set_value(n(5));
+    let mut untrusted = request(ALICE, 5);
+    untrusted.1 = STATION;
+    let result = contract.execute(untrusted, signature.clone());
+    let untrustful = UntrustfulTarget { target: STATION, forwarder: vm.contract_address() };
+    assert_eq!(result, Err(revert(ForwarderError::UntrustfulTarget(untrustful))));
+
+    // A target that got too little gas reverts the whole request
+    vm.set_gas_left(1_000);
+    let result = contract.execute(request(ALICE, 5), signature.clone());
+    let insufficient = InsufficientGas { requested_gas: n(100_000) };
+    assert_eq!(result, Err(revert(ForwarderError::InsufficientGas(insufficient))));
+
+    vm.set_block_timestamp(2_001);
+    let result = contract.execute(request(ALICE, 5), signature);
+    assert_eq!(result, Err(revert(ForwarderError::ExpiredRequest(ExpiredRequest { deadline: n(2_000) }))));
+}
+
+#[test]
+fn a_failing_target_reverts_with_its_error() {
+    let (vm, mut contract) = setup();
+    let signature = Bytes::from(sign(&vm, ALICE, contract.digest(&request(ALICE, 0), U256::ZERO)));
+    let calldata = [MINT.as_slice(), ALICE.as_slice()].concat();
+    vm.mock_call(TARGET, calldata, Err(b"sold out".to_vec()));
+    assert_eq!(contract.execute(request(ALICE, 0), signature), Err(b"sold out".to_vec()));
+}
@@ -0,0 +1,690 @@
+//! Fractionalization vault for an ERC-721 or ERC-1155 token
+//!
+//! The curator deposits a token and the vault mints ERC-20 fractions against it;
+//! the vault contract is the fraction token itself. While the vault is inactive,
+//! every holder can vote on a reserve price, and the reserve price is the average
+//! of the votes weighted by the voters' balances.
+//!
+//! Once enough of the supply has voted, anyone can start a buyout auction with a
+//! bid at or above the reserve price. The winner receives the token when the
+//! auction ends, and fraction holders burn their fractions for their share of the
+//! winning bid.
+//!
+//! An account holding the entire supply can instead redeem it for the token.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+/// Bids this close to the end push the end back by the same amount.
+const EXTENSION_WINDOW: u64 = 15 * 60;
+
+const IERC1155_INTERFACE_ID: u32 = 0xd9b67a26;
+const IERC721_INTERFACE_ID: u32 = 0x80ac58cd;
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC721Received`.
+const ERC721_RECEIVED: u32 = 0x150b7a02;
+
+/// Vault has no token yet.
+const STATE_EMPTY: u8 = 0;
+/// Token is deposited and holders may vote on the reserve price.
+const STATE_INACTIVE: u8 = 1;
+/// Buyout auction is running.
+const STATE_LIVE: u8 = 2;
+/// Buyout auction has ended; holders cash out their fractions.
+const STATE_ENDED: u8 = 3;
+/// Token was redeemed by an account holding every fraction.
+const STATE_REDEEMED: u8 = 4;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct FractionalVault {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Deposits the token and receives the initial supply
+        address curator;
+        /// One of the `STATE_*` constants
+        uint8 state;
+
+        /// Fraction token metadata
+        string name;
+        string symbol;
+        /// Fraction balances
+        mapping(address => uint256) balances;
+        /// Fraction allowances
+        mapping(address => mapping(address => uint256)) allowances;
+        /// Fractions in circulation
+        uint256 total_supply;
+
+        /// Contract of the vaulted token
+        address token_contract;
+        /// ID of the vaulted token
+        uint256 token_id;
+        /// Units vaulted, always 1 for ERC-721
+        uint256 token_amount;
+        /// Whether the vaulted token is an ERC-1155
+        bool is_erc1155;
+
+        /// Reserve price voted by each holder; zero means no vote
+        mapping(address => uint256) user_prices;
+        /// Fractions held by accounts that voted
+        uint256 voting_tokens;
+        /// Sum of votes weighted by the voters' balances
+        uint256 reserve_total;
+        /// Share of the supply that must vote before a buyout can start, in basis points
+        uint256 quorum_bps;
+
+        /// Length of a buyout auction in seconds
+        uint256 auction_length;
+        /// Minimum raise over the current bid, in basis points
+        uint256 min_bid_increment_bps;
+        /// End of the buyout auction
+        uint256 auction_end;
+        /// Highest bid
+        uint256 live_price;
+        /// Highest bidder
+        address winning;
+        /// ETH owed to outbid bidders
+        mapping(address => uint256) pending_returns;
+        /// Winning bid not yet cashed out by holders
+        uint256 proceeds;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event Deposited(address indexed token_contract, uint256 indexed token_id, uint256 amount, uint256 supply);
+    event PriceUpdated(address indexed account, uint256 price);
+    event AuctionStarted(address indexed bidder, uint256 price, uint256 auction_end);
+    event Bid(address indexed bidder, uint256 price, uint256 auction_end);
+    event AuctionWon(address indexed winner, uint256 price);
+    event Cashed(address indexed account, uint256 fractions, uint256 amount);
+    event Redeemed(address indexed account);
+    event Withdrawal(address indexed account, uint256 amount);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Action is not available in the vault's current state
+    error WrongState(uint8 state);
+    // Token contract reports neither ERC-1155 nor ERC-721
+    error UnsupportedToken(address token_contract);
+    // Amount, supply or parameter is zero or out of range
+    error InvalidAmount(uint256 amount);
+    // Fraction balance is too low
+    error InsufficientBalance(address account, uint256 balance, uint256 needed);
+    // Fraction allowance is too low
+    error InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    // Not enough of the supply has voted on a reserve price
+    error QuorumNotReached(uint256 voting_tokens, uint256 required);
+    // Bid is below the reserve price or the minimum raise
+    error BidTooLow(uint256 bid, uint256 minimum);
+    // Buyout auction has ended
+    error AuctionEnded();
+    // Buyout auction is still running
+    error AuctionNotEnded(uint256 auction_end);
+    // A token or ETH transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum VaultError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    WrongState(WrongState),
+    UnsupportedToken(UnsupportedToken),
+    InvalidAmount(InvalidAmount),
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    QuorumNotReached(QuorumNotReached),
+    BidTooLow(BidTooLow),
+    AuctionEnded(AuctionEnded),
+    AuctionNotEnded(AuctionNotEnded),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC165 {
+        function supportsInterface(bytes4 interface_id) external view returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    interface IERC721 {
+        function safeTransferFrom(address from, address to, uint256 token_id) external;
+    }
+}
+
+/// Context of the calls to the vaulted token's contract and of ETH payouts, with the ETH they send.
+/// Made through the host, unlike `sol_interface!` calls, they build with the SDK's `reentrant`
+/// feature and are mocked under test.
+struct VaultCall(U256);
+
+impl calls::CallContext for VaultCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when the token contract calls back into the vault's receiver hooks
+unsafe impl calls::MutatingCallContext for VaultCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl FractionalVault {
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn require_state(&self, expected: u8) -> Result<(), VaultError> {
+        let state = self.state.get().to::<u8>();
+        if state != expected {
+            return Err(VaultError::WrongState(WrongState { state }));
+        }
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: u8) {
+        self.state.set(alloy_primitives::Uint::<8, 1>::from(state));
+    }
+
+    fn supports(&self, token_contract: Address, interface_id: u32) -> bool {
+        let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+        self.vm()
+            .static_call(&self, token_contract, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC165::supportsInterfaceCall::abi_decode_returns(&output, true).ok())
+            .is_some_and(|supported| supported._0)
+    }
+
+    /// Calls `target` with `calldata`, attaching `value` wei.
+    fn call(&mut self, target: Address, value: U256, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&VaultCall(value), target, calldata).map_err(Vec::from)
+    }
+
+    /// Moves the vaulted token from `from` to `to`.
+    fn transfer_token(&mut self, from: Address, to: Address) -> Result<(), VaultError> {
+        let token_contract = self.token_contract.get();
+        let token_id = self.token_id.get();
+        let amount = self.token_amount.get();
+        let calldata = if self.is_erc1155.get() {
+            IERC1155::safeTransferFromCall { from, to, id: token_id, value: amount, data: Default::default() }
+                .abi_encode()
+        } else {
+            IERC721::safeTransferFromCall { from, to, token_id }.abi_encode()
+        };
+        if self.call(token_contract, U256::ZERO, &calldata).is_err() {
+            return Err(VaultError::TransferFailed(TransferFailed {
+                token: token_contract,
+                to,
+                amount,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Sends ETH to `to`.
+    fn send_eth(&mut self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.call(to, amount, &[]).map(drop)
+    }
+
+    /// Adds `amount` fractions held by `account` to the reserve price votes, or removes them.
+    fn adjust_votes(&mut self, account: Address, amount: U256, add: bool) {
+        let price = self.user_prices.get(account);
+        if price.is_zero() {
+            return;
+        }
+        if add {
+            self.voting_tokens.set(self.voting_tokens.get() + amount);
+            self.reserve_total.set(self.reserve_total.get() + amount * price);
+        } else {
+            self.voting_tokens.set(self.voting_tokens.get() - amount);
+            self.reserve_total.set(self.reserve_total.get() - amount * price);
+        }
+    }
+
+    /// Moves fractions, minting when `from` is zero and burning when `to` is zero.
+    fn update(&mut self, from: Address, to: Address, value: U256) -> Result<(), VaultError> {
+        if from.is_zero() {
+            self.total_supply.set(self.total_supply.get() + value);
+        } else {
+            let balance = self.balances.get(from);
+            if balance < value {
+                return Err(VaultError::InsufficientBalance(InsufficientBalance {
+                    account: from,
+                    balance,
+                    needed: value,
+                }));
+            }
+            self.balances.insert(from, balance - value);
+            self.adjust_votes(from, value, false);
+        }
+        if to.is_zero() {
+            self.total_supply.set(self.total_supply.get() - value);
+        } else {
+            self.balances.insert(to, self.balances.get(to) + value);
+            self.adjust_votes(to, value, true);
+        }
+
+        log(self.vm(), Transfer { from, to, value });
+        Ok(())
+    }
+
+    fn escrow_receipt(&self, operator: Address, selector: u32) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes(selector.to_be_bytes())
+        } else {
+            FixedBytes([0; 4])
+        }
+    }
+}
+
+#[public]
+impl FractionalVault {
+    /// Sets the curator, the fraction metadata and the buyout parameters. Can only be called once.
+    pub fn initialize(
+        &mut self,
+        curator: Address,
+        name: String,
+        symbol: String,
+        auction_length: U256,
+        min_bid_increment_bps: U256,
+        quorum_bps: U256,
+    ) -> Result<(), VaultError> {
+        if self.initialized.get() {
+            return Err(VaultError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if auction_length.is_zero() {
+            return Err(VaultError::InvalidAmount(InvalidAmount { amount: auction_length }));
+        }
+        if quorum_bps > U256::from(BPS_DENOMINATOR) {
+            return Err(VaultError::InvalidAmount(InvalidAmount { amount: quorum_bps }));
+        }
+        self.initialized.set(true);
+        self.curator.set(curator);
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.auction_length.set(auction_length);
+        self.min_bid_increment_bps.set(min_bid_increment_bps);
+        self.quorum_bps.set(quorum_bps);
+        Ok(())
+    }
+
+    /// Deposits the token and mints `supply` fractions to the curator, who votes `list_price` (curator only).
+    /// Requires `setApprovalForAll(vault, true)` on the token contract.
+    pub fn deposit(
+        &mut self,
+        token_contract: Address,
+        token_id: U256,
+        amount: U256,
+        supply: U256,
+        list_price: U256,
+    ) -> Result<(), VaultError> {
+        let curator = self.vm().msg_sender();
+        if curator != self.curator.get() {
+            return Err(VaultError::Unauthorized(Unauthorized { caller: curator }));
+        }
+        self.require_state(STATE_EMPTY)?;
+        if supply.is_zero() {
+            return Err(VaultError::InvalidAmount(InvalidAmount { amount: supply }));
+        }
+        let (is_erc1155, amount) = if self.supports(token_contract, IERC1155_INTERFACE_ID) {
+            if amount.is_zero() {
+                return Err(VaultError::InvalidAmount(InvalidAmount { amount }));
+            }
+            (true, amount)
+        } else if self.supports(token_contract, IERC721_INTERFACE_ID) {
+            (false, U256::from(1))
+        } else {
+            return Err(VaultError::UnsupportedToken(UnsupportedToken { token_contract }));
+        };
+
+        self.set_state(STATE_INACTIVE);
+        self.token_contract.set(token_contract);
+        self.token_id.set(token_id);
+        self.token_amount.set(amount);
+        self.is_erc1155.set(is_erc1155);
+        let this = self.vm().contract_address();
+        self.transfer_token(curator, this)?;
+
+        self.user_prices.insert(curator, list_price);
+        self.update(Address::ZERO, curator, supply)?;
+
+        log(self.vm(), Deposited {
+            token_contract,
+            token_id,
+            amount,
+            supply,
+        });
+        Ok(())
+    }
+
+    /// Votes a reserve price for the caller's fractions; zero withdraws the vote.
+    pub fn update_user_price(&mut self, price: U256) -> Result<(), VaultError> {
+        self.require_state(STATE_INACTIVE)?;
+        let account = self.vm().msg_sender();
+        let balance = self.balances.get(account);
+        self.adjust_votes(account, balance, false);
+        self.user_prices.insert(account, price);
+        self.adjust_votes(account, balance, true);
+
+        log(self.vm(), PriceUpdated { account, price });
+        Ok(())
+    }
+
+    /// Starts the buyout auction with the attached ETH as the opening bid.
+    #[payable]
+    pub fn start(&mut self) -> Result<(), VaultError> {
+        self.require_state(STATE_INACTIVE)?;
+        let voting_tokens = self.voting_tokens.get();
+        let required = self.total_supply.get() * self.quorum_bps.get() / U256::from(BPS_DENOMINATOR);
+        if voting_tokens.is_zero() || voting_tokens < required {
+            return Err(VaultError::QuorumNotReached(QuorumNotReached {
+                voting_tokens,
+                required,
+            }));
+        }
+        let bid = self.vm().msg_value();
+        let minimum = self.reserve_price();
+        if bid < minimum || bid.is_zero() {
+            return Err(VaultError::BidTooLow(BidTooLow { bid, minimum }));
+        }
+
+        let bidder = self.vm().msg_sender();
+        let auction_end = self.now() + self.auction_length.get();
+        self.set_state(STATE_LIVE);
+        self.auction_end.set(auction_end);
+        self.live_price.set(bid);
+        self.winning.set(bidder);
+
+        log(self.vm(), AuctionStarted {
+            bidder,
+            price: bid,
+            auction_end,
+        });
+        Ok(())
+    }
+
+    /// Outbids the current winner with the attached ETH. The previous winner can `withdraw` their bid.
+    #[payable]
+    pub fn bid(&mut self) -> Result<(), VaultError> {
+        self.require_state(STATE_LIVE)?;
+        let now = self.now();
+        let mut auction_end = self.auction_end.get();
+        if now >= auction_end {
+            return Err(VaultError::AuctionEnded(AuctionEnded {}));
+        }
+        let live_price = self.live_price.get();
+        let minimum =
+            live_price * (U256::from(BPS_DENOMINATOR) + self.min_bid_increment_bps.get()) / U256::from(BPS_DENOMINATOR);
+        let bid = self.vm().msg_value();
+        if bid <= live_price || bid < minimum {
+            return Err(VaultError::BidTooLow(BidTooLow { bid, minimum }));
+        }
+
+        let previous = self.winning.get();
+        self.pending_returns.insert(previous, self.pending_returns.get(previous) + live_price);
+        let bidder = self.vm().msg_sender();
+        self.live_price.set(bid);
+        self.winning.set(bidder);
+        let window = U256::from(EXTENSION_WINDOW);
+        if auction_end - now < window {
+            auction_end = now + window;
+            self.auction_end.set(auction_end);
+        }
+
+        log(self.vm(), Bid {
+            bidder,
+            price: bid,
+            auction_end,
+        });
+        Ok(())
+    }
+
+    /// Ends the buyout auction and sends the token to the winner. Callable by anyone.
+    pub fn end(&mut self) -> Result<(), VaultError> {
+        self.require_state(STATE_LIVE)?;
+        let auction_end = self.auction_end.get();
+        if self.now() < auction_end {
+            return Err(VaultError::AuctionNotEnded(AuctionNotEnded { auction_end }));
+        }
+        let winner = self.winning.get();
+        let price = self.live_price.get();
+        self.set_state(STATE_ENDED);
+        self.proceeds.set(price);
+        let this = self.vm().contract_address();
+        self.transfer_token(this, winner)?;
+
+        log(self.vm(), AuctionWon { winner, price });
+        Ok(())
+    }
+
+    /// Burns the caller's fractions for their share of the winning bid.
+    pub fn cash(&mut self) -> Result<(), VaultError> {
+        self.require_state(STATE_ENDED)?;
+        let account = self.vm().msg_sender();
+        let fractions = self.balances.get(account);
+        if fractions.is_zero() {
+            return Err(VaultError::InsufficientBalance(InsufficientBalance {
+                account,
+                balance: fractions,
+                needed: U256::from(1),
+            }));
+        }
+        let proceeds = self.proceeds.get();
+        let amount = fractions * proceeds / self.total_supply.get();
+        self.update(account, Address::ZERO, fractions)?;
+        self.proceeds.set(proceeds - amount);
+        self.send_eth(account, amount)
+            .map_err(|_| VaultError::TransferFailed(TransferFailed {
+                token: Address::ZERO,
+                to: account,
+                amount,
+            }))?;
+
+        log(self.vm(), Cashed {
+            account,
+            fractions,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Burns the entire supply held by the caller and sends them the token.
+    pub fn redeem(&mut self) -> Result<(), VaultError> {
+        self.require_state(STATE_INACTIVE)?;
+        let account = self.vm().msg_sender();
+        let balance = self.balances.get(account);
+        let supply = self.total_supply.get();
+        if balance != supply {
+            return Err(VaultError::InsufficientBalance(InsufficientBalance {
+                account,
+                balance,
+                needed: supply,
+            }));
+        }
+        self.update(account, Address::ZERO, supply)?;
+        self.set_state(STATE_REDEEMED);
+        let this = self.vm().contract_address();
+        self.transfer_token(this, account)?;
+
+        log(self.vm(), Redeemed { account });
+        Ok(())
+    }
+
+    /// Withdraws the caller's outbid bids.
+    pub fn withdraw(&mut self) -> Result<(), VaultError> {
+        let account = self.vm().msg_sender();
+        let amount = self.pending_returns.get(account);
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.pending_returns.insert(account, U256::ZERO);
+        self.send_eth(account, amount)
+            .map_err(|_| VaultError::TransferFailed(TransferFailed {
+                token: Address::ZERO,
+                to: account,
+                amount,
+            }))?;
+
+        log(self.vm(), Withdrawal { account, amount });
+        Ok(())
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        18
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
+
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, VaultError> {
+        let from = self.vm().msg_sender();
+        self.update(from, to, value)?;
+        Ok(true)
+    }
+
+    pub fn approve(&mut self, spender: Address, value: U256) -> bool {
+        let owner = self.vm().msg_sender();
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval { owner, spender, value });
+        true
+    }
+
+    pub fn transfer_from(&mut self, from: Address, to: Address, value: U256) -> Result<bool, VaultError> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowances.getter(from).get(spender);
+        if allowance < value {
+            return Err(VaultError::InsufficientAllowance(InsufficientAllowance {
+                spender,
+                allowance,
+                needed: value,
+            }));
+        }
+        if allowance != U256::MAX {
+            self.allowances.setter(from).insert(spender, allowance - value);
+        }
+        self.update(from, to, value)?;
+        Ok(true)
+    }
+
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC1155_RECEIVED)
+    }
+
+    #[selector(name = "onERC721Received")]
+    pub fn on_erc721_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _token_id: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC721_RECEIVED)
+    }
+
+    /// Balance-weighted average of the reserve price votes; zero if nobody voted.
+    pub fn reserve_price(&self) -> U256 {
+        let voting_tokens = self.voting_tokens.get();
+        if voting_tokens.is_zero() {
+            return U256::ZERO;
+        }
+        self.reserve_total.get() / voting_tokens
+    }
+
+    /// Reserve price voted by `account`.
+    pub fn user_price(&self, account: Address) -> U256 {
+        self.user_prices.get(account)
+    }
+
+    /// Returns `(voting_tokens, quorum_bps)`.
+    pub fn voting(&self) -> (U256, U256) {
+        (self.voting_tokens.get(), self.quorum_bps.get())
+    }
+
+    /// Returns `(state, token_contract, token_id, token_amount, is_erc1155)`.
+    pub fn vault(&self) -> (u8, Address, U256, U256, bool) {
+        (
+            self.state.get().to::<u8>(),
+            self.token_contract.get(),
+            self.token_id.get(),
+            self.token_amount.get(),
+            self.is_erc1155.get(),
+        )
+    }
+
+    /// Returns `(winning, live_price, auction_end, proceeds)`.
+    pub fn auction(&self) -> (Address, U256, U256, U256) {
+        (
+            self.winning.get(),
+            self.live_price.get(),
+            self.auction_end.get(),
+            self.proceeds.get(),
+        )
+    }
+
+    /// Returns `(auction_length, min_bid_increment_bps)`.
+    pub fn parameters(&self) -> (U256, U256) {
+        (self.auction_length.get(), self.min_bid_increment_bps.get())
+    }
+
+    /// ETH the account can `withdraw` after being outbid.
+    pub fn pending_returns(&self, account: Address) -> U256 {
+        self.pending_returns.get(account)
+    }
+
+    /// Account that deposited the token.
+    pub fn curator(&self) -> Address {
+        self.curator.get()
+    }
+}
@@ -0,0 +1,166 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::SolValue;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const CURATOR: Address = address!("000000000000000000000000000000000000c0a7");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const NFT: Address = address!("0000000000000000000000000000000000000721");
+
+/// Mocks whether `NFT` reports supporting `interface_id`.
+fn mock_support(vm: &TestVM, interface_id: u32, supported: bool) {
+    let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+    vm.mock_static_call(NFT, call.abi_encode(), Ok(supported.abi_encode()));
+}
+
+/// A vault at time 1,000 with one hour auctions, 5% raises and a 50% quorum.
+fn setup() -> (TestVM, FractionalVault) {
+    let (vm, mut contract) = deploy::<FractionalVault>(CURATOR);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(CURATOR, "Vaulted Punk".into(), "VPUNK".into(), n(3_600), n(500), n(5_000)));
+    (vm, contract)
+}
+
+/// `setup`, with the curator's ERC-721 token 7 split into 1,000 fractions listed at 10,000 wei.
+fn setup_deposited() -> (TestVM, FractionalVault) {
+    let (vm, mut contract) = setup();
+    mock_support(&vm, IERC1155_INTERFACE_ID, false);
+    mock_support(&vm, IERC721_INTERFACE_ID, true);
+    ok(contract.deposit(NFT, n(7), n(5), n(1_000), n(10_000)));
+    (vm, contract)
+}
+
+/// Sends `value` wei to a payable method as `sender`.
+fn pay(vm: &TestVM, sender: Address, value: u64) {
+    vm.set_sender(sender);
+    vm.set_value(n(value));
+}
+
+#[test]
+fn buyouts_pay_holders_their_share_of_the_winning_bid() {
+    let (vm, mut contract) = setup_deposited();
+    assert_eq!(contract.vault(), (STATE_INACTIVE, NFT, n(7), n(1), false));
+    assert_eq!(contract.balance_of(CURATOR), n(1_000));
+    ok(contract.transfer(ALICE, n(400)));
+    assert_eq!(contract.voting(), (n(600), n(5_000)));
+
+    // The reserve is the balance-weighted average of the votes
+    vm.set_sender(ALICE);
+    ok(contract.update_user_price(n(20_000)));
+    assert_eq!(contract.reserve_price(), n(14_000));
+
+    pay(&vm, BOB, 13_999);
+    assert!(matches!(contract.start(), Err(VaultError::BidTooLow(e)) if e.minimum == n(14_000)));
+    pay(&vm, BOB, 14_000);
+    ok(contract.start());
+    assert!(matches!(contract.update_user_price(n(1)), Err(VaultError::WrongState(e)) if e.state == STATE_LIVE));
+
+    // Raises must clear 5%, and late ones push the end back
+    vm.set_block_timestamp(4_000);
+    pay(&vm, ALICE, 14_699);
+    assert!(matches!(contract.bid(), Err(VaultError::BidTooLow(e)) if e.minimum == n(14_700)));
+    pay(&vm, ALICE, 14_700);
+    ok(contract.bid());
+    assert_eq!(contract.auction(), (ALICE, n(14_700), n(4_900), U256::ZERO));
+    assert_eq!(contract.pending_returns(BOB), n(14_000));
+    vm.set_value(U256::ZERO);
+    assert!(matches!(contract.end(), Err(VaultError::AuctionNotEnded(e)) if e.auction_end == n(4_900)));
+
+    vm.set_block_timestamp(4_900);
+    ok(contract.end());
+    let won = &emitted::<AuctionWon>(&vm)[0];
+    assert_eq!((won.winner, won.price), (ALICE, n(14_700)));
+
+    vm.set_sender(BOB);
+    ok(contract.withdraw());
+    let withdrawal = &emitted::<Withdrawal>(&vm)[0];
+    assert_eq!((withdrawal.account, withdrawal.amount), (BOB, n(14_000)));
+
+    // Holders burn their fractions for their share
+    vm.set_sender(CURATOR);
+    ok(contract.cash());
+    vm.set_sender(ALICE);
+    ok(contract.cash());
+    let cashed: Vec<_> = emitted::<Cashed>(&vm).into_iter().map(|e| (e.account, e.fractions, e.amount)).collect();
+    assert_eq!(cashed, vec![(CURATOR, n(600), n(8_820)), (ALICE, n(400), n(5_880))]);
+    assert_eq!((contract.total_supply(), contract.auction().3), (U256::ZERO, U256::ZERO));
+    assert!(matches!(contract.cash(), Err(VaultError::InsufficientBalance(e)) if e.account == ALICE));
+}
+
+#[test]
+fn buyouts_need_a_quorum_and_payouts_must_arrive() {
+    let (vm, mut contract) = setup_deposited();
+    ok(contract.update_user_price(U256::ZERO));
+    pay(&vm, BOB, 10_000);
+    assert!(matches!(contract.start(), Err(VaultError::QuorumNotReached(e)) if e.voting_tokens.is_zero()));
+
+    vm.set_sender(CURATOR);
+    ok(contract.update_user_price(n(10_000)));
+    pay(&vm, BOB, 10_000);
+    ok(contract.start());
+    pay(&vm, ALICE, 11_000);
+    ok(contract.bid());
+    vm.set_block_timestamp(4_600);
+    pay(&vm, BOB, 20_000);
+    assert!(matches!(contract.bid(), Err(VaultError::AuctionEnded(_))));
+
+    // A bidder that refuses ETH can't withdraw
+    vm.mock_call(BOB, Vec::new(), Err(b"no ETH".to_vec()));
+    vm.set_value(U256::ZERO);
+    let result = contract.withdraw();
+    assert!(matches!(result, Err(VaultError::TransferFailed(e)) if e.to == BOB && e.amount == n(10_000)));
+}
+
+#[test]
+fn deposits_check_the_curator_and_the_token() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.deposit(NFT, n(7), n(1), n(1_000), n(10_000));
+    assert!(matches!(result, Err(VaultError::Unauthorized(e)) if e.caller == ALICE));
+    vm.set_sender(CURATOR);
+    let result = contract.deposit(NFT, n(7), n(1), n(1_000), n(10_000));
+    assert!(matches!(result, Err(VaultError::UnsupportedToken(e)) if e.token_contract == NFT));
+
+    // ERC-1155 deposits vault the given amount
+    mock_support(&vm, IERC1155_INTERFACE_ID, true);
+    let result = contract.deposit(NFT, n(7), U256::ZERO, n(1_000), n(10_000));
+    assert!(matches!(result, Err(VaultError::InvalidAmount(e)) if e.amount.is_zero()));
+    ok(contract.deposit(NFT, n(7), n(5), n(1_000), n(10_000)));
+    assert_eq!(contract.vault(), (STATE_INACTIVE, NFT, n(7), n(5), true));
+    let deposited = &emitted::<Deposited>(&vm)[0];
+    assert_eq!((deposited.amount, deposited.supply), (n(5), n(1_000)));
+
+    // Only the vault's own transfers are accepted
+    let this = vm.contract_address();
+    let accepted = contract.on_erc1155_received(this, CURATOR, n(7), n(5), Vec::new().into());
+    assert_eq!(accepted, FixedBytes(ERC1155_RECEIVED.to_be_bytes()));
+    let refused = contract.on_erc721_received(ALICE, ALICE, n(7), Vec::new().into());
+    assert_eq!(refused, FixedBytes([0; 4]));
+}
+
+#[test]
+fn holders_of_every_fraction_redeem_the_token() {
+    let (vm, mut contract) = setup_deposited();
+    ok(contract.transfer(ALICE, n(1)));
+    let result = contract.redeem();
+    assert!(matches!(result, Err(VaultError::InsufficientBalance(e)) if e.balance == n(999) && e.needed == n(1_000)));
+
+    vm.set_sender(ALICE);
+    ok(contract.transfer(CURATOR, n(1)));
+    vm.set_sender(CURATOR);
+    ok(contract.redeem());
+    assert_eq!((contract.vault().0, contract.total_supply()), (STATE_REDEEMED, U256::ZERO));
+    assert_eq!(emitted::<Redeemed>(&vm)[0].account, CURATOR);
+}
+
+#[test]
+fn a_token_that_wont_move_fails_the_deposit() {
+    let (vm, mut contract) = setup();
+    mock_support(&vm, IERC721_INTERFACE_ID, true);
+    let call = IERC721::safeTransferFromCall { from: CURATOR, to: vm.contract_address(), token_id: n(7) };
+    vm.mock_call(NFT, call.abi_encode(), Err(b"not approved".to_vec()));
+    let result = contract.deposit(NFT, n(7), n(1), n(1_000), n(10_000));
+    assert!(matches!(result, Err(VaultError::TransferFailed(e)) if e.token == NFT));
+}
@@ -0,0 +1,389 @@
+//! Prepaid gas credits
+//!
+//! Users deposit ETH for gas credits, one per wei, held as ERC-1155 token `GAS_CREDIT_ID` of
+//! this contract. Credits can't be transferred, only spent on the holder's own transactions or
+//! redeemed for the ETH behind them.
+//!
+//! Spenders, contracts such as `paymaster-stylus` and `forwarder-stylus` that the owner approves,
+//! spend them when relaying the holder's transactions. Before relaying, a spender reserves the
+//! most the transaction could cost. Afterwards it settles the actual cost: that many credits are
+//! burned and their ETH is owed to the beneficiary the spender names, and the rest of the
+//! reservation is released to the holder. Reserved credits still count in `balanceOf` but can't
+//! be redeemed or reserved again.
+//!
+//! Beneficiaries claim the ETH they're owed. The ETH owed to a paymaster can instead be added to
+//! its deposit at the EntryPoint by anyone.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall};
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount, IERC173, IERC5313};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// ERC-1155, `0xd9b67a26`.
+pub const IERC1155: Interface = interface!(
+    "balanceOf"(Address, U256),
+    "balanceOfBatch"(Vec<Address>, Vec<U256>),
+    "setApprovalForAll"(Address, bool),
+    "isApprovedForAll"(Address, Address),
+    "safeTransferFrom"(Address, Address, U256, U256, Bytes),
+    "safeBatchTransferFrom"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+/// Token ID of the gas credits; the contract has no other tokens.
+pub const GAS_CREDIT_ID: U256 = U256::ZERO;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct GasStation {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Approves spenders
+        #[borrow]
+        Ownable ownable;
+        /// EntryPoint that paymasters' earnings can be deposited at
+        address entry_point;
+
+        /// Credits in circulation, including reserved ones
+        uint256 total_supply;
+        /// Credits of each holder, including reserved ones
+        mapping(address => uint256) balances;
+        /// Credits of each holder reserved by any spender
+        mapping(address => uint256) reserved;
+        /// Credits of each holder reserved by each spender
+        mapping(address => mapping(address => uint256)) reservations;
+
+        /// Contracts that may reserve and settle credits
+        mapping(address => bool) spenders;
+        /// ETH owed to each beneficiary for credits spent
+        mapping(address => uint256) earnings;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event TransferSingle(
+        address indexed operator,
+        address indexed from,
+        address indexed to,
+        uint256 id,
+        uint256 value
+    );
+    event SpenderSet(address indexed spender, bool allowed);
+    event GasCreditsReserved(address indexed account, address indexed spender, uint256 amount);
+    event GasCreditsSettled(
+        address indexed account,
+        address indexed spender,
+        address indexed beneficiary,
+        uint256 charged,
+        uint256 released
+    );
+    event EarningsClaimed(address indexed beneficiary, address to, uint256 amount);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Amount is zero
+    error ZeroAmount();
+    // Gas credits can't be transferred or approved
+    error NonTransferable();
+    // Caller isn't an approved spender
+    error NotSpender(address caller);
+    // Holder's credits that aren't reserved don't cover the amount
+    error InsufficientGasCredits(address account, uint256 available, uint256 needed);
+    // Spender is settling more than it reserved of the holder's credits
+    error ReservationExceeded(address account, address spender, uint256 reservation, uint256 amount);
+    // Accounts and IDs of `balanceOfBatch` have different lengths
+    error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
+    // Sending ETH failed
+    error EthTransferFailed(address to);
+    // A call to the EntryPoint failed
+    error EntryPointCallFailed();
+}
+
+// External interfaces
+sol! {
+    interface IEntryPoint {
+        function depositTo(address account) external payable;
+    }
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum GasStationError {
+    AlreadyInitialized(AlreadyInitialized),
+    ZeroAmount(ZeroAmount),
+    NonTransferable(NonTransferable),
+    NotSpender(NotSpender),
+    InsufficientGasCredits(InsufficientGasCredits),
+    ReservationExceeded(ReservationExceeded),
+    InvalidArrayLength(ERC1155InvalidArrayLength),
+    EthTransferFailed(EthTransferFailed),
+    EntryPointCallFailed(EntryPointCallFailed),
+    OwnableUnauthorizedAccount(OwnableUnauthorizedAccount),
+    OwnableInvalidOwner(OwnableInvalidOwner),
+}
+
+impl From<OwnableError> for GasStationError {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => GasStationError::OwnableUnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => GasStationError::OwnableInvalidOwner(e),
+        }
+    }
+}
+
+#[public]
+#[inherit(Ownable)]
+impl GasStation {
+    /// Sets the owner and the EntryPoint. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, entry_point: Address) -> Result<(), GasStationError> {
+        if self.initialized.get() {
+            return Err(GasStationError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.ownable.initialize(owner)?;
+        self.initialized.set(true);
+        self.entry_point.set(entry_point);
+        Ok(())
+    }
+
+    /// Mints gas credits for the ETH sent to `account`.
+    #[payable]
+    pub fn deposit(&mut self, account: Address) -> Result<(), GasStationError> {
+        let amount = self.vm().msg_value();
+        if amount.is_zero() {
+            return Err(GasStationError::ZeroAmount(ZeroAmount {}));
+        }
+        self.balances.insert(account, self.balances.get(account) + amount);
+        self.total_supply.set(self.total_supply.get() + amount);
+        let operator = self.vm().msg_sender();
+        log(self.vm(), TransferSingle { operator, from: Address::ZERO, to: account, id: GAS_CREDIT_ID, value: amount });
+        Ok(())
+    }
+
+    /// Redeems `amount` of the caller's credits that aren't reserved for ETH sent to `to`.
+    pub fn withdraw(&mut self, to: Address, amount: U256) -> Result<(), GasStationError> {
+        if amount.is_zero() {
+            return Err(GasStationError::ZeroAmount(ZeroAmount {}));
+        }
+        let account = self.vm().msg_sender();
+        self.require_available(account, amount)?;
+        self.burn(account, amount);
+        self.send_eth(to, amount)
+    }
+
+    /// Reserves `amount` of `account`'s credits for a transaction the caller is about to relay
+    /// (spenders only). Only writes storage keyed by `account`, so a staked paymaster can call it
+    /// while validating the account's user operation.
+    pub fn reserve(&mut self, account: Address, amount: U256) -> Result<(), GasStationError> {
+        let spender = self.only_spender()?;
+        self.require_available(account, amount)?;
+        self.reserved.insert(account, self.reserved.get(account) + amount);
+        let reservation = self.reservations.getter(account).get(spender);
+        self.reservations.setter(account).insert(spender, reservation + amount);
+        log(self.vm(), GasCreditsReserved { account, spender, amount });
+        Ok(())
+    }
+
+    /// Settles `reserved` credits the caller reserved of `account` for a transaction that cost
+    /// `actual_cost` (spenders only). Burns up to `reserved` credits for the cost, owing their ETH
+    /// to `beneficiary`, releases the rest to `account`, and returns the credits burned.
+    pub fn settle(
+        &mut self,
+        account: Address,
+        reserved: U256,
+        actual_cost: U256,
+        beneficiary: Address,
+    ) -> Result<U256, GasStationError> {
+        let spender = self.only_spender()?;
+        let reservation = self.reservations.getter(account).get(spender);
+        if reserved > reservation {
+            return Err(GasStationError::ReservationExceeded(ReservationExceeded {
+                account,
+                spender,
+                reservation,
+                amount: reserved,
+            }));
+        }
+        self.reservations.setter(account).insert(spender, reservation - reserved);
+        self.reserved.insert(account, self.reserved.get(account) - reserved);
+
+        let charged = actual_cost.min(reserved);
+        if !charged.is_zero() {
+            self.burn(account, charged);
+            self.earnings.insert(beneficiary, self.earnings.get(beneficiary) + charged);
+        }
+        log(
+            self.vm(),
+            GasCreditsSettled { account, spender, beneficiary, charged, released: reserved - charged },
+        );
+        Ok(charged)
+    }
+
+    /// Sends the ETH owed to the caller for spent credits to `to`, and returns the amount.
+    pub fn claim_earnings(&mut self, to: Address) -> Result<U256, GasStationError> {
+        let beneficiary = self.vm().msg_sender();
+        let amount = self.take_earnings(beneficiary)?;
+        self.send_eth(to, amount)?;
+        log(self.vm(), EarningsClaimed { beneficiary, to, amount });
+        Ok(amount)
+    }
+
+    /// Adds the ETH owed to `paymaster` to its deposit at the EntryPoint. Anyone can call this,
+    /// and returns the amount.
+    pub fn deposit_earnings(&mut self, paymaster: Address) -> Result<U256, GasStationError> {
+        let amount = self.take_earnings(paymaster)?;
+        let context = calls::context::Call::new().value(amount);
+        let calldata = IEntryPoint::depositToCall { account: paymaster }.abi_encode();
+        self.vm()
+            .call(&context, self.entry_point.get(), &calldata)
+            .map_err(|_| GasStationError::EntryPointCallFailed(EntryPointCallFailed {}))?;
+        let to = self.entry_point.get();
+        log(self.vm(), EarningsClaimed { beneficiary: paymaster, to, amount });
+        Ok(amount)
+    }
+
+    /// Approves or revokes a spender (owner only).
+    pub fn set_spender(&mut self, spender: Address, allowed: bool) -> Result<(), GasStationError> {
+        self.ownable.require_owner()?;
+        self.spenders.insert(spender, allowed);
+        log(self.vm(), SpenderSet { spender, allowed });
+        Ok(())
+    }
+
+    pub fn is_spender(&self, spender: Address) -> bool {
+        self.spenders.get(spender)
+    }
+
+    /// Credits of `account` that can be redeemed or reserved.
+    pub fn available_credits(&self, account: Address) -> U256 {
+        self.balances.get(account) - self.reserved.get(account)
+    }
+
+    /// Credits of `account` reserved by `spender`.
+    pub fn reservation(&self, account: Address, spender: Address) -> U256 {
+        self.reservations.getter(account).get(spender)
+    }
+
+    /// ETH owed to `beneficiary`.
+    pub fn earnings_of(&self, beneficiary: Address) -> U256 {
+        self.earnings.get(beneficiary)
+    }
+
+    pub fn entry_point(&self) -> Address {
+        self.entry_point.get()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    /// Credits of `account` for `GAS_CREDIT_ID`, including reserved ones; zero for other IDs.
+    pub fn balance_of(&self, account: Address, id: U256) -> U256 {
+        if id != GAS_CREDIT_ID {
+            return U256::ZERO;
+        }
+        self.balances.get(account)
+    }
+
+    pub fn balance_of_batch(&self, accounts: Vec<Address>, ids: Vec<U256>) -> Result<Vec<U256>, GasStationError> {
+        if accounts.len() != ids.len() {
+            return Err(GasStationError::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(accounts.len()),
+            }));
+        }
+        Ok(accounts.into_iter().zip(ids).map(|(account, id)| self.balance_of(account, id)).collect())
+    }
+
+    /// Always fails: credits can't be approved.
+    pub fn set_approval_for_all(&mut self, _operator: Address, _approved: bool) -> Result<(), GasStationError> {
+        Err(GasStationError::NonTransferable(NonTransferable {}))
+    }
+
+    pub fn is_approved_for_all(&self, _account: Address, _operator: Address) -> bool {
+        false
+    }
+
+    /// Always fails: credits can't be transferred.
+    pub fn safe_transfer_from(
+        &mut self,
+        _from: Address,
+        _to: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> Result<(), GasStationError> {
+        Err(GasStationError::NonTransferable(NonTransferable {}))
+    }
+
+    /// Always fails: credits can't be transferred.
+    pub fn safe_batch_transfer_from(
+        &mut self,
+        _from: Address,
+        _to: Address,
+        _ids: Vec<U256>,
+        _values: Vec<U256>,
+        _data: Bytes,
+    ) -> Result<(), GasStationError> {
+        Err(GasStationError::NonTransferable(NonTransferable {}))
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC1155, IERC173, IERC5313])
+    }
+}
+
+// Helpers used by the public methods above.
+impl GasStation {
+    fn only_spender(&self) -> Result<Address, GasStationError> {
+        let caller = self.vm().msg_sender();
+        if !self.spenders.get(caller) {
+            return Err(GasStationError::NotSpender(NotSpender { caller }));
+        }
+        Ok(caller)
+    }
+
+    fn require_available(&self, account: Address, amount: U256) -> Result<(), GasStationError> {
+        let available = self.available_credits(account);
+        if available < amount {
+            return Err(GasStationError::InsufficientGasCredits(InsufficientGasCredits {
+                account,
+                available,
+                needed: amount,
+            }));
+        }
+        Ok(())
+    }
+
+    fn burn(&mut self, account: Address, amount: U256) {
+        self.balances.insert(account, self.balances.get(account) - amount);
+        self.total_supply.set(self.total_supply.get() - amount);
+        let operator = self.vm().msg_sender();
+        log(self.vm(), TransferSingle { operator, from: account, to: Address::ZERO, id: GAS_CREDIT_ID, value: amount });
+    }
+
+    fn take_earnings(&mut self, beneficiary: Address) -> Result<U256, GasStationError> {
+        let amount = self.earnings.get(beneficiary);
+        if amount.is_zero() {
+            return Err(GasStationError::ZeroAmount(ZeroAmount {}));
+        }
+        self.earnings.delete(beneficiary);
+        Ok(amount)
+    }
+
+    fn send_eth(&mut self, to: Address, amount: U256) -> Result<(), GasStationError> {
+        self.vm()
+            .transfer_eth(to, amount)
+            .map_err(|_| GasStationError::EthTransferFailed(EthTransferFailed { to }))
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const RELAYER: Address = address!("000000000000000000000000000000000000e1a7");
+const PAYMASTER: Address = address!("0000000000000000000000000000000000009a73");
+const ENTRY_POINT: Address = address!("0000000071727de22e5e9d8baf0edac6f37da032");
+
+/// A station with `PAYMASTER` approved and 1,000 credits deposited for `ALICE`.
+fn setup() -> (TestVM, GasStation) {
+    let (vm, mut contract) = deploy::<GasStation>(OWNER);
+    ok(contract.initialize(OWNER, ENTRY_POINT));
+    ok(contract.set_spender(PAYMASTER, true));
+    vm.set_sender(ALICE);
+    vm.set_value(n(1_000));
+    ok(contract.deposit(ALICE));
+    vm.set_value(U256::ZERO);
+    vm.set_balance(vm.contract_address(), n(1_000));
+    (vm, contract)
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IERC1155.id(), 0xd9b67a26);
+}
+
+#[test]
+fn deposits_mint_credits_that_cannot_move() {
+    let (vm, mut contract) = setup();
+    assert_eq!(contract.balance_of(ALICE, GAS_CREDIT_ID), n(1_000));
+    assert_eq!(contract.balance_of(ALICE, n(1)), U256::ZERO);
+    assert_eq!(contract.total_supply(), n(1_000));
+    let minted = &emitted::<TransferSingle>(&vm)[0];
+    assert_eq!((minted.from, minted.to, minted.value), (Address::ZERO, ALICE, n(1_000)));
+
+    let result = contract.safe_transfer_from(ALICE, RELAYER, GAS_CREDIT_ID, n(1), Vec::new().into());
+    assert!(matches!(result, Err(GasStationError::NonTransferable(_))));
+    let result = contract.set_approval_for_all(RELAYER, true);
+    assert!(matches!(result, Err(GasStationError::NonTransferable(_))));
+    assert!(matches!(contract.deposit(ALICE), Err(GasStationError::ZeroAmount(_))));
+}
+
+#[test]
+fn settling_charges_the_actual_cost_and_releases_the_rest() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let result = contract.reserve(ALICE, n(400));
+    assert!(matches!(result, Err(GasStationError::NotSpender(e)) if e.caller == ALICE));
+
+    vm.set_sender(PAYMASTER);
+    ok(contract.reserve(ALICE, n(400)));
+    assert_eq!((contract.available_credits(ALICE), contract.balance_of(ALICE, GAS_CREDIT_ID)), (n(600), n(1_000)));
+    let result = contract.reserve(ALICE, n(601));
+    assert!(matches!(result, Err(GasStationError::InsufficientGasCredits(e)) if e.available == n(600)));
+    let result = contract.settle(ALICE, n(401), n(100), PAYMASTER);
+    assert!(matches!(result, Err(GasStationError::ReservationExceeded(e)) if e.reservation == n(400)));
+
+    assert_eq!(ok(contract.settle(ALICE, n(400), n(150), PAYMASTER)), n(150));
+    assert_eq!(contract.reservation(ALICE, PAYMASTER), U256::ZERO);
+    assert_eq!((contract.available_credits(ALICE), contract.balance_of(ALICE, GAS_CREDIT_ID)), (n(850), n(850)));
+    assert_eq!(contract.earnings_of(PAYMASTER), n(150));
+    let settled = &emitted::<GasCreditsSettled>(&vm)[0];
+    assert_eq!((settled.charged, settled.released), (n(150), n(250)));
+
+    // The charge never goes over the reservation
+    ok(contract.reserve(ALICE, n(50)));
+    assert_eq!(ok(contract.settle(ALICE, n(50), n(80), RELAYER)), n(50));
+    assert_eq!(contract.total_supply(), n(800));
+}
+
+#[test]
+fn holders_redeem_credits_that_are_not_reserved() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(PAYMASTER);
+    ok(contract.reserve(ALICE, n(700)));
+
+    vm.set_sender(ALICE);
+    let result = contract.withdraw(ALICE, n(301));
+    assert!(matches!(result, Err(GasStationError::InsufficientGasCredits(e)) if e.needed == n(301)));
+    ok(contract.withdraw(ALICE, n(300)));
+    assert_eq!(vm.balance(ALICE), n(300));
+    assert_eq!(contract.balance_of(ALICE, GAS_CREDIT_ID), n(700));
+}
+
+#[test]
+fn earnings_are_claimed_or_deposited_at_the_entry_point() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(PAYMASTER);
+    ok(contract.reserve(ALICE, n(500)));
+    ok(contract.settle(ALICE, n(300), n(300), RELAYER));
+    ok(contract.settle(ALICE, n(200), n(200), PAYMASTER));
+
+    vm.set_sender(RELAYER);
+    assert_eq!(ok(contract.claim_earnings(RELAYER)), n(300));
+    assert_eq!(vm.balance(RELAYER), n(300));
+    assert!(matches!(contract.claim_earnings(RELAYER), Err(GasStationError::ZeroAmount(_))));
+
+    // Anyone tops up the paymaster's deposit with what it earned
+    let deposit = IEntryPoint::depositToCall { account: PAYMASTER };
+    vm.mock_call(ENTRY_POINT, deposit.abi_encode(), Ok(Vec::new()));
+    assert_eq!(ok(contract.deposit_earnings(PAYMASTER)), n(200));
+    assert_eq!(contract.earnings_of(PAYMASTER), U256::ZERO);
+    assert_eq!(emitted::<EarningsClaimed>(&vm)[1].to, ENTRY_POINT);
+}
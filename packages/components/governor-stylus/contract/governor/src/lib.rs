@@ -0,0 +1,663 @@
+//! Governor with pluggable vote counting
+//!
+//! Members of a Cradle ERC-1155 collection propose and vote on batches of
+//! calls, which the governor executes once a proposal passes. The governor is
+//! meant to control a treasury and other owned components.
+//!
+//! Voting power comes from the collection's vote classes: each configured
+//! token ID counts with its own weight, so e.g. a council pass can count for
+//! more than a member pass. Votes are cast by locking tokens in the governor
+//! until the vote ends, which stops the same tokens from voting twice.
+//!
+//! The counting mode is chosen at initialization:
+//!
+//! - Weighted: power is the weighted sum of the locked tokens. A single ID with
+//!   weight 1 gives one token, one vote.
+//! - Quadratic: power is the square root of the weighted sum.
+//! - Optimistic: proposals pass unless vetoes reach a threshold, and only
+//!   vetoes are cast.
+//!
+//! So is the clock, which counts voting periods and execution delays in
+//! seconds of `block.timestamp`, L2 blocks or L1 blocks.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_arb::{
+    clock::{Clock, InvalidClockMode},
+    PrecompileCallFailed,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Power is the weighted sum of locked tokens.
+const MODE_WEIGHTED: u8 = 0;
+/// Power is the square root of the weighted sum of locked tokens.
+const MODE_QUADRATIC: u8 = 1;
+/// Proposals pass unless vetoes reach the quorum.
+const MODE_OPTIMISTIC: u8 = 2;
+/// Vote against, or veto in optimistic mode.
+const SUPPORT_AGAINST: u8 = 0;
+/// Vote for.
+const SUPPORT_FOR: u8 = 1;
+/// Vote abstain, which counts towards the quorum.
+const SUPPORT_ABSTAIN: u8 = 2;
+/// Proposal states returned by `state`.
+const STATE_ACTIVE: u8 = 0;
+const STATE_CANCELED: u8 = 1;
+const STATE_DEFEATED: u8 = 2;
+const STATE_SUCCEEDED: u8 = 3;
+const STATE_EXECUTED: u8 = 4;
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC1155BatchReceived`.
+const ERC1155_BATCH_RECEIVED: u32 = 0xbc197c81;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Governor {
+        /// Set once by `initialize`
+        bool initialized;
+        /// ERC-1155 collection votes are cast with
+        address token;
+        /// Weighted (0), quadratic (1) or optimistic (2)
+        uint8 counting_mode;
+        /// Token IDs that carry voting power
+        uint256[] class_ids;
+        /// Weight of each vote class by token ID
+        mapping(uint256 => uint256) class_weights;
+        /// Length of a vote on the clock
+        uint256 voting_period;
+        /// Time between the end of a vote and when the proposal can be executed
+        uint256 execution_delay;
+        /// Power needed for a vote to count, or vetoes needed to defeat an optimistic proposal
+        uint256 quorum;
+        /// Power a proposer must hold
+        uint256 proposal_threshold;
+        /// Proposals by ID
+        mapping(uint256 => Proposal) proposals;
+        /// Votes by proposal and voter
+        mapping(uint256 => mapping(address => Receipt)) receipts;
+        /// Tokens locked by proposal, voter and token ID
+        mapping(uint256 => mapping(address => mapping(uint256 => uint256))) locked;
+        /// What voting periods and delays are counted in, set by `initialize`
+        Clock clock;
+    }
+
+    pub struct Proposal {
+        address proposer;
+        /// Zero if the proposal doesn't exist
+        uint256 vote_end;
+        uint256 for_votes;
+        uint256 against_votes;
+        uint256 abstain_votes;
+        bool canceled;
+        bool executed;
+    }
+
+    pub struct Receipt {
+        bool has_voted;
+        uint8 support;
+        uint256 weight;
+        /// Whether the locked tokens were returned
+        bool withdrawn;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event ProposalCreated(uint256 indexed proposal_id, address indexed proposer, address[] targets, uint256[] values, bytes[] calldatas, uint256 vote_end, string description);
+    event VoteCast(address indexed voter, uint256 indexed proposal_id, uint8 support, uint256 weight);
+    event VotesWithdrawn(address indexed voter, uint256 indexed proposal_id);
+    event ProposalCanceled(uint256 indexed proposal_id);
+    event ProposalExecuted(uint256 indexed proposal_id);
+    event SettingsUpdated(uint256 voting_period, uint256 execution_delay, uint256 quorum, uint256 proposal_threshold);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Counting mode, vote classes or voting period aren't valid
+    error InvalidConfig();
+    // Array lengths don't match, or a proposal has no calls
+    error LengthMismatch();
+    // Proposer holds less power than the proposal threshold
+    error InsufficientPower(uint256 power, uint256 threshold);
+    // Proposal with the same calls and description exists
+    error ProposalExists(uint256 proposal_id);
+    // Proposal doesn't exist
+    error UnknownProposal(uint256 proposal_id);
+    // Proposal isn't in the state the action needs
+    error UnexpectedState(uint256 proposal_id, uint8 state);
+    // Support value isn't allowed in this counting mode
+    error InvalidSupport(uint8 support);
+    // Voter already voted on the proposal
+    error AlreadyVoted(address voter);
+    // No tokens were locked
+    error NoVotingPower();
+    // Nothing to withdraw
+    error NothingToWithdraw();
+    // Execution delay hasn't passed
+    error ExecutionDelayPending(uint256 executable_at);
+    // Moving the locked tokens failed
+    error TokenTransferFailed();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum GovernorError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidConfig(InvalidConfig),
+    LengthMismatch(LengthMismatch),
+    InsufficientPower(InsufficientPower),
+    ProposalExists(ProposalExists),
+    UnknownProposal(UnknownProposal),
+    UnexpectedState(UnexpectedState),
+    InvalidSupport(InvalidSupport),
+    AlreadyVoted(AlreadyVoted),
+    NoVotingPower(NoVotingPower),
+    NothingToWithdraw(NothingToWithdraw),
+    ExecutionDelayPending(ExecutionDelayPending),
+    TokenTransferFailed(TokenTransferFailed),
+    InvalidClockMode(InvalidClockMode),
+    PrecompileCallFailed(PrecompileCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface ICradle1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values, bytes data) external;
+    }
+}
+
+/// Context of the governor's calls, with the ETH they send. Made through the host, unlike
+/// `sol_interface!` calls, they build with the SDK's `reentrant` feature and are mocked under test.
+struct GovernorCall(U256);
+
+impl calls::CallContext for GovernorCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a proposal's target or the collection calls back into the governor
+unsafe impl calls::MutatingCallContext for GovernorCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl Governor {
+    fn now(&self) -> Result<U256, GovernorError> {
+        self.clock.now().map_err(GovernorError::PrecompileCallFailed)
+    }
+
+    fn counting_mode(&self) -> u8 {
+        self.counting_mode.get().to::<u8>()
+    }
+
+    fn class_ids(&self) -> Vec<U256> {
+        (0..self.class_ids.len()).filter_map(|i| self.class_ids.get(i)).collect()
+    }
+
+    /// Voting power of `amounts`, given per vote class in `class_ids` order.
+    fn power_of(&self, amounts: &[U256]) -> U256 {
+        let weighted = self
+            .class_ids()
+            .iter()
+            .zip(amounts)
+            .fold(U256::ZERO, |sum, (id, amount)| sum + *amount * self.class_weights.get(*id));
+        if self.counting_mode() == MODE_QUADRATIC {
+            weighted.root(2)
+        } else {
+            weighted
+        }
+    }
+
+    fn hash_proposal(&self, targets: &[Address], values: &[U256], calldatas: &[Bytes], description_hash: B256) -> U256 {
+        let calldatas: Vec<alloy_primitives::Bytes> = calldatas.iter().map(|data| data.0.clone().into()).collect();
+        let encoded = (targets.to_vec(), values.to_vec(), calldatas, description_hash).abi_encode_params();
+        U256::from_be_bytes(self.vm().native_keccak256(&encoded).0)
+    }
+
+    fn state_of(&self, proposal_id: U256) -> Result<u8, GovernorError> {
+        let proposal = self.proposals.getter(proposal_id);
+        let vote_end = proposal.vote_end.get();
+        if vote_end.is_zero() {
+            return Err(GovernorError::UnknownProposal(UnknownProposal { proposal_id }));
+        }
+        if proposal.executed.get() {
+            return Ok(STATE_EXECUTED);
+        }
+        if proposal.canceled.get() {
+            return Ok(STATE_CANCELED);
+        }
+        if self.now()? < vote_end {
+            return Ok(STATE_ACTIVE);
+        }
+        let for_votes = proposal.for_votes.get();
+        let against_votes = proposal.against_votes.get();
+        let quorum = self.quorum.get();
+        let passed = if self.counting_mode() == MODE_OPTIMISTIC {
+            against_votes < quorum
+        } else {
+            for_votes > against_votes && for_votes + proposal.abstain_votes.get() >= quorum
+        };
+        Ok(if passed { STATE_SUCCEEDED } else { STATE_DEFEATED })
+    }
+
+    /// Calls `target`, bubbling up its revert data.
+    fn call(&mut self, target: Address, value: U256, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&GovernorCall(value), target, calldata).map_err(Vec::from)
+    }
+
+    fn balance_of(&self, account: Address, id: U256) -> U256 {
+        let calldata = ICradle1155::balanceOfCall { account, id }.abi_encode();
+        self.vm()
+            .static_call(&self, self.token.get(), &calldata)
+            .ok()
+            .and_then(|output| ICradle1155::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map_or(U256::ZERO, |balance| balance._0)
+    }
+
+    /// Moves `values` of the vote classes between the governor and a voter.
+    fn transfer_votes(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+    ) -> Result<(), GovernorError> {
+        let data = Vec::new().into();
+        let calldata = ICradle1155::safeBatchTransferFromCall { from, to, ids, values, data }.abi_encode();
+        self.call(self.token.get(), U256::ZERO, &calldata)
+            .map_err(|_| GovernorError::TokenTransferFailed(TokenTransferFailed {}))?;
+        Ok(())
+    }
+}
+
+#[public]
+impl Governor {
+    /// Sets the collection, counting mode, vote classes and clock. Can only be called once.
+    /// `counting_mode` is 0 for weighted, 1 for quadratic and 2 for optimistic voting. In optimistic
+    /// mode `quorum` is the amount of vetoes that defeats a proposal. `clock_mode` is 0 to count
+    /// `voting_period` and `execution_delay` in seconds, 1 in L2 blocks and 2 in L1 blocks.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        token: Address,
+        counting_mode: u8,
+        class_ids: Vec<U256>,
+        class_weights: Vec<U256>,
+        voting_period: U256,
+        execution_delay: U256,
+        quorum: U256,
+        proposal_threshold: U256,
+        clock_mode: u8,
+    ) -> Result<(), GovernorError> {
+        if self.initialized.get() {
+            return Err(GovernorError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.clock.init(clock_mode).map_err(GovernorError::InvalidClockMode)?;
+        if !matches!(counting_mode, MODE_WEIGHTED | MODE_QUADRATIC | MODE_OPTIMISTIC) || class_ids.is_empty() || voting_period.is_zero() {
+            return Err(GovernorError::InvalidConfig(InvalidConfig {}));
+        }
+        if class_ids.len() != class_weights.len() {
+            return Err(GovernorError::LengthMismatch(LengthMismatch {}));
+        }
+        for (id, weight) in class_ids.iter().zip(&class_weights) {
+            if weight.is_zero() || !self.class_weights.get(*id).is_zero() {
+                return Err(GovernorError::InvalidConfig(InvalidConfig {}));
+            }
+            self.class_ids.push(*id);
+            self.class_weights.insert(*id, *weight);
+        }
+        self.initialized.set(true);
+        self.token.set(token);
+        self.counting_mode.set(alloy_primitives::Uint::<8, 1>::from(counting_mode));
+        self.voting_period.set(voting_period);
+        self.execution_delay.set(execution_delay);
+        self.quorum.set(quorum);
+        self.proposal_threshold.set(proposal_threshold);
+
+        log(self.vm(), SettingsUpdated {
+            voting_period,
+            execution_delay,
+            quorum,
+            proposal_threshold,
+        });
+        Ok(())
+    }
+
+    /// Proposes a batch of calls. The caller's power from the tokens they hold must reach the
+    /// proposal threshold. Voting starts immediately. Returns the proposal ID.
+    pub fn propose(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<U256>,
+        calldatas: Vec<Bytes>,
+        description: String,
+    ) -> Result<U256, GovernorError> {
+        if targets.is_empty() || targets.len() != values.len() || targets.len() != calldatas.len() {
+            return Err(GovernorError::LengthMismatch(LengthMismatch {}));
+        }
+        let proposer = self.vm().msg_sender();
+        let balances: Vec<U256> = self.class_ids().into_iter().map(|id| self.balance_of(proposer, id)).collect();
+        let power = self.power_of(&balances);
+        let threshold = self.proposal_threshold.get();
+        if power < threshold {
+            return Err(GovernorError::InsufficientPower(InsufficientPower { power, threshold }));
+        }
+
+        let description_hash = self.vm().native_keccak256(description.as_bytes());
+        let proposal_id = self.hash_proposal(&targets, &values, &calldatas, description_hash);
+        if !self.proposals.getter(proposal_id).vote_end.get().is_zero() {
+            return Err(GovernorError::ProposalExists(ProposalExists { proposal_id }));
+        }
+        let vote_end = self.now()? + self.voting_period.get();
+        let mut proposal = self.proposals.setter(proposal_id);
+        proposal.proposer.set(proposer);
+        proposal.vote_end.set(vote_end);
+
+        log(self.vm(), ProposalCreated {
+            proposal_id,
+            proposer,
+            targets,
+            values,
+            calldatas: calldatas.into_iter().map(|data| data.0.into()).collect(),
+            vote_end,
+            description,
+        });
+        Ok(proposal_id)
+    }
+
+    /// Votes by locking `amounts` of each vote class, in `voteClasses` order, until the vote ends.
+    /// The caller must have approved the governor with `setApprovalForAll`. `support` is 0 against,
+    /// 1 for and 2 abstain; only 0, a veto, is allowed in optimistic mode. Returns the vote's power.
+    pub fn cast_vote(&mut self, proposal_id: U256, support: u8, amounts: Vec<U256>) -> Result<U256, GovernorError> {
+        let state = self.state_of(proposal_id)?;
+        if state != STATE_ACTIVE {
+            return Err(GovernorError::UnexpectedState(UnexpectedState { proposal_id, state }));
+        }
+        let allowed = if self.counting_mode() == MODE_OPTIMISTIC {
+            support == SUPPORT_AGAINST
+        } else {
+            support <= SUPPORT_ABSTAIN
+        };
+        if !allowed {
+            return Err(GovernorError::InvalidSupport(InvalidSupport { support }));
+        }
+        let class_ids = self.class_ids();
+        if amounts.len() != class_ids.len() {
+            return Err(GovernorError::LengthMismatch(LengthMismatch {}));
+        }
+        let voter = self.vm().msg_sender();
+        if self.receipts.getter(proposal_id).getter(voter).has_voted.get() {
+            return Err(GovernorError::AlreadyVoted(AlreadyVoted { voter }));
+        }
+        let weight = self.power_of(&amounts);
+        if weight.is_zero() {
+            return Err(GovernorError::NoVotingPower(NoVotingPower {}));
+        }
+
+        let mut votes = self.receipts.setter(proposal_id);
+        let mut receipt = votes.setter(voter);
+        receipt.has_voted.set(true);
+        receipt.support.set(alloy_primitives::Uint::<8, 1>::from(support));
+        receipt.weight.set(weight);
+        let mut locked = self.locked.setter(proposal_id);
+        let mut voter_locked = locked.setter(voter);
+        for (id, amount) in class_ids.iter().zip(&amounts) {
+            voter_locked.insert(*id, *amount);
+        }
+        let mut proposal = self.proposals.setter(proposal_id);
+        let tally = match support {
+            SUPPORT_FOR => &mut proposal.for_votes,
+            SUPPORT_AGAINST => &mut proposal.against_votes,
+            _ => &mut proposal.abstain_votes,
+        };
+        let votes = tally.get();
+        tally.set(votes + weight);
+
+        let this = self.vm().contract_address();
+        self.transfer_votes(voter, this, class_ids, amounts)?;
+
+        log(self.vm(), VoteCast {
+            voter,
+            proposal_id,
+            support,
+            weight,
+        });
+        Ok(weight)
+    }
+
+    /// Returns the tokens the caller locked to vote on a proposal, once the vote is over.
+    pub fn withdraw_votes(&mut self, proposal_id: U256) -> Result<(), GovernorError> {
+        let state = self.state_of(proposal_id)?;
+        if state == STATE_ACTIVE {
+            return Err(GovernorError::UnexpectedState(UnexpectedState { proposal_id, state }));
+        }
+        let voter = self.vm().msg_sender();
+        let receipt = self.receipts.getter(proposal_id);
+        let receipt = receipt.getter(voter);
+        if !receipt.has_voted.get() || receipt.withdrawn.get() {
+            return Err(GovernorError::NothingToWithdraw(NothingToWithdraw {}));
+        }
+        self.receipts.setter(proposal_id).setter(voter).withdrawn.set(true);
+
+        let class_ids = self.class_ids();
+        let mut amounts = Vec::with_capacity(class_ids.len());
+        let mut locked = self.locked.setter(proposal_id);
+        let mut voter_locked = locked.setter(voter);
+        for id in &class_ids {
+            amounts.push(voter_locked.get(*id));
+            voter_locked.delete(*id);
+        }
+
+        let this = self.vm().contract_address();
+        self.transfer_votes(this, voter, class_ids, amounts)?;
+
+        log(self.vm(), VotesWithdrawn { voter, proposal_id });
+        Ok(())
+    }
+
+    /// Cancels a proposal while its vote is running (proposer only).
+    pub fn cancel(&mut self, proposal_id: U256) -> Result<(), GovernorError> {
+        let state = self.state_of(proposal_id)?;
+        if state != STATE_ACTIVE {
+            return Err(GovernorError::UnexpectedState(UnexpectedState { proposal_id, state }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != self.proposals.getter(proposal_id).proposer.get() {
+            return Err(GovernorError::Unauthorized(Unauthorized { caller }));
+        }
+        self.proposals.setter(proposal_id).canceled.set(true);
+
+        log(self.vm(), ProposalCanceled { proposal_id });
+        Ok(())
+    }
+
+    /// Executes a passed proposal's calls in order once the execution delay is over, bubbling up
+    /// the first revert. Anyone can execute. ETH sent to the calls comes from the governor.
+    pub fn execute(
+        &mut self,
+        targets: Vec<Address>,
+        values: Vec<U256>,
+        calldatas: Vec<Bytes>,
+        description_hash: B256,
+    ) -> Result<(), Vec<u8>> {
+        let proposal_id = self.hash_proposal(&targets, &values, &calldatas, description_hash);
+        let state = self.state_of(proposal_id)?;
+        if state != STATE_SUCCEEDED {
+            return Err(GovernorError::UnexpectedState(UnexpectedState { proposal_id, state }).into());
+        }
+        let executable_at = self.proposals.getter(proposal_id).vote_end.get() + self.execution_delay.get();
+        if self.now()? < executable_at {
+            return Err(GovernorError::ExecutionDelayPending(ExecutionDelayPending { executable_at }).into());
+        }
+        self.proposals.setter(proposal_id).executed.set(true);
+
+        for ((target, value), data) in targets.into_iter().zip(values).zip(calldatas) {
+            self.call(target, value, &data)?;
+        }
+
+        log(self.vm(), ProposalExecuted { proposal_id });
+        Ok(())
+    }
+
+    /// Updates the voting settings (the governor itself only, through a proposal).
+    pub fn update_settings(
+        &mut self,
+        voting_period: U256,
+        execution_delay: U256,
+        quorum: U256,
+        proposal_threshold: U256,
+    ) -> Result<(), GovernorError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.vm().contract_address() {
+            return Err(GovernorError::Unauthorized(Unauthorized { caller }));
+        }
+        if voting_period.is_zero() {
+            return Err(GovernorError::InvalidConfig(InvalidConfig {}));
+        }
+        self.voting_period.set(voting_period);
+        self.execution_delay.set(execution_delay);
+        self.quorum.set(quorum);
+        self.proposal_threshold.set(proposal_threshold);
+
+        log(self.vm(), SettingsUpdated {
+            voting_period,
+            execution_delay,
+            quorum,
+            proposal_threshold,
+        });
+        Ok(())
+    }
+
+    /// Accepts the tokens the governor locks itself and rejects any sent to it directly.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes::from(ERC1155_RECEIVED.to_be_bytes())
+        } else {
+            FixedBytes::ZERO
+        }
+    }
+
+    /// Accepts the batches the governor locks itself and rejects any sent to it directly.
+    #[selector(name = "onERC1155BatchReceived")]
+    pub fn on_erc1155_batch_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _ids: Vec<U256>,
+        _values: Vec<U256>,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes::from(ERC1155_BATCH_RECEIVED.to_be_bytes())
+        } else {
+            FixedBytes::ZERO
+        }
+    }
+
+    /// State of a proposal: 0 active, 1 canceled, 2 defeated, 3 succeeded, 4 executed.
+    pub fn state(&self, proposal_id: U256) -> Result<u8, GovernorError> {
+        self.state_of(proposal_id)
+    }
+
+    /// Returns `(proposer, vote_end, for_votes, against_votes, abstain_votes)`.
+    pub fn get_proposal(&self, proposal_id: U256) -> (Address, U256, U256, U256, U256) {
+        let proposal = self.proposals.getter(proposal_id);
+        (
+            proposal.proposer.get(),
+            proposal.vote_end.get(),
+            proposal.for_votes.get(),
+            proposal.against_votes.get(),
+            proposal.abstain_votes.get(),
+        )
+    }
+
+    /// Returns `(has_voted, support, weight, withdrawn)` for a voter.
+    pub fn get_receipt(&self, proposal_id: U256, voter: Address) -> (bool, u8, U256, bool) {
+        let votes = self.receipts.getter(proposal_id);
+        let receipt = votes.getter(voter);
+        (
+            receipt.has_voted.get(),
+            receipt.support.get().to::<u8>(),
+            receipt.weight.get(),
+            receipt.withdrawn.get(),
+        )
+    }
+
+    /// ID a proposal with these calls and description hash has.
+    pub fn hash_proposal_id(
+        &self,
+        targets: Vec<Address>,
+        values: Vec<U256>,
+        calldatas: Vec<Bytes>,
+        description_hash: B256,
+    ) -> U256 {
+        self.hash_proposal(&targets, &values, &calldatas, description_hash)
+    }
+
+    /// Voting power `amounts` of each vote class would give.
+    pub fn voting_power(&self, amounts: Vec<U256>) -> U256 {
+        self.power_of(&amounts)
+    }
+
+    /// Returns `(ids, weights)` of the vote classes.
+    pub fn vote_classes(&self) -> (Vec<U256>, Vec<U256>) {
+        let ids = self.class_ids();
+        let weights = ids.iter().map(|id| self.class_weights.get(*id)).collect();
+        (ids, weights)
+    }
+
+    /// Returns `(token, counting_mode, voting_period, execution_delay, quorum, proposal_threshold)`.
+    pub fn settings(&self) -> (Address, u8, U256, U256, U256, U256) {
+        (
+            self.token.get(),
+            self.counting_mode(),
+            self.voting_period.get(),
+            self.execution_delay.get(),
+            self.quorum.get(),
+            self.proposal_threshold.get(),
+        )
+    }
+
+    /// What voting periods and delays are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1
+    /// blocks.
+    pub fn clock_mode(&self) -> u8 {
+        self.clock.mode()
+    }
+
+    /// Current time on the governor's clock, which vote ends are compared against.
+    pub fn clock(&self) -> Result<U256, GovernorError> {
+        self.now()
+    }
+
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+use alloy_primitives::{address, keccak256};
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const COLLECTION: Address = address!("0000000000000000000000000000000000001155");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const TARGET: Address = address!("000000000000000000000000000000000000da99");
+
+/// An encoded call to `mint()`.
+const MINT: [u8; 4] = [0x12, 0x49, 0xc5, 0x8b];
+const DESCRIPTION: &str = "Mint the grant";
+
+/// Revert data of `error`.
+fn revert(error: GovernorError) -> Vec<u8> {
+    error.into()
+}
+
+/// Mocks how many of the council pass, class 2, `account` holds.
+fn mock_council(vm: &TestVM, account: Address, balance: u64) {
+    let call = ICradle1155::balanceOfCall { account, id: n(2) };
+    vm.mock_static_call(COLLECTION, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// Lets the collection move `members` member passes and `council` council passes from `from` to `to`.
+fn expect_transfer(vm: &TestVM, from: Address, to: Address, members: u64, council: u64) {
+    let (ids, values, data) = (vec![n(1), n(2)], vec![n(members), n(council)], Vec::new().into());
+    let call = ICradle1155::safeBatchTransferFromCall { from, to, ids, values, data };
+    vm.mock_call(COLLECTION, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// A proposal to mint at `TARGET` with 100 wei.
+fn proposal() -> (Vec<Address>, Vec<U256>, Vec<Bytes>) {
+    (vec![TARGET], vec![n(100)], vec![MINT.to_vec().into()])
+}
+
+/// Alice proposes the mint, and gets its ID.
+fn propose(contract: &mut Governor) -> U256 {
+    let (targets, values, calldatas) = proposal();
+    ok(contract.propose(targets, values, calldatas, DESCRIPTION.into()))
+}
+
+fn execute(contract: &mut Governor) -> Result<(), Vec<u8>> {
+    let (targets, values, calldatas) = proposal();
+    contract.execute(targets, values, calldatas, keccak256(DESCRIPTION))
+}
+
+/// A weighted governor at time 1,000 holding 1,000 wei, where member passes count 1 and council
+/// passes 3. Votes run 100 seconds and pass with 5 power after a 50 second delay. Alice holds a
+/// council pass, which is enough to propose.
+fn setup(counting_mode: u8) -> (TestVM, Governor) {
+    let (vm, mut contract) = deploy::<Governor>(ALICE);
+    vm.set_block_timestamp(1_000);
+    vm.set_balance(vm.contract_address(), n(1_000));
+    mock_council(&vm, ALICE, 1);
+    let (ids, weights) = (vec![n(1), n(2)], vec![n(1), n(3)]);
+    ok(contract.initialize(COLLECTION, counting_mode, ids, weights, n(100), n(50), n(5), n(3), 0));
+    (vm, contract)
+}
+
+#[test]
+fn passed_proposals_execute_and_voters_get_their_tokens_back() {
+    let (vm, mut contract) = setup(MODE_WEIGHTED);
+    let proposal_id = propose(&mut contract);
+    assert_eq!(contract.get_proposal(proposal_id).0, ALICE);
+    assert_eq!(emitted::<ProposalCreated>(&vm)[0].vote_end, n(1_100));
+
+    let this = vm.contract_address();
+    expect_transfer(&vm, ALICE, this, 2, 1);
+    assert_eq!(ok(contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(2), n(1)])), n(5));
+    vm.set_sender(BOB);
+    expect_transfer(&vm, BOB, this, 1, 0);
+    assert_eq!(ok(contract.cast_vote(proposal_id, SUPPORT_AGAINST, vec![n(1), n(0)])), n(1));
+    assert_eq!(contract.get_receipt(proposal_id, ALICE), (true, SUPPORT_FOR, n(5), false));
+    let result = contract.withdraw_votes(proposal_id);
+    assert!(matches!(result, Err(GovernorError::UnexpectedState(e)) if e.state == STATE_ACTIVE));
+
+    vm.set_block_timestamp(1_100);
+    assert_eq!(ok(contract.state(proposal_id)), STATE_SUCCEEDED);
+    let pending = ExecutionDelayPending { executable_at: n(1_150) };
+    assert_eq!(execute(&mut contract), Err(revert(GovernorError::ExecutionDelayPending(pending))));
+    vm.set_block_timestamp(1_150);
+    vm.mock_call(TARGET, MINT.to_vec(), Ok(Vec::new()));
+    assert_eq!(execute(&mut contract), Ok(()));
+    assert_eq!(ok(contract.state(proposal_id)), STATE_EXECUTED);
+    assert_eq!(emitted::<ProposalExecuted>(&vm)[0].proposal_id, proposal_id);
+
+    // Voters on either side take their tokens back once
+    expect_transfer(&vm, this, BOB, 1, 0);
+    ok(contract.withdraw_votes(proposal_id));
+    assert!(contract.get_receipt(proposal_id, BOB).3);
+    assert!(matches!(contract.withdraw_votes(proposal_id), Err(GovernorError::NothingToWithdraw(_))));
+}
+
+#[test]
+fn optimistic_proposals_pass_unless_vetoed() {
+    let (vm, mut contract) = setup(MODE_OPTIMISTIC);
+    let proposal_id = propose(&mut contract);
+    let result = contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(1), n(0)]);
+    assert!(matches!(result, Err(GovernorError::InvalidSupport(e)) if e.support == SUPPORT_FOR));
+    expect_transfer(&vm, ALICE, vm.contract_address(), 4, 0);
+    ok(contract.cast_vote(proposal_id, SUPPORT_AGAINST, vec![n(4), n(0)]));
+    vm.set_block_timestamp(1_100);
+    assert_eq!(ok(contract.state(proposal_id)), STATE_SUCCEEDED);
+
+    // One more veto reaches the quorum of 5
+    let (targets, values, _) = proposal();
+    let second = ok(contract.propose(targets, values, vec![Vec::new().into()], DESCRIPTION.into()));
+    expect_transfer(&vm, ALICE, vm.contract_address(), 2, 1);
+    ok(contract.cast_vote(second, SUPPORT_AGAINST, vec![n(2), n(1)]));
+    vm.set_block_timestamp(1_200);
+    assert_eq!(ok(contract.state(second)), STATE_DEFEATED);
+    assert_eq!(execute(&mut contract), Ok(()));
+}
+
+#[test]
+fn proposals_and_votes_are_checked() {
+    let (vm, mut contract) = setup(MODE_QUADRATIC);
+    let result = contract.propose(vec![TARGET], vec![U256::ZERO], vec![Vec::new().into()], String::new());
+    assert!(matches!(result, Err(GovernorError::InsufficientPower(e)) if e.power == n(1) && e.threshold == n(3)));
+    mock_council(&vm, ALICE, 3);
+    let proposal_id = propose(&mut contract);
+    let (targets, values, calldatas) = proposal();
+    let result = contract.propose(targets, values, calldatas, DESCRIPTION.into());
+    assert!(matches!(result, Err(GovernorError::ProposalExists(e)) if e.proposal_id == proposal_id));
+    assert_eq!(contract.voting_power(vec![n(6), n(1)]), n(3));
+
+    vm.set_sender(BOB);
+    let result = contract.propose(vec![TARGET], vec![U256::ZERO], vec![Vec::new().into()], String::new());
+    assert!(matches!(result, Err(GovernorError::InsufficientPower(e)) if e.power.is_zero()));
+    let result = contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(0), n(0)]);
+    assert!(matches!(result, Err(GovernorError::NoVotingPower(_))));
+    let result = contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(1)]);
+    assert!(matches!(result, Err(GovernorError::LengthMismatch(_))));
+    assert!(matches!(contract.cancel(proposal_id), Err(GovernorError::Unauthorized(e)) if e.caller == BOB));
+    let result = contract.update_settings(n(1), n(0), n(0), n(0));
+    assert!(matches!(result, Err(GovernorError::Unauthorized(e)) if e.caller == BOB));
+
+    vm.set_sender(ALICE);
+    ok(contract.cancel(proposal_id));
+    let result = contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(1), n(0)]);
+    assert!(matches!(result, Err(GovernorError::UnexpectedState(e)) if e.state == STATE_CANCELED));
+    let canceled = UnexpectedState { proposal_id, state: STATE_CANCELED };
+    assert_eq!(execute(&mut contract), Err(revert(GovernorError::UnexpectedState(canceled))));
+}
+
+#[test]
+fn failing_token_pulls_and_targets_revert() {
+    let (vm, mut contract) = setup(MODE_WEIGHTED);
+    let proposal_id = propose(&mut contract);
+    let (ids, values, data) = (vec![n(1), n(2)], vec![n(2), n(1)], Vec::new().into());
+    let call = ICradle1155::safeBatchTransferFromCall { from: ALICE, to: vm.contract_address(), ids, values, data };
+    vm.mock_call(COLLECTION, call.abi_encode(), Err(b"not approved".to_vec()));
+    let result = contract.cast_vote(proposal_id, SUPPORT_FOR, vec![n(2), n(1)]);
+    assert!(matches!(result, Err(GovernorError::TokenTransferFailed(_))));
+
+    // Without the rollback of a real revert, the vote above still counts, and passes the proposal
+    vm.set_block_timestamp(1_150);
+    vm.mock_call(TARGET, MINT.to_vec(), Err(b"sold out".to_vec()));
+    assert_eq!(execute(&mut contract), Err(b"sold out".to_vec()));
+}
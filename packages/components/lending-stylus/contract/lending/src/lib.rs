@@ -0,0 +1,653 @@
+//! Collateralized lending pool with ERC-1155 debt positions
+//!
+//! Lenders supply the borrow token and receive pool shares. Borrowers open a
+//! position, which mints a token ID from a Cradle ERC-1155 contract owned by the
+//! pool; whoever holds that token controls the position. A position holds ERC-20
+//! collateral and can borrow up to the loan-to-value ratio of its value.
+//!
+//! Interest accrues per block through a global borrow index, so every position's
+//! debt grows at the same rate without being touched. Positions whose debt exceeds
+//! the liquidation threshold can be liquidated: the liquidator repays debt and
+//! seizes collateral worth the repayment plus a bonus. If the collateral is worth
+//! less than that, the liquidator seizes all of it and repays only what it covers.
+//!
+//! The collateral price is set by the owner in borrow-token units, and is never zero.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale of the price, the borrow index and the interest rate.
+const WAD: u128 = 1_000_000_000_000_000_000;
+/// Basis point denominator.
+const BPS: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct LendingPool {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the price and risk parameters
+        address owner;
+        /// ERC-20 held as collateral
+        address collateral_token;
+        /// ERC-20 lent out
+        address borrow_token;
+        /// Cradle ERC-1155 owned by the pool; each token ID is a position
+        address position_token;
+        /// Borrow-token units per collateral unit, WAD-scaled
+        uint256 collateral_price;
+        /// Maximum borrow against collateral value, in basis points
+        uint256 ltv_bps;
+        /// Debt to collateral value ratio that allows liquidation, in basis points
+        uint256 liquidation_threshold_bps;
+        /// Extra collateral paid to liquidators, in basis points
+        uint256 liquidation_bonus_bps;
+        /// Interest per block, WAD-scaled
+        uint256 rate_per_block;
+        /// Cumulative interest factor, WAD-scaled
+        uint256 borrow_index;
+        /// Block at which `borrow_index` was last updated
+        uint256 last_accrual_block;
+        /// Borrow tokens held by the pool and not lent out
+        uint256 cash;
+        /// Sum of all position debts divided by the borrow index
+        uint256 total_scaled_debt;
+        /// Lender shares outstanding
+        uint256 total_shares;
+        /// Lender shares per account
+        mapping(address => uint256) shares;
+        /// Position IDs issued so far
+        uint256 next_position_id;
+        /// Collateral held per position
+        mapping(uint256 => uint256) collateral;
+        /// Debt per position divided by the borrow index
+        mapping(uint256 => uint256) scaled_debt;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Supplied(address indexed lender, uint256 amount, uint256 shares);
+    event Withdrawn(address indexed lender, uint256 amount, uint256 shares);
+    event PositionOpened(uint256 indexed position_id, address indexed owner);
+    event CollateralDeposited(uint256 indexed position_id, address indexed from, uint256 amount);
+    event CollateralWithdrawn(uint256 indexed position_id, address indexed to, uint256 amount);
+    event Borrowed(uint256 indexed position_id, address indexed to, uint256 amount);
+    event Repaid(uint256 indexed position_id, address indexed from, uint256 amount);
+    event Liquidated(uint256 indexed position_id, address indexed liquidator, uint256 repaid, uint256 seized);
+    event PriceUpdated(uint256 price);
+    event ParametersUpdated(uint256 ltv_bps, uint256 liquidation_threshold_bps, uint256 liquidation_bonus_bps, uint256 rate_per_block);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Amount is zero, or collateral and borrow token are the same
+    error InvalidAmount(uint256 amount);
+    // Collateral price is zero
+    error InvalidPrice(uint256 price);
+    // Risk parameters are inconsistent
+    error InvalidParameters(uint256 ltv_bps, uint256 liquidation_threshold_bps, uint256 liquidation_bonus_bps);
+    // Caller does not hold the position token
+    error NotPositionOwner(uint256 position_id, address caller);
+    // Action would leave the position above its loan-to-value ratio
+    error InsufficientCollateral(uint256 position_id, uint256 debt, uint256 max_debt);
+    // Pool doesn't have enough cash for the withdrawal or borrow
+    error InsufficientLiquidity(uint256 cash, uint256 requested);
+    // Lender doesn't have enough shares
+    error InsufficientShares(uint256 have, uint256 want);
+    // Position is healthy and can't be liquidated
+    error PositionHealthy(uint256 position_id);
+    // An ERC-20 transfer or position mint failed
+    error ExternalCallFailed(address target);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum LendingError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidAmount(InvalidAmount),
+    InvalidPrice(InvalidPrice),
+    InvalidParameters(InvalidParameters),
+    NotPositionOwner(NotPositionOwner),
+    InsufficientCollateral(InsufficientCollateral),
+    InsufficientLiquidity(InsufficientLiquidity),
+    InsufficientShares(InsufficientShares),
+    PositionHealthy(PositionHealthy),
+    ExternalCallFailed(ExternalCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface ICradle1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+    }
+}
+
+// Helpers used by the public methods below.
+impl LendingPool {
+    fn only_owner(&self) -> Result<(), LendingError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(LendingError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn only_position_owner(&self, position_id: U256) -> Result<Address, LendingError> {
+        let caller = self.vm().msg_sender();
+        let position_token = self.position_token.get();
+        let call = ICradle1155::balanceOfCall { account: caller, id: position_id };
+        let balance = self
+            .vm()
+            .static_call(&self, position_token, &call.abi_encode())
+            .ok()
+            .and_then(|output| ICradle1155::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .ok_or(LendingError::ExternalCallFailed(ExternalCallFailed { target: position_token }))?
+            ._0;
+        if balance.is_zero() {
+            return Err(LendingError::NotPositionOwner(NotPositionOwner { position_id, caller }));
+        }
+        Ok(caller)
+    }
+
+    /// Borrow index as of the current block.
+    fn current_index(&self) -> U256 {
+        let index = self.borrow_index.get();
+        let blocks = U256::from(self.vm().block_number()) - self.last_accrual_block.get();
+        index + index * self.rate_per_block.get() * blocks / U256::from(WAD)
+    }
+
+    /// Brings the borrow index up to the current block.
+    fn accrue(&mut self) {
+        let index = self.current_index();
+        self.borrow_index.set(index);
+        self.last_accrual_block.set(U256::from(self.vm().block_number()));
+    }
+
+    /// Converts scaled debt to borrow-token units, rounding up.
+    fn debt_at(scaled: U256, index: U256) -> U256 {
+        (scaled * index).div_ceil(U256::from(WAD))
+    }
+
+    fn collateral_value(&self, position_id: U256) -> U256 {
+        self.collateral.get(position_id) * self.collateral_price.get() / U256::from(WAD)
+    }
+
+    fn total_assets(&self) -> U256 {
+        self.cash.get() + Self::debt_at(self.total_scaled_debt.get(), self.current_index())
+    }
+
+    /// Fails if the position's debt exceeds its loan-to-value limit.
+    fn check_ltv(&self, position_id: U256) -> Result<(), LendingError> {
+        let debt = Self::debt_at(self.scaled_debt.get(position_id), self.borrow_index.get());
+        let max_debt = self.collateral_value(position_id) * self.ltv_bps.get() / U256::from(BPS);
+        if debt > max_debt {
+            return Err(LendingError::InsufficientCollateral(InsufficientCollateral {
+                position_id,
+                debt,
+                max_debt,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Reduces a position's debt by up to `amount`, returning the amount actually repaid.
+    fn reduce_debt(&mut self, position_id: U256, amount: U256) -> U256 {
+        let index = self.borrow_index.get();
+        let scaled = self.scaled_debt.get(position_id);
+        let debt = Self::debt_at(scaled, index);
+        let (repaid, scaled_repaid) = if amount >= debt {
+            (debt, scaled)
+        } else {
+            (amount, amount * U256::from(WAD) / index)
+        };
+        self.scaled_debt.insert(position_id, scaled - scaled_repaid);
+        self.total_scaled_debt.set(self.total_scaled_debt.get().saturating_sub(scaled_repaid));
+        self.cash.set(self.cash.get() + repaid);
+        repaid
+    }
+
+    fn pull(&mut self, token: Address, from: Address, amount: U256) -> Result<(), LendingError> {
+        let this = self.vm().contract_address();
+        self.call_token(token, IERC20::transferFromCall { from, to: this, value: amount }.abi_encode())
+    }
+
+    fn push(&mut self, token: Address, to: Address, amount: U256) -> Result<(), LendingError> {
+        self.call_token(token, IERC20::transferCall { to, value: amount }.abi_encode())
+    }
+
+    /// Calls an ERC-20 transfer method, which must return `true`.
+    fn call_token(&mut self, token: Address, calldata: Vec<u8>) -> Result<(), LendingError> {
+        match self.vm().call(&calls::context::Call::new(), token, &calldata) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(LendingError::ExternalCallFailed(ExternalCallFailed { target: token })),
+        }
+    }
+
+    /// Fails on a zero price, which would make every position with debt liquidatable and value
+    /// its collateral at nothing.
+    fn check_price(price: U256) -> Result<(), LendingError> {
+        if price.is_zero() {
+            return Err(LendingError::InvalidPrice(InvalidPrice { price }));
+        }
+        Ok(())
+    }
+
+    fn write_parameters(
+        &mut self,
+        ltv_bps: U256,
+        liquidation_threshold_bps: U256,
+        liquidation_bonus_bps: U256,
+        rate_per_block: U256,
+    ) -> Result<(), LendingError> {
+        let bps = U256::from(BPS);
+        if ltv_bps.is_zero()
+            || ltv_bps > liquidation_threshold_bps
+            || liquidation_threshold_bps >= bps
+            || liquidation_bonus_bps >= bps
+        {
+            return Err(LendingError::InvalidParameters(InvalidParameters {
+                ltv_bps,
+                liquidation_threshold_bps,
+                liquidation_bonus_bps,
+            }));
+        }
+        self.ltv_bps.set(ltv_bps);
+        self.liquidation_threshold_bps.set(liquidation_threshold_bps);
+        self.liquidation_bonus_bps.set(liquidation_bonus_bps);
+        self.rate_per_block.set(rate_per_block);
+
+        log(self.vm(), ParametersUpdated {
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+            rate_per_block,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl LendingPool {
+    /// Sets the tokens, the initial price and the risk parameters. Can only be called once.
+    /// The pool must be the owner of `position_token` so it can mint positions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        collateral_token: Address,
+        borrow_token: Address,
+        position_token: Address,
+        collateral_price: U256,
+        ltv_bps: U256,
+        liquidation_threshold_bps: U256,
+        liquidation_bonus_bps: U256,
+        rate_per_block: U256,
+    ) -> Result<(), LendingError> {
+        if self.initialized.get() {
+            return Err(LendingError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if collateral_token == borrow_token {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount: U256::ZERO }));
+        }
+        Self::check_price(collateral_price)?;
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.collateral_token.set(collateral_token);
+        self.borrow_token.set(borrow_token);
+        self.position_token.set(position_token);
+        self.collateral_price.set(collateral_price);
+        self.borrow_index.set(U256::from(WAD));
+        self.last_accrual_block.set(U256::from(self.vm().block_number()));
+        self.write_parameters(ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps, rate_per_block)?;
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Supplies `amount` of the borrow token and mints pool shares. Returns the shares minted.
+    pub fn supply(&mut self, amount: U256) -> Result<U256, LendingError> {
+        if amount.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let total_shares = self.total_shares.get();
+        let total_assets = self.total_assets();
+        let minted = if total_shares.is_zero() || total_assets.is_zero() {
+            amount
+        } else {
+            amount * total_shares / total_assets
+        };
+        if minted.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+
+        let lender = self.vm().msg_sender();
+        let token = self.borrow_token.get();
+        self.pull(token, lender, amount)?;
+        self.cash.set(self.cash.get() + amount);
+        self.total_shares.set(total_shares + minted);
+        self.shares.insert(lender, self.shares.get(lender) + minted);
+
+        log(self.vm(), Supplied {
+            lender,
+            amount,
+            shares: minted,
+        });
+        Ok(minted)
+    }
+
+    /// Burns `shares` and returns their value in the borrow token. Returns the amount paid out.
+    pub fn withdraw(&mut self, shares: U256) -> Result<U256, LendingError> {
+        let lender = self.vm().msg_sender();
+        let have = self.shares.get(lender);
+        if shares.is_zero() || shares > have {
+            return Err(LendingError::InsufficientShares(InsufficientShares { have, want: shares }));
+        }
+        self.accrue();
+        let amount = shares * self.total_assets() / self.total_shares.get();
+        let cash = self.cash.get();
+        if amount > cash {
+            return Err(LendingError::InsufficientLiquidity(InsufficientLiquidity {
+                cash,
+                requested: amount,
+            }));
+        }
+        self.shares.insert(lender, have - shares);
+        self.total_shares.set(self.total_shares.get() - shares);
+        self.cash.set(cash - amount);
+        let token = self.borrow_token.get();
+        self.push(token, lender, amount)?;
+
+        log(self.vm(), Withdrawn { lender, amount, shares });
+        Ok(amount)
+    }
+
+    /// Opens an empty position and mints its token to the caller. Returns the position ID.
+    pub fn open_position(&mut self) -> Result<U256, LendingError> {
+        let owner = self.vm().msg_sender();
+        let position_id = self.next_position_id.get() + U256::from(1);
+        self.next_position_id.set(position_id);
+
+        let position_token = self.position_token.get();
+        let call =
+            ICradle1155::mintCall { to: owner, id: position_id, amount: U256::from(1), data: Default::default() };
+        self.vm()
+            .call(&calls::context::Call::new(), position_token, &call.abi_encode())
+            .map_err(|_| LendingError::ExternalCallFailed(ExternalCallFailed { target: position_token }))?;
+
+        log(self.vm(), PositionOpened { position_id, owner });
+        Ok(position_id)
+    }
+
+    /// Adds `amount` of collateral to a position. Anyone may top up any position.
+    pub fn deposit_collateral(&mut self, position_id: U256, amount: U256) -> Result<(), LendingError> {
+        if amount.is_zero() || position_id.is_zero() || position_id > self.next_position_id.get() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let from = self.vm().msg_sender();
+        let token = self.collateral_token.get();
+        self.pull(token, from, amount)?;
+        self.collateral.insert(position_id, self.collateral.get(position_id) + amount);
+
+        log(self.vm(), CollateralDeposited {
+            position_id,
+            from,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Withdraws collateral from a position held by the caller, keeping it within its loan-to-value limit.
+    pub fn withdraw_collateral(&mut self, position_id: U256, amount: U256) -> Result<(), LendingError> {
+        let to = self.only_position_owner(position_id)?;
+        let collateral = self.collateral.get(position_id);
+        if amount.is_zero() || amount > collateral {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        self.collateral.insert(position_id, collateral - amount);
+        self.check_ltv(position_id)?;
+        let token = self.collateral_token.get();
+        self.push(token, to, amount)?;
+
+        log(self.vm(), CollateralWithdrawn {
+            position_id,
+            to,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Borrows `amount` against a position held by the caller.
+    pub fn borrow(&mut self, position_id: U256, amount: U256) -> Result<(), LendingError> {
+        let to = self.only_position_owner(position_id)?;
+        if amount.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let cash = self.cash.get();
+        if amount > cash {
+            return Err(LendingError::InsufficientLiquidity(InsufficientLiquidity {
+                cash,
+                requested: amount,
+            }));
+        }
+        self.accrue();
+        // Round the scaled debt up so borrowers never owe less than they took out
+        let scaled = (amount * U256::from(WAD)).div_ceil(self.borrow_index.get());
+        self.scaled_debt.insert(position_id, self.scaled_debt.get(position_id) + scaled);
+        self.total_scaled_debt.set(self.total_scaled_debt.get() + scaled);
+        self.check_ltv(position_id)?;
+        self.cash.set(cash - amount);
+        let token = self.borrow_token.get();
+        self.push(token, to, amount)?;
+
+        log(self.vm(), Borrowed {
+            position_id,
+            to,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Repays up to `amount` of a position's debt. Anyone may repay any position. Returns the amount repaid.
+    pub fn repay(&mut self, position_id: U256, amount: U256) -> Result<U256, LendingError> {
+        if amount.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let repaid = self.reduce_debt(position_id, amount);
+        let from = self.vm().msg_sender();
+        let token = self.borrow_token.get();
+        self.pull(token, from, repaid)?;
+
+        log(self.vm(), Repaid {
+            position_id,
+            from,
+            amount: repaid,
+        });
+        Ok(repaid)
+    }
+
+    /// Repays up to `amount` of an unhealthy position's debt and seizes collateral worth the
+    /// repayment plus the liquidation bonus. If the position holds less, seizes all of it and
+    /// repays only what it's worth less the bonus. Returns the collateral seized.
+    pub fn liquidate(&mut self, position_id: U256, amount: U256) -> Result<U256, LendingError> {
+        if amount.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.accrue();
+        let debt = Self::debt_at(self.scaled_debt.get(position_id), self.borrow_index.get());
+        let threshold = self.collateral_value(position_id) * self.liquidation_threshold_bps.get() / U256::from(BPS);
+        if debt.is_zero() || debt <= threshold {
+            return Err(LendingError::PositionHealthy(PositionHealthy { position_id }));
+        }
+
+        let collateral = self.collateral.get(position_id);
+        let bonus = U256::from(BPS) + self.liquidation_bonus_bps.get();
+        let price = self.collateral_price.get();
+        let requested = amount.min(debt);
+        let wanted = requested * U256::from(WAD) / price * bonus / U256::from(BPS);
+        // Collateral worth less than the repayment plus the bonus only pays for what it covers,
+        // leaving the rest of the debt on the position
+        let (repayment, seized) = if wanted > collateral {
+            (collateral * price / U256::from(WAD) * U256::from(BPS) / bonus, collateral)
+        } else {
+            (requested, wanted)
+        };
+        if repayment.is_zero() {
+            return Err(LendingError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let repaid = self.reduce_debt(position_id, repayment);
+        self.collateral.insert(position_id, collateral - seized);
+
+        let liquidator = self.vm().msg_sender();
+        let borrow_token = self.borrow_token.get();
+        self.pull(borrow_token, liquidator, repaid)?;
+        let collateral_token = self.collateral_token.get();
+        self.push(collateral_token, liquidator, seized)?;
+
+        log(self.vm(), Liquidated {
+            position_id,
+            liquidator,
+            repaid,
+            seized,
+        });
+        Ok(seized)
+    }
+
+    /// Sets the collateral price in borrow-token units, WAD-scaled, which can't be zero (owner only).
+    pub fn set_price(&mut self, price: U256) -> Result<(), LendingError> {
+        self.only_owner()?;
+        Self::check_price(price)?;
+        self.collateral_price.set(price);
+
+        log(self.vm(), PriceUpdated { price });
+        Ok(())
+    }
+
+    /// Updates the risk parameters and interest rate (owner only). Accrues interest at the old rate first.
+    pub fn set_parameters(
+        &mut self,
+        ltv_bps: U256,
+        liquidation_threshold_bps: U256,
+        liquidation_bonus_bps: U256,
+        rate_per_block: U256,
+    ) -> Result<(), LendingError> {
+        self.only_owner()?;
+        self.accrue();
+        self.write_parameters(ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps, rate_per_block)
+    }
+
+    /// Transfers ownership of the pool (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), LendingError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(collateral, debt)` of a position, with interest accrued to the current block.
+    pub fn position(&self, position_id: U256) -> (U256, U256) {
+        (
+            self.collateral.get(position_id),
+            Self::debt_at(self.scaled_debt.get(position_id), self.current_index()),
+        )
+    }
+
+    /// Debt over collateral value in basis points; liquidatable above the liquidation threshold.
+    pub fn debt_ratio_bps(&self, position_id: U256) -> U256 {
+        let debt = Self::debt_at(self.scaled_debt.get(position_id), self.current_index());
+        let value = self.collateral_value(position_id);
+        if debt.is_zero() {
+            return U256::ZERO;
+        }
+        if value.is_zero() {
+            return U256::MAX;
+        }
+        debt * U256::from(BPS) / value
+    }
+
+    /// Value of `account`'s shares in the borrow token.
+    pub fn balance_of_underlying(&self, account: Address) -> U256 {
+        let total_shares = self.total_shares.get();
+        if total_shares.is_zero() {
+            return U256::ZERO;
+        }
+        self.shares.get(account) * self.total_assets() / total_shares
+    }
+
+    /// Pool shares held by `account`.
+    pub fn shares_of(&self, account: Address) -> U256 {
+        self.shares.get(account)
+    }
+
+    /// Returns `(cash, total_debt, total_shares, borrow_index)`, accrued to the current block.
+    pub fn pool_state(&self) -> (U256, U256, U256, U256) {
+        let index = self.current_index();
+        (
+            self.cash.get(),
+            Self::debt_at(self.total_scaled_debt.get(), index),
+            self.total_shares.get(),
+            index,
+        )
+    }
+
+    /// Returns `(collateral_price, ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps, rate_per_block)`.
+    pub fn parameters(&self) -> (U256, U256, U256, U256, U256) {
+        (
+            self.collateral_price.get(),
+            self.ltv_bps.get(),
+            self.liquidation_threshold_bps.get(),
+            self.liquidation_bonus_bps.get(),
+            self.rate_per_block.get(),
+        )
+    }
+
+    /// Returns `(collateral_token, borrow_token, position_token)`.
+    pub fn tokens(&self) -> (Address, Address, Address) {
+        (
+            self.collateral_token.get(),
+            self.borrow_token.get(),
+            self.position_token.get(),
+        )
+    }
+
+    /// Number of positions opened.
+    pub fn total_positions(&self) -> U256 {
+        self.next_position_id.get()
+    }
+
+    /// Current owner of the pool.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
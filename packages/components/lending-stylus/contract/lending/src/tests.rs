@@ -0,0 +1,171 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const LENDER: Address = address!("00000000000000000000000000000000000011d5");
+const BORROWER: Address = address!("0000000000000000000000000000000000000b0b");
+const LIQUIDATOR: Address = address!("000000000000000000000000000000000000dead");
+const WETH: Address = address!("000000000000000000000000000000000000e7e4");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const POSITIONS: Address = address!("0000000000000000000000000000000000001155");
+
+/// `value` hundredths, WAD-scaled.
+fn wad_cents(value: u64) -> U256 {
+    U256::from(WAD / 100) * U256::from(value)
+}
+
+fn expect_pull(vm: &TestVM, token: Address, from: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to: vm.contract_address(), value: n(amount) };
+    vm.mock_call(token, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+fn expect_push(vm: &TestVM, token: Address, to: Address, amount: u64) {
+    vm.mock_call(token, IERC20::transferCall { to, value: n(amount) }.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Mocks how many of position `position_id`'s token `account` holds.
+fn mock_position(vm: &TestVM, account: Address, position_id: U256, balance: u64) {
+    let call = ICradle1155::balanceOfCall { account, id: position_id };
+    vm.mock_static_call(POSITIONS, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// A pool lending USDC against WETH at 1 USDC per WETH, with a 75% loan-to-value ratio, an 85%
+/// liquidation threshold, a 5% bonus and 0.1% interest per block, and 10,000 USDC supplied.
+fn setup() -> (TestVM, LendingPool) {
+    let (vm, mut contract) = deploy::<LendingPool>(OWNER);
+    vm.set_block_number(100);
+    let rate = U256::from(WAD / 1_000);
+    ok(contract.initialize(OWNER, WETH, USDC, POSITIONS, wad_cents(100), n(7_500), n(8_500), n(500), rate));
+
+    vm.set_sender(LENDER);
+    expect_pull(&vm, USDC, LENDER, 10_000);
+    ok(contract.supply(n(10_000)));
+    (vm, contract)
+}
+
+/// `setup`, plus `BORROWER`'s position 1 holding 1,000 WETH and owing 750 USDC.
+fn setup_borrowed() -> (TestVM, LendingPool, U256) {
+    let (vm, mut contract) = setup();
+    vm.set_sender(BORROWER);
+    let position_id = ok(contract.open_position());
+    mock_position(&vm, BORROWER, position_id, 1);
+    expect_pull(&vm, WETH, BORROWER, 1_000);
+    ok(contract.deposit_collateral(position_id, n(1_000)));
+    expect_push(&vm, USDC, BORROWER, 750);
+    ok(contract.borrow(position_id, n(750)));
+    (vm, contract, position_id)
+}
+
+#[test]
+fn collateral_must_have_a_price() {
+    let (vm, mut contract) = deploy::<LendingPool>(OWNER);
+    let result = contract.initialize(OWNER, WETH, USDC, POSITIONS, U256::ZERO, n(7_500), n(8_500), n(500), U256::ZERO);
+    assert!(matches!(result, Err(LendingError::InvalidPrice(_))));
+    ok(contract.initialize(OWNER, WETH, USDC, POSITIONS, wad_cents(100), n(7_500), n(8_500), n(500), U256::ZERO));
+
+    let result = contract.set_price(U256::ZERO);
+    assert!(matches!(result, Err(LendingError::InvalidPrice(_))));
+    ok(contract.set_price(wad_cents(80)));
+    assert_eq!(contract.parameters().0, wad_cents(80));
+    vm.set_sender(BORROWER);
+    let result = contract.set_price(wad_cents(1));
+    assert!(matches!(result, Err(LendingError::Unauthorized(e)) if e.caller == BORROWER));
+}
+
+#[test]
+fn lenders_supply_and_withdraw_for_shares() {
+    let (vm, mut contract) = setup();
+    assert_eq!(contract.shares_of(LENDER), n(10_000));
+    assert_eq!(contract.balance_of_underlying(LENDER), n(10_000));
+    let event = emitted::<Supplied>(&vm).pop().expect("supply logged");
+    assert_eq!((event.lender, event.amount, event.shares), (LENDER, n(10_000), n(10_000)));
+
+    expect_push(&vm, USDC, LENDER, 4_000);
+    assert_eq!(ok(contract.withdraw(n(4_000))), n(4_000));
+    assert_eq!(contract.pool_state().0, n(6_000));
+    let result = contract.withdraw(n(6_001));
+    assert!(matches!(result, Err(LendingError::InsufficientShares(e)) if e.have == n(6_000)));
+}
+
+#[test]
+fn supplies_fail_unless_the_tokens_move() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(BORROWER);
+    let result = contract.supply(n(500));
+    assert!(matches!(result, Err(LendingError::ExternalCallFailed(e)) if e.target == USDC));
+}
+
+#[test]
+fn borrowing_stays_within_the_loan_to_value_ratio() {
+    let (vm, mut contract, position_id) = setup_borrowed();
+    assert_eq!(contract.position(position_id), (n(1_000), n(750)));
+    assert_eq!(contract.debt_ratio_bps(position_id), n(7_500));
+    assert_eq!(contract.pool_state().0, n(9_250));
+
+    // Only the position's holder borrows against it
+    mock_position(&vm, LIQUIDATOR, position_id, 0);
+    vm.set_sender(LIQUIDATOR);
+    let result = contract.borrow(position_id, n(1));
+    assert!(matches!(result, Err(LendingError::NotPositionOwner(e)) if e.caller == LIQUIDATOR));
+
+    vm.set_sender(BORROWER);
+    let result = contract.borrow(position_id, n(1));
+    assert!(matches!(result, Err(LendingError::InsufficientCollateral(e)) if e.max_debt == n(750)));
+}
+
+#[test]
+fn interest_accrues_per_block_until_repaid() {
+    let (vm, mut contract, position_id) = setup_borrowed();
+    // 100 blocks at 0.1% each add 10%
+    vm.set_block_number(200);
+    assert_eq!(contract.position(position_id).1, n(825));
+    assert_eq!(contract.balance_of_underlying(LENDER), n(10_075));
+
+    // Repaying more than the debt only takes the debt
+    vm.set_sender(LIQUIDATOR);
+    expect_pull(&vm, USDC, LIQUIDATOR, 825);
+    assert_eq!(ok(contract.repay(position_id, n(1_000))), n(825));
+    assert_eq!(contract.position(position_id), (n(1_000), U256::ZERO));
+    assert_eq!(contract.pool_state().0, n(10_075));
+}
+
+#[test]
+fn unhealthy_positions_are_liquidated_for_a_bonus() {
+    let (vm, mut contract, position_id) = setup_borrowed();
+    vm.set_sender(LIQUIDATOR);
+    let result = contract.liquidate(position_id, n(100));
+    assert!(matches!(result, Err(LendingError::PositionHealthy(_))));
+
+    // At 0.80 the collateral is worth 800, and 750 of debt is past 85% of it
+    vm.set_sender(OWNER);
+    ok(contract.set_price(wad_cents(80)));
+    vm.set_sender(LIQUIDATOR);
+    expect_pull(&vm, USDC, LIQUIDATOR, 100);
+    expect_push(&vm, WETH, LIQUIDATOR, 131);
+    assert_eq!(ok(contract.liquidate(position_id, n(100))), n(131));
+    assert_eq!(contract.position(position_id), (n(869), n(650)));
+    let event = emitted::<Liquidated>(&vm).pop().expect("liquidation logged");
+    assert_eq!((event.liquidator, event.repaid, event.seized), (LIQUIDATOR, n(100), n(131)));
+}
+
+#[test]
+fn seizures_capped_at_the_collateral_repay_only_what_it_covers() {
+    let (vm, mut contract, position_id) = setup_borrowed();
+    vm.set_sender(OWNER);
+    ok(contract.set_price(wad_cents(50)));
+
+    // 1,000 WETH at 0.50 is worth 500 USDC, which covers 476 of debt with the 5% bonus
+    vm.set_sender(LIQUIDATOR);
+    expect_pull(&vm, USDC, LIQUIDATOR, 476);
+    expect_push(&vm, WETH, LIQUIDATOR, 1_000);
+    assert_eq!(ok(contract.liquidate(position_id, n(750))), n(1_000));
+    assert_eq!(contract.position(position_id), (U256::ZERO, n(274)));
+    let event = emitted::<Liquidated>(&vm).pop().expect("liquidation logged");
+    assert_eq!((event.repaid, event.seized), (n(476), n(1_000)));
+
+    // With nothing left to seize, nothing more can be repaid by liquidating
+    let result = contract.liquidate(position_id, n(274));
+    assert!(matches!(result, Err(LendingError::InvalidAmount(_))));
+}
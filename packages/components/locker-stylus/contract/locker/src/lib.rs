@@ -0,0 +1,489 @@
+//! Token locker for ERC-20, LP and ERC-1155 tokens
+//!
+//! Anyone can lock tokens until a timestamp, after which only the lock's owner
+//! can withdraw them. Launchpads use it to prove that liquidity can't be pulled:
+//! `locks_of_token` lists every lock of an LP token, and `locked_amount` sums what
+//! is still held.
+//!
+//! A lock's owner can push its unlock time further out, split part of it into a
+//! new lock with the same unlock time, or hand the lock to another account. No
+//! action moves the unlock time earlier.
+//!
+//! ERC-20 locks record the amount actually received, so fee-on-transfer tokens
+//! are accounted correctly.
+//!
+//! Unlock times are Unix timestamps unless `initialize` set the clock to count L2
+//! or L1 blocks before the first lock.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_arb::{
+    clock::{Clock, InvalidClockMode},
+    PrecompileCallFailed,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Locker {
+        /// Locks created so far
+        uint256 total_locks;
+        /// Locks by ID, starting at 1
+        mapping(uint256 => Lock) locks;
+        /// Active lock IDs per owner
+        mapping(address => uint256[]) owner_locks;
+        /// Position of each lock in its owner's list
+        mapping(uint256 => uint256) owner_lock_index;
+        /// Every lock ever created per token contract
+        mapping(address => uint256[]) token_locks;
+        /// Amount still locked per token contract and ID; the ID is zero for ERC-20s
+        mapping(address => mapping(uint256 => uint256)) locked;
+        /// Set once by `initialize`
+        bool initialized;
+        /// What unlock times are counted in
+        Clock clock;
+    }
+
+    pub struct Lock {
+        address owner;
+        address token;
+        /// Whether `token` is an ERC-1155
+        bool is_erc1155;
+        /// ERC-1155 token ID; zero for ERC-20s
+        uint256 token_id;
+        uint256 amount;
+        uint256 unlock_time;
+        /// Set once the tokens are withdrawn
+        bool withdrawn;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Locked(uint256 indexed lock_id, address indexed owner, address indexed token, uint256 token_id, uint256 amount, uint256 unlock_time);
+    event Extended(uint256 indexed lock_id, uint256 unlock_time);
+    event Split(uint256 indexed lock_id, uint256 indexed new_lock_id, uint256 amount);
+    event LockTransferred(uint256 indexed lock_id, address indexed from, address indexed to);
+    event Withdrawn(uint256 indexed lock_id, address indexed owner, uint256 amount);
+
+    // Caller doesn't own the lock
+    error Unauthorized(address caller);
+    // Lock ID doesn't exist or was already withdrawn
+    error InactiveLock(uint256 lock_id);
+    // Amount is zero or doesn't leave both locks non-empty
+    error InvalidAmount(uint256 amount);
+    // Unlock time is in the past or earlier than the current one
+    error InvalidUnlockTime(uint256 unlock_time);
+    // Lock hasn't reached its unlock time
+    error StillLocked(uint256 lock_id, uint256 unlock_time);
+    // New owner is the zero address
+    error InvalidOwner(address owner);
+    // A token transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+    // Clock was already set, or locks were created on the default one
+    error AlreadyInitialized();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum LockerError {
+    Unauthorized(Unauthorized),
+    InactiveLock(InactiveLock),
+    InvalidAmount(InvalidAmount),
+    InvalidUnlockTime(InvalidUnlockTime),
+    StillLocked(StillLocked),
+    InvalidOwner(InvalidOwner),
+    TransferFailed(TransferFailed),
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidClockMode(InvalidClockMode),
+    PrecompileCallFailed(PrecompileCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+/// Context of the calls to locked tokens' contracts. Made through the host, unlike `sol_interface!`
+/// calls, they build with the SDK's `reentrant` feature and are mocked under test.
+struct TokenCall;
+
+impl calls::CallContext for TokenCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when an ERC-1155 contract calls back into the locker's receiver hook
+unsafe impl calls::MutatingCallContext for TokenCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl Locker {
+    fn now(&self) -> Result<U256, LockerError> {
+        self.clock.now().map_err(LockerError::PrecompileCallFailed)
+    }
+
+    /// Fails unless the lock is active and owned by the caller; returns the caller.
+    fn only_lock_owner(&self, lock_id: U256) -> Result<Address, LockerError> {
+        let lock = self.locks.getter(lock_id);
+        if lock.owner.get().is_zero() || lock.withdrawn.get() {
+            return Err(LockerError::InactiveLock(InactiveLock { lock_id }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != lock.owner.get() {
+            return Err(LockerError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(caller)
+    }
+
+    fn erc20_balance(&self, token: Address) -> Result<U256, LockerError> {
+        let this = self.vm().contract_address();
+        let call = IERC20::balanceOfCall { account: this };
+        self.vm()
+            .static_call(&self, token, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC20::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map(|balance| balance._0)
+            .ok_or(LockerError::TransferFailed(TransferFailed {
+                token,
+                to: this,
+                amount: U256::ZERO,
+            }))
+    }
+
+    /// Calls a token contract with `calldata`.
+    fn call(&mut self, token: Address, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&TokenCall, token, calldata).map_err(Vec::from)
+    }
+
+    /// Calls an ERC-20 contract, returning whether it succeeded and returned `true`.
+    fn call_erc20(&mut self, token: Address, calldata: &[u8]) -> bool {
+        self.call(token, calldata).is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+    }
+
+    /// Stores a new lock and indexes it. Returns its ID.
+    fn create(
+        &mut self,
+        owner: Address,
+        token: Address,
+        is_erc1155: bool,
+        token_id: U256,
+        amount: U256,
+        unlock_time: U256,
+    ) -> U256 {
+        let lock_id = self.total_locks.get() + U256::from(1);
+        self.total_locks.set(lock_id);
+
+        let mut lock = self.locks.setter(lock_id);
+        lock.owner.set(owner);
+        lock.token.set(token);
+        lock.is_erc1155.set(is_erc1155);
+        lock.token_id.set(token_id);
+        lock.amount.set(amount);
+        lock.unlock_time.set(unlock_time);
+        self.add_to_owner(owner, lock_id);
+        self.token_locks.setter(token).push(lock_id);
+
+        log(self.vm(), Locked {
+            lock_id,
+            owner,
+            token,
+            token_id,
+            amount,
+            unlock_time,
+        });
+        lock_id
+    }
+
+    fn add_to_owner(&mut self, owner: Address, lock_id: U256) {
+        let mut owned = self.owner_locks.setter(owner);
+        self.owner_lock_index.insert(lock_id, U256::from(owned.len()));
+        owned.push(lock_id);
+    }
+
+    /// Removes a lock from its owner's list by swapping in the last entry.
+    fn remove_from_owner(&mut self, owner: Address, lock_id: U256) {
+        let index = self.owner_lock_index.get(lock_id).to::<usize>();
+        let mut owned = self.owner_locks.setter(owner);
+        let last_index = owned.len() - 1;
+        if index != last_index {
+            let last = owned.get(last_index).unwrap_or_default();
+            if let Some(mut slot) = owned.setter(index) {
+                slot.set(last);
+            }
+            self.owner_lock_index.insert(last, U256::from(index));
+        }
+        self.owner_locks.setter(owner).pop();
+    }
+
+    fn check_unlock_time(&self, unlock_time: U256) -> Result<(), LockerError> {
+        if unlock_time <= self.now()? {
+            return Err(LockerError::InvalidUnlockTime(InvalidUnlockTime { unlock_time }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl Locker {
+    /// Sets the clock unlock times are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1
+    /// blocks. Can only be called once, before the first lock; without it locks count seconds.
+    pub fn initialize(&mut self, clock_mode: u8) -> Result<(), LockerError> {
+        if self.initialized.get() || !self.total_locks.get().is_zero() {
+            return Err(LockerError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.clock.init(clock_mode).map_err(LockerError::InvalidClockMode)?;
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// Locks `amount` of an ERC-20 or LP token until `unlock_time`. Requires an allowance.
+    /// Returns the lock ID.
+    pub fn lock(&mut self, token: Address, amount: U256, unlock_time: U256) -> Result<U256, LockerError> {
+        if amount.is_zero() {
+            return Err(LockerError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.check_unlock_time(unlock_time)?;
+
+        let owner = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        let before = self.erc20_balance(token)?;
+        let call = IERC20::transferFromCall { from: owner, to: this, value: amount };
+        if !self.call_erc20(token, &call.abi_encode()) {
+            return Err(LockerError::TransferFailed(TransferFailed {
+                token,
+                to: this,
+                amount,
+            }));
+        }
+        let received = self.erc20_balance(token)? - before;
+        if received.is_zero() {
+            return Err(LockerError::InvalidAmount(InvalidAmount { amount: received }));
+        }
+
+        let total = self.locked.getter(token).get(U256::ZERO);
+        self.locked.setter(token).insert(U256::ZERO, total + received);
+        Ok(self.create(owner, token, false, U256::ZERO, received, unlock_time))
+    }
+
+    /// Locks `amount` of an ERC-1155 token ID until `unlock_time`. Requires `setApprovalForAll`.
+    /// Returns the lock ID.
+    pub fn lock_erc1155(
+        &mut self,
+        token: Address,
+        token_id: U256,
+        amount: U256,
+        unlock_time: U256,
+    ) -> Result<U256, LockerError> {
+        if amount.is_zero() {
+            return Err(LockerError::InvalidAmount(InvalidAmount { amount }));
+        }
+        self.check_unlock_time(unlock_time)?;
+
+        let owner = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        let call = IERC1155::safeTransferFromCall {
+            from: owner,
+            to: this,
+            id: token_id,
+            value: amount,
+            data: Default::default(),
+        };
+        self.call(token, &call.abi_encode())
+            .map_err(|_| LockerError::TransferFailed(TransferFailed {
+                token,
+                to: this,
+                amount,
+            }))?;
+
+        let total = self.locked.getter(token).get(token_id);
+        self.locked.setter(token).insert(token_id, total + amount);
+        Ok(self.create(owner, token, true, token_id, amount, unlock_time))
+    }
+
+    /// Pushes a lock's unlock time further out (lock owner only).
+    pub fn extend(&mut self, lock_id: U256, unlock_time: U256) -> Result<(), LockerError> {
+        self.only_lock_owner(lock_id)?;
+        if unlock_time <= self.locks.getter(lock_id).unlock_time.get() {
+            return Err(LockerError::InvalidUnlockTime(InvalidUnlockTime { unlock_time }));
+        }
+        self.locks.setter(lock_id).unlock_time.set(unlock_time);
+
+        log(self.vm(), Extended { lock_id, unlock_time });
+        Ok(())
+    }
+
+    /// Moves `amount` out of a lock into a new lock with the same owner and unlock time
+    /// (lock owner only). Returns the new lock ID.
+    pub fn split(&mut self, lock_id: U256, amount: U256) -> Result<U256, LockerError> {
+        let owner = self.only_lock_owner(lock_id)?;
+        let lock = self.locks.getter(lock_id);
+        let current = lock.amount.get();
+        if amount.is_zero() || amount >= current {
+            return Err(LockerError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let token = lock.token.get();
+        let is_erc1155 = lock.is_erc1155.get();
+        let token_id = lock.token_id.get();
+        let unlock_time = lock.unlock_time.get();
+        self.locks.setter(lock_id).amount.set(current - amount);
+
+        let new_lock_id = self.create(owner, token, is_erc1155, token_id, amount, unlock_time);
+        log(self.vm(), Split {
+            lock_id,
+            new_lock_id,
+            amount,
+        });
+        Ok(new_lock_id)
+    }
+
+    /// Hands a lock to `new_owner` (lock owner only).
+    pub fn transfer_lock(&mut self, lock_id: U256, new_owner: Address) -> Result<(), LockerError> {
+        let owner = self.only_lock_owner(lock_id)?;
+        if new_owner.is_zero() {
+            return Err(LockerError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.remove_from_owner(owner, lock_id);
+        self.add_to_owner(new_owner, lock_id);
+        self.locks.setter(lock_id).owner.set(new_owner);
+
+        log(self.vm(), LockTransferred {
+            lock_id,
+            from: owner,
+            to: new_owner,
+        });
+        Ok(())
+    }
+
+    /// Withdraws an unlocked lock's tokens to its owner (lock owner only).
+    pub fn withdraw(&mut self, lock_id: U256) -> Result<(), LockerError> {
+        let owner = self.only_lock_owner(lock_id)?;
+        let lock = self.locks.getter(lock_id);
+        let unlock_time = lock.unlock_time.get();
+        if self.now()? < unlock_time {
+            return Err(LockerError::StillLocked(StillLocked { lock_id, unlock_time }));
+        }
+        let token = lock.token.get();
+        let is_erc1155 = lock.is_erc1155.get();
+        let token_id = lock.token_id.get();
+        let amount = lock.amount.get();
+
+        self.locks.setter(lock_id).withdrawn.set(true);
+        self.remove_from_owner(owner, lock_id);
+        let total = self.locked.getter(token).get(token_id);
+        self.locked.setter(token).insert(token_id, total - amount);
+
+        let this = self.vm().contract_address();
+        let transferred = if is_erc1155 {
+            let call = IERC1155::safeTransferFromCall {
+                from: this,
+                to: owner,
+                id: token_id,
+                value: amount,
+                data: Default::default(),
+            };
+            self.call(token, &call.abi_encode()).is_ok()
+        } else {
+            self.call_erc20(token, &IERC20::transferCall { to: owner, value: amount }.abi_encode())
+        };
+        if !transferred {
+            return Err(LockerError::TransferFailed(TransferFailed {
+                token,
+                to: owner,
+                amount,
+            }));
+        }
+
+        log(self.vm(), Withdrawn { lock_id, owner, amount });
+        Ok(())
+    }
+
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes(ERC1155_RECEIVED.to_be_bytes())
+        } else {
+            FixedBytes([0; 4])
+        }
+    }
+
+    /// Returns `(owner, token, is_erc1155, token_id, amount, unlock_time, withdrawn)`.
+    #[allow(clippy::type_complexity)]
+    pub fn lock_info(&self, lock_id: U256) -> (Address, Address, bool, U256, U256, U256, bool) {
+        let lock = self.locks.getter(lock_id);
+        (
+            lock.owner.get(),
+            lock.token.get(),
+            lock.is_erc1155.get(),
+            lock.token_id.get(),
+            lock.amount.get(),
+            lock.unlock_time.get(),
+            lock.withdrawn.get(),
+        )
+    }
+
+    /// IDs of the locks `owner` holds and hasn't withdrawn.
+    pub fn locks_of(&self, owner: Address) -> Vec<U256> {
+        let owned = self.owner_locks.getter(owner);
+        (0..owned.len()).filter_map(|i| owned.get(i)).collect()
+    }
+
+    /// IDs of every lock created for a token contract, including withdrawn ones.
+    pub fn locks_of_token(&self, token: Address) -> Vec<U256> {
+        let locks = self.token_locks.getter(token);
+        (0..locks.len()).filter_map(|i| locks.get(i)).collect()
+    }
+
+    /// Amount still locked for a token contract and ID; use zero as the ID for ERC-20s.
+    pub fn locked_amount(&self, token: Address, token_id: U256) -> U256 {
+        self.locked.getter(token).get(token_id)
+    }
+
+    /// Number of locks created.
+    pub fn total_locks(&self) -> U256 {
+        self.total_locks.get()
+    }
+
+    /// What unlock times are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1 blocks.
+    pub fn clock_mode(&self) -> u8 {
+        self.clock.mode()
+    }
+
+    /// Current time on the locker's clock, which unlock times are compared against.
+    pub fn clock(&self) -> Result<U256, LockerError> {
+        self.now()
+    }
+}
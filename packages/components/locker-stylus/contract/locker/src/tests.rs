@@ -0,0 +1,106 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const LP: Address = address!("00000000000000000000000000000000000020c0");
+const ITEMS: Address = address!("0000000000000000000000000000000000001155");
+
+/// The transfer of `amount` of `ITEMS` token 7 between `from` and `to`.
+fn item_transfer(from: Address, to: Address, amount: u64) -> Vec<u8> {
+    IERC1155::safeTransferFromCall { from, to, id: n(7), value: n(amount), data: Default::default() }.abi_encode()
+}
+
+/// A locker counting seconds at time 1,000, with 100 of Alice's `ITEMS` token 7 locked until 2,000.
+fn setup() -> (TestVM, Locker) {
+    let (vm, mut contract) = deploy::<Locker>(ALICE);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(0));
+    vm.mock_call(ITEMS, item_transfer(ALICE, vm.contract_address(), 100), Ok(Vec::new()));
+    assert_eq!(ok(contract.lock_erc1155(ITEMS, n(7), n(100), n(2_000))), n(1));
+    (vm, contract)
+}
+
+#[test]
+fn locks_split_move_and_withdraw_once_unlocked() {
+    let (vm, mut contract) = setup();
+    assert_eq!(contract.lock_info(n(1)), (ALICE, ITEMS, true, n(7), n(100), n(2_000), false));
+    assert_eq!(contract.locked_amount(ITEMS, n(7)), n(100));
+
+    // Splits keep the unlock time, which only moves out
+    ok(contract.extend(n(1), n(3_000)));
+    assert_eq!(ok(contract.split(n(1), n(40))), n(2));
+    assert_eq!(contract.lock_info(n(2)), (ALICE, ITEMS, true, n(7), n(40), n(3_000), false));
+    let split = &emitted::<Split>(&vm)[0];
+    assert_eq!((split.lock_id, split.new_lock_id, split.amount), (n(1), n(2), n(40)));
+
+    ok(contract.transfer_lock(n(1), BOB));
+    assert_eq!((contract.locks_of(ALICE), contract.locks_of(BOB)), (vec![n(2)], vec![n(1)]));
+    assert_eq!(contract.locks_of_token(ITEMS), vec![n(1), n(2)]);
+
+    vm.set_block_timestamp(3_000);
+    vm.set_sender(BOB);
+    vm.mock_call(ITEMS, item_transfer(vm.contract_address(), BOB, 60), Ok(Vec::new()));
+    ok(contract.withdraw(n(1)));
+    assert_eq!(contract.locked_amount(ITEMS, n(7)), n(40));
+    assert!(contract.locks_of(BOB).is_empty() && contract.lock_info(n(1)).6);
+    let withdrawn = &emitted::<Withdrawn>(&vm)[0];
+    assert_eq!((withdrawn.lock_id, withdrawn.owner, withdrawn.amount), (n(1), BOB, n(60)));
+    assert!(matches!(contract.withdraw(n(1)), Err(LockerError::InactiveLock(e)) if e.lock_id == n(1)));
+}
+
+#[test]
+fn only_the_owner_acts_and_never_before_the_unlock() {
+    let (vm, mut contract) = setup();
+    let result = contract.withdraw(n(1));
+    assert!(matches!(result, Err(LockerError::StillLocked(e)) if e.unlock_time == n(2_000)));
+    let result = contract.extend(n(1), n(2_000));
+    assert!(matches!(result, Err(LockerError::InvalidUnlockTime(e)) if e.unlock_time == n(2_000)));
+    assert!(matches!(contract.split(n(1), n(100)), Err(LockerError::InvalidAmount(e)) if e.amount == n(100)));
+    let result = contract.transfer_lock(n(1), Address::ZERO);
+    assert!(matches!(result, Err(LockerError::InvalidOwner(_))));
+    let result = contract.lock_erc1155(ITEMS, n(7), n(1), n(1_000));
+    assert!(matches!(result, Err(LockerError::InvalidUnlockTime(e)) if e.unlock_time == n(1_000)));
+    assert!(matches!(contract.initialize(1), Err(LockerError::AlreadyInitialized(_))));
+
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(BOB);
+    assert!(matches!(contract.withdraw(n(1)), Err(LockerError::Unauthorized(e)) if e.caller == BOB));
+    assert!(matches!(contract.withdraw(n(2)), Err(LockerError::InactiveLock(_))));
+
+    // Only the locker's own transfers are accepted
+    let this = vm.contract_address();
+    let accepted = contract.on_erc1155_received(this, ALICE, n(7), n(100), Vec::new().into());
+    assert_eq!(accepted, FixedBytes(ERC1155_RECEIVED.to_be_bytes()));
+    let refused = contract.on_erc1155_received(BOB, BOB, n(7), n(1), Vec::new().into());
+    assert_eq!(refused, FixedBytes([0; 4]));
+}
+
+#[test]
+fn erc20_locks_need_the_tokens_to_arrive() {
+    let (vm, mut contract) = setup();
+    let this = vm.contract_address();
+    let balance = IERC20::balanceOfCall { account: this };
+    vm.mock_static_call(LP, balance.abi_encode(), Ok(n(500).abi_encode()));
+    let result = contract.lock(LP, n(100), n(2_000));
+    assert!(matches!(result, Err(LockerError::TransferFailed(e)) if e.token == LP && e.amount == n(100)));
+
+    // The mocked balance can't change between the two reads, so nothing counts as received
+    let pull = IERC20::transferFromCall { from: ALICE, to: this, value: n(100) };
+    vm.mock_call(LP, pull.abi_encode(), Ok(true.abi_encode()));
+    let result = contract.lock(LP, n(100), n(2_000));
+    assert!(matches!(result, Err(LockerError::InvalidAmount(e)) if e.amount.is_zero()));
+    assert!(matches!(contract.lock(LP, U256::ZERO, n(2_000)), Err(LockerError::InvalidAmount(_))));
+    assert_eq!(contract.total_locks(), n(1));
+}
+
+#[test]
+fn a_token_that_wont_move_fails_the_withdrawal() {
+    let (vm, mut contract) = setup();
+    vm.set_block_timestamp(2_000);
+    vm.mock_call(ITEMS, item_transfer(vm.contract_address(), ALICE, 100), Err(b"paused".to_vec()));
+    let result = contract.withdraw(n(1));
+    assert!(matches!(result, Err(LockerError::TransferFailed(e)) if e.to == ALICE && e.amount == n(100)));
+}
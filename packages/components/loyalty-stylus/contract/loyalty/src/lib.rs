@@ -0,0 +1,294 @@
+//! Loyalty points
+//!
+//! Members earn points from merchants, addresses the admin grants `MERCHANT_ROLE`, and spend
+//! them with merchants too: a merchant awards points for a purchase and consumes them when the
+//! member redeems a reward, each with a reference to the sale or reward in its own records. The
+//! admin caps the points each merchant can ever award, so a compromised merchant key can't print
+//! points without limit, and raises the cap as the merchant's allowance grows.
+//!
+//! Points are a balance in this contract with the read side of ERC-20, so wallets show them, but
+//! they can't be transferred. A member's points expire `expiryPeriod` seconds after they last
+//! earned any, as with airline miles; expired points read as zero at once and anyone can sweep
+//! them out of the total supply.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::sol;
+use cradle_access_control::{
+    only_role, AccessControl, AccessControlBadConfirmation, AccessControlError, AccessControlMemberOutOfBounds,
+    AccessControlUnauthorizedAccount, DEFAULT_ADMIN_ROLE,
+};
+use stylus_sdk::prelude::*;
+
+/// Role allowed to award and redeem points, `keccak256("MERCHANT_ROLE")`
+pub const MERCHANT_ROLE: B256 = b256!("3c4a2d89ed8b4cf4347fec87df1c38410f8fc538bf9fd64c10f2717bc0feff36");
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Loyalty {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Grants `MERCHANT_ROLE`, sets the caps and the expiry period
+        #[borrow]
+        AccessControl access_control;
+        string name;
+        string symbol;
+        /// Seconds after an account last earned points that they expire; zero if they don't
+        uint256 expiry_period;
+        /// Points held, including expired points not yet swept
+        uint256 total_supply;
+        /// Points by account, including expired points not yet swept
+        mapping(address => uint256) balances;
+        /// Time each account last earned points
+        mapping(address => uint256) last_earned;
+        /// Most points each merchant can award in total
+        mapping(address => uint256) caps;
+        /// Points each merchant has awarded so far
+        mapping(address => uint256) issued;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event PointsAwarded(address indexed account, address indexed merchant, uint256 amount, bytes32 reference);
+    event PointsRedeemed(address indexed account, address indexed merchant, uint256 amount, bytes32 reference);
+    event PointsExpired(address indexed account, uint256 amount);
+    event MerchantCapSet(address indexed merchant, uint256 cap);
+    event ExpiryPeriodSet(uint256 period);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Points can't be transferred
+    error NonTransferable();
+    // Awarding or redeeming no points
+    error ZeroAmount();
+    // The award would take the merchant past its cap
+    error CapExceeded(address merchant, uint256 cap, uint256 issued);
+    // The account has fewer unexpired points than the redemption
+    error InsufficientPoints(address account, uint256 balance, uint256 needed);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum LoyaltyError {
+    AlreadyInitialized(AlreadyInitialized),
+    NonTransferable(NonTransferable),
+    ZeroAmount(ZeroAmount),
+    CapExceeded(CapExceeded),
+    InsufficientPoints(InsufficientPoints),
+    AccessControlUnauthorizedAccount(AccessControlUnauthorizedAccount),
+    AccessControlBadConfirmation(AccessControlBadConfirmation),
+    AccessControlMemberOutOfBounds(AccessControlMemberOutOfBounds),
+}
+
+impl From<AccessControlError> for LoyaltyError {
+    fn from(error: AccessControlError) -> Self {
+        match error {
+            AccessControlError::UnauthorizedAccount(e) => LoyaltyError::AccessControlUnauthorizedAccount(e),
+            AccessControlError::BadConfirmation(e) => LoyaltyError::AccessControlBadConfirmation(e),
+            AccessControlError::MemberOutOfBounds(e) => LoyaltyError::AccessControlMemberOutOfBounds(e),
+        }
+    }
+}
+
+#[public]
+#[inherit(AccessControl)]
+impl Loyalty {
+    /// Sets the admin, the points' name and symbol and the expiry period. Can only be called
+    /// once.
+    pub fn initialize(
+        &mut self,
+        admin: Address,
+        name: String,
+        symbol: String,
+        expiry_period: U256,
+    ) -> Result<(), LoyaltyError> {
+        if self.initialized.get() {
+            return Err(LoyaltyError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, admin);
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.expiry_period.set(expiry_period);
+        Ok(())
+    }
+
+    /// Awards `amount` points to `account` (merchants only), within the caller's cap.
+    /// `reference` identifies the purchase in the merchant's records.
+    pub fn award(&mut self, account: Address, amount: U256, reference: B256) -> Result<(), LoyaltyError> {
+        only_role!(self.access_control, MERCHANT_ROLE);
+        if amount.is_zero() {
+            return Err(LoyaltyError::ZeroAmount(ZeroAmount {}));
+        }
+        let merchant = self.vm().msg_sender();
+        let (cap, issued) = (self.caps.get(merchant), self.issued.get(merchant));
+        if amount > cap.saturating_sub(issued) {
+            return Err(LoyaltyError::CapExceeded(CapExceeded { merchant, cap, issued }));
+        }
+        self.issued.insert(merchant, issued + amount);
+
+        // Earning points doesn't bring expired ones back
+        self.expire(account);
+        let balance = self.balances.get(account);
+        self.balances.insert(account, balance + amount);
+        let total_supply = self.total_supply.get();
+        self.total_supply.set(total_supply + amount);
+        self.last_earned.insert(account, U256::from(self.vm().block_timestamp()));
+
+        log(self.vm(), Transfer { from: Address::ZERO, to: account, value: amount });
+        log(self.vm(), PointsAwarded { account, merchant, amount, reference });
+        Ok(())
+    }
+
+    /// Consumes `amount` of `account`'s unexpired points for a reward (merchants only).
+    /// `reference` identifies the reward in the merchant's records.
+    pub fn redeem(&mut self, account: Address, amount: U256, reference: B256) -> Result<(), LoyaltyError> {
+        only_role!(self.access_control, MERCHANT_ROLE);
+        if amount.is_zero() {
+            return Err(LoyaltyError::ZeroAmount(ZeroAmount {}));
+        }
+        // Expired points read as zero, so they can't pay for a reward
+        let balance = self.balance_of(account);
+        if balance < amount {
+            return Err(LoyaltyError::InsufficientPoints(InsufficientPoints { account, balance, needed: amount }));
+        }
+        self.balances.insert(account, balance - amount);
+        let total_supply = self.total_supply.get();
+        self.total_supply.set(total_supply - amount);
+
+        let merchant = self.vm().msg_sender();
+        log(self.vm(), Transfer { from: account, to: Address::ZERO, value: amount });
+        log(self.vm(), PointsRedeemed { account, merchant, amount, reference });
+        Ok(())
+    }
+
+    /// Sets the most points `merchant` can award in total (admin only). A cap below what the
+    /// merchant already awarded stops its awards.
+    pub fn set_cap(&mut self, merchant: Address, cap: U256) -> Result<(), LoyaltyError> {
+        only_role!(self.access_control, DEFAULT_ADMIN_ROLE);
+        self.caps.insert(merchant, cap);
+        log(self.vm(), MerchantCapSet { merchant, cap });
+        Ok(())
+    }
+
+    /// Sets the seconds after an account last earned points that they expire, zero for never
+    /// (admin only). The new period applies to points already held.
+    pub fn set_expiry_period(&mut self, period: U256) -> Result<(), LoyaltyError> {
+        only_role!(self.access_control, DEFAULT_ADMIN_ROLE);
+        self.expiry_period.set(period);
+        log(self.vm(), ExpiryPeriodSet { period });
+        Ok(())
+    }
+
+    /// Removes the expired points of `accounts` from the total supply and returns how many there
+    /// were. Anyone can sweep any account.
+    pub fn sweep(&mut self, accounts: Vec<Address>) -> U256 {
+        accounts.into_iter().fold(U256::ZERO, |swept, account| swept + self.expire(account))
+    }
+
+    /// Reverts: points can't be transferred.
+    pub fn transfer(&mut self, _to: Address, _value: U256) -> Result<bool, LoyaltyError> {
+        Err(LoyaltyError::NonTransferable(NonTransferable {}))
+    }
+
+    /// Reverts: points can't be transferred.
+    pub fn transfer_from(&mut self, _from: Address, _to: Address, _value: U256) -> Result<bool, LoyaltyError> {
+        Err(LoyaltyError::NonTransferable(NonTransferable {}))
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    /// Points are whole numbers.
+    pub fn decimals(&self) -> u8 {
+        0
+    }
+
+    /// Points held, including expired points not yet swept.
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    /// `account`'s unexpired points.
+    pub fn balance_of(&self, account: Address) -> U256 {
+        if self.is_expired(account) {
+            return U256::ZERO;
+        }
+        self.balances.get(account)
+    }
+
+    /// Time `account`'s points expire, unless it earns more first; zero if they don't expire.
+    pub fn expires_at(&self, account: Address) -> U256 {
+        let period = self.expiry_period.get();
+        if period.is_zero() {
+            return U256::ZERO;
+        }
+        self.last_earned.get(account).saturating_add(period)
+    }
+
+    /// Seconds after an account last earned points that they expire, zero for never.
+    pub fn expiry_period(&self) -> U256 {
+        self.expiry_period.get()
+    }
+
+    /// Most points `merchant` can award in total.
+    pub fn cap_of(&self, merchant: Address) -> U256 {
+        self.caps.get(merchant)
+    }
+
+    /// Points `merchant` has awarded so far.
+    pub fn issued_by(&self, merchant: Address) -> U256 {
+        self.issued.get(merchant)
+    }
+
+    /// Points `merchant` can still award.
+    pub fn remaining_cap(&self, merchant: Address) -> U256 {
+        self.caps.get(merchant).saturating_sub(self.issued.get(merchant))
+    }
+
+    pub fn merchant_role(&self) -> B256 {
+        MERCHANT_ROLE
+    }
+}
+
+// Helpers used by the public methods above.
+impl Loyalty {
+    /// Whether `account` holds points that have expired.
+    fn is_expired(&self, account: Address) -> bool {
+        let expires_at = self.expires_at(account);
+        !expires_at.is_zero()
+            && U256::from(self.vm().block_timestamp()) >= expires_at
+            && !self.balances.get(account).is_zero()
+    }
+
+    /// Removes `account`'s points if they have expired, returning how many there were.
+    fn expire(&mut self, account: Address) -> U256 {
+        if !self.is_expired(account) {
+            return U256::ZERO;
+        }
+        let amount = self.balances.get(account);
+        self.balances.insert(account, U256::ZERO);
+        let total_supply = self.total_supply.get();
+        self.total_supply.set(total_supply - amount);
+
+        log(self.vm(), Transfer { from: account, to: Address::ZERO, value: amount });
+        log(self.vm(), PointsExpired { account, amount });
+        amount
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ADMIN: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const CAFE: Address = address!("000000000000000000000000000000000000caf3");
+const EXPIRY: u64 = 1_000;
+
+/// A program administered by `ADMIN` whose points expire `EXPIRY` seconds after they're earned,
+/// with `CAFE` a merchant capped at 500 points.
+fn setup() -> (TestVM, Loyalty) {
+    let (vm, mut contract) = deploy::<Loyalty>(ADMIN);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(ADMIN, "Cafe Points".into(), "BEAN".into(), n(EXPIRY)));
+    ok(contract.access_control.grant_role(MERCHANT_ROLE, CAFE).map_err(LoyaltyError::from));
+    ok(contract.set_cap(CAFE, n(500)));
+    (vm, contract)
+}
+
+#[test]
+fn merchants_award_points_within_their_cap() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(CAFE);
+    ok(contract.award(ALICE, n(300), B256::repeat_byte(1)));
+    assert_eq!((contract.balance_of(ALICE), contract.total_supply()), (n(300), n(300)));
+    assert_eq!((contract.issued_by(CAFE), contract.remaining_cap(CAFE)), (n(300), n(200)));
+    assert_eq!(emitted::<PointsAwarded>(&vm)[0].reference, B256::repeat_byte(1));
+    assert_eq!(emitted::<Transfer>(&vm)[0].from, Address::ZERO);
+
+    let result = contract.award(BOB, n(201), B256::ZERO);
+    assert!(matches!(result, Err(LoyaltyError::CapExceeded(e)) if e.cap == n(500) && e.issued == n(300)));
+    // Redeeming doesn't give a merchant its cap back
+    ok(contract.redeem(ALICE, n(100), B256::ZERO));
+    assert!(matches!(contract.award(BOB, n(201), B256::ZERO), Err(LoyaltyError::CapExceeded(_))));
+
+    vm.set_sender(BOB);
+    let result = contract.award(BOB, n(1), B256::ZERO);
+    assert!(matches!(result, Err(LoyaltyError::AccessControlUnauthorizedAccount(e)) if e.account == BOB));
+    assert!(matches!(contract.set_cap(BOB, n(1)), Err(LoyaltyError::AccessControlUnauthorizedAccount(_))));
+}
+
+#[test]
+fn redemptions_consume_points_which_never_transfer() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(CAFE);
+    ok(contract.award(ALICE, n(100), B256::ZERO));
+    let result = contract.redeem(ALICE, n(101), B256::ZERO);
+    assert!(matches!(result, Err(LoyaltyError::InsufficientPoints(e)) if e.balance == n(100)));
+    ok(contract.redeem(ALICE, n(60), B256::repeat_byte(7)));
+    assert_eq!((contract.balance_of(ALICE), contract.total_supply()), (n(40), n(40)));
+    assert_eq!(emitted::<PointsRedeemed>(&vm)[0].amount, n(60));
+    assert!(matches!(contract.redeem(ALICE, U256::ZERO, B256::ZERO), Err(LoyaltyError::ZeroAmount(_))));
+
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.transfer(BOB, n(1)), Err(LoyaltyError::NonTransferable(_))));
+    assert!(matches!(contract.transfer_from(ALICE, BOB, n(1)), Err(LoyaltyError::NonTransferable(_))));
+    assert_eq!((contract.name(), contract.decimals()), ("Cafe Points".into(), 0));
+}
+
+#[test]
+fn points_expire_after_the_last_award_and_are_swept() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(CAFE);
+    ok(contract.award(ALICE, n(100), B256::ZERO));
+    ok(contract.award(BOB, n(50), B256::ZERO));
+    assert_eq!(contract.expires_at(ALICE), n(1_000 + EXPIRY));
+
+    // Earning again pushes the expiry back for the whole balance
+    vm.set_block_timestamp(1_500);
+    ok(contract.award(ALICE, n(10), B256::ZERO));
+    vm.set_block_timestamp(2_000);
+    assert_eq!((contract.balance_of(ALICE), contract.balance_of(BOB)), (n(110), U256::ZERO));
+    assert_eq!(contract.total_supply(), n(160));
+    let result = contract.redeem(BOB, n(1), B256::ZERO);
+    assert!(matches!(result, Err(LoyaltyError::InsufficientPoints(e)) if e.balance.is_zero()));
+
+    vm.set_sender(ALICE);
+    assert_eq!(contract.sweep(vec![ALICE, BOB]), n(50));
+    assert_eq!(contract.total_supply(), n(110));
+    assert_eq!(emitted::<PointsExpired>(&vm)[0].account, BOB);
+    assert_eq!(contract.sweep(vec![BOB]), U256::ZERO);
+
+    // Points earned after expiring start afresh
+    vm.set_sender(CAFE);
+    ok(contract.award(BOB, n(5), B256::ZERO));
+    assert_eq!(contract.balance_of(BOB), n(5));
+    vm.set_sender(ADMIN);
+    ok(contract.set_expiry_period(U256::ZERO));
+    vm.set_block_timestamp(1_000_000);
+    assert_eq!((contract.balance_of(ALICE), contract.expires_at(ALICE)), (n(110), U256::ZERO));
+}
@@ -0,0 +1,25 @@
+use std::{env, fs, path::Path};
+
+fn main() {
+    emit_deployer();
+}
+
+/// Writes `DEPLOYER`, the account `CRADLE_DEPLOYER` names, which alone may call `initialize`.
+fn emit_deployer() {
+    println!("cargo:rerun-if-env-changed=CRADLE_DEPLOYER");
+    let deployer = match env::var("CRADLE_DEPLOYER") {
+        Ok(deployer) if !deployer.is_empty() => {
+            let hex = deployer.strip_prefix("0x").unwrap_or(&deployer);
+            assert!(
+                hex.len() == 40 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()),
+                "CRADLE_DEPLOYER must be an address, not {deployer:?}"
+            );
+            let bytes: Vec<String> = (0..40).step_by(2).map(|at| format!("0x{}", &hex[at..at + 2])).collect();
+            format!("Some(Address::new([{}]))", bytes.join(", "))
+        }
+        _ => "None".to_string(),
+    };
+    let code = format!("const DEPLOYER: Option<Address> = {deployer};\n");
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(Path::new(&out).join("deployer.rs"), code).expect("failed to write deployer.rs");
+}
@@ -0,0 +1,544 @@
+//! Fixed-price marketplace for ERC-1155 and ERC-721 tokens
+//!
+//! Sellers list an amount of a token they hold at a per-unit price, paid either
+//! in ETH or in an ERC-20. Listings are non-custodial: the seller keeps the tokens
+//! and approves the marketplace as an operator, and `buy` moves the tokens and the
+//! payment in a single call.
+//!
+//! Every sale pays, in order, the protocol fee (in basis points), the ERC-2981
+//! royalty reported by the token contract (if it supports the interface), and
+//! the remainder to the seller.
+//!
+//! The owner can point the marketplace at a `pause-registry-stylus` deployment,
+//! whose switch then stops new listings and sales while cancellations stay open.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_pausable::{registry_pauses, EnforcedPause};
+use stylus_sdk::prelude::*;
+
+/// Upper bound for the protocol fee, 10%.
+const MAX_PROTOCOL_FEE_BPS: u64 = 1_000;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+const IERC1155_INTERFACE_ID: u32 = 0xd9b67a26;
+const IERC721_INTERFACE_ID: u32 = 0x80ac58cd;
+const IERC2981_INTERFACE_ID: u32 = 0x2a55205a;
+
+// `DEPLOYER`, the only account that may call `initialize` if `CRADLE_DEPLOYER` was set at build
+// time, so no one can initialize a fresh deployment with themselves as owner before its deployer
+include!(concat!(env!("OUT_DIR"), "/deployer.rs"));
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Marketplace {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Can update the protocol fee and cancel any listing
+        address owner;
+        /// Receives the protocol fee of every sale
+        address fee_recipient;
+        /// Protocol fee in basis points
+        uint256 protocol_fee_bps;
+        /// Id assigned to the next listing
+        uint256 next_listing_id;
+        /// Listing id to listing
+        mapping(uint256 => Listing) listings;
+        /// `pause-registry-stylus` deployment that can pause listing and buying, zero for none
+        address pause_registry;
+    }
+
+    pub struct Listing {
+        address seller;
+        address token_contract;
+        uint256 token_id;
+        /// Units still available; zero once sold out or cancelled
+        uint256 amount;
+        /// ERC-20 used for payment, or the zero address for ETH
+        address payment_token;
+        /// Price per unit
+        uint256 price;
+        bool is_erc1155;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Listed(uint256 indexed listing_id, address indexed seller, address indexed token_contract, uint256 token_id, uint256 amount, address payment_token, uint256 price);
+    event Sale(uint256 indexed listing_id, address indexed buyer, uint256 amount, uint256 total_price, uint256 protocol_fee, address royalty_receiver, uint256 royalty_amount);
+    event ListingCancelled(uint256 indexed listing_id);
+    event ProtocolFeeUpdated(address fee_recipient, uint256 fee_bps);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event PauseRegistrySet(address registry);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // The contract was built for `deployer` to initialize
+    error UnauthorizedInitializer(address caller, address deployer);
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Protocol fee is above the allowed maximum, or nonzero without a recipient
+    error InvalidFee(uint256 fee_bps);
+    // Amount or price is zero, or does not fit the token standard
+    error InvalidAmount(uint256 amount);
+    // Token contract implements neither ERC-1155 nor ERC-721
+    error UnsupportedToken(address token_contract);
+    // Seller does not hold the listed tokens or has not approved the marketplace
+    error NotApproved(address seller, address token_contract, uint256 token_id);
+    // Listing is sold out, cancelled or was never created
+    error ListingNotActive(uint256 listing_id);
+    // Buyer asked for more units than the listing has left
+    error InsufficientListingAmount(uint256 listing_id, uint256 available, uint256 requested);
+    // Attached ETH does not match the price
+    error IncorrectPayment(uint256 expected, uint256 received);
+    // Payment to a fee recipient, royalty receiver or seller failed
+    error PaymentFailed(address payment_token, address to, uint256 amount);
+    // Moving the listed tokens to the buyer failed
+    error TokenTransferFailed(address token_contract, uint256 token_id);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum MarketplaceError {
+    AlreadyInitialized(AlreadyInitialized),
+    UnauthorizedInitializer(UnauthorizedInitializer),
+    Unauthorized(Unauthorized),
+    InvalidFee(InvalidFee),
+    InvalidAmount(InvalidAmount),
+    UnsupportedToken(UnsupportedToken),
+    NotApproved(NotApproved),
+    ListingNotActive(ListingNotActive),
+    InsufficientListingAmount(InsufficientListingAmount),
+    IncorrectPayment(IncorrectPayment),
+    PaymentFailed(PaymentFailed),
+    TokenTransferFailed(TokenTransferFailed),
+    EnforcedPause(EnforcedPause),
+}
+
+// External interfaces
+sol! {
+    interface IERC165 {
+        function supportsInterface(bytes4 interface_id) external view returns (bool);
+    }
+
+    interface IERC1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function isApprovedForAll(address account, address operator) external view returns (bool);
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    interface IERC721 {
+        function ownerOf(uint256 token_id) external view returns (address);
+        function getApproved(uint256 token_id) external view returns (address);
+        function isApprovedForAll(address owner, address operator) external view returns (bool);
+        function safeTransferFrom(address from, address to, uint256 token_id) external;
+    }
+
+    interface IERC2981 {
+        function royaltyInfo(uint256 token_id, uint256 sale_price) external view returns (address, uint256);
+    }
+
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Fails unless `caller` is `deployer`, when the contract was built with one.
+fn check_initializer(deployer: Option<Address>, caller: Address) -> Result<(), MarketplaceError> {
+    match deployer {
+        Some(deployer) if caller != deployer => {
+            Err(MarketplaceError::UnauthorizedInitializer(UnauthorizedInitializer { caller, deployer }))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Helpers used by the public methods below.
+impl Marketplace {
+    fn only_owner(&self) -> Result<(), MarketplaceError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(MarketplaceError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Fails if the fee is above the maximum, or nonzero with no one to receive it, which would
+    /// burn ETH fees and fail ERC-20 sales.
+    fn check_protocol_fee(fee_recipient: Address, fee_bps: U256) -> Result<(), MarketplaceError> {
+        if fee_bps > U256::from(MAX_PROTOCOL_FEE_BPS)
+            || (fee_recipient.is_zero() && !fee_bps.is_zero())
+        {
+            return Err(MarketplaceError::InvalidFee(InvalidFee { fee_bps }));
+        }
+        Ok(())
+    }
+
+    /// Fails while the pause registry pauses the marketplace.
+    fn when_not_paused(&self) -> Result<(), MarketplaceError> {
+        if registry_pauses(self.vm(), self.pause_registry.get(), self.vm().contract_address()) {
+            return Err(MarketplaceError::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    fn supports(&self, token_contract: Address, interface_id: u32) -> bool {
+        let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+        self.view(token_contract, call).is_some_and(|supported| supported._0)
+    }
+
+    /// Calls a view of `target` and decodes what it returns, or `None` if it reverts.
+    fn view<C: SolCall>(&self, target: Address, call: C) -> Option<C::Return> {
+        self.vm()
+            .static_call(&self, target, &call.abi_encode())
+            .ok()
+            .and_then(|output| C::abi_decode_returns(&output, true).ok())
+    }
+
+    /// Checks that `seller` holds `amount` of the token and has approved the marketplace.
+    /// Returns whether the token is an ERC-1155.
+    fn check_listable(
+        &self,
+        seller: Address,
+        token_contract: Address,
+        token_id: U256,
+        amount: U256,
+    ) -> Result<bool, MarketplaceError> {
+        let this = self.vm().contract_address();
+        let not_approved = MarketplaceError::NotApproved(NotApproved {
+            seller,
+            token_contract,
+            token_id,
+        });
+
+        if self.supports(token_contract, IERC1155_INTERFACE_ID) {
+            let balance = self
+                .view(token_contract, IERC1155::balanceOfCall { account: seller, id: token_id })
+                .map_or(U256::ZERO, |balance| balance._0);
+            let approved = self
+                .view(token_contract, IERC1155::isApprovedForAllCall { account: seller, operator: this })
+                .is_some_and(|approved| approved._0);
+            if balance < amount || !approved {
+                return Err(not_approved);
+            }
+            return Ok(true);
+        }
+
+        if self.supports(token_contract, IERC721_INTERFACE_ID) {
+            if amount != U256::from(1) {
+                return Err(MarketplaceError::InvalidAmount(InvalidAmount { amount }));
+            }
+            let owner = self
+                .view(token_contract, IERC721::ownerOfCall { token_id })
+                .map_or(Address::ZERO, |owner| owner._0);
+            let approved = self
+                .view(token_contract, IERC721::isApprovedForAllCall { owner: seller, operator: this })
+                .is_some_and(|approved| approved._0)
+                || self
+                    .view(token_contract, IERC721::getApprovedCall { token_id })
+                    .is_some_and(|approved| approved._0 == this);
+            if owner != seller || !approved {
+                return Err(not_approved);
+            }
+            return Ok(false);
+        }
+
+        Err(MarketplaceError::UnsupportedToken(UnsupportedToken { token_contract }))
+    }
+
+    /// Returns the ERC-2981 royalty for a sale, capped at `max_amount`.
+    /// Tokens that don't implement ERC-2981, or revert when asked, pay no royalty.
+    fn royalty_for(
+        &self,
+        token_contract: Address,
+        token_id: U256,
+        sale_price: U256,
+        max_amount: U256,
+    ) -> (Address, U256) {
+        if !self.supports(token_contract, IERC2981_INTERFACE_ID) {
+            return (Address::ZERO, U256::ZERO);
+        }
+        match self.view(token_contract, IERC2981::royaltyInfoCall { token_id, sale_price }) {
+            Some(royalty) if !royalty._0.is_zero() => (royalty._0, royalty._1.min(max_amount)),
+            _ => (Address::ZERO, U256::ZERO),
+        }
+    }
+
+    /// Pays `amount` of `payment_token` (or ETH) from `buyer` to `to`.
+    fn pay(
+        &mut self,
+        payment_token: Address,
+        buyer: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), MarketplaceError> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let failed = MarketplaceError::PaymentFailed(PaymentFailed {
+            payment_token,
+            to,
+            amount,
+        });
+        if payment_token.is_zero() {
+            return self.vm().transfer_eth(to, amount).map_err(|_| failed);
+        }
+        let calldata = IERC20::transferFromCall { from: buyer, to, value: amount }.abi_encode();
+        match self.vm().call(&calls::context::Call::new(), payment_token, &calldata) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(failed),
+        }
+    }
+}
+
+#[public]
+impl Marketplace {
+    /// Sets the owner and the protocol fee. Can only be called once, and only by the deployer if the
+    /// contract was built with `CRADLE_DEPLOYER`.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        fee_recipient: Address,
+        fee_bps: U256,
+    ) -> Result<(), MarketplaceError> {
+        if self.initialized.get() {
+            return Err(MarketplaceError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        check_initializer(DEPLOYER, self.vm().msg_sender())?;
+        Self::check_protocol_fee(fee_recipient, fee_bps)?;
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.fee_recipient.set(fee_recipient);
+        self.protocol_fee_bps.set(fee_bps);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), ProtocolFeeUpdated {
+            fee_recipient,
+            fee_bps,
+        });
+        Ok(())
+    }
+
+    /// Lists `amount` units of `token_id` at `price` per unit.
+    /// Pass the zero address as `payment_token` to be paid in ETH.
+    pub fn list(
+        &mut self,
+        token_contract: Address,
+        token_id: U256,
+        amount: U256,
+        payment_token: Address,
+        price: U256,
+    ) -> Result<U256, MarketplaceError> {
+        self.when_not_paused()?;
+        if amount.is_zero() || price.is_zero() {
+            return Err(MarketplaceError::InvalidAmount(InvalidAmount { amount }));
+        }
+        let seller = self.vm().msg_sender();
+        let is_erc1155 = self.check_listable(seller, token_contract, token_id, amount)?;
+
+        let listing_id = self.next_listing_id.get();
+        self.next_listing_id.set(listing_id + U256::from(1));
+
+        let mut listing = self.listings.setter(listing_id);
+        listing.seller.set(seller);
+        listing.token_contract.set(token_contract);
+        listing.token_id.set(token_id);
+        listing.amount.set(amount);
+        listing.payment_token.set(payment_token);
+        listing.price.set(price);
+        listing.is_erc1155.set(is_erc1155);
+
+        log(self.vm(), Listed {
+            listing_id,
+            seller,
+            token_contract,
+            token_id,
+            amount,
+            payment_token,
+            price,
+        });
+        Ok(listing_id)
+    }
+
+    /// Buys `amount` units of a listing.
+    /// ETH listings require the exact total price as `msg.value`; ERC-20 listings
+    /// require the buyer to have approved the marketplace for the total price.
+    #[payable]
+    pub fn buy(&mut self, listing_id: U256, amount: U256) -> Result<(), MarketplaceError> {
+        self.when_not_paused()?;
+        let buyer = self.vm().msg_sender();
+        let listing = self.listings.get(listing_id);
+        let seller = listing.seller.get();
+        let token_contract = listing.token_contract.get();
+        let token_id = listing.token_id.get();
+        let available = listing.amount.get();
+        let payment_token = listing.payment_token.get();
+        let price = listing.price.get();
+        let is_erc1155 = listing.is_erc1155.get();
+
+        if available.is_zero() {
+            return Err(MarketplaceError::ListingNotActive(ListingNotActive { listing_id }));
+        }
+        if amount.is_zero() || amount > available {
+            return Err(MarketplaceError::InsufficientListingAmount(InsufficientListingAmount {
+                listing_id,
+                available,
+                requested: amount,
+            }));
+        }
+        let total_price = price
+            .checked_mul(amount)
+            .ok_or(MarketplaceError::InvalidAmount(InvalidAmount { amount }))?;
+
+        let expected_value = if payment_token.is_zero() { total_price } else { U256::ZERO };
+        let received = self.vm().msg_value();
+        if received != expected_value {
+            return Err(MarketplaceError::IncorrectPayment(IncorrectPayment {
+                expected: expected_value,
+                received,
+            }));
+        }
+
+        // Update the listing before calling out to other contracts
+        self.listings.setter(listing_id).amount.set(available - amount);
+
+        let protocol_fee =
+            total_price * self.protocol_fee_bps.get() / U256::from(BPS_DENOMINATOR);
+        let (royalty_receiver, royalty_amount) =
+            self.royalty_for(token_contract, token_id, total_price, total_price - protocol_fee);
+        let proceeds = total_price - protocol_fee - royalty_amount;
+
+        let fee_recipient = self.fee_recipient.get();
+        self.pay(payment_token, buyer, fee_recipient, protocol_fee)?;
+        self.pay(payment_token, buyer, royalty_receiver, royalty_amount)?;
+        self.pay(payment_token, buyer, seller, proceeds)?;
+
+        let calldata = if is_erc1155 {
+            IERC1155::safeTransferFromCall { from: seller, to: buyer, id: token_id, value: amount, data: Default::default() }
+                .abi_encode()
+        } else {
+            IERC721::safeTransferFromCall { from: seller, to: buyer, token_id }.abi_encode()
+        };
+        if self.vm().call(&calls::context::Call::new(), token_contract, &calldata).is_err() {
+            return Err(MarketplaceError::TokenTransferFailed(TokenTransferFailed {
+                token_contract,
+                token_id,
+            }));
+        }
+
+        log(self.vm(), Sale {
+            listing_id,
+            buyer,
+            amount,
+            total_price,
+            protocol_fee,
+            royalty_receiver,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    /// Cancels a listing. Callable by the seller or the marketplace owner.
+    pub fn cancel(&mut self, listing_id: U256) -> Result<(), MarketplaceError> {
+        let caller = self.vm().msg_sender();
+        let listing = self.listings.get(listing_id);
+        if listing.amount.get().is_zero() {
+            return Err(MarketplaceError::ListingNotActive(ListingNotActive { listing_id }));
+        }
+        if caller != listing.seller.get() && caller != self.owner.get() {
+            return Err(MarketplaceError::Unauthorized(Unauthorized { caller }));
+        }
+        self.listings.setter(listing_id).amount.set(U256::ZERO);
+
+        log(self.vm(), ListingCancelled { listing_id });
+        Ok(())
+    }
+
+    /// Updates the protocol fee and its recipient (owner only).
+    pub fn set_protocol_fee(
+        &mut self,
+        fee_recipient: Address,
+        fee_bps: U256,
+    ) -> Result<(), MarketplaceError> {
+        self.only_owner()?;
+        Self::check_protocol_fee(fee_recipient, fee_bps)?;
+        self.fee_recipient.set(fee_recipient);
+        self.protocol_fee_bps.set(fee_bps);
+
+        log(self.vm(), ProtocolFeeUpdated {
+            fee_recipient,
+            fee_bps,
+        });
+        Ok(())
+    }
+
+    /// Transfers ownership of the marketplace (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), MarketplaceError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Lets `registry`'s switch pause listing and buying, or stops with the zero
+    /// address (owner only).
+    pub fn set_pause_registry(&mut self, registry: Address) -> Result<(), MarketplaceError> {
+        self.only_owner()?;
+        self.pause_registry.set(registry);
+
+        log(self.vm(), PauseRegistrySet { registry });
+        Ok(())
+    }
+
+    /// `pause-registry-stylus` deployment the marketplace consults, zero until set.
+    pub fn pause_registry(&self) -> Address {
+        self.pause_registry.get()
+    }
+
+    /// Current owner of the marketplace.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Protocol fee recipient and fee in basis points.
+    pub fn protocol_fee(&self) -> (Address, U256) {
+        (self.fee_recipient.get(), self.protocol_fee_bps.get())
+    }
+
+    /// Returns `(seller, token_contract, token_id, amount, payment_token, price)` of a listing.
+    pub fn listing(&self, listing_id: U256) -> (Address, Address, U256, U256, Address, U256) {
+        let listing = self.listings.get(listing_id);
+        (
+            listing.seller.get(),
+            listing.token_contract.get(),
+            listing.token_id.get(),
+            listing.amount.get(),
+            listing.payment_token.get(),
+            listing.price.get(),
+        )
+    }
+
+    /// Number of listings created so far.
+    pub fn total_listings(&self) -> U256 {
+        self.next_listing_id.get()
+    }
+}
@@ -0,0 +1,194 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const TREASURY: Address = address!("000000000000000000000000000000000000fee5");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const SELLER: Address = address!("0000000000000000000000000000000000005e11");
+const ARTIST: Address = address!("00000000000000000000000000000000000a2715");
+const ITEMS: Address = address!("0000000000000000000000000000000000001155");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+
+fn mock_support(vm: &TestVM, interface_id: u32, supported: bool) {
+    let call = IERC165::supportsInterfaceCall { interface_id: FixedBytes(interface_id.to_be_bytes()) };
+    vm.mock_static_call(ITEMS, call.abi_encode(), Ok(supported.abi_encode()));
+}
+
+/// Mocks `SELLER` holding `balance` of ERC-1155 token 7 and whether it approved the marketplace.
+fn mock_holding(vm: &TestVM, balance: u64, approved: bool) {
+    let call = IERC1155::balanceOfCall { account: SELLER, id: n(7) };
+    vm.mock_static_call(ITEMS, call.abi_encode(), Ok(n(balance).abi_encode()));
+    let call = IERC1155::isApprovedForAllCall { account: SELLER, operator: vm.contract_address() };
+    vm.mock_static_call(ITEMS, call.abi_encode(), Ok(approved.abi_encode()));
+}
+
+/// Mocks the token asking `royalty` for a sale at `sale_price`, paid to `ARTIST`.
+fn mock_royalty(vm: &TestVM, sale_price: u64, royalty: u64) {
+    mock_support(vm, IERC2981_INTERFACE_ID, true);
+    let call = IERC2981::royaltyInfoCall { token_id: n(7), sale_price: n(sale_price) };
+    vm.mock_static_call(ITEMS, call.abi_encode(), Ok((ARTIST, n(royalty)).abi_encode()));
+}
+
+/// A marketplace with a 2.5% fee, and 10 of `SELLER`'s ERC-1155 token 7 listed at 1,000 each in
+/// `payment_token`.
+fn setup(payment_token: Address) -> (TestVM, Marketplace, U256) {
+    let (vm, mut contract) = deploy::<Marketplace>(OWNER);
+    ok(contract.initialize(OWNER, TREASURY, n(250)));
+
+    mock_support(&vm, IERC1155_INTERFACE_ID, true);
+    mock_holding(&vm, 10, true);
+    vm.set_sender(SELLER);
+    let listing_id = ok(contract.list(ITEMS, n(7), n(10), payment_token, n(1_000)));
+    (vm, contract, listing_id)
+}
+
+#[test]
+fn initialize_is_deployer_only_when_built_with_one() {
+    assert!(check_initializer(None, SELLER).is_ok());
+    assert!(check_initializer(Some(OWNER), OWNER).is_ok());
+    let result = check_initializer(Some(OWNER), SELLER);
+    assert!(
+        matches!(result, Err(MarketplaceError::UnauthorizedInitializer(e)) if e.caller == SELLER && e.deployer == OWNER)
+    );
+}
+
+#[test]
+fn protocol_fees_need_a_recipient() {
+    let (vm, mut contract) = deploy::<Marketplace>(OWNER);
+    let result = contract.initialize(OWNER, Address::ZERO, U256::from(250));
+    assert!(matches!(result, Err(MarketplaceError::InvalidFee(e)) if e.fee_bps == U256::from(250)));
+    let result = contract.initialize(OWNER, TREASURY, U256::from(1_001));
+    assert!(matches!(result, Err(MarketplaceError::InvalidFee(_))));
+
+    // No fee needs no one to receive it
+    ok(contract.initialize(OWNER, Address::ZERO, U256::ZERO));
+    assert_eq!(contract.protocol_fee(), (Address::ZERO, U256::ZERO));
+    let result = contract.set_protocol_fee(Address::ZERO, U256::from(100));
+    assert!(matches!(result, Err(MarketplaceError::InvalidFee(_))));
+    ok(contract.set_protocol_fee(TREASURY, U256::from(100)));
+    assert_eq!(contract.protocol_fee(), (TREASURY, U256::from(100)));
+
+    vm.set_sender(ALICE);
+    let result = contract.set_protocol_fee(ALICE, U256::from(100));
+    assert!(matches!(result, Err(MarketplaceError::Unauthorized(e)) if e.caller == ALICE));
+}
+
+#[test]
+fn listings_need_the_tokens_and_an_approval() {
+    let (vm, mut contract, listing_id) = setup(Address::ZERO);
+    assert_eq!(contract.listing(listing_id), (SELLER, ITEMS, n(7), n(10), Address::ZERO, n(1_000)));
+    let event = emitted::<Listed>(&vm).pop().expect("listing logged");
+    assert_eq!((event.seller, event.amount, event.price), (SELLER, n(10), n(1_000)));
+
+    let result = contract.list(ITEMS, n(7), n(11), Address::ZERO, n(1_000));
+    assert!(matches!(result, Err(MarketplaceError::NotApproved(e)) if e.seller == SELLER));
+    mock_holding(&vm, 10, false);
+    let result = contract.list(ITEMS, n(7), n(1), Address::ZERO, n(1_000));
+    assert!(matches!(result, Err(MarketplaceError::NotApproved(_))));
+    let result = contract.list(ITEMS, n(7), n(1), Address::ZERO, U256::ZERO);
+    assert!(matches!(result, Err(MarketplaceError::InvalidAmount(_))));
+    mock_support(&vm, IERC1155_INTERFACE_ID, false);
+    let result = contract.list(ITEMS, n(7), n(1), Address::ZERO, n(1_000));
+    assert!(matches!(result, Err(MarketplaceError::UnsupportedToken(e)) if e.token_contract == ITEMS));
+    assert_eq!(contract.total_listings(), n(1));
+}
+
+#[test]
+fn eth_sales_pay_the_fee_then_the_royalty_then_the_seller() {
+    let (vm, mut contract, listing_id) = setup(Address::ZERO);
+    mock_royalty(&vm, 4_000, 400);
+
+    vm.set_sender(ALICE);
+    vm.set_value(n(3_000));
+    let result = contract.buy(listing_id, n(4));
+    assert!(matches!(result, Err(MarketplaceError::IncorrectPayment(e)) if e.expected == n(4_000)));
+    // The attached ETH is the contract's to pay out
+    vm.set_value(n(4_000));
+    vm.set_balance(vm.contract_address(), n(4_000));
+    ok(contract.buy(listing_id, n(4)));
+    assert_eq!(vm.balance(TREASURY), n(100));
+    assert_eq!(vm.balance(ARTIST), n(400));
+    assert_eq!(vm.balance(SELLER), n(3_500));
+    assert_eq!(contract.listing(listing_id).3, n(6));
+    let event = emitted::<Sale>(&vm).pop().expect("sale logged");
+    assert_eq!(
+        (event.buyer, event.amount, event.total_price, event.protocol_fee, event.royalty_amount),
+        (ALICE, n(4), n(4_000), n(100), n(400)),
+    );
+
+    let result = contract.buy(listing_id, n(7));
+    assert!(matches!(result, Err(MarketplaceError::InsufficientListingAmount(e)) if e.available == n(6)));
+}
+
+#[test]
+fn royalties_are_capped_at_what_the_fee_leaves() {
+    let (vm, mut contract, listing_id) = setup(Address::ZERO);
+    mock_royalty(&vm, 1_000, 5_000);
+
+    vm.set_sender(ALICE);
+    vm.set_value(n(1_000));
+    vm.set_balance(vm.contract_address(), n(1_000));
+    ok(contract.buy(listing_id, n(1)));
+    assert_eq!(vm.balance(TREASURY), n(25));
+    assert_eq!(vm.balance(ARTIST), n(975));
+    assert_eq!(vm.balance(SELLER), U256::ZERO);
+}
+
+#[test]
+fn token_sales_pull_each_share_from_the_buyer() {
+    let (vm, mut contract, listing_id) = setup(USDC);
+    // Only the exact shares are mocked, so any other split fails the sale
+    for (to, amount) in [(TREASURY, 50), (SELLER, 1_950)] {
+        let call = IERC20::transferFromCall { from: ALICE, to, value: n(amount) };
+        vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+    }
+    vm.set_sender(ALICE);
+    ok(contract.buy(listing_id, n(2)));
+    assert_eq!(contract.listing(listing_id).3, n(8));
+
+    // ETH can't pay for a token listing
+    vm.set_value(n(1_000));
+    let result = contract.buy(listing_id, n(1));
+    assert!(matches!(result, Err(MarketplaceError::IncorrectPayment(e)) if e.expected.is_zero()));
+}
+
+#[test]
+fn token_sales_fail_when_a_share_does_not_move() {
+    let (vm, mut contract, listing_id) = setup(USDC);
+    vm.set_sender(ALICE);
+    let result = contract.buy(listing_id, n(2));
+    assert!(matches!(result, Err(MarketplaceError::PaymentFailed(e)) if e.to == TREASURY && e.amount == n(50)));
+}
+
+#[test]
+fn sales_fail_once_the_seller_revokes_the_approval() {
+    let (vm, mut contract, listing_id) = setup(Address::ZERO);
+    // The listing stays up, but the token contract refuses to move the tokens
+    let call =
+        IERC1155::safeTransferFromCall { from: SELLER, to: ALICE, id: n(7), value: n(1), data: Default::default() };
+    vm.mock_call(ITEMS, call.abi_encode(), Err(Vec::new()));
+    vm.set_sender(ALICE);
+    vm.set_value(n(1_000));
+    vm.set_balance(vm.contract_address(), n(1_000));
+    let result = contract.buy(listing_id, n(1));
+    assert!(matches!(result, Err(MarketplaceError::TokenTransferFailed(e)) if e.token_contract == ITEMS));
+}
+
+#[test]
+fn only_the_seller_or_owner_cancels() {
+    let (vm, mut contract, listing_id) = setup(Address::ZERO);
+    vm.set_sender(ALICE);
+    let result = contract.cancel(listing_id);
+    assert!(matches!(result, Err(MarketplaceError::Unauthorized(e)) if e.caller == ALICE));
+
+    vm.set_sender(OWNER);
+    ok(contract.cancel(listing_id));
+    assert_eq!(contract.listing(listing_id).3, U256::ZERO);
+    assert_eq!(emitted::<ListingCancelled>(&vm).len(), 1);
+    vm.set_sender(ALICE);
+    vm.set_value(n(1_000));
+    let result = contract.buy(listing_id, n(1));
+    assert!(matches!(result, Err(MarketplaceError::ListingNotActive(_))));
+}
@@ -0,0 +1,415 @@
+//! Name registry
+//!
+//! Registers unique human-readable handles, such as a creator's name for
+//! their ERC-1155 collections. A name maps to a target address and a metadata
+//! URI and is held by an owner, who can change both and transfer the name.
+//!
+//! Names are registered for whole years and paid for in ETH. An expired name
+//! stops resolving, but its owner can still renew it during the grace period;
+//! after that anyone can register it.
+//!
+//! An address can set a name that resolves to it as its primary name, which is
+//! what apps show instead of the address. The primary name is only returned
+//! while the name is active and still points at that address.
+//!
+//! Names are 3 to 32 characters of lowercase letters, digits and hyphens, and
+//! can't start or end with a hyphen.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::string::String;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+/// Length of a registration year in seconds.
+const YEAR: u64 = 365 * 24 * 60 * 60;
+/// Shortest name allowed.
+const MIN_NAME_LENGTH: usize = 3;
+/// Longest name allowed.
+const MAX_NAME_LENGTH: usize = 32;
+/// Most years a name can be registered or renewed for at once.
+const MAX_YEARS: u64 = 10;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct NameRegistry {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the price and withdraws fees
+        address owner;
+        /// Price of one registration year in wei
+        uint256 price_per_year;
+        /// Seconds after expiry during which only the owner can renew
+        uint256 grace_period;
+        /// Records by `keccak256(name)`
+        mapping(bytes32 => NameRecord) records;
+        /// Primary name hash per address
+        mapping(address => bytes32) primary_names;
+    }
+
+    pub struct NameRecord {
+        string name;
+        address owner;
+        /// Address the name resolves to
+        address target;
+        string metadata_uri;
+        uint256 expires_at;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event NameRegistered(bytes32 indexed name_hash, string name, address indexed owner, address target, uint256 expires_at);
+    event NameRenewed(bytes32 indexed name_hash, uint256 expires_at);
+    event NameTransferred(bytes32 indexed name_hash, address indexed from, address indexed to);
+    event TargetChanged(bytes32 indexed name_hash, address target);
+    event MetadataChanged(bytes32 indexed name_hash, string metadata_uri);
+    event PrimaryNameSet(address indexed account, bytes32 indexed name_hash);
+    event PriceUpdated(uint256 price_per_year, uint256 grace_period);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Name has the wrong length or invalid characters
+    error InvalidName(string name);
+    // Name is registered and not yet past its grace period
+    error NameUnavailable(string name);
+    // Name is not registered, or past its grace period
+    error UnknownName(string name);
+    // Name has expired and must be renewed first
+    error NameExpired(string name);
+    // Years is zero or above the maximum
+    error InvalidYears(uint256 years);
+    // ETH sent doesn't cover the price
+    error InsufficientPayment(uint256 price, uint256 paid);
+    // New owner is the zero address
+    error InvalidOwner(address owner);
+    // Name doesn't resolve to the caller
+    error NotTarget(string name, address account);
+    // An ETH transfer failed
+    error TransferFailed(address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum NameRegistryError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidName(InvalidName),
+    NameUnavailable(NameUnavailable),
+    UnknownName(UnknownName),
+    NameExpired(NameExpired),
+    InvalidYears(InvalidYears),
+    InsufficientPayment(InsufficientPayment),
+    InvalidOwner(InvalidOwner),
+    NotTarget(NotTarget),
+    TransferFailed(TransferFailed),
+}
+
+// Helpers used by the public methods below.
+impl NameRegistry {
+    fn only_owner(&self) -> Result<(), NameRegistryError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(NameRegistryError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn is_valid_name(name: &str) -> bool {
+        let bytes = name.as_bytes();
+        (MIN_NAME_LENGTH..=MAX_NAME_LENGTH).contains(&bytes.len())
+            && bytes
+                .iter()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-')
+            && bytes[0] != b'-'
+            && bytes[bytes.len() - 1] != b'-'
+    }
+
+    fn name_hash(&self, name: &str) -> B256 {
+        self.vm().native_keccak256(name.as_bytes())
+    }
+
+    /// Whether the name is registered and not expired.
+    fn is_active(&self, name_hash: B256) -> bool {
+        self.records.getter(name_hash).expires_at.get() > self.now()
+    }
+
+    /// Whether the name can be renewed by its owner, i.e. not past its grace period.
+    fn is_held(&self, name_hash: B256) -> bool {
+        let expires_at = self.records.getter(name_hash).expires_at.get();
+        !expires_at.is_zero() && expires_at + self.grace_period.get() > self.now()
+    }
+
+    /// Fails unless the name is active and the caller owns it.
+    fn only_name_owner(&self, name: &str) -> Result<B256, NameRegistryError> {
+        let name_hash = self.name_hash(name);
+        if !self.is_active(name_hash) {
+            return Err(NameRegistryError::NameExpired(NameExpired { name: name.into() }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != self.records.getter(name_hash).owner.get() {
+            return Err(NameRegistryError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(name_hash)
+    }
+
+    /// Checks the payment for `years` and refunds any excess. Returns the registration length in seconds.
+    fn take_payment(&mut self, years: U256) -> Result<U256, NameRegistryError> {
+        if years.is_zero() || years > U256::from(MAX_YEARS) {
+            return Err(NameRegistryError::InvalidYears(InvalidYears { years }));
+        }
+        let price = self.price_per_year.get() * years;
+        let paid = self.vm().msg_value();
+        if paid < price {
+            return Err(NameRegistryError::InsufficientPayment(InsufficientPayment { price, paid }));
+        }
+        if paid > price {
+            let caller = self.vm().msg_sender();
+            let refund = paid - price;
+            self.vm()
+                .transfer_eth(caller, refund)
+                .map_err(|_| NameRegistryError::TransferFailed(TransferFailed { to: caller, amount: refund }))?;
+        }
+        Ok(years * U256::from(YEAR))
+    }
+}
+
+#[public]
+impl NameRegistry {
+    /// Sets the owner, the yearly price and the grace period. Can only be called once.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        price_per_year: U256,
+        grace_period: U256,
+    ) -> Result<(), NameRegistryError> {
+        if self.initialized.get() {
+            return Err(NameRegistryError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.price_per_year.set(price_per_year);
+        self.grace_period.set(grace_period);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), PriceUpdated {
+            price_per_year,
+            grace_period,
+        });
+        Ok(())
+    }
+
+    /// Registers an available name for `years` to the caller, resolving to `target`.
+    /// Send `price(years)` in ETH; any excess is refunded.
+    #[payable]
+    pub fn register(
+        &mut self,
+        name: String,
+        target: Address,
+        metadata_uri: String,
+        years: U256,
+    ) -> Result<(), NameRegistryError> {
+        if !Self::is_valid_name(&name) {
+            return Err(NameRegistryError::InvalidName(InvalidName { name }));
+        }
+        let name_hash = self.name_hash(&name);
+        if self.is_held(name_hash) {
+            return Err(NameRegistryError::NameUnavailable(NameUnavailable { name }));
+        }
+        let duration = self.take_payment(years)?;
+        let owner = self.vm().msg_sender();
+        let expires_at = self.now() + duration;
+
+        let mut record = self.records.setter(name_hash);
+        record.name.set_str(&name);
+        record.owner.set(owner);
+        record.target.set(target);
+        record.metadata_uri.set_str(&metadata_uri);
+        record.expires_at.set(expires_at);
+
+        log(self.vm(), NameRegistered {
+            name_hash,
+            name,
+            owner,
+            target,
+            expires_at,
+        });
+        log(self.vm(), MetadataChanged {
+            name_hash,
+            metadata_uri,
+        });
+        Ok(())
+    }
+
+    /// Extends a name by `years` from its current expiry. Anyone can pay, until the grace period ends.
+    #[payable]
+    pub fn renew(&mut self, name: String, years: U256) -> Result<(), NameRegistryError> {
+        let name_hash = self.name_hash(&name);
+        if !self.is_held(name_hash) {
+            return Err(NameRegistryError::UnknownName(UnknownName { name }));
+        }
+        let duration = self.take_payment(years)?;
+        let expires_at = self.records.getter(name_hash).expires_at.get() + duration;
+        self.records.setter(name_hash).expires_at.set(expires_at);
+
+        log(self.vm(), NameRenewed { name_hash, expires_at });
+        Ok(())
+    }
+
+    /// Transfers an active name to a new owner (name owner only). The target is left unchanged.
+    pub fn transfer(&mut self, name: String, new_owner: Address) -> Result<(), NameRegistryError> {
+        let name_hash = self.only_name_owner(&name)?;
+        if new_owner.is_zero() {
+            return Err(NameRegistryError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        let from = self.records.getter(name_hash).owner.get();
+        self.records.setter(name_hash).owner.set(new_owner);
+
+        log(self.vm(), NameTransferred {
+            name_hash,
+            from,
+            to: new_owner,
+        });
+        Ok(())
+    }
+
+    /// Points an active name at a new address (name owner only).
+    pub fn set_target(&mut self, name: String, target: Address) -> Result<(), NameRegistryError> {
+        let name_hash = self.only_name_owner(&name)?;
+        self.records.setter(name_hash).target.set(target);
+
+        log(self.vm(), TargetChanged { name_hash, target });
+        Ok(())
+    }
+
+    /// Replaces the metadata URI of an active name (name owner only).
+    pub fn set_metadata(&mut self, name: String, metadata_uri: String) -> Result<(), NameRegistryError> {
+        let name_hash = self.only_name_owner(&name)?;
+        self.records.setter(name_hash).metadata_uri.set_str(&metadata_uri);
+
+        log(self.vm(), MetadataChanged {
+            name_hash,
+            metadata_uri,
+        });
+        Ok(())
+    }
+
+    /// Sets the caller's primary name. The name must be active and resolve to the caller.
+    pub fn set_primary_name(&mut self, name: String) -> Result<(), NameRegistryError> {
+        let name_hash = self.name_hash(&name);
+        let account = self.vm().msg_sender();
+        if !self.is_active(name_hash) {
+            return Err(NameRegistryError::NameExpired(NameExpired { name }));
+        }
+        if self.records.getter(name_hash).target.get() != account {
+            return Err(NameRegistryError::NotTarget(NotTarget { name, account }));
+        }
+        self.primary_names.insert(account, name_hash);
+
+        log(self.vm(), PrimaryNameSet { account, name_hash });
+        Ok(())
+    }
+
+    /// Updates the yearly price and the grace period (owner only).
+    pub fn set_price(&mut self, price_per_year: U256, grace_period: U256) -> Result<(), NameRegistryError> {
+        self.only_owner()?;
+        self.price_per_year.set(price_per_year);
+        self.grace_period.set(grace_period);
+
+        log(self.vm(), PriceUpdated {
+            price_per_year,
+            grace_period,
+        });
+        Ok(())
+    }
+
+    /// Sends the collected fees to `to` (owner only).
+    pub fn withdraw(&mut self, to: Address) -> Result<(), NameRegistryError> {
+        self.only_owner()?;
+        let amount = self.vm().balance(self.vm().contract_address());
+        self.vm()
+            .transfer_eth(to, amount)
+            .map_err(|_| NameRegistryError::TransferFailed(TransferFailed { to, amount }))
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), NameRegistryError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Address an active name resolves to; zero if it is unregistered or expired.
+    pub fn resolve(&self, name: String) -> Address {
+        let name_hash = self.name_hash(&name);
+        if !self.is_active(name_hash) {
+            return Address::ZERO;
+        }
+        self.records.getter(name_hash).target.get()
+    }
+
+    /// Primary name of `account`; empty if unset, expired, or no longer pointing at the account.
+    pub fn primary_name(&self, account: Address) -> String {
+        let name_hash = self.primary_names.get(account);
+        let record = self.records.getter(name_hash);
+        if !self.is_active(name_hash) || record.target.get() != account {
+            return String::new();
+        }
+        record.name.get_string()
+    }
+
+    /// Returns `(owner, target, metadata_uri, expires_at)` for a name, whether or not it is active.
+    pub fn name_info(&self, name: String) -> (Address, Address, String, U256) {
+        let record = self.records.getter(self.name_hash(&name));
+        (
+            record.owner.get(),
+            record.target.get(),
+            record.metadata_uri.get_string(),
+            record.expires_at.get(),
+        )
+    }
+
+    /// Whether `name` is valid and can be registered now.
+    pub fn is_available(&self, name: String) -> bool {
+        Self::is_valid_name(&name) && !self.is_held(self.name_hash(&name))
+    }
+
+    /// Price in wei of registering or renewing for `years`.
+    pub fn price(&self, years: U256) -> U256 {
+        self.price_per_year.get() * years
+    }
+
+    /// Returns `(price_per_year, grace_period)`.
+    pub fn pricing(&self) -> (U256, U256) {
+        (self.price_per_year.get(), self.grace_period.get())
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
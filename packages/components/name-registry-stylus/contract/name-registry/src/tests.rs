@@ -0,0 +1,193 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::SolError;
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const PRICE: u64 = 1_000;
+const GRACE: u64 = 30 * 24 * 60 * 60;
+
+/// A registry at timestamp 1000 charging `PRICE` a year with a 30-day grace period.
+fn setup() -> (TestVM, NameRegistry) {
+    let (vm, mut contract) = deploy::<NameRegistry>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, n(PRICE), n(GRACE)));
+    (vm, contract)
+}
+
+/// Has `account` register `name`, resolving to itself, for `years`, paying exactly.
+fn register(vm: &TestVM, contract: &mut NameRegistry, account: Address, name: &str, years: u64) {
+    vm.set_sender(account);
+    vm.set_value(n(PRICE * years));
+    ok(contract.register(name.into(), account, "ipfs://alice".into(), n(years)));
+    vm.set_value(U256::ZERO);
+}
+
+#[test]
+fn initialize_runs_once() {
+    let (vm, mut contract) = setup();
+    assert_eq!((contract.owner(), contract.pricing()), (OWNER, (n(PRICE), n(GRACE))));
+    let event = emitted::<PriceUpdated>(&vm).pop().expect("pricing logged");
+    assert_eq!((event.price_per_year, event.grace_period), (n(PRICE), n(GRACE)));
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.initialize(ALICE, U256::ZERO, U256::ZERO)), AlreadyInitialized {}.abi_encode());
+}
+
+#[test]
+fn names_are_registered_for_paid_years() {
+    let (vm, mut contract) = setup();
+    assert!(contract.is_available("alice".into()));
+    register(&vm, &mut contract, ALICE, "alice", 2);
+    let expires_at = n(1_000 + 2 * YEAR);
+    assert_eq!(contract.name_info("alice".into()), (ALICE, ALICE, "ipfs://alice".into(), expires_at));
+    assert_eq!(contract.resolve("alice".into()), ALICE);
+    assert!(!contract.is_available("alice".into()));
+    let event = emitted::<NameRegistered>(&vm).pop().expect("registration logged");
+    assert_eq!((event.name.as_str(), event.owner, event.target, event.expires_at), ("alice", ALICE, ALICE, expires_at));
+    assert_eq!(event.name_hash, vm.native_keccak256(b"alice"));
+
+    vm.set_sender(BOB);
+    vm.set_value(n(PRICE));
+    let error = NameUnavailable { name: "alice".into() }.abi_encode();
+    assert_eq!(reverted(contract.register("alice".into(), BOB, String::new(), n(1))), error);
+}
+
+#[test]
+fn names_must_be_short_lowercase_handles() {
+    let (vm, mut contract) = setup();
+    vm.set_value(n(PRICE));
+    let too_long = "a".repeat(MAX_NAME_LENGTH + 1);
+    for name in ["ab", "Alice", "al ice", "-alice", "alice-", "al.ice", too_long.as_str()] {
+        assert!(!contract.is_available(name.into()));
+        let error = InvalidName { name: name.into() }.abi_encode();
+        assert_eq!(reverted(contract.register(name.into(), OWNER, String::new(), n(1))), error);
+    }
+    for name in ["abc", "a-1", "0x00", &"z".repeat(MAX_NAME_LENGTH)] {
+        ok(contract.register(name.into(), OWNER, String::new(), n(1)));
+    }
+}
+
+#[test]
+fn registrations_are_paid_in_full_and_excess_is_refunded() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    vm.set_value(n(PRICE * 3 - 1));
+    let error = InsufficientPayment { price: n(PRICE * 3), paid: n(PRICE * 3 - 1) }.abi_encode();
+    assert_eq!(reverted(contract.register("alice".into(), ALICE, String::new(), n(3))), error);
+    for years in [0, MAX_YEARS + 1] {
+        let error = InvalidYears { years: n(years) }.abi_encode();
+        assert_eq!(reverted(contract.register("alice".into(), ALICE, String::new(), n(years))), error);
+    }
+
+    // The VM doesn't credit the value sent, so the registry is given it
+    vm.set_value(n(PRICE + 400));
+    vm.set_balance(vm.contract_address(), n(PRICE + 400));
+    ok(contract.register("alice".into(), ALICE, String::new(), n(1)));
+    assert_eq!(vm.balance(ALICE), n(400));
+    assert_eq!(vm.balance(vm.contract_address()), n(PRICE));
+}
+
+#[test]
+fn names_lapse_after_expiry_and_free_up_after_the_grace_period() {
+    let (vm, mut contract) = setup();
+    register(&vm, &mut contract, ALICE, "alice", 1);
+    vm.set_sender(ALICE);
+    ok(contract.set_primary_name("alice".into()));
+    let expires_at = 1_000 + YEAR;
+
+    // Expired, the name stops resolving and its owner can't manage it
+    vm.set_block_timestamp(expires_at);
+    assert_eq!(contract.resolve("alice".into()), Address::ZERO);
+    assert_eq!(contract.primary_name(ALICE), "");
+    let error = NameExpired { name: "alice".into() }.abi_encode();
+    assert_eq!(reverted(contract.set_target("alice".into(), BOB)), error);
+    assert!(!contract.is_available("alice".into()));
+
+    // Anyone renews it during the grace period, from its expiry
+    vm.set_sender(BOB);
+    vm.set_value(n(PRICE));
+    ok(contract.renew("alice".into(), n(1)));
+    assert_eq!(contract.name_info("alice".into()).3, n(expires_at + YEAR));
+    let event = emitted::<NameRenewed>(&vm).pop().expect("renewal logged");
+    assert_eq!(event.expires_at, n(expires_at + YEAR));
+    assert_eq!(contract.resolve("alice".into()), ALICE);
+
+    // Past the grace period it's nobody's
+    vm.set_block_timestamp(expires_at + YEAR + GRACE);
+    assert!(contract.is_available("alice".into()));
+    let error = UnknownName { name: "alice".into() }.abi_encode();
+    assert_eq!(reverted(contract.renew("alice".into(), n(1))), error);
+    register(&vm, &mut contract, BOB, "alice", 1);
+    assert_eq!(contract.name_info("alice".into()).0, BOB);
+}
+
+#[test]
+fn name_owners_transfer_and_repoint_their_names() {
+    let (vm, mut contract) = setup();
+    register(&vm, &mut contract, ALICE, "alice", 1);
+    vm.set_sender(BOB);
+    let error = Unauthorized { caller: BOB }.abi_encode();
+    assert_eq!(reverted(contract.transfer("alice".into(), BOB)), error);
+    assert_eq!(reverted(contract.set_target("alice".into(), BOB)), error);
+    assert_eq!(reverted(contract.set_metadata("alice".into(), String::new())), error);
+
+    vm.set_sender(ALICE);
+    ok(contract.set_target("alice".into(), BOB));
+    assert_eq!(contract.resolve("alice".into()), BOB);
+    let event = emitted::<TargetChanged>(&vm).pop().expect("target logged");
+    assert_eq!(event.target, BOB);
+    ok(contract.set_metadata("alice".into(), "ipfs://bob".into()));
+    assert_eq!(contract.name_info("alice".into()).2, "ipfs://bob");
+    let error = InvalidOwner { owner: Address::ZERO }.abi_encode();
+    assert_eq!(reverted(contract.transfer("alice".into(), Address::ZERO)), error);
+    ok(contract.transfer("alice".into(), BOB));
+    let event = emitted::<NameTransferred>(&vm).pop().expect("transfer logged");
+    assert_eq!((event.from, event.to), (ALICE, BOB));
+    assert_eq!(reverted(contract.set_target("alice".into(), ALICE)), Unauthorized { caller: ALICE }.abi_encode());
+}
+
+#[test]
+fn primary_names_must_resolve_to_their_account() {
+    let (vm, mut contract) = setup();
+    register(&vm, &mut contract, ALICE, "alice", 1);
+    vm.set_sender(BOB);
+    let error = NotTarget { name: "alice".into(), account: BOB }.abi_encode();
+    assert_eq!(reverted(contract.set_primary_name("alice".into())), error);
+    let error = NameExpired { name: "nobody".into() }.abi_encode();
+    assert_eq!(reverted(contract.set_primary_name("nobody".into())), error);
+
+    vm.set_sender(ALICE);
+    ok(contract.set_primary_name("alice".into()));
+    assert_eq!(contract.primary_name(ALICE), "alice");
+    let event = emitted::<PrimaryNameSet>(&vm).pop().expect("primary name logged");
+    assert_eq!((event.account, event.name_hash), (ALICE, vm.native_keccak256(b"alice")));
+
+    // Pointing the name elsewhere drops it as ALICE's primary name
+    ok(contract.set_target("alice".into(), BOB));
+    assert_eq!(contract.primary_name(ALICE), "");
+}
+
+#[test]
+fn only_the_owner_prices_names_and_withdraws_fees() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_price(U256::ZERO, U256::ZERO)), error);
+    assert_eq!(reverted(contract.withdraw(ALICE)), error);
+    assert_eq!(reverted(contract.transfer_ownership(ALICE)), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.set_price(n(5), n(10)));
+    assert_eq!(contract.price(n(3)), n(15));
+    vm.set_balance(vm.contract_address(), n(2_500));
+    ok(contract.withdraw(BOB));
+    assert_eq!(vm.balance(BOB), n(2_500));
+    ok(contract.transfer_ownership(ALICE));
+    assert_eq!(contract.owner(), ALICE);
+    let event = emitted::<OwnershipTransferred>(&vm).pop().expect("ownership logged");
+    assert_eq!((event.previous_owner, event.new_owner), (OWNER, ALICE));
+    assert_eq!(reverted(contract.set_price(U256::ZERO, U256::ZERO)), Unauthorized { caller: OWNER }.abi_encode());
+}
@@ -0,0 +1,309 @@
+//! Chainlink price feed consumer
+//!
+//! Wraps `AggregatorV3Interface` feeds behind one set of safety checks so every
+//! Cradle component that needs a price reads it the same way. The owner registers
+//! each feed with a maximum age and a maximum move between consecutive rounds;
+//! `latest_price` only returns an answer that passes every check:
+//!
+//! - the feed is registered and its answer is positive
+//! - the round is complete and not older than the feed's staleness limit
+//! - the answer moved by no more than the deviation limit since the previous round
+//! - the Arbitrum sequencer is up and has been for the grace period, if a
+//!   sequencer uptime feed is configured
+//!
+//! Prices are returned with 18 decimals regardless of the feed's own decimals.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::{Address, I256, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::prelude::*;
+
+/// Decimals of the prices returned by `latest_price`.
+const PRICE_DECIMALS: u8 = 18;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct PriceOracle {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Registers feeds and sets the sequencer check
+        address owner;
+        /// Chainlink L2 sequencer uptime feed; zero disables the check
+        address sequencer_uptime_feed;
+        /// Seconds the sequencer must be back up before prices are trusted again
+        uint256 grace_period;
+        /// Per-feed configuration
+        mapping(address => FeedConfig) feeds;
+    }
+
+    pub struct FeedConfig {
+        /// Whether the feed is registered
+        bool enabled;
+        /// Decimals reported by the feed at registration
+        uint8 decimals;
+        /// Maximum age of an answer in seconds
+        uint256 max_staleness;
+        /// Maximum move from the previous round in basis points; zero disables the check
+        uint256 max_deviation_bps;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event FeedConfigured(address indexed feed, uint8 decimals, uint256 max_staleness, uint256 max_deviation_bps);
+    event FeedRemoved(address indexed feed);
+    event SequencerCheckUpdated(address sequencer_uptime_feed, uint256 grace_period);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Feed is not registered
+    error UnknownFeed(address feed);
+    // Staleness limit is zero or the feed reports too many decimals
+    error InvalidFeedConfig(address feed);
+    // Feed answer is zero or negative
+    error InvalidAnswer(address feed, int256 answer);
+    // Round is incomplete or older than the staleness limit
+    error StalePrice(address feed, uint256 updated_at);
+    // Answer moved more than the deviation limit since the previous round
+    error PriceDeviation(address feed, uint256 deviation_bps);
+    // Sequencer is down or still inside its grace period
+    error SequencerDown();
+    // A call to a feed failed
+    error FeedCallFailed(address feed);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum OracleError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    UnknownFeed(UnknownFeed),
+    InvalidFeedConfig(InvalidFeedConfig),
+    InvalidAnswer(InvalidAnswer),
+    StalePrice(StalePrice),
+    PriceDeviation(PriceDeviation),
+    SequencerDown(SequencerDown),
+    FeedCallFailed(FeedCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IAggregatorV3 {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (uint80, int256, uint256, uint256, uint80);
+        function getRoundData(uint80 round_id) external view returns (uint80, int256, uint256, uint256, uint80);
+    }
+}
+
+// Helpers used by the public methods below.
+impl PriceOracle {
+    fn only_owner(&self) -> Result<(), OracleError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(OracleError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Calls a view of `feed` and decodes what it returns.
+    fn read_feed<C: SolCall>(&self, feed: Address, call: C) -> Result<C::Return, OracleError> {
+        self.vm()
+            .static_call(&calls::context::Call::new(), feed, &call.abi_encode())
+            .ok()
+            .and_then(|output| C::abi_decode_returns(&output, true).ok())
+            .ok_or(OracleError::FeedCallFailed(FeedCallFailed { feed }))
+    }
+
+    /// Fails unless the sequencer is up and its grace period has passed.
+    fn check_sequencer(&self) -> Result<(), OracleError> {
+        let feed = self.sequencer_uptime_feed.get();
+        if feed.is_zero() {
+            return Ok(());
+        }
+        let IAggregatorV3::latestRoundDataReturn { _1: answer, _2: started_at, .. } =
+            self.read_feed(feed, IAggregatorV3::latestRoundDataCall {})?;
+        // An answer of 0 means up, 1 means down; `started_at` is when the status last changed
+        if !answer.is_zero() || started_at.is_zero() || self.now() < started_at + self.grace_period.get() {
+            return Err(OracleError::SequencerDown(SequencerDown {}));
+        }
+        Ok(())
+    }
+
+    /// Converts a positive feed answer to `PRICE_DECIMALS` decimals.
+    fn normalize(answer: I256, decimals: u8) -> U256 {
+        let answer = answer.into_raw();
+        if decimals <= PRICE_DECIMALS {
+            answer * U256::from(10).pow(U256::from(PRICE_DECIMALS - decimals))
+        } else {
+            answer / U256::from(10).pow(U256::from(decimals - PRICE_DECIMALS))
+        }
+    }
+}
+
+#[public]
+impl PriceOracle {
+    /// Sets the owner and the sequencer check. Can only be called once.
+    /// Pass the zero address as `sequencer_uptime_feed` to skip the sequencer check.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        sequencer_uptime_feed: Address,
+        grace_period: U256,
+    ) -> Result<(), OracleError> {
+        if self.initialized.get() {
+            return Err(OracleError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.sequencer_uptime_feed.set(sequencer_uptime_feed);
+        self.grace_period.set(grace_period);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), SequencerCheckUpdated {
+            sequencer_uptime_feed,
+            grace_period,
+        });
+        Ok(())
+    }
+
+    /// Latest answer of `feed` with 18 decimals, and the time it was updated.
+    /// Fails unless the answer passes every check configured for the feed.
+    pub fn latest_price(&self, feed: Address) -> Result<(U256, U256), OracleError> {
+        let config = self.feeds.getter(feed);
+        if !config.enabled.get() {
+            return Err(OracleError::UnknownFeed(UnknownFeed { feed }));
+        }
+        let decimals = config.decimals.get().to::<u8>();
+        let max_staleness = config.max_staleness.get();
+        let max_deviation_bps = config.max_deviation_bps.get();
+        self.check_sequencer()?;
+
+        let IAggregatorV3::latestRoundDataReturn { _0: round_id, _1: answer, _3: updated_at, _4: answered_in_round, .. } =
+            self.read_feed(feed, IAggregatorV3::latestRoundDataCall {})?;
+        if answer <= I256::ZERO {
+            return Err(OracleError::InvalidAnswer(InvalidAnswer { feed, answer }));
+        }
+        let now = self.now();
+        if updated_at.is_zero() || answered_in_round < round_id || updated_at > now || now - updated_at > max_staleness {
+            return Err(OracleError::StalePrice(StalePrice { feed, updated_at }));
+        }
+        let price = Self::normalize(answer, decimals);
+
+        // The first round of a phase has no predecessor to compare against
+        if !max_deviation_bps.is_zero() && round_id > alloy_primitives::Uint::<80, 2>::from(1) {
+            let previous_round = round_id - alloy_primitives::Uint::<80, 2>::from(1);
+            let previous_round = self.read_feed(feed, IAggregatorV3::getRoundDataCall { round_id: previous_round });
+            if let Ok(IAggregatorV3::getRoundDataReturn { _1: previous_answer, .. }) = previous_round {
+                if previous_answer > I256::ZERO {
+                    let previous = Self::normalize(previous_answer, decimals);
+                    let deviation_bps = price.abs_diff(previous) * U256::from(BPS_DENOMINATOR) / previous;
+                    if deviation_bps > max_deviation_bps {
+                        return Err(OracleError::PriceDeviation(PriceDeviation { feed, deviation_bps }));
+                    }
+                }
+            }
+        }
+        Ok((price, updated_at))
+    }
+
+    /// Registers or reconfigures a feed (owner only). Reads and stores the feed's decimals.
+    pub fn set_feed(&mut self, feed: Address, max_staleness: U256, max_deviation_bps: U256) -> Result<(), OracleError> {
+        self.only_owner()?;
+        let decimals = self.read_feed(feed, IAggregatorV3::decimalsCall {})?._0;
+        if max_staleness.is_zero() || decimals > 36 {
+            return Err(OracleError::InvalidFeedConfig(InvalidFeedConfig { feed }));
+        }
+        let mut config = self.feeds.setter(feed);
+        config.enabled.set(true);
+        config.decimals.set(alloy_primitives::Uint::<8, 1>::from(decimals));
+        config.max_staleness.set(max_staleness);
+        config.max_deviation_bps.set(max_deviation_bps);
+
+        log(self.vm(), FeedConfigured {
+            feed,
+            decimals,
+            max_staleness,
+            max_deviation_bps,
+        });
+        Ok(())
+    }
+
+    /// Unregisters a feed (owner only).
+    pub fn remove_feed(&mut self, feed: Address) -> Result<(), OracleError> {
+        self.only_owner()?;
+        if !self.feeds.getter(feed).enabled.get() {
+            return Err(OracleError::UnknownFeed(UnknownFeed { feed }));
+        }
+        self.feeds.setter(feed).enabled.set(false);
+
+        log(self.vm(), FeedRemoved { feed });
+        Ok(())
+    }
+
+    /// Updates the sequencer uptime feed and grace period (owner only).
+    pub fn set_sequencer_check(&mut self, sequencer_uptime_feed: Address, grace_period: U256) -> Result<(), OracleError> {
+        self.only_owner()?;
+        self.sequencer_uptime_feed.set(sequencer_uptime_feed);
+        self.grace_period.set(grace_period);
+
+        log(self.vm(), SequencerCheckUpdated {
+            sequencer_uptime_feed,
+            grace_period,
+        });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), OracleError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(enabled, decimals, max_staleness, max_deviation_bps)` for a feed.
+    pub fn feed_config(&self, feed: Address) -> (bool, u8, U256, U256) {
+        let config = self.feeds.getter(feed);
+        (
+            config.enabled.get(),
+            config.decimals.get().to::<u8>(),
+            config.max_staleness.get(),
+            config.max_deviation_bps.get(),
+        )
+    }
+
+    /// Returns `(sequencer_uptime_feed, grace_period)`.
+    pub fn sequencer_check(&self) -> (Address, U256) {
+        (self.sequencer_uptime_feed.get(), self.grace_period.get())
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
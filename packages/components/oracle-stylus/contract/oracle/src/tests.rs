@@ -0,0 +1,186 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::{SolError, SolValue};
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const FEED: Address = address!("000000000000000000000000000000000000feed");
+const SEQUENCER: Address = address!("0000000000000000000000000000000000005e90");
+const NOW: u64 = 100_000;
+const STALENESS: u64 = 3_600;
+const DEVIATION_BPS: u64 = 1_000;
+
+/// An oracle at `NOW` without a sequencer check, reading `FEED` with 8 decimals.
+fn setup() -> (TestVM, PriceOracle) {
+    let (vm, mut contract) = deploy::<PriceOracle>(OWNER);
+    vm.set_block_timestamp(NOW);
+    ok(contract.initialize(OWNER, Address::ZERO, U256::ZERO));
+    mock_decimals(&vm, FEED, 8);
+    ok(contract.set_feed(FEED, n(STALENESS), n(DEVIATION_BPS)));
+    (vm, contract)
+}
+
+fn mock_decimals(vm: &TestVM, feed: Address, decimals: u8) {
+    vm.mock_static_call(feed, IAggregatorV3::decimalsCall {}.abi_encode(), Ok(n(decimals.into()).abi_encode()));
+}
+
+fn answer(answer: i64) -> I256 {
+    I256::try_from(answer).unwrap()
+}
+
+/// `(round_id, answer, started_at, updated_at, answered_in_round)` as a feed returns it.
+fn round_data(round_id: u64, answer: I256, started_at: u64, updated_at: u64, answered_in_round: u64) -> Vec<u8> {
+    (n(round_id), answer, n(started_at), n(updated_at), n(answered_in_round)).abi_encode()
+}
+
+/// Mocks `feed`'s latest round as a complete `round_id` answering `value` at `updated_at`.
+fn mock_latest(vm: &TestVM, feed: Address, round_id: u64, value: i64, updated_at: u64) {
+    let data = round_data(round_id, answer(value), updated_at, updated_at, round_id);
+    vm.mock_static_call(feed, IAggregatorV3::latestRoundDataCall {}.abi_encode(), Ok(data));
+}
+
+/// Mocks `FEED`'s round `round_id` answering `value`.
+fn mock_round(vm: &TestVM, round_id: u64, value: i64) {
+    let call = IAggregatorV3::getRoundDataCall { round_id: alloy_primitives::Uint::<80, 2>::from(round_id) };
+    vm.mock_static_call(FEED, call.abi_encode(), Ok(round_data(round_id, answer(value), NOW, NOW, round_id)));
+}
+
+/// Mocks the sequencer uptime feed reporting `status` since `started_at`.
+fn mock_sequencer(vm: &TestVM, status: i64, started_at: u64) {
+    let data = round_data(1, answer(status), started_at, started_at, 1);
+    vm.mock_static_call(SEQUENCER, IAggregatorV3::latestRoundDataCall {}.abi_encode(), Ok(data));
+}
+
+fn price(whole: u64) -> U256 {
+    n(whole) * U256::from(10).pow(n(18))
+}
+
+#[test]
+fn initialize_runs_once() {
+    let (vm, mut contract) = deploy::<PriceOracle>(OWNER);
+    ok(contract.initialize(OWNER, SEQUENCER, n(60)));
+    assert_eq!((contract.owner(), contract.sequencer_check()), (OWNER, (SEQUENCER, n(60))));
+    let event = emitted::<SequencerCheckUpdated>(&vm).pop().expect("sequencer check logged");
+    assert_eq!((event.sequencer_uptime_feed, event.grace_period), (SEQUENCER, n(60)));
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.initialize(ALICE, Address::ZERO, U256::ZERO)), AlreadyInitialized {}.abi_encode());
+}
+
+#[test]
+fn feeds_are_registered_with_their_decimals() {
+    let (vm, mut contract) = setup();
+    assert_eq!(contract.feed_config(FEED), (true, 8, n(STALENESS), n(DEVIATION_BPS)));
+    let event = emitted::<FeedConfigured>(&vm).pop().expect("feed logged");
+    assert_eq!((event.feed, event.decimals, event.max_staleness), (FEED, 8, n(STALENESS)));
+
+    // A feed that doesn't answer `decimals` isn't a feed
+    assert_eq!(
+        reverted(contract.set_feed(ALICE, n(STALENESS), U256::ZERO)),
+        FeedCallFailed { feed: ALICE }.abi_encode()
+    );
+    mock_decimals(&vm, ALICE, 37);
+    let error = InvalidFeedConfig { feed: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_feed(ALICE, n(STALENESS), U256::ZERO)), error);
+    assert_eq!(
+        reverted(contract.set_feed(FEED, U256::ZERO, U256::ZERO)),
+        InvalidFeedConfig { feed: FEED }.abi_encode()
+    );
+
+    ok(contract.remove_feed(FEED));
+    assert!(!contract.feed_config(FEED).0);
+    assert_eq!(emitted::<FeedRemoved>(&vm).pop().expect("removal logged").feed, FEED);
+    assert_eq!(reverted(contract.latest_price(FEED)), UnknownFeed { feed: FEED }.abi_encode());
+    assert_eq!(reverted(contract.remove_feed(FEED)), UnknownFeed { feed: FEED }.abi_encode());
+}
+
+#[test]
+fn prices_are_returned_with_18_decimals() {
+    let (vm, mut contract) = setup();
+    mock_latest(&vm, FEED, 1, 200_000_000_000, NOW - 10);
+    assert_eq!(ok(contract.latest_price(FEED)), (price(2_000), n(NOW - 10)));
+
+    let precise = address!("0000000000000000000000000000000000000020");
+    mock_decimals(&vm, precise, 20);
+    ok(contract.set_feed(precise, n(STALENESS), U256::ZERO));
+    mock_latest(&vm, precise, 1, 3_000_000, NOW);
+    assert_eq!(ok(contract.latest_price(precise)), (n(30_000), n(NOW)));
+}
+
+#[test]
+fn answers_must_be_positive_complete_and_fresh() {
+    let (vm, contract) = setup();
+    assert_eq!(reverted(contract.latest_price(FEED)), FeedCallFailed { feed: FEED }.abi_encode());
+    for value in [0, -1] {
+        mock_latest(&vm, FEED, 1, value, NOW);
+        let error = InvalidAnswer { feed: FEED, answer: answer(value) }.abi_encode();
+        assert_eq!(reverted(contract.latest_price(FEED)), error);
+    }
+    for updated_at in [0, NOW - STALENESS - 1, NOW + 1] {
+        mock_latest(&vm, FEED, 1, 100, updated_at);
+        let error = StalePrice { feed: FEED, updated_at: n(updated_at) }.abi_encode();
+        assert_eq!(reverted(contract.latest_price(FEED)), error);
+    }
+    // An answer carried over from an earlier round is stale too
+    let data = round_data(3, answer(100), NOW, NOW, 2);
+    vm.mock_static_call(FEED, IAggregatorV3::latestRoundDataCall {}.abi_encode(), Ok(data));
+    assert_eq!(reverted(contract.latest_price(FEED)), StalePrice { feed: FEED, updated_at: n(NOW) }.abi_encode());
+
+    mock_latest(&vm, FEED, 1, 100, NOW - STALENESS);
+    ok(contract.latest_price(FEED));
+}
+
+#[test]
+fn answers_may_only_move_so_far_from_the_previous_round() {
+    let (vm, contract) = setup();
+    // Without a previous round to read, the answer stands
+    mock_latest(&vm, FEED, 5, 1_100, NOW);
+    assert_eq!(ok(contract.latest_price(FEED)).0, n(1_100) * U256::from(10).pow(n(10)));
+
+    mock_round(&vm, 4, 1_000);
+    ok(contract.latest_price(FEED));
+    mock_latest(&vm, FEED, 5, 899, NOW);
+    let error = PriceDeviation { feed: FEED, deviation_bps: n(1_010) }.abi_encode();
+    assert_eq!(reverted(contract.latest_price(FEED)), error);
+
+    // The first round of a phase has no predecessor
+    mock_latest(&vm, FEED, 1, 5_000, NOW);
+    ok(contract.latest_price(FEED));
+}
+
+#[test]
+fn prices_wait_for_the_sequencer_and_its_grace_period() {
+    let (vm, mut contract) = setup();
+    ok(contract.set_sequencer_check(SEQUENCER, n(3_600)));
+    mock_latest(&vm, FEED, 1, 100, NOW);
+    assert_eq!(reverted(contract.latest_price(FEED)), FeedCallFailed { feed: SEQUENCER }.abi_encode());
+
+    // Down, back up too recently, or never reported
+    for (status, started_at) in [(1, 1), (0, NOW - 3_599), (0, 0)] {
+        mock_sequencer(&vm, status, started_at);
+        assert_eq!(reverted(contract.latest_price(FEED)), SequencerDown {}.abi_encode());
+    }
+    mock_sequencer(&vm, 0, NOW - 3_600);
+    ok(contract.latest_price(FEED));
+}
+
+#[test]
+fn only_the_owner_configures_the_oracle() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_feed(FEED, n(1), U256::ZERO)), error);
+    assert_eq!(reverted(contract.remove_feed(FEED)), error);
+    assert_eq!(reverted(contract.set_sequencer_check(ALICE, U256::ZERO)), error);
+    assert_eq!(reverted(contract.transfer_ownership(ALICE)), error);
+
+    vm.set_sender(OWNER);
+    ok(contract.set_sequencer_check(SEQUENCER, n(60)));
+    let event = emitted::<SequencerCheckUpdated>(&vm).pop().expect("sequencer check logged");
+    assert_eq!((event.sequencer_uptime_feed, event.grace_period), (SEQUENCER, n(60)));
+    ok(contract.transfer_ownership(ALICE));
+    let event = emitted::<OwnershipTransferred>(&vm).pop().expect("ownership logged");
+    assert_eq!((event.previous_owner, event.new_owner), (OWNER, ALICE));
+    assert_eq!(reverted(contract.remove_feed(FEED)), Unauthorized { caller: OWNER }.abi_encode());
+}
@@ -0,0 +1,391 @@
+//! Two-party OTC basket swaps
+//!
+//! A maker offers a basket of assets for a counter-basket. Baskets mix ETH,
+//! ERC-20s and ERC-1155 token IDs, which suits treasury swaps between DAOs.
+//!
+//! Creating a swap deposits the maker's basket in escrow. The counterparty
+//! accepts by paying the counter-basket, which goes straight to the maker while
+//! the escrowed basket is released to the counterparty in the same transaction;
+//! either both sides move or neither does.
+//!
+//! The offer is firm until its expiry. After that, anyone can return the
+//! escrowed basket to the maker if the swap wasn't accepted.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*, storage::StorageVec};
+
+/// Asset kind of ETH; its token and ID are ignored.
+const KIND_ETH: u8 = 0;
+/// Asset kind of an ERC-20; its ID is ignored.
+const KIND_ERC20: u8 = 1;
+/// Asset kind of an ERC-1155 token ID.
+const KIND_ERC1155: u8 = 2;
+/// Most assets a basket can hold.
+const MAX_BASKET_SIZE: usize = 16;
+/// `status` of a swap waiting for its counterparty.
+const STATUS_OPEN: u8 = 1;
+/// `status` of an accepted swap.
+const STATUS_SETTLED: u8 = 2;
+/// `status` of a swap whose basket went back to the maker.
+const STATUS_REFUNDED: u8 = 3;
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+
+/// `(kind, token, id, amount)`, where kind is 0 for ETH, 1 for an ERC-20 and 2 for an ERC-1155.
+pub type Asset = (u8, Address, U256, U256);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct OtcEscrow {
+        /// Swaps by ID
+        mapping(uint256 => Swap) swaps;
+        /// Swaps created so far
+        uint256 swap_count;
+    }
+
+    pub struct Swap {
+        /// Deposited `give` and receives `want`
+        address maker;
+        /// Only account that can accept; zero lets anyone accept
+        address counterparty;
+        /// Last time the swap can be accepted
+        uint256 expiry;
+        /// None (0), open (1), settled (2) or refunded (3)
+        uint8 status;
+        /// Basket held in escrow for the counterparty
+        StoredAsset[] give;
+        /// Basket the counterparty pays the maker
+        StoredAsset[] want;
+    }
+
+    pub struct StoredAsset {
+        uint8 kind;
+        address token;
+        uint256 id;
+        uint256 amount;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event SwapCreated(uint256 indexed swap_id, address indexed maker, address indexed counterparty, uint256 expiry);
+    event SwapSettled(uint256 indexed swap_id, address indexed counterparty);
+    event SwapRefunded(uint256 indexed swap_id);
+
+    // Basket is empty, too large, or holds an invalid asset
+    error InvalidBasket();
+    // Expiry is in the past
+    error InvalidExpiry(uint256 expiry);
+    // ETH sent doesn't match the basket's ETH
+    error MismatchedValue(uint256 expected, uint256 msg_value);
+    // Swap isn't open
+    error SwapNotOpen(uint256 swap_id);
+    // Swap has expired
+    error SwapExpired(uint256 expiry);
+    // Swap hasn't expired yet
+    error SwapNotExpired(uint256 expiry);
+    // Caller is not the swap's counterparty
+    error Unauthorized(address caller);
+    // An asset transfer failed
+    error TransferFailed(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum OtcError {
+    InvalidBasket(InvalidBasket),
+    InvalidExpiry(InvalidExpiry),
+    MismatchedValue(MismatchedValue),
+    SwapNotOpen(SwapNotOpen),
+    SwapExpired(SwapExpired),
+    SwapNotExpired(SwapNotExpired),
+    Unauthorized(Unauthorized),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+/// ETH a basket holds, or `None` if it is empty, too large or has an invalid asset.
+fn basket_value(basket: &[Asset]) -> Option<U256> {
+    if basket.is_empty() || basket.len() > MAX_BASKET_SIZE {
+        return None;
+    }
+    let mut value = U256::ZERO;
+    for (kind, token, _, amount) in basket {
+        if *kind > KIND_ERC1155 || amount.is_zero() || (*kind != KIND_ETH && token.is_zero()) {
+            return None;
+        }
+        if *kind == KIND_ETH {
+            value += *amount;
+        }
+    }
+    Some(value)
+}
+
+/// Appends `assets` to a stored basket.
+fn store_basket(basket: &mut StorageVec<StoredAsset>, assets: &[Asset]) {
+    for &(kind, token, id, amount) in assets {
+        let mut slot = basket.grow();
+        slot.kind.set(alloy_primitives::Uint::<8, 1>::from(kind));
+        slot.token.set(token);
+        slot.id.set(id);
+        slot.amount.set(amount);
+    }
+}
+
+// Helpers used by the public methods below.
+impl OtcEscrow {
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Fails unless the swap is open and hasn't expired.
+    fn require_open(&self, swap_id: U256) -> Result<(), OtcError> {
+        let swap = self.swaps.getter(swap_id);
+        if swap.status.get().to::<u8>() != STATUS_OPEN {
+            return Err(OtcError::SwapNotOpen(SwapNotOpen { swap_id }));
+        }
+        let expiry = swap.expiry.get();
+        if self.now() > expiry {
+            return Err(OtcError::SwapExpired(SwapExpired { expiry }));
+        }
+        Ok(())
+    }
+
+    /// Output of a call to `token`, or `None` if it reverted.
+    fn call_token(&mut self, token: Address, calldata: &[u8]) -> Option<Vec<u8>> {
+        self.vm().call(&calls::context::Call::new(), token, calldata).ok()
+    }
+
+    /// Moves one asset from `from` to `to`. ETH is only ever sent by the escrow, so `from` is
+    /// ignored for it.
+    fn move_asset(&mut self, asset: Asset, from: Address, to: Address) -> Result<(), OtcError> {
+        let (kind, token, id, amount) = asset;
+        let this = self.vm().contract_address();
+        let moved = match kind {
+            KIND_ETH => self.vm().transfer_eth(to, amount).is_ok(),
+            KIND_ERC20 => {
+                let calldata = if from == this {
+                    IERC20::transferCall { to, value: amount }.abi_encode()
+                } else {
+                    IERC20::transferFromCall { from, to, value: amount }.abi_encode()
+                };
+                self.call_token(token, &calldata)
+                    .is_some_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+            }
+            _ => {
+                let data = Vec::new().into();
+                let calldata = IERC1155::safeTransferFromCall { from, to, id, value: amount, data }.abi_encode();
+                self.call_token(token, &calldata).is_some()
+            }
+        };
+        if !moved {
+            return Err(OtcError::TransferFailed(TransferFailed { token }));
+        }
+        Ok(())
+    }
+
+    fn read_basket(&self, swap_id: U256, give: bool) -> Vec<Asset> {
+        let swap = self.swaps.getter(swap_id);
+        let basket = if give { &swap.give } else { &swap.want };
+        (0..basket.len())
+            .filter_map(|i| basket.get(i))
+            .map(|asset| {
+                (
+                    asset.kind.get().to::<u8>(),
+                    asset.token.get(),
+                    asset.id.get(),
+                    asset.amount.get(),
+                )
+            })
+            .collect()
+    }
+
+    /// Releases the escrowed basket of a swap to `to`.
+    fn release(&mut self, swap_id: U256, to: Address) -> Result<(), OtcError> {
+        let this = self.vm().contract_address();
+        for asset in self.read_basket(swap_id, true) {
+            self.move_asset(asset, this, to)?;
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl OtcEscrow {
+    /// Offers `give` for `want` until `expiry`, depositing `give` in escrow. The ETH sent must equal
+    /// the ETH in `give`, and its tokens must be approved. A zero `counterparty` lets anyone accept.
+    /// Returns the swap ID.
+    #[payable]
+    pub fn create_swap(
+        &mut self,
+        counterparty: Address,
+        give: Vec<Asset>,
+        want: Vec<Asset>,
+        expiry: U256,
+    ) -> Result<U256, OtcError> {
+        let (Some(give_value), Some(_)) = (basket_value(&give), basket_value(&want)) else {
+            return Err(OtcError::InvalidBasket(InvalidBasket {}));
+        };
+        if expiry < self.now() {
+            return Err(OtcError::InvalidExpiry(InvalidExpiry { expiry }));
+        }
+        let msg_value = self.vm().msg_value();
+        if msg_value != give_value {
+            return Err(OtcError::MismatchedValue(MismatchedValue {
+                expected: give_value,
+                msg_value,
+            }));
+        }
+
+        let maker = self.vm().msg_sender();
+        let swap_id = self.swap_count.get();
+        self.swap_count.set(swap_id + U256::from(1));
+        let mut swap = self.swaps.setter(swap_id);
+        swap.maker.set(maker);
+        swap.counterparty.set(counterparty);
+        swap.expiry.set(expiry);
+        swap.status.set(alloy_primitives::Uint::<8, 1>::from(STATUS_OPEN));
+        store_basket(&mut swap.give, &give);
+        store_basket(&mut swap.want, &want);
+
+        let this = self.vm().contract_address();
+        for asset in give {
+            if asset.0 != KIND_ETH {
+                self.move_asset(asset, maker, this)?;
+            }
+        }
+
+        log(self.vm(), SwapCreated {
+            swap_id,
+            maker,
+            counterparty,
+            expiry,
+        });
+        Ok(swap_id)
+    }
+
+    /// Accepts a swap: pays the counter-basket to the maker and receives the escrowed basket. The
+    /// ETH sent must equal the ETH in the counter-basket, and its tokens must be approved.
+    #[payable]
+    pub fn accept(&mut self, swap_id: U256) -> Result<(), OtcError> {
+        self.require_open(swap_id)?;
+        let caller = self.vm().msg_sender();
+        let swap = self.swaps.getter(swap_id);
+        let maker = swap.maker.get();
+        let counterparty = swap.counterparty.get();
+        if !counterparty.is_zero() && caller != counterparty {
+            return Err(OtcError::Unauthorized(Unauthorized { caller }));
+        }
+        let want = self.read_basket(swap_id, false);
+        let want_value = basket_value(&want).unwrap_or_default();
+        let msg_value = self.vm().msg_value();
+        if msg_value != want_value {
+            return Err(OtcError::MismatchedValue(MismatchedValue {
+                expected: want_value,
+                msg_value,
+            }));
+        }
+
+        self.swaps
+            .setter(swap_id)
+            .status
+            .set(alloy_primitives::Uint::<8, 1>::from(STATUS_SETTLED));
+        for asset in want {
+            self.move_asset(asset, caller, maker)?;
+        }
+        self.release(swap_id, caller)?;
+
+        log(self.vm(), SwapSettled {
+            swap_id,
+            counterparty: caller,
+        });
+        Ok(())
+    }
+
+    /// Returns the escrowed basket of an expired, unaccepted swap to its maker. Anyone can call it.
+    pub fn refund(&mut self, swap_id: U256) -> Result<(), OtcError> {
+        let swap = self.swaps.getter(swap_id);
+        if swap.status.get().to::<u8>() != STATUS_OPEN {
+            return Err(OtcError::SwapNotOpen(SwapNotOpen { swap_id }));
+        }
+        let expiry = swap.expiry.get();
+        if self.now() <= expiry {
+            return Err(OtcError::SwapNotExpired(SwapNotExpired { expiry }));
+        }
+        let maker = swap.maker.get();
+
+        self.swaps
+            .setter(swap_id)
+            .status
+            .set(alloy_primitives::Uint::<8, 1>::from(STATUS_REFUNDED));
+        self.release(swap_id, maker)?;
+
+        log(self.vm(), SwapRefunded { swap_id });
+        Ok(())
+    }
+
+    /// Returns `(maker, counterparty, expiry, status)`; status is 1 open, 2 settled, 3 refunded.
+    pub fn get_swap(&self, swap_id: U256) -> (Address, Address, U256, u8) {
+        let swap = self.swaps.getter(swap_id);
+        (
+            swap.maker.get(),
+            swap.counterparty.get(),
+            swap.expiry.get(),
+            swap.status.get().to::<u8>(),
+        )
+    }
+
+    /// Basket held in escrow for the counterparty.
+    pub fn give_basket(&self, swap_id: U256) -> Vec<Asset> {
+        self.read_basket(swap_id, true)
+    }
+
+    /// Basket the counterparty pays the maker.
+    pub fn want_basket(&self, swap_id: U256) -> Vec<Asset> {
+        self.read_basket(swap_id, false)
+    }
+
+    /// Number of swaps created.
+    pub fn swap_count(&self) -> U256 {
+        self.swap_count.get()
+    }
+
+    /// Accepts tokens the escrow pulls itself and rejects any sent to it directly.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes::from(ERC1155_RECEIVED.to_be_bytes())
+        } else {
+            FixedBytes::ZERO
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const CAROL: Address = address!("000000000000000000000000000000000000ca01");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const TICKETS: Address = address!("0000000000000000000000000000000000001155");
+
+fn eth(amount: u64) -> Asset {
+    (KIND_ETH, Address::ZERO, U256::ZERO, n(amount))
+}
+
+fn usdc(amount: u64) -> Asset {
+    (KIND_ERC20, USDC, U256::ZERO, n(amount))
+}
+
+fn tickets(amount: u64) -> Asset {
+    (KIND_ERC1155, TICKETS, n(7), n(amount))
+}
+
+/// Lets `amount` USDC move from `from`, or from the escrow if that is the escrow, to `to`.
+fn expect_usdc(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    let calldata = if from == vm.contract_address() {
+        IERC20::transferCall { to, value: n(amount) }.abi_encode()
+    } else {
+        IERC20::transferFromCall { from, to, value: n(amount) }.abi_encode()
+    };
+    vm.mock_call(USDC, calldata, Ok(true.abi_encode()));
+}
+
+/// Lets `amount` tickets move from `from` to `to`.
+fn expect_tickets(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    let call = IERC1155::safeTransferFromCall { from, to, id: n(7), value: n(amount), data: Vec::new().into() };
+    vm.mock_call(TICKETS, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// An escrow at time 1,000, called by Alice.
+fn setup() -> (TestVM, OtcEscrow) {
+    let (vm, contract) = deploy::<OtcEscrow>(ALICE);
+    vm.set_block_timestamp(1_000);
+    (vm, contract)
+}
+
+#[test]
+fn accepted_swaps_pay_the_maker_and_release_the_escrow() {
+    let (vm, mut contract) = setup();
+    let this = vm.contract_address();
+    expect_tickets(&vm, ALICE, this, 10);
+    vm.set_value(n(500));
+    vm.set_balance(this, n(500));
+    assert_eq!(
+        ok(contract.create_swap(Address::ZERO, vec![eth(500), tickets(10)], vec![usdc(1_000)], n(2_000))),
+        U256::ZERO
+    );
+    assert_eq!(contract.give_basket(U256::ZERO), vec![eth(500), tickets(10)]);
+    assert_eq!(emitted::<SwapCreated>(&vm)[0].maker, ALICE);
+
+    vm.set_sender(BOB);
+    vm.set_value(U256::ZERO);
+    expect_usdc(&vm, BOB, ALICE, 1_000);
+    expect_tickets(&vm, this, BOB, 10);
+    ok(contract.accept(U256::ZERO));
+    assert_eq!(vm.balance(BOB), n(500));
+    assert_eq!(contract.get_swap(U256::ZERO), (ALICE, Address::ZERO, n(2_000), STATUS_SETTLED));
+    assert_eq!(emitted::<SwapSettled>(&vm)[0].counterparty, BOB);
+    assert!(matches!(contract.accept(U256::ZERO), Err(OtcError::SwapNotOpen(_))));
+}
+
+#[test]
+fn unaccepted_swaps_are_refunded_after_expiry() {
+    let (vm, mut contract) = setup();
+    let this = vm.contract_address();
+    expect_usdc(&vm, ALICE, this, 100);
+    ok(contract.create_swap(BOB, vec![usdc(100)], vec![eth(1_000)], n(2_000)));
+    vm.set_sender(CAROL);
+    let result = contract.accept(U256::ZERO);
+    assert!(matches!(result, Err(OtcError::Unauthorized(e)) if e.caller == CAROL));
+    vm.set_sender(BOB);
+    vm.set_value(n(999));
+    let result = contract.accept(U256::ZERO);
+    assert!(matches!(result, Err(OtcError::MismatchedValue(e)) if e.expected == n(1_000)));
+    let result = contract.refund(U256::ZERO);
+    assert!(matches!(result, Err(OtcError::SwapNotExpired(e)) if e.expiry == n(2_000)));
+
+    vm.set_block_timestamp(2_001);
+    let result = contract.accept(U256::ZERO);
+    assert!(matches!(result, Err(OtcError::SwapExpired(e)) if e.expiry == n(2_000)));
+    expect_usdc(&vm, this, ALICE, 100);
+    ok(contract.refund(U256::ZERO));
+    assert_eq!(contract.get_swap(U256::ZERO).3, STATUS_REFUNDED);
+    assert_eq!(emitted::<SwapRefunded>(&vm)[0].swap_id, U256::ZERO);
+}
+
+#[test]
+fn baskets_expiries_and_deposits_are_checked() {
+    let (_vm, mut contract) = setup();
+    let result = contract.create_swap(BOB, Vec::new(), vec![usdc(1)], n(2_000));
+    assert!(matches!(result, Err(OtcError::InvalidBasket(_))));
+    let result = contract.create_swap(BOB, vec![usdc(0)], vec![usdc(1)], n(2_000));
+    assert!(matches!(result, Err(OtcError::InvalidBasket(_))));
+    let result = contract.create_swap(BOB, vec![(KIND_ERC20, Address::ZERO, U256::ZERO, n(1))], vec![eth(1)], n(2_000));
+    assert!(matches!(result, Err(OtcError::InvalidBasket(_))));
+    let result = contract.create_swap(BOB, vec![usdc(1)], vec![eth(1)], n(999));
+    assert!(matches!(result, Err(OtcError::InvalidExpiry(e)) if e.expiry == n(999)));
+    let result = contract.create_swap(BOB, vec![eth(10)], vec![usdc(1)], n(2_000));
+    assert!(matches!(result, Err(OtcError::MismatchedValue(e)) if e.expected == n(10) && e.msg_value.is_zero()));
+
+    // A maker whose deposit doesn't go through opens no swap
+    let result = contract.create_swap(BOB, vec![usdc(100)], vec![eth(1)], n(2_000));
+    assert!(matches!(result, Err(OtcError::TransferFailed(e)) if e.token == USDC));
+}
+
+#[test]
+fn unpaid_counter_baskets_revert() {
+    let (vm, mut contract) = setup();
+    expect_tickets(&vm, ALICE, vm.contract_address(), 10);
+    ok(contract.create_swap(Address::ZERO, vec![tickets(10)], vec![usdc(1_000)], n(2_000)));
+    vm.set_sender(BOB);
+    let result = contract.accept(U256::ZERO);
+    assert!(matches!(result, Err(OtcError::TransferFailed(e)) if e.token == USDC));
+}
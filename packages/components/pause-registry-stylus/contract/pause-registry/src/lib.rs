@@ -0,0 +1,197 @@
+//! Deployment-wide pause registry
+//!
+//! One switch for every contract of a protocol deployment. Contracts opt in by storing the
+//! registry's address and asking `is_paused(self)` before their pausable actions, through
+//! `cradle-pausable`'s `require_not_paused_with` or `registry_pauses`, so a single `pause_all`
+//! stops the token, the marketplace and the staking pool in the same transaction.
+//!
+//! Guardians, incident responders the owner appoints, can pause the whole deployment or a single
+//! contract but never unpause. The owner unpauses, and overrides single contracts: paused whatever
+//! the switch says, or exempt from it, such as a contract whose withdrawals must stay open.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::{Address, FixedBytes, Uint};
+use alloy_sol_types::sol;
+use cradle_introspection::{interface, Interface};
+use cradle_ownable::{Ownable, OwnableError, OwnableInvalidOwner, OwnableUnauthorizedAccount, IERC173, IERC5313};
+use stylus_sdk::prelude::*;
+
+/// The registry's view, `isPaused(address)`, which consulting contracts call.
+pub const IPAUSE_REGISTRY: Interface = interface!("isPaused"(Address));
+
+/// A contract follows the deployment's switch.
+pub const OVERRIDE_NONE: u8 = 0;
+/// A contract is paused whatever the switch says.
+pub const OVERRIDE_PAUSED: u8 = 1;
+/// A contract is never paused by the switch.
+pub const OVERRIDE_EXEMPT: u8 = 2;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct PauseRegistry {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Appoints guardians, unpauses and sets overrides
+        #[borrow]
+        Ownable ownable;
+        /// Whether the whole deployment is paused
+        bool paused;
+        /// Accounts that may pause
+        mapping(address => bool) guardians;
+        /// `OVERRIDE_*` mode of each contract
+        mapping(address => uint8) overrides;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event ProtocolPaused(address account);
+    event ProtocolUnpaused(address account);
+    event GuardianSet(address indexed account, bool allowed);
+    event OverrideSet(address indexed target, uint8 mode, address account);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is neither a guardian nor the owner
+    error NotGuardian(address caller);
+    // The deployment is already paused
+    error AlreadyPaused();
+    // The deployment isn't paused
+    error NotPaused();
+    // Mode isn't one of the `OVERRIDE_*` values
+    error InvalidOverride(uint8 mode);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum PauseRegistryError {
+    AlreadyInitialized(AlreadyInitialized),
+    NotGuardian(NotGuardian),
+    AlreadyPaused(AlreadyPaused),
+    NotPaused(NotPaused),
+    InvalidOverride(InvalidOverride),
+    OwnableUnauthorizedAccount(OwnableUnauthorizedAccount),
+    OwnableInvalidOwner(OwnableInvalidOwner),
+}
+
+impl From<OwnableError> for PauseRegistryError {
+    fn from(error: OwnableError) -> Self {
+        match error {
+            OwnableError::UnauthorizedAccount(e) => PauseRegistryError::OwnableUnauthorizedAccount(e),
+            OwnableError::InvalidOwner(e) => PauseRegistryError::OwnableInvalidOwner(e),
+        }
+    }
+}
+
+// Helpers used by the public methods below.
+impl PauseRegistry {
+    fn only_guardian(&self) -> Result<Address, PauseRegistryError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.ownable.owner() && !self.guardians.get(caller) {
+            return Err(PauseRegistryError::NotGuardian(NotGuardian { caller }));
+        }
+        Ok(caller)
+    }
+
+    fn set_mode(&mut self, target: Address, mode: u8, account: Address) {
+        self.overrides.insert(target, Uint::<8, 1>::from(mode));
+        log(self.vm(), OverrideSet { target, mode, account });
+    }
+}
+
+#[public]
+#[inherit(Ownable)]
+impl PauseRegistry {
+    /// Sets the owner. Can only be called once.
+    pub fn initialize(&mut self, owner: Address) -> Result<(), PauseRegistryError> {
+        if self.initialized.get() {
+            return Err(PauseRegistryError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.ownable.initialize(owner)?;
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// Lets `account` pause, or stops it (owner only).
+    pub fn set_guardian(&mut self, account: Address, allowed: bool) -> Result<(), PauseRegistryError> {
+        self.ownable.require_owner()?;
+        self.guardians.insert(account, allowed);
+        log(self.vm(), GuardianSet { account, allowed });
+        Ok(())
+    }
+
+    /// Pauses every contract consulting the registry, except exempt ones (guardians or owner).
+    pub fn pause_all(&mut self) -> Result<(), PauseRegistryError> {
+        let account = self.only_guardian()?;
+        if self.paused.get() {
+            return Err(PauseRegistryError::AlreadyPaused(AlreadyPaused {}));
+        }
+        self.paused.set(true);
+        log(self.vm(), ProtocolPaused { account });
+        Ok(())
+    }
+
+    /// Lifts the deployment-wide pause; overrides stay (owner only).
+    pub fn unpause_all(&mut self) -> Result<(), PauseRegistryError> {
+        self.ownable.require_owner()?;
+        if !self.paused.get() {
+            return Err(PauseRegistryError::NotPaused(NotPaused {}));
+        }
+        self.paused.set(false);
+        log(self.vm(), ProtocolUnpaused { account: self.vm().msg_sender() });
+        Ok(())
+    }
+
+    /// Pauses `target` alone, whatever the switch says (guardians or owner).
+    pub fn pause(&mut self, target: Address) -> Result<(), PauseRegistryError> {
+        let account = self.only_guardian()?;
+        self.set_mode(target, OVERRIDE_PAUSED, account);
+        Ok(())
+    }
+
+    /// Sets `target`'s `OVERRIDE_*` mode: following the switch, paused or exempt (owner only).
+    pub fn set_override(&mut self, target: Address, mode: u8) -> Result<(), PauseRegistryError> {
+        self.ownable.require_owner()?;
+        if mode > OVERRIDE_EXEMPT {
+            return Err(PauseRegistryError::InvalidOverride(InvalidOverride { mode }));
+        }
+        self.set_mode(target, mode, self.vm().msg_sender());
+        Ok(())
+    }
+
+    /// Whether `target` is paused, by its override or else by the deployment's switch.
+    pub fn is_paused(&self, target: Address) -> bool {
+        match self.override_of(target) {
+            OVERRIDE_PAUSED => true,
+            OVERRIDE_EXEMPT => false,
+            _ => self.paused.get(),
+        }
+    }
+
+    /// Whether the whole deployment is paused, before overrides.
+    pub fn paused_all(&self) -> bool {
+        self.paused.get()
+    }
+
+    /// `target`'s `OVERRIDE_*` mode.
+    pub fn override_of(&self, target: Address) -> u8 {
+        self.overrides.get(target).to::<u8>()
+    }
+
+    /// Whether `account` may pause.
+    pub fn is_guardian(&self, account: Address) -> bool {
+        self.guardians.get(account)
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IPAUSE_REGISTRY, IERC173, IERC5313])
+    }
+}
@@ -0,0 +1,74 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const GUARDIAN: Address = address!("000000000000000000000000000000000000ca5d");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const TOKEN: Address = address!("0000000000000000000000000000000000007043");
+const MARKET: Address = address!("000000000000000000000000000000000000a4e7");
+const STAKING: Address = address!("0000000000000000000000000000000000005a4e");
+
+/// A registry owned by `OWNER` with `GUARDIAN` appointed.
+fn setup() -> (TestVM, PauseRegistry) {
+    let (vm, mut contract) = deploy::<PauseRegistry>(OWNER);
+    ok(contract.initialize(OWNER));
+    ok(contract.set_guardian(GUARDIAN, true));
+    (vm, contract)
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IPAUSE_REGISTRY.id(), 0x5b14f183);
+    assert_eq!(IERC173.id(), 0x7f5828d0);
+    assert_eq!(IERC5313.id(), 0x8da5cb5b);
+}
+
+#[test]
+fn guardians_pause_the_whole_deployment_and_only_the_owner_unpauses() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.pause_all(), Err(PauseRegistryError::NotGuardian(e)) if e.caller == ALICE));
+
+    vm.set_sender(GUARDIAN);
+    ok(contract.pause_all());
+    assert_eq!(emitted::<ProtocolPaused>(&vm)[0].account, GUARDIAN);
+    assert!([TOKEN, MARKET, STAKING].iter().all(|&target| contract.is_paused(target)));
+    assert!(matches!(contract.pause_all(), Err(PauseRegistryError::AlreadyPaused(_))));
+    let result = contract.unpause_all();
+    assert!(matches!(result, Err(PauseRegistryError::OwnableUnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.unpause_all());
+    assert!(!contract.paused_all() && !contract.is_paused(TOKEN));
+    assert!(matches!(contract.unpause_all(), Err(PauseRegistryError::NotPaused(_))));
+
+    ok(contract.set_guardian(GUARDIAN, false));
+    vm.set_sender(GUARDIAN);
+    assert!(matches!(contract.pause_all(), Err(PauseRegistryError::NotGuardian(_))));
+}
+
+#[test]
+fn overrides_pause_or_exempt_single_contracts() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(GUARDIAN);
+    ok(contract.pause(MARKET));
+    assert!(contract.is_paused(MARKET) && !contract.is_paused(TOKEN));
+    assert_eq!(contract.override_of(MARKET), OVERRIDE_PAUSED);
+    let result = contract.set_override(MARKET, OVERRIDE_NONE);
+    assert!(matches!(result, Err(PauseRegistryError::OwnableUnauthorizedAccount(_))));
+
+    vm.set_sender(OWNER);
+    ok(contract.set_override(STAKING, OVERRIDE_EXEMPT));
+    ok(contract.pause_all());
+    assert_eq!((contract.is_paused(TOKEN), contract.is_paused(STAKING)), (true, false));
+    // Lifting the switch leaves single pauses in place
+    ok(contract.unpause_all());
+    assert!(contract.is_paused(MARKET));
+    ok(contract.set_override(MARKET, OVERRIDE_NONE));
+    assert!(!contract.is_paused(MARKET));
+    assert_eq!(emitted::<OverrideSet>(&vm).len(), 3);
+    let result = contract.set_override(MARKET, 3);
+    assert!(matches!(result, Err(PauseRegistryError::InvalidOverride(e)) if e.mode == 3));
+}
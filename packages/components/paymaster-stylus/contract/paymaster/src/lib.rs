@@ -0,0 +1,522 @@
+//! ERC-4337 verifying paymaster
+//!
+//! Pays for user operations that carry an EIP-712 sponsorship signed by the
+//! Cradle backend, so players can mint and transfer items without holding ETH.
+//! Gas is paid from the paymaster's deposit at the EntryPoint, which the owner
+//! tops up.
+//!
+//! Each sender may spend at most `spend_limit` wei of sponsored gas per period.
+//! The ordinary ERC-4337 validation rules don't allow reading the block time,
+//! so the period of an operation is taken from the `valid_after` time the
+//! backend signed. Validation reserves the operation's maximum cost against the
+//! sender's limit and `post_op` releases whatever wasn't spent.
+//!
+//! `paymasterAndData` is laid out as:
+//!
+//! - bytes 0..52: paymaster address and gas limits, as defined by EntryPoint v0.7
+//! - bytes 52..116: `abi.encode(uint48 valid_until, uint48 valid_after)`
+//! - bytes 116..: the backend's 65-byte signature
+//!
+//! Senders with gas credits from `gas-station-stylus` pay for their own operations instead:
+//! `paymasterAndData` stops after the gas limits, validation reserves the maximum cost of the
+//! credits and `post_op` spends the actual cost of them, releasing the rest. The ETH behind the
+//! spent credits is owed to the paymaster and can be added back to its deposit with the gas
+//! station's `depositEarnings`.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_crypto::{domain_separator, hash_typed_data, recover};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain.
+const DOMAIN_NAME: &str = "Cradle Paymaster";
+/// Version in the EIP-712 domain.
+const DOMAIN_VERSION: &str = "1";
+/// `keccak256("SponsoredUserOperation(address sender,uint256 nonce,bytes32 initCodeHash,bytes32 callDataHash,bytes32 accountGasLimits,bytes32 paymasterGasLimits,uint256 preVerificationGas,bytes32 gasFees,uint48 validUntil,uint48 validAfter)")`
+const SPONSORED_USER_OPERATION_TYPEHASH: B256 =
+    b256!("29ba142ab49f8c4980376c8e4387ed194fc3f27834b35d98a8a851a983a4dfd1");
+/// Start of the paymaster-specific data in `paymasterAndData`.
+const PAYMASTER_DATA_OFFSET: usize = 52;
+/// Start of the signature in `paymasterAndData`.
+const SIGNATURE_OFFSET: usize = PAYMASTER_DATA_OFFSET + 64;
+/// Bit set in the validation data when the signature is invalid.
+const SIG_VALIDATION_FAILED: u64 = 1;
+/// Length of the `post_op` context of an operation paid with gas credits, `abi.encode(sender, max_cost)`.
+const GAS_CREDITS_CONTEXT_LENGTH: usize = 64;
+/// Largest value of a `uint48`.
+const MAX_UINT48: u64 = (1 << 48) - 1;
+
+/// ERC-4337 v0.7 `PackedUserOperation`: `(sender, nonce, initCode, callData, accountGasLimits,
+/// preVerificationGas, gasFees, paymasterAndData, signature)`.
+pub type PackedUserOperation = (Address, U256, Bytes, Bytes, B256, U256, B256, Bytes, Bytes);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Paymaster {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Manages the signer, limits and deposit
+        address owner;
+        /// EntryPoint this paymaster sponsors operations for
+        address entry_point;
+        /// Backend key whose signatures are accepted
+        address verifying_signer;
+        /// Most gas in wei a sender may be sponsored for per period
+        uint256 spend_limit;
+        /// Length of a spend period in seconds
+        uint256 period_length;
+        /// Sponsored gas per sender in their latest period
+        mapping(address => SenderSpend) spending;
+        /// Gas station whose credits senders may pay with, zero to accept sponsorships only
+        address gas_station;
+    }
+
+    pub struct SenderSpend {
+        /// Index of the period `spent` belongs to
+        uint256 period;
+        /// Wei spent or reserved in that period
+        uint256 spent;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event VerifyingSignerUpdated(address indexed verifying_signer);
+    event SpendLimitUpdated(uint256 spend_limit, uint256 period_length);
+    event UserOperationSponsored(address indexed sender, uint256 period, uint256 actual_gas_cost);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event GasStationUpdated(address indexed gas_station);
+    event UserOperationPaidWithCredits(address indexed sender, uint256 actual_gas_cost, uint256 charged);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // paymasterAndData is too short to hold the validity window and a signature
+    error InvalidPaymasterData(uint256 length);
+    // Sponsoring the operation would take the sender over their spend limit for the period
+    error SpendLimitExceeded(address sender, uint256 period, uint256 requested, uint256 remaining);
+    // Period length is zero
+    error InvalidPeriod();
+    // A call to the EntryPoint failed
+    error EntryPointCallFailed();
+    // No gas station is set, or it didn't reserve or settle the sender's credits
+    error GasCreditsUnavailable(address sender);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum PaymasterError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidPaymasterData(InvalidPaymasterData),
+    SpendLimitExceeded(SpendLimitExceeded),
+    InvalidPeriod(InvalidPeriod),
+    EntryPointCallFailed(EntryPointCallFailed),
+    GasCreditsUnavailable(GasCreditsUnavailable),
+}
+
+// External interfaces
+sol! {
+    interface IEntryPoint {
+        function withdrawTo(address withdraw_address, uint256 withdraw_amount) external;
+        function unlockStake() external;
+        function withdrawStake(address withdraw_address) external;
+        function balanceOf(address account) external view returns (uint256);
+    }
+
+    interface IGasStation {
+        function reserve(address account, uint256 amount) external;
+        function settle(address account, uint256 reserved, uint256 actual_cost, address beneficiary) external returns (uint256);
+    }
+}
+
+sol! {
+    /// EntryPoint methods that take ETH, called with the value sent to the paymaster.
+    interface IEntryPointPayable {
+        function depositTo(address account) external payable;
+        function addStake(uint32 unstake_delay_sec) external payable;
+    }
+}
+
+// Helpers used by the public methods below.
+impl Paymaster {
+    fn only_owner(&self) -> Result<(), PaymasterError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(PaymasterError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn only_entry_point(&self) -> Result<(), PaymasterError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.entry_point.get() {
+            return Err(PaymasterError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn set_spend_limit_inner(&mut self, spend_limit: U256, period_length: U256) -> Result<(), PaymasterError> {
+        if period_length.is_zero() {
+            return Err(PaymasterError::InvalidPeriod(InvalidPeriod {}));
+        }
+        self.spend_limit.set(spend_limit);
+        self.period_length.set(period_length);
+
+        log(self.vm(), SpendLimitUpdated {
+            spend_limit,
+            period_length,
+        });
+        Ok(())
+    }
+
+    /// EIP-712 digest the backend signs to sponsor `user_op` between `valid_after` and `valid_until`.
+    fn sponsorship_digest(&self, user_op: &PackedUserOperation, valid_until: u64, valid_after: u64) -> B256 {
+        let (sender, nonce, init_code, call_data, account_gas_limits, pre_verification_gas, gas_fees, paymaster_and_data, _) =
+            user_op;
+        let paymaster_gas_limits = paymaster_and_data
+            .get(20..PAYMASTER_DATA_OFFSET)
+            .map(B256::from_slice)
+            .unwrap_or_default();
+        let struct_hash = self.vm().native_keccak256(
+            &(
+                SPONSORED_USER_OPERATION_TYPEHASH,
+                *sender,
+                *nonce,
+                self.vm().native_keccak256(init_code),
+                self.vm().native_keccak256(call_data),
+                *account_gas_limits,
+                paymaster_gas_limits,
+                *pre_verification_gas,
+                *gas_fees,
+                U256::from(valid_until),
+                U256::from(valid_after),
+            )
+                .abi_encode(),
+        );
+        hash_typed_data(self.vm(), domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION), struct_hash)
+    }
+
+    /// Decodes `(valid_until, valid_after, signature)` from `paymasterAndData`.
+    fn parse_paymaster_data(paymaster_and_data: &[u8]) -> Result<(U256, U256, &[u8]), PaymasterError> {
+        if paymaster_and_data.len() < SIGNATURE_OFFSET {
+            return Err(PaymasterError::InvalidPaymasterData(InvalidPaymasterData {
+                length: U256::from(paymaster_and_data.len()),
+            }));
+        }
+        let valid_until = U256::from_be_slice(&paymaster_and_data[PAYMASTER_DATA_OFFSET..PAYMASTER_DATA_OFFSET + 32]);
+        let valid_after = U256::from_be_slice(&paymaster_and_data[PAYMASTER_DATA_OFFSET + 32..SIGNATURE_OFFSET]);
+        Ok((valid_until, valid_after, &paymaster_and_data[SIGNATURE_OFFSET..]))
+    }
+
+    /// Calls the EntryPoint with `calldata`, attaching `value` wei.
+    fn call_entry_point(&mut self, value: U256, calldata: &[u8]) -> Result<(), PaymasterError> {
+        let context = calls::context::Call::new().value(value);
+        self.vm()
+            .call(&context, self.entry_point.get(), calldata)
+            .map(|_| ())
+            .map_err(|_| PaymasterError::EntryPointCallFailed(EntryPointCallFailed {}))
+    }
+
+    /// Wei `sender` has spent or reserved in `period`.
+    fn spent_in(&self, sender: Address, period: U256) -> U256 {
+        let spend = self.spending.getter(sender);
+        if spend.period.get() == period {
+            spend.spent.get()
+        } else {
+            U256::ZERO
+        }
+    }
+
+    /// Reserves `max_cost` of `sender`'s gas credits, for an operation without a sponsorship.
+    fn reserve_gas_credits(&mut self, sender: Address, max_cost: U256) -> Result<(Bytes, U256), PaymasterError> {
+        let gas_station = self.gas_station.get();
+        if gas_station.is_zero() {
+            return Err(PaymasterError::GasCreditsUnavailable(GasCreditsUnavailable { sender }));
+        }
+        let reserve = IGasStation::reserveCall { account: sender, amount: max_cost };
+        self.vm()
+            .call(&calls::context::Call::new(), gas_station, &reserve.abi_encode())
+            .map_err(|_| PaymasterError::GasCreditsUnavailable(GasCreditsUnavailable { sender }))?;
+        let context = (sender, max_cost).abi_encode();
+        Ok((context.into(), U256::ZERO))
+    }
+
+    /// Spends the actual cost of the credits reserved in `context` for the paymaster, and
+    /// releases the rest.
+    fn settle_gas_credits(&mut self, context: &[u8], actual_gas_cost: U256) -> Result<(), PaymasterError> {
+        let sender = Address::from_slice(&context[12..32]);
+        let max_cost = U256::from_be_slice(&context[32..64]);
+        let settle = IGasStation::settleCall {
+            account: sender,
+            reserved: max_cost,
+            actual_cost: actual_gas_cost,
+            beneficiary: self.vm().contract_address(),
+        };
+        let charged = self
+            .vm()
+            .call(&calls::context::Call::new(), self.gas_station.get(), &settle.abi_encode())
+            .ok()
+            .and_then(|output| IGasStation::settleCall::abi_decode_returns(&output, true).ok())
+            .ok_or(PaymasterError::GasCreditsUnavailable(GasCreditsUnavailable { sender }))?
+            ._0;
+
+        log(self.vm(), UserOperationPaidWithCredits {
+            sender,
+            actual_gas_cost,
+            charged,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl Paymaster {
+    /// Sets the owner, EntryPoint, backend signer and spend limit. Can only be called once.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        entry_point: Address,
+        verifying_signer: Address,
+        spend_limit: U256,
+        period_length: U256,
+    ) -> Result<(), PaymasterError> {
+        if self.initialized.get() {
+            return Err(PaymasterError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.entry_point.set(entry_point);
+        self.verifying_signer.set(verifying_signer);
+        self.set_spend_limit_inner(spend_limit, period_length)?;
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        log(self.vm(), VerifyingSignerUpdated { verifying_signer });
+        Ok(())
+    }
+
+    /// Checks the backend's sponsorship and reserves the operation's maximum cost against the
+    /// sender's limit (EntryPoint only). Returns `(context, validation_data)`; an invalid signature
+    /// sets the failure bit instead of reverting.
+    pub fn validate_paymaster_user_op(
+        &mut self,
+        user_op: PackedUserOperation,
+        _user_op_hash: B256,
+        max_cost: U256,
+    ) -> Result<(Bytes, U256), PaymasterError> {
+        self.only_entry_point()?;
+        if user_op.7.len() == PAYMASTER_DATA_OFFSET {
+            return self.reserve_gas_credits(user_op.0, max_cost);
+        }
+        let (valid_until, valid_after, signature) = Self::parse_paymaster_data(&user_op.7)?;
+        let max_time = U256::from(MAX_UINT48);
+        let valid_window = valid_until <= max_time && valid_after <= max_time;
+        let signed = valid_window && {
+            let digest = self.sponsorship_digest(&user_op, valid_until.to::<u64>(), valid_after.to::<u64>());
+            let signer = recover(self.vm(), digest, signature);
+            !signer.is_zero() && signer == self.verifying_signer.get()
+        };
+        let validation_data = (valid_until & max_time) << 160 | (valid_after & max_time) << 208;
+        if !signed {
+            return Ok((Vec::new().into(), validation_data | U256::from(SIG_VALIDATION_FAILED)));
+        }
+
+        let sender = user_op.0;
+        let period = valid_after / self.period_length.get();
+        let spent = self.spent_in(sender, period);
+        let remaining = self.spend_limit.get().saturating_sub(spent);
+        if max_cost > remaining {
+            return Err(PaymasterError::SpendLimitExceeded(SpendLimitExceeded {
+                sender,
+                period,
+                requested: max_cost,
+                remaining,
+            }));
+        }
+        let mut spend = self.spending.setter(sender);
+        spend.period.set(period);
+        spend.spent.set(spent + max_cost);
+
+        let context = (sender, period, max_cost).abi_encode();
+        Ok((context.into(), validation_data))
+    }
+
+    /// Releases the part of the reserved cost or credits that wasn't spent (EntryPoint only).
+    pub fn post_op(
+        &mut self,
+        _mode: u8,
+        context: Bytes,
+        actual_gas_cost: U256,
+        _actual_user_op_fee_per_gas: U256,
+    ) -> Result<(), PaymasterError> {
+        self.only_entry_point()?;
+        if context.len() == GAS_CREDITS_CONTEXT_LENGTH {
+            return self.settle_gas_credits(&context, actual_gas_cost);
+        }
+        if context.len() < 96 {
+            return Err(PaymasterError::InvalidPaymasterData(InvalidPaymasterData {
+                length: U256::from(context.len()),
+            }));
+        }
+        let sender = Address::from_slice(&context[12..32]);
+        let period = U256::from_be_slice(&context[32..64]);
+        let max_cost = U256::from_be_slice(&context[64..96]);
+
+        // The sender's spend only moves to a new period after this reservation was made
+        let spend = self.spending.getter(sender);
+        if spend.period.get() == period {
+            let unused = max_cost.saturating_sub(actual_gas_cost);
+            let spent = spend.spent.get().saturating_sub(unused);
+            self.spending.setter(sender).spent.set(spent);
+        }
+
+        log(self.vm(), UserOperationSponsored {
+            sender,
+            period,
+            actual_gas_cost,
+        });
+        Ok(())
+    }
+
+    /// Replaces the backend key whose sponsorships are accepted (owner only).
+    pub fn set_verifying_signer(&mut self, verifying_signer: Address) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        self.verifying_signer.set(verifying_signer);
+
+        log(self.vm(), VerifyingSignerUpdated { verifying_signer });
+        Ok(())
+    }
+
+    /// Sets the per-sender spend limit and the period it applies to (owner only).
+    /// Changing the period length starts every sender in a fresh period.
+    pub fn set_spend_limit(&mut self, spend_limit: U256, period_length: U256) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        self.set_spend_limit_inner(spend_limit, period_length)
+    }
+
+    /// Adds the ETH sent to the paymaster's deposit at the EntryPoint.
+    #[payable]
+    pub fn deposit(&mut self) -> Result<(), PaymasterError> {
+        let account = self.vm().contract_address();
+        let calldata = IEntryPointPayable::depositToCall { account }.abi_encode();
+        self.call_entry_point(self.vm().msg_value(), &calldata)
+    }
+
+    /// Withdraws from the paymaster's deposit at the EntryPoint (owner only).
+    pub fn withdraw_to(&mut self, withdraw_address: Address, amount: U256) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        let calldata = IEntryPoint::withdrawToCall { withdraw_address, withdraw_amount: amount }.abi_encode();
+        self.call_entry_point(U256::ZERO, &calldata)
+    }
+
+    /// Stakes the ETH sent at the EntryPoint, which bundlers require of paymasters (owner only).
+    #[payable]
+    pub fn add_stake(&mut self, unstake_delay_sec: u32) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        let calldata = IEntryPointPayable::addStakeCall { unstake_delay_sec }.abi_encode();
+        self.call_entry_point(self.vm().msg_value(), &calldata)
+    }
+
+    /// Starts the unstake delay (owner only).
+    pub fn unlock_stake(&mut self) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        self.call_entry_point(U256::ZERO, &IEntryPoint::unlockStakeCall {}.abi_encode())
+    }
+
+    /// Withdraws the stake once the unstake delay has passed (owner only).
+    pub fn withdraw_stake(&mut self, withdraw_address: Address) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        let calldata = IEntryPoint::withdrawStakeCall { withdraw_address }.abi_encode();
+        self.call_entry_point(U256::ZERO, &calldata)
+    }
+
+    /// Sets the gas station whose credits senders may pay with, or the zero address to stop
+    /// accepting credits (owner only). The gas station must approve the paymaster as a spender.
+    pub fn set_gas_station(&mut self, gas_station: Address) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        self.gas_station.set(gas_station);
+
+        log(self.vm(), GasStationUpdated { gas_station });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), PaymasterError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// EIP-712 digest the backend signs to sponsor `user_op`. `paymasterAndData` only needs
+    /// its first 52 bytes filled in.
+    pub fn get_hash(&self, user_op: PackedUserOperation, valid_until: u64, valid_after: u64) -> B256 {
+        self.sponsorship_digest(&user_op, valid_until, valid_after)
+    }
+
+    /// Returns `(period, spent)`: the current period index and what `sender` has spent or reserved in it.
+    pub fn spending_of(&self, sender: Address) -> (U256, U256) {
+        let period = U256::from(self.vm().block_timestamp()) / self.period_length.get();
+        (period, self.spent_in(sender, period))
+    }
+
+    /// Returns `(spend_limit, period_length)`.
+    pub fn spend_limit(&self) -> (U256, U256) {
+        (self.spend_limit.get(), self.period_length.get())
+    }
+
+    /// The paymaster's deposit at the EntryPoint.
+    pub fn get_deposit(&self) -> Result<U256, PaymasterError> {
+        let call = IEntryPoint::balanceOfCall { account: self.vm().contract_address() };
+        self.vm()
+            .static_call(&self, self.entry_point.get(), &call.abi_encode())
+            .ok()
+            .and_then(|output| IEntryPoint::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map(|balance| balance._0)
+            .ok_or(PaymasterError::EntryPointCallFailed(EntryPointCallFailed {}))
+    }
+
+    /// EIP-712 domain separator for this paymaster and chain.
+    pub fn domain_separator(&self) -> B256 {
+        domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Backend key whose sponsorships are accepted.
+    pub fn verifying_signer(&self) -> Address {
+        self.verifying_signer.get()
+    }
+
+    /// EntryPoint this paymaster sponsors operations for.
+    pub fn entry_point(&self) -> Address {
+        self.entry_point.get()
+    }
+
+    /// Gas station whose credits senders may pay with.
+    pub fn gas_station(&self) -> Address {
+        self.gas_station.get()
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok, sign};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ENTRY_POINT: Address = address!("0000000071727de22e5e9d8baf0edac6f37da032");
+const SIGNER: Address = address!("000000000000000000000000000000000000519e");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const STATION: Address = address!("0000000000000000000000000000000000006a50");
+
+/// Sponsorships are valid from 1,000 to 5,000.
+const VALID_AFTER: u64 = 1_000;
+const VALID_UNTIL: u64 = 5_000;
+
+/// Alice's operation with `paymaster_and_data`.
+fn user_op(paymaster_and_data: Vec<u8>) -> PackedUserOperation {
+    let (init_code, call_data, signature) = (Vec::new().into(), vec![0xb6, 0x1d, 0x27, 0xf6].into(), Vec::new().into());
+    (ALICE, U256::ZERO, init_code, call_data, B256::ZERO, n(50_000), B256::ZERO, paymaster_and_data.into(), signature)
+}
+
+/// `paymasterAndData` up to the gas limits, which is all an operation paid with gas credits has.
+fn paymaster_gas_limits(vm: &TestVM) -> Vec<u8> {
+    [vm.contract_address().as_slice(), &[0x01; 32]].concat()
+}
+
+/// Alice's operation with a sponsorship `signer` signed.
+fn sponsored_op(vm: &TestVM, contract: &Paymaster, signer: Address) -> PackedUserOperation {
+    let digest = contract.get_hash(user_op(paymaster_gas_limits(vm)), VALID_UNTIL, VALID_AFTER);
+    let validity = (n(VALID_UNTIL), n(VALID_AFTER)).abi_encode();
+    user_op([paymaster_gas_limits(vm), validity, sign(vm, signer, digest)].concat())
+}
+
+/// A paymaster at time 1,000 sponsoring up to 1,000,000 wei per day, called by the EntryPoint.
+fn setup() -> (TestVM, Paymaster) {
+    let (vm, mut contract) = deploy::<Paymaster>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, ENTRY_POINT, SIGNER, n(1_000_000), n(86_400)));
+    vm.set_sender(ENTRY_POINT);
+    (vm, contract)
+}
+
+#[test]
+fn sponsorships_reserve_the_limit_and_release_what_went_unspent() {
+    let (vm, mut contract) = setup();
+    let op = sponsored_op(&vm, &contract, SIGNER);
+    let (context, validation_data) = ok(contract.validate_paymaster_user_op(op.clone(), B256::ZERO, n(600_000)));
+    assert_eq!(validation_data, n(VALID_UNTIL) << 160 | n(VALID_AFTER) << 208);
+    assert_eq!(contract.spending_of(ALICE), (U256::ZERO, n(600_000)));
+
+    ok(contract.post_op(0, context, n(200_000), n(1)));
+    assert_eq!(contract.spending_of(ALICE), (U256::ZERO, n(200_000)));
+    let sponsored = &emitted::<UserOperationSponsored>(&vm)[0];
+    assert_eq!((sponsored.sender, sponsored.actual_gas_cost), (ALICE, n(200_000)));
+
+    let result = contract.validate_paymaster_user_op(op, B256::ZERO, n(800_001));
+    assert!(matches!(
+        result,
+        Err(PaymasterError::SpendLimitExceeded(e)) if e.requested == n(800_001) && e.remaining == n(800_000)
+    ));
+}
+
+#[test]
+fn only_the_entry_point_validates_and_only_the_backend_sponsors() {
+    let (vm, mut contract) = setup();
+    let op = sponsored_op(&vm, &contract, ALICE);
+    let (context, validation_data) = ok(contract.validate_paymaster_user_op(op.clone(), B256::ZERO, n(1)));
+    assert!(context.is_empty() && validation_data.bit(0));
+    assert_eq!(contract.spending_of(ALICE).1, U256::ZERO);
+
+    let result = contract.validate_paymaster_user_op(user_op(vec![0; 60]), B256::ZERO, n(1));
+    assert!(matches!(result, Err(PaymasterError::InvalidPaymasterData(e)) if e.length == n(60)));
+    vm.set_sender(ALICE);
+    let result = contract.validate_paymaster_user_op(op, B256::ZERO, n(1));
+    assert!(matches!(result, Err(PaymasterError::Unauthorized(e)) if e.caller == ALICE));
+}
+
+#[test]
+fn gas_credits_pay_for_unsponsored_operations() {
+    let (vm, mut contract) = setup();
+    let op = user_op(paymaster_gas_limits(&vm));
+    let result = contract.validate_paymaster_user_op(op.clone(), B256::ZERO, n(300_000));
+    assert!(matches!(result, Err(PaymasterError::GasCreditsUnavailable(e)) if e.sender == ALICE));
+
+    vm.set_sender(OWNER);
+    ok(contract.set_gas_station(STATION));
+    let reserve = IGasStation::reserveCall { account: ALICE, amount: n(300_000) };
+    vm.mock_call(STATION, reserve.abi_encode(), Ok(Vec::new()));
+    vm.set_sender(ENTRY_POINT);
+    let (context, validation_data) = ok(contract.validate_paymaster_user_op(op, B256::ZERO, n(300_000)));
+    assert_eq!((context.to_vec(), validation_data), ((ALICE, n(300_000)).abi_encode(), U256::ZERO));
+
+    let this = vm.contract_address();
+    let settle =
+        IGasStation::settleCall { account: ALICE, reserved: n(300_000), actual_cost: n(120_000), beneficiary: this };
+    vm.mock_call(STATION, settle.abi_encode(), Ok(n(120_000).abi_encode()));
+    ok(contract.post_op(0, context.clone(), n(120_000), n(1)));
+    let paid = &emitted::<UserOperationPaidWithCredits>(&vm)[0];
+    assert_eq!((paid.sender, paid.actual_gas_cost, paid.charged), (ALICE, n(120_000), n(120_000)));
+
+    // A station that won't settle fails the operation's post_op
+    let result = contract.post_op(0, context, n(100_000), n(1));
+    assert!(matches!(result, Err(PaymasterError::GasCreditsUnavailable(e)) if e.sender == ALICE));
+}
+
+#[test]
+fn the_owner_manages_the_deposit_at_the_entry_point() {
+    let (vm, mut contract) = setup();
+    let this = vm.contract_address();
+    vm.mock_static_call(ENTRY_POINT, IEntryPoint::balanceOfCall { account: this }.abi_encode(), Ok(n(7).abi_encode()));
+    assert_eq!(ok(contract.get_deposit()), n(7));
+    let result = contract.withdraw_to(OWNER, n(7));
+    assert!(matches!(result, Err(PaymasterError::Unauthorized(e)) if e.caller == ENTRY_POINT));
+
+    vm.set_sender(OWNER);
+    vm.mock_call(ENTRY_POINT, IEntryPointPayable::depositToCall { account: this }.abi_encode(), Ok(Vec::new()));
+    vm.set_value(n(7));
+    ok(contract.deposit());
+    vm.set_value(U256::ZERO);
+    let withdraw = IEntryPoint::withdrawToCall { withdraw_address: OWNER, withdraw_amount: n(8) };
+    vm.mock_call(ENTRY_POINT, withdraw.abi_encode(), Err(b"insufficient deposit".to_vec()));
+    assert!(matches!(contract.withdraw_to(OWNER, n(8)), Err(PaymasterError::EntryPointCallFailed(_))));
+}
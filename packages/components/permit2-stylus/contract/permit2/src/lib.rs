@@ -0,0 +1,457 @@
+//! Unified allowances
+//!
+//! Holders approve this contract once per token, and from then on grant spenders access through
+//! it, after Uniswap's Permit2, so every Cradle DeFi component can pull tokens through a single
+//! approval surface instead of asking for its own approval. Either way a grant works for ERC-20s
+//! and for a token ID of an ERC-1155.
+//!
+//! Allowance transfers: a holder gives a spender an allowance of a token, bounded in amount and
+//! time, either with `approve` or by signing an EIP-712 permit anyone can submit, and the spender
+//! then moves tokens within it with `transferFrom`. Allowances of `type(uint256).max` aren't
+//! drawn down. Permits carry a sequential nonce per holder, token and spender, which the holder
+//! moves forward to revoke permits they signed, and `lockdown` revokes allowances at once.
+//!
+//! Signature transfers: a holder signs a one-off transfer of up to an amount of one token, or of
+//! several tokens in a batch, to a named spender, which submits it with `permitTransferFrom` or
+//! `permitBatchTransferFrom` and picks the recipients and amounts. Nothing is stored but the
+//! signature's unordered nonce, so any number can be outstanding; holders cancel one that wasn't
+//! submitted with `cancelNonce`.
+//!
+//! Holders with code, such as smart accounts, sign through ERC-1271. ERC-20s that return nothing
+//! from `transferFrom` are accepted, so tokens like USDT can be moved.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256, U64};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_crypto::{
+    domain_separator, eip712_domain, hash_struct, hash_typed_data, is_valid_signature_now, Eip712Domain, Nonces,
+    UsedNonce,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain.
+const DOMAIN_NAME: &str = "Cradle Permit2";
+/// Version in the EIP-712 domain.
+const DOMAIN_VERSION: &str = "1";
+/// `keccak256("TokenPermissions(uint8 kind,address token,uint256 id,uint256 amount)")`
+pub const TOKEN_PERMISSIONS_TYPEHASH: B256 = b256!("e694b5bf0ad5ee9fab418391679acaa825a2234e655a7a27551ce4375b55c089");
+/// `keccak256("PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(uint8 kind,address token,uint256 id,uint256 amount)")`
+pub const PERMIT_TRANSFER_FROM_TYPEHASH: B256 =
+    b256!("47fe3f3ed3cfdeceb35cb16bb49fe6ad7b4b26aba292d93887c47b699f4d4a5c");
+/// `keccak256("PermitBatchTransferFrom(TokenPermissions[] permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(uint8 kind,address token,uint256 id,uint256 amount)")`
+pub const PERMIT_BATCH_TRANSFER_FROM_TYPEHASH: B256 =
+    b256!("a56c729b955abb4214aa52bc443f1a2fc3f4b8fdfeb3ee2cd5aa24ce852fb30d");
+/// `keccak256("PermitDetails(address token,uint256 id,uint256 amount,uint64 expiration,uint64 nonce)")`
+pub const PERMIT_DETAILS_TYPEHASH: B256 = b256!("8bbde0767715fdfbef5a54444056ef7c66f981b13cf543891203e73f8b8494c4");
+/// `keccak256("PermitSingle(PermitDetails details,address spender,uint256 sigDeadline)PermitDetails(address token,uint256 id,uint256 amount,uint64 expiration,uint64 nonce)")`
+pub const PERMIT_SINGLE_TYPEHASH: B256 = b256!("68fc7d5f979c9448dd132e2644091835b87e525e630022bcd747ccda752bb73d");
+/// Asset kind of an ERC-20; its token ID is ignored.
+pub const KIND_ERC20: u8 = 0;
+/// Asset kind of an ERC-1155 token ID.
+pub const KIND_ERC1155: u8 = 1;
+
+/// `(kind, token, id, amount)` a signature transfer permits moving.
+pub type TokenPermissions = (u8, Address, U256, U256);
+/// `(permitted, nonce, deadline)` of a signature transfer of one token.
+pub type PermitTransferFrom = (TokenPermissions, U256, U256);
+/// `(permitted, nonce, deadline)` of a signature transfer of several tokens.
+pub type PermitBatchTransferFrom = (Vec<TokenPermissions>, U256, U256);
+/// `(to, requested_amount)` of each token in a signature transfer.
+pub type TransferDetails = (Address, U256);
+/// `(token, id, amount, expiration, nonce)` of the allowance a permit grants.
+pub type PermitDetails = (Address, U256, U256, u64, u64);
+/// `(kind, token, id, from, to, amount)` of an allowance transfer.
+pub type AllowanceTransferDetails = (u8, Address, U256, Address, Address, U256);
+/// `(token, id, spender)` of an allowance to revoke.
+pub type TokenSpenderPair = (Address, U256, Address);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Permit2 {
+        /// Allowances by holder, token, token ID and spender; ERC-20 allowances are under ID zero
+        mapping(address => mapping(address => mapping(uint256 => mapping(address => Allowance)))) allowances;
+        /// Unordered nonces of signature transfers
+        Nonces nonces;
+    }
+
+    pub struct Allowance {
+        /// Amount the spender can still move, `type(uint256).max` for no limit
+        uint256 amount;
+        /// Last timestamp the allowance can be used at
+        uint64 expiration;
+        /// Nonce the holder's next permit for the allowance must sign over
+        uint64 nonce;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Approval(address indexed owner, address indexed token, address indexed spender, uint256 id, uint256 amount, uint64 expiration);
+    event Permit(address indexed owner, address indexed token, address indexed spender, uint256 id, uint256 amount, uint64 expiration, uint64 nonce);
+    event Lockdown(address indexed owner, address token, address spender, uint256 id);
+    event NonceInvalidation(address indexed owner, address indexed token, address indexed spender, uint256 id, uint64 newNonce, uint64 oldNonce);
+
+    // The signature or permit can't be used after `deadline`
+    error SignatureExpired(uint256 deadline);
+    // Signature is malformed or wasn't made by the holder
+    error InvalidSignature(address owner);
+    // Permit was signed over another nonce than the allowance's
+    error InvalidNonce(uint64 nonce, uint64 expected);
+    // Nonces can only move forward, by at most `type(uint16).max` at a time
+    error InvalidNonceInvalidation(uint64 oldNonce, uint64 newNonce);
+    // Transfer asks for more than the signature permits
+    error InvalidAmount(uint256 maxAmount, uint256 requested);
+    // A batch has a different number of transfers than permitted tokens
+    error LengthMismatch();
+    // The allowance can't be used after `expiration`
+    error AllowanceExpired(uint64 expiration);
+    error InsufficientAllowance(uint256 amount, uint256 requested);
+    // Asset kind is neither ERC-20 nor ERC-1155
+    error InvalidKind(uint8 kind);
+    // A token transfer failed
+    error TransferFailed(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum Permit2Error {
+    SignatureExpired(SignatureExpired),
+    InvalidSignature(InvalidSignature),
+    InvalidNonce(InvalidNonce),
+    InvalidNonceInvalidation(InvalidNonceInvalidation),
+    InvalidAmount(InvalidAmount),
+    LengthMismatch(LengthMismatch),
+    AllowanceExpired(AllowanceExpired),
+    InsufficientAllowance(InsufficientAllowance),
+    InvalidKind(InvalidKind),
+    TransferFailed(TransferFailed),
+    UsedNonce(UsedNonce),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+// Helpers used by the public methods below.
+impl Permit2 {
+    fn hash_token_permissions(&self, permitted: &TokenPermissions) -> B256 {
+        let (kind, token, id, amount) = *permitted;
+        hash_struct(self.vm(), TOKEN_PERMISSIONS_TYPEHASH, (U256::from(kind), token, id, amount))
+    }
+
+    /// EIP-712 digest of a signature transfer of one token to `spender`.
+    fn hash_permit_transfer_from(&self, permit: &PermitTransferFrom, spender: Address) -> B256 {
+        let (permitted, nonce, deadline) = permit;
+        let permitted = self.hash_token_permissions(permitted);
+        let struct_hash =
+            hash_struct(self.vm(), PERMIT_TRANSFER_FROM_TYPEHASH, (permitted, spender, *nonce, *deadline));
+        hash_typed_data(self.vm(), self.domain_separator(), struct_hash)
+    }
+
+    /// EIP-712 digest of a signature transfer of several tokens to `spender`.
+    fn hash_permit_batch_transfer_from(&self, permit: &PermitBatchTransferFrom, spender: Address) -> B256 {
+        let (permitted, nonce, deadline) = permit;
+        let hashes: Vec<u8> = permitted.iter().flat_map(|p| self.hash_token_permissions(p).0).collect();
+        let permitted = self.vm().native_keccak256(&hashes);
+        let struct_hash =
+            hash_struct(self.vm(), PERMIT_BATCH_TRANSFER_FROM_TYPEHASH, (permitted, spender, *nonce, *deadline));
+        hash_typed_data(self.vm(), self.domain_separator(), struct_hash)
+    }
+
+    /// EIP-712 digest of a permit granting `spender` an allowance.
+    fn hash_permit_single(&self, details: &PermitDetails, spender: Address, sig_deadline: U256) -> B256 {
+        let (token, id, amount, expiration, nonce) = *details;
+        let details = hash_struct(
+            self.vm(),
+            PERMIT_DETAILS_TYPEHASH,
+            (token, id, amount, U256::from(expiration), U256::from(nonce)),
+        );
+        let struct_hash = hash_struct(self.vm(), PERMIT_SINGLE_TYPEHASH, (details, spender, sig_deadline));
+        hash_typed_data(self.vm(), self.domain_separator(), struct_hash)
+    }
+
+    /// Checks that `owner` signed `digest` and it's still valid, and consumes its unordered `nonce`.
+    fn use_signature(
+        &mut self,
+        owner: Address,
+        digest: B256,
+        nonce: U256,
+        deadline: U256,
+        signature: &[u8],
+    ) -> Result<(), Permit2Error> {
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(Permit2Error::SignatureExpired(SignatureExpired { deadline }));
+        }
+        self.nonces
+            .use_unordered_nonce(owner, nonce)
+            .map_err(|_| Permit2Error::UsedNonce(UsedNonce { account: owner, nonce }))?;
+        if !is_valid_signature_now(self.vm(), owner, digest, signature) {
+            return Err(Permit2Error::InvalidSignature(InvalidSignature { owner }));
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` of an ERC-20 or ERC-1155 token ID from `from` to `to`. ERC-20s may return
+    /// nothing instead of `true`.
+    fn move_asset(
+        &mut self,
+        kind: u8,
+        token: Address,
+        id: U256,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Permit2Error> {
+        let calldata = match kind {
+            KIND_ERC20 => IERC20::transferFromCall { from, to, value: amount }.abi_encode(),
+            KIND_ERC1155 => {
+                IERC1155::safeTransferFromCall { from, to, id, value: amount, data: Vec::new().into() }.abi_encode()
+            }
+            _ => return Err(Permit2Error::InvalidKind(InvalidKind { kind })),
+        };
+        let moved = match self.vm().call(&calls::context::Call::new(), token, &calldata) {
+            Ok(result) if kind == KIND_ERC20 => result.is_empty() || bool::abi_decode(&result, true).unwrap_or(false),
+            Ok(_) => true,
+            Err(_) => false,
+        };
+        if !moved {
+            return Err(Permit2Error::TransferFailed(TransferFailed { token }));
+        }
+        Ok(())
+    }
+
+    /// Draws `amount` from `from`'s allowance of `token` to the caller and moves it to `to`.
+    fn transfer_within_allowance(&mut self, transfer: AllowanceTransferDetails) -> Result<(), Permit2Error> {
+        let (kind, token, id, from, to, amount) = transfer;
+        let spender = self.vm().msg_sender();
+        let now = self.vm().block_timestamp();
+        let mut holder = self.allowances.setter(from);
+        let mut by_token = holder.setter(token);
+        let mut by_id = by_token.setter(id);
+        let mut allowance = by_id.setter(spender);
+        let expiration = allowance.expiration.get().to::<u64>();
+        if now > expiration {
+            return Err(Permit2Error::AllowanceExpired(AllowanceExpired { expiration }));
+        }
+        let available = allowance.amount.get();
+        if available != U256::MAX {
+            if available < amount {
+                return Err(Permit2Error::InsufficientAllowance(InsufficientAllowance {
+                    amount: available,
+                    requested: amount,
+                }));
+            }
+            allowance.amount.set(available - amount);
+        }
+        self.move_asset(kind, token, id, from, to, amount)
+    }
+
+    /// Sets `owner`'s allowance of `token` to `spender`, keeping its nonce.
+    fn set_allowance(
+        &mut self,
+        owner: Address,
+        token: Address,
+        id: U256,
+        spender: Address,
+        amount: U256,
+        expiration: u64,
+    ) {
+        let mut holder = self.allowances.setter(owner);
+        let mut by_token = holder.setter(token);
+        let mut by_id = by_token.setter(id);
+        let mut allowance = by_id.setter(spender);
+        allowance.amount.set(amount);
+        allowance.expiration.set(U64::from(expiration));
+    }
+}
+
+#[public]
+impl Permit2 {
+    /// Gives `spender` an allowance of `amount` of the caller's `token`, or of its ERC-1155 `id`,
+    /// usable until `expiration`. Replaces the current allowance.
+    pub fn approve(&mut self, token: Address, id: U256, spender: Address, amount: U256, expiration: u64) {
+        let owner = self.vm().msg_sender();
+        self.set_allowance(owner, token, id, spender, amount, expiration);
+        log(self.vm(), Approval { owner, token, spender, id, amount, expiration });
+    }
+
+    /// Sets the allowance `owner` signed a permit for, consuming the permit's nonce. Anyone can
+    /// submit it until `sig_deadline`.
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        details: PermitDetails,
+        spender: Address,
+        sig_deadline: U256,
+        signature: Bytes,
+    ) -> Result<(), Permit2Error> {
+        if U256::from(self.vm().block_timestamp()) > sig_deadline {
+            return Err(Permit2Error::SignatureExpired(SignatureExpired { deadline: sig_deadline }));
+        }
+        let (token, id, amount, expiration, nonce) = details;
+        let expected = self.allowance(owner, token, id, spender).2;
+        if nonce != expected {
+            return Err(Permit2Error::InvalidNonce(InvalidNonce { nonce, expected }));
+        }
+        let digest = self.hash_permit_single(&details, spender, sig_deadline);
+        if !is_valid_signature_now(self.vm(), owner, digest, &signature) {
+            return Err(Permit2Error::InvalidSignature(InvalidSignature { owner }));
+        }
+
+        self.set_allowance(owner, token, id, spender, amount, expiration);
+        let mut holder = self.allowances.setter(owner);
+        let mut by_token = holder.setter(token);
+        let mut by_id = by_token.setter(id);
+        by_id.setter(spender).nonce.set(U64::from(nonce + 1));
+        log(self.vm(), Permit { owner, token, spender, id, amount, expiration, nonce });
+        Ok(())
+    }
+
+    /// Moves `amount` of `from`'s token to `to` within the allowance `from` gave the caller.
+    /// `kind` says whether `token` is an ERC-20 or an ERC-1155.
+    pub fn transfer_from(
+        &mut self,
+        kind: u8,
+        token: Address,
+        id: U256,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Permit2Error> {
+        self.transfer_within_allowance((kind, token, id, from, to, amount))
+    }
+
+    /// Makes several allowance transfers at once, all or none.
+    pub fn transfer_from_batch(&mut self, transfers: Vec<AllowanceTransferDetails>) -> Result<(), Permit2Error> {
+        for transfer in transfers {
+            self.transfer_within_allowance(transfer)?;
+        }
+        Ok(())
+    }
+
+    /// Revokes the caller's allowances to each `(token, id, spender)`, keeping their nonces.
+    pub fn lockdown(&mut self, approvals: Vec<TokenSpenderPair>) {
+        let owner = self.vm().msg_sender();
+        for (token, id, spender) in approvals {
+            self.set_allowance(owner, token, id, spender, U256::ZERO, 0);
+            log(self.vm(), Lockdown { owner, token, spender, id });
+        }
+    }
+
+    /// Moves the nonce of the caller's allowance to `spender` forward to `new_nonce`, revoking the
+    /// permits the caller signed over the nonces before it.
+    pub fn invalidate_nonces(
+        &mut self,
+        token: Address,
+        id: U256,
+        spender: Address,
+        new_nonce: u64,
+    ) -> Result<(), Permit2Error> {
+        let owner = self.vm().msg_sender();
+        let old_nonce = self.allowance(owner, token, id, spender).2;
+        if new_nonce <= old_nonce || new_nonce - old_nonce > u64::from(u16::MAX) {
+            return Err(Permit2Error::InvalidNonceInvalidation(InvalidNonceInvalidation {
+                oldNonce: old_nonce,
+                newNonce: new_nonce,
+            }));
+        }
+        let mut holder = self.allowances.setter(owner);
+        let mut by_token = holder.setter(token);
+        let mut by_id = by_token.setter(id);
+        by_id.setter(spender).nonce.set(U64::from(new_nonce));
+        log(self.vm(), NonceInvalidation { owner, token, spender, id, newNonce: new_nonce, oldNonce: old_nonce });
+        Ok(())
+    }
+
+    /// Moves up to the permitted amount of one of `owner`'s tokens to `to` with a signature
+    /// `owner` made for the caller.
+    pub fn permit_transfer_from(
+        &mut self,
+        permit: PermitTransferFrom,
+        transfer: TransferDetails,
+        owner: Address,
+        signature: Bytes,
+    ) -> Result<(), Permit2Error> {
+        let ((kind, token, id, max_amount), nonce, deadline) = permit;
+        let (to, requested) = transfer;
+        if requested > max_amount {
+            return Err(Permit2Error::InvalidAmount(InvalidAmount { maxAmount: max_amount, requested }));
+        }
+        let digest = self.hash_permit_transfer_from(&permit, self.vm().msg_sender());
+        self.use_signature(owner, digest, nonce, deadline, &signature)?;
+        self.move_asset(kind, token, id, owner, to, requested)
+    }
+
+    /// Moves up to the permitted amounts of several of `owner`'s tokens with one signature
+    /// `owner` made for the caller, with a transfer for each permitted token. Transfers of zero
+    /// are skipped.
+    pub fn permit_batch_transfer_from(
+        &mut self,
+        permit: PermitBatchTransferFrom,
+        transfers: Vec<TransferDetails>,
+        owner: Address,
+        signature: Bytes,
+    ) -> Result<(), Permit2Error> {
+        if permit.0.len() != transfers.len() {
+            return Err(Permit2Error::LengthMismatch(LengthMismatch {}));
+        }
+        let digest = self.hash_permit_batch_transfer_from(&permit, self.vm().msg_sender());
+        let (permitted, nonce, deadline) = permit;
+        self.use_signature(owner, digest, nonce, deadline, &signature)?;
+        for ((kind, token, id, max_amount), (to, requested)) in permitted.into_iter().zip(transfers) {
+            if requested > max_amount {
+                return Err(Permit2Error::InvalidAmount(InvalidAmount { maxAmount: max_amount, requested }));
+            }
+            if !requested.is_zero() {
+                self.move_asset(kind, token, id, owner, to, requested)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancels the caller's signature transfer with the unordered `nonce` if it wasn't submitted.
+    pub fn cancel_nonce(&mut self, nonce: U256) {
+        self.nonces.cancel_nonce(nonce);
+    }
+
+    /// Returns `(amount, expiration, nonce)` of `owner`'s allowance of `token` to `spender`.
+    pub fn allowance(&self, owner: Address, token: Address, id: U256, spender: Address) -> (U256, u64, u64) {
+        let holder = self.allowances.getter(owner);
+        let by_token = holder.getter(token);
+        let by_id = by_token.getter(id);
+        let allowance = by_id.getter(spender);
+        (allowance.amount.get(), allowance.expiration.get().to(), allowance.nonce.get().to())
+    }
+
+    /// Whether `owner` used or cancelled the unordered signature transfer `nonce`.
+    pub fn nonce_used(&self, owner: Address, nonce: U256) -> bool {
+        self.nonces.is_unordered_nonce_used(owner, nonce)
+    }
+
+    /// EIP-712 domain separator for this contract and chain.
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Returns `(name, version, chain_id, verifying_contract)` of the EIP-712 domain.
+    pub fn eip712_domain(&self) -> Eip712Domain {
+        eip712_domain(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+}
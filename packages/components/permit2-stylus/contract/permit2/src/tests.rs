@@ -0,0 +1,167 @@
+use super::*;
+use alloy_primitives::{address, keccak256};
+use cradle_test_utils::{emitted, n, ok, sign};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const DAPP: Address = address!("000000000000000000000000000000000000da99");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const ITEMS: Address = address!("0000000000000000000000000000000000001155");
+
+fn setup() -> (TestVM, Permit2) {
+    let vm = TestVM::new();
+    vm.set_block_timestamp(1_000);
+    let contract = Permit2::from(&vm);
+    (vm, contract)
+}
+
+fn expect_erc20_transfer(vm: &TestVM, token: Address, from: Address, to: Address, amount: u64, result: Vec<u8>) {
+    vm.mock_call(token, IERC20::transferFromCall { from, to, value: n(amount) }.abi_encode(), Ok(result));
+}
+
+fn expect_erc1155_transfer(vm: &TestVM, from: Address, to: Address, id: u64, amount: u64) {
+    let transfer = IERC1155::safeTransferFromCall { from, to, id: n(id), value: n(amount), data: Vec::new().into() };
+    vm.mock_call(ITEMS, transfer.abi_encode(), Ok(Vec::new()));
+}
+
+#[test]
+fn typehashes_match_their_types() {
+    let permissions = "TokenPermissions(uint8 kind,address token,uint256 id,uint256 amount)";
+    let details = "PermitDetails(address token,uint256 id,uint256 amount,uint64 expiration,uint64 nonce)";
+    assert_eq!(TOKEN_PERMISSIONS_TYPEHASH, keccak256(permissions));
+    assert_eq!(
+        PERMIT_TRANSFER_FROM_TYPEHASH,
+        keccak256(format!(
+            "PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline){permissions}"
+        ))
+    );
+    assert_eq!(
+        PERMIT_BATCH_TRANSFER_FROM_TYPEHASH,
+        keccak256(format!(
+            "PermitBatchTransferFrom(TokenPermissions[] permitted,address spender,uint256 nonce,uint256 deadline){permissions}"
+        ))
+    );
+    assert_eq!(PERMIT_DETAILS_TYPEHASH, keccak256(details));
+    assert_eq!(
+        PERMIT_SINGLE_TYPEHASH,
+        keccak256(format!("PermitSingle(PermitDetails details,address spender,uint256 sigDeadline){details}"))
+    );
+}
+
+#[test]
+fn signature_transfers_move_up_to_the_permitted_amount_once() {
+    let (vm, mut contract) = setup();
+    let permit = ((KIND_ERC20, USDC, U256::ZERO, n(500)), n(7), n(2_000));
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_transfer_from(&permit, DAPP)));
+    // Tokens that return nothing from `transferFrom` are accepted
+    expect_erc20_transfer(&vm, USDC, ALICE, BOB, 300, Vec::new());
+
+    vm.set_sender(DAPP);
+    let result = contract.permit_transfer_from(permit, (BOB, n(501)), ALICE, signature.clone());
+    assert!(matches!(result, Err(Permit2Error::InvalidAmount(e)) if e.maxAmount == n(500)));
+    ok(contract.permit_transfer_from(permit, (BOB, n(300)), ALICE, signature.clone()));
+    assert!(contract.nonce_used(ALICE, n(7)));
+
+    let result = contract.permit_transfer_from(permit, (BOB, n(200)), ALICE, signature.clone());
+    assert!(matches!(result, Err(Permit2Error::UsedNonce(e)) if e.nonce == n(7)));
+    // The signature names its spender
+    let permit = ((KIND_ERC20, USDC, U256::ZERO, n(500)), n(8), n(2_000));
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_transfer_from(&permit, DAPP)));
+    vm.set_sender(BOB);
+    let result = contract.permit_transfer_from(permit, (BOB, n(300)), ALICE, signature);
+    assert!(matches!(result, Err(Permit2Error::InvalidSignature(e)) if e.owner == ALICE));
+}
+
+#[test]
+fn holders_cancel_signature_transfers() {
+    let (vm, mut contract) = setup();
+    let permit = ((KIND_ERC20, USDC, U256::ZERO, n(500)), n(1), n(2_000));
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_transfer_from(&permit, DAPP)));
+
+    vm.set_sender(ALICE);
+    contract.cancel_nonce(n(1));
+    vm.set_sender(DAPP);
+    let result = contract.permit_transfer_from(permit, (BOB, n(100)), ALICE, signature.clone());
+    assert!(matches!(result, Err(Permit2Error::UsedNonce(_))));
+
+    let permit = ((KIND_ERC20, USDC, U256::ZERO, n(500)), n(2), n(999));
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_transfer_from(&permit, DAPP)));
+    let result = contract.permit_transfer_from(permit, (BOB, n(100)), ALICE, signature);
+    assert!(matches!(result, Err(Permit2Error::SignatureExpired(e)) if e.deadline == n(999)));
+}
+
+#[test]
+fn batch_signature_transfers_move_erc20s_and_erc1155s() {
+    let (vm, mut contract) = setup();
+    let permitted = vec![
+        (KIND_ERC20, USDC, U256::ZERO, n(100)),
+        (KIND_ERC1155, ITEMS, n(3), n(2)),
+        (KIND_ERC1155, ITEMS, n(4), n(1)),
+    ];
+    let permit = (permitted, n(1), n(2_000));
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_batch_transfer_from(&permit, DAPP)));
+    expect_erc20_transfer(&vm, USDC, ALICE, BOB, 100, true.abi_encode());
+    expect_erc1155_transfer(&vm, ALICE, DAPP, 3, 2);
+
+    vm.set_sender(DAPP);
+    let result = contract.permit_batch_transfer_from(permit.clone(), vec![(BOB, n(100))], ALICE, signature.clone());
+    assert!(matches!(result, Err(Permit2Error::LengthMismatch(_))));
+    // The zero transfer of ID 4 is skipped
+    let transfers = vec![(BOB, n(100)), (DAPP, n(2)), (BOB, U256::ZERO)];
+    ok(contract.permit_batch_transfer_from(permit, transfers, ALICE, signature));
+    assert!(contract.nonce_used(ALICE, n(1)));
+}
+
+#[test]
+fn allowances_are_drawn_down_until_they_expire() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    contract.approve(ITEMS, n(7), DAPP, n(5), 2_000);
+    contract.approve(USDC, U256::ZERO, DAPP, U256::MAX, 2_000);
+    assert_eq!(emitted::<Approval>(&vm)[0].expiration, 2_000);
+    expect_erc1155_transfer(&vm, ALICE, BOB, 7, 3);
+    expect_erc20_transfer(&vm, USDC, ALICE, BOB, 1_000, true.abi_encode());
+
+    vm.set_sender(DAPP);
+    ok(contract.transfer_from(KIND_ERC1155, ITEMS, n(7), ALICE, BOB, n(3)));
+    assert_eq!(contract.allowance(ALICE, ITEMS, n(7), DAPP), (n(2), 2_000, 0));
+    let result = contract.transfer_from(KIND_ERC1155, ITEMS, n(7), ALICE, BOB, n(3));
+    assert!(matches!(result, Err(Permit2Error::InsufficientAllowance(e)) if e.amount == n(2)));
+    // Unlimited allowances aren't drawn down
+    ok(contract.transfer_from_batch(vec![(KIND_ERC20, USDC, U256::ZERO, ALICE, BOB, n(1_000))]));
+    assert_eq!(contract.allowance(ALICE, USDC, U256::ZERO, DAPP).0, U256::MAX);
+    let result = contract.transfer_from(2, USDC, U256::ZERO, ALICE, BOB, n(1));
+    assert!(matches!(result, Err(Permit2Error::InvalidKind(e)) if e.kind == 2));
+
+    vm.set_block_timestamp(2_001);
+    let result = contract.transfer_from(KIND_ERC1155, ITEMS, n(7), ALICE, BOB, n(1));
+    assert!(matches!(result, Err(Permit2Error::AllowanceExpired(e)) if e.expiration == 2_000));
+}
+
+#[test]
+fn permits_set_allowances_under_sequential_nonces() {
+    let (vm, mut contract) = setup();
+    let details = (USDC, U256::ZERO, n(500), 5_000, 0);
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_single(&details, DAPP, n(2_000))));
+
+    // Anyone can submit the permit
+    vm.set_sender(BOB);
+    ok(contract.permit(ALICE, details, DAPP, n(2_000), signature.clone()));
+    assert_eq!(contract.allowance(ALICE, USDC, U256::ZERO, DAPP), (n(500), 5_000, 1));
+    assert_eq!(emitted::<Permit>(&vm)[0].nonce, 0);
+    let result = contract.permit(ALICE, details, DAPP, n(2_000), signature);
+    assert!(matches!(result, Err(Permit2Error::InvalidNonce(e)) if e.expected == 1));
+
+    // The holder revokes the permit over nonce 1 they signed, and the allowance
+    let details = (USDC, U256::ZERO, n(900), 5_000, 1);
+    let signature = Bytes::from(sign(&vm, ALICE, contract.hash_permit_single(&details, DAPP, n(2_000))));
+    vm.set_sender(ALICE);
+    let result = contract.invalidate_nonces(USDC, U256::ZERO, DAPP, 1 + 65_536);
+    assert!(matches!(result, Err(Permit2Error::InvalidNonceInvalidation(_))));
+    ok(contract.invalidate_nonces(USDC, U256::ZERO, DAPP, 2));
+    contract.lockdown(vec![(USDC, U256::ZERO, DAPP)]);
+    assert_eq!(contract.allowance(ALICE, USDC, U256::ZERO, DAPP), (U256::ZERO, 0, 2));
+    let result = contract.permit(ALICE, details, DAPP, n(2_000), signature);
+    assert!(matches!(result, Err(Permit2Error::InvalidNonce(e)) if e.nonce == 1));
+}
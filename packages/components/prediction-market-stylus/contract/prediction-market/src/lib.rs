@@ -0,0 +1,719 @@
+//! Binary prediction market
+//!
+//! Each market asks a yes/no question and trades two outcome shares, YES and
+//! NO, minted as token IDs of a Cradle ERC-1155 collection owned by this
+//! contract. One YES plus one NO share is always backed by one unit of the
+//! ERC-20 collateral.
+//!
+//! Prices come from a fixed product market maker funded by the market's
+//! creator: buying YES adds the collateral to both sides of the pool and takes
+//! YES shares out while keeping the product of the reserves constant, and
+//! selling runs the same trade backwards. A trading fee is set aside for the
+//! creator.
+//!
+//! After trading closes the market is resolved either by its resolver, or from
+//! a Cradle price oracle feed against a strike price. Winning shares redeem for
+//! one unit of collateral each; if the market is resolved invalid every share
+//! redeems for half a unit.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Outcome index of YES shares.
+const YES: u8 = 0;
+/// Outcome index of NO shares.
+const NO: u8 = 1;
+/// `resolution` of a market that hasn't been resolved.
+const UNRESOLVED: u8 = 0;
+/// `resolution` of a market where YES won.
+const RESOLVED_YES: u8 = 1;
+/// `resolution` of a market where NO won.
+const RESOLVED_NO: u8 = 2;
+/// `resolution` of a market that couldn't be resolved; every share redeems for half.
+const RESOLVED_INVALID: u8 = 3;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+/// Highest trading fee, 10%.
+const MAX_FEE_BPS: u64 = 1_000;
+/// Prices are returned with 18 decimals.
+const PRICE_SCALE: u64 = 1_000_000_000_000_000_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct PredictionMarket {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the fee and the oracle
+        address owner;
+        /// ERC-1155 collection outcome shares are minted on; owned by this contract
+        address shares;
+        /// ERC-20 backing the shares
+        address collateral;
+        /// Cradle price oracle used by oracle-resolved markets
+        address oracle;
+        /// Trading fee in basis points
+        uint256 fee_bps;
+        /// Markets by ID
+        mapping(uint256 => Market) markets;
+        /// Markets created so far
+        uint256 market_count;
+    }
+
+    pub struct Market {
+        /// Funds the pool and earns the fees; zero if the market doesn't exist
+        address creator;
+        string question;
+        /// Resolves the market; zero for an oracle-resolved market
+        address resolver;
+        /// Oracle feed an oracle-resolved market is settled on
+        address feed;
+        /// YES wins if the feed's price is at least the strike
+        uint256 strike;
+        /// Trading stops and resolution opens at this time
+        uint256 close_time;
+        /// YES shares held by the pool
+        uint256 yes_reserve;
+        /// NO shares held by the pool
+        uint256 no_reserve;
+        /// Collateral backing every share of the market
+        uint256 collateral;
+        /// Fees owed to the creator
+        uint256 fees;
+        /// Unresolved (0), YES (1), NO (2) or invalid (3)
+        uint8 resolution;
+        bool liquidity_withdrawn;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event MarketCreated(uint256 indexed market_id, address indexed creator, string question, address resolver, address feed, uint256 strike, uint256 close_time, uint256 funding);
+    event SharesBought(uint256 indexed market_id, address indexed buyer, uint8 outcome, uint256 collateral_in, uint256 shares_out);
+    event SharesSold(uint256 indexed market_id, address indexed seller, uint8 outcome, uint256 shares_in, uint256 collateral_out);
+    event MarketResolved(uint256 indexed market_id, uint8 resolution);
+    event Redeemed(uint256 indexed market_id, address indexed account, uint256 payout);
+    event LiquidityWithdrawn(uint256 indexed market_id, address indexed creator, uint256 amount);
+    event FeeUpdated(uint256 fee_bps);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Fee is above the maximum
+    error InvalidFee(uint256 fee_bps);
+    // Market needs a resolver or an oracle feed, a future close time and funding
+    error InvalidMarket();
+    // Market doesn't exist
+    error UnknownMarket(uint256 market_id);
+    // Outcome or resolution value isn't valid
+    error InvalidOutcome(uint8 outcome);
+    // Amount is zero or too large for the pool
+    error InvalidAmount();
+    // Trading has closed
+    error MarketClosed(uint256 close_time);
+    // Market can't be resolved yet
+    error MarketOpen(uint256 close_time);
+    // Market was already resolved
+    error AlreadyResolved(uint256 market_id);
+    // Market hasn't been resolved
+    error NotResolved(uint256 market_id);
+    // Trade gives less than the minimum asked for
+    error SlippageExceeded(uint256 amount, uint256 minimum);
+    // Oracle price wasn't updated after trading closed
+    error StalePrice(uint256 updated_at);
+    // Oracle call failed
+    error OracleCallFailed();
+    // Nothing to redeem or withdraw
+    error NothingToClaim();
+    // Collateral transfer failed
+    error TransferFailed();
+    // Minting or burning shares failed
+    error ShareCallFailed();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum MarketError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidFee(InvalidFee),
+    InvalidMarket(InvalidMarket),
+    UnknownMarket(UnknownMarket),
+    InvalidOutcome(InvalidOutcome),
+    InvalidAmount(InvalidAmount),
+    MarketClosed(MarketClosed),
+    MarketOpen(MarketOpen),
+    AlreadyResolved(AlreadyResolved),
+    NotResolved(NotResolved),
+    SlippageExceeded(SlippageExceeded),
+    StalePrice(StalePrice),
+    OracleCallFailed(OracleCallFailed),
+    NothingToClaim(NothingToClaim),
+    TransferFailed(TransferFailed),
+    ShareCallFailed(ShareCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface ICradle1155 {
+        function mint(address to, uint256 id, uint256 amount, bytes data) external;
+        function burnFrom(address from, uint256 id, uint256 amount) external;
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+    }
+
+    interface ICradleOracle {
+        function latestPrice(address feed) external view returns (uint256, uint256);
+    }
+}
+
+/// ERC-1155 token ID of a market's YES (0) or NO (1) shares.
+pub fn share_id(market_id: U256, outcome: u8) -> U256 {
+    (market_id << 1) | U256::from(outcome)
+}
+
+/// Shares out for `amount` of collateral added to both sides of a pool, taking the bought side out
+/// while keeping the product of the reserves. Returns `(shares_out, bought_reserve, other_reserve)`.
+fn calc_buy(bought_reserve: U256, other_reserve: U256, amount: U256) -> (U256, U256, U256) {
+    let invariant = bought_reserve * other_reserve;
+    let other_after = other_reserve + amount;
+    // Round the bought side up so the pool never loses to rounding.
+    let bought_after = invariant.div_ceil(other_after);
+    (bought_reserve + amount - bought_after, bought_after, other_after)
+}
+
+/// Collateral out for `shares` of one side added to a pool, removing complete sets while keeping
+/// the product of the reserves. Returns `(collateral_out, sold_reserve, other_reserve)`.
+fn calc_sell(sold_reserve: U256, other_reserve: U256, shares: U256) -> (U256, U256, U256) {
+    // Solves (sold + shares - r) * (other - r) = sold * other for r, the sets removed.
+    let sum = sold_reserve + shares + other_reserve;
+    let discriminant = sum * sum - U256::from(4) * shares * other_reserve;
+    let mut root = discriminant.root(2);
+    // Round the root up, and with it the sets removed down.
+    if root * root < discriminant {
+        root += U256::from(1);
+    }
+    let removed = (sum - root) / U256::from(2);
+    (removed, sold_reserve + shares - removed, other_reserve - removed)
+}
+
+// Helpers used by the public methods below.
+impl PredictionMarket {
+    fn only_owner(&self) -> Result<(), MarketError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(MarketError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn fee_of(&self, amount: U256) -> U256 {
+        amount * self.fee_bps.get() / U256::from(BPS_DENOMINATOR)
+    }
+
+    /// Fails if the market doesn't exist or trading has closed.
+    fn require_open(&self, market_id: U256) -> Result<(), MarketError> {
+        let market = self.markets.getter(market_id);
+        if market.creator.get().is_zero() {
+            return Err(MarketError::UnknownMarket(UnknownMarket { market_id }));
+        }
+        let close_time = market.close_time.get();
+        if self.now() >= close_time || market.resolution.get().to::<u8>() != UNRESOLVED {
+            return Err(MarketError::MarketClosed(MarketClosed { close_time }));
+        }
+        Ok(())
+    }
+
+    /// Fails if the market doesn't exist, trading hasn't closed or it was already resolved.
+    fn require_resolvable(&self, market_id: U256) -> Result<(), MarketError> {
+        let market = self.markets.getter(market_id);
+        if market.creator.get().is_zero() {
+            return Err(MarketError::UnknownMarket(UnknownMarket { market_id }));
+        }
+        let close_time = market.close_time.get();
+        if self.now() < close_time {
+            return Err(MarketError::MarketOpen(MarketOpen { close_time }));
+        }
+        if market.resolution.get().to::<u8>() != UNRESOLVED {
+            return Err(MarketError::AlreadyResolved(AlreadyResolved { market_id }));
+        }
+        Ok(())
+    }
+
+    /// Collateral `yes` and `no` shares of a resolved market redeem for.
+    fn payout_of(&self, market_id: U256, yes: U256, no: U256) -> Result<U256, MarketError> {
+        match self.markets.getter(market_id).resolution.get().to::<u8>() {
+            RESOLVED_YES => Ok(yes),
+            RESOLVED_NO => Ok(no),
+            RESOLVED_INVALID => Ok((yes + no) / U256::from(2)),
+            _ => Err(MarketError::NotResolved(NotResolved { market_id })),
+        }
+    }
+
+    fn set_resolution(&mut self, market_id: U256, resolution: u8) {
+        self.markets
+            .setter(market_id)
+            .resolution
+            .set(alloy_primitives::Uint::<8, 1>::from(resolution));
+
+        log(self.vm(), MarketResolved { market_id, resolution });
+    }
+
+    /// Calls the collateral token, which must return `true`.
+    fn call_collateral(&mut self, calldata: &[u8]) -> Result<(), MarketError> {
+        match self.vm().call(&calls::context::Call::new(), self.collateral.get(), calldata) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(MarketError::TransferFailed(TransferFailed {})),
+        }
+    }
+
+    fn pull_collateral(&mut self, from: Address, amount: U256) -> Result<(), MarketError> {
+        let to = self.vm().contract_address();
+        self.call_collateral(&IERC20::transferFromCall { from, to, value: amount }.abi_encode())
+    }
+
+    fn send_collateral(&mut self, to: Address, amount: U256) -> Result<(), MarketError> {
+        self.call_collateral(&IERC20::transferCall { to, value: amount }.abi_encode())
+    }
+
+    /// Mints or burns shares on the share collection.
+    fn call_shares(&mut self, calldata: &[u8]) -> Result<(), MarketError> {
+        self.vm()
+            .call(&calls::context::Call::new(), self.shares.get(), calldata)
+            .map_err(|_| MarketError::ShareCallFailed(ShareCallFailed {}))?;
+        Ok(())
+    }
+
+    fn share_balance(&self, account: Address, id: U256) -> U256 {
+        let calldata = ICradle1155::balanceOfCall { account, id }.abi_encode();
+        self.vm()
+            .static_call(&self, self.shares.get(), &calldata)
+            .ok()
+            .and_then(|output| ICradle1155::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map_or(U256::ZERO, |balance| balance._0)
+    }
+}
+
+#[public]
+impl PredictionMarket {
+    /// Sets the owner, the share collection, the collateral, the oracle and the trading fee. Can
+    /// only be called once. This contract must own the share collection to mint shares.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        shares: Address,
+        collateral: Address,
+        oracle: Address,
+        fee_bps: U256,
+    ) -> Result<(), MarketError> {
+        if self.initialized.get() {
+            return Err(MarketError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if fee_bps > U256::from(MAX_FEE_BPS) {
+            return Err(MarketError::InvalidFee(InvalidFee { fee_bps }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.shares.set(shares);
+        self.collateral.set(collateral);
+        self.oracle.set(oracle);
+        self.fee_bps.set(fee_bps);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Creates a market funded with `funding` collateral from the caller, which starts both sides
+    /// at a price of 0.5. Set `resolver` to resolve it by hand, or `feed` and `strike` to resolve it
+    /// from the oracle, where YES wins if the price is at least the strike. Returns the market ID.
+    pub fn create_market(
+        &mut self,
+        question: String,
+        resolver: Address,
+        feed: Address,
+        strike: U256,
+        close_time: U256,
+        funding: U256,
+    ) -> Result<U256, MarketError> {
+        if resolver.is_zero() == feed.is_zero() || close_time <= self.now() || funding.is_zero() {
+            return Err(MarketError::InvalidMarket(InvalidMarket {}));
+        }
+        let creator = self.vm().msg_sender();
+        self.pull_collateral(creator, funding)?;
+
+        let market_id = self.market_count.get();
+        self.market_count.set(market_id + U256::from(1));
+        let mut market = self.markets.setter(market_id);
+        market.creator.set(creator);
+        market.question.set_str(question.clone());
+        market.resolver.set(resolver);
+        market.feed.set(feed);
+        market.strike.set(strike);
+        market.close_time.set(close_time);
+        market.yes_reserve.set(funding);
+        market.no_reserve.set(funding);
+        market.collateral.set(funding);
+
+        log(self.vm(), MarketCreated {
+            market_id,
+            creator,
+            question,
+            resolver,
+            feed,
+            strike,
+            close_time,
+            funding,
+        });
+        Ok(market_id)
+    }
+
+    /// Buys YES (0) or NO (1) shares with `amount` collateral, less the trading fee. Reverts if
+    /// fewer than `min_shares_out` would be bought. Returns the shares bought.
+    pub fn buy(&mut self, market_id: U256, outcome: u8, amount: U256, min_shares_out: U256) -> Result<U256, MarketError> {
+        self.require_open(market_id)?;
+        if outcome != YES && outcome != NO {
+            return Err(MarketError::InvalidOutcome(InvalidOutcome { outcome }));
+        }
+        let fee = self.fee_of(amount);
+        let invested = amount - fee;
+        if invested.is_zero() {
+            return Err(MarketError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let market = self.markets.getter(market_id);
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        let (shares_out, yes_after, no_after) = if outcome == YES {
+            calc_buy(yes_reserve, no_reserve, invested)
+        } else {
+            let (shares_out, no_after, yes_after) = calc_buy(no_reserve, yes_reserve, invested);
+            (shares_out, yes_after, no_after)
+        };
+        if shares_out < min_shares_out {
+            return Err(MarketError::SlippageExceeded(SlippageExceeded {
+                amount: shares_out,
+                minimum: min_shares_out,
+            }));
+        }
+        let collateral = market.collateral.get();
+        let fees = market.fees.get();
+
+        let buyer = self.vm().msg_sender();
+        self.pull_collateral(buyer, amount)?;
+        let mut market = self.markets.setter(market_id);
+        market.yes_reserve.set(yes_after);
+        market.no_reserve.set(no_after);
+        market.collateral.set(collateral + invested);
+        market.fees.set(fees + fee);
+
+        let mint = ICradle1155::mintCall {
+            to: buyer,
+            id: share_id(market_id, outcome),
+            amount: shares_out,
+            data: Vec::new().into(),
+        };
+        self.call_shares(&mint.abi_encode())?;
+
+        log(self.vm(), SharesBought {
+            market_id,
+            buyer,
+            outcome,
+            collateral_in: amount,
+            shares_out,
+        });
+        Ok(shares_out)
+    }
+
+    /// Sells `shares` YES (0) or NO (1) shares back to the pool for collateral, less the trading
+    /// fee. The caller must have approved this contract on the share collection. Reverts if less
+    /// than `min_collateral_out` would be paid. Returns the collateral paid.
+    pub fn sell(&mut self, market_id: U256, outcome: u8, shares: U256, min_collateral_out: U256) -> Result<U256, MarketError> {
+        self.require_open(market_id)?;
+        if outcome != YES && outcome != NO {
+            return Err(MarketError::InvalidOutcome(InvalidOutcome { outcome }));
+        }
+        if shares.is_zero() {
+            return Err(MarketError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let market = self.markets.getter(market_id);
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        let (removed, yes_after, no_after) = if outcome == YES {
+            calc_sell(yes_reserve, no_reserve, shares)
+        } else {
+            let (removed, no_after, yes_after) = calc_sell(no_reserve, yes_reserve, shares);
+            (removed, yes_after, no_after)
+        };
+        let fee = self.fee_of(removed);
+        let collateral_out = removed - fee;
+        if collateral_out < min_collateral_out {
+            return Err(MarketError::SlippageExceeded(SlippageExceeded {
+                amount: collateral_out,
+                minimum: min_collateral_out,
+            }));
+        }
+        let collateral = market.collateral.get();
+        let fees = market.fees.get();
+
+        let seller = self.vm().msg_sender();
+        let id = share_id(market_id, outcome);
+        self.call_shares(&ICradle1155::burnFromCall { from: seller, id, amount: shares }.abi_encode())?;
+        let mut market = self.markets.setter(market_id);
+        market.yes_reserve.set(yes_after);
+        market.no_reserve.set(no_after);
+        market.collateral.set(collateral - removed);
+        market.fees.set(fees + fee);
+        self.send_collateral(seller, collateral_out)?;
+
+        log(self.vm(), SharesSold {
+            market_id,
+            seller,
+            outcome,
+            shares_in: shares,
+            collateral_out,
+        });
+        Ok(collateral_out)
+    }
+
+    /// Resolves a market once trading has closed: 1 for YES, 2 for NO, 3 for invalid (the market's
+    /// resolver only; the owner may also declare an oracle-resolved market invalid).
+    pub fn resolve(&mut self, market_id: U256, resolution: u8) -> Result<(), MarketError> {
+        self.require_resolvable(market_id)?;
+        if !matches!(resolution, RESOLVED_YES | RESOLVED_NO | RESOLVED_INVALID) {
+            return Err(MarketError::InvalidOutcome(InvalidOutcome { outcome: resolution }));
+        }
+        let caller = self.vm().msg_sender();
+        let resolver = self.markets.getter(market_id).resolver.get();
+        let owner_invalidates = resolver.is_zero() && caller == self.owner.get() && resolution == RESOLVED_INVALID;
+        if caller != resolver && !owner_invalidates {
+            return Err(MarketError::Unauthorized(Unauthorized { caller }));
+        }
+        self.set_resolution(market_id, resolution);
+        Ok(())
+    }
+
+    /// Resolves an oracle-resolved market from its feed once trading has closed. Anyone can call
+    /// it. The price must have been updated after the close time.
+    pub fn resolve_from_oracle(&mut self, market_id: U256) -> Result<u8, MarketError> {
+        self.require_resolvable(market_id)?;
+        let market = self.markets.getter(market_id);
+        let feed = market.feed.get();
+        if feed.is_zero() {
+            return Err(MarketError::InvalidMarket(InvalidMarket {}));
+        }
+        let strike = market.strike.get();
+        let close_time = market.close_time.get();
+        let calldata = ICradleOracle::latestPriceCall { feed }.abi_encode();
+        let latest = self
+            .vm()
+            .static_call(&self, self.oracle.get(), &calldata)
+            .ok()
+            .and_then(|output| ICradleOracle::latestPriceCall::abi_decode_returns(&output, true).ok())
+            .ok_or(MarketError::OracleCallFailed(OracleCallFailed {}))?;
+        let (price, updated_at) = (latest._0, latest._1);
+        if updated_at < close_time {
+            return Err(MarketError::StalePrice(StalePrice { updated_at }));
+        }
+        let resolution = if price >= strike { RESOLVED_YES } else { RESOLVED_NO };
+        self.set_resolution(market_id, resolution);
+        Ok(resolution)
+    }
+
+    /// Burns the caller's shares of a resolved market and pays out their collateral. The caller
+    /// must have approved this contract on the share collection. Returns the payout.
+    pub fn redeem(&mut self, market_id: U256) -> Result<U256, MarketError> {
+        let account = self.vm().msg_sender();
+        let yes_id = share_id(market_id, YES);
+        let no_id = share_id(market_id, NO);
+        let yes = self.share_balance(account, yes_id);
+        let no = self.share_balance(account, no_id);
+        let payout = self.payout_of(market_id, yes, no)?;
+        if yes.is_zero() && no.is_zero() {
+            return Err(MarketError::NothingToClaim(NothingToClaim {}));
+        }
+
+        for (id, amount) in [(yes_id, yes), (no_id, no)] {
+            if !amount.is_zero() {
+                self.call_shares(&ICradle1155::burnFromCall { from: account, id, amount }.abi_encode())?;
+            }
+        }
+        let collateral = self.markets.getter(market_id).collateral.get();
+        self.markets.setter(market_id).collateral.set(collateral - payout);
+        if !payout.is_zero() {
+            self.send_collateral(account, payout)?;
+        }
+
+        log(self.vm(), Redeemed {
+            market_id,
+            account,
+            payout,
+        });
+        Ok(payout)
+    }
+
+    /// Pays the pool's shares of a resolved market and the trading fees to its creator (creator
+    /// only). Returns the amount paid.
+    pub fn withdraw_liquidity(&mut self, market_id: U256) -> Result<U256, MarketError> {
+        let market = self.markets.getter(market_id);
+        let creator = market.creator.get();
+        let caller = self.vm().msg_sender();
+        if caller != creator {
+            return Err(MarketError::Unauthorized(Unauthorized { caller }));
+        }
+        if market.liquidity_withdrawn.get() {
+            return Err(MarketError::NothingToClaim(NothingToClaim {}));
+        }
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        let fees = market.fees.get();
+        let collateral = market.collateral.get();
+        let payout = self.payout_of(market_id, yes_reserve, no_reserve)?;
+
+        let mut market = self.markets.setter(market_id);
+        market.liquidity_withdrawn.set(true);
+        market.yes_reserve.set(U256::ZERO);
+        market.no_reserve.set(U256::ZERO);
+        market.fees.set(U256::ZERO);
+        market.collateral.set(collateral - payout);
+        let amount = payout + fees;
+        self.send_collateral(creator, amount)?;
+
+        log(self.vm(), LiquidityWithdrawn {
+            market_id,
+            creator,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    /// Changes the trading fee (owner only).
+    pub fn set_fee(&mut self, fee_bps: U256) -> Result<(), MarketError> {
+        self.only_owner()?;
+        if fee_bps > U256::from(MAX_FEE_BPS) {
+            return Err(MarketError::InvalidFee(InvalidFee { fee_bps }));
+        }
+        self.fee_bps.set(fee_bps);
+
+        log(self.vm(), FeeUpdated { fee_bps });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), MarketError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(creator, question, resolver, feed, strike, close_time, resolution)`.
+    #[allow(clippy::type_complexity)]
+    pub fn get_market(&self, market_id: U256) -> (Address, String, Address, Address, U256, U256, u8) {
+        let market = self.markets.getter(market_id);
+        (
+            market.creator.get(),
+            market.question.get_string(),
+            market.resolver.get(),
+            market.feed.get(),
+            market.strike.get(),
+            market.close_time.get(),
+            market.resolution.get().to::<u8>(),
+        )
+    }
+
+    /// Returns `(yes_reserve, no_reserve, collateral, fees)`.
+    pub fn get_pool(&self, market_id: U256) -> (U256, U256, U256, U256) {
+        let market = self.markets.getter(market_id);
+        (
+            market.yes_reserve.get(),
+            market.no_reserve.get(),
+            market.collateral.get(),
+            market.fees.get(),
+        )
+    }
+
+    /// Price of a YES share with 18 decimals; NO costs one minus this.
+    pub fn yes_price(&self, market_id: U256) -> U256 {
+        let market = self.markets.getter(market_id);
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        if yes_reserve.is_zero() && no_reserve.is_zero() {
+            return U256::ZERO;
+        }
+        no_reserve * U256::from(PRICE_SCALE) / (yes_reserve + no_reserve)
+    }
+
+    /// Shares `buy` would give for `amount` collateral.
+    pub fn quote_buy(&self, market_id: U256, outcome: u8, amount: U256) -> U256 {
+        let market = self.markets.getter(market_id);
+        let invested = amount - self.fee_of(amount);
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        if yes_reserve.is_zero() {
+            return U256::ZERO;
+        }
+        let (bought, other) = if outcome == YES { (yes_reserve, no_reserve) } else { (no_reserve, yes_reserve) };
+        calc_buy(bought, other, invested).0
+    }
+
+    /// Collateral `sell` would pay for `shares`.
+    pub fn quote_sell(&self, market_id: U256, outcome: u8, shares: U256) -> U256 {
+        let market = self.markets.getter(market_id);
+        let (yes_reserve, no_reserve) = (market.yes_reserve.get(), market.no_reserve.get());
+        if yes_reserve.is_zero() {
+            return U256::ZERO;
+        }
+        let (sold, other) = if outcome == YES { (yes_reserve, no_reserve) } else { (no_reserve, yes_reserve) };
+        let removed = calc_sell(sold, other, shares).0;
+        removed - self.fee_of(removed)
+    }
+
+    /// ERC-1155 token ID of a market's YES (0) or NO (1) shares.
+    pub fn token_id(&self, market_id: U256, outcome: u8) -> U256 {
+        share_id(market_id, outcome)
+    }
+
+    /// Number of markets created.
+    pub fn market_count(&self) -> U256 {
+        self.market_count.get()
+    }
+
+    /// Returns `(shares, collateral, oracle, fee_bps)`.
+    pub fn config(&self) -> (Address, Address, Address, U256) {
+        (
+            self.shares.get(),
+            self.collateral.get(),
+            self.oracle.get(),
+            self.fee_bps.get(),
+        )
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,153 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const SHARES: Address = address!("0000000000000000000000000000000000001155");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const ORACLE: Address = address!("000000000000000000000000000000000000047a");
+const FEED: Address = address!("000000000000000000000000000000000000fee0");
+const RESOLVER: Address = address!("000000000000000000000000000000000000e501");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+
+/// Lets the market pull `amount` USDC from `from`.
+fn expect_pull(vm: &TestVM, from: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to: vm.contract_address(), value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets the market send `amount` USDC to `to`.
+fn expect_send(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC20::transferCall { to, value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets the market mint `amount` of share `id` to `to`.
+fn expect_mint(vm: &TestVM, to: Address, id: U256, amount: u64) {
+    let call = ICradle1155::mintCall { to, id, amount: n(amount), data: Vec::new().into() };
+    vm.mock_call(SHARES, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// Lets the market burn `amount` of share `id` from `from`.
+fn expect_burn(vm: &TestVM, from: Address, id: U256, amount: u64) {
+    let call = ICradle1155::burnFromCall { from, id, amount: n(amount) };
+    vm.mock_call(SHARES, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// Mocks how many of share `id` `account` holds.
+fn mock_shares(vm: &TestVM, account: Address, id: U256, balance: u64) {
+    let call = ICradle1155::balanceOfCall { account, id };
+    vm.mock_static_call(SHARES, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// A market at time 1,000 with a 2% fee, where Alice opened market 0 with 1,000 USDC. It closes at
+/// 2,000 and is resolved by `resolver`, or from the oracle with a strike of 50 if that is zero.
+fn setup(resolver: Address) -> (TestVM, PredictionMarket) {
+    let (vm, mut contract) = deploy::<PredictionMarket>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, SHARES, USDC, ORACLE, n(200)));
+    vm.set_sender(ALICE);
+    expect_pull(&vm, ALICE, 1_000);
+    let feed = if resolver.is_zero() { FEED } else { Address::ZERO };
+    assert_eq!(ok(contract.create_market("Rain?".into(), resolver, feed, n(50), n(2_000), n(1_000))), U256::ZERO);
+    (vm, contract)
+}
+
+#[test]
+fn winning_shares_and_the_pool_are_paid_out_after_resolution() {
+    let (vm, mut contract) = setup(RESOLVER);
+    let yes = share_id(U256::ZERO, YES);
+    vm.set_sender(BOB);
+    expect_pull(&vm, BOB, 500);
+    expect_mint(&vm, BOB, yes, 818);
+    assert_eq!(contract.quote_buy(U256::ZERO, YES, n(500)), n(818));
+    assert_eq!(ok(contract.buy(U256::ZERO, YES, n(500), n(818))), n(818));
+    assert_eq!(contract.get_pool(U256::ZERO), (n(672), n(1_490), n(1_490), n(10)));
+    assert_eq!(emitted::<SharesBought>(&vm)[0].shares_out, n(818));
+    let result = contract.resolve(U256::ZERO, RESOLVED_YES);
+    assert!(matches!(result, Err(MarketError::MarketOpen(e)) if e.close_time == n(2_000)));
+
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(RESOLVER);
+    ok(contract.resolve(U256::ZERO, RESOLVED_YES));
+    assert_eq!(contract.get_market(U256::ZERO).6, RESOLVED_YES);
+
+    // Each YES share redeems for one USDC, and the creator gets the pool's YES shares and the fees
+    vm.set_sender(BOB);
+    mock_shares(&vm, BOB, yes, 818);
+    expect_burn(&vm, BOB, yes, 818);
+    expect_send(&vm, BOB, 818);
+    assert_eq!(ok(contract.redeem(U256::ZERO)), n(818));
+    vm.set_sender(ALICE);
+    expect_send(&vm, ALICE, 682);
+    assert_eq!(ok(contract.withdraw_liquidity(U256::ZERO)), n(682));
+    assert_eq!(contract.get_pool(U256::ZERO), (U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO));
+    assert!(matches!(contract.withdraw_liquidity(U256::ZERO), Err(MarketError::NothingToClaim(_))));
+}
+
+#[test]
+fn shares_sell_back_to_the_pool_less_the_fee() {
+    let (vm, mut contract) = setup(RESOLVER);
+    let no = share_id(U256::ZERO, NO);
+    vm.set_sender(BOB);
+    expect_pull(&vm, BOB, 500);
+    expect_mint(&vm, BOB, no, 818);
+    ok(contract.buy(U256::ZERO, NO, n(500), U256::ZERO));
+
+    let result = contract.sell(U256::ZERO, NO, n(818), n(481));
+    assert!(matches!(result, Err(MarketError::SlippageExceeded(e)) if e.amount == n(480)));
+    expect_burn(&vm, BOB, no, 818);
+    expect_send(&vm, BOB, 480);
+    assert_eq!(ok(contract.sell(U256::ZERO, NO, n(818), n(480))), n(480));
+    // Rounding in the pool's favour leaves it a unit ahead of where it started
+    assert_eq!(contract.get_pool(U256::ZERO), (n(1_001), n(1_001), n(1_001), n(19)));
+    let sold = &emitted::<SharesSold>(&vm)[0];
+    assert_eq!((sold.seller, sold.shares_in, sold.collateral_out), (BOB, n(818), n(480)));
+}
+
+#[test]
+fn oracle_markets_settle_on_a_fresh_price() {
+    let (vm, mut contract) = setup(Address::ZERO);
+    vm.set_block_timestamp(2_000);
+    let call = ICradleOracle::latestPriceCall { feed: FEED };
+    vm.mock_static_call(ORACLE, call.abi_encode(), Ok((n(49), n(1_999)).abi_encode_params()));
+    let result = contract.resolve_from_oracle(U256::ZERO);
+    assert!(matches!(result, Err(MarketError::StalePrice(e)) if e.updated_at == n(1_999)));
+    let result = contract.resolve(U256::ZERO, RESOLVED_YES);
+    assert!(matches!(result, Err(MarketError::Unauthorized(e)) if e.caller == ALICE));
+
+    vm.mock_static_call(ORACLE, call.abi_encode(), Ok((n(49), n(2_000)).abi_encode_params()));
+    assert_eq!(ok(contract.resolve_from_oracle(U256::ZERO)), RESOLVED_NO);
+    let result = contract.resolve_from_oracle(U256::ZERO);
+    assert!(matches!(result, Err(MarketError::AlreadyResolved(_))));
+
+    // The pool's NO shares cover all of Alice's funding
+    expect_send(&vm, ALICE, 1_000);
+    assert_eq!(ok(contract.withdraw_liquidity(U256::ZERO)), n(1_000));
+}
+
+#[test]
+fn trades_that_cant_settle_revert() {
+    let (vm, mut contract) = setup(RESOLVER);
+    vm.set_sender(BOB);
+    let result = contract.buy(U256::ZERO, 2, n(500), U256::ZERO);
+    assert!(matches!(result, Err(MarketError::InvalidOutcome(e)) if e.outcome == 2));
+    let result = contract.buy(U256::ZERO, YES, n(500), n(819));
+    assert!(matches!(result, Err(MarketError::SlippageExceeded(e)) if e.amount == n(818)));
+    assert!(matches!(contract.redeem(U256::ZERO), Err(MarketError::NotResolved(_))));
+
+    // Collateral that doesn't move and shares that don't mint fail the trade
+    let result = contract.buy(U256::ZERO, YES, n(400), U256::ZERO);
+    assert!(matches!(result, Err(MarketError::TransferFailed(_))));
+    let call =
+        ICradle1155::mintCall { to: BOB, id: share_id(U256::ZERO, YES), amount: n(818), data: Vec::new().into() };
+    vm.mock_call(SHARES, call.abi_encode(), Err(b"not owner".to_vec()));
+    expect_pull(&vm, BOB, 500);
+    assert!(matches!(contract.buy(U256::ZERO, YES, n(500), U256::ZERO), Err(MarketError::ShareCallFailed(_))));
+
+    vm.set_block_timestamp(2_000);
+    let result = contract.buy(U256::ZERO, YES, n(500), U256::ZERO);
+    assert!(matches!(result, Err(MarketError::MarketClosed(e)) if e.close_time == n(2_000)));
+}
@@ -0,0 +1,771 @@
+//! Raffles for escrowed prizes
+//!
+//! An organizer escrows a prize (ERC-20, ERC-721 or ERC-1155) and sells tickets
+//! for ETH or an ERC-20 until the raffle ends or sells out. The organizer then
+//! draws it through the `randomness-stylus` component by committing to a secret;
+//! revealing the secret there delivers the randomness back to the raffle, which
+//! picks the winning ticket. Anyone can then settle the raffle, which sends the
+//! prize to the winner and the ticket proceeds to the beneficiary, minus the
+//! organizer's fee.
+//!
+//! If no tickets sell, the organizer can cancel and take the prize back. If the
+//! organizer never reveals, the draw expires: the prize returns to the organizer
+//! and every ticket is refunded, so withholding the secret can only abort a
+//! raffle, never pick its winner.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Upper bound for the organizer fee, 50%.
+const MAX_ORGANIZER_FEE_BPS: u64 = 5_000;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC721Received`.
+const ERC721_RECEIVED: u32 = 0x150b7a02;
+
+/// `prize_kind` of an ERC-20 prize.
+const PRIZE_ERC20: u8 = 0;
+/// `prize_kind` of an ERC-721 prize.
+const PRIZE_ERC721: u8 = 1;
+/// `prize_kind` of an ERC-1155 prize.
+const PRIZE_ERC1155: u8 = 2;
+
+/// Tickets are on sale.
+const STATE_OPEN: u8 = 0;
+/// Waiting for the randomness to be revealed.
+const STATE_DRAWING: u8 = 1;
+/// Winning ticket is known; ready to settle.
+const STATE_DRAWN: u8 = 2;
+/// Prize and proceeds have been paid out.
+const STATE_SETTLED: u8 = 3;
+/// Cancelled or expired; tickets are refundable.
+const STATE_CANCELLED: u8 = 4;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Raffle {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Can change the randomness contract
+        address owner;
+        /// `randomness-stylus` deployment used for draws
+        address randomness;
+        /// Raffles created so far
+        uint256 total_raffles;
+        /// Raffles by ID, starting at 1
+        mapping(uint256 => RaffleData) raffles;
+        /// Raffle drawn by each randomness request
+        mapping(uint256 => uint256) request_raffles;
+    }
+
+    pub struct RaffleData {
+        /// Escrowed the prize and draws the raffle
+        address organizer;
+        /// Receives the ticket proceeds minus the organizer fee
+        address beneficiary;
+        /// Organizer's cut of the proceeds, in basis points
+        uint256 organizer_fee_bps;
+        /// One of the `PRIZE_*` constants
+        uint8 prize_kind;
+        address prize_contract;
+        /// Ignored for ERC-20 prizes
+        uint256 prize_id;
+        /// Always 1 for ERC-721 prizes
+        uint256 prize_amount;
+        /// ERC-20 tickets are paid in, or the zero address for ETH
+        address payment_token;
+        uint256 ticket_price;
+        /// Zero means no limit
+        uint256 max_tickets;
+        uint256 end_time;
+        /// One of the `STATE_*` constants
+        uint8 state;
+        uint256 tickets_sold;
+        /// Buyer of each purchase
+        mapping(uint256 => address) purchase_buyers;
+        /// Tickets sold up to and including each purchase
+        mapping(uint256 => uint256) purchase_ends;
+        uint256 purchase_count;
+        /// Tickets bought per account, used for refunds
+        mapping(address => uint256) tickets_of;
+        /// Randomness request of the draw
+        uint256 request_id;
+        /// Index of the winning ticket
+        uint256 winning_ticket;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event RaffleCreated(uint256 indexed raffle_id, address indexed organizer, address prize_contract, uint256 prize_id, uint256 prize_amount, uint256 ticket_price, uint256 end_time);
+    event TicketsPurchased(uint256 indexed raffle_id, address indexed buyer, uint256 count, uint256 first_ticket);
+    event DrawRequested(uint256 indexed raffle_id, uint256 request_id);
+    event WinnerDrawn(uint256 indexed raffle_id, uint256 winning_ticket, address winner);
+    event RaffleSettled(uint256 indexed raffle_id, address indexed winner, uint256 proceeds, uint256 organizer_fee);
+    event RaffleCancelled(uint256 indexed raffle_id);
+    event Refunded(uint256 indexed raffle_id, address indexed buyer, uint256 amount);
+    event RandomnessUpdated(address randomness);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Raffle parameters are invalid
+    error InvalidRaffle();
+    // Raffle ID doesn't exist
+    error UnknownRaffle(uint256 raffle_id);
+    // Action is not available in the raffle's current state
+    error WrongState(uint256 raffle_id, uint8 state);
+    // Ticket sales have closed
+    error SalesClosed(uint256 raffle_id);
+    // Ticket sales are still open
+    error SalesOpen(uint256 raffle_id);
+    // Purchase would exceed the ticket limit
+    error SoldOut(uint256 raffle_id, uint256 available);
+    // Attached ETH doesn't match the ticket cost
+    error IncorrectPayment(uint256 expected, uint256 received);
+    // No tickets to refund, or tickets were sold so the raffle can't be cancelled
+    error NothingToRefund(uint256 raffle_id, address account);
+    // Randomness for the draw is not available yet, or the request hasn't expired
+    error DrawPending(uint256 raffle_id);
+    // A call to the randomness contract failed
+    error RandomnessCallFailed();
+    // A token or ETH transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum RaffleError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidRaffle(InvalidRaffle),
+    UnknownRaffle(UnknownRaffle),
+    WrongState(WrongState),
+    SalesClosed(SalesClosed),
+    SalesOpen(SalesOpen),
+    SoldOut(SoldOut),
+    IncorrectPayment(IncorrectPayment),
+    NothingToRefund(NothingToRefund),
+    DrawPending(DrawPending),
+    RandomnessCallFailed(RandomnessCallFailed),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+
+    interface IERC721 {
+        function safeTransferFrom(address from, address to, uint256 token_id) external;
+    }
+
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IRandomness {
+        function request(bytes32 commitment, bool mix_blockhash) external returns (uint256);
+        function randomness(uint256 request_id) external view returns (bool, bytes32);
+        function isExpired(uint256 request_id) external view returns (bool);
+    }
+}
+
+/// Context of the calls to prize contracts, payment tokens and the randomness contract, and of ETH
+/// payouts, with the ETH they send. Made through the host, unlike `sol_interface!` calls, they
+/// build with the SDK's `reentrant` feature and are mocked under test.
+struct RaffleCall(U256);
+
+impl calls::CallContext for RaffleCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a prize contract calls back into the raffle's receiver hooks
+unsafe impl calls::MutatingCallContext for RaffleCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl Raffle {
+    fn only_owner(&self) -> Result<(), RaffleError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(RaffleError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Returns the raffle's state, failing if it doesn't exist or isn't `expected`.
+    fn require_state(&self, raffle_id: U256, expected: u8) -> Result<(), RaffleError> {
+        let raffle = self.raffles.getter(raffle_id);
+        if raffle.organizer.get().is_zero() {
+            return Err(RaffleError::UnknownRaffle(UnknownRaffle { raffle_id }));
+        }
+        let state = raffle.state.get().to::<u8>();
+        if state != expected {
+            return Err(RaffleError::WrongState(WrongState { raffle_id, state }));
+        }
+        Ok(())
+    }
+
+    fn set_state(&mut self, raffle_id: U256, state: u8) {
+        self.raffles
+            .setter(raffle_id)
+            .state
+            .set(alloy_primitives::Uint::<8, 1>::from(state));
+    }
+
+    /// Calls `target` with `calldata`, attaching `value` wei.
+    fn call(&mut self, target: Address, value: U256, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&RaffleCall(value), target, calldata).map_err(Vec::from)
+    }
+
+    /// Calls an ERC-20 transfer method, which must return `true`.
+    fn call_token(&mut self, token: Address, calldata: &[u8]) -> bool {
+        self.call(token, U256::ZERO, calldata)
+            .is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+    }
+
+    /// Reads the randomness contract.
+    fn randomness_view<C: SolCall>(&self, call: C) -> Result<C::Return, RaffleError> {
+        self.vm()
+            .static_call(&self, self.randomness.get(), &call.abi_encode())
+            .ok()
+            .and_then(|output| C::abi_decode_returns(&output, true).ok())
+            .ok_or(RaffleError::RandomnessCallFailed(RandomnessCallFailed {}))
+    }
+
+    /// Moves the raffle's prize from `from` to `to`.
+    fn transfer_prize(&mut self, raffle_id: U256, from: Address, to: Address) -> Result<(), RaffleError> {
+        let raffle = self.raffles.getter(raffle_id);
+        let prize_kind = raffle.prize_kind.get().to::<u8>();
+        let prize_contract = raffle.prize_contract.get();
+        let prize_id = raffle.prize_id.get();
+        let amount = raffle.prize_amount.get();
+        let this = self.vm().contract_address();
+        let transferred = match prize_kind {
+            PRIZE_ERC20 if from == this => {
+                self.call_token(prize_contract, &IERC20::transferCall { to, value: amount }.abi_encode())
+            }
+            PRIZE_ERC20 => {
+                let call = IERC20::transferFromCall { from, to, value: amount };
+                self.call_token(prize_contract, &call.abi_encode())
+            }
+            PRIZE_ERC721 => {
+                let call = IERC721::safeTransferFromCall { from, to, token_id: prize_id };
+                self.call(prize_contract, U256::ZERO, &call.abi_encode()).is_ok()
+            }
+            _ => {
+                let call =
+                    IERC1155::safeTransferFromCall { from, to, id: prize_id, value: amount, data: Default::default() };
+                self.call(prize_contract, U256::ZERO, &call.abi_encode()).is_ok()
+            }
+        };
+        if !transferred {
+            return Err(RaffleError::TransferFailed(TransferFailed {
+                token: prize_contract,
+                to,
+                amount,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Pays `amount` of `payment_token`, or ETH for the zero address, from the contract to `to`.
+    fn pay(&mut self, payment_token: Address, to: Address, amount: U256) -> Result<(), RaffleError> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let sent = if payment_token.is_zero() {
+            self.call(to, amount, &[]).is_ok()
+        } else {
+            self.call_token(payment_token, &IERC20::transferCall { to, value: amount }.abi_encode())
+        };
+        if !sent {
+            return Err(RaffleError::TransferFailed(TransferFailed {
+                token: payment_token,
+                to,
+                amount,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Owner of `ticket`, found by binary search over the purchases.
+    fn ticket_owner(&self, raffle_id: U256, ticket: U256) -> Address {
+        let raffle = self.raffles.getter(raffle_id);
+        let (mut low, mut high) = (U256::ZERO, raffle.purchase_count.get());
+        while low < high {
+            let mid = (low + high) / U256::from(2);
+            if raffle.purchase_ends.get(mid) > ticket {
+                high = mid;
+            } else {
+                low = mid + U256::from(1);
+            }
+        }
+        raffle.purchase_buyers.get(low)
+    }
+
+    /// Records the winning ticket once the randomness for the draw is known.
+    fn record_draw(&mut self, raffle_id: U256, randomness: B256) {
+        let tickets_sold = self.raffles.getter(raffle_id).tickets_sold.get();
+        let winning_ticket = U256::from_be_bytes(randomness.0) % tickets_sold;
+        self.raffles.setter(raffle_id).winning_ticket.set(winning_ticket);
+        self.set_state(raffle_id, STATE_DRAWN);
+
+        let winner = self.ticket_owner(raffle_id, winning_ticket);
+        log(self.vm(), WinnerDrawn {
+            raffle_id,
+            winning_ticket,
+            winner,
+        });
+    }
+
+    fn escrow_receipt(&self, operator: Address, selector: u32) -> FixedBytes<4> {
+        if operator == self.vm().contract_address() {
+            FixedBytes(selector.to_be_bytes())
+        } else {
+            FixedBytes([0; 4])
+        }
+    }
+}
+
+#[public]
+impl Raffle {
+    /// Sets the owner and the randomness contract. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, randomness: Address) -> Result<(), RaffleError> {
+        if self.initialized.get() {
+            return Err(RaffleError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.randomness.set(randomness);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Escrows the prize and opens ticket sales. Returns the raffle ID.
+    /// ERC-20 prizes need an allowance; ERC-721 and ERC-1155 prizes need `setApprovalForAll`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_raffle(
+        &mut self,
+        prize_kind: u8,
+        prize_contract: Address,
+        prize_id: U256,
+        prize_amount: U256,
+        payment_token: Address,
+        ticket_price: U256,
+        max_tickets: U256,
+        end_time: U256,
+        beneficiary: Address,
+        organizer_fee_bps: U256,
+    ) -> Result<U256, RaffleError> {
+        let prize_amount = if prize_kind == PRIZE_ERC721 {
+            U256::from(1)
+        } else {
+            prize_amount
+        };
+        if prize_kind > PRIZE_ERC1155
+            || prize_amount.is_zero()
+            || ticket_price.is_zero()
+            || end_time <= U256::from(self.vm().block_timestamp())
+            || organizer_fee_bps > U256::from(MAX_ORGANIZER_FEE_BPS)
+        {
+            return Err(RaffleError::InvalidRaffle(InvalidRaffle {}));
+        }
+
+        let organizer = self.vm().msg_sender();
+        let raffle_id = self.total_raffles.get() + U256::from(1);
+        self.total_raffles.set(raffle_id);
+
+        let mut raffle = self.raffles.setter(raffle_id);
+        raffle.organizer.set(organizer);
+        raffle.beneficiary.set(if beneficiary.is_zero() { organizer } else { beneficiary });
+        raffle.organizer_fee_bps.set(organizer_fee_bps);
+        raffle.prize_kind.set(alloy_primitives::Uint::<8, 1>::from(prize_kind));
+        raffle.prize_contract.set(prize_contract);
+        raffle.prize_id.set(prize_id);
+        raffle.prize_amount.set(prize_amount);
+        raffle.payment_token.set(payment_token);
+        raffle.ticket_price.set(ticket_price);
+        raffle.max_tickets.set(max_tickets);
+        raffle.end_time.set(end_time);
+
+        let this = self.vm().contract_address();
+        self.transfer_prize(raffle_id, organizer, this)?;
+
+        log(self.vm(), RaffleCreated {
+            raffle_id,
+            organizer,
+            prize_contract,
+            prize_id,
+            prize_amount,
+            ticket_price,
+            end_time,
+        });
+        Ok(raffle_id)
+    }
+
+    /// Buys `count` tickets. ETH raffles require the exact cost as `msg.value`,
+    /// ERC-20 raffles require an allowance for it.
+    #[payable]
+    pub fn buy_tickets(&mut self, raffle_id: U256, count: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_OPEN)?;
+        let raffle = self.raffles.getter(raffle_id);
+        if U256::from(self.vm().block_timestamp()) >= raffle.end_time.get() {
+            return Err(RaffleError::SalesClosed(SalesClosed { raffle_id }));
+        }
+        let tickets_sold = raffle.tickets_sold.get();
+        let max_tickets = raffle.max_tickets.get();
+        if count.is_zero() || (!max_tickets.is_zero() && tickets_sold + count > max_tickets) {
+            return Err(RaffleError::SoldOut(SoldOut {
+                raffle_id,
+                available: max_tickets.saturating_sub(tickets_sold),
+            }));
+        }
+        let cost = raffle.ticket_price.get() * count;
+        let payment_token = raffle.payment_token.get();
+
+        let buyer = self.vm().msg_sender();
+        let mut raffle = self.raffles.setter(raffle_id);
+        let purchase = raffle.purchase_count.get();
+        raffle.purchase_buyers.insert(purchase, buyer);
+        raffle.purchase_ends.insert(purchase, tickets_sold + count);
+        raffle.purchase_count.set(purchase + U256::from(1));
+        raffle.tickets_sold.set(tickets_sold + count);
+        let bought = raffle.tickets_of.get(buyer);
+        raffle.tickets_of.insert(buyer, bought + count);
+
+        if payment_token.is_zero() {
+            let received = self.vm().msg_value();
+            if received != cost {
+                return Err(RaffleError::IncorrectPayment(IncorrectPayment {
+                    expected: cost,
+                    received,
+                }));
+            }
+        } else {
+            let received = self.vm().msg_value();
+            if !received.is_zero() {
+                return Err(RaffleError::IncorrectPayment(IncorrectPayment {
+                    expected: U256::ZERO,
+                    received,
+                }));
+            }
+            let this = self.vm().contract_address();
+            let call = IERC20::transferFromCall { from: buyer, to: this, value: cost };
+            if !self.call_token(payment_token, &call.abi_encode()) {
+                return Err(RaffleError::TransferFailed(TransferFailed {
+                    token: payment_token,
+                    to: this,
+                    amount: cost,
+                }));
+            }
+        }
+
+        log(self.vm(), TicketsPurchased {
+            raffle_id,
+            buyer,
+            count,
+            first_ticket: tickets_sold,
+        });
+        Ok(())
+    }
+
+    /// Closes ticket sales and requests the draw's randomness against `commitment = keccak256(secret)`
+    /// (organizer only). Reveal the secret with `fulfill` on the randomness contract to pick the winner.
+    pub fn draw(&mut self, raffle_id: U256, commitment: B256) -> Result<U256, RaffleError> {
+        self.require_state(raffle_id, STATE_OPEN)?;
+        let raffle = self.raffles.getter(raffle_id);
+        let caller = self.vm().msg_sender();
+        if caller != raffle.organizer.get() {
+            return Err(RaffleError::Unauthorized(Unauthorized { caller }));
+        }
+        let tickets_sold = raffle.tickets_sold.get();
+        let sold_out = !raffle.max_tickets.get().is_zero() && tickets_sold == raffle.max_tickets.get();
+        if tickets_sold.is_zero() || (U256::from(self.vm().block_timestamp()) < raffle.end_time.get() && !sold_out) {
+            return Err(RaffleError::SalesOpen(SalesOpen { raffle_id }));
+        }
+
+        let randomness = self.randomness.get();
+        let call = IRandomness::requestCall { commitment, mix_blockhash: true };
+        let request_id = self
+            .call(randomness, U256::ZERO, &call.abi_encode())
+            .ok()
+            .and_then(|output| IRandomness::requestCall::abi_decode_returns(&output, true).ok())
+            .ok_or(RaffleError::RandomnessCallFailed(RandomnessCallFailed {}))?
+            ._0;
+        self.raffles.setter(raffle_id).request_id.set(request_id);
+        self.request_raffles.insert(request_id, raffle_id);
+        self.set_state(raffle_id, STATE_DRAWING);
+
+        log(self.vm(), DrawRequested { raffle_id, request_id });
+        Ok(request_id)
+    }
+
+    /// Receives the draw's randomness from the randomness contract.
+    pub fn on_randomness_fulfilled(&mut self, request_id: U256, randomness: B256) -> Result<(), RaffleError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.randomness.get() {
+            return Err(RaffleError::Unauthorized(Unauthorized { caller }));
+        }
+        let raffle_id = self.request_raffles.get(request_id);
+        self.require_state(raffle_id, STATE_DRAWING)?;
+        if self.raffles.getter(raffle_id).request_id.get() != request_id {
+            return Err(RaffleError::DrawPending(DrawPending { raffle_id }));
+        }
+        self.record_draw(raffle_id, randomness);
+        Ok(())
+    }
+
+    /// Reads a fulfilled draw from the randomness contract, in case its callback failed.
+    pub fn sync_draw(&mut self, raffle_id: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_DRAWING)?;
+        let request_id = self.raffles.getter(raffle_id).request_id.get();
+        let IRandomness::randomnessReturn { _0: fulfilled, _1: randomness } =
+            self.randomness_view(IRandomness::randomnessCall { request_id })?;
+        if !fulfilled {
+            return Err(RaffleError::DrawPending(DrawPending { raffle_id }));
+        }
+        self.record_draw(raffle_id, randomness);
+        Ok(())
+    }
+
+    /// Sends the prize to the winner and the proceeds to the beneficiary and organizer. Callable by anyone.
+    pub fn settle(&mut self, raffle_id: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_DRAWN)?;
+        let raffle = self.raffles.getter(raffle_id);
+        let winner = self.ticket_owner(raffle_id, raffle.winning_ticket.get());
+        let proceeds = raffle.ticket_price.get() * raffle.tickets_sold.get();
+        let organizer_fee = proceeds * raffle.organizer_fee_bps.get() / U256::from(BPS_DENOMINATOR);
+        let payment_token = raffle.payment_token.get();
+        let organizer = raffle.organizer.get();
+        let beneficiary = raffle.beneficiary.get();
+        self.set_state(raffle_id, STATE_SETTLED);
+
+        let this = self.vm().contract_address();
+        self.transfer_prize(raffle_id, this, winner)?;
+        self.pay(payment_token, organizer, organizer_fee)?;
+        self.pay(payment_token, beneficiary, proceeds - organizer_fee)?;
+
+        log(self.vm(), RaffleSettled {
+            raffle_id,
+            winner,
+            proceeds,
+            organizer_fee,
+        });
+        Ok(())
+    }
+
+    /// Cancels a raffle with no tickets sold and returns the prize (organizer only).
+    pub fn cancel(&mut self, raffle_id: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_OPEN)?;
+        let raffle = self.raffles.getter(raffle_id);
+        let organizer = raffle.organizer.get();
+        let caller = self.vm().msg_sender();
+        if caller != organizer {
+            return Err(RaffleError::Unauthorized(Unauthorized { caller }));
+        }
+        if !raffle.tickets_sold.get().is_zero() {
+            return Err(RaffleError::NothingToRefund(NothingToRefund {
+                raffle_id,
+                account: caller,
+            }));
+        }
+        self.set_state(raffle_id, STATE_CANCELLED);
+        let this = self.vm().contract_address();
+        self.transfer_prize(raffle_id, this, organizer)?;
+
+        log(self.vm(), RaffleCancelled { raffle_id });
+        Ok(())
+    }
+
+    /// Cancels a raffle whose draw was never revealed, returns the prize to the organizer
+    /// and makes every ticket refundable. Callable by anyone.
+    pub fn expire_draw(&mut self, raffle_id: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_DRAWING)?;
+        let request_id = self.raffles.getter(raffle_id).request_id.get();
+        let expired = self.randomness_view(IRandomness::isExpiredCall { request_id })?._0;
+        if !expired {
+            return Err(RaffleError::DrawPending(DrawPending { raffle_id }));
+        }
+        self.set_state(raffle_id, STATE_CANCELLED);
+        let organizer = self.raffles.getter(raffle_id).organizer.get();
+        let this = self.vm().contract_address();
+        self.transfer_prize(raffle_id, this, organizer)?;
+
+        log(self.vm(), RaffleCancelled { raffle_id });
+        Ok(())
+    }
+
+    /// Refunds the caller's tickets in a cancelled raffle.
+    pub fn refund(&mut self, raffle_id: U256) -> Result<(), RaffleError> {
+        self.require_state(raffle_id, STATE_CANCELLED)?;
+        let buyer = self.vm().msg_sender();
+        let raffle = self.raffles.getter(raffle_id);
+        let tickets = raffle.tickets_of.get(buyer);
+        if tickets.is_zero() {
+            return Err(RaffleError::NothingToRefund(NothingToRefund {
+                raffle_id,
+                account: buyer,
+            }));
+        }
+        let amount = tickets * raffle.ticket_price.get();
+        let payment_token = raffle.payment_token.get();
+        self.raffles.setter(raffle_id).tickets_of.insert(buyer, U256::ZERO);
+        self.pay(payment_token, buyer, amount)?;
+
+        log(self.vm(), Refunded {
+            raffle_id,
+            buyer,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Points draws at a different randomness contract (owner only). Draws already pending against the
+    /// old contract can then only expire, so switch while none are in flight.
+    pub fn set_randomness(&mut self, randomness: Address) -> Result<(), RaffleError> {
+        self.only_owner()?;
+        self.randomness.set(randomness);
+
+        log(self.vm(), RandomnessUpdated { randomness });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), RaffleError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC1155_RECEIVED)
+    }
+
+    #[selector(name = "onERC721Received")]
+    pub fn on_erc721_received(
+        &mut self,
+        operator: Address,
+        _from: Address,
+        _token_id: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        self.escrow_receipt(operator, ERC721_RECEIVED)
+    }
+
+    /// Returns `(organizer, prize_kind, prize_contract, prize_id, prize_amount, payment_token, ticket_price,
+    /// max_tickets, end_time)`.
+    #[allow(clippy::type_complexity)]
+    pub fn raffle(&self, raffle_id: U256) -> (Address, u8, Address, U256, U256, Address, U256, U256, U256) {
+        let raffle = self.raffles.getter(raffle_id);
+        (
+            raffle.organizer.get(),
+            raffle.prize_kind.get().to::<u8>(),
+            raffle.prize_contract.get(),
+            raffle.prize_id.get(),
+            raffle.prize_amount.get(),
+            raffle.payment_token.get(),
+            raffle.ticket_price.get(),
+            raffle.max_tickets.get(),
+            raffle.end_time.get(),
+        )
+    }
+
+    /// Returns `(state, tickets_sold, request_id, winning_ticket)`.
+    pub fn raffle_state(&self, raffle_id: U256) -> (u8, U256, U256, U256) {
+        let raffle = self.raffles.getter(raffle_id);
+        (
+            raffle.state.get().to::<u8>(),
+            raffle.tickets_sold.get(),
+            raffle.request_id.get(),
+            raffle.winning_ticket.get(),
+        )
+    }
+
+    /// Returns `(beneficiary, organizer_fee_bps)`.
+    pub fn proceeds_split(&self, raffle_id: U256) -> (Address, U256) {
+        let raffle = self.raffles.getter(raffle_id);
+        (raffle.beneficiary.get(), raffle.organizer_fee_bps.get())
+    }
+
+    /// Winner of a drawn or settled raffle, or the zero address.
+    pub fn winner(&self, raffle_id: U256) -> Address {
+        let raffle = self.raffles.getter(raffle_id);
+        let state = raffle.state.get().to::<u8>();
+        if state != STATE_DRAWN && state != STATE_SETTLED {
+            return Address::ZERO;
+        }
+        self.ticket_owner(raffle_id, raffle.winning_ticket.get())
+    }
+
+    /// Owner of a ticket, or the zero address if it wasn't sold.
+    pub fn owner_of_ticket(&self, raffle_id: U256, ticket: U256) -> Address {
+        if ticket >= self.raffles.getter(raffle_id).tickets_sold.get() {
+            return Address::ZERO;
+        }
+        self.ticket_owner(raffle_id, ticket)
+    }
+
+    /// Tickets `account` holds in a raffle; zero after a refund.
+    pub fn tickets_of(&self, raffle_id: U256, account: Address) -> U256 {
+        self.raffles.getter(raffle_id).tickets_of.get(account)
+    }
+
+    /// Randomness contract used for draws.
+    pub fn randomness(&self) -> Address {
+        self.randomness.get()
+    }
+
+    /// Number of raffles created.
+    pub fn total_raffles(&self) -> U256 {
+        self.total_raffles.get()
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,229 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ORGANIZER: Address = address!("00000000000000000000000000000000000000a9");
+const BENEFICIARY: Address = address!("000000000000000000000000000000000000be9e");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const RANDOMNESS: Address = address!("000000000000000000000000000000000000da7a");
+const NFT: Address = address!("0000000000000000000000000000000000000721");
+const PRIZE: Address = address!("00000000000000000000000000000000000020c0");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const COMMITMENT: B256 = B256::repeat_byte(0xc0);
+
+fn setup() -> (TestVM, Raffle) {
+    let (vm, mut contract) = deploy::<Raffle>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, RANDOMNESS));
+    (vm, contract)
+}
+
+/// Raffles the organizer's ERC-721 token 7 for tickets of 100 `payment_token`, ten at most, until
+/// 2,000, with a 10% organizer fee.
+fn create(vm: &TestVM, contract: &mut Raffle, payment_token: Address) -> Result<U256, RaffleError> {
+    vm.set_sender(ORGANIZER);
+    vm.set_value(U256::ZERO);
+    let (price, max_tickets, end_time, fee_bps) = (n(100), n(10), n(2_000), n(1_000));
+    contract.create_raffle(
+        PRIZE_ERC721,
+        NFT,
+        n(7),
+        n(1),
+        payment_token,
+        price,
+        max_tickets,
+        end_time,
+        BENEFICIARY,
+        fee_bps,
+    )
+}
+
+/// Buys `count` tickets as `buyer`, attaching `value` wei.
+fn buy(vm: &TestVM, contract: &mut Raffle, buyer: Address, count: u64, value: u64) -> Result<(), RaffleError> {
+    vm.set_sender(buyer);
+    vm.set_value(n(value));
+    contract.buy_tickets(U256::from(1), n(count))
+}
+
+/// Draws raffle 1 as the organizer at the end of sales, the randomness contract answering with
+/// request 5.
+fn draw(vm: &TestVM, contract: &mut Raffle) -> U256 {
+    let request = IRandomness::requestCall { commitment: COMMITMENT, mix_blockhash: true };
+    vm.mock_call(RANDOMNESS, request.abi_encode(), Ok(n(5).abi_encode()));
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(ORGANIZER);
+    vm.set_value(U256::ZERO);
+    ok(contract.draw(U256::from(1), COMMITMENT))
+}
+
+#[test]
+fn the_drawn_ticket_wins_and_the_proceeds_are_split() {
+    let (vm, mut contract) = setup();
+    let raffle_id = ok(create(&vm, &mut contract, Address::ZERO));
+    ok(buy(&vm, &mut contract, ALICE, 3, 300));
+    ok(buy(&vm, &mut contract, BOB, 2, 200));
+    assert_eq!((contract.owner_of_ticket(raffle_id, n(2)), contract.owner_of_ticket(raffle_id, n(3))), (ALICE, BOB));
+    assert_eq!(contract.owner_of_ticket(raffle_id, n(5)), Address::ZERO);
+
+    // Only the organizer draws, and only once sales close
+    vm.set_sender(ORGANIZER);
+    assert!(matches!(contract.draw(raffle_id, COMMITMENT), Err(RaffleError::SalesOpen(_))));
+    vm.set_block_timestamp(2_000);
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.draw(raffle_id, COMMITMENT), Err(RaffleError::Unauthorized(e)) if e.caller == ALICE));
+    assert_eq!(draw(&vm, &mut contract), n(5));
+    assert_eq!(contract.raffle_state(raffle_id), (STATE_DRAWING, n(5), n(5), U256::ZERO));
+
+    // Only the randomness contract delivers it: 8 picks ticket 3 of 5
+    let result = contract.on_randomness_fulfilled(n(5), U256::from(8).into());
+    assert!(matches!(result, Err(RaffleError::Unauthorized(e)) if e.caller == ORGANIZER));
+    vm.set_sender(RANDOMNESS);
+    ok(contract.on_randomness_fulfilled(n(5), U256::from(8).into()));
+    assert_eq!(contract.winner(raffle_id), BOB);
+
+    vm.set_sender(ALICE);
+    ok(contract.settle(raffle_id));
+    let settled = &emitted::<RaffleSettled>(&vm)[0];
+    assert_eq!((settled.winner, settled.proceeds, settled.organizer_fee), (BOB, n(500), n(50)));
+    assert_eq!(contract.raffle_state(raffle_id).0, STATE_SETTLED);
+    assert!(matches!(contract.settle(raffle_id), Err(RaffleError::WrongState(e)) if e.state == STATE_SETTLED));
+}
+
+#[test]
+fn token_raffles_pull_tickets_and_pay_out_in_the_token() {
+    let (vm, mut contract) = setup();
+    // An ERC-20 prize is pulled from the organizer
+    let pull_prize = IERC20::transferFromCall { from: ORGANIZER, to: vm.contract_address(), value: n(1_000) };
+    vm.mock_call(PRIZE, pull_prize.abi_encode(), Ok(true.abi_encode()));
+    vm.set_sender(ORGANIZER);
+    let (price, end_time, fee_bps) = (n(100), n(2_000), n(1_000));
+    let created = contract.create_raffle(
+        PRIZE_ERC20,
+        PRIZE,
+        U256::ZERO,
+        n(1_000),
+        USDC,
+        price,
+        U256::ZERO,
+        end_time,
+        BENEFICIARY,
+        fee_bps,
+    );
+    let raffle_id = ok(created);
+
+    let pull = IERC20::transferFromCall { from: ALICE, to: vm.contract_address(), value: n(400) };
+    vm.mock_call(USDC, pull.abi_encode(), Ok(true.abi_encode()));
+    ok(buy(&vm, &mut contract, ALICE, 4, 0));
+
+    // A draw whose callback failed is read back from the randomness contract
+    draw(&vm, &mut contract);
+    let read = IRandomness::randomnessCall { request_id: n(5) };
+    vm.mock_static_call(RANDOMNESS, read.abi_encode(), Ok((false, B256::ZERO).abi_encode()));
+    assert!(matches!(contract.sync_draw(raffle_id), Err(RaffleError::DrawPending(_))));
+    vm.mock_static_call(RANDOMNESS, read.abi_encode(), Ok((true, B256::from(U256::from(1))).abi_encode()));
+    ok(contract.sync_draw(raffle_id));
+    assert_eq!(contract.winner(raffle_id), ALICE);
+
+    // Only the exact payouts are mocked
+    for (to, value) in [(ALICE, 1_000), (ORGANIZER, 40), (BENEFICIARY, 360)] {
+        let token = if to == ALICE { PRIZE } else { USDC };
+        vm.mock_call(token, IERC20::transferCall { to, value: n(value) }.abi_encode(), Ok(true.abi_encode()));
+    }
+    ok(contract.settle(raffle_id));
+}
+
+#[test]
+fn token_tickets_take_no_eth_and_fail_unless_the_payment_moves() {
+    let (vm, mut contract) = setup();
+    ok(create(&vm, &mut contract, USDC));
+    let result = buy(&vm, &mut contract, BOB, 1, 100);
+    assert!(matches!(result, Err(RaffleError::IncorrectPayment(e)) if e.expected.is_zero()));
+    let result = buy(&vm, &mut contract, BOB, 1, 0);
+    assert!(matches!(
+        result,
+        Err(RaffleError::TransferFailed(e)) if e.token == USDC && e.to == vm.contract_address() && e.amount == n(100)
+    ));
+}
+
+#[test]
+fn unrevealed_draws_expire_into_refunds() {
+    let (vm, mut contract) = setup();
+    let raffle_id = ok(create(&vm, &mut contract, Address::ZERO));
+    ok(buy(&vm, &mut contract, ALICE, 2, 200));
+    ok(buy(&vm, &mut contract, BOB, 1, 100));
+    draw(&vm, &mut contract);
+
+    let expired = IRandomness::isExpiredCall { request_id: n(5) };
+    vm.mock_static_call(RANDOMNESS, expired.abi_encode(), Ok(false.abi_encode()));
+    assert!(matches!(contract.expire_draw(raffle_id), Err(RaffleError::DrawPending(_))));
+    vm.mock_static_call(RANDOMNESS, expired.abi_encode(), Ok(true.abi_encode()));
+    ok(contract.expire_draw(raffle_id));
+    assert_eq!(contract.raffle_state(raffle_id).0, STATE_CANCELLED);
+
+    vm.set_sender(ALICE);
+    ok(contract.refund(raffle_id));
+    let refunded = &emitted::<Refunded>(&vm)[0];
+    assert_eq!((refunded.buyer, refunded.amount), (ALICE, n(200)));
+    let result = contract.refund(raffle_id);
+    assert!(matches!(result, Err(RaffleError::NothingToRefund(e)) if e.account == ALICE));
+
+    // A buyer that refuses ETH can't be refunded
+    vm.mock_call(BOB, Vec::new(), Err(b"no ETH".to_vec()));
+    vm.set_sender(BOB);
+    let result = contract.refund(raffle_id);
+    assert!(matches!(result, Err(RaffleError::TransferFailed(e)) if e.to == BOB && e.amount == n(100)));
+}
+
+#[test]
+fn raffles_are_checked_and_cancel_only_unsold() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ORGANIZER);
+    let mut create_ending = |end_time: u64, fee_bps: u64| {
+        let (price, max_tickets) = (n(100), n(10));
+        let (end_time, fee_bps) = (n(end_time), n(fee_bps));
+        contract.create_raffle(
+            PRIZE_ERC721,
+            NFT,
+            n(7),
+            n(1),
+            Address::ZERO,
+            price,
+            max_tickets,
+            end_time,
+            BENEFICIARY,
+            fee_bps,
+        )
+    };
+    let past = create_ending(1_000, 0);
+    assert!(matches!(past, Err(RaffleError::InvalidRaffle(_))));
+    let greedy = create_ending(2_000, 5_001);
+    assert!(matches!(greedy, Err(RaffleError::InvalidRaffle(_))));
+    assert!(matches!(buy(&vm, &mut contract, ALICE, 1, 100), Err(RaffleError::UnknownRaffle(_))));
+
+    let raffle_id = ok(create(&vm, &mut contract, Address::ZERO));
+    assert_eq!(contract.proceeds_split(raffle_id), (BENEFICIARY, n(1_000)));
+    let result = buy(&vm, &mut contract, ALICE, 11, 1_100);
+    assert!(matches!(result, Err(RaffleError::SoldOut(e)) if e.available == n(10)));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.cancel(raffle_id), Err(RaffleError::Unauthorized(_))));
+    vm.set_sender(ORGANIZER);
+    ok(contract.cancel(raffle_id));
+    assert_eq!(contract.raffle_state(raffle_id).0, STATE_CANCELLED);
+
+    // Sold tickets keep the raffle running
+    let raffle_id = ok(create(&vm, &mut contract, Address::ZERO));
+    vm.set_sender(ALICE);
+    vm.set_value(n(100));
+    ok(contract.buy_tickets(raffle_id, n(1)));
+    vm.set_sender(ORGANIZER);
+    assert!(matches!(contract.cancel(raffle_id), Err(RaffleError::NothingToRefund(_))));
+
+    // And a prize that won't move fails the raffle
+    let escrow = IERC721::safeTransferFromCall { from: ORGANIZER, to: vm.contract_address(), token_id: n(7) };
+    vm.mock_call(NFT, escrow.abi_encode(), Err(b"not approved".to_vec()));
+    let result = create(&vm, &mut contract, Address::ZERO);
+    assert!(matches!(result, Err(RaffleError::TransferFailed(e)) if e.token == NFT));
+}
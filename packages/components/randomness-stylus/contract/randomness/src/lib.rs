@@ -0,0 +1,345 @@
+//! Commit-reveal randomness for Cradle components
+//!
+//! A consumer requests randomness by submitting `keccak256(secret)` for a secret
+//! only the revealer knows, typically the player opening a loot box. Once a later
+//! block exists, anyone holding the secret calls `fulfill` with it, and the
+//! randomness is derived from the secret, the request and, optionally, the hash
+//! of the block after the request:
+//!
+//! `keccak256(secret ‖ request_id ‖ requester ‖ block_hash)`
+//!
+//! The revealer fixes the secret before the block hash exists, and the sequencer
+//! never sees the secret before the reveal, so neither can pick the outcome alone.
+//! Requests not revealed within the reveal window expire; consumers should treat
+//! an expired request as a loss for the revealer, since withholding is the one
+//! choice the revealer still has.
+//!
+//! When fulfilled, the requester receives an `onRandomnessFulfilled` callback if
+//! it is a contract, and the value can also be read with `randomness`.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use cradle_arb::BLOCK_HASH_WINDOW;
+use stylus_sdk::prelude::*;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Randomness {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the reveal window and callback gas
+        address owner;
+        /// Blocks after the request during which the secret can be revealed
+        uint256 reveal_window;
+        /// Gas forwarded to `onRandomnessFulfilled`
+        uint64 callback_gas;
+        /// Requests made so far
+        uint256 total_requests;
+        /// Requests by ID, starting at 1
+        mapping(uint256 => Request) requests;
+    }
+
+    pub struct Request {
+        /// Account that made the request and receives the callback
+        address requester;
+        /// `keccak256(secret)`
+        bytes32 commitment;
+        /// L2 block of the request
+        uint256 request_block;
+        /// Whether the hash of `request_block + 1` is mixed in
+        bool mix_blockhash;
+        /// Set by `fulfill`
+        bool fulfilled;
+        /// Derived randomness, once fulfilled
+        bytes32 randomness;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event RandomnessRequested(uint256 indexed request_id, address indexed requester, bytes32 commitment, bool mix_blockhash);
+    event RandomnessFulfilled(uint256 indexed request_id, bytes32 randomness, bool callback_succeeded);
+    event ParametersUpdated(uint256 reveal_window, uint64 callback_gas);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Reveal window is zero, or too long for block hash mixing
+    error InvalidRevealWindow(uint256 reveal_window);
+    // Request ID doesn't exist
+    error UnknownRequest(uint256 request_id);
+    // Request was already fulfilled
+    error AlreadyFulfilled(uint256 request_id);
+    // Secret can only be revealed after the request's block
+    error TooEarly(uint256 request_id, uint256 reveal_block);
+    // Reveal window has passed
+    error Expired(uint256 request_id);
+    // Secret does not match the commitment
+    error InvalidSecret(uint256 request_id);
+    // A call to ArbSys failed
+    error ArbSysCallFailed();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum RandomnessError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidRevealWindow(InvalidRevealWindow),
+    UnknownRequest(UnknownRequest),
+    AlreadyFulfilled(AlreadyFulfilled),
+    TooEarly(TooEarly),
+    Expired(Expired),
+    InvalidSecret(InvalidSecret),
+    ArbSysCallFailed(ArbSysCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IRandomnessConsumer {
+        function onRandomnessFulfilled(uint256 request_id, bytes32 randomness) external;
+    }
+}
+
+/// Context of the `onRandomnessFulfilled` callback, which only gets the callback gas. Made through
+/// the host rather than with the deprecated `Call::new_in`, so it can be mocked under test.
+struct CallbackCall {
+    gas: u64,
+}
+
+impl calls::CallContext for CallbackCall {
+    fn gas(&self) -> u64 {
+        self.gas
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale if the requester calls back into the contract from its callback
+unsafe impl calls::MutatingCallContext for CallbackCall {
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+// Helpers used by the public methods below.
+impl Randomness {
+    fn only_owner(&self) -> Result<(), RandomnessError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(RandomnessError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Current L2 block number, which unlike `block.number` advances with every Arbitrum block.
+    fn arb_block_number(&self) -> Result<U256, RandomnessError> {
+        cradle_arb::arb_block_number(self.vm())
+            .map(U256::from)
+            .map_err(|_| RandomnessError::ArbSysCallFailed(ArbSysCallFailed {}))
+    }
+
+    /// Hash of an L2 block, which the reveal window keeps within ArbSys's reach.
+    fn arb_block_hash(&self, block_number: U256) -> Result<B256, RandomnessError> {
+        cradle_arb::arb_block_hash(self.vm(), block_number.saturating_to())
+            .ok()
+            .flatten()
+            .ok_or(RandomnessError::ArbSysCallFailed(ArbSysCallFailed {}))
+    }
+
+    /// Last block in which `request_id` can be fulfilled.
+    fn deadline(&self, request_id: U256) -> U256 {
+        self.requests.getter(request_id).request_block.get() + self.reveal_window.get()
+    }
+
+    /// Calls `onRandomnessFulfilled` on the requester with a bounded amount of gas.
+    fn notify(&mut self, requester: Address, request_id: U256, randomness: B256) -> bool {
+        let context = CallbackCall { gas: self.callback_gas.get().to::<u64>() };
+        let calldata = IRandomnessConsumer::onRandomnessFulfilledCall { request_id, randomness }.abi_encode();
+        self.vm().call(&context, requester, &calldata).is_ok()
+    }
+
+    fn write_parameters(&mut self, reveal_window: U256, callback_gas: u64) -> Result<(), RandomnessError> {
+        if reveal_window.is_zero() || reveal_window >= U256::from(BLOCK_HASH_WINDOW) {
+            return Err(RandomnessError::InvalidRevealWindow(InvalidRevealWindow { reveal_window }));
+        }
+        self.reveal_window.set(reveal_window);
+        self.callback_gas.set(alloy_primitives::Uint::<64, 1>::from(callback_gas));
+
+        log(self.vm(), ParametersUpdated {
+            reveal_window,
+            callback_gas,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl Randomness {
+    /// Sets the owner, the reveal window in L2 blocks and the callback gas. Can only be called once.
+    /// The reveal window must stay below 256 blocks so the mixed block hash is still available.
+    pub fn initialize(&mut self, owner: Address, reveal_window: U256, callback_gas: u64) -> Result<(), RandomnessError> {
+        if self.initialized.get() {
+            return Err(RandomnessError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.write_parameters(reveal_window, callback_gas)?;
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Requests randomness against `commitment = keccak256(secret)`. Returns the request ID.
+    pub fn request(&mut self, commitment: B256, mix_blockhash: bool) -> Result<U256, RandomnessError> {
+        let requester = self.vm().msg_sender();
+        let request_block = self.arb_block_number()?;
+        let request_id = self.total_requests.get() + U256::from(1);
+        self.total_requests.set(request_id);
+
+        let mut request = self.requests.setter(request_id);
+        request.requester.set(requester);
+        request.commitment.set(commitment);
+        request.request_block.set(request_block);
+        request.mix_blockhash.set(mix_blockhash);
+
+        log(self.vm(), RandomnessRequested {
+            request_id,
+            requester,
+            commitment,
+            mix_blockhash,
+        });
+        Ok(request_id)
+    }
+
+    /// Reveals the secret behind a request, derives its randomness and notifies the requester.
+    /// Callable by anyone holding the secret. Returns the randomness.
+    pub fn fulfill(&mut self, request_id: U256, secret: B256) -> Result<B256, RandomnessError> {
+        let request = self.requests.getter(request_id);
+        let requester = request.requester.get();
+        if requester.is_zero() {
+            return Err(RandomnessError::UnknownRequest(UnknownRequest { request_id }));
+        }
+        if request.fulfilled.get() {
+            return Err(RandomnessError::AlreadyFulfilled(AlreadyFulfilled { request_id }));
+        }
+        let request_block = request.request_block.get();
+        let commitment = request.commitment.get();
+        let mix_blockhash = request.mix_blockhash.get();
+
+        let current_block = self.arb_block_number()?;
+        let reveal_block = request_block + U256::from(1);
+        if current_block <= reveal_block {
+            return Err(RandomnessError::TooEarly(TooEarly {
+                request_id,
+                reveal_block: reveal_block + U256::from(1),
+            }));
+        }
+        if current_block > self.deadline(request_id) {
+            return Err(RandomnessError::Expired(Expired { request_id }));
+        }
+        if self.vm().native_keccak256(secret.as_slice()) != commitment {
+            return Err(RandomnessError::InvalidSecret(InvalidSecret { request_id }));
+        }
+
+        let mut preimage = Vec::with_capacity(116);
+        preimage.extend_from_slice(secret.as_slice());
+        preimage.extend_from_slice(&request_id.to_be_bytes::<32>());
+        preimage.extend_from_slice(requester.as_slice());
+        if mix_blockhash {
+            preimage.extend_from_slice(self.arb_block_hash(reveal_block)?.as_slice());
+        }
+        let randomness = self.vm().native_keccak256(&preimage);
+
+        let mut request = self.requests.setter(request_id);
+        request.fulfilled.set(true);
+        request.randomness.set(randomness);
+
+        let callback_succeeded = self.vm().code_size(requester) > 0 && self.notify(requester, request_id, randomness);
+
+        log(self.vm(), RandomnessFulfilled {
+            request_id,
+            randomness,
+            callback_succeeded,
+        });
+        Ok(randomness)
+    }
+
+    /// Updates the reveal window and callback gas (owner only). Applies to pending requests too.
+    pub fn set_parameters(&mut self, reveal_window: U256, callback_gas: u64) -> Result<(), RandomnessError> {
+        self.only_owner()?;
+        self.write_parameters(reveal_window, callback_gas)
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), RandomnessError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Returns `(fulfilled, randomness)` for a request.
+    pub fn randomness(&self, request_id: U256) -> (bool, B256) {
+        let request = self.requests.getter(request_id);
+        (request.fulfilled.get(), request.randomness.get())
+    }
+
+    /// Returns `(requester, commitment, request_block, mix_blockhash, fulfilled)`.
+    pub fn request_info(&self, request_id: U256) -> (Address, B256, U256, bool, bool) {
+        let request = self.requests.getter(request_id);
+        (
+            request.requester.get(),
+            request.commitment.get(),
+            request.request_block.get(),
+            request.mix_blockhash.get(),
+            request.fulfilled.get(),
+        )
+    }
+
+    /// Whether an unfulfilled request can no longer be revealed.
+    pub fn is_expired(&self, request_id: U256) -> Result<bool, RandomnessError> {
+        let request = self.requests.getter(request_id);
+        if request.requester.get().is_zero() {
+            return Err(RandomnessError::UnknownRequest(UnknownRequest { request_id }));
+        }
+        if request.fulfilled.get() {
+            return Ok(false);
+        }
+        Ok(self.arb_block_number()? > self.deadline(request_id))
+    }
+
+    /// Returns `(reveal_window, callback_gas)`.
+    pub fn parameters(&self) -> (U256, u64) {
+        (self.reveal_window.get(), self.callback_gas.get().to::<u64>())
+    }
+
+    /// Number of requests made.
+    pub fn total_requests(&self) -> U256 {
+        self.total_requests.get()
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
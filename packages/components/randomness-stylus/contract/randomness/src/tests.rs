@@ -0,0 +1,164 @@
+use super::*;
+use alloy_primitives::address;
+use alloy_sol_types::{SolError, SolValue};
+use cradle_arb::{ArbSys, ARB_SYS};
+use cradle_test_utils::{deploy, emitted, n, ok, reverted};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const CONSUMER: Address = address!("000000000000000000000000000000000000c0de");
+const WINDOW: u64 = 100;
+const CALLBACK_GAS: u64 = 50_000;
+const SECRET: B256 = B256::repeat_byte(0x5e);
+
+/// A contract with a 100-block reveal window, at L2 block 10.
+fn setup() -> (TestVM, Randomness) {
+    let (vm, mut contract) = deploy::<Randomness>(OWNER);
+    ok(contract.initialize(OWNER, n(WINDOW), CALLBACK_GAS));
+    at_block(&vm, 10);
+    (vm, contract)
+}
+
+/// Mocks ArbSys reporting `block` as the current L2 block.
+fn at_block(vm: &TestVM, block: u64) {
+    vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(n(block).abi_encode()));
+}
+
+fn commitment(vm: &TestVM) -> B256 {
+    vm.native_keccak256(SECRET.as_slice())
+}
+
+/// `keccak256(secret ‖ request_id ‖ requester ‖ block_hash)`, without the block hash if it's `None`.
+fn derive(vm: &TestVM, request_id: u64, requester: Address, block_hash: Option<B256>) -> B256 {
+    let mut preimage = SECRET.to_vec();
+    preimage.extend(n(request_id).to_be_bytes::<32>());
+    preimage.extend(requester.as_slice());
+    preimage.extend(block_hash.iter().flat_map(|hash| hash.0));
+    vm.native_keccak256(&preimage)
+}
+
+#[test]
+fn initialize_runs_once_with_a_window_in_block_hash_reach() {
+    for reveal_window in [0, BLOCK_HASH_WINDOW] {
+        let (_vm, mut contract) = deploy::<Randomness>(OWNER);
+        let error = InvalidRevealWindow { reveal_window: n(reveal_window) }.abi_encode();
+        assert_eq!(reverted(contract.initialize(OWNER, n(reveal_window), CALLBACK_GAS)), error);
+    }
+    let (vm, mut contract) = deploy::<Randomness>(OWNER);
+    ok(contract.initialize(OWNER, n(BLOCK_HASH_WINDOW - 1), CALLBACK_GAS));
+    assert_eq!((contract.owner(), contract.parameters()), (OWNER, (n(BLOCK_HASH_WINDOW - 1), CALLBACK_GAS)));
+    let event = emitted::<ParametersUpdated>(&vm).pop().expect("parameters logged");
+    assert_eq!((event.reveal_window, event.callback_gas), (n(BLOCK_HASH_WINDOW - 1), CALLBACK_GAS));
+
+    vm.set_sender(ALICE);
+    assert_eq!(reverted(contract.initialize(ALICE, n(1), 0)), AlreadyInitialized {}.abi_encode());
+}
+
+#[test]
+fn requests_record_the_commitment_at_the_current_l2_block() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.request(commitment(&vm), true)), n(1));
+    assert_eq!(contract.request_info(n(1)), (ALICE, commitment(&vm), n(10), true, false));
+    let event = emitted::<RandomnessRequested>(&vm).pop().expect("request logged");
+    assert_eq!((event.request_id, event.requester, event.commitment), (n(1), ALICE, commitment(&vm)));
+    assert_eq!(ok(contract.request(B256::ZERO, false)), n(2));
+    assert_eq!(contract.total_requests(), n(2));
+
+    // Off Arbitrum, ArbSys answers nothing
+    vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(Vec::new()));
+    assert_eq!(reverted(contract.request(B256::ZERO, false)), ArbSysCallFailed {}.abi_encode());
+}
+
+#[test]
+fn secrets_are_revealed_after_the_next_block_and_within_the_window() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    ok(contract.request(commitment(&vm), false));
+    let error = UnknownRequest { request_id: n(2) }.abi_encode();
+    assert_eq!(reverted(contract.fulfill(n(2), SECRET)), error);
+    assert_eq!(reverted(contract.is_expired(n(2))), error);
+
+    for block in [10, 11] {
+        at_block(&vm, block);
+        let error = TooEarly { request_id: n(1), reveal_block: n(12) }.abi_encode();
+        assert_eq!(reverted(contract.fulfill(n(1), SECRET)), error);
+    }
+    at_block(&vm, 12);
+    let error = InvalidSecret { request_id: n(1) }.abi_encode();
+    assert_eq!(reverted(contract.fulfill(n(1), B256::ZERO)), error);
+
+    at_block(&vm, 10 + WINDOW + 1);
+    assert!(ok(contract.is_expired(n(1))));
+    assert_eq!(reverted(contract.fulfill(n(1), SECRET)), Expired { request_id: n(1) }.abi_encode());
+
+    // Anyone holding the secret reveals it, up to the last block of the window
+    at_block(&vm, 10 + WINDOW);
+    assert!(!ok(contract.is_expired(n(1))));
+    vm.set_sender(OWNER);
+    let randomness = ok(contract.fulfill(n(1), SECRET));
+    assert_eq!(randomness, derive(&vm, 1, ALICE, None));
+    assert_eq!(contract.randomness(n(1)), (true, randomness));
+    let event = emitted::<RandomnessFulfilled>(&vm).pop().expect("fulfillment logged");
+    assert_eq!((event.request_id, event.randomness, event.callback_succeeded), (n(1), randomness, false));
+    assert_eq!(reverted(contract.fulfill(n(1), SECRET)), AlreadyFulfilled { request_id: n(1) }.abi_encode());
+    at_block(&vm, 10 + WINDOW + 1);
+    assert!(!ok(contract.is_expired(n(1))));
+}
+
+#[test]
+fn the_hash_of_the_block_after_the_request_is_mixed_in_on_request() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    ok(contract.request(commitment(&vm), true));
+    at_block(&vm, 20);
+    // The hash ArbSys can't give fails the reveal rather than being left out
+    assert_eq!(reverted(contract.fulfill(n(1), SECRET)), ArbSysCallFailed {}.abi_encode());
+
+    let block_hash = B256::repeat_byte(0xb1);
+    let call = ArbSys::arbBlockHashCall { arbBlockNum: n(11) };
+    vm.mock_static_call(ARB_SYS, call.abi_encode(), Ok(block_hash.abi_encode()));
+    let randomness = ok(contract.fulfill(n(1), SECRET));
+    assert_eq!(randomness, derive(&vm, 1, ALICE, Some(block_hash)));
+    assert_ne!(randomness, derive(&vm, 1, ALICE, None));
+}
+
+#[test]
+fn contract_requesters_are_called_back_and_may_fail_the_callback() {
+    let (vm, mut contract) = setup();
+    vm.set_code(CONSUMER, vec![0xfe]);
+    vm.set_sender(CONSUMER);
+    ok(contract.request(commitment(&vm), false));
+    ok(contract.request(commitment(&vm), false));
+    at_block(&vm, 12);
+
+    for (request_id, answer) in [(1, Ok(Vec::new())), (2, Err(Vec::new()))] {
+        let randomness = derive(&vm, request_id, CONSUMER, None);
+        let call = IRandomnessConsumer::onRandomnessFulfilledCall { request_id: n(request_id), randomness };
+        vm.mock_call(CONSUMER, call.abi_encode(), answer);
+        // A failed callback doesn't stop the request from being fulfilled
+        assert_eq!(ok(contract.fulfill(n(request_id), SECRET)), randomness);
+        let event = emitted::<RandomnessFulfilled>(&vm).pop().expect("fulfillment logged");
+        assert_eq!(event.callback_succeeded, request_id == 1);
+    }
+}
+
+#[test]
+fn only_the_owner_sets_parameters() {
+    let (vm, mut contract) = setup();
+    vm.set_sender(ALICE);
+    let error = Unauthorized { caller: ALICE }.abi_encode();
+    assert_eq!(reverted(contract.set_parameters(n(5), 1)), error);
+    assert_eq!(reverted(contract.transfer_ownership(ALICE)), error);
+
+    vm.set_sender(OWNER);
+    let error = InvalidRevealWindow { reveal_window: U256::ZERO }.abi_encode();
+    assert_eq!(reverted(contract.set_parameters(U256::ZERO, 1)), error);
+    ok(contract.set_parameters(n(5), 1));
+    assert_eq!(contract.parameters(), (n(5), 1));
+    ok(contract.transfer_ownership(ALICE));
+    let event = emitted::<OwnershipTransferred>(&vm).pop().expect("ownership logged");
+    assert_eq!((event.previous_owner, event.new_owner), (OWNER, ALICE));
+    assert_eq!(reverted(contract.set_parameters(n(5), 1)), Unauthorized { caller: OWNER }.abi_encode());
+}
@@ -0,0 +1,280 @@
+//! Signed-order swaps
+//!
+//! Makers sign EIP-712 orders off-chain offering an amount of one asset for an
+//! amount of another; takers settle them on-chain. Either side of an order can
+//! be an ERC-20 or a token ID of an ERC-1155, so the same venue serves
+//! ERC-20/ERC-20 and ERC-20/ERC-1155 trades.
+//!
+//! Orders can be filled in parts. The maker's side is paid out pro rata to the
+//! amount the taker pays, rounded down in the maker's favour, until the order's
+//! full taker amount has been paid. An order can name its taker, making it a
+//! private quote, or leave the taker open to anyone.
+//!
+//! Makers cancel single orders on-chain, or every order with a salt below a
+//! minimum, which lets them use a timestamp as the salt and invalidate all
+//! older quotes at once.
+//!
+//! Both parties approve this contract on the assets they give: `approve` for
+//! ERC-20s and `setApprovalForAll` for ERC-1155s.
+//!
+//! A maker can be a smart account. Orders from an address with code are
+//! checked with its ERC-1271 `isValidSignature` instead of `ecrecover`.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_crypto::{
+    domain_separator, eip712_domain, hash_struct, hash_typed_data, is_valid_signature_now, recover, Eip712Domain,
+};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain.
+const DOMAIN_NAME: &str = "Cradle RFQ";
+/// Version in the EIP-712 domain.
+const DOMAIN_VERSION: &str = "1";
+/// `keccak256("Order(address maker,address taker,uint8 makerKind,address makerToken,uint256 makerId,uint256 makerAmount,uint8 takerKind,address takerToken,uint256 takerId,uint256 takerAmount,uint256 expiry,uint256 salt)")`
+const ORDER_TYPEHASH: B256 = b256!("389a925ec88608ea3e2d7bc0abc83e0fc2d491465bc928b349c26c4b494c7eda");
+/// Asset kind of an ERC-20; its token ID is ignored.
+const KIND_ERC20: u8 = 0;
+/// Asset kind of an ERC-1155 token ID.
+const KIND_ERC1155: u8 = 1;
+
+/// `(maker, taker, maker_kind, maker_token, maker_id, maker_amount, taker_kind, taker_token,
+/// taker_id, taker_amount, expiry, salt)`. A zero taker lets anyone fill the order.
+pub type Order = (Address, Address, u8, Address, U256, U256, u8, Address, U256, U256, U256, U256);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct RfqSwap {
+        /// Taker amount paid into each order so far, by order hash
+        mapping(bytes32 => uint256) filled;
+        /// Orders cancelled by their maker, by order hash
+        mapping(bytes32 => bool) cancelled;
+        /// Orders with a lower salt are cancelled, by maker
+        mapping(address => uint256) min_valid_salt;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event OrderFilled(bytes32 indexed order_hash, address indexed maker, address indexed taker, uint256 maker_amount, uint256 taker_amount);
+    event OrderCancelled(bytes32 indexed order_hash, address indexed maker);
+    event OrdersCancelledBelow(address indexed maker, uint256 min_valid_salt);
+
+    // Signature is malformed or wasn't made by the maker. `signer` is zero for contract makers
+    error InvalidSigner(address signer, address maker);
+    // Order names a different taker
+    error InvalidTaker(address taker, address caller);
+    // Asset kind is neither ERC-20 nor ERC-1155, or an amount is zero
+    error InvalidOrder();
+    // Order has expired
+    error OrderExpired(uint256 expiry);
+    // Order was cancelled by its maker
+    error OrderCancelledByMaker(bytes32 order_hash);
+    // Fill is larger than what is left of the order, or too small to pay the maker's side
+    error InvalidFillAmount(uint256 taker_amount, uint256 remaining);
+    // Caller is not the order's maker
+    error Unauthorized(address caller);
+    // Minimum salt is not higher than the current one
+    error InvalidSalt(uint256 min_valid_salt);
+    // A token transfer failed
+    error TransferFailed(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum RfqError {
+    InvalidSigner(InvalidSigner),
+    InvalidTaker(InvalidTaker),
+    InvalidOrder(InvalidOrder),
+    OrderExpired(OrderExpired),
+    OrderCancelledByMaker(OrderCancelledByMaker),
+    InvalidFillAmount(InvalidFillAmount),
+    Unauthorized(Unauthorized),
+    InvalidSalt(InvalidSalt),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data) external;
+    }
+}
+
+// Helpers used by the public methods below.
+impl RfqSwap {
+    /// EIP-712 digest of `order`, which is also its ID.
+    fn hash_order(&self, order: &Order) -> B256 {
+        let (maker, taker, maker_kind, maker_token, maker_id, maker_amount, taker_kind, taker_token, taker_id, taker_amount, expiry, salt) =
+            *order;
+        let struct_hash = hash_struct(
+            self.vm(),
+            ORDER_TYPEHASH,
+            (
+                maker,
+                taker,
+                U256::from(maker_kind),
+                maker_token,
+                maker_id,
+                maker_amount,
+                U256::from(taker_kind),
+                taker_token,
+                taker_id,
+                taker_amount,
+                expiry,
+                salt,
+            ),
+        );
+        hash_typed_data(self.vm(), domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION), struct_hash)
+    }
+
+    /// Checks everything about `order` but the fill amount; returns its hash and the taker amount left.
+    fn validate(&self, order: &Order, signature: &[u8]) -> Result<(B256, U256), RfqError> {
+        let (maker, _, maker_kind, _, _, maker_amount, taker_kind, _, _, taker_amount, expiry, salt) = *order;
+        let valid_kind = |kind| kind == KIND_ERC20 || kind == KIND_ERC1155;
+        if !valid_kind(maker_kind) || !valid_kind(taker_kind) || maker_amount.is_zero() || taker_amount.is_zero() {
+            return Err(RfqError::InvalidOrder(InvalidOrder {}));
+        }
+        if U256::from(self.vm().block_timestamp()) > expiry {
+            return Err(RfqError::OrderExpired(OrderExpired { expiry }));
+        }
+        let order_hash = self.hash_order(order);
+        if self.cancelled.get(order_hash) || salt < self.min_valid_salt.get(maker) {
+            return Err(RfqError::OrderCancelledByMaker(OrderCancelledByMaker { order_hash }));
+        }
+        if !is_valid_signature_now(self.vm(), maker, order_hash, signature) {
+            let signer = recover(self.vm(), order_hash, signature);
+            return Err(RfqError::InvalidSigner(InvalidSigner { signer, maker }));
+        }
+        Ok((order_hash, taker_amount.saturating_sub(self.filled.get(order_hash))))
+    }
+
+    /// Moves `amount` of an ERC-20 or ERC-1155 token ID from `from` to `to`.
+    fn move_asset(
+        &mut self,
+        kind: u8,
+        token: Address,
+        id: U256,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), RfqError> {
+        let calldata = if kind == KIND_ERC1155 {
+            IERC1155::safeTransferFromCall { from, to, id, value: amount, data: Vec::new().into() }.abi_encode()
+        } else {
+            IERC20::transferFromCall { from, to, value: amount }.abi_encode()
+        };
+        let moved = match self.vm().call(&calls::context::Call::new(), token, &calldata) {
+            Ok(output) => kind == KIND_ERC1155 || bool::abi_decode(&output, true).unwrap_or(false),
+            Err(_) => false,
+        };
+        if !moved {
+            return Err(RfqError::TransferFailed(TransferFailed { token }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl RfqSwap {
+    /// Fills `order` by paying `taker_amount` of its taker side, and receiving the maker side pro
+    /// rata. Returns the maker amount received.
+    pub fn fill(&mut self, order: Order, signature: Bytes, taker_amount: U256) -> Result<U256, RfqError> {
+        let (order_hash, remaining) = self.validate(&order, &signature)?;
+        let (maker, taker, maker_kind, maker_token, maker_id, maker_total, taker_kind, taker_token, taker_id, taker_total, _, _) =
+            order;
+        let caller = self.vm().msg_sender();
+        if !taker.is_zero() && taker != caller {
+            return Err(RfqError::InvalidTaker(InvalidTaker { taker, caller }));
+        }
+        let maker_amount = taker_amount * maker_total / taker_total;
+        if taker_amount > remaining || maker_amount.is_zero() {
+            return Err(RfqError::InvalidFillAmount(InvalidFillAmount { taker_amount, remaining }));
+        }
+
+        self.filled.insert(order_hash, taker_total - remaining + taker_amount);
+        self.move_asset(taker_kind, taker_token, taker_id, caller, maker, taker_amount)?;
+        self.move_asset(maker_kind, maker_token, maker_id, maker, caller, maker_amount)?;
+
+        log(self.vm(), OrderFilled {
+            order_hash,
+            maker,
+            taker: caller,
+            maker_amount,
+            taker_amount,
+        });
+        Ok(maker_amount)
+    }
+
+    /// Cancels `order` (maker only).
+    pub fn cancel(&mut self, order: Order) -> Result<(), RfqError> {
+        let caller = self.vm().msg_sender();
+        if caller != order.0 {
+            return Err(RfqError::Unauthorized(Unauthorized { caller }));
+        }
+        let order_hash = self.hash_order(&order);
+        self.cancelled.insert(order_hash, true);
+
+        log(self.vm(), OrderCancelled {
+            order_hash,
+            maker: caller,
+        });
+        Ok(())
+    }
+
+    /// Cancels every order of the caller with a salt below `min_valid_salt`. The minimum can only go up.
+    pub fn cancel_below(&mut self, min_valid_salt: U256) -> Result<(), RfqError> {
+        let maker = self.vm().msg_sender();
+        if min_valid_salt <= self.min_valid_salt.get(maker) {
+            return Err(RfqError::InvalidSalt(InvalidSalt { min_valid_salt }));
+        }
+        self.min_valid_salt.insert(maker, min_valid_salt);
+
+        log(self.vm(), OrdersCancelledBelow { maker, min_valid_salt });
+        Ok(())
+    }
+
+    /// Taker amount that can still be paid into `order` with `signature`; zero if it can't be filled.
+    pub fn remaining(&self, order: Order, signature: Bytes) -> U256 {
+        self.validate(&order, &signature).map_or(U256::ZERO, |(_, remaining)| remaining)
+    }
+
+    /// Returns `(filled, cancelled)` of an order hash.
+    pub fn order_status(&self, order_hash: B256) -> (U256, bool) {
+        (self.filled.get(order_hash), self.cancelled.get(order_hash))
+    }
+
+    /// EIP-712 digest of `order`, which makers sign and which identifies the order.
+    pub fn order_hash(&self, order: Order) -> B256 {
+        self.hash_order(&order)
+    }
+
+    /// Orders of `maker` with a lower salt are cancelled.
+    pub fn min_valid_salt(&self, maker: Address) -> U256 {
+        self.min_valid_salt.get(maker)
+    }
+
+    /// EIP-712 domain separator for this contract and chain.
+    pub fn domain_separator(&self) -> B256 {
+        domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+
+    /// Returns `(name, version, chain_id, verifying_contract)` of the EIP-712 domain.
+    pub fn eip712_domain(&self) -> Eip712Domain {
+        eip712_domain(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok, sign};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const WALLET: Address = address!("0000000000000000000000000000000000005afe");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const TICKETS: Address = address!("0000000000000000000000000000000000001155");
+
+/// `maker` offers 10 tickets of ID 7 for 1,000 USDC from `taker`, until time 2,000.
+fn order(maker: Address, taker: Address) -> Order {
+    (maker, taker, KIND_ERC1155, TICKETS, n(7), n(10), KIND_ERC20, USDC, U256::ZERO, n(1_000), n(2_000), n(1))
+}
+
+/// Lets `amount` USDC move from `from` to `to`.
+fn expect_usdc(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to, value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets `amount` tickets move from `from` to `to`.
+fn expect_tickets(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    let call = IERC1155::safeTransferFromCall { from, to, id: n(7), value: n(amount), data: Vec::new().into() };
+    vm.mock_call(TICKETS, call.abi_encode(), Ok(Vec::new()));
+}
+
+/// A venue at time 1,000, called by Bob.
+fn setup() -> (TestVM, RfqSwap) {
+    let (vm, contract) = deploy::<RfqSwap>(BOB);
+    vm.set_block_timestamp(1_000);
+    (vm, contract)
+}
+
+#[test]
+fn orders_fill_in_parts_pro_rata() {
+    let (vm, mut contract) = setup();
+    let order = order(ALICE, Address::ZERO);
+    let signature = Bytes::from(sign(&vm, ALICE, contract.order_hash(order)));
+    assert_eq!(contract.remaining(order, signature.clone()), n(1_000));
+
+    expect_usdc(&vm, BOB, ALICE, 450);
+    expect_tickets(&vm, ALICE, BOB, 4);
+    assert_eq!(ok(contract.fill(order, signature.clone(), n(450))), n(4));
+    let filled = &emitted::<OrderFilled>(&vm)[0];
+    assert_eq!((filled.maker, filled.taker, filled.maker_amount, filled.taker_amount), (ALICE, BOB, n(4), n(450)));
+
+    expect_usdc(&vm, BOB, ALICE, 550);
+    expect_tickets(&vm, ALICE, BOB, 5);
+    assert_eq!(ok(contract.fill(order, signature.clone(), n(550))), n(5));
+    assert_eq!(contract.order_status(contract.order_hash(order)), (n(1_000), false));
+    let result = contract.fill(order, signature, n(1));
+    assert!(matches!(result, Err(RfqError::InvalidFillAmount(e)) if e.remaining.is_zero()));
+}
+
+#[test]
+fn smart_account_makers_sign_through_erc1271() {
+    let (vm, mut contract) = setup();
+    vm.set_code(WALLET, vec![0xfe]);
+    let order = order(WALLET, BOB);
+    let signature = Bytes::from(vec![0x5a; 65]);
+    let mut calldata = cradle_crypto::ERC1271_MAGIC_VALUE.to_vec();
+    calldata.extend((contract.order_hash(order), signature.clone()).abi_encode_params());
+    vm.mock_static_call(WALLET, calldata, Ok(cradle_crypto::ERC1271_MAGIC_VALUE.abi_encode()));
+
+    expect_usdc(&vm, BOB, WALLET, 1_000);
+    expect_tickets(&vm, WALLET, BOB, 10);
+    assert_eq!(ok(contract.fill(order, signature.clone(), n(1_000))), n(10));
+    vm.set_sender(ALICE);
+    let result = contract.fill(order, signature, n(1));
+    assert!(matches!(result, Err(RfqError::InvalidTaker(e)) if e.taker == BOB && e.caller == ALICE));
+}
+
+#[test]
+fn makers_cancel_orders_one_by_one_or_by_salt() {
+    let (vm, mut contract) = setup();
+    let order = order(ALICE, Address::ZERO);
+    let signature = Bytes::from(sign(&vm, ALICE, contract.order_hash(order)));
+    assert!(matches!(contract.cancel(order), Err(RfqError::Unauthorized(e)) if e.caller == BOB));
+
+    vm.set_sender(ALICE);
+    ok(contract.cancel(order));
+    assert_eq!(contract.remaining(order, signature.clone()), U256::ZERO);
+    let result = contract.fill(order, signature, n(100));
+    assert!(matches!(result, Err(RfqError::OrderCancelledByMaker(_))));
+
+    let mut newer = order;
+    newer.11 = n(5);
+    let signature = Bytes::from(sign(&vm, ALICE, contract.order_hash(newer)));
+    ok(contract.cancel_below(n(6)));
+    assert!(matches!(contract.fill(newer, signature, n(100)), Err(RfqError::OrderCancelledByMaker(_))));
+    assert!(matches!(contract.cancel_below(n(6)), Err(RfqError::InvalidSalt(_))));
+}
+
+#[test]
+fn unsigned_expired_and_unpaid_orders_revert() {
+    let (vm, mut contract) = setup();
+    let order = order(ALICE, Address::ZERO);
+    let forged = Bytes::from(sign(&vm, BOB, contract.order_hash(order)));
+    let result = contract.fill(order, forged, n(100));
+    assert!(matches!(result, Err(RfqError::InvalidSigner(e)) if e.signer == BOB && e.maker == ALICE));
+
+    let signature = Bytes::from(sign(&vm, ALICE, contract.order_hash(order)));
+    let result = contract.fill(order, signature.clone(), n(99));
+    assert!(matches!(result, Err(RfqError::InvalidFillAmount(e)) if e.taker_amount == n(99)));
+
+    // A taker whose payment doesn't go through gets nothing
+    let result = contract.fill(order, signature.clone(), n(100));
+    assert!(matches!(result, Err(RfqError::TransferFailed(e)) if e.token == USDC));
+
+    vm.set_block_timestamp(2_001);
+    let result = contract.fill(order, signature, n(100));
+    assert!(matches!(result, Err(RfqError::OrderExpired(e)) if e.expiry == n(2_000)));
+}
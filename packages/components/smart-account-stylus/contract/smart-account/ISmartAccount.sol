@@ -0,0 +1,35 @@
+/**
+ * Generated from the smart-account-stylus contract, do not edit. To regenerate, run in contract/smart-account:
+ *   cargo run --features export-abi -- interface > ISmartAccount.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface ISmartAccount {
+    event AccountInitialized(address indexed entryPoint, address indexed owner);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error AlreadyInitialized();
+    error Unauthorized(address caller);
+    error LengthMismatch();
+    error InvalidOwner(address owner);
+    error EntryPointCallFailed();
+
+    function initialize(address entry_point, address owner) external;
+    function validateUserOp((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes) memory user_op, bytes32 user_op_hash, uint256 missing_account_funds) external returns (uint256);
+    function execute(address dest, uint256 value, bytes calldata func) external;
+    function executeBatch(address[] memory dest, uint256[] memory value, bytes[] memory func) external;
+    function addDeposit() external payable;
+    function withdrawDepositTo(address withdraw_address, uint256 amount) external;
+    function transferOwnership(address new_owner) external;
+    function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4);
+    function getDeposit() external view returns (uint256);
+    function getNonce() external view returns (uint256);
+    function entryPoint() external view returns (address);
+    function owner() external view returns (address);
+    function onERC721Received(address _operator, address _from, uint256 _token_id, bytes calldata _data) external returns (bytes4);
+    function onERC1155Received(address _operator, address _from, uint256 _id, uint256 _value, bytes calldata _data) external returns (bytes4);
+    function onERC1155BatchReceived(address _operator, address _from, uint256[] memory _ids, uint256[] memory _values, bytes calldata _data) external returns (bytes4);
+    function supportsInterface(bytes4 interface_id) external view returns (bool);
+}
@@ -0,0 +1,158 @@
+//! A complete Solidity interface for wallets and clients that call the account.
+//!
+//! The SDK's `export-abi` output leaves out events and drops the names of error parameters.
+//! [`solidity_interface`] adds the events and errors to its methods, with their parameter names,
+//! as `ISmartAccount`. Every event and error declaration is checked against the Rust type it
+//! describes, and every error the SDK exports must be declared, so the interface can't drift from
+//! the contract.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use alloy_json_abi::{Error, Event};
+use stylus_sdk::{
+    abi::GenerateAbi,
+    alloy_sol_types::{SolError, SolEvent, TopicList},
+};
+
+use crate::{
+    AccountInitialized, AlreadyInitialized, EntryPointCallFailed, InvalidOwner, LengthMismatch, OwnershipTransferred,
+    SmartAccount, Unauthorized,
+};
+
+const HEADER: &str = "\
+/**
+ * Generated from the smart-account-stylus contract, do not edit. To regenerate, run in contract/smart-account:
+ *   cargo run --features export-abi -- interface > ISmartAccount.sol
+ */
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+interface ISmartAccount {
+";
+
+/// Renders `ISmartAccount.sol`.
+///
+/// # Panics
+///
+/// If a declaration below doesn't match its Rust type, or the SDK exports an error that has no
+/// declaration here.
+pub fn solidity_interface() -> String {
+    let exported = format!("{}", Exported);
+    let functions = declarations(&exported, "function ");
+
+    let errors = errors();
+    let exported_errors = declarations(&exported, "error ");
+    for line in &exported_errors {
+        let selector = parse_error(line).selector();
+        assert!(
+            errors.iter().any(|declaration| parse_error(declaration).selector() == selector),
+            "`{line}` is exported but isn't declared in the interface"
+        );
+    }
+    let mut out = String::from(HEADER);
+    for (i, group) in [events(), errors, functions].iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for declaration in group {
+            out.push_str(&format!("    {declaration};\n"));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn events() -> Vec<String> {
+    [
+        event::<AccountInitialized>("event AccountInitialized(address indexed entryPoint, address indexed owner)"),
+        event::<OwnershipTransferred>(
+            "event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)",
+        ),
+    ]
+    .into()
+}
+
+fn errors() -> Vec<String> {
+    [
+        error::<AlreadyInitialized>("error AlreadyInitialized()"),
+        error::<Unauthorized>("error Unauthorized(address caller)"),
+        error::<LengthMismatch>("error LengthMismatch()"),
+        error::<InvalidOwner>("error InvalidOwner(address owner)"),
+        error::<EntryPointCallFailed>("error EntryPointCallFailed()"),
+    ]
+    .into()
+}
+
+/// Checks that `declaration` has the signature and indexed parameters of `E`.
+fn event<E: SolEvent>(declaration: &str) -> String {
+    let event = Event::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"));
+    assert_eq!(event.selector(), E::SIGNATURE_HASH, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    let topics = 1 + event.inputs.iter().filter(|input| input.indexed).count();
+    assert_eq!(
+        topics,
+        <E::TopicList as TopicList>::COUNT,
+        "`{declaration}` indexes different parameters than `{}`",
+        E::SIGNATURE
+    );
+    declaration.into()
+}
+
+/// Checks that `declaration` has the signature of `E`.
+fn error<E: SolError>(declaration: &str) -> String {
+    assert_eq!(parse_error(declaration).selector(), E::SELECTOR, "`{declaration}` doesn't match `{}`", E::SIGNATURE);
+    declaration.into()
+}
+
+fn parse_error(declaration: &str) -> Error {
+    Error::parse(declaration).unwrap_or_else(|e| panic!("`{declaration}`: {e}"))
+}
+
+/// The declarations starting with `keyword` in the SDK's output, without the `;` and in order
+/// of first appearance.
+fn declarations(exported: &str, keyword: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in exported.lines().map(str::trim).filter(|line| line.starts_with(keyword)) {
+        let declaration = line.trim_end_matches(';');
+        if !found.iter().any(|seen| seen == declaration) {
+            found.push(declaration.into());
+        }
+    }
+    found
+}
+
+/// The interface the SDK exports for [`SmartAccount`].
+struct Exported;
+
+impl fmt::Display for Exported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <SmartAccount as GenerateAbi>::fmt_abi(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::solidity_interface;
+
+    #[test]
+    fn committed_interface_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/ISmartAccount.sol");
+        let committed = std::fs::read_to_string(path).unwrap_or_default();
+        let generated = solidity_interface();
+        if committed != generated {
+            let (committed, generated): (Vec<_>, Vec<_>) = (committed.lines().collect(), generated.lines().collect());
+            let line = (0..committed.len().max(generated.len()))
+                .find(|&i| committed.get(i) != generated.get(i))
+                .unwrap_or(committed.len());
+            let (old, new) = (committed.get(line).unwrap_or(&""), generated.get(line).unwrap_or(&""));
+            let line = line + 1;
+            panic!(
+                "ISmartAccount.sol is out of date at line {line}:\n- {old}\n+ {new}\n\
+                 Regenerate it with `cargo run --features export-abi -- interface > ISmartAccount.sol`"
+            );
+        }
+    }
+}
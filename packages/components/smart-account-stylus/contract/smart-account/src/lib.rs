@@ -0,0 +1,367 @@
+//! ERC-4337 smart account
+//!
+//! A contract wallet controlled by a single owner key and operated through an
+//! ERC-4337 v0.7 EntryPoint. Bundlers submit user operations signed by the
+//! owner; the EntryPoint calls `validate_user_op` and then the account's
+//! `execute` or `execute_batch`. The owner can also call the account directly.
+//!
+//! User operations are signed as an Ethereum signed message over the
+//! `userOpHash`. Invalid signatures return `SIG_VALIDATION_FAILED` rather than
+//! reverting, as the EntryPoint requires.
+//!
+//! The account accepts ETH, ERC-721 and ERC-1155 tokens, validates ERC-1271
+//! signatures from its owner, and manages its gas deposit at the EntryPoint.
+//! Accounts are meant to be deployed per user through the clone factory.
+//!
+//! An EOA can also delegate to the account with EIP-7702 and initialize it with
+//! itself as the owner, in the same transaction so nobody can initialize it
+//! first. It then batches calls by sending `execute_batch` to its own address,
+//! which is how wallets implement EIP-5792 `wallet_sendCalls` for it.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, Uint, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use cradle_crypto::recover;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+#[cfg(feature = "export-abi")]
+pub mod interface;
+
+/// `validate_user_op` result for a valid signature with no time range.
+const SIG_VALIDATION_SUCCESS: u64 = 0;
+/// `validate_user_op` result for an invalid signature.
+const SIG_VALIDATION_FAILED: u64 = 1;
+/// ERC-1271 magic value, the selector of `isValidSignature`.
+const ERC1271_MAGIC_VALUE: u32 = 0x1626ba7e;
+/// Selector for `onERC721Received`.
+const ERC721_RECEIVED: u32 = 0x150b7a02;
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC1155BatchReceived`.
+const ERC1155_BATCH_RECEIVED: u32 = 0xbc197c81;
+/// ERC-721 receiver, as the account exports it.
+const IERC721_RECEIVER: Interface = interface!("onERC721Received"(Address, Address, U256, Bytes));
+/// ERC-1155 receiver, as the account exports it.
+const IERC1155_RECEIVER: Interface = interface!(
+    "onERC1155Received"(Address, Address, U256, U256, Bytes),
+    "onERC1155BatchReceived"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+/// ERC-4337 v0.7 `PackedUserOperation`: `(sender, nonce, initCode, callData, accountGasLimits,
+/// preVerificationGas, gasFees, paymasterAndData, signature)`.
+pub type PackedUserOperation = (Address, U256, Bytes, Bytes, B256, U256, B256, Bytes, Bytes);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct SmartAccount {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Key that signs user operations and may call the account directly
+        address owner;
+        /// EntryPoint trusted to validate and execute user operations
+        address entry_point;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event AccountInitialized(address indexed entry_point, address indexed owner);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Batch arrays have different lengths
+    error LengthMismatch();
+    // New owner is the zero address
+    error InvalidOwner(address owner);
+    // A call to the EntryPoint failed
+    error EntryPointCallFailed();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum SmartAccountError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    LengthMismatch(LengthMismatch),
+    InvalidOwner(InvalidOwner),
+    EntryPointCallFailed(EntryPointCallFailed),
+}
+
+// External interfaces
+sol! {
+    interface IEntryPoint {
+        function depositTo(address account) external payable;
+        function withdrawTo(address withdraw_address, uint256 withdraw_amount) external;
+        function balanceOf(address account) external view returns (uint256);
+        function getNonce(address sender, uint192 key) external view returns (uint256);
+    }
+}
+
+/// Context of the account's calls, with the ETH they send. Made through the host, unlike
+/// `sol_interface!` calls, they build with the SDK's `reentrant` feature and are mocked under test.
+struct AccountCall(U256);
+
+impl calls::CallContext for AccountCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a target or the EntryPoint calls back into the account
+unsafe impl calls::MutatingCallContext for AccountCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl SmartAccount {
+    fn only_entry_point(&self) -> Result<(), SmartAccountError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.entry_point.get() {
+            return Err(SmartAccountError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Allows the EntryPoint, the owner, or the account itself, which is the caller when an EOA
+    /// that delegated to the account with EIP-7702 sends a transaction to its own address.
+    fn only_entry_point_owner_or_self(&self) -> Result<(), SmartAccountError> {
+        let caller = self.vm().msg_sender();
+        let allowed = [self.entry_point.get(), self.owner.get(), self.vm().contract_address()];
+        if !allowed.contains(&caller) {
+            return Err(SmartAccountError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Allows the owner, or the account itself through `execute`.
+    fn only_owner_or_self(&self) -> Result<(), SmartAccountError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() && caller != self.vm().contract_address() {
+            return Err(SmartAccountError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Whether the owner signed `hash` as an Ethereum signed message.
+    fn is_owner_signature(&self, hash: B256, signature: &[u8]) -> bool {
+        let mut message = Vec::with_capacity(60);
+        message.extend_from_slice(b"\x19Ethereum Signed Message:\n32");
+        message.extend_from_slice(hash.as_slice());
+        let signer = recover(self.vm(), self.vm().native_keccak256(&message), signature);
+        !signer.is_zero() && signer == self.owner.get()
+    }
+
+    /// Calls `target`, bubbling up its revert data.
+    fn call_target(&mut self, target: Address, value: U256, data: &[u8]) -> Result<(), Vec<u8>> {
+        self.vm()
+            .call(&AccountCall(value), target, data)
+            .map(|_| ())
+            .map_err(Vec::from)
+    }
+
+    /// Calls the EntryPoint with `calldata`, attaching `value` wei.
+    fn call_entry_point(&mut self, value: U256, calldata: &[u8]) -> Result<(), SmartAccountError> {
+        self.call_target(self.entry_point.get(), value, calldata)
+            .map_err(|_| SmartAccountError::EntryPointCallFailed(EntryPointCallFailed {}))
+    }
+
+    /// Calls a view of the EntryPoint and decodes what it returns.
+    fn entry_point_view<C: SolCall>(&self, call: C) -> Result<C::Return, SmartAccountError> {
+        self.vm()
+            .static_call(&self, self.entry_point.get(), &call.abi_encode())
+            .ok()
+            .and_then(|output| C::abi_decode_returns(&output, true).ok())
+            .ok_or(SmartAccountError::EntryPointCallFailed(EntryPointCallFailed {}))
+    }
+}
+
+#[public]
+impl SmartAccount {
+    /// Sets the EntryPoint and the owner. Can only be called once.
+    pub fn initialize(&mut self, entry_point: Address, owner: Address) -> Result<(), SmartAccountError> {
+        if self.initialized.get() {
+            return Err(SmartAccountError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if owner.is_zero() {
+            return Err(SmartAccountError::InvalidOwner(InvalidOwner { owner }));
+        }
+        self.initialized.set(true);
+        self.entry_point.set(entry_point);
+        self.owner.set(owner);
+
+        log(self.vm(), AccountInitialized { entry_point, owner });
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Validates a user operation's signature and pays the EntryPoint what it is missing
+    /// for the operation (EntryPoint only). Returns 0 if the owner signed it, 1 otherwise.
+    pub fn validate_user_op(
+        &mut self,
+        user_op: PackedUserOperation,
+        user_op_hash: B256,
+        missing_account_funds: U256,
+    ) -> Result<U256, SmartAccountError> {
+        self.only_entry_point()?;
+        let signature = user_op.8;
+        let validation_data = if self.is_owner_signature(user_op_hash, &signature) {
+            SIG_VALIDATION_SUCCESS
+        } else {
+            SIG_VALIDATION_FAILED
+        };
+        if !missing_account_funds.is_zero() {
+            // The EntryPoint checks the deposit itself, so a failed payment is left to it
+            let entry_point = self.entry_point.get();
+            let _ = self.call_target(entry_point, missing_account_funds, &[]);
+        }
+        Ok(U256::from(validation_data))
+    }
+
+    /// Calls `dest` with `value` and `func` (EntryPoint, owner or the account itself only).
+    pub fn execute(&mut self, dest: Address, value: U256, func: Bytes) -> Result<(), Vec<u8>> {
+        self.only_entry_point_owner_or_self()?;
+        self.call_target(dest, value, &func)
+    }
+
+    /// Makes several calls in order, reverting all of them if one fails (EntryPoint, owner or the
+    /// account itself only). `value` may be empty to send no ETH with any call.
+    pub fn execute_batch(&mut self, dest: Vec<Address>, value: Vec<U256>, func: Vec<Bytes>) -> Result<(), Vec<u8>> {
+        self.only_entry_point_owner_or_self()?;
+        if dest.len() != func.len() || (!value.is_empty() && value.len() != dest.len()) {
+            return Err(SmartAccountError::LengthMismatch(LengthMismatch {}).into());
+        }
+        for (i, (target, data)) in dest.into_iter().zip(func).enumerate() {
+            let amount = value.get(i).copied().unwrap_or_default();
+            self.call_target(target, amount, &data)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the ETH sent to the account's deposit at the EntryPoint.
+    #[payable]
+    pub fn add_deposit(&mut self) -> Result<(), SmartAccountError> {
+        let account = self.vm().contract_address();
+        let calldata = IEntryPoint::depositToCall { account }.abi_encode();
+        self.call_entry_point(self.vm().msg_value(), &calldata)
+    }
+
+    /// Withdraws from the account's deposit at the EntryPoint (owner, or the account through `execute`).
+    pub fn withdraw_deposit_to(&mut self, withdraw_address: Address, amount: U256) -> Result<(), SmartAccountError> {
+        self.only_owner_or_self()?;
+        let calldata = IEntryPoint::withdrawToCall { withdraw_address, withdraw_amount: amount }.abi_encode();
+        self.call_entry_point(U256::ZERO, &calldata)
+    }
+
+    /// Transfers the account to a new owner key (owner, or the account through `execute`).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), SmartAccountError> {
+        self.only_owner_or_self()?;
+        if new_owner.is_zero() {
+            return Err(SmartAccountError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// ERC-1271: returns the magic value if the owner signed `hash` as an Ethereum signed message.
+    pub fn is_valid_signature(&self, hash: B256, signature: Bytes) -> FixedBytes<4> {
+        if self.is_owner_signature(hash, &signature) {
+            FixedBytes(ERC1271_MAGIC_VALUE.to_be_bytes())
+        } else {
+            FixedBytes([0xff; 4])
+        }
+    }
+
+    /// The account's deposit at the EntryPoint.
+    pub fn get_deposit(&self) -> Result<U256, SmartAccountError> {
+        let account = self.vm().contract_address();
+        self.entry_point_view(IEntryPoint::balanceOfCall { account }).map(|balance| balance._0)
+    }
+
+    /// The account's next nonce at the EntryPoint for the default key.
+    pub fn get_nonce(&self) -> Result<U256, SmartAccountError> {
+        let sender = self.vm().contract_address();
+        self.entry_point_view(IEntryPoint::getNonceCall { sender, key: Uint::ZERO }).map(|nonce| nonce._0)
+    }
+
+    /// EntryPoint trusted to validate and execute user operations.
+    pub fn entry_point(&self) -> Address {
+        self.entry_point.get()
+    }
+
+    /// Current owner key of the account.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    #[selector(name = "onERC721Received")]
+    pub fn on_erc721_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _token_id: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes(ERC721_RECEIVED.to_be_bytes())
+    }
+
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes(ERC1155_RECEIVED.to_be_bytes())
+    }
+
+    #[selector(name = "onERC1155BatchReceived")]
+    pub fn on_erc1155_batch_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _ids: Vec<U256>,
+        _values: Vec<U256>,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes(ERC1155_BATCH_RECEIVED.to_be_bytes())
+    }
+
+    /// ERC-165 support for ERC-165 itself and the ERC-721 and ERC-1155 receiver interfaces.
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC721_RECEIVER, IERC1155_RECEIVER])
+    }
+
+    /// Accepts plain ETH transfers.
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+
+#[cfg(not(any(test, feature = "export-abi")))]
+#[no_mangle]
+pub extern "C" fn main() {}
+
+#[cfg(feature = "export-abi")]
+fn main() {
+    // `interface` prints ISmartAccount.sol, anything else goes to the SDK's ABI export
+    if std::env::args().nth(1).as_deref() == Some("interface") {
+        print!("{}", smart_account_stylus::interface::solidity_interface());
+    } else {
+        smart_account_stylus::print_from_args();
+    }
+}
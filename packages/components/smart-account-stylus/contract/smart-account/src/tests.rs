@@ -0,0 +1,126 @@
+use super::*;
+use alloy_primitives::{address, keccak256};
+use alloy_sol_types::SolValue;
+use cradle_test_utils::{deploy, emitted, n, ok, reverted, sign};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ENTRY_POINT: Address = address!("0000000071727de22e5e9d8baf0edac6f37da032");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const TARGET: Address = address!("0000000000000000000000000000000000007a26");
+
+/// A signature `signer` made over `hash` as an Ethereum signed message.
+fn sign_message(vm: &TestVM, signer: Address, hash: B256) -> Bytes {
+    let digest = keccak256([b"\x19Ethereum Signed Message:\n32".as_slice(), hash.as_slice()].concat());
+    sign(vm, signer, digest).into()
+}
+
+/// An operation carrying `signature`.
+fn user_op(signature: Bytes) -> PackedUserOperation {
+    let call_data = vec![0xb6, 0x1d, 0x27, 0xf6].into();
+    (ALICE, U256::ZERO, Vec::new().into(), call_data, B256::ZERO, n(50_000), B256::ZERO, Vec::new().into(), signature)
+}
+
+/// An account owned by the owner key, called by the EntryPoint.
+fn setup() -> (TestVM, SmartAccount) {
+    let (vm, mut contract) = deploy::<SmartAccount>(OWNER);
+    ok(contract.initialize(ENTRY_POINT, OWNER));
+    vm.set_sender(ENTRY_POINT);
+    (vm, contract)
+}
+
+#[test]
+fn user_ops_signed_by_the_owner_validate_and_pay_the_entry_point() {
+    let (vm, mut contract) = setup();
+    let initialized = &emitted::<AccountInitialized>(&vm)[0];
+    assert_eq!((initialized.entry_point, initialized.owner), (ENTRY_POINT, OWNER));
+    let result = contract.initialize(ENTRY_POINT, ALICE);
+    assert!(matches!(result, Err(SmartAccountError::AlreadyInitialized(_))));
+
+    let hash = B256::repeat_byte(0x4a);
+    let op = user_op(sign_message(&vm, OWNER, hash));
+    vm.mock_call(ENTRY_POINT, Vec::new(), Ok(Vec::new()));
+    assert_eq!(ok(contract.validate_user_op(op.clone(), hash, n(1_000))), n(SIG_VALIDATION_SUCCESS));
+    let forged = user_op(sign_message(&vm, ALICE, hash));
+    assert_eq!(ok(contract.validate_user_op(forged, hash, U256::ZERO)), n(SIG_VALIDATION_FAILED));
+    // Signatures are over the operation hash
+    assert_eq!(ok(contract.validate_user_op(op.clone(), B256::ZERO, U256::ZERO)), n(SIG_VALIDATION_FAILED));
+
+    vm.set_sender(OWNER);
+    let result = contract.validate_user_op(op, hash, U256::ZERO);
+    assert!(matches!(result, Err(SmartAccountError::Unauthorized(e)) if e.caller == OWNER));
+}
+
+#[test]
+fn the_entry_point_and_owner_execute_calls_and_batches_revert_together() {
+    let (vm, mut contract) = setup();
+    let (sold, sold_out) = (vec![0x01], vec![0x02]);
+    vm.mock_call(TARGET, sold.clone(), Ok(Vec::new()));
+    vm.mock_call(TARGET, sold_out.clone(), Err(b"sold out".to_vec()));
+    ok(contract.execute(TARGET, n(5), sold.clone().into()));
+    vm.set_sender(OWNER);
+    assert_eq!(contract.execute(TARGET, U256::ZERO, sold_out.clone().into()), Err(b"sold out".to_vec()));
+
+    // Without values, no call sends ETH
+    ok(contract.execute_batch(vec![TARGET, TARGET], Vec::new(), vec![sold.clone().into(), sold.clone().into()]));
+    let result = contract.execute_batch(vec![TARGET, TARGET], Vec::new(), vec![sold.clone().into(), sold_out.into()]);
+    assert_eq!(result, Err(b"sold out".to_vec()));
+    let result = contract.execute_batch(vec![TARGET], vec![n(1), n(2)], vec![sold.clone().into()]);
+    assert_eq!(reverted(result), Vec::from(SmartAccountError::LengthMismatch(LengthMismatch {})));
+
+    vm.set_sender(ALICE);
+    let result = contract.execute(TARGET, U256::ZERO, sold.into());
+    assert_eq!(reverted(result), Vec::from(SmartAccountError::Unauthorized(Unauthorized { caller: ALICE })));
+}
+
+#[test]
+fn deposits_are_managed_at_the_entry_point() {
+    let (vm, mut contract) = setup();
+    let account = vm.contract_address();
+    vm.set_sender(ALICE);
+    vm.set_value(n(700));
+    let deposit = IEntryPoint::depositToCall { account };
+    vm.mock_call(ENTRY_POINT, deposit.abi_encode(), Ok(Vec::new()));
+    ok(contract.add_deposit());
+
+    let withdrawal = IEntryPoint::withdrawToCall { withdraw_address: ALICE, withdraw_amount: n(300) };
+    vm.mock_call(ENTRY_POINT, withdrawal.abi_encode(), Ok(Vec::new()));
+    let result = contract.withdraw_deposit_to(ALICE, n(300));
+    assert!(matches!(result, Err(SmartAccountError::Unauthorized(e)) if e.caller == ALICE));
+    vm.set_sender(OWNER);
+    ok(contract.withdraw_deposit_to(ALICE, n(300)));
+    let overdraft = IEntryPoint::withdrawToCall { withdraw_address: ALICE, withdraw_amount: n(401) };
+    vm.mock_call(ENTRY_POINT, overdraft.abi_encode(), Err(b"withdraw amount too large".to_vec()));
+    let result = contract.withdraw_deposit_to(ALICE, n(401));
+    assert!(matches!(result, Err(SmartAccountError::EntryPointCallFailed(_))));
+
+    let balance = IEntryPoint::balanceOfCall { account };
+    vm.mock_static_call(ENTRY_POINT, balance.abi_encode(), Ok(n(400).abi_encode()));
+    assert_eq!(ok(contract.get_deposit()), n(400));
+    let nonce = IEntryPoint::getNonceCall { sender: account, key: Uint::ZERO };
+    vm.mock_static_call(ENTRY_POINT, nonce.abi_encode(), Ok(n(3).abi_encode()));
+    assert_eq!(ok(contract.get_nonce()), n(3));
+}
+
+#[test]
+fn the_owner_signs_for_the_account_until_it_hands_over_the_key() {
+    let (vm, mut contract) = setup();
+    let hash = B256::repeat_byte(0x4a);
+    let signature = sign_message(&vm, OWNER, hash);
+    assert_eq!(contract.is_valid_signature(hash, signature.clone()), FixedBytes(ERC1271_MAGIC_VALUE.to_be_bytes()));
+
+    let result = contract.transfer_ownership(ALICE);
+    assert!(matches!(result, Err(SmartAccountError::Unauthorized(e)) if e.caller == ENTRY_POINT));
+    vm.set_sender(OWNER);
+    let result = contract.transfer_ownership(Address::ZERO);
+    assert!(matches!(result, Err(SmartAccountError::InvalidOwner(e)) if e.owner.is_zero()));
+    ok(contract.transfer_ownership(ALICE));
+    let transferred = emitted::<OwnershipTransferred>(&vm).pop().unwrap();
+    assert_eq!((transferred.previous_owner, transferred.new_owner), (OWNER, ALICE));
+    assert_eq!(contract.is_valid_signature(hash, signature), FixedBytes([0xff; 4]));
+
+    for interface_id in [0x01ffc9a7u32, 0x150b7a02, 0x4e2312e0] {
+        assert!(contract.supports_interface(FixedBytes(interface_id.to_be_bytes())));
+    }
+    assert!(!contract.supports_interface(FixedBytes([0xff; 4])));
+}
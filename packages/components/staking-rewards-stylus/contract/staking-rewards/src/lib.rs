@@ -0,0 +1,415 @@
+//! Staking rewards pool: stake an ERC-20, earn another ERC-20
+//!
+//! A port of the Synthetix `StakingRewards` contract. The owner funds a reward
+//! period with `notify_reward_amount`, which streams the rewards linearly over
+//! `rewards_duration` seconds. Stakers earn a share of the stream proportional to
+//! their stake, tracked through the cumulative `reward_per_token_stored` value, and
+//! can claim at any time.
+//!
+//! The staking and rewards token may be the same token; staked balances are never
+//! counted as rewards.
+//!
+//! As in Synthetix, pausing only stops new stakes: the owner can point the pool
+//! at a `pause-registry-stylus` deployment, and withdrawals and claims stay open
+//! while its switch is on.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_pausable::{registry_pauses, EnforcedPause};
+use stylus_sdk::prelude::*;
+
+/// Fixed-point scale of `reward_per_token_stored`.
+const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct StakingRewards {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Can fund reward periods and change their duration
+        address owner;
+        /// Token users stake
+        address staking_token;
+        /// Token paid out as rewards
+        address rewards_token;
+        /// Timestamp at which the current reward period ends
+        uint256 period_finish;
+        /// Rewards streamed per second during the current period
+        uint256 reward_rate;
+        /// Length of a reward period in seconds
+        uint256 rewards_duration;
+        /// Last time `reward_per_token_stored` was updated
+        uint256 last_update_time;
+        /// Rewards accumulated per staked token, scaled by `PRECISION`
+        uint256 reward_per_token_stored;
+        /// `reward_per_token_stored` at each user's last update
+        mapping(address => uint256) user_reward_per_token_paid;
+        /// Rewards earned but not yet claimed
+        mapping(address => uint256) rewards;
+        /// Total amount staked
+        uint256 total_supply;
+        /// Amount staked per user
+        mapping(address => uint256) balances;
+        /// `pause-registry-stylus` deployment that can pause staking, zero for none
+        address pause_registry;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Staked(address indexed user, uint256 amount);
+    event Withdrawn(address indexed user, uint256 amount);
+    event RewardPaid(address indexed user, uint256 reward);
+    event RewardAdded(uint256 reward);
+    event RewardsDurationUpdated(uint256 new_duration);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event PauseRegistrySet(address registry);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Staking or withdrawing zero tokens
+    error ZeroAmount();
+    // Withdrawing more than the staked balance
+    error InsufficientStake(address user, uint256 have, uint256 want);
+    // Reward rate would exceed the rewards held by the pool
+    error RewardTooHigh(uint256 reward_rate, uint256 balance);
+    // Duration can't change while a reward period is running, or is zero
+    error InvalidDuration(uint256 duration);
+    // An ERC-20 transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum StakingRewardsError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    ZeroAmount(ZeroAmount),
+    InsufficientStake(InsufficientStake),
+    RewardTooHigh(RewardTooHigh),
+    InvalidDuration(InvalidDuration),
+    TransferFailed(TransferFailed),
+    EnforcedPause(EnforcedPause),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+// Helpers used by the public methods below.
+impl StakingRewards {
+    fn only_owner(&self) -> Result<(), StakingRewardsError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(StakingRewardsError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Fails while the pause registry pauses the pool.
+    fn when_not_paused(&self) -> Result<(), StakingRewardsError> {
+        if registry_pauses(self.vm(), self.pause_registry.get(), self.vm().contract_address()) {
+            return Err(StakingRewardsError::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Checkpoints the global accumulator and, if `account` is non-zero, the account's earnings.
+    fn update_reward(&mut self, account: Address) {
+        let reward_per_token = self.reward_per_token();
+        self.reward_per_token_stored.set(reward_per_token);
+        self.last_update_time.set(self.last_time_reward_applicable());
+        if !account.is_zero() {
+            let earned = self.earned(account);
+            self.rewards.insert(account, earned);
+            self.user_reward_per_token_paid.insert(account, reward_per_token);
+        }
+    }
+
+    /// Calls an ERC-20 transfer method, which must return `true`.
+    fn call_token(&mut self, token: Address, calldata: Vec<u8>) -> bool {
+        self.vm()
+            .call(&calls::context::Call::new(), token, &calldata)
+            .is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+    }
+
+    fn transfer_out(&mut self, token: Address, to: Address, amount: U256) -> Result<(), StakingRewardsError> {
+        if !self.call_token(token, IERC20::transferCall { to, value: amount }.abi_encode()) {
+            return Err(StakingRewardsError::TransferFailed(TransferFailed { token, to, amount }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl StakingRewards {
+    /// Sets the owner, the tokens and the reward period length. Can only be called once.
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        staking_token: Address,
+        rewards_token: Address,
+        rewards_duration: U256,
+    ) -> Result<(), StakingRewardsError> {
+        if self.initialized.get() {
+            return Err(StakingRewardsError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if rewards_duration.is_zero() {
+            return Err(StakingRewardsError::InvalidDuration(InvalidDuration {
+                duration: rewards_duration,
+            }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.staking_token.set(staking_token);
+        self.rewards_token.set(rewards_token);
+        self.rewards_duration.set(rewards_duration);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Stakes `amount` of the staking token. Requires an allowance for the pool.
+    pub fn stake(&mut self, amount: U256) -> Result<(), StakingRewardsError> {
+        self.when_not_paused()?;
+        if amount.is_zero() {
+            return Err(StakingRewardsError::ZeroAmount(ZeroAmount {}));
+        }
+        let user = self.vm().msg_sender();
+        self.update_reward(user);
+
+        self.total_supply.set(self.total_supply.get() + amount);
+        let balance = self.balances.get(user);
+        self.balances.insert(user, balance + amount);
+
+        let token = self.staking_token.get();
+        let this = self.vm().contract_address();
+        let call = IERC20::transferFromCall { from: user, to: this, value: amount };
+        if !self.call_token(token, call.abi_encode()) {
+            return Err(StakingRewardsError::TransferFailed(TransferFailed {
+                token,
+                to: this,
+                amount,
+            }));
+        }
+
+        log(self.vm(), Staked { user, amount });
+        Ok(())
+    }
+
+    /// Withdraws `amount` of the caller's stake.
+    pub fn withdraw(&mut self, amount: U256) -> Result<(), StakingRewardsError> {
+        if amount.is_zero() {
+            return Err(StakingRewardsError::ZeroAmount(ZeroAmount {}));
+        }
+        let user = self.vm().msg_sender();
+        self.update_reward(user);
+
+        let balance = self.balances.get(user);
+        if balance < amount {
+            return Err(StakingRewardsError::InsufficientStake(InsufficientStake {
+                user,
+                have: balance,
+                want: amount,
+            }));
+        }
+        self.total_supply.set(self.total_supply.get() - amount);
+        self.balances.insert(user, balance - amount);
+
+        let token = self.staking_token.get();
+        self.transfer_out(token, user, amount)?;
+
+        log(self.vm(), Withdrawn { user, amount });
+        Ok(())
+    }
+
+    /// Claims the caller's earned rewards.
+    pub fn get_reward(&mut self) -> Result<(), StakingRewardsError> {
+        let user = self.vm().msg_sender();
+        self.update_reward(user);
+
+        let reward = self.rewards.get(user);
+        if reward.is_zero() {
+            return Ok(());
+        }
+        self.rewards.insert(user, U256::ZERO);
+
+        let token = self.rewards_token.get();
+        self.transfer_out(token, user, reward)?;
+
+        log(self.vm(), RewardPaid { user, reward });
+        Ok(())
+    }
+
+    /// Withdraws the caller's whole stake and claims their rewards.
+    pub fn exit(&mut self) -> Result<(), StakingRewardsError> {
+        let balance = self.balances.get(self.vm().msg_sender());
+        if !balance.is_zero() {
+            self.withdraw(balance)?;
+        }
+        self.get_reward()
+    }
+
+    /// Starts a new reward period streaming `reward` (plus whatever is left of the
+    /// current period) over `rewards_duration`. The pool must already hold the rewards.
+    pub fn notify_reward_amount(&mut self, reward: U256) -> Result<(), StakingRewardsError> {
+        self.only_owner()?;
+        self.update_reward(Address::ZERO);
+
+        let now = self.now();
+        let duration = self.rewards_duration.get();
+        let period_finish = self.period_finish.get();
+        let reward_rate = if now >= period_finish {
+            reward / duration
+        } else {
+            let leftover = (period_finish - now) * self.reward_rate.get();
+            (reward + leftover) / duration
+        };
+
+        // Make sure the rate can be paid out, so rewards never exceed the pool's balance.
+        let rewards_token = self.rewards_token.get();
+        let this = self.vm().contract_address();
+        let call = IERC20::balanceOfCall { account: this };
+        let mut balance = self
+            .vm()
+            .static_call(&self, rewards_token, &call.abi_encode())
+            .ok()
+            .and_then(|output| IERC20::balanceOfCall::abi_decode_returns(&output, true).ok())
+            .map_or(U256::ZERO, |balance| balance._0);
+        if rewards_token == self.staking_token.get() {
+            balance = balance.saturating_sub(self.total_supply.get());
+        }
+        if reward_rate > balance / duration {
+            return Err(StakingRewardsError::RewardTooHigh(RewardTooHigh { reward_rate, balance }));
+        }
+
+        self.reward_rate.set(reward_rate);
+        self.last_update_time.set(now);
+        self.period_finish.set(now + duration);
+
+        log(self.vm(), RewardAdded { reward });
+        Ok(())
+    }
+
+    /// Changes the length of future reward periods (owner only, between periods).
+    pub fn set_rewards_duration(&mut self, duration: U256) -> Result<(), StakingRewardsError> {
+        self.only_owner()?;
+        if duration.is_zero() || self.now() <= self.period_finish.get() {
+            return Err(StakingRewardsError::InvalidDuration(InvalidDuration { duration }));
+        }
+        self.rewards_duration.set(duration);
+
+        log(self.vm(), RewardsDurationUpdated { new_duration: duration });
+        Ok(())
+    }
+
+    /// Transfers ownership of the pool (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), StakingRewardsError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Lets `registry`'s switch pause staking, or stops with the zero address
+    /// (owner only).
+    pub fn set_pause_registry(&mut self, registry: Address) -> Result<(), StakingRewardsError> {
+        self.only_owner()?;
+        self.pause_registry.set(registry);
+
+        log(self.vm(), PauseRegistrySet { registry });
+        Ok(())
+    }
+
+    /// `pause-registry-stylus` deployment the pool consults, zero until set.
+    pub fn pause_registry(&self) -> Address {
+        self.pause_registry.get()
+    }
+
+    /// Total amount staked.
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    /// Amount staked by `account`.
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
+
+    /// The current time, capped at the end of the reward period.
+    pub fn last_time_reward_applicable(&self) -> U256 {
+        self.now().min(self.period_finish.get())
+    }
+
+    /// Rewards accumulated per staked token, scaled by 1e18.
+    pub fn reward_per_token(&self) -> U256 {
+        let total_supply = self.total_supply.get();
+        let stored = self.reward_per_token_stored.get();
+        if total_supply.is_zero() {
+            return stored;
+        }
+        let elapsed = self
+            .last_time_reward_applicable()
+            .saturating_sub(self.last_update_time.get());
+        stored + elapsed * self.reward_rate.get() * U256::from(PRECISION) / total_supply
+    }
+
+    /// Rewards earned by `account` and not yet claimed.
+    pub fn earned(&self, account: Address) -> U256 {
+        let accrued = self.reward_per_token() - self.user_reward_per_token_paid.get(account);
+        self.balances.get(account) * accrued / U256::from(PRECISION) + self.rewards.get(account)
+    }
+
+    /// Rewards streamed over a whole period at the current rate.
+    pub fn get_reward_for_duration(&self) -> U256 {
+        self.reward_rate.get() * self.rewards_duration.get()
+    }
+
+    /// Returns `(staking_token, rewards_token)`.
+    pub fn tokens(&self) -> (Address, Address) {
+        (self.staking_token.get(), self.rewards_token.get())
+    }
+
+    /// Returns `(reward_rate, period_finish, rewards_duration)`.
+    pub fn reward_period(&self) -> (U256, U256, U256) {
+        (
+            self.reward_rate.get(),
+            self.period_finish.get(),
+            self.rewards_duration.get(),
+        )
+    }
+
+    /// Current owner of the pool.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,136 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_pausable::IPauseRegistry;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const STAKE: Address = address!("0000000000000000000000000000000000005a4e");
+const REWARD: Address = address!("000000000000000000000000000000000000e4a2");
+const REGISTRY: Address = address!("0000000000000000000000000000000000009a05");
+
+/// Mocks `token`'s balance of the pool.
+fn mock_balance(vm: &TestVM, token: Address, balance: u64) {
+    let call = IERC20::balanceOfCall { account: vm.contract_address() };
+    vm.mock_static_call(token, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// Lets `from` stake `amount` of `STAKE`; any other amount isn't mocked and fails.
+fn expect_pull(vm: &TestVM, from: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to: vm.contract_address(), value: n(amount) };
+    vm.mock_call(STAKE, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Lets the pool send exactly `amount` of `token` to `to`.
+fn expect_payout(vm: &TestVM, token: Address, to: Address, amount: u64) {
+    let call = IERC20::transferCall { to, value: n(amount) };
+    vm.mock_call(token, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// A pool with 100 second periods, `rewards_token` paid out, at time 1,000.
+fn setup(rewards_token: Address) -> (TestVM, StakingRewards) {
+    let (vm, mut contract) = deploy::<StakingRewards>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, STAKE, rewards_token, n(100)));
+    (vm, contract)
+}
+
+#[test]
+fn stakers_share_the_stream_by_stake_and_time() {
+    let (vm, mut contract) = setup(REWARD);
+    mock_balance(&vm, REWARD, 1_000);
+    ok(contract.notify_reward_amount(n(1_000)));
+    assert_eq!(contract.reward_period(), (n(10), n(1_100), n(100)));
+
+    expect_pull(&vm, ALICE, 100);
+    vm.set_sender(ALICE);
+    ok(contract.stake(n(100)));
+    vm.set_block_timestamp(1_050);
+    assert_eq!(contract.earned(ALICE), n(500));
+
+    // Bob's equal stake halves Alice's share from here on
+    expect_pull(&vm, BOB, 100);
+    vm.set_sender(BOB);
+    ok(contract.stake(n(100)));
+    assert_eq!(contract.total_supply(), n(200));
+    vm.set_block_timestamp(1_200);
+    assert_eq!((contract.earned(ALICE), contract.earned(BOB)), (n(750), n(250)));
+
+    // Exiting returns the stake and pays exactly what was earned
+    expect_payout(&vm, STAKE, ALICE, 100);
+    expect_payout(&vm, REWARD, ALICE, 750);
+    vm.set_sender(ALICE);
+    ok(contract.exit());
+    assert_eq!((contract.balance_of(ALICE), contract.earned(ALICE)), (U256::ZERO, U256::ZERO));
+    let withdrawn = &emitted::<Withdrawn>(&vm)[0];
+    assert_eq!((withdrawn.user, withdrawn.amount), (ALICE, n(100)));
+    let paid = &emitted::<RewardPaid>(&vm)[0];
+    assert_eq!((paid.user, paid.reward), (ALICE, n(750)));
+}
+
+#[test]
+fn rewards_never_count_the_stake_when_the_tokens_match() {
+    let (vm, mut contract) = setup(STAKE);
+    expect_pull(&vm, ALICE, 500);
+    vm.set_sender(ALICE);
+    ok(contract.stake(n(500)));
+
+    // The pool holds 800, but 500 of it is Alice's stake
+    mock_balance(&vm, STAKE, 800);
+    vm.set_sender(OWNER);
+    let result = contract.notify_reward_amount(n(400));
+    assert!(matches!(
+        result,
+        Err(StakingRewardsError::RewardTooHigh(e)) if e.reward_rate == n(4) && e.balance == n(300)
+    ));
+    ok(contract.notify_reward_amount(n(300)));
+    assert_eq!(contract.get_reward_for_duration(), n(300));
+}
+
+#[test]
+fn stakes_and_payouts_fail_without_the_tokens_moving() {
+    let (vm, mut contract) = setup(REWARD);
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.stake(U256::ZERO), Err(StakingRewardsError::ZeroAmount(_))));
+    let result = contract.notify_reward_amount(n(1_000));
+    assert!(matches!(result, Err(StakingRewardsError::Unauthorized(e)) if e.caller == ALICE));
+
+    expect_pull(&vm, ALICE, 100);
+    ok(contract.stake(n(100)));
+    let result = contract.withdraw(n(101));
+    assert!(matches!(
+        result,
+        Err(StakingRewardsError::InsufficientStake(e)) if e.have == n(100) && e.want == n(101)
+    ));
+
+    // A token that doesn't return `true` fails the withdrawal
+    let result = contract.withdraw(n(100));
+    assert!(matches!(
+        result,
+        Err(StakingRewardsError::TransferFailed(e)) if e.token == STAKE && e.to == ALICE && e.amount == n(100)
+    ));
+
+    // As does a stake the token doesn't pull
+    vm.set_sender(BOB);
+    let result = contract.stake(n(100));
+    assert!(matches!(result, Err(StakingRewardsError::TransferFailed(e)) if e.token == STAKE));
+}
+
+#[test]
+fn pausing_stops_stakes_but_not_withdrawals() {
+    let (vm, mut contract) = setup(REWARD);
+    ok(contract.set_pause_registry(REGISTRY));
+    let call = IPauseRegistry::isPausedCall { target: vm.contract_address() };
+    vm.mock_static_call(REGISTRY, call.abi_encode(), Ok(false.abi_encode()));
+    expect_pull(&vm, ALICE, 100);
+    vm.set_sender(ALICE);
+    ok(contract.stake(n(100)));
+
+    vm.mock_static_call(REGISTRY, call.abi_encode(), Ok(true.abi_encode()));
+    assert!(matches!(contract.stake(n(100)), Err(StakingRewardsError::EnforcedPause(_))));
+    expect_payout(&vm, STAKE, ALICE, 100);
+    ok(contract.withdraw(n(100)));
+    assert_eq!(contract.balance_of(ALICE), U256::ZERO);
+}
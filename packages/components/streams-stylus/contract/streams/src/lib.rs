@@ -0,0 +1,657 @@
+//! Token streams
+//!
+//! Sablier-style linear streams of ERC-20 tokens, for vesting schedules, payroll and grants. A
+//! sender escrows a deposit that unlocks linearly between a start and an end time, with nothing
+//! unlocking before an optional cliff. Every stream is an ERC-721 token of this contract minted
+//! to the recipient, so streams can be transferred or sold on an NFT marketplace, and whoever
+//! holds the token withdraws what has unlocked.
+//!
+//! Cancelable streams can be canceled by their sender: the part that hasn't unlocked is refunded
+//! and the unlocked part stays withdrawable by the holder. Senders can renounce cancellation to
+//! make a stream irrevocable. A stream's token can be burned once everything has been withdrawn.
+//!
+//! Start, cliff and end times are read off the contract's clock, which counts seconds of
+//! `block.timestamp` unless `initialize` set it to count L2 or L1 blocks before the first stream.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{aliases::U64, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use cradle_arb::{
+    clock::{Clock, InvalidClockMode},
+    PrecompileCallFailed,
+};
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// ERC-721, `0x80ac58cd`.
+pub const IERC721: Interface = interface!(
+    "balanceOf"(Address),
+    "ownerOf"(U256),
+    "safeTransferFrom"(Address, Address, U256, Bytes),
+    "safeTransferFrom"(Address, Address, U256),
+    "transferFrom"(Address, Address, U256),
+    "approve"(Address, U256),
+    "setApprovalForAll"(Address, bool),
+    "getApproved"(U256),
+    "isApprovedForAll"(Address, Address),
+);
+
+/// Collection name of the stream tokens
+pub const NAME: &str = "Cradle Streams";
+/// Collection symbol of the stream tokens
+pub const SYMBOL: &str = "STREAM";
+
+/// Stream status: the start time hasn't passed
+pub const STATUS_PENDING: u8 = 0;
+/// Stream status: the deposit is unlocking
+pub const STATUS_STREAMING: u8 = 1;
+/// Stream status: the whole deposit has unlocked, but not all of it has been withdrawn
+pub const STATUS_SETTLED: u8 = 2;
+/// Stream status: canceled, with an unlocked amount left to withdraw
+pub const STATUS_CANCELED: u8 = 3;
+/// Stream status: everything the holder is owed has been withdrawn
+pub const STATUS_DEPLETED: u8 = 4;
+
+/// A stream to create in a batch: `(recipient, total, start, cliff, end)`
+pub type StreamParams = (Address, U256, u64, u64, u64);
+
+/// A stream's details: `(sender, token, total, withdrawn, refunded, start, cliff, end,
+/// cancelable, canceled)`
+pub type StreamDetails = (Address, Address, U256, U256, U256, u64, u64, u64, bool, bool);
+
+sol_storage! {
+    pub struct Stream {
+        /// Funded the stream and may cancel it
+        address sender;
+        uint64 start;
+        /// ERC-20 being streamed
+        address token;
+        /// Nothing unlocks before this time; equal to `start` for no cliff
+        uint64 cliff;
+        /// The whole deposit has unlocked at this time
+        uint64 end;
+        bool cancelable;
+        bool canceled;
+        /// Deposit escrowed at creation
+        uint256 total;
+        /// Withdrawn by the holders of the stream token
+        uint256 withdrawn;
+        /// Paid back to the sender on cancellation
+        uint256 refunded;
+    }
+
+    #[entrypoint]
+    pub struct Streams {
+        /// Last stream ID, which is also the stream token ID; IDs start at 1
+        uint256 last_stream_id;
+        /// Holder of each stream token, zero for burned or nonexistent tokens
+        mapping(uint256 => address) owners;
+        /// Number of stream tokens each account holds
+        mapping(address => uint256) balances;
+        /// ERC-721 approved address of each stream token
+        mapping(uint256 => address) token_approvals;
+        /// ERC-721 operators of each holder
+        mapping(address => mapping(address => bool)) operator_approvals;
+        mapping(uint256 => Stream) streams;
+        /// Set once by `initialize`
+        bool initialized;
+        /// What stream times are counted in
+        Clock clock;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    event StreamCreated(
+        uint256 indexed streamId,
+        address indexed sender,
+        address indexed recipient,
+        address token,
+        uint256 total,
+        uint64 start,
+        uint64 cliff,
+        uint64 end,
+        bool cancelable
+    );
+    event Withdrawn(uint256 indexed streamId, address indexed to, address token, uint256 amount);
+    event StreamCanceled(
+        uint256 indexed streamId,
+        address indexed sender,
+        address indexed recipient,
+        uint256 senderAmount,
+        uint256 recipientAmount
+    );
+    event StreamRenounced(uint256 indexed streamId);
+
+    // Account is the zero address
+    error ERC721InvalidOwner(address owner);
+    // Token doesn't exist
+    error ERC721NonexistentToken(uint256 tokenId);
+    // Token isn't held by `sender`
+    error ERC721IncorrectOwner(address sender, uint256 tokenId, address owner);
+    // Recipient is the zero address, or a contract that refused the token
+    error ERC721InvalidReceiver(address receiver);
+    // Caller may not move, approve or withdraw from the token
+    error ERC721InsufficientApproval(address operator, uint256 tokenId);
+    // Operator is the zero address
+    error ERC721InvalidOperator(address operator);
+    // Stream was never created
+    error StreamNotFound(uint256 streamId);
+    // Deposit is zero
+    error ZeroDeposit();
+    // Times aren't `start <= cliff <= end` with `start < end`
+    error InvalidSchedule(uint64 start, uint64 cliff, uint64 end);
+    // Caller isn't the stream's sender
+    error NotStreamSender(uint256 streamId, address caller);
+    // Stream was made irrevocable, or already canceled
+    error StreamNotCancelable(uint256 streamId);
+    // Whole deposit has already unlocked, so there is nothing to cancel
+    error StreamSettled(uint256 streamId);
+    // Amount to withdraw is zero or over what has unlocked and not been withdrawn
+    error Overdraw(uint256 streamId, uint256 amount, uint256 withdrawable);
+    // Withdrawal to the zero address
+    error InvalidWithdrawalAddress(uint256 streamId);
+    // Stream token can only be burned once everything owed has been withdrawn
+    error StreamNotDepleted(uint256 streamId);
+    // Token transfer reverted or returned false
+    error TransferFailed(address token);
+    // Clock was already set, or streams were created on the default one
+    error AlreadyInitialized();
+
+    function onERC721Received(address operator, address from, uint256 tokenId, bytes data) returns (bytes4);
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum StreamsError {
+    InvalidOwner(ERC721InvalidOwner),
+    NonexistentToken(ERC721NonexistentToken),
+    IncorrectOwner(ERC721IncorrectOwner),
+    InvalidReceiver(ERC721InvalidReceiver),
+    InsufficientApproval(ERC721InsufficientApproval),
+    InvalidOperator(ERC721InvalidOperator),
+    StreamNotFound(StreamNotFound),
+    ZeroDeposit(ZeroDeposit),
+    InvalidSchedule(InvalidSchedule),
+    NotStreamSender(NotStreamSender),
+    StreamNotCancelable(StreamNotCancelable),
+    StreamSettled(StreamSettled),
+    Overdraw(Overdraw),
+    InvalidWithdrawalAddress(InvalidWithdrawalAddress),
+    StreamNotDepleted(StreamNotDepleted),
+    TransferFailed(TransferFailed),
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidClockMode(InvalidClockMode),
+    PrecompileCallFailed(PrecompileCallFailed),
+}
+
+#[public]
+impl Streams {
+    /// Sets the clock stream times are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1
+    /// blocks. Can only be called once, before the first stream; without it streams count seconds.
+    pub fn initialize(&mut self, clock_mode: u8) -> Result<(), StreamsError> {
+        if self.initialized.get() || !self.last_stream_id.get().is_zero() {
+            return Err(StreamsError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.clock.init(clock_mode).map_err(StreamsError::InvalidClockMode)?;
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// Creates a stream of `total` of `token` from the caller to `recipient`, unlocking linearly
+    /// from `start` to `end` with nothing before `cliff`, and mints its token to `recipient`.
+    /// Pulls the deposit with `transferFrom`, so the caller must have approved this contract.
+    /// Returns the stream ID.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream(
+        &mut self,
+        recipient: Address,
+        token: Address,
+        total: U256,
+        start: u64,
+        cliff: u64,
+        end: u64,
+        cancelable: bool,
+    ) -> Result<U256, StreamsError> {
+        let stream_id = self.add_stream((recipient, total, start, cliff, end), token, cancelable)?;
+        self.pull(token, total)?;
+        Ok(stream_id)
+    }
+
+    /// Creates a stream of `token` for each of `streams`, such as a vesting schedule for every
+    /// member of a team, pulling their deposits in one transfer. Returns the stream IDs.
+    pub fn create_streams(
+        &mut self,
+        token: Address,
+        cancelable: bool,
+        streams: Vec<StreamParams>,
+    ) -> Result<Vec<U256>, StreamsError> {
+        let mut stream_ids = Vec::with_capacity(streams.len());
+        let mut total = U256::ZERO;
+        for params in streams {
+            total += params.1;
+            stream_ids.push(self.add_stream(params, token, cancelable)?);
+        }
+        self.pull(token, total)?;
+        Ok(stream_ids)
+    }
+
+    /// Withdraws `amount` of what has unlocked to `to`. The holder of the stream token, its
+    /// operators and the token's approved address can withdraw anywhere; anyone else can only
+    /// withdraw to the holder.
+    pub fn withdraw(&mut self, stream_id: U256, to: Address, amount: U256) -> Result<(), StreamsError> {
+        let owner = self.owner_of(stream_id)?;
+        let sender = self.vm().msg_sender();
+        if to != owner && !self.is_authorized(owner, sender, stream_id) {
+            return Err(StreamsError::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: stream_id,
+            }));
+        }
+        if to.is_zero() {
+            return Err(StreamsError::InvalidWithdrawalAddress(InvalidWithdrawalAddress { streamId: stream_id }));
+        }
+        let withdrawable = self.withdrawable_amount_of(stream_id)?;
+        if amount.is_zero() || amount > withdrawable {
+            return Err(StreamsError::Overdraw(Overdraw { streamId: stream_id, amount, withdrawable }));
+        }
+        let mut stream = self.streams.setter(stream_id);
+        let withdrawn = stream.withdrawn.get();
+        stream.withdrawn.set(withdrawn + amount);
+        let token = stream.token.get();
+        self.send(token, to, amount)?;
+        log(self.vm(), Withdrawn { streamId: stream_id, to, token, amount });
+        Ok(())
+    }
+
+    /// Withdraws everything that has unlocked to `to`, as `withdraw` allows, and returns the
+    /// amount.
+    pub fn withdraw_max(&mut self, stream_id: U256, to: Address) -> Result<U256, StreamsError> {
+        let amount = self.withdrawable_amount_of(stream_id)?;
+        self.withdraw(stream_id, to, amount)?;
+        Ok(amount)
+    }
+
+    /// Cancels a cancelable stream (sender only), refunding the part that hasn't unlocked. The
+    /// unlocked part stays withdrawable by the holder.
+    pub fn cancel(&mut self, stream_id: U256) -> Result<(), StreamsError> {
+        let sender = self.require_sender(stream_id)?;
+        let streamed = self.streamed_amount_of(stream_id)?;
+        let mut stream = self.streams.setter(stream_id);
+        let total = stream.total.get();
+        if streamed == total {
+            return Err(StreamsError::StreamSettled(StreamSettled { streamId: stream_id }));
+        }
+        let refund = total - streamed;
+        let recipient_amount = streamed - stream.withdrawn.get();
+        stream.refunded.set(refund);
+        stream.canceled.set(true);
+        stream.cancelable.set(false);
+        let token = stream.token.get();
+        self.send(token, sender, refund)?;
+        let recipient = self.owners.get(stream_id);
+        log(
+            self.vm(),
+            StreamCanceled {
+                streamId: stream_id,
+                sender,
+                recipient,
+                senderAmount: refund,
+                recipientAmount: recipient_amount,
+            },
+        );
+        Ok(())
+    }
+
+    /// Makes a cancelable stream irrevocable (sender only).
+    pub fn renounce(&mut self, stream_id: U256) -> Result<(), StreamsError> {
+        self.require_sender(stream_id)?;
+        self.streams.setter(stream_id).cancelable.set(false);
+        log(self.vm(), StreamRenounced { streamId: stream_id });
+        Ok(())
+    }
+
+    /// Burns a depleted stream's token. The holder, its operators and the token's approved
+    /// address can burn. The stream's details stay readable.
+    pub fn burn(&mut self, stream_id: U256) -> Result<(), StreamsError> {
+        let owner = self.owner_of(stream_id)?;
+        let sender = self.vm().msg_sender();
+        if !self.is_authorized(owner, sender, stream_id) {
+            return Err(StreamsError::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: stream_id,
+            }));
+        }
+        if self.status_of(stream_id)? != STATUS_DEPLETED {
+            return Err(StreamsError::StreamNotDepleted(StreamNotDepleted { streamId: stream_id }));
+        }
+        self.update(Address::ZERO, stream_id);
+        Ok(())
+    }
+
+    pub fn get_stream(&self, stream_id: U256) -> Result<StreamDetails, StreamsError> {
+        self.require_stream(stream_id)?;
+        let stream = self.streams.getter(stream_id);
+        Ok((
+            stream.sender.get(),
+            stream.token.get(),
+            stream.total.get(),
+            stream.withdrawn.get(),
+            stream.refunded.get(),
+            stream.start.get().to(),
+            stream.cliff.get().to(),
+            stream.end.get().to(),
+            stream.cancelable.get(),
+            stream.canceled.get(),
+        ))
+    }
+
+    /// Amount unlocked so far, including what has been withdrawn. Frozen when canceled.
+    pub fn streamed_amount_of(&self, stream_id: U256) -> Result<U256, StreamsError> {
+        self.require_stream(stream_id)?;
+        let stream = self.streams.getter(stream_id);
+        let total = stream.total.get();
+        if stream.canceled.get() {
+            return Ok(total - stream.refunded.get());
+        }
+        let now = self.now()?;
+        let start = stream.start.get().to::<u64>();
+        let end = stream.end.get().to::<u64>();
+        if now < stream.cliff.get().to::<u64>() || now < start {
+            return Ok(U256::ZERO);
+        }
+        if now >= end {
+            return Ok(total);
+        }
+        // total * elapsed / duration, split so that it can't overflow
+        let elapsed = U256::from(now - start);
+        let duration = U256::from(end - start);
+        Ok(total / duration * elapsed + total % duration * elapsed / duration)
+    }
+
+    /// Amount the holder can withdraw now.
+    pub fn withdrawable_amount_of(&self, stream_id: U256) -> Result<U256, StreamsError> {
+        let streamed = self.streamed_amount_of(stream_id)?;
+        Ok(streamed - self.streams.getter(stream_id).withdrawn.get())
+    }
+
+    /// Amount the sender would get back by canceling now, zero if the stream can't be canceled.
+    pub fn refundable_amount_of(&self, stream_id: U256) -> Result<U256, StreamsError> {
+        let streamed = self.streamed_amount_of(stream_id)?;
+        let stream = self.streams.getter(stream_id);
+        if !stream.cancelable.get() {
+            return Ok(U256::ZERO);
+        }
+        Ok(stream.total.get() - streamed)
+    }
+
+    /// One of the `STATUS_*` values.
+    pub fn status_of(&self, stream_id: U256) -> Result<u8, StreamsError> {
+        let streamed = self.streamed_amount_of(stream_id)?;
+        let stream = self.streams.getter(stream_id);
+        let status = if stream.canceled.get() && stream.withdrawn.get() == streamed {
+            STATUS_DEPLETED
+        } else if stream.canceled.get() {
+            STATUS_CANCELED
+        } else if self.now()? < stream.start.get().to::<u64>() {
+            STATUS_PENDING
+        } else if streamed < stream.total.get() {
+            STATUS_STREAMING
+        } else if stream.withdrawn.get() < streamed {
+            STATUS_SETTLED
+        } else {
+            STATUS_DEPLETED
+        };
+        Ok(status)
+    }
+
+    pub fn next_stream_id(&self) -> U256 {
+        self.last_stream_id.get() + U256::from(1)
+    }
+
+    /// What stream times are counted in: 0 for seconds, 1 for L2 blocks and 2 for L1 blocks.
+    pub fn clock_mode(&self) -> u8 {
+        self.clock.mode()
+    }
+
+    /// Current time on the contract's clock, which stream times are compared against.
+    pub fn clock(&self) -> Result<U256, StreamsError> {
+        self.now().map(U256::from)
+    }
+
+    pub fn name(&self) -> String {
+        NAME.into()
+    }
+
+    pub fn symbol(&self) -> String {
+        SYMBOL.into()
+    }
+
+    /// Number of stream tokens `owner` holds.
+    pub fn balance_of(&self, owner: Address) -> Result<U256, StreamsError> {
+        if owner.is_zero() {
+            return Err(StreamsError::InvalidOwner(ERC721InvalidOwner { owner }));
+        }
+        Ok(self.balances.get(owner))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, StreamsError> {
+        let owner = self.owners.get(token_id);
+        if owner.is_zero() {
+            return Err(StreamsError::NonexistentToken(ERC721NonexistentToken { tokenId: token_id }));
+        }
+        Ok(owner)
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, StreamsError> {
+        self.owner_of(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(owner).get(operator)
+    }
+
+    /// Approves `approved` to move and withdraw from `token_id`.
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), StreamsError> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if sender != owner && !self.is_approved_for_all(owner, sender) {
+            return Err(StreamsError::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        self.token_approvals.insert(token_id, approved);
+        log(self.vm(), Approval { owner, approved, tokenId: token_id });
+        Ok(())
+    }
+
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), StreamsError> {
+        if operator.is_zero() {
+            return Err(StreamsError::InvalidOperator(ERC721InvalidOperator { operator }));
+        }
+        let owner = self.vm().msg_sender();
+        self.operator_approvals.setter(owner).insert(operator, approved);
+        log(self.vm(), ApprovalForAll { owner, operator, approved });
+        Ok(())
+    }
+
+    /// Moves the stream `token_id`, and the right to withdraw from it, from `from` to `to`.
+    pub fn transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), StreamsError> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if !self.is_authorized(owner, sender, token_id) {
+            return Err(StreamsError::InsufficientApproval(ERC721InsufficientApproval {
+                operator: sender,
+                tokenId: token_id,
+            }));
+        }
+        if owner != from {
+            return Err(StreamsError::IncorrectOwner(ERC721IncorrectOwner { sender: from, tokenId: token_id, owner }));
+        }
+        if to.is_zero() {
+            return Err(StreamsError::InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+        self.update(to, token_id);
+        Ok(())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), StreamsError> {
+        self.safe_transfer_from_with_data(from, to, token_id, Vec::new().into())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), StreamsError> {
+        self.transfer_from(from, to, token_id)?;
+        self.check_received(from, to, token_id, data)
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC721])
+    }
+}
+
+// Helpers used by the public methods above.
+impl Streams {
+    fn now(&self) -> Result<u64, StreamsError> {
+        self.clock.now().map(|now| now.saturating_to()).map_err(StreamsError::PrecompileCallFailed)
+    }
+
+    fn require_stream(&self, stream_id: U256) -> Result<(), StreamsError> {
+        if self.streams.getter(stream_id).total.get().is_zero() {
+            return Err(StreamsError::StreamNotFound(StreamNotFound { streamId: stream_id }));
+        }
+        Ok(())
+    }
+
+    /// Fails unless the caller is the sender of a stream that can still be canceled, and returns
+    /// the sender.
+    fn require_sender(&self, stream_id: U256) -> Result<Address, StreamsError> {
+        self.require_stream(stream_id)?;
+        let stream = self.streams.getter(stream_id);
+        let caller = self.vm().msg_sender();
+        if stream.sender.get() != caller {
+            return Err(StreamsError::NotStreamSender(NotStreamSender { streamId: stream_id, caller }));
+        }
+        if !stream.cancelable.get() {
+            return Err(StreamsError::StreamNotCancelable(StreamNotCancelable { streamId: stream_id }));
+        }
+        Ok(caller)
+    }
+
+    /// Records a stream from the caller and mints its token, without pulling the deposit.
+    fn add_stream(&mut self, params: StreamParams, token: Address, cancelable: bool) -> Result<U256, StreamsError> {
+        let (recipient, total, start, cliff, end) = params;
+        if recipient.is_zero() {
+            return Err(StreamsError::InvalidReceiver(ERC721InvalidReceiver { receiver: recipient }));
+        }
+        if total.is_zero() {
+            return Err(StreamsError::ZeroDeposit(ZeroDeposit {}));
+        }
+        if start >= end || cliff < start || cliff > end {
+            return Err(StreamsError::InvalidSchedule(InvalidSchedule { start, cliff, end }));
+        }
+        let stream_id = self.next_stream_id();
+        self.last_stream_id.set(stream_id);
+        let sender = self.vm().msg_sender();
+        let mut stream = self.streams.setter(stream_id);
+        stream.sender.set(sender);
+        stream.token.set(token);
+        stream.total.set(total);
+        stream.start.set(U64::from(start));
+        stream.cliff.set(U64::from(cliff));
+        stream.end.set(U64::from(end));
+        stream.cancelable.set(cancelable);
+        self.update(recipient, stream_id);
+        log(
+            self.vm(),
+            StreamCreated { streamId: stream_id, sender, recipient, token, total, start, cliff, end, cancelable },
+        );
+        Ok(stream_id)
+    }
+
+    /// Pulls `amount` of `token` from the caller into escrow.
+    fn pull(&mut self, token: Address, amount: U256) -> Result<(), StreamsError> {
+        let from = self.vm().msg_sender();
+        let to = self.vm().contract_address();
+        self.call_token(token, IERC20::transferFromCall { from, to, value: amount }.abi_encode())
+    }
+
+    /// Pays `amount` of escrowed `token` to `to`.
+    fn send(&mut self, token: Address, to: Address, amount: U256) -> Result<(), StreamsError> {
+        self.call_token(token, IERC20::transferCall { to, value: amount }.abi_encode())
+    }
+
+    /// Calls an ERC-20 transfer method, which may return nothing instead of `true`.
+    fn call_token(&mut self, token: Address, calldata: Vec<u8>) -> Result<(), StreamsError> {
+        match self.vm().call(&calls::context::Call::new(), token, &calldata) {
+            Ok(result) if result.is_empty() || bool::abi_decode(&result, true).unwrap_or(false) => Ok(()),
+            _ => Err(StreamsError::TransferFailed(TransferFailed { token })),
+        }
+    }
+
+    /// Whether `sender` may move and withdraw from `token_id` of `owner`.
+    fn is_authorized(&self, owner: Address, sender: Address, token_id: U256) -> bool {
+        sender == owner || self.is_approved_for_all(owner, sender) || self.token_approvals.get(token_id) == sender
+    }
+
+    /// Moves `token_id` to `to`, burning it for the zero address and minting it from the zero
+    /// address, and clears its approval.
+    fn update(&mut self, to: Address, token_id: U256) {
+        let from = self.owners.get(token_id);
+        if !from.is_zero() {
+            let balance = self.balances.get(from);
+            self.balances.insert(from, balance - U256::from(1));
+            self.token_approvals.delete(token_id);
+        }
+        if !to.is_zero() {
+            let balance = self.balances.get(to);
+            self.balances.insert(to, balance + U256::from(1));
+        }
+        self.owners.insert(token_id, to);
+        log(self.vm(), Transfer { from, to, tokenId: token_id });
+    }
+
+    /// Calls `onERC721Received` if `to` is a contract, requiring it to return the selector.
+    fn check_received(&mut self, from: Address, to: Address, token_id: U256, data: Bytes) -> Result<(), StreamsError> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+        let operator = self.vm().msg_sender();
+        let call = onERC721ReceivedCall { operator, from, tokenId: token_id, data: data.0.into() };
+        let rejected = || StreamsError::InvalidReceiver(ERC721InvalidReceiver { receiver: to });
+        let result = self.vm().call(&calls::context::Call::new(), to, &call.abi_encode()).map_err(|_| rejected())?;
+        match FixedBytes::<4>::abi_decode(&result, true) {
+            Ok(selector) if selector == onERC721ReceivedCall::SELECTOR => Ok(()),
+            _ => Err(rejected()),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const CAROL: Address = address!("000000000000000000000000000000000000ca20");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+
+fn expect_pull(vm: &TestVM, from: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to: vm.contract_address(), value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+fn expect_payment(vm: &TestVM, to: Address, amount: u64) {
+    vm.mock_call(USDC, IERC20::transferCall { to, value: n(amount) }.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// A cancelable stream of 1,000 from `ALICE` to `BOB` over 1,000 to 2,000, with a cliff at 1,250.
+fn setup() -> (TestVM, Streams, U256) {
+    let (vm, mut contract) = deploy::<Streams>(ALICE);
+    vm.set_block_timestamp(500);
+    expect_pull(&vm, ALICE, 1_000);
+    let stream_id = ok(contract.create_stream(BOB, USDC, n(1_000), 1_000, 1_250, 2_000, true));
+    (vm, contract, stream_id)
+}
+
+#[test]
+fn interface_ids_match_the_standards() {
+    assert_eq!(IERC721.id(), 0x80ac58cd);
+    assert!(Streams::from(&TestVM::new()).supports_interface(IERC721.id().to_be_bytes().into()));
+}
+
+#[test]
+fn streams_unlock_linearly_after_the_cliff() {
+    let (vm, mut contract, stream_id) = setup();
+    assert_eq!(stream_id, n(1));
+    assert_eq!(ok(contract.owner_of(stream_id)), BOB);
+    assert_eq!(emitted::<StreamCreated>(&vm)[0].cliff, 1_250);
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_PENDING);
+
+    vm.set_block_timestamp(1_249);
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_STREAMING);
+    assert_eq!(ok(contract.streamed_amount_of(stream_id)), U256::ZERO);
+    vm.set_block_timestamp(1_250);
+    assert_eq!(ok(contract.streamed_amount_of(stream_id)), n(250));
+
+    vm.set_block_timestamp(1_600);
+    expect_payment(&vm, BOB, 600);
+    vm.set_sender(BOB);
+    let result = contract.withdraw(stream_id, BOB, n(601));
+    assert!(matches!(result, Err(StreamsError::Overdraw(e)) if e.withdrawable == n(600)));
+    assert_eq!(ok(contract.withdraw_max(stream_id, BOB)), n(600));
+    assert_eq!(ok(contract.withdrawable_amount_of(stream_id)), U256::ZERO);
+
+    vm.set_block_timestamp(2_500);
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_SETTLED);
+    assert_eq!(ok(contract.refundable_amount_of(stream_id)), U256::ZERO);
+    assert_eq!(ok(contract.withdrawable_amount_of(stream_id)), n(400));
+}
+
+#[test]
+fn the_stream_token_carries_the_right_to_withdraw() {
+    let (vm, mut contract, stream_id) = setup();
+    vm.set_block_timestamp(1_500);
+    vm.set_sender(BOB);
+    ok(contract.transfer_from(BOB, CAROL, stream_id));
+    assert_eq!((ok(contract.balance_of(BOB)), ok(contract.balance_of(CAROL))), (U256::ZERO, n(1)));
+
+    let result = contract.withdraw(stream_id, BOB, n(100));
+    assert!(matches!(result, Err(StreamsError::InsufficientApproval(e)) if e.operator == BOB));
+    // Anyone can push unlocked funds to the holder
+    expect_payment(&vm, CAROL, 100);
+    ok(contract.withdraw(stream_id, CAROL, n(100)));
+    assert_eq!(emitted::<Withdrawn>(&vm)[0].to, CAROL);
+
+    // An approved account withdraws anywhere
+    vm.set_sender(CAROL);
+    ok(contract.approve(ALICE, stream_id));
+    expect_payment(&vm, ALICE, 400);
+    vm.set_sender(ALICE);
+    ok(contract.withdraw(stream_id, ALICE, n(400)));
+    assert_eq!(ok(contract.get_stream(stream_id)).3, n(500));
+}
+
+#[test]
+fn senders_cancel_for_a_refund_of_what_has_not_unlocked() {
+    let (vm, mut contract, stream_id) = setup();
+    vm.set_block_timestamp(1_300);
+    vm.set_sender(BOB);
+    let result = contract.cancel(stream_id);
+    assert!(matches!(result, Err(StreamsError::NotStreamSender(e)) if e.caller == BOB));
+
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.refundable_amount_of(stream_id)), n(700));
+    expect_payment(&vm, ALICE, 700);
+    ok(contract.cancel(stream_id));
+    let canceled = &emitted::<StreamCanceled>(&vm)[0];
+    assert_eq!((canceled.recipient, canceled.senderAmount, canceled.recipientAmount), (BOB, n(700), n(300)));
+    assert!(matches!(contract.cancel(stream_id), Err(StreamsError::StreamNotCancelable(_))));
+
+    // The unlocked part stays withdrawable, and stops growing
+    vm.set_block_timestamp(1_900);
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_CANCELED);
+    assert_eq!(ok(contract.withdrawable_amount_of(stream_id)), n(300));
+    vm.set_sender(BOB);
+    assert!(matches!(contract.burn(stream_id), Err(StreamsError::StreamNotDepleted(_))));
+    expect_payment(&vm, BOB, 300);
+    ok(contract.withdraw_max(stream_id, BOB));
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_DEPLETED);
+    ok(contract.burn(stream_id));
+    assert!(matches!(contract.owner_of(stream_id), Err(StreamsError::NonexistentToken(_))));
+    assert_eq!(ok(contract.get_stream(stream_id)).4, n(700));
+}
+
+#[test]
+fn renounced_and_fully_unlocked_streams_cannot_be_canceled() {
+    let (vm, mut contract, stream_id) = setup();
+    ok(contract.renounce(stream_id));
+    assert_eq!(emitted::<StreamRenounced>(&vm).len(), 1);
+    assert!(matches!(contract.cancel(stream_id), Err(StreamsError::StreamNotCancelable(_))));
+    assert!(!ok(contract.get_stream(stream_id)).8);
+
+    expect_pull(&vm, ALICE, 500);
+    let stream_id = ok(contract.create_stream(BOB, USDC, n(500), 1_000, 1_000, 1_100, true));
+    vm.set_block_timestamp(1_100);
+    assert!(matches!(contract.cancel(stream_id), Err(StreamsError::StreamSettled(_))));
+}
+
+#[test]
+fn batches_create_a_stream_token_per_recipient() {
+    let (vm, mut contract, _) = setup();
+    expect_pull(&vm, ALICE, 300);
+    let streams = vec![(BOB, n(100), 1_000, 1_000, 2_000), (CAROL, n(200), 1_000, 1_500, 3_000)];
+    assert_eq!(ok(contract.create_streams(USDC, false, streams)), vec![n(2), n(3)]);
+    assert_eq!((ok(contract.balance_of(BOB)), ok(contract.owner_of(n(3)))), (n(2), CAROL));
+    assert_eq!(contract.next_stream_id(), n(4));
+
+    let result = contract.create_stream(BOB, USDC, n(100), 1_000, 900, 2_000, false);
+    assert!(matches!(result, Err(StreamsError::InvalidSchedule(e)) if e.cliff == 900));
+    let result = contract.create_stream(BOB, USDC, U256::ZERO, 1_000, 1_000, 2_000, false);
+    assert!(matches!(result, Err(StreamsError::ZeroDeposit(_))));
+    assert!(matches!(contract.streamed_amount_of(n(9)), Err(StreamsError::StreamNotFound(_))));
+}
+
+#[test]
+fn block_clocks_unlock_streams_by_block() {
+    use cradle_arb::{clock::CLOCK_L2_BLOCK, ArbSys, ARB_SYS};
+
+    let vm = TestVM::new();
+    vm.set_block_timestamp(1_000_000);
+    let at_block = |block: u64| {
+        vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(n(block).abi_encode()))
+    };
+    at_block(50);
+    let mut contract = Streams::from(&vm);
+    ok(contract.initialize(CLOCK_L2_BLOCK));
+    assert!(matches!(contract.initialize(CLOCK_L2_BLOCK), Err(StreamsError::AlreadyInitialized(_))));
+    assert!(matches!(Streams::from(&TestVM::new()).initialize(3), Err(StreamsError::InvalidClockMode(_))));
+
+    vm.set_sender(ALICE);
+    expect_pull(&vm, ALICE, 1_000);
+    let stream_id = ok(contract.create_stream(BOB, USDC, n(1_000), 100, 100, 200, true));
+    assert_eq!((contract.clock_mode(), ok(contract.clock())), (CLOCK_L2_BLOCK, n(50)));
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_PENDING);
+    at_block(150);
+    assert_eq!(ok(contract.streamed_amount_of(stream_id)), n(500));
+    at_block(200);
+    assert_eq!(ok(contract.status_of(stream_id)), STATUS_SETTLED);
+
+    let (_, mut contract, _) = setup();
+    assert!(matches!(contract.initialize(CLOCK_L2_BLOCK), Err(StreamsError::AlreadyInitialized(_))));
+}
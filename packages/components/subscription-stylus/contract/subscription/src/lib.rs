@@ -0,0 +1,438 @@
+//! Recurring ERC-20 subscription payments
+//!
+//! Merchants register plans that charge a fixed amount of an ERC-20 every period.
+//! A subscriber approves the contract for the token and subscribes, which pays
+//! the first period straight away. From then on anyone can call `charge` once a
+//! period is due; the caller keeps the keeper fee, taken out of the merchant's
+//! payment, so keepers have a reason to run the charges.
+//!
+//! A due subscription stays active for the plan's grace period, which gives the
+//! subscriber time to top up their balance or allowance. A subscription that is
+//! still unpaid when the grace period runs out lapses. Cancelling stops future
+//! charges, but the subscription stays active until the end of the paid period.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::prelude::*;
+
+/// Upper bound for the keeper fee, 5%.
+const MAX_KEEPER_FEE_BPS: u64 = 500;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Subscriptions {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Sets the keeper fee
+        address owner;
+        /// Share of each charge paid to the caller of `charge`, in basis points
+        uint256 keeper_fee_bps;
+        /// Plans registered so far
+        uint256 total_plans;
+        /// Plans by ID, starting at 1
+        mapping(uint256 => Plan) plans;
+        /// Subscriptions created so far
+        uint256 total_subscriptions;
+        /// Subscriptions by ID, starting at 1
+        mapping(uint256 => Subscription) subscriptions;
+        /// Latest subscription of each subscriber to each plan
+        mapping(address => mapping(uint256 => uint256)) subscription_ids;
+    }
+
+    pub struct Plan {
+        /// Receives the payments
+        address merchant;
+        /// ERC-20 the plan is paid in
+        address token;
+        /// Charged every period
+        uint256 amount;
+        /// Seconds between charges
+        uint256 period;
+        /// Seconds a due subscription stays active before it lapses
+        uint256 grace_period;
+        /// Whether new subscriptions and charges are accepted
+        bool active;
+    }
+
+    pub struct Subscription {
+        address subscriber;
+        uint256 plan_id;
+        /// Time the next period is due; the subscription is paid until then
+        uint256 paid_until;
+        /// Set by `cancel` or when the subscription lapses
+        bool cancelled;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event PlanCreated(uint256 indexed plan_id, address indexed merchant, address token, uint256 amount, uint256 period, uint256 grace_period);
+    event PlanUpdated(uint256 indexed plan_id, bool active);
+    event Subscribed(uint256 indexed subscription_id, uint256 indexed plan_id, address indexed subscriber, uint256 paid_until);
+    event Charged(uint256 indexed subscription_id, address indexed keeper, uint256 amount, uint256 keeper_fee, uint256 paid_until);
+    event Lapsed(uint256 indexed subscription_id);
+    event Cancelled(uint256 indexed subscription_id, address indexed by);
+    event KeeperFeeUpdated(uint256 keeper_fee_bps);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Plan amount or period is zero
+    error InvalidPlan();
+    // Plan doesn't exist or no longer accepts subscriptions
+    error PlanInactive(uint256 plan_id);
+    // Subscriber already has an active subscription to the plan
+    error AlreadySubscribed(uint256 subscription_id);
+    // Subscription doesn't exist
+    error UnknownSubscription(uint256 subscription_id);
+    // Subscription was cancelled or has lapsed
+    error SubscriptionEnded(uint256 subscription_id);
+    // Next period isn't due yet
+    error NotDue(uint256 subscription_id, uint256 paid_until);
+    // Keeper fee is above the maximum
+    error KeeperFeeTooHigh(uint256 keeper_fee_bps);
+    // An ERC-20 transfer failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum SubscriptionError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidPlan(InvalidPlan),
+    PlanInactive(PlanInactive),
+    AlreadySubscribed(AlreadySubscribed),
+    UnknownSubscription(UnknownSubscription),
+    SubscriptionEnded(SubscriptionEnded),
+    NotDue(NotDue),
+    KeeperFeeTooHigh(KeeperFeeTooHigh),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+// Helpers used by the public methods below.
+impl Subscriptions {
+    fn only_owner(&self) -> Result<(), SubscriptionError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(SubscriptionError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    fn pull(&mut self, token: Address, from: Address, to: Address, amount: U256) -> Result<(), SubscriptionError> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let call = IERC20::transferFromCall { from, to, value: amount };
+        match self.vm().call(&calls::context::Call::new(), token, &call.abi_encode()) {
+            Ok(output) if bool::abi_decode(&output, true).unwrap_or(false) => Ok(()),
+            _ => Err(SubscriptionError::TransferFailed(TransferFailed { token, to, amount })),
+        }
+    }
+
+    /// Whether the subscription is paid up or still within the plan's grace period.
+    fn within_grace(&self, subscription_id: U256) -> bool {
+        let subscription = self.subscriptions.getter(subscription_id);
+        let grace_period = self.plans.getter(subscription.plan_id.get()).grace_period.get();
+        self.now() < subscription.paid_until.get() + grace_period
+    }
+}
+
+#[public]
+impl Subscriptions {
+    /// Sets the owner and the keeper fee. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, keeper_fee_bps: U256) -> Result<(), SubscriptionError> {
+        if self.initialized.get() {
+            return Err(SubscriptionError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if keeper_fee_bps > U256::from(MAX_KEEPER_FEE_BPS) {
+            return Err(SubscriptionError::KeeperFeeTooHigh(KeeperFeeTooHigh { keeper_fee_bps }));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.keeper_fee_bps.set(keeper_fee_bps);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Registers a plan for the caller. Returns the plan ID.
+    pub fn create_plan(
+        &mut self,
+        token: Address,
+        amount: U256,
+        period: U256,
+        grace_period: U256,
+    ) -> Result<U256, SubscriptionError> {
+        if token.is_zero() || amount.is_zero() || period.is_zero() {
+            return Err(SubscriptionError::InvalidPlan(InvalidPlan {}));
+        }
+        let merchant = self.vm().msg_sender();
+        let plan_id = self.total_plans.get() + U256::from(1);
+        self.total_plans.set(plan_id);
+
+        let mut plan = self.plans.setter(plan_id);
+        plan.merchant.set(merchant);
+        plan.token.set(token);
+        plan.amount.set(amount);
+        plan.period.set(period);
+        plan.grace_period.set(grace_period);
+        plan.active.set(true);
+
+        log(self.vm(), PlanCreated {
+            plan_id,
+            merchant,
+            token,
+            amount,
+            period,
+            grace_period,
+        });
+        Ok(plan_id)
+    }
+
+    /// Pauses or resumes a plan (merchant only). Inactive plans take no new subscribers and can't be charged.
+    pub fn set_plan_active(&mut self, plan_id: U256, active: bool) -> Result<(), SubscriptionError> {
+        let caller = self.vm().msg_sender();
+        let merchant = self.plans.getter(plan_id).merchant.get();
+        if merchant.is_zero() || caller != merchant {
+            return Err(SubscriptionError::Unauthorized(Unauthorized { caller }));
+        }
+        self.plans.setter(plan_id).active.set(active);
+
+        log(self.vm(), PlanUpdated { plan_id, active });
+        Ok(())
+    }
+
+    /// Subscribes the caller to a plan and pays the first period. Requires an allowance for the plan's token.
+    /// Returns the subscription ID.
+    pub fn subscribe(&mut self, plan_id: U256) -> Result<U256, SubscriptionError> {
+        let plan = self.plans.getter(plan_id);
+        if !plan.active.get() {
+            return Err(SubscriptionError::PlanInactive(PlanInactive { plan_id }));
+        }
+        let merchant = plan.merchant.get();
+        let token = plan.token.get();
+        let amount = plan.amount.get();
+        let period = plan.period.get();
+
+        let subscriber = self.vm().msg_sender();
+        let existing = self.subscription_ids.getter(subscriber).get(plan_id);
+        if !existing.is_zero() && !self.subscriptions.getter(existing).cancelled.get() && self.within_grace(existing) {
+            return Err(SubscriptionError::AlreadySubscribed(AlreadySubscribed {
+                subscription_id: existing,
+            }));
+        }
+
+        let subscription_id = self.total_subscriptions.get() + U256::from(1);
+        self.total_subscriptions.set(subscription_id);
+        let paid_until = self.now() + period;
+        let mut subscription = self.subscriptions.setter(subscription_id);
+        subscription.subscriber.set(subscriber);
+        subscription.plan_id.set(plan_id);
+        subscription.paid_until.set(paid_until);
+        self.subscription_ids.setter(subscriber).insert(plan_id, subscription_id);
+
+        self.pull(token, subscriber, merchant, amount)?;
+
+        log(self.vm(), Subscribed {
+            subscription_id,
+            plan_id,
+            subscriber,
+            paid_until,
+        });
+        Ok(subscription_id)
+    }
+
+    /// Charges the next period of a due subscription. Callable by anyone; the caller receives the keeper fee.
+    /// A subscription past its grace period lapses instead, and `false` is returned.
+    pub fn charge(&mut self, subscription_id: U256) -> Result<bool, SubscriptionError> {
+        let subscription = self.subscriptions.getter(subscription_id);
+        let subscriber = subscription.subscriber.get();
+        if subscriber.is_zero() {
+            return Err(SubscriptionError::UnknownSubscription(UnknownSubscription { subscription_id }));
+        }
+        if subscription.cancelled.get() {
+            return Err(SubscriptionError::SubscriptionEnded(SubscriptionEnded { subscription_id }));
+        }
+        let paid_until = subscription.paid_until.get();
+        if self.now() < paid_until {
+            return Err(SubscriptionError::NotDue(NotDue {
+                subscription_id,
+                paid_until,
+            }));
+        }
+        if !self.within_grace(subscription_id) {
+            self.subscriptions.setter(subscription_id).cancelled.set(true);
+            log(self.vm(), Lapsed { subscription_id });
+            return Ok(false);
+        }
+
+        let plan_id = subscription.plan_id.get();
+        let plan = self.plans.getter(plan_id);
+        if !plan.active.get() {
+            return Err(SubscriptionError::PlanInactive(PlanInactive { plan_id }));
+        }
+        let merchant = plan.merchant.get();
+        let token = plan.token.get();
+        let amount = plan.amount.get();
+        // Periods follow the original schedule, so a late charge doesn't shift later due dates
+        let paid_until = paid_until + plan.period.get();
+        self.subscriptions.setter(subscription_id).paid_until.set(paid_until);
+
+        let keeper = self.vm().msg_sender();
+        let keeper_fee = amount * self.keeper_fee_bps.get() / U256::from(BPS_DENOMINATOR);
+        self.pull(token, subscriber, merchant, amount - keeper_fee)?;
+        self.pull(token, subscriber, keeper, keeper_fee)?;
+
+        log(self.vm(), Charged {
+            subscription_id,
+            keeper,
+            amount,
+            keeper_fee,
+            paid_until,
+        });
+        Ok(true)
+    }
+
+    /// Stops future charges (subscriber or merchant). The subscription stays active until the paid period ends.
+    pub fn cancel(&mut self, subscription_id: U256) -> Result<(), SubscriptionError> {
+        let subscription = self.subscriptions.getter(subscription_id);
+        let subscriber = subscription.subscriber.get();
+        if subscriber.is_zero() {
+            return Err(SubscriptionError::UnknownSubscription(UnknownSubscription { subscription_id }));
+        }
+        if subscription.cancelled.get() {
+            return Err(SubscriptionError::SubscriptionEnded(SubscriptionEnded { subscription_id }));
+        }
+        let caller = self.vm().msg_sender();
+        let merchant = self.plans.getter(subscription.plan_id.get()).merchant.get();
+        if caller != subscriber && caller != merchant {
+            return Err(SubscriptionError::Unauthorized(Unauthorized { caller }));
+        }
+        self.subscriptions.setter(subscription_id).cancelled.set(true);
+
+        log(self.vm(), Cancelled {
+            subscription_id,
+            by: caller,
+        });
+        Ok(())
+    }
+
+    /// Updates the keeper fee (owner only).
+    pub fn set_keeper_fee(&mut self, keeper_fee_bps: U256) -> Result<(), SubscriptionError> {
+        self.only_owner()?;
+        if keeper_fee_bps > U256::from(MAX_KEEPER_FEE_BPS) {
+            return Err(SubscriptionError::KeeperFeeTooHigh(KeeperFeeTooHigh { keeper_fee_bps }));
+        }
+        self.keeper_fee_bps.set(keeper_fee_bps);
+
+        log(self.vm(), KeeperFeeUpdated { keeper_fee_bps });
+        Ok(())
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), SubscriptionError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Whether the subscription grants access now: paid up, or due but within the grace period
+    /// and not cancelled. Cancelled subscriptions stay active until their paid period ends.
+    pub fn is_active(&self, subscription_id: U256) -> bool {
+        let subscription = self.subscriptions.getter(subscription_id);
+        if subscription.subscriber.get().is_zero() {
+            return false;
+        }
+        if subscription.cancelled.get() {
+            return self.now() < subscription.paid_until.get();
+        }
+        self.within_grace(subscription_id)
+    }
+
+    /// Whether a subscription can be charged now.
+    pub fn is_due(&self, subscription_id: U256) -> bool {
+        let subscription = self.subscriptions.getter(subscription_id);
+        !subscription.subscriber.get().is_zero()
+            && !subscription.cancelled.get()
+            && self.now() >= subscription.paid_until.get()
+    }
+
+    /// Returns `(merchant, token, amount, period, grace_period, active)`.
+    pub fn plan(&self, plan_id: U256) -> (Address, Address, U256, U256, U256, bool) {
+        let plan = self.plans.getter(plan_id);
+        (
+            plan.merchant.get(),
+            plan.token.get(),
+            plan.amount.get(),
+            plan.period.get(),
+            plan.grace_period.get(),
+            plan.active.get(),
+        )
+    }
+
+    /// Returns `(subscriber, plan_id, paid_until, cancelled)`.
+    pub fn subscription(&self, subscription_id: U256) -> (Address, U256, U256, bool) {
+        let subscription = self.subscriptions.getter(subscription_id);
+        (
+            subscription.subscriber.get(),
+            subscription.plan_id.get(),
+            subscription.paid_until.get(),
+            subscription.cancelled.get(),
+        )
+    }
+
+    /// Latest subscription of `subscriber` to a plan, or zero.
+    pub fn subscription_of(&self, subscriber: Address, plan_id: U256) -> U256 {
+        self.subscription_ids.getter(subscriber).get(plan_id)
+    }
+
+    /// Share of each charge paid to keepers, in basis points.
+    pub fn keeper_fee_bps(&self) -> U256 {
+        self.keeper_fee_bps.get()
+    }
+
+    /// Returns `(total_plans, total_subscriptions)`.
+    pub fn totals(&self) -> (U256, U256) {
+        (self.total_plans.get(), self.total_subscriptions.get())
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
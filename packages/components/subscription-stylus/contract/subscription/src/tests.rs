@@ -0,0 +1,131 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const MERCHANT: Address = address!("000000000000000000000000000000000000e4c7");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const KEEPER: Address = address!("000000000000000000000000000000000000bee9");
+const TOKEN: Address = address!("00000000000000000000000000000000000020c0");
+
+/// Lets the contract move exactly `amount` of `TOKEN` from `from` to `to`.
+fn expect_pull(vm: &TestVM, from: Address, to: Address, amount: u64) {
+    let call = IERC20::transferFromCall { from, to, value: n(amount) };
+    vm.mock_call(TOKEN, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// Contract at time 1,000 with a 2% keeper fee, and a merchant plan of 1,000 per 100 seconds
+/// with a 50 second grace period.
+fn setup() -> (TestVM, Subscriptions) {
+    let (vm, mut contract) = deploy::<Subscriptions>(OWNER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(OWNER, n(200)));
+    vm.set_sender(MERCHANT);
+    assert_eq!(ok(contract.create_plan(TOKEN, n(1_000), n(100), n(50))), n(1));
+    (vm, contract)
+}
+
+/// `setup`, with Alice subscribed to the plan.
+fn setup_subscribed() -> (TestVM, Subscriptions) {
+    let (vm, mut contract) = setup();
+    expect_pull(&vm, ALICE, MERCHANT, 1_000);
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.subscribe(n(1))), n(1));
+    (vm, contract)
+}
+
+#[test]
+fn keepers_charge_due_subscriptions_for_a_fee() {
+    let (vm, mut contract) = setup_subscribed();
+    assert_eq!(contract.subscription(n(1)), (ALICE, n(1), n(1_100), false));
+    assert_eq!(contract.subscription_of(ALICE, n(1)), n(1));
+    let subscribed = &emitted::<Subscribed>(&vm)[0];
+    assert_eq!((subscribed.subscriber, subscribed.paid_until), (ALICE, n(1_100)));
+
+    vm.set_sender(KEEPER);
+    let result = contract.charge(n(1));
+    assert!(matches!(result, Err(SubscriptionError::NotDue(e)) if e.paid_until == n(1_100)));
+
+    // A late charge keeps the original schedule, and the keeper takes 2%
+    vm.set_block_timestamp(1_130);
+    assert!(contract.is_due(n(1)) && contract.is_active(n(1)));
+    expect_pull(&vm, ALICE, MERCHANT, 980);
+    expect_pull(&vm, ALICE, KEEPER, 20);
+    assert!(ok(contract.charge(n(1))));
+    assert_eq!(contract.subscription(n(1)).2, n(1_200));
+    let charged = &emitted::<Charged>(&vm)[0];
+    assert_eq!(
+        (charged.keeper, charged.amount, charged.keeper_fee, charged.paid_until),
+        (KEEPER, n(1_000), n(20), n(1_200))
+    );
+}
+
+#[test]
+fn subscriptions_lapse_after_the_grace_period() {
+    let (vm, mut contract) = setup_subscribed();
+    let result = contract.subscribe(n(1));
+    assert!(matches!(result, Err(SubscriptionError::AlreadySubscribed(e)) if e.subscription_id == n(1)));
+
+    vm.set_block_timestamp(1_150);
+    assert!(!contract.is_active(n(1)));
+    vm.set_sender(KEEPER);
+    assert!(!ok(contract.charge(n(1))));
+    assert_eq!(emitted::<Lapsed>(&vm)[0].subscription_id, n(1));
+    assert!(matches!(contract.charge(n(1)), Err(SubscriptionError::SubscriptionEnded(_))));
+
+    // A lapsed subscriber can sign up again
+    expect_pull(&vm, ALICE, MERCHANT, 1_000);
+    vm.set_sender(ALICE);
+    assert_eq!(ok(contract.subscribe(n(1))), n(2));
+    assert_eq!(contract.subscription(n(2)), (ALICE, n(1), n(1_250), false));
+}
+
+#[test]
+fn cancelled_subscriptions_run_out_their_paid_period() {
+    let (vm, mut contract) = setup_subscribed();
+    vm.set_sender(KEEPER);
+    let result = contract.cancel(n(1));
+    assert!(matches!(result, Err(SubscriptionError::Unauthorized(e)) if e.caller == KEEPER));
+    vm.set_sender(MERCHANT);
+    ok(contract.cancel(n(1)));
+    assert_eq!(emitted::<Cancelled>(&vm)[0].by, MERCHANT);
+    assert!(contract.is_active(n(1)) && !contract.is_due(n(1)));
+
+    vm.set_block_timestamp(1_100);
+    assert!(!contract.is_active(n(1)));
+    assert!(matches!(contract.charge(n(1)), Err(SubscriptionError::SubscriptionEnded(_))));
+    assert!(matches!(contract.cancel(n(2)), Err(SubscriptionError::UnknownSubscription(_))));
+}
+
+#[test]
+fn plans_and_fees_are_checked() {
+    let (vm, mut contract) = setup();
+    let result = contract.create_plan(TOKEN, n(1_000), U256::ZERO, n(50));
+    assert!(matches!(result, Err(SubscriptionError::InvalidPlan(_))));
+    let result = contract.set_keeper_fee(n(100));
+    assert!(matches!(result, Err(SubscriptionError::Unauthorized(e)) if e.caller == MERCHANT));
+    vm.set_sender(OWNER);
+    let result = contract.set_keeper_fee(n(501));
+    assert!(matches!(result, Err(SubscriptionError::KeeperFeeTooHigh(e)) if e.keeper_fee_bps == n(501)));
+    assert!(matches!(contract.initialize(OWNER, n(0)), Err(SubscriptionError::AlreadyInitialized(_))));
+
+    let result = contract.set_plan_active(n(1), false);
+    assert!(matches!(result, Err(SubscriptionError::Unauthorized(e)) if e.caller == OWNER));
+    vm.set_sender(MERCHANT);
+    ok(contract.set_plan_active(n(1), false));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.subscribe(n(1)), Err(SubscriptionError::PlanInactive(e)) if e.plan_id == n(1)));
+}
+
+#[test]
+fn a_token_that_doesnt_pay_fails_the_charge() {
+    let (vm, mut contract) = setup_subscribed();
+    vm.set_block_timestamp(1_100);
+    vm.set_sender(KEEPER);
+    let result = contract.charge(n(1));
+    assert!(matches!(
+        result,
+        Err(SubscriptionError::TransferFailed(e)) if e.token == TOKEN && e.to == MERCHANT && e.amount == n(980)
+    ));
+}
@@ -0,0 +1,541 @@
+//! Event ticketing on a Cradle ERC-1155 collection
+//!
+//! An organizer creates an event with its seat classes, each a token ID of a collection owned by
+//! this contract, and sells them at a price per class until the event starts. Proceeds are
+//! credited here and withdrawn, so a sale never sends ETH.
+//!
+//! At the door the holder shows a QR code carrying an EIP-712 `CheckIn` they signed, and one of
+//! the event's gates submits it, which marks one of the holder's tickets of that class used. The
+//! collection should be built with the `soulbound` feature, so tickets only change hands through
+//! the resale listings here: used tickets can't be listed or resold, and every resale pays the
+//! organizer its royalty. Resales can't go below the class's price, so a payment made on the
+//! side can't take the royalty below the one on the face value.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{b256, Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Name in the EIP-712 domain check-ins are signed under.
+pub const DOMAIN_NAME: &str = "Cradle Ticketing";
+/// Version in the EIP-712 domain.
+pub const DOMAIN_VERSION: &str = "1";
+/// `keccak256("CheckIn(address holder,uint256 ticketId,uint256 deadline,bytes32 nonce)")`
+pub const CHECK_IN_TYPEHASH: B256 = b256!("5bee0c10bf4e795029088602b3dd320fd0feb5306ff4b82f88651bc7e6d2d467");
+/// Bits of a ticket ID below the event ID, which hold the seat class.
+pub const CLASS_BITS: usize = 64;
+/// Most seat classes in one event.
+pub const MAX_CLASSES: usize = 32;
+/// Highest resale royalty, 25%.
+pub const MAX_ROYALTY_BPS: u64 = 2_500;
+/// Denominator for basis point calculations.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Ticketing {
+        /// Set once by `initialize`
+        bool initialized;
+        /// ERC-1155 collection tickets are minted on; owned by this contract
+        address tickets;
+        /// Events created so far
+        uint256 event_count;
+        /// Events by ID
+        mapping(uint256 => EventInfo) events;
+        /// Seat classes by ticket ID
+        mapping(uint256 => SeatClass) classes;
+        /// Accounts allowed to check tickets in, by event
+        mapping(uint256 => mapping(address => bool)) gates;
+        /// Tickets each holder has checked in, by ticket ID
+        mapping(uint256 => mapping(address => uint256)) used;
+        /// Check-in nonces each holder has used
+        mapping(address => mapping(bytes32 => bool)) redeemed;
+        /// Resale listings by ID
+        mapping(uint256 => Listing) listings;
+        /// Listings created so far
+        uint256 listing_count;
+        /// ETH each organizer and seller can withdraw
+        mapping(address => uint256) proceeds;
+    }
+
+    pub struct EventInfo {
+        /// Sets the gates and earns the sales and royalties; zero if the event doesn't exist
+        address organizer;
+        /// Sales and resales close at this time
+        uint256 start_time;
+        /// Share of each resale paid to the organizer, in basis points
+        uint256 royalty_bps;
+        /// Seat classes, numbered from 0
+        uint256 class_count;
+    }
+
+    pub struct SeatClass {
+        /// Price of one ticket, in wei
+        uint256 price;
+        /// Tickets for sale; zero if the class doesn't exist
+        uint256 supply;
+        /// Tickets sold so far
+        uint256 sold;
+    }
+
+    pub struct Listing {
+        /// Holder reselling the tickets; zero if the listing doesn't exist
+        address seller;
+        uint256 ticket_id;
+        /// Tickets left to buy
+        uint256 amount;
+        /// Price of one ticket, in wei
+        uint256 price;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event EventCreated(uint256 indexed eventId, address indexed organizer, uint256 startTime, uint256 royaltyBps, uint256 classes);
+    event GateSet(uint256 indexed eventId, address indexed gate, bool allowed);
+    event TicketsSold(uint256 indexed ticketId, address indexed buyer, uint256 amount, uint256 paid);
+    event CheckedIn(uint256 indexed ticketId, address indexed holder, address indexed gate, bytes32 nonce);
+    event Listed(uint256 indexed listingId, address indexed seller, uint256 ticketId, uint256 amount, uint256 price);
+    event ListingCanceled(uint256 indexed listingId);
+    event Resold(uint256 indexed listingId, address indexed buyer, uint256 amount, uint256 paid, uint256 royalty);
+    event Withdrawn(address indexed account, uint256 amount);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Event needs a future start, a royalty up to the maximum and 1 to `MAX_CLASSES` classes with tickets
+    error InvalidEvent();
+    error UnknownEvent(uint256 eventId);
+    error UnknownTicket(uint256 ticketId);
+    error UnknownListing(uint256 listingId);
+    // Caller is not the organizer, a gate or the seller
+    error Unauthorized(address caller);
+    // Sales and resales closed when the event started
+    error SalesClosed(uint256 startTime);
+    // Amount is zero, or more than the listing has left
+    error InvalidAmount();
+    error SoldOut(uint256 ticketId, uint256 available);
+    // Attached ETH does not match the price
+    error IncorrectPayment(uint256 expected, uint256 received);
+    // The holder has fewer tickets that weren't checked in than needed
+    error InsufficientUnused(address holder, uint256 ticketId, uint256 available);
+    error ResalePriceTooLow(uint256 price, uint256 minimum);
+    // The QR code's deadline has passed
+    error ExpiredCheckIn(uint256 deadline);
+    // The QR code was already redeemed
+    error CheckInAlreadyUsed(address holder, bytes32 nonce);
+    error InvalidCheckInSignature(address holder);
+    // Minting, burning or reading tickets on the collection failed
+    error TicketCallFailed();
+    error NothingToWithdraw();
+    error TransferFailed(address to, uint256 amount);
+
+    function mint(address to, uint256 id, uint256 amount, bytes data);
+    function burnFrom(address from, uint256 id, uint256 amount);
+    function balanceOf(address account, uint256 id) returns (uint256);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum TicketingError {
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidEvent(InvalidEvent),
+    UnknownEvent(UnknownEvent),
+    UnknownTicket(UnknownTicket),
+    UnknownListing(UnknownListing),
+    Unauthorized(Unauthorized),
+    SalesClosed(SalesClosed),
+    InvalidAmount(InvalidAmount),
+    SoldOut(SoldOut),
+    IncorrectPayment(IncorrectPayment),
+    InsufficientUnused(InsufficientUnused),
+    ResalePriceTooLow(ResalePriceTooLow),
+    ExpiredCheckIn(ExpiredCheckIn),
+    CheckInAlreadyUsed(CheckInAlreadyUsed),
+    InvalidCheckInSignature(InvalidCheckInSignature),
+    TicketCallFailed(TicketCallFailed),
+    NothingToWithdraw(NothingToWithdraw),
+    TransferFailed(TransferFailed),
+}
+
+/// ERC-1155 token ID of an event's seat class.
+pub fn ticket_id(event_id: U256, class: U256) -> U256 {
+    (event_id << CLASS_BITS) | class
+}
+
+/// Event a ticket ID belongs to.
+pub fn event_of(ticket_id: U256) -> U256 {
+    ticket_id >> CLASS_BITS
+}
+
+#[public]
+impl Ticketing {
+    /// Sets the ticket collection. Can only be called once. This contract must own the collection
+    /// to mint tickets.
+    pub fn initialize(&mut self, tickets: Address) -> Result<(), TicketingError> {
+        if self.initialized.get() {
+            return Err(TicketingError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.tickets.set(tickets);
+        Ok(())
+    }
+
+    /// Creates an event organized by the caller, with a seat class for each price and supply, and
+    /// returns its ID. Ticket sales and resales close at `start_time`.
+    pub fn create_event(
+        &mut self,
+        start_time: U256,
+        royalty_bps: U256,
+        prices: Vec<U256>,
+        supplies: Vec<U256>,
+    ) -> Result<U256, TicketingError> {
+        let now = U256::from(self.vm().block_timestamp());
+        if start_time <= now
+            || royalty_bps > U256::from(MAX_ROYALTY_BPS)
+            || prices.is_empty()
+            || prices.len() > MAX_CLASSES
+            || prices.len() != supplies.len()
+            || supplies.contains(&U256::ZERO)
+        {
+            return Err(TicketingError::InvalidEvent(InvalidEvent {}));
+        }
+
+        let organizer = self.vm().msg_sender();
+        let event_id = self.event_count.get();
+        self.event_count.set(event_id + U256::from(1));
+        let mut event = self.events.setter(event_id);
+        event.organizer.set(organizer);
+        event.start_time.set(start_time);
+        event.royalty_bps.set(royalty_bps);
+        event.class_count.set(U256::from(prices.len()));
+        for (class, (&price, &supply)) in prices.iter().zip(&supplies).enumerate() {
+            let mut seat_class = self.classes.setter(ticket_id(event_id, U256::from(class)));
+            seat_class.price.set(price);
+            seat_class.supply.set(supply);
+        }
+
+        log(self.vm(), EventCreated {
+            eventId: event_id,
+            organizer,
+            startTime: start_time,
+            royaltyBps: royalty_bps,
+            classes: U256::from(prices.len()),
+        });
+        Ok(event_id)
+    }
+
+    /// Allows or stops `gate` checking tickets of `event_id` in (organizer only).
+    pub fn set_gate(&mut self, event_id: U256, gate: Address, allowed: bool) -> Result<(), TicketingError> {
+        self.require_organizer(event_id)?;
+        self.gates.setter(event_id).insert(gate, allowed);
+        log(self.vm(), GateSet { eventId: event_id, gate, allowed });
+        Ok(())
+    }
+
+    /// Buys `amount` tickets of a seat class at its price, attached in ETH.
+    #[payable]
+    pub fn buy(&mut self, ticket_id: U256, amount: U256) -> Result<(), TicketingError> {
+        let seat_class = self.classes.getter(ticket_id);
+        let (price, supply, sold) = (seat_class.price.get(), seat_class.supply.get(), seat_class.sold.get());
+        if supply.is_zero() {
+            return Err(TicketingError::UnknownTicket(UnknownTicket { ticketId: ticket_id }));
+        }
+        let event_id = event_of(ticket_id);
+        self.require_sales_open(event_id)?;
+        if amount.is_zero() {
+            return Err(TicketingError::InvalidAmount(InvalidAmount {}));
+        }
+        if amount > supply - sold {
+            return Err(TicketingError::SoldOut(SoldOut { ticketId: ticket_id, available: supply - sold }));
+        }
+        let paid = price * amount;
+        self.require_payment(paid)?;
+
+        self.classes.setter(ticket_id).sold.set(sold + amount);
+        let organizer = self.events.getter(event_id).organizer.get();
+        self.credit(organizer, paid);
+        let buyer = self.vm().msg_sender();
+        self.call_tickets(&mintCall { to: buyer, id: ticket_id, amount, data: Vec::new().into() }.abi_encode())?;
+
+        log(self.vm(), TicketsSold { ticketId: ticket_id, buyer, amount, paid });
+        Ok(())
+    }
+
+    /// Checks one of `holder`'s tickets in (gates only), with the `CheckIn` they signed for the
+    /// QR code. The ticket stays with the holder, but can no longer be checked in or resold.
+    /// Contract accounts sign through ERC-1271.
+    pub fn check_in(
+        &mut self,
+        holder: Address,
+        ticket_id: U256,
+        deadline: U256,
+        nonce: B256,
+        signature: Bytes,
+    ) -> Result<(), TicketingError> {
+        let gate = self.vm().msg_sender();
+        if !self.gates.getter(event_of(ticket_id)).get(gate) {
+            return Err(TicketingError::Unauthorized(Unauthorized { caller: gate }));
+        }
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(TicketingError::ExpiredCheckIn(ExpiredCheckIn { deadline }));
+        }
+        if self.redeemed.getter(holder).get(nonce) {
+            return Err(TicketingError::CheckInAlreadyUsed(CheckInAlreadyUsed { holder, nonce }));
+        }
+        let struct_hash =
+            cradle_crypto::hash_struct(self.vm(), CHECK_IN_TYPEHASH, (holder, ticket_id, deadline, nonce));
+        let digest = cradle_crypto::hash_typed_data(self.vm(), self.domain_separator(), struct_hash);
+        if !cradle_crypto::is_valid_signature_now(self.vm(), holder, digest, &signature) {
+            return Err(TicketingError::InvalidCheckInSignature(InvalidCheckInSignature { holder }));
+        }
+        self.require_unused(holder, ticket_id, U256::from(1))?;
+
+        self.redeemed.setter(holder).insert(nonce, true);
+        let used = self.used.getter(ticket_id).get(holder);
+        self.used.setter(ticket_id).insert(holder, used + U256::from(1));
+        log(self.vm(), CheckedIn { ticketId: ticket_id, holder, gate, nonce });
+        Ok(())
+    }
+
+    /// Lists `amount` of the caller's unused tickets for resale at `price` each, at least the
+    /// class's price, and returns the listing's ID. The caller must have approved this contract
+    /// on the collection, which burns the tickets it sells from them.
+    pub fn list(&mut self, ticket_id: U256, amount: U256, price: U256) -> Result<U256, TicketingError> {
+        let seat_class = self.classes.getter(ticket_id);
+        if seat_class.supply.get().is_zero() {
+            return Err(TicketingError::UnknownTicket(UnknownTicket { ticketId: ticket_id }));
+        }
+        let minimum = seat_class.price.get();
+        self.require_sales_open(event_of(ticket_id))?;
+        if amount.is_zero() {
+            return Err(TicketingError::InvalidAmount(InvalidAmount {}));
+        }
+        if price < minimum {
+            return Err(TicketingError::ResalePriceTooLow(ResalePriceTooLow { price, minimum }));
+        }
+        let seller = self.vm().msg_sender();
+        self.require_unused(seller, ticket_id, amount)?;
+
+        let listing_id = self.listing_count.get();
+        self.listing_count.set(listing_id + U256::from(1));
+        let mut listing = self.listings.setter(listing_id);
+        listing.seller.set(seller);
+        listing.ticket_id.set(ticket_id);
+        listing.amount.set(amount);
+        listing.price.set(price);
+
+        log(self.vm(), Listed { listingId: listing_id, seller, ticketId: ticket_id, amount, price });
+        Ok(listing_id)
+    }
+
+    /// Withdraws what's left of a listing (seller only).
+    pub fn cancel_listing(&mut self, listing_id: U256) -> Result<(), TicketingError> {
+        let seller = self.listings.getter(listing_id).seller.get();
+        if seller.is_zero() {
+            return Err(TicketingError::UnknownListing(UnknownListing { listingId: listing_id }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != seller {
+            return Err(TicketingError::Unauthorized(Unauthorized { caller }));
+        }
+        self.listings.setter(listing_id).amount.set(U256::ZERO);
+        log(self.vm(), ListingCanceled { listingId: listing_id });
+        Ok(())
+    }
+
+    /// Buys `amount` tickets from a listing at its price, attached in ETH. The organizer is
+    /// credited the event's royalty and the seller the rest.
+    #[payable]
+    pub fn buy_resale(&mut self, listing_id: U256, amount: U256) -> Result<(), TicketingError> {
+        let listing = self.listings.getter(listing_id);
+        let (seller, ticket_id, listed, price) =
+            (listing.seller.get(), listing.ticket_id.get(), listing.amount.get(), listing.price.get());
+        if seller.is_zero() {
+            return Err(TicketingError::UnknownListing(UnknownListing { listingId: listing_id }));
+        }
+        let event_id = event_of(ticket_id);
+        self.require_sales_open(event_id)?;
+        if amount.is_zero() || amount > listed {
+            return Err(TicketingError::InvalidAmount(InvalidAmount {}));
+        }
+        let paid = price * amount;
+        self.require_payment(paid)?;
+        // The seller may have checked tickets in or sold them elsewhere since listing
+        self.require_unused(seller, ticket_id, amount)?;
+
+        self.listings.setter(listing_id).amount.set(listed - amount);
+        let event = self.events.getter(event_id);
+        let (organizer, royalty_bps) = (event.organizer.get(), event.royalty_bps.get());
+        let royalty = paid * royalty_bps / U256::from(BPS_DENOMINATOR);
+        self.credit(organizer, royalty);
+        self.credit(seller, paid - royalty);
+
+        let buyer = self.vm().msg_sender();
+        self.call_tickets(&burnFromCall { from: seller, id: ticket_id, amount }.abi_encode())?;
+        self.call_tickets(&mintCall { to: buyer, id: ticket_id, amount, data: Vec::new().into() }.abi_encode())?;
+
+        log(self.vm(), Resold { listingId: listing_id, buyer, amount, paid, royalty });
+        Ok(())
+    }
+
+    /// Pays the caller the sales, royalties and resales credited to them, returning the amount.
+    pub fn withdraw(&mut self) -> Result<U256, TicketingError> {
+        let account = self.vm().msg_sender();
+        let amount = self.proceeds.get(account);
+        if amount.is_zero() {
+            return Err(TicketingError::NothingToWithdraw(NothingToWithdraw {}));
+        }
+        self.proceeds.insert(account, U256::ZERO);
+        if self.vm().transfer_eth(account, amount).is_err() {
+            return Err(TicketingError::TransferFailed(TransferFailed { to: account, amount }));
+        }
+        log(self.vm(), Withdrawn { account, amount });
+        Ok(amount)
+    }
+
+    /// An event's organizer, start time, resale royalty in basis points and number of classes.
+    pub fn get_event(&self, event_id: U256) -> (Address, U256, U256, U256) {
+        let event = self.events.getter(event_id);
+        (event.organizer.get(), event.start_time.get(), event.royalty_bps.get(), event.class_count.get())
+    }
+
+    /// A seat class's price, supply and tickets sold.
+    pub fn get_class(&self, ticket_id: U256) -> (U256, U256, U256) {
+        let seat_class = self.classes.getter(ticket_id);
+        (seat_class.price.get(), seat_class.supply.get(), seat_class.sold.get())
+    }
+
+    /// A listing's seller, ticket ID, tickets left and price of one ticket.
+    pub fn get_listing(&self, listing_id: U256) -> (Address, U256, U256, U256) {
+        let listing = self.listings.getter(listing_id);
+        (listing.seller.get(), listing.ticket_id.get(), listing.amount.get(), listing.price.get())
+    }
+
+    /// Whether `account` can check tickets of `event_id` in.
+    pub fn is_gate(&self, event_id: U256, account: Address) -> bool {
+        self.gates.getter(event_id).get(account)
+    }
+
+    /// Tickets of `ticket_id` `holder` has checked in.
+    pub fn used_of(&self, holder: Address, ticket_id: U256) -> U256 {
+        self.used.getter(ticket_id).get(holder)
+    }
+
+    /// Tickets of `ticket_id` `holder` holds and hasn't checked in.
+    pub fn unused_of(&self, holder: Address, ticket_id: U256) -> Result<U256, TicketingError> {
+        self.unused(holder, ticket_id)
+    }
+
+    /// Whether `holder` has redeemed the check-in with `nonce`.
+    pub fn is_redeemed(&self, holder: Address, nonce: B256) -> bool {
+        self.redeemed.getter(holder).get(nonce)
+    }
+
+    /// ETH `account` can withdraw.
+    pub fn proceeds_of(&self, account: Address) -> U256 {
+        self.proceeds.get(account)
+    }
+
+    /// ERC-1155 token ID of an event's seat class.
+    pub fn ticket_id(&self, event_id: U256, class: U256) -> U256 {
+        ticket_id(event_id, class)
+    }
+
+    /// Events created so far.
+    pub fn event_count(&self) -> U256 {
+        self.event_count.get()
+    }
+
+    /// ERC-1155 collection tickets are minted on.
+    pub fn tickets(&self) -> Address {
+        self.tickets.get()
+    }
+
+    /// EIP-712 domain separator check-ins are signed under.
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        cradle_crypto::domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION)
+    }
+}
+
+// Helpers used by the public methods above.
+impl Ticketing {
+    /// Fails unless the event exists and the caller organizes it.
+    fn require_organizer(&self, event_id: U256) -> Result<(), TicketingError> {
+        let organizer = self.events.getter(event_id).organizer.get();
+        if organizer.is_zero() {
+            return Err(TicketingError::UnknownEvent(UnknownEvent { eventId: event_id }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != organizer {
+            return Err(TicketingError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    /// Fails once the event has started.
+    fn require_sales_open(&self, event_id: U256) -> Result<(), TicketingError> {
+        let start_time = self.events.getter(event_id).start_time.get();
+        if U256::from(self.vm().block_timestamp()) >= start_time {
+            return Err(TicketingError::SalesClosed(SalesClosed { startTime: start_time }));
+        }
+        Ok(())
+    }
+
+    /// Fails unless exactly `expected` ETH is attached.
+    fn require_payment(&self, expected: U256) -> Result<(), TicketingError> {
+        let received = self.vm().msg_value();
+        if received != expected {
+            return Err(TicketingError::IncorrectPayment(IncorrectPayment { expected, received }));
+        }
+        Ok(())
+    }
+
+    /// Fails unless `holder` has `amount` tickets of `ticket_id` that weren't checked in.
+    fn require_unused(&self, holder: Address, ticket_id: U256, amount: U256) -> Result<(), TicketingError> {
+        let available = self.unused(holder, ticket_id)?;
+        if available < amount {
+            return Err(TicketingError::InsufficientUnused(InsufficientUnused {
+                holder,
+                ticketId: ticket_id,
+                available,
+            }));
+        }
+        Ok(())
+    }
+
+    /// `holder`'s balance of `ticket_id` on the collection, less the tickets it checked in.
+    fn unused(&self, holder: Address, ticket_id: U256) -> Result<U256, TicketingError> {
+        let query = balanceOfCall { account: holder, id: ticket_id };
+        let failed = || TicketingError::TicketCallFailed(TicketCallFailed {});
+        let result = self
+            .vm()
+            .static_call(&calls::context::Call::new(), self.tickets.get(), &query.abi_encode())
+            .map_err(|_| failed())?;
+        let balance = U256::abi_decode(&result, true).map_err(|_| failed())?;
+        Ok(balance.saturating_sub(self.used.getter(ticket_id).get(holder)))
+    }
+
+    fn credit(&mut self, account: Address, amount: U256) {
+        let proceeds = self.proceeds.get(account);
+        self.proceeds.insert(account, proceeds + amount);
+    }
+
+    /// Calls the ticket collection, which reverts on failure.
+    fn call_tickets(&mut self, calldata: &[u8]) -> Result<(), TicketingError> {
+        let tickets = self.tickets.get();
+        self.vm()
+            .call(&calls::context::Call::new(), tickets, calldata)
+            .map(|_| ())
+            .map_err(|_| TicketingError::TicketCallFailed(TicketCallFailed {}))
+    }
+}
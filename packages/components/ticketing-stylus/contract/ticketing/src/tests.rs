@@ -0,0 +1,152 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const ORGANIZER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const GATE: Address = address!("0000000000000000000000000000000000006a7e");
+const TICKETS: Address = address!("0000000000000000000000000000000000001155");
+const START: u64 = 10_000;
+
+/// Makes the collection report `balance` of `ticket_id` for `account`.
+fn hold(vm: &TestVM, account: Address, ticket_id: U256, balance: u64) {
+    let query = balanceOfCall { account, id: ticket_id };
+    vm.mock_static_call(TICKETS, query.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// Lets the collection mint `amount` of `ticket_id` to `to`.
+fn expect_mint(vm: &TestVM, to: Address, ticket_id: U256, amount: u64) {
+    let mint = mintCall { to, id: ticket_id, amount: n(amount), data: Vec::new().into() };
+    vm.mock_call(TICKETS, mint.abi_encode(), Ok(Vec::new()));
+}
+
+/// Makes ecrecover return `holder` for its check-in, and returns the signature.
+fn sign_check_in(vm: &TestVM, contract: &Ticketing, holder: Address, ticket_id: U256, nonce: B256) -> Bytes {
+    let struct_hash = cradle_crypto::hash_struct(vm, CHECK_IN_TYPEHASH, (holder, ticket_id, U256::MAX, nonce));
+    let digest = cradle_crypto::hash_typed_data(vm, contract.domain_separator(), struct_hash);
+    let mut signature = vec![0x11; 64];
+    signature.push(27);
+    let mut ecrecover_input = digest.to_vec();
+    ecrecover_input.extend([0; 31]);
+    ecrecover_input.extend([27]);
+    ecrecover_input.extend(&signature[..64]);
+    vm.mock_static_call(cradle_crypto::ECRECOVER, ecrecover_input, Ok(holder.into_word().to_vec()));
+    signature.into()
+}
+
+/// A ticketing contract with one event by `ORGANIZER`: class 0 at 100 wei with 10 seats, class 1
+/// at 300 wei with 2, and a resale royalty of 10%.
+fn setup() -> (TestVM, Ticketing) {
+    let (vm, mut contract) = deploy::<Ticketing>(ORGANIZER);
+    vm.set_block_timestamp(1_000);
+    ok(contract.initialize(TICKETS));
+    ok(contract.create_event(n(START), n(1_000), vec![n(100), n(300)], vec![n(10), n(2)]));
+    (vm, contract)
+}
+
+#[test]
+fn seat_classes_sell_at_their_price_until_the_event_starts() {
+    let (vm, mut contract) = setup();
+    let vip = ticket_id(U256::ZERO, n(1));
+    assert_eq!(contract.ticket_id(U256::ZERO, n(1)), n(1));
+    assert_eq!(contract.get_event(U256::ZERO), (ORGANIZER, n(START), n(1_000), n(2)));
+    assert_eq!(emitted::<EventCreated>(&vm)[0].classes, n(2));
+
+    vm.set_sender(ALICE);
+    vm.set_value(n(300));
+    let result = contract.buy(vip, n(2));
+    assert!(matches!(result, Err(TicketingError::IncorrectPayment(e)) if e.expected == n(600)));
+    vm.set_value(n(600));
+    expect_mint(&vm, ALICE, vip, 2);
+    ok(contract.buy(vip, n(2)));
+    assert_eq!(contract.get_class(vip), (n(300), n(2), n(2)));
+    assert_eq!(contract.proceeds_of(ORGANIZER), n(600));
+    assert_eq!(emitted::<TicketsSold>(&vm)[0].paid, n(600));
+    vm.set_value(n(300));
+    assert!(matches!(contract.buy(vip, n(1)), Err(TicketingError::SoldOut(e)) if e.available.is_zero()));
+    assert!(matches!(contract.buy(n(2), n(1)), Err(TicketingError::UnknownTicket(_))));
+
+    vm.set_block_timestamp(START);
+    vm.set_value(n(100));
+    let result = contract.buy(ticket_id(U256::ZERO, U256::ZERO), n(1));
+    assert!(matches!(result, Err(TicketingError::SalesClosed(e)) if e.startTime == n(START)));
+    let result = contract.create_event(n(START), U256::ZERO, vec![n(1)], vec![n(1)]);
+    assert!(matches!(result, Err(TicketingError::InvalidEvent(_))));
+}
+
+#[test]
+fn gates_check_in_each_signed_ticket_once() {
+    let (vm, mut contract) = setup();
+    let ticket = ticket_id(U256::ZERO, U256::ZERO);
+    let nonce = B256::repeat_byte(1);
+    let signature = sign_check_in(&vm, &contract, ALICE, ticket, nonce);
+    hold(&vm, ALICE, ticket, 1);
+
+    vm.set_sender(GATE);
+    let result = contract.check_in(ALICE, ticket, U256::MAX, nonce, signature.clone());
+    assert!(matches!(result, Err(TicketingError::Unauthorized(e)) if e.caller == GATE));
+    vm.set_sender(ORGANIZER);
+    ok(contract.set_gate(U256::ZERO, GATE, true));
+
+    vm.set_sender(GATE);
+    ok(contract.check_in(ALICE, ticket, U256::MAX, nonce, signature.clone()));
+    assert_eq!((contract.used_of(ALICE, ticket), ok(contract.unused_of(ALICE, ticket))), (n(1), U256::ZERO));
+    assert!(contract.is_redeemed(ALICE, nonce));
+    assert_eq!(emitted::<CheckedIn>(&vm)[0].gate, GATE);
+
+    // The QR code can't be shown twice, and a fresh one finds no ticket left to check in
+    let result = contract.check_in(ALICE, ticket, U256::MAX, nonce, signature);
+    assert!(matches!(result, Err(TicketingError::CheckInAlreadyUsed(_))));
+    let nonce = B256::repeat_byte(2);
+    let signature = sign_check_in(&vm, &contract, ALICE, ticket, nonce);
+    let result = contract.check_in(ALICE, ticket, U256::MAX, nonce, signature.clone());
+    assert!(matches!(result, Err(TicketingError::InsufficientUnused(e)) if e.available.is_zero()));
+    let result = contract.check_in(BOB, ticket, U256::MAX, nonce, signature.clone());
+    assert!(matches!(result, Err(TicketingError::InvalidCheckInSignature(e)) if e.holder == BOB));
+    let result = contract.check_in(ALICE, ticket, n(999), nonce, signature);
+    assert!(matches!(result, Err(TicketingError::ExpiredCheckIn(_))));
+}
+
+#[test]
+fn resales_pay_the_organizer_its_royalty() {
+    let (vm, mut contract) = setup();
+    let ticket = ticket_id(U256::ZERO, U256::ZERO);
+    hold(&vm, ALICE, ticket, 2);
+
+    vm.set_sender(ALICE);
+    let result = contract.list(ticket, n(2), n(99));
+    assert!(matches!(result, Err(TicketingError::ResalePriceTooLow(e)) if e.minimum == n(100)));
+    assert!(matches!(contract.list(ticket, n(3), n(150)), Err(TicketingError::InsufficientUnused(_))));
+    let listing = ok(contract.list(ticket, n(2), n(150)));
+    assert_eq!(contract.get_listing(listing), (ALICE, ticket, n(2), n(150)));
+
+    vm.set_sender(BOB);
+    vm.set_value(n(150));
+    let burn = burnFromCall { from: ALICE, id: ticket, amount: n(1) };
+    vm.mock_call(TICKETS, burn.abi_encode(), Ok(Vec::new()));
+    expect_mint(&vm, BOB, ticket, 1);
+    ok(contract.buy_resale(listing, n(1)));
+    assert_eq!((contract.proceeds_of(ORGANIZER), contract.proceeds_of(ALICE)), (n(15), n(135)));
+    assert_eq!(emitted::<Resold>(&vm)[0].royalty, n(15));
+    assert_eq!(contract.get_listing(listing).2, n(1));
+
+    // A ticket checked in since it was listed can't be resold
+    hold(&vm, ALICE, ticket, 1);
+    vm.set_sender(ORGANIZER);
+    ok(contract.set_gate(U256::ZERO, GATE, true));
+    let nonce = B256::repeat_byte(1);
+    let signature = sign_check_in(&vm, &contract, ALICE, ticket, nonce);
+    vm.set_sender(GATE);
+    ok(contract.check_in(ALICE, ticket, U256::MAX, nonce, signature));
+    vm.set_sender(BOB);
+    assert!(matches!(contract.buy_resale(listing, n(1)), Err(TicketingError::InsufficientUnused(_))));
+    assert!(matches!(contract.cancel_listing(listing), Err(TicketingError::Unauthorized(_))));
+
+    vm.set_sender(ALICE);
+    vm.set_value(U256::ZERO);
+    vm.set_balance(vm.contract_address(), n(150));
+    assert_eq!(ok(contract.withdraw()), n(135));
+    assert!(matches!(contract.withdraw(), Err(TicketingError::NothingToWithdraw(_))));
+}
@@ -0,0 +1,557 @@
+//! DAO treasury
+//!
+//! Holds a DAO's ETH, ERC-20 and ERC-1155 tokens. The treasury is controlled by
+//! a linked governor, normally the timelock that executes passed proposals:
+//! only the governor can make arbitrary calls from the treasury, so moving
+//! funds outside the limits below takes a vote.
+//!
+//! For day-to-day spending the governor grants roles to accounts and gives
+//! each role a spending limit per asset and period. Members of a role can then
+//! pay out up to the limit without a proposal.
+//!
+//! The governor can also stream a budget to a team: the amount vests linearly
+//! between a start and an end time and the recipient withdraws what has vested.
+//! Streamed amounts are set aside, so role spending can't use them.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Selector for `onERC1155Received`.
+const ERC1155_RECEIVED: u32 = 0xf23a6e61;
+/// Selector for `onERC1155BatchReceived`.
+const ERC1155_BATCH_RECEIVED: u32 = 0xbc197c81;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Treasury {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Governor or timelock that controls the treasury
+        address governor;
+        /// Members of each role
+        mapping(bytes32 => mapping(address => bool)) role_members;
+        /// Spending limit per role and asset; the zero address is ETH
+        mapping(bytes32 => mapping(address => SpendingLimit)) spending_limits;
+        /// Amount of each asset set aside for streams
+        mapping(address => uint256) committed;
+        /// Budget streams by ID
+        mapping(uint256 => Stream) streams;
+        /// Streams created so far
+        uint256 stream_count;
+    }
+
+    pub struct SpendingLimit {
+        /// Amount the role may spend per period
+        uint256 limit;
+        /// Length of a period in seconds; zero if the role can't spend the asset
+        uint256 period;
+        uint256 period_start;
+        /// Amount spent in the current period
+        uint256 spent;
+    }
+
+    pub struct Stream {
+        address recipient;
+        /// Asset streamed; the zero address is ETH
+        address token;
+        /// Amount vesting over the stream, reduced to the vested amount on cancellation
+        uint256 amount;
+        uint256 withdrawn;
+        uint256 start_time;
+        uint256 end_time;
+        bool cancelled;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Executed(address indexed target, uint256 value, bytes data);
+    event GovernorUpdated(address indexed previous_governor, address indexed new_governor);
+    event RoleGranted(bytes32 indexed role, address indexed account);
+    event RoleRevoked(bytes32 indexed role, address indexed account);
+    event SpendingLimitSet(bytes32 indexed role, address indexed token, uint256 limit, uint256 period);
+    event Spent(bytes32 indexed role, address indexed spender, address indexed token, address to, uint256 amount);
+    event StreamCreated(uint256 indexed stream_id, address indexed recipient, address indexed token, uint256 amount, uint256 start_time, uint256 end_time);
+    event StreamWithdrawn(uint256 indexed stream_id, address indexed recipient, uint256 amount);
+    event StreamCancelled(uint256 indexed stream_id, uint256 vested, uint256 released);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Amount is zero
+    error InvalidAmount();
+    // Stream ends before it starts, or has already ended
+    error InvalidSchedule();
+    // Amount exceeds what the role may still spend this period
+    error SpendingLimitExceeded(bytes32 role, address token, uint256 available);
+    // Treasury holds less than the amount outside of stream commitments
+    error InsufficientFunds(address token, uint256 available);
+    // Stream doesn't exist
+    error UnknownStream(uint256 stream_id);
+    // Stream was already cancelled
+    error StreamAlreadyCancelled(uint256 stream_id);
+    // Nothing has vested since the last withdrawal
+    error NothingToWithdraw();
+    // Sending ETH or tokens failed
+    error TransferFailed(address token, address to, uint256 amount);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum TreasuryError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidAmount(InvalidAmount),
+    InvalidSchedule(InvalidSchedule),
+    SpendingLimitExceeded(SpendingLimitExceeded),
+    InsufficientFunds(InsufficientFunds),
+    UnknownStream(UnknownStream),
+    StreamAlreadyCancelled(StreamAlreadyCancelled),
+    NothingToWithdraw(NothingToWithdraw),
+    TransferFailed(TransferFailed),
+}
+
+// External interfaces
+sol! {
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Context of the treasury's calls, with the ETH they send. Made through the host, unlike
+/// `stylus_sdk::call`, they build with the SDK's `reentrant` feature and are mocked under test.
+struct TreasuryCall(U256);
+
+impl calls::CallContext for TreasuryCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+// SAFETY: the host flushes the storage cache before every call and clears it, so nothing it holds
+// is stale when a target or token calls back into the treasury
+unsafe impl calls::MutatingCallContext for TreasuryCall {
+    fn value(&self) -> U256 {
+        self.0
+    }
+}
+
+// Helpers used by the public methods below.
+impl Treasury {
+    fn only_governor(&self) -> Result<(), TreasuryError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.governor.get() {
+            return Err(TreasuryError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Treasury's balance of an asset; the zero address is ETH.
+    fn balance_of(&self, token: Address) -> U256 {
+        let this = self.vm().contract_address();
+        if token.is_zero() {
+            self.vm().balance(this)
+        } else {
+            self.vm()
+                .static_call(&self, token, &IERC20::balanceOfCall { account: this }.abi_encode())
+                .ok()
+                .and_then(|output| IERC20::balanceOfCall::abi_decode_returns(&output, true).ok())
+                .map_or(U256::ZERO, |balance| balance._0)
+        }
+    }
+
+    /// Balance of an asset that isn't set aside for streams.
+    fn available_of(&self, token: Address) -> U256 {
+        self.balance_of(token).saturating_sub(self.committed.get(token))
+    }
+
+    /// Amount of a stream vested at `now`.
+    fn vested_of(&self, stream_id: U256, now: U256) -> U256 {
+        let stream = self.streams.getter(stream_id);
+        let amount = stream.amount.get();
+        let start_time = stream.start_time.get();
+        let end_time = stream.end_time.get();
+        if stream.cancelled.get() || now >= end_time {
+            amount
+        } else if now <= start_time {
+            U256::ZERO
+        } else {
+            amount * (now - start_time) / (end_time - start_time)
+        }
+    }
+
+    /// Calls `target` with `calldata`, attaching `value` wei.
+    fn call(&mut self, target: Address, value: U256, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(&TreasuryCall(value), target, calldata).map_err(Vec::from)
+    }
+
+    fn send(&mut self, token: Address, to: Address, amount: U256) -> Result<(), TreasuryError> {
+        let sent = if token.is_zero() {
+            self.call(to, amount, &[]).is_ok()
+        } else {
+            let calldata = IERC20::transferCall { to, value: amount }.abi_encode();
+            self.call(token, U256::ZERO, &calldata)
+                .is_ok_and(|output| bool::abi_decode(&output, true).unwrap_or(false))
+        };
+        if !sent {
+            return Err(TreasuryError::TransferFailed(TransferFailed { token, to, amount }));
+        }
+        Ok(())
+    }
+}
+
+#[public]
+impl Treasury {
+    /// Links the governor. Can only be called once.
+    pub fn initialize(&mut self, governor: Address) -> Result<(), TreasuryError> {
+        if self.initialized.get() {
+            return Err(TreasuryError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.governor.set(governor);
+
+        log(self.vm(), GovernorUpdated {
+            previous_governor: Address::ZERO,
+            new_governor: governor,
+        });
+        Ok(())
+    }
+
+    /// Calls `target` with `value` wei from the treasury, bubbling up its revert data (governor
+    /// only). Returns the call's return data.
+    pub fn execute(&mut self, target: Address, value: U256, data: Bytes) -> Result<Bytes, Vec<u8>> {
+        self.only_governor()?;
+        let result = self.call(target, value, &data)?;
+
+        log(self.vm(), Executed {
+            target,
+            value,
+            data: data.0.into(),
+        });
+        Ok(result.into())
+    }
+
+    /// Moves control of the treasury to a new governor (governor only).
+    pub fn set_governor(&mut self, new_governor: Address) -> Result<(), TreasuryError> {
+        self.only_governor()?;
+        let previous_governor = self.governor.get();
+        self.governor.set(new_governor);
+
+        log(self.vm(), GovernorUpdated {
+            previous_governor,
+            new_governor,
+        });
+        Ok(())
+    }
+
+    /// Grants `role` to `account` (governor only).
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), TreasuryError> {
+        self.only_governor()?;
+        self.role_members.setter(role).insert(account, true);
+
+        log(self.vm(), RoleGranted { role, account });
+        Ok(())
+    }
+
+    /// Revokes `role` from `account` (governor only).
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), TreasuryError> {
+        self.only_governor()?;
+        self.role_members.setter(role).insert(account, false);
+
+        log(self.vm(), RoleRevoked { role, account });
+        Ok(())
+    }
+
+    /// Lets members of `role` spend up to `limit` of `token` every `period` seconds (governor only).
+    /// The zero address is ETH. A zero period stops the role from spending the asset.
+    pub fn set_spending_limit(
+        &mut self,
+        role: B256,
+        token: Address,
+        limit: U256,
+        period: U256,
+    ) -> Result<(), TreasuryError> {
+        self.only_governor()?;
+        let now = self.now();
+        let mut role_limits = self.spending_limits.setter(role);
+        let mut spending_limit = role_limits.setter(token);
+        spending_limit.limit.set(limit);
+        spending_limit.period.set(period);
+        spending_limit.period_start.set(now);
+        spending_limit.spent.set(U256::ZERO);
+
+        log(self.vm(), SpendingLimitSet {
+            role,
+            token,
+            limit,
+            period,
+        });
+        Ok(())
+    }
+
+    /// Pays `amount` of `token` to `to` within the spending limit of `role` (members of `role` only).
+    pub fn spend(&mut self, role: B256, token: Address, to: Address, amount: U256) -> Result<(), TreasuryError> {
+        let spender = self.vm().msg_sender();
+        if !self.role_members.getter(role).get(spender) {
+            return Err(TreasuryError::Unauthorized(Unauthorized { caller: spender }));
+        }
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount(InvalidAmount {}));
+        }
+        let available = self.available_of(token);
+        if amount > available {
+            return Err(TreasuryError::InsufficientFunds(InsufficientFunds { token, available }));
+        }
+
+        let now = self.now();
+        let mut role_limits = self.spending_limits.setter(role);
+        let mut spending_limit = role_limits.setter(token);
+        let period = spending_limit.period.get();
+        if period.is_zero() {
+            return Err(TreasuryError::SpendingLimitExceeded(SpendingLimitExceeded {
+                role,
+                token,
+                available: U256::ZERO,
+            }));
+        }
+        if now >= spending_limit.period_start.get() + period {
+            spending_limit.period_start.set(now);
+            spending_limit.spent.set(U256::ZERO);
+        }
+        let limit = spending_limit.limit.get();
+        let spent = spending_limit.spent.get();
+        if spent + amount > limit {
+            return Err(TreasuryError::SpendingLimitExceeded(SpendingLimitExceeded {
+                role,
+                token,
+                available: limit.saturating_sub(spent),
+            }));
+        }
+        spending_limit.spent.set(spent + amount);
+
+        self.send(token, to, amount)?;
+
+        log(self.vm(), Spent {
+            role,
+            spender,
+            token,
+            to,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Streams `amount` of `token` to `recipient`, vesting linearly from `start_time` to `end_time`
+    /// (governor only). The zero address is ETH. Returns the stream ID.
+    pub fn create_stream(
+        &mut self,
+        recipient: Address,
+        token: Address,
+        amount: U256,
+        start_time: U256,
+        end_time: U256,
+    ) -> Result<U256, TreasuryError> {
+        self.only_governor()?;
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount(InvalidAmount {}));
+        }
+        if end_time <= start_time || end_time <= self.now() {
+            return Err(TreasuryError::InvalidSchedule(InvalidSchedule {}));
+        }
+        let available = self.available_of(token);
+        if amount > available {
+            return Err(TreasuryError::InsufficientFunds(InsufficientFunds { token, available }));
+        }
+        let committed = self.committed.get(token);
+        self.committed.insert(token, committed + amount);
+
+        let stream_id = self.stream_count.get();
+        self.stream_count.set(stream_id + U256::from(1));
+        let mut stream = self.streams.setter(stream_id);
+        stream.recipient.set(recipient);
+        stream.token.set(token);
+        stream.amount.set(amount);
+        stream.start_time.set(start_time);
+        stream.end_time.set(end_time);
+
+        log(self.vm(), StreamCreated {
+            stream_id,
+            recipient,
+            token,
+            amount,
+            start_time,
+            end_time,
+        });
+        Ok(stream_id)
+    }
+
+    /// Pays out what has vested on a stream since the last withdrawal (recipient only). Returns the
+    /// amount paid.
+    pub fn withdraw_from_stream(&mut self, stream_id: U256) -> Result<U256, TreasuryError> {
+        let stream = self.streams.getter(stream_id);
+        let recipient = stream.recipient.get();
+        if recipient.is_zero() {
+            return Err(TreasuryError::UnknownStream(UnknownStream { stream_id }));
+        }
+        let caller = self.vm().msg_sender();
+        if caller != recipient {
+            return Err(TreasuryError::Unauthorized(Unauthorized { caller }));
+        }
+        let token = stream.token.get();
+        let withdrawn = stream.withdrawn.get();
+        let amount = self.vested_of(stream_id, self.now()) - withdrawn;
+        if amount.is_zero() {
+            return Err(TreasuryError::NothingToWithdraw(NothingToWithdraw {}));
+        }
+        self.streams.setter(stream_id).withdrawn.set(withdrawn + amount);
+        let committed = self.committed.get(token);
+        self.committed.insert(token, committed - amount);
+
+        self.send(token, recipient, amount)?;
+
+        log(self.vm(), StreamWithdrawn {
+            stream_id,
+            recipient,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    /// Stops a stream (governor only). What has vested stays withdrawable by the recipient and the
+    /// rest is released back to the treasury.
+    pub fn cancel_stream(&mut self, stream_id: U256) -> Result<(), TreasuryError> {
+        self.only_governor()?;
+        let stream = self.streams.getter(stream_id);
+        if stream.recipient.get().is_zero() {
+            return Err(TreasuryError::UnknownStream(UnknownStream { stream_id }));
+        }
+        if stream.cancelled.get() {
+            return Err(TreasuryError::StreamAlreadyCancelled(StreamAlreadyCancelled { stream_id }));
+        }
+        let token = stream.token.get();
+        let amount = stream.amount.get();
+        let vested = self.vested_of(stream_id, self.now());
+        let released = amount - vested;
+
+        let mut stream = self.streams.setter(stream_id);
+        stream.amount.set(vested);
+        stream.cancelled.set(true);
+        let committed = self.committed.get(token);
+        self.committed.insert(token, committed - released);
+
+        log(self.vm(), StreamCancelled {
+            stream_id,
+            vested,
+            released,
+        });
+        Ok(())
+    }
+
+    /// Accepts ERC-1155 tokens.
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _id: U256,
+        _value: U256,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes::from(ERC1155_RECEIVED.to_be_bytes())
+    }
+
+    /// Accepts batches of ERC-1155 tokens.
+    #[selector(name = "onERC1155BatchReceived")]
+    pub fn on_erc1155_batch_received(
+        &mut self,
+        _operator: Address,
+        _from: Address,
+        _ids: Vec<U256>,
+        _values: Vec<U256>,
+        _data: Bytes,
+    ) -> FixedBytes<4> {
+        FixedBytes::from(ERC1155_BATCH_RECEIVED.to_be_bytes())
+    }
+
+    /// Whether `account` has `role`.
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.role_members.getter(role).get(account)
+    }
+
+    /// Returns `(limit, period, spent, remaining)` for a role and asset in the current period.
+    pub fn spending_limit(&self, role: B256, token: Address) -> (U256, U256, U256, U256) {
+        let role_limits = self.spending_limits.getter(role);
+        let spending_limit = role_limits.getter(token);
+        let limit = spending_limit.limit.get();
+        let period = spending_limit.period.get();
+        if period.is_zero() {
+            return (limit, period, U256::ZERO, U256::ZERO);
+        }
+        let spent = if self.now() >= spending_limit.period_start.get() + period {
+            U256::ZERO
+        } else {
+            spending_limit.spent.get()
+        };
+        (limit, period, spent, limit.saturating_sub(spent))
+    }
+
+    /// Returns `(recipient, token, amount, withdrawn, start_time, end_time, cancelled)`.
+    #[allow(clippy::type_complexity)]
+    pub fn get_stream(&self, stream_id: U256) -> (Address, Address, U256, U256, U256, U256, bool) {
+        let stream = self.streams.getter(stream_id);
+        (
+            stream.recipient.get(),
+            stream.token.get(),
+            stream.amount.get(),
+            stream.withdrawn.get(),
+            stream.start_time.get(),
+            stream.end_time.get(),
+            stream.cancelled.get(),
+        )
+    }
+
+    /// Amount a stream's recipient can withdraw now.
+    pub fn withdrawable(&self, stream_id: U256) -> U256 {
+        self.vested_of(stream_id, self.now()) - self.streams.getter(stream_id).withdrawn.get()
+    }
+
+    /// Number of streams created.
+    pub fn stream_count(&self) -> U256 {
+        self.stream_count.get()
+    }
+
+    /// Returns `(balance, committed, available)` for an asset; the zero address is ETH.
+    pub fn funds(&self, token: Address) -> (U256, U256, U256) {
+        let balance = self.balance_of(token);
+        let committed = self.committed.get(token);
+        (balance, committed, balance.saturating_sub(committed))
+    }
+
+    /// Governor or timelock that controls the treasury.
+    pub fn governor(&self) -> Address {
+        self.governor.get()
+    }
+
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        Ok(())
+    }
+}
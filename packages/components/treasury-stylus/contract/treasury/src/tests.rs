@@ -0,0 +1,123 @@
+use super::*;
+use alloy_primitives::{address, b256};
+use cradle_test_utils::{deploy, emitted, n, ok};
+use stylus_sdk::testing::TestVM;
+
+const GOVERNOR: Address = address!("000000000000000000000000000000000000d0a0");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const USDC: Address = address!("0000000000000000000000000000000000005dc0");
+const TARGET: Address = address!("000000000000000000000000000000000000da99");
+const OPS: B256 = b256!("00000000000000000000000000000000000000000000000000000000000000a5");
+
+/// Mocks the treasury's balance of `USDC`.
+fn mock_usdc(vm: &TestVM, balance: u64) {
+    let call = IERC20::balanceOfCall { account: vm.contract_address() };
+    vm.mock_static_call(USDC, call.abi_encode(), Ok(n(balance).abi_encode()));
+}
+
+/// Lets the treasury send exactly `amount` of `USDC` to `to`.
+fn expect_usdc(vm: &TestVM, to: Address, amount: u64) {
+    let call = IERC20::transferCall { to, value: n(amount) };
+    vm.mock_call(USDC, call.abi_encode(), Ok(true.abi_encode()));
+}
+
+/// A treasury at time 1,000 holding 10,000 wei and 10,000 USDC, with Alice in the ops role.
+fn setup() -> (TestVM, Treasury) {
+    let (vm, mut contract) = deploy::<Treasury>(GOVERNOR);
+    vm.set_block_timestamp(1_000);
+    vm.set_balance(vm.contract_address(), n(10_000));
+    mock_usdc(&vm, 10_000);
+    ok(contract.initialize(GOVERNOR));
+    ok(contract.grant_role(OPS, ALICE));
+    (vm, contract)
+}
+
+#[test]
+fn roles_spend_within_their_limit_per_period() {
+    let (vm, mut contract) = setup();
+    ok(contract.set_spending_limit(OPS, Address::ZERO, n(1_000), n(86_400)));
+    vm.set_sender(ALICE);
+    ok(contract.spend(OPS, Address::ZERO, BOB, n(600)));
+    assert_eq!(contract.spending_limit(OPS, Address::ZERO), (n(1_000), n(86_400), n(600), n(400)));
+    let spent = &emitted::<Spent>(&vm)[0];
+    assert_eq!((spent.spender, spent.to, spent.amount), (ALICE, BOB, n(600)));
+
+    let result = contract.spend(OPS, Address::ZERO, BOB, n(401));
+    assert!(matches!(result, Err(TreasuryError::SpendingLimitExceeded(e)) if e.available == n(400)));
+    let result = contract.spend(OPS, USDC, BOB, n(1));
+    assert!(matches!(result, Err(TreasuryError::SpendingLimitExceeded(e)) if e.token == USDC));
+
+    // The limit resets once the period is over
+    vm.set_block_timestamp(1_000 + 86_400);
+    ok(contract.spend(OPS, Address::ZERO, BOB, n(1_000)));
+    vm.set_sender(BOB);
+    assert!(matches!(contract.spend(OPS, Address::ZERO, BOB, n(1)), Err(TreasuryError::Unauthorized(_))));
+}
+
+#[test]
+fn streams_vest_linearly_and_cancel_at_what_vested() {
+    let (vm, mut contract) = setup();
+    assert_eq!(ok(contract.create_stream(BOB, USDC, n(1_000), n(1_000), n(2_000))), U256::ZERO);
+    assert_eq!(contract.funds(USDC), (n(10_000), n(1_000), n(9_000)));
+
+    vm.set_block_timestamp(1_250);
+    expect_usdc(&vm, BOB, 250);
+    vm.set_sender(BOB);
+    assert_eq!(ok(contract.withdraw_from_stream(U256::ZERO)), n(250));
+    assert!(matches!(contract.withdraw_from_stream(U256::ZERO), Err(TreasuryError::NothingToWithdraw(_))));
+
+    vm.set_block_timestamp(1_500);
+    vm.set_sender(GOVERNOR);
+    ok(contract.cancel_stream(U256::ZERO));
+    let cancelled = &emitted::<StreamCancelled>(&vm)[0];
+    assert_eq!((cancelled.vested, cancelled.released), (n(500), n(500)));
+    assert_eq!(contract.funds(USDC).1, n(250));
+    assert!(matches!(contract.cancel_stream(U256::ZERO), Err(TreasuryError::StreamAlreadyCancelled(_))));
+
+    // The vested rest stays Bob's after cancellation
+    vm.set_block_timestamp(3_000);
+    vm.set_sender(BOB);
+    assert_eq!(ok(contract.withdraw_from_stream(U256::ZERO)), n(250));
+    assert_eq!(contract.funds(USDC).1, U256::ZERO);
+}
+
+#[test]
+fn only_the_governor_executes_arbitrary_calls() {
+    let (vm, mut contract) = setup();
+    let data = vec![0x12, 0x49, 0xc5, 0x8b];
+    vm.mock_call(TARGET, data.clone(), Ok(true.abi_encode()));
+    assert_eq!(contract.execute(TARGET, n(100), data.clone().into()).map(|r| r.0), Ok(true.abi_encode()));
+    let executed = &emitted::<Executed>(&vm)[0];
+    assert_eq!((executed.target, executed.value), (TARGET, n(100)));
+
+    vm.mock_call(TARGET, data.clone(), Err(b"not allowed".to_vec()));
+    assert_eq!(contract.execute(TARGET, U256::ZERO, data.clone().into()).map(|r| r.0), Err(b"not allowed".to_vec()));
+    vm.set_sender(ALICE);
+    let unauthorized: Vec<u8> = TreasuryError::Unauthorized(Unauthorized { caller: ALICE }).into();
+    assert_eq!(contract.execute(TARGET, U256::ZERO, data.into()).map(|r| r.0), Err(unauthorized));
+    assert!(matches!(contract.set_governor(ALICE), Err(TreasuryError::Unauthorized(_))));
+}
+
+#[test]
+fn commitments_and_schedules_are_checked() {
+    let (vm, mut contract) = setup();
+    let result = contract.create_stream(BOB, USDC, n(1_000), n(2_000), n(2_000));
+    assert!(matches!(result, Err(TreasuryError::InvalidSchedule(_))));
+    let result = contract.create_stream(BOB, USDC, n(1_000), n(0), n(1_000));
+    assert!(matches!(result, Err(TreasuryError::InvalidSchedule(_))));
+    let result = contract.create_stream(BOB, USDC, n(10_001), n(1_000), n(2_000));
+    assert!(matches!(result, Err(TreasuryError::InsufficientFunds(e)) if e.available == n(10_000)));
+    ok(contract.create_stream(BOB, USDC, n(9_500), n(1_000), n(2_000)));
+
+    // Streamed funds can't be spent by roles
+    ok(contract.set_spending_limit(OPS, USDC, n(1_000), n(86_400)));
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.spend(OPS, USDC, ALICE, U256::ZERO), Err(TreasuryError::InvalidAmount(_))));
+    let result = contract.spend(OPS, USDC, ALICE, n(501));
+    assert!(matches!(result, Err(TreasuryError::InsufficientFunds(e)) if e.available == n(500)));
+
+    // A token that doesn't return `true` fails the payment
+    let result = contract.spend(OPS, USDC, ALICE, n(500));
+    assert!(matches!(result, Err(TreasuryError::TransferFailed(e)) if e.to == ALICE && e.amount == n(500)));
+}
@@ -0,0 +1,123 @@
+//! Upgradeable beacon
+//!
+//! Holds the implementation address that every beacon proxy pointing at it
+//! delegates to. Upgrading the beacon upgrades the whole fleet in one
+//! transaction, e.g. every per-creator collection deployed through the clone
+//! factory.
+//!
+//! Only the owner can change the implementation, and only to an address with code.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloy_primitives::Address;
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct UpgradeableBeacon {
+        /// Set once by `initialize`
+        bool initialized;
+        /// Upgrades the implementation
+        address owner;
+        /// Implementation the proxies delegate to
+        address implementation;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Upgraded(address indexed implementation);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    // Contract has already been initialized
+    error AlreadyInitialized();
+    // Caller is not allowed to perform the action
+    error Unauthorized(address caller);
+    // Implementation has no code
+    error InvalidImplementation(address implementation);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum BeaconError {
+    AlreadyInitialized(AlreadyInitialized),
+    Unauthorized(Unauthorized),
+    InvalidImplementation(InvalidImplementation),
+}
+
+// Helpers used by the public methods below.
+impl UpgradeableBeacon {
+    fn only_owner(&self) -> Result<(), BeaconError> {
+        let caller = self.vm().msg_sender();
+        if caller != self.owner.get() {
+            return Err(BeaconError::Unauthorized(Unauthorized { caller }));
+        }
+        Ok(())
+    }
+
+    fn set_implementation(&mut self, implementation: Address) -> Result<(), BeaconError> {
+        if self.vm().code_size(implementation) == 0 {
+            return Err(BeaconError::InvalidImplementation(InvalidImplementation { implementation }));
+        }
+        self.implementation.set(implementation);
+
+        log(self.vm(), Upgraded { implementation });
+        Ok(())
+    }
+}
+
+#[public]
+impl UpgradeableBeacon {
+    /// Sets the owner and the first implementation. Can only be called once.
+    pub fn initialize(&mut self, owner: Address, implementation: Address) -> Result<(), BeaconError> {
+        if self.initialized.get() {
+            return Err(BeaconError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.owner.set(owner);
+        self.set_implementation(implementation)?;
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+        Ok(())
+    }
+
+    /// Points every proxy of this beacon at a new implementation (owner only).
+    pub fn upgrade_to(&mut self, new_implementation: Address) -> Result<(), BeaconError> {
+        self.only_owner()?;
+        self.set_implementation(new_implementation)
+    }
+
+    /// Transfers ownership of the contract (owner only).
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), BeaconError> {
+        self.only_owner()?;
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Implementation the proxies delegate to.
+    pub fn implementation(&self) -> Address {
+        self.implementation.get()
+    }
+
+    /// Current owner of the contract.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+}
@@ -0,0 +1,25 @@
+use super::*;
+use alloy_primitives::address;
+use cradle_test_utils::deploy;
+
+const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const V1: Address = address!("0000000000000000000000000000000000000001");
+const V2: Address = address!("0000000000000000000000000000000000000002");
+
+#[test]
+fn only_the_owner_upgrades_and_only_to_code() {
+    let (vm, mut contract) = deploy::<UpgradeableBeacon>(OWNER);
+    vm.set_code(V1, vec![0xfe]);
+    assert!(contract.initialize(OWNER, V1).is_ok());
+    assert!(matches!(contract.initialize(OWNER, V1), Err(BeaconError::AlreadyInitialized(_))));
+
+    let result = contract.upgrade_to(V2);
+    assert!(matches!(result, Err(BeaconError::InvalidImplementation(e)) if e.implementation == V2));
+    vm.set_code(V2, vec![0xfe]);
+    vm.set_sender(ALICE);
+    assert!(matches!(contract.upgrade_to(V2), Err(BeaconError::Unauthorized(e)) if e.caller == ALICE));
+    vm.set_sender(OWNER);
+    assert!(contract.upgrade_to(V2).is_ok());
+    assert_eq!(contract.implementation(), V2);
+}
@@ -0,0 +1,83 @@
+//! The builds to measure and the limits they must stay within, from `budgets.toml`.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use eyre::WrapErr;
+use serde::Deserialize;
+
+use crate::stylus::Measurement;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    /// Limits of every build, unless its component sets its own
+    default: Limits,
+    #[serde(default)]
+    components: BTreeMap<String, ComponentBudget>,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Limits {
+    /// Compressed WASM, in bytes
+    pub contract_size: Option<u64>,
+    /// Data fee ArbOS charges to activate the contract, in gwei
+    pub data_fee_gwei: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ComponentBudget {
+    #[serde(flatten)]
+    limits: Limits,
+    /// Feature combinations to measure besides the default build, each comma-separated
+    #[serde(default)]
+    features: Vec<String>,
+}
+
+impl Budgets {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("can't read {}", path.display()))?;
+        Self::parse(&text).wrap_err_with(|| format!("in {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> eyre::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// The components that have budgets of their own.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        self.components.keys().map(String::as_str)
+    }
+
+    /// The feature sets to build `component` with, starting with the default build's.
+    pub fn builds(&self, component: &str) -> Vec<Vec<String>> {
+        let combinations = self.components.get(component).map(|budget| budget.features.as_slice()).unwrap_or_default();
+        let mut builds = vec![Vec::new()];
+        builds.extend(combinations.iter().map(|features| features.split(',').map(|f| f.trim().to_string()).collect()));
+        builds
+    }
+
+    /// The limits of every build of `component`.
+    pub fn limits(&self, component: &str) -> Limits {
+        let own = self.components.get(component).map(|budget| budget.limits).unwrap_or_default();
+        Limits {
+            contract_size: own.contract_size.or(self.default.contract_size),
+            data_fee_gwei: own.data_fee_gwei.or(self.default.data_fee_gwei),
+        }
+    }
+}
+
+impl Limits {
+    /// How `measurement` goes over these limits, if it does.
+    pub fn exceeded(&self, measurement: &Measurement) -> Vec<String> {
+        let mut over = Vec::new();
+        if let Some(limit) = self.contract_size.filter(|&limit| measurement.contract_size > limit) {
+            over.push(format!("contract size {} bytes is over the budget of {limit}", measurement.contract_size));
+        }
+        if let Some(limit) = self.data_fee_gwei.filter(|&limit| measurement.data_fee_gwei > limit) {
+            over.push(format!("data fee {} gwei is over the budget of {limit}", measurement.data_fee_gwei));
+        }
+        over
+    }
+}
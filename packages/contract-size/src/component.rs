@@ -0,0 +1,52 @@
+//! Finding the Stylus components and the builds of each to measure.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{ensure, WrapErr};
+
+/// A component with a Stylus contract, e.g. `packages/components/erc1155-stylus`.
+pub struct Component {
+    /// Directory name, like `erc1155-stylus`
+    pub name: String,
+    /// The contract crate, like `contract/erc1155`
+    pub contract: PathBuf,
+}
+
+/// Every component under `dir` that has a contract crate, by name.
+pub fn all(dir: &Path) -> eyre::Result<Vec<Component>> {
+    let mut components = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("can't read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(contract) = contract_crate(&path)? else { continue };
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        components.push(Component { name, contract });
+    }
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(components)
+}
+
+impl Component {
+    /// The features the contract crate declares, for checking that builds only name real ones.
+    pub fn features(&self) -> eyre::Result<Vec<String>> {
+        let manifest: toml::Table = fs::read_to_string(self.contract.join("Cargo.toml"))?.parse()?;
+        let features = manifest.get("features").and_then(|features| features.as_table());
+        Ok(features.map(|features| features.keys().cloned().collect()).unwrap_or_default())
+    }
+}
+
+/// The one crate in `component/contract`, if there is one.
+fn contract_crate(component: &Path) -> eyre::Result<Option<PathBuf>> {
+    let Ok(entries) = fs::read_dir(component.join("contract")) else { return Ok(None) };
+    let mut crates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.join("Cargo.toml").exists() {
+            crates.push(path);
+        }
+    }
+    ensure!(crates.len() <= 1, "{} has more than one contract crate", component.display());
+    Ok(crates.pop())
+}
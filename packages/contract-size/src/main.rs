@@ -0,0 +1,141 @@
+//! Measures the size and activation cost of Cradle's Stylus components.
+//!
+//! For every component in `packages/components` with a contract crate, runs `cargo stylus check`
+//! on its default build and on each feature combination `budgets.toml` lists for it. Records the
+//! compressed WASM size and the data fee ArbOS charges to activate it in `reports/sizes.json`,
+//! with the change since the last report, and fails if a build is over its budget.
+
+mod budget;
+mod component;
+mod stylus;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use eyre::{bail, ensure};
+use serde::{Deserialize, Serialize};
+
+use budget::Budgets;
+use component::Component;
+use stylus::Measurement;
+
+#[derive(Parser)]
+struct Args {
+    /// Node to check the contracts against, which prices their activation
+    #[arg(long, env = "RPC_URL", default_value = "http://localhost:8547")]
+    endpoint: String,
+    /// Only measure these components, e.g. `erc1155-stylus`
+    #[arg(long = "component")]
+    components: Vec<String>,
+    /// Where to write the report, and read the last one from
+    #[arg(long, default_value = "reports/sizes.json")]
+    out: PathBuf,
+}
+
+/// The measurements of one run.
+#[derive(Default, Serialize, Deserialize)]
+struct Report {
+    builds: Vec<Build>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Build {
+    component: String,
+    features: Vec<String>,
+    #[serde(flatten)]
+    measurement: Measurement,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let budgets = Budgets::load(&root.join("budgets.toml"))?;
+    let out = root.join(&args.out);
+    let last: Report = fs::read(&out).ok().and_then(|json| serde_json::from_slice(&json).ok()).unwrap_or_default();
+
+    let mut components = component::all(&root.join("../components"))?;
+    check_budgets(&budgets, &components)?;
+    for name in &args.components {
+        ensure!(components.iter().any(|component| &component.name == name), "no component named {name}");
+    }
+    if !args.components.is_empty() {
+        components.retain(|component| args.components.contains(&component.name));
+    }
+
+    let mut report = Report::default();
+    let mut over = Vec::new();
+    println!("{:<40} {:>10} {:>10} {:>12}", "build", "size", "change", "data fee");
+    for component in &components {
+        for features in budgets.builds(&component.name) {
+            let measurement = stylus::check(&component.contract, &args.endpoint, &features)?;
+            let build = Build { component: component.name.clone(), features, measurement };
+
+            let change = last
+                .builds
+                .iter()
+                .find(|earlier| earlier.component == build.component && earlier.features == build.features)
+                .map(|earlier| measurement.contract_size as i64 - earlier.measurement.contract_size as i64);
+            println!(
+                "{:<40} {:>10} {:>10} {:>8} gwei",
+                build.label(),
+                measurement.contract_size,
+                change.map_or("new".into(), |change| format!("{change:+}")),
+                measurement.data_fee_gwei
+            );
+            let exceeded = budgets.limits(&component.name).exceeded(&measurement);
+            over.extend(exceeded.into_iter().map(|reason| format!("{}: {reason}", build.label())));
+            report.builds.push(build);
+        }
+    }
+
+    // Measuring only some components keeps the others from the last report
+    if !args.components.is_empty() {
+        let measured = |earlier: &Build| report.builds.iter().any(|build| build.component == earlier.component);
+        let kept: Vec<_> = last.builds.into_iter().filter(|earlier| !measured(earlier)).collect();
+        report.builds.extend(kept);
+        report.builds.sort_by(|a, b| a.component.cmp(&b.component));
+    }
+    fs::create_dir_all(out.parent().unwrap())?;
+    fs::write(&out, serde_json::to_string_pretty(&report)? + "\n")?;
+    println!("\nWrote {}", out.display());
+
+    if !over.is_empty() {
+        for reason in &over {
+            println!("over budget: {reason}");
+        }
+        bail!("{} limits in budgets.toml are exceeded", over.len());
+    }
+    Ok(())
+}
+
+/// Fails if `budgets` names a component that doesn't exist, or a feature its contract doesn't have.
+fn check_budgets(budgets: &Budgets, components: &[Component]) -> eyre::Result<()> {
+    for name in budgets.components() {
+        let Some(component) = components.iter().find(|component| component.name == name) else {
+            bail!("budgets.toml has budgets for {name}, which isn't a component");
+        };
+        let declared = component.features()?;
+        for features in budgets.builds(name) {
+            if let Some(unknown) = features.iter().find(|feature| !declared.contains(feature)) {
+                bail!("budgets.toml builds {name} with `{unknown}`, which it doesn't have");
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Build {
+    /// The component and its features, like `erc1155-stylus[packed-balances]`.
+    fn label(&self) -> String {
+        if self.features.is_empty() {
+            self.component.clone()
+        } else {
+            format!("{}[{}]", self.component, self.features.join(","))
+        }
+    }
+}
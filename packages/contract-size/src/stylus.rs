@@ -0,0 +1,93 @@
+//! Running `cargo stylus check` and reading what it reports.
+
+use std::{path::Path, process::Command};
+
+use eyre::{ensure, eyre, WrapErr};
+use serde::{Deserialize, Serialize};
+
+/// What `cargo stylus check` reported for one build.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// Compressed WASM deployed onchain, in bytes, which ArbOS limits to 24 KiB
+    pub contract_size: u64,
+    /// WASM before compression, in bytes
+    pub wasm_size: u64,
+    /// Data fee ArbOS charges to activate the contract, in gwei
+    pub data_fee_gwei: u64,
+}
+
+/// Builds the contract crate in `dir` with `features` and checks it against the node at
+/// `endpoint`, which prices its activation.
+pub fn check(dir: &Path, endpoint: &str, features: &[String]) -> eyre::Result<Measurement> {
+    let mut command = Command::new("cargo");
+    command.args(["stylus", "check", "--endpoint", endpoint]);
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+    // Let the contract's rust-toolchain.toml pick the toolchain rather than ours
+    command.env_remove("RUSTUP_TOOLCHAIN").current_dir(dir);
+    let output = command.output().wrap_err("failed to run cargo stylus, is cargo-stylus installed?")?;
+    let log =
+        strip_ansi(&format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)));
+    ensure!(output.status.success(), "cargo stylus check failed:\n{log}");
+    parse(&log).wrap_err_with(|| format!("in cargo stylus output:\n{log}"))
+}
+
+/// Reads the sizes and data fee from `cargo stylus check` output without colours.
+pub fn parse(log: &str) -> eyre::Result<Measurement> {
+    let value = |label: &str| {
+        log.lines()
+            .filter_map(|line| line.trim().strip_prefix(label))
+            .next_back()
+            .map(str::trim)
+            .ok_or_else(|| eyre!("no `{label}` line"))
+    };
+    Ok(Measurement {
+        contract_size: bytes(value("contract size:")?)?,
+        wasm_size: bytes(value("wasm size:")?)?,
+        data_fee_gwei: gwei(value("wasm data fee:")?)?,
+    })
+}
+
+/// A size like `6.5 KiB (6617 bytes)`, or `6.5 KiB` from versions that don't print the bytes.
+fn bytes(size: &str) -> eyre::Result<u64> {
+    if let Some((_, exact)) = size.split_once('(') {
+        let exact = exact.trim_end_matches(')').trim_end_matches("bytes").trim();
+        return exact.parse().wrap_err_with(|| format!("bad size `{size}`"));
+    }
+    let (number, unit) = size.split_once(' ').ok_or_else(|| eyre!("bad size `{size}`"))?;
+    let scale = match unit.trim() {
+        "B" | "bytes" => 1.0,
+        "KB" => 1000.0,
+        "KiB" => 1024.0,
+        unit => return Err(eyre!("unknown unit `{unit}` in `{size}`")),
+    };
+    let number: f64 = number.parse().wrap_err_with(|| format!("bad size `{size}`"))?;
+    Ok((number * scale).round() as u64)
+}
+
+/// A fee like `0.000074 ETH (originally 0.000061 ETH with 20% bump)`, in gwei. The bumped fee
+/// is what activation charges, so it's the one that counts.
+fn gwei(fee: &str) -> eyre::Result<u64> {
+    let eth = fee.split(" (").next().unwrap_or(fee);
+    let eth = eth.strip_suffix("ETH").ok_or_else(|| eyre!("bad fee `{fee}`"))?.trim();
+    let (whole, fraction) = eth.split_once('.').unwrap_or((eth, ""));
+    ensure!(fraction.len() <= 9, "fee `{fee}` is more precise than a gwei");
+    let whole: u64 = whole.parse().wrap_err_with(|| format!("bad fee `{fee}`"))?;
+    let fraction: u64 = format!("{fraction:0<9}").parse().wrap_err_with(|| format!("bad fee `{fee}`"))?;
+    Ok(whole * 1_000_000_000 + fraction)
+}
+
+/// Removes the terminal colour codes cargo-stylus wraps its output in.
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
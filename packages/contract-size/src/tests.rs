@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use crate::{
+    budget::Budgets,
+    check_budgets, component,
+    stylus::{self, Measurement},
+};
+
+/// `cargo stylus check` output, after the colours are stripped.
+const CHECK_OUTPUT: &str = "\
+Building project with Cargo.toml version: 0.1.0
+    Finished `release` profile [optimized] target(s) in 0.61s
+stripped custom section from user wasm to remove any sensitive data
+contract size: 21.5 KiB (22051 bytes)
+wasm size: 78.3 KiB (80164 bytes)
+File used for deployment hash: ./Cargo.lock
+stripped custom section from user wasm to remove any sensitive data
+contract size: 21.5 KiB (22051 bytes)
+wasm data fee: 0.000187 ETH (originally 0.000156 ETH with 20% bump)
+";
+
+fn measurement(contract_size: u64, data_fee_gwei: u64) -> Measurement {
+    Measurement { contract_size, wasm_size: contract_size * 4, data_fee_gwei }
+}
+
+#[test]
+fn reads_sizes_and_the_bumped_data_fee() {
+    let measured = stylus::parse(CHECK_OUTPUT).unwrap();
+    assert_eq!(measured, Measurement { contract_size: 22051, wasm_size: 80164, data_fee_gwei: 187_000 });
+}
+
+#[test]
+fn reads_sizes_without_exact_bytes() {
+    let output = CHECK_OUTPUT.replace(" (22051 bytes)", "").replace(" (80164 bytes)", "").replace("KiB", "KB");
+    let measured = stylus::parse(&output).unwrap();
+    assert_eq!((measured.contract_size, measured.wasm_size), (21500, 78300));
+}
+
+#[test]
+fn fails_on_output_without_a_fee() {
+    let output: String =
+        CHECK_OUTPUT.lines().filter(|line| !line.contains("fee")).map(|line| line.to_owned() + "\n").collect();
+    let error = stylus::parse(&output).unwrap_err();
+    assert!(error.to_string().contains("wasm data fee"), "{error}");
+}
+
+#[test]
+fn components_override_default_limits() {
+    let budgets = Budgets::parse(
+        r#"
+        [default]
+        contract-size = 20000
+        data-fee-gwei = 150000
+
+        [components.big]
+        contract-size = 24000
+        features = ["a", "a,b"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(budgets.builds("small"), vec![Vec::<String>::new()]);
+    assert_eq!(budgets.builds("big"), vec![vec![], vec!["a".to_string()], vec!["a".into(), "b".into()]]);
+
+    assert!(budgets.limits("small").exceeded(&measurement(20000, 150000)).is_empty());
+    let over = budgets.limits("small").exceeded(&measurement(20001, 150001));
+    assert_eq!(
+        over,
+        ["contract size 20001 bytes is over the budget of 20000", "data fee 150001 gwei is over the budget of 150000"]
+    );
+    let big = budgets.limits("big");
+    assert_eq!((big.contract_size, big.data_fee_gwei), (Some(24000), Some(150000)));
+}
+
+#[test]
+fn committed_budgets_name_real_components_and_features() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let budgets = Budgets::load(&root.join("budgets.toml")).unwrap();
+    let components = component::all(&root.join("../components")).unwrap();
+    check_budgets(&budgets, &components).unwrap();
+}
@@ -0,0 +1,69 @@
+//! Encoding calls from the generated ABI in `packages/abis`.
+
+use std::{fs, path::Path};
+
+use ethabi::token::{LenientTokenizer, Tokenizer};
+use eyre::{bail, eyre, WrapErr};
+use serde::Deserialize;
+
+/// `packages/abis/abis/<component>.json`.
+#[derive(Deserialize)]
+struct Artifact {
+    version: String,
+    abi: ethabi::Contract,
+}
+
+pub struct Abi {
+    /// Version of the contract crate the ABI was generated from
+    pub version: String,
+    contract: ethabi::Contract,
+}
+
+/// Calldata for one method, with the arguments it was encoded from.
+pub struct Encoded {
+    pub signature: String,
+    pub args: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+impl Abi {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let json = fs::read(path).wrap_err_with(|| {
+            format!("no ABI at {}, generate it with `cargo run` in packages/abis/generator", path.display())
+        })?;
+        let artifact: Artifact =
+            serde_json::from_slice(&json).wrap_err_with(|| format!("invalid ABI {}", path.display()))?;
+        Ok(Self { version: artifact.version, contract: artifact.abi })
+    }
+
+    pub fn has_function(&self, name: &str) -> bool {
+        self.contract.functions.contains_key(name)
+    }
+
+    /// Encodes a call of `name`, picking the overload that takes as many arguments as `args`.
+    pub fn encode(&self, name: &str, args: Vec<String>) -> eyre::Result<Encoded> {
+        let Some(overloads) = self.contract.functions.get(name) else { bail!("the contract has no method `{name}`") };
+        let Some(function) = overloads.iter().find(|function| function.inputs.len() == args.len()) else {
+            let signatures: Vec<_> = overloads.iter().map(signature).collect();
+            bail!("no `{name}` takes {} arguments, the contract has {}", args.len(), signatures.join(" and "));
+        };
+        let tokens = function
+            .inputs
+            .iter()
+            .zip(&args)
+            .map(|(param, arg)| {
+                LenientTokenizer::tokenize(&param.kind, arg)
+                    .map_err(|e| eyre!("{e}"))
+                    .wrap_err_with(|| format!("`{arg}` isn't a valid {} for `{}` of `{name}`", param.kind, param.name))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        let data = function.encode_input(&tokens)?;
+        Ok(Encoded { signature: signature(function), args, data })
+    }
+}
+
+/// `name(type,...)`, without the outputs `ethabi`'s own signature adds.
+fn signature(function: &ethabi::Function) -> String {
+    let inputs: Vec<_> = function.inputs.iter().map(|param| param.kind.to_string()).collect();
+    format!("{}({})", function.name, inputs.join(","))
+}
@@ -0,0 +1,147 @@
+//! Deploying with cargo-stylus and sending the setup transactions.
+
+use std::{path::Path, process::Command};
+
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::{address, Address, B256};
+use alloy_provider::{DynProvider, Provider, ProviderBuilder};
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{sol, SolCall};
+use eyre::{ensure, eyre, WrapErr};
+
+sol! {
+    /// The ArbOS precompile that activates Stylus programs.
+    interface ArbWasm {
+        function programVersion(address program) external view returns (uint16 version);
+    }
+
+    /// Components built with `cradle-build-info`.
+    interface BuildInfo {
+        function buildMetadata() external view returns (bytes32 sourceHash, string version);
+    }
+}
+
+const ARB_WASM: Address = address!("0000000000000000000000000000000000000071");
+
+type TransactionRequest = <alloy_network::Ethereum as alloy_network::Network>::TransactionRequest;
+
+/// What `cargo stylus deploy` reported.
+pub struct Deployed {
+    pub address: Address,
+    pub transaction: Option<B256>,
+}
+
+/// A node and the account that deploys and sets up the contract.
+pub struct Chain {
+    provider: DynProvider,
+    rpc_url: String,
+    private_key: String,
+    pub deployer: Address,
+    pub chain_id: u64,
+}
+
+impl Chain {
+    pub async fn connect(rpc_url: &str, private_key: &str) -> eyre::Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse().wrap_err("invalid private key")?;
+        let deployer = signer.address();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(rpc_url.parse().wrap_err("invalid RPC URL")?)
+            .erased();
+        let chain_id = provider.get_chain_id().await.wrap_err_with(|| format!("no node at {rpc_url}"))?;
+        Ok(Self { provider, rpc_url: rpc_url.into(), private_key: private_key.into(), deployer, chain_id })
+    }
+
+    pub fn provider(&self) -> &DynProvider {
+        &self.provider
+    }
+
+    /// Deploys and activates the contract crate in `dir` with `cargo stylus deploy`. Unless
+    /// `reproducible`, it's built with the local toolchain rather than in Docker, which is faster
+    /// but can't be checked with `cargo stylus verify`, and with `CRADLE_DEPLOYER` set to the
+    /// deployer, so components that read it only let the deployer initialize them.
+    pub fn deploy(&self, dir: &Path, features: &[String], reproducible: bool) -> eyre::Result<Deployed> {
+        let mut command = Command::new("cargo");
+        command.args(["stylus", "deploy", "--endpoint", &self.rpc_url, "--private-key", &self.private_key]);
+        if !reproducible {
+            command.arg("--no-verify").env("CRADLE_DEPLOYER", self.deployer.to_string());
+        }
+        if !features.is_empty() {
+            command.args(["--features", &features.join(",")]);
+        }
+        let log = run(command.current_dir(dir))?;
+
+        let find =
+            |label: &str| log.lines().find(|line| line.contains(label)).and_then(|line| line.split_whitespace().last());
+        let address = find("deployed code at address")
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(|| eyre!("no contract address in cargo stylus output:\n{log}"))?;
+        let transaction = find("deployment tx hash").and_then(|word| word.parse().ok());
+        Ok(Deployed { address, transaction })
+    }
+
+    /// The Stylus version `address` was activated with. Fails if it has no code or ArbOS hasn't
+    /// activated it, in which case calls to it would revert.
+    pub async fn activated_version(&self, address: Address) -> eyre::Result<u16> {
+        let code = self.provider.get_code_at(address).await?;
+        ensure!(!code.is_empty(), "no code at {address}");
+        let call = ArbWasm::programVersionCall { program: address };
+        let tx = TransactionRequest::default().with_to(ARB_WASM).with_input(call.abi_encode());
+        let output = self.provider.call(&tx).await.wrap_err_with(|| format!("{address} isn't activated"))?;
+        Ok(ArbWasm::programVersionCall::abi_decode_returns(&output, true)?.version)
+    }
+
+    /// Sends `data` to `to` and waits for it to succeed.
+    pub async fn send(&self, to: Address, data: Vec<u8>) -> eyre::Result<B256> {
+        let tx = TransactionRequest::default().with_to(to).with_input(data);
+        let receipt = self.provider.send_transaction(tx).await?.get_receipt().await?;
+        ensure!(receipt.status(), "transaction {} reverted", receipt.transaction_hash);
+        Ok(receipt.transaction_hash)
+    }
+}
+
+/// A node to read from, without an account.
+pub async fn reader(rpc_url: &str) -> eyre::Result<(DynProvider, u64)> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().wrap_err("invalid RPC URL")?).erased();
+    let chain_id = provider.get_chain_id().await.wrap_err_with(|| format!("no node at {rpc_url}"))?;
+    Ok((provider, chain_id))
+}
+
+/// The source hash and crate version a component reports it was built from.
+pub async fn build_metadata(provider: &DynProvider, address: Address) -> eyre::Result<(B256, String)> {
+    let tx = TransactionRequest::default().with_to(address).with_input(BuildInfo::buildMetadataCall {}.abi_encode());
+    let output = provider.call(&tx).await.wrap_err_with(|| format!("{address} has no buildMetadata()"))?;
+    let metadata = BuildInfo::buildMetadataCall::abi_decode_returns(&output, true)?;
+    Ok((metadata.sourceHash, metadata.version))
+}
+
+/// Rebuilds the contract crate in `dir` in cargo-stylus's Docker image and checks that it's the
+/// code `deployment` deployed.
+pub fn reproduce(dir: &Path, rpc_url: &str, deployment: B256) -> eyre::Result<()> {
+    let mut command = Command::new("cargo");
+    command.args(["stylus", "verify", "--endpoint", rpc_url, "--deployment-tx", &deployment.to_string()]);
+    run(command.current_dir(dir)).map(drop)
+}
+
+/// Runs a cargo-stylus command, returning its output without colours.
+fn run(command: &mut Command) -> eyre::Result<String> {
+    let output = command.output().wrap_err("failed to run cargo stylus, is cargo-stylus installed?")?;
+    let log =
+        strip_ansi(&format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)));
+    ensure!(output.status.success(), "cargo stylus failed:\n{log}");
+    Ok(log)
+}
+
+/// Removes the terminal colour codes cargo-stylus wraps its output in.
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
@@ -0,0 +1,63 @@
+//! The TOML file describing one deployment.
+
+use std::{fs, path::Path};
+
+use eyre::{bail, WrapErr};
+use serde::Deserialize;
+
+/// A component to deploy and the calls that set it up.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Directory in `packages/components`, like `erc1155-stylus`
+    pub component: String,
+    /// Key in the deployments manifest, the component by default. Give deployments of the same
+    /// component different names to keep them all in the manifest.
+    pub name: Option<String>,
+    /// Features to build the contract with
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Arguments of `initialize`, required if the contract has one
+    pub initialize: Option<Vec<toml::Value>>,
+    /// Calls to send after `initialize`, in order
+    #[serde(default)]
+    pub calls: Vec<Call>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Call {
+    /// Method name as in the ABI, like `grantRole`
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<toml::Value>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).wrap_err_with(|| format!("invalid config {}", path.display()))
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.component)
+    }
+}
+
+/// An argument as text for the ABI tokenizer, with `$deployer` and `$<deployment>` replaced by
+/// `resolve`. Arrays are written `[a,b]`.
+pub fn argument(value: &toml::Value, resolve: &impl Fn(&str) -> eyre::Result<String>) -> eyre::Result<String> {
+    Ok(match value {
+        toml::Value::String(text) => match text.strip_prefix('$') {
+            Some(reference) => resolve(reference)?,
+            None => text.clone(),
+        },
+        toml::Value::Integer(number) => number.to_string(),
+        toml::Value::Boolean(flag) => flag.to_string(),
+        toml::Value::Array(items) => {
+            let items = items.iter().map(|item| argument(item, resolve)).collect::<eyre::Result<Vec<_>>>()?;
+            format!("[{}]", items.join(","))
+        }
+        other => bail!("unsupported argument {other}, use a string, integer, boolean or array"),
+    })
+}
@@ -0,0 +1,116 @@
+//! The `deploy` command.
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::{bail, ensure, eyre};
+
+use crate::{
+    abi::Encoded,
+    chain::{self, Chain},
+    config::{self, Config},
+    manifest::{Call, Deployment, Manifest},
+    Network, DEV_NODE_KEY,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Deployment config, see the README
+    config: PathBuf,
+    #[command(flatten)]
+    network: Network,
+    /// Key of the deployer, the dev node's prefunded key on `local`
+    #[arg(long, env = "PRIVATE_KEY", hide_env_values = true)]
+    private_key: Option<String>,
+    /// Build in cargo-stylus's Docker image, so `verify` can reproduce the build
+    #[arg(long)]
+    reproducible: bool,
+}
+
+pub async fn run(args: Args) -> eyre::Result<()> {
+    let config = Config::load(&args.config)?;
+    let contract = crate::contract_crate(&config.component)?;
+    let abi = crate::component_abi(&config.component)?;
+    let source_hash = crate::source_hash(&contract)?;
+
+    let private_key = match (&args.private_key, args.network.network.as_str()) {
+        (Some(key), _) => key.clone(),
+        (None, "local") => DEV_NODE_KEY.into(),
+        (None, _) => bail!("give the deployer's --private-key or set PRIVATE_KEY"),
+    };
+    let chain = Chain::connect(&args.network.rpc_url()?, &private_key).await?;
+    let manifest_path = args.network.manifest();
+    let mut manifest = Manifest::load(&manifest_path, &args.network.network, chain.chain_id)?;
+
+    // Encode every call up front, so a mistake in the config doesn't leave a contract half set up
+    let resolve = |reference: &str| match reference {
+        "deployer" => Ok(chain.deployer.to_string()),
+        name => manifest
+            .deployments
+            .get(name)
+            .map(|deployment| deployment.address.to_string())
+            .ok_or_else(|| eyre!("`${name}` isn't `$deployer` or a deployment in {}", manifest_path.display())),
+    };
+    let encode = |function: &str, args: &[toml::Value]| -> eyre::Result<Encoded> {
+        let args = args.iter().map(|arg| config::argument(arg, &resolve)).collect::<eyre::Result<_>>()?;
+        abi.encode(function, args)
+    };
+    let mut calls = Vec::new();
+    match &config.initialize {
+        Some(args) => calls.push(encode("initialize", args)?),
+        // Anyone could initialize it otherwise
+        None => {
+            ensure!(!abi.has_function("initialize"), "{} has an `initialize`, give its arguments", config.component)
+        }
+    }
+    for call in &config.calls {
+        calls.push(encode(&call.function, &call.args)?);
+    }
+
+    println!("Deploying {} {} to {} as {}", config.component, abi.version, args.network.network, chain.deployer);
+    let deployed = chain.deploy(&contract, &config.features, args.reproducible)?;
+    let program_version = chain.activated_version(deployed.address).await?;
+    println!("Deployed to {}, activated with Stylus version {program_version}", deployed.address);
+    if abi.has_function("buildMetadata") {
+        let (deployed_hash, _) = chain::build_metadata(chain.provider(), deployed.address).await?;
+        ensure!(
+            deployed_hash == source_hash,
+            "deployed source hash {deployed_hash} isn't the checkout's {source_hash}"
+        );
+    }
+
+    let mut sent = Vec::new();
+    for call in calls {
+        let transaction = chain.send(deployed.address, call.data).await?;
+        println!("{}({}) in {transaction}", call.signature, call.args.join(", "));
+        sent.push(Call { function: call.signature, args: call.args, transaction });
+    }
+
+    let deployment = Deployment {
+        component: config.component.clone(),
+        version: abi.version,
+        features: config.features.clone(),
+        address: deployed.address,
+        deployer: chain.deployer,
+        program_version,
+        transaction: deployed.transaction,
+        source_hash,
+        reproducible: args.reproducible,
+        calls: sent,
+        verifications: Vec::new(),
+        deployed_at: now()?,
+    };
+    if let Some(previous) = manifest.deployments.insert(config.name().into(), deployment) {
+        println!("Replaced the previous {} at {}", config.name(), previous.address);
+    }
+    manifest.save(&manifest_path)?;
+    println!("Wrote {}", manifest_path.display());
+    Ok(())
+}
+
+/// Unix time.
+pub fn now() -> eyre::Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
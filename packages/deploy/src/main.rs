@@ -0,0 +1,118 @@
+//! Deploys Cradle components, records them in per-network manifests, and verifies them.
+//!
+//! `deploy` takes a TOML config naming the component and the arguments of its `initialize` and of
+//! any calls that finish setting it up, such as granting roles or setting URIs. The calls are
+//! encoded against the component's ABI in `packages/abis` before anything is sent. The contract
+//! is then deployed with `cargo stylus deploy`, checked to be activated, initialized and set up,
+//! and its address written to `deployments/<network>.json`.
+//!
+//! `verify` checks a deployment in the manifest against the local sources, reproduces its build
+//! with `cargo stylus verify`, and submits its sources and build metadata to a verifier.
+
+mod abi;
+mod chain;
+mod config;
+mod deploy;
+mod manifest;
+#[cfg(test)]
+mod tests;
+mod verify;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::B256;
+use clap::{Parser, Subcommand};
+use eyre::{bail, ensure, eyre, OptionExt};
+
+/// The prefunded account of `nitro-devnode`.
+const DEV_NODE_KEY: &str = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9acb659";
+
+/// Networks that don't need `--rpc-url`.
+const NETWORKS: [(&str, &str); 3] = [
+    ("arbitrum", "https://arb1.arbitrum.io/rpc"),
+    ("arbitrum-sepolia", "https://sepolia-rollup.arbitrum.io/rpc"),
+    ("local", "http://localhost:8547"),
+];
+
+#[derive(Parser)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy, initialize and set up a component
+    Deploy(deploy::Args),
+    /// Check a deployment against the sources and submit it to a verifier
+    Verify(verify::Args),
+}
+
+/// The node to use and the manifest to record in.
+#[derive(clap::Args)]
+struct Network {
+    /// `arbitrum`, `arbitrum-sepolia`, `local`, or any other name with `--rpc-url`
+    #[arg(long)]
+    network: String,
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: Option<String>,
+    /// Directory of the per-network manifests
+    #[arg(long, default_value = "deployments")]
+    manifests: PathBuf,
+}
+
+impl Network {
+    fn rpc_url(&self) -> eyre::Result<String> {
+        match (&self.rpc_url, NETWORKS.iter().find(|(name, _)| *name == self.network)) {
+            (Some(url), _) => Ok(url.clone()),
+            (None, Some((_, url))) => Ok(url.to_string()),
+            (None, None) => bail!("unknown network {}, give its --rpc-url", self.network),
+        }
+    }
+
+    fn manifest(&self) -> PathBuf {
+        self.manifests.join(format!("{}.json", self.network))
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    match Args::parse().command {
+        Command::Deploy(args) => deploy::run(args).await,
+        Command::Verify(args) => verify::run(args).await,
+    }
+}
+
+/// `packages`, where the components and their ABIs are.
+fn packages() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// The one crate in the component's `contract` directory.
+fn contract_crate(component: &str) -> eyre::Result<PathBuf> {
+    let component = packages().join("components").join(component);
+    let entries = fs::read_dir(component.join("contract"))
+        .map_err(|_| eyre!("{} isn't a component with a contract", component.display()))?;
+    let mut crates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.join("Cargo.toml").exists() {
+            crates.push(path);
+        }
+    }
+    ensure!(crates.len() <= 1, "{} has more than one contract crate", component.display());
+    crates.pop().ok_or_eyre(format!("{} has no contract crate", component.display()))
+}
+
+/// The component's ABI in `packages/abis`.
+fn component_abi(component: &str) -> eyre::Result<abi::Abi> {
+    abi::Abi::load(&packages().join("abis/abis").join(format!("{component}.json")))
+}
+
+/// What the contract crate's `build_metadata()` returns when built from this checkout.
+fn source_hash(contract: &Path) -> eyre::Result<B256> {
+    Ok(cradle_build_info::source_hash(contract)?.into())
+}
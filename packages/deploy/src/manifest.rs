@@ -0,0 +1,90 @@
+//! The per-network record of what's deployed where.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use alloy_primitives::{Address, B256};
+use eyre::{ensure, WrapErr};
+use serde::{Deserialize, Serialize};
+
+/// `deployments/<network>.json`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub network: String,
+    pub chain_id: u64,
+    /// By the config's `name`, which defaults to the component
+    pub deployments: BTreeMap<String, Deployment>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+    pub component: String,
+    /// Version of the contract crate
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    pub address: Address,
+    pub deployer: Address,
+    /// Stylus version ArbOS activated the program with
+    pub program_version: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<B256>,
+    /// `cradle-build-info` hash of the sources it was deployed from
+    pub source_hash: B256,
+    /// Built in cargo-stylus's Docker image, so `cargo stylus verify` can reproduce it
+    #[serde(default)]
+    pub reproducible: bool,
+    /// `initialize` and the other setup calls, in the order they were sent
+    pub calls: Vec<Call>,
+    /// Submissions to verifiers, latest last
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verifications: Vec<Verification>,
+    /// Unix time
+    pub deployed_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Call {
+    pub function: String,
+    pub args: Vec<String>,
+    pub transaction: B256,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+    /// `sourcify` or `etherscan`
+    pub verifier: String,
+    pub url: String,
+    /// What the verifier answered, like a match status or a request GUID
+    pub response: String,
+    /// Unix time
+    pub submitted_at: u64,
+}
+
+impl Manifest {
+    /// Reads the manifest at `path`, or starts one if there's none yet.
+    pub fn load(path: &Path, network: &str, chain_id: u64) -> eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self { network: network.into(), chain_id, deployments: BTreeMap::new() });
+        }
+        let manifest: Self = serde_json::from_slice(&fs::read(path)?)
+            .wrap_err_with(|| format!("invalid deployments manifest {}", path.display()))?;
+        ensure!(
+            manifest.chain_id == chain_id,
+            "{} is for chain {}, but the node is on chain {chain_id}",
+            path.display(),
+            manifest.chain_id
+        );
+        Ok(manifest)
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
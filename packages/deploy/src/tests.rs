@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use alloy_primitives::{address, hex, keccak256, Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall};
+
+use crate::{
+    abi::Abi,
+    config::{self, Config},
+};
+
+sol! {
+    function initialize(string uri, address admin);
+    function mintBatch(address to, uint256[] ids, uint256[] amounts, bytes data);
+}
+
+const DEPLOYER: Address = address!("3f1eae7d46d88f08fc2f8ed27fcb2ab183eb2d0e");
+
+fn erc1155() -> Abi {
+    Abi::load(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../abis/abis/erc1155-stylus.json")).unwrap()
+}
+
+fn arguments(toml: &str) -> eyre::Result<Vec<String>> {
+    let value: toml::Table = toml::from_str(toml).unwrap();
+    let resolve = |reference: &str| match reference {
+        "deployer" => Ok(DEPLOYER.to_string()),
+        name => Err(eyre::eyre!("no deployment {name}")),
+    };
+    value["args"].as_array().unwrap().iter().map(|arg| config::argument(arg, &resolve)).collect()
+}
+
+#[test]
+fn encodes_initialize_with_the_deployer() {
+    let args = arguments(r#"args = ["ipfs://tokens/{id}.json", "$deployer"]"#).unwrap();
+    let encoded = erc1155().encode("initialize", args).unwrap();
+    let expected = initializeCall { uri: "ipfs://tokens/{id}.json".into(), admin: DEPLOYER };
+    assert_eq!(encoded.signature, "initialize(string,address)");
+    assert_eq!(encoded.data, expected.abi_encode());
+}
+
+#[test]
+fn encodes_arrays_and_hex() {
+    let role = keccak256("BRIDGE_ROLE");
+    let args = arguments(&format!(r#"args = ["{role}", "$deployer"]"#)).unwrap();
+    let encoded = erc1155().encode("grantRole", args).unwrap();
+    assert_eq!(encoded.data[..4], hex!("2f2ff15d"));
+
+    let args = arguments(r#"args = ["$deployer", [1, 2], [10, 20], "0x"]"#).unwrap();
+    let encoded = erc1155().encode("mintBatch", args).unwrap();
+    let expected = mintBatchCall {
+        to: DEPLOYER,
+        ids: vec![U256::from(1), U256::from(2)],
+        amounts: vec![U256::from(10), U256::from(20)],
+        data: Bytes::new(),
+    };
+    assert_eq!(encoded.data, expected.abi_encode());
+}
+
+#[test]
+fn rejects_bad_calls() {
+    assert!(arguments(r#"args = ["$token"]"#).is_err());
+    let abi = erc1155();
+    assert!(abi.encode("initialise", vec![]).is_err());
+    assert!(abi.encode("initialize", vec!["ipfs://".into()]).is_err());
+    assert!(abi.encode("initialize", vec!["ipfs://".into(), "not an address".into()]).is_err());
+}
+
+#[test]
+fn example_config_encodes() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/erc1155.toml");
+    let config = Config::load(&path).unwrap();
+    let resolve = |_: &str| Ok(DEPLOYER.to_string());
+    let args = |args: &[toml::Value]| -> eyre::Result<Vec<String>> {
+        args.iter().map(|arg| config::argument(arg, &resolve)).collect()
+    };
+    let abi = erc1155();
+    abi.encode("initialize", args(config.initialize.as_ref().unwrap()).unwrap()).unwrap();
+    let grant = abi.encode(&config.calls[0].function, args(&config.calls[0].args).unwrap()).unwrap();
+    assert_eq!(grant.args[0], keccak256("BRIDGE_ROLE").to_string());
+}
+
+#[test]
+fn reads_the_pinned_toolchain() {
+    let contract = crate::contract_crate("erc1155-stylus").unwrap();
+    assert_eq!(crate::verify::toolchain(&contract).unwrap(), "1.87.0");
+}
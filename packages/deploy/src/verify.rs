@@ -0,0 +1,203 @@
+//! The `verify` command.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use alloy_primitives::{Address, B256};
+use clap::ValueEnum;
+use eyre::{bail, ensure, OptionExt, WrapErr};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    chain,
+    deploy::now,
+    manifest::{Deployment, Manifest, Verification},
+    Network,
+};
+
+const SOURCIFY_URL: &str = "https://sourcify.dev/server";
+/// Etherscan's multichain API, which serves Arbiscan too.
+const ETHERSCAN_URL: &str = "https://api.etherscan.io/v2/api";
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Name of the deployment in the manifest
+    name: String,
+    #[command(flatten)]
+    network: Network,
+    /// Where to submit the sources and metadata; without one, only checks and writes the metadata
+    #[arg(long)]
+    verifier: Option<Verifier>,
+    /// API of the verifier, `https://sourcify.dev/server` or `https://api.etherscan.io/v2/api` by default
+    #[arg(long)]
+    verifier_url: Option<String>,
+    #[arg(long, env = "ETHERSCAN_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+    /// `codeformat` of the Etherscan request
+    #[arg(long, default_value = "stylus")]
+    code_format: String,
+    /// Skip rebuilding the contract in Docker with `cargo stylus verify`
+    #[arg(long)]
+    no_reproduce: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Verifier {
+    /// Sourcify's `POST /verify`, with every file in the request
+    Sourcify,
+    /// Etherscan-compatible `verifysourcecode`, with the files as JSON input
+    Etherscan,
+}
+
+/// Build metadata published with the sources, and written next to the manifest.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    pub component: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub address: Address,
+    pub deployment_transaction: Option<B256>,
+    pub features: Vec<String>,
+    /// `rust-toolchain.toml` channel the contract is built with
+    pub toolchain: String,
+    pub source_hash: B256,
+    /// Whether `cargo stylus verify` rebuilt the deployed code from these sources
+    pub reproduced: bool,
+    /// Hashed file names, like `erc1155/src/lib.rs`
+    pub sources: Vec<String>,
+}
+
+pub async fn run(args: Args) -> eyre::Result<()> {
+    let rpc_url = args.network.rpc_url()?;
+    let (provider, chain_id) = chain::reader(&rpc_url).await?;
+    let manifest_path = args.network.manifest();
+    let mut manifest = Manifest::load(&manifest_path, &args.network.network, chain_id)?;
+    let deployment = manifest.deployments.get(&args.name).ok_or_eyre(format!(
+        "no deployment {} in {}",
+        args.name,
+        manifest_path.display()
+    ))?;
+    let contract = crate::contract_crate(&deployment.component)?;
+
+    // The checkout, the manifest and the contract must agree on the sources
+    let source_hash = crate::source_hash(&contract)?;
+    ensure!(
+        source_hash == deployment.source_hash,
+        "the checkout's source hash {source_hash} isn't the deployed {}, check out the commit it was deployed from",
+        deployment.source_hash
+    );
+    if crate::component_abi(&deployment.component)?.has_function("buildMetadata") {
+        let (deployed_hash, version) = chain::build_metadata(&provider, deployment.address).await?;
+        ensure!(deployed_hash == source_hash, "{} reports source hash {deployed_hash}", deployment.address);
+        ensure!(version == deployment.version, "{} reports version {version}", deployment.address);
+    }
+    println!("Sources match {} at {}", args.name, deployment.address);
+
+    let reproduced = !args.no_reproduce;
+    if reproduced {
+        ensure!(deployment.reproducible, "{} wasn't deployed with --reproducible, pass --no-reproduce", args.name);
+        let transaction = deployment.transaction.ok_or_eyre("the manifest has no deployment transaction")?;
+        println!("Reproducing the build with cargo stylus verify");
+        chain::reproduce(&contract, &rpc_url, transaction)?;
+    }
+
+    let files = cradle_build_info::source_files(&contract)?;
+    let metadata = Metadata {
+        component: deployment.component.clone(),
+        version: deployment.version.clone(),
+        chain_id,
+        address: deployment.address,
+        deployment_transaction: deployment.transaction,
+        features: deployment.features.clone(),
+        toolchain: toolchain(&contract)?,
+        source_hash,
+        reproduced,
+        sources: files.keys().cloned().collect(),
+    };
+    let metadata = serde_json::to_string_pretty(&metadata)? + "\n";
+    let metadata_path = args.network.manifests.join(&args.network.network).join(format!("{}.metadata.json", args.name));
+    fs::create_dir_all(metadata_path.parent().unwrap())?;
+    fs::write(&metadata_path, &metadata)?;
+    println!("Wrote {}", metadata_path.display());
+
+    let Some(verifier) = args.verifier else { return Ok(()) };
+    let mut sources = BTreeMap::new();
+    for (name, path) in files {
+        sources.insert(name, fs::read_to_string(&path).wrap_err_with(|| format!("{} isn't text", path.display()))?);
+    }
+    sources.insert("metadata.json".into(), metadata);
+    let client = reqwest::Client::new();
+    let verification = match verifier {
+        Verifier::Sourcify => {
+            let url = args.verifier_url.unwrap_or_else(|| SOURCIFY_URL.into());
+            sourcify(&client, &url, chain_id, deployment, sources).await?
+        }
+        Verifier::Etherscan => {
+            let url = args.verifier_url.unwrap_or_else(|| ETHERSCAN_URL.into());
+            let api_key = args.api_key.ok_or_eyre("give the verifier's --api-key or set ETHERSCAN_API_KEY")?;
+            etherscan(&client, &url, &api_key, &args.code_format, chain_id, deployment, sources).await?
+        }
+    };
+    println!("Submitted to {}: {}", verification.url, verification.response);
+
+    manifest.deployments.get_mut(&args.name).unwrap().verifications.push(verification);
+    manifest.save(&manifest_path)?;
+    println!("Wrote {}", manifest_path.display());
+    Ok(())
+}
+
+async fn sourcify(
+    client: &reqwest::Client,
+    url: &str,
+    chain_id: u64,
+    deployment: &Deployment,
+    files: BTreeMap<String, String>,
+) -> eyre::Result<Verification> {
+    let url = format!("{}/verify", url.trim_end_matches('/'));
+    let body = json!({ "address": deployment.address, "chain": chain_id.to_string(), "files": files });
+    let response: serde_json::Value = client.post(&url).json(&body).send().await?.json().await?;
+    let Some(status) = response["result"][0]["status"].as_str() else {
+        bail!("{url} rejected the sources: {}", response["error"].as_str().unwrap_or(&response.to_string()));
+    };
+    Ok(Verification { verifier: "sourcify".into(), url, response: status.into(), submitted_at: now()? })
+}
+
+async fn etherscan(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: &str,
+    code_format: &str,
+    chain_id: u64,
+    deployment: &Deployment,
+    files: BTreeMap<String, String>,
+) -> eyre::Result<Verification> {
+    let sources: BTreeMap<_, _> =
+        files.into_iter().map(|(name, content)| (name, json!({ "content": content }))).collect();
+    let input = json!({ "language": "Rust", "sources": sources });
+    let form = [
+        ("apikey", api_key.to_string()),
+        ("module", "contract".into()),
+        ("action", "verifysourcecode".into()),
+        ("contractaddress", deployment.address.to_string()),
+        ("contractname", deployment.component.clone()),
+        ("codeformat", code_format.into()),
+        ("sourceCode", input.to_string()),
+    ];
+    let response: serde_json::Value =
+        client.post(url).query(&[("chainid", chain_id)]).form(&form).send().await?.json().await?;
+    let result = response["result"].as_str().unwrap_or_default().to_string();
+    ensure!(response["status"] == "1", "{url} rejected the sources: {result}");
+    Ok(Verification { verifier: "etherscan".into(), url: url.into(), response: result, submitted_at: now()? })
+}
+
+/// The toolchain channel the contract crate pins.
+pub fn toolchain(contract: &Path) -> eyre::Result<String> {
+    let path = contract.join("rust-toolchain.toml");
+    let file: toml::Table = toml::from_str(&fs::read_to_string(&path)?)?;
+    file.get("toolchain")
+        .and_then(|toolchain| toolchain.get("channel"))
+        .and_then(|channel| channel.as_str())
+        .map(String::from)
+        .ok_or_eyre(format!("{} pins no channel", path.display()))
+}
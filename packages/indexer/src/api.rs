@@ -0,0 +1,125 @@
+//! The JSON API over the store.
+//!
+//! Amounts and IDs are decimal strings, since they don't fit JSON numbers, and addresses
+//! lowercase hex. Paths take IDs in decimal or `0x` hex and addresses in either case.
+
+use alloy_primitives::{Address, U256};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::store::{Balance, RoleMember, Store, Token, Transfer};
+
+/// Transfers a `/transfers` without a `limit` returns, and the most one with a limit does.
+const TRANSFERS: u32 = 100;
+const MAX_TRANSFERS: u32 = 1000;
+
+pub fn router(store: Store) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/{contract}/tokens/{id}", get(token))
+        .route("/{contract}/tokens/{id}/holders", get(holders))
+        .route("/{contract}/accounts/{account}/balances", get(balances))
+        .route("/{contract}/roles", get(roles))
+        .route("/{contract}/transfers", get(transfers))
+        .with_state(store)
+}
+
+/// A request the API can't answer, as `{"error": ..}`.
+#[derive(Debug)]
+pub enum ApiError {
+    /// A path segment or query parameter isn't valid
+    BadRequest(String),
+    NotFound(String),
+    Store(eyre::Report),
+}
+
+impl From<eyre::Report> for ApiError {
+    fn from(e: eyre::Report) -> Self {
+        Self::Store(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            Self::Store(e) => {
+                eprintln!("query failed: {e:#}");
+                (StatusCode::INTERNAL_SERVER_ERROR, String::from("the index couldn't be read"))
+            }
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Serialize)]
+struct Cursor {
+    contract: String,
+    /// Last block indexed
+    block: u64,
+}
+
+#[derive(Deserialize)]
+struct TransfersQuery {
+    account: Option<String>,
+    limit: Option<u32>,
+}
+
+/// How far each contract has been indexed.
+async fn status(State(store): State<Store>) -> ApiResult<Vec<Cursor>> {
+    let cursors = store.cursors().await?;
+    Ok(Json(cursors.into_iter().map(|(contract, block)| Cursor { contract, block }).collect()))
+}
+
+async fn token(State(store): State<Store>, Path((contract, id)): Path<(String, String)>) -> ApiResult<Token> {
+    let (contract, id) = (address(&contract)?, token_id(&id)?);
+    let token = store.token(contract, id).await?;
+    Ok(Json(token.ok_or_else(|| ApiError::NotFound(format!("no transfers of ID {id}")))?))
+}
+
+async fn holders(State(store): State<Store>, Path((contract, id)): Path<(String, String)>) -> ApiResult<Vec<Balance>> {
+    Ok(Json(store.holders(address(&contract)?, token_id(&id)?).await?))
+}
+
+async fn balances(
+    State(store): State<Store>,
+    Path((contract, account)): Path<(String, String)>,
+) -> ApiResult<Vec<Balance>> {
+    Ok(Json(store.balances(address(&contract)?, address(&account)?).await?))
+}
+
+async fn roles(State(store): State<Store>, Path(contract): Path<String>) -> ApiResult<Vec<RoleMember>> {
+    Ok(Json(store.roles(address(&contract)?).await?))
+}
+
+/// The latest transfers, newest first, of `account` if it's given.
+async fn transfers(
+    State(store): State<Store>,
+    Path(contract): Path<String>,
+    Query(query): Query<TransfersQuery>,
+) -> ApiResult<Vec<Transfer>> {
+    let account = query.account.as_deref().map(address).transpose()?;
+    let limit = query.limit.unwrap_or(TRANSFERS);
+    if limit == 0 || limit > MAX_TRANSFERS {
+        return Err(ApiError::BadRequest(format!("limit must be between 1 and {MAX_TRANSFERS}")));
+    }
+    Ok(Json(store.transfers(address(&contract)?, account, limit).await?))
+}
+
+fn address(value: &str) -> Result<Address, ApiError> {
+    value.parse().map_err(|_| ApiError::BadRequest(format!("{value:?} isn't an address")))
+}
+
+fn token_id(value: &str) -> Result<U256, ApiError> {
+    value.parse().map_err(|_| ApiError::BadRequest(format!("{value:?} isn't a token ID")))
+}
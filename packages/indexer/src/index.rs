@@ -0,0 +1,123 @@
+//! Polling a node for the logs of the indexed contracts.
+
+use std::time::Duration;
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types_eth::{Filter, Log};
+use alloy_sol_types::{sol, SolEvent, SolEventInterface};
+use cradle_clients::{
+    erc1155::{Erc1155, IERC1155Token, IERC1155TokenEvents},
+    Error,
+};
+
+use crate::store::Store;
+
+sol! {
+    /// The metadata event of ERC-1155. erc1155-stylus doesn't emit it, but other tokens the
+    /// indexer is pointed at may.
+    event URI(string value, uint256 indexed id);
+}
+
+/// How many IDs without a URI are read from the contract per contract and poll.
+const URIS_PER_POLL: u32 = 100;
+
+/// What an indexed log changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// A `TransferSingle` is a batch of one
+    Transfer { operator: Address, from: Address, to: Address, ids: Vec<U256>, values: Vec<U256> },
+    Uri { id: U256, uri: String },
+    RoleGranted { role: B256, account: Address },
+    RoleRevoked { role: B256, account: Address },
+}
+
+/// An indexed log and where it is in the chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub block: u64,
+    pub log_index: u64,
+    pub transaction: B256,
+    pub change: Change,
+}
+
+/// What `log` changes, if it's one of the indexed events and its block is known.
+pub fn decode(log: &Log) -> Option<Event> {
+    let (block, log_index, transaction) = (log.block_number?, log.log_index?, log.transaction_hash?);
+    let (topics, data) = (log.inner.data.topics(), &log.inner.data.data);
+    let change = match IERC1155TokenEvents::decode_raw_log(topics, data, true) {
+        Ok(IERC1155TokenEvents::TransferSingle(e)) => {
+            Change::Transfer { operator: e.operator, from: e.from, to: e.to, ids: vec![e.id], values: vec![e.value] }
+        }
+        Ok(IERC1155TokenEvents::TransferBatch(e)) => {
+            Change::Transfer { operator: e.operator, from: e.from, to: e.to, ids: e.ids, values: e.values }
+        }
+        Ok(IERC1155TokenEvents::RoleGranted(e)) => Change::RoleGranted { role: e.role, account: e.account },
+        Ok(IERC1155TokenEvents::RoleRevoked(e)) => Change::RoleRevoked { role: e.role, account: e.account },
+        Ok(_) => return None,
+        Err(_) => {
+            let e = URI::decode_raw_log(topics, data, true).ok()?;
+            Change::Uri { id: e.id, uri: e.value }
+        }
+    };
+    Some(Event { block, log_index, transaction, change })
+}
+
+/// Follows the logs of `contracts` from `from_block` into the store.
+pub struct Indexer<P> {
+    pub provider: P,
+    pub store: Store,
+    pub contracts: Vec<Address>,
+    /// First block to index contracts the store has no cursor for
+    pub from_block: u64,
+    /// Blocks behind the head to stay, so reorgs shallower than this don't reach the index
+    pub confirmations: u64,
+    /// Most blocks per `eth_getLogs`
+    pub batch_blocks: u64,
+}
+
+impl<P: Provider + Clone> Indexer<P> {
+    /// Polls every `interval`. A failed poll is logged and retried on the next one, from the last
+    /// range that was applied.
+    pub async fn run(self, interval: Duration) {
+        loop {
+            if let Err(e) = self.poll().await {
+                eprintln!("indexing failed, retrying: {e:#}");
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Indexes every contract up to the confirmed head, then reads the URIs of new IDs.
+    pub async fn poll(&self) -> eyre::Result<()> {
+        let head = self.provider.get_block_number().await?.saturating_sub(self.confirmations);
+        for &contract in &self.contracts {
+            let mut next = self.store.cursor(contract).await?.map_or(self.from_block, |block| block + 1);
+            while next <= head {
+                let to = head.min(next + self.batch_blocks.max(1) - 1);
+                let filter = Filter::new().address(contract).from_block(next).to_block(to);
+                let logs = self.provider.get_logs(&filter).await?;
+                let events: Vec<_> = logs.iter().filter(|log| !log.removed).filter_map(decode).collect();
+                self.store.apply(contract, to, &events).await?;
+                next = to + 1;
+            }
+            self.read_uris(contract).await?;
+        }
+        Ok(())
+    }
+
+    /// Reads `uri(id)` of the IDs minted since the last poll. An ID whose `uri` reverts is
+    /// recorded with an empty URI rather than read again on every poll.
+    async fn read_uris(&self, contract: Address) -> eyre::Result<()> {
+        let token = Erc1155::new(contract, self.provider.clone());
+        for id in self.store.missing_uris(contract, URIS_PER_POLL).await? {
+            let uri = match token.call(IERC1155Token::uriCall { id }).call().await {
+                Ok(uri) => uri._0,
+                Err(Error::Revert(_) | Error::UnknownRevert(_) | Error::Decode(_)) => String::new(),
+                Err(e) => return Err(e.into()),
+            };
+            self.store.set_uri(contract, id, &uri).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Indexes the transfers, metadata and roles of Cradle's ERC-1155 components, and serves the
+//! balances, supplies, URIs and role members they add up to as JSON.
+//!
+//! The indexer polls a node for the `TransferSingle`, `TransferBatch`, `URI`, `RoleGranted` and
+//! `RoleRevoked` logs of every erc1155-stylus deployment `cradle-addresses` has for the node's
+//! chain, and of any other contract given with `--contract`, and applies them to a SQLite or
+//! Postgres database. It stays `--confirmations` blocks behind the head, so the index only
+//! follows blocks that won't be reorganized.
+
+mod api;
+mod index;
+mod store;
+#[cfg(test)]
+mod tests;
+
+use std::{net::SocketAddr, time::Duration};
+
+use alloy_primitives::Address;
+use alloy_provider::{Provider, ProviderBuilder};
+use clap::Parser;
+use cradle_addresses::Network;
+use eyre::ensure;
+
+use crate::{index::Indexer, store::Store};
+
+#[derive(Parser)]
+struct Args {
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+    /// `sqlite://index.db?mode=rwc`, `postgres://user@host/db`, ...
+    #[arg(long, env = "DATABASE_URL", default_value = "sqlite://index.db?mode=rwc")]
+    database_url: String,
+    /// Address to serve the API on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    /// Another contract to index, besides the chain's erc1155-stylus deployments
+    #[arg(long = "contract")]
+    contracts: Vec<Address>,
+    /// First block to index contracts that haven't been indexed yet, e.g. their deployment block
+    #[arg(long, default_value_t = 0)]
+    from_block: u64,
+    /// Blocks behind the head to stay
+    #[arg(long, default_value_t = 12)]
+    confirmations: u64,
+    /// Most blocks to fetch the logs of at once
+    #[arg(long, default_value_t = 10_000)]
+    batch_blocks: u64,
+    /// Seconds between polls
+    #[arg(long, default_value_t = 2)]
+    poll_interval: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let provider = ProviderBuilder::new().on_http(args.rpc_url.parse()?).erased();
+    let chain_id = provider.get_chain_id().await?;
+
+    let mut contracts = args.contracts;
+    if let Some(network) = Network::by_chain_id(chain_id) {
+        for deployment in network.deployments_of("erc1155-stylus") {
+            println!("indexing {} at {}", deployment.name, deployment.address);
+            contracts.push(deployment.address);
+        }
+    }
+    contracts.sort();
+    contracts.dedup();
+    ensure!(!contracts.is_empty(), "nothing is deployed on chain {chain_id}, give the contracts with --contract");
+
+    let store = Store::connect(&args.database_url).await?;
+    let indexer = Indexer {
+        provider,
+        store: store.clone(),
+        contracts,
+        from_block: args.from_block,
+        confirmations: args.confirmations,
+        batch_blocks: args.batch_blocks,
+    };
+    tokio::spawn(indexer.run(Duration::from_secs(args.poll_interval)));
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    println!("serving on http://{}", args.listen);
+    axum::serve(listener, api::router(store)).await?;
+    Ok(())
+}
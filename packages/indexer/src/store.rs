@@ -0,0 +1,374 @@
+//! The indexed state, in SQLite or Postgres.
+//!
+//! Amounts and IDs are stored as decimal text, since neither database has a 256-bit integer, and
+//! addresses, roles and hashes as lowercase `0x` hex, so the API returns them as they're stored.
+//! Each range of blocks is applied in one transaction with the contract's cursor, so a restart
+//! resumes after the last range that was fully applied.
+
+use alloy_primitives::{hex, Address, U256};
+use sqlx::{any::install_default_drivers, AnyPool, Row};
+
+use crate::index::{Change, Event};
+
+/// Each statement is one query, since the Postgres driver doesn't run several in one.
+const SCHEMA: [&str; 5] = [
+    "CREATE TABLE IF NOT EXISTS cursors (
+        contract TEXT PRIMARY KEY,
+        block BIGINT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS tokens (
+        contract TEXT NOT NULL,
+        id TEXT NOT NULL,
+        supply TEXT NOT NULL,
+        uri TEXT,
+        PRIMARY KEY (contract, id)
+    )",
+    "CREATE TABLE IF NOT EXISTS balances (
+        contract TEXT NOT NULL,
+        id TEXT NOT NULL,
+        account TEXT NOT NULL,
+        balance TEXT NOT NULL,
+        PRIMARY KEY (contract, id, account)
+    )",
+    "CREATE TABLE IF NOT EXISTS roles (
+        contract TEXT NOT NULL,
+        role TEXT NOT NULL,
+        account TEXT NOT NULL,
+        PRIMARY KEY (contract, role, account)
+    )",
+    "CREATE TABLE IF NOT EXISTS transfers (
+        contract TEXT NOT NULL,
+        block BIGINT NOT NULL,
+        log_index BIGINT NOT NULL,
+        position BIGINT NOT NULL,
+        transaction_hash TEXT NOT NULL,
+        operator TEXT NOT NULL,
+        from_account TEXT NOT NULL,
+        to_account TEXT NOT NULL,
+        id TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (contract, block, log_index, position)
+    )",
+];
+
+/// Connection pool of the index database.
+#[derive(Clone, Debug)]
+pub struct Store {
+    pool: AnyPool,
+}
+
+/// An ID's supply and metadata URI.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Token {
+    pub id: String,
+    pub supply: String,
+    /// `None` until it's been read from the contract or a `URI` event
+    pub uri: Option<String>,
+}
+
+/// An account's balance of one ID.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Balance {
+    pub id: String,
+    pub account: String,
+    pub balance: String,
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct RoleMember {
+    pub role: String,
+    pub account: String,
+}
+
+/// One ID moved by a `TransferSingle` or `TransferBatch`.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Transfer {
+    pub block: u64,
+    pub transaction: String,
+    pub operator: String,
+    pub from: String,
+    pub to: String,
+    pub id: String,
+    pub value: String,
+}
+
+impl Store {
+    /// Connects to `url`, such as `sqlite://index.db?mode=rwc` or `postgres://localhost/cradle`,
+    /// and creates the tables that don't exist yet.
+    pub async fn connect(url: &str) -> eyre::Result<Self> {
+        install_default_drivers();
+        let pool = AnyPool::connect(url).await?;
+        for statement in SCHEMA {
+            sqlx::query(statement).execute(&pool).await?;
+        }
+        Ok(Self { pool })
+    }
+
+    /// The last block indexed for `contract`, if any was.
+    pub async fn cursor(&self, contract: Address) -> eyre::Result<Option<u64>> {
+        let row = sqlx::query("SELECT block FROM cursors WHERE contract = $1")
+            .bind(key(contract))
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|row| row.get::<i64, _>(0) as u64))
+    }
+
+    /// Every indexed contract and its cursor.
+    pub async fn cursors(&self) -> eyre::Result<Vec<(String, u64)>> {
+        let rows = sqlx::query("SELECT contract, block FROM cursors ORDER BY contract").fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|row| (row.get(0), row.get::<i64, _>(1) as u64)).collect())
+    }
+
+    /// Applies the events of `contract` up to block `to`, in order, and moves its cursor to `to`.
+    pub async fn apply(&self, contract: Address, to: u64, events: &[Event]) -> eyre::Result<()> {
+        let contract = key(contract);
+        let mut tx = self.pool.begin().await?;
+        for event in events {
+            match &event.change {
+                Change::Transfer { operator, from, to, ids, values } => {
+                    for (position, (id, value)) in ids.iter().zip(values).enumerate() {
+                        let id = id.to_string();
+                        sqlx::query(
+                            "INSERT INTO transfers (contract, block, log_index, position, transaction_hash, operator,
+                                from_account, to_account, id, value)
+                            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                            ON CONFLICT DO NOTHING",
+                        )
+                        .bind(&contract)
+                        .bind(event.block as i64)
+                        .bind(event.log_index as i64)
+                        .bind(position as i64)
+                        .bind(hex::encode_prefixed(event.transaction))
+                        .bind(key(*operator))
+                        .bind(key(*from))
+                        .bind(key(*to))
+                        .bind(&id)
+                        .bind(value.to_string())
+                        .execute(&mut *tx)
+                        .await?;
+
+                        // Mints come from and burns go to the zero address, which has no balance
+                        let supply = "SELECT supply FROM tokens WHERE contract = $1 AND id = $2";
+                        let supply: Option<String> =
+                            sqlx::query_scalar(supply).bind(&contract).bind(&id).fetch_optional(&mut *tx).await?;
+                        let mut supply = parse(supply.as_deref())?;
+                        if from.is_zero() {
+                            supply = supply.saturating_add(*value);
+                        } else {
+                            let balance = balance(&mut tx, &contract, &id, *from).await?;
+                            set_balance(&mut tx, &contract, &id, *from, balance.saturating_sub(*value)).await?;
+                        }
+                        if to.is_zero() {
+                            supply = supply.saturating_sub(*value);
+                        } else {
+                            let balance = balance(&mut tx, &contract, &id, *to).await?;
+                            set_balance(&mut tx, &contract, &id, *to, balance.saturating_add(*value)).await?;
+                        }
+                        sqlx::query(
+                            "INSERT INTO tokens (contract, id, supply) VALUES ($1, $2, $3)
+                            ON CONFLICT (contract, id) DO UPDATE SET supply = excluded.supply",
+                        )
+                        .bind(&contract)
+                        .bind(&id)
+                        .bind(supply.to_string())
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+                Change::Uri { id, uri } => {
+                    sqlx::query(
+                        "INSERT INTO tokens (contract, id, supply, uri) VALUES ($1, $2, '0', $3)
+                        ON CONFLICT (contract, id) DO UPDATE SET uri = excluded.uri",
+                    )
+                    .bind(&contract)
+                    .bind(id.to_string())
+                    .bind(uri)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                Change::RoleGranted { role, account } => {
+                    let grant = "INSERT INTO roles (contract, role, account) VALUES ($1, $2, $3)
+                        ON CONFLICT DO NOTHING";
+                    sqlx::query(grant)
+                        .bind(&contract)
+                        .bind(hex::encode_prefixed(role))
+                        .bind(key(*account))
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                Change::RoleRevoked { role, account } => {
+                    sqlx::query("DELETE FROM roles WHERE contract = $1 AND role = $2 AND account = $3")
+                        .bind(&contract)
+                        .bind(hex::encode_prefixed(role))
+                        .bind(key(*account))
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+        sqlx::query(
+            "INSERT INTO cursors (contract, block) VALUES ($1, $2)
+            ON CONFLICT (contract) DO UPDATE SET block = excluded.block",
+        )
+        .bind(&contract)
+        .bind(to as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Up to `limit` IDs of `contract` whose URI hasn't been read yet.
+    pub async fn missing_uris(&self, contract: Address, limit: u32) -> eyre::Result<Vec<U256>> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tokens WHERE contract = $1 AND uri IS NULL LIMIT $2")
+            .bind(key(contract))
+            .bind(i64::from(limit))
+            .fetch_all(&self.pool)
+            .await?;
+        ids.iter().map(|id| parse(Some(id))).collect()
+    }
+
+    /// Records the URI read from the contract, unless a `URI` event already set one.
+    pub async fn set_uri(&self, contract: Address, id: U256, uri: &str) -> eyre::Result<()> {
+        sqlx::query("UPDATE tokens SET uri = $1 WHERE contract = $2 AND id = $3 AND uri IS NULL")
+            .bind(uri)
+            .bind(key(contract))
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn token(&self, contract: Address, id: U256) -> eyre::Result<Option<Token>> {
+        let row = sqlx::query("SELECT id, supply, uri FROM tokens WHERE contract = $1 AND id = $2")
+            .bind(key(contract))
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|row| Token { id: row.get(0), supply: row.get(1), uri: row.get(2) }))
+    }
+
+    /// The accounts holding `id`, largest balances first.
+    pub async fn holders(&self, contract: Address, id: U256) -> eyre::Result<Vec<Balance>> {
+        let rows = sqlx::query(
+            "SELECT id, account, balance FROM balances WHERE contract = $1 AND id = $2
+            ORDER BY length(balance) DESC, balance DESC, account",
+        )
+        .bind(key(contract))
+        .bind(id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(balance_row).collect())
+    }
+
+    /// The IDs `account` holds, in order of ID.
+    pub async fn balances(&self, contract: Address, account: Address) -> eyre::Result<Vec<Balance>> {
+        let rows = sqlx::query(
+            "SELECT id, account, balance FROM balances WHERE contract = $1 AND account = $2
+            ORDER BY length(id), id",
+        )
+        .bind(key(contract))
+        .bind(key(account))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(balance_row).collect())
+    }
+
+    /// The accounts holding each role, in order of role.
+    pub async fn roles(&self, contract: Address) -> eyre::Result<Vec<RoleMember>> {
+        let rows = sqlx::query("SELECT role, account FROM roles WHERE contract = $1 ORDER BY role, account")
+            .bind(key(contract))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(|row| RoleMember { role: row.get(0), account: row.get(1) }).collect())
+    }
+
+    /// The latest `limit` transfers, newest first, only those from or to `account` if it's given.
+    pub async fn transfers(
+        &self,
+        contract: Address,
+        account: Option<Address>,
+        limit: u32,
+    ) -> eyre::Result<Vec<Transfer>> {
+        let rows = sqlx::query(
+            "SELECT block, transaction_hash, operator, from_account, to_account, id, value FROM transfers
+            WHERE contract = $1 AND ($2 = '' OR from_account = $2 OR to_account = $2)
+            ORDER BY block DESC, log_index DESC, position DESC LIMIT $3",
+        )
+        .bind(key(contract))
+        .bind(account.map(key).unwrap_or_default())
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| Transfer {
+                block: row.get::<i64, _>(0) as u64,
+                transaction: row.get(1),
+                operator: row.get(2),
+                from: row.get(3),
+                to: row.get(4),
+                id: row.get(5),
+                value: row.get(6),
+            })
+            .collect())
+    }
+}
+
+type Transaction<'a> = sqlx::Transaction<'a, sqlx::Any>;
+
+async fn balance(tx: &mut Transaction<'_>, contract: &str, id: &str, account: Address) -> eyre::Result<U256> {
+    let balance: Option<String> =
+        sqlx::query_scalar("SELECT balance FROM balances WHERE contract = $1 AND id = $2 AND account = $3")
+            .bind(contract)
+            .bind(id)
+            .bind(key(account))
+            .fetch_optional(&mut **tx)
+            .await?;
+    parse(balance.as_deref())
+}
+
+/// Stores `account`'s balance, dropping the row once it's zero so holders only lists holders.
+async fn set_balance(
+    tx: &mut Transaction<'_>,
+    contract: &str,
+    id: &str,
+    account: Address,
+    balance: U256,
+) -> eyre::Result<()> {
+    let account = key(account);
+    if balance.is_zero() {
+        sqlx::query("DELETE FROM balances WHERE contract = $1 AND id = $2 AND account = $3")
+            .bind(contract)
+            .bind(id)
+            .bind(account)
+            .execute(&mut **tx)
+            .await?;
+    } else {
+        sqlx::query(
+            "INSERT INTO balances (contract, id, account, balance) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (contract, id, account) DO UPDATE SET balance = excluded.balance",
+        )
+        .bind(contract)
+        .bind(id)
+        .bind(account)
+        .bind(balance.to_string())
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+fn balance_row(row: &sqlx::any::AnyRow) -> Balance {
+    Balance { id: row.get(0), account: row.get(1), balance: row.get(2) }
+}
+
+/// A stored amount or ID, zero if there's no row.
+fn parse(value: Option<&str>) -> eyre::Result<U256> {
+    Ok(value.map(str::parse).transpose()?.unwrap_or_default())
+}
+
+/// How addresses are stored: lowercase `0x` hex.
+pub fn key(address: Address) -> String {
+    hex::encode_prefixed(address)
+}
@@ -0,0 +1,145 @@
+use alloy_primitives::{address, b256, Address, LogData, B256, U256};
+use alloy_rpc_types_eth::Log;
+use alloy_sol_types::SolEvent;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use cradle_clients::erc1155::IERC1155Token;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::{
+    api::router,
+    index::{decode, Change, Event, URI},
+    store::Store,
+};
+
+const TOKEN: Address = address!("a6e41ffd769491a42a6e5ce453259b93983a22ef");
+const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const MINTER_ROLE: B256 = b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+/// A store in a new SQLite file, since every connection to `sqlite::memory:` has its own database.
+async fn store(name: &str) -> Store {
+    let path = std::env::temp_dir().join(format!("cradle-indexer-{name}-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    Store::connect(&format!("sqlite://{}?mode=rwc", path.display())).await.unwrap()
+}
+
+fn event(block: u64, change: Change) -> Event {
+    Event { block, log_index: 0, transaction: B256::with_last_byte(block as u8), change }
+}
+
+fn transfer(from: Address, to: Address, ids: &[u64], values: &[u64]) -> Change {
+    let ids = ids.iter().map(|id| U256::from(*id)).collect();
+    let values = values.iter().map(|value| U256::from(*value)).collect();
+    Change::Transfer { operator: ALICE, from, to, ids, values }
+}
+
+fn log(data: LogData) -> Log {
+    Log {
+        inner: alloy_primitives::Log { address: TOKEN, data },
+        block_number: Some(7),
+        log_index: Some(2),
+        transaction_hash: Some(B256::with_last_byte(1)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn logs_decode_into_changes() {
+    let (id, value) = (U256::from(1), U256::from(5));
+    let single = IERC1155Token::TransferSingle { operator: ALICE, from: Address::ZERO, to: BOB, id, value };
+    let event = decode(&log(single.encode_log_data())).unwrap();
+    assert_eq!((event.block, event.log_index, event.transaction), (7, 2, B256::with_last_byte(1)));
+    assert_eq!(event.change, transfer(Address::ZERO, BOB, &[1], &[5]));
+
+    let uri = URI { value: String::from("ipfs://x/1.json"), id: U256::from(1) };
+    let change = decode(&log(uri.encode_log_data())).unwrap().change;
+    assert_eq!(change, Change::Uri { id: U256::from(1), uri: String::from("ipfs://x/1.json") });
+
+    let granted = IERC1155Token::RoleGranted { role: MINTER_ROLE, account: BOB, sender: ALICE };
+    let change = decode(&log(granted.encode_log_data())).unwrap().change;
+    assert_eq!(change, Change::RoleGranted { role: MINTER_ROLE, account: BOB });
+
+    // Other events, and logs not yet in a block, aren't indexed
+    let approval = IERC1155Token::ApprovalForAll { account: ALICE, operator: BOB, approved: true };
+    assert_eq!(decode(&log(approval.encode_log_data())), None);
+    let pending = Log { block_number: None, ..log(single.encode_log_data()) };
+    assert_eq!(decode(&pending), None);
+}
+
+#[tokio::test]
+async fn transfers_add_up_to_balances_and_supplies() {
+    let store = store("balances").await;
+    let events = [
+        event(1, transfer(Address::ZERO, ALICE, &[1, 2], &[10, 3])),
+        event(2, transfer(ALICE, BOB, &[1], &[4])),
+        event(3, transfer(ALICE, Address::ZERO, &[2], &[3])),
+        event(3, Change::RoleGranted { role: MINTER_ROLE, account: BOB }),
+    ];
+    store.apply(TOKEN, 3, &events).await.unwrap();
+    assert_eq!(store.cursor(TOKEN).await.unwrap(), Some(3));
+
+    let token = store.token(TOKEN, U256::from(1)).await.unwrap().unwrap();
+    assert_eq!((token.supply.as_str(), token.uri), ("10", None));
+    // The burned ID keeps its row, with no supply and no holders
+    assert_eq!(store.token(TOKEN, U256::from(2)).await.unwrap().unwrap().supply, "0");
+    assert!(store.holders(TOKEN, U256::from(2)).await.unwrap().is_empty());
+    let holders = store.holders(TOKEN, U256::from(1)).await.unwrap();
+    let holders: Vec<_> = holders.iter().map(|holder| (holder.account.as_str(), holder.balance.as_str())).collect();
+    let (alice, bob) = ("0x00000000000000000000000000000000000a11ce", "0x0000000000000000000000000000000000000b0b");
+    assert_eq!(holders, [(alice, "6"), (bob, "4")]);
+
+    assert_eq!(store.missing_uris(TOKEN, 10).await.unwrap().len(), 2);
+    store.set_uri(TOKEN, U256::from(1), "ipfs://x/1.json").await.unwrap();
+    assert_eq!(store.missing_uris(TOKEN, 10).await.unwrap(), [U256::from(2)]);
+
+    let revoked = [event(4, Change::RoleRevoked { role: MINTER_ROLE, account: BOB })];
+    assert_eq!(store.roles(TOKEN).await.unwrap().len(), 1);
+    store.apply(TOKEN, 5, &revoked).await.unwrap();
+    assert!(store.roles(TOKEN).await.unwrap().is_empty());
+    assert_eq!(store.cursor(TOKEN).await.unwrap(), Some(5));
+}
+
+#[tokio::test]
+async fn the_api_serves_the_index_as_json() {
+    let store = store("api").await;
+    let events = [event(1, transfer(Address::ZERO, ALICE, &[1], &[10])), event(2, transfer(ALICE, BOB, &[1], &[4]))];
+    store.apply(TOKEN, 2, &events).await.unwrap();
+
+    let get = |uri: String| {
+        let app = router(store.clone());
+        async move {
+            let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&body).unwrap())
+        }
+    };
+
+    let (status, token) = get(format!("/{TOKEN}/tokens/1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(token, json!({ "id": "1", "supply": "10", "uri": null }));
+
+    // Checksummed addresses and hex IDs find the same rows
+    let (_, balances) = get(format!("/{TOKEN}/accounts/{BOB}/balances")).await;
+    let bob = "0x0000000000000000000000000000000000000b0b";
+    assert_eq!(balances, json!([{ "id": "1", "account": bob, "balance": "4" }]));
+    let (_, holders) = get(format!("/{TOKEN}/tokens/0x1/holders")).await;
+    assert_eq!(holders.as_array().unwrap().len(), 2);
+
+    let (_, transfers) = get(format!("/{TOKEN}/transfers?account={BOB}&limit=10")).await;
+    assert_eq!(transfers.as_array().unwrap().len(), 1);
+    assert_eq!(transfers[0]["from"], "0x00000000000000000000000000000000000a11ce");
+    let (_, status) = get(String::from("/status")).await;
+    assert_eq!(status, json!([{ "contract": "0xa6e41ffd769491a42a6e5ce453259b93983a22ef", "block": 2 }]));
+
+    let (status, error) = get(format!("/{TOKEN}/tokens/2")).await;
+    assert_eq!((status, error["error"].as_str()), (StatusCode::NOT_FOUND, Some("no transfers of ID 2")));
+    let (status, _) = get(String::from("/0x1234/roles")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get(format!("/{TOKEN}/transfers?limit=0")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
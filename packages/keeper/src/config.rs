@@ -0,0 +1,46 @@
+//! The TOML file naming the deployments to keep and how much to pay for it.
+
+use std::{fs, path::Path};
+
+use alloy_primitives::{Address, U256};
+use eyre::WrapErr;
+use serde::Deserialize;
+
+use crate::gas::GasPolicy;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Seconds between rounds
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    #[serde(default)]
+    pub gas: GasPolicy,
+    pub jobs: Vec<JobConfig>,
+}
+
+/// A deployment to keep, by its name in the network's manifest or by address.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobConfig {
+    /// Key in the deployments manifest of the node's chain
+    pub deployment: Option<String>,
+    /// Address of a deployment that isn't in a manifest, with its `component`
+    pub address: Option<Address>,
+    /// Directory in `packages/components`, like `subscription-stylus`. Taken from the manifest
+    /// for `deployment`s.
+    pub component: Option<String>,
+    /// streams-stylus only: the smallest withdrawable amount worth pushing to the stream's holder
+    pub min_withdraw: Option<U256>,
+}
+
+fn default_interval() -> u64 {
+    30
+}
+
+impl Config {
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).wrap_err_with(|| format!("invalid config {}", path.display()))
+    }
+}
@@ -0,0 +1,39 @@
+//! What the keeper pays for its transactions.
+
+use serde::Deserialize;
+
+/// Limits on the fees and number of transactions of each round. Fees are in wei.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasPolicy {
+    /// Rounds where the node's estimated max fee per gas is above this send nothing, and the
+    /// upkeep waits for cheaper blocks
+    pub max_fee_per_gas: Option<u128>,
+    /// Tip to send with instead of the node's estimate
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Percent added to each transaction's gas estimate, 20 by default
+    pub gas_limit_margin: Option<u64>,
+    /// Most transactions per round, 20 by default; the rest wait for the next round
+    pub max_transactions: Option<usize>,
+}
+
+impl GasPolicy {
+    /// The max fee and tip to send with, given the node's estimates, or `None` if the fee is
+    /// above `max_fee_per_gas`. A tip above the max fee is lowered to it.
+    pub fn fees(&self, max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Option<(u128, u128)> {
+        if self.max_fee_per_gas.is_some_and(|cap| max_fee_per_gas > cap) {
+            return None;
+        }
+        let tip = self.max_priority_fee_per_gas.unwrap_or(max_priority_fee_per_gas);
+        Some((max_fee_per_gas, tip.min(max_fee_per_gas)))
+    }
+
+    pub fn gas_limit(&self, estimate: u64) -> u64 {
+        let margin = self.gas_limit_margin.unwrap_or(20);
+        estimate.saturating_add(estimate.saturating_mul(margin) / 100)
+    }
+
+    pub fn max_transactions(&self) -> usize {
+        self.max_transactions.unwrap_or(20)
+    }
+}
@@ -0,0 +1,244 @@
+//! Finding the upkeep each component is due.
+
+use std::collections::HashSet;
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{Address, U256};
+use alloy_provider::{DynProvider, Provider};
+use alloy_sol_types::{sol, SolCall};
+use cradle_addresses::Network;
+use eyre::{bail, eyre, OptionExt};
+
+use crate::config::JobConfig;
+
+sol! {
+    /// The parts of the components' ABIs the keeper uses, as in `packages/abis`.
+    interface Subscriptions {
+        function totals() external view returns (uint256 plans, uint256 subscriptions);
+        function subscription(uint256 subscription_id) external view
+            returns (address subscriber, uint256 plan_id, uint256 paid_until, bool cancelled);
+        function isDue(uint256 subscription_id) external view returns (bool);
+        function charge(uint256 subscription_id) external returns (bool);
+    }
+
+    interface Auctions {
+        function totalAuctions() external view returns (uint256);
+        function auction(uint256 auction_id) external view returns (
+            address seller,
+            address token_contract,
+            uint256 token_id,
+            uint256 amount,
+            address payment_token,
+            uint256 reserve_price,
+            uint256 end_time,
+            address highest_bidder,
+            uint256 highest_bid,
+            bool closed
+        );
+        function settle(uint256 auction_id) external;
+        function clock() external view returns (uint256);
+    }
+
+    interface Streams {
+        function nextStreamId() external view returns (uint256);
+        function getStream(uint256 stream_id) external view returns (
+            address sender,
+            address token,
+            uint256 total,
+            uint256 withdrawn,
+            uint256 refunded,
+            uint64 start,
+            uint64 cliff,
+            uint64 end,
+            bool cancelable,
+            bool canceled
+        );
+        function ownerOf(uint256 token_id) external view returns (address);
+        function withdrawableAmountOf(uint256 stream_id) external view returns (uint256);
+        function withdrawMax(uint256 stream_id, address to) external returns (uint256);
+    }
+}
+
+type TransactionRequest = <alloy_network::Ethereum as alloy_network::Network>::TransactionRequest;
+
+/// A task's label and calldata.
+type Call = (String, Vec<u8>);
+
+/// The upkeep a component needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// subscription-stylus: `charge` subscriptions that are due, for the keeper fee
+    Subscriptions,
+    /// auction-stylus: `settle` auctions that have ended
+    Auctions,
+    /// streams-stylus: `withdrawMax` what has unlocked to each stream's holder
+    Streams { min_withdraw: U256 },
+}
+
+/// Where an item stands, from the views the keeper reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Needs its transaction now
+    Due,
+    /// Will need one later
+    Pending,
+    /// Never will again, so it isn't read on later rounds
+    Done,
+}
+
+/// A transaction that's due.
+#[derive(Clone, Debug)]
+pub struct Task {
+    pub job: String,
+    /// Like `charge(3)`
+    pub label: String,
+    pub request: TransactionRequest,
+}
+
+/// One deployment to keep.
+#[derive(Debug)]
+pub struct Job {
+    pub name: String,
+    pub address: Address,
+    pub kind: Kind,
+    /// Items that are `Done`
+    done: HashSet<U256>,
+}
+
+impl Job {
+    /// The job `config` describes, with deployments looked up in `network`.
+    pub fn resolve(config: &JobConfig, network: Option<&Network>) -> eyre::Result<Self> {
+        let (name, address, component) = match (&config.deployment, config.address) {
+            (Some(name), None) => {
+                let network = network.ok_or_eyre(format!("no manifest for the node's chain to find {name} in"))?;
+                let deployment = network.deployment(name).ok_or_eyre(format!("no {name} in {}", network.name))?;
+                if config.component.as_deref().is_some_and(|component| component != deployment.component) {
+                    bail!("{name} is a deployment of {}", deployment.component);
+                }
+                (name.clone(), deployment.address, deployment.component.to_string())
+            }
+            (None, Some(address)) => {
+                let component = config.component.clone().ok_or_eyre(format!("give the component of {address}"))?;
+                (address.to_string(), address, component)
+            }
+            _ => bail!("give a job either a deployment or an address"),
+        };
+        let kind = match component.as_str() {
+            "subscription-stylus" => Kind::Subscriptions,
+            "auction-stylus" => Kind::Auctions,
+            "streams-stylus" => Kind::Streams { min_withdraw: config.min_withdraw.unwrap_or(U256::from(1)) },
+            other => bail!("{name}: the keeper doesn't keep {other}, only subscription-stylus, auction-stylus and \
+                            streams-stylus"),
+        };
+        if config.min_withdraw.is_some() && !matches!(kind, Kind::Streams { .. }) {
+            bail!("{name}: min_withdraw is only for streams-stylus");
+        }
+        Ok(Self { name, address, kind, done: HashSet::new() })
+    }
+
+    /// The transactions due at `now`, the latest block's timestamp. Auctions are checked against
+    /// their contract's own clock instead, which may count blocks.
+    pub async fn due(&mut self, provider: &DynProvider, now: u64) -> eyre::Result<Vec<Task>> {
+        let mut tasks = Vec::new();
+        let now = match self.kind {
+            Kind::Auctions => self.view(provider, Auctions::clockCall {}).await?._0.saturating_to(),
+            _ => now,
+        };
+        let ids: Vec<U256> = match self.kind {
+            Kind::Subscriptions => {
+                let total = self.view(provider, Subscriptions::totalsCall {}).await?.subscriptions;
+                (1..=total.saturating_to::<u64>()).map(U256::from).collect()
+            }
+            Kind::Auctions => {
+                let total = self.view(provider, Auctions::totalAuctionsCall {}).await?._0;
+                (0..total.saturating_to::<u64>()).map(U256::from).collect()
+            }
+            Kind::Streams { .. } => {
+                let next = self.view(provider, Streams::nextStreamIdCall {}).await?._0;
+                (1..next.saturating_to::<u64>()).map(U256::from).collect()
+            }
+        };
+        for id in ids.into_iter().filter(|id| !self.done.contains(id)) {
+            match self.check(provider, id, now).await? {
+                (Status::Due, Some((label, input))) => {
+                    let request = TransactionRequest::default().with_to(self.address).with_input(input);
+                    tasks.push(Task { job: self.name.clone(), label, request });
+                }
+                (Status::Done, _) => {
+                    self.done.insert(id);
+                }
+                _ => {}
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Where item `id` stands, and the call and its label if it's due.
+    async fn check(&self, provider: &DynProvider, id: U256, now: u64) -> eyre::Result<(Status, Option<Call>)> {
+        Ok(match &self.kind {
+            Kind::Subscriptions => {
+                let subscription = self.view(provider, Subscriptions::subscriptionCall { subscription_id: id }).await?;
+                let due = self.view(provider, Subscriptions::isDueCall { subscription_id: id }).await?._0;
+                let call = Subscriptions::chargeCall { subscription_id: id };
+                (subscription_status(&subscription, due), Some((format!("charge({id})"), call.abi_encode())))
+            }
+            Kind::Auctions => {
+                let auction = self.view(provider, Auctions::auctionCall { auction_id: id }).await?;
+                let call = Auctions::settleCall { auction_id: id };
+                (auction_status(&auction, now), Some((format!("settle({id})"), call.abi_encode())))
+            }
+            Kind::Streams { min_withdraw } => {
+                let stream = self.view(provider, Streams::getStreamCall { stream_id: id }).await?;
+                let withdrawable = self.view(provider, Streams::withdrawableAmountOfCall { stream_id: id }).await?._0;
+                match stream_status(&stream, withdrawable, *min_withdraw) {
+                    // Anyone can withdraw to the holder, so only the holder is looked up
+                    Status::Due => {
+                        let to = self.view(provider, Streams::ownerOfCall { token_id: id }).await?._0;
+                        let call = Streams::withdrawMaxCall { stream_id: id, to };
+                        (Status::Due, Some((format!("withdrawMax({id}, {to})"), call.abi_encode())))
+                    }
+                    status => (status, None),
+                }
+            }
+        })
+    }
+
+    async fn view<C: SolCall>(&self, provider: &DynProvider, call: C) -> eyre::Result<C::Return> {
+        let request = TransactionRequest::default().with_to(self.address).with_input(call.abi_encode());
+        let output = provider.call(&request).await.map_err(|e| eyre!("{}: {} failed: {e}", self.name, C::SIGNATURE))?;
+        Ok(C::abi_decode_returns(&output, true)?)
+    }
+}
+
+/// Subscriptions are due once `isDue`, including past their grace period, when `charge` lapses
+/// them and pays nothing, and done once cancelled or lapsed.
+pub fn subscription_status(subscription: &Subscriptions::subscriptionReturn, due: bool) -> Status {
+    match (subscription.cancelled, due) {
+        (true, _) => Status::Done,
+        (false, true) => Status::Due,
+        (false, false) => Status::Pending,
+    }
+}
+
+/// Auctions are due once they've ended, with or without bids, and done once settled or cancelled.
+pub fn auction_status(auction: &Auctions::auctionReturn, now: u64) -> Status {
+    if auction.closed || auction.seller.is_zero() {
+        Status::Done
+    } else if U256::from(now) >= auction.end_time {
+        Status::Due
+    } else {
+        Status::Pending
+    }
+}
+
+/// Streams are due when at least `min_withdraw` has unlocked, and done once everything owed has
+/// been withdrawn or refunded.
+pub fn stream_status(stream: &Streams::getStreamReturn, withdrawable: U256, min_withdraw: U256) -> Status {
+    if stream.withdrawn + stream.refunded >= stream.total {
+        Status::Done
+    } else if !withdrawable.is_zero() && withdrawable >= min_withdraw {
+        Status::Due
+    } else {
+        Status::Pending
+    }
+}
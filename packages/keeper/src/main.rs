@@ -0,0 +1,131 @@
+//! Sends the permissionless upkeep transactions of Cradle's time-dependent components.
+//!
+//! Subscriptions need someone to `charge` them every period, auctions to `settle` them once they
+//! end, and streams someone to push what has unlocked to their holders. Each round, the keeper
+//! reads the views of every deployment in its config to find what's due, simulates each call, and
+//! sends the ones that would succeed within the config's gas policy. Deployments are named as in
+//! the `cradle-addresses` manifest of the node's chain, or given by address.
+
+mod config;
+mod gas;
+mod jobs;
+#[cfg(test)]
+mod tests;
+
+use std::{path::PathBuf, time::Duration};
+
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::Address;
+use alloy_provider::{DynProvider, Provider, ProviderBuilder};
+use alloy_rpc_types_eth::BlockNumberOrTag;
+use alloy_signer_local::PrivateKeySigner;
+use clap::Parser;
+use cradle_addresses::Network;
+use eyre::{ensure, OptionExt, WrapErr};
+
+use crate::{config::Config, gas::GasPolicy, jobs::Job};
+
+#[derive(Parser)]
+struct Args {
+    /// TOML file of the jobs and gas policy
+    config: PathBuf,
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+    /// Account that sends the upkeep and receives the keeper fees
+    #[arg(long, env = "PRIVATE_KEY")]
+    private_key: Option<String>,
+    /// List what's due and simulate it without sending anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Run one round and exit, e.g. from cron
+    #[arg(long)]
+    once: bool,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let config = Config::load(&args.config)?;
+    ensure!(args.dry_run || args.private_key.is_some(), "give the keeper's --private-key, or use --dry-run");
+
+    let rpc_url = args.rpc_url.parse().wrap_err("invalid RPC URL")?;
+    let (provider, keeper) = match &args.private_key {
+        Some(key) => {
+            let signer: PrivateKeySigner = key.parse().wrap_err("invalid private key")?;
+            let keeper = signer.address();
+            (ProviderBuilder::new().wallet(EthereumWallet::from(signer)).on_http(rpc_url).erased(), Some(keeper))
+        }
+        None => (ProviderBuilder::new().on_http(rpc_url).erased(), None),
+    };
+    let chain_id = provider.get_chain_id().await.wrap_err_with(|| format!("no node at {}", args.rpc_url))?;
+
+    let network = Network::by_chain_id(chain_id);
+    let mut jobs = config.jobs.iter().map(|job| Job::resolve(job, network)).collect::<eyre::Result<Vec<_>>>()?;
+    for job in &jobs {
+        println!("keeping {} at {} ({:?})", job.name, job.address, job.kind);
+    }
+
+    loop {
+        if let Err(e) = round(&provider, &mut jobs, &config.gas, keeper, args.dry_run).await {
+            eprintln!("round failed: {e:#}");
+        }
+        if args.once {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(config.interval)).await;
+    }
+}
+
+/// Finds the tasks due across `jobs` and sends those that would succeed, at most
+/// `max_transactions` of them. A job whose views fail is skipped until the next round.
+async fn round(
+    provider: &DynProvider,
+    jobs: &mut [Job],
+    policy: &GasPolicy,
+    keeper: Option<Address>,
+    dry_run: bool,
+) -> eyre::Result<()> {
+    let block = provider.get_block_by_number(BlockNumberOrTag::Latest).await?.ok_or_eyre("no latest block")?;
+    let mut tasks = Vec::new();
+    for job in jobs.iter_mut() {
+        match job.due(provider, block.header.timestamp).await {
+            Ok(due) => tasks.extend(due),
+            Err(e) => eprintln!("{}: {e:#}", job.name),
+        }
+    }
+    if tasks.is_empty() {
+        return Ok(());
+    }
+
+    let estimate = provider.estimate_eip1559_fees().await?;
+    let Some((max_fee, tip)) = policy.fees(estimate.max_fee_per_gas, estimate.max_priority_fee_per_gas) else {
+        println!("{} due, waiting while the max fee is {} wei per gas", tasks.len(), estimate.max_fee_per_gas);
+        return Ok(());
+    };
+    for task in tasks.into_iter().take(policy.max_transactions()) {
+        let mut request = task.request;
+        if let Some(keeper) = keeper {
+            request.set_from(keeper);
+        }
+        // Calls that would revert, like a charge the subscriber can't pay yet, aren't sent
+        let gas = match provider.estimate_gas(&request).await {
+            Ok(gas) => gas,
+            Err(e) => {
+                eprintln!("{}: {} would revert: {e}", task.job, task.label);
+                continue;
+            }
+        };
+        if dry_run {
+            println!("{}: {} is due, {gas} gas", task.job, task.label);
+            continue;
+        }
+        let request = request
+            .with_gas_limit(policy.gas_limit(gas))
+            .with_max_fee_per_gas(max_fee)
+            .with_max_priority_fee_per_gas(tip);
+        let receipt = provider.send_transaction(request).await?.get_receipt().await?;
+        let outcome = if receipt.status() { "sent" } else { "reverted" };
+        println!("{}: {} {outcome} in {}", task.job, task.label, receipt.transaction_hash);
+    }
+    Ok(())
+}
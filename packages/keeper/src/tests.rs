@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use alloy_primitives::{address, Address, U256};
+use cradle_addresses::{Deployment, Network};
+
+use crate::{
+    config::{Config, JobConfig},
+    gas::GasPolicy,
+    jobs::{auction_status, stream_status, subscription_status, Auctions, Job, Kind, Status, Streams, Subscriptions},
+};
+
+const SUBSCRIPTIONS: Deployment = Deployment {
+    name: "subscriptions",
+    component: "subscription-stylus",
+    version: "0.1.0",
+    features: &[],
+    address: address!("a6e41ffd769491a42a6e5ce453259b93983a22ef"),
+    roles: &[],
+};
+const ITEMS: Deployment = Deployment { name: "game-items", component: "erc1155-stylus", ..SUBSCRIPTIONS };
+const NETWORK: Network = Network { name: "local", chain_id: 412346, deployments: &[ITEMS, SUBSCRIPTIONS] };
+const SELLER: Address = address!("00000000000000000000000000000000000a11ce");
+
+fn job(toml: &str) -> eyre::Result<Job> {
+    let config: JobConfig = toml::from_str(toml).unwrap();
+    Job::resolve(&config, Some(&NETWORK))
+}
+
+#[test]
+fn the_example_config_loads() {
+    let config = Config::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/keeper.toml")).unwrap();
+    assert_eq!(config.interval, 30);
+    assert_eq!(config.gas.max_fee_per_gas, Some(100_000_000));
+    assert_eq!(config.jobs.len(), 3);
+    assert_eq!(config.jobs[2].min_withdraw, Some(U256::from(10).pow(U256::from(18))));
+}
+
+#[test]
+fn jobs_resolve_from_the_manifest_or_an_address() {
+    let subscriptions = job(r#"deployment = "subscriptions""#).unwrap();
+    assert_eq!((subscriptions.address, subscriptions.kind), (SUBSCRIPTIONS.address, Kind::Subscriptions));
+    let streams = job(&format!("address = \"{SELLER}\"\ncomponent = \"streams-stylus\"")).unwrap();
+    assert_eq!(streams.kind, Kind::Streams { min_withdraw: U256::from(1) });
+
+    let error = job(r#"deployment = "game-items""#).unwrap_err().to_string();
+    assert!(error.contains("doesn't keep erc1155-stylus"), "{error}");
+    let error = job(r#"deployment = "missing""#).unwrap_err().to_string();
+    assert!(error.contains("no missing in local"), "{error}");
+    let error = job(&format!("address = \"{SELLER}\"")).unwrap_err().to_string();
+    assert!(error.contains("give the component"), "{error}");
+    let error = job("deployment = \"subscriptions\"\nmin_withdraw = \"5\"").unwrap_err().to_string();
+    assert!(error.contains("only for streams-stylus"), "{error}");
+}
+
+#[test]
+fn the_gas_policy_caps_fees_and_pads_estimates() {
+    let policy = GasPolicy { max_fee_per_gas: Some(100), max_priority_fee_per_gas: Some(10), ..Default::default() };
+    assert_eq!(policy.fees(80, 1), Some((80, 10)));
+    assert_eq!(policy.fees(101, 1), None);
+    // The tip never exceeds the max fee
+    assert_eq!(policy.fees(5, 1), Some((5, 5)));
+    assert_eq!(GasPolicy::default().fees(1_000, 2), Some((1_000, 2)));
+
+    assert_eq!(GasPolicy::default().gas_limit(100_000), 120_000);
+    assert_eq!(GasPolicy { gas_limit_margin: Some(0), ..Default::default() }.gas_limit(100_000), 100_000);
+    assert_eq!(GasPolicy::default().max_transactions(), 20);
+}
+
+#[test]
+fn items_are_due_pending_or_done() {
+    let subscription = Subscriptions::subscriptionReturn {
+        subscriber: SELLER,
+        plan_id: U256::from(1),
+        paid_until: U256::from(100),
+        cancelled: false,
+    };
+    assert_eq!(subscription_status(&subscription, true), Status::Due);
+    assert_eq!(subscription_status(&subscription, false), Status::Pending);
+    let cancelled = Subscriptions::subscriptionReturn { cancelled: true, ..subscription };
+    assert_eq!(subscription_status(&cancelled, false), Status::Done);
+
+    let auction = Auctions::auctionReturn {
+        seller: SELLER,
+        token_contract: ITEMS.address,
+        token_id: U256::from(1),
+        amount: U256::from(1),
+        payment_token: Address::ZERO,
+        reserve_price: U256::ZERO,
+        end_time: U256::from(1_000),
+        highest_bidder: Address::ZERO,
+        highest_bid: U256::ZERO,
+        closed: false,
+    };
+    assert_eq!(auction_status(&auction, 999), Status::Pending);
+    // Auctions without bids are settled too, which hands the lot back
+    assert_eq!(auction_status(&auction, 1_000), Status::Due);
+    assert_eq!(auction_status(&Auctions::auctionReturn { closed: true, ..auction }, 1_000), Status::Done);
+
+    let stream = Streams::getStreamReturn {
+        sender: SELLER,
+        token: Address::ZERO,
+        total: U256::from(100),
+        withdrawn: U256::from(40),
+        refunded: U256::ZERO,
+        start: 0,
+        cliff: 0,
+        end: 1_000,
+        cancelable: true,
+        canceled: false,
+    };
+    assert_eq!(stream_status(&stream, U256::from(10), U256::from(10)), Status::Due);
+    assert_eq!(stream_status(&stream, U256::from(9), U256::from(10)), Status::Pending);
+    assert_eq!(stream_status(&stream, U256::ZERO, U256::ZERO), Status::Pending);
+    let canceled = Streams::getStreamReturn { refunded: U256::from(60), canceled: true, ..stream };
+    assert_eq!(stream_status(&canceled, U256::ZERO, U256::from(1)), Status::Done);
+}
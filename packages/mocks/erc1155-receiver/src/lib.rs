@@ -0,0 +1,136 @@
+//! ERC-1155 receiver mock
+//!
+//! Answers `onERC1155Received` and `onERC1155BatchReceived` the way it's told to with
+//! `set_behavior`, so tests can check how a token or escrow handles each kind of recipient:
+//!
+//! - `ACCEPT` (the default) returns the selector, as a well-behaved receiver does
+//! - `REJECT` returns another value, which a token must treat as a refusal
+//! - `REVERT` reverts with `ReceiverReverted()`, which a token should bubble up
+//! - `REVERT_EMPTY` reverts without data
+//! - `REENTER` calls the caller back with the calldata given to `set_behavior` and then accepts,
+//!   bubbling up a revert, to exercise reentrancy guards
+//!
+//! Every hook it accepts emits `Received` or `BatchReceived` with its arguments.
+//!
+//! Note that this code is for tests only and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{aliases::U8, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolError};
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// Returns the hook's selector.
+pub const ACCEPT: u8 = 0;
+/// Returns a value other than the selector.
+pub const REJECT: u8 = 1;
+/// Reverts with `ReceiverReverted()`.
+pub const REVERT: u8 = 2;
+/// Reverts without data.
+pub const REVERT_EMPTY: u8 = 3;
+/// Calls the caller back, then accepts.
+pub const REENTER: u8 = 4;
+
+/// What `REJECT` returns instead of the selector.
+const REJECTED: [u8; 4] = [0; 4];
+
+/// ERC-1155 receiver, `0x4e2312e0`.
+const IERC1155_RECEIVER: Interface = interface!(
+    "onERC1155Received"(Address, Address, U256, U256, Bytes),
+    "onERC1155BatchReceived"(Address, Address, Vec<U256>, Vec<U256>, Bytes),
+);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc1155ReceiverMock {
+        /// How the hooks answer, one of the constants above
+        uint8 behavior;
+        /// Calldata `REENTER` sends back to the caller
+        bytes reentry;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Received(address operator, address from, uint256 id, uint256 value, bytes data);
+    event BatchReceived(address operator, address from, uint256[] ids, uint256[] values, bytes data);
+
+    // `REVERT` behavior
+    error ReceiverReverted();
+    // Behavior isn't one of the constants
+    error InvalidBehavior(uint8 behavior);
+}
+
+#[public]
+impl Erc1155ReceiverMock {
+    /// Sets how the hooks answer, and the calldata `REENTER` calls the caller back with.
+    pub fn set_behavior(&mut self, behavior: u8, reentry: Bytes) -> Result<(), Vec<u8>> {
+        if behavior > REENTER {
+            return Err(InvalidBehavior { behavior }.abi_encode());
+        }
+        self.behavior.set(U8::from(behavior));
+        self.reentry.set_bytes(reentry);
+        Ok(())
+    }
+
+    pub fn behavior(&self) -> u8 {
+        self.behavior.get().to()
+    }
+
+    #[selector(name = "onERC1155Received")]
+    pub fn on_erc1155_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        let accepted = self.answer(IERC1155_RECEIVER.selectors()[0])?;
+        log(self.vm(), Received { operator, from, id, value, data: data.0.into() });
+        Ok(accepted)
+    }
+
+    #[selector(name = "onERC1155BatchReceived")]
+    pub fn on_erc1155_batch_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        let accepted = self.answer(IERC1155_RECEIVER.selectors()[1])?;
+        log(self.vm(), BatchReceived { operator, from, ids, values, data: data.0.into() });
+        Ok(accepted)
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC1155_RECEIVER])
+    }
+}
+
+impl Erc1155ReceiverMock {
+    /// What a hook with `selector` returns, or its revert data.
+    fn answer(&mut self, selector: [u8; 4]) -> Result<FixedBytes<4>, Vec<u8>> {
+        match self.behavior() {
+            REJECT => Ok(FixedBytes(REJECTED)),
+            REVERT => Err(ReceiverReverted {}.abi_encode()),
+            REVERT_EMPTY => Err(Vec::new()),
+            REENTER => {
+                let caller = self.vm().msg_sender();
+                let calldata = self.reentry.get_bytes();
+                self.vm().call(&calls::context::Call::new(), caller, &calldata).map_err(Vec::<u8>::from)?;
+                Ok(FixedBytes(selector))
+            }
+            _ => Ok(FixedBytes(selector)),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use alloc::vec;
+use alloy_sol_types::SolEvent;
+use stylus_sdk::{alloy_primitives::address, testing::TestVM};
+
+const TOKEN: Address = address!("0000000000000000000000000000000000001155");
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+
+/// A receiver set to `behavior`, called by `TOKEN`.
+fn setup(behavior: u8, reentry: Vec<u8>) -> (TestVM, Erc1155ReceiverMock) {
+    let vm = TestVM::new();
+    let mut receiver = Erc1155ReceiverMock::from(&vm);
+    receiver.set_behavior(behavior, reentry.into()).unwrap();
+    vm.set_sender(TOKEN);
+    (vm, receiver)
+}
+
+fn receive(receiver: &mut Erc1155ReceiverMock) -> Result<FixedBytes<4>, Vec<u8>> {
+    receiver.on_erc1155_received(TOKEN, ALICE, U256::from(1), U256::from(10), vec![7].into())
+}
+
+fn receive_batch(receiver: &mut Erc1155ReceiverMock) -> Result<FixedBytes<4>, Vec<u8>> {
+    receiver.on_erc1155_batch_received(TOKEN, ALICE, vec![U256::from(1)], vec![U256::from(10)], vec![].into())
+}
+
+#[test]
+fn accepts_with_the_selectors_and_logs_the_transfer() {
+    let (vm, mut receiver) = setup(ACCEPT, vec![]);
+    assert_eq!(receive(&mut receiver).unwrap(), FixedBytes([0xf2, 0x3a, 0x6e, 0x61]));
+    assert_eq!(receive_batch(&mut receiver).unwrap(), FixedBytes([0xbc, 0x19, 0x7c, 0x81]));
+
+    let logs = vm.get_emitted_logs();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].0[0], Received::SIGNATURE_HASH);
+    let received = Received::decode_raw_log(logs[0].0.iter().copied(), &logs[0].1, true).unwrap();
+    assert_eq!((received.operator, received.from, received.value), (TOKEN, ALICE, U256::from(10)));
+    assert_eq!(received.data.to_vec(), vec![7]);
+    assert_eq!(logs[1].0[0], BatchReceived::SIGNATURE_HASH);
+}
+
+#[test]
+fn rejects_and_reverts_when_told_to() {
+    let (_vm, mut receiver) = setup(REJECT, vec![]);
+    assert_eq!(receive(&mut receiver).unwrap(), FixedBytes(REJECTED));
+    assert_eq!(receive_batch(&mut receiver).unwrap(), FixedBytes(REJECTED));
+
+    receiver.set_behavior(REVERT, vec![].into()).unwrap();
+    assert_eq!(receive(&mut receiver), Err(ReceiverReverted {}.abi_encode()));
+    receiver.set_behavior(REVERT_EMPTY, vec![].into()).unwrap();
+    assert_eq!(receive_batch(&mut receiver), Err(vec![]));
+
+    assert_eq!(
+        receiver.set_behavior(REENTER + 1, vec![].into()),
+        Err(InvalidBehavior { behavior: REENTER + 1 }.abi_encode())
+    );
+    assert_eq!(receiver.behavior(), REVERT_EMPTY);
+}
+
+#[test]
+fn reenters_the_caller() {
+    let reentry = vec![0xde, 0xad, 0xbe, 0xef];
+    let (vm, mut receiver) = setup(REENTER, reentry.clone());
+    vm.mock_call(TOKEN, reentry.clone(), Ok(vec![]));
+    assert_eq!(receive(&mut receiver).unwrap(), FixedBytes([0xf2, 0x3a, 0x6e, 0x61]));
+
+    // A reentry the token refuses fails the hook with the token's revert data
+    vm.mock_call(TOKEN, reentry, Err(vec![1, 2, 3]));
+    assert_eq!(receive(&mut receiver), Err(vec![1, 2, 3]));
+}
+
+#[test]
+fn supports_the_receiver_interface() {
+    let (_vm, receiver) = setup(ACCEPT, vec![]);
+    assert!(receiver.supports_interface(FixedBytes([0x4e, 0x23, 0x12, 0xe0])));
+    assert!(receiver.supports_interface(FixedBytes([0x01, 0xff, 0xc9, 0xa7])));
+    assert!(!receiver.supports_interface(FixedBytes([0xd9, 0xb6, 0x7a, 0x26])));
+}
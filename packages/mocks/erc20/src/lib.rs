@@ -0,0 +1,151 @@
+//! ERC-20 mock
+//!
+//! A plain ERC-20 named "Mock Token" (`MOCK`, 18 decimals) that anyone can `mint` and `burn`, so
+//! tests can fund accounts with a payment token without an owner or a role to set up. Failures
+//! revert with the ERC-6093 errors.
+//!
+//! Note that this code is for tests only and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::prelude::*;
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc20Mock {
+        mapping(address => uint256) balances;
+        mapping(address => mapping(address => uint256)) allowances;
+        uint256 total_supply;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    error ERC20InvalidSender(address sender);
+    error ERC20InvalidReceiver(address receiver);
+    error ERC20InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    error ERC20InvalidApprover(address approver);
+    error ERC20InvalidSpender(address spender);
+}
+
+#[public]
+impl Erc20Mock {
+    pub fn name(&self) -> String {
+        "Mock Token".into()
+    }
+
+    pub fn symbol(&self) -> String {
+        "MOCK".into()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        18
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balances.get(owner)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
+
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Vec<u8>> {
+        let from = self.vm().msg_sender();
+        self.transfer_internal(from, to, value)?;
+        Ok(true)
+    }
+
+    pub fn transfer_from(&mut self, from: Address, to: Address, value: U256) -> Result<bool, Vec<u8>> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowance(from, spender);
+        if allowance != U256::MAX {
+            if allowance < value {
+                return Err(ERC20InsufficientAllowance { spender, allowance, needed: value }.abi_encode());
+            }
+            self.allowances.setter(from).insert(spender, allowance - value);
+        }
+        self.transfer_internal(from, to, value)?;
+        Ok(true)
+    }
+
+    pub fn approve(&mut self, spender: Address, value: U256) -> Result<bool, Vec<u8>> {
+        let owner = self.vm().msg_sender();
+        if owner.is_zero() {
+            return Err(ERC20InvalidApprover { approver: owner }.abi_encode());
+        }
+        if spender.is_zero() {
+            return Err(ERC20InvalidSpender { spender }.abi_encode());
+        }
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval { owner, spender, value });
+        Ok(true)
+    }
+
+    /// Mints `value` to `to`. Anyone can mint.
+    pub fn mint(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        if to.is_zero() {
+            return Err(ERC20InvalidReceiver { receiver: to }.abi_encode());
+        }
+        self.update(Address::ZERO, to, value)
+    }
+
+    /// Burns `value` of `from`'s tokens. Anyone can burn, without an allowance.
+    pub fn burn(&mut self, from: Address, value: U256) -> Result<(), Vec<u8>> {
+        if from.is_zero() {
+            return Err(ERC20InvalidSender { sender: from }.abi_encode());
+        }
+        self.update(from, Address::ZERO, value)
+    }
+}
+
+impl Erc20Mock {
+    fn transfer_internal(&mut self, from: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        if from.is_zero() {
+            return Err(ERC20InvalidSender { sender: from }.abi_encode());
+        }
+        if to.is_zero() {
+            return Err(ERC20InvalidReceiver { receiver: to }.abi_encode());
+        }
+        self.update(from, to, value)
+    }
+
+    /// Moves `value` from `from` to `to`, minting from or burning to the zero address.
+    fn update(&mut self, from: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        if from.is_zero() {
+            let supply = self.total_supply.get();
+            self.total_supply.set(supply.checked_add(value).ok_or_else(Vec::new)?);
+        } else {
+            let balance = self.balances.get(from);
+            if balance < value {
+                return Err(ERC20InsufficientBalance { sender: from, balance, needed: value }.abi_encode());
+            }
+            self.balances.insert(from, balance - value);
+        }
+        if to.is_zero() {
+            let supply = self.total_supply.get();
+            self.total_supply.set(supply - value);
+        } else {
+            let balance = self.balances.get(to);
+            self.balances.insert(to, balance + value);
+        }
+        log(self.vm(), Transfer { from, to, value });
+        Ok(())
+    }
+}
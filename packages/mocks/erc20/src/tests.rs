@@ -0,0 +1,61 @@
+use super::*;
+use alloy_sol_types::SolEvent;
+use stylus_sdk::{alloy_primitives::address, testing::TestVM};
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+
+fn setup() -> (TestVM, Erc20Mock) {
+    let vm = TestVM::new();
+    let token = Erc20Mock::from(&vm);
+    vm.set_sender(ALICE);
+    (vm, token)
+}
+
+#[test]
+fn anyone_can_mint_and_burn() {
+    let (vm, mut token) = setup();
+    vm.set_sender(BOB);
+    token.mint(ALICE, U256::from(100)).unwrap();
+    token.burn(ALICE, U256::from(40)).unwrap();
+    assert_eq!(token.balance_of(ALICE), U256::from(60));
+    assert_eq!(token.total_supply(), U256::from(60));
+
+    assert_eq!(
+        token.burn(ALICE, U256::from(61)),
+        Err(ERC20InsufficientBalance { sender: ALICE, balance: U256::from(60), needed: U256::from(61) }.abi_encode())
+    );
+    assert_eq!(
+        token.mint(Address::ZERO, U256::from(1)),
+        Err(ERC20InvalidReceiver { receiver: Address::ZERO }.abi_encode())
+    );
+
+    let logs = vm.get_emitted_logs();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].0[0], Transfer::SIGNATURE_HASH);
+}
+
+#[test]
+fn transfers_and_spends_allowances() {
+    let (vm, mut token) = setup();
+    token.mint(ALICE, U256::from(100)).unwrap();
+    assert!(token.transfer(BOB, U256::from(30)).unwrap());
+    assert!(token.approve(BOB, U256::from(50)).unwrap());
+
+    vm.set_sender(BOB);
+    assert!(token.transfer_from(ALICE, BOB, U256::from(20)).unwrap());
+    assert_eq!(token.allowance(ALICE, BOB), U256::from(30));
+    assert_eq!(
+        token.transfer_from(ALICE, BOB, U256::from(31)),
+        Err(ERC20InsufficientAllowance { spender: BOB, allowance: U256::from(30), needed: U256::from(31) }.abi_encode())
+    );
+    assert_eq!(token.balance_of(ALICE), U256::from(50));
+    assert_eq!(token.balance_of(BOB), U256::from(50));
+
+    // An unlimited allowance isn't spent
+    vm.set_sender(ALICE);
+    token.approve(BOB, U256::MAX).unwrap();
+    vm.set_sender(BOB);
+    token.transfer_from(ALICE, BOB, U256::from(10)).unwrap();
+    assert_eq!(token.allowance(ALICE, BOB), U256::MAX);
+}
@@ -0,0 +1,203 @@
+//! ERC-721 mock
+//!
+//! A plain ERC-721 that anyone can `mint` and `burn`, so tests can hand out NFTs without an owner
+//! or a role to set up. `safeTransferFrom` and `safe_mint` call `onERC721Received` on contract
+//! recipients, and failures revert with the ERC-6093 errors.
+//!
+//! Note that this code is for tests only and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolError, SolValue};
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{abi::Bytes, prelude::*};
+
+/// ERC-721, `0x80ac58cd`.
+pub const IERC721: Interface = interface!(
+    "balanceOf"(Address),
+    "ownerOf"(U256),
+    "safeTransferFrom"(Address, Address, U256, Bytes),
+    "safeTransferFrom"(Address, Address, U256),
+    "transferFrom"(Address, Address, U256),
+    "approve"(Address, U256),
+    "setApprovalForAll"(Address, bool),
+    "getApproved"(U256),
+    "isApprovedForAll"(Address, Address),
+);
+
+sol_storage! {
+    #[entrypoint]
+    pub struct Erc721Mock {
+        mapping(uint256 => address) owners;
+        mapping(address => uint256) balances;
+        mapping(uint256 => address) token_approvals;
+        mapping(address => mapping(address => bool)) operator_approvals;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+    error ERC721InvalidOwner(address owner);
+    error ERC721NonexistentToken(uint256 token_id);
+    error ERC721IncorrectOwner(address sender, uint256 token_id, address owner);
+    error ERC721InvalidSender(address sender);
+    error ERC721InvalidReceiver(address receiver);
+    error ERC721InsufficientApproval(address operator, uint256 token_id);
+    error ERC721InvalidOperator(address operator);
+
+    function onERC721Received(address operator, address from, uint256 token_id, bytes data) returns (bytes4);
+}
+
+#[public]
+impl Erc721Mock {
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Vec<u8>> {
+        if owner.is_zero() {
+            return Err(ERC721InvalidOwner { owner }.abi_encode());
+        }
+        Ok(self.balances.get(owner))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Vec<u8>> {
+        let owner = self.owners.get(token_id);
+        if owner.is_zero() {
+            return Err(ERC721NonexistentToken { token_id }.abi_encode());
+        }
+        Ok(owner)
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Vec<u8>> {
+        self.owner_of(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(owner).get(operator)
+    }
+
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Vec<u8>> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if sender != owner && !self.is_approved_for_all(owner, sender) {
+            return Err(ERC721InsufficientApproval { operator: sender, token_id }.abi_encode());
+        }
+        self.token_approvals.insert(token_id, approved);
+        log(self.vm(), Approval { owner, approved, token_id });
+        Ok(())
+    }
+
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Vec<u8>> {
+        if operator.is_zero() {
+            return Err(ERC721InvalidOperator { operator }.abi_encode());
+        }
+        let owner = self.vm().msg_sender();
+        self.operator_approvals.setter(owner).insert(operator, approved);
+        log(self.vm(), ApprovalForAll { owner, operator, approved });
+        Ok(())
+    }
+
+    pub fn transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Vec<u8>> {
+        let owner = self.owner_of(token_id)?;
+        let sender = self.vm().msg_sender();
+        if sender != owner && !self.is_approved_for_all(owner, sender) && self.token_approvals.get(token_id) != sender {
+            return Err(ERC721InsufficientApproval { operator: sender, token_id }.abi_encode());
+        }
+        if owner != from {
+            return Err(ERC721IncorrectOwner { sender: from, token_id, owner }.abi_encode());
+        }
+        if to.is_zero() {
+            return Err(ERC721InvalidReceiver { receiver: to }.abi_encode());
+        }
+        self.update(to, token_id);
+        Ok(())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(&mut self, from: Address, to: Address, token_id: U256) -> Result<(), Vec<u8>> {
+        self.safe_transfer_from_with_data(from, to, token_id, Vec::new().into())
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        self.transfer_from(from, to, token_id)?;
+        self.check_received(from, to, token_id, data)
+    }
+
+    /// Mints `token_id` to `to`. Anyone can mint.
+    pub fn mint(&mut self, to: Address, token_id: U256) -> Result<(), Vec<u8>> {
+        if to.is_zero() {
+            return Err(ERC721InvalidReceiver { receiver: to }.abi_encode());
+        }
+        if self.owners.get(token_id) != Address::ZERO {
+            return Err(ERC721InvalidSender { sender: Address::ZERO }.abi_encode());
+        }
+        self.update(to, token_id);
+        Ok(())
+    }
+
+    /// Mints `token_id` to `to`, checking that a contract recipient accepts it.
+    pub fn safe_mint(&mut self, to: Address, token_id: U256) -> Result<(), Vec<u8>> {
+        self.mint(to, token_id)?;
+        self.check_received(Address::ZERO, to, token_id, Vec::new().into())
+    }
+
+    /// Burns `token_id`. Anyone can burn, without an approval.
+    pub fn burn(&mut self, token_id: U256) -> Result<(), Vec<u8>> {
+        self.owner_of(token_id)?;
+        self.update(Address::ZERO, token_id);
+        Ok(())
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        cradle_introspection::supports_interface(interface_id, [IERC721])
+    }
+}
+
+impl Erc721Mock {
+    /// Moves `token_id` to `to`, burning it for the zero address, and clears its approval.
+    fn update(&mut self, to: Address, token_id: U256) {
+        let from = self.owners.get(token_id);
+        if !from.is_zero() {
+            let balance = self.balances.get(from);
+            self.balances.insert(from, balance - U256::from(1));
+            self.token_approvals.delete(token_id);
+        }
+        if !to.is_zero() {
+            let balance = self.balances.get(to);
+            self.balances.insert(to, balance + U256::from(1));
+        }
+        self.owners.insert(token_id, to);
+        log(self.vm(), Transfer { from, to, token_id });
+    }
+
+    /// Calls `onERC721Received` if `to` is a contract, requiring it to return the selector.
+    fn check_received(&mut self, from: Address, to: Address, token_id: U256, data: Bytes) -> Result<(), Vec<u8>> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+        let operator = self.vm().msg_sender();
+        let call = onERC721ReceivedCall { operator, from, token_id, data: data.0.into() };
+        let rejected = || ERC721InvalidReceiver { receiver: to }.abi_encode();
+        let result = self.vm().call(&calls::context::Call::new(), to, &call.abi_encode()).map_err(|_| rejected())?;
+        match FixedBytes::<4>::abi_decode(&result, true) {
+            Ok(selector) if selector == onERC721ReceivedCall::SELECTOR => Ok(()),
+            _ => Err(rejected()),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+use alloc::vec;
+use stylus_sdk::{alloy_primitives::address, testing::TestVM};
+
+const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+const RECEIVER: Address = address!("0000000000000000000000000000000000000721");
+
+fn setup() -> (TestVM, Erc721Mock) {
+    let vm = TestVM::new();
+    let mut token = Erc721Mock::from(&vm);
+    vm.set_sender(ALICE);
+    token.mint(ALICE, U256::from(1)).unwrap();
+    (vm, token)
+}
+
+/// The `onERC721Received` calldata for ALICE sending `token_id` to `RECEIVER`.
+fn received_call(token_id: u64) -> Vec<u8> {
+    onERC721ReceivedCall { operator: ALICE, from: ALICE, token_id: U256::from(token_id), data: Default::default() }
+        .abi_encode()
+}
+
+#[test]
+fn anyone_can_mint_and_burn() {
+    let (vm, mut token) = setup();
+    assert_eq!(token.owner_of(U256::from(1)).unwrap(), ALICE);
+    assert_eq!(token.mint(BOB, U256::from(1)), Err(ERC721InvalidSender { sender: Address::ZERO }.abi_encode()));
+
+    vm.set_sender(BOB);
+    token.burn(U256::from(1)).unwrap();
+    assert_eq!(token.balance_of(ALICE).unwrap(), U256::ZERO);
+    assert_eq!(token.owner_of(U256::from(1)), Err(ERC721NonexistentToken { token_id: U256::from(1) }.abi_encode()));
+}
+
+#[test]
+fn transfers_need_an_approval() {
+    let (vm, mut token) = setup();
+    vm.set_sender(BOB);
+    assert_eq!(
+        token.transfer_from(ALICE, BOB, U256::from(1)),
+        Err(ERC721InsufficientApproval { operator: BOB, token_id: U256::from(1) }.abi_encode())
+    );
+
+    vm.set_sender(ALICE);
+    token.approve(BOB, U256::from(1)).unwrap();
+    vm.set_sender(BOB);
+    token.transfer_from(ALICE, BOB, U256::from(1)).unwrap();
+    assert_eq!(token.owner_of(U256::from(1)).unwrap(), BOB);
+    assert_eq!(token.get_approved(U256::from(1)).unwrap(), Address::ZERO);
+
+    token.set_approval_for_all(ALICE, true).unwrap();
+    vm.set_sender(ALICE);
+    token.safe_transfer_from(BOB, ALICE, U256::from(1)).unwrap();
+    assert_eq!(token.balance_of(ALICE).unwrap(), U256::from(1));
+}
+
+#[test]
+fn safe_transfers_check_contract_recipients() {
+    let (vm, mut token) = setup();
+    vm.set_code(RECEIVER, vec![0xfe]);
+    vm.mock_call(RECEIVER, received_call(1), Ok(onERC721ReceivedCall::SELECTOR.abi_encode()));
+    token.safe_transfer_from(ALICE, RECEIVER, U256::from(1)).unwrap();
+    assert_eq!(token.owner_of(U256::from(1)).unwrap(), RECEIVER);
+
+    // TestVM doesn't roll back a failed call, so the refusal gets its own token
+    token.mint(ALICE, U256::from(2)).unwrap();
+    vm.mock_call(RECEIVER, received_call(2), Ok(FixedBytes([0; 4]).abi_encode()));
+    assert_eq!(
+        token.safe_transfer_from(ALICE, RECEIVER, U256::from(2)),
+        Err(ERC721InvalidReceiver { receiver: RECEIVER }.abi_encode())
+    );
+}
+
+#[test]
+fn supports_erc721() {
+    let (_vm, token) = setup();
+    assert_eq!(IERC721.id(), 0x80ac58cd);
+    assert!(token.supports_interface(FixedBytes(0x80ac58cd_u32.to_be_bytes())));
+    assert!(token.supports_interface(FixedBytes(0x01ffc9a7_u32.to_be_bytes())));
+}
@@ -0,0 +1,190 @@
+//! Signs the EIP-712 messages Cradle's components verify on-chain.
+//!
+//! A backend holding a signing key uses this to authorize what the contracts only accept with its
+//! signature: sponsoring a user operation with `paymaster-stylus`, letting an account `drip` from
+//! `faucet-stylus` once it passed a captcha, or relaying its own meta-transactions through
+//! `forwarder-stylus`. The message types are declared once here with `sol!`, and the tests check
+//! their type strings and typehashes against the constants in the contracts' sources, so a change
+//! to either side that would make the hashes differ fails the build's tests instead of
+//! signatures on-chain.
+//!
+//! ```no_run
+//! use alloy_primitives::{address, U256};
+//! use cradle_signer::{Drip, Signer};
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let signer = Signer::new("0x...".parse()?, 421614);
+//! let faucet = address!("a6e41ffd769491a42a6e5ce453259b93983a22ef");
+//! let recipient = address!("00000000000000000000000000000000000a11ce");
+//! // The nonce is the faucet's `nonces(recipient)`
+//! let drip = Drip { recipient, nonce: U256::ZERO, deadline: U256::from(1_700_000_000) };
+//! let signature = signer.drip(faucet, &drip)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use alloy_primitives::{aliases::U48, keccak256, Address, Bytes, B256, U256};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{eip712_domain, sol, Eip712Domain, SolStruct, SolValue};
+
+#[cfg(test)]
+mod tests;
+
+/// Name in `forwarder-stylus`'s EIP-712 domain.
+pub const FORWARDER_NAME: &str = "Cradle Forwarder";
+/// Name in `paymaster-stylus`'s EIP-712 domain.
+pub const PAYMASTER_NAME: &str = "Cradle Paymaster";
+/// Name in `faucet-stylus`'s EIP-712 domain.
+pub const FAUCET_NAME: &str = "Cradle Faucet";
+/// Version in each component's domain.
+pub const DOMAIN_VERSION: &str = "1";
+
+sol! {
+    /// A call `from` signs for `forwarder-stylus` to relay. `nonce` is the forwarder's
+    /// `nonces(from)`.
+    #[derive(Debug, PartialEq, Eq)]
+    struct ForwardRequest {
+        address from;
+        address to;
+        uint256 value;
+        uint256 gas;
+        uint256 nonce;
+        uint256 deadline;
+        bytes data;
+    }
+
+    /// What `paymaster-stylus` checks its signer sponsored: the user operation with its byte
+    /// fields hashed, and the time window it may be included in.
+    #[derive(Debug, PartialEq, Eq)]
+    struct SponsoredUserOperation {
+        address sender;
+        uint256 nonce;
+        bytes32 initCodeHash;
+        bytes32 callDataHash;
+        bytes32 accountGasLimits;
+        bytes32 paymasterGasLimits;
+        uint256 preVerificationGas;
+        bytes32 gasFees;
+        uint48 validUntil;
+        uint48 validAfter;
+    }
+
+    /// A drip `faucet-stylus` may send to `recipient` until `deadline`. `nonce` is the faucet's
+    /// `nonces(recipient)`.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Drip {
+        address recipient;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+/// An ERC-4337 v0.7 user operation, without the paymaster fields and signature the sponsorship
+/// adds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    #[serde(default)]
+    pub init_code: Bytes,
+    pub call_data: Bytes,
+    /// `verificationGasLimit` and `callGasLimit`, 16 bytes each
+    pub account_gas_limits: B256,
+    pub pre_verification_gas: U256,
+    /// `maxPriorityFeePerGas` and `maxFeePerGas`, 16 bytes each
+    pub gas_fees: B256,
+    /// Gas the EntryPoint gives the paymaster's validation
+    pub paymaster_verification_gas_limit: u128,
+    /// Gas the EntryPoint gives the paymaster's `postOp`
+    pub paymaster_post_op_gas_limit: u128,
+}
+
+impl UserOperation {
+    /// `paymasterVerificationGasLimit` and `paymasterPostOpGasLimit` as packed in
+    /// `paymasterAndData`.
+    pub fn paymaster_gas_limits(&self) -> B256 {
+        let mut limits = B256::ZERO;
+        limits[..16].copy_from_slice(&self.paymaster_verification_gas_limit.to_be_bytes());
+        limits[16..].copy_from_slice(&self.paymaster_post_op_gas_limit.to_be_bytes());
+        limits
+    }
+}
+
+/// A signing key and the chain its signatures are for.
+#[derive(Clone, Debug)]
+pub struct Signer {
+    key: PrivateKeySigner,
+    chain_id: u64,
+}
+
+impl Signer {
+    pub fn new(key: PrivateKeySigner, chain_id: u64) -> Self {
+        Self { key, chain_id }
+    }
+
+    /// The account the components must be configured to trust, like the paymaster's `signer`.
+    pub fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    /// The EIP-712 domain of the component named `name` at `contract`.
+    pub fn domain(&self, name: &'static str, contract: Address) -> Eip712Domain {
+        eip712_domain! {
+            name: name,
+            version: DOMAIN_VERSION,
+            chain_id: self.chain_id,
+            verifying_contract: contract,
+        }
+    }
+
+    /// 65-byte `(r, s, v)` signature of `message` under `domain`, with a low `s` and `v` of 27
+    /// or 28, as the components' `ecrecover` checks expect.
+    pub fn sign<T: SolStruct>(&self, message: &T, domain: &Eip712Domain) -> alloy_signer::Result<Bytes> {
+        let signature = self.key.sign_hash_sync(&message.eip712_signing_hash(domain))?;
+        Ok(Bytes::copy_from_slice(&signature.as_bytes()))
+    }
+
+    /// Signature of `request` for `forwarder`'s `execute`. The signer must be `request.from`.
+    pub fn forward_request(&self, forwarder: Address, request: &ForwardRequest) -> alloy_signer::Result<Bytes> {
+        self.sign(request, &self.domain(FORWARDER_NAME, forwarder))
+    }
+
+    /// Signature of `drip` for `faucet`'s `drip`.
+    pub fn drip(&self, faucet: Address, drip: &Drip) -> alloy_signer::Result<Bytes> {
+        self.sign(drip, &self.domain(FAUCET_NAME, faucet))
+    }
+
+    /// The `paymasterAndData` with which `paymaster` pays for `operation` between `valid_after`
+    /// and `valid_until`, zero for no end: the paymaster, its gas limits, the window and the
+    /// signature over them.
+    pub fn sponsor(
+        &self,
+        paymaster: Address,
+        operation: &UserOperation,
+        valid_until: u64,
+        valid_after: u64,
+    ) -> alloy_signer::Result<Bytes> {
+        let sponsorship = SponsoredUserOperation {
+            sender: operation.sender,
+            nonce: operation.nonce,
+            initCodeHash: keccak256(&operation.init_code),
+            callDataHash: keccak256(&operation.call_data),
+            accountGasLimits: operation.account_gas_limits,
+            paymasterGasLimits: operation.paymaster_gas_limits(),
+            preVerificationGas: operation.pre_verification_gas,
+            gasFees: operation.gas_fees,
+            validUntil: U48::from(valid_until),
+            validAfter: U48::from(valid_after),
+        };
+        let signature = self.sign(&sponsorship, &self.domain(PAYMASTER_NAME, paymaster))?;
+
+        let mut data = Vec::with_capacity(116 + signature.len());
+        data.extend_from_slice(paymaster.as_slice());
+        data.extend_from_slice(operation.paymaster_gas_limits().as_slice());
+        data.extend_from_slice(&(U256::from(valid_until), U256::from(valid_after)).abi_encode());
+        data.extend_from_slice(&signature);
+        Ok(data.into())
+    }
+}
@@ -0,0 +1,99 @@
+//! Prints the signatures `cradle-signer` makes, for scripts and for checking a backend's.
+
+use std::{fs, io::Read, path::PathBuf};
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_signer_local::PrivateKeySigner;
+use clap::{Parser, Subcommand};
+use cradle_signer::{Drip, ForwardRequest, Signer, UserOperation};
+use eyre::{ensure, WrapErr};
+
+#[derive(Parser)]
+struct Args {
+    #[arg(long, env = "SIGNER_KEY", hide_env_values = true)]
+    private_key: String,
+    /// Chain the signatures are for
+    #[arg(long)]
+    chain_id: u64,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the signer's address, to configure the components with
+    Address,
+    /// Sign a drip for faucet-stylus
+    Drip {
+        #[arg(long)]
+        faucet: Address,
+        #[arg(long)]
+        recipient: Address,
+        /// The faucet's `nonces(recipient)`
+        #[arg(long)]
+        nonce: U256,
+        #[arg(long)]
+        deadline: U256,
+    },
+    /// Sign a request from the signer's account for forwarder-stylus
+    Forward {
+        #[arg(long)]
+        forwarder: Address,
+        #[arg(long)]
+        to: Address,
+        #[arg(long, default_value = "0")]
+        value: U256,
+        #[arg(long)]
+        gas: U256,
+        /// The forwarder's `nonces(signer)`
+        #[arg(long)]
+        nonce: U256,
+        #[arg(long)]
+        deadline: U256,
+        #[arg(long, default_value = "0x")]
+        data: Bytes,
+    },
+    /// Print the `paymasterAndData` sponsoring a user operation with paymaster-stylus
+    Sponsor {
+        #[arg(long)]
+        paymaster: Address,
+        /// Zero for no end
+        #[arg(long, default_value_t = 0)]
+        valid_until: u64,
+        #[arg(long, default_value_t = 0)]
+        valid_after: u64,
+        /// JSON file of the user operation, camelCase as in ERC-4337, or `-` for stdin
+        operation: PathBuf,
+    },
+}
+
+fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let key: PrivateKeySigner = args.private_key.parse().wrap_err("invalid private key")?;
+    let signer = Signer::new(key, args.chain_id);
+
+    let output = match args.command {
+        Command::Address => signer.address().to_string(),
+        Command::Drip { faucet, recipient, nonce, deadline } => {
+            signer.drip(faucet, &Drip { recipient, nonce, deadline })?.to_string()
+        }
+        Command::Forward { forwarder, to, value, gas, nonce, deadline, data } => {
+            let request = ForwardRequest { from: signer.address(), to, value, gas, nonce, deadline, data };
+            signer.forward_request(forwarder, &request)?.to_string()
+        }
+        Command::Sponsor { paymaster, valid_until, valid_after, operation } => {
+            ensure!(valid_until == 0 || valid_until > valid_after, "--valid-until must be after --valid-after");
+            let json = if operation.as_os_str() == "-" {
+                let mut json = String::new();
+                std::io::stdin().read_to_string(&mut json)?;
+                json
+            } else {
+                fs::read_to_string(&operation).wrap_err_with(|| format!("can't read {}", operation.display()))?
+            };
+            let operation: UserOperation = serde_json::from_str(&json).wrap_err("invalid user operation")?;
+            signer.sponsor(paymaster, &operation, valid_until, valid_after)?.to_string()
+        }
+    };
+    println!("{output}");
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use alloy_primitives::{address, b256, bytes, keccak256, Address, Bytes, PrimitiveSignature, B256, U256};
+use alloy_sol_types::{SolStruct, SolValue};
+
+use crate::{
+    Drip, ForwardRequest, Signer, SponsoredUserOperation, UserOperation, FAUCET_NAME, FORWARDER_NAME, PAYMASTER_NAME,
+};
+
+const FORWARDER: &str = include_str!("../../components/forwarder-stylus/contract/forwarder/src/lib.rs");
+const PAYMASTER: &str = include_str!("../../components/paymaster-stylus/contract/paymaster/src/lib.rs");
+const FAUCET: &str = include_str!("../../components/faucet-stylus/contract/faucet/src/lib.rs");
+
+const CONTRACT: Address = address!("a6e41ffd769491a42a6e5ce453259b93983a22ef");
+const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`
+const DOMAIN_TYPEHASH: B256 = b256!("8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f");
+
+fn signer() -> Signer {
+    let key = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9acb659".parse().unwrap();
+    Signer::new(key, 412346)
+}
+
+/// The type string in the doc comment above the `name` constant in `source`, and its value.
+fn typehash(source: &str, name: &str) -> (String, B256) {
+    let at = source.find(&format!("const {name}: B256")).unwrap_or_else(|| panic!("no {name}"));
+    let doc = source[..at].lines().rev().find(|line| line.trim_start().starts_with("///")).unwrap();
+    let doc = doc.split("keccak256(\"").nth(1).and_then(|rest| rest.split("\")").next()).unwrap();
+    let value = source[at..].split("b256!(\"").nth(1).and_then(|rest| rest.split('"').next()).unwrap();
+    (doc.to_string(), value.parse().unwrap())
+}
+
+fn domain_name(source: &str) -> &str {
+    source.split("const DOMAIN_NAME: &str = \"").nth(1).and_then(|rest| rest.split('"').next()).unwrap()
+}
+
+fn operation() -> UserOperation {
+    UserOperation {
+        sender: ALICE,
+        nonce: U256::from(3),
+        init_code: Bytes::new(),
+        call_data: bytes!("b61d27f6"),
+        account_gas_limits: B256::with_last_byte(1),
+        pre_verification_gas: U256::from(50_000),
+        gas_fees: B256::with_last_byte(2),
+        paymaster_verification_gas_limit: 100_000,
+        paymaster_post_op_gas_limit: 50_000,
+    }
+}
+
+#[test]
+fn types_match_the_contracts() {
+    let types = [
+        (FORWARDER, "FORWARD_REQUEST_TYPEHASH", ForwardRequest::eip712_encode_type(), FORWARDER_NAME),
+        (PAYMASTER, "SPONSORED_USER_OPERATION_TYPEHASH", SponsoredUserOperation::eip712_encode_type(), PAYMASTER_NAME),
+        (FAUCET, "DRIP_TYPEHASH", Drip::eip712_encode_type(), FAUCET_NAME),
+    ];
+    for (source, constant, encoded, name) in types {
+        let (doc, value) = typehash(source, constant);
+        assert_eq!(doc, encoded, "{constant}");
+        assert_eq!(keccak256(doc.as_bytes()), value, "{constant} isn't the hash of its type string");
+        assert_eq!(domain_name(source), name);
+    }
+}
+
+#[test]
+fn signatures_recover_to_the_signer() {
+    let signer = signer();
+    let request = ForwardRequest {
+        from: signer.address(),
+        to: CONTRACT,
+        value: U256::ZERO,
+        gas: U256::from(100_000),
+        nonce: U256::ZERO,
+        deadline: U256::from(1_700_000_000),
+        data: bytes!("f242432a"),
+    };
+    let signature = signer.forward_request(CONTRACT, &request).unwrap();
+    assert_eq!(signature.len(), 65);
+    assert!(matches!(signature[64], 27 | 28));
+    let digest = request.eip712_signing_hash(&signer.domain(FORWARDER_NAME, CONTRACT));
+    let recovered = PrimitiveSignature::try_from(&signature[..]).unwrap().recover_address_from_prehash(&digest);
+    assert_eq!(recovered.unwrap(), signer.address());
+
+    // A signature for another faucet, or another chain, is over another digest
+    let drip = Drip { recipient: ALICE, nonce: U256::ZERO, deadline: U256::from(1_700_000_000) };
+    assert_ne!(signer.drip(CONTRACT, &drip).unwrap(), signer.drip(ALICE, &drip).unwrap());
+    let key = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9acb659".parse().unwrap();
+    assert_ne!(signer.drip(CONTRACT, &drip).unwrap(), Signer::new(key, 1).drip(CONTRACT, &drip).unwrap());
+}
+
+#[test]
+fn sponsorships_are_laid_out_as_the_paymaster_reads_them() {
+    let signer = signer();
+    let operation = operation();
+    let data = signer.sponsor(CONTRACT, &operation, 2_000, 1_000).unwrap();
+    assert_eq!(data.len(), 116 + 65);
+    assert_eq!(&data[..20], CONTRACT.as_slice());
+    assert_eq!(u128::from_be_bytes(data[20..36].try_into().unwrap()), 100_000);
+    assert_eq!(u128::from_be_bytes(data[36..52].try_into().unwrap()), 50_000);
+    assert_eq!(U256::from_be_slice(&data[52..84]), U256::from(2_000));
+    assert_eq!(U256::from_be_slice(&data[84..116]), U256::from(1_000));
+
+    // The paymaster hashes the sponsorship by hand rather than through cradle-crypto
+    let domain_separator = keccak256(
+        (DOMAIN_TYPEHASH, keccak256(PAYMASTER_NAME), keccak256("1"), U256::from(412346), CONTRACT).abi_encode(),
+    );
+    let struct_hash = keccak256(
+        (
+            typehash(PAYMASTER, "SPONSORED_USER_OPERATION_TYPEHASH").1,
+            operation.sender,
+            operation.nonce,
+            keccak256(&operation.init_code),
+            keccak256(&operation.call_data),
+            operation.account_gas_limits,
+            operation.paymaster_gas_limits(),
+            operation.pre_verification_gas,
+            operation.gas_fees,
+            U256::from(2_000),
+            U256::from(1_000),
+        )
+            .abi_encode(),
+    );
+    let digest = keccak256([&[0x19, 0x01][..], domain_separator.as_slice(), struct_hash.as_slice()].concat());
+    let signature = PrimitiveSignature::try_from(&data[116..]).unwrap();
+    assert_eq!(signature.recover_address_from_prehash(&digest).unwrap(), signer.address());
+}
+
+#[test]
+fn user_operations_parse_from_erc4337_json() {
+    let json = r#"{
+        "sender": "0x00000000000000000000000000000000000a11ce",
+        "nonce": "0x3",
+        "callData": "0xb61d27f6",
+        "accountGasLimits": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "preVerificationGas": "0xc350",
+        "gasFees": "0x0000000000000000000000000000000000000000000000000000000000000002",
+        "paymasterVerificationGasLimit": 100000,
+        "paymasterPostOpGasLimit": 50000
+    }"#;
+    assert_eq!(serde_json::from_str::<UserOperation>(json).unwrap(), operation());
+}
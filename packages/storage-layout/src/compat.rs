@@ -0,0 +1,121 @@
+//! Checking that a new layout reads the storage an old one wrote.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    layout::{Layout, Member},
+    types::Type,
+};
+
+/// Changes from `old` to `new` that would corrupt the storage of a deployment upgraded from one
+/// to the other. Appending fields, renaming fields and structs, growing structs that are only
+/// stored in mappings, and moving a field into a struct as its first member are fine.
+pub fn check(old: &Layout, new: &Layout) -> Vec<String> {
+    let mut checker = Checker { old, new, compared: BTreeSet::new(), problems: Vec::new() };
+    checker.members(&old.contract, &old.storage, &new.storage);
+    checker.problems
+}
+
+struct Checker<'a> {
+    old: &'a Layout,
+    new: &'a Layout,
+    /// Struct pairs already compared, as a struct is often used in more than one place
+    compared: BTreeSet<(String, String)>,
+    problems: Vec<String>,
+}
+
+impl Checker<'_> {
+    /// Every old member must still be at its slot and offset, under a type that reads it.
+    fn members(&mut self, scope: &str, old: &[Member], new: &[Member]) {
+        for member in old {
+            let path = format!("{scope}.{}", member.label);
+            let at = new.iter().find(|other| other.slot == member.slot && other.offset == member.offset);
+            let renamed_to = new.iter().find(|other| other.label == member.label);
+            let Some(at) = at.filter(|at| at.label == member.label || renamed_to.is_none()) else {
+                self.problems.push(match renamed_to {
+                    Some(to) => format!(
+                        "{path} moved from slot {} offset {} to slot {} offset {}",
+                        member.slot, member.offset, to.slot, to.offset
+                    ),
+                    None => format!("{path} at slot {} offset {} was removed", member.slot, member.offset),
+                });
+                continue;
+            };
+            match (member.ty.parse::<Type>(), at.ty.parse::<Type>()) {
+                (Ok(old_ty), Ok(new_ty)) => self.types(&path, &old_ty, &new_ty),
+                _ => self.problems.push(format!("{path} has a malformed type")),
+            }
+        }
+    }
+
+    fn types(&mut self, path: &str, old: &Type, new: &Type) {
+        match (old, new) {
+            (Type::Mapping(old_key, old_value), Type::Mapping(new_key, new_value)) => {
+                if old_key != new_key {
+                    self.problems.push(format!("{path} changed key type from {old_key} to {new_key}"));
+                } else {
+                    self.types(path, old_value, new_value);
+                }
+            }
+            (Type::Vec(old_element), Type::Vec(new_element)) => self.elements(path, old_element, new_element),
+            (Type::Array(old_element, old_length), Type::Array(new_element, new_length)) => {
+                if old_length != new_length {
+                    self.problems.push(format!("{path} changed length from {old_length} to {new_length}"));
+                } else {
+                    self.elements(path, old_element, new_element);
+                }
+            }
+            (Type::Struct(old_name), Type::Struct(new_name)) => self.structs(old_name, new_name),
+            (old, Type::Struct(new_name)) if old.slot_bytes() == 32 => self.wrapped(path, old, new_name),
+            (old, new) if old == new => {}
+            (old, new) => self.problems.push(format!("{path} changed type from {old} to {new}")),
+        }
+    }
+
+    /// Elements of vectors and arrays are laid out one after the other, so they can't grow.
+    fn elements(&mut self, path: &str, old: &Type, new: &Type) {
+        self.types(path, old, new);
+        let (old_slots, new_slots) = (required_slots(self.old, old), required_slots(self.new, new));
+        if old_slots != new_slots {
+            self.problems.push(format!("{path} elements changed from {old_slots} to {new_slots} slots"));
+        }
+    }
+
+    /// A field that takes a whole slot can be moved into a struct as its first member, which
+    /// keeps it at the same slot.
+    fn wrapped(&mut self, path: &str, old: &Type, new: &str) {
+        let layout = self.new.types.get(new);
+        let first =
+            layout.and_then(|layout| layout.members.iter().find(|member| member.slot == 0 && member.offset == 0));
+        let Some(first) = first else {
+            self.problems.push(format!("{path} changed type from {old} to {new}"));
+            return;
+        };
+        let path = format!("{new}.{}", first.label);
+        match first.ty.parse::<Type>() {
+            Ok(new_ty) => self.types(&path, old, &new_ty),
+            Err(_) => self.problems.push(format!("{path} has a malformed type")),
+        }
+    }
+
+    fn structs(&mut self, old: &str, new: &str) {
+        if !self.compared.insert((old.into(), new.into())) {
+            return;
+        }
+        match (self.old.types.get(old), self.new.types.get(new)) {
+            (Some(old_struct), Some(new_struct)) => self.members(old, &old_struct.members, &new_struct.members),
+            _ => self.problems.push(format!("the layout of {old} or {new} is missing")),
+        }
+    }
+}
+
+/// `REQUIRED_SLOTS` of a type, from the struct sizes in `layout`.
+fn required_slots(layout: &Layout, ty: &Type) -> usize {
+    match ty {
+        Type::Struct(name) => layout.types.get(name).map_or(0, |layout| layout.slots),
+        Type::Array(element, length) => {
+            (length * required_slots(layout, element)).max(length.div_ceil(32 / element.slot_bytes()))
+        }
+        _ => 0,
+    }
+}
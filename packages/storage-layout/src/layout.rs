@@ -0,0 +1,133 @@
+//! Assigning slots to storage fields the way the SDK does.
+
+use std::collections::BTreeMap;
+
+use eyre::{bail, OptionExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    source::{Field, Sources},
+    types::Type,
+};
+
+/// `layouts/<component>.json`.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Layout {
+    pub component: String,
+    /// The `#[entrypoint]` struct, whose fields start at slot 0
+    pub contract: String,
+    pub storage: Vec<Member>,
+    /// Every struct used by `storage`, inline or through mappings and arrays
+    pub types: BTreeMap<String, StructLayout>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct StructLayout {
+    /// The SDK's `REQUIRED_SLOTS`
+    pub slots: usize,
+    /// Slots relative to the struct's first
+    pub members: Vec<Member>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Member {
+    pub label: String,
+    pub slot: usize,
+    /// The SDK's byte offset in the slot, counted from the start of the word
+    pub offset: usize,
+    pub bytes: usize,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+pub fn compute(component: &str, sources: &Sources) -> eyre::Result<Layout> {
+    let mut computer = Computer { sources, types: BTreeMap::new(), in_progress: Vec::new() };
+    let entrypoint = &sources.structs[&sources.entrypoint];
+    let (storage, _) = computer.members(&entrypoint.fields)?;
+    Ok(Layout { component: component.into(), contract: sources.entrypoint.clone(), storage, types: computer.types })
+}
+
+struct Computer<'a> {
+    sources: &'a Sources,
+    types: BTreeMap<String, StructLayout>,
+    in_progress: Vec<String>,
+}
+
+impl Computer<'_> {
+    /// Lays out `fields` from slot 0 and returns them with the SDK's `REQUIRED_SLOTS`.
+    ///
+    /// This mirrors the code `#[storage]` generates. `new` places each field, while the slot
+    /// count comes from the separate `required_slots`, which doesn't count a partly used slot
+    /// before a multi-slot field. A struct like `{ bool a; Inner b; }` is therefore reported one
+    /// slot smaller than it is, and a parent struct places its next field inside it.
+    fn members(&mut self, fields: &[Field]) -> eyre::Result<(Vec<Member>, usize)> {
+        let mut members = Vec::new();
+        let (mut slot, mut space) = (0, 32);
+        let (mut total, mut total_space) = (0, 32);
+        for field in fields {
+            let bytes = field.ty.slot_bytes();
+            let words = self.required_slots(&field.ty)?;
+            if space < bytes {
+                space = 32;
+                slot += 1;
+            }
+            space -= bytes;
+            members.push(Member { label: field.label.clone(), slot, offset: space, bytes, ty: field.ty.to_string() });
+            if words > 0 {
+                slot += words;
+                space = 32;
+            }
+
+            if words > 0 {
+                total += words;
+                total_space = 32;
+            } else {
+                if total_space < bytes {
+                    total_space = 32;
+                    total += 1;
+                }
+                total_space -= bytes;
+            }
+        }
+        if total_space != 32 || total == 0 {
+            total += 1;
+        }
+        Ok((members, total))
+    }
+
+    /// The SDK's `REQUIRED_SLOTS`, and lays out any struct the type uses.
+    fn required_slots(&mut self, ty: &Type) -> eyre::Result<usize> {
+        Ok(match ty {
+            Type::Struct(name) => self.struct_slots(name)?,
+            Type::Array(element, length) => {
+                let reserved = length * self.required_slots(element)?;
+                let packed = length.div_ceil(32 / element.slot_bytes());
+                reserved.max(packed)
+            }
+            Type::Mapping(_, value) => {
+                self.required_slots(value)?;
+                0
+            }
+            Type::Vec(element) => {
+                self.required_slots(element)?;
+                0
+            }
+            _ => 0,
+        })
+    }
+
+    fn struct_slots(&mut self, name: &str) -> eyre::Result<usize> {
+        if let Some(layout) = self.types.get(name) {
+            return Ok(layout.slots);
+        }
+        if self.in_progress.iter().any(|other| other == name) {
+            bail!("storage struct {name} contains itself");
+        }
+        let item = self.sources.structs.get(name).ok_or_eyre(format!("no storage struct {name}"))?;
+        self.in_progress.push(name.into());
+        let (members, slots) = self.members(&item.fields)?;
+        self.in_progress.pop();
+        self.types.insert(name.into(), StructLayout { slots, members });
+        Ok(slots)
+    }
+}
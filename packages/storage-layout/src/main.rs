@@ -0,0 +1,161 @@
+//! Exports the storage layout of Cradle's Stylus components and guards it across upgrades.
+//!
+//! For every component in `packages/components` with a contract crate, reads the storage
+//! structs of the contract and of the Stylus modules it uses, computes the slot and offset of
+//! every field as the SDK assigns them, and writes the result to `layouts/<component>.json`.
+//! The committed layouts are the baseline: a layout that would read an existing deployment's
+//! storage differently, because a field was inserted, removed, reordered or retyped, is refused
+//! unless `--force` is given. With `--check` nothing is written, and the run fails if a layout
+//! is out of date or incompatible with its baseline.
+
+mod compat;
+mod layout;
+mod source;
+#[cfg(test)]
+mod tests;
+mod types;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use eyre::{bail, ensure, WrapErr};
+
+use crate::layout::Layout;
+
+#[derive(Parser)]
+struct Args {
+    /// Fail if the committed layouts differ from the contracts instead of writing them
+    #[arg(long)]
+    check: bool,
+    /// Overwrite baselines even with changes that break upgrades, for components that are
+    /// never upgraded in place
+    #[arg(long)]
+    force: bool,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let dir = layouts_dir();
+    let layouts = compute_all()?;
+    let report = compare(&dir, &layouts)?;
+
+    if args.check {
+        report.print();
+        ensure!(
+            report.is_clean(),
+            "the storage layouts in packages/storage-layout are out of date or break upgrades, see above"
+        );
+        return Ok(());
+    }
+
+    for problem in &report.incompatible {
+        println!("{problem}");
+    }
+    if !report.incompatible.is_empty() && !args.force {
+        bail!("these changes would corrupt the storage of upgraded deployments, pass --force to accept them anyway");
+    }
+    fs::create_dir_all(&dir)?;
+    for (path, (_, contents)) in &layouts {
+        fs::write(path, contents)?;
+    }
+    for path in &report.stale {
+        fs::remove_file(path)?;
+    }
+    println!("Wrote {} layouts", layouts.len());
+    Ok(())
+}
+
+fn layouts_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("layouts")
+}
+
+/// The layout of every component, with its JSON, by the path of its baseline.
+fn compute_all() -> eyre::Result<BTreeMap<PathBuf, (Layout, String)>> {
+    let components = Path::new(env!("CARGO_MANIFEST_DIR")).join("../components");
+    let mut layouts = BTreeMap::new();
+    for (name, contract) in contract_crates(&components)? {
+        let sources = source::read(&contract).wrap_err_with(|| format!("in {name}"))?;
+        let layout = layout::compute(&name, &sources).wrap_err_with(|| format!("in {name}"))?;
+        let json = serde_json::to_string_pretty(&layout)? + "\n";
+        layouts.insert(layouts_dir().join(format!("{name}.json")), (layout, json));
+    }
+    Ok(layouts)
+}
+
+/// How the computed layouts differ from the committed ones.
+#[derive(Default)]
+struct Report {
+    incompatible: Vec<String>,
+    outdated: Vec<PathBuf>,
+    stale: Vec<PathBuf>,
+}
+
+impl Report {
+    fn is_clean(&self) -> bool {
+        self.incompatible.is_empty() && self.outdated.is_empty() && self.stale.is_empty()
+    }
+
+    fn print(&self) {
+        for problem in &self.incompatible {
+            println!("{problem}");
+        }
+        for path in &self.outdated {
+            println!("out of date: {}", path.display());
+        }
+        for path in &self.stale {
+            println!("no longer generated: {}", path.display());
+        }
+    }
+}
+
+fn compare(dir: &Path, layouts: &BTreeMap<PathBuf, (Layout, String)>) -> eyre::Result<Report> {
+    let mut report = Report::default();
+    for (path, (layout, contents)) in layouts {
+        let Ok(committed) = fs::read_to_string(path) else {
+            report.outdated.push(path.clone());
+            continue;
+        };
+        if committed != *contents {
+            report.outdated.push(path.clone());
+        }
+        let baseline: Layout =
+            serde_json::from_str(&committed).wrap_err_with(|| format!("can't read {}", path.display()))?;
+        let problems = compat::check(&baseline, layout);
+        report.incompatible.extend(problems.into_iter().map(|problem| format!("{}: {problem}", layout.component)));
+    }
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") && !layouts.contains_key(&path) {
+                report.stale.push(path);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Components under `dir` with a crate in `contract/`, like `erc1155-stylus`.
+fn contract_crates(dir: &Path) -> eyre::Result<Vec<(String, PathBuf)>> {
+    let mut crates = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("can't read {}", dir.display()))? {
+        let component = entry?.path();
+        let Ok(entries) = fs::read_dir(component.join("contract")) else { continue };
+        let mut found = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.join("Cargo.toml").exists() {
+                found.push(path);
+            }
+        }
+        ensure!(found.len() <= 1, "{} has more than one contract crate", component.display());
+        if let Some(contract) = found.pop() {
+            crates.push((component.file_name().unwrap().to_string_lossy().into_owned(), contract));
+        }
+    }
+    crates.sort();
+    Ok(crates)
+}
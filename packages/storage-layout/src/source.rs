@@ -0,0 +1,163 @@
+//! Reading the storage structs of a contract crate and of its path dependencies.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, ensure, eyre, OptionExt, WrapErr};
+use proc_macro2::TokenTree;
+use syn::{braced, parse::ParseStream, Attribute, Ident, Item, Token};
+
+use crate::types::Type;
+
+/// A `sol_storage!` or `#[storage]` struct.
+pub struct Struct {
+    pub name: String,
+    pub entrypoint: bool,
+    /// In declaration order, which is storage order
+    pub fields: Vec<Field>,
+}
+
+pub struct Field {
+    pub label: String,
+    pub ty: Type,
+}
+
+/// The storage structs a contract can use.
+pub struct Sources {
+    /// The contract crate's `#[entrypoint]`
+    pub entrypoint: String,
+    pub structs: BTreeMap<String, Struct>,
+}
+
+/// Reads the crate in `contract` and its path dependencies, following `mod` declarations from
+/// `src/lib.rs`. Modules behind `#[cfg]`, like tests, are skipped: contracts are built without
+/// them.
+pub fn read(contract: &Path) -> eyre::Result<Sources> {
+    let mut structs = BTreeMap::new();
+    let mut entrypoints = Vec::new();
+    let mut crates = vec![contract.canonicalize()?];
+    let mut seen = Vec::new();
+    while let Some(dir) = crates.pop() {
+        if seen.contains(&dir) {
+            continue;
+        }
+        let mut found = Vec::new();
+        read_module(&dir.join("src/lib.rs"), &dir.join("src"), &mut found)?;
+        for item in found {
+            if item.entrypoint && seen.is_empty() {
+                entrypoints.push(item.name.clone());
+            }
+            // The same name in two crates would make the layout ambiguous
+            ensure!(!structs.contains_key(&item.name), "two storage structs are named {}", item.name);
+            structs.insert(item.name.clone(), item);
+        }
+        for dependency in path_dependencies(&dir)? {
+            crates.push(dir.join(dependency).canonicalize()?);
+        }
+        seen.push(dir);
+    }
+    let [entrypoint] = &entrypoints[..] else {
+        bail!("{} has {} #[entrypoint] structs, not one", contract.display(), entrypoints.len())
+    };
+    Ok(Sources { entrypoint: entrypoint.clone(), structs })
+}
+
+/// Collects the storage structs of the module in `file`, whose submodules are in `dir`.
+fn read_module(file: &Path, dir: &Path, structs: &mut Vec<Struct>) -> eyre::Result<()> {
+    let text = fs::read_to_string(file).wrap_err_with(|| format!("can't read {}", file.display()))?;
+    let parsed = syn::parse_file(&text).wrap_err_with(|| format!("can't parse {}", file.display()))?;
+    read_items(&parsed.items, dir, structs).wrap_err_with(|| format!("in {}", file.display()))
+}
+
+fn read_items(items: &[Item], dir: &Path, structs: &mut Vec<Struct>) -> eyre::Result<()> {
+    for item in items {
+        match item {
+            Item::Mod(module) if !has(&module.attrs, "cfg") => {
+                let name = module.ident.to_string();
+                match &module.content {
+                    Some((_, items)) => read_items(items, &dir.join(&name), structs)?,
+                    None => {
+                        let file = [dir.join(format!("{name}.rs")), dir.join(&name).join("mod.rs")]
+                            .into_iter()
+                            .find(|file| file.exists())
+                            .ok_or_else(|| eyre!("no file for mod {name}"))?;
+                        read_module(&file, &dir.join(&name), structs)?;
+                    }
+                }
+            }
+            Item::Macro(item) if item.mac.path.segments.last().is_some_and(|s| s.ident == "sol_storage") => {
+                structs.extend(item.mac.parse_body_with(sol_storage)?)
+            }
+            Item::Struct(item) if has(&item.attrs, "storage") => structs.push(storage_struct(item)?),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// A struct with `#[storage]`, whose fields have the SDK's storage types.
+fn storage_struct(item: &syn::ItemStruct) -> eyre::Result<Struct> {
+    let name = item.ident.to_string();
+    let mut fields = Vec::new();
+    for field in &item.fields {
+        ensure!(!has(&field.attrs, "cfg"), "#[cfg] on a storage field of {name} isn't supported");
+        let label = field.ident.as_ref().ok_or_eyre(format!("{name} has unnamed fields"))?.to_string();
+        let ty = Type::from_rust(&field.ty).wrap_err_with(|| format!("in {name}.{label}"))?;
+        fields.extend(ty.map(|ty| Field { label, ty }));
+    }
+    Ok(Struct { name, entrypoint: has(&item.attrs, "entrypoint"), fields })
+}
+
+/// The structs of a `sol_storage!` block, whose fields have Solidity types.
+pub fn sol_storage(input: ParseStream) -> syn::Result<Vec<Struct>> {
+    let mut structs = Vec::new();
+    while !input.is_empty() {
+        let attrs = input.call(Attribute::parse_outer)?;
+        input.parse::<syn::Visibility>()?;
+        input.parse::<Token![struct]>()?;
+        let name: Ident = input.parse()?;
+        input.parse::<syn::Generics>()?;
+        let body;
+        braced!(body in input);
+        let mut fields = Vec::new();
+        while !body.is_empty() {
+            let attrs = body.call(Attribute::parse_outer)?;
+            if has(&attrs, "cfg") {
+                return Err(body.error(format!("#[cfg] on a storage field of {name} isn't supported")));
+            }
+            let mut tokens = Vec::new();
+            while !body.peek(Token![;]) {
+                tokens.push(body.parse::<TokenTree>()?);
+            }
+            body.parse::<Token![;]>()?;
+            let Some(TokenTree::Ident(label)) = tokens.pop() else {
+                return Err(body.error(format!("expected `type name;` in {name}")));
+            };
+            let ty: String = tokens.iter().map(|token| token.to_string()).collect();
+            // `PhantomData<T>` holds the generic parameters, and takes no space
+            if ty.starts_with("PhantomData") {
+                continue;
+            }
+            let ty = ty.parse().map_err(|e| syn::Error::new(label.span(), format!("in {name}.{label}: {e}")))?;
+            fields.push(Field { label: label.to_string(), ty });
+        }
+        structs.push(Struct { name: name.to_string(), entrypoint: has(&attrs, "entrypoint"), fields });
+    }
+    Ok(structs)
+}
+
+fn has(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// The `path` of every entry in `[dependencies]`, such as the shared Stylus modules.
+fn path_dependencies(dir: &Path) -> eyre::Result<Vec<PathBuf>> {
+    let manifest: toml::Table = toml::from_str(&fs::read_to_string(dir.join("Cargo.toml"))?)?;
+    let Some(dependencies) = manifest.get("dependencies").and_then(|deps| deps.as_table()) else {
+        return Ok(Vec::new());
+    };
+    Ok(dependencies.values().filter_map(|dependency| dependency.get("path")?.as_str()).map(PathBuf::from).collect())
+}
@@ -0,0 +1,165 @@
+// For the code `sol_storage!` generates
+extern crate alloc;
+
+use stylus_sdk::{prelude::*, storage::StorageType};
+use syn::parse::Parser;
+
+use crate::{
+    compat,
+    layout::{self, Layout},
+    source::{self, Sources},
+};
+
+/// Declares the probe structs for the SDK and keeps their source for our own parser.
+macro_rules! probes {
+    ($($item:tt)*) => {
+        sol_storage! { $($item)* }
+        const PROBES: &str = stringify!($($item)*);
+    };
+}
+
+probes! {
+    pub struct Probes {
+        Packed packed;
+        Unaligned unaligned;
+        Partial partial;
+    }
+
+    pub struct Packed {
+        bool flag;
+        address owner;
+        uint64 since;
+        uint96 amount;
+        uint256 total;
+    }
+
+    pub struct Inner {
+        uint128 a;
+        uint128 b;
+        mapping(address => uint256) c;
+    }
+
+    pub struct Unaligned {
+        bool flag;
+        Inner inner;
+    }
+
+    pub struct Partial {
+        uint64 a;
+        uint64[5] small;
+        Inner[2] inners;
+        uint8 tail;
+    }
+}
+
+fn layout(text: &str, entrypoint: &str) -> Layout {
+    let structs = source::sol_storage.parse_str(text).unwrap();
+    let sources = Sources {
+        entrypoint: entrypoint.into(),
+        structs: structs.into_iter().map(|item| (item.name.clone(), item)).collect(),
+    };
+    layout::compute("test", &sources).unwrap()
+}
+
+fn problems(old: &str, new: &str) -> Vec<String> {
+    compat::check(&layout(old, "Token"), &layout(new, "Token"))
+}
+
+#[test]
+fn committed_layouts_are_current_and_upgrade_safe() {
+    let layouts = crate::compute_all().unwrap();
+    let report = crate::compare(&crate::layouts_dir(), &layouts).unwrap();
+    report.print();
+    assert!(report.is_clean(), "run `cargo run` in packages/storage-layout, see the README");
+}
+
+#[test]
+fn packs_small_fields_from_the_end_of_the_slot() {
+    let layout = layout(PROBES, "Probes");
+    let placements: Vec<_> = layout.types["Packed"]
+        .members
+        .iter()
+        .map(|member| (member.label.as_str(), member.slot, member.offset))
+        .collect();
+    assert_eq!(placements, [("flag", 0, 31), ("owner", 0, 11), ("since", 0, 3), ("amount", 1, 20), ("total", 2, 0)]);
+}
+
+#[test]
+fn matches_the_sdk_slot_counts() {
+    let layout = layout(PROBES, "Probes");
+    assert_eq!(layout.types["Packed"].slots, Packed::REQUIRED_SLOTS);
+    assert_eq!(layout.types["Inner"].slots, Inner::REQUIRED_SLOTS);
+    assert_eq!(layout.types["Partial"].slots, Partial::REQUIRED_SLOTS);
+    // The SDK doesn't count `flag`'s slot, so `partial` starts inside `unaligned.inner`
+    assert_eq!(layout.types["Unaligned"].slots, Unaligned::REQUIRED_SLOTS);
+    assert_eq!(layout.types["Unaligned"].members[1].slot, 1);
+    assert_eq!(Unaligned::REQUIRED_SLOTS, 2);
+    let partial = layout.storage.iter().find(|member| member.label == "partial").unwrap();
+    assert_eq!(partial.slot, 3 + Unaligned::REQUIRED_SLOTS);
+}
+
+const OLD: &str = "
+    pub struct Token {
+        address owner;
+        bool paused;
+        mapping(address => Account) accounts;
+        Checkpoint[] checkpoints;
+    }
+
+    pub struct Account {
+        uint256 balance;
+    }
+
+    pub struct Checkpoint {
+        uint64 block;
+        uint192 votes;
+    }
+";
+
+#[test]
+fn allows_appending_and_renaming() {
+    let new = OLD
+        .replace("bool paused;", "bool frozen;")
+        .replace("Checkpoint[] checkpoints;", "Checkpoint[] checkpoints; uint256 supply;")
+        .replace("uint256 balance;", "uint256 balance; uint256 nonce;");
+    assert_eq!(problems(OLD, &new), Vec::<String>::new());
+}
+
+#[test]
+fn rejects_inserting_removing_and_retyping() {
+    let inserted = OLD.replace("address owner;", "address owner; uint256 supply;");
+    assert_eq!(
+        problems(OLD, &inserted),
+        [
+            "Token.paused moved from slot 0 offset 11 to slot 2 offset 31",
+            "Token.accounts moved from slot 1 offset 0 to slot 3 offset 0",
+            "Token.checkpoints moved from slot 2 offset 0 to slot 4 offset 0",
+        ]
+    );
+
+    let removed = OLD.replace("bool paused;", "");
+    assert_eq!(problems(OLD, &removed), ["Token.paused at slot 0 offset 11 was removed"]);
+
+    let retyped = OLD.replace("mapping(address => Account)", "mapping(uint256 => Account)");
+    assert_eq!(problems(OLD, &retyped), ["Token.accounts changed key type from address to uint256"]);
+
+    let retyped = OLD.replace("uint192 votes;", "int192 votes;");
+    assert_eq!(problems(OLD, &retyped), ["Checkpoint.votes changed type from uint192 to int192"]);
+}
+
+#[test]
+fn rejects_growing_array_elements() {
+    let grown = OLD.replace("uint192 votes;", "uint192 votes; uint256 delegated;");
+    assert_eq!(problems(OLD, &grown), ["Token.checkpoints elements changed from 1 to 2 slots"]);
+}
+
+#[test]
+fn allows_moving_a_field_into_a_struct_as_its_first_member() {
+    let wrapped = OLD.replace("mapping(address => Account) accounts;", "Accounts accounts;")
+        + "pub struct Accounts { mapping(address => Account) balances; }";
+    assert_eq!(problems(OLD, &wrapped), Vec::<String>::new());
+
+    let wrapped = OLD.replace("Checkpoint[] checkpoints;", "History checkpoints;")
+        + "pub struct History { uint256 length; Checkpoint[] items; }";
+    assert_eq!(problems(OLD, &wrapped), ["History.length changed type from Checkpoint[] to uint256"]);
+}
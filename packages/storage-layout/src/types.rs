@@ -0,0 +1,259 @@
+//! Storage types, written in Solidity syntax as in `sol_storage!`.
+
+use std::{fmt, str::FromStr};
+
+use eyre::{bail, ensure, eyre, OptionExt};
+use quote::ToTokens;
+use syn::{GenericArgument, PathArguments};
+
+/// The type of a storage field, independent of whether it was declared with `sol_storage!` or
+/// with the SDK's storage types under `#[storage]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Type {
+    Uint(usize),
+    Int(usize),
+    Address,
+    Bool,
+    FixedBytes(usize),
+    String,
+    Bytes,
+    Mapping(Box<Type>, Box<Type>),
+    Vec(Box<Type>),
+    Array(Box<Type>, usize),
+    /// Another storage struct, by name without generics
+    Struct(String),
+}
+
+impl Type {
+    /// The SDK's `StorageType::SLOT_BYTES`: bytes the type takes in a word it may share.
+    pub fn slot_bytes(&self) -> usize {
+        match self {
+            Type::Uint(bits) | Type::Int(bits) => bits / 8,
+            Type::Address => 20,
+            Type::Bool => 1,
+            Type::FixedBytes(bytes) => *bytes,
+            _ => 32,
+        }
+    }
+
+    /// Converts an SDK storage type used in a `#[storage]` struct, like
+    /// `StorageMap<Address, StorageU256>`. `None` for `PhantomData`, which takes no space.
+    pub fn from_rust(ty: &syn::Type) -> eyre::Result<Option<Self>> {
+        let syn::Type::Path(path) = ty else { bail!("unsupported storage type {}", tokens(ty)) };
+        let segment = path.path.segments.last().ok_or_eyre("empty type")?;
+        let name = segment.ident.to_string();
+        let args = generic_args(&segment.arguments);
+        let ty = match name.as_str() {
+            "PhantomData" => return Ok(None),
+            "StorageAddress" => Type::Address,
+            "StorageBool" => Type::Bool,
+            "StorageString" => Type::String,
+            "StorageBytes" => Type::Bytes,
+            "StorageBlockNumber" => Type::Uint(64),
+            "StorageBlockHash" => Type::FixedBytes(32),
+            "StorageUint" => Type::Uint(number(args.first().copied())?),
+            "StorageSigned" => Type::Int(number(args.first().copied())?),
+            "StorageFixedBytes" => Type::FixedBytes(number(args.first().copied())?),
+            "StorageMap" => {
+                let [key, value] = args[..] else { bail!("StorageMap takes a key and a value") };
+                let key = Type::from_key(generic_type(key)?)?;
+                let value = Type::from_rust(generic_type(value)?)?.ok_or_eyre("PhantomData map value")?;
+                Type::Mapping(Box::new(key), Box::new(value))
+            }
+            "StorageVec" => {
+                let [element] = args[..] else { bail!("StorageVec takes an element type") };
+                Type::Vec(Box::new(Type::from_rust(generic_type(element)?)?.ok_or_eyre("PhantomData element")?))
+            }
+            "StorageArray" => {
+                let [element, length] = args[..] else { bail!("StorageArray takes an element type and a length") };
+                let element = Type::from_rust(generic_type(element)?)?.ok_or_eyre("PhantomData element")?;
+                Type::Array(Box::new(element), number(Some(length))?)
+            }
+            _ => match sized(&name, &["StorageU", "StorageI", "StorageB"]) {
+                Some(("StorageU", bits)) => Type::Uint(bits),
+                Some(("StorageI", bits)) => Type::Int(bits),
+                Some((_, bits)) => Type::FixedBytes(bits / 8),
+                None if name.starts_with("Storage") => bail!("unsupported storage type {name}"),
+                None => Type::Struct(name),
+            },
+        };
+        Ok(Some(ty))
+    }
+
+    /// Converts the Rust key type of a `StorageMap`, like `Address` or `U256`.
+    fn from_key(ty: &syn::Type) -> eyre::Result<Self> {
+        let syn::Type::Path(path) = ty else { bail!("unsupported map key {}", tokens(ty)) };
+        let segment = path.path.segments.last().ok_or_eyre("empty type")?;
+        let name = segment.ident.to_string();
+        let args = generic_args(&segment.arguments);
+        Ok(match name.as_str() {
+            "Address" => Type::Address,
+            "bool" => Type::Bool,
+            "String" => Type::String,
+            "Bytes" => Type::Bytes,
+            "FixedBytes" => Type::FixedBytes(number(args.first().copied())?),
+            "Uint" => Type::Uint(number(args.first().copied())?),
+            "Signed" => Type::Int(number(args.first().copied())?),
+            _ => match sized(&name, &["U", "I", "B", "u", "i"]) {
+                Some(("U" | "u", bits)) => Type::Uint(bits),
+                Some(("I" | "i", bits)) => Type::Int(bits),
+                Some((_, bits)) => Type::FixedBytes(bits / 8),
+                None => bail!("unsupported map key {name}"),
+            },
+        })
+    }
+}
+
+/// Splits names like `StorageU64` into `("StorageU", 64)`.
+fn sized<'a>(name: &str, prefixes: &[&'a str]) -> Option<(&'a str, usize)> {
+    prefixes.iter().find_map(|prefix| {
+        let bits: usize = name.strip_prefix(prefix)?.parse().ok()?;
+        (bits > 0 && bits <= 256 && bits % 8 == 0).then_some((*prefix, bits))
+    })
+}
+
+fn generic_args(arguments: &PathArguments) -> Vec<&GenericArgument> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn generic_type(arg: &GenericArgument) -> eyre::Result<&syn::Type> {
+    match arg {
+        GenericArgument::Type(ty) => Ok(ty),
+        _ => bail!("expected a type argument"),
+    }
+}
+
+fn number(arg: Option<&GenericArgument>) -> eyre::Result<usize> {
+    let text = match arg {
+        Some(GenericArgument::Const(expr)) => tokens(expr),
+        Some(GenericArgument::Type(ty)) => tokens(ty),
+        _ => bail!("expected a number"),
+    };
+    text.replace('_', "").parse().map_err(|_| eyre!("{text} isn't a literal number"))
+}
+
+fn tokens(node: &impl ToTokens) -> String {
+    node.to_token_stream().to_string()
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Uint(bits) => write!(f, "uint{bits}"),
+            Type::Int(bits) => write!(f, "int{bits}"),
+            Type::Address => f.write_str("address"),
+            Type::Bool => f.write_str("bool"),
+            Type::FixedBytes(bytes) => write!(f, "bytes{bytes}"),
+            Type::String => f.write_str("string"),
+            Type::Bytes => f.write_str("bytes"),
+            Type::Mapping(key, value) => write!(f, "mapping({key} => {value})"),
+            Type::Vec(element) => write!(f, "{element}[]"),
+            Type::Array(element, length) => write!(f, "{element}[{length}]"),
+            Type::Struct(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Parses the Solidity syntax of `sol_storage!` fields and of the layout files. Generic
+/// arguments of structs are dropped, as they don't change the layout.
+impl FromStr for Type {
+    type Err = eyre::Report;
+
+    fn from_str(text: &str) -> eyre::Result<Self> {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut parser = Parser { text: &text, position: 0 };
+        let ty = parser.parse()?;
+        ensure!(parser.position == text.len(), "unexpected `{}` in {text}", &text[parser.position..]);
+        Ok(ty)
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(&mut self) -> eyre::Result<Type> {
+        let mut ty = if self.eat("mapping(") {
+            let key = self.parse()?;
+            ensure!(self.eat("=>"), "expected `=>` in {}", self.text);
+            let value = self.parse()?;
+            ensure!(self.eat(")"), "expected `)` in {}", self.text);
+            Type::Mapping(Box::new(key), Box::new(value))
+        } else {
+            let name = self.identifier();
+            ensure!(!name.is_empty(), "expected a type in {}", self.text);
+            if self.eat("<") {
+                self.skip_generics()?;
+            }
+            elementary(name).unwrap_or_else(|| Type::Struct(name.into()))
+        };
+        while self.eat("[") {
+            let length = self.take_while(|c| c.is_ascii_digit());
+            ensure!(self.eat("]"), "expected `]` in {}", self.text);
+            ty = match length {
+                "" => Type::Vec(Box::new(ty)),
+                length => Type::Array(Box::new(ty), length.parse()?),
+            };
+        }
+        Ok(ty)
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        self.take_while(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    fn skip_generics(&mut self) -> eyre::Result<()> {
+        let mut depth = 1;
+        for (i, c) in self.text[self.position..].char_indices() {
+            depth += match c {
+                '<' => 1,
+                '>' => -1,
+                _ => 0,
+            };
+            if depth == 0 {
+                self.position += i + 1;
+                return Ok(());
+            }
+        }
+        bail!("unclosed `<` in {}", self.text)
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest: &'a str = &self.text[self.position..];
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let matched = self.text[self.position..].starts_with(token);
+        if matched {
+            self.position += token.len();
+        }
+        matched
+    }
+}
+
+fn elementary(name: &str) -> Option<Type> {
+    Some(match name {
+        "address" => Type::Address,
+        "bool" => Type::Bool,
+        "string" => Type::String,
+        "bytes" => Type::Bytes,
+        "uint" => Type::Uint(256),
+        "int" => Type::Int(256),
+        "byte" => Type::FixedBytes(1),
+        _ => match name.strip_prefix("bytes") {
+            Some(bytes) => Type::FixedBytes(bytes.parse().ok().filter(|bytes| (1..=32).contains(bytes))?),
+            None => match sized(name, &["uint", "int"])? {
+                ("uint", bits) => Type::Uint(bits),
+                (_, bits) => Type::Int(bits),
+            },
+        },
+    })
+}
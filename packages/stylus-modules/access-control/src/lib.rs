@@ -0,0 +1,405 @@
+//! Role-based access control
+//!
+//! The [`AccessControl`] storage type keeps role members, each role's admin
+//! role and an enumerable member list per role. Add it as a `#[borrow]`
+//! field of a contract and inherit it to expose `hasRole`, `grantRole`,
+//! `revokeRole`, `renounceRole`, `getRoleAdmin`, `getRoleMember` and
+//! `getRoleMemberCount`:
+//!
+//! ```ignore
+//! #[public]
+//! #[inherit(AccessControl)]
+//! impl MyContract {
+//!     pub fn initialize(&mut self, admin: Address) -> Result<(), Vec<u8>> {
+//!         self.access_control._grant_role(DEFAULT_ADMIN_ROLE, admin);
+//!         Ok(())
+//!     }
+//!
+//!     pub fn mint(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+//!         only_role!(self.access_control, MINTER_ROLE);
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! Every role is administered by [`DEFAULT_ADMIN_ROLE`] unless the contract
+//! changes it with [`AccessControl::_set_role_admin`]. Members are kept in a
+//! list as well as a mapping, so granting and revoking cost a few more
+//! storage writes in exchange for on-chain enumeration.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::sol;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{
+    prelude::*,
+    storage::{StorageAddress, StorageB256, StorageMap, StorageU256, StorageVec},
+};
+
+/// Role that administers every role without an admin of its own, including itself.
+pub const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+
+/// OpenZeppelin's `IAccessControl`, for `supportsInterface`.
+pub const IACCESS_CONTROL: Interface = interface!(
+    "hasRole"(B256, Address),
+    "getRoleAdmin"(B256),
+    "grantRole"(B256, Address),
+    "revokeRole"(B256, Address),
+    "renounceRole"(B256, Address),
+);
+
+/// OpenZeppelin's `IAccessControlEnumerable`, for `supportsInterface`.
+pub const IACCESS_CONTROL_ENUMERABLE: Interface =
+    interface!("getRoleMember"(B256, U256), "getRoleMemberCount"(B256));
+
+// Declare events and Solidity error types
+sol! {
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole);
+
+    // Account is missing the role
+    error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+    // Caller passed someone else's address to `renounceRole`
+    error AccessControlBadConfirmation();
+    // Role has fewer members than the index
+    error AccessControlMemberOutOfBounds(bytes32 role, uint256 index);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum AccessControlError {
+    UnauthorizedAccount(AccessControlUnauthorizedAccount),
+    BadConfirmation(AccessControlBadConfirmation),
+    MemberOutOfBounds(AccessControlMemberOutOfBounds),
+}
+
+/// Fails the enclosing method unless the caller has `role`.
+///
+/// `only_role!(self.access_control, MINTER_ROLE)` is shorthand for
+/// `self.access_control.require_role(MINTER_ROLE)?`, so the method's error
+/// type must convert from [`AccessControlError`].
+#[macro_export]
+macro_rules! only_role {
+    ($access_control:expr, $role:expr) => {
+        $access_control.require_role($role)?
+    };
+}
+
+#[storage]
+pub struct RoleData {
+    /// Role that can grant and revoke this one. Zero means [`DEFAULT_ADMIN_ROLE`].
+    admin_role: StorageB256,
+    /// Members in no particular order
+    members: StorageVec<StorageAddress>,
+    /// Maps members to their index in `members` plus one, zero for non-members
+    positions: StorageMap<Address, StorageU256>,
+}
+
+#[storage]
+pub struct AccessControl {
+    /// Maps roles to their admin and members
+    roles: StorageMap<B256, RoleData>,
+}
+
+// These methods aren't exposed to other contracts
+impl AccessControl {
+    /// Fails unless the caller has `role`.
+    pub fn require_role(&self, role: B256) -> Result<(), AccessControlError> {
+        self.check_role(role, self.vm().msg_sender())
+    }
+
+    /// Fails unless `account` has `role`.
+    pub fn check_role(&self, role: B256, account: Address) -> Result<(), AccessControlError> {
+        if !self.has_role(role, account) {
+            return Err(AccessControlError::UnauthorizedAccount(AccessControlUnauthorizedAccount {
+                account,
+                neededRole: role,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Grants `role` without checking the caller. Returns whether `account` was newly added.
+    pub fn _grant_role(&mut self, role: B256, account: Address) -> bool {
+        if self.has_role(role, account) {
+            return false;
+        }
+        let mut data = self.roles.setter(role);
+        data.members.push(account);
+        let position = U256::from(data.members.len());
+        data.positions.insert(account, position);
+
+        log(self.vm(), RoleGranted {
+            role,
+            account,
+            sender: self.vm().msg_sender(),
+        });
+        true
+    }
+
+    /// Revokes `role` without checking the caller. Returns whether `account` was removed.
+    pub fn _revoke_role(&mut self, role: B256, account: Address) -> bool {
+        let mut data = self.roles.setter(role);
+        let position = data.positions.get(account);
+        if position.is_zero() {
+            return false;
+        }
+
+        // Move the last member into the freed slot
+        let index = position.to::<usize>() - 1;
+        let last_index = data.members.len() - 1;
+        if index != last_index {
+            let last = data.members.get(last_index).unwrap_or_default();
+            if let Some(mut slot) = data.members.setter(index) {
+                slot.set(last);
+            }
+            data.positions.insert(last, position);
+        }
+        data.members.erase_last();
+        data.positions.delete(account);
+
+        log(self.vm(), RoleRevoked {
+            role,
+            account,
+            sender: self.vm().msg_sender(),
+        });
+        true
+    }
+
+    /// Makes `admin_role` the role that grants and revokes `role`.
+    pub fn _set_role_admin(&mut self, role: B256, admin_role: B256) {
+        let previous_admin_role = self.get_role_admin(role);
+        self.roles.setter(role).admin_role.set(admin_role);
+
+        log(self.vm(), RoleAdminChanged {
+            role,
+            previousAdminRole: previous_admin_role,
+            newAdminRole: admin_role,
+        });
+    }
+}
+
+#[public]
+impl AccessControl {
+    /// Whether `account` has `role`.
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        !self.roles.getter(role).positions.get(account).is_zero()
+    }
+
+    /// Role that grants and revokes `role`.
+    pub fn get_role_admin(&self, role: B256) -> B256 {
+        self.roles.getter(role).admin_role.get()
+    }
+
+    /// Grants `role` to `account` (admin of `role` only).
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), AccessControlError> {
+        self.require_role(self.get_role_admin(role))?;
+        self._grant_role(role, account);
+        Ok(())
+    }
+
+    /// Revokes `role` from `account` (admin of `role` only).
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), AccessControlError> {
+        self.require_role(self.get_role_admin(role))?;
+        self._revoke_role(role, account);
+        Ok(())
+    }
+
+    /// Gives up `role`. `caller_confirmation` must be the caller's own address.
+    pub fn renounce_role(&mut self, role: B256, caller_confirmation: Address) -> Result<(), AccessControlError> {
+        if caller_confirmation != self.vm().msg_sender() {
+            return Err(AccessControlError::BadConfirmation(AccessControlBadConfirmation {}));
+        }
+        self._revoke_role(role, caller_confirmation);
+        Ok(())
+    }
+
+    /// Member of `role` at `index`. The order changes when members are revoked.
+    pub fn get_role_member(&self, role: B256, index: U256) -> Result<Address, AccessControlError> {
+        self.roles
+            .getter(role)
+            .members
+            .get(index)
+            .ok_or(AccessControlError::MemberOutOfBounds(AccessControlMemberOutOfBounds { role, index }))
+    }
+
+    /// Number of accounts with `role`.
+    pub fn get_role_member_count(&self, role: B256) -> U256 {
+        U256::from(self.roles.getter(role).members.len())
+    }
+
+    #[selector(name = "DEFAULT_ADMIN_ROLE")]
+    pub fn default_admin_role(&self) -> B256 {
+        DEFAULT_ADMIN_ROLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, b256};
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+
+    const ADMIN: Address = address!("0000000000000000000000000000000000000a11");
+    const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+    const CAROL: Address = address!("000000000000000000000000000000000000ca01");
+    const MINTER_ROLE: B256 = b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+    const MINTER_ADMIN_ROLE: B256 = b256!("00000000000000000000000000000000000000000000000000000000000000aa");
+
+    /// Access control where `ADMIN` holds the default admin role and is the caller.
+    fn setup() -> (TestVM, AccessControl) {
+        let vm = TestVM::new();
+        vm.set_sender(ADMIN);
+        let mut access_control = AccessControl::from(&vm);
+        access_control._grant_role(DEFAULT_ADMIN_ROLE, ADMIN);
+        (vm, access_control)
+    }
+
+    fn members(access_control: &AccessControl, role: B256) -> Vec<Address> {
+        let count = access_control.get_role_member_count(role).to::<u64>();
+        (0..count)
+            .map(|index| {
+                let member = access_control.get_role_member(role, U256::from(index));
+                member.unwrap_or_else(|_| panic!("member {index} is out of bounds"))
+            })
+            .collect()
+    }
+
+    fn unauthorized(account: Address, role: B256) -> impl Fn(&Result<(), AccessControlError>) -> bool {
+        move |result| {
+            matches!(
+                result,
+                Err(AccessControlError::UnauthorizedAccount(AccessControlUnauthorizedAccount { account: a, neededRole }))
+                    if *a == account && *neededRole == role
+            )
+        }
+    }
+
+    fn mint(access_control: &AccessControl) -> Result<(), AccessControlError> {
+        only_role!(access_control, MINTER_ROLE);
+        Ok(())
+    }
+
+    #[test]
+    fn admins_grant_and_revoke_roles() {
+        let (vm, mut access_control) = setup();
+        assert!(access_control.grant_role(MINTER_ROLE, ALICE).is_ok());
+        assert!(access_control.has_role(MINTER_ROLE, ALICE));
+        assert!(!access_control.has_role(MINTER_ROLE, BOB));
+        vm.set_sender(ALICE);
+        assert!(mint(&access_control).is_ok());
+
+        vm.set_sender(ADMIN);
+        assert!(access_control.revoke_role(MINTER_ROLE, ALICE).is_ok());
+        assert!(!access_control.has_role(MINTER_ROLE, ALICE));
+        vm.set_sender(ALICE);
+        assert!(unauthorized(ALICE, MINTER_ROLE)(&mint(&access_control)));
+
+        let logs = vm.get_emitted_logs();
+        let granted = RoleGranted::decode_raw_log(logs[1].0.clone(), &logs[1].1, true).expect("event decodes");
+        assert_eq!((granted.role, granted.account, granted.sender), (MINTER_ROLE, ALICE, ADMIN));
+        let revoked = RoleRevoked::decode_raw_log(logs[2].0.clone(), &logs[2].1, true).expect("event decodes");
+        assert_eq!((revoked.role, revoked.account, revoked.sender), (MINTER_ROLE, ALICE, ADMIN));
+    }
+
+    #[test]
+    fn granting_or_revoking_twice_changes_nothing() {
+        let (vm, mut access_control) = setup();
+        assert!(access_control._grant_role(MINTER_ROLE, ALICE));
+        assert!(!access_control._grant_role(MINTER_ROLE, ALICE));
+        assert!(access_control.grant_role(MINTER_ROLE, ALICE).is_ok());
+        assert_eq!(members(&access_control, MINTER_ROLE), [ALICE]);
+        assert!(access_control._revoke_role(MINTER_ROLE, ALICE));
+        assert!(!access_control._revoke_role(MINTER_ROLE, ALICE));
+        assert!(access_control.revoke_role(MINTER_ROLE, ALICE).is_ok());
+        assert_eq!(access_control.get_role_member_count(MINTER_ROLE), U256::ZERO);
+        // The admin's grant, ALICE's grant and her revocation
+        assert_eq!(vm.get_emitted_logs().len(), 3);
+    }
+
+    #[test]
+    fn only_a_roles_admin_grants_and_revokes_it() {
+        let (vm, mut access_control) = setup();
+        assert!(access_control.grant_role(MINTER_ROLE, ALICE).is_ok());
+        vm.set_sender(ALICE);
+        let check = unauthorized(ALICE, DEFAULT_ADMIN_ROLE);
+        assert!(check(&access_control.grant_role(MINTER_ROLE, BOB)));
+        assert!(check(&access_control.revoke_role(MINTER_ROLE, ALICE)));
+        assert!(check(&access_control.grant_role(DEFAULT_ADMIN_ROLE, ALICE)));
+        assert!(!access_control.has_role(MINTER_ROLE, BOB));
+        assert!(access_control.has_role(MINTER_ROLE, ALICE));
+
+        // Once the minter admin role administers minters, the default admin doesn't
+        vm.set_sender(ADMIN);
+        access_control._set_role_admin(MINTER_ROLE, MINTER_ADMIN_ROLE);
+        assert_eq!(access_control.get_role_admin(MINTER_ROLE), MINTER_ADMIN_ROLE);
+        assert_eq!(access_control.get_role_admin(MINTER_ADMIN_ROLE), DEFAULT_ADMIN_ROLE);
+        assert!(unauthorized(ADMIN, MINTER_ADMIN_ROLE)(&access_control.grant_role(MINTER_ROLE, BOB)));
+        assert!(access_control.grant_role(MINTER_ADMIN_ROLE, BOB).is_ok());
+        vm.set_sender(BOB);
+        assert!(access_control.grant_role(MINTER_ROLE, CAROL).is_ok());
+        assert!(access_control.revoke_role(MINTER_ROLE, ALICE).is_ok());
+        assert_eq!(members(&access_control, MINTER_ROLE), [CAROL]);
+
+        let (topics, data) = vm.get_emitted_logs().swap_remove(2);
+        let changed = RoleAdminChanged::decode_raw_log(topics, &data, true).expect("event decodes");
+        assert_eq!(
+            (changed.role, changed.previousAdminRole, changed.newAdminRole),
+            (MINTER_ROLE, DEFAULT_ADMIN_ROLE, MINTER_ADMIN_ROLE)
+        );
+    }
+
+    #[test]
+    fn members_only_renounce_their_own_roles() {
+        let (vm, mut access_control) = setup();
+        assert!(access_control.grant_role(MINTER_ROLE, ALICE).is_ok());
+        vm.set_sender(BOB);
+        assert!(matches!(
+            access_control.renounce_role(MINTER_ROLE, ALICE),
+            Err(AccessControlError::BadConfirmation(_))
+        ));
+        assert!(access_control.has_role(MINTER_ROLE, ALICE));
+        vm.set_sender(ALICE);
+        assert!(access_control.renounce_role(MINTER_ROLE, ALICE).is_ok());
+        assert!(!access_control.has_role(MINTER_ROLE, ALICE));
+
+        // The last admin can renounce too, leaving roles nobody can grant
+        vm.set_sender(ADMIN);
+        assert!(access_control.renounce_role(DEFAULT_ADMIN_ROLE, ADMIN).is_ok());
+        assert!(unauthorized(ADMIN, DEFAULT_ADMIN_ROLE)(&access_control.grant_role(MINTER_ROLE, ADMIN)));
+    }
+
+    #[test]
+    fn members_are_enumerable_after_revocations() {
+        let (_vm, mut access_control) = setup();
+        for account in [ALICE, BOB, CAROL] {
+            assert!(access_control.grant_role(MINTER_ROLE, account).is_ok());
+        }
+        assert_eq!(members(&access_control, MINTER_ROLE), [ALICE, BOB, CAROL]);
+
+        // The last member takes the revoked one's place
+        assert!(access_control.revoke_role(MINTER_ROLE, ALICE).is_ok());
+        assert_eq!(members(&access_control, MINTER_ROLE), [CAROL, BOB]);
+        assert!(access_control.revoke_role(MINTER_ROLE, BOB).is_ok());
+        assert_eq!(members(&access_control, MINTER_ROLE), [CAROL]);
+        assert!(access_control.grant_role(MINTER_ROLE, ALICE).is_ok());
+        assert!(access_control.revoke_role(MINTER_ROLE, CAROL).is_ok());
+        assert_eq!(members(&access_control, MINTER_ROLE), [ALICE]);
+        assert!(access_control.has_role(MINTER_ROLE, ALICE));
+        assert!(!access_control.has_role(MINTER_ROLE, CAROL));
+
+        assert!(matches!(
+            access_control.get_role_member(MINTER_ROLE, U256::from(1)),
+            Err(AccessControlError::MemberOutOfBounds(AccessControlMemberOutOfBounds { index, .. }))
+                if index == U256::from(1)
+        ));
+        assert_eq!(members(&access_control, DEFAULT_ADMIN_ROLE), [ADMIN]);
+    }
+}
@@ -0,0 +1,88 @@
+//! A clock each deployment sets to count timestamps, L2 blocks or L1 blocks.
+
+use alloy_primitives::{Uint, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{prelude::*, storage::StorageU8};
+
+use crate::{arb_block_number, l1_block_number, PrecompileCallFailed};
+
+/// `mode` of a clock counting seconds of `block.timestamp`, which the sequencer may set up to a
+/// day behind or an hour ahead of real time. The mode of a clock that was never set.
+pub const CLOCK_TIMESTAMP: u8 = 0;
+/// `mode` of a clock counting L2 blocks, which come every quarter second or so but at no fixed
+/// rate.
+pub const CLOCK_L2_BLOCK: u8 = 1;
+/// `mode` of a clock counting L1 blocks, about 12 seconds each, as of the chain's last sync.
+pub const CLOCK_L1_BLOCK: u8 = 2;
+
+sol! {
+    // Clock mode isn't timestamp (0), L2 block (1) or L1 block (2)
+    error InvalidClockMode(uint8 mode);
+}
+
+/// What a contract's deadlines, periods and unlock times are counted in, chosen when it's
+/// initialized.
+///
+/// Deployments disagree on which to trust: timestamps read as dates but drift with the sequencer,
+/// L2 blocks can't be moved by it but don't map to wall time, and L1 blocks do both roughly while
+/// standing still between syncs. Every time and duration a contract with a clock takes or returns
+/// is in its unit.
+#[storage]
+pub struct Clock {
+    /// One of the `CLOCK_*` modes
+    mode: StorageU8,
+}
+
+// These methods aren't exposed to other contracts
+impl Clock {
+    /// Sets the clock's mode, for a contract's `initialize`.
+    pub fn init(&mut self, mode: u8) -> Result<(), InvalidClockMode> {
+        if !matches!(mode, CLOCK_TIMESTAMP | CLOCK_L2_BLOCK | CLOCK_L1_BLOCK) {
+            return Err(InvalidClockMode { mode });
+        }
+        self.mode.set(Uint::<8, 1>::from(mode));
+        Ok(())
+    }
+
+    /// The clock's mode, one of the `CLOCK_*` constants.
+    pub fn mode(&self) -> u8 {
+        self.mode.get().to()
+    }
+
+    /// The current time in the clock's unit. Only L2 block clocks call a precompile, and so can
+    /// fail, off Arbitrum.
+    pub fn now(&self) -> Result<U256, PrecompileCallFailed> {
+        match self.mode() {
+            CLOCK_L2_BLOCK => arb_block_number(self.vm()).map(U256::from),
+            CLOCK_L1_BLOCK => Ok(U256::from(l1_block_number(self.vm()))),
+            _ => Ok(U256::from(self.vm().block_timestamp())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::{SolCall, SolValue};
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+    use crate::{ArbSys, ARB_SYS};
+
+    #[test]
+    fn clocks_count_in_the_mode_they_were_set_to() {
+        let vm = TestVM::new();
+        vm.set_block_timestamp(1_700_000_000);
+        vm.set_block_number(20_000_000);
+        vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(U256::from(300).abi_encode()));
+        let mut clock = Clock::from(&vm);
+
+        assert_eq!(clock.mode(), CLOCK_TIMESTAMP);
+        assert_eq!(clock.now().ok(), Some(U256::from(1_700_000_000)));
+        assert!(clock.init(CLOCK_L2_BLOCK).is_ok());
+        assert_eq!(clock.now().ok(), Some(U256::from(300)));
+        assert!(clock.init(CLOCK_L1_BLOCK).is_ok());
+        assert_eq!(clock.now().ok(), Some(U256::from(20_000_000)));
+        assert!(matches!(clock.init(3), Err(InvalidClockMode { mode: 3 })));
+        assert_eq!(clock.mode(), CLOCK_L1_BLOCK);
+    }
+}
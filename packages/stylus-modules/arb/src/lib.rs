@@ -0,0 +1,240 @@
+//! Arbitrum precompiles
+//!
+//! Free functions around the ArbSys and ArbGasInfo precompiles, for components that
+//! need Arbitrum's own view of blocks, gas and L1 messaging:
+//!
+//! - [`arb_block_number`] and [`arb_block_hash`] for L2 blocks
+//! - [`l1_block_number`] for the L1 block the chain last synced to
+//! - [`gas_prices`] and [`l1_base_fee_estimate`] for ArbOS's current pricing
+//! - [`send_tx_to_l1`] for L2-to-L1 messages, executed through the Outbox once the
+//!   assertion that holds them is confirmed
+//! - [`clock::Clock`], a storage type that counts whichever of timestamps, L2 blocks
+//!   and L1 blocks a deployment chose
+//!
+//! On Arbitrum, `block.number` is an approximation of the L1 block number that the
+//! sequencer updates every few L2 blocks, so counting it counts roughly 12-second L1
+//! blocks, and `blockhash` isn't a hash of any block. `block.timestamp` is the L2
+//! block's, which the sequencer may set up to a day behind or an hour ahead of real
+//! time, so it measures durations of hours well but not of seconds. Components that
+//! want "the next block" or a per-block cap count [`arb_block_number`] instead.
+//!
+//! Each function takes the contract's host, `self.vm()`:
+//!
+//! ```ignore
+//! let current = arb_block_number(self.vm()).map_err(MyError::PrecompileCallFailed)?;
+//! let hash = arb_block_hash(self.vm(), current - 1).map_err(MyError::PrecompileCallFailed)?;
+//! ```
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloy_primitives::{address, Address, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{prelude::*, stylus_core::Host};
+
+pub mod clock;
+
+/// ArbSys precompile, for L2 blocks and L2-to-L1 messages.
+pub const ARB_SYS: Address = address!("0000000000000000000000000000000000000064");
+/// ArbGasInfo precompile, for ArbOS's gas pricing.
+pub const ARB_GAS_INFO: Address = address!("000000000000000000000000000000000000006c");
+/// ArbSys only returns the hashes of the most recent 256 L2 blocks.
+pub const BLOCK_HASH_WINDOW: u64 = 256;
+
+sol! {
+    interface ArbSys {
+        function arbBlockNumber() external view returns (uint256);
+        function arbBlockHash(uint256 arbBlockNum) external view returns (bytes32);
+        function sendTxToL1(address destination, bytes calldata data) external payable returns (uint256);
+    }
+
+    interface ArbGasInfo {
+        function getPricesInWei() external view returns (uint256, uint256, uint256, uint256, uint256, uint256);
+        function getL1BaseFeeEstimate() external view returns (uint256);
+    }
+
+    // A call to an Arbitrum precompile reverted or returned something else than expected, as it
+    // does off Arbitrum
+    error PrecompileCallFailed(address precompile);
+}
+
+/// ArbOS's current prices in wei, as returned by `ArbGasInfo.getPricesInWei`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GasPrices {
+    /// Per L2 transaction, for posting it to L1
+    pub per_l2_tx: U256,
+    /// Per byte of calldata posted to L1
+    pub per_l1_calldata_byte: U256,
+    /// Per storage slot allocated
+    pub per_storage_allocation: U256,
+    /// Per unit of L2 gas, at the minimum price
+    pub per_arb_gas_base: U256,
+    /// Per unit of L2 gas, on top of the minimum while the chain is congested
+    pub per_arb_gas_congestion: U256,
+    /// Per unit of L2 gas in total
+    pub per_arb_gas_total: U256,
+}
+
+/// Context of calls to precompiles, which builds with and without the SDK's `reentrant`
+/// feature, unlike `Call::new`, since the contract using this crate may enable it.
+struct PrecompileCall {
+    value: U256,
+}
+
+impl calls::CallContext for PrecompileCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+impl calls::StaticCallContext for PrecompileCall {}
+
+// SAFETY: there's no storage to hold, since precompiles never call back into the contract, so
+// nothing can change its storage behind the cache
+unsafe impl calls::MutatingCallContext for PrecompileCall {
+    fn value(&self) -> U256 {
+        self.value
+    }
+}
+
+/// Calls a view of a precompile and decodes what it returns.
+fn static_call<C: SolCall>(vm: &dyn Host, precompile: Address, call: C) -> Result<C::Return, PrecompileCallFailed> {
+    vm.static_call(&PrecompileCall { value: U256::ZERO }, precompile, &call.abi_encode())
+        .ok()
+        .and_then(|output| C::abi_decode_returns(&output, true).ok())
+        .ok_or(PrecompileCallFailed { precompile })
+}
+
+/// Current L2 block number, which unlike `block.number` advances with every Arbitrum block.
+pub fn arb_block_number(vm: &dyn Host) -> Result<u64, PrecompileCallFailed> {
+    static_call(vm, ARB_SYS, ArbSys::arbBlockNumberCall {}).map(|number| number._0.saturating_to())
+}
+
+/// Hash of the L2 block `block_number`, or `None` unless it is one of the [`BLOCK_HASH_WINDOW`]
+/// blocks before the current one, for which ArbSys reverts.
+pub fn arb_block_hash(vm: &dyn Host, block_number: u64) -> Result<Option<B256>, PrecompileCallFailed> {
+    let current = arb_block_number(vm)?;
+    if block_number >= current || current - block_number > BLOCK_HASH_WINDOW {
+        return Ok(None);
+    }
+    let call = ArbSys::arbBlockHashCall { arbBlockNum: U256::from(block_number) };
+    static_call(vm, ARB_SYS, call).map(|hash| Some(hash._0))
+}
+
+/// L1 block number the chain last synced to, which is `block.number` on Arbitrum. It lags L1
+/// by a few blocks and stands still between the sequencer's updates.
+pub fn l1_block_number(vm: &dyn Host) -> u64 {
+    vm.block_number()
+}
+
+/// ArbOS's current prices.
+pub fn gas_prices(vm: &dyn Host) -> Result<GasPrices, PrecompileCallFailed> {
+    let prices = static_call(vm, ARB_GAS_INFO, ArbGasInfo::getPricesInWeiCall {})?;
+    Ok(GasPrices {
+        per_l2_tx: prices._0,
+        per_l1_calldata_byte: prices._1,
+        per_storage_allocation: prices._2,
+        per_arb_gas_base: prices._3,
+        per_arb_gas_congestion: prices._4,
+        per_arb_gas_total: prices._5,
+    })
+}
+
+/// ArbOS's estimate of the L1 base fee, in wei, which prices the calldata it posts to L1.
+pub fn l1_base_fee_estimate(vm: &dyn Host) -> Result<U256, PrecompileCallFailed> {
+    static_call(vm, ARB_GAS_INFO, ArbGasInfo::getL1BaseFeeEstimateCall {}).map(|fee| fee._0)
+}
+
+/// Sends an L2-to-L1 message that calls `destination` with `data` and `value` wei, taken from
+/// the calling contract's balance, and returns its ID. Anyone can execute it through the Outbox
+/// once its assertion is confirmed, about a week later, where `l2ToL1Sender` is the calling
+/// contract.
+pub fn send_tx_to_l1(
+    vm: &dyn Host,
+    destination: Address,
+    data: &[u8],
+    value: U256,
+) -> Result<U256, PrecompileCallFailed> {
+    let calldata = ArbSys::sendTxToL1Call { destination, data: data.to_vec().into() }.abi_encode();
+    vm.call(&PrecompileCall { value }, ARB_SYS, &calldata)
+        .ok()
+        .and_then(|output| ArbSys::sendTxToL1Call::abi_decode_returns(&output, true).ok())
+        .map(|message_id| message_id._0)
+        .ok_or(PrecompileCallFailed { precompile: ARB_SYS })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::b256;
+    use alloy_sol_types::SolValue;
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+
+    const DESTINATION: Address = address!("000000000000000000000000000000000000d357");
+
+    fn mock_block_number(vm: &TestVM, number: u64) {
+        vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Ok(U256::from(number).abi_encode()));
+    }
+
+    #[test]
+    fn block_hashes_are_only_read_within_the_window() {
+        let vm = TestVM::new();
+        vm.set_block_number(20_000_000);
+        mock_block_number(&vm, 1_000);
+        let hash = b256!("00000000000000000000000000000000000000000000000000000000000000aa");
+        for block in [999, 1_000 - BLOCK_HASH_WINDOW] {
+            let call = ArbSys::arbBlockHashCall { arbBlockNum: U256::from(block) };
+            vm.mock_static_call(ARB_SYS, call.abi_encode(), Ok(hash.abi_encode()));
+        }
+
+        assert_eq!(arb_block_number(&vm).ok(), Some(1_000));
+        assert_eq!(l1_block_number(&vm), 20_000_000);
+        assert_eq!(arb_block_hash(&vm, 999).ok(), Some(Some(hash)));
+        assert_eq!(arb_block_hash(&vm, 1_000 - BLOCK_HASH_WINDOW).ok(), Some(Some(hash)));
+        assert_eq!(arb_block_hash(&vm, 1_000 - BLOCK_HASH_WINDOW - 1).ok(), Some(None));
+        assert_eq!(arb_block_hash(&vm, 1_000).ok(), Some(None));
+        assert_eq!(arb_block_hash(&vm, 1_001).ok(), Some(None));
+    }
+
+    #[test]
+    fn gas_prices_are_read_from_arb_gas_info() {
+        let vm = TestVM::new();
+        let prices: [U256; 6] = core::array::from_fn(|index| U256::from(index + 1));
+        vm.mock_static_call(ARB_GAS_INFO, ArbGasInfo::getPricesInWeiCall {}.abi_encode(), Ok(prices.abi_encode()));
+        vm.mock_static_call(
+            ARB_GAS_INFO,
+            ArbGasInfo::getL1BaseFeeEstimateCall {}.abi_encode(),
+            Ok(U256::from(30).abi_encode()),
+        );
+
+        let prices = gas_prices(&vm).ok().unwrap();
+        assert_eq!(prices.per_l2_tx, U256::from(1));
+        assert_eq!(prices.per_arb_gas_total, U256::from(6));
+        assert_eq!(l1_base_fee_estimate(&vm).ok(), Some(U256::from(30)));
+    }
+
+    #[test]
+    fn failed_precompile_calls_name_the_precompile() {
+        let vm = TestVM::new();
+        vm.mock_static_call(ARB_SYS, ArbSys::arbBlockNumberCall {}.abi_encode(), Err(Vec::new()));
+        vm.mock_static_call(ARB_GAS_INFO, ArbGasInfo::getL1BaseFeeEstimateCall {}.abi_encode(), Ok(Vec::new()));
+
+        assert!(matches!(arb_block_number(&vm), Err(PrecompileCallFailed { precompile }) if precompile == ARB_SYS));
+        assert!(matches!(
+            l1_base_fee_estimate(&vm),
+            Err(PrecompileCallFailed { precompile }) if precompile == ARB_GAS_INFO
+        ));
+    }
+
+    #[test]
+    fn messages_to_l1_return_their_id() {
+        let vm = TestVM::new();
+        let data = [0xab, 0xcd];
+        let call = ArbSys::sendTxToL1Call { destination: DESTINATION, data: data.to_vec().into() };
+        vm.mock_call(ARB_SYS, call.abi_encode(), Ok(U256::from(7).abi_encode()));
+
+        assert_eq!(send_tx_to_l1(&vm, DESTINATION, &data, U256::ZERO).ok(), Some(U256::from(7)));
+    }
+}
@@ -0,0 +1,166 @@
+//! Source hash of a Stylus contract crate
+//!
+//! [`source_hash`] identifies the exact sources a contract was built from, so
+//! anyone can check a deployment against a checkout by recomputing it. A
+//! component embeds it from its `build.rs`:
+//!
+//! ```ignore
+//! fn main() {
+//!     cradle_build_info::emit().unwrap();
+//! }
+//! ```
+//!
+//! and returns it from a `build_metadata()` view:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+//!
+//! pub fn build_metadata(&self) -> (B256, String) {
+//!     (B256::new(SOURCE_HASH), env!("CARGO_PKG_VERSION").into())
+//! }
+//! ```
+//!
+//! The hash covers `Cargo.toml`, `rust-toolchain.toml`, `build.rs` and `src/`
+//! of the crate and of its path dependencies, such as the shared Stylus
+//! modules. Each file is hashed with its path relative to its crate, prefixed
+//! with the crate's directory name, so the hash doesn't depend on where the
+//! repository is checked out. `Cargo.lock` is left out, as it isn't committed.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use tiny_keccak::{Hasher, Keccak};
+
+/// Files outside `src/` that are part of a crate's sources.
+const CRATE_FILES: [&str; 3] = ["Cargo.toml", "rust-toolchain.toml", "build.rs"];
+
+/// The source files of the crate in `dir` and its path dependencies, by their
+/// hashed name, like `erc1155/src/lib.rs`.
+pub fn source_files(dir: &Path) -> io::Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    let mut crates = vec![dir.canonicalize()?];
+    let mut seen = Vec::new();
+    while let Some(dir) = crates.pop() {
+        if seen.contains(&dir) {
+            continue;
+        }
+        let name = dir.file_name().and_then(|name| name.to_str()).ok_or_else(|| invalid(&dir, "no crate name"))?;
+        for file in CRATE_FILES {
+            if dir.join(file).is_file() {
+                files.insert(format!("{name}/{file}"), dir.join(file));
+            }
+        }
+        collect(&dir.join("src"), &format!("{name}/src"), &mut files)?;
+        for dependency in path_dependencies(&dir)? {
+            crates.push(dir.join(dependency).canonicalize()?);
+        }
+        seen.push(dir);
+    }
+    Ok(files)
+}
+
+/// Keccak-256 over the name, length and contents of every source file, in
+/// order of name.
+pub fn source_hash(dir: &Path) -> io::Result<[u8; 32]> {
+    let mut keccak = Keccak::v256();
+    for (name, path) in source_files(dir)? {
+        let contents = fs::read(path)?;
+        keccak.update(name.as_bytes());
+        keccak.update(&[0]);
+        keccak.update(&(contents.len() as u64).to_be_bytes());
+        keccak.update(&contents);
+    }
+    let mut hash = [0; 32];
+    keccak.finalize(&mut hash);
+    Ok(hash)
+}
+
+/// Writes `build_info.rs` to `OUT_DIR`, declaring the crate's
+/// `SOURCE_HASH: [u8; 32]`, and reruns the build script when any of the
+/// hashed files changes.
+pub fn emit() -> io::Result<()> {
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").map_err(io::Error::other)?);
+    let files = source_files(&dir)?;
+    let mut watched: Vec<&Path> = files.values().map(PathBuf::as_path).collect();
+    // New files in src/ change the hash too
+    watched.extend(files.iter().filter(|(name, _)| name.contains("/src/")).filter_map(|(_, path)| path.parent()));
+    watched.sort();
+    watched.dedup();
+    for path in watched {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    let out = PathBuf::from(env::var("OUT_DIR").map_err(io::Error::other)?);
+    let hash = source_hash(&dir)?;
+    fs::write(out.join("build_info.rs"), format!("const SOURCE_HASH: [u8; 32] = {hash:?};\n"))
+}
+
+fn collect(dir: &Path, name: &str, files: &mut BTreeMap<String, PathBuf>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file = path.file_name().and_then(|file| file.to_str()).ok_or_else(|| invalid(&path, "not UTF-8"))?;
+        let name = format!("{name}/{file}");
+        if path.is_dir() {
+            collect(&path, &name, files)?;
+        } else {
+            files.insert(name, path);
+        }
+    }
+    Ok(())
+}
+
+/// The `path` of every entry in `[dependencies]`; build and dev dependencies
+/// don't end up in the contract.
+fn path_dependencies(dir: &Path) -> io::Result<Vec<String>> {
+    let manifest = dir.join("Cargo.toml");
+    let manifest: toml::Table =
+        toml::from_str(&fs::read_to_string(&manifest)?).map_err(|e| invalid(&manifest, &e.to_string()))?;
+    let Some(dependencies) = manifest.get("dependencies").and_then(|deps| deps.as_table()) else {
+        return Ok(Vec::new());
+    };
+    Ok(dependencies.values().filter_map(|dependency| dependency.get("path")?.as_str()).map(String::from).collect())
+}
+
+fn invalid(path: &Path, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {message}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes_path_dependencies() {
+        let erc1155 = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../components/erc1155-stylus/contract/erc1155");
+        let files = source_files(&erc1155).unwrap();
+        assert!(files.contains_key("erc1155/Cargo.toml"));
+        assert!(files.contains_key("erc1155/src/lib.rs"));
+        assert!(files.contains_key("ownable/src/lib.rs"));
+        assert!(files.contains_key("access-control/Cargo.toml"));
+        assert!(!files.keys().any(|name| name.starts_with("erc1155/tests/")));
+    }
+
+    #[test]
+    fn hash_depends_on_contents_and_names() {
+        let dir = env::temp_dir().join(format!("cradle-build-info-{}", std::process::id()));
+        let dir = dir.join("token");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"token\"\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), "// one").unwrap();
+        let original = source_hash(&dir).unwrap();
+        assert_eq!(source_hash(&dir).unwrap(), original);
+
+        fs::write(dir.join("src/lib.rs"), "// two").unwrap();
+        let edited = source_hash(&dir).unwrap();
+        assert_ne!(edited, original);
+
+        fs::rename(dir.join("src/lib.rs"), dir.join("src/main.rs")).unwrap();
+        assert_ne!(source_hash(&dir).unwrap(), edited);
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+}
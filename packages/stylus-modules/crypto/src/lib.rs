@@ -0,0 +1,464 @@
+//! EIP-712 signatures
+//!
+//! Free functions for the hashing and signature checks every signed-message
+//! component repeats, plus a [`Nonces`] storage type for replay protection and
+//! revoking signatures that haven't been submitted:
+//!
+//! - [`domain_separator`] and [`eip712_domain`] for a contract's EIP-712 domain
+//! - [`hash_struct`] and [`hash_typed_data`] for the digest a user signs
+//! - [`recover`] for 65-byte ECDSA signatures, via the `ecrecover` precompile
+//! - [`is_valid_signature_now`], which also accepts ERC-1271 contract signatures
+//!
+//! Each function takes the contract's host, `self.vm()`:
+//!
+//! ```ignore
+//! let struct_hash = hash_struct(self.vm(), VOUCHER_TYPEHASH, (recipient, amount, nonce));
+//! let digest = hash_typed_data(self.vm(), domain_separator(self.vm(), DOMAIN_NAME, DOMAIN_VERSION), struct_hash);
+//! if !is_valid_signature_now(self.vm(), signer, digest, &signature) {
+//!     return Err(...);
+//! }
+//! ```
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{address, b256, fixed_bytes, Address, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolValue};
+use stylus_sdk::{
+    abi::Bytes,
+    prelude::*,
+    storage::{StorageMap, StorageU256},
+    stylus_core::Host,
+};
+
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`
+pub const DOMAIN_TYPEHASH: B256 = b256!("8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f");
+/// Half the secp256k1 curve order; signatures with a larger `s` are rejected as malleable.
+pub const SECP256K1_HALF_ORDER: U256 = U256::from_be_bytes([
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x5d, 0x57, 0x6e,
+    0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+]);
+/// `ecrecover` precompile.
+pub const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+/// Selector of `isValidSignature(bytes32,bytes)`, which ERC-1271 wallets return when a signature is valid.
+pub const ERC1271_MAGIC_VALUE: FixedBytes<4> = fixed_bytes!("1626ba7e");
+
+/// `(name, version, chain_id, verifying_contract)` of an EIP-712 domain.
+pub type Eip712Domain = (String, String, U256, Address);
+
+/// EIP-712 domain separator of the calling contract on the current chain.
+pub fn domain_separator(vm: &dyn Host, name: &str, version: &str) -> B256 {
+    let encoded = (
+        DOMAIN_TYPEHASH,
+        vm.native_keccak256(name.as_bytes()),
+        vm.native_keccak256(version.as_bytes()),
+        U256::from(vm.chain_id()),
+        vm.contract_address(),
+    )
+        .abi_encode();
+    vm.native_keccak256(&encoded)
+}
+
+/// Fields of the calling contract's EIP-712 domain, as returned by `eip712Domain` views.
+pub fn eip712_domain(vm: &dyn Host, name: &str, version: &str) -> Eip712Domain {
+    (name.into(), version.into(), U256::from(vm.chain_id()), vm.contract_address())
+}
+
+/// `hashStruct` of a message: the keccak of `typehash` followed by its encoded fields.
+///
+/// `fields` must be a tuple of static values in type-string order, with `string` and
+/// `bytes` fields already hashed and `uint8` fields widened to `U256`.
+pub fn hash_struct<T: SolValue>(vm: &dyn Host, typehash: B256, fields: T) -> B256 {
+    vm.native_keccak256(&(typehash, fields).abi_encode())
+}
+
+/// Digest to sign for a message with `struct_hash` under `domain_separator`.
+pub fn hash_typed_data(vm: &dyn Host, domain_separator: B256, struct_hash: B256) -> B256 {
+    let mut preimage = Vec::with_capacity(66);
+    preimage.extend_from_slice(&[0x19, 0x01]);
+    preimage.extend_from_slice(domain_separator.as_slice());
+    preimage.extend_from_slice(struct_hash.as_slice());
+    vm.native_keccak256(&preimage)
+}
+
+/// Context of the `ecrecover` and ERC-1271 views, which builds with and without the SDK's
+/// `reentrant` feature, unlike `Call::new`, since the contract using this crate may enable it.
+struct ViewCall;
+
+impl calls::CallContext for ViewCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+impl calls::StaticCallContext for ViewCall {}
+
+/// Recovers the signer of a 65-byte `(r, s, v)` signature, or the zero address if it is invalid.
+pub fn recover(vm: &dyn Host, digest: B256, signature: &[u8]) -> Address {
+    if signature.len() != 65 {
+        return Address::ZERO;
+    }
+    let v = signature[64];
+    let s = U256::from_be_slice(&signature[32..64]);
+    if (v != 27 && v != 28) || s > SECP256K1_HALF_ORDER {
+        return Address::ZERO;
+    }
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(digest.as_slice());
+    input[63] = v;
+    input[64..].copy_from_slice(&signature[..64]);
+    match vm.static_call(&ViewCall, ECRECOVER, &input) {
+        Ok(output) if output.len() == 32 => Address::from_slice(&output[12..]),
+        _ => Address::ZERO,
+    }
+}
+
+/// Whether `signer` signed `digest`. Accounts with code are asked through ERC-1271
+/// `isValidSignature`; others must match the ECDSA signer.
+pub fn is_valid_signature_now(vm: &dyn Host, signer: Address, digest: B256, signature: &[u8]) -> bool {
+    if signer.is_zero() {
+        return false;
+    }
+    if vm.code_size(signer) == 0 {
+        return recover(vm, digest, signature) == signer;
+    }
+
+    let mut calldata = ERC1271_MAGIC_VALUE.to_vec();
+    calldata.extend((digest, Bytes::from(signature.to_vec())).abi_encode_params());
+    match vm.static_call(&ViewCall, signer, &calldata) {
+        Ok(output) if output.len() >= 32 => output[..4] == ERC1271_MAGIC_VALUE,
+        _ => false,
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event NoncesInvalidated(address indexed account, uint256 newNonce);
+    event NonceCancelled(address indexed account, uint256 nonce);
+
+    // Nonce doesn't match the account's current one
+    error InvalidAccountNonce(address account, uint256 currentNonce);
+    // Unordered nonce was already used or cancelled
+    error UsedNonce(address account, uint256 nonce);
+    // Sequential nonces can only move forward
+    error InvalidNonceInvalidation(address account, uint256 currentNonce, uint256 newNonce);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum NoncesError {
+    InvalidAccountNonce(InvalidAccountNonce),
+    UsedNonce(UsedNonce),
+    InvalidNonceInvalidation(InvalidNonceInvalidation),
+}
+
+/// Replay protection for signed operations.
+///
+/// Sequential nonces must be used in order, like an account's transaction nonce, so one
+/// signature can't be submitted before an earlier one. Unordered nonces can be used in any order,
+/// for signatures that are handed out in advance and submitted independently, like vouchers; each
+/// account's are kept as a bitmap, 256 to a slot.
+///
+/// Accounts revoke signatures they haven't submitted with the inherited `invalidate_nonces_up_to`
+/// for sequential nonces, and `cancel_nonce` for unordered ones.
+#[storage]
+pub struct Nonces {
+    /// Next nonce each account must sign over
+    nonces: StorageMap<Address, StorageU256>,
+    /// Used and cancelled unordered nonces of each account, by `nonce >> 8`
+    unordered: StorageMap<Address, StorageMap<U256, StorageU256>>,
+}
+
+/// Slot key and bit of an unordered nonce in its account's bitmap.
+fn bitmap_position(nonce: U256) -> (U256, U256) {
+    (nonce >> 8, U256::from(1) << (nonce.as_limbs()[0] & 0xff) as usize)
+}
+
+// These methods aren't exposed to other contracts
+impl Nonces {
+    /// Consumes the current nonce of `owner` and returns it.
+    pub fn use_nonce(&mut self, owner: Address) -> U256 {
+        let mut nonce = self.nonces.setter(owner);
+        let current = nonce.get();
+        nonce.set(current + U256::from(1));
+        current
+    }
+
+    /// Consumes the current nonce of `owner`, failing unless it is `nonce`.
+    pub fn use_checked_nonce(&mut self, owner: Address, nonce: U256) -> Result<(), NoncesError> {
+        let current = self.use_nonce(owner);
+        if nonce != current {
+            return Err(NoncesError::InvalidAccountNonce(InvalidAccountNonce {
+                account: owner,
+                currentNonce: current,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Moves the sequential nonce of `account` forward to `new_nonce`, for contracts that expose
+    /// `invalidate_nonces_up_to` themselves rather than inheriting it.
+    pub fn invalidate_up_to(&mut self, account: Address, new_nonce: U256) -> Result<(), InvalidNonceInvalidation> {
+        let current = self.nonces.get(account);
+        if new_nonce <= current {
+            return Err(InvalidNonceInvalidation { account, currentNonce: current, newNonce: new_nonce });
+        }
+        self.nonces.insert(account, new_nonce);
+        log(self.vm(), NoncesInvalidated { account, newNonce: new_nonce });
+        Ok(())
+    }
+
+    /// Consumes the unordered `nonce` of `owner`, failing if it was used or cancelled.
+    pub fn use_unordered_nonce(&mut self, owner: Address, nonce: U256) -> Result<(), NoncesError> {
+        let (key, bit) = bitmap_position(nonce);
+        let mut bitmap = self.unordered.setter(owner);
+        let mut word = bitmap.setter(key);
+        let used = word.get();
+        if used & bit != U256::ZERO {
+            return Err(NoncesError::UsedNonce(UsedNonce { account: owner, nonce }));
+        }
+        word.set(used | bit);
+        Ok(())
+    }
+}
+
+#[public]
+impl Nonces {
+    /// Next nonce `owner` must sign over.
+    pub fn nonces(&self, owner: Address) -> U256 {
+        self.nonces.get(owner)
+    }
+
+    /// Whether `owner` used or cancelled the unordered `nonce`.
+    pub fn is_unordered_nonce_used(&self, owner: Address, nonce: U256) -> bool {
+        let (key, bit) = bitmap_position(nonce);
+        self.unordered.getter(owner).get(key) & bit != U256::ZERO
+    }
+
+    /// Moves the caller's sequential nonce forward to `new_nonce`, revoking every signature over
+    /// the nonces before it.
+    pub fn invalidate_nonces_up_to(&mut self, new_nonce: U256) -> Result<(), NoncesError> {
+        let account = self.vm().msg_sender();
+        self.invalidate_up_to(account, new_nonce).map_err(NoncesError::InvalidNonceInvalidation)
+    }
+
+    /// Cancels the caller's unordered `nonce`, revoking signatures over it. Cancelling a used
+    /// nonce does nothing more.
+    pub fn cancel_nonce(&mut self, nonce: U256) {
+        let account = self.vm().msg_sender();
+        let (key, bit) = bitmap_position(nonce);
+        let mut bitmap = self.unordered.setter(account);
+        let mut word = bitmap.setter(key);
+        let used = word.get();
+        word.set(used | bit);
+        log(self.vm(), NonceCancelled { account, nonce });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, keccak256};
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+
+    const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+
+    /// The `Mail` example of EIP-712, from Cow to Bob, hashed with `hash_struct`.
+    fn mail_struct_hash(vm: &TestVM) -> B256 {
+        let person_typehash = keccak256("Person(string name,address wallet)");
+        let person = |name: &str, wallet| hash_struct(vm, person_typehash, (keccak256(name), wallet));
+        let from = person("Cow", address!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"));
+        let to = person("Bob", address!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"));
+        let mail_typehash = keccak256("Mail(Person from,Person to,string contents)Person(string name,address wallet)");
+        hash_struct(vm, mail_typehash, (from, to, keccak256("Hello, Bob!")))
+    }
+
+    #[test]
+    fn digests_match_the_eip712_example() {
+        let vm = TestVM::new();
+        vm.set_chain_id(1);
+        vm.set_contract_address(address!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"));
+        let domain = domain_separator(&vm, "Ether Mail", "1");
+        assert_eq!(domain, b256!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"));
+        let mail = mail_struct_hash(&vm);
+        assert_eq!(mail, b256!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"));
+        let digest = hash_typed_data(&vm, domain, mail);
+        assert_eq!(digest, b256!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"));
+
+        // The domain is the calling contract's on the current chain
+        vm.set_chain_id(42_161);
+        assert_ne!(domain_separator(&vm, "Ether Mail", "1"), domain);
+        assert_eq!(
+            eip712_domain(&vm, "Ether Mail", "1"),
+            ("Ether Mail".into(), "1".into(), U256::from(42_161), vm.contract_address())
+        );
+    }
+
+    const DIGEST: B256 = b256!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2");
+
+    /// A signature with `s` and `v`, and the precompile answering `output` for it.
+    fn signature(vm: &TestVM, s: U256, v: u8, output: Vec<u8>) -> Vec<u8> {
+        let mut signature = [[0x22; 32], s.to_be_bytes()].concat();
+        signature.push(v);
+        let mut input = DIGEST.to_vec();
+        input.extend(U256::from(v).to_be_bytes::<32>());
+        input.extend(&signature[..64]);
+        vm.mock_static_call(ECRECOVER, input, Ok(output));
+        signature
+    }
+
+    #[test]
+    fn recovery_rejects_malformed_and_malleable_signatures() {
+        let vm = TestVM::new();
+        let low_s = SECP256K1_HALF_ORDER;
+        for v in [27, 28] {
+            let signature = signature(&vm, low_s, v, ALICE.into_word().to_vec());
+            assert_eq!(recover(&vm, DIGEST, &signature), ALICE);
+            assert_eq!(recover(&vm, DIGEST, &signature[..64]), Address::ZERO);
+            assert_eq!(recover(&vm, DIGEST, &[signature.as_slice(), &[0]].concat()), Address::ZERO);
+        }
+
+        // The precompile would answer for these, but they never reach it
+        let high_s = signature(&vm, low_s + U256::from(1), 27, ALICE.into_word().to_vec());
+        assert_eq!(recover(&vm, DIGEST, &high_s), Address::ZERO);
+        for v in [0, 1, 29] {
+            let bad_v = signature(&vm, low_s, v, ALICE.into_word().to_vec());
+            assert_eq!(recover(&vm, DIGEST, &bad_v), Address::ZERO);
+        }
+
+        // The precompile answers nothing for signatures it can't recover
+        let unrecoverable = signature(&vm, U256::from(1), 27, Vec::new());
+        assert_eq!(recover(&vm, DIGEST, &unrecoverable), Address::ZERO);
+    }
+
+    #[test]
+    fn nobody_signs_for_the_zero_address() {
+        let vm = TestVM::new();
+        let zero = signature(&vm, U256::from(1), 27, Address::ZERO.into_word().to_vec());
+        assert_eq!(recover(&vm, DIGEST, &zero), Address::ZERO);
+        assert!(!is_valid_signature_now(&vm, Address::ZERO, DIGEST, &zero));
+        assert!(!is_valid_signature_now(&vm, Address::ZERO, DIGEST, &[]));
+
+        let signed = signature(&vm, U256::from(2), 28, ALICE.into_word().to_vec());
+        assert!(is_valid_signature_now(&vm, ALICE, DIGEST, &signed));
+        assert!(!is_valid_signature_now(&vm, ALICE, DIGEST, &zero));
+    }
+
+    #[test]
+    fn contract_accounts_sign_through_erc1271() {
+        let vm = TestVM::new();
+        let signature = signature(&vm, U256::from(1), 27, ALICE.into_word().to_vec());
+        vm.set_code(ALICE, vec![0]);
+        // An ECDSA signature over the digest doesn't count for an account with code
+        assert!(!is_valid_signature_now(&vm, ALICE, DIGEST, &signature));
+
+        let mut calldata = ERC1271_MAGIC_VALUE.to_vec();
+        calldata.extend((DIGEST, Bytes::from(signature.clone())).abi_encode_params());
+        vm.mock_static_call(ALICE, calldata.clone(), Ok(ERC1271_MAGIC_VALUE.abi_encode()));
+        assert!(is_valid_signature_now(&vm, ALICE, DIGEST, &signature));
+        vm.mock_static_call(ALICE, calldata.clone(), Ok(FixedBytes::<4>::ZERO.abi_encode()));
+        assert!(!is_valid_signature_now(&vm, ALICE, DIGEST, &signature));
+        vm.mock_static_call(ALICE, calldata, Err(hex!("08c379a0").to_vec()));
+        assert!(!is_valid_signature_now(&vm, ALICE, DIGEST, &signature));
+    }
+
+    #[test]
+    fn unordered_nonces_are_used_once_in_any_order() {
+        let vm = TestVM::new();
+        let mut nonces = Nonces::from(&vm);
+        for nonce in [U256::from(300), U256::from(1), U256::MAX] {
+            assert!(nonces.use_unordered_nonce(ALICE, nonce).is_ok());
+            assert!(nonces.is_unordered_nonce_used(ALICE, nonce));
+        }
+        assert!(!nonces.is_unordered_nonce_used(ALICE, U256::from(0)));
+        assert!(!nonces.is_unordered_nonce_used(ALICE, U256::from(256 + 1)));
+        assert!(matches!(
+            nonces.use_unordered_nonce(ALICE, U256::from(300)),
+            Err(NoncesError::UsedNonce(UsedNonce { nonce, .. })) if nonce == U256::from(300)
+        ));
+    }
+
+    #[test]
+    fn accounts_cancel_their_own_nonces() {
+        let vm = TestVM::new();
+        let mut nonces = Nonces::from(&vm);
+        vm.set_sender(ALICE);
+        nonces.cancel_nonce(U256::from(7));
+        assert!(matches!(nonces.use_unordered_nonce(ALICE, U256::from(7)), Err(NoncesError::UsedNonce(_))));
+        assert!(!nonces.is_unordered_nonce_used(Address::ZERO, U256::from(7)));
+
+        assert!(nonces.invalidate_nonces_up_to(U256::from(5)).is_ok());
+        assert_eq!(nonces.use_nonce(ALICE), U256::from(5));
+        assert!(matches!(
+            nonces.invalidate_nonces_up_to(U256::from(6)),
+            Err(NoncesError::InvalidNonceInvalidation(InvalidNonceInvalidation { currentNonce, .. }))
+                if currentNonce == U256::from(6)
+        ));
+        assert_eq!(vm.get_emitted_logs().len(), 2);
+    }
+
+    #[test]
+    fn unordered_nonces_are_bits_of_256_nonce_words() {
+        let vm = TestVM::new();
+        let mut nonces = Nonces::from(&vm);
+        assert!(nonces.use_unordered_nonce(ALICE, U256::from(255)).is_ok());
+        assert!(nonces.use_unordered_nonce(ALICE, U256::from(256)).is_ok());
+        assert_eq!(bitmap_position(U256::from(255)), (U256::ZERO, U256::from(1) << 255));
+        assert_eq!(bitmap_position(U256::from(256)), (U256::from(1), U256::from(1)));
+        // Neighbours in the same word and the next are untouched
+        for nonce in [254, 257, 511, 512] {
+            assert!(!nonces.is_unordered_nonce_used(ALICE, U256::from(nonce)));
+        }
+        assert!(nonces.use_unordered_nonce(ALICE, U256::from(254)).is_ok());
+        assert!(nonces.is_unordered_nonce_used(ALICE, U256::from(255)));
+        assert!(!nonces.is_unordered_nonce_used(Address::ZERO, U256::from(255)));
+    }
+
+    #[test]
+    fn cancelling_a_nonce_is_logged_even_if_it_was_used() {
+        let vm = TestVM::new();
+        let mut nonces = Nonces::from(&vm);
+        vm.set_sender(ALICE);
+        assert!(nonces.use_unordered_nonce(ALICE, U256::from(3)).is_ok());
+        nonces.cancel_nonce(U256::from(3));
+        nonces.cancel_nonce(U256::from(4));
+        assert!(nonces.is_unordered_nonce_used(ALICE, U256::from(3)));
+        assert!(nonces.is_unordered_nonce_used(ALICE, U256::from(4)));
+        let cancelled: Vec<_> = vm
+            .get_emitted_logs()
+            .into_iter()
+            .map(|(topics, data)| NonceCancelled::decode_raw_log(topics, &data, true).expect("event decodes"))
+            .map(|event| (event.account, event.nonce))
+            .collect();
+        assert_eq!(cancelled, [(ALICE, U256::from(3)), (ALICE, U256::from(4))]);
+    }
+
+    #[test]
+    fn sequential_nonces_only_move_forward() {
+        let vm = TestVM::new();
+        let mut nonces = Nonces::from(&vm);
+        vm.set_sender(ALICE);
+        assert!(nonces.use_checked_nonce(ALICE, U256::ZERO).is_ok());
+        assert!(matches!(
+            nonces.use_checked_nonce(ALICE, U256::ZERO),
+            Err(NoncesError::InvalidAccountNonce(InvalidAccountNonce { currentNonce, .. })) if currentNonce == U256::from(1)
+        ));
+
+        // Invalidating up to the current nonce moves nothing
+        let current = nonces.nonces(ALICE);
+        assert!(matches!(
+            nonces.invalidate_nonces_up_to(current),
+            Err(NoncesError::InvalidNonceInvalidation(InvalidNonceInvalidation { newNonce, .. })) if newNonce == current
+        ));
+        assert!(nonces.invalidate_nonces_up_to(U256::from(10)).is_ok());
+        assert_eq!(nonces.nonces(ALICE), U256::from(10));
+        assert_eq!(nonces.nonces(Address::ZERO), U256::ZERO);
+        let (topics, data) = vm.get_emitted_logs().pop().expect("invalidation logged");
+        let event = NoncesInvalidated::decode_raw_log(topics, &data, true).expect("event decodes");
+        assert_eq!((event.account, event.newNonce), (ALICE, U256::from(10)));
+    }
+}
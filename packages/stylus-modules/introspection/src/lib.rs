@@ -0,0 +1,125 @@
+//! ERC-165 interface detection
+//!
+//! An [`Interface`] is declared with [`interface!`] from the names and Rust argument types of
+//! the methods a contract exports, and its ID is the XOR of their selectors, computed at compile
+//! time the same way the SDK computes the selectors it routes. IDs can't drift from the methods,
+//! and a method exported with a Solidity type other than the standard's, like `Vec<u8>` for
+//! `bytes`, gives an ID that doesn't match the standard's:
+//!
+//! ```ignore
+//! const IERC2981: Interface = interface!("royaltyInfo"(U256, U256));
+//!
+//! #[public]
+//! impl MyContract {
+//!     pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+//!         supports_interface(interface_id, [IERC2981, cradle_ownable::IERC173])
+//!     }
+//! }
+//! ```
+//!
+//! The methods in an interface should be checked against the contract's router in its tests,
+//! with [`Interface::selectors`].
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloy_primitives::FixedBytes;
+
+/// Declares an [`Interface`] from method names as exported and their Rust argument types.
+#[macro_export]
+macro_rules! interface {
+    ($($name:literal($($ty:ty),* $(,)?)),* $(,)?) => {
+        {
+            const INTERFACE: $crate::Interface =
+                $crate::Interface::new(&[$(::stylus_sdk::function_selector!($name $(, $ty)*)),*]);
+            INTERFACE
+        }
+    };
+}
+
+/// A set of methods and its ERC-165 ID.
+#[derive(Clone, Copy, Debug)]
+pub struct Interface {
+    selectors: &'static [[u8; 4]],
+    id: u32,
+}
+
+impl Interface {
+    pub const fn new(selectors: &'static [[u8; 4]]) -> Self {
+        let mut id = 0;
+        let mut i = 0;
+        while i < selectors.len() {
+            id ^= u32::from_be_bytes(selectors[i]);
+            i += 1;
+        }
+        Self { selectors, id }
+    }
+
+    /// The interface ID, the XOR of the selectors.
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub const fn selectors(&self) -> &'static [[u8; 4]] {
+        self.selectors
+    }
+}
+
+/// ERC-165 itself, `supportsInterface(bytes4)`.
+pub const IERC165: Interface = interface!("supportsInterface"(FixedBytes<4>));
+
+/// Whether `interface_id` is ERC-165 or one of `supported`. The invalid ID `0xffffffff` is never
+/// supported, as ERC-165 requires.
+pub fn supports_interface(interface_id: FixedBytes<4>, supported: impl IntoIterator<Item = Interface>) -> bool {
+    let id = u32::from_be_bytes(interface_id.0);
+    id != u32::MAX && (id == IERC165.id() || supported.into_iter().any(|interface| interface.id() == id))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use stylus_sdk::abi::Bytes;
+
+    use super::*;
+
+    fn id(interface_id: u32) -> FixedBytes<4> {
+        FixedBytes(interface_id.to_be_bytes())
+    }
+
+    #[test]
+    fn ids_match_the_standards() {
+        assert_eq!(IERC165.id(), 0x01ffc9a7);
+        let erc2981 = interface!("royaltyInfo"(U256, U256));
+        assert_eq!(erc2981.id(), 0x2a55205a);
+        let erc721 = interface!(
+            "balanceOf"(Address),
+            "ownerOf"(U256),
+            "safeTransferFrom"(Address, Address, U256, Bytes),
+            "safeTransferFrom"(Address, Address, U256),
+            "transferFrom"(Address, Address, U256),
+            "approve"(Address, U256),
+            "setApprovalForAll"(Address, bool),
+            "getApproved"(U256),
+            "isApprovedForAll"(Address, Address),
+        );
+        assert_eq!(erc721.id(), 0x80ac58cd);
+        assert_eq!(erc721.selectors().len(), 9);
+    }
+
+    #[test]
+    fn exported_types_change_the_id() {
+        let with_bytes = interface!("safeTransferFrom"(Address, Address, U256, Bytes));
+        let with_vec = interface!("safeTransferFrom"(Address, Address, U256, alloc::vec::Vec<u8>));
+        assert_ne!(with_bytes.id(), with_vec.id());
+    }
+
+    #[test]
+    fn supports_erc165_and_the_listed_interfaces() {
+        let erc2981 = interface!("royaltyInfo"(U256, U256));
+        assert!(supports_interface(id(0x01ffc9a7), []));
+        assert!(supports_interface(id(0x2a55205a), [erc2981]));
+        assert!(!supports_interface(id(0x2a55205a), []));
+        assert!(!supports_interface(id(0xffffffff), [Interface::new(&[[0xff; 4]])]));
+    }
+}
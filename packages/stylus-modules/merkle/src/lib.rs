@@ -0,0 +1,205 @@
+//! Merkle proofs
+//!
+//! Verifies proofs against trees whose pairs are sorted before hashing, the
+//! layout produced by OpenZeppelin's `StandardMerkleTree` and `merkletreejs`
+//! with `sortPairs`. Sorting means a proof is just the list of siblings, with
+//! no left/right flags, and the same proofs verify in Solidity contracts using
+//! OpenZeppelin's `MerkleProof`.
+//!
+//! - [`verify`] checks one leaf against a root
+//! - [`multi_proof_verify`] checks several leaves with one shared proof
+//! - [`standard_leaf`] hashes ABI-encoded values the way `StandardMerkleTree` does
+//!
+//! With the `std` feature, [`tree::MerkleTree`] builds roots and proofs
+//! off-chain for tests and tooling.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+
+#[cfg(any(feature = "std", test))]
+pub mod tree;
+
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, B256};
+
+/// Hashes two nodes in sorted order, so the result doesn't depend on which is on the left.
+pub fn hash_pair(a: B256, b: B256) -> B256 {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(low.as_slice());
+    preimage[32..].copy_from_slice(high.as_slice());
+    keccak256(preimage)
+}
+
+/// Leaf of a `StandardMerkleTree` for ABI-encoded values: hashed twice so it can't be
+/// mistaken for an inner node.
+pub fn standard_leaf(encoded: &[u8]) -> B256 {
+    keccak256(keccak256(encoded))
+}
+
+/// Root of the tree that `leaf` belongs to, according to `proof`.
+pub fn process_proof(proof: &[B256], leaf: B256) -> B256 {
+    proof.iter().fold(leaf, |node, &sibling| hash_pair(node, sibling))
+}
+
+/// Whether `leaf` is in the tree with `root`.
+pub fn verify(proof: &[B256], root: B256, leaf: B256) -> bool {
+    process_proof(proof, leaf) == root
+}
+
+/// Root of the tree that all `leaves` belong to, according to a multiproof, or `None`
+/// if the proof is malformed.
+///
+/// Leaves must be in the order the tree stores them, and `proof_flags` says for each
+/// hashing step whether the second node comes from the leaves and earlier hashes
+/// (`true`) or from `proof` (`false`).
+pub fn process_multi_proof(proof: &[B256], proof_flags: &[bool], leaves: &[B256]) -> Option<B256> {
+    if leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return None;
+    }
+
+    let mut hashes: Vec<B256> = Vec::with_capacity(proof_flags.len());
+    let (mut leaf_pos, mut hash_pos, mut proof_pos) = (0, 0, 0);
+    for &flag in proof_flags {
+        let mut next = || {
+            if leaf_pos < leaves.len() {
+                leaf_pos += 1;
+                Some(leaves[leaf_pos - 1])
+            } else {
+                hash_pos += 1;
+                hashes.get(hash_pos - 1).copied()
+            }
+        };
+        let a = next()?;
+        let b = if flag {
+            next()?
+        } else {
+            proof_pos += 1;
+            *proof.get(proof_pos - 1)?
+        };
+        hashes.push(hash_pair(a, b));
+    }
+
+    match hashes.last() {
+        Some(&root) if proof_pos == proof.len() => Some(root),
+        Some(_) => None,
+        None => leaves.first().or(proof.first()).copied(),
+    }
+}
+
+/// Whether all `leaves` are in the tree with `root`. See [`process_multi_proof`].
+pub fn multi_proof_verify(proof: &[B256], proof_flags: &[bool], root: B256, leaves: &[B256]) -> bool {
+    process_multi_proof(proof, proof_flags, leaves) == Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{b256, U256};
+
+    use super::*;
+    use crate::tree::MerkleTree;
+
+    /// Standard leaves of the values 0 to `count - 1`.
+    fn leaves(count: u64) -> Vec<B256> {
+        (0..count).map(|value| standard_leaf(&U256::from(value).to_be_bytes::<32>())).collect()
+    }
+
+    #[test]
+    fn every_leaf_proves_against_the_root() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let tree = MerkleTree::new(leaves.clone()).expect("tree has leaves");
+            for &leaf in &leaves {
+                let proof = tree.proof(leaf).expect("leaf is in the tree");
+                assert_eq!(process_proof(&proof, leaf), tree.root());
+                assert!(verify(&proof, tree.root(), leaf));
+            }
+        }
+
+        // A single leaf is its own root
+        let leaf = leaves(1)[0];
+        assert_eq!(MerkleTree::new(vec![leaf]).expect("tree has leaves").root(), leaf);
+        assert!(verify(&[], leaf, leaf));
+    }
+
+    #[test]
+    fn roots_dont_depend_on_the_order_of_leaves_or_pairs() {
+        let mut leaves = leaves(5);
+        let root = MerkleTree::new(leaves.clone()).expect("tree has leaves").root();
+        leaves.reverse();
+        assert_eq!(MerkleTree::new(leaves.clone()).expect("tree has leaves").root(), root);
+        assert_eq!(hash_pair(leaves[0], leaves[1]), hash_pair(leaves[1], leaves[0]));
+        assert_eq!(hash_pair(leaves[0], leaves[1]), keccak256([leaves[1].0, leaves[0].0].concat()));
+    }
+
+    #[test]
+    fn tampered_leaves_and_proofs_dont_verify() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(leaves.clone()).expect("tree has leaves");
+        let proof = tree.proof(leaves[2]).expect("leaf is in the tree");
+        let outsider = standard_leaf(&U256::from(5).to_be_bytes::<32>());
+        assert!(tree.proof(outsider).is_none());
+        assert!(!verify(&proof, tree.root(), outsider));
+        assert!(!verify(&proof, tree.root(), leaves[3]));
+
+        // Nor does the leaf's own preimage, which standard leaves hash twice
+        assert!(!verify(&proof, tree.root(), keccak256(U256::from(2).to_be_bytes::<32>())));
+        let mut tampered = proof.clone();
+        tampered[0] = b256!("0000000000000000000000000000000000000000000000000000000000000001");
+        assert!(!verify(&tampered, tree.root(), leaves[2]));
+        assert!(!verify(&proof[1..], tree.root(), leaves[2]));
+    }
+
+    #[test]
+    fn every_set_of_leaves_proves_with_a_multiproof() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(leaves.clone()).expect("tree has leaves");
+        for set in 0..1u32 << leaves.len() {
+            let chosen: Vec<B256> = (0..leaves.len()).filter(|i| set & (1 << i) != 0).map(|i| leaves[i]).collect();
+            let multi = tree.multi_proof(&chosen).expect("leaves are in the tree");
+            assert_eq!(process_multi_proof(&multi.proof, &multi.proof_flags, &multi.leaves), Some(tree.root()));
+            assert!(multi_proof_verify(&multi.proof, &multi.proof_flags, tree.root(), &multi.leaves));
+        }
+    }
+
+    #[test]
+    fn malformed_multiproofs_dont_verify() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(leaves.clone()).expect("tree has leaves");
+        let multi = tree.multi_proof(&[leaves[0], leaves[3]]).expect("leaves are in the tree");
+        let root = tree.root();
+
+        // One flag too many or too few
+        let mut flags = multi.proof_flags.clone();
+        flags.push(true);
+        assert_eq!(process_multi_proof(&multi.proof, &flags, &multi.leaves), None);
+        assert_eq!(process_multi_proof(&multi.proof, &multi.proof_flags[1..], &multi.leaves), None);
+
+        // A flipped flag takes a node from the wrong side
+        let mut flags = multi.proof_flags.clone();
+        flags[0] = !flags[0];
+        assert!(!multi_proof_verify(&multi.proof, &flags, root, &multi.leaves));
+
+        // A tampered or reordered leaf
+        let outsider = standard_leaf(&U256::from(5).to_be_bytes::<32>());
+        assert!(tree.multi_proof(&[leaves[0], outsider]).is_none());
+        assert!(!multi_proof_verify(&multi.proof, &multi.proof_flags, root, &[multi.leaves[0], outsider]));
+        let reordered = [multi.leaves[1], multi.leaves[0]];
+        assert!(!multi_proof_verify(&multi.proof, &multi.proof_flags, root, &reordered));
+    }
+
+    #[test]
+    fn empty_trees_and_proofs_prove_nothing() {
+        assert!(MerkleTree::new(Vec::new()).is_none());
+        assert_eq!(process_multi_proof(&[], &[], &[]), None);
+        assert!(!multi_proof_verify(&[], &[], B256::ZERO, &[]));
+
+        // Proving no leaves just states the root
+        let tree = MerkleTree::new(leaves(3)).expect("tree has leaves");
+        let multi = tree.multi_proof(&[]).expect("no leaves to find");
+        assert_eq!((multi.proof.as_slice(), multi.proof_flags.len()), ([tree.root()].as_slice(), 0));
+        assert!(multi_proof_verify(&multi.proof, &multi.proof_flags, tree.root(), &[]));
+    }
+}
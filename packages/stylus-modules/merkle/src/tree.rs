@@ -0,0 +1,111 @@
+//! Off-chain tree builder
+//!
+//! [`MerkleTree`] lays a tree out like OpenZeppelin's `StandardMerkleTree`:
+//! leaves are sorted, stored at the end of a flat array and hashed pairwise
+//! towards the root at index 0. Its roots and proofs verify with the functions
+//! at the crate root and with OpenZeppelin's `MerkleProof` in Solidity.
+
+use crate::hash_pair;
+use alloy_primitives::B256;
+
+/// A shared proof for several leaves, see [`crate::multi_proof_verify`].
+pub struct MultiProof {
+    /// The proven leaves, in the order the proof expects them
+    pub leaves: Vec<B256>,
+    pub proof: Vec<B256>,
+    pub proof_flags: Vec<bool>,
+}
+
+/// A complete tree over a set of leaf hashes.
+pub struct MerkleTree {
+    /// Nodes in breadth-first order: the root first, the leaves last
+    nodes: Vec<B256>,
+}
+
+impl MerkleTree {
+    /// Builds a tree over `leaves`, which are sorted first. Returns `None` for no leaves.
+    pub fn new(mut leaves: Vec<B256>) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+        leaves.sort();
+
+        let mut nodes = vec![B256::ZERO; 2 * leaves.len() - 1];
+        let first_leaf = nodes.len() - leaves.len();
+        for (i, leaf) in leaves.into_iter().enumerate() {
+            let index = nodes.len() - 1 - i;
+            nodes[index] = leaf;
+        }
+        for i in (0..first_leaf).rev() {
+            nodes[i] = hash_pair(nodes[2 * i + 1], nodes[2 * i + 2]);
+        }
+        Some(Self { nodes })
+    }
+
+    pub fn root(&self) -> B256 {
+        self.nodes[0]
+    }
+
+    /// Proof for `leaf`, or `None` if it isn't in the tree.
+    pub fn proof(&self, leaf: B256) -> Option<Vec<B256>> {
+        let mut index = self.leaf_index(leaf)?;
+        let mut proof = Vec::new();
+        while index > 0 {
+            proof.push(self.nodes[sibling(index)]);
+            index = parent(index);
+        }
+        Some(proof)
+    }
+
+    /// Shared proof for `leaves`, or `None` if any of them isn't in the tree.
+    pub fn multi_proof(&self, leaves: &[B256]) -> Option<MultiProof> {
+        let mut indices = leaves.iter().map(|&leaf| self.leaf_index(leaf)).collect::<Option<Vec<_>>>()?;
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        indices.dedup();
+
+        let mut stack: std::collections::VecDeque<usize> = indices.iter().copied().collect();
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+        while let Some(&index) = stack.front() {
+            if index == 0 {
+                break;
+            }
+            stack.pop_front();
+            let sibling = sibling(index);
+            if stack.front() == Some(&sibling) {
+                proof_flags.push(true);
+                stack.pop_front();
+            } else {
+                proof_flags.push(false);
+                proof.push(self.nodes[sibling]);
+            }
+            stack.push_back(parent(index));
+        }
+        if indices.is_empty() {
+            proof.push(self.root());
+        }
+
+        Some(MultiProof {
+            leaves: indices.iter().map(|&index| self.nodes[index]).collect(),
+            proof,
+            proof_flags,
+        })
+    }
+
+    fn leaf_index(&self, leaf: B256) -> Option<usize> {
+        let first_leaf = self.nodes.len() / 2;
+        (first_leaf..self.nodes.len()).find(|&index| self.nodes[index] == leaf)
+    }
+}
+
+fn parent(index: usize) -> usize {
+    (index - 1) / 2
+}
+
+fn sibling(index: usize) -> usize {
+    if index % 2 == 1 {
+        index + 1
+    } else {
+        index - 1
+    }
+}
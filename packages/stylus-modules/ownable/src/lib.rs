@@ -0,0 +1,213 @@
+//! Single-owner access control
+//!
+//! The [`Ownable`] storage type keeps the owner address and provides the
+//! checks, transfers and events. Add it as a `#[borrow]` field of a contract
+//! and inherit it to expose `owner`, `transferOwnership` and
+//! `renounceOwnership`:
+//!
+//! ```ignore
+//! #[storage]
+//! #[entrypoint]
+//! pub struct MyContract {
+//!     #[borrow]
+//!     ownable: Ownable,
+//! }
+//!
+//! #[public]
+//! #[inherit(Ownable)]
+//! impl MyContract {
+//!     pub fn initialize(&mut self, owner: Address) -> Result<(), Vec<u8>> {
+//!         Ok(self.ownable.initialize(owner)?)
+//!     }
+//! }
+//! ```
+//!
+//! Owner-only methods of the contract call [`Ownable::require_owner`].
+//! Inheritance is used rather than an `#[implements]` trait because
+//! `export-abi` only lists inherited methods in this SDK version.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloy_primitives::Address;
+use alloy_sol_types::sol;
+use cradle_introspection::{interface, Interface};
+use stylus_sdk::{prelude::*, storage::StorageAddress};
+
+/// ERC-173, `owner` and `transferOwnership`, for `supportsInterface`.
+pub const IERC173: Interface = interface!("owner"(), "transferOwnership"(Address));
+/// ERC-5313's light ownership, `owner` alone, for `supportsInterface`. Its ID is the selector of
+/// `owner()`, which the inherited method keeps.
+pub const IERC5313: Interface = interface!("owner"());
+
+// Declare events and Solidity error types
+sol! {
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    // Caller isn't the owner
+    error OwnableUnauthorizedAccount(address account);
+    // Owner can't be the zero address
+    error OwnableInvalidOwner(address owner);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum OwnableError {
+    UnauthorizedAccount(OwnableUnauthorizedAccount),
+    InvalidOwner(OwnableInvalidOwner),
+}
+
+#[storage]
+pub struct Ownable {
+    /// Current owner. Zero before `initialize` and after `renounce_ownership`.
+    owner: StorageAddress,
+}
+
+// These methods aren't exposed to other contracts
+impl Ownable {
+    /// Sets the first owner. Call it once from the contract's own initializer.
+    pub fn initialize(&mut self, owner: Address) -> Result<(), OwnableError> {
+        if owner.is_zero() {
+            return Err(OwnableError::InvalidOwner(OwnableInvalidOwner { owner }));
+        }
+        self._transfer_ownership(owner);
+        Ok(())
+    }
+
+    /// Fails unless the caller is the owner.
+    pub fn require_owner(&self) -> Result<(), OwnableError> {
+        let account = self.vm().msg_sender();
+        if account != self.owner.get() {
+            return Err(OwnableError::UnauthorizedAccount(OwnableUnauthorizedAccount { account }));
+        }
+        Ok(())
+    }
+
+    /// Sets the owner without any checks.
+    pub fn _transfer_ownership(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+
+        log(self.vm(), OwnershipTransferred {
+            previousOwner: previous_owner,
+            newOwner: new_owner,
+        });
+    }
+}
+
+#[public]
+impl Ownable {
+    /// Current owner, or the zero address once renounced. ERC-173 and ERC-5313 both read it as
+    /// `owner()`, so the selector is pinned rather than left to the method's name.
+    #[selector(name = "owner")]
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Hands ownership to `new_owner` (owner only). The new owner can't be the zero address.
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), OwnableError> {
+        self.require_owner()?;
+        if new_owner.is_zero() {
+            return Err(OwnableError::InvalidOwner(OwnableInvalidOwner { owner: new_owner }));
+        }
+        self._transfer_ownership(new_owner);
+        Ok(())
+    }
+
+    /// Gives up ownership for good, disabling every owner-only method (owner only).
+    pub fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
+        self.require_owner()?;
+        self._transfer_ownership(Address::ZERO);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+
+    const OWNER: Address = address!("0000000000000000000000000000000000000a11");
+    const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+
+    /// An `Ownable` owned by `OWNER`, who is also the caller.
+    fn setup() -> (TestVM, Ownable) {
+        let vm = TestVM::new();
+        vm.set_sender(OWNER);
+        let mut ownable = Ownable::from(&vm);
+        assert!(ownable.initialize(OWNER).is_ok());
+        (vm, ownable)
+    }
+
+    /// `(previousOwner, newOwner)` of every `OwnershipTransferred` logged so far.
+    fn transfers(vm: &TestVM) -> Vec<(Address, Address)> {
+        vm.get_emitted_logs()
+            .into_iter()
+            .map(|(topics, data)| OwnershipTransferred::decode_raw_log(topics, &data, true).expect("event decodes"))
+            .map(|event| (event.previousOwner, event.newOwner))
+            .collect()
+    }
+
+    #[test]
+    fn the_first_owner_cant_be_the_zero_address() {
+        let vm = TestVM::new();
+        let mut ownable = Ownable::from(&vm);
+        assert!(matches!(
+            ownable.initialize(Address::ZERO),
+            Err(OwnableError::InvalidOwner(OwnableInvalidOwner { owner })) if owner.is_zero()
+        ));
+        assert!(ownable.initialize(OWNER).is_ok());
+        assert_eq!(ownable.owner(), OWNER);
+        assert_eq!(transfers(&vm), [(Address::ZERO, OWNER)]);
+    }
+
+    #[test]
+    fn the_owner_hands_ownership_over() {
+        let (vm, mut ownable) = setup();
+        assert!(matches!(
+            ownable.transfer_ownership(Address::ZERO),
+            Err(OwnableError::InvalidOwner(OwnableInvalidOwner { owner })) if owner.is_zero()
+        ));
+        assert!(ownable.transfer_ownership(ALICE).is_ok());
+        assert_eq!(ownable.owner(), ALICE);
+        assert_eq!(transfers(&vm), [(Address::ZERO, OWNER), (OWNER, ALICE)]);
+
+        // The previous owner is just another account now
+        assert!(ownable.require_owner().is_err());
+        vm.set_sender(ALICE);
+        assert!(ownable.require_owner().is_ok());
+        assert!(ownable.transfer_ownership(OWNER).is_ok());
+        assert_eq!(ownable.owner(), OWNER);
+    }
+
+    #[test]
+    fn renouncing_leaves_nobody_in_charge() {
+        let (vm, mut ownable) = setup();
+        assert!(ownable.renounce_ownership().is_ok());
+        assert_eq!(ownable.owner(), Address::ZERO);
+        assert_eq!(transfers(&vm).pop(), Some((OWNER, Address::ZERO)));
+
+        assert!(matches!(
+            ownable.transfer_ownership(OWNER),
+            Err(OwnableError::UnauthorizedAccount(OwnableUnauthorizedAccount { account })) if account == OWNER
+        ));
+    }
+
+    #[test]
+    fn only_the_owner_calls_owner_only_methods() {
+        let (vm, mut ownable) = setup();
+        vm.set_sender(ALICE);
+        for result in [ownable.require_owner(), ownable.transfer_ownership(ALICE), ownable.renounce_ownership()] {
+            assert!(matches!(
+                result,
+                Err(OwnableError::UnauthorizedAccount(OwnableUnauthorizedAccount { account })) if account == ALICE
+            ));
+        }
+        assert_eq!(ownable.owner(), OWNER);
+        assert_eq!(transfers(&vm).len(), 1);
+    }
+}
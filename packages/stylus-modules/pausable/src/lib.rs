@@ -0,0 +1,222 @@
+//! Emergency stop
+//!
+//! The [`Pausable`] storage type keeps a pause flag and provides the checks
+//! and events. Add it as a `#[borrow]` field of a contract and inherit it to
+//! expose `paused`. Who may pause is up to the contract, so it wraps
+//! [`Pausable::_pause`] and [`Pausable::_unpause`] in its own guarded methods:
+//!
+//! ```ignore
+//! #[public]
+//! #[inherit(Pausable)]
+//! impl MyContract {
+//!     pub fn pause(&mut self) -> Result<(), Vec<u8>> {
+//!         self.ownable.require_owner()?;
+//!         Ok(self.pausable._pause()?)
+//!     }
+//!
+//!     pub fn transfer(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+//!         self.pausable.require_not_paused()?;
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! A contract can also opt in to a `pause-registry-stylus` deployment, which
+//! pauses every contract of a deployment at once, by checking
+//! [`Pausable::require_not_paused_with`] or [`registry_pauses`] with the
+//! registry's address.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+use alloy_primitives::Address;
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{prelude::*, storage::StorageBool, stylus_core::Host};
+
+// Declare events and Solidity error types
+sol! {
+    event Paused(address account);
+    event Unpaused(address account);
+
+    // Contract is paused
+    error EnforcedPause();
+    // Contract isn't paused
+    error ExpectedPause();
+
+    interface IPauseRegistry {
+        function isPaused(address target) external view returns (bool);
+    }
+}
+
+/// Context of the registry's view, which builds with and without the SDK's `reentrant` feature,
+/// unlike `Call::new`, since the contract using this crate may enable it.
+struct ViewCall;
+
+impl calls::CallContext for ViewCall {
+    fn gas(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+impl calls::StaticCallContext for ViewCall {}
+
+/// Whether the `pause-registry-stylus` deployment at `registry` pauses `target`.
+/// A zero `registry` pauses nothing, and one that doesn't answer pauses
+/// everything, so a wrong registry address fails closed.
+pub fn registry_pauses(vm: &dyn Host, registry: Address, target: Address) -> bool {
+    if registry.is_zero() {
+        return false;
+    }
+    let calldata = IPauseRegistry::isPausedCall { target }.abi_encode();
+    match vm.static_call(&ViewCall, registry, &calldata) {
+        Ok(output) => bool::abi_decode(&output, true).unwrap_or(true),
+        Err(_) => true,
+    }
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum PausableError {
+    EnforcedPause(EnforcedPause),
+    ExpectedPause(ExpectedPause),
+}
+
+#[storage]
+pub struct Pausable {
+    /// Whether guarded methods are stopped. Starts unpaused.
+    paused: StorageBool,
+}
+
+// These methods aren't exposed to other contracts
+impl Pausable {
+    /// Fails while the contract is paused.
+    pub fn require_not_paused(&self) -> Result<(), PausableError> {
+        if self.paused.get() {
+            return Err(PausableError::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    /// Fails while the contract is paused, by its own flag or by `registry`, a
+    /// `pause-registry-stylus` deployment. A zero `registry` isn't consulted.
+    pub fn require_not_paused_with(&self, registry: Address) -> Result<(), PausableError> {
+        self.require_not_paused()?;
+        if registry_pauses(self.vm(), registry, self.vm().contract_address()) {
+            return Err(PausableError::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    /// Fails unless the contract is paused.
+    pub fn require_paused(&self) -> Result<(), PausableError> {
+        if !self.paused.get() {
+            return Err(PausableError::ExpectedPause(ExpectedPause {}));
+        }
+        Ok(())
+    }
+
+    /// Pauses the contract. Fails if it's already paused.
+    pub fn _pause(&mut self) -> Result<(), PausableError> {
+        self.require_not_paused()?;
+        self.paused.set(true);
+
+        log(self.vm(), Paused {
+            account: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+
+    /// Unpauses the contract. Fails unless it's paused.
+    pub fn _unpause(&mut self) -> Result<(), PausableError> {
+        self.require_paused()?;
+        self.paused.set(false);
+
+        log(self.vm(), Unpaused {
+            account: self.vm().msg_sender(),
+        });
+        Ok(())
+    }
+}
+
+#[public]
+impl Pausable {
+    /// Whether the contract is paused.
+    pub fn paused(&self) -> bool {
+        self.paused.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::TestVM;
+
+    use super::*;
+
+    const ALICE: Address = address!("000000000000000000000000000000000000a11c");
+    const REGISTRY: Address = address!("0000000000000000000000000000000000005e61");
+
+    #[test]
+    fn pausing_stops_guarded_methods_until_unpaused() {
+        let vm = TestVM::new();
+        vm.set_sender(ALICE);
+        let mut pausable = Pausable::from(&vm);
+        assert!(!pausable.paused());
+        assert!(pausable.require_not_paused().is_ok());
+        assert!(matches!(pausable.require_paused(), Err(PausableError::ExpectedPause(_))));
+
+        assert!(pausable._pause().is_ok());
+        assert!(pausable.paused());
+        assert!(matches!(pausable.require_not_paused(), Err(PausableError::EnforcedPause(_))));
+        assert!(matches!(pausable.require_not_paused_with(Address::ZERO), Err(PausableError::EnforcedPause(_))));
+        assert!(pausable.require_paused().is_ok());
+
+        assert!(pausable._unpause().is_ok());
+        assert!(!pausable.paused());
+        assert!(pausable.require_not_paused().is_ok());
+
+        let logs = vm.get_emitted_logs();
+        let paused = Paused::decode_raw_log(logs[0].0.clone(), &logs[0].1, true).expect("event decodes");
+        let unpaused = Unpaused::decode_raw_log(logs[1].0.clone(), &logs[1].1, true).expect("event decodes");
+        assert_eq!((logs.len(), paused.account, unpaused.account), (2, ALICE, ALICE));
+    }
+
+    #[test]
+    fn pausing_twice_or_unpausing_while_running_fails() {
+        let vm = TestVM::new();
+        let mut pausable = Pausable::from(&vm);
+        assert!(matches!(pausable._unpause(), Err(PausableError::ExpectedPause(_))));
+        assert!(pausable._pause().is_ok());
+        assert!(matches!(pausable._pause(), Err(PausableError::EnforcedPause(_))));
+        assert!(pausable.paused());
+        assert_eq!(vm.get_emitted_logs().len(), 1);
+    }
+
+    #[test]
+    fn registries_pause_their_targets_and_fail_closed() {
+        let vm = TestVM::new();
+        let pausable = Pausable::from(&vm);
+        let target = vm.contract_address();
+        let calldata = IPauseRegistry::isPausedCall { target }.abi_encode();
+
+        // A zero registry isn't asked
+        assert!(!registry_pauses(&vm, Address::ZERO, target));
+        assert!(pausable.require_not_paused_with(Address::ZERO).is_ok());
+
+        vm.mock_static_call(REGISTRY, calldata.clone(), Ok(false.abi_encode()));
+        assert!(!registry_pauses(&vm, REGISTRY, target));
+        assert!(pausable.require_not_paused_with(REGISTRY).is_ok());
+        vm.mock_static_call(REGISTRY, calldata.clone(), Ok(true.abi_encode()));
+        assert!(registry_pauses(&vm, REGISTRY, target));
+        assert!(matches!(pausable.require_not_paused_with(REGISTRY), Err(PausableError::EnforcedPause(_))));
+
+        // A registry that reverts or answers nothing pauses everything
+        vm.mock_static_call(REGISTRY, calldata.clone(), Err(Vec::new()));
+        assert!(registry_pauses(&vm, REGISTRY, target));
+        vm.mock_static_call(REGISTRY, calldata, Ok(Vec::new()));
+        assert!(registry_pauses(&vm, REGISTRY, target));
+        assert!(!pausable.paused());
+    }
+}
@@ -0,0 +1,69 @@
+//! TestVM helpers
+//!
+//! What every component's `src/tests.rs` needs besides its own fixtures: unwrapping calls that
+//! must succeed or fail, decoding the events a call logged, and signatures the mocked `ecrecover`
+//! precompile attributes to a chosen signer. Components take the crate as a dev-dependency:
+//!
+//! ```ignore
+//! use cradle_test_utils::{deploy, emitted, n, ok};
+//!
+//! let (vm, mut contract) = deploy::<Auction>(OWNER);
+//! ok(contract.initialize(OWNER));
+//! ok(contract.bid(n(1), n(100)));
+//! assert_eq!(emitted::<BidPlaced>(&vm).len(), 1);
+//! ```
+
+use alloy_primitives::{hex, Address, B256, U256};
+use alloy_sol_types::SolEvent;
+use stylus_sdk::testing::TestVM;
+
+/// Unwraps a call that must succeed, showing the revert data otherwise.
+pub fn ok<T, E: Into<Vec<u8>>>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("call reverted with 0x{}", hex::encode(error.into())),
+    }
+}
+
+/// The revert data of a call that must fail.
+pub fn reverted<T, E: Into<Vec<u8>>>(result: Result<T, E>) -> Vec<u8> {
+    match result {
+        Ok(_) => panic!("call succeeded"),
+        Err(error) => error.into(),
+    }
+}
+
+pub fn n(value: u64) -> U256 {
+    U256::from(value)
+}
+
+/// The `E` events logged so far, oldest first.
+pub fn emitted<E: SolEvent>(vm: &TestVM) -> Vec<E> {
+    vm.get_emitted_logs()
+        .into_iter()
+        .filter(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+        .map(|(topics, data)| E::decode_raw_log(topics, &data, true).expect("event decodes"))
+        .collect()
+}
+
+/// A VM whose calls come from `sender`, with a fresh `C` on it to drive.
+pub fn deploy<C: for<'a> From<&'a TestVM>>(sender: Address) -> (TestVM, C) {
+    let vm = TestVM::new();
+    vm.set_sender(sender);
+    let contract = C::from(&vm);
+    (vm, contract)
+}
+
+/// A 65-byte signature the mocked `ecrecover` attributes to `signer` for `digest`. Its `r` comes
+/// from the digest, so signatures over different digests differ.
+pub fn sign(vm: &TestVM, signer: Address, digest: B256) -> Vec<u8> {
+    let mut signature = digest[..16].repeat(2);
+    signature.extend([0x11; 32]);
+    signature.push(27);
+    let mut ecrecover_input = digest.to_vec();
+    ecrecover_input.extend([0; 31]);
+    ecrecover_input.extend([27]);
+    ecrecover_input.extend(&signature[..64]);
+    vm.mock_static_call(cradle_crypto::ECRECOVER, ecrecover_input, Ok(signer.into_word().to_vec()));
+    signature
+}